@@ -0,0 +1,58 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use ezkl::fieldutils::{felt_to_integer_rep, integer_rep_to_felt, IntegerRep};
+use ezkl::graph::utilities::quantize_tensor;
+use ezkl::graph::{QuantRounding, Visibility};
+use ezkl::tensor::*;
+use halo2curves::bn256::Fr;
+use rand::Rng;
+
+fn runmap(c: &mut Criterion) {
+    let mut group = c.benchmark_group("par_map");
+    let mut rng = rand::thread_rng();
+    for &len in [1 << 10, 1 << 14, 1 << 18].iter() {
+        let ints = Tensor::<IntegerRep>::from((0..len).map(|_| rng.gen_range(-32768..32768)));
+        let felts = ints.map(integer_rep_to_felt::<Fr>);
+        let floats = Tensor::<f32>::from((0..len).map(|_| rng.gen_range(-1.0..1.0)));
+
+        group.throughput(Throughput::Elements(len as u64));
+        group.bench_with_input(BenchmarkId::new("felt_to_int/map", len), &felts, |b, x| {
+            b.iter(|| x.map(felt_to_integer_rep));
+        });
+        group.bench_with_input(
+            BenchmarkId::new("felt_to_int/par_map", len),
+            &felts,
+            |b, x| {
+                b.iter(|| x.par_map(felt_to_integer_rep));
+            },
+        );
+        group.bench_with_input(BenchmarkId::new("int_to_felt/map", len), &ints, |b, x| {
+            b.iter(|| x.map(integer_rep_to_felt::<Fr>));
+        });
+        group.bench_with_input(
+            BenchmarkId::new("int_to_felt/par_map", len),
+            &ints,
+            |b, x| {
+                b.iter(|| x.par_map(integer_rep_to_felt::<Fr>));
+            },
+        );
+        group.bench_with_input(BenchmarkId::new("quantize", len), &floats, |b, x| {
+            b.iter(|| {
+                quantize_tensor::<Fr>(
+                    x.clone(),
+                    7,
+                    &Visibility::Fixed,
+                    &QuantRounding::HalfAwayFromZero,
+                )
+                .unwrap()
+            });
+        });
+    }
+    group.finish();
+}
+
+criterion_group! {
+  name = benches;
+  config = Criterion::default().with_plots();
+  targets = runmap
+}
+criterion_main!(benches);
@@ -0,0 +1,81 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use ezkl::circuit::region::RegionSettings;
+use ezkl::graph::input::GraphData;
+use ezkl::graph::GraphCircuit;
+use ezkl::RunArgs;
+use halo2_proofs::poly::kzg::commitment::KZGCommitmentScheme;
+use halo2curves::bn256::Bn256;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Counts the allocations and the allocated bytes, such that slices, transposes and broadcasts that copy tensors show
+/// up next to those that are views
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+const MODEL: &str = "examples/onnx/nanoGPT";
+
+fn load() -> (GraphCircuit, GraphData) {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(MODEL);
+    let circuit =
+        GraphCircuit::from_run_args(&RunArgs::default(), &dir.join("network.onnx")).unwrap();
+    let data = GraphData::from_path(dir.join("input.json")).unwrap();
+    (circuit, data)
+}
+
+/// The witness generation of a forward pass of a transformer
+fn forward(circuit: &mut GraphCircuit, data: &GraphData) {
+    let mut inputs = circuit.load_graph_from_file_exclusively(data).unwrap();
+    let run_args = &circuit.settings().run_args;
+    let region_settings = RegionSettings::all_true(run_args.decomp_base, run_args.decomp_legs);
+    circuit
+        .forward::<KZGCommitmentScheme<Bn256>>(&mut inputs, None, None, region_settings)
+        .unwrap();
+}
+
+fn runforward(c: &mut Criterion) {
+    let (mut circuit, data) = load();
+
+    let (allocations, bytes) = (
+        ALLOCATIONS.load(Ordering::Relaxed),
+        ALLOCATED_BYTES.load(Ordering::Relaxed),
+    );
+    forward(&mut circuit, &data);
+    println!(
+        "nanoGPT forward pass: {} allocations, {} bytes allocated",
+        ALLOCATIONS.load(Ordering::Relaxed) - allocations,
+        ALLOCATED_BYTES.load(Ordering::Relaxed) - bytes
+    );
+
+    let mut group = c.benchmark_group("tensor_views");
+    group.sample_size(10);
+    group.bench_with_input(BenchmarkId::new("forward", "nanoGPT"), &data, |b, data| {
+        b.iter(|| forward(&mut circuit, data));
+    });
+    group.finish();
+}
+
+criterion_group! {
+  name = benches;
+  config = Criterion::default().with_plots();
+  targets = runforward
+}
+criterion_main!(benches);
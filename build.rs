@@ -3,5 +3,32 @@ fn main() {
         println!("cargo::rustc-env=UNIFFI_CARGO_BUILD_EXTRA_ARGS=--features=ios-bindings --no-default-features");
     }
 
+    #[cfg(feature = "c-bindings")]
+    generate_c_header();
+
     println!("cargo::rerun-if-changed=build.rs");
 }
+
+/// Generates the header of the C ABI into target/<profile>, next to the libraries it declares
+#[cfg(feature = "c-bindings")]
+fn generate_c_header() {
+    let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let out_dir = std::path::PathBuf::from(std::env::var("OUT_DIR").unwrap());
+    // OUT_DIR is target/<profile>/build/ezkl-<hash>/out
+    let header = out_dir.ancestors().nth(3).unwrap().join("ezkl.h");
+
+    let config = cbindgen::Config {
+        language: cbindgen::Language::C,
+        include_guard: Some("EZKL_H".to_string()),
+        usize_is_size_t: true,
+        ..Default::default()
+    };
+    cbindgen::Builder::new()
+        .with_config(config)
+        .with_src(format!("{}/src/bindings/c.rs", crate_dir))
+        .generate()
+        .expect("failed to generate the C header")
+        .write_to_file(header);
+
+    println!("cargo::rerun-if-changed=src/bindings/c.rs");
+}
@@ -170,12 +170,18 @@ contract SwapProofCommitments {
 // Overview of the contract functionality:
 // 1. Initialization: Through the constructor, it sets up the contract calls that the EZKL model will read from.
 // 2. Data Quantization: Quantizes the returned data into a scaled fixed-point representation. See the `quantizeData` method for details.
+// 2b. Return Selection: Calls returning nested or dynamic types first have the attested int256 word selected out of the returned data,
+//  following the steps generated from the ABI of the call. See the `selectReturnWord` method.
 // 3. Static Calls: Makes static calls to fetch data from other contracts. See the `staticCall` method.
 // 4. Field Element Conversion: The fixed-point representation is then converted into a field element modulo P using the `toFieldElement` method.
 // 5. Data Attestation: The `attestData` method validates that the public instances match the data fetched and processed by the contract.
 // 6. Proof Verification: The `verifyWithDataAttestation` method parses the instances out of the encoded calldata and calls the `attestData` method to validate the public instances,
 // 6b. Optional KZG Commitment Verification: It also checks the KZG commitments in the proof against the expected commitments using the `checkKzgCommits` method.
 //  then calls the `verifyProof` method to verify the proof on the verifier.
+// 6c. Optional Instance Bindings: It also checks that the public instances bound to on-chain values, such as a stored commitment to a hashed output,
+//  equal those values using the `checkInstanceBindings` method.
+// 7. Block Pinning: The `pinBlock` method snapshots the results of the account calls at the current block, after which the instances are attested against
+//  the snapshot rather than the live state. Witnesses are then generated by reading the calls at `pinnedBlock` from an archive node.
 
 contract DataAttestation is LoadInstances, SwapProofCommitments {
     /**
@@ -211,6 +217,19 @@ contract DataAttestation is LoadInstances, SwapProofCommitments {
 
     uint8 public instanceOffset;
 
+    /**
+     * @notice The block the account calls were snapshotted at by `pinBlock`, zero if the calls are read from the live state.
+     * @dev The snapshot is taken mid block, so the data read must not change later within the pinned block.
+     */
+    uint256 public pinnedBlock;
+
+    /**
+     * @notice The parent hash of `pinnedBlock`, so a header of the pinned block can be checked after it leaves the `blockhash` window.
+     */
+    bytes32 public pinnedParentHash;
+
+    mapping(uint256 => uint256) internal pinnedElements;
+
     /**
      * @dev Initialize the contract with account calls the EZKL model will read from.
      * @param _contractAddresses - The calls to all the contracts EZKL reads storage from.
@@ -247,6 +266,25 @@ contract DataAttestation is LoadInstances, SwapProofCommitments {
     ) external {
         require(msg.sender == admin, "Only admin can update account calls");
         populateAccountCalls(_contractAddresses, _callData, _decimals);
+        // a snapshot of the previous calls no longer attests to anything
+        pinnedBlock = 0;
+    }
+
+    /**
+     * @dev Snapshot the account calls at the current block so proofs of witnesses read at this block keep verifying after the data changes.
+     */
+    function pinBlock() external {
+        require(msg.sender == admin, "Only admin can pin a block");
+        uint256 _accountCount = accountCalls.length;
+        uint counter = 0;
+        for (uint8 i = 0; i < _accountCount; ++i) {
+            for (uint8 j = 0; j < accountCalls[i].callCount; j++) {
+                pinnedElements[counter] = fetchFieldElement(i, j, counter);
+                counter++;
+            }
+        }
+        pinnedBlock = block.number;
+        pinnedParentHash = blockhash(block.number - 1);
     }
 
     function populateAccountCalls(
@@ -374,6 +412,67 @@ contract DataAttestation is LoadInstances, SwapProofCommitments {
             revert("Address: low-level call failed");
         }
     }
+
+    uint256 constant STEP_FIELD = 0;
+    uint256 constant STEP_DYNAMIC_FIELD = 1;
+    uint256 constant STEP_ELEMENT = 2;
+    uint256 constant STEP_DYNAMIC_ELEMENT = 3;
+
+    /**
+     * @dev The steps locating the attested word in the data returned by each call, generated from the return selectors of the calls.
+     *  Calls without steps return a single int256.
+     * @param counter - The index of the call across all of the accounts.
+     */
+    function returnSteps(
+        uint256 counter
+    ) internal pure returns (uint256[] memory steps) {}
+
+    function readWord(
+        bytes memory data,
+        uint256 pos
+    ) internal pure returns (uint256 word) {
+        require(pos + 32 <= data.length, "Return data out of bounds");
+        assembly {
+            word := mload(add(add(data, 0x20), pos))
+        }
+    }
+
+    /**
+     * @dev Select the attested word out of the data returned by a call with a nested return type.
+     * @param data - The data returned from the account call.
+     * @param steps - The steps locating the word, each packed as `kind << 248 | stride << 128 | arg`.
+     * @return The abi encoded word, to be quantized as an int256.
+     */
+    function selectReturnWord(
+        bytes memory data,
+        uint256[] memory steps
+    ) internal pure returns (bytes memory) {
+        uint256 base = 0;
+        for (uint256 k = 0; k < steps.length; k++) {
+            uint256 kind = steps[k] >> 248;
+            uint256 stride = (steps[k] >> 128) & type(uint120).max;
+            uint256 arg = steps[k] & type(uint128).max;
+            if (kind == STEP_FIELD) {
+                base += arg;
+            } else if (kind == STEP_DYNAMIC_FIELD) {
+                base += readWord(data, base + arg);
+            } else if (kind == STEP_ELEMENT) {
+                require(arg < readWord(data, base), "Return index out of bounds");
+                base += 32 + arg * stride;
+            } else if (kind == STEP_DYNAMIC_ELEMENT) {
+                require(arg < readWord(data, base), "Return index out of bounds");
+                // element offsets are relative to the start of the elements, after the length
+                base += 32;
+                base += readWord(data, base + arg * 32);
+            } else {
+                require(
+                    readWord(data, base) >> 255 == 0,
+                    "Return value overflows int256"
+                );
+            }
+        }
+        return abi.encode(readWord(data, base));
+    }
     /**
      * @dev Convert the fixed point quantized data into a field element.
      * @param x - The quantized data.
@@ -387,6 +486,52 @@ contract DataAttestation is LoadInstances, SwapProofCommitments {
         return uint256(x + int(ORDER)) % ORDER;
     }
 
+    /**
+     * @dev Make an account call and convert the returned data into the field element EZKL reads.
+     * @param i - The index of the account.
+     * @param j - The index of the call to the account.
+     * @param counter - The index of the call across all of the accounts.
+     */
+    function fetchFieldElement(
+        uint8 i,
+        uint8 j,
+        uint counter
+    ) internal view returns (uint256) {
+        bytes memory returnData = staticCall(
+            accountCalls[i].contractAddress,
+            accountCalls[i].callData[j]
+        );
+        uint256[] memory steps = returnSteps(counter);
+        if (steps.length > 0) {
+            returnData = selectReturnWord(returnData, steps);
+        }
+        uint256 scale = scales[counter];
+        int256 quantized_data = quantizeData(
+            returnData,
+            accountCalls[i].decimals[j],
+            scale
+        );
+        return toFieldElement(quantized_data);
+    }
+
+    /**
+     * @dev Read the uint256 value a public instance is bound to.
+     * @param target - The address of the contract holding the value.
+     * @param data - The abi encoded view call returning the value, `extsload(bytes32)` for values bound to a storage slot.
+     */
+    function readBinding(
+        address target,
+        bytes memory data
+    ) internal view returns (uint256) {
+        return abi.decode(staticCall(target, data), (uint256));
+    }
+
+    /**
+     * @dev Check that the public instances bound to on-chain values equal them. The checks are generated from the instance bindings.
+     * @param instances - The public instances to the proof.
+     */
+    function checkInstanceBindings(uint256[] memory instances) internal view {}
+
     /**
      * @dev Make the account calls to fetch the data that EZKL reads from and attest to the data.
      * @param instances - The public instances to the proof (the data in the proof that publicly accessible to the verifier).
@@ -399,19 +544,10 @@ contract DataAttestation is LoadInstances, SwapProofCommitments {
         uint256 _accountCount = accountCalls.length;
         uint counter = 0;
         for (uint8 i = 0; i < _accountCount; ++i) {
-            address account = accountCalls[i].contractAddress;
             for (uint8 j = 0; j < accountCalls[i].callCount; j++) {
-                bytes memory returnData = staticCall(
-                    account,
-                    accountCalls[i].callData[j]
-                );
-                uint256 scale = scales[counter];
-                int256 quantized_data = quantizeData(
-                    returnData,
-                    accountCalls[i].decimals[j],
-                    scale
-                );
-                uint256 field_element = toFieldElement(quantized_data);
+                uint256 field_element = pinnedBlock == 0
+                    ? fetchFieldElement(i, j, counter)
+                    : pinnedElements[counter];
                 require(
                     field_element == instances[counter + instanceOffset],
                     "Public input does not match"
@@ -431,7 +567,9 @@ contract DataAttestation is LoadInstances, SwapProofCommitments {
         bytes calldata encoded
     ) public view returns (bool) {
         require(verifier.code.length > 0, "Address: call to non-contract");
-        attestData(getInstancesCalldata(encoded));
+        uint256[] memory instances = getInstancesCalldata(encoded);
+        attestData(instances);
+        checkInstanceBindings(instances);
         require(checkKzgCommits(encoded), "Invalid KZG commitments");
         // static call the verifier contract to verify the proof
         (bool success, bytes memory returndata) = verifier.staticcall(encoded);
@@ -0,0 +1,422 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.20;
+import "./AttestData.sol";
+
+// This contract serves as a Data Attestation Verifier for EZKL models that read historical contract storage.
+// Rather than calling view functions at verification time, every storage slot the model reads is proven with an
+// eth_getProof style Merkle-Patricia account + storage proof against the state root of a past block.
+
+// Overview of the contract functionality:
+// 1. Initialization: Through the constructor, it sets up the accounts and storage slots the EZKL model reads from, and the block they are read at.
+// 2. Block Header: The rlp encoded block header passed at verification time must hash to the trusted block hash,
+//    either supplied at deployment (e.g. from an oracle or by the user) or read through `blockhash`, which only reaches back 256 blocks.
+// 3. Storage Proofs: The account proofs are checked against the header's state root and the storage proofs against each account's storage root.
+// 4. Data Attestation: The proven values are quantized and converted to field elements exactly as in `DataAttestation`, then checked against the public instances.
+// 5. Proof Verification: The `verifyWithStorageProofs` method attests to the storage, optionally checks the KZG commitments, and calls `verifyProof` on the verifier.
+// Note: only hashed trie nodes are supported, nodes shorter than 32 bytes inlined into their parent are rejected.
+
+contract StorageAttestation is DataAttestation {
+    /**
+     * @notice Struct used to record the storage slots EZKL reads from an account.
+     * @param the address of the account to read storage from
+     * @param the storage slots to read, each must hold a single int256
+     * @param the decimals of the value held in each slot (for floating point representation)
+     */
+    struct AccountSlots {
+        address contractAddress;
+        bytes32[] slots;
+        uint256[] decimals;
+    }
+    AccountSlots[] internal accountSlots;
+
+    uint256 public blockNumber;
+
+    /**
+     * @notice The hash of `blockNumber`, if zero `blockhash(blockNumber)` is used instead.
+     */
+    bytes32 public blockHash;
+
+    /**
+     * @notice Root of a trie with no entries, the storage root of accounts without storage.
+     */
+    bytes32 constant EMPTY_TRIE_ROOT =
+        0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421;
+
+    /**
+     * @dev Initialize the contract with the storage slots the EZKL model will read from.
+     * @param _blockNumber - The block the storage is read at.
+     * @param _blockHash - The hash of `_blockNumber`, zero to use `blockhash`.
+     * @param _contractAddresses - The accounts EZKL reads storage from.
+     * @param _slots - The storage slots to read from each account.
+     * @param _decimals - The decimals of the value held in each slot.
+     */
+    constructor(
+        uint256 _blockNumber,
+        bytes32 _blockHash,
+        address[] memory _contractAddresses,
+        bytes32[][] memory _slots,
+        uint256[][] memory _decimals,
+        uint[] memory _scales,
+        uint8 _instanceOffset,
+        address _admin
+    )
+        DataAttestation(
+            new address[](0),
+            new bytes[][](0),
+            new uint256[][](0),
+            _scales,
+            _instanceOffset,
+            _admin
+        )
+    {
+        require(
+            _contractAddresses.length == _slots.length &&
+                _slots.length == _decimals.length,
+            "Invalid input length"
+        );
+        for (uint256 i = 0; i < _contractAddresses.length; i++) {
+            require(
+                _slots[i].length == _decimals[i].length,
+                "Invalid number of decimals"
+            );
+            AccountSlots storage account = accountSlots.push();
+            account.contractAddress = _contractAddresses[i];
+            for (uint256 j = 0; j < _slots[i].length; j++) {
+                account.slots.push(_slots[i][j]);
+                account.decimals.push(10 ** _decimals[i][j]);
+            }
+        }
+        blockNumber = _blockNumber;
+        blockHash = _blockHash;
+    }
+
+    function updateBlock(uint256 _blockNumber, bytes32 _blockHash) external {
+        require(msg.sender == admin, "Only admin can update the block");
+        blockNumber = _blockNumber;
+        blockHash = _blockHash;
+    }
+
+    /**
+     * @dev The hash the block header passed at verification time must match.
+     */
+    function trustedBlockHash() public view returns (bytes32 hash) {
+        hash = blockHash;
+        if (hash == bytes32(0)) {
+            hash = blockhash(blockNumber);
+            require(hash != bytes32(0), "Block hash unavailable");
+        }
+    }
+
+    /**
+     * @dev Decode the rlp item starting at `offset`.
+     * @return payloadOffset - The offset of the item's payload.
+     * @return payloadLength - The length of the item's payload.
+     * @return isList - Whether the item is a list.
+     */
+    function rlpItem(
+        bytes memory data,
+        uint256 offset
+    )
+        internal
+        pure
+        returns (uint256 payloadOffset, uint256 payloadLength, bool isList)
+    {
+        require(offset < data.length, "Invalid rlp");
+        uint256 prefix = uint8(data[offset]);
+        if (prefix < 0x80) {
+            return (offset, 1, false);
+        } else if (prefix < 0xb8) {
+            return (offset + 1, prefix - 0x80, false);
+        } else if (prefix < 0xc0) {
+            uint256 lengthOfLength = prefix - 0xb7;
+            return (
+                offset + 1 + lengthOfLength,
+                readUint(data, offset + 1, lengthOfLength),
+                false
+            );
+        } else if (prefix < 0xf8) {
+            return (offset + 1, prefix - 0xc0, true);
+        } else {
+            uint256 lengthOfLength = prefix - 0xf7;
+            return (
+                offset + 1 + lengthOfLength,
+                readUint(data, offset + 1, lengthOfLength),
+                true
+            );
+        }
+    }
+
+    /**
+     * @dev Read `length` big endian bytes starting at `offset` as a uint.
+     */
+    function readUint(
+        bytes memory data,
+        uint256 offset,
+        uint256 length
+    ) internal pure returns (uint256 value) {
+        require(length <= 32 && offset + length <= data.length, "Invalid rlp");
+        for (uint256 i = 0; i < length; i++) {
+            value = (value << 8) | uint8(data[offset + i]);
+        }
+    }
+
+    function slice(
+        bytes memory data,
+        uint256 offset,
+        uint256 length
+    ) internal pure returns (bytes memory out) {
+        require(offset + length <= data.length, "Invalid rlp");
+        out = new bytes(length);
+        for (uint256 i = 0; i < length; i++) {
+            out[i] = data[offset + i];
+        }
+    }
+
+    /**
+     * @dev Decode an rlp list into its items. String items are returned as their payload, nested lists as their full encoding.
+     */
+    function rlpList(
+        bytes memory data
+    ) internal pure returns (bytes[] memory items) {
+        (uint256 offset, uint256 length, bool isList) = rlpItem(data, 0);
+        require(isList && offset + length == data.length, "Invalid rlp list");
+        uint256 end = offset + length;
+        uint256 count = 0;
+        for (uint256 ptr = offset; ptr < end; count++) {
+            (uint256 itemOffset, uint256 itemLength, ) = rlpItem(data, ptr);
+            ptr = itemOffset + itemLength;
+        }
+        items = new bytes[](count);
+        for (uint256 i = 0; i < count; i++) {
+            (uint256 itemOffset, uint256 itemLength, bool itemIsList) = rlpItem(
+                data,
+                offset
+            );
+            items[i] = itemIsList
+                ? slice(data, offset, itemOffset + itemLength - offset)
+                : slice(data, itemOffset, itemLength);
+            offset = itemOffset + itemLength;
+        }
+    }
+
+    function nibbleAt(
+        bytes32 path,
+        uint256 index
+    ) internal pure returns (uint256) {
+        uint256 b = uint8(path[index / 2]);
+        return index % 2 == 0 ? b >> 4 : b & 0x0f;
+    }
+
+    /**
+     * @dev Match the hex prefix encoded path of an extension or leaf node against `path` from nibble `start`.
+     * @return matches - Whether the node's path is a prefix of the remaining path.
+     * @return pathLength - The number of nibbles in the node's path.
+     * @return isLeaf - Whether the node is a leaf.
+     */
+    function matchPath(
+        bytes memory encodedPath,
+        bytes32 path,
+        uint256 start
+    ) internal pure returns (bool matches, uint256 pathLength, bool isLeaf) {
+        require(encodedPath.length > 0, "Invalid proof node");
+        uint256 flag = uint8(encodedPath[0]) >> 4;
+        isLeaf = flag >= 2;
+        // odd length paths carry their first nibble in the flag byte
+        uint256 skip = 2 - (flag & 1);
+        pathLength = encodedPath.length * 2 - skip;
+        if (start + pathLength > 64) {
+            return (false, pathLength, isLeaf);
+        }
+        for (uint256 i = 0; i < pathLength; i++) {
+            uint256 n = uint8(encodedPath[(i + skip) / 2]);
+            n = (i + skip) % 2 == 0 ? n >> 4 : n & 0x0f;
+            if (n != nibbleAt(path, start + i)) {
+                return (false, pathLength, isLeaf);
+            }
+        }
+        matches = true;
+    }
+
+    /**
+     * @dev Walk a Merkle-Patricia proof from `root` to the value stored under the hashed key `path`.
+     * @return value - The rlp encoded value, empty if the proof shows the key is not in the trie.
+     */
+    function verifyMerkleProof(
+        bytes32 root,
+        bytes32 path,
+        bytes[] calldata proof
+    ) internal pure returns (bytes memory value) {
+        if (proof.length == 0) {
+            require(root == EMPTY_TRIE_ROOT, "Incomplete proof");
+            return "";
+        }
+        uint256 nibble = 0;
+        bytes32 expected = root;
+        for (uint256 i = 0; i < proof.length; i++) {
+            bytes memory node = proof[i];
+            require(keccak256(node) == expected, "Invalid proof node");
+            bytes[] memory items = rlpList(node);
+            if (items.length == 17) {
+                // branch node, follow the child for the next nibble
+                bytes memory child = items[nibbleAt(path, nibble)];
+                nibble++;
+                if (child.length == 0) {
+                    return "";
+                }
+                require(child.length == 32, "Inline nodes are unsupported");
+                expected = bytes32(child);
+            } else {
+                require(items.length == 2, "Invalid proof node");
+                (bool matches, uint256 pathLength, bool isLeaf) = matchPath(
+                    items[0],
+                    path,
+                    nibble
+                );
+                if (!matches) {
+                    // the path diverges so the key is not in the trie
+                    return "";
+                }
+                nibble += pathLength;
+                if (isLeaf) {
+                    require(nibble == 64, "Invalid leaf");
+                    return items[1];
+                }
+                require(items[1].length == 32, "Inline nodes are unsupported");
+                expected = bytes32(items[1]);
+            }
+        }
+        revert("Incomplete proof");
+    }
+
+    /**
+     * @dev Prove the value held in `slot` against an account's storage root.
+     */
+    function provenValue(
+        bytes32 storageRoot,
+        bytes32 slot,
+        bytes[] calldata proof
+    ) internal pure returns (uint256 value) {
+        bytes memory valueRlp = verifyMerkleProof(
+            storageRoot,
+            keccak256(abi.encodePacked(slot)),
+            proof
+        );
+        // slots that were never written to are not in the trie and hold zero
+        if (valueRlp.length > 0) {
+            (uint256 offset, uint256 length, ) = rlpItem(valueRlp, 0);
+            value = readUint(valueRlp, offset, length);
+        }
+    }
+
+    /**
+     * @dev Prove the storage slots of a single account and attest to their values.
+     * @return The number of instances attested to so far.
+     */
+    function attestAccount(
+        uint256[] memory instances,
+        AccountSlots storage account,
+        bytes32 stateRoot,
+        bytes[] calldata accountProof,
+        bytes[][] calldata storageProofs,
+        uint256 counter
+    ) internal view returns (uint256) {
+        bytes memory accountRlp = verifyMerkleProof(
+            stateRoot,
+            keccak256(abi.encodePacked(account.contractAddress)),
+            accountProof
+        );
+        require(accountRlp.length > 0, "Account does not exist");
+        // accounts are encoded as [nonce, balance, storageRoot, codeHash]
+        bytes32 storageRoot = bytes32(rlpList(accountRlp)[2]);
+        require(
+            storageProofs.length == account.slots.length,
+            "Invalid number of storage proofs"
+        );
+        for (uint256 j = 0; j < account.slots.length; j++) {
+            int256 quantized_data = quantizeData(
+                abi.encode(
+                    provenValue(storageRoot, account.slots[j], storageProofs[j])
+                ),
+                account.decimals[j],
+                scales[counter]
+            );
+            require(
+                toFieldElement(quantized_data) ==
+                    instances[counter + instanceOffset],
+                "Public input does not match"
+            );
+            counter++;
+        }
+        return counter;
+    }
+
+    /**
+     * @dev Prove the storage slots EZKL reads from and attest to the data.
+     * @param instances - The public instances to the proof.
+     * @param header - The rlp encoded header of `blockNumber`.
+     * @param accountProofs - The eth_getProof account proof of each account.
+     * @param storageProofs - The eth_getProof storage proofs of each slot of each account.
+     */
+    function attestStorage(
+        uint256[] memory instances,
+        bytes calldata header,
+        bytes[][] calldata accountProofs,
+        bytes[][][] calldata storageProofs
+    ) internal view {
+        require(
+            keccak256(header) == trustedBlockHash(),
+            "Invalid block header"
+        );
+        // the state root is the fourth field of the header
+        bytes32 stateRoot = bytes32(rlpList(header)[3]);
+        require(
+            accountProofs.length == accountSlots.length &&
+                storageProofs.length == accountSlots.length,
+            "Invalid number of account proofs"
+        );
+        uint256 counter = 0;
+        for (uint256 i = 0; i < accountSlots.length; ++i) {
+            counter = attestAccount(
+                instances,
+                accountSlots[i],
+                stateRoot,
+                accountProofs[i],
+                storageProofs[i],
+                counter
+            );
+        }
+    }
+
+    /**
+     * @dev Verify the proof with the storage attestation.
+     * @param verifier - The address of the verifier contract.
+     * @param encoded - The verifier calldata.
+     * @param header - The rlp encoded header of `blockNumber`.
+     * @param accountProofs - The eth_getProof account proof of each account.
+     * @param storageProofs - The eth_getProof storage proofs of each slot of each account.
+     */
+    function verifyWithStorageProofs(
+        address verifier,
+        bytes calldata encoded,
+        bytes calldata header,
+        bytes[][] calldata accountProofs,
+        bytes[][][] calldata storageProofs
+    ) public view returns (bool) {
+        require(verifier.code.length > 0, "Address: call to non-contract");
+        attestStorage(
+            getInstancesCalldata(encoded),
+            header,
+            accountProofs,
+            storageProofs
+        );
+        require(checkKzgCommits(encoded), "Invalid KZG commitments");
+        // static call the verifier contract to verify the proof
+        (bool success, bytes memory returndata) = verifier.staticcall(encoded);
+
+        if (success) {
+            return abi.decode(returndata, (bool));
+        } else {
+            revert("low-level call to verifier failed");
+        }
+    }
+}
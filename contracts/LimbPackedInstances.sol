@@ -0,0 +1,81 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.20;
+
+// This contract verifies the proofs of a circuit whose public outputs are packed several values to an instance, as the
+// limbs of the instance, and returns the values of the outputs unpacked from the instances once the proof is verified,
+// such that the caller uses the values it has been proven instead of unpacking them itself.
+
+// Overview of the contract functionality:
+// 1. Proof Verification: The `verifyProof` method calls the verifier with the proof and the instances as they are.
+// 2. Unpacking: The values of each packed output are read from the limbs of its instances, the first value in the least significant limb, and unbiased by half a limb.
+
+contract LimbPackedInstances {
+    // the number of instances of the verifier
+    uint256 constant NUM_INSTANCES = 0;
+
+    // the number of values packed to an instance, and the bits of their limbs
+    uint256 constant LIMBS = 0;
+    uint256 constant LIMB_BITS = 0;
+
+    address public immutable verifier;
+
+    constructor(address _verifier) {
+        verifier = _verifier;
+    }
+
+    /**
+     * @dev The index of the first instance of each packed output, and the number of values of the output, in the order
+     * of the instances.
+     */
+    function packedOutputs()
+        internal
+        pure
+        returns (uint256[] memory starts, uint256[] memory lens)
+    {}
+
+    /**
+     * @dev Verify a proof and unpack the values of its packed outputs.
+     * @param proof - The proof, as passed to the verifier.
+     * @param instances - The instances of the proof, with the packed outputs.
+     * @return values - The values of the packed outputs, one after the other, at the scale of the outputs.
+     */
+    function verifyProof(
+        bytes calldata proof,
+        uint256[] calldata instances
+    ) external view returns (int256[] memory values) {
+        require(
+            instances.length == NUM_INSTANCES,
+            "Wrong number of instances"
+        );
+
+        // static call the verifier contract to verify the proof
+        (bool success, bytes memory returndata) = verifier.staticcall(
+            abi.encodeWithSignature(
+                "verifyProof(bytes,uint256[])",
+                proof,
+                instances
+            )
+        );
+        require(success, "low-level call to verifier failed");
+        require(abi.decode(returndata, (bool)), "Invalid proof");
+
+        (uint256[] memory starts, uint256[] memory lens) = packedOutputs();
+        uint256 total = 0;
+        for (uint256 i = 0; i < lens.length; i++) {
+            total += lens[i];
+        }
+        values = new int256[](total);
+
+        uint256 mask = (1 << LIMB_BITS) - 1;
+        int256 bias = int256(1 << (LIMB_BITS - 1));
+        uint256 offset = 0;
+        for (uint256 i = 0; i < lens.length; i++) {
+            for (uint256 j = 0; j < lens[i]; j++) {
+                uint256 instance = instances[starts[i] + j / LIMBS];
+                uint256 limb = (instance >> (LIMB_BITS * (j % LIMBS))) & mask;
+                values[offset + j] = int256(limb) - bias;
+            }
+            offset += lens[i];
+        }
+    }
+}
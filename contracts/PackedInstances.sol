@@ -0,0 +1,198 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.20;
+
+// This contract verifies the proofs of a circuit whose large public inputs and outputs are packed: each of them is a
+// single instance of the circuit, the circomlib Poseidon digest of its values. The values are passed to this contract,
+// which hashes them as the circuit does, such that the proof is verified against the values the caller sees instead of
+// digests it has to trust.
+
+// Overview of the contract functionality:
+// 1. Hashing: The values of each packed tensor are hashed as a binary tree of 2 to 1 circomlib Poseidon hashes, the last node of a level of odd length being hashed with zero.
+// 2. Unpacking: The digests are put in place among the instances of the proof that aren't packed, at the indices given by the layout of the circuit.
+// 3. Proof Verification: The `verifyProof` method calls the verifier with the proof and the unpacked instances.
+
+contract PackedInstances {
+    /**
+     * @notice The scalar field of BN254, the instances and packed values are elements of it
+     */
+    uint256 constant ORDER =
+        uint256(
+            0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001
+        );
+
+    // the number of instances of the verifier, with the digests
+    uint256 constant NUM_INSTANCES = 0;
+
+    // 8 full rounds, half of them before the 57 partial rounds
+    uint256 constant HALF_FULL_ROUNDS = 4;
+    uint256 constant PARTIAL_ROUNDS = 57;
+    uint256 constant ROUNDS = 65;
+
+    address public immutable verifier;
+
+    constructor(address _verifier) {
+        verifier = _verifier;
+    }
+
+    /**
+     * @dev The index of the digest of each packed tensor in the instances, and the number of values of the tensor, in
+     * the order their values are passed in.
+     */
+    function packedTensors()
+        internal
+        pure
+        returns (uint256[] memory indices, uint256[] memory lens)
+    {}
+
+    /**
+     * @dev The round constants of the circomlib Poseidon permutation of width 3, the constants of a round follow those
+     * of the previous one.
+     */
+    function roundConstants() internal pure returns (uint256[195] memory c) {}
+
+    /**
+     * @dev The MDS matrix of the circomlib Poseidon permutation of width 3, row by row.
+     */
+    function mds() internal pure returns (uint256[9] memory m) {}
+
+    function sbox(uint256 x) internal pure returns (uint256) {
+        uint256 x2 = mulmod(x, x, ORDER);
+        return mulmod(mulmod(x2, x2, ORDER), x, ORDER);
+    }
+
+    /**
+     * @dev The circomlib Poseidon hash of 2 elements, the first element of the permutation of `[0, left, right]`.
+     */
+    function hash(
+        uint256 left,
+        uint256 right,
+        uint256[195] memory c,
+        uint256[9] memory m
+    ) internal pure returns (uint256) {
+        uint256 s0 = 0;
+        uint256 s1 = left;
+        uint256 s2 = right;
+        for (uint256 r = 0; r < ROUNDS; r++) {
+            s0 = sbox(addmod(s0, c[3 * r], ORDER));
+            s1 = addmod(s1, c[3 * r + 1], ORDER);
+            s2 = addmod(s2, c[3 * r + 2], ORDER);
+            if (r < HALF_FULL_ROUNDS || r >= HALF_FULL_ROUNDS + PARTIAL_ROUNDS) {
+                s1 = sbox(s1);
+                s2 = sbox(s2);
+            }
+            (s0, s1, s2) = (
+                addmod(
+                    addmod(mulmod(m[0], s0, ORDER), mulmod(m[1], s1, ORDER), ORDER),
+                    mulmod(m[2], s2, ORDER),
+                    ORDER
+                ),
+                addmod(
+                    addmod(mulmod(m[3], s0, ORDER), mulmod(m[4], s1, ORDER), ORDER),
+                    mulmod(m[5], s2, ORDER),
+                    ORDER
+                ),
+                addmod(
+                    addmod(mulmod(m[6], s0, ORDER), mulmod(m[7], s1, ORDER), ORDER),
+                    mulmod(m[8], s2, ORDER),
+                    ORDER
+                )
+            );
+        }
+        return s0;
+    }
+
+    /**
+     * @dev The digest of the values of a packed tensor, the root of the binary tree of their hashes.
+     */
+    function digest(
+        uint256[] calldata values,
+        uint256[195] memory c,
+        uint256[9] memory m
+    ) internal pure returns (uint256) {
+        uint256 len = values.length;
+        uint256 n = (len + 1) / 2;
+        uint256[] memory level = new uint256[](n);
+        for (uint256 i = 0; i < n; i++) {
+            level[i] = hash(
+                values[2 * i],
+                2 * i + 1 < len ? values[2 * i + 1] : 0,
+                c,
+                m
+            );
+        }
+        // the hashes of a level are written over the level they hash, which is read ahead of them
+        while (n > 1) {
+            uint256 next = (n + 1) / 2;
+            for (uint256 i = 0; i < next; i++) {
+                level[i] = hash(
+                    level[2 * i],
+                    2 * i + 1 < n ? level[2 * i + 1] : 0,
+                    c,
+                    m
+                );
+            }
+            n = next;
+        }
+        return level[0];
+    }
+
+    /**
+     * @dev Verify a proof against the instances that aren't packed and the values of the packed tensors.
+     * @param proof - The proof, as passed to the verifier.
+     * @param instances - The instances of the proof, without the digests of the packed tensors.
+     * @param values - The values of the packed tensors, one after the other.
+     */
+    function verifyProof(
+        bytes calldata proof,
+        uint256[] calldata instances,
+        uint256[] calldata values
+    ) external view returns (bool) {
+        (uint256[] memory indices, uint256[] memory lens) = packedTensors();
+        require(
+            instances.length + indices.length == NUM_INSTANCES,
+            "Wrong number of instances"
+        );
+        for (uint256 i = 0; i < values.length; i++) {
+            require(values[i] < ORDER, "Invalid field element");
+        }
+
+        uint256[195] memory c = roundConstants();
+        uint256[9] memory m = mds();
+        uint256[] memory unpacked = new uint256[](NUM_INSTANCES);
+        uint256 packed = 0;
+        uint256 offset = 0;
+        for (uint256 i = 0; i < NUM_INSTANCES; i++) {
+            if (packed < indices.length && indices[packed] == i) {
+                require(
+                    offset + lens[packed] <= values.length,
+                    "Not enough packed values"
+                );
+                unpacked[i] = digest(
+                    values[offset:offset + lens[packed]],
+                    c,
+                    m
+                );
+                offset += lens[packed];
+                packed++;
+            } else {
+                unpacked[i] = instances[i - packed];
+            }
+        }
+        require(offset == values.length, "Too many packed values");
+
+        // static call the verifier contract to verify the proof
+        (bool success, bytes memory returndata) = verifier.staticcall(
+            abi.encodeWithSignature(
+                "verifyProof(bytes,uint256[])",
+                proof,
+                unpacked
+            )
+        );
+
+        if (success) {
+            return abi.decode(returndata, (bool));
+        } else {
+            revert("low-level call to verifier failed");
+        }
+    }
+}
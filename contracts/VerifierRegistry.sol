@@ -0,0 +1,78 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.20;
+
+// This contract maps the fingerprint of a model's circuit settings to the verifier deployed for it.
+// Contracts that verify proofs of a model can resolve its verifier through the registry, so redeploying
+// the verifier on a model update doesn't change the address they call.
+
+// Overview of the contract functionality:
+// 1. Registration: The `register` method records the verifier of a fingerprint, replacing the previous one. Only the owner can register verifiers.
+// 2. Resolution: The `resolve` method returns the verifier registered for a fingerprint, reverting if there is none.
+// 3. Proof Verification: The `verifyProof` method forwards the verifier calldata to the verifier registered for a fingerprint.
+
+contract VerifierRegistry {
+    address public owner;
+
+    mapping(bytes32 => address) public verifiers;
+
+    event VerifierRegistered(
+        bytes32 indexed fingerprint,
+        address indexed verifier,
+        address previous
+    );
+
+    constructor(address _owner) {
+        owner = _owner;
+    }
+
+    function updateOwner(address _owner) external {
+        require(msg.sender == owner, "Only owner can update owner");
+        if (_owner == address(0)) {
+            revert();
+        }
+        owner = _owner;
+    }
+
+    /**
+     * @dev Register the verifier of a model, replacing the verifier previously registered for it.
+     * @param fingerprint - The keccak256 hash of the model's circuit settings.
+     * @param verifier - The address of the deployed verifier.
+     */
+    function register(bytes32 fingerprint, address verifier) external {
+        require(msg.sender == owner, "Only owner can register verifiers");
+        require(verifier.code.length > 0, "Address: call to non-contract");
+        emit VerifierRegistered(fingerprint, verifier, verifiers[fingerprint]);
+        verifiers[fingerprint] = verifier;
+    }
+
+    /**
+     * @dev Resolve the verifier registered for a model.
+     * @param fingerprint - The keccak256 hash of the model's circuit settings.
+     */
+    function resolve(
+        bytes32 fingerprint
+    ) public view returns (address verifier) {
+        verifier = verifiers[fingerprint];
+        require(verifier != address(0), "No verifier registered");
+    }
+
+    /**
+     * @dev Verify a proof with the verifier registered for a model.
+     * @param fingerprint - The keccak256 hash of the model's circuit settings.
+     * @param encoded - The verifier calldata.
+     */
+    function verifyProof(
+        bytes32 fingerprint,
+        bytes calldata encoded
+    ) external view returns (bool) {
+        // static call the verifier contract to verify the proof
+        (bool success, bytes memory returndata) = resolve(fingerprint)
+            .staticcall(encoded);
+
+        if (success) {
+            return abi.decode(returndata, (bool));
+        } else {
+            revert("low-level call to verifier failed");
+        }
+    }
+}
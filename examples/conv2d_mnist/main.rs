@@ -1,6 +1,6 @@
 use ezkl::circuit::region::RegionCtx;
 use ezkl::circuit::{
-    ops::lookup::LookupOp, ops::poly::PolyOp, BaseConfig as PolyConfig, CheckMode,
+    ops::lookup::LookupOp, ops::poly::PolyOp, BaseConfig as PolyConfig, CheckMode, DivRounding,
 };
 use ezkl::fieldutils::{self, integer_rep_to_felt, IntegerRep};
 use ezkl::tensor::*;
@@ -175,7 +175,10 @@ where
                 &params,
                 (LOOKUP_MIN, LOOKUP_MAX),
                 K,
-                &LookupOp::Div { denom: 32.0.into() },
+                &LookupOp::Div {
+                    denom: 32.0.into(),
+                    rounding: DivRounding::Round,
+                },
             )
             .unwrap();
 
@@ -233,7 +236,10 @@ where
                         .layout(
                             &mut region,
                             &[x.unwrap()],
-                            Box::new(LookupOp::Div { denom: 32.0.into() }),
+                            Box::new(LookupOp::Div {
+                                denom: 32.0.into(),
+                                rounding: DivRounding::Round,
+                            }),
                         )
                         .unwrap()
                         .unwrap();
@@ -1,6 +1,6 @@
 use ezkl::circuit::region::RegionCtx;
 use ezkl::circuit::{
-    ops::lookup::LookupOp, ops::poly::PolyOp, BaseConfig as PolyConfig, CheckMode,
+    ops::lookup::LookupOp, ops::poly::PolyOp, BaseConfig as PolyConfig, CheckMode, DivRounding,
 };
 use ezkl::fieldutils::{integer_rep_to_felt, IntegerRep};
 use ezkl::tensor::*;
@@ -84,6 +84,7 @@ impl<const LEN: usize, const LOOKUP_MIN: IntegerRep, const LOOKUP_MAX: IntegerRe
                 K,
                 &LookupOp::Div {
                     denom: ezkl::circuit::utils::F32::from(128.),
+                    rounding: DivRounding::Round,
                 },
             )
             .unwrap();
@@ -196,6 +197,7 @@ impl<const LEN: usize, const LOOKUP_MIN: IntegerRep, const LOOKUP_MAX: IntegerRe
                             &[x.unwrap()],
                             Box::new(LookupOp::Div {
                                 denom: ezkl::circuit::utils::F32::from(128.),
+                                rounding: DivRounding::Round,
                             }),
                         )
                         .unwrap())
@@ -0,0 +1,42 @@
+from torch import nn
+import torch
+import json
+
+
+class MyModel(nn.Module):
+    def __init__(self, bag):
+        super(MyModel, self).__init__()
+        self.embedding = nn.Embedding.from_pretrained(bag.weight)
+
+    def forward(self, x):
+        # exported as a gather summed over each bag, as torch exports static embedding bags as a loop
+        return self.embedding(x).sum(dim=1)
+
+
+bag = nn.EmbeddingBag(20, 8, mode="sum")
+circuit = MyModel(bag)
+
+x = torch.randint(0, 20, (4, 5))
+
+# the reference the model is checked against
+reference = bag(x)
+assert torch.allclose(circuit(x), reference)
+
+torch.onnx.export(circuit, x, "network.onnx",
+                  export_params=True,        # store the trained parameter weights inside the model file
+                  opset_version=15,          # the ONNX version to export the model to
+                  do_constant_folding=True,  # whether to execute constant folding for optimization
+                  input_names=['input'],   # the model's input names
+                  output_names=['output'])  # the model's output names
+
+
+d = ((x).detach().numpy()).reshape([-1]).tolist()
+d1 = ((reference).detach().numpy()).reshape([-1]).tolist()
+
+data = dict(
+    input_data=[d],
+    output_data=[d1],
+)
+
+# Serialize data into file:
+json.dump(data, open("input.json", 'w'))
@@ -4,15 +4,19 @@
 static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
 
 #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
-use clap::{CommandFactory, Parser};
+use clap::CommandFactory;
 #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
 use colored_json::ToColoredJson;
 #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
 use ezkl::commands::Cli;
 #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
-use ezkl::execute::run;
+use ezkl::config::parse_args;
 #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
-use ezkl::logger::init_logger;
+use ezkl::execute::{run_json, run_with_timeout, CommandStatus};
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+use ezkl::logger::{init_logger, init_logger_with_target};
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+use ezkl::profiling::Profiler;
 #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
 use log::{error, info};
 #[cfg(not(any(target_arch = "wasm32", feature = "no-banner")))]
@@ -20,16 +24,57 @@ use rand::prelude::SliceRandom;
 #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
 #[cfg(feature = "icicle")]
 use std::env;
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+use tracing::Instrument;
 
 #[tokio::main(flavor = "current_thread")]
 #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
 pub async fn main() {
-    let args = Cli::parse();
+    // the arguments omitted on the command line are filled in from the project configuration
+    let (args, matches) = match parse_args() {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            init_logger();
+            error!("{}", e);
+            std::process::exit(CommandStatus::UserError.exit_code())
+        }
+    };
 
     if let Some(generator) = args.generator {
         ezkl::commands::print_completions(generator, &mut Cli::command());
     } else if let Some(command) = args.command {
-        init_logger();
+        if args.json {
+            // stdout is left to the JSON output
+            init_logger_with_target(env_logger::Target::Stderr);
+        } else {
+            init_logger();
+        }
+        ezkl::pfsys::srs::set_skip_srs_check(args.skip_srs_check);
+        ezkl::pfsys::srs::set_offline(args.offline);
+        ezkl::pfsys::srs::set_srs_mirrors(args.srs_mirrors);
+        ezkl::graph::schema::set_lenient_json(args.lenient_json);
+        // without a subscriber the spans are disabled, the profile costs nothing unless it's asked for, or the memory
+        // of the phases is reported with --json
+        let profiler = if args.profile_json.is_some() || args.json {
+            Profiler::start()
+                .map_err(|e| error!("failed to start profiling: {}", e))
+                .ok()
+        } else {
+            None
+        };
+        let write_profile = || {
+            if let (Some(profiler), Some(path)) = (&profiler, &args.profile_json) {
+                match profiler.write(path) {
+                    Ok(()) => info!("profile written to {}", path.display()),
+                    Err(e) => error!("failed to write profile {}: {}", path.display(), e),
+                }
+            }
+        };
+        let timeout = args.timeout.map(std::time::Duration::from_secs);
+        let span = tracing::info_span!(
+            "ezkl",
+            command = matches.subcommand_name().unwrap_or_default()
+        );
         #[cfg(not(any(target_arch = "wasm32", feature = "no-banner")))]
         banner();
         #[cfg(feature = "icicle")]
@@ -42,20 +87,30 @@ pub async fn main() {
             "command: \n {}",
             &command.as_json().to_colored_json_auto().unwrap()
         );
-        let res = run(command).await;
+        if args.json {
+            let name = matches.subcommand_name().unwrap_or_default();
+            let output = run_json(name, command, timeout).instrument(span).await;
+            write_profile();
+            println!("{}", serde_json::to_string(&output).unwrap());
+            std::process::exit(output.exit_code)
+        }
+        let res = run_with_timeout(command.clone(), timeout)
+            .instrument(span)
+            .await;
+        write_profile();
         match &res {
             Ok(_) => {
                 info!("succeeded");
             }
             Err(e) => {
                 error!("{}", e);
-                std::process::exit(1)
+                std::process::exit(CommandStatus::of_error(&command, e).exit_code())
             }
         }
     } else {
         init_logger();
         error!("No command provided");
-        std::process::exit(1)
+        std::process::exit(CommandStatus::UserError.exit_code())
     }
 }
 
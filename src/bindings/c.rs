@@ -0,0 +1,200 @@
+//! A C ABI for verifying proofs and generating witnesses from in-memory artifacts, declared in the `ezkl.h` header
+//! generated into the target directory by the build script.
+//!
+//! Ownership: every input buffer is borrowed for the duration of the call, and outputs are written to buffers the
+//! caller allocates. A function with an output first writes the length of the output to `out_len`, and returns
+//! [EZKL_BUFFER_TOO_SMALL] without writing the output if `out` is null or `out_cap` is smaller, such that the caller
+//! can call it again with a buffer of `*out_len` bytes. The message of the last error of a thread is owned by the
+//! library, see [ezkl_last_error_message].
+
+use crate::circuit::region::RegionSettings;
+use crate::graph::input::GraphData;
+use crate::graph::GraphCircuit;
+use crate::pfsys::verifier::{verify_proof_bytes, VerifierParams};
+use crate::pfsys::{PfsysError, Snark};
+use halo2_proofs::poly::kzg::commitment::KZGCommitmentScheme;
+use halo2curves::bn256::{Bn256, Fr, G1Affine};
+use std::cell::RefCell;
+use std::ffi::{c_char, CString};
+use std::panic::{catch_unwind, UnwindSafe};
+
+/// The call succeeded, and the proof verified for [ezkl_verify]
+pub const EZKL_OK: i32 = 1;
+/// The proof didn't verify
+pub const EZKL_NOT_VERIFIED: i32 = 0;
+/// The call failed, see [ezkl_last_error_message]
+pub const EZKL_ERROR: i32 = -1;
+/// The output buffer is null or too small for the output, whose length was written to `out_len`
+pub const EZKL_BUFFER_TOO_SMALL: i32 = -2;
+
+thread_local!(
+    /// The message of the last error of a call on this thread
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) }
+);
+
+fn set_last_error(message: String) {
+    // the message is truncated at an interior nul rather than dropped
+    let message = message.split('\0').next().unwrap_or_default().to_string();
+    LAST_ERROR.with(|e| *e.borrow_mut() = CString::new(message).ok());
+}
+
+/// Runs a call of the ABI, clearing the last error first and turning its errors and panics into [EZKL_ERROR]
+fn ffi_call(f: impl FnOnce() -> Result<i32, String> + UnwindSafe) -> i32 {
+    LAST_ERROR.with(|e| *e.borrow_mut() = None);
+    match catch_unwind(f) {
+        Ok(Ok(status)) => status,
+        Ok(Err(message)) => {
+            set_last_error(message);
+            EZKL_ERROR
+        }
+        Err(panic) => {
+            let message = panic
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| panic.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_string());
+            set_last_error(format!("panicked: {}", message));
+            EZKL_ERROR
+        }
+    }
+}
+
+/// The bytes of a buffer of the caller, a null buffer being empty only if its length is 0
+///
+/// # Safety
+/// `ptr` must be valid for reads of `len` bytes for the duration of the call
+unsafe fn input<'a>(ptr: *const u8, len: usize, name: &str) -> Result<&'a [u8], String> {
+    if ptr.is_null() {
+        if len == 0 {
+            return Ok(&[]);
+        }
+        return Err(format!("{} is null", name));
+    }
+    Ok(std::slice::from_raw_parts(ptr, len))
+}
+
+/// Writes an output to the buffer of the caller, see the ownership rules of the module
+///
+/// # Safety
+/// `out` must be null or valid for writes of `out_cap` bytes, and `out_len` must be valid for a write
+unsafe fn output(
+    bytes: &[u8],
+    out: *mut u8,
+    out_cap: usize,
+    out_len: *mut usize,
+) -> Result<i32, String> {
+    if out_len.is_null() {
+        return Err("out_len is null".to_string());
+    }
+    *out_len = bytes.len();
+    if out.is_null() || out_cap < bytes.len() {
+        return Ok(EZKL_BUFFER_TOO_SMALL);
+    }
+    std::ptr::copy_nonoverlapping(bytes.as_ptr(), out, bytes.len());
+    Ok(EZKL_OK)
+}
+
+/// Verifies a proof .json against the bytes of the verifying key, the settings .json and the srs of its circuit.
+/// Returns [EZKL_OK] if it verifies, [EZKL_NOT_VERIFIED] if it doesn't, and [EZKL_ERROR] if the artifacts can't be
+/// read or don't fit together.
+///
+/// # Safety
+/// Each pointer must be valid for reads of its length for the duration of the call
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn ezkl_verify(
+    proof: *const u8,
+    proof_len: usize,
+    vk: *const u8,
+    vk_len: usize,
+    settings: *const u8,
+    settings_len: usize,
+    srs: *const u8,
+    srs_len: usize,
+) -> i32 {
+    ffi_call(|| {
+        let proof = input(proof, proof_len, "proof")?;
+        let vk = input(vk, vk_len, "vk")?;
+        let settings = input(settings, settings_len, "settings")?;
+        let srs = input(srs, srs_len, "srs")?;
+
+        let snark: Snark<Fr, G1Affine> = serde_json::from_slice(proof)
+            .map_err(|e| format!("failed to deserialize the proof: {}", e))?;
+        let mut params = VerifierParams::from_bytes(settings, srs).map_err(|e| e.to_string())?;
+        params.transcript = snark.transcript_type;
+
+        match verify_proof_bytes(vk, &snark.proof, &snark.instances.concat(), &params) {
+            Ok(()) => Ok(EZKL_OK),
+            Err(PfsysError::Halo2Error(e)) => {
+                set_last_error(format!("the proof didn't verify: {}", e));
+                Ok(EZKL_NOT_VERIFIED)
+            }
+            Err(e) => Err(e.to_string()),
+        }
+    })
+}
+
+/// Generates the witness .json of a compiled circuit for an input .json, see the module for the negotiation of the
+/// length of `out`. The witness is generated again when called again with a larger buffer. Returns [EZKL_OK],
+/// [EZKL_BUFFER_TOO_SMALL] or [EZKL_ERROR].
+///
+/// # Safety
+/// `compiled_circuit` and `input_data` must be valid for reads of their lengths, `out` must be null or valid for
+/// writes of `out_cap` bytes, and `out_len` must be valid for a write
+#[no_mangle]
+pub unsafe extern "C" fn ezkl_gen_witness(
+    compiled_circuit: *const u8,
+    compiled_circuit_len: usize,
+    input_data: *const u8,
+    input_data_len: usize,
+    out: *mut u8,
+    out_cap: usize,
+    out_len: *mut usize,
+) -> i32 {
+    ffi_call(|| {
+        let compiled_circuit = input(compiled_circuit, compiled_circuit_len, "compiled_circuit")?;
+        let input_data = input(input_data, input_data_len, "input_data")?;
+
+        let mut circuit: GraphCircuit = bincode::deserialize(compiled_circuit)
+            .map_err(|e| format!("failed to deserialize the compiled circuit: {}", e))?;
+        let data: GraphData = serde_json::from_slice(input_data)
+            .map_err(|e| format!("failed to deserialize the input: {}", e))?;
+
+        #[cfg(any(not(feature = "ezkl"), target_arch = "wasm32"))]
+        let inputs = circuit.load_graph_input(&data);
+        #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+        let inputs = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| e.to_string())?
+            .block_on(circuit.load_graph_input(&data));
+        let mut inputs = inputs.map_err(|e| e.to_string())?;
+
+        let settings = circuit.settings();
+        let witness = circuit
+            .forward::<KZGCommitmentScheme<Bn256>>(
+                &mut inputs,
+                None,
+                None,
+                RegionSettings::all_true(
+                    settings.run_args.decomp_base,
+                    settings.run_args.decomp_legs,
+                ),
+            )
+            .map_err(|e| e.to_string())?;
+        let witness = serde_json::to_vec(&witness)
+            .map_err(|e| format!("failed to serialize the witness: {}", e))?;
+
+        output(&witness, out, out_cap, out_len)
+    })
+}
+
+/// The message of the last error of a call on this thread, or null if the last call succeeded. The message is owned
+/// by the library and valid until the next call on the thread, the caller must not free it.
+#[no_mangle]
+pub extern "C" fn ezkl_last_error_message() -> *const c_char {
+    LAST_ERROR.with(|e| match &*e.borrow() {
+        Some(message) => message.as_ptr(),
+        None => std::ptr::null(),
+    })
+}
@@ -1,3 +1,6 @@
+/// C ABI for verification and witness generation
+#[cfg(feature = "c-bindings")]
+pub mod c;
 /// Python bindings
 #[cfg(feature = "python-bindings")]
 pub mod python;
@@ -4,34 +4,229 @@ use crate::circuit::modules::poseidon::{
     PoseidonChip,
 };
 use crate::circuit::modules::Module;
-use crate::circuit::{CheckMode, Tolerance};
+use crate::circuit::{CheckMode, DivRounding, RangeCheckStrategy, Tolerance};
 use crate::commands::*;
+use crate::execute::DataInput;
 use crate::fieldutils::{felt_to_integer_rep, integer_rep_to_felt, IntegerRep};
-use crate::graph::modules::POSEIDON_LEN_GRAPH;
+use crate::graph::input::{felt_from_str, FileSourceInner};
+use crate::graph::modules::{ModuleCircomlib, ModuleElGamal, POSEIDON_LEN_GRAPH};
 use crate::graph::TestDataSource;
 use crate::graph::{
-    quantize_float, scale_to_multiplier, GraphCircuit, GraphSettings, Model, Visibility,
+    quantize_float, scale_to_multiplier, CommitmentGroup, GraphCircuit, GraphSettings,
+    GraphWitness, Model, OutputBound, OutputScale, PoseidonParams, QuantRounding, RescaleOverride,
+    Visibility,
 };
 use crate::pfsys::evm::aggregation_kzg::AggregationCircuit;
+use crate::pfsys::progress::{with_observer, CancellationToken};
 use crate::pfsys::{
     load_pk, load_vk, save_params, save_vk, srs::gen_srs as ezkl_gen_srs, srs::load_srs_prover,
-    ProofType, TranscriptType,
+    ProofType, Snark, TranscriptType,
 };
 use crate::Commitments;
+use crate::EZKLError;
 use crate::RunArgs;
 use halo2_proofs::poly::ipa::commitment::IPACommitmentScheme;
 use halo2_proofs::poly::kzg::commitment::KZGCommitmentScheme;
 use halo2curves::bn256::{Bn256, Fq, Fr, G1Affine, G1};
-use pyo3::exceptions::{PyIOError, PyRuntimeError};
+use numpy::ndarray::{ArrayD, IxDyn};
+use numpy::{IntoPyArray, PyArrayDyn, PyArrayMethods, PyUntypedArray, PyUntypedArrayMethods};
+use pyo3::exceptions::{PyIOError, PyRuntimeError, PyValueError};
 use pyo3::prelude::*;
+use pyo3::types::{PyList, PyTuple};
 use pyo3::wrap_pyfunction;
 use pyo3_log;
 use snark_verifier::util::arithmetic::PrimeField;
 use std::str::FromStr;
 use std::{fs::File, path::PathBuf};
 
+mod errors;
+
+use self::errors::{ezkl_error, input_error, verify_error};
+
 type PyFelt = String;
 
+/// The DLPack device type of host memory
+const DLPACK_CPU: i32 = 1;
+
+/// Whether an input is a numpy array or a tensor implementing the DLPack protocol (torch, jax, cupy...)
+fn is_array(input: &Bound<'_, PyAny>) -> bool {
+    input.downcast::<PyUntypedArray>().is_ok() || input.hasattr("__dlpack__").unwrap_or(false)
+}
+
+/// Converts a tensor implementing the DLPack protocol to a numpy array sharing its memory, or a copy of it on the host
+/// when it lives on another device
+fn dlpack_to_numpy<'py>(tensor: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+    let numpy = PyModule::import_bound(tensor.py(), "numpy")?;
+    let mut tensor = tensor.clone();
+    // torch refuses to export the tensors that require gradients
+    if tensor
+        .getattr("requires_grad")
+        .and_then(|r| r.is_truthy())
+        .unwrap_or(false)
+    {
+        tensor = tensor.call_method0("detach")?;
+    }
+    let (device, _): (i32, i32) = tensor.call_method0("__dlpack_device__")?.extract()?;
+    if device != DLPACK_CPU {
+        // torch tensors are copied to the host with `cpu`, cupy arrays with `get` and jax arrays by numpy
+        if tensor.hasattr("cpu")? {
+            tensor = tensor.call_method0("cpu")?;
+        } else if tensor.hasattr("get")? {
+            return tensor.call_method0("get");
+        } else {
+            return numpy.call_method1("asarray", (tensor,));
+        }
+    }
+    numpy.call_method1("from_dlpack", (tensor,))
+}
+
+/// Reads the numpy arrays or DLPack tensors passed for the inputs of a model, borrowing their buffers rather than
+/// copying them
+fn numpy_arrays(inputs: &[Bound<'_, PyAny>]) -> PyResult<Vec<(Vec<FileSourceInner>, Vec<usize>)>> {
+    macro_rules! read_array {
+        ($input:expr, $($t:ty => $inner:expr),*) => {
+            $(
+                if let Ok(array) = $input.downcast::<PyArrayDyn<$t>>() {
+                    let array = array.readonly();
+                    let view = array.as_array();
+                    return Ok((view.iter().map(|x| $inner(*x)).collect(), view.shape().to_vec()));
+                }
+            )*
+        };
+    }
+
+    inputs
+        .iter()
+        .enumerate()
+        .map(|(i, input)| {
+            let converted;
+            let input = if input.downcast::<PyUntypedArray>().is_err() && is_array(input) {
+                converted = dlpack_to_numpy(input)?;
+                &converted
+            } else {
+                input
+            };
+            read_array!(
+                input,
+                f64 => FileSourceInner::Float,
+                f32 => |x: f32| FileSourceInner::Float(x as f64),
+                i64 => |x: i64| FileSourceInner::Float(x as f64),
+                i32 => |x: i32| FileSourceInner::Float(x as f64),
+                bool => FileSourceInner::Bool
+            );
+            let py = input.py();
+            let dtype = input
+                .getattr("dtype")
+                .map(|d| d.to_string())
+                .unwrap_or_else(|_| input.get_type().to_string());
+            Err(input_error(
+                py,
+                format!(
+                    "input {} must be a numpy array or DLPack tensor of float64, float32, int64, int32 or bool, got {}",
+                    i, dtype
+                ),
+                vec![("input", i.to_object(py)), ("dtype", dtype.to_object(py))],
+            ))
+        })
+        .collect()
+}
+
+/// Reads the numpy arrays or DLPack tensors passed for the inputs of a model
+fn numpy_inputs(inputs: &[Bound<'_, PyAny>]) -> PyResult<DataInput> {
+    Ok(DataInput::Arrays(numpy_arrays(inputs)?))
+}
+
+/// Reads the calibration inputs of a model: one array per input with the batches stacked along the first dimension,
+/// or an iterable of batches (as a torch `DataLoader`), each holding one array per input or a single array for models
+/// with a single input. The batches are stacked along their first dimension
+fn numpy_batches(inputs: &Bound<'_, PyAny>) -> PyResult<DataInput> {
+    let py = inputs.py();
+    if is_array(inputs) {
+        return numpy_inputs(&[inputs.clone()]);
+    }
+    if inputs.downcast::<PyList>().is_ok() || inputs.downcast::<PyTuple>().is_ok() {
+        let arrays = inputs.iter()?.collect::<PyResult<Vec<_>>>()?;
+        if arrays.first().map_or(true, is_array) {
+            return numpy_inputs(&arrays);
+        }
+    }
+
+    let mut stacked: Vec<(Vec<FileSourceInner>, Vec<usize>)> = vec![];
+    for (b, batch) in inputs.iter()?.enumerate() {
+        let batch = batch?;
+        let arrays = if is_array(&batch) {
+            numpy_arrays(&[batch])?
+        } else {
+            numpy_arrays(&batch.iter()?.collect::<PyResult<Vec<_>>>()?)?
+        };
+        if b > 0 && arrays.len() != stacked.len() {
+            return Err(input_error(
+                py,
+                format!(
+                    "batch {} has {} inputs, the first batch has {}",
+                    b,
+                    arrays.len(),
+                    stacked.len()
+                ),
+                vec![
+                    ("batch", b.to_object(py)),
+                    ("expected", stacked.len().to_object(py)),
+                    ("actual", arrays.len().to_object(py)),
+                ],
+            ));
+        }
+        for (i, (values, shape)) in arrays.into_iter().enumerate() {
+            let Some((stacked_values, stacked_shape)) = stacked.get_mut(i) else {
+                stacked.push((values, shape));
+                continue;
+            };
+            if shape.is_empty()
+                || stacked_shape.len() != shape.len()
+                || shape[1..] != stacked_shape[1..]
+            {
+                return Err(input_error(
+                    py,
+                    format!(
+                        "input {} of batch {} has shape {:?}, which doesn't stack onto {:?}",
+                        i, b, shape, stacked_shape
+                    ),
+                    vec![
+                        ("input", i.to_object(py)),
+                        ("batch", b.to_object(py)),
+                        ("expected_shape", stacked_shape.to_object(py)),
+                        ("actual_shape", shape.to_object(py)),
+                    ],
+                ));
+            }
+            stacked_values.extend(values);
+            stacked_shape[0] += shape[0];
+        }
+    }
+    if stacked.is_empty() {
+        return Err(input_error(
+            py,
+            "no calibration batches".to_string(),
+            vec![],
+        ));
+    }
+    Ok(DataInput::Arrays(stacked))
+}
+
+/// Hands the flattened values of each output over to python as numpy arrays of the given shapes
+fn numpy_outputs<T: numpy::Element>(
+    py: Python<'_>,
+    outputs: Vec<(Vec<T>, Vec<usize>)>,
+) -> PyResult<Vec<PyObject>> {
+    outputs
+        .into_iter()
+        .map(|(data, shape)| {
+            let array = ArrayD::from_shape_vec(IxDyn(&shape), data)
+                .map_err(|e| ezkl_error("Failed to shape output", e.to_string()))?;
+            Ok(array.into_pyarray_bound(py).into_any().unbind())
+        })
+        .collect()
+}
+
 /// pyclass representing an enum
 #[pyclass]
 #[derive(Debug, Clone)]
@@ -40,6 +235,10 @@ enum PyTestDataSource {
     File,
     /// The data is loaded from the chain
     OnChain,
+    /// The data is loaded from contract storage and attested with storage proofs
+    StorageProof,
+    /// The data is loaded from the chain, through view functions returning nested types
+    NestedOnChain,
 }
 
 impl From<PyTestDataSource> for TestDataSource {
@@ -47,6 +246,8 @@ impl From<PyTestDataSource> for TestDataSource {
         match py_test_data_source {
             PyTestDataSource::File => TestDataSource::File,
             PyTestDataSource::OnChain => TestDataSource::OnChain,
+            PyTestDataSource::StorageProof => TestDataSource::StorageProof,
+            PyTestDataSource::NestedOnChain => TestDataSource::NestedOnChain,
         }
     }
 }
@@ -165,16 +366,16 @@ struct PyRunArgs {
     /// int: The log_2 number of rows
     pub logrows: u32,
     #[pyo3(get, set)]
-    /// int: The number of inner columns used for the lookup table
+    /// int: The number of inner columns of the advice blocks, 0 picks the number that takes the least area while fitting in logrows
     pub num_inner_cols: usize,
     #[pyo3(get, set)]
-    /// string: accepts `public`, `private`, `fixed`, `hashed/public`, `hashed/private`, `polycommit`
+    /// string: accepts `public`, `private`, `fixed`, `hashed/public`, `hashed/private`, `hashed/sha256/public`, `hashed/sha256/private`, `polycommit`, `merkle/<depth>`
     pub input_visibility: Visibility,
     #[pyo3(get, set)]
-    /// string: accepts `public`, `private`, `fixed`, `hashed/public`, `hashed/private`, `polycommit`
+    /// string: accepts `public`, `private`, `fixed`, `hashed/public`, `hashed/private`, `hashed/sha256/public`, `hashed/sha256/private`, `polycommit`
     pub output_visibility: Visibility,
     #[pyo3(get, set)]
-    /// string: accepts `public`, `private`, `fixed`, `hashed/public`, `hashed/private`, `polycommit`
+    /// string: accepts `public`, `private`, `fixed`, `hashed/public`, `hashed/private`, `hashed/sha256/public`, `hashed/sha256/private`, `polycommit`
     pub param_visibility: Visibility,
     #[pyo3(get, set)]
     /// list[tuple[str, int]]: Hand-written parser for graph variables, eg. batch_size=1
@@ -197,6 +398,72 @@ struct PyRunArgs {
     /// int: The number of legs used for decomposition
     #[pyo3(get, set)]
     pub decomp_legs: usize,
+    /// list[str]: names of the outputs of the onnx model to keep, the nodes that don't feed them are removed before quantization. All the outputs are kept if empty
+    #[pyo3(get, set)]
+    pub outputs: Vec<String>,
+    /// list[str]: per output visibilities overriding output_visibility, one for each output. Accepts `public`, `private`, `fixed`, `hashed/public`, `hashed/sha256/public`, `polycommit`, with at most one of the hashed or polycommit visibilities
+    #[pyo3(get, set)]
+    pub output_visibilities: Vec<Visibility>,
+    /// list[int]: outputs hashed in public (`hashed/public`, `hashed/sha256/public`) that are also exposed in the clear as public instances, by index
+    #[pyo3(get, set)]
+    pub revealed_outputs: Vec<usize>,
+    /// list[str]: inclusive bounds asserted in the circuit on outputs, as `output:lower:upper` with either bound possibly empty, eg. `0::0.2`. The bounds are in float and converted at the output scale
+    #[pyo3(get, set)]
+    pub output_bounds: Vec<OutputBound>,
+    /// str: the parameters of the poseidon hash of the hashed visibilities, accepts `ezkl`, `circomlib`
+    #[pyo3(get, set)]
+    pub poseidon_params: PoseidonParams,
+    /// float | None: constant params with at least this fraction of zeros are represented sparsely, the positions of their zeros then become part of the circuit
+    #[pyo3(get, set)]
+    pub sparsity_threshold: Option<f32>,
+    /// float | None: fixed params with at least this fraction of zeros are represented sparsely when `sparsity_threshold` is None, the positions of their zeros being in the circuit anyway
+    #[pyo3(get, set)]
+    pub fixed_sparsity_threshold: Option<f32>,
+    /// str: how the inputs and params are rounded when quantized, accepts `half_away_from_zero`, `half_to_even`, `stochastic:<seed>`
+    #[pyo3(get, set)]
+    pub rounding: QuantRounding,
+    /// int: constants with at least this many values that are identical to a constant laid out before reuse its cells, 0 disables the deduplication
+    #[pyo3(get, set)]
+    pub constant_dedup_threshold: usize,
+    /// list[str]: overrides of the rescales inserted at the outputs of nodes, as `node:keep` to keep the precision of the output of a node or `node:scale` to rescale it to a scale, eg. `4:keep`
+    #[pyo3(get, set)]
+    pub rescale_overrides: Vec<RescaleOverride>,
+    /// str: how the quotients of the divisions and reciprocals are rounded to integers, accepts `round`, `floor`, `trunc`
+    #[pyo3(get, set)]
+    pub div_rounding: DivRounding,
+    /// int | None: public inputs and outputs with more than this many values are exposed as a single circomlib Poseidon digest, which the verifier created by `create_evm_packed` re-derives from the values in calldata
+    #[pyo3(get, set)]
+    pub pack_public_threshold: Option<usize>,
+    /// bool: splits the dot products too long to be accumulated exactly into chunks whose partial sums are divided by a power of two before being added up, bounding the accumulated values by 2^53
+    #[pyo3(get, set)]
+    pub widen_accumulation: bool,
+    /// int | None: the length of the chunks of the widened dot products, derived from the constant operands and the lookup range when None
+    #[pyo3(get, set)]
+    pub accumulation_chunk_size: Option<usize>,
+    /// list[str]: named groups of nodes whose polycommit params are committed to separately, as `name:nodes` with the nodes joined by `+` and inclusive ranges as `first-last`, eg. `head:12-14+17`
+    #[pyo3(get, set)]
+    pub commitment_groups: Vec<CommitmentGroup>,
+    /// int | None: packs the values of the public outputs this many at a time into each of their instances, as limbs range checked in the circuit to the bits of the decomposition
+    #[pyo3(get, set)]
+    pub instance_limbs: Option<usize>,
+    /// str: how the range checks are laid out, accepts `lookup`, `bits` (boolean constrained bit decompositions without tables) and `auto` (bits for the ranges whose table would take more rows)
+    #[pyo3(get, set)]
+    pub range_check_strategy: RangeCheckStrategy,
+    /// list[str]: the scales the outputs are rescaled to before they are exposed, as `output:scale` with the output given by its index or the name of the node computing it, eg. `0:14`
+    #[pyo3(get, set)]
+    pub output_scales: Vec<OutputScale>,
+    /// bool: fuse elementwise adds into the lookups they feed, such that the sums are looked up without being assigned
+    #[pyo3(get, set)]
+    pub fuse_add_lookups: bool,
+    /// bool: fold chains of multiplications and divisions by scalar constants into a single multiplication, or into the weights of the conv or matmul they follow or feed
+    #[pyo3(get, set)]
+    pub fold_constant_scalings: bool,
+    /// float: the margin calibration sizes the tables of the reciprocals and reciprocal square roots with around the inputs it observed for them, 0 sizes them to the lookup range
+    #[pyo3(get, set)]
+    pub tight_lookup_margin: f32,
+    /// bool: takes a last boolean input masking the samples of the batch, which multiplies the outputs along their batch axis and is exposed as a last output
+    #[pyo3(get, set)]
+    pub batch_mask: bool,
 }
 
 /// default instantiation of PyRunArgs
@@ -229,6 +496,31 @@ impl From<PyRunArgs> for RunArgs {
             commitment: Some(py_run_args.commitment.into()),
             decomp_base: py_run_args.decomp_base,
             decomp_legs: py_run_args.decomp_legs,
+            outputs: py_run_args.outputs,
+            output_visibilities: py_run_args.output_visibilities,
+            revealed_outputs: py_run_args.revealed_outputs,
+            output_bounds: py_run_args.output_bounds,
+            poseidon_params: py_run_args.poseidon_params,
+            sparsity_threshold: py_run_args.sparsity_threshold,
+            fixed_sparsity_threshold: py_run_args.fixed_sparsity_threshold,
+            rounding: py_run_args.rounding,
+            constant_dedup_threshold: py_run_args.constant_dedup_threshold,
+            rescale_overrides: py_run_args.rescale_overrides,
+            div_rounding: py_run_args.div_rounding,
+            pack_public_threshold: py_run_args.pack_public_threshold,
+            widen_accumulation: py_run_args.widen_accumulation,
+            accumulation_chunk_size: py_run_args.accumulation_chunk_size,
+            commitment_groups: py_run_args.commitment_groups,
+            instance_limbs: py_run_args.instance_limbs,
+            range_check_strategy: py_run_args.range_check_strategy,
+            bit_range_checks: vec![],
+            output_scales: py_run_args.output_scales,
+            fuse_add_lookups: py_run_args.fuse_add_lookups,
+            fold_constant_scalings: py_run_args.fold_constant_scalings,
+            tight_lookup_margin: py_run_args.tight_lookup_margin,
+            tight_lookup_ranges: vec![],
+            batch_mask: py_run_args.batch_mask,
+            weights: None,
         }
     }
 }
@@ -253,6 +545,28 @@ impl Into<PyRunArgs> for RunArgs {
             commitment: self.commitment.into(),
             decomp_base: self.decomp_base,
             decomp_legs: self.decomp_legs,
+            outputs: self.outputs,
+            output_visibilities: self.output_visibilities,
+            revealed_outputs: self.revealed_outputs,
+            output_bounds: self.output_bounds,
+            poseidon_params: self.poseidon_params,
+            sparsity_threshold: self.sparsity_threshold,
+            fixed_sparsity_threshold: self.fixed_sparsity_threshold,
+            rounding: self.rounding,
+            constant_dedup_threshold: self.constant_dedup_threshold,
+            rescale_overrides: self.rescale_overrides,
+            div_rounding: self.div_rounding,
+            pack_public_threshold: self.pack_public_threshold,
+            widen_accumulation: self.widen_accumulation,
+            accumulation_chunk_size: self.accumulation_chunk_size,
+            commitment_groups: self.commitment_groups,
+            instance_limbs: self.instance_limbs,
+            range_check_strategy: self.range_check_strategy,
+            output_scales: self.output_scales,
+            fuse_add_lookups: self.fuse_add_lookups,
+            fold_constant_scalings: self.fold_constant_scalings,
+            tight_lookup_margin: self.tight_lookup_margin,
+            batch_mask: self.batch_mask,
         }
     }
 }
@@ -478,6 +792,9 @@ fn buffer_to_felts(buffer: Vec<u8>) -> PyResult<Vec<String>> {
 /// message: list[str]
 ///     List of field elements represented as strings
 ///
+/// poseidon_params: str
+///     The parameters of the hash, `ezkl` or `circomlib`, matching the poseidon_params of the run args
+///
 /// Returns
 /// -------
 /// list[str]
@@ -485,18 +802,23 @@ fn buffer_to_felts(buffer: Vec<u8>) -> PyResult<Vec<String>> {
 ///
 #[pyfunction(signature = (
     message,
+    poseidon_params=PoseidonParams::default(),
 ))]
-fn poseidon_hash(message: Vec<PyFelt>) -> PyResult<Vec<PyFelt>> {
+fn poseidon_hash(message: Vec<PyFelt>, poseidon_params: PoseidonParams) -> PyResult<Vec<PyFelt>> {
     let message: Vec<Fr> = message
         .iter()
         .map(crate::pfsys::string_to_field::<Fr>)
         .collect::<Vec<_>>();
 
-    let output =
-        PoseidonChip::<PoseidonSpec, POSEIDON_WIDTH, POSEIDON_RATE, POSEIDON_LEN_GRAPH>::run(
-            message.clone(),
-        )
-        .map_err(|_| PyIOError::new_err("Failed to run poseidon"))?;
+    let output = match poseidon_params {
+        PoseidonParams::Ezkl => {
+            PoseidonChip::<PoseidonSpec, POSEIDON_WIDTH, POSEIDON_RATE, POSEIDON_LEN_GRAPH>::run(
+                message.clone(),
+            )
+        }
+        PoseidonParams::Circomlib => ModuleCircomlib::run(message.clone()),
+    }
+    .map_err(|_| PyIOError::new_err("Failed to run poseidon"))?;
 
     let hash = output[0]
         .iter()
@@ -505,6 +827,77 @@ fn poseidon_hash(message: Vec<PyFelt>) -> PyResult<Vec<PyFelt>> {
     Ok(hash)
 }
 
+/// Generate an ElGamal key pair for the encryption of outputs
+///
+/// Returns
+/// -------
+/// tuple[str, list[str]]
+///     The secret key and the coordinates of the public key, as hex field elements to pass as the recipient_key of `gen_witness`
+///
+#[pyfunction(signature = ())]
+fn elgamal_gen_keys() -> PyResult<(PyFelt, Vec<PyFelt>)> {
+    let (secret_key, public_key) = ModuleElGamal::gen_keys(&mut rand::rngs::OsRng);
+    Ok((
+        format!("{:?}", secret_key),
+        vec![format!("{:?}", public_key.x), format!("{:?}", public_key.y)],
+    ))
+}
+
+/// Decrypts the encrypted outputs of a proof with the secret key of the recipient
+///
+/// Arguments
+/// -------
+/// proof_path: str
+///     Path to the proof file
+///
+/// settings_path: str
+///     Path to the settings file
+///
+/// secret_key: str
+///     The secret key of the recipient, as a hex or decimal field element (see `elgamal_gen_keys`)
+///
+/// Returns
+/// -------
+/// list[list[str]]
+///     The decrypted outputs, in the order of the encrypted outputs of the settings
+///
+#[pyfunction(signature = (
+    proof_path,
+    settings_path,
+    secret_key,
+))]
+fn elgamal_decrypt(
+    proof_path: PathBuf,
+    settings_path: PathBuf,
+    secret_key: PyFelt,
+) -> PyResult<Vec<Vec<PyFelt>>> {
+    let settings = GraphSettings::load(&settings_path)
+        .map_err(|e| ezkl_error("Failed to load circuit settings", e))?;
+    let proof = Snark::load::<KZGCommitmentScheme<Bn256>>(&proof_path)
+        .map_err(|e| ezkl_error("Failed to load proof", e))?;
+    let instances = proof
+        .instances
+        .first()
+        .ok_or_else(|| PyIOError::new_err("The proof has no instances"))?;
+
+    let secret_key = felt_from_str(&secret_key)
+        .ok_or_else(|| PyIOError::new_err("The secret key is not a field element"))?;
+
+    let outputs = settings
+        .decrypt_outputs(instances, &secret_key)
+        .map_err(|e| ezkl_error("Failed to decrypt outputs", e))?;
+
+    Ok(outputs
+        .iter()
+        .map(|output| {
+            output
+                .iter()
+                .map(crate::pfsys::field_to_string::<Fr>)
+                .collect()
+        })
+        .collect())
+}
+
 /// Generate a kzg commitment.
 ///
 /// Arguments
@@ -521,6 +914,9 @@ fn poseidon_hash(message: Vec<PyFelt>) -> PyResult<Vec<PyFelt>> {
 /// srs_path: str
 ///     Path to the Structure Reference String (SRS) file
 ///
+/// blinder: str
+///     The blinder folded into the commitment when the settings blind the commitments, as in the polycommit_blinders of the witness
+///
 /// Returns
 /// -------
 /// list[PyG1Affine]
@@ -529,30 +925,34 @@ fn poseidon_hash(message: Vec<PyFelt>) -> PyResult<Vec<PyFelt>> {
     message,
     vk_path=PathBuf::from(DEFAULT_VK),
     settings_path=PathBuf::from(DEFAULT_SETTINGS),
-    srs_path=None
+    srs_path=None,
+    blinder=None,
 ))]
 fn kzg_commit(
     message: Vec<PyFelt>,
     vk_path: PathBuf,
     settings_path: PathBuf,
     srs_path: Option<PathBuf>,
+    blinder: Option<PyFelt>,
 ) -> PyResult<Vec<PyG1Affine>> {
-    let message: Vec<Fr> = message
+    let mut message: Vec<Fr> = message
         .iter()
         .map(crate::pfsys::string_to_field::<Fr>)
         .collect::<Vec<_>>();
+    // the blinder takes the row following the message
+    message.extend(blinder.as_ref().map(crate::pfsys::string_to_field::<Fr>));
 
     let settings = GraphSettings::load(&settings_path)
-        .map_err(|_| PyIOError::new_err("Failed to load circuit settings"))?;
+        .map_err(|e| ezkl_error("Failed to load circuit settings", e))?;
 
     let srs_path =
         crate::execute::get_srs_path(settings.run_args.logrows, srs_path, Commitments::KZG);
 
     let srs = load_srs_prover::<KZGCommitmentScheme<Bn256>>(srs_path)
-        .map_err(|_| PyIOError::new_err("Failed to load srs"))?;
+        .map_err(|e| ezkl_error("Failed to load srs", e))?;
 
     let vk = load_vk::<KZGCommitmentScheme<Bn256>, GraphCircuit>(vk_path, settings)
-        .map_err(|_| PyIOError::new_err("Failed to load vk"))?;
+        .map_err(|e| ezkl_error("Failed to load vk", e))?;
 
     let output = PolyCommitChip::commit::<KZGCommitmentScheme<Bn256>>(
         message,
@@ -579,6 +979,9 @@ fn kzg_commit(
 /// srs_path: str
 ///     Path to the Structure Reference String (SRS) file
 ///
+/// blinder: str
+///     The blinder folded into the commitment when the settings blind the commitments, as in the polycommit_blinders of the witness
+///
 /// Returns
 /// -------
 /// list[PyG1Affine]
@@ -587,30 +990,34 @@ fn kzg_commit(
     message,
     vk_path=PathBuf::from(DEFAULT_VK),
     settings_path=PathBuf::from(DEFAULT_SETTINGS),
-    srs_path=None
+    srs_path=None,
+    blinder=None,
 ))]
 fn ipa_commit(
     message: Vec<PyFelt>,
     vk_path: PathBuf,
     settings_path: PathBuf,
     srs_path: Option<PathBuf>,
+    blinder: Option<PyFelt>,
 ) -> PyResult<Vec<PyG1Affine>> {
-    let message: Vec<Fr> = message
+    let mut message: Vec<Fr> = message
         .iter()
         .map(crate::pfsys::string_to_field::<Fr>)
         .collect::<Vec<_>>();
+    // the blinder takes the row following the message
+    message.extend(blinder.as_ref().map(crate::pfsys::string_to_field::<Fr>));
 
     let settings = GraphSettings::load(&settings_path)
-        .map_err(|_| PyIOError::new_err("Failed to load circuit settings"))?;
+        .map_err(|e| ezkl_error("Failed to load circuit settings", e))?;
 
     let srs_path =
         crate::execute::get_srs_path(settings.run_args.logrows, srs_path, Commitments::KZG);
 
     let srs = load_srs_prover::<IPACommitmentScheme<G1Affine>>(srs_path)
-        .map_err(|_| PyIOError::new_err("Failed to load srs"))?;
+        .map_err(|e| ezkl_error("Failed to load srs", e))?;
 
     let vk = load_vk::<IPACommitmentScheme<G1Affine>, GraphCircuit>(vk_path, settings)
-        .map_err(|_| PyIOError::new_err("Failed to load vk"))?;
+        .map_err(|e| ezkl_error("Failed to load vk", e))?;
 
     let output = PolyCommitChip::commit::<IPACommitmentScheme<G1Affine>>(
         message,
@@ -621,6 +1028,78 @@ fn ipa_commit(
     Ok(output.iter().map(|x| (*x).into()).collect::<Vec<_>>())
 }
 
+/// Verify that a kzg commitment opens to a message and its blinder
+///
+/// Arguments
+/// -------
+/// commitment: list[PyG1Affine]
+///     The commitment, as in the polycommit of the witness
+///
+/// message: list[str]
+///     List of field elements represented as strings
+///
+/// blinder: str
+///     The blinder folded into the commitment, as in the polycommit_blinders of the witness, or None if the commitment isn't blinded
+///
+/// vk_path: str
+///     Path to the verification key
+///
+/// settings_path: str
+///     Path to the settings file
+///
+/// srs_path: str
+///     Path to the Structure Reference String (SRS) file
+///
+/// Returns
+/// -------
+/// bool
+///
+#[pyfunction(signature = (
+    commitment,
+    message,
+    blinder=None,
+    vk_path=PathBuf::from(DEFAULT_VK),
+    settings_path=PathBuf::from(DEFAULT_SETTINGS),
+    srs_path=None,
+))]
+fn kzg_verify_opening(
+    commitment: Vec<PyG1Affine>,
+    message: Vec<PyFelt>,
+    blinder: Option<PyFelt>,
+    vk_path: PathBuf,
+    settings_path: PathBuf,
+    srs_path: Option<PathBuf>,
+) -> PyResult<bool> {
+    let commitment: Vec<G1Affine> = commitment.into_iter().map(|c| c.into()).collect();
+    let message: Vec<Fr> = message
+        .iter()
+        .map(crate::pfsys::string_to_field::<Fr>)
+        .collect::<Vec<_>>();
+    let blinder = blinder.as_ref().map(crate::pfsys::string_to_field::<Fr>);
+
+    let settings = GraphSettings::load(&settings_path)
+        .map_err(|e| ezkl_error("Failed to load circuit settings", e))?;
+
+    let srs_path =
+        crate::execute::get_srs_path(settings.run_args.logrows, srs_path, Commitments::KZG);
+
+    let srs = load_srs_prover::<KZGCommitmentScheme<Bn256>>(srs_path)
+        .map_err(|e| ezkl_error("Failed to load srs", e))?;
+
+    let vk = load_vk::<KZGCommitmentScheme<Bn256>, GraphCircuit>(vk_path, settings)
+        .map_err(|e| ezkl_error("Failed to load vk", e))?;
+
+    let opens = PolyCommitChip::verify_opening::<KZGCommitmentScheme<Bn256>>(
+        &commitment,
+        message,
+        blinder,
+        (vk.cs().blinding_factors() + 1) as u32,
+        &srs,
+    );
+
+    Ok(opens)
+}
+
 /// Swap the commitments in a proof
 ///
 /// Arguments
@@ -631,17 +1110,57 @@ fn ipa_commit(
 /// witness_path: str
 ///     Path to the witness file
 ///
+/// group_commitments: list[str]
+///     Paths to the commitments of groups of params (generated with `commit_param_groups`), if set only the commitments of these groups are swapped and the witness isn't read
+///
 #[pyfunction(signature = (
     proof_path=PathBuf::from(DEFAULT_PROOF),
     witness_path=PathBuf::from(DEFAULT_WITNESS),
+    group_commitments=vec![],
 ))]
-fn swap_proof_commitments(proof_path: PathBuf, witness_path: PathBuf) -> PyResult<()> {
-    crate::execute::swap_proof_commitments_cmd(proof_path, witness_path)
-        .map_err(|_| PyIOError::new_err("Failed to swap commitments"))?;
+fn swap_proof_commitments(
+    proof_path: PathBuf,
+    witness_path: PathBuf,
+    group_commitments: Vec<PathBuf>,
+) -> PyResult<()> {
+    crate::execute::swap_proof_commitments_cmd(proof_path, witness_path, group_commitments)
+        .map_err(|e| ezkl_error("Failed to swap commitments", e))?;
 
     Ok(())
 }
 
+/// Writes the commitments of each commitment group of the polycommit params of a witness to `<output_dir>/<group>.json`
+///
+/// Arguments
+/// -------
+/// witness_path: str
+///     Path to the witness file, generated with the verifying key and srs
+///
+/// settings_path: str
+///     Path to the settings file
+///
+/// output_dir: str
+///     Directory the commitments of the groups are written to
+///
+/// Returns
+/// -------
+/// list[str]
+///     The paths of the commitments of the groups
+///
+#[pyfunction(signature = (
+    witness_path=PathBuf::from(DEFAULT_WITNESS),
+    settings_path=PathBuf::from(DEFAULT_SETTINGS),
+    output_dir=PathBuf::from(DEFAULT_PARAM_GROUPS_DIR),
+))]
+fn commit_param_groups(
+    witness_path: PathBuf,
+    settings_path: PathBuf,
+    output_dir: PathBuf,
+) -> PyResult<Vec<PathBuf>> {
+    crate::execute::commit_param_groups(witness_path, settings_path, output_dir)
+        .map_err(|e| ezkl_error("Failed to commit to the groups of params", e))
+}
+
 /// Generates a vk from a pk for a model circuit and saves it to a file
 ///
 /// Arguments
@@ -670,16 +1189,15 @@ fn gen_vk_from_pk_single(
     vk_output_path: PathBuf,
 ) -> PyResult<bool> {
     let settings = GraphSettings::load(&circuit_settings_path)
-        .map_err(|_| PyIOError::new_err("Failed to load circuit settings"))?;
+        .map_err(|e| ezkl_error("Failed to load circuit settings", e))?;
 
     let pk = load_pk::<KZGCommitmentScheme<Bn256>, GraphCircuit>(path_to_pk, settings)
-        .map_err(|_| PyIOError::new_err("Failed to load pk"))?;
+        .map_err(|e| ezkl_error("Failed to load pk", e))?;
 
     let vk = pk.get_vk();
 
     // now save
-    save_vk::<G1Affine>(&vk_output_path, vk)
-        .map_err(|_| PyIOError::new_err("Failed to save vk"))?;
+    save_vk::<G1Affine>(&vk_output_path, vk).map_err(|e| ezkl_error("Failed to save vk", e))?;
 
     Ok(true)
 }
@@ -703,13 +1221,12 @@ fn gen_vk_from_pk_single(
 ))]
 fn gen_vk_from_pk_aggr(path_to_pk: PathBuf, vk_output_path: PathBuf) -> PyResult<bool> {
     let pk = load_pk::<KZGCommitmentScheme<Bn256>, AggregationCircuit>(path_to_pk, ())
-        .map_err(|_| PyIOError::new_err("Failed to load pk"))?;
+        .map_err(|e| ezkl_error("Failed to load pk", e))?;
 
     let vk = pk.get_vk();
 
     // now save
-    save_vk::<G1Affine>(&vk_output_path, vk)
-        .map_err(|_| PyIOError::new_err("Failed to save vk"))?;
+    save_vk::<G1Affine>(&vk_output_path, vk).map_err(|e| ezkl_error("Failed to save vk", e))?;
 
     Ok(true)
 }
@@ -732,12 +1249,12 @@ fn gen_vk_from_pk_aggr(path_to_pk: PathBuf, vk_output_path: PathBuf) -> PyResult
 ))]
 fn table(model: PathBuf, py_run_args: Option<PyRunArgs>) -> PyResult<String> {
     let run_args: RunArgs = py_run_args.unwrap_or_else(PyRunArgs::new).into();
-    let mut reader = File::open(model).map_err(|_| PyIOError::new_err("Failed to open model"))?;
+    let mut reader = File::open(model).map_err(|e| ezkl_error("Failed to open model", e))?;
     let result = Model::new(&mut reader, &run_args);
 
     match result {
         Ok(m) => Ok(m.table_nodes()),
-        Err(_) => Err(PyIOError::new_err("Failed to import model")),
+        Err(e) => Err(ezkl_error("Failed to import model", e)),
     }
 }
 
@@ -802,16 +1319,37 @@ fn get_srs(
     pyo3_asyncio::tokio::future_into_py(py, async move {
         crate::execute::get_srs_cmd(srs_path, settings_path, logrows, commitment)
             .await
-            .map_err(|e| {
-                let err_str = format!("Failed to get srs: {}", e);
-                PyRuntimeError::new_err(err_str)
-            })?;
+            .map_err(|e| ezkl_error("Failed to get srs", e))?;
 
         Ok(true)
     })
 }
 
-/// Generates the circuit settings
+/// Estimates the proof size, proving time and EVM verification gas without running setup
+///
+/// Arguments
+/// ---------
+/// model: str
+///     Path to the onnx file, used to generate settings if none exist at settings_path
+///
+/// settings: str
+///     Path to the settings file
+///
+/// Returns
+/// -------
+/// str
+///     The estimate as a json string
+///
+#[pyfunction(signature = (
+    model=PathBuf::from(DEFAULT_MODEL),
+    settings=PathBuf::from(DEFAULT_SETTINGS),
+))]
+fn estimate(model: PathBuf, settings: PathBuf) -> PyResult<String> {
+    crate::execute::estimate(model, settings, None)
+        .map_err(|e| ezkl_error("Failed to run estimate", e))
+}
+
+/// Profiles the rows, lookups and equality constraints each op of a model lays out
 ///
 /// Arguments
 /// ---------
@@ -819,77 +1357,254 @@ fn get_srs(
 ///     Path to the onnx file
 ///
 /// output: str
-///     Path to create the settings file
+///     Path to write the profile json to, if any
 ///
 /// py_run_args: PyRunArgs
-///     PyRunArgs object to initialize the settings
+///     PyRunArgs the model is profiled with
 ///
 /// Returns
 /// -------
-/// bool
+/// str
+///     The profile as a json string, with the ops sorted by the rows they take
 ///
 #[pyfunction(signature = (
     model=PathBuf::from(DEFAULT_MODEL),
-    output=PathBuf::from(DEFAULT_SETTINGS),
-    py_run_args = None,
+    output=None,
+    py_run_args=None,
 ))]
-fn gen_settings(
+fn circuit_profile(
     model: PathBuf,
-    output: PathBuf,
+    output: Option<PathBuf>,
     py_run_args: Option<PyRunArgs>,
-) -> Result<bool, PyErr> {
+) -> PyResult<String> {
     let run_args: RunArgs = py_run_args.unwrap_or_else(PyRunArgs::new).into();
-
-    crate::execute::gen_circuit_settings(model, output, run_args).map_err(|e| {
-        let err_str = format!("Failed to generate settings: {}", e);
-        PyRuntimeError::new_err(err_str)
-    })?;
-
-    Ok(true)
+    crate::execute::circuit_profile(model, run_args, output)
+        .map_err(|e| ezkl_error("Failed to run circuit_profile", e))
 }
 
-/// Calibrates the circuit settings
+/// Estimates the rows, lookups, instances and minimum logrows of the circuit of a model from a dry run of its layout,
+/// without keygen. The estimate is what the real layout of the model lays out.
 ///
 /// Arguments
 /// ---------
-/// data: str
-///     Path to the calibration data
-///
 /// model: str
 ///     Path to the onnx file
 ///
-/// settings: str
-///     Path to the settings file
+/// py_run_args: PyRunArgs
+///     PyRunArgs the circuit is laid out with
 ///
-/// lookup_safety_margin: int
-///      the lookup safety margin to use for calibration. if the max lookup is 2^k, then the max lookup will be 2^k * lookup_safety_margin. larger = safer but slower
+/// Returns
+/// -------
+/// str
+///     The estimate as a json string, with the ops in the order they are laid out
 ///
-/// scales: list[int]
-///     Optional scales to specifically try for calibration
+#[pyfunction(signature = (
+    model=PathBuf::from(DEFAULT_MODEL),
+    py_run_args=None,
+))]
+fn estimate_layout(model: PathBuf, py_run_args: Option<PyRunArgs>) -> PyResult<String> {
+    let run_args: RunArgs = py_run_args.unwrap_or_else(PyRunArgs::new).into();
+    let model = Model::from_run_args(&run_args, &model)
+        .map_err(|e| ezkl_error("Failed to import model", e))?;
+    let estimate = model
+        .estimate_layout(&run_args)
+        .map_err(|e| ezkl_error("Failed to estimate the layout", e))?;
+    serde_json::to_string(&estimate).map_err(|e| ezkl_error("Failed to serialize the estimate", e))
+}
+
+/// Inspects a compiled circuit without the onnx model it was compiled from
 ///
-/// scale_rebase_multiplier: list[int]
-///     Optional scale rebase multipliers to specifically try for calibration. This is the multiplier at which we divide to return to the input scale.
+/// Arguments
+/// ---------
+/// compiled_circuit: str
+///     Path to the compiled circuit
 ///
-/// max_logrows: int
-///     Optional max logrows to use for calibration
+/// node: int
+///     Index of a node of the main graph to only inspect that node, if any
 ///
-/// only_range_check_rebase: bool
-///     Check ranges when rebasing
+/// Returns
+/// -------
+/// dict
+///     The nodes of the model with their names, ops, input and output shapes, scales and visibility, the lookups and
+///     range checks the circuit requires and its instance layout, or the node at `node` (None if there is none)
+///
+#[pyfunction(signature = (
+    compiled_circuit=PathBuf::from(DEFAULT_COMPILED_CIRCUIT),
+    node=None,
+))]
+fn inspect(py: Python, compiled_circuit: PathBuf, node: Option<usize>) -> PyResult<PyObject> {
+    let circuit = GraphCircuit::load(compiled_circuit)
+        .map_err(|e| ezkl_error("Failed to load circuit", e))?;
+    Ok(match node {
+        Some(idx) => circuit.node(idx).to_object(py),
+        None => circuit.inspect().to_object(py),
+    })
+}
+
+/// Decodes the instances of a proof into the named, dequantized public inputs and outputs of the model
+///
+/// Arguments
+/// ---------
+/// instances: list[list[str]]
+///     The instances of the proof as field element hex strings, per instance column as in the `instances` of a proof
+///
+/// settings_path: str
+///     Path to the settings of the circuit the proof is of
 ///
 /// Returns
 /// -------
-/// bool
+/// dict
+///     The public `inputs` and `outputs` of the model, each with its `name`, `idx`, `shape`, `scale` and dequantized
+///     `values`
 ///
 #[pyfunction(signature = (
-    data = PathBuf::from(DEFAULT_CALIBRATION_FILE),
-    model = PathBuf::from(DEFAULT_MODEL),
-    settings = PathBuf::from(DEFAULT_SETTINGS),
-    target = CalibrationTarget::default(), // default is "resources
-    lookup_safety_margin = DEFAULT_LOOKUP_SAFETY_MARGIN.parse().unwrap(),
-    scales = None,
-    scale_rebase_multiplier = DEFAULT_SCALE_REBASE_MULTIPLIERS.split(",").map(|x| x.parse().unwrap()).collect(),
-    max_logrows = None,
-    only_range_check_rebase = DEFAULT_ONLY_RANGE_CHECK_REBASE.parse().unwrap(),
+    instances,
+    settings_path=PathBuf::from(DEFAULT_SETTINGS),
+))]
+fn decode_instances(
+    py: Python,
+    instances: Vec<Vec<PyFelt>>,
+    settings_path: PathBuf,
+) -> PyResult<PyObject> {
+    let settings = GraphSettings::load(&settings_path)
+        .map_err(|e| ezkl_error("Failed to load circuit settings", e))?;
+    let instances = instances
+        .concat()
+        .iter()
+        .map(|felt| crate::pfsys::string_to_field::<Fr>(felt))
+        .collect::<Vec<_>>();
+    let decoded = crate::graph::instances::decode_instances(&instances, &settings)
+        .map_err(|e| ezkl_error("Failed to decode the instances", e))?;
+    session_object(py, &decoded)
+}
+
+/// Generates the circuit settings
+///
+/// Arguments
+/// ---------
+/// model: str
+///     Path to the onnx file
+///
+/// output: str
+///     Path to create the settings file
+///
+/// py_run_args: PyRunArgs
+///     PyRunArgs object to initialize the settings
+///
+/// image_preprocessing: str
+///     Path to a .json spec of the preprocessing applied to png / jpeg inputs, stored in the settings
+///
+/// normalize: tuple[str, str]
+///     Paths to the .json mean and std of each input, stored in the settings so file inputs are normalized as (x - mean) / std before quantization
+///
+/// input_signature: str
+///     Path to a .json spec of the inputs whose signature is verified in the circuit, the recovered public key becomes a public instance
+///
+/// nullifier: bool
+///     Whether to expose the nullifier of the inputs and a private salt as the last public instance
+///
+/// encrypted_outputs: list[int]
+///     Indices of the private outputs to encrypt to a recipient public key in the circuit, the public key and the ciphertext become public instances
+///
+/// polycommit_blinding: bool
+///     Whether to fold a private random blinder into each polycommit commitment, the blinders are kept in the witness to reopen the commitments with
+///
+/// session: bool
+///     Whether to expose the digest of the previous step of a session and the digest chaining the outputs to it as public instances
+///
+/// Returns
+/// -------
+/// bool
+///
+#[pyfunction(signature = (
+    model=PathBuf::from(DEFAULT_MODEL),
+    output=PathBuf::from(DEFAULT_SETTINGS),
+    py_run_args = None,
+    image_preprocessing = None,
+    normalize = None,
+    input_signature = None,
+    nullifier = false,
+    encrypted_outputs = None,
+    polycommit_blinding = false,
+    session = false,
+))]
+fn gen_settings(
+    model: PathBuf,
+    output: PathBuf,
+    py_run_args: Option<PyRunArgs>,
+    image_preprocessing: Option<PathBuf>,
+    normalize: Option<(PathBuf, PathBuf)>,
+    input_signature: Option<PathBuf>,
+    nullifier: bool,
+    encrypted_outputs: Option<Vec<usize>>,
+    polycommit_blinding: bool,
+    session: bool,
+) -> Result<bool, PyErr> {
+    let run_args: RunArgs = py_run_args.unwrap_or_else(PyRunArgs::new).into();
+
+    crate::execute::gen_circuit_settings(
+        model,
+        output,
+        run_args,
+        image_preprocessing,
+        normalize,
+        input_signature,
+        nullifier,
+        encrypted_outputs,
+        polycommit_blinding,
+        session,
+    )
+    .map_err(|e| ezkl_error("Failed to generate settings", e))?;
+
+    Ok(true)
+}
+
+/// Calibrates the circuit settings
+///
+/// Arguments
+/// ---------
+/// data: str
+///     Path to the calibration data, a .json file, a .csv file, a png / jpeg image or a .npy file / .npz archive holding the input arrays
+///
+/// model: str
+///     Path to the onnx file
+///
+/// settings: str
+///     Path to the settings file
+///
+/// lookup_safety_margin: int
+///      the lookup safety margin to use for calibration. if the max lookup is 2^k, then the max lookup will be 2^k * lookup_safety_margin. larger = safer but slower
+///
+/// scales: list[int]
+///     Optional scales to specifically try for calibration
+///
+/// scale_rebase_multiplier: list[int]
+///     Optional scale rebase multipliers to specifically try for calibration. This is the multiplier at which we divide to return to the input scale.
+///
+/// max_logrows: int
+///     Optional max logrows to use for calibration
+///
+/// only_range_check_rebase: bool
+///     Check ranges when rebasing
+///
+/// csv_mapping: str
+///     Path to a .json spec of the csv columns feeding each model input, without it every column of a .csv data file feeds the first input
+///
+/// Returns
+/// -------
+/// bool
+///
+#[pyfunction(signature = (
+    data = PathBuf::from(DEFAULT_CALIBRATION_FILE),
+    model = PathBuf::from(DEFAULT_MODEL),
+    settings = PathBuf::from(DEFAULT_SETTINGS),
+    target = CalibrationTarget::default(), // default is "resources
+    lookup_safety_margin = DEFAULT_LOOKUP_SAFETY_MARGIN.parse().unwrap(),
+    scales = None,
+    scale_rebase_multiplier = DEFAULT_SCALE_REBASE_MULTIPLIERS.split(",").map(|x| x.parse().unwrap()).collect(),
+    max_logrows = None,
+    only_range_check_rebase = DEFAULT_ONLY_RANGE_CHECK_REBASE.parse().unwrap(),
+    csv_mapping = None,
 ))]
 fn calibrate_settings(
     py: Python,
@@ -902,7 +1617,89 @@ fn calibrate_settings(
     scale_rebase_multiplier: Vec<u32>,
     max_logrows: Option<u32>,
     only_range_check_rebase: bool,
+    csv_mapping: Option<PathBuf>,
 ) -> PyResult<Bound<'_, PyAny>> {
+    pyo3_asyncio::tokio::future_into_py(py, async move {
+        crate::execute::calibrate(
+            model,
+            data.into(),
+            settings,
+            target,
+            lookup_safety_margin,
+            scales,
+            scale_rebase_multiplier,
+            only_range_check_rebase,
+            max_logrows,
+            csv_mapping,
+        )
+        .await
+        .map_err(|e| ezkl_error("Failed to calibrate settings", e))?;
+
+        Ok(true)
+    })
+}
+
+/// Calibrates the circuit settings on input arrays held in memory, as `calibrate_settings` does on a data file
+///
+/// Arguments
+/// ---------
+/// inputs: list[numpy.ndarray] | Iterable
+///     One float64, float32, int64, int32 or bool array per model input, the calibration batches stacked along the first dimension. Or an iterable of batches (as a torch DataLoader), each a list of one array per model input or a single array for models with a single input. Tensors implementing `__dlpack__` (torch, jax, cupy) are accepted in place of numpy arrays
+///
+/// model: str
+///     Path to the onnx file
+///
+/// settings: str
+///     Path to the settings file
+///
+/// lookup_safety_margin: int
+///      the lookup safety margin to use for calibration. if the max lookup is 2^k, then the max lookup will be 2^k * lookup_safety_margin. larger = safer but slower
+///
+/// scales: list[int]
+///     Optional scales to specifically try for calibration
+///
+/// scale_rebase_multiplier: list[int]
+///     Optional scale rebase multipliers to specifically try for calibration. This is the multiplier at which we divide to return to the input scale.
+///
+/// max_logrows: int
+///     Optional max logrows to use for calibration
+///
+/// only_range_check_rebase: bool
+///     Check ranges when rebasing
+///
+/// Returns
+/// -------
+/// bool
+///
+/// Raises
+/// ------
+/// ValueError
+///     If an array has an unsupported dtype or a shape that doesn't stack batches of its model input
+///
+#[pyfunction(signature = (
+    inputs,
+    model = PathBuf::from(DEFAULT_MODEL),
+    settings = PathBuf::from(DEFAULT_SETTINGS),
+    target = CalibrationTarget::default(),
+    lookup_safety_margin = DEFAULT_LOOKUP_SAFETY_MARGIN.parse().unwrap(),
+    scales = None,
+    scale_rebase_multiplier = DEFAULT_SCALE_REBASE_MULTIPLIERS.split(",").map(|x| x.parse().unwrap()).collect(),
+    max_logrows = None,
+    only_range_check_rebase = DEFAULT_ONLY_RANGE_CHECK_REBASE.parse().unwrap(),
+))]
+fn calibrate_settings_arrays<'py>(
+    py: Python<'py>,
+    inputs: Bound<'py, PyAny>,
+    model: PathBuf,
+    settings: PathBuf,
+    target: CalibrationTarget,
+    lookup_safety_margin: f64,
+    scales: Option<Vec<crate::Scale>>,
+    scale_rebase_multiplier: Vec<u32>,
+    max_logrows: Option<u32>,
+    only_range_check_rebase: bool,
+) -> PyResult<Bound<'py, PyAny>> {
+    let data = numpy_batches(&inputs)?;
     pyo3_asyncio::tokio::future_into_py(py, async move {
         crate::execute::calibrate(
             model,
@@ -914,65 +1711,642 @@ fn calibrate_settings(
             scale_rebase_multiplier,
             only_range_check_rebase,
             max_logrows,
+            None,
+        )
+        .await
+        .map_err(|e| ezkl_error("Failed to calibrate settings", e))?;
+
+        Ok(true)
+    })
+}
+
+/// Runs gen-settings, calibrate-settings, compile-circuit, get-srs and setup in sequence, writing the artifacts to an
+/// output directory with consistent names. Stages whose inputs and outputs are unchanged since their last run are skipped
+///
+/// Arguments
+/// ---------
+/// model: str
+///     Path to the onnx file
+///
+/// data: str
+///     Path to the data file, used to calibrate the settings
+///
+/// output_dir: str
+///     Path to the directory to write the artifacts to
+///
+/// py_run_args: PyRunArgs
+///     PyRunArgs object to initialize the settings
+///
+/// target: str
+///     Target for calibration. Set to "resources" to optimize for computational resource. Otherwise, set to "accuracy" to optimize for accuracy.
+///
+/// srs_path: str
+///     Path to the SRS file, if None will use $EZKL_REPO_PATH/srs/kzg{logrows}.srs
+///
+/// stop_after: str
+///     The last stage to run, one of "gen-settings", "calibrate-settings", "compile-circuit", "get-srs" or "setup"
+///
+/// Returns
+/// -------
+/// bool
+///
+#[pyfunction(signature = (
+    model = PathBuf::from(DEFAULT_MODEL),
+    data = PathBuf::from(DEFAULT_DATA),
+    output_dir = PathBuf::from(DEFAULT_PIPELINE_DIR),
+    py_run_args = None,
+    target = CalibrationTarget::default(),
+    srs_path = None,
+    stop_after = PipelineStage::default(),
+))]
+fn run_pipeline(
+    py: Python,
+    model: PathBuf,
+    data: PathBuf,
+    output_dir: PathBuf,
+    py_run_args: Option<PyRunArgs>,
+    target: CalibrationTarget,
+    srs_path: Option<PathBuf>,
+    stop_after: PipelineStage,
+) -> PyResult<Bound<'_, PyAny>> {
+    let run_args: RunArgs = py_run_args.unwrap_or_else(PyRunArgs::new).into();
+
+    pyo3_asyncio::tokio::future_into_py(py, async move {
+        crate::execute::pipeline(
+            model, data, output_dir, run_args, target, srs_path, stop_after,
+        )
+        .await
+        .map_err(|e| ezkl_error("Failed to run pipeline", e))?;
+
+        Ok(true)
+    })
+}
+
+/// Runs the forward pass operation to generate a witness
+///
+/// Arguments
+/// ---------
+/// data: str
+///     Path to the data file, a .json file, a .csv file, a png / jpeg image or a .npy file / .npz archive holding the input arrays
+///
+/// model: str
+///     Path to the compiled model file
+///
+/// output: str
+///     Path to create the witness file
+///
+/// vk_path: str
+///     Path to the verification key
+///
+/// srs_path: str
+///     Path to the SRS file
+///
+/// dequantized_output: str
+///     Path to save the dequantized outputs to as a .npy file, models with several outputs need a .npz archive
+///
+/// field_output: str
+///     Path to save the outputs to as raw field elements, in the .json data format of already quantized inputs with their scales declared, to feed the inputs of a chained circuit
+///
+/// csv_mapping: str
+///     Path to a .json spec of the csv columns feeding each model input, without it every column of a .csv data file feeds the first input
+///
+/// precision: int
+///     Decimal places to print the dequantized floats of the witness file with, defaults to as many as f64 needs
+///
+/// felts: bool
+///     Whether to write the field elements of the inputs and outputs to the witness file, without them it can't be proven
+///
+/// rescaled: bool
+///     Whether to write the dequantized floats of the inputs and outputs to the witness file
+///
+/// processed: bool
+///     Whether to write the processed inputs, params and outputs (hashes and commitments) to the witness file, without them it can't be proven if any visibility is hashed or polycommit
+///
+/// sidecar_threshold: int
+///     The number of field elements above which a tensor of the inputs or outputs is written to a binary sidecar next to the witness file (`<output>.bin`) rather than to its json, defaults to writing every tensor to the json
+///
+/// merkle_tree: str
+///     Path to the .json merkle tree of the dataset the inputs are proven to belong to, required when the input visibility is merkle
+///
+/// signature: str
+///     The hex recoverable secp256k1 signature over the digest of the signed inputs, required when the settings sign inputs
+///
+/// salt: str
+///     The private salt, as a hex or decimal field element, the nullifier of the inputs is derived with, required when the settings expose a nullifier
+///
+/// prev_digest: str
+///     The digest of the previous step of the session, as a hex or decimal field element, the outputs are chained to, `0` for the first step. Required when the settings expose session digests, the digest of this step is in the `session` of the witness
+///
+/// recipient_key: list[str]
+///     The public key the encrypted outputs are encrypted to, as the hex or decimal field elements of its coordinates (see `elgamal_gen_keys`), required when the settings encrypt outputs
+///
+/// blinding_key: list[str]
+///     The public key to encrypt the blinders of the polycommit commitments to, as the hex or decimal field elements of its coordinates (see `elgamal_gen_keys`), when the settings blind the commitments. Without it the blinders are kept in the clear
+///
+/// redact: bool
+///     Whether to write and return the witness redacted for sharing with a verifier, see `redact_witness`. The dequantized and field outputs are still written in full
+///
+/// redact_recipient: str
+///     The age X25519 public key (`age1...`) to encrypt the full witness to within the redacted witness
+///
+/// identity: str
+///     Path to the age identity file to decrypt the compiled model with, when it is encrypted (see `compile_circuit`)
+///
+/// Returns
+/// -------
+/// dict
+///     Python object containing the witness values
+///
+#[pyfunction(signature = (
+    data=PathBuf::from(DEFAULT_DATA),
+    model=PathBuf::from(DEFAULT_COMPILED_CIRCUIT),
+    output=PathBuf::from(DEFAULT_WITNESS),
+    vk_path=None,
+    srs_path=None,
+    dequantized_output=None,
+    field_output=None,
+    csv_mapping=None,
+    precision=None,
+    felts=true,
+    rescaled=true,
+    processed=true,
+    sidecar_threshold=None,
+    merkle_tree=None,
+    signature=None,
+    salt=None,
+    prev_digest=None,
+    recipient_key=None,
+    blinding_key=None,
+    redact=false,
+    redact_recipient=None,
+    identity=None,
+))]
+fn gen_witness(
+    py: Python,
+    data: PathBuf,
+    model: PathBuf,
+    output: Option<PathBuf>,
+    vk_path: Option<PathBuf>,
+    srs_path: Option<PathBuf>,
+    dequantized_output: Option<PathBuf>,
+    field_output: Option<PathBuf>,
+    csv_mapping: Option<PathBuf>,
+    precision: Option<usize>,
+    felts: bool,
+    rescaled: bool,
+    processed: bool,
+    sidecar_threshold: Option<usize>,
+    merkle_tree: Option<PathBuf>,
+    signature: Option<String>,
+    salt: Option<String>,
+    prev_digest: Option<String>,
+    recipient_key: Option<Vec<String>>,
+    blinding_key: Option<Vec<String>>,
+    redact: bool,
+    redact_recipient: Option<String>,
+    identity: Option<PathBuf>,
+) -> PyResult<Bound<'_, PyAny>> {
+    pyo3_asyncio::tokio::future_into_py(py, async move {
+        let output = crate::execute::gen_witness(
+            model,
+            data.into(),
+            output,
+            vk_path,
+            srs_path,
+            dequantized_output,
+            field_output,
+            csv_mapping,
+            crate::graph::WitnessFormat {
+                precision,
+                felts,
+                rescaled,
+                processed,
+                sidecar_threshold,
+            },
+            merkle_tree,
+            signature,
+            salt,
+            prev_digest,
+            None,
+            recipient_key,
+            blinding_key,
+            redact,
+            redact_recipient,
+            identity,
+        )
+        .await
+        .map_err(|e| ezkl_error("Failed to generate witness", e))?;
+        Python::with_gil(|py| Ok(output.to_object(py)))
+    })
+}
+
+/// Runs the forward pass operation to generate a witness on the blocking thread pool of ezkl, without blocking the
+/// asyncio event loop. The other options of `gen_witness` are only available through it
+///
+/// Arguments
+/// ---------
+/// data: str | list[numpy.ndarray]
+///     Path to the data file, or one numpy array per model input as in `gen_witness_arrays`
+///
+/// model: str
+///     Path to the compiled model file
+///
+/// output: str
+///     Path to create the witness file, if None the witness is only returned
+///
+/// vk_path: str
+///     Path to the verification key
+///
+/// srs_path: str
+///     Path to the SRS file
+///
+/// timeout: float
+///     Seconds after which the forward pass is cancelled, failing without writing the witness
+///
+/// Returns
+/// -------
+/// Awaitable[dict]
+///     Python object containing the witness values. Cancelling the awaitable stops the forward pass at its next node
+///     and discards the witness
+///
+#[pyfunction(signature = (
+    data,
+    model=PathBuf::from(DEFAULT_COMPILED_CIRCUIT),
+    output=None,
+    vk_path=None,
+    srs_path=None,
+    timeout=None,
+))]
+fn gen_witness_async<'py>(
+    py: Python<'py>,
+    data: Bound<'py, PyAny>,
+    model: PathBuf,
+    output: Option<PathBuf>,
+    vk_path: Option<PathBuf>,
+    srs_path: Option<PathBuf>,
+    timeout: Option<f64>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let data = match data.extract::<PathBuf>() {
+        Ok(path) => DataInput::Path(path),
+        Err(_) => numpy_inputs(&data.extract::<Vec<Bound<'py, PyAny>>>()?)?,
+    };
+    spawn_cancellable(py, timeout, move || {
+        let witness = tokio::runtime::Handle::current()
+            .block_on(crate::execute::gen_witness(
+                model,
+                data,
+                output,
+                vk_path,
+                srs_path,
+                None,
+                None,
+                None,
+                crate::graph::WitnessFormat::default(),
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                false,
+                None,
+                None,
+            ))
+            .map_err(|e| ezkl_error("Failed to generate witness", e))?;
+        Python::with_gil(|py| Ok(witness.to_object(py)))
+    })
+}
+
+/// Runs the forward pass operation on input arrays held in memory to generate a witness, as `gen_witness` does on a
+/// data file
+///
+/// Arguments
+/// ---------
+/// inputs: list[numpy.ndarray]
+///     One float64, float32, int64, int32 or bool array per model input, of the shape of the input up to dimensions of size 1. Tensors implementing `__dlpack__` (torch, jax, cupy) are accepted in place of numpy arrays, and read without a copy when they are on the host
+///
+/// model: str
+///     Path to the compiled model file
+///
+/// output: str
+///     Path to create the witness file, if None the witness is only returned
+///
+/// vk_path: str
+///     Path to the verification key
+///
+/// srs_path: str
+///     Path to the SRS file
+///
+/// Returns
+/// -------
+/// tuple[dict, list[numpy.ndarray]]
+///     Python object containing the witness values, and the dequantized outputs as float64 arrays of the shapes of the model outputs
+///
+/// Raises
+/// ------
+/// ValueError
+///     If an array has an unsupported dtype or doesn't have the shape of its model input
+///
+#[pyfunction(signature = (
+    inputs,
+    model=PathBuf::from(DEFAULT_COMPILED_CIRCUIT),
+    output=None,
+    vk_path=None,
+    srs_path=None,
+))]
+fn gen_witness_arrays<'py>(
+    py: Python<'py>,
+    inputs: Vec<Bound<'py, PyAny>>,
+    model: PathBuf,
+    output: Option<PathBuf>,
+    vk_path: Option<PathBuf>,
+    srs_path: Option<PathBuf>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let data = numpy_inputs(&inputs)?;
+    pyo3_asyncio::tokio::future_into_py(py, async move {
+        let witness = crate::execute::gen_witness(
+            model.clone(),
+            data,
+            output,
+            vk_path,
+            srs_path,
+            None,
+            None,
+            None,
+            crate::graph::WitnessFormat::default(),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            false,
+            None,
+            None,
+        )
+        .await
+        .map_err(|e| ezkl_error("Failed to generate witness", e))?;
+
+        let circuit =
+            GraphCircuit::load(model).map_err(|e| ezkl_error("Failed to load circuit", e))?;
+        let shapes = circuit
+            .model()
+            .graph
+            .output_shapes()
+            .map_err(|e| ezkl_error("Failed to get output shapes", e))?;
+        let outputs = witness
+            .dequantized_outputs(&circuit.settings().model_output_scales)
+            .into_iter()
+            .zip(shapes)
+            .collect::<Vec<_>>();
+        Python::with_gil(|py| {
+            let outputs = numpy_outputs(py, outputs)?;
+            Ok((witness.to_object(py), outputs).to_object(py))
+        })
+    })
+}
+
+/// Runs the float pass of the onnx model on input arrays held in memory, normalized as the quantized forward pass sees
+/// them, to compare the outputs of a witness against
+///
+/// Arguments
+/// ---------
+/// inputs: list[numpy.ndarray]
+///     One float64, float32, int64, int32 or bool array per model input, of the shape of the input up to dimensions of size 1. Tensors implementing `__dlpack__` (torch, jax, cupy) are accepted in place of numpy arrays, and read without a copy when they are on the host
+///
+/// model: str
+///     Path to the onnx file
+///
+/// settings: str
+///     Path to the settings file
+///
+/// Returns
+/// -------
+/// list[numpy.ndarray]
+///     The float32 outputs of the model
+///
+/// Raises
+/// ------
+/// ValueError
+///     If an array has an unsupported dtype or doesn't have the shape of its model input
+///
+#[pyfunction(signature = (
+    inputs,
+    model=PathBuf::from(DEFAULT_MODEL),
+    settings=PathBuf::from(DEFAULT_SETTINGS),
+))]
+fn forward_arrays(
+    py: Python<'_>,
+    inputs: Vec<Bound<'_, PyAny>>,
+    model: PathBuf,
+    settings: PathBuf,
+) -> PyResult<Vec<PyObject>> {
+    let data = numpy_inputs(&inputs)?;
+    let outputs = crate::execute::float_forward(model, settings, data)
+        .map_err(|e| ezkl_error("Failed to run the forward pass", e))?;
+    numpy_outputs(
+        py,
+        outputs
+            .into_iter()
+            .map(|t| (t.iter().copied().collect(), t.dims().to_vec()))
+            .collect(),
+    )
+}
+
+/// Runs the forward pass operation on every data file in a directory, loading the compiled model once
+///
+/// Arguments
+/// ---------
+/// input_dir: str
+///     Path to the directory of data files, each a .json file, a .csv file, a png / jpeg image or a .npy file / .npz archive holding the input arrays
+///
+/// output_dir: str
+///     Path to the directory to create a witness file for each data file in, along with a summary.json of their outputs and errors
+///
+/// model: str
+///     Path to the compiled model file
+///
+/// vk_path: str
+///     Path to the verification key
+///
+/// srs_path: str
+///     Path to the SRS file
+///
+/// threads: int
+///     Maximum number of threads to run the forward passes on, defaults to the available parallelism
+///
+/// csv_mapping: str
+///     Path to a .json spec of the csv columns feeding each model input, without it every column of a .csv data file feeds the first input
+///
+/// merkle_tree: str
+///     Path to the .json merkle tree of the dataset the inputs are proven to belong to, required when the input visibility is merkle
+///
+/// Returns
+/// -------
+/// dict
+///     Python object containing the number of succeeded and failed data files, and the witness file, rescaled outputs or error of each
+///
+#[pyfunction(signature = (
+    input_dir,
+    output_dir,
+    model=PathBuf::from(DEFAULT_COMPILED_CIRCUIT),
+    vk_path=None,
+    srs_path=None,
+    threads=None,
+    csv_mapping=None,
+    merkle_tree=None,
+))]
+fn gen_witness_batch(
+    py: Python,
+    input_dir: PathBuf,
+    output_dir: PathBuf,
+    model: PathBuf,
+    vk_path: Option<PathBuf>,
+    srs_path: Option<PathBuf>,
+    threads: Option<usize>,
+    csv_mapping: Option<PathBuf>,
+    merkle_tree: Option<PathBuf>,
+) -> PyResult<Bound<'_, PyAny>> {
+    pyo3_asyncio::tokio::future_into_py(py, async move {
+        let output = crate::execute::gen_witness_batch(
+            model,
+            input_dir,
+            output_dir,
+            vk_path,
+            srs_path,
+            threads,
+            csv_mapping,
+            merkle_tree,
         )
         .await
-        .map_err(|e| {
-            let err_str = format!("Failed to calibrate settings: {}", e);
-            PyRuntimeError::new_err(err_str)
-        })?;
-
-        Ok(true)
+        .map_err(|e| ezkl_error("Failed to generate witnesses", e))?;
+        Python::with_gil(|py| Ok(output.to_object(py)))
     })
 }
 
-/// Runs the forward pass operation to generate a witness
+/// Builds the merkle tree of a dataset, whose root the inputs are proven to belong to when the input visibility is merkle
 ///
 /// Arguments
 /// ---------
-/// data: str
-///     Path to the data file
+/// dataset: str
+///     Path to the .json dataset file, an array of rows each in the `input_data` format of a .json data file
 ///
 /// model: str
 ///     Path to the compiled model file
 ///
 /// output: str
-///     Path to create the witness file
+///     Path to save the .json merkle tree to
 ///
-/// vk_path: str
-///     Path to the verification key
+/// Returns
+/// -------
+/// str
+///     The merkle root of the dataset, as a field element
 ///
-/// srs_path: str
-///     Path to the SRS file
+#[pyfunction(signature = (
+    dataset,
+    model=PathBuf::from(DEFAULT_COMPILED_CIRCUIT),
+    output=PathBuf::from(DEFAULT_MERKLE_TREE),
+))]
+fn merkle_tree(dataset: PathBuf, model: PathBuf, output: PathBuf) -> PyResult<String> {
+    crate::execute::merkle_tree(model, dataset, output)
+        .map_err(|e| ezkl_error("Failed to build the merkle tree", e))
+}
+
+/// Redacts a witness for sharing with a verifier. The inputs and outputs the visibilities of the settings keep private
+/// are left out with the other private values (the merkle path of the inputs, the blinders of the commitments in the
+/// clear, the salt of the nullifier and the randomness of the encrypted outputs). The public instances, hashes and
+/// commitments are kept, the redacted witness can no longer be proven
+///
+/// Arguments
+/// ---------
+/// witness: str
+///     Path to the full witness file
+///
+/// settings: str
+///     Path to the settings file the witness was generated with
+///
+/// output: str
+///     Path to write the redacted witness to, if None it is only returned
+///
+/// recipient: str
+///     The age X25519 public key (`age1...`) to encrypt the full witness to within the redacted witness, such that the holder of its identity can recover it with `unredact_witness`. Without it the private values are removed
 ///
 /// Returns
 /// -------
 /// dict
-///     Python object containing the witness values
+///     Python object containing the redacted witness values
 ///
 #[pyfunction(signature = (
-    data=PathBuf::from(DEFAULT_DATA),
-    model=PathBuf::from(DEFAULT_COMPILED_CIRCUIT),
-    output=PathBuf::from(DEFAULT_WITNESS),
-    vk_path=None,
-    srs_path=None,
+    witness=PathBuf::from(DEFAULT_WITNESS),
+    settings=PathBuf::from(DEFAULT_SETTINGS),
+    output=None,
+    recipient=None,
 ))]
-fn gen_witness(
-    py: Python,
-    data: PathBuf,
-    model: PathBuf,
+fn redact_witness(
+    py: Python<'_>,
+    witness: PathBuf,
+    settings: PathBuf,
     output: Option<PathBuf>,
-    vk_path: Option<PathBuf>,
-    srs_path: Option<PathBuf>,
-) -> PyResult<Bound<'_, PyAny>> {
-    pyo3_asyncio::tokio::future_into_py(py, async move {
-        let output = crate::execute::gen_witness(model, data, output, vk_path, srs_path)
-            .await
-            .map_err(|e| {
-                let err_str = format!("Failed to generate witness: {}", e);
-                PyRuntimeError::new_err(err_str)
-            })?;
-        Python::with_gil(|py| Ok(output.to_object(py)))
-    })
+    recipient: Option<String>,
+) -> PyResult<PyObject> {
+    let witness =
+        GraphWitness::from_path(witness).map_err(|e| ezkl_error("Failed to load witness", e))?;
+    let settings =
+        GraphSettings::load(&settings).map_err(|e| ezkl_error("Failed to load settings", e))?;
+    let redacted = match recipient {
+        Some(recipient) => witness.redact_to(&settings, &recipient),
+        None => witness.redact(&settings),
+    }
+    .map_err(|e| ezkl_error("Failed to redact witness", e))?;
+    if let Some(output) = output {
+        redacted
+            .save(output)
+            .map_err(|e| ezkl_error("Failed to save witness", e))?;
+    }
+    Ok(redacted.to_object(py))
+}
+
+/// Recovers the full witness kept encrypted in a redacted witness
+///
+/// Arguments
+/// ---------
+/// witness: str
+///     Path to the redacted witness file, redacted with a recipient
+///
+/// identity: str
+///     The age X25519 secret key (`AGE-SECRET-KEY-1...`) of the recipient the full witness was encrypted to
+///
+/// output: str
+///     Path to write the full witness to, if None it is only returned
+///
+/// Returns
+/// -------
+/// dict
+///     Python object containing the full witness values
+///
+#[pyfunction(signature = (
+    witness,
+    identity,
+    output=None,
+))]
+fn unredact_witness(
+    py: Python<'_>,
+    witness: PathBuf,
+    identity: String,
+    output: Option<PathBuf>,
+) -> PyResult<PyObject> {
+    let witness =
+        GraphWitness::from_path(witness).map_err(|e| ezkl_error("Failed to load witness", e))?;
+    let full = witness
+        .unredact(&identity)
+        .map_err(|e| ezkl_error("Failed to unredact witness", e))?;
+    if let Some(output) = output {
+        full.save(output)
+            .map_err(|e| ezkl_error("Failed to save witness", e))?;
+    }
+    Ok(full.to_object(py))
 }
 
 /// Mocks the prover
@@ -985,19 +2359,56 @@ fn gen_witness(
 /// model: str
 ///     Path to the compiled model file
 ///
+/// blinding_secret_key: str
+///     The hex or decimal secret key the blinders of the polycommit commitments are encrypted to, when they are encrypted in the witness
+///
+/// Returns
+/// -------
+/// bool
+///
+#[pyfunction(signature = (
+    witness=PathBuf::from(DEFAULT_WITNESS),
+    model=PathBuf::from(DEFAULT_COMPILED_CIRCUIT),
+    blinding_secret_key=None,
+))]
+fn mock(witness: PathBuf, model: PathBuf, blinding_secret_key: Option<String>) -> PyResult<bool> {
+    crate::execute::mock(model, witness, blinding_secret_key)
+        .map_err(|e| verify_error("Failed to run mock", e))?;
+    Ok(true)
+}
+
+/// Validates a witness against a compiled circuit with the mock prover, without proving. The constraints the witness
+/// fails are attributed to the nodes of the model they stem from
+///
+/// Arguments
+/// ---------
+/// witness: str
+///     Path to the witness file
+///
+/// model: str
+///     Path to the compiled model file
+///
+/// blinding_secret_key: str
+///     The hex or decimal secret key the blinders of the polycommit commitments are encrypted to, when they are encrypted in the witness
+///
 /// Returns
 /// -------
 /// bool
+///     True if the witness satisfies the circuit, otherwise a ProofVerificationError is raised whose `failures` read
+///     as "node 14 (SIGMOID(scale=128)) row 20312: ..." and whose `nodes` are the nodes blamed
 ///
 #[pyfunction(signature = (
     witness=PathBuf::from(DEFAULT_WITNESS),
     model=PathBuf::from(DEFAULT_COMPILED_CIRCUIT),
+    blinding_secret_key=None,
 ))]
-fn mock(witness: PathBuf, model: PathBuf) -> PyResult<bool> {
-    crate::execute::mock(model, witness).map_err(|e| {
-        let err_str = format!("Failed to run mock: {}", e);
-        PyRuntimeError::new_err(err_str)
-    })?;
+fn validate_witness(
+    witness: PathBuf,
+    model: PathBuf,
+    blinding_secret_key: Option<String>,
+) -> PyResult<bool> {
+    crate::execute::validate_witness(model, witness, blinding_secret_key)
+        .map_err(|e| verify_error("Failed to validate witness", e))?;
     Ok(true)
 }
 
@@ -1028,10 +2439,8 @@ fn mock_aggregate(
     logrows: u32,
     split_proofs: bool,
 ) -> PyResult<bool> {
-    crate::execute::mock_aggregate(aggregation_snarks, logrows, split_proofs).map_err(|e| {
-        let err_str = format!("Failed to run mock: {}", e);
-        PyRuntimeError::new_err(err_str)
-    })?;
+    crate::execute::mock_aggregate(aggregation_snarks, logrows, split_proofs)
+        .map_err(|e| verify_error("Failed to run mock", e))?;
 
     Ok(true)
 }
@@ -1058,6 +2467,9 @@ fn mock_aggregate(
 /// disable_selector_compression: bool
 ///     Whether to compress the selectors or not
 ///
+/// identity: str
+///     Path to the age identity file to decrypt the compiled model with, when it is encrypted (see `compile_circuit`). The proving key is then encrypted to its public key
+///
 /// Returns
 /// -------
 /// bool
@@ -1069,6 +2481,7 @@ fn mock_aggregate(
     srs_path=None,
     witness_path = None,
     disable_selector_compression=DEFAULT_DISABLE_SELECTOR_COMPRESSION.parse().unwrap(),
+    identity=None,
 ))]
 fn setup(
     model: PathBuf,
@@ -1077,6 +2490,7 @@ fn setup(
     srs_path: Option<PathBuf>,
     witness_path: Option<PathBuf>,
     disable_selector_compression: bool,
+    identity: Option<PathBuf>,
 ) -> Result<bool, PyErr> {
     crate::execute::setup(
         model,
@@ -1085,16 +2499,295 @@ fn setup(
         pk_path,
         witness_path,
         disable_selector_compression,
+        identity,
+    )
+    .map_err(|e| ezkl_error("Failed to run setup", e))?;
+
+    Ok(true)
+}
+
+/// Creates the keys of each member of a family of batch sizes in its directory
+///
+/// Arguments
+/// ---------
+/// batch_family: str
+///     Path to the directory of the family (see `compile_batch_family`)
+///
+/// srs_path: str
+///     Path to the SRS file
+///
+/// disable_selector_compression: bool
+///     Whether to compress the selectors or not
+///
+/// Returns
+/// -------
+/// bool
+///
+#[pyfunction(signature = (
+    batch_family=PathBuf::from(DEFAULT_BATCH_FAMILY_DIR),
+    srs_path=None,
+    disable_selector_compression=DEFAULT_DISABLE_SELECTOR_COMPRESSION.parse().unwrap(),
+))]
+fn setup_batch_family(
+    batch_family: PathBuf,
+    srs_path: Option<PathBuf>,
+    disable_selector_compression: bool,
+) -> Result<bool, PyErr> {
+    crate::execute::setup_batch_family(batch_family, srs_path, disable_selector_compression)
+        .map_err(|e| ezkl_error("Failed to run setup", e))?;
+
+    Ok(true)
+}
+
+/// Runs the prover on a set of inputs
+///
+/// Arguments
+/// ---------
+/// witness: str
+///     Path to the witness file
+///
+/// model: str
+///     Path to the compiled model file
+///
+/// pk_path: str
+///     Path to the proving key file
+///
+/// proof_path: str
+///     Path to create the proof file
+///
+/// proof_type: str
+///     Accepts `single`, `for-aggr`
+///
+/// srs_path: str
+///     Path to the SRS file
+///
+/// transcript: str
+///     Accepts `poseidon`, `blake`, `evm`. Defaults to `poseidon` for `for-aggr` proofs and `evm` otherwise
+///
+/// blinding_secret_key: str
+///     The hex or decimal secret key the blinders of the polycommit commitments are encrypted to, when they are encrypted in the witness
+///
+/// timeout: float
+///     Seconds after which the proof is cancelled, failing without writing the proof. The proof stops as it lays out
+///     the circuit, a proof committing to the witness runs to completion before it is discarded
+///
+/// identity: str
+///     Path to the age identity file to decrypt the compiled model and proving key with, when they are encrypted (see
+///     `compile_circuit`)
+///
+/// Returns
+/// -------
+/// bool
+///
+#[pyfunction(signature = (
+    witness=PathBuf::from(DEFAULT_WITNESS),
+    model=PathBuf::from(DEFAULT_COMPILED_CIRCUIT),
+    pk_path=PathBuf::from(DEFAULT_PK),
+    proof_path=None,
+    proof_type=ProofType::default(),
+    srs_path=None,
+    transcript=None,
+    blinding_secret_key=None,
+    timeout=None,
+    identity=None,
+))]
+fn prove(
+    witness: PathBuf,
+    model: PathBuf,
+    pk_path: PathBuf,
+    proof_path: Option<PathBuf>,
+    proof_type: ProofType,
+    srs_path: Option<PathBuf>,
+    transcript: Option<TranscriptType>,
+    blinding_secret_key: Option<String>,
+    timeout: Option<f64>,
+    identity: Option<PathBuf>,
+) -> PyResult<PyObject> {
+    let token = timeout_token(timeout)?;
+    let snark = with_observer(std::rc::Rc::new(token), || {
+        crate::execute::prove(
+            witness,
+            model,
+            pk_path,
+            proof_path,
+            srs_path,
+            proof_type,
+            transcript,
+            CheckMode::UNSAFE,
+            blinding_secret_key,
+            identity,
+        )
+    })
+    .map_err(|e| ezkl_error("Failed to run prove", e))?;
+
+    Python::with_gil(|py| Ok(snark.to_object(py)))
+}
+
+/// Creates a proof for each witness file in a directory, loading the proving key and SRS once
+///
+/// Arguments
+/// ---------
+/// witness_dir: str
+///     Path to the directory of witness files
+///
+/// proof_dir: str
+///     Path to the directory to save the proof of each witness to, along with a summary.json of their errors
+///
+/// model: str
+///     Path to the compiled model file
+///
+/// pk_path: str
+///     Path to the proving key file
+///
+/// proof_type: str
+///     Accepts `single`, `for-aggr`
+///
+/// srs_path: str
+///     Path to the SRS file
+///
+/// transcript: str
+///     Accepts `poseidon`, `blake`, `evm`. Defaults to `poseidon` for `for-aggr` proofs and `evm` otherwise
+///
+/// blinding_secret_key: str
+///     The hex or decimal secret key the blinders of the polycommit commitments are encrypted to, when they are encrypted in the witnesses
+///
+/// workers: int
+///     Number of proofs to create at once, lowered to as many as fit in the available memory
+///
+/// Returns
+/// -------
+/// dict
+///     Python object containing the number of succeeded and failed witnesses, and the proof file or error of each
+///
+#[pyfunction(signature = (
+    witness_dir,
+    proof_dir,
+    model=PathBuf::from(DEFAULT_COMPILED_CIRCUIT),
+    pk_path=PathBuf::from(DEFAULT_PK),
+    proof_type=ProofType::default(),
+    srs_path=None,
+    transcript=None,
+    blinding_secret_key=None,
+    workers=DEFAULT_PROVE_WORKERS.parse().unwrap(),
+))]
+fn prove_batch(
+    witness_dir: PathBuf,
+    proof_dir: PathBuf,
+    model: PathBuf,
+    pk_path: PathBuf,
+    proof_type: ProofType,
+    srs_path: Option<PathBuf>,
+    transcript: Option<TranscriptType>,
+    blinding_secret_key: Option<String>,
+    workers: usize,
+) -> PyResult<PyObject> {
+    let summary = crate::execute::prove_batch(
+        model,
+        witness_dir,
+        proof_dir,
+        pk_path,
+        srs_path,
+        proof_type,
+        transcript,
+        CheckMode::UNSAFE,
+        blinding_secret_key,
+        workers,
     )
-    .map_err(|e| {
-        let err_str = format!("Failed to run setup: {}", e);
-        PyRuntimeError::new_err(err_str)
-    })?;
+    .map_err(|e| ezkl_error("Failed to run prove_batch", e))?;
+
+    Python::with_gil(|py| Ok(summary.to_object(py)))
+}
+
+/// Proves the samples of some data with the smallest member of a family of batch sizes they fit, padded with the
+/// padding sample of the family. The proof verifies with the settings and verification key of the member, and its
+/// instances hold the outputs of the padding zeroed and the mask of the samples last
+///
+/// Arguments
+/// ---------
+/// data: str
+///     Path to the data file of the samples
+///
+/// batch_family: str
+///     Path to the directory of the family (see `compile_batch_family` and `setup_batch_family`)
+///
+/// proof_path: str
+///     Path to create the proof file
+///
+/// proof_type: str
+///     Accepts `single`, `for-aggr`
+///
+/// srs_path: str
+///     Path to the SRS file
+///
+/// transcript: str
+///     Accepts `poseidon`, `blake`, `evm`. Defaults to `poseidon` for `for-aggr` proofs and `evm` otherwise
+///
+/// Returns
+/// -------
+/// dict
+///
+#[pyfunction(signature = (
+    data=PathBuf::from(DEFAULT_DATA),
+    batch_family=PathBuf::from(DEFAULT_BATCH_FAMILY_DIR),
+    proof_path=None,
+    proof_type=ProofType::default(),
+    srs_path=None,
+    transcript=None,
+))]
+fn prove_batch_family(
+    py: Python,
+    data: PathBuf,
+    batch_family: PathBuf,
+    proof_path: Option<PathBuf>,
+    proof_type: ProofType,
+    srs_path: Option<PathBuf>,
+    transcript: Option<TranscriptType>,
+) -> PyResult<Bound<'_, PyAny>> {
+    pyo3_asyncio::tokio::future_into_py(py, async move {
+        let snark = crate::execute::prove_batch_family(
+            batch_family,
+            data,
+            proof_path,
+            srs_path,
+            proof_type,
+            transcript,
+        )
+        .await
+        .map_err(|e| ezkl_error("Failed to run prove_batch_family", e))?;
+        Python::with_gil(|py| Ok(snark.to_object(py)))
+    })
+}
 
-    Ok(true)
+/// Runs `work` on the blocking thread pool of the runtime, without the GIL, and returns an awaitable of its outcome.
+/// Cancelling the awaitable drops its future, which cancels the proofs of `work` at their next progress report, as
+/// does the elapsing of `timeout` seconds
+fn spawn_cancellable<F>(py: Python<'_>, timeout: Option<f64>, work: F) -> PyResult<Bound<'_, PyAny>>
+where
+    F: FnOnce() -> PyResult<PyObject> + Send + 'static,
+{
+    let token = timeout_token(timeout)?;
+    let cancel = token.cancel_on_drop();
+    let task = pyo3_asyncio::tokio::get_runtime()
+        .spawn_blocking(move || with_observer(std::rc::Rc::new(token), work));
+    pyo3_asyncio::tokio::future_into_py(py, async move {
+        let _cancel = cancel;
+        task.await
+            .map_err(|e| PyRuntimeError::new_err(format!("Failed to run task: {}", e)))?
+    })
 }
 
-/// Runs the prover on a set of inputs
+/// A token cancelling the proofs it observes after `timeout` seconds, or only once cancelled without a timeout
+fn timeout_token(timeout: Option<f64>) -> PyResult<CancellationToken> {
+    match timeout {
+        Some(timeout) => std::time::Duration::try_from_secs_f64(timeout)
+            .map(CancellationToken::with_timeout)
+            .map_err(|e| PyValueError::new_err(format!("Invalid timeout {}: {}", timeout, e))),
+        None => Ok(CancellationToken::new()),
+    }
+}
+
+/// Runs the prover on a set of inputs on the blocking thread pool of ezkl, without holding the GIL or blocking the
+/// asyncio event loop
 ///
 /// Arguments
 /// ---------
@@ -1116,9 +2809,20 @@ fn setup(
 /// srs_path: str
 ///     Path to the SRS file
 ///
+/// transcript: str
+///     Accepts `poseidon`, `blake`, `evm`. Defaults to `poseidon` for `for-aggr` proofs and `evm` otherwise
+///
+/// blinding_secret_key: str
+///     The hex or decimal secret key the blinders of the polycommit commitments are encrypted to, when they are encrypted in the witness
+///
+/// timeout: float
+///     Seconds after which the proof is cancelled, as when the awaitable is cancelled
+///
 /// Returns
 /// -------
-/// bool
+/// Awaitable[dict]
+///     Python object containing the proof. Cancelling the awaitable stops the proof as it lays out the circuit, or
+///     discards it once halo2 commits to the witness
 ///
 #[pyfunction(signature = (
     witness=PathBuf::from(DEFAULT_WITNESS),
@@ -1127,30 +2831,228 @@ fn setup(
     proof_path=None,
     proof_type=ProofType::default(),
     srs_path=None,
+    transcript=None,
+    blinding_secret_key=None,
+    timeout=None,
 ))]
-fn prove(
+fn prove_async(
+    py: Python<'_>,
     witness: PathBuf,
     model: PathBuf,
     pk_path: PathBuf,
     proof_path: Option<PathBuf>,
     proof_type: ProofType,
     srs_path: Option<PathBuf>,
-) -> PyResult<PyObject> {
-    let snark = crate::execute::prove(
+    transcript: Option<TranscriptType>,
+    blinding_secret_key: Option<String>,
+    timeout: Option<f64>,
+) -> PyResult<Bound<'_, PyAny>> {
+    spawn_cancellable(py, timeout, move || {
+        let snark = crate::execute::prove(
+            witness,
+            model,
+            pk_path,
+            proof_path,
+            srs_path,
+            proof_type,
+            transcript,
+            CheckMode::UNSAFE,
+            blinding_secret_key,
+            None,
+        )
+        .map_err(|e| ezkl_error("Failed to run prove", e))?;
+        Python::with_gil(|py| Ok(snark.to_object(py)))
+    })
+}
+
+/// Reads a witness or proof passed to a prover session, either the path to its .json file or its parsed json
+fn session_json<T: serde::de::DeserializeOwned>(value: &Bound<'_, PyAny>) -> PyResult<T> {
+    let json = if value.is_instance_of::<pyo3::types::PyDict>() {
+        value
+            .py()
+            .import_bound("json")?
+            .call_method1("dumps", (value,))?
+            .extract::<String>()?
+    } else {
+        let path: PathBuf = value.extract()?;
+        std::fs::read_to_string(&path)
+            .map_err(|e| ezkl_error(&format!("Failed to read {}", path.display()), e))?
+    };
+    serde_json::from_str(&json).map_err(|e| ezkl_error("Failed to parse", e))
+}
+
+/// Hands a value over to python as its parsed json, as the witnesses and proofs of a prover session, which the
+/// session reads back
+fn session_object<T: serde::Serialize>(py: Python<'_>, value: &T) -> PyResult<PyObject> {
+    let json = serde_json::to_string(value).map_err(|e| ezkl_error("Failed to serialize", e))?;
+    Ok(py
+        .import_bound("json")?
+        .call_method1("loads", (json,))?
+        .unbind())
+}
+
+/// A compiled circuit with its proving key and SRS loaded once, to generate witnesses, prove and verify without
+/// reading the artifacts again. The session can be shared between python threads, which prove and verify concurrently
+/// as the GIL is released while they do
+///
+/// Arguments
+/// ---------
+/// compiled_circuit: str
+///     Path to the compiled model file
+///
+/// pk_path: str
+///     Path to the proving key file
+///
+/// srs_path: str
+///     Path to the SRS file
+///
+#[pyclass(name = "ProverSession")]
+struct PyProverSession {
+    inner: std::sync::Arc<crate::execute::ProverSession>,
+}
+
+#[pymethods]
+impl PyProverSession {
+    #[new]
+    #[pyo3(signature = (
+        compiled_circuit=PathBuf::from(DEFAULT_COMPILED_CIRCUIT),
+        pk_path=PathBuf::from(DEFAULT_PK),
+        srs_path=None,
+    ))]
+    fn new(
+        py: Python<'_>,
+        compiled_circuit: PathBuf,
+        pk_path: PathBuf,
+        srs_path: Option<PathBuf>,
+    ) -> PyResult<Self> {
+        let inner = py
+            .allow_threads(|| {
+                crate::execute::ProverSession::load(compiled_circuit, pk_path, srs_path)
+            })
+            .map_err(|e| ezkl_error("Failed to load prover session", e))?;
+        Ok(PyProverSession {
+            inner: std::sync::Arc::new(inner),
+        })
+    }
+
+    /// Runs the forward pass operation to generate a witness
+    ///
+    /// Arguments
+    /// ---------
+    /// input: str | list[numpy.ndarray]
+    ///     Path to the data file, or one numpy array per model input as in `gen_witness_arrays`
+    ///
+    /// output: str
+    ///     Path to create the witness file, if None the witness is only returned
+    ///
+    /// Returns
+    /// -------
+    /// dict
+    ///     The witness as it is written to a witness file, which `prove` accepts
+    ///
+    #[pyo3(signature = (input, output=None))]
+    fn gen_witness<'py>(
+        &self,
+        py: Python<'py>,
+        input: Bound<'py, PyAny>,
+        output: Option<PathBuf>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let data = match input.extract::<PathBuf>() {
+            Ok(path) => DataInput::Path(path),
+            Err(_) => numpy_inputs(&input.extract::<Vec<Bound<'py, PyAny>>>()?)?,
+        };
+        let session = self.inner.clone();
+        pyo3_asyncio::tokio::future_into_py(py, async move {
+            let witness = session
+                .gen_witness(data)
+                .await
+                .map_err(|e| ezkl_error("Failed to generate witness", e))?;
+            if let Some(output) = output {
+                witness
+                    .save(output)
+                    .map_err(|e| ezkl_error("Failed to save witness", e))?;
+            }
+            Python::with_gil(|py| session_object(py, &witness))
+        })
+    }
+
+    /// Creates a proof of a witness, without holding the GIL
+    ///
+    /// Arguments
+    /// ---------
+    /// witness: str | dict
+    ///     Path to the witness file, or the witness returned by `gen_witness`
+    ///
+    /// proof_path: str
+    ///     Path to create the proof file, if None the proof is only returned
+    ///
+    /// proof_type: str
+    ///     Accepts `single`, `for-aggr`
+    ///
+    /// transcript: str
+    ///     Accepts `poseidon`, `blake`, `evm`. Defaults to `poseidon` for `for-aggr` proofs and `evm` otherwise
+    ///
+    /// blinding_secret_key: str
+    ///     The hex or decimal secret key the blinders of the polycommit commitments are encrypted to, when they are encrypted in the witness
+    ///
+    /// Returns
+    /// -------
+    /// dict
+    ///     The proof as it is written to a proof file, which `verify` accepts
+    ///
+    #[pyo3(signature = (
         witness,
-        model,
-        pk_path,
-        proof_path,
-        srs_path,
-        proof_type,
-        CheckMode::UNSAFE,
-    )
-    .map_err(|e| {
-        let err_str = format!("Failed to run prove: {}", e);
-        PyRuntimeError::new_err(err_str)
-    })?;
+        proof_path=None,
+        proof_type=ProofType::default(),
+        transcript=None,
+        blinding_secret_key=None,
+    ))]
+    fn prove(
+        &self,
+        py: Python<'_>,
+        witness: Bound<'_, PyAny>,
+        proof_path: Option<PathBuf>,
+        proof_type: ProofType,
+        transcript: Option<TranscriptType>,
+        blinding_secret_key: Option<String>,
+    ) -> PyResult<PyObject> {
+        let witness = session_json(&witness)?;
+        let snark = py
+            .allow_threads(|| {
+                let snark =
+                    self.inner
+                        .prove(witness, proof_type, transcript, blinding_secret_key)?;
+                if let Some(proof_path) = proof_path {
+                    snark.save(&proof_path)?;
+                }
+                Ok::<_, EZKLError>(snark)
+            })
+            .map_err(|e| ezkl_error("Failed to run prove", e))?;
+        session_object(py, &snark)
+    }
 
-    Python::with_gil(|py| Ok(snark.to_object(py)))
+    /// Verifies a proof against the verifying key of the loaded proving key, without holding the GIL
+    ///
+    /// Arguments
+    /// ---------
+    /// proof: str | dict
+    ///     Path to the proof file, or the proof returned by `prove`
+    ///
+    /// Returns
+    /// -------
+    /// bool
+    ///
+    fn verify(&self, py: Python<'_>, proof: Bound<'_, PyAny>) -> PyResult<bool> {
+        let proof: Snark<Fr, G1Affine> = session_json(&proof)?;
+        py.allow_threads(|| self.inner.verify(&proof))
+            .map_err(|e| verify_error("Failed to run verify", e))
+    }
+
+    /// int: The log_2 number of rows of the circuit
+    #[getter]
+    fn logrows(&self) -> u32 {
+        self.inner.settings().run_args.logrows
+    }
 }
 
 /// Verifies a given proof
@@ -1172,6 +3074,12 @@ fn prove(
 /// non_reduced_srs: bool
 ///     Whether to reduce the number of SRS logrows to the number of instances rather than the number of logrows used for proofs (only works if the srs were generated in the same ceremony)
 ///
+/// transcript: str
+///     If set, rejects proofs that were not generated with this transcript (`poseidon`, `blake`, `evm`)
+///
+/// group_commitments: list[str]
+///     Paths to the commitments of groups of params (generated with `commit_param_groups`) the proof is checked against, each group on its own
+///
 /// Returns
 /// -------
 /// bool
@@ -1182,6 +3090,8 @@ fn prove(
     vk_path=PathBuf::from(DEFAULT_VK),
     srs_path=None,
     reduced_srs=DEFAULT_USE_REDUCED_SRS_FOR_VERIFICATION.parse::<bool>().unwrap(),
+    transcript=None,
+    group_commitments=vec![],
 ))]
 fn verify(
     proof_path: PathBuf,
@@ -1189,17 +3099,83 @@ fn verify(
     vk_path: PathBuf,
     srs_path: Option<PathBuf>,
     reduced_srs: bool,
+    transcript: Option<TranscriptType>,
+    group_commitments: Vec<PathBuf>,
 ) -> Result<bool, PyErr> {
-    crate::execute::verify(proof_path, settings_path, vk_path, srs_path, reduced_srs).map_err(
-        |e| {
-            let err_str = format!("Failed to run verify: {}", e);
-            PyRuntimeError::new_err(err_str)
-        },
-    )?;
+    crate::execute::check_group_commitments(&proof_path, &settings_path, &group_commitments)
+        .map_err(|e| verify_error("Failed to check the commitments of the groups", e))?;
+    crate::execute::verify(
+        proof_path,
+        settings_path,
+        vk_path,
+        srs_path,
+        reduced_srs,
+        transcript,
+    )
+    .map_err(|e| verify_error("Failed to run verify", e))?;
 
     Ok(true)
 }
 
+/// Verifies a given proof on the blocking thread pool of ezkl, without holding the GIL or blocking the asyncio event
+/// loop
+///
+/// Arguments
+/// ---------
+/// proof_path: str
+///     Path to the proof file
+///
+/// settings_path: str
+///     Path to the settings file
+///
+/// vk_path: str
+///     Path to the verification key file
+///
+/// srs_path: str
+///     Path to the SRS file
+///
+/// non_reduced_srs: bool
+///     Whether to reduce the number of SRS logrows to the number of instances rather than the number of logrows used for proofs (only works if the srs were generated in the same ceremony)
+///
+/// transcript: str
+///     If set, rejects proofs that were not generated with this transcript (`poseidon`, `blake`, `evm`)
+///
+/// Returns
+/// -------
+/// Awaitable[bool]
+///     Cancelling the awaitable discards the outcome, the verification itself runs to completion
+///
+#[pyfunction(signature = (
+    proof_path=PathBuf::from(DEFAULT_PROOF),
+    settings_path=PathBuf::from(DEFAULT_SETTINGS),
+    vk_path=PathBuf::from(DEFAULT_VK),
+    srs_path=None,
+    reduced_srs=DEFAULT_USE_REDUCED_SRS_FOR_VERIFICATION.parse::<bool>().unwrap(),
+    transcript=None,
+))]
+fn verify_async(
+    py: Python<'_>,
+    proof_path: PathBuf,
+    settings_path: PathBuf,
+    vk_path: PathBuf,
+    srs_path: Option<PathBuf>,
+    reduced_srs: bool,
+    transcript: Option<TranscriptType>,
+) -> PyResult<Bound<'_, PyAny>> {
+    spawn_cancellable(py, None, move || {
+        crate::execute::verify(
+            proof_path,
+            settings_path,
+            vk_path,
+            srs_path,
+            reduced_srs,
+            transcript,
+        )
+        .map_err(|e| verify_error("Failed to run verify", e))?;
+        Python::with_gil(|py| Ok(true.to_object(py)))
+    })
+}
+
 ///  Runs the setup process for an aggregate setup
 ///
 /// Arguments
@@ -1262,10 +3238,7 @@ fn setup_aggregate(
         disable_selector_compression,
         commitment.into(),
     )
-    .map_err(|e| {
-        let err_str = format!("Failed to setup aggregate: {}", e);
-        PyRuntimeError::new_err(err_str)
-    })?;
+    .map_err(|e| ezkl_error("Failed to setup aggregate", e))?;
 
     Ok(true)
 }
@@ -1283,6 +3256,13 @@ fn setup_aggregate(
 /// settings_path: str
 ///     Path to the settings files
 ///
+/// weights: str | None
+///     Path to a safetensors file whose tensors override the initializers of the model of the same names, its sha256 being recorded in the settings
+///
+/// encrypt_to: str | None
+///     The age X25519 public key (`age1...`), or a file holding it, to encrypt the compiled circuit to. It is then
+///     only loaded with the `identity` of the key, which setup encrypts the proving key to as well
+///
 /// Returns
 /// -------
 /// bool
@@ -1291,95 +3271,282 @@ fn setup_aggregate(
     model=PathBuf::from(DEFAULT_MODEL),
     compiled_circuit=PathBuf::from(DEFAULT_COMPILED_CIRCUIT),
     settings_path=PathBuf::from(DEFAULT_SETTINGS),
+    weights=None,
+    encrypt_to=None,
 ))]
 fn compile_circuit(
     model: PathBuf,
     compiled_circuit: PathBuf,
     settings_path: PathBuf,
+    weights: Option<PathBuf>,
+    encrypt_to: Option<String>,
+) -> Result<bool, PyErr> {
+    crate::execute::compile_circuit(model, compiled_circuit, settings_path, weights, encrypt_to)
+        .map_err(|e| ezkl_error("Failed to setup aggregate", e))?;
+
+    Ok(true)
+}
+
+/// Compiles the circuits of a model at the batch sizes of the powers of two up to a max, masking the samples of each
+/// batch, at the same logrows such that they share the SRS and the commitments of the params
+///
+/// Arguments
+/// ---------
+/// padding: str
+///     Path to the data file of the single sample the batches are padded with, whose outputs are masked out of the instances
+///
+/// model: str
+///     Path to the onnx model file
+///
+/// settings_path: str
+///     Path to the settings file, whose run args the members are compiled with at their batch size
+///
+/// max_batch_size: int
+///     The largest batch size of the family
+///
+/// output_dir: str
+///     Path to the directory to save the settings and compiled circuits of the members to, along with a family.json of the family
+///
+/// Returns
+/// -------
+/// bool
+///
+#[pyfunction(signature = (
+    padding,
+    model=PathBuf::from(DEFAULT_MODEL),
+    settings_path=PathBuf::from(DEFAULT_SETTINGS),
+    max_batch_size=DEFAULT_MAX_BATCH_SIZE.parse().unwrap(),
+    output_dir=PathBuf::from(DEFAULT_BATCH_FAMILY_DIR),
+))]
+fn compile_batch_family(
+    padding: PathBuf,
+    model: PathBuf,
+    settings_path: PathBuf,
+    max_batch_size: usize,
+    output_dir: PathBuf,
+) -> Result<bool, PyErr> {
+    crate::execute::compile_batch_family(model, settings_path, padding, max_batch_size, output_dir)
+        .map_err(|e| ezkl_error("Failed to compile batch family", e))?;
+
+    Ok(true)
+}
+
+/// Creates an aggregated proof
+///
+/// Arguments
+/// ---------
+/// aggregation_snarks: list[str]
+///     List of paths to the various proofs
+///
+/// proof_path: str
+///     Path to output the aggregated proof
+///
+/// vk_path: str
+///     Path to the VK file
+///
+/// transcript:
+///     Proof transcript type to be used. `evm` used by default. `poseidon` is also supported
+///
+/// logrows:
+///     Logrows used for aggregation circuit
+///
+/// check_mode: str
+///     Run sanity checks during calculations. Accepts `safe` or `unsafe`
+///
+/// split-proofs: bool
+///      Whether the accumulated proofs are segments of a larger circuit
+///
+/// srs_path: str
+///     Path to the SRS used
+///
+/// commitment: str
+///     Accepts "kzg" or "ipa"
+///
+/// Returns
+/// -------
+/// bool
+///
+#[pyfunction(signature = (
+    aggregation_snarks=vec![PathBuf::from(DEFAULT_PROOF)],
+    proof_path=PathBuf::from(DEFAULT_PROOF_AGGREGATED),
+    vk_path=PathBuf::from(DEFAULT_VK_AGGREGATED),
+    transcript=TranscriptType::default(),
+    logrows=DEFAULT_AGGREGATED_LOGROWS.parse().unwrap(),
+    check_mode=CheckMode::UNSAFE,
+    split_proofs = false,
+    srs_path=None,
+    commitment=DEFAULT_COMMITMENT.parse().unwrap(),
+))]
+fn aggregate(
+    aggregation_snarks: Vec<PathBuf>,
+    proof_path: PathBuf,
+    vk_path: PathBuf,
+    transcript: TranscriptType,
+    logrows: u32,
+    check_mode: CheckMode,
+    split_proofs: bool,
+    srs_path: Option<PathBuf>,
+    commitment: PyCommitments,
 ) -> Result<bool, PyErr> {
-    crate::execute::compile_circuit(model, compiled_circuit, settings_path).map_err(|e| {
-        let err_str = format!("Failed to setup aggregate: {}", e);
-        PyRuntimeError::new_err(err_str)
-    })?;
+    // the K used for the aggregation circuit
+    crate::execute::aggregate(
+        proof_path,
+        aggregation_snarks,
+        vk_path,
+        srs_path,
+        transcript,
+        logrows,
+        check_mode,
+        split_proofs,
+        commitment.into(),
+    )
+    .map_err(|e| ezkl_error("Failed to run aggregate", e))?;
 
     Ok(true)
 }
 
-/// Creates an aggregated proof
+/// Checks that the hashed/public or polycommit outputs of each proof of a chain of circuits are the inputs of the
+/// next, and optionally aggregates the proofs into one proof that also constrains them to match
+///
+/// Arguments
+/// ---------
+/// proof_paths: list[str]
+///     The paths to the proofs of the chain, in order
+///
+/// settings_paths: list[str]
+///     The paths to the settings files of the circuits of the proofs, in the same order
+///
+/// aggregate: bool
+///     Whether to aggregate the linked proofs (generated with the `for-aggr` proof type) into one proof binding them
+///
+/// aggregated_proof_path: str
+///     The path to output the aggregated proof to
+///
+/// vk_path: str
+///     The path to output the verification key of the aggregation circuit to
+///
+/// pk_path: str
+///     The path to output the proving key of the aggregation circuit to
+///
+/// srs_path: str
+///     Path to the SRS used for the aggregation circuit
+///
+/// logrows: int
+///     Logrows used for the aggregation circuit
+///
+/// Returns
+/// -------
+/// str
+///     The links between consecutive proofs, as JSON
+///
+#[pyfunction(signature = (
+    proof_paths,
+    settings_paths,
+    aggregate=DEFAULT_LINK_AGGREGATE.parse().unwrap(),
+    aggregated_proof_path=PathBuf::from(DEFAULT_PROOF_AGGREGATED),
+    vk_path=PathBuf::from(DEFAULT_VK_AGGREGATED),
+    pk_path=PathBuf::from(DEFAULT_PK_AGGREGATED),
+    srs_path=None,
+    logrows=DEFAULT_AGGREGATED_LOGROWS.parse().unwrap(),
+))]
+#[allow(clippy::too_many_arguments)]
+fn link_proofs(
+    proof_paths: Vec<PathBuf>,
+    settings_paths: Vec<PathBuf>,
+    aggregate: bool,
+    aggregated_proof_path: PathBuf,
+    vk_path: PathBuf,
+    pk_path: PathBuf,
+    srs_path: Option<PathBuf>,
+    logrows: u32,
+) -> Result<String, PyErr> {
+    crate::execute::link_proofs(
+        proof_paths,
+        settings_paths,
+        aggregate,
+        aggregated_proof_path,
+        vk_path,
+        pk_path,
+        srs_path,
+        logrows,
+        None,
+    )
+    .map_err(|e| ezkl_error("Failed to link proofs", e))
+}
+
+/// Checks that the proofs of a session, generated from witnesses chained with `prev_digest`, are chained in order, and
+/// optionally aggregates them into one proof that also constrains the chain
 ///
 /// Arguments
 /// ---------
-/// aggregation_snarks: list[str]
-///     List of paths to the various proofs
+/// proof_paths: list[str]
+///     The paths to the proofs of the session, in order
 ///
-/// proof_path: str
-///     Path to output the aggregated proof
+/// settings_paths: list[str]
+///     The paths to the settings files of the circuits of the proofs, in the same order, or a single one for every proof
 ///
-/// vk_path: str
-///     Path to the VK file
+/// genesis_digest: str
+///     The digest the first proof is chained to, as a hex string, if None the session may continue another
 ///
-/// transcript:
-///     Proof transcript type to be used. `evm` used by default. `poseidon` is also supported
+/// aggregate: bool
+///     Whether to aggregate the proofs (generated with the `for-aggr` proof type) into one proof binding the chain
 ///
-/// logrows:
-///     Logrows used for aggregation circuit
+/// aggregated_proof_path: str
+///     The path to output the aggregated proof to
 ///
-/// check_mode: str
-///     Run sanity checks during calculations. Accepts `safe` or `unsafe`
+/// vk_path: str
+///     The path to output the verification key of the aggregation circuit to
 ///
-/// split-proofs: bool
-///      Whether the accumulated proofs are segments of a larger circuit
+/// pk_path: str
+///     The path to output the proving key of the aggregation circuit to
 ///
 /// srs_path: str
-///     Path to the SRS used
+///     Path to the SRS used for the aggregation circuit
 ///
-/// commitment: str
-///     Accepts "kzg" or "ipa"
+/// logrows: int
+///     Logrows used for the aggregation circuit
 ///
 /// Returns
 /// -------
-/// bool
+/// str
+///     The digests of each step of the session, as JSON
 ///
 #[pyfunction(signature = (
-    aggregation_snarks=vec![PathBuf::from(DEFAULT_PROOF)],
-    proof_path=PathBuf::from(DEFAULT_PROOF_AGGREGATED),
+    proof_paths,
+    settings_paths,
+    genesis_digest=None,
+    aggregate=DEFAULT_LINK_AGGREGATE.parse().unwrap(),
+    aggregated_proof_path=PathBuf::from(DEFAULT_PROOF_AGGREGATED),
     vk_path=PathBuf::from(DEFAULT_VK_AGGREGATED),
-    transcript=TranscriptType::default(),
-    logrows=DEFAULT_AGGREGATED_LOGROWS.parse().unwrap(),
-    check_mode=CheckMode::UNSAFE,
-    split_proofs = false,
+    pk_path=PathBuf::from(DEFAULT_PK_AGGREGATED),
     srs_path=None,
-    commitment=DEFAULT_COMMITMENT.parse().unwrap(),
+    logrows=DEFAULT_AGGREGATED_LOGROWS.parse().unwrap(),
 ))]
-fn aggregate(
-    aggregation_snarks: Vec<PathBuf>,
-    proof_path: PathBuf,
+#[allow(clippy::too_many_arguments)]
+fn check_session(
+    proof_paths: Vec<PathBuf>,
+    settings_paths: Vec<PathBuf>,
+    genesis_digest: Option<String>,
+    aggregate: bool,
+    aggregated_proof_path: PathBuf,
     vk_path: PathBuf,
-    transcript: TranscriptType,
-    logrows: u32,
-    check_mode: CheckMode,
-    split_proofs: bool,
+    pk_path: PathBuf,
     srs_path: Option<PathBuf>,
-    commitment: PyCommitments,
-) -> Result<bool, PyErr> {
-    // the K used for the aggregation circuit
-    crate::execute::aggregate(
-        proof_path,
-        aggregation_snarks,
+    logrows: u32,
+) -> Result<String, PyErr> {
+    crate::execute::check_session(
+        proof_paths,
+        settings_paths,
+        genesis_digest,
+        aggregate,
+        aggregated_proof_path,
         vk_path,
+        pk_path,
         srs_path,
-        transcript,
         logrows,
-        check_mode,
-        split_proofs,
-        commitment.into(),
+        None,
     )
-    .map_err(|e| {
-        let err_str = format!("Failed to run aggregate: {}", e);
-        PyRuntimeError::new_err(err_str)
-    })?;
-
-    Ok(true)
+    .map_err(|e| ezkl_error("Failed to check the session", e))
 }
 
 /// Verifies and aggregate proof
@@ -1404,6 +3571,9 @@ fn aggregate(
 /// srs_path: str
 ///     The path to the SRS file
 ///
+/// transcript: str
+///     If set, rejects proofs that were not generated with this transcript (`poseidon`, `blake`, `evm`)
+///
 /// Returns
 /// -------
 /// bool
@@ -1415,6 +3585,7 @@ fn aggregate(
     commitment=DEFAULT_COMMITMENT.parse().unwrap(),
     reduced_srs=DEFAULT_USE_REDUCED_SRS_FOR_VERIFICATION.parse().unwrap(),
     srs_path=None,
+    transcript=None,
 ))]
 fn verify_aggr(
     proof_path: PathBuf,
@@ -1423,6 +3594,7 @@ fn verify_aggr(
     commitment: PyCommitments,
     reduced_srs: bool,
     srs_path: Option<PathBuf>,
+    transcript: Option<TranscriptType>,
 ) -> Result<bool, PyErr> {
     crate::execute::verify_aggr(
         proof_path,
@@ -1431,11 +3603,9 @@ fn verify_aggr(
         logrows,
         reduced_srs,
         commitment.into(),
+        transcript,
     )
-    .map_err(|e| {
-        let err_str = format!("Failed to run verify_aggr: {}", e);
-        PyRuntimeError::new_err(err_str)
-    })?;
+    .map_err(|e| verify_error("Failed to run verify_aggr", e))?;
 
     Ok(true)
 }
@@ -1475,10 +3645,8 @@ fn encode_evm_calldata<'a>(
         None
     };
 
-    crate::execute::encode_evm_calldata(proof, calldata, addr_vk).map_err(|e| {
-        let err_str = format!("Failed to generate calldata: {}", e);
-        PyRuntimeError::new_err(err_str)
-    })
+    crate::execute::encode_evm_calldata(proof, calldata, addr_vk)
+        .map_err(|e| ezkl_error("Failed to generate calldata", e))
 }
 
 /// Creates an EVM compatible verifier, you will need solc installed in your environment to run this
@@ -1503,6 +3671,15 @@ fn encode_evm_calldata<'a>(
 /// reusable: bool
 ///     Whether the verifier should be rendered as a reusable contract. If so, then you will need to deploy the VK artifact separately which you can generate using the create_evm_vka command
 ///
+/// batch: bool
+///     Whether to add a `verifyBatch(bytes[] proofs, uint256[][] instances)` entrypoint that verifies several proofs of the circuit in one transaction
+///
+/// foundry_dir: str
+///     If set, a Foundry project with the verifier, a test of the fixture proof and a deployment script is written to this directory
+///
+/// fixture_proof_path: str
+///     The proof to export as the fixture of the Foundry tests, required with foundry_dir
+///
 /// Returns
 /// -------
 /// bool
@@ -1514,6 +3691,9 @@ fn encode_evm_calldata<'a>(
     abi_path=PathBuf::from(DEFAULT_VERIFIER_ABI),
     srs_path=None,
     reusable = DEFAULT_RENDER_REUSABLE.parse().unwrap(),
+    batch = DEFAULT_RENDER_BATCH.parse().unwrap(),
+    foundry_dir = None,
+    fixture_proof_path = None,
 ))]
 fn create_evm_verifier(
     py: Python,
@@ -1523,6 +3703,9 @@ fn create_evm_verifier(
     abi_path: PathBuf,
     srs_path: Option<PathBuf>,
     reusable: bool,
+    batch: bool,
+    foundry_dir: Option<PathBuf>,
+    fixture_proof_path: Option<PathBuf>,
 ) -> PyResult<Bound<'_, PyAny>> {
     pyo3_asyncio::tokio::future_into_py(py, async move {
         crate::execute::create_evm_verifier(
@@ -1532,12 +3715,12 @@ fn create_evm_verifier(
             sol_code_path,
             abi_path,
             reusable,
+            batch,
+            foundry_dir,
+            fixture_proof_path,
         )
         .await
-        .map_err(|e| {
-            let err_str = format!("Failed to run create_evm_verifier: {}", e);
-            PyRuntimeError::new_err(err_str)
-        })?;
+        .map_err(|e| ezkl_error("Failed to run create_evm_verifier", e))?;
 
         Ok(true)
     })
@@ -1585,10 +3768,7 @@ fn create_evm_vka(
     pyo3_asyncio::tokio::future_into_py(py, async move {
         crate::execute::create_evm_vka(vk_path, srs_path, settings_path, sol_code_path, abi_path)
             .await
-            .map_err(|e| {
-                let err_str = format!("Failed to run create_evm_verifier: {}", e);
-                PyRuntimeError::new_err(err_str)
-            })?;
+            .map_err(|e| ezkl_error("Failed to run create_evm_verifier", e))?;
 
         Ok(true)
     })
@@ -1610,6 +3790,9 @@ fn create_evm_vka(
 /// abi_path: str
 ///     The path to create the ABI for the solidity verifier
 ///
+/// instance_bindings: str
+///     The path to an instance_bindings.json file, binding public instances to storage slots or view calls whose values they must equal
+///
 /// Returns
 /// -------
 /// bool
@@ -1620,6 +3803,7 @@ fn create_evm_vka(
     sol_code_path=PathBuf::from(DEFAULT_SOL_CODE_DA),
     abi_path=PathBuf::from(DEFAULT_VERIFIER_DA_ABI),
     witness_path=None,
+    instance_bindings=None,
 ))]
 fn create_evm_data_attestation(
     py: Python,
@@ -1628,6 +3812,7 @@ fn create_evm_data_attestation(
     sol_code_path: PathBuf,
     abi_path: PathBuf,
     witness_path: Option<PathBuf>,
+    instance_bindings: Option<PathBuf>,
 ) -> PyResult<Bound<'_, PyAny>> {
     pyo3_asyncio::tokio::future_into_py(py, async move {
         crate::execute::create_evm_data_attestation(
@@ -1636,12 +3821,47 @@ fn create_evm_data_attestation(
             abi_path,
             input_data,
             witness_path,
+            instance_bindings,
         )
         .await
-        .map_err(|e| {
-            let err_str = format!("Failed to run create_evm_data_attestation: {}", e);
-            PyRuntimeError::new_err(err_str)
-        })?;
+        .map_err(|e| ezkl_error("Failed to run create_evm_data_attestation", e))?;
+
+        Ok(true)
+    })
+}
+
+/// Creates an EVM contract verifying the proofs of a circuit with packed instances, re-deriving the digests of the packed tensors from their values in calldata, you will need solc installed in your environment to run this
+///
+/// Arguments
+/// ---------
+/// settings_path: str
+///     The path to the settings file, generated with a `pack_public_threshold`
+///
+/// sol_code_path: str
+///     The path to the create the solidity contract
+///
+/// abi_path: str
+///     The path to create the ABI for the solidity contract
+///
+/// Returns
+/// -------
+/// bool
+///
+#[pyfunction(signature = (
+    settings_path=PathBuf::from(DEFAULT_SETTINGS),
+    sol_code_path=PathBuf::from(DEFAULT_SOL_CODE_PACKED),
+    abi_path=PathBuf::from(DEFAULT_VERIFIER_PACKED_ABI),
+))]
+fn create_evm_packed(
+    py: Python,
+    settings_path: PathBuf,
+    sol_code_path: PathBuf,
+    abi_path: PathBuf,
+) -> PyResult<Bound<'_, PyAny>> {
+    pyo3_asyncio::tokio::future_into_py(py, async move {
+        crate::execute::create_evm_packed(settings_path, sol_code_path, abi_path)
+            .await
+            .map_err(|e| ezkl_error("Failed to run create_evm_packed", e))?;
 
         Ok(true)
     })
@@ -1700,10 +3920,7 @@ fn setup_test_evm_witness(
             output_source.into(),
         )
         .await
-        .map_err(|e| {
-            let err_str = format!("Failed to run setup_test_evm_witness: {}", e);
-            PyRuntimeError::new_err(err_str)
-        })?;
+        .map_err(|e| ezkl_error("Failed to run setup_test_evm_witness", e))?;
 
         Ok(true)
     })
@@ -1717,16 +3934,22 @@ fn setup_test_evm_witness(
     contract_type=ContractType::default(),
     optimizer_runs=DEFAULT_OPTIMIZER_RUNS.parse().unwrap(),
     private_key=None,
+    registry=None,
+    settings_path=PathBuf::from(DEFAULT_SETTINGS),
 ))]
-fn deploy_evm(
-    py: Python,
+fn deploy_evm<'a>(
+    py: Python<'a>,
     addr_path: PathBuf,
     sol_code_path: PathBuf,
     rpc_url: Option<String>,
     contract_type: ContractType,
     optimizer_runs: usize,
     private_key: Option<String>,
-) -> PyResult<Bound<'_, PyAny>> {
+    registry: Option<&'a str>,
+    settings_path: PathBuf,
+) -> PyResult<Bound<'a, PyAny>> {
+    let registry = registry.map(H160Flag::from);
+
     pyo3_asyncio::tokio::future_into_py(py, async move {
         crate::execute::deploy_evm(
             sol_code_path,
@@ -1735,17 +3958,62 @@ fn deploy_evm(
             optimizer_runs,
             private_key,
             contract_type,
+            registry,
+            settings_path,
         )
         .await
-        .map_err(|e| {
-            let err_str = format!("Failed to run deploy_evm: {}", e);
-            PyRuntimeError::new_err(err_str)
-        })?;
+        .map_err(|e| ezkl_error("Failed to run deploy_evm", e))?;
+
+        Ok(true)
+    })
+}
+
+/// deploys a registry mapping the fingerprints of circuit settings to their verifiers
+#[pyfunction(signature = (
+    addr_path,
+    rpc_url=None,
+    optimizer_runs=DEFAULT_OPTIMIZER_RUNS.parse().unwrap(),
+    private_key=None,
+))]
+fn deploy_evm_registry(
+    py: Python,
+    addr_path: PathBuf,
+    rpc_url: Option<String>,
+    optimizer_runs: usize,
+    private_key: Option<String>,
+) -> PyResult<Bound<'_, PyAny>> {
+    pyo3_asyncio::tokio::future_into_py(py, async move {
+        crate::execute::deploy_evm_registry(rpc_url, addr_path, optimizer_runs, private_key)
+            .await
+            .map_err(|e| ezkl_error("Failed to run deploy_evm_registry", e))?;
 
         Ok(true)
     })
 }
 
+/// resolves the address of the verifier registered in a registry for the circuit settings
+#[pyfunction(signature = (
+    registry,
+    settings_path=PathBuf::from(DEFAULT_SETTINGS),
+    rpc_url=None,
+))]
+fn resolve_evm_verifier<'a>(
+    py: Python<'a>,
+    registry: &'a str,
+    settings_path: PathBuf,
+    rpc_url: Option<String>,
+) -> PyResult<Bound<'a, PyAny>> {
+    let registry = H160Flag::from(registry);
+
+    pyo3_asyncio::tokio::future_into_py(py, async move {
+        let verifier = crate::execute::resolve_evm_verifier(registry, settings_path, rpc_url, None)
+            .await
+            .map_err(|e| ezkl_error("Failed to run resolve_evm_verifier", e))?;
+
+        Ok(verifier)
+    })
+}
+
 /// deploys the solidity da verifier
 #[pyfunction(signature = (
     addr_path,
@@ -1777,14 +4045,57 @@ fn deploy_da_evm(
             private_key,
         )
         .await
-        .map_err(|e| {
-            let err_str = format!("Failed to run deploy_da_evm: {}", e);
-            PyRuntimeError::new_err(err_str)
-        })?;
+        .map_err(|e| ezkl_error("Failed to run deploy_da_evm", e))?;
 
         Ok(true)
     })
 }
+
+/// snapshots the account calls of a deployed da verifier at the current block and pins the on-chain sources of the data to it
+///
+/// Arguments
+/// ---------
+/// addr_da: str
+///     The data attestation contract's address as a hex string
+///
+/// input_data: str
+///     The path to the .json data file with the on-chain sources to pin, it is updated in place
+///
+/// rpc_url: str
+///     RPC URL for an Ethereum node, if None will use Anvil but WON'T persist state
+///
+/// private_key: str
+///     The private key of the admin account of the contract
+/// Returns
+/// -------
+/// int
+///     The pinned block number
+///
+#[pyfunction(signature = (
+    addr_da,
+    input_data=PathBuf::from(DEFAULT_DATA),
+    rpc_url=None,
+    private_key=None
+))]
+fn pin_evm_da<'a>(
+    py: Python<'a>,
+    addr_da: &'a str,
+    input_data: PathBuf,
+    rpc_url: Option<String>,
+    private_key: Option<String>,
+) -> PyResult<Bound<'a, PyAny>> {
+    let addr_da = H160Flag::from(addr_da);
+
+    pyo3_asyncio::tokio::future_into_py(py, async move {
+        let block = crate::execute::pin_evm_da(addr_da, input_data, rpc_url, private_key)
+            .await
+            .map_err(|e| ezkl_error("Failed to run pin_evm_da", e))?;
+
+        block
+            .parse::<u64>()
+            .map_err(|e| ezkl_error("Failed to run pin_evm_da", e))
+    })
+}
 /// verifies an evm compatible proof, you will need solc installed in your environment to run this
 ///
 /// Arguments
@@ -1803,6 +4114,9 @@ fn deploy_da_evm(
 ///
 /// addr_vk: str
 ///     The addess of the separate VK contract (if the verifier key is rendered as a separate contract)
+///
+/// data_path: str
+///     The path to the input json file, required when addr_da is a StorageAttestation contract
 /// Returns
 /// -------
 /// bool
@@ -1813,6 +4127,7 @@ fn deploy_da_evm(
     rpc_url=None,
     addr_da = None,
     addr_vk = None,
+    data_path = None,
 ))]
 fn verify_evm<'a>(
     py: Python<'a>,
@@ -1821,6 +4136,7 @@ fn verify_evm<'a>(
     rpc_url: Option<String>,
     addr_da: Option<&'a str>,
     addr_vk: Option<&'a str>,
+    data_path: Option<PathBuf>,
 ) -> PyResult<Bound<'a, PyAny>> {
     let addr_verifier = H160Flag::from(addr_verifier);
     let addr_da = if let Some(addr_da) = addr_da {
@@ -1837,17 +4153,126 @@ fn verify_evm<'a>(
     };
 
     pyo3_asyncio::tokio::future_into_py(py, async move {
-        crate::execute::verify_evm(proof_path, addr_verifier, rpc_url, addr_da, addr_vk)
+        crate::execute::verify_evm(
+            proof_path,
+            addr_verifier,
+            rpc_url,
+            addr_da,
+            addr_vk,
+            data_path,
+        )
+        .await
+        .map_err(|e| ezkl_error("Failed to run verify_evm", e))?;
+
+        Ok(true)
+    })
+}
+
+/// verifies a batch of evm compatible proofs in one call, the verifier must have been created with batch=True
+///
+/// Arguments
+/// ---------
+/// addr_verifier: str
+///     The verifier contract's address as a hex string
+///
+/// proof_paths: list[str]
+///     The paths to the proof files (generated using the prove command), all for the same circuit
+///
+/// rpc_url: str
+///     RPC URL for an Ethereum node, if None will use Anvil but WON'T persist state
+///
+/// addr_vk: str
+///     The addess of the separate VK contract (if the verifier key is rendered as a separate contract)
+/// Returns
+/// -------
+/// bool
+///
+#[pyfunction(signature = (
+    addr_verifier,
+    proof_paths,
+    rpc_url=None,
+    addr_vk = None,
+))]
+fn verify_evm_batch<'a>(
+    py: Python<'a>,
+    addr_verifier: &'a str,
+    proof_paths: Vec<PathBuf>,
+    rpc_url: Option<String>,
+    addr_vk: Option<&'a str>,
+) -> PyResult<Bound<'a, PyAny>> {
+    let addr_verifier = H160Flag::from(addr_verifier);
+    let addr_vk = addr_vk.map(H160Flag::from);
+
+    pyo3_asyncio::tokio::future_into_py(py, async move {
+        crate::execute::verify_evm_batch(proof_paths, addr_verifier, rpc_url, addr_vk)
             .await
-            .map_err(|e| {
-                let err_str = format!("Failed to run verify_evm: {}", e);
-                PyRuntimeError::new_err(err_str)
-            })?;
+            .map_err(|e| ezkl_error("Failed to run verify_evm_batch", e))?;
 
         Ok(true)
     })
 }
 
+/// Deploys the verifier to a local evm and reports the gas needed to verify a proof, you will need solc installed in your environment to run this
+///
+/// Arguments
+/// ---------
+/// proof_path: str
+///     The path to the proof file (generated using the prove command)
+///
+/// sol_code_path: str
+///     The path to the Solidity verifier (generated using the create_evm_verifier command)
+///
+/// vka_path: str
+///     The path to the Solidity vk artifact, required if the verifier is reusable
+///
+/// backend: str
+///     The evm to run against, either `revm` (in-process) or `anvil`
+///
+/// rpc_url: str
+///     RPC URL for an Ethereum node (anvil backend only), if None will spin up a local Anvil
+///
+/// optimizer_runs: int
+///     The number of runs set to the SOLC optimizer
+///
+/// Returns
+/// -------
+/// str
+///     json with the gas used, calldata size and its proof/instance breakdown, and whether the proof fits the block gas target and limit
+///
+#[pyfunction(signature = (
+    proof_path=PathBuf::from(DEFAULT_PROOF),
+    sol_code_path=PathBuf::from(DEFAULT_SOL_CODE),
+    vka_path=None,
+    backend=EvmBackend::default(),
+    rpc_url=None,
+    optimizer_runs=DEFAULT_OPTIMIZER_RUNS.parse().unwrap(),
+))]
+fn estimate_gas(
+    py: Python,
+    proof_path: PathBuf,
+    sol_code_path: PathBuf,
+    vka_path: Option<PathBuf>,
+    backend: EvmBackend,
+    rpc_url: Option<String>,
+    optimizer_runs: usize,
+) -> PyResult<Bound<'_, PyAny>> {
+    pyo3_asyncio::tokio::future_into_py(py, async move {
+        let report = crate::execute::estimate_gas(
+            proof_path,
+            sol_code_path,
+            vka_path,
+            backend,
+            rpc_url,
+            optimizer_runs,
+            None,
+        )
+        .await
+        .map_err(|e| ezkl_error("Failed to run estimate_gas", e))?;
+
+        Ok(report)
+    })
+}
+
 /// Creates an evm compatible aggregate verifier, you will need solc installed in your environment to run this
 ///
 /// Arguments
@@ -1907,10 +4332,7 @@ fn create_evm_verifier_aggr(
             reusable,
         )
         .await
-        .map_err(|e| {
-            let err_str = format!("Failed to run create_evm_verifier_aggr: {}", e);
-            PyRuntimeError::new_err(err_str)
-        })?;
+        .map_err(|e| ezkl_error("Failed to run create_evm_verifier_aggr", e))?;
 
         Ok(true)
     })
@@ -1925,41 +4347,79 @@ fn ezkl(_py: Python<'_>, m: &PyModule) -> PyResult<()> {
     m.add_class::<PyG1>()?;
     m.add_class::<PyTestDataSource>()?;
     m.add_class::<PyCommitments>()?;
+    m.add_class::<PyProverSession>()?;
+    let exceptions = errors::exceptions(_py)?;
+    for name in errors::EXCEPTION_NAMES {
+        m.add(name, exceptions.getattr(name)?)?;
+    }
     m.add("__version__", env!("CARGO_PKG_VERSION"))?;
     m.add_function(wrap_pyfunction!(felt_to_big_endian, m)?)?;
     m.add_function(wrap_pyfunction!(felt_to_int, m)?)?;
     m.add_function(wrap_pyfunction!(felt_to_float, m)?)?;
     m.add_function(wrap_pyfunction!(kzg_commit, m)?)?;
+    m.add_function(wrap_pyfunction!(kzg_verify_opening, m)?)?;
     m.add_function(wrap_pyfunction!(ipa_commit, m)?)?;
     m.add_function(wrap_pyfunction!(swap_proof_commitments, m)?)?;
+    m.add_function(wrap_pyfunction!(commit_param_groups, m)?)?;
     m.add_function(wrap_pyfunction!(poseidon_hash, m)?)?;
+    m.add_function(wrap_pyfunction!(elgamal_gen_keys, m)?)?;
+    m.add_function(wrap_pyfunction!(elgamal_decrypt, m)?)?;
     m.add_function(wrap_pyfunction!(float_to_felt, m)?)?;
     m.add_function(wrap_pyfunction!(buffer_to_felts, m)?)?;
     m.add_function(wrap_pyfunction!(gen_vk_from_pk_aggr, m)?)?;
     m.add_function(wrap_pyfunction!(gen_vk_from_pk_single, m)?)?;
     m.add_function(wrap_pyfunction!(table, m)?)?;
+    m.add_function(wrap_pyfunction!(redact_witness, m)?)?;
+    m.add_function(wrap_pyfunction!(unredact_witness, m)?)?;
     m.add_function(wrap_pyfunction!(mock, m)?)?;
+    m.add_function(wrap_pyfunction!(validate_witness, m)?)?;
     m.add_function(wrap_pyfunction!(setup, m)?)?;
+    m.add_function(wrap_pyfunction!(setup_batch_family, m)?)?;
     m.add_function(wrap_pyfunction!(prove, m)?)?;
+    m.add_function(wrap_pyfunction!(prove_async, m)?)?;
+    m.add_function(wrap_pyfunction!(prove_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(prove_batch_family, m)?)?;
     m.add_function(wrap_pyfunction!(verify, m)?)?;
+    m.add_function(wrap_pyfunction!(verify_async, m)?)?;
     m.add_function(wrap_pyfunction!(gen_srs, m)?)?;
     m.add_function(wrap_pyfunction!(get_srs, m)?)?;
     m.add_function(wrap_pyfunction!(gen_witness, m)?)?;
+    m.add_function(wrap_pyfunction!(gen_witness_async, m)?)?;
+    m.add_function(wrap_pyfunction!(gen_witness_arrays, m)?)?;
+    m.add_function(wrap_pyfunction!(forward_arrays, m)?)?;
+    m.add_function(wrap_pyfunction!(gen_witness_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(merkle_tree, m)?)?;
     m.add_function(wrap_pyfunction!(gen_settings, m)?)?;
+    m.add_function(wrap_pyfunction!(estimate, m)?)?;
+    m.add_function(wrap_pyfunction!(circuit_profile, m)?)?;
+    m.add_function(wrap_pyfunction!(estimate_layout, m)?)?;
+    m.add_function(wrap_pyfunction!(inspect, m)?)?;
+    m.add_function(wrap_pyfunction!(decode_instances, m)?)?;
     m.add_function(wrap_pyfunction!(calibrate_settings, m)?)?;
+    m.add_function(wrap_pyfunction!(calibrate_settings_arrays, m)?)?;
+    m.add_function(wrap_pyfunction!(run_pipeline, m)?)?;
     m.add_function(wrap_pyfunction!(aggregate, m)?)?;
+    m.add_function(wrap_pyfunction!(link_proofs, m)?)?;
+    m.add_function(wrap_pyfunction!(check_session, m)?)?;
     m.add_function(wrap_pyfunction!(mock_aggregate, m)?)?;
     m.add_function(wrap_pyfunction!(setup_aggregate, m)?)?;
     m.add_function(wrap_pyfunction!(compile_circuit, m)?)?;
+    m.add_function(wrap_pyfunction!(compile_batch_family, m)?)?;
     m.add_function(wrap_pyfunction!(verify_aggr, m)?)?;
     m.add_function(wrap_pyfunction!(create_evm_verifier, m)?)?;
     m.add_function(wrap_pyfunction!(create_evm_vka, m)?)?;
     m.add_function(wrap_pyfunction!(deploy_evm, m)?)?;
+    m.add_function(wrap_pyfunction!(deploy_evm_registry, m)?)?;
+    m.add_function(wrap_pyfunction!(resolve_evm_verifier, m)?)?;
     m.add_function(wrap_pyfunction!(deploy_da_evm, m)?)?;
+    m.add_function(wrap_pyfunction!(pin_evm_da, m)?)?;
     m.add_function(wrap_pyfunction!(verify_evm, m)?)?;
+    m.add_function(wrap_pyfunction!(verify_evm_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(estimate_gas, m)?)?;
     m.add_function(wrap_pyfunction!(setup_test_evm_witness, m)?)?;
     m.add_function(wrap_pyfunction!(create_evm_verifier_aggr, m)?)?;
     m.add_function(wrap_pyfunction!(create_evm_data_attestation, m)?)?;
+    m.add_function(wrap_pyfunction!(create_evm_packed, m)?)?;
     m.add_function(wrap_pyfunction!(encode_evm_calldata, m)?)?;
     Ok(())
 }
@@ -0,0 +1,394 @@
+use crate::circuit::CircuitError;
+use crate::execute::ExecutionError;
+use crate::graph::errors::GraphError;
+use crate::pfsys::encryption::EncryptionError;
+use crate::pfsys::errors::PfsysError;
+use crate::EZKLError;
+use pyo3::prelude::*;
+use pyo3::sync::GILOnceCell;
+use pyo3::types::PyDict;
+
+/// The exception classes raised by the bindings. Every class derives from `EzklError`, itself a `RuntimeError` as all
+/// errors were raised before the categories, and the input and artifact errors also derive from `ValueError` and
+/// `OSError` they were raised as.
+const EXCEPTIONS: &str = r#"
+class EzklError(RuntimeError):
+    """Base class of the errors raised by ezkl. `kind` is the component the error stems from and `details` holds
+    its structured fields, each also set as an attribute"""
+
+    def __init__(self, message, kind=None, **details):
+        super().__init__(message)
+        self.kind = kind
+        self.details = details
+        for name, value in details.items():
+            setattr(self, name, value)
+
+
+class InputError(EzklError, ValueError):
+    """The inputs don't fit the model or the settings: wrong shapes, types or values"""
+
+
+class SettingsError(EzklError):
+    """The settings or run args are invalid or inconsistent with the model"""
+
+
+class UnsupportedModelError(EzklError):
+    """The model has ops or datatypes ezkl can't lay out in a circuit"""
+
+
+class CircuitOverflowError(EzklError):
+    """The circuit is too small for the model: it runs out of rows, or values exceed the lookup and range check
+    ranges or the integer representation"""
+
+
+class ProofVerificationError(EzklError):
+    """A proof or a witness failed to verify"""
+
+
+class ArtifactError(EzklError, OSError):
+    """An artifact (model, settings, keys, srs, proof) couldn't be read or written, or is malformed"""
+
+
+class CancellationError(EzklError):
+    """The witness generation or the proof was cancelled, or ran out of its timeout, before it completed"""
+"#;
+
+/// The names of the exception classes, as exported by the module
+pub(crate) const EXCEPTION_NAMES: [&str; 8] = [
+    "EzklError",
+    "InputError",
+    "SettingsError",
+    "UnsupportedModelError",
+    "CircuitOverflowError",
+    "ProofVerificationError",
+    "ArtifactError",
+    "CancellationError",
+];
+
+static EXCEPTIONS_MODULE: GILOnceCell<Py<PyModule>> = GILOnceCell::new();
+
+/// The module defining the exception classes, created once
+pub(crate) fn exceptions(py: Python<'_>) -> PyResult<&Bound<'_, PyModule>> {
+    EXCEPTIONS_MODULE
+        .get_or_try_init(py, || {
+            PyModule::from_code_bound(py, EXCEPTIONS, "ezkl_exceptions.py", "ezkl.exceptions")
+                .map(Bound::unbind)
+        })
+        .map(|m| m.bind(py))
+}
+
+/// The category of an error, naming the exception class it is raised as
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Category {
+    Internal,
+    Input,
+    Settings,
+    UnsupportedModel,
+    CircuitOverflow,
+    ProofVerification,
+    Artifact,
+    Cancelled,
+}
+
+impl Category {
+    fn class_name(&self) -> &'static str {
+        match self {
+            Category::Internal => "EzklError",
+            Category::Input => "InputError",
+            Category::Settings => "SettingsError",
+            Category::UnsupportedModel => "UnsupportedModelError",
+            Category::CircuitOverflow => "CircuitOverflowError",
+            Category::ProofVerification => "ProofVerificationError",
+            Category::Artifact => "ArtifactError",
+            Category::Cancelled => "CancellationError",
+        }
+    }
+}
+
+/// The category and structured fields of an error
+struct Classified {
+    category: Category,
+    details: Vec<(&'static str, PyObject)>,
+}
+
+impl Classified {
+    fn new(category: Category) -> Self {
+        Classified {
+            category,
+            details: vec![],
+        }
+    }
+
+    fn with(mut self, py: Python<'_>, name: &'static str, value: impl ToPyObject) -> Self {
+        self.details.push((name, value.to_object(py)));
+        self
+    }
+}
+
+fn classify_circuit(py: Python<'_>, e: &CircuitError) -> Classified {
+    match e {
+        CircuitError::RowOverflow {
+            node,
+            op,
+            row,
+            max,
+            largest,
+        } => Classified::new(Category::CircuitOverflow)
+            .with(py, "node", node)
+            .with(py, "op", op)
+            .with(py, "row", row)
+            .with(py, "max_rows", max)
+            .with(py, "largest_ops", largest),
+        CircuitError::TableOOR(value, min, max) => Classified::new(Category::CircuitOverflow)
+            .with(py, "value", value)
+            .with(py, "range", (*min, *max)),
+        CircuitError::TightLookupOOR {
+            node,
+            op,
+            value,
+            min,
+            max,
+        } => Classified::new(Category::CircuitOverflow)
+            .with(py, "node", node)
+            .with(py, "op", op)
+            .with(py, "value", value)
+            .with(py, "range", (*min, *max)),
+        CircuitError::IntegerRepOverflow(value, op) => Classified::new(Category::CircuitOverflow)
+            .with(py, "value", value)
+            .with(py, "op", op),
+        CircuitError::InvalidMinMaxRange(min, max) => {
+            Classified::new(Category::Settings).with(py, "range", (*min, *max))
+        }
+        CircuitError::LookupNotConfigured(op) | CircuitError::RangeCheckNotConfigured(op) => {
+            Classified::new(Category::Settings).with(py, "op", op)
+        }
+        CircuitError::UnsupportedOp | CircuitError::InvalidEinsum => {
+            Classified::new(Category::UnsupportedModel)
+        }
+        CircuitError::DimMismatch(op) => Classified::new(Category::Input).with(py, "op", op),
+        CircuitError::IoError(_) => Classified::new(Category::Artifact),
+        _ => Classified::new(Category::Internal),
+    }
+}
+
+fn classify_graph(py: Python<'_>, e: &GraphError) -> Classified {
+    match e {
+        GraphError::InvalidInputShape {
+            input,
+            expected,
+            actual,
+        } => Classified::new(Category::Input)
+            .with(py, "input", input)
+            .with(py, "expected_shape", expected)
+            .with(py, "actual_shape", actual),
+        GraphError::InvalidInputCount { expected, actual } => Classified::new(Category::Input)
+            .with(py, "expected", expected)
+            .with(py, "actual", actual),
+        GraphError::InvalidDims(node, reason) => Classified::new(Category::Input)
+            .with(py, "node", node)
+            .with(py, "reason", reason),
+        GraphError::OutputOutOfBounds(output, reason) => Classified::new(Category::Input)
+            .with(py, "output", output)
+            .with(py, "reason", reason),
+        GraphError::InvalidLookupInputs
+        | GraphError::InvalidInputTypes
+        | GraphError::MissingDataSource
+        | GraphError::OnChainDataSource
+        | GraphError::MissingInput(_)
+        | GraphError::InvalidNpy(_)
+        | GraphError::InvalidCsv(_)
+        | GraphError::InvalidImage(_)
+        | GraphError::InvalidFieldInput(_)
+        | GraphError::InvalidMerkle(_)
+        | GraphError::InvalidSignature(_)
+        | GraphError::InvalidNullifier(_)
+        | GraphError::InvalidEncryption(_)
+        | GraphError::InvalidBlinding(_)
+        | GraphError::InvalidRedaction(_)
+        | GraphError::InconsistentWitness(_) => Classified::new(Category::Input),
+        GraphError::Visibility
+        | GraphError::ParamsPublicVisibility
+        | GraphError::MissingBatchSize
+        | GraphError::InvalidRunArgs(_)
+        | GraphError::InvalidNormalization(_) => Classified::new(Category::Settings),
+        GraphError::WrongMethod(node, op)
+        | GraphError::OpMismatch(node, op)
+        | GraphError::UnsupportedDataType(node, op) => Classified::new(Category::UnsupportedModel)
+            .with(py, "node", node)
+            .with(py, "op", op),
+        GraphError::MissingParams(_)
+        | GraphError::MisformedParams(_)
+        | GraphError::NonConstantDiv
+        | GraphError::NonConstantPower
+        | GraphError::NonConstantRange
+        | GraphError::NonConstantTrilu
+        | GraphError::TractError(_) => Classified::new(Category::UnsupportedModel),
+        GraphError::IntegerRepOverflow(value, op, node) => {
+            Classified::new(Category::CircuitOverflow)
+                .with(py, "node", node)
+                .with(py, "op", op)
+                .with(py, "value", value)
+        }
+        GraphError::ExtendedKTooLarge(logrows) => {
+            Classified::new(Category::CircuitOverflow).with(py, "logrows", logrows)
+        }
+        GraphError::LookupRangeTooLarge(range) | GraphError::RangeCheckTooLarge(range) => {
+            Classified::new(Category::CircuitOverflow).with(py, "range_size", range)
+        }
+        GraphError::PackingExponent => Classified::new(Category::CircuitOverflow),
+        GraphError::ReadWriteFileError(path, _) => {
+            Classified::new(Category::Artifact).with(py, "path", path)
+        }
+        GraphError::ModelSerialize(_) | GraphError::JsonError(_) => {
+            Classified::new(Category::Artifact)
+        }
+        GraphError::InvalidJson { path, reason } => Classified::new(Category::Artifact)
+            .with(py, "json_path", path)
+            .with(py, "reason", reason),
+        GraphError::UnlinkableCircuits(_) => Classified::new(Category::Settings),
+        GraphError::BrokenLink {
+            proof,
+            output,
+            input,
+        } => Classified::new(Category::ProofVerification)
+            .with(py, "proof", proof)
+            .with(py, "output", output)
+            .with(py, "input", input),
+        GraphError::InvalidCommitmentGroups(_) => Classified::new(Category::Settings),
+        GraphError::CommitmentGroupMismatch { group } => {
+            Classified::new(Category::ProofVerification).with(py, "group", group)
+        }
+        GraphError::InvalidLimbPacking(_) => Classified::new(Category::Input),
+        GraphError::InvalidWitnessSidecar(_) => Classified::new(Category::Input),
+        GraphError::InvalidSession(_) => Classified::new(Category::Input),
+        GraphError::BrokenSessionChain { proof } => {
+            Classified::new(Category::ProofVerification).with(py, "proof", proof)
+        }
+        GraphError::CircuitError(e) => classify_circuit(py, e),
+        GraphError::Encryption(e) => classify_encryption(py, e),
+        _ => Classified::new(Category::Internal),
+    }
+}
+
+fn classify_encryption(py: Python<'_>, e: &EncryptionError) -> Classified {
+    match e {
+        EncryptionError::WrongIdentity { path, identity } => Classified::new(Category::Artifact)
+            .with(py, "path", path)
+            .with(py, "identity", identity),
+        EncryptionError::MissingIdentity(path)
+        | EncryptionError::Decrypt(path, _)
+        | EncryptionError::Io(path, _) => {
+            Classified::new(Category::Artifact).with(py, "path", path)
+        }
+        EncryptionError::InvalidRecipient(..) | EncryptionError::InvalidIdentity(..) => {
+            Classified::new(Category::Input)
+        }
+    }
+}
+
+fn classify_pfsys(py: Python<'_>, e: &PfsysError, verifying: bool) -> Classified {
+    match e {
+        PfsysError::LayoutError(e) => classify_graph(py, e),
+        PfsysError::TranscriptMismatch(actual, expected) => {
+            Classified::new(Category::ProofVerification)
+                .with(py, "transcript", actual)
+                .with(py, "expected_transcript", expected)
+        }
+        PfsysError::Halo2Error(_) if verifying => Classified::new(Category::ProofVerification),
+        PfsysError::SaveProof(_)
+        | PfsysError::LoadProof(_)
+        | PfsysError::LoadVk(_)
+        | PfsysError::LoadSrs(_)
+        | PfsysError::LoadSettings(_)
+        | PfsysError::LoadPk(_)
+        | PfsysError::MmapPk(_) => Classified::new(Category::Artifact),
+        PfsysError::InstanceCount { expected, actual } => Classified::new(Category::Input)
+            .with(py, "expected", expected)
+            .with(py, "actual", actual),
+        PfsysError::InvalidCommitmentScheme => Classified::new(Category::Settings),
+        PfsysError::Encryption(e) => classify_encryption(py, e),
+        _ => Classified::new(Category::Internal),
+    }
+}
+
+/// Classifies an error, `verifying` when it's the outcome of a verification, whose halo2 errors are the proof failing
+/// to verify
+fn classify(py: Python<'_>, e: &EZKLError, verifying: bool) -> Classified {
+    match e {
+        e if e.is_cancelled() => Classified::new(Category::Cancelled),
+        EZKLError::GraphError(e) => classify_graph(py, e),
+        EZKLError::CircuitError(e) => classify_circuit(py, e),
+        EZKLError::PfsysError(e) => classify_pfsys(py, e, verifying),
+        EZKLError::ExecutionError(ExecutionError::VerifyError(failures)) => {
+            Classified::new(Category::ProofVerification).with(
+                py,
+                "failures",
+                failures.iter().map(|f| f.to_string()).collect::<Vec<_>>(),
+            )
+        }
+        EZKLError::ExecutionError(ExecutionError::InvalidWitness(failures)) => {
+            Classified::new(Category::ProofVerification)
+                .with(
+                    py,
+                    "failures",
+                    failures.iter().map(|f| f.to_string()).collect::<Vec<_>>(),
+                )
+                .with(
+                    py,
+                    "nodes",
+                    failures.iter().map(|f| f.node).collect::<Vec<_>>(),
+                )
+        }
+        EZKLError::ExecutionError(ExecutionError::PipelineStageFailed { stage, error }) => {
+            classify(py, error, verifying).with(py, "stage", stage.to_string())
+        }
+        EZKLError::Halo2Error(_) if verifying => Classified::new(Category::ProofVerification),
+        EZKLError::IoError(_)
+        | EZKLError::JsonError(_)
+        | EZKLError::Utf8Error(_)
+        | EZKLError::SrsError(_) => Classified::new(Category::Artifact),
+        _ => Classified::new(Category::Internal),
+    }
+}
+
+fn raise(py: Python<'_>, message: String, kind: &str, classified: Classified) -> PyResult<PyErr> {
+    let class = exceptions(py)?.getattr(classified.category.class_name())?;
+    let kwargs = PyDict::new_bound(py);
+    kwargs.set_item("kind", kind)?;
+    for (name, value) in classified.details {
+        kwargs.set_item(name, value)?;
+    }
+    let exception = class.call((message,), Some(&kwargs))?;
+    Ok(PyErr::from_value_bound(exception))
+}
+
+fn convert(context: &str, e: EZKLError, verifying: bool) -> PyErr {
+    Python::with_gil(|py| {
+        let classified = classify(py, &e, verifying);
+        raise(py, format!("{}: {}", context, e), e.kind(), classified).unwrap_or_else(|err| err)
+    })
+}
+
+/// Raises an `InputError` for inputs rejected by the bindings themselves, with the given structured fields
+pub(crate) fn input_error(
+    py: Python<'_>,
+    message: String,
+    details: Vec<(&'static str, PyObject)>,
+) -> PyErr {
+    let classified = Classified {
+        category: Category::Input,
+        details,
+    };
+    raise(py, message, "input", classified).unwrap_or_else(|err| err)
+}
+
+/// Raises an error of the crate as the exception of its category, with `context` prefixing its message
+pub(crate) fn ezkl_error(context: &str, e: impl Into<EZKLError>) -> PyErr {
+    convert(context, e.into(), false)
+}
+
+/// Raises an error of a verification as the exception of its category, the halo2 errors of the verifier raising
+/// `ProofVerificationError`
+pub(crate) fn verify_error(context: &str, e: impl Into<EZKLError>) -> PyErr {
+    convert(context, e.into(), true)
+}
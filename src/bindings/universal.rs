@@ -14,6 +14,7 @@ use halo2_proofs::{
         },
         VerificationStrategy,
     },
+    transcript::{Blake2bRead, Challenge255},
 };
 use std::fmt::Display;
 use std::io::BufReader;
@@ -25,10 +26,11 @@ use crate::{
     pfsys::{
         create_proof_circuit,
         evm::aggregation_kzg::{AggregationCircuit, PoseidonTranscript},
+        progress::{self, ProvingPhase},
         verify_proof_circuit, TranscriptType,
     },
     tensor::TensorType,
-    CheckMode, Commitments, EZKLError as InnerEZKLError,
+    CheckMode, Commitments, EZKLError as InnerEZKLError, RunArgs,
 };
 
 use crate::graph::{GraphCircuit, GraphWitness};
@@ -93,15 +95,23 @@ pub(crate) fn encode_verifier_calldata(
 /// Generate witness from compiled circuit and input json
 #[cfg_attr(feature = "ios-bindings", uniffi::export)]
 pub(crate) fn gen_witness(compiled_circuit: Vec<u8>, input: Vec<u8>) -> Result<Vec<u8>, EZKLError> {
-    let mut circuit: crate::graph::GraphCircuit = bincode::deserialize(&compiled_circuit[..])
-        .map_err(|e| {
+    let circuit: crate::graph::GraphCircuit =
+        bincode::deserialize(&compiled_circuit[..]).map_err(|e| {
             EZKLError::InternalError(format!("Failed to deserialize compiled model: {}", e))
         })?;
     let input: crate::graph::input::GraphData = serde_json::from_slice(&input[..])
         .map_err(|e| EZKLError::InternalError(format!("Failed to deserialize input: {}", e)))?;
 
+    gen_witness_for(circuit, &input)
+}
+
+/// Generate a witness for a compiled circuit from already read input data
+pub(crate) fn gen_witness_for(
+    mut circuit: GraphCircuit,
+    input: &crate::graph::input::GraphData,
+) -> Result<Vec<u8>, EZKLError> {
     let mut input = circuit
-        .load_graph_input(&input)
+        .load_graph_input(input)
         .map_err(|e| EZKLError::InternalError(format!("{}", e)))?;
 
     let witness = circuit
@@ -227,6 +237,13 @@ pub(crate) fn verify(
                         PoseidonTranscript<NativeLoader, _>,
                     >(&proof, &params, &vk, strategy, orig_n)
                 }
+                TranscriptType::Blake => verify_proof_circuit::<
+                    VerifierSHPLONK<'_, Bn256>,
+                    KZGCommitmentScheme<Bn256>,
+                    KZGSingleStrategy<_>,
+                    _,
+                    Blake2bRead<_, _, Challenge255<_>>,
+                >(&proof, &params, &vk, strategy, orig_n),
             }
         }
         Commitments::IPA => {
@@ -249,6 +266,13 @@ pub(crate) fn verify(
                         PoseidonTranscript<NativeLoader, _>,
                     >(&proof, &params, &vk, strategy, orig_n)
                 }
+                TranscriptType::Blake => verify_proof_circuit::<
+                    VerifierIPA<_>,
+                    IPACommitmentScheme<G1Affine>,
+                    IPASingleStrategy<_>,
+                    _,
+                    Blake2bRead<_, _, Challenge255<_>>,
+                >(&proof, &params, &vk, strategy, orig_n),
             }
         }
     };
@@ -310,6 +334,13 @@ pub(crate) fn verify_aggr(
                         PoseidonTranscript<NativeLoader, _>,
                     >(&proof, &params, &vk, strategy, orig_n)
                 }
+                TranscriptType::Blake => verify_proof_circuit::<
+                    VerifierSHPLONK<'_, Bn256>,
+                    KZGCommitmentScheme<Bn256>,
+                    KZGSingleStrategy<_>,
+                    _,
+                    Blake2bRead<_, _, Challenge255<_>>,
+                >(&proof, &params, &vk, strategy, orig_n),
             }
         }
         Commitments::IPA => {
@@ -335,6 +366,13 @@ pub(crate) fn verify_aggr(
                         PoseidonTranscript<NativeLoader, _>,
                     >(&proof, &params, &vk, strategy, orig_n)
                 }
+                TranscriptType::Blake => verify_proof_circuit::<
+                    VerifierIPA<_>,
+                    IPACommitmentScheme<G1Affine>,
+                    IPASingleStrategy<_>,
+                    _,
+                    Blake2bRead<_, _, Challenge255<_>>,
+                >(&proof, &params, &vk, strategy, orig_n),
             }
         }
     };
@@ -344,6 +382,47 @@ pub(crate) fn verify_aggr(
         .map_err(|e| EZKLError::InternalError(format!("{}", e)))
 }
 
+/// An srs read for the commitment scheme it was generated for
+pub(crate) enum Srs {
+    /// An srs for KZG commitments
+    KZG(ParamsKZG<Bn256>),
+    /// An srs for IPA commitments
+    IPA(ParamsIPA<G1Affine>),
+}
+
+impl Srs {
+    /// Reads an srs for the given commitment scheme
+    pub(crate) fn read(srs: &[u8], commitment: Commitments) -> Result<Self, EZKLError> {
+        let mut reader = BufReader::new(srs);
+        let srs = match commitment {
+            Commitments::KZG => Srs::KZG(get_params(&mut reader)?),
+            Commitments::IPA => Srs::IPA(get_params(&mut reader)?),
+        };
+        Ok(srs)
+    }
+
+    fn commitment(&self) -> Commitments {
+        match self {
+            Srs::KZG(_) => Commitments::KZG,
+            Srs::IPA(_) => Commitments::IPA,
+        }
+    }
+}
+
+/// Reads a proving key for a circuit with the given settings
+pub(crate) fn read_pk(
+    pk: &[u8],
+    settings: GraphSettings,
+) -> Result<ProvingKey<G1Affine>, EZKLError> {
+    let mut reader = BufReader::new(pk);
+    ProvingKey::<G1Affine>::read::<_, GraphCircuit>(
+        &mut reader,
+        halo2_proofs::SerdeFormat::RawBytes,
+        settings,
+    )
+    .map_err(|e| EZKLError::InternalError(format!("Failed to deserialize proving key: {}", e)))
+}
+
 /// Prove in browser with compiled circuit, witness json, proving key, and srs
 #[cfg_attr(feature = "ios-bindings", uniffi::export)]
 pub(crate) fn prove(
@@ -351,24 +430,28 @@ pub(crate) fn prove(
     pk: Vec<u8>,
     compiled_circuit: Vec<u8>,
     srs: Vec<u8>,
+) -> Result<Vec<u8>, EZKLError> {
+    progress::report(ProvingPhase::Load, 0.0).map_err(InnerEZKLError::from)?;
+    let circuit = read_compiled_circuit(&compiled_circuit)?;
+    let pk = read_pk(&pk, circuit.settings().clone())?;
+    let srs = Srs::read(&srs, circuit.settings().run_args.commitment.into())?;
+
+    prove_with(&witness, circuit, &pk, &srs)
+}
+
+/// Prove with a compiled circuit and witness json, given an already read proving key and srs
+pub(crate) fn prove_with(
+    witness: &[u8],
+    mut circuit: GraphCircuit,
+    pk: &ProvingKey<G1Affine>,
+    srs: &Srs,
 ) -> Result<Vec<u8>, EZKLError> {
     #[cfg(feature = "det-prove")]
     log::set_max_level(log::LevelFilter::Debug);
     #[cfg(not(feature = "det-prove"))]
     log::set_max_level(log::LevelFilter::Info);
 
-    let mut circuit: GraphCircuit = bincode::deserialize(&compiled_circuit[..])
-        .map_err(|e| EZKLError::InternalError(format!("Failed to deserialize circuit: {}", e)))?;
-
-    let data: GraphWitness = serde_json::from_slice(&witness[..]).map_err(InnerEZKLError::from)?;
-
-    let mut reader = BufReader::new(&pk[..]);
-    let pk = ProvingKey::<G1Affine>::read::<_, GraphCircuit>(
-        &mut reader,
-        halo2_proofs::SerdeFormat::RawBytes,
-        circuit.settings().clone(),
-    )
-    .map_err(|e| EZKLError::InternalError(format!("Failed to deserialize proving key: {}", e)))?;
+    let data: GraphWitness = serde_json::from_slice(witness).map_err(InnerEZKLError::from)?;
 
     circuit
         .load_graph_witness(&data)
@@ -378,68 +461,91 @@ pub(crate) fn prove(
         .map_err(InnerEZKLError::from)?;
     let proof_split_commits: Option<crate::pfsys::ProofSplitCommit> = data.into();
 
-    let mut reader = BufReader::new(&srs[..]);
-    let commitment = circuit.settings().run_args.commitment.into();
+    let commitment: Commitments = circuit.settings().run_args.commitment.into();
+    if srs.commitment() != commitment {
+        return Err(EZKLError::InternalError(format!(
+            "the srs is for {} commitments but the circuit uses {}",
+            srs.commitment(),
+            commitment
+        )));
+    }
+    progress::report(ProvingPhase::Load, 1.0).map_err(InnerEZKLError::from)?;
+
+    let proof = match srs {
+        Srs::KZG(params) => create_proof_circuit::<
+            KZGCommitmentScheme<Bn256>,
+            _,
+            ProverSHPLONK<_>,
+            VerifierSHPLONK<_>,
+            KZGSingleStrategy<_>,
+            _,
+            EvmTranscript<_, _, _, _>,
+            EvmTranscript<_, _, _, _>,
+        >(
+            circuit,
+            vec![public_inputs],
+            params,
+            pk,
+            CheckMode::UNSAFE,
+            Commitments::KZG,
+            TranscriptType::EVM,
+            proof_split_commits,
+            None,
+        ),
+        Srs::IPA(params) => create_proof_circuit::<
+            IPACommitmentScheme<G1Affine>,
+            _,
+            ProverIPA<_>,
+            VerifierIPA<_>,
+            IPASingleStrategy<_>,
+            _,
+            EvmTranscript<_, _, _, _>,
+            EvmTranscript<_, _, _, _>,
+        >(
+            circuit,
+            vec![public_inputs],
+            params,
+            pk,
+            CheckMode::UNSAFE,
+            Commitments::IPA,
+            TranscriptType::EVM,
+            proof_split_commits,
+            None,
+        ),
+    }
+    .map_err(InnerEZKLError::from)?;
 
-    let proof = match commitment {
-        Commitments::KZG => {
-            let params: ParamsKZG<Bn256> =
-                halo2_proofs::poly::commitment::Params::<'_, G1Affine>::read(&mut reader).map_err(
-                    |e| EZKLError::InternalError(format!("Failed to deserialize srs: {}", e)),
-                )?;
+    let proof = serde_json::to_vec(&proof).map_err(InnerEZKLError::from)?;
+    // the proof is complete, a late cancellation doesn't discard it
+    let _ = progress::report(ProvingPhase::Done, 1.0);
+    Ok(proof)
+}
 
-            create_proof_circuit::<
-                KZGCommitmentScheme<Bn256>,
-                _,
-                ProverSHPLONK<_>,
-                VerifierSHPLONK<_>,
-                KZGSingleStrategy<_>,
-                _,
-                EvmTranscript<_, _, _, _>,
-                EvmTranscript<_, _, _, _>,
-            >(
-                circuit,
-                vec![public_inputs],
-                &params,
-                &pk,
-                CheckMode::UNSAFE,
-                Commitments::KZG,
-                TranscriptType::EVM,
-                proof_split_commits,
-                None,
-            )
-        }
-        Commitments::IPA => {
-            let params: ParamsIPA<_> =
-                halo2_proofs::poly::commitment::Params::<'_, G1Affine>::read(&mut reader).map_err(
-                    |e| EZKLError::InternalError(format!("Failed to deserialize srs: {}", e)),
-                )?;
+/// Reads a compiled circuit
+pub(crate) fn read_compiled_circuit(compiled_circuit: &[u8]) -> Result<GraphCircuit, EZKLError> {
+    bincode::deserialize(compiled_circuit)
+        .map_err(|e| EZKLError::InternalError(format!("Failed to deserialize circuit: {}", e)))
+}
 
-            create_proof_circuit::<
-                IPACommitmentScheme<G1Affine>,
-                _,
-                ProverIPA<_>,
-                VerifierIPA<_>,
-                IPASingleStrategy<_>,
-                _,
-                EvmTranscript<_, _, _, _>,
-                EvmTranscript<_, _, _, _>,
-            >(
-                circuit,
-                vec![public_inputs],
-                &params,
-                &pk,
-                CheckMode::UNSAFE,
-                Commitments::IPA,
-                TranscriptType::EVM,
-                proof_split_commits,
-                None,
-            )
-        }
-    }
-    .map_err(InnerEZKLError::from)?;
+/// Estimate the layout of a compiled circuit from a dry run of the layout of its model, see
+/// [crate::graph::Model::estimate_layout], with the run args of its settings unless `run_args` json is given
+#[cfg_attr(feature = "ios-bindings", uniffi::export)]
+pub(crate) fn estimate_layout(
+    compiled_circuit: Vec<u8>,
+    run_args: Option<Vec<u8>>,
+) -> Result<Vec<u8>, EZKLError> {
+    let circuit = read_compiled_circuit(&compiled_circuit)?;
+    let run_args: RunArgs = match run_args {
+        Some(run_args) => serde_json::from_slice(&run_args[..]).map_err(InnerEZKLError::from)?,
+        None => circuit.settings().run_args.clone(),
+    };
+    let estimate = circuit
+        .model()
+        .estimate_layout(&run_args)
+        .map_err(InnerEZKLError::from)?;
 
-    Ok(serde_json::to_vec(&proof).map_err(InnerEZKLError::from)?)
+    serde_json::to_vec(&estimate)
+        .map_err(|e| EZKLError::InternalError(format!("Failed to serialize estimate: {}", e)))
 }
 
 /// Validate the witness json
@@ -450,6 +556,23 @@ pub(crate) fn witness_validation(witness: Vec<u8>) -> Result<bool, EZKLError> {
     Ok(true)
 }
 
+/// Validate the witness json against the settings json of the circuit it was generated for, see
+/// [GraphWitness::check_consistency]
+#[cfg_attr(feature = "ios-bindings", uniffi::export)]
+pub(crate) fn witness_consistency_validation(
+    witness: Vec<u8>,
+    settings: Vec<u8>,
+) -> Result<bool, EZKLError> {
+    let witness: GraphWitness = serde_json::from_slice(&witness[..]).map_err(InnerEZKLError::from)?;
+    let settings: GraphSettings =
+        serde_json::from_slice(&settings[..]).map_err(InnerEZKLError::from)?;
+    witness
+        .check_consistency(&settings)
+        .map_err(InnerEZKLError::from)?;
+
+    Ok(true)
+}
+
 /// Validate the compiled circuit
 #[cfg_attr(feature = "ios-bindings", uniffi::export)]
 pub(crate) fn compiled_circuit_validation(compiled_circuit: Vec<u8>) -> Result<bool, EZKLError> {
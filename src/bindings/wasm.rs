@@ -1,7 +1,9 @@
+use crate::pfsys::progress::{with_observer, ProgressObserver, ProvingPhase};
 use crate::{
     circuit::modules::{
         polycommit::PolyCommitChip,
         poseidon::{
+            circomlib::CircomlibChip,
             spec::{PoseidonSpec, POSEIDON_RATE, POSEIDON_WIDTH},
             PoseidonChip,
         },
@@ -9,9 +11,12 @@ use crate::{
     },
     fieldutils::{felt_to_integer_rep, integer_rep_to_felt},
     graph::{
-        modules::POSEIDON_LEN_GRAPH, quantize_float, scale_to_multiplier, GraphCircuit,
-        GraphSettings,
+        input::{DataSource, FileSourceInner, GraphData},
+        modules::POSEIDON_LEN_GRAPH,
+        quantize_float, scale_to_multiplier, GraphCircuit, GraphSettings, GraphWitness,
+        PoseidonParams,
     },
+    Commitments,
 };
 use console_error_panic_hook;
 use halo2_proofs::{
@@ -22,17 +27,29 @@ use halo2curves::{
     bn256::{Bn256, Fr, G1Affine},
     ff::PrimeField,
 };
+use std::cell::RefCell;
+use std::rc::Rc;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen_console_logger::DEFAULT_LOGGER;
 
 use crate::bindings::universal::{
-    compiled_circuit_validation, encode_verifier_calldata, gen_pk, gen_vk, gen_witness,
-    input_validation, pk_validation, proof_validation, settings_validation, srs_validation,
-    verify_aggr, vk_validation, witness_validation, EZKLError as ExternalEZKLError,
+    compiled_circuit_validation, encode_verifier_calldata, estimate_layout, gen_pk, gen_vk,
+    gen_witness, gen_witness_for, input_validation, pk_validation, proof_validation, prove_with,
+    read_compiled_circuit, read_pk, settings_validation, srs_validation, verify_aggr,
+    vk_validation, witness_consistency_validation, witness_validation,
+    EZKLError as ExternalEZKLError, Srs,
 };
 #[cfg(feature = "web")]
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+#[cfg(feature = "web")]
 pub use wasm_bindgen_rayon::init_thread_pool;
 
+/// The number of threads proofs run on, once [initProverThreads] started them
+#[cfg(feature = "web")]
+static PROVER_THREADS: AtomicUsize = AtomicUsize::new(0);
+#[cfg(feature = "web")]
+static PROVER_THREADS_STARTED: AtomicBool = AtomicBool::new(false);
+
 impl From<ExternalEZKLError> for JsError {
     fn from(e: ExternalEZKLError) -> Self {
         JsError::new(&format!("{}", e))
@@ -51,6 +68,55 @@ pub fn init_panic_hook() {
     console_error_panic_hook::set_once();
 }
 
+/// Reads a property of the global object (the window or the worker scope)
+#[cfg(feature = "web")]
+fn global_property(path: &[&str]) -> JsValue {
+    path.iter()
+        .fold(js_sys::global().into(), |value: JsValue, key| {
+            js_sys::Reflect::get(&value, &JsValue::from_str(key)).unwrap_or(JsValue::UNDEFINED)
+        })
+}
+
+/// Starts the thread pool that witness generation, the MSMs and the FFTs of proofs run on, with `workers` web
+/// workers or `navigator.hardwareConcurrency` of them. Resolves to the number of threads proofs run on.
+///
+/// The workers share the memory of the module, which takes a `SharedArrayBuffer`, only available to pages served
+/// cross-origin isolated (with the `Cross-Origin-Opener-Policy: same-origin` and
+/// `Cross-Origin-Embedder-Policy: require-corp` headers). Without it no pool is started and proofs run on the calling
+/// thread, resolving to 1. Calling it again resolves to the threads of the pool already started.
+#[cfg(feature = "web")]
+#[wasm_bindgen]
+#[allow(non_snake_case)]
+pub async fn initProverThreads(workers: Option<usize>) -> Result<usize, JsValue> {
+    if PROVER_THREADS_STARTED.swap(true, Ordering::SeqCst) {
+        return Ok(PROVER_THREADS.load(Ordering::SeqCst).max(1));
+    }
+    let isolated = global_property(&["crossOriginIsolated"]).is_truthy()
+        && !global_property(&["SharedArrayBuffer"]).is_undefined();
+    if !isolated {
+        PROVER_THREADS_STARTED.store(false, Ordering::SeqCst);
+        log::warn!(
+            "SharedArrayBuffer is unavailable as the page isn't cross-origin isolated, proving on a single thread"
+        );
+        return Ok(1);
+    }
+
+    let workers = workers
+        .or_else(|| {
+            global_property(&["navigator", "hardwareConcurrency"])
+                .as_f64()
+                .map(|n| n as usize)
+        })
+        .unwrap_or(1)
+        .max(1);
+    if let Err(e) = wasm_bindgen_futures::JsFuture::from(init_thread_pool(workers)).await {
+        PROVER_THREADS_STARTED.store(false, Ordering::SeqCst);
+        return Err(e);
+    }
+    PROVER_THREADS.store(workers, Ordering::SeqCst);
+    Ok(workers)
+}
+
 /// Wrapper around the halo2 encode call data method
 #[wasm_bindgen]
 #[allow(non_snake_case)]
@@ -125,12 +191,14 @@ pub fn floatToFelt(
     )?))
 }
 
-/// Generate a kzg commitment.
+/// Generate a kzg commitment, as the PolyCommit visibility commits to an input, output or the params. The number of
+/// blinding factors of the circuit is read from the verifying key, or if no `vk` is given from the settings. Settings
+/// with `polycommit_blinding` fold a private blinder into their commitments, which this doesn't.
 #[wasm_bindgen]
 #[allow(non_snake_case)]
 pub fn kzgCommit(
     message: wasm_bindgen::Clamped<Vec<u8>>,
-    vk: wasm_bindgen::Clamped<Vec<u8>>,
+    vk: Option<Vec<u8>>,
     settings: wasm_bindgen::Clamped<Vec<u8>>,
     params_ser: wasm_bindgen::Clamped<Vec<u8>>,
 ) -> Result<wasm_bindgen::Clamped<Vec<u8>>, JsError> {
@@ -142,19 +210,27 @@ pub fn kzgCommit(
         halo2_proofs::poly::commitment::Params::<'_, G1Affine>::read(&mut reader)
             .map_err(|e| JsError::new(&format!("Failed to deserialize params: {}", e)))?;
 
-    let mut reader = std::io::BufReader::new(&vk[..]);
     let circuit_settings: GraphSettings = serde_json::from_slice(&settings[..])
         .map_err(|e| JsError::new(&format!("Failed to deserialize settings: {}", e)))?;
-    let vk = VerifyingKey::<G1Affine>::read::<_, GraphCircuit>(
-        &mut reader,
-        halo2_proofs::SerdeFormat::RawBytes,
-        circuit_settings,
-    )
-    .map_err(|e| JsError::new(&format!("Failed to deserialize vk: {}", e)))?;
+    let blinding_factors = match vk {
+        Some(vk) => {
+            let mut reader = std::io::BufReader::new(&vk[..]);
+            let vk = VerifyingKey::<G1Affine>::read::<_, GraphCircuit>(
+                &mut reader,
+                halo2_proofs::SerdeFormat::RawBytes,
+                circuit_settings,
+            )
+            .map_err(|e| JsError::new(&format!("Failed to deserialize vk: {}", e)))?;
+            vk.cs().blinding_factors()
+        }
+        None => circuit_settings.num_blinding_factors.ok_or_else(|| {
+            JsError::new("the settings don't record the number of blinding factors, pass the vk")
+        })?,
+    };
 
     let output = PolyCommitChip::commit::<KZGCommitmentScheme<Bn256>>(
         message,
-        (vk.cs().blinding_factors() + 1) as u32,
+        (blinding_factors + 1) as u32,
         &params,
     );
 
@@ -215,20 +291,30 @@ pub fn bufferToVecOfFelt(
     ))
 }
 
-/// Generate a poseidon hash in browser. Input message
+/// Generate a poseidon hash in browser. Input message, hashed with the poseidon parameters of the settings (`ezkl`,
+/// the default, or `circomlib`)
 #[wasm_bindgen]
 #[allow(non_snake_case)]
 pub fn poseidonHash(
     message: wasm_bindgen::Clamped<Vec<u8>>,
+    params: Option<String>,
 ) -> Result<wasm_bindgen::Clamped<Vec<u8>>, JsError> {
     let message: Vec<Fr> = serde_json::from_slice(&message[..])
         .map_err(|e| JsError::new(&format!("Failed to deserialize message: {}", e)))?;
-
-    let output =
-        PoseidonChip::<PoseidonSpec, POSEIDON_WIDTH, POSEIDON_RATE, POSEIDON_LEN_GRAPH>::run(
-            message.clone(),
-        )
-        .map_err(|e| JsError::new(&format!("{}", e)))?;
+    let params: PoseidonParams = match params {
+        Some(params) => params.parse().map_err(|e: String| JsError::new(&e))?,
+        None => PoseidonParams::Ezkl,
+    };
+
+    let output = match params {
+        PoseidonParams::Ezkl => {
+            PoseidonChip::<PoseidonSpec, POSEIDON_WIDTH, POSEIDON_RATE, POSEIDON_LEN_GRAPH>::run(
+                message,
+            )
+        }
+        PoseidonParams::Circomlib => CircomlibChip::run(message),
+    }
+    .map_err(|e| JsError::new(&format!("{}", e)))?;
 
     Ok(wasm_bindgen::Clamped(serde_json::to_vec(&output).map_err(
         |e| JsError::new(&format!("Failed to serialize poseidon hash output: {}", e)),
@@ -245,6 +331,133 @@ pub fn genWitness(
     gen_witness(compiled_circuit.0, input.0).map_err(JsError::from)
 }
 
+/// Reads the numbers of a typed array (a `Float32Array`, `Float64Array`, `Int32Array`, ...) as float file data
+fn typed_array_data(array: &JsValue, input: usize) -> Result<Vec<FileSourceInner>, JsError> {
+    macro_rules! read {
+        ($($ty:ty),*) => {
+            $(
+                if let Some(array) = array.dyn_ref::<$ty>() {
+                    return Ok(array
+                        .to_vec()
+                        .into_iter()
+                        .map(|x| FileSourceInner::Float(x as f64))
+                        .collect());
+                }
+            )*
+        };
+    }
+    read!(
+        js_sys::Float32Array,
+        js_sys::Float64Array,
+        js_sys::Int32Array,
+        js_sys::Int16Array,
+        js_sys::Int8Array,
+        js_sys::Uint32Array,
+        js_sys::Uint16Array,
+        js_sys::Uint8Array
+    );
+    Err(JsError::new(&format!(
+        "input {} is not a typed array",
+        input
+    )))
+}
+
+/// Generate a witness file from a compiled model and typed arrays, one per input of the model and in the order of the
+/// inputs, holding the flattened elements of the inputs. The arrays are quantized as the `input_data` of an input
+/// json is, without going through json. `shapes`, if given, are checked against the shapes of the inputs of the model.
+#[wasm_bindgen]
+#[allow(non_snake_case)]
+pub fn genWitnessFromArrays(
+    compiled_circuit: wasm_bindgen::Clamped<Vec<u8>>,
+    inputs: js_sys::Array,
+    shapes: Option<js_sys::Array>,
+) -> Result<Vec<u8>, JsError> {
+    let circuit = read_compiled_circuit(&compiled_circuit)?;
+    let model_shapes = circuit
+        .model()
+        .graph
+        .input_shapes()
+        .map_err(|e| JsError::new(&format!("{}", e)))?;
+    if inputs.length() as usize != model_shapes.len() {
+        return Err(JsError::new(&format!(
+            "the model has {} inputs but {} arrays were given",
+            model_shapes.len(),
+            inputs.length()
+        )));
+    }
+    if let Some(shapes) = shapes {
+        let shapes: Vec<Vec<usize>> = serde_wasm_bindgen::from_value(shapes.into())
+            .map_err(|e| JsError::new(&format!("Failed to read the shapes: {}", e)))?;
+        if shapes != model_shapes {
+            return Err(JsError::new(&format!(
+                "the inputs have shapes {:?} but the model takes {:?}",
+                shapes, model_shapes
+            )));
+        }
+    }
+
+    let mut data = vec![];
+    for (i, (input, shape)) in inputs.iter().zip(&model_shapes).enumerate() {
+        let input = typed_array_data(&input, i)?;
+        if input.len() != shape.iter().product::<usize>() {
+            return Err(JsError::new(&format!(
+                "input {} has {} elements but the model takes shape {:?}",
+                i,
+                input.len(),
+                shape
+            )));
+        }
+        data.push(input);
+    }
+
+    gen_witness_for(circuit, &GraphData::new(DataSource::File(data))).map_err(JsError::from)
+}
+
+/// The dequantized outputs of a witness file, as one flattened `Float32Array` per output, given the settings of the
+/// circuit the witness was generated for
+#[wasm_bindgen]
+#[allow(non_snake_case)]
+pub fn witnessFloatOutputs(
+    witness: wasm_bindgen::Clamped<Vec<u8>>,
+    settings: wasm_bindgen::Clamped<Vec<u8>>,
+) -> Result<js_sys::Array, JsError> {
+    let witness: GraphWitness = serde_json::from_slice(&witness[..])
+        .map_err(|e| JsError::new(&format!("Failed to deserialize witness: {}", e)))?;
+    let settings: GraphSettings = serde_json::from_slice(&settings[..])
+        .map_err(|e| JsError::new(&format!("Failed to deserialize settings: {}", e)))?;
+    if witness.outputs.len() != settings.model_output_scales.len() {
+        return Err(JsError::new(&format!(
+            "the witness has {} outputs but the settings have {}",
+            witness.outputs.len(),
+            settings.model_output_scales.len()
+        )));
+    }
+
+    Ok(witness
+        .get_float_outputs(&settings.model_output_scales)
+        .iter()
+        .map(|output| JsValue::from(js_sys::Float32Array::from(&output[..])))
+        .collect())
+}
+
+/// Decodes the instances of a proof, as the json of their field elements per instance column, into the named,
+/// dequantized public inputs and outputs of the model, as json, given the settings of the circuit
+#[wasm_bindgen]
+#[allow(non_snake_case)]
+pub fn decodeInstances(
+    instances: wasm_bindgen::Clamped<Vec<u8>>,
+    settings: wasm_bindgen::Clamped<Vec<u8>>,
+) -> Result<Vec<u8>, JsError> {
+    let instances: Vec<Vec<Fr>> = serde_json::from_slice(&instances[..])
+        .map_err(|e| JsError::new(&format!("Failed to deserialize instances: {}", e)))?;
+    let settings: GraphSettings = serde_json::from_slice(&settings[..])
+        .map_err(|e| JsError::new(&format!("Failed to deserialize settings: {}", e)))?;
+    let decoded = crate::graph::instances::decode_instances(&instances.concat(), &settings)
+        .map_err(|e| JsError::new(&format!("Failed to decode instances: {}", e)))?;
+    serde_json::to_vec(&decoded)
+        .map_err(|e| JsError::new(&format!("Failed to serialize decoded instances: {}", e)))
+}
+
 /// Generate verifying key in browser
 #[wasm_bindgen]
 #[allow(non_snake_case)]
@@ -291,15 +504,207 @@ pub fn verifyAggr(
     verify_aggr(proof_js.0, vk.0, logrows, srs.0, commitment).map_err(JsError::from)
 }
 
-/// Prove in browser using wasm
+/// Reports the progress of a proof to a JS callback, and reads the cancellation from an `AbortSignal`-like token
+struct JsProgress {
+    callback: Option<js_sys::Function>,
+    signal: Option<JsValue>,
+}
+
+impl ProgressObserver for JsProgress {
+    fn progress(&self, phase: ProvingPhase, fraction: f64) {
+        if let Some(callback) = &self.callback {
+            let phase = JsValue::from_str(phase.as_str());
+            if let Err(e) = callback.call2(&JsValue::NULL, &phase, &JsValue::from_f64(fraction)) {
+                log::warn!("progress callback failed: {:?}", e);
+            }
+        }
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.signal.as_ref().map_or(false, |signal| {
+            js_sys::Reflect::get(signal, &JsValue::from_str("aborted"))
+                .map_or(false, |aborted| aborted.is_truthy())
+        })
+    }
+}
+
+/// Prove in browser using wasm.
+///
+/// `progress` is called with the name of the phase (`load`, `synthesis`, `commit` or `done`) and the fraction of the
+/// phase that is complete, at the boundaries of the phases and as synthesis lays out the nodes of the model. The
+/// `aborted` property of `signal` (an `AbortSignal` or any object with that property) is read at the same points, and
+/// once it is true proving stops with an `Error` named `AbortError`.
+///
+/// Proving is synchronous: the callback is invoked from within the call, such that it also fires in a worker (where it
+/// would typically `postMessage` the progress), but a worker doesn't get to handle messages until the call returns.
+/// To cancel a proof running in a worker from another thread, give a `signal` whose `aborted` getter reads shared
+/// memory (as a `SharedArrayBuffer` flag set with `Atomics.store`).
 #[wasm_bindgen]
 pub fn prove(
     witness: wasm_bindgen::Clamped<Vec<u8>>,
     pk: wasm_bindgen::Clamped<Vec<u8>>,
     compiled_circuit: wasm_bindgen::Clamped<Vec<u8>>,
     srs: wasm_bindgen::Clamped<Vec<u8>>,
-) -> Result<Vec<u8>, JsError> {
-    super::universal::prove(witness.0, pk.0, compiled_circuit.0, srs.0).map_err(JsError::from)
+    progress: Option<js_sys::Function>,
+    signal: Option<JsValue>,
+) -> Result<Vec<u8>, JsValue> {
+    observed(progress, signal, || {
+        super::universal::prove(witness.0, pk.0, compiled_circuit.0, srs.0)
+    })
+}
+
+/// Runs a proof with its progress reported to `progress` and cancelled by `signal`
+fn observed(
+    progress: Option<js_sys::Function>,
+    signal: Option<JsValue>,
+    prove: impl FnOnce() -> Result<Vec<u8>, ExternalEZKLError>,
+) -> Result<Vec<u8>, JsValue> {
+    let observer = Rc::new(JsProgress {
+        callback: progress,
+        signal: signal.filter(|s| !s.is_undefined() && !s.is_null()),
+    });
+    with_observer(observer.clone(), prove).map_err(|e| {
+        if observer.is_cancelled() {
+            let error = js_sys::Error::new("proving was cancelled");
+            error.set_name("AbortError");
+            error.into()
+        } else {
+            JsError::from(e).into()
+        }
+    })
+}
+
+// STREAMED LOADING
+
+/// A buffer assembled in wasm memory from chunks given at their offsets, in any order
+#[derive(Default)]
+struct ChunkedBuffer {
+    bytes: Vec<u8>,
+    /// The byte ranges written so far
+    received: Vec<(usize, usize)>,
+}
+
+impl ChunkedBuffer {
+    /// Copies `chunk` straight from the JS heap to `offset`. `total_len`, once known (e.g. from the `Content-Range`
+    /// of the first response), allocates the buffer once instead of growing it chunk by chunk, and lets a missing
+    /// last chunk be detected.
+    fn write(&mut self, chunk: &js_sys::Uint8Array, offset: usize, total_len: Option<usize>) {
+        let end = offset + chunk.length() as usize;
+        let len = end.max(total_len.unwrap_or(0));
+        if self.bytes.len() < len {
+            self.bytes.resize(len, 0);
+        }
+        chunk.copy_to(&mut self.bytes[offset..end]);
+        self.received.push((offset, end));
+    }
+
+    /// The assembled bytes, or an error naming the first byte range that was never received
+    fn finish(&mut self) -> Result<Vec<u8>, JsError> {
+        let mut received = std::mem::take(&mut self.received);
+        let bytes = std::mem::take(&mut self.bytes);
+        received.sort_unstable();
+        let mut covered = 0;
+        for (start, end) in received {
+            if start > covered {
+                return Err(JsError::new(&format!(
+                    "missing bytes {}..{} of {}",
+                    covered,
+                    start,
+                    bytes.len()
+                )));
+            }
+            covered = covered.max(end);
+        }
+        if covered < bytes.len() {
+            return Err(JsError::new(&format!(
+                "missing bytes {}..{}",
+                covered,
+                bytes.len()
+            )));
+        }
+        if bytes.is_empty() {
+            return Err(JsError::new("no chunks were loaded"));
+        }
+        Ok(bytes)
+    }
+}
+
+thread_local! {
+    static SRS_CHUNKS: RefCell<ChunkedBuffer> = RefCell::new(ChunkedBuffer::default());
+    static PK_CHUNKS: RefCell<ChunkedBuffer> = RefCell::new(ChunkedBuffer::default());
+    /// The srs read by [finalizeSrs]
+    static LOADED_SRS: RefCell<Option<Srs>> = const { RefCell::new(None) };
+    /// The proving key read by [finalizePk]
+    static LOADED_PK: RefCell<Option<ProvingKey<G1Affine>>> = const { RefCell::new(None) };
+}
+
+/// Writes a chunk of an srs at `offset`, e.g. the body of a `Range` request. `totalLength` may be given (with any
+/// chunk) to allocate the srs at once.
+#[wasm_bindgen]
+#[allow(non_snake_case)]
+pub fn loadSrsChunk(bytes: js_sys::Uint8Array, offset: usize, totalLength: Option<usize>) {
+    SRS_CHUNKS.with(|chunks| chunks.borrow_mut().write(&bytes, offset, totalLength));
+}
+
+/// Reads the srs assembled by [loadSrsChunk] for the commitment scheme (`kzg`, the default, or `ipa`), freeing the
+/// chunks, and keeps it for [proveLoaded]
+#[wasm_bindgen]
+#[allow(non_snake_case)]
+pub fn finalizeSrs(commitment: Option<String>) -> Result<(), JsError> {
+    let commitment: Commitments = match commitment {
+        Some(commitment) => commitment.parse().map_err(|e: String| JsError::new(&e))?,
+        None => Commitments::KZG,
+    };
+    let bytes = SRS_CHUNKS.with(|chunks| chunks.borrow_mut().finish())?;
+    let srs = Srs::read(&bytes, commitment)?;
+    LOADED_SRS.with(|loaded| *loaded.borrow_mut() = Some(srs));
+    Ok(())
+}
+
+/// Writes a chunk of a proving key at `offset`, as [loadSrsChunk] does for the srs
+#[wasm_bindgen]
+#[allow(non_snake_case)]
+pub fn loadPkChunk(bytes: js_sys::Uint8Array, offset: usize, totalLength: Option<usize>) {
+    PK_CHUNKS.with(|chunks| chunks.borrow_mut().write(&bytes, offset, totalLength));
+}
+
+/// Reads the proving key assembled by [loadPkChunk] for a circuit with the given settings, freeing the chunks, and
+/// keeps it for [proveLoaded]
+#[wasm_bindgen]
+#[allow(non_snake_case)]
+pub fn finalizePk(settings: wasm_bindgen::Clamped<Vec<u8>>) -> Result<(), JsError> {
+    let settings: GraphSettings = serde_json::from_slice(&settings[..])
+        .map_err(|e| JsError::new(&format!("Failed to deserialize settings: {}", e)))?;
+    let bytes = PK_CHUNKS.with(|chunks| chunks.borrow_mut().finish())?;
+    let pk = read_pk(&bytes, settings)?;
+    LOADED_PK.with(|loaded| *loaded.borrow_mut() = Some(pk));
+    Ok(())
+}
+
+/// Prove as [prove] does, with the srs and proving key loaded by [finalizeSrs] and [finalizePk]. They stay loaded
+/// for further proofs.
+#[wasm_bindgen]
+#[allow(non_snake_case)]
+pub fn proveLoaded(
+    witness: wasm_bindgen::Clamped<Vec<u8>>,
+    compiled_circuit: wasm_bindgen::Clamped<Vec<u8>>,
+    progress: Option<js_sys::Function>,
+    signal: Option<JsValue>,
+) -> Result<Vec<u8>, JsValue> {
+    observed(progress, signal, || {
+        let circuit = read_compiled_circuit(&compiled_circuit)?;
+        LOADED_SRS.with(|srs| {
+            LOADED_PK.with(|pk| match (&*srs.borrow(), &*pk.borrow()) {
+                (Some(srs), Some(pk)) => prove_with(&witness, circuit, pk, srs),
+                (None, _) => Err(ExternalEZKLError::InternalError(
+                    "no srs was loaded, call finalizeSrs first".to_string(),
+                )),
+                (_, None) => Err(ExternalEZKLError::InternalError(
+                    "no proving key was loaded, call finalizePk first".to_string(),
+                )),
+            })
+        })
+    })
 }
 
 // VALIDATION FUNCTIONS
@@ -310,6 +715,28 @@ pub fn prove(
 pub fn witnessValidation(witness: wasm_bindgen::Clamped<Vec<u8>>) -> Result<bool, JsError> {
     witness_validation(witness.0).map_err(JsError::from)
 }
+/// Witness file validation against the settings of the circuit it was generated for, recomputing the hashes of the
+/// inputs and outputs from the elements of the witness
+#[wasm_bindgen]
+#[allow(non_snake_case)]
+pub fn validateWitness(
+    witness: wasm_bindgen::Clamped<Vec<u8>>,
+    settings: wasm_bindgen::Clamped<Vec<u8>>,
+) -> Result<bool, JsError> {
+    witness_consistency_validation(witness.0, settings.0).map_err(JsError::from)
+}
+
+/// Estimates the rows, lookups, instances and minimum logrows of a compiled circuit from a dry run of the layout of its
+/// model, without keygen, with the run args of its settings unless `run_args` json is given
+#[wasm_bindgen]
+#[allow(non_snake_case)]
+pub fn estimateLayout(
+    compiled_circuit: wasm_bindgen::Clamped<Vec<u8>>,
+    run_args: Option<Vec<u8>>,
+) -> Result<Vec<u8>, JsError> {
+    estimate_layout(compiled_circuit.0, run_args).map_err(JsError::from)
+}
+
 /// Compiled circuit validation
 #[wasm_bindgen]
 #[allow(non_snake_case)]
@@ -0,0 +1,1695 @@
+/*
+An ECDSA chip verifying a secp256k1 signature over the hash of some inputs, against a public key exposed in the
+instance column.
+
+The coordinates and scalars of the curve don't fit in the native field, so they are witnessed as integers of four
+64 bit limbs. Every limb is range checked by decomposing it into 16 bit chunks of bits, one chunk per row, the
+limb being accumulated along the rows. A product `a * b = c` modulo `m` is checked by witnessing the quotient `q`
+of `a * b - c` by `m` and the carries of the limbs of `a * b - q * m - c`: the carries show that it vanishes modulo
+2^320, and a native constraint that it vanishes modulo the native modulus. As both operands are bounded, it is
+zero as an integer. The operands `b` and `c` of a product are small linear combinations of integers (and of a
+constant), such that the point formulas only take a product per equation.
+
+The scalar multiplications `u1 * G + u2 * Q` are computed with a double-and-add over the bits of both scalars,
+adding one of `A, A + G, A + Q, A + G + Q` at every step. The affine formulas are incomplete, and the offset point
+`A` keeps the accumulator away from the exceptional cases, its multiple being subtracted at the end.
+
+The digest is the Poseidon hash of the inputs, constrained to its canonical encoding as an integer, or the two
+128 bit halves of their SHA-256 digest.
+*/
+
+use halo2_proofs::arithmetic::Field;
+use halo2_proofs::halo2curves::bn256::Fr as Fp;
+use halo2_proofs::poly::Rotation;
+use halo2_proofs::{circuit::*, plonk::*};
+use halo2curves::ff::PrimeField;
+use itertools::Itertools;
+use num::bigint::Sign;
+use num::{BigInt, BigUint, Integer, One, Zero};
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+use schemars::JsonSchema;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::circuit::region::ConstantsMap;
+use crate::tensor::{Tensor, ValTensor, ValType};
+
+use super::errors::ModuleError;
+use super::Module;
+
+/// The number of instance columns used by the ECDSA verification
+pub const NUM_INSTANCE_COLUMNS: usize = 1;
+/// Number of limbs of a non-native integer
+const LIMBS: usize = 4;
+/// Number of bits of a limb
+const LIMB_BITS: usize = 64;
+/// Number of bits of the chunks a limb is range checked in, one chunk per row
+const CHUNK_BITS: usize = 16;
+/// Number of rows of the range check of an integer
+const INTEGER_ROWS: usize = 4;
+/// Number of rows of the range check of the quotient of a product, which may exceed 256 bits
+const QUOTIENT_ROWS: usize = 5;
+/// Number of rows of the range check of the (offset) carries of a product
+const CARRY_ROWS: usize = 6;
+/// Number of rows of the product itself: `a, b1, b2, q, c1, c2, c3` and the carries
+const PRODUCT_ROWS: usize = 8;
+/// Number of rows of a product check, including the range checks of its quotient and carries
+const MUL_ROWS: usize = QUOTIENT_ROWS + CARRY_ROWS + PRODUCT_ROWS;
+/// Number of rows of a point addition or doubling: four integers and four products
+const POINT_ROWS: usize = 4 * (INTEGER_ROWS + MUL_ROWS);
+/// Number of rows of the selection of a point of the table: four points and the selected one
+const SELECT_ROWS: usize = 10;
+/// Number of bits of the scalars
+const SCALAR_BITS: usize = 256;
+/// The carries of a product are offset by 2^CARRY_OFFSET to be range checked as positive integers
+const CARRY_OFFSET: usize = 95;
+/// Number of instances of the public key, the limbs of its coordinates
+pub const PUBLIC_KEY_LIMBS: usize = 2 * LIMBS;
+/// Number of witnessed limbs, those of `r`, `s` and of the public key coordinates
+pub const WITNESS_LIMBS: usize = 4 * LIMBS;
+
+/// Field modulus of secp256k1
+const P_HEX: &str = "fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f";
+/// Order of the group of secp256k1
+const N_HEX: &str = "fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141";
+/// Coordinates of the generator of secp256k1
+const GX_HEX: &str = "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+const GY_HEX: &str = "483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8";
+
+/// The curve the signature is over
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), derive(JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum SignatureCurve {
+    /// the curve of Ethereum and Bitcoin signatures
+    #[default]
+    Secp256k1,
+}
+
+/// The hash the signed digest of the inputs is computed with
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), derive(JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum SignatureHash {
+    /// the Poseidon hash of the inputs, signed as a 32 byte big-endian integer
+    #[default]
+    Poseidon,
+    /// the SHA-256 digest of the inputs serialized as in [super::sha256]
+    Sha256,
+}
+
+/// The inputs authenticated by a signature, verified in the circuit
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), derive(JsonSchema))]
+pub struct InputSignature {
+    /// indices of the signed inputs, hashed in this order
+    pub inputs: Vec<usize>,
+    /// the curve of the signature
+    #[serde(default)]
+    pub curve: SignatureCurve,
+    /// the hash of the signed inputs
+    #[serde(default)]
+    pub hash: SignatureHash,
+}
+
+/// A 256 bit big-endian integer, serialized as a 0x prefixed hex string
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Word(pub [u8; 32]);
+
+impl Word {
+    /// Parses a (0x prefixed) hex string of at most 32 bytes
+    pub fn from_hex(s: &str) -> Result<Self, ModuleError> {
+        let digits = s.strip_prefix("0x").unwrap_or(s);
+        let mut bytes = [0u8; 32];
+        hex::decode_to_slice(format!("{:0>64}", digits), &mut bytes)
+            .map_err(|e| ModuleError::InvalidSignature(format!("{}: {}", s, e)))?;
+        Ok(Word(bytes))
+    }
+
+    /// The digest signed for the output of a hash: the Poseidon hash as an integer, or the
+    /// high and low halves of a SHA-256 digest
+    pub fn from_hash(hash: SignatureHash, output: &[Fp]) -> Self {
+        let value = match hash {
+            SignatureHash::Poseidon => felt_to_big(&output[0]),
+            SignatureHash::Sha256 => (felt_to_big(&output[0]) << 128) + felt_to_big(&output[1]),
+        };
+        Word::from_big(&value)
+    }
+
+    /// The 0x prefixed hex encoding of the integer
+    pub fn to_hex(&self) -> String {
+        let digits: String = self.0.iter().map(|b| format!("{:02x}", b)).collect();
+        format!("0x{}", digits)
+    }
+
+    fn from_big(value: &BigUint) -> Self {
+        let bytes = value.to_bytes_be();
+        let mut word = [0u8; 32];
+        word[32 - bytes.len()..].copy_from_slice(&bytes);
+        Word(word)
+    }
+
+    fn to_big(self) -> BigUint {
+        BigUint::from_bytes_be(&self.0)
+    }
+
+    /// The little-endian limbs of the integer
+    fn limbs(self) -> [Fp; LIMBS] {
+        split(&self.to_big()).map(|l| big_to_felt(&l))
+    }
+}
+
+impl Serialize for Word {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_hex())
+    }
+}
+
+impl<'de> Deserialize<'de> for Word {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Word::from_hex(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+/// An ECDSA signature along with the public key it verifies against
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EcdsaSignature {
+    ///
+    pub r: Word,
+    ///
+    pub s: Word,
+    /// the affine coordinates of the public key
+    pub public_key: [Word; 2],
+}
+
+impl EcdsaSignature {
+    /// Recovers the public key of a 65 byte `r || s || v` signature over a digest (`v` being
+    /// 0 or 1, or 27 or 28 as in Ethereum signatures), checking that the signature verifies
+    pub fn recover(digest: &Word, signature: &[u8]) -> Result<Self, ModuleError> {
+        use k256::ecdsa::{RecoveryId, Signature, VerifyingKey};
+        use k256::elliptic_curve::sec1::ToEncodedPoint;
+
+        if signature.len() != 65 {
+            return Err(ModuleError::InvalidSignature(format!(
+                "expected a 65 byte signature, got {} bytes",
+                signature.len()
+            )));
+        }
+        let v = match signature[64] {
+            v if v >= 27 => v - 27,
+            v => v,
+        };
+        let recovery_id = RecoveryId::from_byte(v)
+            .ok_or_else(|| ModuleError::InvalidSignature(format!("invalid recovery id {}", v)))?;
+        let sig = Signature::from_slice(&signature[..64])
+            .map_err(|e| ModuleError::InvalidSignature(e.to_string()))?;
+        let key = VerifyingKey::recover_from_prehash(&digest.0, &sig, recovery_id)
+            .map_err(|e| ModuleError::InvalidSignature(e.to_string()))?;
+
+        let point = k256::PublicKey::from(key).to_encoded_point(false);
+        let coordinate = |c: Option<&k256::FieldBytes>| -> Result<Word, ModuleError> {
+            let c = c.ok_or_else(|| {
+                ModuleError::InvalidSignature("the public key is the identity".to_string())
+            })?;
+            let mut word = [0u8; 32];
+            word.copy_from_slice(c);
+            Ok(Word(word))
+        };
+        let mut r = [0u8; 32];
+        let mut s = [0u8; 32];
+        r.copy_from_slice(&signature[..32]);
+        s.copy_from_slice(&signature[32..64]);
+
+        let signature = EcdsaSignature {
+            r: Word(r),
+            s: Word(s),
+            public_key: [coordinate(point.x())?, coordinate(point.y())?],
+        };
+        EcdsaChip::run((*digest, signature.clone()))?;
+        Ok(signature)
+    }
+
+    /// The limbs of the public key coordinates, as exposed in the instance column
+    pub fn public_key_instances(&self) -> Vec<Fp> {
+        self.public_key.iter().flat_map(|c| c.limbs()).collect()
+    }
+
+    /// The limbs of `r`, `s` and of the public key coordinates, as witnessed in the circuit
+    pub fn witness_limbs(&self) -> Vec<Fp> {
+        [self.r, self.s, self.public_key[0], self.public_key[1]]
+            .iter()
+            .flat_map(|w| w.limbs())
+            .collect()
+    }
+}
+
+/// The signed digest of the inputs, and the signature once it is attached to the witness
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InputSignatureWitness {
+    /// the digest to sign
+    pub digest: Word,
+    /// the signature over the digest
+    pub signature: Option<EcdsaSignature>,
+}
+
+#[derive(Debug, Clone)]
+/// Configuration for the ECDSA chip
+pub struct EcdsaConfig {
+    /// the limbs of the integers, and the bits of the scalars
+    pub limbs: [Column<Advice>; LIMBS],
+    /// the bits of the chunks of the range checked limbs
+    pub bits: [[Column<Advice>; CHUNK_BITS]; LIMBS],
+    /// the digest and the bits selecting a point of the table
+    pub aux: Column<Advice>,
+    /// the weight of the chunk of a range check row
+    pub shift: Column<Fixed>,
+    /// whether a range check or a scalar decomposition row accumulates the previous row
+    pub prev: Column<Fixed>,
+    /// the coefficients of the integers of the `b` operand of a product
+    pub beta: [Column<Fixed>; 2],
+    /// the coefficients of the integers of the `c` operand of a product
+    pub gamma: [Column<Fixed>; 3],
+    /// the limbs of the constants of the `b` operand of a product
+    pub kb: [Column<Fixed>; LIMBS],
+    /// the limbs of the constants of the `c` operand of a product, and of constant integers
+    pub kc: [Column<Fixed>; LIMBS],
+    /// range checks the limbs of a row
+    pub q_range: Selector,
+    /// checks a product modulo the base field and the scalar field of the curve
+    pub q_mul: [Selector; 2],
+    /// constrains the limbs of a row to a constant
+    pub q_const: Selector,
+    /// decomposes the scalars into bits
+    pub q_bits: Selector,
+    /// selects a point of the table
+    pub q_select: Selector,
+    /// constrains an integer to the digest
+    pub q_digest: Selector,
+    /// the hash the digest is computed with
+    pub hash: SignatureHash,
+    ///
+    pub instance: Option<Column<Instance>>,
+}
+
+/// The moduli the products are checked against
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Modulus {
+    /// the field of the coordinates
+    Base,
+    /// the order of the group
+    Scalar,
+}
+
+/// A point in affine coordinates
+type NativePoint = (BigUint, BigUint);
+
+/// secp256k1, along with the offset point of the scalar multiplications
+#[derive(Debug, Clone)]
+struct Curve {
+    p: BigUint,
+    n: BigUint,
+    g: NativePoint,
+    /// the offset point, of abscissa 1 and of even ordinate
+    a: NativePoint,
+    /// `A + G`
+    a_plus_g: NativePoint,
+    /// `-(2^256 - 1) * A`, cancelling the offsets added along the double-and-add
+    correction: NativePoint,
+}
+
+impl Curve {
+    fn secp256k1() -> Self {
+        let p = from_hex(P_HEX);
+        let n = from_hex(N_HEX);
+        let g = (from_hex(GX_HEX), from_hex(GY_HEX));
+        // p = 3 mod 4, so the square root of 1 + 7 is a power of it
+        let y = BigUint::from(8u32).modpow(&((&p + 1u32) >> 2), &p);
+        let y = if y.is_even() { y } else { &p - y };
+        let a = (BigUint::one(), y);
+        let a_plus_g =
+            point_add(&p, &Some(a.clone()), &Some(g.clone())).unwrap_or_else(|| unreachable!());
+        let scalar = ((BigUint::one() << SCALAR_BITS) - 1u32) % &n;
+        let (x, y) = point_mul(&p, &scalar, &a).unwrap_or_else(|| unreachable!());
+        let correction = (x, &p - y);
+        Curve {
+            p,
+            n,
+            g,
+            a,
+            a_plus_g,
+            correction,
+        }
+    }
+
+    fn modulus(&self, modulus: Modulus) -> &BigUint {
+        match modulus {
+            Modulus::Base => &self.p,
+            Modulus::Scalar => &self.n,
+        }
+    }
+
+    /// The slope of the sum of two points, the inverse of the difference of their abscissas
+    /// and the sum itself
+    fn add_witness(&self, (x1, y1): &NativePoint, (x2, y2): &NativePoint) -> [BigUint; 4] {
+        let p = &self.p;
+        let inv = inverse(&sub_mod(x2, x1, p), p);
+        let lambda = sub_mod(y2, y1, p) * &inv % p;
+        let x3 = sub_mod(&sub_mod(&(&lambda * &lambda % p), x1, p), x2, p);
+        let y3 = sub_mod(&(&lambda * sub_mod(x1, &x3, p) % p), y1, p);
+        [lambda, inv, x3, y3]
+    }
+
+    /// The square of the abscissa of a point, the slope of its tangent and its double
+    fn double_witness(&self, (x, y): &NativePoint) -> [BigUint; 4] {
+        let p = &self.p;
+        let xx = x * x % p;
+        let lambda = &xx * 3u32 * inverse(&(y * 2u32), p) % p;
+        let x3 = sub_mod(&(&lambda * &lambda % p), &(x * 2u32), p);
+        let y3 = sub_mod(&(&lambda * sub_mod(x, &x3, p) % p), y, p);
+        [xx, lambda, x3, y3]
+    }
+
+    /// Verifies a signature natively, as the circuit does
+    fn verify(&self, z: &BigUint, r: &BigUint, s: &BigUint, key: &NativePoint) -> bool {
+        let (x, y) = key;
+        let on_curve =
+            x < &self.p && y < &self.p && y * y % &self.p == (x * x * x + 7u32) % &self.p;
+        if !on_curve || r.is_zero() || s.is_zero() || r >= &self.n || s >= &self.n {
+            return false;
+        }
+        let w = inverse(s, &self.n);
+        let u1 = z * &w % &self.n;
+        let u2 = r * &w % &self.n;
+        let point = point_add(
+            &self.p,
+            &point_mul(&self.p, &u1, &self.g),
+            &point_mul(&self.p, &u2, key),
+        );
+        matches!(point, Some((x, _)) if &(x % &self.n) == r)
+    }
+}
+
+fn from_hex(s: &str) -> BigUint {
+    BigUint::parse_bytes(s.as_bytes(), 16).unwrap_or_else(|| unreachable!())
+}
+
+/// `a - b` modulo `m`
+fn sub_mod(a: &BigUint, b: &BigUint, m: &BigUint) -> BigUint {
+    (a % m + m - b % m) % m
+}
+
+/// The inverse of `x` modulo a prime `m`
+fn inverse(x: &BigUint, m: &BigUint) -> BigUint {
+    (x % m).modpow(&(m - 2u32), m)
+}
+
+/// The complete addition of two points, `None` being the point at infinity
+fn point_add(p: &BigUint, a: &Option<NativePoint>, b: &Option<NativePoint>) -> Option<NativePoint> {
+    let ((x1, y1), (x2, y2)) = match (a, b) {
+        (None, _) => return b.clone(),
+        (_, None) => return a.clone(),
+        (Some(a), Some(b)) => (a, b),
+    };
+    let lambda = if x1 == x2 {
+        if ((y1 + y2) % p).is_zero() {
+            return None;
+        }
+        x1 * x1 * 3u32 * inverse(&(y1 * 2u32), p) % p
+    } else {
+        sub_mod(y2, y1, p) * inverse(&sub_mod(x2, x1, p), p) % p
+    };
+    let x3 = sub_mod(&sub_mod(&(&lambda * &lambda % p), x1, p), x2, p);
+    let y3 = sub_mod(&(&lambda * sub_mod(x1, &x3, p) % p), y1, p);
+    Some((x3, y3))
+}
+
+fn point_mul(p: &BigUint, k: &BigUint, point: &NativePoint) -> Option<NativePoint> {
+    let point = Some(point.clone());
+    let mut acc = None;
+    for i in (0..k.bits()).rev() {
+        acc = point_add(p, &acc, &acc);
+        if k.bit(i) {
+            acc = point_add(p, &acc, &point);
+        }
+    }
+    acc
+}
+
+/// The little-endian 64 bit limbs of an integer, the last limb holding the remaining bits
+fn split(x: &BigUint) -> [BigUint; LIMBS] {
+    let mask = (BigUint::one() << LIMB_BITS) - 1u32;
+    std::array::from_fn(|i| {
+        let limb = x >> (LIMB_BITS * i);
+        if i < LIMBS - 1 {
+            limb & &mask
+        } else {
+            limb
+        }
+    })
+}
+
+/// The limbs of a signed integer, each carrying its sign
+fn split_signed(x: &BigInt) -> [BigInt; LIMBS] {
+    split(x.magnitude()).map(|l| BigInt::from_biguint(x.sign(), l))
+}
+
+fn from_limbs(limbs: &[Fp]) -> BigUint {
+    limbs
+        .iter()
+        .enumerate()
+        .fold(BigUint::zero(), |acc, (i, l)| {
+            acc + (felt_to_big(l) << (LIMB_BITS * i))
+        })
+}
+
+fn felt_to_big(x: &Fp) -> BigUint {
+    BigUint::from_bytes_le(x.to_repr().as_ref())
+}
+
+/// The field element of an integer, reduced modulo the native modulus
+fn big_to_felt(x: &BigUint) -> Fp {
+    Fp::from_str_vartime(&x.to_str_radix(10)).unwrap_or_else(|| unreachable!())
+}
+
+fn signed_to_felt(x: &BigInt) -> Fp {
+    let magnitude = big_to_felt(x.magnitude());
+    match x.sign() {
+        Sign::Minus => -magnitude,
+        _ => magnitude,
+    }
+}
+
+fn low_u64(x: &BigUint) -> u64 {
+    x.iter_u64_digits().next().unwrap_or(0)
+}
+
+fn one() -> Expression<Fp> {
+    Expression::Constant(Fp::ONE)
+}
+
+fn zero() -> Expression<Fp> {
+    Expression::Constant(Fp::ZERO)
+}
+
+/// The weight of the `i`-th limb
+fn limb_shift(i: usize) -> Fp {
+    Fp::from(2).pow_vartime([(LIMB_BITS * i) as u64])
+}
+
+/// Pack little-endian bits into an integer
+fn pack(bits: &[Expression<Fp>]) -> Expression<Fp> {
+    bits.iter().enumerate().fold(zero(), |acc, (i, bit)| {
+        acc + bit.clone() * Fp::from(1u64 << i)
+    })
+}
+
+/// Compose little-endian limbs into an integer, modulo the native modulus
+fn compose(limbs: &[Expression<Fp>]) -> Expression<Fp> {
+    limbs
+        .iter()
+        .enumerate()
+        .fold(zero(), |acc, (i, limb)| acc + limb.clone() * limb_shift(i))
+}
+
+/// Query the limbs of the rows following the current one
+fn query_rows<const N: usize>(
+    meta: &mut VirtualCells<'_, Fp>,
+    limbs: &[Column<Advice>; LIMBS],
+) -> [[Expression<Fp>; LIMBS]; N] {
+    std::array::from_fn(|rot| limbs.map(|col| meta.query_advice(col, Rotation(rot as i32))))
+}
+
+/// A non-native integer, the cells of its limbs along with its value
+#[derive(Debug, Clone)]
+struct AssignedInteger {
+    limbs: Vec<AssignedCell<Fp, Fp>>,
+    value: Value<BigUint>,
+}
+
+#[derive(Debug, Clone)]
+struct AssignedPoint {
+    x: AssignedInteger,
+    y: AssignedInteger,
+}
+
+impl AssignedPoint {
+    fn value(&self) -> Value<NativePoint> {
+        self.x.value.clone().zip(self.y.value.clone())
+    }
+}
+
+/// A term of an operand of a product, an integer times a small coefficient
+type Term<'a> = (i64, &'a AssignedInteger);
+
+/// The bits of `u1` and `u2` of a double-and-add step
+type ScalarBits = (AssignedCell<Fp, Fp>, AssignedCell<Fp, Fp>);
+
+/// Lays out the verification row after row, in a single region as the range checks and the
+/// scalar decomposition accumulate along the rows
+struct EcdsaRegion<'a, 'r> {
+    config: &'a EcdsaConfig,
+    region: &'a mut Region<'r, Fp>,
+    curve: &'a Curve,
+    offset: usize,
+}
+
+impl EcdsaRegion<'_, '_> {
+    /// Assign the limb columns of a row
+    fn assign_row(
+        &mut self,
+        row: usize,
+        values: &[Value<Fp>; LIMBS],
+    ) -> Result<Vec<AssignedCell<Fp, Fp>>, Error> {
+        values
+            .iter()
+            .zip(self.config.limbs)
+            .map(|(v, col)| self.region.assign_advice(|| "limb", col, row, || *v))
+            .collect()
+    }
+
+    /// Copy limbs into the limb columns of a row
+    fn copy_row(&mut self, row: usize, cells: &[AssignedCell<Fp, Fp>]) -> Result<(), Error> {
+        for (cell, col) in cells.iter().zip(self.config.limbs) {
+            cell.copy_advice(|| "limb", self.region, col, row)?;
+        }
+        Ok(())
+    }
+
+    fn zero_row(&mut self, row: usize) -> Result<Vec<AssignedCell<Fp, Fp>>, Error> {
+        self.assign_row(row, &[Value::known(Fp::ZERO); LIMBS])
+    }
+
+    /// Range checks limbs of `CHUNK_BITS * rows` bits, returning the cells of the limbs
+    /// accumulated on the last row
+    fn range_check(
+        &mut self,
+        limbs: &[Value<BigUint>; LIMBS],
+        rows: usize,
+    ) -> Result<Vec<AssignedCell<Fp, Fp>>, Error> {
+        let mut cells = vec![];
+        for r in 0..rows {
+            let row = self.offset + r;
+            self.config.q_range.enable(self.region, row)?;
+            self.region.assign_fixed(
+                || "shift",
+                self.config.shift,
+                row,
+                || Value::known(Fp::from_u128(1u128 << (CHUNK_BITS * r))),
+            )?;
+            self.region.assign_fixed(
+                || "prev",
+                self.config.prev,
+                row,
+                || Value::known(Fp::from((r > 0) as u64)),
+            )?;
+            cells = vec![];
+            for (i, limb) in limbs.iter().enumerate() {
+                let chunk = limb
+                    .as_ref()
+                    .map(|l| low_u64(&(l >> (CHUNK_BITS * r))) & 0xffff);
+                for (j, col) in self.config.bits[i].iter().enumerate() {
+                    self.region.assign_advice(
+                        || "bit",
+                        *col,
+                        row,
+                        || chunk.map(|c| Fp::from((c >> j) & 1)),
+                    )?;
+                }
+                let acc = limb
+                    .as_ref()
+                    .map(|l| big_to_felt(&(l % (BigUint::one() << (CHUNK_BITS * (r + 1))))));
+                cells.push(self.region.assign_advice(
+                    || "accumulator",
+                    self.config.limbs[i],
+                    row,
+                    || acc,
+                )?);
+            }
+        }
+        self.offset += rows;
+        Ok(cells)
+    }
+
+    fn assign_integer(&mut self, value: Value<BigUint>) -> Result<AssignedInteger, Error> {
+        let limbs = std::array::from_fn(|i| value.as_ref().map(|v| split(v)[i].clone()));
+        let limbs = self.range_check(&limbs, INTEGER_ROWS)?;
+        Ok(AssignedInteger { limbs, value })
+    }
+
+    fn assign_integers(
+        &mut self,
+        values: Value<[BigUint; 4]>,
+    ) -> Result<[AssignedInteger; 4], Error> {
+        let mut integers = vec![];
+        for i in 0..4 {
+            integers.push(self.assign_integer(values.as_ref().map(|v| v[i].clone()))?);
+        }
+        integers.try_into().map_err(|_| Error::Synthesis)
+    }
+
+    fn assign_constant(&mut self, value: &BigUint) -> Result<AssignedInteger, Error> {
+        let row = self.offset;
+        self.config.q_const.enable(self.region, row)?;
+        let limbs = split(value).map(|l| big_to_felt(&l));
+        for (col, limb) in self.config.kc.iter().zip(limbs) {
+            self.region
+                .assign_fixed(|| "constant", *col, row, || Value::known(limb))?;
+        }
+        let limbs = self.assign_row(row, &limbs.map(Value::known))?;
+        self.offset += 1;
+        Ok(AssignedInteger {
+            limbs,
+            value: Value::known(value.clone()),
+        })
+    }
+
+    fn assign_point_constant(&mut self, (x, y): &NativePoint) -> Result<AssignedPoint, Error> {
+        Ok(AssignedPoint {
+            x: self.assign_constant(x)?,
+            y: self.assign_constant(y)?,
+        })
+    }
+
+    /// Checks `a * b = c` modulo the base field or the scalar field, where `b` and `c` are
+    /// linear combinations of integers plus a constant. The integer `a * b - c` must be
+    /// non-negative.
+    fn mul_check(
+        &mut self,
+        modulus: Modulus,
+        a: &AssignedInteger,
+        b: &[Term],
+        kb: &BigInt,
+        c: &[Term],
+        kc: &BigInt,
+    ) -> Result<(), Error> {
+        assert!(b.len() <= 2 && c.len() <= 3);
+        let m = self.curve.modulus(modulus).clone();
+
+        let limbs_of = |terms: &[Term], constant: &BigInt| -> Value<Vec<BigInt>> {
+            terms.iter().fold(
+                Value::known(split_signed(constant).to_vec()),
+                |acc, (coeff, t)| {
+                    acc.zip(t.value.as_ref()).map(|(acc, v)| {
+                        acc.iter()
+                            .zip(split(v))
+                            .map(|(x, l)| x + BigInt::from(*coeff) * BigInt::from(l))
+                            .collect()
+                    })
+                },
+            )
+        };
+        let compose_limbs = |limbs: &[BigInt]| -> BigInt {
+            limbs
+                .iter()
+                .enumerate()
+                .fold(BigInt::zero(), |acc, (i, l)| acc + (l << (LIMB_BITS * i)))
+        };
+        let a_limbs = limbs_of(&[(1, a)], &BigInt::zero());
+        let b_limbs = limbs_of(b, kb);
+        let c_limbs = limbs_of(c, kc);
+
+        let operands = a_limbs.zip(b_limbs).zip(c_limbs);
+        let quotient = operands.as_ref().map(|((a, b), c)| {
+            let product = compose_limbs(a) * compose_limbs(b) - compose_limbs(c);
+            product
+                .div_floor(&BigInt::from(m.clone()))
+                .to_biguint()
+                .unwrap_or_default()
+        });
+        let carries = operands.zip(quotient.as_ref()).map(|(((a, b), c), q)| {
+            let q = split(q).map(BigInt::from);
+            let m = split(&m).map(BigInt::from);
+            // the limbs of a * b - q * m - c, up to the weight the carries vanish at
+            let t = (0..=LIMBS)
+                .map(|k| {
+                    let mut t = c.get(k).map_or(BigInt::zero(), |c| -c);
+                    for i in 0..LIMBS {
+                        if k >= i && k - i < LIMBS {
+                            t += &a[i] * &b[k - i] - &q[i] * &m[k - i];
+                        }
+                    }
+                    t
+                })
+                .collect_vec();
+            let base = BigInt::one() << LIMB_BITS;
+            let v0 = (&t[0] + &t[1] * &base).div_floor(&(&base * &base));
+            let v1 = (&v0 + &t[2] + &t[3] * &base).div_floor(&(&base * &base));
+            let v2 = (&v1 + &t[4]).div_floor(&base);
+            let offset = BigInt::one() << CARRY_OFFSET;
+            let bound = BigUint::one() << (CHUNK_BITS * CARRY_ROWS);
+            let carries = [v0, v1, v2].map(|v| {
+                (v + &offset)
+                    .to_biguint()
+                    .filter(|v| v < &bound)
+                    .unwrap_or_default()
+            });
+            [
+                carries[0].clone(),
+                carries[1].clone(),
+                carries[2].clone(),
+                BigUint::zero(),
+            ]
+        });
+
+        let q_limbs = std::array::from_fn(|i| quotient.as_ref().map(|q| split(q)[i].clone()));
+        let quotient = self.range_check(&q_limbs, QUOTIENT_ROWS)?;
+        let carry_limbs = std::array::from_fn(|i| carries.as_ref().map(|v| v[i].clone()));
+        let carries = self.range_check(&carry_limbs, CARRY_ROWS)?;
+
+        let row = self.offset;
+        self.config.q_mul[modulus as usize].enable(self.region, row)?;
+        let coefficients = |terms: &[Term], n: usize| {
+            (0..n)
+                .map(|i| {
+                    terms
+                        .get(i)
+                        .map_or(Fp::ZERO, |(c, _)| signed_to_felt(&BigInt::from(*c)))
+                })
+                .collect_vec()
+        };
+        let fixed = self
+            .config
+            .beta
+            .iter()
+            .zip(coefficients(b, 2))
+            .chain(self.config.gamma.iter().zip(coefficients(c, 3)))
+            .chain(
+                self.config
+                    .kb
+                    .iter()
+                    .zip(split_signed(kb).map(|l| signed_to_felt(&l))),
+            )
+            .chain(
+                self.config
+                    .kc
+                    .iter()
+                    .zip(split_signed(kc).map(|l| signed_to_felt(&l))),
+            );
+        for (col, coeff) in fixed {
+            self.region
+                .assign_fixed(|| "coefficient", *col, row, || Value::known(coeff))?;
+        }
+
+        self.copy_row(row, &a.limbs)?;
+        for i in 0..2 {
+            match b.get(i) {
+                Some((_, t)) => self.copy_row(row + 1 + i, &t.limbs)?,
+                None => {
+                    self.zero_row(row + 1 + i)?;
+                }
+            }
+        }
+        self.copy_row(row + 3, &quotient)?;
+        for i in 0..3 {
+            match c.get(i) {
+                Some((_, t)) => self.copy_row(row + 4 + i, &t.limbs)?,
+                None => {
+                    self.zero_row(row + 4 + i)?;
+                }
+            }
+        }
+        self.copy_row(row + 7, &carries)?;
+        self.offset += PRODUCT_ROWS;
+        Ok(())
+    }
+
+    fn add(&mut self, p1: &AssignedPoint, p2: &AssignedPoint) -> Result<AssignedPoint, Error> {
+        let curve = self.curve;
+        let witness = p1
+            .value()
+            .zip(p2.value())
+            .map(|(p1, p2)| curve.add_witness(&p1, &p2));
+        let [lambda, inv, x3, y3] = self.assign_integers(witness)?;
+        let m = BigInt::from(curve.p.clone());
+        let (x1, y1, x2, y2) = (&p1.x, &p1.y, &p2.x, &p2.y);
+        // lambda * (x2 - x1) = y2 - y1
+        self.mul_check(
+            Modulus::Base,
+            &lambda,
+            &[(1, x2), (-1, x1)],
+            &m,
+            &[(1, y2), (-1, y1)],
+            &-&m,
+        )?;
+        // inv * (x2 - x1) = 1, the abscissas differ
+        self.mul_check(
+            Modulus::Base,
+            &inv,
+            &[(1, x2), (-1, x1)],
+            &m,
+            &[],
+            &(BigInt::one() - &m),
+        )?;
+        // lambda^2 = x1 + x2 + x3
+        self.mul_check(
+            Modulus::Base,
+            &lambda,
+            &[(1, &lambda)],
+            &BigInt::zero(),
+            &[(1, x1), (1, x2), (1, &x3)],
+            &(BigInt::from(-3) * &m),
+        )?;
+        // lambda * (x1 - x3) = y1 + y3
+        self.mul_check(
+            Modulus::Base,
+            &lambda,
+            &[(1, x1), (-1, &x3)],
+            &m,
+            &[(1, y1), (1, &y3)],
+            &(BigInt::from(-2) * &m),
+        )?;
+        Ok(AssignedPoint { x: x3, y: y3 })
+    }
+
+    fn double(&mut self, point: &AssignedPoint) -> Result<AssignedPoint, Error> {
+        let curve = self.curve;
+        let witness = point.value().map(|p| curve.double_witness(&p));
+        let [xx, lambda, x3, y3] = self.assign_integers(witness)?;
+        let m = BigInt::from(curve.p.clone());
+        let (x, y) = (&point.x, &point.y);
+        // x^2 = xx
+        self.mul_check(
+            Modulus::Base,
+            x,
+            &[(1, x)],
+            &BigInt::zero(),
+            &[(1, &xx)],
+            &-&m,
+        )?;
+        // lambda * 2y = 3 xx
+        self.mul_check(
+            Modulus::Base,
+            &lambda,
+            &[(2, y)],
+            &BigInt::zero(),
+            &[(3, &xx)],
+            &(BigInt::from(-3) * &m),
+        )?;
+        // lambda^2 = 2x + x3
+        self.mul_check(
+            Modulus::Base,
+            &lambda,
+            &[(1, &lambda)],
+            &BigInt::zero(),
+            &[(2, x), (1, &x3)],
+            &(BigInt::from(-3) * &m),
+        )?;
+        // lambda * (x - x3) = y + y3
+        self.mul_check(
+            Modulus::Base,
+            &lambda,
+            &[(1, x), (-1, &x3)],
+            &m,
+            &[(1, y), (1, &y3)],
+            &(BigInt::from(-2) * &m),
+        )?;
+        Ok(AssignedPoint { x: x3, y: y3 })
+    }
+
+    /// Selects the point of the table indexed by the bits of `u1` and `u2`
+    fn select(
+        &mut self,
+        table: &[AssignedPoint; 4],
+        (b1, b2): &ScalarBits,
+    ) -> Result<AssignedPoint, Error> {
+        let row = self.offset;
+        self.config.q_select.enable(self.region, row)?;
+        for (k, point) in table.iter().enumerate() {
+            self.copy_row(row + 2 * k, &point.x.limbs)?;
+            self.copy_row(row + 2 * k + 1, &point.y.limbs)?;
+        }
+        b1.copy_advice(|| "u1 bit", self.region, self.config.aux, row)?;
+        b2.copy_advice(|| "u2 bit", self.region, self.config.aux, row + 1)?;
+
+        let index = b1
+            .value()
+            .zip(b2.value())
+            .map(|(b1, b2)| (*b1 == Fp::ONE) as usize + 2 * (*b2 == Fp::ONE) as usize);
+        let points: Value<Vec<NativePoint>> = table.iter().map(|t| t.value()).collect();
+        let selected = index.zip(points).map(|(i, points)| points[i].clone());
+        let x = selected.as_ref().map(|p| p.0.clone());
+        let y = selected.map(|p| p.1);
+        let limbs = |v: &Value<BigUint>| -> [Value<Fp>; LIMBS] {
+            std::array::from_fn(|i| v.as_ref().map(|v| big_to_felt(&split(v)[i])))
+        };
+        let x_limbs = self.assign_row(row + 8, &limbs(&x))?;
+        let y_limbs = self.assign_row(row + 9, &limbs(&y))?;
+        self.offset += SELECT_ROWS;
+        Ok(AssignedPoint {
+            x: AssignedInteger {
+                limbs: x_limbs,
+                value: x,
+            },
+            y: AssignedInteger {
+                limbs: y_limbs,
+                value: y,
+            },
+        })
+    }
+
+    /// Decomposes `u1` and `u2` into bits, most significant first
+    fn scalar_bits(
+        &mut self,
+        u1: &AssignedInteger,
+        u2: &AssignedInteger,
+    ) -> Result<Vec<ScalarBits>, Error> {
+        let mut bits = vec![];
+        for limb in (0..LIMBS).rev() {
+            for j in 0..LIMB_BITS {
+                let row = self.offset;
+                self.config.q_bits.enable(self.region, row)?;
+                self.region.assign_fixed(
+                    || "prev",
+                    self.config.prev,
+                    row,
+                    || Value::known(Fp::from((j > 0) as u64)),
+                )?;
+                let shift = LIMB_BITS * limb + LIMB_BITS - 1 - j;
+                let mut cells = vec![];
+                for (scalar, [bit_col, acc_col]) in [(u1, [0, 1]), (u2, [2, 3])] {
+                    let bit = scalar
+                        .value
+                        .as_ref()
+                        .map(|v| Fp::from(v.bit(shift as u64) as u64));
+                    let acc = scalar
+                        .value
+                        .as_ref()
+                        .map(|v| big_to_felt(&((v >> shift) % (BigUint::one() << (j + 1)))));
+                    let bit = self.region.assign_advice(
+                        || "scalar bit",
+                        self.config.limbs[bit_col],
+                        row,
+                        || bit,
+                    )?;
+                    let acc = self.region.assign_advice(
+                        || "scalar accumulator",
+                        self.config.limbs[acc_col],
+                        row,
+                        || acc,
+                    )?;
+                    if j == LIMB_BITS - 1 {
+                        self.region
+                            .constrain_equal(acc.cell(), scalar.limbs[limb].cell())?;
+                    }
+                    cells.push(bit);
+                }
+                bits.push((cells[0].clone(), cells[1].clone()));
+                self.offset += 1;
+            }
+        }
+        Ok(bits)
+    }
+
+    /// Assigns the integer the digest is signed as, constrained to the cells of the digest
+    fn assign_digest(&mut self, digest: &[AssignedCell<Fp, Fp>]) -> Result<AssignedInteger, Error> {
+        match self.config.hash {
+            SignatureHash::Poseidon => {
+                let h = &digest[0];
+                let z = h.value().map(felt_to_big);
+                // z + d = r - 1, such that z is the canonical encoding of the hash
+                let max = felt_to_big(&-Fp::ONE);
+                let d = z
+                    .as_ref()
+                    .map(|z| if z <= &max { &max - z } else { BigUint::zero() });
+                let carries = z.as_ref().zip(d.as_ref()).map(|(z, d)| {
+                    std::array::from_fn::<_, LIMBS, _>(|i| {
+                        let mask = (BigUint::one() << (LIMB_BITS * (i + 1))) - 1u32;
+                        if i < LIMBS - 1 {
+                            big_to_felt(&(((z & &mask) + (d & &mask)) >> (LIMB_BITS * (i + 1))))
+                        } else {
+                            Fp::ZERO
+                        }
+                    })
+                });
+                let z = self.assign_integer(z)?;
+                let d = self.assign_integer(d)?;
+                let row = self.offset;
+                self.config.q_digest.enable(self.region, row)?;
+                self.copy_row(row, &z.limbs)?;
+                self.copy_row(row + 1, &d.limbs)?;
+                self.assign_row(
+                    row + 2,
+                    &std::array::from_fn(|i| carries.as_ref().map(|c| c[i])),
+                )?;
+                h.copy_advice(|| "digest", self.region, self.config.aux, row)?;
+                self.offset += 3;
+                Ok(z)
+            }
+            SignatureHash::Sha256 => {
+                let (hi, lo) = (&digest[0], &digest[1]);
+                let z = hi
+                    .value()
+                    .zip(lo.value())
+                    .map(|(hi, lo)| (felt_to_big(hi) << 128) + felt_to_big(lo));
+                let z = self.assign_integer(z)?;
+                let row = self.offset;
+                self.config.q_digest.enable(self.region, row)?;
+                self.copy_row(row, &z.limbs)?;
+                self.zero_row(row + 1)?;
+                lo.copy_advice(|| "digest", self.region, self.config.aux, row)?;
+                hi.copy_advice(|| "digest", self.region, self.config.aux, row + 1)?;
+                self.offset += 2;
+                Ok(z)
+            }
+        }
+    }
+
+    /// Verifies the signature `r, s` of the digest against the public key, the witness holding
+    /// the limbs of `r, s` and of the public key coordinates. Returns the public key limbs.
+    fn verify(
+        &mut self,
+        digest: &[AssignedCell<Fp, Fp>],
+        witness: &[Value<Fp>],
+    ) -> Result<Vec<AssignedCell<Fp, Fp>>, Error> {
+        let curve = self.curve;
+        let (p, n) = (BigInt::from(curve.p.clone()), BigInt::from(curve.n.clone()));
+        let zero = BigInt::zero();
+        let integer = |i: usize| -> Value<BigUint> {
+            witness[LIMBS * i..LIMBS * (i + 1)]
+                .iter()
+                .copied()
+                .collect::<Value<Vec<Fp>>>()
+                .map(|limbs| from_limbs(&limbs))
+        };
+
+        // the first range check and scalar decomposition rows query the previous row
+        self.zero_row(self.offset)?;
+        self.offset += 1;
+
+        // the public key is on the curve: x * x^2 = y^2 - 7
+        let key = AssignedPoint {
+            x: self.assign_integer(integer(2))?,
+            y: self.assign_integer(integer(3))?,
+        };
+        let xx = self.assign_integer(key.x.value.as_ref().map(|x| x * x % &curve.p))?;
+        let yy = self.assign_integer(key.y.value.as_ref().map(|y| y * y % &curve.p))?;
+        self.mul_check(
+            Modulus::Base,
+            &key.x,
+            &[(1, &key.x)],
+            &zero,
+            &[(1, &xx)],
+            &-&p,
+        )?;
+        self.mul_check(
+            Modulus::Base,
+            &key.y,
+            &[(1, &key.y)],
+            &zero,
+            &[(1, &yy)],
+            &-&p,
+        )?;
+        self.mul_check(
+            Modulus::Base,
+            &key.x,
+            &[(1, &xx)],
+            &zero,
+            &[(1, &yy)],
+            &(BigInt::from(-7) - &p),
+        )?;
+
+        let r = self.assign_integer(integer(0))?;
+        let s = self.assign_integer(integer(1))?;
+        let z = self.assign_digest(digest)?;
+
+        // w = 1 / s, u1 = z / s and u2 = r / s, where s and r are invertible
+        let w = self.assign_integer(s.value.as_ref().map(|s| inverse(s, &curve.n)))?;
+        let r_inv = self.assign_integer(r.value.as_ref().map(|r| inverse(r, &curve.n)))?;
+        let u1 = self.assign_integer(
+            w.value
+                .as_ref()
+                .zip(z.value.as_ref())
+                .map(|(w, z)| w * z % &curve.n),
+        )?;
+        let u2 = self.assign_integer(
+            w.value
+                .as_ref()
+                .zip(r.value.as_ref())
+                .map(|(w, r)| w * r % &curve.n),
+        )?;
+        let one_minus_n = BigInt::one() - &n;
+        self.mul_check(Modulus::Scalar, &s, &[(1, &w)], &zero, &[], &one_minus_n)?;
+        self.mul_check(
+            Modulus::Scalar,
+            &r,
+            &[(1, &r_inv)],
+            &zero,
+            &[],
+            &one_minus_n,
+        )?;
+        self.mul_check(Modulus::Scalar, &w, &[(1, &z)], &zero, &[(1, &u1)], &-&n)?;
+        self.mul_check(Modulus::Scalar, &w, &[(1, &r)], &zero, &[(1, &u2)], &-&n)?;
+
+        let bits = self.scalar_bits(&u1, &u2)?;
+
+        // the table of A + b1 * G + b2 * Q
+        let offset = self.assign_point_constant(&curve.a)?;
+        let offset_generator = self.assign_point_constant(&curve.a_plus_g)?;
+        let offset_key = self.add(&offset, &key)?;
+        let offset_both = self.add(&offset_generator, &key)?;
+        let table = [offset, offset_generator, offset_key, offset_both];
+
+        let mut acc = self.select(&table, &bits[0])?;
+        for bit in &bits[1..] {
+            let doubled = self.double(&acc)?;
+            let selected = self.select(&table, bit)?;
+            acc = self.add(&doubled, &selected)?;
+        }
+        let correction = self.assign_point_constant(&curve.correction)?;
+        let point = self.add(&acc, &correction)?;
+
+        // the abscissa of u1 * G + u2 * Q is r modulo n
+        self.mul_check(
+            Modulus::Scalar,
+            &point.x,
+            &[],
+            &BigInt::one(),
+            &[(1, &r)],
+            &-&n,
+        )?;
+
+        Ok(key.x.limbs.iter().chain(&key.y.limbs).cloned().collect())
+    }
+}
+
+/// ECDSA chip verifying a signature over a digest against a public key
+#[derive(Debug, Clone)]
+pub struct EcdsaChip {
+    config: EcdsaConfig,
+}
+
+impl EcdsaChip {
+    /// Configuration of the EcdsaChip
+    pub fn configure_with_optional_instance(
+        meta: &mut ConstraintSystem<Fp>,
+        hash: SignatureHash,
+        instance: Option<Column<Instance>>,
+    ) -> EcdsaConfig {
+        let limbs = [(); LIMBS].map(|_| meta.advice_column());
+        for col in &limbs {
+            meta.enable_equality(*col);
+        }
+        let bits = [(); LIMBS].map(|_| [(); CHUNK_BITS].map(|_| meta.advice_column()));
+        let aux = meta.advice_column();
+        meta.enable_equality(aux);
+
+        let shift = meta.fixed_column();
+        let prev = meta.fixed_column();
+        let beta = [(); 2].map(|_| meta.fixed_column());
+        let gamma = [(); 3].map(|_| meta.fixed_column());
+        let kb = [(); LIMBS].map(|_| meta.fixed_column());
+        let kc = [(); LIMBS].map(|_| meta.fixed_column());
+
+        let q_range = meta.selector();
+        let q_mul = [(); 2].map(|_| meta.selector());
+        let q_const = meta.selector();
+        let q_bits = meta.selector();
+        let q_select = meta.selector();
+        let q_digest = meta.selector();
+
+        meta.create_gate("ecdsa range check", |meta| {
+            let q = meta.query_selector(q_range);
+            let shift = meta.query_fixed(shift, Rotation::cur());
+            let prev = meta.query_fixed(prev, Rotation::cur());
+            let mut constraints = vec![];
+            for i in 0..LIMBS {
+                let chunk = bits[i]
+                    .iter()
+                    .map(|col| meta.query_advice(*col, Rotation::cur()))
+                    .collect_vec();
+                for bit in &chunk {
+                    constraints.push(q.clone() * bit.clone() * (bit.clone() - one()));
+                }
+                let acc = meta.query_advice(limbs[i], Rotation::cur());
+                let acc_prev = meta.query_advice(limbs[i], Rotation::prev());
+                constraints.push(
+                    q.clone() * (acc - (prev.clone() * acc_prev + shift.clone() * pack(&chunk))),
+                );
+            }
+            constraints
+        });
+
+        let curve = Curve::secp256k1();
+        for (selector, modulus) in q_mul.iter().zip([Modulus::Base, Modulus::Scalar]) {
+            let selector = *selector;
+            let m = curve.modulus(modulus);
+            let m_limbs = split(m).map(|l| big_to_felt(&l));
+            let m_native = big_to_felt(m);
+            let name = match modulus {
+                Modulus::Base => "ecdsa base field product",
+                Modulus::Scalar => "ecdsa scalar field product",
+            };
+            meta.create_gate(name, |meta| {
+                let q = meta.query_selector(selector);
+                let [a, b1, b2, quotient, c1, c2, c3, carries] =
+                    query_rows::<PRODUCT_ROWS>(meta, &limbs);
+                let beta = beta.map(|col| meta.query_fixed(col, Rotation::cur()));
+                let gamma = gamma.map(|col| meta.query_fixed(col, Rotation::cur()));
+                let kb = kb.map(|col| meta.query_fixed(col, Rotation::cur()));
+                let kc = kc.map(|col| meta.query_fixed(col, Rotation::cur()));
+
+                let b = (0..LIMBS)
+                    .map(|j| {
+                        beta[0].clone() * b1[j].clone()
+                            + beta[1].clone() * b2[j].clone()
+                            + kb[j].clone()
+                    })
+                    .collect_vec();
+                let c = (0..LIMBS)
+                    .map(|j| {
+                        gamma[0].clone() * c1[j].clone()
+                            + gamma[1].clone() * c2[j].clone()
+                            + gamma[2].clone() * c3[j].clone()
+                            + kc[j].clone()
+                    })
+                    .collect_vec();
+
+                // the limbs of a * b - q * m - c, up to the weight the carries vanish at
+                let t = (0..=LIMBS)
+                    .map(|k| {
+                        let mut t = c.get(k).map_or(zero(), |c| -c.clone());
+                        for i in 0..LIMBS {
+                            if k >= i && k - i < LIMBS {
+                                t = t + a[i].clone() * b[k - i].clone()
+                                    - quotient[i].clone() * m_limbs[k - i];
+                            }
+                        }
+                        t
+                    })
+                    .collect_vec();
+                let offset = Expression::Constant(Fp::from_u128(1 << CARRY_OFFSET));
+                let v = carries.map(|v| v - offset.clone());
+                let (base, base_2) = (limb_shift(1), limb_shift(2));
+
+                vec![
+                    q.clone() * (t[0].clone() + t[1].clone() * base - v[0].clone() * base_2),
+                    q.clone()
+                        * (v[0].clone() + t[2].clone() + t[3].clone() * base
+                            - v[1].clone() * base_2),
+                    q.clone() * (v[1].clone() + t[4].clone() - v[2].clone() * base),
+                    q * (compose(&a) * compose(&b) - compose(&quotient) * m_native - compose(&c)),
+                ]
+            });
+        }
+
+        meta.create_gate("ecdsa constant", |meta| {
+            let q = meta.query_selector(q_const);
+            (0..LIMBS)
+                .map(|i| {
+                    let limb = meta.query_advice(limbs[i], Rotation::cur());
+                    let constant = meta.query_fixed(kc[i], Rotation::cur());
+                    q.clone() * (limb - constant)
+                })
+                .collect_vec()
+        });
+
+        meta.create_gate("ecdsa scalar bits", |meta| {
+            let q = meta.query_selector(q_bits);
+            let prev = meta.query_fixed(prev, Rotation::cur());
+            let mut constraints = vec![];
+            for (bit, acc) in [(0, 1), (2, 3)] {
+                let bit = meta.query_advice(limbs[bit], Rotation::cur());
+                let acc_prev = meta.query_advice(limbs[acc], Rotation::prev());
+                let acc = meta.query_advice(limbs[acc], Rotation::cur());
+                constraints.push(q.clone() * bit.clone() * (bit.clone() - one()));
+                constraints.push(q.clone() * (acc - (prev.clone() * acc_prev * Fp::from(2) + bit)));
+            }
+            constraints
+        });
+
+        meta.create_gate("ecdsa table selection", |meta| {
+            let q = meta.query_selector(q_select);
+            let rows = query_rows::<SELECT_ROWS>(meta, &limbs);
+            let b1 = meta.query_advice(aux, Rotation::cur());
+            let b2 = meta.query_advice(aux, Rotation::next());
+            let mut constraints = vec![];
+            for coordinate in 0..2 {
+                for i in 0..LIMBS {
+                    let t = |k: usize| rows[2 * k + coordinate][i].clone();
+                    let selected = t(0)
+                        + b1.clone() * (t(1) - t(0))
+                        + b2.clone() * (t(2) - t(0))
+                        + b1.clone() * b2.clone() * (t(3) - t(2) - t(1) + t(0));
+                    constraints.push(q.clone() * (rows[8 + coordinate][i].clone() - selected));
+                }
+            }
+            constraints
+        });
+
+        match hash {
+            SignatureHash::Poseidon => {
+                let max = split(&felt_to_big(&-Fp::ONE)).map(|l| big_to_felt(&l));
+                meta.create_gate("ecdsa poseidon digest", |meta| {
+                    let q = meta.query_selector(q_digest);
+                    let [z, d, carries] = query_rows::<3>(meta, &limbs);
+                    let h = meta.query_advice(aux, Rotation::cur());
+                    let mut constraints = vec![q.clone() * (compose(&z) - h)];
+                    // z + d = r - 1 limb by limb, without overflowing
+                    for i in 0..LIMBS {
+                        let carry_in = if i > 0 {
+                            carries[i - 1].clone()
+                        } else {
+                            zero()
+                        };
+                        let carry_out = if i < LIMBS - 1 {
+                            carries[i].clone() * limb_shift(1)
+                        } else {
+                            zero()
+                        };
+                        constraints.push(
+                            q.clone()
+                                * (z[i].clone() + d[i].clone() + carry_in
+                                    - carry_out
+                                    - Expression::Constant(max[i])),
+                        );
+                    }
+                    for carry in carries.iter().take(LIMBS - 1) {
+                        constraints.push(q.clone() * carry.clone() * (carry.clone() - one()));
+                    }
+                    constraints
+                });
+            }
+            SignatureHash::Sha256 => {
+                meta.create_gate("ecdsa sha256 digest", |meta| {
+                    let q = meta.query_selector(q_digest);
+                    let z = limbs.map(|col| meta.query_advice(col, Rotation::cur()));
+                    let lo = meta.query_advice(aux, Rotation::cur());
+                    let hi = meta.query_advice(aux, Rotation::next());
+                    let base = limb_shift(1);
+                    vec![
+                        q.clone() * (z[0].clone() + z[1].clone() * base - lo),
+                        q * (z[2].clone() + z[3].clone() * base - hi),
+                    ]
+                });
+            }
+        }
+
+        EcdsaConfig {
+            limbs,
+            bits,
+            aux,
+            shift,
+            prev,
+            beta,
+            gamma,
+            kb,
+            kc,
+            q_range,
+            q_mul,
+            q_const,
+            q_bits,
+            q_select,
+            q_digest,
+            hash,
+            instance,
+        }
+    }
+
+    /// Number of rows of the verification of a signature over a digest of the given hash
+    pub fn rows(hash: SignatureHash) -> usize {
+        let digest = match hash {
+            SignatureHash::Poseidon => 2 * INTEGER_ROWS + 3,
+            SignatureHash::Sha256 => INTEGER_ROWS + 2,
+        };
+        let step = POINT_ROWS + SELECT_ROWS + POINT_ROWS;
+        // the leading row, the public key and its curve equation
+        1 + 4 * INTEGER_ROWS + 3 * MUL_ROWS
+            // r, s and the digest, then w, 1 / r, u1 and u2
+            + 2 * INTEGER_ROWS + digest + 4 * (INTEGER_ROWS + MUL_ROWS)
+            + SCALAR_BITS
+            // the table, the double-and-add and the correction of the offset
+            + 4 + 2 * POINT_ROWS
+            + SELECT_ROWS + (SCALAR_BITS - 1) * step
+            + 2 + POINT_ROWS
+            // the abscissa of the result
+            + MUL_ROWS
+    }
+}
+
+impl Module<Fp> for EcdsaChip {
+    type Config = EcdsaConfig;
+    type InputAssignments = Vec<AssignedCell<Fp, Fp>>;
+    type RunInputs = (Word, EcdsaSignature);
+    type Params = SignatureHash;
+
+    fn name(&self) -> &'static str {
+        "ECDSA"
+    }
+
+    fn instance_increment_input(&self) -> Vec<usize> {
+        vec![PUBLIC_KEY_LIMBS]
+    }
+
+    /// Constructs a new EcdsaChip
+    fn new(config: Self::Config) -> Self {
+        Self { config }
+    }
+
+    /// Configuration of the EcdsaChip
+    fn configure(meta: &mut ConstraintSystem<Fp>, hash: Self::Params) -> Self::Config {
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        Self::configure_with_optional_instance(meta, hash, Some(instance))
+    }
+
+    /// Takes the cells of the digest and the witnessed limbs of the signature and of the
+    /// public key, and lays out the whole verification in a single region. Returns the cells
+    /// of the public key limbs.
+    fn layout_inputs(
+        &self,
+        layouter: &mut impl Layouter<Fp>,
+        input: &[ValTensor<Fp>],
+        _: &mut ConstantsMap<Fp>,
+    ) -> Result<Self::InputAssignments, ModuleError> {
+        if input.len() != 2 {
+            return Err(ModuleError::InputWrongLength(input.len()));
+        }
+        let digest = match &input[0] {
+            ValTensor::Value { inner, .. } => inner
+                .iter()
+                .map(|v| match v {
+                    ValType::PrevAssigned(c) | ValType::AssignedConstant(c, ..) => Ok(c.clone()),
+                    e => Err(ModuleError::WrongInputType(
+                        format!("{:?}", e),
+                        "PrevAssigned".to_string(),
+                    )),
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+            e => {
+                return Err(ModuleError::WrongInputType(
+                    format!("{:?}", e),
+                    "PrevAssigned".to_string(),
+                ))
+            }
+        };
+        let digest_len = match self.config.hash {
+            SignatureHash::Poseidon => 1,
+            SignatureHash::Sha256 => 2,
+        };
+        if digest.len() != digest_len {
+            return Err(ModuleError::InputWrongLength(digest.len()));
+        }
+        let witness = input[1]
+            .get_inner()
+            .map_err(|e| ModuleError::WrongInputType(format!("{:?}", e), "Value".to_string()))?
+            .to_vec();
+        if witness.len() != WITNESS_LIMBS {
+            return Err(ModuleError::InputWrongLength(witness.len()));
+        }
+
+        let start_time = instant::Instant::now();
+        let curve = Curve::secp256k1();
+        let res = layouter.assign_region(
+            || "ecdsa",
+            |mut region| {
+                let mut ecdsa = EcdsaRegion {
+                    config: &self.config,
+                    region: &mut region,
+                    curve: &curve,
+                    offset: 0,
+                };
+                ecdsa.verify(&digest, &witness)
+            },
+        );
+        log::trace!("ecdsa layout took: {:?}", start_time.elapsed());
+        res.map_err(|e| e.into())
+    }
+
+    /// Lays out the verification and constrains the public key limbs to the instance column,
+    /// returning them.
+    fn layout(
+        &self,
+        layouter: &mut impl Layouter<Fp>,
+        input: &[ValTensor<Fp>],
+        row_offset: usize,
+        constants: &mut ConstantsMap<Fp>,
+    ) -> Result<ValTensor<Fp>, ModuleError> {
+        let key = self.layout_inputs(layouter, input, constants)?;
+
+        if let Some(instance) = self.config.instance {
+            for (i, limb) in key.iter().enumerate() {
+                layouter.constrain_instance(limb.cell(), instance, row_offset + i)?;
+            }
+        }
+
+        Ok(Tensor::from(key.into_iter().map(ValType::from)).into())
+    }
+
+    /// Verifies the signature natively, returning the public key limbs
+    fn run((digest, signature): Self::RunInputs) -> Result<Vec<Vec<Fp>>, ModuleError> {
+        let curve = Curve::secp256k1();
+        let [x, y] = signature.public_key.map(|c| c.to_big());
+        if !curve.verify(
+            &digest.to_big(),
+            &signature.r.to_big(),
+            &signature.s.to_big(),
+            &(x, y),
+        ) {
+            return Err(ModuleError::InvalidSignature(
+                "the signature does not verify against its public key".to_string(),
+            ));
+        }
+        Ok(vec![signature.public_key_instances()])
+    }
+
+    /// The rows of a verification over a Poseidon digest, which is the larger of the two
+    fn num_rows(_: usize) -> usize {
+        Self::rows(SignatureHash::Poseidon)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::circuit::modules::ModulePlanner;
+
+    use super::*;
+
+    use std::collections::HashMap;
+
+    use halo2_proofs::circuit::{Layouter, Value};
+    use halo2_proofs::plonk::{Circuit, ConstraintSystem};
+    use k256::ecdsa::SigningKey;
+    use k256::elliptic_curve::sec1::ToEncodedPoint;
+
+    fn sign(digest: &Word) -> Vec<u8> {
+        let key = SigningKey::from_slice(&[7u8; 32]).unwrap();
+        let (signature, recovery_id) = key.sign_prehash_recoverable(&digest.0).unwrap();
+        let mut bytes = signature.to_bytes().to_vec();
+        bytes.push(recovery_id.to_byte());
+        bytes
+    }
+
+    struct EcdsaCircuit {
+        digest: Value<Fp>,
+        witness: Vec<Value<Fp>>,
+    }
+
+    impl Circuit<Fp> for EcdsaCircuit {
+        type Config = (EcdsaConfig, Column<Advice>);
+        type FloorPlanner = ModulePlanner;
+        type Params = ();
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                digest: Value::unknown(),
+                witness: vec![Value::unknown(); WITNESS_LIMBS],
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let digest = meta.advice_column();
+            meta.enable_equality(digest);
+            (EcdsaChip::configure(meta, SignatureHash::Poseidon), digest)
+        }
+
+        fn synthesize(
+            &self,
+            (config, column): Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let digest = layouter.assign_region(
+                || "digest",
+                |mut region| region.assign_advice(|| "digest", column, 0, || self.digest),
+            )?;
+            let digest: Tensor<ValType<Fp>> =
+                vec![ValType::PrevAssigned(digest)].into_iter().into();
+            let witness: Tensor<ValType<Fp>> = self.witness.iter().map(|w| (*w).into()).into();
+
+            let chip = EcdsaChip::new(config);
+            chip.layout(
+                &mut layouter,
+                &[digest.into(), witness.into()],
+                0,
+                &mut HashMap::new(),
+            )?;
+            Ok(())
+        }
+    }
+
+    fn circuit() -> (EcdsaCircuit, EcdsaSignature) {
+        let hash = Fp::from(0x5eed);
+        let digest = Word::from_hash(SignatureHash::Poseidon, &[hash]);
+        let signature = EcdsaSignature::recover(&digest, &sign(&digest)).unwrap();
+        let circuit = EcdsaCircuit {
+            digest: Value::known(hash),
+            witness: signature
+                .witness_limbs()
+                .into_iter()
+                .map(Value::known)
+                .collect(),
+        };
+        (circuit, signature)
+    }
+
+    #[test]
+    fn ecdsa_recover() {
+        let digest = Word::from_hex("0x1234").unwrap();
+        let signature = EcdsaSignature::recover(&digest, &sign(&digest)).unwrap();
+
+        let key = SigningKey::from_slice(&[7u8; 32]).unwrap();
+        let point = k256::PublicKey::from(key.verifying_key()).to_encoded_point(false);
+        assert_eq!(&signature.public_key[0].0[..], &point.x().unwrap()[..]);
+        assert_eq!(&signature.public_key[1].0[..], &point.y().unwrap()[..]);
+
+        // the signature doesn't verify over another digest
+        let other = Word::from_hex("0x1235").unwrap();
+        assert!(EcdsaChip::run((other, signature)).is_err());
+        assert!(EcdsaSignature::recover(&digest, &sign(&digest)[..64]).is_err());
+    }
+
+    #[test]
+    fn ecdsa_verification() {
+        let (circuit, signature) = circuit();
+        assert!(EcdsaChip::rows(SignatureHash::Poseidon) < 1 << 16);
+
+        let k = 16;
+        let prover =
+            halo2_proofs::dev::MockProver::run(k, &circuit, vec![signature.public_key_instances()])
+                .unwrap();
+        assert_eq!(prover.verify(), Ok(()))
+    }
+
+    #[test]
+    fn ecdsa_verification_wrong_key() {
+        let (circuit, signature) = circuit();
+        let mut instances = signature.public_key_instances();
+        instances[0] += Fp::ONE;
+
+        let k = 16;
+        let prover = halo2_proofs::dev::MockProver::run(k, &circuit, vec![instances]).unwrap();
+        assert!(prover.verify().is_err())
+    }
+
+    #[test]
+    fn ecdsa_verification_wrong_digest() {
+        let (mut circuit, signature) = circuit();
+        circuit.digest = circuit.digest.map(|d| d + Fp::ONE);
+
+        let k = 16;
+        let prover =
+            halo2_proofs::dev::MockProver::run(k, &circuit, vec![signature.public_key_instances()])
+                .unwrap();
+        assert!(prover.verify().is_err())
+    }
+}
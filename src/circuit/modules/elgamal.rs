@@ -0,0 +1,1014 @@
+/*
+An ElGamal chip encrypting a message of field elements to a recipient public key, over Grumpkin, the curve
+`y^2 = x^3 - 17` whose base field is the native field.
+
+The encryption of a message `m` under the public key `P = sk * G` with the ephemeral randomness `r` is the point
+`c1 = r * G` and the elements `c2_i = m_i + H(H(s), i)`, where `s = r * P` is the shared secret and `H` the
+Poseidon hash of two elements. The recipient recovers `s = sk * c1` and subtracts the masks.
+
+The scalar multiplications are a double-and-add over the 254 bits of `r`, one row per doubling and one per
+addition, the point of the previous row being the input of the formulas of the current row. The affine formulas
+are incomplete: the offset point `A` is added at every step, keeping the accumulator away from the exceptional
+cases, and its multiple is subtracted at the end. Both multiplications share the bits of `r`.
+*/
+
+use halo2_gadgets::poseidon::primitives::{ConstantLength, Hash, Spec};
+use halo2_proofs::arithmetic::Field;
+use halo2_proofs::halo2curves::bn256::Fr as Fp;
+use halo2_proofs::poly::Rotation;
+use halo2_proofs::{circuit::*, plonk::*};
+use halo2curves::ff::PrimeField;
+use rand::RngCore;
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use std::marker::PhantomData;
+
+use crate::circuit::region::ConstantsMap;
+use crate::tensor::{Tensor, ValTensor, ValType};
+
+use super::errors::ModuleError;
+use super::poseidon::{PoseidonChip, PoseidonConfig};
+use super::Module;
+
+/// The number of instance columns used by the encryption
+pub const NUM_INSTANCE_COLUMNS: usize = 1;
+/// Number of instances preceding the ciphertext of the message, the coordinates of the public key
+/// and of `c1`
+pub const HEADER_INSTANCES: usize = 4;
+/// Number of witnessed values, the randomness and the coordinates of the public key
+pub const WITNESS_LEN: usize = 3;
+/// Number of bits of the randomness
+const SCALAR_BITS: usize = 254;
+/// Number of rows of a scalar multiplication: the point, its sum with the offset, the initial
+/// accumulator, a doubling and an addition per bit and the correction of the offset
+const MUL_ROWS: usize = 3 + 2 * SCALAR_BITS + 1;
+/// The constant of the curve equation `y^2 = x^3 - 17`
+const CURVE_B: u64 = 17;
+
+/// A point of Grumpkin in affine coordinates
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CurvePoint {
+    ///
+    pub x: Fp,
+    ///
+    pub y: Fp,
+}
+
+impl CurvePoint {
+    /// The generator of Grumpkin
+    pub fn generator() -> Self {
+        CurvePoint {
+            x: Fp::ONE,
+            y: Fp::from_raw([
+                0x833fc48d823f272c,
+                0x2d270d45f1181294,
+                0xcf135e7506a45d63,
+                0x2,
+            ]),
+        }
+    }
+
+    /// Whether the point is on the curve
+    pub fn is_on_curve(&self) -> bool {
+        self.y.square() == self.x.square() * self.x - Fp::from(CURVE_B)
+    }
+
+    /// The offset point of the scalar multiplications, of abscissa 2 and of even ordinate
+    fn offset() -> Self {
+        let x = Fp::from(2);
+        let y = (x.square() * x - Fp::from(CURVE_B))
+            .sqrt()
+            .unwrap_or_else(|| unreachable!());
+        let y = if bool::from(y.is_odd()) { -y } else { y };
+        CurvePoint { x, y }
+    }
+
+    /// `-(2^255 - 1) * A`, cancelling the offsets added along the double-and-add, which starts
+    /// from `A` and adds it at every step
+    fn correction() -> Self {
+        let a = Self::offset();
+        let mut acc = Some(a);
+        for _ in 0..SCALAR_BITS {
+            acc = point_add(point_add(acc, acc), Some(a));
+        }
+        acc.unwrap_or_else(|| unreachable!()).neg()
+    }
+
+    fn neg(&self) -> Self {
+        CurvePoint {
+            x: self.x,
+            y: -self.y,
+        }
+    }
+}
+
+/// The complete addition of two points, `None` being the point at infinity
+fn point_add(a: Option<CurvePoint>, b: Option<CurvePoint>) -> Option<CurvePoint> {
+    let (p, q) = match (a, b) {
+        (None, _) => return b,
+        (_, None) => return a,
+        (Some(p), Some(q)) => (p, q),
+    };
+    let lambda = if p.x == q.x {
+        if p.y + q.y == Fp::ZERO {
+            return None;
+        }
+        p.x.square() * Fp::from(3) * (p.y + p.y).invert().unwrap_or(Fp::ZERO)
+    } else {
+        (q.y - p.y) * (q.x - p.x).invert().unwrap_or(Fp::ZERO)
+    };
+    let x = lambda.square() - p.x - q.x;
+    let y = lambda * (p.x - x) - p.y;
+    Some(CurvePoint { x, y })
+}
+
+/// The little-endian bits of a scalar
+fn scalar_bits(k: &Fp) -> Vec<bool> {
+    let repr = k.to_repr();
+    (0..SCALAR_BITS)
+        .map(|i| (repr.as_ref()[i / 8] >> (i % 8)) & 1 == 1)
+        .collect()
+}
+
+fn point_mul(k: &Fp, point: &CurvePoint) -> Option<CurvePoint> {
+    let mut acc = None;
+    for bit in scalar_bits(k).into_iter().rev() {
+        acc = point_add(acc, acc);
+        if bit {
+            acc = point_add(acc, Some(*point));
+        }
+    }
+    acc
+}
+
+/// The slope of the sum of two points, the inverse of the difference of their abscissas and the
+/// sum itself, as witnessed by the addition rows
+fn add_witness(p: &CurvePoint, q: &CurvePoint) -> [Fp; 4] {
+    let inv = (q.x - p.x).invert().unwrap_or(Fp::ZERO);
+    let lambda = (q.y - p.y) * inv;
+    let x = lambda.square() - p.x - q.x;
+    let y = lambda * (p.x - x) - p.y;
+    [lambda, inv, x, y]
+}
+
+/// The slope of the tangent at a point and its double, as witnessed by the doubling rows
+fn double_witness(p: &CurvePoint) -> [Fp; 3] {
+    let lambda = p.x.square() * Fp::from(3) * (p.y + p.y).invert().unwrap_or(Fp::ZERO);
+    let x = lambda.square() - p.x - p.x;
+    let y = lambda * (p.x - x) - p.y;
+    [lambda, x, y]
+}
+
+/// The ciphertext of a message, `c1 = r * G` and the masked elements of the message
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ElGamalCiphertext {
+    /// the ephemeral public key
+    pub c1: CurvePoint,
+    /// the masked elements of the message
+    pub c2: Vec<Fp>,
+}
+
+impl ElGamalCiphertext {
+    /// The ciphertext as exposed in the instance column, following the public key
+    pub fn instances(&self) -> Vec<Fp> {
+        let mut instances = vec![self.c1.x, self.c1.y];
+        instances.extend(&self.c2);
+        instances
+    }
+
+    /// Reads a ciphertext from its instances
+    pub fn from_instances(instances: &[Fp]) -> Result<Self, ModuleError> {
+        if instances.len() < 2 {
+            return Err(ModuleError::InputWrongLength(instances.len()));
+        }
+        Ok(ElGamalCiphertext {
+            c1: CurvePoint {
+                x: instances[0],
+                y: instances[1],
+            },
+            c2: instances[2..].to_vec(),
+        })
+    }
+}
+
+/// The recipient public key, the private randomness and the ciphertext of the encrypted outputs
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EncryptionWitness {
+    /// the public key of the recipient
+    pub public_key: CurvePoint,
+    /// the private ephemeral randomness
+    pub randomness: Fp,
+    /// the ciphertext of the encrypted outputs
+    pub ciphertext: ElGamalCiphertext,
+}
+
+impl EncryptionWitness {
+    /// The public key and the ciphertext, as exposed in the instance column
+    pub fn instances(&self) -> Vec<Fp> {
+        let mut instances = vec![self.public_key.x, self.public_key.y];
+        instances.extend(self.ciphertext.instances());
+        instances
+    }
+
+    /// The randomness and the coordinates of the public key, as witnessed in the circuit
+    pub fn witness(&self) -> Vec<Fp> {
+        vec![self.randomness, self.public_key.x, self.public_key.y]
+    }
+}
+
+/// The outputs encrypted to a recipient, in the circuit
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), derive(JsonSchema))]
+pub struct OutputEncryption {
+    /// indices of the encrypted outputs, encrypted in this order as a single message
+    pub outputs: Vec<usize>,
+    /// number of elements of each encrypted output
+    pub lens: Vec<usize>,
+}
+
+#[derive(Debug, Clone)]
+/// Configuration for the ElGamal chip, the masks are hashed in the columns of the Poseidon config
+pub struct ElGamalConfig<const WIDTH: usize, const RATE: usize> {
+    ///
+    pub poseidon: PoseidonConfig<WIDTH, RATE>,
+    /// the coordinates of the point of a row, and the message element and mask of a masking row
+    pub point: [Column<Advice>; 2],
+    /// the slope of an addition or a doubling, and the masked element of a masking row
+    pub lambda: Column<Advice>,
+    /// the inverse of the difference of the abscissas of an addition
+    pub inv: Column<Advice>,
+    /// the bit of the randomness of an addition
+    pub bit: Column<Advice>,
+    /// the point added when the bit is set
+    pub table: [Column<Advice>; 2],
+    /// the point added when the bit is unset
+    pub base: [Column<Fixed>; 2],
+    /// checks the point of a row is on the curve
+    pub q_on_curve: Selector,
+    /// doubles the point of the previous row
+    pub q_double: Selector,
+    /// adds the table or the base point to the point of the previous row, selected by the bit
+    pub q_add: Selector,
+    /// adds the base point to the point of the previous row
+    pub q_add_base: Selector,
+    /// masks an element of the message
+    pub q_mask: Selector,
+    ///
+    pub instance: Option<Column<Instance>>,
+}
+
+type InputAssignments = (Vec<AssignedCell<Fp, Fp>>, Vec<AssignedCell<Fp, Fp>>);
+
+/// ElGamalChip encrypts a message to a public key, hashing the masks with the Poseidon chip
+#[derive(Debug, Clone)]
+pub struct ElGamalChip<S: Spec<Fp, WIDTH, RATE> + Sync, const WIDTH: usize, const RATE: usize> {
+    config: ElGamalConfig<WIDTH, RATE>,
+    _marker: PhantomData<S>,
+}
+
+/// The bits of the randomness of a scalar multiplication, witnessed by the first one and copied by
+/// the second one
+enum Bits<'a> {
+    Witness(Value<Vec<bool>>),
+    Copy(&'a [AssignedCell<Fp, Fp>]),
+}
+
+/// A bit of the randomness, witnessed or copied
+enum Bit<'a> {
+    Witness(Value<bool>),
+    Copy(&'a AssignedCell<Fp, Fp>),
+}
+
+/// The cells of the coordinates of a point, along with its value
+#[derive(Debug, Clone)]
+struct AssignedPoint {
+    cells: [AssignedCell<Fp, Fp>; 2],
+    value: Value<CurvePoint>,
+}
+
+/// Lays out the scalar multiplications row after row, in a single region as the formulas of a row
+/// query the point of the previous row
+struct ElGamalRegion<'a, 'r, const WIDTH: usize, const RATE: usize> {
+    config: &'a ElGamalConfig<WIDTH, RATE>,
+    region: &'a mut Region<'r, Fp>,
+    offset: usize,
+}
+
+impl<const WIDTH: usize, const RATE: usize> ElGamalRegion<'_, '_, WIDTH, RATE> {
+    fn assign_point(&mut self, value: Value<CurvePoint>) -> Result<AssignedPoint, Error> {
+        let [x, y] = self.config.point;
+        let row = self.offset;
+        let cells = [
+            self.region
+                .assign_advice(|| "x", x, row, || value.map(|p| p.x))?,
+            self.region
+                .assign_advice(|| "y", y, row, || value.map(|p| p.y))?,
+        ];
+        self.offset += 1;
+        Ok(AssignedPoint { cells, value })
+    }
+
+    /// Assigns a point of the curve, checked to be on it
+    fn load_point(&mut self, value: Value<CurvePoint>) -> Result<AssignedPoint, Error> {
+        self.config.q_on_curve.enable(self.region, self.offset)?;
+        self.assign_point(value)
+    }
+
+    fn constant_point(&mut self, point: CurvePoint) -> Result<AssignedPoint, Error> {
+        let [x, y] = self.config.point;
+        let row = self.offset;
+        let cells = [
+            self.region
+                .assign_advice_from_constant(|| "x", x, row, point.x)?,
+            self.region
+                .assign_advice_from_constant(|| "y", y, row, point.y)?,
+        ];
+        self.offset += 1;
+        Ok(AssignedPoint {
+            cells,
+            value: Value::known(point),
+        })
+    }
+
+    fn assign_base(&mut self, base: CurvePoint) -> Result<(), Error> {
+        let [bx, by] = self.config.base;
+        self.region
+            .assign_fixed(|| "base x", bx, self.offset, || Value::known(base.x))?;
+        self.region
+            .assign_fixed(|| "base y", by, self.offset, || Value::known(base.y))?;
+        Ok(())
+    }
+
+    /// Adds the base point to the point of the previous row
+    fn add_base(&mut self, prev: &AssignedPoint, base: CurvePoint) -> Result<AssignedPoint, Error> {
+        self.config.q_add_base.enable(self.region, self.offset)?;
+        self.assign_base(base)?;
+        self.assign_sum(prev, Value::known(base))
+    }
+
+    /// Assigns the slope, the inverse and the sum of the point of the previous row and of an
+    /// operand, on the current row
+    fn assign_sum(
+        &mut self,
+        prev: &AssignedPoint,
+        operand: Value<CurvePoint>,
+    ) -> Result<AssignedPoint, Error> {
+        let witness = prev.value.zip(operand).map(|(p, q)| add_witness(&p, &q));
+        let row = self.offset;
+        self.region.assign_advice(
+            || "lambda",
+            self.config.lambda,
+            row,
+            || witness.map(|w| w[0]),
+        )?;
+        self.region
+            .assign_advice(|| "inv", self.config.inv, row, || witness.map(|w| w[1]))?;
+        let sum = witness.map(|w| CurvePoint { x: w[2], y: w[3] });
+        self.assign_point(sum)
+    }
+
+    fn double(&mut self, prev: &AssignedPoint) -> Result<AssignedPoint, Error> {
+        self.config.q_double.enable(self.region, self.offset)?;
+        let witness = prev.value.map(|p| double_witness(&p));
+        self.region.assign_advice(
+            || "lambda",
+            self.config.lambda,
+            self.offset,
+            || witness.map(|w| w[0]),
+        )?;
+        self.assign_point(witness.map(|w| CurvePoint { x: w[1], y: w[2] }))
+    }
+
+    /// Adds the table point if the bit is set and the base point otherwise, returning the sum and
+    /// the cell of the bit
+    fn add(
+        &mut self,
+        prev: &AssignedPoint,
+        table: &AssignedPoint,
+        base: CurvePoint,
+        bit: Bit,
+    ) -> Result<(AssignedPoint, AssignedCell<Fp, Fp>), Error> {
+        let row = self.offset;
+        self.config.q_add.enable(self.region, row)?;
+        self.assign_base(base)?;
+        for (cell, col) in table.cells.iter().zip(self.config.table) {
+            cell.copy_advice(|| "table", self.region, col, row)?;
+        }
+        let (bit, value) = match bit {
+            Bit::Witness(value) => {
+                let cell = self.region.assign_advice(
+                    || "bit",
+                    self.config.bit,
+                    row,
+                    || value.map(|b| Fp::from(b as u64)),
+                )?;
+                (cell, value)
+            }
+            Bit::Copy(cell) => {
+                let copy = cell.copy_advice(|| "bit", self.region, self.config.bit, row)?;
+                (copy, cell.value().map(|b| *b == Fp::ONE))
+            }
+        };
+        let operand = value
+            .zip(table.value)
+            .map(|(b, t)| if b { t } else { base });
+        Ok((self.assign_sum(prev, operand)?, bit))
+    }
+
+    /// Multiplies the point of the previous row by the randomness, returning the product and the
+    /// cells of the bits of the randomness, most significant first
+    fn mul(
+        &mut self,
+        point: &AssignedPoint,
+        bits: Bits,
+    ) -> Result<(AssignedPoint, Vec<AssignedCell<Fp, Fp>>), Error> {
+        let offset = CurvePoint::offset();
+        let table = self.add_base(point, offset)?;
+        let mut acc = self.constant_point(offset)?;
+        let mut cells = vec![];
+        for i in 0..SCALAR_BITS {
+            let doubled = self.double(&acc)?;
+            let bit = match &bits {
+                Bits::Witness(bits) => Bit::Witness(bits.as_ref().map(|b| b[SCALAR_BITS - 1 - i])),
+                Bits::Copy(copies) => Bit::Copy(&copies[i]),
+            };
+            let (sum, bit) = self.add(&doubled, &table, offset, bit)?;
+            acc = sum;
+            cells.push(bit);
+        }
+        let product = self.add_base(&acc, CurvePoint::correction())?;
+        Ok((product, cells))
+    }
+}
+
+/// Constraints of the sum of the point of the previous row and of an operand, landing on the point
+/// of the current row
+fn add_constraints(
+    prev: [Expression<Fp>; 2],
+    operand: [Expression<Fp>; 2],
+    lambda: Expression<Fp>,
+    inv: Expression<Fp>,
+    sum: [Expression<Fp>; 2],
+) -> Vec<Expression<Fp>> {
+    let [xp, yp] = prev;
+    let [xo, yo] = operand;
+    let [x, y] = sum;
+    vec![
+        // lambda * (xo - xp) = yo - yp
+        lambda.clone() * (xo.clone() - xp.clone()) - (yo - yp.clone()),
+        // the abscissas differ
+        inv * (xo.clone() - xp.clone()) - Expression::Constant(Fp::ONE),
+        lambda.clone() * lambda.clone() - xp.clone() - xo - x.clone(),
+        lambda * (xp - x) - yp - y,
+    ]
+}
+
+impl<S: Spec<Fp, WIDTH, RATE> + Sync, const WIDTH: usize, const RATE: usize>
+    ElGamalChip<S, WIDTH, RATE>
+{
+    /// Configures the chip to hash the masks in the columns of an existing Poseidon config
+    pub fn configure_with_poseidon(
+        meta: &mut ConstraintSystem<Fp>,
+        poseidon: PoseidonConfig<WIDTH, RATE>,
+        instance: Option<Column<Instance>>,
+    ) -> ElGamalConfig<WIDTH, RATE> {
+        let point = [(); 2].map(|_| meta.advice_column());
+        let lambda = meta.advice_column();
+        let inv = meta.advice_column();
+        let bit = meta.advice_column();
+        let table = [(); 2].map(|_| meta.advice_column());
+        for col in point.iter().chain(&table).chain([&lambda, &bit]) {
+            meta.enable_equality(*col);
+        }
+        let base = [(); 2].map(|_| meta.fixed_column());
+
+        let q_on_curve = meta.selector();
+        let q_double = meta.selector();
+        let q_add = meta.selector();
+        let q_add_base = meta.selector();
+        let q_mask = meta.selector();
+
+        let query_point = |meta: &mut VirtualCells<'_, Fp>, rotation: Rotation| {
+            point.map(|col| meta.query_advice(col, rotation))
+        };
+
+        meta.create_gate("elgamal on curve", |meta| {
+            let q = meta.query_selector(q_on_curve);
+            let [x, y] = query_point(meta, Rotation::cur());
+            vec![
+                q * (y.clone() * y - x.clone() * x.clone() * x
+                    + Expression::Constant(Fp::from(CURVE_B))),
+            ]
+        });
+
+        meta.create_gate("elgamal double", |meta| {
+            let q = meta.query_selector(q_double);
+            let [xp, yp] = query_point(meta, Rotation::prev());
+            let [x, y] = query_point(meta, Rotation::cur());
+            let lambda = meta.query_advice(lambda, Rotation::cur());
+            [
+                // 2 * yp * lambda = 3 * xp^2
+                lambda.clone() * yp.clone() * Fp::from(2) - xp.clone() * xp.clone() * Fp::from(3),
+                lambda.clone() * lambda.clone() - xp.clone() * Fp::from(2) - x.clone(),
+                lambda * (xp - x) - yp - y,
+            ]
+            .map(|c| q.clone() * c)
+        });
+
+        meta.create_gate("elgamal add", |meta| {
+            let q = meta.query_selector(q_add);
+            let prev = query_point(meta, Rotation::prev());
+            let sum = query_point(meta, Rotation::cur());
+            let lambda = meta.query_advice(lambda, Rotation::cur());
+            let inv = meta.query_advice(inv, Rotation::cur());
+            let bit = meta.query_advice(bit, Rotation::cur());
+            let table = table.map(|col| meta.query_advice(col, Rotation::cur()));
+            let base = base.map(|col| meta.query_fixed(col, Rotation::cur()));
+            let operand = [0, 1]
+                .map(|i| base[i].clone() + bit.clone() * (table[i].clone() - base[i].clone()));
+            let mut constraints = vec![bit.clone() * (bit - Expression::Constant(Fp::ONE))];
+            constraints.extend(add_constraints(prev, operand, lambda, inv, sum));
+            constraints
+                .into_iter()
+                .map(|c| q.clone() * c)
+                .collect::<Vec<_>>()
+        });
+
+        meta.create_gate("elgamal add base", |meta| {
+            let q = meta.query_selector(q_add_base);
+            let prev = query_point(meta, Rotation::prev());
+            let sum = query_point(meta, Rotation::cur());
+            let lambda = meta.query_advice(lambda, Rotation::cur());
+            let inv = meta.query_advice(inv, Rotation::cur());
+            let base = base.map(|col| meta.query_fixed(col, Rotation::cur()));
+            add_constraints(prev, base, lambda, inv, sum)
+                .into_iter()
+                .map(|c| q.clone() * c)
+                .collect::<Vec<_>>()
+        });
+
+        meta.create_gate("elgamal mask", |meta| {
+            let q = meta.query_selector(q_mask);
+            let [message, mask] = query_point(meta, Rotation::cur());
+            let masked = meta.query_advice(lambda, Rotation::cur());
+            vec![q * (message + mask - masked)]
+        });
+
+        ElGamalConfig {
+            poseidon,
+            point,
+            lambda,
+            inv,
+            bit,
+            table,
+            base,
+            q_on_curve,
+            q_double,
+            q_add,
+            q_add_base,
+            q_mask,
+            instance,
+        }
+    }
+
+    /// The public key of a secret key
+    pub fn public_key(secret_key: &Fp) -> Result<CurvePoint, ModuleError> {
+        point_mul(secret_key, &CurvePoint::generator())
+            .ok_or_else(|| ModuleError::InvalidEncryption("the secret key is zero".to_string()))
+    }
+
+    /// Generates a secret key and its public key
+    pub fn gen_keys(rng: &mut impl RngCore) -> (Fp, CurvePoint) {
+        loop {
+            let secret_key = Fp::random(&mut *rng);
+            if let Ok(public_key) = Self::public_key(&secret_key) {
+                return (secret_key, public_key);
+            }
+        }
+    }
+
+    /// The mask of the `i`-th element of a message, derived from the shared secret
+    fn masks(shared: &CurvePoint, len: usize) -> Vec<Fp> {
+        let key = Hash::<_, S, ConstantLength<2>, WIDTH, RATE>::init().hash([shared.x, shared.y]);
+        (0..len)
+            .map(|i| {
+                Hash::<_, S, ConstantLength<2>, WIDTH, RATE>::init().hash([key, Fp::from(i as u64)])
+            })
+            .collect()
+    }
+
+    /// Encrypts a message to a public key with some randomness
+    pub fn encrypt(
+        public_key: &CurvePoint,
+        randomness: &Fp,
+        message: &[Fp],
+    ) -> Result<ElGamalCiphertext, ModuleError> {
+        if !public_key.is_on_curve() {
+            return Err(ModuleError::InvalidEncryption(
+                "the public key is not on the curve".to_string(),
+            ));
+        }
+        let c1 = point_mul(randomness, &CurvePoint::generator());
+        let shared = point_mul(randomness, public_key);
+        let (Some(c1), Some(shared)) = (c1, shared) else {
+            return Err(ModuleError::InvalidEncryption(
+                "the randomness is zero".to_string(),
+            ));
+        };
+        let c2 = message
+            .iter()
+            .zip(Self::masks(&shared, message.len()))
+            .map(|(m, mask)| *m + mask)
+            .collect();
+        Ok(ElGamalCiphertext { c1, c2 })
+    }
+
+    /// Decrypts a ciphertext with the secret key of the public key it was encrypted to
+    pub fn decrypt(
+        ciphertext: &ElGamalCiphertext,
+        secret_key: &Fp,
+    ) -> Result<Vec<Fp>, ModuleError> {
+        if !ciphertext.c1.is_on_curve() {
+            return Err(ModuleError::InvalidEncryption(
+                "c1 is not on the curve".to_string(),
+            ));
+        }
+        let shared = point_mul(secret_key, &ciphertext.c1)
+            .ok_or_else(|| ModuleError::InvalidEncryption("the secret key is zero".to_string()))?;
+        Ok(ciphertext
+            .c2
+            .iter()
+            .zip(Self::masks(&shared, ciphertext.c2.len()))
+            .map(|(c, mask)| *c - mask)
+            .collect())
+    }
+
+    /// Number of rows of the masks in the Poseidon columns, for a message of some length
+    pub fn poseidon_rows(input_len: usize) -> usize {
+        // a hash per mask and the key, each after the row of its preimage, and the padding row
+        (input_len + 1) * (PoseidonChip::<S, WIDTH, RATE, 2>::num_rows(1) + 1) + 1
+    }
+
+    /// Loads an element of the message in the message column of a masking row
+    fn load_element(
+        &self,
+        region: &mut Region<'_, Fp>,
+        value: &ValType<Fp>,
+        row: usize,
+        constants: &mut ConstantsMap<Fp>,
+    ) -> Result<AssignedCell<Fp, Fp>, ModuleError> {
+        let col = self.config.point[0];
+        match value {
+            ValType::Value(v) => Ok(region.assign_advice(|| "message", col, row, || *v)?),
+            ValType::PrevAssigned(v) | ValType::AssignedConstant(v, ..) => {
+                Ok(v.copy_advice(|| "message", region, col, row)?)
+            }
+            ValType::Constant(f) => {
+                let res = region.assign_advice_from_constant(|| "message", col, row, *f)?;
+                constants.insert(*f, ValType::AssignedConstant(res.clone(), *f));
+                Ok(res)
+            }
+            e => Err(ModuleError::WrongInputType(
+                format!("{:?}", e),
+                "PrevAssigned".to_string(),
+            )),
+        }
+    }
+}
+
+impl<S: Spec<Fp, WIDTH, RATE> + Sync, const WIDTH: usize, const RATE: usize> Module<Fp>
+    for ElGamalChip<S, WIDTH, RATE>
+{
+    type Config = ElGamalConfig<WIDTH, RATE>;
+    /// the cells of the public key, of `c1` and of the shared secret
+    type InputAssignments = [[AssignedCell<Fp, Fp>; 2]; 3];
+    /// the message, the public key and the randomness
+    type RunInputs = (Vec<Fp>, CurvePoint, Fp);
+    type Params = ();
+
+    fn name(&self) -> &'static str {
+        "ElGamal"
+    }
+
+    /// The public key and `c1`, the ciphertext of the message follows them
+    fn instance_increment_input(&self) -> Vec<usize> {
+        vec![HEADER_INSTANCES]
+    }
+
+    /// Constructs a new ElGamalChip
+    fn new(config: Self::Config) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Configuration of the ElGamalChip
+    fn configure(meta: &mut ConstraintSystem<Fp>, _: Self::Params) -> Self::Config {
+        let poseidon =
+            PoseidonChip::<S, WIDTH, RATE, 2>::configure_with_optional_instance(meta, None);
+
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+
+        Self::configure_with_poseidon(meta, poseidon, Some(instance))
+    }
+
+    /// Takes the message and the witnessed randomness and public key, and lays out the scalar
+    /// multiplications `r * G` and `r * P` in a single region
+    fn layout_inputs(
+        &self,
+        layouter: &mut impl Layouter<Fp>,
+        input: &[ValTensor<Fp>],
+        _: &mut ConstantsMap<Fp>,
+    ) -> Result<Self::InputAssignments, ModuleError> {
+        if input.len() != 2 {
+            return Err(ModuleError::InputWrongLength(input.len()));
+        }
+        let witness = input[1]
+            .get_inner()
+            .map_err(|e| ModuleError::WrongInputType(format!("{:?}", e), "Value".to_string()))?
+            .to_vec();
+        if witness.len() != WITNESS_LEN {
+            return Err(ModuleError::InputWrongLength(witness.len()));
+        }
+        let randomness = witness[0];
+        let public_key = witness[1].zip(witness[2]).map(|(x, y)| CurvePoint { x, y });
+
+        let start_time = instant::Instant::now();
+        let res = layouter.assign_region(
+            || "elgamal",
+            |mut region| {
+                let mut elgamal = ElGamalRegion {
+                    config: &self.config,
+                    region: &mut region,
+                    offset: 0,
+                };
+                let generator = elgamal.constant_point(CurvePoint::generator())?;
+                let bits = Bits::Witness(randomness.map(|r| scalar_bits(&r)));
+                let (c1, bits) = elgamal.mul(&generator, bits)?;
+                let key = elgamal.load_point(public_key)?;
+                let (shared, _) = elgamal.mul(&key, Bits::Copy(&bits))?;
+                Ok([key.cells, c1.cells, shared.cells])
+            },
+        );
+        log::trace!("elgamal layout took: {:?}", start_time.elapsed());
+        res.map_err(|e| e.into())
+    }
+
+    /// Encrypts the message, constraining the public key, `c1` and the ciphertext of the message
+    /// to the instances from `row_offset`. Returns the cells of the ciphertext.
+    fn layout(
+        &self,
+        layouter: &mut impl Layouter<Fp>,
+        input: &[ValTensor<Fp>],
+        row_offset: usize,
+        constants: &mut ConstantsMap<Fp>,
+    ) -> Result<ValTensor<Fp>, ModuleError> {
+        let [key, c1, shared] = self.layout_inputs(layouter, input, constants)?;
+        let message = match &input[0] {
+            ValTensor::Value { inner, .. } => inner.clone(),
+            e => {
+                return Err(ModuleError::WrongInputType(
+                    format!("{:?}", e),
+                    "Value".to_string(),
+                ))
+            }
+        };
+
+        let hash_inputs = &self.config.poseidon.hash_inputs;
+        let (preimage, zero) = layouter.assign_region(
+            || "elgamal key preimage",
+            |mut region| {
+                let preimage = shared
+                    .iter()
+                    .zip(hash_inputs)
+                    .map(|(cell, col)| cell.copy_advice(|| "shared secret", &mut region, *col, 0))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let zero =
+                    region.assign_advice_from_constant(|| "zero", hash_inputs[0], 1, Fp::ZERO)?;
+                Ok((preimage, zero))
+            },
+        )?;
+        let chip = PoseidonChip::<S, WIDTH, RATE, 2>::new(self.config.poseidon.clone());
+        let key_hash = chip.hash_assigned(layouter, preimage, &zero)?;
+
+        let mut masks = vec![];
+        for i in 0..message.len() {
+            let preimage = layouter.assign_region(
+                || "elgamal mask preimage",
+                |mut region| {
+                    let key = key_hash.copy_advice(|| "key", &mut region, hash_inputs[0], 0)?;
+                    let index = region.assign_advice_from_constant(
+                        || "index",
+                        hash_inputs[1],
+                        0,
+                        Fp::from(i as u64),
+                    )?;
+                    Ok(vec![key, index])
+                },
+            )?;
+            masks.push(chip.hash_assigned(layouter, preimage, &zero)?);
+        }
+
+        let ciphertext = layouter.assign_region(
+            || "elgamal ciphertext",
+            |mut region| {
+                let mut cells = vec![];
+                for (row, (value, mask)) in message.iter().zip(&masks).enumerate() {
+                    self.config.q_mask.enable(&mut region, row)?;
+                    let element = self
+                        .load_element(&mut region, value, row, constants)
+                        .map_err(|_| Error::Synthesis)?;
+                    mask.copy_advice(|| "mask", &mut region, self.config.point[1], row)?;
+                    let masked = element.value().zip(mask.value()).map(|(m, k)| *m + *k);
+                    cells.push(region.assign_advice(
+                        || "masked",
+                        self.config.lambda,
+                        row,
+                        || masked,
+                    )?);
+                }
+                Ok(cells)
+            },
+        )?;
+
+        if let Some(instance) = self.config.instance {
+            let cells = key.iter().chain(&c1).chain(&ciphertext);
+            for (i, cell) in cells.enumerate() {
+                layouter.constrain_instance(cell.cell(), instance, row_offset + i)?;
+            }
+        }
+
+        let cells = c1.into_iter().chain(ciphertext).map(ValType::from);
+        Ok(Tensor::from(cells).into())
+    }
+
+    /// Encrypts the message natively, returning the public key and the ciphertext
+    fn run(
+        (message, public_key, randomness): Self::RunInputs,
+    ) -> Result<Vec<Vec<Fp>>, ModuleError> {
+        let ciphertext = Self::encrypt(&public_key, &randomness, &message)?;
+        let mut instances = vec![public_key.x, public_key.y];
+        instances.extend(ciphertext.instances());
+        Ok(vec![instances])
+    }
+
+    /// Number of rows of the scalar multiplications and of the masking of a message of some length,
+    /// the masks are hashed in the Poseidon columns, see [Self::poseidon_rows]
+    fn num_rows(input_len: usize) -> usize {
+        2 * MUL_ROWS + input_len
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::circuit::modules::poseidon::spec::{PoseidonSpec, POSEIDON_RATE, POSEIDON_WIDTH};
+    use crate::circuit::modules::ModulePlanner;
+
+    use super::*;
+
+    use std::collections::HashMap;
+
+    use halo2_proofs::circuit::{Layouter, Value};
+    use halo2_proofs::plonk::{Circuit, ConstraintSystem};
+
+    type Chip = ElGamalChip<PoseidonSpec, POSEIDON_WIDTH, POSEIDON_RATE>;
+
+    fn message() -> Vec<Fp> {
+        (0..6u64).map(|i| Fp::from(i * 31 + 5)).collect()
+    }
+
+    struct ElGamalCircuit {
+        message: Vec<Value<Fp>>,
+        witness: Vec<Value<Fp>>,
+    }
+
+    impl Circuit<Fp> for ElGamalCircuit {
+        type Config = ElGamalConfig<POSEIDON_WIDTH, POSEIDON_RATE>;
+        type FloorPlanner = ModulePlanner;
+        type Params = ();
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                message: vec![Value::unknown(); self.message.len()],
+                witness: vec![Value::unknown(); WITNESS_LEN],
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            Chip::configure(meta, ())
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let message: Tensor<ValType<Fp>> = self.message.iter().map(|v| (*v).into()).into();
+            let witness: Tensor<ValType<Fp>> = self.witness.iter().map(|w| (*w).into()).into();
+            let chip = Chip::new(config);
+            chip.layout(
+                &mut layouter,
+                &[message.into(), witness.into()],
+                0,
+                &mut HashMap::new(),
+            )?;
+            Ok(())
+        }
+    }
+
+    fn circuit(public_key: &CurvePoint, randomness: Fp) -> ElGamalCircuit {
+        ElGamalCircuit {
+            message: message().into_iter().map(Value::known).collect(),
+            witness: vec![randomness, public_key.x, public_key.y]
+                .into_iter()
+                .map(Value::known)
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn elgamal_curve() {
+        let generator = CurvePoint::generator();
+        assert!(generator.is_on_curve());
+        assert!(CurvePoint::offset().is_on_curve());
+        assert!(CurvePoint::correction().is_on_curve());
+        // the double-and-add of the circuit lands on the product
+        assert_eq!(
+            point_add(
+                point_mul(&Fp::from(5), &generator),
+                point_mul(&Fp::from(3), &generator)
+            ),
+            point_mul(&Fp::from(8), &generator)
+        );
+        assert_eq!(point_add(Some(generator), Some(generator.neg())), None);
+    }
+
+    #[test]
+    fn elgamal_decrypt() {
+        let (secret_key, public_key) = Chip::gen_keys(&mut rand::rngs::OsRng);
+        let randomness = Fp::random(rand::rngs::OsRng);
+        let ciphertext = Chip::encrypt(&public_key, &randomness, &message()).unwrap();
+        assert_ne!(ciphertext.c2, message());
+        assert_eq!(Chip::decrypt(&ciphertext, &secret_key).unwrap(), message());
+        // another key doesn't decrypt the message
+        assert_ne!(
+            Chip::decrypt(&ciphertext, &(secret_key + Fp::ONE)).unwrap(),
+            message()
+        );
+        let off_curve = CurvePoint {
+            x: public_key.x,
+            y: public_key.y + Fp::ONE,
+        };
+        assert!(Chip::encrypt(&off_curve, &randomness, &message()).is_err());
+    }
+
+    #[test]
+    fn elgamal_encryption() {
+        let (_, public_key) = Chip::gen_keys(&mut rand::rngs::OsRng);
+        let randomness = Fp::random(rand::rngs::OsRng);
+        let instances = Chip::run((message(), public_key, randomness)).unwrap();
+
+        let k = 11;
+        let prover =
+            halo2_proofs::dev::MockProver::run(k, &circuit(&public_key, randomness), instances)
+                .unwrap();
+        assert_eq!(prover.verify(), Ok(()))
+    }
+
+    #[test]
+    fn elgamal_encryption_wrong_ciphertext() {
+        let (_, public_key) = Chip::gen_keys(&mut rand::rngs::OsRng);
+        let randomness = Fp::random(rand::rngs::OsRng);
+        let mut instances = Chip::run((message(), public_key, randomness)).unwrap();
+        instances[0][HEADER_INSTANCES] += Fp::ONE;
+
+        let k = 11;
+        let prover =
+            halo2_proofs::dev::MockProver::run(k, &circuit(&public_key, randomness), instances)
+                .unwrap();
+        assert!(prover.verify().is_err())
+    }
+
+    #[test]
+    fn elgamal_encryption_off_curve_key() {
+        let (_, public_key) = Chip::gen_keys(&mut rand::rngs::OsRng);
+        let randomness = Fp::random(rand::rngs::OsRng);
+        let mut instances = Chip::run((message(), public_key, randomness)).unwrap();
+        let off_curve = CurvePoint {
+            x: public_key.x,
+            y: public_key.y + Fp::ONE,
+        };
+        instances[0][1] = off_curve.y;
+
+        let k = 11;
+        let prover =
+            halo2_proofs::dev::MockProver::run(k, &circuit(&off_curve, randomness), instances)
+                .unwrap();
+        assert!(prover.verify().is_err())
+    }
+}
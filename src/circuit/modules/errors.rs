@@ -16,6 +16,15 @@ pub enum ModuleError {
     /// Input length is wrong
     #[error("input length is wrong {0}")]
     InputWrongLength(usize),
+    /// A value cannot be serialized for the module
+    #[error("value {0} is out of range for the module")]
+    ValueOutOfRange(String),
+    /// A signature cannot be parsed or does not verify
+    #[error("invalid signature: {0}")]
+    InvalidSignature(String),
+    /// A message cannot be encrypted or decrypted
+    #[error("invalid encryption: {0}")]
+    InvalidEncryption(String),
 }
 
 impl From<ModuleError> for PlonkError {
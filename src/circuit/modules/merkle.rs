@@ -0,0 +1,499 @@
+/*
+Inclusion proofs against a Poseidon Merkle root. The leaf is the Poseidon hash of the message, hashed as by the
+Poseidon chip, and every node is the Poseidon hash of its left and right children. Empty leaves are zero, such that
+a dataset of any size up to 2^depth rows commits to a root of the same depth.
+
+The path is given as the sibling at every level along with the bit of the leaf index at that level, the bit
+swapping the current node and its sibling into the left and right children hashed into the node above.
+*/
+
+use halo2_gadgets::poseidon::primitives::{ConstantLength, Hash, Spec};
+use halo2_proofs::arithmetic::Field;
+use halo2_proofs::halo2curves::bn256::Fr as Fp;
+use halo2_proofs::poly::Rotation;
+use halo2_proofs::{circuit::*, plonk::*};
+use serde::{Deserialize, Serialize};
+
+use std::marker::PhantomData;
+
+use crate::circuit::region::ConstantsMap;
+use crate::tensor::{Tensor, ValTensor, ValType};
+
+use super::errors::ModuleError;
+use super::poseidon::{PoseidonChip, PoseidonConfig};
+use super::Module;
+
+/// The number of instance columns used by the Merkle inclusion proof
+pub const NUM_INSTANCE_COLUMNS: usize = 1;
+
+/// Number of advice columns of the gate ordering a node and its sibling
+const SWAP_COLUMNS: usize = 5;
+
+#[derive(Debug, Clone)]
+/// Configuration for the Merkle chip, the leaf and the nodes are hashed in the columns of the Poseidon config
+pub struct MerkleConfig<const WIDTH: usize, const RATE: usize> {
+    ///
+    pub poseidon: PoseidonConfig<WIDTH, RATE>,
+    /// the current node, its sibling, the index bit and the resulting left and right children
+    pub swap: [Column<Advice>; SWAP_COLUMNS],
+    /// orders the current node and its sibling
+    pub q_swap: Selector,
+    /// the depth of the tree
+    pub depth: usize,
+    ///
+    pub instance: Option<Column<Instance>>,
+}
+
+/// The path of a leaf to the root of a [MerkleTree]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MerklePath {
+    /// index of the leaf, its bits (least significant first) are the positions of the nodes along the path
+    pub index: usize,
+    /// sibling of the node at every level, starting from the leaf
+    pub siblings: Vec<Fp>,
+    /// the root the path leads to
+    pub root: Fp,
+}
+
+/// A Poseidon Merkle tree of fixed depth, the leaves past the end of the dataset are zero
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MerkleTree {
+    /// the depth of the tree
+    pub depth: usize,
+    /// the root of the tree
+    pub root: Fp,
+    /// the leaves of the dataset
+    pub leaves: Vec<Fp>,
+}
+
+type InputAssignments = (Vec<AssignedCell<Fp, Fp>>, AssignedCell<Fp, Fp>);
+
+/// MerkleChip verifies the path of a message to a root, hashing with the Poseidon chip
+#[derive(Debug, Clone)]
+pub struct MerkleChip<
+    S: Spec<Fp, WIDTH, RATE> + Sync,
+    const WIDTH: usize,
+    const RATE: usize,
+    const L: usize,
+> {
+    config: MerkleConfig<WIDTH, RATE>,
+    _marker: PhantomData<S>,
+}
+
+impl<S: Spec<Fp, WIDTH, RATE> + Sync, const WIDTH: usize, const RATE: usize, const L: usize>
+    MerkleChip<S, WIDTH, RATE, L>
+{
+    /// Configures the chip to hash in the columns of an existing Poseidon config
+    pub fn configure_with_poseidon(
+        meta: &mut ConstraintSystem<Fp>,
+        poseidon: PoseidonConfig<WIDTH, RATE>,
+        depth: usize,
+        instance: Option<Column<Instance>>,
+    ) -> MerkleConfig<WIDTH, RATE> {
+        let swap: [Column<Advice>; SWAP_COLUMNS] = std::array::from_fn(|_| meta.advice_column());
+        for col in &swap {
+            meta.enable_equality(*col);
+        }
+        let q_swap = meta.selector();
+
+        meta.create_gate("merkle swap", |meta| {
+            let q = meta.query_selector(q_swap);
+            let [node, sibling, bit, left, right] =
+                swap.map(|col| meta.query_advice(col, Rotation::cur()));
+            let one = Expression::Constant(Fp::ONE);
+
+            vec![
+                q.clone() * bit.clone() * (one - bit.clone()),
+                q.clone() * (left - node.clone() - bit.clone() * (sibling.clone() - node.clone())),
+                q * (right - sibling.clone() - bit * (node - sibling)),
+            ]
+        });
+
+        MerkleConfig {
+            poseidon,
+            swap,
+            q_swap,
+            depth,
+            instance,
+        }
+    }
+
+    /// The leaf a message hashes to
+    pub fn leaf(message: Vec<Fp>) -> Result<Fp, ModuleError> {
+        Ok(PoseidonChip::<S, WIDTH, RATE, L>::run(message)?[0][0])
+    }
+
+    /// The node above two children
+    fn node(left: Fp, right: Fp) -> Fp {
+        Hash::<_, S, ConstantLength<2>, WIDTH, RATE>::init().hash([left, right])
+    }
+
+    /// The root of an empty subtree at every level up to the depth
+    fn empty_roots(depth: usize) -> Vec<Fp> {
+        let mut roots = vec![Fp::ZERO];
+        for _ in 0..depth {
+            let root = roots[roots.len() - 1];
+            roots.push(Self::node(root, root));
+        }
+        roots
+    }
+
+    /// The nodes of every level of a tree, without the empty subtrees past the end of the leaves
+    fn levels(leaves: &[Fp], depth: usize) -> Vec<Vec<Fp>> {
+        let empty = Self::empty_roots(depth);
+        let mut levels = vec![leaves.to_vec()];
+        for empty in empty.iter().take(depth) {
+            let nodes = levels[levels.len() - 1]
+                .chunks(2)
+                .map(|pair| Self::node(pair[0], *pair.get(1).unwrap_or(empty)))
+                .collect();
+            levels.push(nodes);
+        }
+        levels
+    }
+
+    /// Builds the tree of the leaves of a dataset
+    pub fn tree(leaves: Vec<Fp>, depth: usize) -> Result<MerkleTree, ModuleError> {
+        if depth >= usize::BITS as usize || leaves.len() > 1 << depth {
+            return Err(ModuleError::InputWrongLength(leaves.len()));
+        }
+        let root = match Self::levels(&leaves, depth)[depth].first() {
+            Some(root) => *root,
+            None => Self::empty_roots(depth)[depth],
+        };
+        Ok(MerkleTree {
+            depth,
+            root,
+            leaves,
+        })
+    }
+
+    /// The path of the leaf at an index of the tree
+    pub fn path(tree: &MerkleTree, index: usize) -> Result<MerklePath, ModuleError> {
+        if index >= tree.leaves.len() {
+            return Err(ModuleError::InputWrongLength(index));
+        }
+        let empty = Self::empty_roots(tree.depth);
+        let levels = Self::levels(&tree.leaves, tree.depth);
+        let siblings = (0..tree.depth)
+            .map(|level| {
+                let sibling = (index >> level) ^ 1;
+                *levels[level].get(sibling).unwrap_or(&empty[level])
+            })
+            .collect();
+        Ok(MerklePath {
+            index,
+            siblings,
+            root: tree.root,
+        })
+    }
+
+    /// The index bits of a path as field elements, least significant first
+    pub fn index_bits(index: usize, depth: usize) -> Vec<Fp> {
+        (0..depth)
+            .map(|level| Fp::from(((index >> level) & 1) as u64))
+            .collect()
+    }
+
+    /// Number of rows used to verify the path of a message of some length in a tree of some depth
+    pub fn num_rows_with_depth(input_len: usize, depth: usize) -> usize {
+        PoseidonChip::<S, WIDTH, RATE, L>::num_rows(input_len)
+            + depth * PoseidonChip::<S, WIDTH, RATE, 2>::num_rows(1)
+    }
+}
+
+impl<S: Spec<Fp, WIDTH, RATE> + Sync, const WIDTH: usize, const RATE: usize, const L: usize>
+    Module<Fp> for MerkleChip<S, WIDTH, RATE, L>
+{
+    type Config = MerkleConfig<WIDTH, RATE>;
+    type InputAssignments = InputAssignments;
+    /// the message, the siblings along its path and its index
+    type RunInputs = (Vec<Fp>, Vec<Fp>, usize);
+    /// the depth of the tree
+    type Params = usize;
+
+    fn name(&self) -> &'static str {
+        "Merkle"
+    }
+
+    fn instance_increment_input(&self) -> Vec<usize> {
+        vec![1]
+    }
+
+    /// Constructs a new MerkleChip
+    fn new(config: Self::Config) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Configuration of the MerkleChip
+    fn configure(meta: &mut ConstraintSystem<Fp>, depth: Self::Params) -> Self::Config {
+        let poseidon =
+            PoseidonChip::<S, WIDTH, RATE, L>::configure_with_optional_instance(meta, None);
+
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+
+        Self::configure_with_poseidon(meta, poseidon, depth, Some(instance))
+    }
+
+    fn layout_inputs(
+        &self,
+        layouter: &mut impl Layouter<Fp>,
+        message: &[ValTensor<Fp>],
+        constants: &mut ConstantsMap<Fp>,
+    ) -> Result<Self::InputAssignments, ModuleError> {
+        PoseidonChip::<S, WIDTH, RATE, L>::new(self.config.poseidon.clone()).layout_inputs(
+            layouter,
+            &message[..1],
+            constants,
+        )
+    }
+
+    /// Takes the message, the siblings along its path and the bits of its index, and constrains the root
+    /// the path leads to to the instance at `row_offset`
+    fn layout(
+        &self,
+        layouter: &mut impl Layouter<Fp>,
+        input: &[ValTensor<Fp>],
+        row_offset: usize,
+        constants: &mut ConstantsMap<Fp>,
+    ) -> Result<ValTensor<Fp>, ModuleError> {
+        if input.len() != 3 {
+            return Err(ModuleError::InputWrongLength(input.len()));
+        }
+        let to_values = |t: &ValTensor<Fp>| {
+            t.get_inner()
+                .map_err(|e| ModuleError::WrongInputType(format!("{:?}", e), "Value".to_string()))
+        };
+        let siblings = to_values(&input[1])?;
+        let bits = to_values(&input[2])?;
+        if siblings.len() != self.config.depth || bits.len() != self.config.depth {
+            return Err(ModuleError::InputWrongLength(siblings.len()));
+        }
+
+        let (message_cells, zero_val) = self.layout_inputs(layouter, input, constants)?;
+        let mut assigned_input: Tensor<ValType<Fp>> = message_cells
+            .iter()
+            .map(|e| ValType::from(e.clone()))
+            .into();
+
+        let leaf_chip = PoseidonChip::<S, WIDTH, RATE, L>::new(self.config.poseidon.clone());
+        let node_chip = PoseidonChip::<S, WIDTH, RATE, 2>::new(self.config.poseidon.clone());
+
+        let mut node = leaf_chip.hash_assigned(layouter, message_cells, &zero_val)?;
+
+        for (level, (sibling, bit)) in siblings.iter().zip(bits.iter()).enumerate() {
+            let children = layouter.assign_region(
+                || format!("merkle level {}", level),
+                |mut region| {
+                    self.config.q_swap.enable(&mut region, 0)?;
+                    let current =
+                        node.copy_advice(|| "node", &mut region, self.config.swap[0], 0)?;
+                    region.assign_advice(|| "sibling", self.config.swap[1], 0, || *sibling)?;
+                    region.assign_advice(|| "index bit", self.config.swap[2], 0, || *bit)?;
+
+                    let current = current.value().copied();
+                    let left = current + *bit * (*sibling - current);
+                    let right = *sibling + *bit * (current - *sibling);
+                    let left = region.assign_advice(|| "left", self.config.swap[3], 0, || left)?;
+                    let right =
+                        region.assign_advice(|| "right", self.config.swap[4], 0, || right)?;
+                    Ok(vec![left, right])
+                },
+            )?;
+            node = node_chip.hash_assigned(layouter, children, &zero_val)?;
+        }
+
+        if let Some(instance) = self.config.instance {
+            layouter.constrain_instance(node.cell(), instance, row_offset)?;
+
+            assigned_input.reshape(input[0].dims()).map_err(|e| {
+                log::error!("reshape failed: {:?}", e);
+                Error::Synthesis
+            })?;
+
+            Ok(assigned_input.into())
+        } else {
+            Ok(Tensor::from([ValType::from(node)].into_iter()).into())
+        }
+    }
+
+    /// Computes the root the path of the message leads to
+    fn run((message, siblings, index): Self::RunInputs) -> Result<Vec<Vec<Fp>>, ModuleError> {
+        let mut node = Self::leaf(message)?;
+        for (level, sibling) in siblings.into_iter().enumerate() {
+            node = if (index >> level) & 1 == 1 {
+                Self::node(sibling, node)
+            } else {
+                Self::node(node, sibling)
+            };
+        }
+        Ok(vec![vec![node]])
+    }
+
+    /// Number of rows used to hash the leaf, see [MerkleChip::num_rows_with_depth] for the whole path
+    fn num_rows(input_len: usize) -> usize {
+        PoseidonChip::<S, WIDTH, RATE, L>::num_rows(input_len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::circuit::modules::poseidon::spec::{PoseidonSpec, POSEIDON_RATE, POSEIDON_WIDTH};
+    use crate::circuit::modules::ModulePlanner;
+
+    use super::*;
+
+    use std::collections::HashMap;
+
+    use halo2_proofs::circuit::{Layouter, Value};
+    use halo2_proofs::plonk::{Circuit, ConstraintSystem};
+
+    const WIDTH: usize = POSEIDON_WIDTH;
+    const RATE: usize = POSEIDON_RATE;
+    const L: usize = 4;
+    const DEPTH: usize = 3;
+
+    type Chip = MerkleChip<PoseidonSpec, WIDTH, RATE, L>;
+
+    fn dataset() -> Vec<Vec<Fp>> {
+        (0..5u64)
+            .map(|row| (0..6).map(|i| Fp::from(row * 10 + i)).collect())
+            .collect()
+    }
+
+    fn tree() -> MerkleTree {
+        let leaves = dataset()
+            .into_iter()
+            .map(|row| Chip::leaf(row).unwrap())
+            .collect();
+        Chip::tree(leaves, DEPTH).unwrap()
+    }
+
+    fn values(t: &[Fp]) -> ValTensor<Fp> {
+        let t: Tensor<ValType<Fp>> = t.iter().map(|x| Value::known(*x).into()).into();
+        t.into()
+    }
+
+    struct MerkleCircuit {
+        message: ValTensor<Fp>,
+        siblings: ValTensor<Fp>,
+        bits: ValTensor<Fp>,
+    }
+
+    impl Circuit<Fp> for MerkleCircuit {
+        type Config = MerkleConfig<WIDTH, RATE>;
+        type FloorPlanner = ModulePlanner;
+        type Params = ();
+
+        fn without_witnesses(&self) -> Self {
+            let unknown = |n: usize| -> ValTensor<Fp> {
+                let t: Tensor<ValType<Fp>> = (0..n).map(|_| Value::<Fp>::unknown().into()).into();
+                t.into()
+            };
+            Self {
+                message: unknown(self.message.len()),
+                siblings: unknown(DEPTH),
+                bits: unknown(DEPTH),
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            Chip::configure(meta, DEPTH)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let chip = Chip::new(config);
+            chip.layout(
+                &mut layouter,
+                &[
+                    self.message.clone(),
+                    self.siblings.clone(),
+                    self.bits.clone(),
+                ],
+                0,
+                &mut HashMap::new(),
+            )?;
+            Ok(())
+        }
+    }
+
+    fn circuit(message: &[Fp], path: &MerklePath) -> MerkleCircuit {
+        MerkleCircuit {
+            message: values(message),
+            siblings: values(&path.siblings),
+            bits: values(&Chip::index_bits(path.index, DEPTH)),
+        }
+    }
+
+    #[test]
+    fn merkle_paths_lead_to_root() {
+        let tree = tree();
+        for (index, row) in dataset().into_iter().enumerate() {
+            let path = Chip::path(&tree, index).unwrap();
+            assert_eq!(path.siblings.len(), DEPTH);
+            let root = Chip::run((row, path.siblings, index)).unwrap();
+            assert_eq!(root, vec![vec![tree.root]]);
+        }
+        assert!(Chip::path(&tree, 5).is_err());
+    }
+
+    #[test]
+    fn merkle_tree_depth() {
+        let leaves = vec![Fp::from(1); 9];
+        assert!(Chip::tree(leaves.clone(), DEPTH).is_err());
+        // the empty leaves are zero, so the root depends on the depth
+        let shallow = Chip::tree(leaves[..2].to_vec(), 1).unwrap();
+        let deep = Chip::tree(leaves[..2].to_vec(), 2).unwrap();
+        assert_ne!(shallow.root, deep.root);
+    }
+
+    #[test]
+    fn merkle_inclusion() {
+        let tree = tree();
+        let row = dataset().remove(3);
+        let path = Chip::path(&tree, 3).unwrap();
+
+        let k = 10;
+        let prover =
+            halo2_proofs::dev::MockProver::run(k, &circuit(&row, &path), vec![vec![tree.root]])
+                .unwrap();
+        assert_eq!(prover.verify(), Ok(()))
+    }
+
+    #[test]
+    fn merkle_inclusion_wrong_sibling() {
+        let tree = tree();
+        let row = dataset().remove(3);
+        let mut path = Chip::path(&tree, 3).unwrap();
+        path.siblings[1] += Fp::ONE;
+
+        let k = 10;
+        let prover =
+            halo2_proofs::dev::MockProver::run(k, &circuit(&row, &path), vec![vec![tree.root]])
+                .unwrap();
+        assert!(prover.verify().is_err())
+    }
+
+    #[test]
+    fn merkle_inclusion_wrong_row() {
+        let tree = tree();
+        let mut row = dataset().remove(3);
+        let path = Chip::path(&tree, 3).unwrap();
+        row[0] += Fp::ONE;
+
+        let k = 10;
+        let prover =
+            halo2_proofs::dev::MockProver::run(k, &circuit(&row, &path), vec![vec![tree.root]])
+                .unwrap();
+        assert!(prover.verify().is_err())
+    }
+}
@@ -4,6 +4,24 @@ pub mod poseidon;
 ///
 pub mod polycommit;
 
+///
+pub mod sha256;
+
+///
+pub mod merkle;
+
+///
+pub mod ecdsa;
+
+///
+pub mod nullifier;
+
+///
+pub mod session;
+
+///
+pub mod elgamal;
+
 ///
 pub mod planner;
 
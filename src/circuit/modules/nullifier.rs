@@ -0,0 +1,327 @@
+/*
+Nullifiers of committed inputs. The commitment is the Poseidon hash of the message, hashed as by the Poseidon chip
+(it is the leaf of a Merkle inclusion proof), and the nullifier is the Poseidon hash of the commitment and of a
+private salt. The same message and salt always give the same nullifier, such that a verifier recording the
+nullifiers it has seen rejects a second proof over the same committed inputs without learning them.
+*/
+
+use halo2_gadgets::poseidon::primitives::{ConstantLength, Hash, Spec};
+use halo2_proofs::halo2curves::bn256::Fr as Fp;
+use halo2_proofs::{circuit::*, plonk::*};
+use serde::{Deserialize, Serialize};
+
+use std::marker::PhantomData;
+
+use crate::circuit::region::ConstantsMap;
+use crate::tensor::{Tensor, ValTensor, ValType};
+
+use super::errors::ModuleError;
+use super::poseidon::{PoseidonChip, PoseidonConfig};
+use super::Module;
+
+/// The number of instance columns used by the nullifier
+pub const NUM_INSTANCE_COLUMNS: usize = 1;
+
+#[derive(Debug, Clone)]
+/// Configuration for the nullifier chip, the commitment and the nullifier are hashed in the columns of the Poseidon config
+pub struct NullifierConfig<const WIDTH: usize, const RATE: usize> {
+    ///
+    pub poseidon: PoseidonConfig<WIDTH, RATE>,
+    ///
+    pub instance: Option<Column<Instance>>,
+}
+
+/// The commitment of the inputs, and the salt and nullifier once the salt is attached to the witness
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct NullifierWitness {
+    /// the commitment the inputs hash to
+    pub commitment: Fp,
+    /// the private salt
+    pub salt: Option<Fp>,
+    /// the public nullifier of the commitment and the salt
+    pub nullifier: Option<Fp>,
+}
+
+type InputAssignments = (Vec<AssignedCell<Fp, Fp>>, AssignedCell<Fp, Fp>);
+
+/// NullifierChip derives the nullifier of a message and a salt, hashing with the Poseidon chip
+#[derive(Debug, Clone)]
+pub struct NullifierChip<
+    S: Spec<Fp, WIDTH, RATE> + Sync,
+    const WIDTH: usize,
+    const RATE: usize,
+    const L: usize,
+> {
+    config: NullifierConfig<WIDTH, RATE>,
+    _marker: PhantomData<S>,
+}
+
+impl<S: Spec<Fp, WIDTH, RATE> + Sync, const WIDTH: usize, const RATE: usize, const L: usize>
+    NullifierChip<S, WIDTH, RATE, L>
+{
+    /// Configures the chip to hash in the columns of an existing Poseidon config
+    pub fn configure_with_poseidon(
+        poseidon: PoseidonConfig<WIDTH, RATE>,
+        instance: Option<Column<Instance>>,
+    ) -> NullifierConfig<WIDTH, RATE> {
+        NullifierConfig { poseidon, instance }
+    }
+
+    /// The commitment a message hashes to
+    pub fn commitment(message: Vec<Fp>) -> Result<Fp, ModuleError> {
+        Ok(PoseidonChip::<S, WIDTH, RATE, L>::run(message)?[0][0])
+    }
+
+    /// The nullifier of a commitment and a salt
+    pub fn nullifier(commitment: Fp, salt: Fp) -> Fp {
+        Hash::<_, S, ConstantLength<2>, WIDTH, RATE>::init().hash([commitment, salt])
+    }
+}
+
+impl<S: Spec<Fp, WIDTH, RATE> + Sync, const WIDTH: usize, const RATE: usize, const L: usize>
+    Module<Fp> for NullifierChip<S, WIDTH, RATE, L>
+{
+    type Config = NullifierConfig<WIDTH, RATE>;
+    type InputAssignments = InputAssignments;
+    /// the message and the salt
+    type RunInputs = (Vec<Fp>, Fp);
+    type Params = ();
+
+    fn name(&self) -> &'static str {
+        "Nullifier"
+    }
+
+    fn instance_increment_input(&self) -> Vec<usize> {
+        vec![1]
+    }
+
+    /// Constructs a new NullifierChip
+    fn new(config: Self::Config) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Configuration of the NullifierChip
+    fn configure(meta: &mut ConstraintSystem<Fp>, _: Self::Params) -> Self::Config {
+        let poseidon =
+            PoseidonChip::<S, WIDTH, RATE, L>::configure_with_optional_instance(meta, None);
+
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+
+        Self::configure_with_poseidon(poseidon, Some(instance))
+    }
+
+    fn layout_inputs(
+        &self,
+        layouter: &mut impl Layouter<Fp>,
+        message: &[ValTensor<Fp>],
+        constants: &mut ConstantsMap<Fp>,
+    ) -> Result<Self::InputAssignments, ModuleError> {
+        PoseidonChip::<S, WIDTH, RATE, L>::new(self.config.poseidon.clone()).layout_inputs(
+            layouter,
+            &message[..1],
+            constants,
+        )
+    }
+
+    /// Takes the message and the salt, and constrains the nullifier they derive to the instance at
+    /// `row_offset`
+    fn layout(
+        &self,
+        layouter: &mut impl Layouter<Fp>,
+        input: &[ValTensor<Fp>],
+        row_offset: usize,
+        constants: &mut ConstantsMap<Fp>,
+    ) -> Result<ValTensor<Fp>, ModuleError> {
+        if input.len() != 2 {
+            return Err(ModuleError::InputWrongLength(input.len()));
+        }
+        let salt = input[1]
+            .get_inner()
+            .map_err(|e| ModuleError::WrongInputType(format!("{:?}", e), "Value".to_string()))?;
+        if salt.len() != 1 {
+            return Err(ModuleError::InputWrongLength(salt.len()));
+        }
+
+        let (message_cells, zero_val) = self.layout_inputs(layouter, input, constants)?;
+        let mut assigned_input: Tensor<ValType<Fp>> = message_cells
+            .iter()
+            .map(|e| ValType::from(e.clone()))
+            .into();
+
+        let commitment_chip = PoseidonChip::<S, WIDTH, RATE, L>::new(self.config.poseidon.clone());
+        let nullifier_chip = PoseidonChip::<S, WIDTH, RATE, 2>::new(self.config.poseidon.clone());
+
+        let commitment = commitment_chip.hash_assigned(layouter, message_cells, &zero_val)?;
+
+        let preimage = layouter.assign_region(
+            || "nullifier preimage",
+            |mut region| {
+                let commitment = commitment.copy_advice(
+                    || "commitment",
+                    &mut region,
+                    self.config.poseidon.hash_inputs[0],
+                    0,
+                )?;
+                let salt = region.assign_advice(
+                    || "salt",
+                    self.config.poseidon.hash_inputs[1],
+                    0,
+                    || salt[0],
+                )?;
+                Ok(vec![commitment, salt])
+            },
+        )?;
+        let nullifier = nullifier_chip.hash_assigned(layouter, preimage, &zero_val)?;
+
+        if let Some(instance) = self.config.instance {
+            layouter.constrain_instance(nullifier.cell(), instance, row_offset)?;
+
+            assigned_input.reshape(input[0].dims()).map_err(|e| {
+                log::error!("reshape failed: {:?}", e);
+                Error::Synthesis
+            })?;
+
+            Ok(assigned_input.into())
+        } else {
+            Ok(Tensor::from([ValType::from(nullifier)].into_iter()).into())
+        }
+    }
+
+    /// Computes the nullifier of the message and the salt
+    fn run((message, salt): Self::RunInputs) -> Result<Vec<Vec<Fp>>, ModuleError> {
+        let commitment = Self::commitment(message)?;
+        Ok(vec![vec![Self::nullifier(commitment, salt)]])
+    }
+
+    /// Number of rows used to hash the commitment of a message of some length and the nullifier
+    fn num_rows(input_len: usize) -> usize {
+        PoseidonChip::<S, WIDTH, RATE, L>::num_rows(input_len)
+            + PoseidonChip::<S, WIDTH, RATE, 2>::num_rows(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::circuit::modules::poseidon::spec::{PoseidonSpec, POSEIDON_RATE, POSEIDON_WIDTH};
+    use crate::circuit::modules::ModulePlanner;
+
+    use super::*;
+
+    use std::collections::HashMap;
+
+    use halo2_proofs::circuit::{Layouter, Value};
+    use halo2_proofs::plonk::{Circuit, ConstraintSystem};
+
+    const WIDTH: usize = POSEIDON_WIDTH;
+    const RATE: usize = POSEIDON_RATE;
+    const L: usize = 4;
+
+    type Chip = NullifierChip<PoseidonSpec, WIDTH, RATE, L>;
+
+    fn message() -> Vec<Fp> {
+        (0..6u64).map(Fp::from).collect()
+    }
+
+    fn values(t: &[Fp]) -> ValTensor<Fp> {
+        let t: Tensor<ValType<Fp>> = t.iter().map(|x| Value::known(*x).into()).into();
+        t.into()
+    }
+
+    struct NullifierCircuit {
+        message: ValTensor<Fp>,
+        salt: ValTensor<Fp>,
+    }
+
+    impl Circuit<Fp> for NullifierCircuit {
+        type Config = NullifierConfig<WIDTH, RATE>;
+        type FloorPlanner = ModulePlanner;
+        type Params = ();
+
+        fn without_witnesses(&self) -> Self {
+            let unknown = |n: usize| -> ValTensor<Fp> {
+                let t: Tensor<ValType<Fp>> = (0..n).map(|_| Value::<Fp>::unknown().into()).into();
+                t.into()
+            };
+            Self {
+                message: unknown(self.message.len()),
+                salt: unknown(1),
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            Chip::configure(meta, ())
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let chip = Chip::new(config);
+            chip.layout(
+                &mut layouter,
+                &[self.message.clone(), self.salt.clone()],
+                0,
+                &mut HashMap::new(),
+            )?;
+            Ok(())
+        }
+    }
+
+    fn circuit(message: &[Fp], salt: Fp) -> NullifierCircuit {
+        NullifierCircuit {
+            message: values(message),
+            salt: values(&[salt]),
+        }
+    }
+
+    #[test]
+    fn nullifier_of_commitment() {
+        let salt = Fp::from(7);
+        let commitment = Chip::commitment(message()).unwrap();
+        assert_eq!(
+            Chip::run((message(), salt)).unwrap(),
+            vec![vec![Chip::nullifier(commitment, salt)]]
+        );
+        // salting differently gives a different nullifier for the same message
+        assert_ne!(
+            Chip::nullifier(commitment, salt),
+            Chip::nullifier(commitment, Fp::from(8))
+        );
+    }
+
+    #[test]
+    fn nullifier() {
+        let salt = Fp::from(7);
+        let nullifier = Chip::run((message(), salt)).unwrap()[0][0];
+
+        let k = 10;
+        let prover = halo2_proofs::dev::MockProver::run(
+            k,
+            &circuit(&message(), salt),
+            vec![vec![nullifier]],
+        )
+        .unwrap();
+        assert_eq!(prover.verify(), Ok(()))
+    }
+
+    #[test]
+    fn nullifier_wrong_salt() {
+        let salt = Fp::from(7);
+        let nullifier = Chip::run((message(), salt)).unwrap()[0][0];
+
+        let k = 10;
+        let prover = halo2_proofs::dev::MockProver::run(
+            k,
+            &circuit(&message(), Fp::from(8)),
+            vec![vec![nullifier]],
+        )
+        .unwrap();
+        assert!(prover.verify().is_err())
+    }
+}
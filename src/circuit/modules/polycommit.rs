@@ -14,11 +14,14 @@ use halo2curves::bn256::G1Affine;
 use halo2curves::group::prime::PrimeCurveAffine;
 use halo2curves::group::Curve;
 use halo2curves::CurveAffine;
+use serde::{Deserialize, Serialize};
 
 use crate::circuit::region::ConstantsMap;
-use crate::tensor::{Tensor, ValTensor, ValType, VarTensor};
+use crate::tensor::{Tensor, TensorError, ValTensor, ValType, VarTensor};
 
+use super::elgamal::{CurvePoint, ElGamalChip, ElGamalCiphertext};
 use super::errors::ModuleError;
+use super::poseidon::spec::{PoseidonSpec, POSEIDON_RATE, POSEIDON_WIDTH};
 use super::Module;
 
 /// The number of instance columns used by the PolyCommit hash function
@@ -35,6 +38,52 @@ pub struct PolyCommitConfig {
 
 type InputAssignments = ();
 
+type BlinderEncryption = ElGamalChip<PoseidonSpec, POSEIDON_WIDTH, POSEIDON_RATE>;
+
+/// The blinders folded into the committed tensors, one for each commitment, such that a
+/// commitment can be reopened to its tensor later on
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PolyCommitBlinders {
+    /// the blinders in the clear
+    Raw(Vec<Fp>),
+    /// the blinders encrypted to the public key of their holder, they have to be decrypted before
+    /// the commitments can be proven or reopened
+    Encrypted(ElGamalCiphertext),
+}
+
+impl PolyCommitBlinders {
+    /// The blinders in the clear, if they aren't encrypted
+    pub fn raw(&self) -> Option<&[Fp]> {
+        match self {
+            PolyCommitBlinders::Raw(blinders) => Some(blinders),
+            PolyCommitBlinders::Encrypted(_) => None,
+        }
+    }
+
+    /// Encrypts the blinders to a public key with some private randomness
+    pub fn encrypt(&self, public_key: &CurvePoint, randomness: &Fp) -> Result<Self, ModuleError> {
+        match self {
+            PolyCommitBlinders::Raw(blinders) => Ok(PolyCommitBlinders::Encrypted(
+                BlinderEncryption::encrypt(public_key, randomness, blinders)?,
+            )),
+            PolyCommitBlinders::Encrypted(_) => Err(ModuleError::InvalidEncryption(
+                "the blinders are already encrypted".to_string(),
+            )),
+        }
+    }
+
+    /// Decrypts the blinders with the secret key of the public key they were encrypted to
+    pub fn decrypt(&self, secret_key: &Fp) -> Result<Self, ModuleError> {
+        match self {
+            PolyCommitBlinders::Raw(_) => Ok(self.clone()),
+            PolyCommitBlinders::Encrypted(ciphertext) => Ok(PolyCommitBlinders::Raw(
+                BlinderEncryption::decrypt(ciphertext, secret_key)?,
+            )),
+        }
+    }
+}
+
 ///
 #[derive(Debug)]
 pub struct PolyCommitChip {
@@ -79,6 +128,19 @@ impl PolyCommitChip {
         );
         advice_commitments
     }
+
+    /// Whether a commitment opens to a message and the blinder folded into it, the blinder takes
+    /// the row following the message as in [Module::layout]
+    pub fn verify_opening<Scheme: CommitmentScheme<Scalar = Fp, Curve = G1Affine>>(
+        commitment: &[G1Affine],
+        mut message: Vec<Scheme::Scalar>,
+        blinder: Option<Scheme::Scalar>,
+        num_unusable_rows: u32,
+        params: &Scheme::ParamsProver,
+    ) -> bool {
+        message.extend(blinder);
+        Self::commit::<Scheme>(message, num_unusable_rows, params) == commitment
+    }
 }
 
 impl Module<Fp> for PolyCommitChip {
@@ -115,9 +177,8 @@ impl Module<Fp> for PolyCommitChip {
         Ok(())
     }
 
-    /// L is the number of inputs to the hash function
-    /// Takes the cells containing the input values of the hash function and return the cell containing the hash output
-    /// It uses the pow5_chip to compute the hash
+    /// Takes the tensor to commit to and optionally its blinder, which is folded into the
+    /// committed column in the row following the tensor, and returns the assigned tensor
     fn layout(
         &self,
         layouter: &mut impl Layouter<Fp>,
@@ -125,25 +186,42 @@ impl Module<Fp> for PolyCommitChip {
         _: usize,
         constants: &mut ConstantsMap<Fp>,
     ) -> Result<ValTensor<Fp>, ModuleError> {
-        assert_eq!(input.len(), 1);
+        let to_module_error =
+            |e: TensorError| ModuleError::WrongInputType(format!("{:?}", e), "Value".into());
+        let committed = match input {
+            [message] => message.clone(),
+            [message, blinder] if blinder.len() == 1 => {
+                message.concat(blinder.clone()).map_err(to_module_error)?
+            }
+            _ => return Err(ModuleError::InputWrongLength(input.len())),
+        };
 
         let local_constants = constants.clone();
-        layouter
-            .assign_region(
-                || "PolyCommit",
-                |mut region| {
-                    let mut local_inner_constants = local_constants.clone();
-                    let res = self.config.inputs.assign(
-                        &mut region,
-                        0,
-                        &input[0],
-                        &mut local_inner_constants,
-                    )?;
-                    *constants = local_inner_constants;
-                    Ok(res)
-                },
-            )
-            .map_err(|e| e.into())
+        let res = layouter.assign_region(
+            || "PolyCommit",
+            |mut region| {
+                let mut local_inner_constants = local_constants.clone();
+                let res = self.config.inputs.assign(
+                    &mut region,
+                    0,
+                    &committed,
+                    &mut local_inner_constants,
+                )?;
+                *constants = local_inner_constants;
+                Ok(res)
+            },
+        )?;
+
+        if input.len() == 1 {
+            return Ok(res);
+        }
+        // the blinder isn't part of the tensor the circuit goes on with
+        let mut res = res
+            .get_slice(&[0..input[0].len()])
+            .map_err(to_module_error)?;
+        res.reshape(input[0].dims()).map_err(to_module_error)?;
+        res.set_scale(input[0].scale());
+        Ok(res)
     }
 
     ///
@@ -263,4 +341,60 @@ mod tests {
         let prover = halo2_proofs::dev::MockProver::run(K as u32, &circuit, vec![]).unwrap();
         assert_eq!(prover.verify(), Ok(()))
     }
+
+    #[test]
+    fn polycommit_blinding() {
+        use halo2_proofs::poly::kzg::commitment::KZGCommitmentScheme;
+        use halo2curves::bn256::Bn256;
+
+        let params = crate::pfsys::srs::gen_srs::<KZGCommitmentScheme<Bn256>>(K as u32);
+        let message: Vec<Fp> = (0..32u64).map(Fp::from).collect();
+        let commit = |message: Vec<Fp>| {
+            PolyCommitChip::commit::<KZGCommitmentScheme<Bn256>>(message, 6, &params)
+        };
+        let opens = |commitment: &[G1Affine], blinder: Option<Fp>| {
+            PolyCommitChip::verify_opening::<KZGCommitmentScheme<Bn256>>(
+                commitment,
+                message.clone(),
+                blinder,
+                6,
+                &params,
+            )
+        };
+
+        // without a blinder the commitment is the commitment of the message alone
+        let unblinded = commit(message.clone());
+        assert!(opens(&unblinded, None));
+
+        // the blinder takes the row following the message
+        let blinder = Fp::random(rand::rngs::OsRng);
+        let mut blinded_message = message.clone();
+        blinded_message.push(blinder);
+        let blinded = commit(blinded_message);
+        assert_ne!(blinded, unblinded);
+        assert!(opens(&blinded, Some(blinder)));
+        assert!(!opens(&blinded, Some(blinder + Fp::ONE)));
+        assert!(!opens(&blinded, None));
+        assert!(!opens(&unblinded, Some(blinder)));
+
+        // the same message and blinder always commit the same
+        let mut blinded_message = message.clone();
+        blinded_message.push(blinder);
+        assert_eq!(commit(blinded_message), blinded);
+    }
+
+    #[test]
+    fn polycommit_blinders_encryption() {
+        let blinders = PolyCommitBlinders::Raw(vec![Fp::from(3), Fp::from(5)]);
+        let (secret_key, public_key) = BlinderEncryption::gen_keys(&mut rand::rngs::OsRng);
+        let encrypted = blinders
+            .encrypt(&public_key, &Fp::random(rand::rngs::OsRng))
+            .unwrap();
+        assert!(encrypted.raw().is_none());
+        assert_eq!(encrypted.decrypt(&secret_key).unwrap(), blinders);
+        // encrypting twice would lose the blinders
+        assert!(encrypted
+            .encrypt(&public_key, &Fp::random(rand::rngs::OsRng))
+            .is_err());
+    }
 }
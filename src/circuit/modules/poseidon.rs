@@ -4,6 +4,8 @@ is already implemented in halo2_gadgets, there is no wrapper chip that makes it
 Thanks to https://github.com/summa-dev/summa-solvency/blob/master/src/chips/poseidon/hash.rs for the inspiration (and also helping us understand how to use this).
 */
 
+pub mod circomlib;
+pub mod circomlib_params;
 pub mod poseidon_params;
 pub mod spec;
 
@@ -116,6 +118,70 @@ impl<S: Spec<Fp, WIDTH, RATE> + Sync, const WIDTH: usize, const RATE: usize, con
     }
 }
 
+impl<S: Spec<Fp, WIDTH, RATE> + Sync, const WIDTH: usize, const RATE: usize, const L: usize>
+    PoseidonChip<S, WIDTH, RATE, L>
+{
+    /// Hashes previously assigned cells in blocks of L, padding the last block with `zero_val`,
+    /// and returns the cell containing the hash
+    pub fn hash_assigned(
+        &self,
+        layouter: &mut impl Layouter<Fp>,
+        mut input_cells: Vec<AssignedCell<Fp, Fp>>,
+        zero_val: &AssignedCell<Fp, Fp>,
+    ) -> Result<AssignedCell<Fp, Fp>, ModuleError> {
+        let len = input_cells.len();
+
+        let start_time = instant::Instant::now();
+
+        let mut one_iter = false;
+        // do the Tree dance baby
+        while input_cells.len() > 1 || !one_iter {
+            let hashes: Result<Vec<AssignedCell<Fp, Fp>>, ModuleError> = input_cells
+                .chunks(L)
+                .enumerate()
+                .map(|(i, block)| {
+                    let _start_time = instant::Instant::now();
+
+                    let mut block = block.to_vec();
+                    let remainder = block.len() % L;
+
+                    if remainder != 0 {
+                        block.extend(vec![zero_val.clone(); L - remainder]);
+                    }
+
+                    let pow5_chip = Pow5Chip::construct(self.config.pow5_config.clone());
+                    // initialize the hasher
+                    let hasher = Hash::<_, _, S, ConstantLength<L>, WIDTH, RATE>::init(
+                        pow5_chip,
+                        layouter.namespace(|| "block_hasher"),
+                    )?;
+
+                    let hash = hasher.hash(
+                        layouter.namespace(|| "hash"),
+                        block.to_vec().try_into().map_err(|_| Error::Synthesis)?,
+                    );
+
+                    if i == 0 {
+                        log::trace!("block (L={:?}) took: {:?}", L, _start_time.elapsed());
+                    }
+
+                    hash
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| e.into());
+
+            log::trace!("hashes (N={:?}) took: {:?}", len, start_time.elapsed());
+            one_iter = true;
+            input_cells = hashes?;
+        }
+
+        let duration = start_time.elapsed();
+        log::trace!("layout (N={:?}) took: {:?}", len, duration);
+
+        Ok(input_cells.remove(0))
+    }
+}
+
 impl<S: Spec<Fp, WIDTH, RATE> + Sync, const WIDTH: usize, const RATE: usize, const L: usize>
     Module<Fp> for PoseidonChip<S, WIDTH, RATE, L>
 {
@@ -295,68 +361,12 @@ impl<S: Spec<Fp, WIDTH, RATE> + Sync, const WIDTH: usize, const RATE: usize, con
         row_offset: usize,
         constants: &mut ConstantsMap<Fp>,
     ) -> Result<ValTensor<Fp>, ModuleError> {
-        let (mut input_cells, zero_val) = self.layout_inputs(layouter, input, constants)?;
+        let (input_cells, zero_val) = self.layout_inputs(layouter, input, constants)?;
         // extract the values from the input cells
         let mut assigned_input: Tensor<ValType<Fp>> =
             input_cells.iter().map(|e| ValType::from(e.clone())).into();
-        let len = assigned_input.len();
-
-        let start_time = instant::Instant::now();
-
-        let mut one_iter = false;
-        // do the Tree dance baby
-        while input_cells.len() > 1 || !one_iter {
-            let hashes: Result<Vec<AssignedCell<Fp, Fp>>, ModuleError> = input_cells
-                .chunks(L)
-                .enumerate()
-                .map(|(i, block)| {
-                    let _start_time = instant::Instant::now();
 
-                    let mut block = block.to_vec();
-                    let remainder = block.len() % L;
-
-                    if remainder != 0 {
-                        block.extend(vec![zero_val.clone(); L - remainder]);
-                    }
-
-                    let pow5_chip = Pow5Chip::construct(self.config.pow5_config.clone());
-                    // initialize the hasher
-                    let hasher = Hash::<_, _, S, ConstantLength<L>, WIDTH, RATE>::init(
-                        pow5_chip,
-                        layouter.namespace(|| "block_hasher"),
-                    )?;
-
-                    let hash = hasher.hash(
-                        layouter.namespace(|| "hash"),
-                        block.to_vec().try_into().map_err(|_| Error::Synthesis)?,
-                    );
-
-                    if i == 0 {
-                        log::trace!("block (L={:?}) took: {:?}", L, _start_time.elapsed());
-                    }
-
-                    hash
-                })
-                .collect::<Result<Vec<_>, _>>()
-                .map_err(|e| e.into());
-
-            log::trace!("hashes (N={:?}) took: {:?}", len, start_time.elapsed());
-            one_iter = true;
-            input_cells = hashes?;
-        }
-
-        let duration = start_time.elapsed();
-        log::trace!("layout (N={:?}) took: {:?}", len, duration);
-
-        let result = Tensor::from(input_cells.iter().map(|e| ValType::from(e.clone())));
-
-        let output = match result[0].clone() {
-            ValType::PrevAssigned(v) => v,
-            _ => {
-                log::error!("wrong input type, must be previously assigned");
-                return Err(Error::Synthesis.into());
-            }
-        };
+        let output = self.hash_assigned(layouter, input_cells, &zero_val)?;
 
         if let Some(instance) = self.config.instance {
             layouter.assign_region(
@@ -381,7 +391,7 @@ impl<S: Spec<Fp, WIDTH, RATE> + Sync, const WIDTH: usize, const RATE: usize, con
 
             Ok(assigned_input.into())
         } else {
-            Ok(result.into())
+            Ok(Tensor::from([ValType::from(output)].into_iter()).into())
         }
     }
 
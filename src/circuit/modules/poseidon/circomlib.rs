@@ -0,0 +1,532 @@
+/*
+The Poseidon hash of circomlib with 2 inputs, such that the hashes computed in the circuit match those of circom
+circuits and of circomlibjs. A hash permutes the state `[0, left, right]` and outputs the first element of the
+permuted state. A message hashes as a binary tree of such hashes, the last node of a level of odd length being
+hashed with zero, and a single element hashes with zero.
+
+The permutation of circomlib uses 57 partial rounds, which the halo2 Pow5Chip can't lay out as it applies the
+partial rounds in pairs. Each round is hence a row of its own: the state of the row and the round constants are
+mapped to the state of the next row, applying the S-box to every element in full rounds and to the first one in
+partial rounds.
+*/
+
+use halo2_proofs::arithmetic::Field;
+use halo2_proofs::halo2curves::bn256::Fr as Fp;
+use halo2_proofs::poly::Rotation;
+use halo2_proofs::{circuit::*, plonk::*};
+
+use crate::circuit::region::ConstantsMap;
+use crate::tensor::{Tensor, ValTensor, ValType};
+
+use super::super::errors::ModuleError;
+use super::super::Module;
+use super::circomlib_params::{MDS, ROUND_CONSTANTS};
+
+/// The number of instance columns used by the circomlib Poseidon hash function
+pub const NUM_INSTANCE_COLUMNS: usize = 1;
+/// The width of the permutation, the capacity element and the 2 inputs
+pub const CIRCOMLIB_WIDTH: usize = 3;
+/// The number of full rounds, half of them before the partial rounds
+const FULL_ROUNDS: usize = 8;
+/// The number of partial rounds
+const PARTIAL_ROUNDS: usize = 57;
+/// Number of rows used by a permutation, the initial state followed by a row per round
+pub const PERMUTATION_ROWS: usize = FULL_ROUNDS + PARTIAL_ROUNDS + 1;
+
+#[derive(Debug, Clone)]
+/// Configuration for the circomlib Poseidon chip
+pub struct CircomlibConfig {
+    /// the state of the permutation, the message is loaded in the same columns
+    pub state: [Column<Advice>; CIRCOMLIB_WIDTH],
+    /// the round constants, the first column also holds the constants of the message
+    pub round_constants: [Column<Fixed>; CIRCOMLIB_WIDTH],
+    /// applies a full round
+    pub q_full: Selector,
+    /// applies a partial round
+    pub q_partial: Selector,
+    ///
+    pub instance: Option<Column<Instance>>,
+}
+
+type InputAssignments = (Vec<AssignedCell<Fp, Fp>>, AssignedCell<Fp, Fp>);
+
+/// CircomlibChip hashes messages with the circomlib Poseidon hash
+#[derive(Debug, Clone)]
+pub struct CircomlibChip {
+    config: CircomlibConfig,
+}
+
+/// Whether the round applies the S-box to every element of the state
+fn is_full_round(round: usize) -> bool {
+    round < FULL_ROUNDS / 2 || round >= FULL_ROUNDS / 2 + PARTIAL_ROUNDS
+}
+
+fn sbox(x: Fp) -> Fp {
+    x.square().square() * x
+}
+
+/// Applies a round of the permutation to the state
+fn round(state: [Fp; CIRCOMLIB_WIDTH], round: usize) -> [Fp; CIRCOMLIB_WIDTH] {
+    let mut state: [Fp; CIRCOMLIB_WIDTH] =
+        std::array::from_fn(|i| state[i] + ROUND_CONSTANTS[round][i]);
+    if is_full_round(round) {
+        state = state.map(sbox);
+    } else {
+        state[0] = sbox(state[0]);
+    }
+    std::array::from_fn(|i| {
+        (0..CIRCOMLIB_WIDTH).fold(Fp::ZERO, |acc, j| acc + MDS[i][j] * state[j])
+    })
+}
+
+impl CircomlibChip {
+    /// Configuration of the CircomlibChip
+    pub fn configure_with_optional_instance(
+        meta: &mut ConstraintSystem<Fp>,
+        instance: Option<Column<Instance>>,
+    ) -> CircomlibConfig {
+        let state = [(); CIRCOMLIB_WIDTH].map(|_| meta.advice_column());
+        for column in &state {
+            meta.enable_equality(*column);
+        }
+        let round_constants = [(); CIRCOMLIB_WIDTH].map(|_| meta.fixed_column());
+        meta.enable_constant(round_constants[0]);
+
+        let q_full = meta.selector();
+        let q_partial = meta.selector();
+
+        let mut round_gate = |name: &'static str, q: Selector, full: bool| {
+            meta.create_gate(name, |meta| {
+                let q = meta.query_selector(q);
+                let sboxed = (0..CIRCOMLIB_WIDTH)
+                    .map(|i| {
+                        let x = meta.query_advice(state[i], Rotation::cur())
+                            + meta.query_fixed(round_constants[i], Rotation::cur());
+                        if full || i == 0 {
+                            x.clone() * x.clone() * x.clone() * x.clone() * x
+                        } else {
+                            x
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                (0..CIRCOMLIB_WIDTH)
+                    .map(|i| {
+                        let next = meta.query_advice(state[i], Rotation::next());
+                        let mixed = sboxed
+                            .iter()
+                            .zip(MDS[i].iter())
+                            .map(|(x, m)| x.clone() * *m)
+                            .reduce(|acc, x| acc + x)
+                            .unwrap();
+                        q.clone() * (next - mixed)
+                    })
+                    .collect::<Vec<_>>()
+            });
+        };
+        round_gate("circomlib poseidon full round", q_full, true);
+        round_gate("circomlib poseidon partial round", q_partial, false);
+
+        CircomlibConfig {
+            state,
+            round_constants,
+            q_full,
+            q_partial,
+            instance,
+        }
+    }
+
+    /// The circomlib Poseidon hash of two elements
+    pub fn hash(left: Fp, right: Fp) -> Fp {
+        (0..FULL_ROUNDS + PARTIAL_ROUNDS).fold([Fp::ZERO, left, right], round)[0]
+    }
+
+    /// The number of hashes of the binary tree of a message of some length
+    fn num_hashes(mut input_len: usize) -> usize {
+        let mut num_hashes = 0;
+        loop {
+            let level = input_len.div_ceil(2).max(1);
+            num_hashes += level;
+            if level == 1 {
+                break;
+            }
+            input_len = level;
+        }
+        num_hashes
+    }
+
+    /// Lays out the permutation of `[zero, left, right]` and returns the cell containing the
+    /// first element of the permuted state
+    fn permute(
+        &self,
+        layouter: &mut impl Layouter<Fp>,
+        zero_val: &AssignedCell<Fp, Fp>,
+        left: &AssignedCell<Fp, Fp>,
+        right: &AssignedCell<Fp, Fp>,
+    ) -> Result<AssignedCell<Fp, Fp>, Error> {
+        layouter.assign_region(
+            || "circomlib poseidon permutation",
+            |mut region| {
+                let state = self.config.state;
+                let mut cells = [zero_val, left, right]
+                    .iter()
+                    .zip(state.iter())
+                    .map(|(cell, column)| {
+                        cell.copy_advice(|| "initial state", &mut region, *column, 0)
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                let mut values = left
+                    .value()
+                    .zip(right.value())
+                    .map(|(l, r)| [Fp::ZERO, *l, *r]);
+                for r in 0..FULL_ROUNDS + PARTIAL_ROUNDS {
+                    if is_full_round(r) {
+                        self.config.q_full.enable(&mut region, r)?;
+                    } else {
+                        self.config.q_partial.enable(&mut region, r)?;
+                    }
+                    for (i, column) in self.config.round_constants.iter().enumerate() {
+                        region.assign_fixed(
+                            || "round constant",
+                            *column,
+                            r,
+                            || Value::known(ROUND_CONSTANTS[r][i]),
+                        )?;
+                    }
+                    values = values.map(|s| round(s, r));
+                    cells = state
+                        .iter()
+                        .enumerate()
+                        .map(|(i, column)| {
+                            region.assign_advice(
+                                || "state",
+                                *column,
+                                r + 1,
+                                || values.map(|s| s[i]),
+                            )
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+                }
+                Ok(cells.remove(0))
+            },
+        )
+    }
+
+    /// Hashes previously assigned cells as a binary tree, padding odd levels with `zero_val`, and
+    /// returns the cell containing the root
+    pub fn hash_assigned(
+        &self,
+        layouter: &mut impl Layouter<Fp>,
+        mut input_cells: Vec<AssignedCell<Fp, Fp>>,
+        zero_val: &AssignedCell<Fp, Fp>,
+    ) -> Result<AssignedCell<Fp, Fp>, ModuleError> {
+        let mut one_iter = false;
+        while input_cells.len() > 1 || !one_iter {
+            input_cells = input_cells
+                .chunks(2)
+                .map(|pair| {
+                    self.permute(
+                        layouter,
+                        zero_val,
+                        &pair[0],
+                        pair.get(1).unwrap_or(zero_val),
+                    )
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            one_iter = true;
+        }
+
+        Ok(input_cells.remove(0))
+    }
+}
+
+impl Module<Fp> for CircomlibChip {
+    type Config = CircomlibConfig;
+    type InputAssignments = InputAssignments;
+    type RunInputs = Vec<Fp>;
+    type Params = ();
+
+    fn name(&self) -> &'static str {
+        "Circomlib Poseidon"
+    }
+
+    fn instance_increment_input(&self) -> Vec<usize> {
+        vec![1]
+    }
+
+    /// Constructs a new CircomlibChip
+    fn new(config: Self::Config) -> Self {
+        Self { config }
+    }
+
+    /// Configuration of the CircomlibChip
+    fn configure(meta: &mut ConstraintSystem<Fp>, _: Self::Params) -> Self::Config {
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        Self::configure_with_optional_instance(meta, Some(instance))
+    }
+
+    fn layout_inputs(
+        &self,
+        layouter: &mut impl Layouter<Fp>,
+        message: &[ValTensor<Fp>],
+        constants: &mut ConstantsMap<Fp>,
+    ) -> Result<Self::InputAssignments, ModuleError> {
+        assert_eq!(message.len(), 1);
+        let message = message[0].clone();
+        let state = self.config.state;
+
+        let res = layouter.assign_region(
+            || "load message",
+            |mut region| {
+                let position = |i: usize| (state[i % CIRCOMLIB_WIDTH], i / CIRCOMLIB_WIDTH);
+                let assigned_message: Result<Vec<AssignedCell<Fp, Fp>>, ModuleError> =
+                    match &message {
+                        ValTensor::Value { inner: v, .. } => {
+                            v.iter()
+                                .enumerate()
+                                .map(|(i, value)| {
+                                    let (column, row) = position(i);
+                                    match value {
+                                        ValType::Value(v) => region
+                                            .assign_advice(|| "load message", column, row, || *v)
+                                            .map_err(|e| e.into()),
+                                        ValType::PrevAssigned(v)
+                                        | ValType::AssignedConstant(v, ..) => Ok(v.clone()),
+                                        ValType::Constant(f) => match constants.get(f) {
+                                            Some(c) => c
+                                                .assigned_cell()
+                                                .ok_or(ModuleError::ConstantNotAssigned),
+                                            None => {
+                                                let res = region.assign_advice_from_constant(
+                                                    || "load message",
+                                                    column,
+                                                    row,
+                                                    *f,
+                                                )?;
+                                                constants.insert(
+                                                    *f,
+                                                    ValType::AssignedConstant(res.clone(), *f),
+                                                );
+                                                Ok(res)
+                                            }
+                                        },
+                                        e => Err(ModuleError::WrongInputType(
+                                            format!("{:?}", e),
+                                            "PrevAssigned".to_string(),
+                                        )),
+                                    }
+                                })
+                                .collect()
+                        }
+                        ValTensor::Instance {
+                            dims,
+                            inner: col,
+                            idx,
+                            initial_offset,
+                            ..
+                        } => {
+                            let num_elems = dims[*idx].iter().product::<usize>();
+                            (0..num_elems)
+                                .map(|i| {
+                                    let (column, row) = position(i);
+                                    region.assign_advice_from_instance(
+                                        || "pub input anchor",
+                                        *col,
+                                        initial_offset + i,
+                                        column,
+                                        row,
+                                    )
+                                })
+                                .collect::<Result<Vec<_>, _>>()
+                                .map_err(|e| e.into())
+                        }
+                    };
+
+                let zero_val = region.assign_advice_from_constant(
+                    || "zero",
+                    state[0],
+                    message.len() / CIRCOMLIB_WIDTH + 1,
+                    Fp::ZERO,
+                )?;
+
+                Ok((assigned_message?, zero_val))
+            },
+        );
+        res.map_err(|e| e.into())
+    }
+
+    /// Takes the cells containing the message and returns the cell containing the root of its tree
+    fn layout(
+        &self,
+        layouter: &mut impl Layouter<Fp>,
+        input: &[ValTensor<Fp>],
+        row_offset: usize,
+        constants: &mut ConstantsMap<Fp>,
+    ) -> Result<ValTensor<Fp>, ModuleError> {
+        let (input_cells, zero_val) = self.layout_inputs(layouter, input, constants)?;
+        let mut assigned_input: Tensor<ValType<Fp>> =
+            input_cells.iter().map(|e| ValType::from(e.clone())).into();
+
+        let output = self.hash_assigned(layouter, input_cells, &zero_val)?;
+
+        if let Some(instance) = self.config.instance {
+            layouter.constrain_instance(output.cell(), instance, row_offset)?;
+
+            assigned_input.reshape(input[0].dims()).map_err(|e| {
+                log::error!("reshape failed: {:?}", e);
+                Error::Synthesis
+            })?;
+
+            Ok(assigned_input.into())
+        } else {
+            Ok(Tensor::from([ValType::from(output)].into_iter()).into())
+        }
+    }
+
+    /// Computes the root of the binary tree of the message
+    fn run(message: Vec<Fp>) -> Result<Vec<Vec<Fp>>, ModuleError> {
+        let mut hashes = message;
+        let mut one_iter = false;
+        while hashes.len() > 1 || !one_iter {
+            hashes = hashes
+                .chunks(2)
+                .map(|pair| Self::hash(pair[0], pair.get(1).copied().unwrap_or(Fp::ZERO)))
+                .collect();
+            one_iter = true;
+        }
+        Ok(vec![hashes])
+    }
+
+    /// Number of rows used by the permutations of the tree of a message of some length, and to
+    /// load the message
+    fn num_rows(input_len: usize) -> usize {
+        Self::num_hashes(input_len) * PERMUTATION_ROWS + input_len / CIRCOMLIB_WIDTH + 2
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::circuit::modules::ModulePlanner;
+
+    use super::*;
+
+    use std::collections::HashMap;
+
+    use halo2_proofs::circuit::{Layouter, Value};
+    use halo2_proofs::plonk::{Circuit, ConstraintSystem};
+    use halo2curves::ff::PrimeField;
+
+    struct HashCircuit {
+        message: ValTensor<Fp>,
+    }
+
+    impl Circuit<Fp> for HashCircuit {
+        type Config = CircomlibConfig;
+        type FloorPlanner = ModulePlanner;
+        type Params = ();
+
+        fn without_witnesses(&self) -> Self {
+            let message: Tensor<ValType<Fp>> = (0..self.message.len())
+                .map(|_| Value::<Fp>::unknown().into())
+                .into();
+            Self {
+                message: message.into(),
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            CircomlibChip::configure(meta, ())
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let chip = CircomlibChip::new(config);
+            chip.layout(
+                &mut layouter,
+                &[self.message.clone()],
+                0,
+                &mut HashMap::new(),
+            )?;
+            Ok(())
+        }
+    }
+
+    fn circuit(message: &[Fp]) -> HashCircuit {
+        let message: Tensor<ValType<Fp>> = message.iter().map(|m| Value::known(*m).into()).into();
+        HashCircuit {
+            message: message.into(),
+        }
+    }
+
+    fn felt(x: &str) -> Fp {
+        Fp::from_str_vartime(x).unwrap()
+    }
+
+    #[test]
+    fn circomlib_test_vectors() {
+        // poseidon([1, 2]) and poseidon([3, 4]) of circomlibjs
+        assert_eq!(
+            CircomlibChip::hash(Fp::from(1), Fp::from(2)),
+            felt("7853200120776062878684798364095072458815029376092732009249414926327459813530")
+        );
+        assert_eq!(
+            CircomlibChip::hash(Fp::from(3), Fp::from(4)),
+            felt("14763215145315200506921711489642608356394854266165572616578112107564877678998")
+        );
+    }
+
+    #[test]
+    fn circomlib_tree() {
+        let message = (1..=5u64).map(Fp::from).collect::<Vec<_>>();
+        let level = [
+            CircomlibChip::hash(message[0], message[1]),
+            CircomlibChip::hash(message[2], message[3]),
+            CircomlibChip::hash(message[4], Fp::ZERO),
+        ];
+        let root = CircomlibChip::hash(
+            CircomlibChip::hash(level[0], level[1]),
+            CircomlibChip::hash(level[2], Fp::ZERO),
+        );
+        assert_eq!(CircomlibChip::run(message).unwrap(), vec![vec![root]]);
+        // a single element is hashed with zero
+        assert_eq!(
+            CircomlibChip::run(vec![Fp::from(1)]).unwrap(),
+            vec![vec![CircomlibChip::hash(Fp::from(1), Fp::ZERO)]]
+        );
+    }
+
+    #[test]
+    fn circomlib_hash() {
+        let message = [Fp::from(1), Fp::from(2)];
+        let output = CircomlibChip::run(message.to_vec()).unwrap();
+
+        let k = 8;
+        let prover = halo2_proofs::dev::MockProver::run(k, &circuit(&message), output).unwrap();
+        assert_eq!(prover.verify(), Ok(()))
+    }
+
+    #[test]
+    fn circomlib_hash_longer_input() {
+        let message = (0..7u64).map(Fp::from).collect::<Vec<_>>();
+        let output = CircomlibChip::run(message.clone()).unwrap();
+
+        let k = 10;
+        let prover = halo2_proofs::dev::MockProver::run(k, &circuit(&message), output).unwrap();
+        assert_eq!(prover.verify(), Ok(()))
+    }
+
+    #[test]
+    fn circomlib_hash_wrong_output() {
+        let message = [Fp::from(1), Fp::from(2)];
+        let output = vec![vec![CircomlibChip::hash(Fp::from(2), Fp::from(1))]];
+
+        let k = 8;
+        let prover = halo2_proofs::dev::MockProver::run(k, &circuit(&message), output).unwrap();
+        assert!(prover.verify().is_err())
+    }
+}
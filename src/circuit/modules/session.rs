@@ -0,0 +1,331 @@
+/*
+Session digests chaining the proofs of a sequence of inferences. The commitment of the outputs of a step is their
+Poseidon hash, hashed as by the Poseidon chip, and the digest of the step is the Poseidon hash of the digest of the
+previous step and of the commitment. Both digests are public, such that the proofs of a session only chain in the
+order they were generated: the next digest of a proof is the previous digest of the one that follows it.
+*/
+
+use halo2_gadgets::poseidon::primitives::{ConstantLength, Hash, Spec};
+use halo2_proofs::halo2curves::bn256::Fr as Fp;
+use halo2_proofs::{circuit::*, plonk::*};
+use serde::{Deserialize, Serialize};
+
+use std::marker::PhantomData;
+
+use crate::circuit::region::ConstantsMap;
+use crate::tensor::{Tensor, ValTensor, ValType};
+
+use super::errors::ModuleError;
+use super::poseidon::{PoseidonChip, PoseidonConfig};
+use super::Module;
+
+/// The number of instance columns used by the session digests
+pub const NUM_INSTANCE_COLUMNS: usize = 1;
+
+#[derive(Debug, Clone)]
+/// Configuration for the session chip, the commitment and the digest are hashed in the columns of the Poseidon config
+pub struct SessionConfig<const WIDTH: usize, const RATE: usize> {
+    ///
+    pub poseidon: PoseidonConfig<WIDTH, RATE>,
+    ///
+    pub instance: Option<Column<Instance>>,
+}
+
+/// The commitment of the outputs, and the digests of the session once the previous digest is attached to the witness
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SessionWitness {
+    /// the commitment the outputs hash to
+    pub commitment: Fp,
+    /// the public digest of the previous step of the session
+    pub prev_digest: Option<Fp>,
+    /// the public digest of this step, of the previous digest and the commitment
+    pub next_digest: Option<Fp>,
+}
+
+type InputAssignments = (Vec<AssignedCell<Fp, Fp>>, AssignedCell<Fp, Fp>);
+
+/// SessionChip chains the commitment of a message to the digest of the previous step, hashing with the Poseidon chip
+#[derive(Debug, Clone)]
+pub struct SessionChip<
+    S: Spec<Fp, WIDTH, RATE> + Sync,
+    const WIDTH: usize,
+    const RATE: usize,
+    const L: usize,
+> {
+    config: SessionConfig<WIDTH, RATE>,
+    _marker: PhantomData<S>,
+}
+
+impl<S: Spec<Fp, WIDTH, RATE> + Sync, const WIDTH: usize, const RATE: usize, const L: usize>
+    SessionChip<S, WIDTH, RATE, L>
+{
+    /// Configures the chip to hash in the columns of an existing Poseidon config
+    pub fn configure_with_poseidon(
+        poseidon: PoseidonConfig<WIDTH, RATE>,
+        instance: Option<Column<Instance>>,
+    ) -> SessionConfig<WIDTH, RATE> {
+        SessionConfig { poseidon, instance }
+    }
+
+    /// The commitment a message hashes to
+    pub fn commitment(message: Vec<Fp>) -> Result<Fp, ModuleError> {
+        Ok(PoseidonChip::<S, WIDTH, RATE, L>::run(message)?[0][0])
+    }
+
+    /// The digest chaining a commitment to the digest of the previous step
+    pub fn next_digest(prev_digest: Fp, commitment: Fp) -> Fp {
+        Hash::<_, S, ConstantLength<2>, WIDTH, RATE>::init().hash([prev_digest, commitment])
+    }
+}
+
+impl<S: Spec<Fp, WIDTH, RATE> + Sync, const WIDTH: usize, const RATE: usize, const L: usize>
+    Module<Fp> for SessionChip<S, WIDTH, RATE, L>
+{
+    type Config = SessionConfig<WIDTH, RATE>;
+    type InputAssignments = InputAssignments;
+    /// the message and the previous digest
+    type RunInputs = (Vec<Fp>, Fp);
+    type Params = ();
+
+    fn name(&self) -> &'static str {
+        "Session"
+    }
+
+    fn instance_increment_input(&self) -> Vec<usize> {
+        vec![2]
+    }
+
+    /// Constructs a new SessionChip
+    fn new(config: Self::Config) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Configuration of the SessionChip
+    fn configure(meta: &mut ConstraintSystem<Fp>, _: Self::Params) -> Self::Config {
+        let poseidon =
+            PoseidonChip::<S, WIDTH, RATE, L>::configure_with_optional_instance(meta, None);
+
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+
+        Self::configure_with_poseidon(poseidon, Some(instance))
+    }
+
+    fn layout_inputs(
+        &self,
+        layouter: &mut impl Layouter<Fp>,
+        message: &[ValTensor<Fp>],
+        constants: &mut ConstantsMap<Fp>,
+    ) -> Result<Self::InputAssignments, ModuleError> {
+        PoseidonChip::<S, WIDTH, RATE, L>::new(self.config.poseidon.clone()).layout_inputs(
+            layouter,
+            &message[..1],
+            constants,
+        )
+    }
+
+    /// Takes the message and the previous digest, and constrains the previous digest to the instance at `row_offset`
+    /// and the digest they chain to to the instance that follows it
+    fn layout(
+        &self,
+        layouter: &mut impl Layouter<Fp>,
+        input: &[ValTensor<Fp>],
+        row_offset: usize,
+        constants: &mut ConstantsMap<Fp>,
+    ) -> Result<ValTensor<Fp>, ModuleError> {
+        if input.len() != 2 {
+            return Err(ModuleError::InputWrongLength(input.len()));
+        }
+        let prev_digest = input[1]
+            .get_inner()
+            .map_err(|e| ModuleError::WrongInputType(format!("{:?}", e), "Value".to_string()))?;
+        if prev_digest.len() != 1 {
+            return Err(ModuleError::InputWrongLength(prev_digest.len()));
+        }
+
+        let (message_cells, zero_val) = self.layout_inputs(layouter, input, constants)?;
+        let mut assigned_input: Tensor<ValType<Fp>> = message_cells
+            .iter()
+            .map(|e| ValType::from(e.clone()))
+            .into();
+
+        let commitment_chip = PoseidonChip::<S, WIDTH, RATE, L>::new(self.config.poseidon.clone());
+        let digest_chip = PoseidonChip::<S, WIDTH, RATE, 2>::new(self.config.poseidon.clone());
+
+        let commitment = commitment_chip.hash_assigned(layouter, message_cells, &zero_val)?;
+
+        let preimage = layouter.assign_region(
+            || "session digest preimage",
+            |mut region| {
+                let prev_digest = region.assign_advice(
+                    || "previous digest",
+                    self.config.poseidon.hash_inputs[0],
+                    0,
+                    || prev_digest[0],
+                )?;
+                let commitment = commitment.copy_advice(
+                    || "commitment",
+                    &mut region,
+                    self.config.poseidon.hash_inputs[1],
+                    0,
+                )?;
+                Ok(vec![prev_digest, commitment])
+            },
+        )?;
+        let prev_digest = preimage[0].clone();
+        let next_digest = digest_chip.hash_assigned(layouter, preimage, &zero_val)?;
+
+        if let Some(instance) = self.config.instance {
+            layouter.constrain_instance(prev_digest.cell(), instance, row_offset)?;
+            layouter.constrain_instance(next_digest.cell(), instance, row_offset + 1)?;
+
+            assigned_input.reshape(input[0].dims()).map_err(|e| {
+                log::error!("reshape failed: {:?}", e);
+                Error::Synthesis
+            })?;
+
+            Ok(assigned_input.into())
+        } else {
+            Ok(
+                Tensor::from([ValType::from(prev_digest), ValType::from(next_digest)].into_iter())
+                    .into(),
+            )
+        }
+    }
+
+    /// Computes the previous digest and the digest it chains to with the commitment of the message
+    fn run((message, prev_digest): Self::RunInputs) -> Result<Vec<Vec<Fp>>, ModuleError> {
+        let commitment = Self::commitment(message)?;
+        Ok(vec![vec![
+            prev_digest,
+            Self::next_digest(prev_digest, commitment),
+        ]])
+    }
+
+    /// Number of rows used to hash the commitment of a message of some length and the digest
+    fn num_rows(input_len: usize) -> usize {
+        PoseidonChip::<S, WIDTH, RATE, L>::num_rows(input_len)
+            + PoseidonChip::<S, WIDTH, RATE, 2>::num_rows(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::circuit::modules::poseidon::spec::{PoseidonSpec, POSEIDON_RATE, POSEIDON_WIDTH};
+    use crate::circuit::modules::ModulePlanner;
+
+    use super::*;
+
+    use std::collections::HashMap;
+
+    use halo2_proofs::circuit::{Layouter, Value};
+    use halo2_proofs::plonk::{Circuit, ConstraintSystem};
+
+    const WIDTH: usize = POSEIDON_WIDTH;
+    const RATE: usize = POSEIDON_RATE;
+    const L: usize = 4;
+
+    type Chip = SessionChip<PoseidonSpec, WIDTH, RATE, L>;
+
+    fn message() -> Vec<Fp> {
+        (0..6u64).map(Fp::from).collect()
+    }
+
+    fn values(t: &[Fp]) -> ValTensor<Fp> {
+        let t: Tensor<ValType<Fp>> = t.iter().map(|x| Value::known(*x).into()).into();
+        t.into()
+    }
+
+    struct SessionCircuit {
+        message: ValTensor<Fp>,
+        prev_digest: ValTensor<Fp>,
+    }
+
+    impl Circuit<Fp> for SessionCircuit {
+        type Config = SessionConfig<WIDTH, RATE>;
+        type FloorPlanner = ModulePlanner;
+        type Params = ();
+
+        fn without_witnesses(&self) -> Self {
+            let unknown = |n: usize| -> ValTensor<Fp> {
+                let t: Tensor<ValType<Fp>> = (0..n).map(|_| Value::<Fp>::unknown().into()).into();
+                t.into()
+            };
+            Self {
+                message: unknown(self.message.len()),
+                prev_digest: unknown(1),
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            Chip::configure(meta, ())
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let chip = Chip::new(config);
+            chip.layout(
+                &mut layouter,
+                &[self.message.clone(), self.prev_digest.clone()],
+                0,
+                &mut HashMap::new(),
+            )?;
+            Ok(())
+        }
+    }
+
+    fn circuit(message: &[Fp], prev_digest: Fp) -> SessionCircuit {
+        SessionCircuit {
+            message: values(message),
+            prev_digest: values(&[prev_digest]),
+        }
+    }
+
+    #[test]
+    fn digest_of_commitment() {
+        let prev_digest = Fp::from(7);
+        let commitment = Chip::commitment(message()).unwrap();
+        assert_eq!(
+            Chip::run((message(), prev_digest)).unwrap(),
+            vec![vec![
+                prev_digest,
+                Chip::next_digest(prev_digest, commitment)
+            ]]
+        );
+        // the same outputs chain to a different digest after a different step
+        assert_ne!(
+            Chip::next_digest(prev_digest, commitment),
+            Chip::next_digest(Fp::from(8), commitment)
+        );
+    }
+
+    #[test]
+    fn session_digests() {
+        let prev_digest = Fp::from(7);
+        let digests = Chip::run((message(), prev_digest)).unwrap().remove(0);
+
+        let k = 10;
+        let prover =
+            halo2_proofs::dev::MockProver::run(k, &circuit(&message(), prev_digest), vec![digests])
+                .unwrap();
+        assert_eq!(prover.verify(), Ok(()))
+    }
+
+    #[test]
+    fn session_wrong_prev_digest() {
+        let digests = Chip::run((message(), Fp::from(7))).unwrap().remove(0);
+
+        let k = 10;
+        let prover =
+            halo2_proofs::dev::MockProver::run(k, &circuit(&message(), Fp::from(8)), vec![digests])
+                .unwrap();
+        assert!(prover.verify().is_err())
+    }
+}
@@ -0,0 +1,876 @@
+/*
+A SHA-256 chip for hashing tensors of quantized values. The tensor is serialized as the concatenation of its
+elements, each a big-endian two's complement 64 bit integer, such that the digest can be recomputed by any
+standard SHA-256 implementation.
+
+Every 32 bit word of the message schedule and of the `a` and `e` state words is decomposed into bits in its own
+row, the remaining state words of a round being the `a` and `e` words of the previous rounds. A block hence takes
+68 rows: 4 rows for the initial state (laid out as `d, c, b, a` and `h, g, f, e`) followed by the 64 rounds.
+The state fed forward at the end of a block is the initial state of the next one, and after the last block 4 more
+rows hold the digest, which is exposed as two 128 bit field elements.
+*/
+
+use halo2_proofs::arithmetic::Field;
+use halo2_proofs::halo2curves::bn256::Fr as Fp;
+use halo2_proofs::poly::Rotation;
+use halo2_proofs::{circuit::*, plonk::*};
+use halo2curves::ff::PrimeField;
+use itertools::Itertools;
+
+use crate::circuit::region::ConstantsMap;
+use crate::fieldutils::{felt_to_integer_rep, integer_rep_to_felt};
+use crate::tensor::{Tensor, ValTensor, ValType};
+
+use super::errors::ModuleError;
+use super::Module;
+
+/// The number of instance columns used by the SHA-256 hash function
+pub const NUM_INSTANCE_COLUMNS: usize = 1;
+/// Number of rows used by a block, the initial state followed by the 64 rounds
+pub const BLOCK_ROWS: usize = 68;
+/// Number of rows used by the state words at the start of a block (and by the digest)
+const STATE_ROWS: usize = 4;
+/// Number of bits of the carry of the additions producing a word
+const CARRY_BITS: usize = 3;
+
+/// Column groups of the message schedule, `a` and `e` words
+const W: usize = 0;
+const A: usize = 1;
+const E: usize = 2;
+
+/// First 32 bits of the fractional parts of the square roots of the first 8 primes
+const IV: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+/// First 32 bits of the fractional parts of the cube roots of the first 64 primes
+const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+#[derive(Debug, Clone)]
+/// Configuration for the SHA-256 chip
+pub struct Sha256Config {
+    /// the bits of the message schedule, `a` and `e` words
+    pub bits: [[Column<Advice>; 32]; 3],
+    /// the message schedule, `a` and `e` words
+    pub words: [Column<Advice>; 3],
+    /// the bits of the carries of the additions producing the words
+    pub carries: [[Column<Advice>; CARRY_BITS]; 3],
+    /// the serialized tensor elements and the two halves of the digest
+    pub element: Column<Advice>,
+    /// round constants, initial state of the `a` and `e` words and the message padding
+    pub fixed: [Column<Fixed>; 4],
+    /// decomposes every word into bits
+    pub q_word: Selector,
+    /// extends the message schedule
+    pub q_schedule: Selector,
+    /// applies a round of the compression function
+    pub q_round: Selector,
+    /// constrains the state words to the initial hash value
+    pub q_iv: Selector,
+    /// adds the state at the end of the previous block to its initial state
+    pub q_feed_forward: Selector,
+    /// constrains a message word to the padding
+    pub q_pad: Selector,
+    /// constrains two message words to the serialization of an element
+    pub q_element: Selector,
+    /// packs the final state into the digest
+    pub q_digest: Selector,
+    ///
+    pub instance: Option<Column<Instance>>,
+}
+
+type InputAssignments = (Vec<AssignedCell<Fp, Fp>>, Vec<AssignedCell<Fp, Fp>>);
+
+/// A row of the hashing region
+#[derive(Debug, Clone, Copy, Default)]
+struct Sha256Row {
+    /// the message schedule, `a` and `e` words
+    words: [u32; 3],
+    /// the carries of the additions producing the words
+    carries: [u32; 3],
+}
+
+/// SHA-256 chip that hashes the serialization of a tensor
+#[derive(Debug, Clone)]
+pub struct Sha256Chip {
+    config: Sha256Config,
+}
+
+impl Sha256Chip {
+    /// Configuration of the Sha256Chip
+    pub fn configure_with_optional_instance(
+        meta: &mut ConstraintSystem<Fp>,
+        instance: Option<Column<Instance>>,
+    ) -> Sha256Config {
+        let bits = [(); 3].map(|_| [(); 32].map(|_| meta.advice_column()));
+        let words = [(); 3].map(|_| meta.advice_column());
+        let carries = [(); 3].map(|_| [(); CARRY_BITS].map(|_| meta.advice_column()));
+        let element = meta.advice_column();
+        meta.enable_equality(element);
+        let fixed = [(); 4].map(|_| meta.fixed_column());
+
+        let q_word = meta.selector();
+        let q_schedule = meta.selector();
+        let q_round = meta.selector();
+        let q_iv = meta.selector();
+        let q_feed_forward = meta.selector();
+        let q_pad = meta.selector();
+        let q_element = meta.selector();
+        let q_digest = meta.selector();
+
+        let two_32 = Fp::from(1u64 << 32);
+
+        meta.create_gate("sha256 word decomposition", |meta| {
+            let q = meta.query_selector(q_word);
+            let mut constraints = vec![];
+            for group in 0..3 {
+                let word_bits = query_bits(meta, &bits[group], 0);
+                for bit in word_bits
+                    .iter()
+                    .chain(&query_bits(meta, &carries[group], 0))
+                {
+                    constraints.push(q.clone() * bit.clone() * (bit.clone() - one()));
+                }
+                let word = meta.query_advice(words[group], Rotation::cur());
+                constraints.push(q.clone() * (pack(&word_bits) - word));
+            }
+            constraints
+        });
+
+        meta.create_gate("sha256 message schedule", |meta| {
+            let q = meta.query_selector(q_schedule);
+            let w = meta.query_advice(words[W], Rotation::cur());
+            let carry = pack(&query_bits(meta, &carries[W], 0));
+            let w_2 = query_bits(meta, &bits[W], -2);
+            let w_15 = query_bits(meta, &bits[W], -15);
+            let sigma_1 = pack(&xor3([rotr(&w_2, 17), rotr(&w_2, 19), shr(&w_2, 10)]));
+            let sigma_0 = pack(&xor3([rotr(&w_15, 7), rotr(&w_15, 18), shr(&w_15, 3)]));
+            let w_7 = meta.query_advice(words[W], Rotation(-7));
+            let w_16 = meta.query_advice(words[W], Rotation(-16));
+            vec![q * (w + carry * two_32 - (sigma_1 + w_7 + sigma_0 + w_16))]
+        });
+
+        meta.create_gate("sha256 round", |meta| {
+            let q = meta.query_selector(q_round);
+            let [a, b, c] = [0, -1, -2].map(|rot| query_bits(meta, &bits[A], rot));
+            let [e, f, g] = [0, -1, -2].map(|rot| query_bits(meta, &bits[E], rot));
+            let d = meta.query_advice(words[A], Rotation(-3));
+            let h = meta.query_advice(words[E], Rotation(-3));
+            let k = meta.query_fixed(fixed[0], Rotation::cur());
+            let w = meta.query_advice(words[W], Rotation::cur());
+
+            let sum_1 = pack(&xor3([rotr(&e, 6), rotr(&e, 11), rotr(&e, 25)]));
+            let sum_0 = pack(&xor3([rotr(&a, 2), rotr(&a, 13), rotr(&a, 22)]));
+            let ch = pack(
+                &(0..32)
+                    .map(|i| e[i].clone() * (f[i].clone() - g[i].clone()) + g[i].clone())
+                    .collect_vec(),
+            );
+            let maj = pack(
+                &(0..32)
+                    .map(|i| {
+                        a[i].clone() * b[i].clone()
+                            + a[i].clone() * c[i].clone()
+                            + b[i].clone() * c[i].clone()
+                            - a[i].clone() * b[i].clone() * c[i].clone() * Fp::from(2)
+                    })
+                    .collect_vec(),
+            );
+
+            let t_1 = h + sum_1 + ch + k + w;
+            let t_2 = sum_0 + maj;
+
+            let next_a = meta.query_advice(words[A], Rotation::next())
+                + pack(&query_bits(meta, &carries[A], 1)) * two_32;
+            let next_e = meta.query_advice(words[E], Rotation::next())
+                + pack(&query_bits(meta, &carries[E], 1)) * two_32;
+
+            vec![
+                q.clone() * (next_e - (d + t_1.clone())),
+                q * (next_a - (t_1 + t_2)),
+            ]
+        });
+
+        meta.create_gate("sha256 initial state", |meta| {
+            let q = meta.query_selector(q_iv);
+            let a = meta.query_advice(words[A], Rotation::cur());
+            let e = meta.query_advice(words[E], Rotation::cur());
+            let iv_a = meta.query_fixed(fixed[1], Rotation::cur());
+            let iv_e = meta.query_fixed(fixed[2], Rotation::cur());
+            vec![q.clone() * (a - iv_a), q * (e - iv_e)]
+        });
+
+        meta.create_gate("sha256 feed forward", |meta| {
+            let q = meta.query_selector(q_feed_forward);
+            [A, E]
+                .map(|group| {
+                    let word = meta.query_advice(words[group], Rotation::cur())
+                        + pack(&query_bits(meta, &carries[group], 0)) * two_32;
+                    let initial = meta.query_advice(words[group], Rotation(-(BLOCK_ROWS as i32)));
+                    let last = meta.query_advice(words[group], Rotation(-(STATE_ROWS as i32)));
+                    q.clone() * (word - (initial + last))
+                })
+                .to_vec()
+        });
+
+        meta.create_gate("sha256 padding", |meta| {
+            let q = meta.query_selector(q_pad);
+            let w = meta.query_advice(words[W], Rotation::cur());
+            let pad = meta.query_fixed(fixed[3], Rotation::cur());
+            vec![q * (w - pad)]
+        });
+
+        meta.create_gate("sha256 element serialization", |meta| {
+            let q = meta.query_selector(q_element);
+            let element = meta.query_advice(element, Rotation::cur());
+            let sign = meta.query_advice(bits[W][31], Rotation::cur());
+            let hi = meta.query_advice(words[W], Rotation::cur());
+            let lo = meta.query_advice(words[W], Rotation::next());
+            vec![q * (element + sign * Fp::from_u128(1 << 64) - (hi * two_32 + lo))]
+        });
+
+        meta.create_gate("sha256 digest", |meta| {
+            let q = meta.query_selector(q_digest);
+            [A, E]
+                .iter()
+                .enumerate()
+                .map(|(rot, group)| {
+                    let half = meta.query_advice(element, Rotation(rot as i32));
+                    let packed = (0..STATE_ROWS).fold(Expression::Constant(Fp::ZERO), |acc, i| {
+                        acc * two_32 + meta.query_advice(words[*group], Rotation((3 - i) as i32))
+                    });
+                    q.clone() * (half - packed)
+                })
+                .collect_vec()
+        });
+
+        Sha256Config {
+            bits,
+            words,
+            carries,
+            element,
+            fixed,
+            q_word,
+            q_schedule,
+            q_round,
+            q_iv,
+            q_feed_forward,
+            q_pad,
+            q_element,
+            q_digest,
+            instance,
+        }
+    }
+
+    /// Number of blocks of the padded serialization of `input_len` elements
+    fn num_blocks(input_len: usize) -> usize {
+        // two words per element, the terminating bit and the 64 bit message length
+        (2 * input_len + 3).div_ceil(16)
+    }
+
+    /// Assign a row of the hashing region
+    fn assign_row(
+        &self,
+        region: &mut Region<'_, Fp>,
+        offset: usize,
+        row: Value<Sha256Row>,
+    ) -> Result<(), Error> {
+        self.config.q_word.enable(region, offset)?;
+        for group in 0..3 {
+            let word = row.map(|r| r.words[group]);
+            region.assign_advice(
+                || "word",
+                self.config.words[group],
+                offset,
+                || word.map(|w| Fp::from(w as u64)),
+            )?;
+            for (i, col) in self.config.bits[group].iter().enumerate() {
+                region.assign_advice(
+                    || "bit",
+                    *col,
+                    offset,
+                    || word.map(|w| Fp::from(((w >> i) & 1) as u64)),
+                )?;
+            }
+            let carry = row.map(|r| r.carries[group]);
+            for (i, col) in self.config.carries[group].iter().enumerate() {
+                region.assign_advice(
+                    || "carry",
+                    *col,
+                    offset,
+                    || carry.map(|c| Fp::from(((c >> i) & 1) as u64)),
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Query the bits of a column group at a rotation
+fn query_bits<const N: usize>(
+    meta: &mut VirtualCells<'_, Fp>,
+    cols: &[Column<Advice>; N],
+    rot: i32,
+) -> Vec<Expression<Fp>> {
+    cols.iter()
+        .map(|col| meta.query_advice(*col, Rotation(rot)))
+        .collect()
+}
+
+fn one() -> Expression<Fp> {
+    Expression::Constant(Fp::ONE)
+}
+
+/// Pack little-endian bits into an integer
+fn pack(bits: &[Expression<Fp>]) -> Expression<Fp> {
+    bits.iter()
+        .enumerate()
+        .fold(Expression::Constant(Fp::ZERO), |acc, (i, bit)| {
+            acc + bit.clone() * Fp::from(1u64 << i)
+        })
+}
+
+/// Bits of a word rotated right by `n`
+fn rotr(bits: &[Expression<Fp>], n: usize) -> Vec<Expression<Fp>> {
+    (0..32).map(|i| bits[(i + n) % 32].clone()).collect()
+}
+
+/// Bits of a word shifted right by `n`
+fn shr(bits: &[Expression<Fp>], n: usize) -> Vec<Expression<Fp>> {
+    (0..32)
+        .map(|i| {
+            bits.get(i + n)
+                .cloned()
+                .unwrap_or(Expression::Constant(Fp::ZERO))
+        })
+        .collect()
+}
+
+/// Bitwise xor of three words
+fn xor3(words: [Vec<Expression<Fp>>; 3]) -> Vec<Expression<Fp>> {
+    let xor = |a: Expression<Fp>, b: Expression<Fp>| a.clone() + b.clone() - a * b * Fp::from(2);
+    (0..32)
+        .map(|i| {
+            xor(
+                xor(words[0][i].clone(), words[1][i].clone()),
+                words[2][i].clone(),
+            )
+        })
+        .collect()
+}
+
+/// The serialized elements followed by the SHA-256 padding, as 32 bit words
+fn message_words(elements: &[i64]) -> Vec<u32> {
+    let mut words = elements
+        .iter()
+        .flat_map(|x| {
+            let x = *x as u64;
+            [(x >> 32) as u32, x as u32]
+        })
+        .collect_vec();
+    words.push(0x8000_0000);
+    while words.len() % 16 != 14 {
+        words.push(0);
+    }
+    let bit_len = 64 * elements.len() as u64;
+    words.extend([(bit_len >> 32) as u32, bit_len as u32]);
+    words
+}
+
+/// The rows of the hashing region for a serialized tensor
+fn trace(elements: &[i64]) -> Vec<Sha256Row> {
+    let words = message_words(elements);
+    let num_blocks = words.len() / 16;
+    let mut rows = vec![Sha256Row::default(); num_blocks * BLOCK_ROWS + STATE_ROWS];
+
+    for i in 0..STATE_ROWS {
+        rows[i].words[A] = IV[3 - i];
+        rows[i].words[E] = IV[7 - i];
+    }
+
+    for (b, block) in words.chunks(16).enumerate() {
+        let base = b * BLOCK_ROWS;
+        let mut w = [0u32; 64];
+        for t in 0..64 {
+            if t < 16 {
+                w[t] = block[t];
+            } else {
+                let sum = sigma_1(w[t - 2]) as u64
+                    + w[t - 7] as u64
+                    + sigma_0(w[t - 15]) as u64
+                    + w[t - 16] as u64;
+                w[t] = sum as u32;
+                rows[base + 3 + t].carries[W] = (sum >> 32) as u32;
+            }
+            rows[base + 3 + t].words[W] = w[t];
+        }
+
+        for (t, w) in w.iter().enumerate() {
+            let r = base + 3 + t;
+            let [a, b, c, d] = [0, 1, 2, 3].map(|i| rows[r - i].words[A]);
+            let [e, f, g, h] = [0, 1, 2, 3].map(|i| rows[r - i].words[E]);
+
+            let t_1 =
+                h as u64 + sum_1(e) as u64 + ((e & f) ^ (!e & g)) as u64 + K[t] as u64 + *w as u64;
+            let t_2 = sum_0(a) as u64 + ((a & b) ^ (a & c) ^ (b & c)) as u64;
+
+            let next_e = d as u64 + t_1;
+            let next_a = t_1 + t_2;
+            rows[r + 1].words[A] = next_a as u32;
+            rows[r + 1].words[E] = next_e as u32;
+            rows[r + 1].carries[A] = (next_a >> 32) as u32;
+            rows[r + 1].carries[E] = (next_e >> 32) as u32;
+        }
+
+        let next = base + BLOCK_ROWS;
+        for i in 0..STATE_ROWS {
+            for group in [A, E] {
+                let sum = rows[base + i].words[group] as u64
+                    + rows[next - STATE_ROWS + i].words[group] as u64;
+                rows[next + i].words[group] = sum as u32;
+                rows[next + i].carries[group] = (sum >> 32) as u32;
+            }
+        }
+    }
+
+    rows
+}
+
+/// The digest as two 128 bit halves, read from the final state of the trace
+fn digest(rows: &[Sha256Row]) -> [u128; 2] {
+    let last = rows.len() - STATE_ROWS;
+    [A, E].map(|group| {
+        (0..STATE_ROWS).fold(0u128, |acc, i| {
+            (acc << 32) | rows[last + 3 - i].words[group] as u128
+        })
+    })
+}
+
+fn sum_0(x: u32) -> u32 {
+    x.rotate_right(2) ^ x.rotate_right(13) ^ x.rotate_right(22)
+}
+
+fn sum_1(x: u32) -> u32 {
+    x.rotate_right(6) ^ x.rotate_right(11) ^ x.rotate_right(25)
+}
+
+fn sigma_0(x: u32) -> u32 {
+    x.rotate_right(7) ^ x.rotate_right(18) ^ (x >> 3)
+}
+
+fn sigma_1(x: u32) -> u32 {
+    x.rotate_right(17) ^ x.rotate_right(19) ^ (x >> 10)
+}
+
+/// Serialize a field element as a 64 bit integer, wrapping out of range values (which the
+/// serialization gate then rejects)
+fn felt_to_i64(x: &Fp) -> i64 {
+    felt_to_integer_rep(*x) as i64
+}
+
+impl Module<Fp> for Sha256Chip {
+    type Config = Sha256Config;
+    type InputAssignments = InputAssignments;
+    type RunInputs = Vec<Fp>;
+    type Params = ();
+
+    fn name(&self) -> &'static str {
+        "SHA-256"
+    }
+
+    fn instance_increment_input(&self) -> Vec<usize> {
+        vec![2]
+    }
+
+    /// Constructs a new Sha256Chip
+    fn new(config: Self::Config) -> Self {
+        Self { config }
+    }
+
+    /// Configuration of the Sha256Chip
+    fn configure(meta: &mut ConstraintSystem<Fp>, _: Self::Params) -> Self::Config {
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        Self::configure_with_optional_instance(meta, Some(instance))
+    }
+
+    /// Lays out the message and the whole hash computation in a single region, as the gates
+    /// query words across the rounds and blocks. Returns the message and the digest cells.
+    fn layout_inputs(
+        &self,
+        layouter: &mut impl Layouter<Fp>,
+        message: &[ValTensor<Fp>],
+        constants: &mut ConstantsMap<Fp>,
+    ) -> Result<Self::InputAssignments, ModuleError> {
+        assert_eq!(message.len(), 1);
+        let message = message[0].clone();
+
+        let start_time = instant::Instant::now();
+
+        let local_constants = constants.clone();
+        let num_elems = match &message {
+            ValTensor::Value { inner, .. } => inner.len(),
+            ValTensor::Instance { dims, idx, .. } => dims[*idx].iter().product::<usize>(),
+        };
+        let num_blocks = Self::num_blocks(num_elems);
+        // the serialization of element i starts at word 2i
+        let element_row = |i: usize| (i / 8) * BLOCK_ROWS + 3 + 2 * (i % 8);
+
+        let res = layouter.assign_region(
+            || "sha256",
+            |mut region| {
+                let col = self.config.element;
+                let assigned_message: Vec<AssignedCell<Fp, Fp>> = match &message {
+                    ValTensor::Value { inner: v, .. } => v
+                        .iter()
+                        .enumerate()
+                        .map(|(i, value)| {
+                            let y = element_row(i);
+                            match value {
+                                ValType::Value(v) => region
+                                    .assign_advice(|| format!("load message_{}", i), col, y, || *v)
+                                    .map_err(|e| e.into()),
+                                ValType::PrevAssigned(v) | ValType::AssignedConstant(v, ..) => v
+                                    .copy_advice(
+                                        || format!("load message_{}", i),
+                                        &mut region,
+                                        col,
+                                        y,
+                                    )
+                                    .map_err(|e| e.into()),
+                                ValType::Constant(f) => {
+                                    if local_constants.contains_key(f) {
+                                        constants
+                                            .get(f)
+                                            .unwrap()
+                                            .assigned_cell()
+                                            .ok_or(ModuleError::ConstantNotAssigned)?
+                                            .copy_advice(
+                                                || format!("load message_{}", i),
+                                                &mut region,
+                                                col,
+                                                y,
+                                            )
+                                            .map_err(|e| e.into())
+                                    } else {
+                                        let res = region.assign_advice_from_constant(
+                                            || format!("load message_{}", i),
+                                            col,
+                                            y,
+                                            *f,
+                                        )?;
+
+                                        constants
+                                            .insert(*f, ValType::AssignedConstant(res.clone(), *f));
+
+                                        Ok(res)
+                                    }
+                                }
+                                e => Err(ModuleError::WrongInputType(
+                                    format!("{:?}", e),
+                                    "PrevAssigned".to_string(),
+                                )),
+                            }
+                        })
+                        .collect::<Result<Vec<_>, ModuleError>>()?,
+                    ValTensor::Instance {
+                        inner: instance,
+                        initial_offset,
+                        ..
+                    } => (0..num_elems)
+                        .map(|i| {
+                            region.assign_advice_from_instance(
+                                || "pub input anchor",
+                                *instance,
+                                initial_offset + i,
+                                col,
+                                element_row(i),
+                            )
+                        })
+                        .collect::<Result<Vec<_>, _>>()?,
+                };
+
+                let elements: Value<Vec<Fp>> = assigned_message
+                    .iter()
+                    .map(|c| c.value().copied())
+                    .collect();
+                let rows = elements.map(|e| trace(&e.iter().map(felt_to_i64).collect_vec()));
+                let words = message_words(&vec![0; num_elems]);
+
+                for b in 0..num_blocks {
+                    let base = b * BLOCK_ROWS;
+                    for i in 0..STATE_ROWS {
+                        if b == 0 {
+                            self.config.q_iv.enable(&mut region, i)?;
+                            region.assign_fixed(
+                                || "iv a",
+                                self.config.fixed[1],
+                                i,
+                                || Value::known(Fp::from(IV[3 - i] as u64)),
+                            )?;
+                            region.assign_fixed(
+                                || "iv e",
+                                self.config.fixed[2],
+                                i,
+                                || Value::known(Fp::from(IV[7 - i] as u64)),
+                            )?;
+                        } else {
+                            self.config.q_feed_forward.enable(&mut region, base + i)?;
+                        }
+                    }
+                    for t in 0..64 {
+                        let r = base + 3 + t;
+                        self.config.q_round.enable(&mut region, r)?;
+                        region.assign_fixed(
+                            || "round constant",
+                            self.config.fixed[0],
+                            r,
+                            || Value::known(Fp::from(K[t] as u64)),
+                        )?;
+                        if t >= 16 {
+                            self.config.q_schedule.enable(&mut region, r)?;
+                        } else if 16 * b + t >= 2 * num_elems {
+                            self.config.q_pad.enable(&mut region, r)?;
+                            region.assign_fixed(
+                                || "padding",
+                                self.config.fixed[3],
+                                r,
+                                || Value::known(Fp::from(words[16 * b + t] as u64)),
+                            )?;
+                        } else if t % 2 == 0 {
+                            self.config.q_element.enable(&mut region, r)?;
+                        }
+                    }
+                }
+
+                let last = num_blocks * BLOCK_ROWS;
+                for i in 0..STATE_ROWS {
+                    self.config.q_feed_forward.enable(&mut region, last + i)?;
+                }
+
+                for offset in 0..last + STATE_ROWS {
+                    self.assign_row(&mut region, offset, rows.as_ref().map(|r| r[offset]))?;
+                }
+
+                self.config.q_digest.enable(&mut region, last)?;
+                let hash = rows.as_ref().map(|r| digest(r));
+                let assigned_digest = (0..2)
+                    .map(|i| {
+                        region.assign_advice(
+                            || "digest",
+                            col,
+                            last + i,
+                            || hash.map(|d| Fp::from_u128(d[i])),
+                        )
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                Ok((assigned_message, assigned_digest))
+            },
+        );
+        log::trace!(
+            "sha256 (N={:?}) layout took: {:?}",
+            num_elems,
+            start_time.elapsed()
+        );
+        res.map_err(|e| e.into())
+    }
+
+    /// Takes the cells containing the tensor and returns the cells of the digest, which are
+    /// constrained to the instance column if the hash is public.
+    fn layout(
+        &self,
+        layouter: &mut impl Layouter<Fp>,
+        input: &[ValTensor<Fp>],
+        row_offset: usize,
+        constants: &mut ConstantsMap<Fp>,
+    ) -> Result<ValTensor<Fp>, ModuleError> {
+        let (message, digest) = self.layout_inputs(layouter, input, constants)?;
+
+        if let Some(instance) = self.config.instance {
+            for (i, half) in digest.iter().enumerate() {
+                layouter.constrain_instance(half.cell(), instance, row_offset + i)?;
+            }
+
+            let mut assigned_input: Tensor<ValType<Fp>> =
+                message.iter().map(|e| ValType::from(e.clone())).into();
+            assigned_input.reshape(input[0].dims()).map_err(|e| {
+                log::error!("reshape failed: {:?}", e);
+                Error::Synthesis
+            })?;
+
+            Ok(assigned_input.into())
+        } else {
+            Ok(Tensor::from(digest.iter().map(|e| ValType::from(e.clone()))).into())
+        }
+    }
+
+    ///
+    fn run(message: Vec<Fp>) -> Result<Vec<Vec<Fp>>, ModuleError> {
+        let start_time = instant::Instant::now();
+
+        let elements = message
+            .iter()
+            .map(|x| {
+                let rep = felt_to_integer_rep(*x);
+                match i64::try_from(rep) {
+                    Ok(v) if integer_rep_to_felt::<Fp>(rep) == *x => Ok(v),
+                    _ => Err(ModuleError::ValueOutOfRange(format!("{:?}", x))),
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let [hi, lo] = digest(&trace(&elements));
+
+        log::trace!(
+            "run (N={:?}) took: {:?}",
+            message.len(),
+            start_time.elapsed()
+        );
+
+        Ok(vec![vec![Fp::from_u128(hi), Fp::from_u128(lo)]])
+    }
+
+    fn num_rows(input_len: usize) -> usize {
+        Self::num_blocks(input_len) * BLOCK_ROWS + STATE_ROWS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::circuit::modules::ModulePlanner;
+
+    use super::*;
+    use crate::fieldutils::IntegerRep;
+
+    use std::collections::HashMap;
+
+    use halo2_proofs::circuit::{Layouter, Value};
+    use halo2_proofs::plonk::{Circuit, ConstraintSystem};
+    use sha2::{Digest, Sha256};
+
+    struct HashCircuit {
+        message: ValTensor<Fp>,
+    }
+
+    impl Circuit<Fp> for HashCircuit {
+        type Config = Sha256Config;
+        type FloorPlanner = ModulePlanner;
+        type Params = ();
+
+        fn without_witnesses(&self) -> Self {
+            let empty_val: Vec<ValType<Fp>> =
+                vec![Value::<Fp>::unknown().into(); self.message.len()];
+            let message: Tensor<ValType<Fp>> = empty_val.into_iter().into();
+
+            Self {
+                message: message.into(),
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Sha256Config {
+            Sha256Chip::configure(meta, ())
+        }
+
+        fn synthesize(
+            &self,
+            config: Sha256Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let chip = Sha256Chip::new(config);
+            chip.layout(
+                &mut layouter,
+                &[self.message.clone()],
+                0,
+                &mut HashMap::new(),
+            )?;
+
+            Ok(())
+        }
+    }
+
+    /// Digest of the serialization computed by the sha2 crate
+    fn host_digest(message: &[Fp]) -> Vec<Fp> {
+        let bytes = message
+            .iter()
+            .flat_map(|x| (felt_to_integer_rep(*x) as i64).to_be_bytes())
+            .collect_vec();
+        let digest = Sha256::digest(bytes);
+        digest
+            .chunks(16)
+            .map(|half| Fp::from_u128(u128::from_be_bytes(half.try_into().unwrap())))
+            .collect()
+    }
+
+    fn message(len: usize) -> Vec<Fp> {
+        (0..len)
+            .map(|i| integer_rep_to_felt((i as IntegerRep - 3) * 1_000_003))
+            .collect()
+    }
+
+    #[test]
+    fn sha256_matches_host() {
+        // lengths around the block boundaries: 7 elements is the last that fit in a block
+        for len in [0, 1, 6, 7, 8, 15, 16, 33] {
+            let message = message(len);
+            let output = Sha256Chip::run(message.clone()).unwrap();
+            assert_eq!(output, vec![host_digest(&message)], "len {}", len);
+            assert_eq!(trace(&vec![0; len]).len(), Sha256Chip::num_rows(len));
+        }
+        // the padding of the empty message
+        assert_eq!(
+            Sha256Chip::run(vec![]).unwrap()[0][0],
+            Fp::from_u128(0xe3b0c44298fc1c149afbf4c8996fb924)
+        );
+    }
+
+    #[test]
+    fn sha256_rejects_out_of_range() {
+        let message = vec![Fp::from_u128(1 << 63)];
+        assert!(Sha256Chip::run(message).is_err());
+    }
+
+    #[test]
+    fn sha256_hash() {
+        for len in [1, 7, 8] {
+            let message = message(len);
+            let output = Sha256Chip::run(message.clone()).unwrap();
+
+            let message: Tensor<ValType<Fp>> =
+                message.into_iter().map(|m| Value::known(m).into()).into();
+
+            let k = 9;
+            let circuit = HashCircuit {
+                message: message.into(),
+            };
+            let prover = halo2_proofs::dev::MockProver::run(k, &circuit, output).unwrap();
+            assert_eq!(prover.verify(), Ok(()), "len {}", len);
+        }
+    }
+
+    #[test]
+    fn sha256_hash_wrong_digest() {
+        let message = message(3);
+        let mut output = Sha256Chip::run(message.clone()).unwrap();
+        output[0][1] += Fp::ONE;
+
+        let message: Tensor<ValType<Fp>> =
+            message.into_iter().map(|m| Value::known(m).into()).into();
+
+        let circuit = HashCircuit {
+            message: message.into(),
+        };
+        let prover = halo2_proofs::dev::MockProver::run(9, &circuit, output).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
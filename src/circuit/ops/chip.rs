@@ -13,6 +13,8 @@ use pyo3::{
     prelude::*,
     types::PyString,
 };
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
 use tosubcommand::ToFlags;
@@ -20,10 +22,10 @@ use tosubcommand::ToFlags;
 use crate::{
     circuit::{
         ops::base::BaseOp,
-        table::{Range, RangeCheck, Table},
+        table::{bit_range_check_rows, lookup_range_check_rows, Range, RangeCheck, Table},
         utils,
     },
-    tensor::{Tensor, TensorType, ValTensor, VarTensor},
+    tensor::{Tensor, TensorError, TensorType, ValTensor, VarTensor},
 };
 use std::{collections::BTreeMap, marker::PhantomData};
 
@@ -35,6 +37,7 @@ use halo2curves::ff::{Field, PrimeField};
 #[derive(
     Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, Default, Copy,
 )]
+#[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), derive(JsonSchema))]
 pub enum CheckMode {
     #[default]
     SAFE,
@@ -76,9 +79,132 @@ impl FromStr for CheckMode {
     }
 }
 
+/// How the quotients of the divisions and reciprocals of the lookups are rounded to integers
+#[derive(
+    Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, Default, Copy,
+)]
+#[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), derive(JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum DivRounding {
+    /// Towards negative infinity, as Python's `//`
+    Floor,
+    /// Towards zero, as integer division in Rust and C
+    Trunc,
+    /// To the nearest integer, halves away from zero
+    #[default]
+    Round,
+}
+
+impl DivRounding {
+    /// Rounds a quotient to an integer
+    pub fn apply(&self, x: f64) -> f64 {
+        match self {
+            DivRounding::Floor => x.floor(),
+            DivRounding::Trunc => x.trunc(),
+            DivRounding::Round => x.round(),
+        }
+    }
+}
+
+impl std::fmt::Display for DivRounding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DivRounding::Floor => write!(f, "floor"),
+            DivRounding::Trunc => write!(f, "trunc"),
+            DivRounding::Round => write!(f, "round"),
+        }
+    }
+}
+
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+impl ToFlags for DivRounding {
+    /// Convert the struct to a subcommand string
+    fn to_flags(&self) -> Vec<String> {
+        vec![format!("{}", self)]
+    }
+}
+
+impl FromStr for DivRounding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "floor" => Ok(DivRounding::Floor),
+            "trunc" => Ok(DivRounding::Trunc),
+            "round" => Ok(DivRounding::Round),
+            _ => Err("Invalid value for DivRounding, expected floor, trunc or round".to_string()),
+        }
+    }
+}
+
+/// How the values of the range checks are checked to be within their ranges
+#[derive(
+    Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, Default, Copy,
+)]
+#[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), derive(JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum RangeCheckStrategy {
+    /// Looked up in a table of the values of each range
+    #[default]
+    Lookup,
+    /// Decomposed in boolean constrained bits, without tables
+    Bits,
+    /// Decomposed in bits for the ranges whose table takes more rows than the decompositions of their values do,
+    /// looked up otherwise
+    Auto,
+}
+
+impl RangeCheckStrategy {
+    /// Whether the `count` values checked to a range are decomposed in bits rather than looked up
+    pub fn decomposes(&self, range: Range, count: usize, num_inner_cols: usize) -> bool {
+        match self {
+            RangeCheckStrategy::Lookup => false,
+            RangeCheckStrategy::Bits => true,
+            RangeCheckStrategy::Auto => {
+                bit_range_check_rows(range, count, num_inner_cols)
+                    < lookup_range_check_rows(range, count, num_inner_cols)
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for RangeCheckStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RangeCheckStrategy::Lookup => write!(f, "lookup"),
+            RangeCheckStrategy::Bits => write!(f, "bits"),
+            RangeCheckStrategy::Auto => write!(f, "auto"),
+        }
+    }
+}
+
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+impl ToFlags for RangeCheckStrategy {
+    /// Convert the struct to a subcommand string
+    fn to_flags(&self) -> Vec<String> {
+        vec![format!("{}", self)]
+    }
+}
+
+impl FromStr for RangeCheckStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "lookup" => Ok(RangeCheckStrategy::Lookup),
+            "bits" => Ok(RangeCheckStrategy::Bits),
+            "auto" => Ok(RangeCheckStrategy::Auto),
+            _ => Err(
+                "Invalid value for RangeCheckStrategy, expected lookup, bits or auto".to_string(),
+            ),
+        }
+    }
+}
+
 #[allow(missing_docs)]
 /// An enum representing the tolerance we can accept for the accumulated arguments, either absolute or percentage
 #[derive(Clone, Default, Debug, PartialEq, PartialOrd, Serialize, Deserialize, Copy)]
+#[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), derive(JsonSchema))]
 pub struct Tolerance {
     pub val: f32,
     pub scale: utils::F32,
@@ -150,6 +276,40 @@ impl<'source> FromPyObject<'source> for CheckMode {
     }
 }
 
+#[cfg(feature = "python-bindings")]
+/// Converts DivRounding into a PyObject (Required for DivRounding to be compatible with Python)
+impl IntoPy<PyObject> for DivRounding {
+    fn into_py(self, py: Python) -> PyObject {
+        self.to_string().to_object(py)
+    }
+}
+
+#[cfg(feature = "python-bindings")]
+/// Obtains DivRounding from PyObject (Required for DivRounding to be compatible with Python)
+impl<'source> FromPyObject<'source> for DivRounding {
+    fn extract(ob: &'source PyAny) -> PyResult<Self> {
+        let trystr = <PyString as PyTryFrom>::try_from(ob)?;
+        DivRounding::from_str(&trystr.to_string()).map_err(PyValueError::new_err)
+    }
+}
+
+#[cfg(feature = "python-bindings")]
+/// Converts RangeCheckStrategy into a PyObject (Required for RangeCheckStrategy to be compatible with Python)
+impl IntoPy<PyObject> for RangeCheckStrategy {
+    fn into_py(self, py: Python) -> PyObject {
+        self.to_string().to_object(py)
+    }
+}
+
+#[cfg(feature = "python-bindings")]
+/// Obtains RangeCheckStrategy from PyObject (Required for RangeCheckStrategy to be compatible with Python)
+impl<'source> FromPyObject<'source> for RangeCheckStrategy {
+    fn extract(ob: &'source PyAny) -> PyResult<Self> {
+        let trystr = <PyString as PyTryFrom>::try_from(ob)?;
+        RangeCheckStrategy::from_str(&trystr.to_string()).map_err(PyValueError::new_err)
+    }
+}
+
 #[cfg(feature = "python-bindings")]
 /// Converts Tolerance into a PyObject (Required for Tolerance to be compatible with Python)
 impl IntoPy<PyObject> for Tolerance {
@@ -238,6 +398,9 @@ impl Shuffles {
 pub struct StaticLookups<F: PrimeField + TensorType + PartialOrd> {
     /// [Selector]s generated when configuring the layer. We use a [BTreeMap] as we expect to configure many dynamic lookup ops.
     pub selectors: BTreeMap<(LookupOp, usize, usize), Selector>,
+    /// [Selector]s of the lookups whose input is the sum of the input and index columns, see
+    /// [BaseConfig::configure_fused_add_lookup]
+    pub fused_add_selectors: BTreeMap<(LookupOp, usize, usize), Selector>,
     /// Selectors for the dynamic lookup tables
     pub tables: BTreeMap<LookupOp, Table<F>>,
     ///
@@ -255,6 +418,7 @@ impl<F: PrimeField + TensorType + PartialOrd> StaticLookups<F> {
 
         Self {
             selectors: BTreeMap::new(),
+            fused_add_selectors: BTreeMap::new(),
             tables: BTreeMap::new(),
             index: dummy_var.clone(),
             output: dummy_var.clone(),
@@ -511,8 +675,13 @@ impl<F: PrimeField + TensorType + PartialOrd + std::hash::Hash> BaseConfig<F> {
         // we borrow mutably twice so we need to do this dance
 
         let table = if !self.static_lookups.tables.contains_key(nl) {
-            // as all tables have the same input we see if there's another table who's input we can reuse
-            let table = if let Some(table) = self.static_lookups.tables.values().next() {
+            // the tables of the same range have the same input, we see if there's another table who's input we can reuse
+            let table = if let Some(table) = self
+                .static_lookups
+                .tables
+                .values()
+                .find(|table| table.range == lookup_range)
+            {
                 Table::<F>::configure(
                     cs,
                     lookup_range,
@@ -628,6 +797,73 @@ impl<F: PrimeField + TensorType + PartialOrd + std::hash::Hash> BaseConfig<F> {
         Ok(())
     }
 
+    /// Configures the lookups of an elementwise sum into the table of `nl`, configured beforehand with
+    /// [BaseConfig::configure_lookup]: the addends are in the lookup input and index columns and the sum is fed to the
+    /// table directly, without being assigned. The index column holds the second addend in place of the column index
+    /// of the input, such that only tables of a single column are fused.
+    pub fn configure_fused_add_lookup(
+        &mut self,
+        cs: &mut ConstraintSystem<F>,
+        nl: &LookupOp,
+    ) -> Result<(), CircuitError>
+    where
+        F: Field,
+    {
+        let table = self
+            .static_lookups
+            .tables
+            .get(nl)
+            .ok_or(CircuitError::LookupNotConfigured(Op::<F>::as_string(nl)))?
+            .clone();
+        if table.selector_constructor.degree != 1 {
+            return Err(CircuitError::LookupNotConfigured(format!(
+                "{} fused with an add, its table takes {} columns",
+                Op::<F>::as_string(nl),
+                table.selector_constructor.degree
+            )));
+        }
+        let (input, index, output) = match (
+            &self.static_lookups.input,
+            &self.static_lookups.index,
+            &self.static_lookups.output,
+        ) {
+            (
+                VarTensor::Advice { inner: input, .. },
+                VarTensor::Advice { inner: index, .. },
+                VarTensor::Advice { inner: output, .. },
+            ) => (input.clone(), index.clone(), output.clone()),
+            _ => return Err(CircuitError::WrongColumnType("lookup input".to_string())),
+        };
+        let (default_x, default_y) = table.get_first_element(0);
+
+        for x in 0..input.len() {
+            for y in 0..input[x].len() {
+                let selector = cs.complex_selector();
+                cs.lookup("", |cs| {
+                    let sel = cs.query_selector(selector);
+                    let sum = cs.query_advice(input[x][y], Rotation(0))
+                        + cs.query_advice(index[x][y], Rotation(0));
+                    let output = cs.query_advice(output[x][y], Rotation(0));
+                    let not_sel = Expression::Constant(F::ONE) - sel.clone();
+                    vec![
+                        (
+                            sel.clone() * sum + not_sel.clone() * Expression::Constant(default_x),
+                            table.table_inputs[0],
+                        ),
+                        (
+                            sel * output + not_sel * Expression::Constant(default_y),
+                            table.table_outputs[0],
+                        ),
+                    ]
+                });
+                self.static_lookups
+                    .fused_add_selectors
+                    .insert((nl.clone(), x, y), selector);
+            }
+        }
+        Ok(())
+    }
+
     /// Configures and creates lookup selectors
     #[allow(clippy::too_many_arguments)]
     pub fn configure_dynamic_lookup(
@@ -931,17 +1167,19 @@ impl<F: PrimeField + TensorType + PartialOrd + std::hash::Hash> BaseConfig<F> {
 
     /// layout_tables must be called before layout.
     pub fn layout_tables(&mut self, layouter: &mut impl Layouter<F>) -> Result<(), CircuitError> {
-        for (i, table) in self.static_lookups.tables.values_mut().enumerate() {
+        // the input of the tables sharing it is assigned by the first of them
+        let mut assigned_inputs = vec![];
+        for table in self.static_lookups.tables.values_mut() {
             if !table.is_assigned {
                 debug!(
                     "laying out table for {}",
                     crate::circuit::ops::Op::<F>::as_string(&table.nonlinearity)
                 );
-                if i == 0 {
-                    table.layout(layouter, false)?;
-                } else {
-                    table.layout(layouter, true)?;
-                }
+                let preassigned = assigned_inputs.contains(&table.table_inputs);
+                table.layout(layouter, preassigned)?;
+            }
+            if !assigned_inputs.contains(&table.table_inputs) {
+                assigned_inputs.push(table.table_inputs.clone());
             }
         }
         Ok(())
@@ -972,6 +1210,11 @@ impl<F: PrimeField + TensorType + PartialOrd + std::hash::Hash> BaseConfig<F> {
         values: &[ValTensor<F>],
         op: Box<dyn Op<F>>,
     ) -> Result<Option<ValTensor<F>>, CircuitError> {
-        op.layout(self, region, values)
+        op.layout(self, region, values).map_err(|e| match e {
+            CircuitError::TensorError(TensorError::IntegerRepOverflow(felt)) => {
+                CircuitError::IntegerRepOverflow(felt, op.as_string())
+            }
+            e => e,
+        })
     }
 }
@@ -82,12 +82,49 @@ pub enum CircuitError {
     /// Table lookup error
     #[error("value ({0}) out of range: ({1}, {2})")]
     TableOOR(IntegerRep, IntegerRep, IntegerRep),
+    /// A node looks up a value outside of the table calibration sized to the inputs of its op
+    #[error("node {node} looks up {value} but calibration sized the table of {op} to ({min}, {max}), recalibrate with data covering the input or raise tight_lookup_margin")]
+    TightLookupOOR {
+        /// the index and name of the node
+        node: String,
+        /// the op looked up
+        op: String,
+        /// the value looked up
+        value: IntegerRep,
+        /// the smallest input of the table
+        min: IntegerRep,
+        /// the largest input of the table
+        max: IntegerRep,
+    },
+    /// A value computed by an op lies outside of the range of the integer representation
+    #[error("field element {0} computed by op {1} overflows the integer representation, try lowering the scales")]
+    IntegerRepOverflow(String, String),
+    /// Laying out a node needs more rows than the columns of the circuit have
+    #[error("node {node} ({op}) needs row {row} but the circuit has {max} rows (2^logrows minus the blinding rows, for each block of columns), increase logrows or num_inner_cols. The largest ops so far: {largest}")]
+    RowOverflow {
+        /// the index and name of the node
+        node: String,
+        /// the op of the node
+        op: String,
+        /// the row the node needs
+        row: usize,
+        /// the rows available
+        max: usize,
+        /// the ops that took the most rows so far
+        largest: String,
+    },
     /// Loookup not configured
     #[error("lookup not configured: {0}")]
     LookupNotConfigured(String),
     /// Range check not configured
     #[error("range check not configured: {0}")]
     RangeCheckNotConfigured(String),
+    /// The bags of an embedding bag aren't of a static size
+    #[error("embedding bags must have static offsets, a fixed number of indices per bag: {0}")]
+    DynamicEmbeddingBagOffsets(String),
+    /// The layout was abandoned as the proof or witness generation was cancelled
+    #[error("cancelled")]
+    Cancelled,
     /// Missing layout
     #[error("missing layout for op: {0}")]
     MissingLayout(String),
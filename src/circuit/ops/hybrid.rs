@@ -1,7 +1,7 @@
 use super::*;
 use crate::{
-    circuit::{layouts, utils, Tolerance},
-    fieldutils::integer_rep_to_felt,
+    circuit::{layouts, utils, DivRounding, Tolerance},
+    fieldutils::{integer_rep_to_felt, IntegerRep},
     graph::multiplier_to_scale,
     tensor::{self, Tensor, TensorType, ValTensor},
 };
@@ -9,6 +9,47 @@ use halo2curves::ff::PrimeField;
 use serde::{Deserialize, Serialize};
 // import run args from model
 
+/// The largest magnitude the partial sums of a widened contraction are kept within, see
+/// [HybridOp::WidenedEinsum]: the quotients of divisions are computed through `f64`, which only represents the
+/// integers up to `2^53` exactly
+pub const MAX_SAFE_ACCUMULATOR: IntegerRep = 1 << 53;
+
+/// How the rows gathered for a bag of an [HybridOp::EmbeddingBag] are aggregated
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EmbeddingBagMode {
+    /// the rows are summed
+    Sum,
+    /// the rows are averaged, the sum being divided by the size of the bag
+    Mean,
+}
+
+impl std::fmt::Display for EmbeddingBagMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EmbeddingBagMode::Sum => write!(f, "sum"),
+            EmbeddingBagMode::Mean => write!(f, "mean"),
+        }
+    }
+}
+
+/// The norm a [HybridOp::NormReduce] reduces with, as the ONNX ReduceL1 and ReduceL2
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NormOrder {
+    /// the sum of the absolute values
+    L1,
+    /// the square root of the sum of the squares
+    L2,
+}
+
+impl std::fmt::Display for NormOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NormOrder::L1 => write!(f, "L1"),
+            NormOrder::L2 => write!(f, "L2"),
+        }
+    }
+}
+
 #[allow(missing_docs)]
 /// An enum representing the operations that consist of both lookups and arithmetic operations.
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -17,10 +58,14 @@ pub enum HybridOp {
         input_scale: utils::F32,
         output_scale: utils::F32,
         use_range_check_for_int: bool,
+        #[serde(default)]
+        rounding: DivRounding,
     },
     Div {
         denom: utils::F32,
         use_range_check_for_int: bool,
+        #[serde(default)]
+        rounding: DivRounding,
     },
     ReduceMax {
         axes: Vec<usize>,
@@ -71,6 +116,32 @@ pub enum HybridOp {
         dim: usize,
         num_classes: usize,
     },
+    /// The rows of an embedding table gathered by bags of `bag_size` indices and aggregated within each bag, see
+    /// [layouts::embedding_bag]
+    EmbeddingBag {
+        mode: EmbeddingBagMode,
+        bag_size: usize,
+    },
+    /// An elementwise add whose sum is fed directly into the lookup of `nl`, see [layouts::add_nonlinearity]
+    FusedAddLookup {
+        nl: LookupOp,
+    },
+    /// An einsum whose partial sums over chunks of the index it sums over are divided by `2^shift` before being
+    /// added up, see [layouts::widened_einsum]
+    WidenedEinsum {
+        equation: String,
+        chunk_size: usize,
+        shift: u32,
+        use_range_check_for_int: bool,
+    },
+    /// The L1 or L2 norm of the input along `axes`, see [layouts::norm_reduce_axes]. `scale` is the multiplier of
+    /// the scale of the input, which the norm is at
+    NormReduce {
+        p: NormOrder,
+        axes: Vec<usize>,
+        keepdims: bool,
+        scale: utils::F32,
+    },
 }
 
 impl<F: PrimeField + TensorType + PartialOrd + std::hash::Hash> Op<F> for HybridOp {
@@ -83,7 +154,8 @@ impl<F: PrimeField + TensorType + PartialOrd + std::hash::Hash> Op<F> for Hybrid
             | HybridOp::GreaterEqual { .. }
             | HybridOp::Max
             | HybridOp::Min
-            | HybridOp::LessEqual { .. } => {
+            | HybridOp::LessEqual { .. }
+            | HybridOp::FusedAddLookup { .. } => {
                 vec![0, 1]
             }
             _ => vec![],
@@ -103,16 +175,18 @@ impl<F: PrimeField + TensorType + PartialOrd + std::hash::Hash> Op<F> for Hybrid
                 input_scale,
                 output_scale,
                 use_range_check_for_int,
+                rounding,
             } => format!(
-                "RECIP (input_scale={}, output_scale={}, use_range_check_for_int={})",
-                input_scale, output_scale, use_range_check_for_int
+                "RECIP (input_scale={}, output_scale={}, use_range_check_for_int={}, rounding={})",
+                input_scale, output_scale, use_range_check_for_int, rounding
             ),
             HybridOp::Div {
                 denom,
                 use_range_check_for_int,
+                rounding,
             } => format!(
-                "DIV (denom={}, use_range_check_for_int={})",
-                denom, use_range_check_for_int
+                "DIV (denom={}, use_range_check_for_int={}, rounding={})",
+                denom, use_range_check_for_int, rounding
             ),
             HybridOp::SumPool {
                 padding,
@@ -158,6 +232,28 @@ impl<F: PrimeField + TensorType + PartialOrd + std::hash::Hash> Op<F> for Hybrid
             HybridOp::OneHot { dim, num_classes } => {
                 format!("ONEHOT (dim={}, num_classes={})", dim, num_classes)
             }
+            HybridOp::EmbeddingBag { mode, bag_size } => {
+                format!("EMBEDDINGBAG (mode={}, bag_size={})", mode, bag_size)
+            }
+            HybridOp::FusedAddLookup { nl } => format!("ADD -> {}", Op::<F>::as_string(nl)),
+            HybridOp::WidenedEinsum {
+                equation,
+                chunk_size,
+                shift,
+                use_range_check_for_int,
+            } => format!(
+                "WIDENEDEINSUM (equation={}, chunk_size={}, shift={}, use_range_check_for_int={})",
+                equation, chunk_size, shift, use_range_check_for_int
+            ),
+            HybridOp::NormReduce {
+                p,
+                axes,
+                keepdims,
+                scale,
+            } => format!(
+                "NORMREDUCE (p={}, axes={:?}, keepdims={}, scale={})",
+                p, axes, keepdims, scale
+            ),
         }
     }
 
@@ -188,10 +284,14 @@ impl<F: PrimeField + TensorType + PartialOrd + std::hash::Hash> Op<F> for Hybrid
                 input_scale,
                 output_scale,
                 use_range_check_for_int,
+                rounding,
             } => {
+                // the range checks bound the error of the quotient to half a unit, which only holds rounding to the
+                // nearest integer, the other roundings are looked up
                 if input_scale.0.fract() == 0.0
                     && output_scale.0.fract() == 0.0
                     && *use_range_check_for_int
+                    && *rounding == DivRounding::Round
                 {
                     layouts::recip(
                         config,
@@ -208,6 +308,7 @@ impl<F: PrimeField + TensorType + PartialOrd + std::hash::Hash> Op<F> for Hybrid
                         &LookupOp::Recip {
                             input_scale: *input_scale,
                             output_scale: *output_scale,
+                            rounding: *rounding,
                         },
                     )?
                 }
@@ -215,9 +316,12 @@ impl<F: PrimeField + TensorType + PartialOrd + std::hash::Hash> Op<F> for Hybrid
             HybridOp::Div {
                 denom,
                 use_range_check_for_int,
-                ..
+                rounding,
             } => {
-                if denom.0.fract() == 0.0 && *use_range_check_for_int {
+                if denom.0.fract() == 0.0
+                    && *use_range_check_for_int
+                    && *rounding == DivRounding::Round
+                {
                     layouts::loop_div(
                         config,
                         region,
@@ -229,7 +333,10 @@ impl<F: PrimeField + TensorType + PartialOrd + std::hash::Hash> Op<F> for Hybrid
                         config,
                         region,
                         values.try_into()?,
-                        &LookupOp::Div { denom: *denom },
+                        &LookupOp::Div {
+                            denom: *denom,
+                            rounding: *rounding,
+                        },
                     )?
                 }
             }
@@ -297,6 +404,40 @@ impl<F: PrimeField + TensorType + PartialOrd + std::hash::Hash> Op<F> for Hybrid
             HybridOp::OneHot { dim, num_classes } => {
                 layouts::one_hot_axis(config, region, values[..].try_into()?, *num_classes, *dim)?
             }
+            HybridOp::EmbeddingBag { mode, bag_size } => {
+                layouts::embedding_bag(config, region, values[..].try_into()?, *mode, *bag_size)?
+            }
+            HybridOp::FusedAddLookup { nl } => {
+                layouts::add_nonlinearity(config, region, values[..].try_into()?, nl)?
+            }
+            HybridOp::WidenedEinsum {
+                equation,
+                chunk_size,
+                shift,
+                use_range_check_for_int,
+            } => layouts::widened_einsum(
+                config,
+                region,
+                values,
+                equation,
+                *chunk_size,
+                *shift,
+                *use_range_check_for_int,
+            )?,
+            HybridOp::NormReduce {
+                p,
+                axes,
+                keepdims,
+                scale,
+            } => layouts::norm_reduce_axes(
+                config,
+                region,
+                values[..].try_into()?,
+                *p,
+                axes,
+                *keepdims,
+                *scale,
+            )?,
         }))
     }
 
@@ -312,6 +453,10 @@ impl<F: PrimeField + TensorType + PartialOrd + std::hash::Hash> Op<F> for Hybrid
             HybridOp::Softmax { output_scale, .. } | HybridOp::Recip { output_scale, .. } => {
                 multiplier_to_scale(output_scale.0 as f64)
             }
+            HybridOp::WidenedEinsum { shift, .. } => {
+                in_scales.iter().sum::<crate::Scale>() - *shift as crate::Scale
+            }
+            HybridOp::FusedAddLookup { nl } => Op::<F>::out_scale(nl, vec![in_scales[0]])?,
             _ => in_scales[0],
         };
         Ok(scale)
@@ -15,18 +15,22 @@ use maybe_rayon::{
 
 use self::tensor::{create_constant_tensor, create_zero_tensor};
 
-use super::{chip::BaseConfig, region::RegionCtx};
+use super::{
+    chip::{BaseConfig, DivRounding},
+    region::RegionCtx,
+};
 use crate::{
     circuit::{ops::base::BaseOp, utils},
-    fieldutils::{felt_to_integer_rep, integer_rep_to_felt, IntegerRep},
+    fieldutils::{felt_cmp, felt_sign, felt_to_integer_rep, integer_rep_to_felt, IntegerRep},
     tensor::{
         create_unit_tensor, get_broadcasted_shape,
-        ops::{accumulated, add, mult, sub},
+        ops::{accumulated, add, max_decomposable, mult, sub},
         Tensor, TensorError, ValType,
     },
 };
 
 use super::*;
+use crate::circuit::ops::hybrid::{EmbeddingBagMode, NormOrder};
 use crate::circuit::ops::lookup::LookupOp;
 
 /// Same as div but splits the division into N parts
@@ -94,6 +98,7 @@ pub(crate) fn div<F: PrimeField + TensorType + PartialOrd + std::hash::Hash>(
         tensor::ops::nonlinearities::const_div(
             &input_evals.clone(),
             felt_to_integer_rep(div) as f64,
+            DivRounding::Round,
         )
         .par_iter()
         .map(|x| Value::known(integer_rep_to_felt(*x)))
@@ -167,6 +172,7 @@ pub(crate) fn recip<F: PrimeField + TensorType + PartialOrd + std::hash::Hash>(
             &input_evals,
             felt_to_integer_rep(input_scale) as f64,
             felt_to_integer_rep(output_scale) as f64,
+            DivRounding::Round,
         )
         .par_iter()
         .map(|x| Value::known(integer_rep_to_felt(*x)))
@@ -756,6 +762,104 @@ pub fn einsum<F: PrimeField + TensorType + PartialOrd + std::hash::Hash>(
     Ok(output)
 }
 
+/// The single index an einsum equation sums over, that of its inputs missing from its output, if there is exactly one
+pub(crate) fn einsum_contracted_index(equation: &str) -> Option<char> {
+    let (inputs_eq, output_eq) = equation.split_once("->")?;
+    let contracted = inputs_eq
+        .chars()
+        .filter(|c| *c != ',' && !output_eq.contains(*c))
+        .unique()
+        .collect::<Vec<_>>();
+    match contracted[..] {
+        [index] => Some(index),
+        _ => None,
+    }
+}
+
+/// Widened einsum layout, for contractions too long for their sums to be accumulated at the scale of their inputs.
+///
+/// The index the einsum sums over is split into chunks of `chunk_size`, and the partial sum of each chunk is divided by
+/// `2^shift` (rounding to the nearest integer, with range checks if `use_range_check_for_int` and lookups otherwise)
+/// before the partial sums are added up. The output is at the scale of the einsum less `shift`, and each of the
+/// `ceil(n / chunk_size)` partial sums is off by at most half a unit of it, such that the output is within
+/// `ceil(n / chunk_size) / 2` of the exact contraction divided by `2^shift`.
+pub fn widened_einsum<F: PrimeField + TensorType + PartialOrd + std::hash::Hash>(
+    config: &BaseConfig<F>,
+    region: &mut RegionCtx<F>,
+    inputs: &[ValTensor<F>],
+    equation: &str,
+    chunk_size: usize,
+    shift: u32,
+    use_range_check_for_int: bool,
+) -> Result<ValTensor<F>, CircuitError> {
+    let index = einsum_contracted_index(equation).ok_or(CircuitError::InvalidEinsum)?;
+    let inputs_eq = equation
+        .split("->")
+        .next()
+        .ok_or(CircuitError::InvalidEinsum)?
+        .split(',')
+        .collect::<Vec<_>>();
+    if inputs.len() != inputs_eq.len() || chunk_size == 0 {
+        return Err(CircuitError::InvalidEinsum);
+    }
+
+    // the inputs are assigned once, such that the chunks of an input are sliced from the same cells
+    let mut axes = vec![];
+    let mut assigned = vec![];
+    for (input, input_eq) in inputs.iter().zip(&inputs_eq) {
+        let mut input = input.clone();
+        if !input.all_prev_assigned() {
+            input = region.assign(&config.custom_gates.output, &input)?;
+            region.increment(input.len());
+        }
+        axes.push(input_eq.find(index));
+        assigned.push(input);
+    }
+    let len = axes
+        .iter()
+        .zip(&assigned)
+        .find_map(|(axis, input)| axis.map(|axis| input.dims()[axis]))
+        .ok_or(CircuitError::InvalidEinsum)?;
+
+    let denom: IntegerRep = 1 << shift;
+    let mut output: Option<ValTensor<F>> = None;
+    for start in (0..len).step_by(chunk_size) {
+        let end = std::cmp::min(start + chunk_size, len);
+        let chunk = assigned
+            .iter()
+            .zip(&axes)
+            .map(|(input, axis)| {
+                let mut input = input.clone();
+                if let Some(axis) = axis {
+                    input.slice(axis, &start, &end)?;
+                }
+                Ok(input)
+            })
+            .collect::<Result<Vec<_>, TensorError>>()?;
+
+        let partial = einsum(config, region, &chunk, equation)?;
+        let rescaled = if use_range_check_for_int {
+            loop_div(config, region, &[partial], integer_rep_to_felt(denom))?
+        } else {
+            nonlinearity(
+                config,
+                region,
+                &[partial],
+                &LookupOp::Div {
+                    denom: utils::F32(denom as f32),
+                    rounding: DivRounding::Round,
+                },
+            )?
+        };
+        output = Some(match output {
+            Some(sum) => pairwise(config, region, &[sum, rescaled], BaseOp::Add)?,
+            None => rescaled,
+        });
+    }
+
+    output.ok_or(CircuitError::InvalidEinsum)
+}
+
 fn _sort_ascending<F: PrimeField + TensorType + PartialOrd + std::hash::Hash>(
     config: &BaseConfig<F>,
     region: &mut RegionCtx<F>,
@@ -1223,6 +1327,65 @@ pub(crate) fn gather<F: PrimeField + TensorType + PartialOrd + std::hash::Hash>(
     Ok(output)
 }
 
+/// Embedding bag layout: sums (or averages) the rows of an embedding table gathered by the indices of each bag,
+/// `values` being the table of shape `[num_embeddings, embedding_dim]` and the indices of shape `[..., bag_size]`. The
+/// output is of shape `[..., 1, embedding_dim]`, keeping the bag axis as reductions do. The rows are looked up in the
+/// table with a single dynamic lookup, whose linear indices are computed with one multiplication per index rather than
+/// by linearizing the index of each gathered element, and are summed within each bag directly, in place of the
+/// gather, reduction and division the op is otherwise decomposed into.
+pub(crate) fn embedding_bag<F: PrimeField + TensorType + PartialOrd + std::hash::Hash>(
+    config: &BaseConfig<F>,
+    region: &mut RegionCtx<F>,
+    values: &[ValTensor<F>; 2],
+    mode: EmbeddingBagMode,
+    bag_size: usize,
+) -> Result<ValTensor<F>, CircuitError> {
+    let (table, mut indices) = (values[0].clone(), values[1].clone());
+    let table_dims = table.dims().to_vec();
+    if table_dims.len() != 2 {
+        return Err(CircuitError::DimMismatch("embedding bag table".to_string()));
+    }
+    if bag_size == 0 || indices.dims().last() != Some(&bag_size) {
+        return Err(CircuitError::DynamicEmbeddingBagOffsets(format!(
+            "indices of shape {:?} aren't bags of {} indices",
+            indices.dims(),
+            bag_size
+        )));
+    }
+    let mut gathered_dims = indices.dims().to_vec();
+    let bag_axis = gathered_dims.len() - 1;
+    let num_indices = indices.len();
+    let embedding_dim = table_dims[1];
+    gathered_dims.push(embedding_dim);
+
+    // the linear index of the element d of row i of the table is i * embedding_dim + d
+    indices.reshape(&[num_indices, 1])?;
+    let row_starts = pairwise(
+        config,
+        region,
+        &[
+            indices,
+            create_constant_tensor(F::from(embedding_dim as u64), 1),
+        ],
+        BaseOp::Mult,
+    )?;
+    let mut columns: ValTensor<F> =
+        Tensor::from((0..embedding_dim as u64).map(|d| ValType::Constant(F::from(d)))).into();
+    columns.reshape(&[1, embedding_dim])?;
+    let mut linear_index = pairwise(config, region, &[row_starts, columns], BaseOp::Add)?;
+    linear_index.flatten();
+
+    let mut gathered = select(config, region, &[table, linear_index])?;
+    gathered.reshape(&gathered_dims)?;
+
+    let output = sum_axes(config, region, &[gathered], &[bag_axis])?;
+
+    match mode {
+        EmbeddingBagMode::Sum => Ok(output),
+        EmbeddingBagMode::Mean => loop_div(config, region, &[output], F::from(bag_size as u64)),
+    }
+}
+
 /// Gather accumulated layout
 pub(crate) fn gather_elements<F: PrimeField + TensorType + PartialOrd + std::hash::Hash>(
     config: &BaseConfig<F>,
@@ -1778,6 +1941,9 @@ pub(crate) fn scatter_nd<F: PrimeField + TensorType + PartialOrd + std::hash::Ha
 }
 
 /// Sums a tensor.
+///
+/// Each row sums `num_inner_cols` values into the running sum of the previous row, such that a sum of `n` values takes
+/// `n / num_inner_cols` rows. A reduction over the partial sums of the rows (as a tree) would take their rows on top.
 /// # Examples
 /// ```
 /// use ezkl::tensor::Tensor;
@@ -2473,6 +2639,87 @@ pub fn mean_of_squares_axes<F: PrimeField + TensorType + PartialOrd + std::hash:
     Ok(mean_squared)
 }
 
+/// The L1 or L2 norm of a tensor along specific axes, at the scale of the tensor of multiplier `scale`. The L1 norm
+/// sums the absolute values, each value being multiplied by its sign as extracted by its decomposition. The L2 norm
+/// sums the squares at twice the scale of the tensor, rescales the sum once to the scale of the tensor and looks up
+/// its square root, rather than rescaling each square. The reduced axes are kept with a dimension of 1 if `keepdims`,
+/// as by the other reductions, and removed otherwise.
+/// # Examples
+/// ```
+/// use ezkl::tensor::Tensor;
+/// use ezkl::fieldutils::IntegerRep;
+/// use ezkl::circuit::ops::hybrid::NormOrder;
+/// use ezkl::circuit::ops::layouts::norm_reduce_axes;
+/// use halo2curves::bn256::Fr as Fp;
+/// use ezkl::circuit::region::RegionCtx;
+/// use ezkl::circuit::region::RegionSettings;
+/// use ezkl::circuit::BaseConfig;
+/// use ezkl::tensor::ValTensor;
+///
+/// let dummy_config = BaseConfig::dummy(12, 2);
+/// let mut dummy_region = RegionCtx::new_dummy(0,2,RegionSettings::all_true(128,2));
+///
+/// let x = ValTensor::from_integer_rep_tensor(Tensor::<IntegerRep>::new(
+///     Some(&[3, -4, 0, 1, -1, 1]),
+///     &[2, 3],
+/// ).unwrap());
+/// let result = norm_reduce_axes::<Fp>(&dummy_config, &mut dummy_region, &[x.clone()], NormOrder::L1, &[1], true, 1.0.into()).unwrap();
+/// let expected = Tensor::<IntegerRep>::new(
+///     Some(&[7, 3]),
+///     &[2, 1],
+/// ).unwrap();
+/// assert_eq!(result.int_evals().unwrap(), expected);
+///
+/// let result = norm_reduce_axes::<Fp>(&dummy_config, &mut dummy_region, &[x], NormOrder::L2, &[1], false, 1.0.into()).unwrap();
+/// let expected = Tensor::<IntegerRep>::new(
+///     Some(&[5, 2]),
+///     &[2],
+/// ).unwrap();
+/// assert_eq!(result.int_evals().unwrap(), expected);
+/// ```
+pub fn norm_reduce_axes<F: PrimeField + TensorType + PartialOrd + std::hash::Hash>(
+    config: &BaseConfig<F>,
+    region: &mut RegionCtx<F>,
+    values: &[ValTensor<F>; 1],
+    p: NormOrder,
+    axes: &[usize],
+    keepdims: bool,
+    scale: utils::F32,
+) -> Result<ValTensor<F>, CircuitError> {
+    let mut norm = match p {
+        NormOrder::L1 => {
+            let abs = abs(config, region, values)?;
+            sum_axes(config, region, &[abs], axes)?
+        }
+        NormOrder::L2 => {
+            let squared = pow(config, region, values, 2)?;
+            let sum_squared = sum_axes(config, region, &[squared], axes)?;
+            let rescaled = loop_div(
+                config,
+                region,
+                &[sum_squared],
+                integer_rep_to_felt(scale.0 as IntegerRep),
+            )?;
+            nonlinearity(config, region, &[rescaled], &LookupOp::Sqrt { scale })?
+        }
+    };
+
+    if !keepdims {
+        let mut dims = norm
+            .dims()
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !axes.contains(i))
+            .map(|(_, d)| *d)
+            .collect::<Vec<_>>();
+        if dims.is_empty() {
+            dims.push(1);
+        }
+        norm.reshape(&dims)?;
+    }
+    Ok(norm)
+}
+
 /// expand the tensor to the given shape
 pub(crate) fn expand<F: PrimeField + TensorType + PartialOrd + std::hash::Hash>(
     config: &BaseConfig<F>,
@@ -2528,6 +2775,16 @@ pub fn greater<F: PrimeField + TensorType + PartialOrd + std::hash::Hash>(
     lhs.expand(&broadcasted_shape)?;
     rhs.expand(&broadcasted_shape)?;
 
+    // the difference wraps around the field and is only that of the integers when the operands and their difference
+    // lie in the range the decomposition of its sign bounds
+    if region.witness_gen() && !lhs.any_unknowns()? && !rhs.any_unknowns()? {
+        let max_abs = max_decomposable(region.base(), region.legs());
+        let (l, r) = (lhs.get_felt_evals()?, rhs.get_felt_evals()?);
+        for (l, r) in l.iter().zip(r.iter()) {
+            felt_cmp(*l, *r, max_abs).map_err(TensorError::from)?;
+        }
+    }
+
     let diff = pairwise(config, region, &[lhs, rhs], BaseOp::Sub)?;
 
     let sign = sign(config, region, &[diff])?;
@@ -2666,10 +2923,28 @@ pub fn less_equal<F: PrimeField + TensorType + PartialOrd + std::hash::Hash>(
     greater_equal(config, region, &[values[1].clone(), values[0].clone()])
 }
 
-/// Elementwise applies and to two tensors
+/// Constrains the inputs at `boolean_checks` to be boolean, the others being boolean by construction
+fn constrain_boolean<F: PrimeField + TensorType + PartialOrd + std::hash::Hash, const N: usize>(
+    config: &BaseConfig<F>,
+    region: &mut RegionCtx<F>,
+    values: &[ValTensor<F>; N],
+    boolean_checks: &[usize],
+) -> Result<[ValTensor<F>; N], CircuitError> {
+    let mut values = values.clone();
+    for i in boolean_checks {
+        let value = values.get_mut(*i).ok_or_else(|| {
+            CircuitError::DimMismatch(format!("no input {} to constrain to be boolean", i))
+        })?;
+        *value = boolean_identity(config, region, &[value.clone()], true)?;
+    }
+    Ok(values)
+}
+
+/// Elementwise applies and to two tensors of {0,1}, as `a·b`
 /// # Arguments
 /// * `a` - Tensor
 /// * `b` - Tensor
+/// * `boolean_checks` - the inputs to constrain to be boolean, as they aren't by construction
 /// # Examples
 /// ```
 /// use ezkl::tensor::Tensor;
@@ -2693,7 +2968,7 @@ pub fn less_equal<F: PrimeField + TensorType + PartialOrd + std::hash::Hash>(
 /// Some(&[1, 0, 1, 0, 1, 0]),
 /// &[2, 3],
 /// ).unwrap());
-/// let result = and::<Fp>(&dummy_config, &mut dummy_region, &[a,b]).unwrap();
+/// let result = and::<Fp>(&dummy_config, &mut dummy_region, &[a,b], &[0, 1]).unwrap();
 /// let expected = Tensor::<IntegerRep>::new(Some(&[1, 0, 1, 0, 1, 0]), &[2, 3]).unwrap();
 /// assert_eq!(result.int_evals().unwrap(), expected);
 /// ```
@@ -2701,19 +2976,18 @@ pub fn and<F: PrimeField + TensorType + PartialOrd + std::hash::Hash>(
     config: &BaseConfig<F>,
     region: &mut RegionCtx<F>,
     values: &[ValTensor<F>; 2],
+    boolean_checks: &[usize],
 ) -> Result<ValTensor<F>, CircuitError> {
-    let a = boolean_identity(config, region, &[values[0].clone()], true)?;
-    let b = boolean_identity(config, region, &[values[1].clone()], true)?;
+    let values = constrain_boolean(config, region, values, boolean_checks)?;
 
-    let res = pairwise(config, region, &[a, b], BaseOp::Mult)?;
-
-    Ok(res)
+    pairwise(config, region, &values, BaseOp::Mult)
 }
 
-/// Elementwise applies or to two tensors .
+/// Elementwise applies or to two tensors of {0,1}, as `a+b-a·b`
 /// # Arguments
 /// * `a` - Tensor
 /// * `b` - Tensor
+/// * `boolean_checks` - the inputs to constrain to be boolean, as they aren't by construction
 /// # Examples
 /// ```
 /// use ezkl::tensor::Tensor;
@@ -2737,7 +3011,7 @@ pub fn and<F: PrimeField + TensorType + PartialOrd + std::hash::Hash>(
 ///  Some(&[1, 0, 1, 0, 1, 0]),
 /// &[2, 3],
 /// ).unwrap());
-/// let result = or::<Fp>(&dummy_config, &mut dummy_region, &[a,b]).unwrap();
+/// let result = or::<Fp>(&dummy_config, &mut dummy_region, &[a,b], &[0, 1]).unwrap();
 /// let expected = Tensor::<IntegerRep>::new(Some(&[1, 1, 1, 1, 1, 0]), &[2, 3]).unwrap();
 /// assert_eq!(result.int_evals().unwrap(), expected);
 /// ```
@@ -2745,17 +3019,14 @@ pub fn or<F: PrimeField + TensorType + PartialOrd + std::hash::Hash>(
     config: &BaseConfig<F>,
     region: &mut RegionCtx<F>,
     values: &[ValTensor<F>; 2],
+    boolean_checks: &[usize],
 ) -> Result<ValTensor<F>, CircuitError> {
-    let a = values[0].clone();
-    let b = values[1].clone();
+    let values = constrain_boolean(config, region, values, boolean_checks)?;
 
-    let b = boolean_identity(config, region, &[b], true)?;
+    let sum = pairwise(config, region, &values, BaseOp::Add)?;
+    let product = pairwise(config, region, &values, BaseOp::Mult)?;
 
-    let iff_values = &[a.clone(), a, b];
-
-    let res = iff(config, region, iff_values)?;
-
-    Ok(res)
+    pairwise(config, region, &[sum, product], BaseOp::Sub)
 }
 
 /// Elementwise applies equals to two tensors .
@@ -2832,10 +3103,11 @@ pub(crate) fn equals_zero<F: PrimeField + TensorType + PartialOrd + std::hash::H
     Ok(output)
 }
 
-/// Elementwise applies xor to two tensors
+/// Elementwise applies xor to two tensors of {0,1}, as `a+b-2·a·b`, which is laid out as `(a-b)²` it equals on {0,1}
 /// # Arguments
 /// * `a` - Tensor
 /// * `b` - Tensor
+/// * `boolean_checks` - the inputs to constrain to be boolean, as they aren't by construction
 /// # Examples
 /// ```
 /// use ezkl::tensor::Tensor;
@@ -2859,7 +3131,7 @@ pub(crate) fn equals_zero<F: PrimeField + TensorType + PartialOrd + std::hash::H
 /// Some(&[1, 0, 1, 0, 1, 0]),
 /// &[2, 3],
 /// ).unwrap());
-/// let result = xor::<Fp>(&dummy_config, &mut dummy_region, &[a,b]).unwrap();
+/// let result = xor::<Fp>(&dummy_config, &mut dummy_region, &[a,b], &[0, 1]).unwrap();
 /// let expected = Tensor::<IntegerRep>::new(Some(&[0, 1, 0, 1, 0, 0]), &[2, 3]).unwrap();
 /// assert_eq!(result.int_evals().unwrap(), expected);
 /// ```
@@ -2868,30 +3140,19 @@ pub fn xor<F: PrimeField + TensorType + PartialOrd + std::hash::Hash>(
     config: &BaseConfig<F>,
     region: &mut RegionCtx<F>,
     values: &[ValTensor<F>; 2],
+    boolean_checks: &[usize],
 ) -> Result<ValTensor<F>, CircuitError> {
-    let lhs = values[0].clone();
-    let rhs = values[1].clone();
+    let values = constrain_boolean(config, region, values, boolean_checks)?;
 
-    let lhs_not = not(config, region, &[lhs.clone()])?;
-    let rhs_not = not(config, region, &[rhs.clone()])?;
-
-    let lhs_and_rhs_not = and(config, region, &[lhs, rhs_not.clone()])?;
-    let lhs_not_and_rhs = and(config, region, &[rhs, lhs_not])?;
-
-    // we can safely use add and not OR here because we know that lhs_and_rhs_not and lhs_not_and_rhs are =1 at different incices
-    let res: ValTensor<F> = pairwise(
-        config,
-        region,
-        &[lhs_and_rhs_not, lhs_not_and_rhs],
-        BaseOp::Add,
-    )?;
+    let diff = pairwise(config, region, &values, BaseOp::Sub)?;
 
-    Ok(res)
+    pairwise(config, region, &[diff.clone(), diff], BaseOp::Mult)
 }
 
-/// Elementwise applies not to a tensor .
+/// Elementwise applies not to a tensor of {0,1}, as `1-a`
 /// # Arguments
 /// * `a` - Tensor
+/// * `boolean_checks` - the input to constrain to be boolean, if it isn't by construction
 /// # Examples
 /// ```
 /// use ezkl::tensor::Tensor;
@@ -2911,7 +3172,7 @@ pub fn xor<F: PrimeField + TensorType + PartialOrd + std::hash::Hash>(
 ///    Some(&[1, 1, 1, 1, 1, 0]),
 ///   &[2, 3],
 /// ).unwrap());
-/// let result = not::<Fp>(&dummy_config, &mut dummy_region, &[x]).unwrap();
+/// let result = not::<Fp>(&dummy_config, &mut dummy_region, &[x], &[0]).unwrap();
 /// let expected = Tensor::<IntegerRep>::new(Some(&[0, 0, 0, 0, 0, 1]), &[2, 3]).unwrap();
 /// assert_eq!(result.int_evals().unwrap(), expected);
 /// ```
@@ -2919,15 +3180,11 @@ pub fn not<F: PrimeField + TensorType + PartialOrd + std::hash::Hash>(
     config: &BaseConfig<F>,
     region: &mut RegionCtx<F>,
     values: &[ValTensor<F>; 1],
+    boolean_checks: &[usize],
 ) -> Result<ValTensor<F>, CircuitError> {
-    let mask = values[0].clone();
+    let [value] = constrain_boolean(config, region, values, boolean_checks)?;
 
-    let unit = create_unit_tensor(1);
-    let nil = create_zero_tensor(1);
-
-    let res = iff(config, region, &[mask, nil, unit])?;
-
-    Ok(res)
+    pairwise(config, region, &[create_unit_tensor(1), value], BaseOp::Sub)
 }
 
 /// IFF operation.
@@ -2989,7 +3246,7 @@ pub fn iff<F: PrimeField + TensorType + PartialOrd + std::hash::Hash>(
     Ok(res)
 }
 
-/// Negates a tensor.
+/// Negates a tensor, multiplying it by a constant -1.
 /// # Arguments
 ///
 /// * `a` - Tensor
@@ -3021,8 +3278,14 @@ pub fn neg<F: PrimeField + TensorType + PartialOrd + std::hash::Hash>(
     region: &mut RegionCtx<F>,
     values: &[ValTensor<F>; 1],
 ) -> Result<ValTensor<F>, CircuitError> {
-    let nil = create_zero_tensor(1);
-    pairwise(config, region, &[nil, values[0].clone()], BaseOp::Sub)
+    // the negations added to or subtracted from another value are folded into the add or sub when the graph is loaded
+    let minus_one = create_constant_tensor(-F::ONE, 1);
+    pairwise(
+        config,
+        region,
+        &[values[0].clone(), minus_one],
+        BaseOp::Mult,
+    )
 }
 
 /// Applies sum pooling over ND tensor of shape B x C x D1 x D2 x ... x DN.
@@ -3194,7 +3457,16 @@ pub fn max_pool<F: PrimeField + TensorType + PartialOrd + std::hash::Hash>(
                     }),
             );
 
-            let slice = padded_image.get_slice(&slice)?;
+            let mut slice = padded_image.get_slice(&slice)?;
+            // the padding of the window is constant zeros, a single one of them bounds the max as well as all of them
+            let mut zero_indices = slice.get_const_zero_indices();
+            if zero_indices.len() == slice.len() {
+                *o = ValType::Constant(F::ZERO);
+                return Ok(());
+            }
+            if zero_indices.len() > 1 {
+                slice.remove_indices(&mut zero_indices[1..], true)?;
+            }
             let max_w = max(config, region, &[slice])?;
             *o = max_w.get_inner_tensor()?[0].clone();
             Ok(())
@@ -3529,8 +3801,11 @@ pub fn conv<
     let mut assigned_len = vec![];
 
     if !kernel.all_prev_assigned() {
-        kernel = region.assign(&config.custom_gates.inputs[0], &kernel)?;
-        assigned_len.push(kernel.len());
+        // the zeros of sparse kernels are constants whose terms are elided from the dot products, no need to assign them
+        let zero_indices = HashSet::from_iter(kernel.get_const_zero_indices());
+        kernel =
+            region.assign_with_omissions(&config.custom_gates.inputs[0], &kernel, &zero_indices)?;
+        assigned_len.push(kernel.len() - zero_indices.len());
     }
     // 2. assign the image
     if !image.all_prev_assigned() {
@@ -3551,6 +3826,8 @@ pub fn conv<
     let image_dims = image.dims();
     let kernel_dims = kernel.dims();
 
+    // the image is padded after it is assigned, the padding is constant zeros whose terms the dot products elide such
+    // that the padded positions are neither assigned nor constrained
     let mut padded_image = image.clone();
     padded_image.pad(padding.to_vec(), 2)?;
 
@@ -3901,12 +4178,39 @@ pub(crate) fn enforce_equality<F: PrimeField + TensorType + PartialOrd + std::ha
     if !region.is_dummy() {
         region.constrain_equal(&input, &output)?;
     }
+    region.count_equalities(output.len());
 
     region.increment(output.len());
 
     Ok(output)
 }
 
+/// layout for enforcing every element of a tensor to lie within inclusive bounds
+pub(crate) fn enforce_bounds<F: PrimeField + TensorType + PartialOrd + std::hash::Hash>(
+    config: &BaseConfig<F>,
+    region: &mut RegionCtx<F>,
+    values: &[ValTensor<F>; 1],
+    lower: Option<IntegerRep>,
+    upper: Option<IntegerRep>,
+) -> Result<ValTensor<F>, CircuitError> {
+    let input = values[0].clone();
+    let ones = create_unit_tensor(input.len());
+
+    if let Some(lower) = lower {
+        let bound = create_constant_tensor(integer_rep_to_felt(lower), 1);
+        let within = greater_equal(config, region, &[input.clone(), bound])?;
+        enforce_equality(config, region, &[within, ones.clone()])?;
+    }
+
+    if let Some(upper) = upper {
+        let bound = create_constant_tensor(integer_rep_to_felt(upper), 1);
+        let within = less_equal(config, region, &[input.clone(), bound])?;
+        enforce_equality(config, region, &[within, ones])?;
+    }
+
+    Ok(input)
+}
+
 /// layout for range check.
 pub(crate) fn range_check<F: PrimeField + TensorType + PartialOrd + std::hash::Hash>(
     config: &BaseConfig<F>,
@@ -3914,7 +4218,12 @@ pub(crate) fn range_check<F: PrimeField + TensorType + PartialOrd + std::hash::H
     values: &[ValTensor<F>; 1],
     range: &crate::circuit::table::Range,
 ) -> Result<ValTensor<F>, CircuitError> {
+    if region.decomposes_range(range) {
+        return range_check_bits(config, region, values, range);
+    }
+
     region.add_used_range_check(*range)?;
+    region.count_range_checks(*range, values[0].len());
 
     // time the entire operation
     let timer = instant::Instant::now();
@@ -3947,6 +4256,7 @@ pub(crate) fn range_check<F: PrimeField + TensorType + PartialOrd + std::hash::H
         .into();
 
     region.assign(&config.range_checks.index, &table_index)?;
+    region.count_lookups(assigned_len);
 
     if !is_dummy {
         (0..assigned_len)
@@ -3986,6 +4296,96 @@ pub(crate) fn range_check<F: PrimeField + TensorType + PartialOrd + std::hash::H
     Ok(w)
 }
 
+/// layout for range check decomposing the values in bits instead of looking them up, such that the range needs no
+/// table. The values minus the lower bound of the range are decomposed in boolean constrained bits, as is the upper
+/// bound minus the values when the range isn't of a power of two values, see
+/// [crate::circuit::table::range_decomposition].
+pub(crate) fn range_check_bits<F: PrimeField + TensorType + PartialOrd + std::hash::Hash>(
+    config: &BaseConfig<F>,
+    region: &mut RegionCtx<F>,
+    values: &[ValTensor<F>; 1],
+    range: &crate::circuit::table::Range,
+) -> Result<ValTensor<F>, CircuitError> {
+    let timer = instant::Instant::now();
+
+    let mut w = values[0].clone();
+    if w.is_empty() {
+        return Ok(w);
+    }
+    if !w.all_prev_assigned() {
+        w = region.assign(&config.custom_gates.output, &w)?;
+        region.increment(w.len());
+    }
+
+    let is_assigned = !w.any_unknowns()?;
+    if is_assigned && region.check_range() {
+        // assert is within range
+        let int_values = w.int_evals()?;
+        for v in int_values.iter() {
+            if v < &range.0 || v > &range.1 {
+                return Err(CircuitError::TableOOR(*v, range.0, range.1));
+            }
+        }
+    }
+
+    let (bits, bounded_above) = crate::circuit::table::range_decomposition(*range);
+    let mut x = w.clone();
+    x.flatten();
+
+    let lower = create_constant_tensor(integer_rep_to_felt(range.0), 1);
+    let above_lower = if range.0 == 0 {
+        x.clone()
+    } else {
+        pairwise(config, region, &[x.clone(), lower], BaseOp::Sub)?
+    };
+    decompose_bits(config, region, &above_lower, bits)?;
+
+    if bounded_above {
+        let upper = create_constant_tensor(integer_rep_to_felt(range.1), 1);
+        let below_upper = pairwise(config, region, &[upper, x], BaseOp::Sub)?;
+        decompose_bits(config, region, &below_upper, bits)?;
+    }
+
+    trace!(
+        "bit range check {:?} layout took {:?}, row: {:?}",
+        range,
+        timer.elapsed(),
+        region.row()
+    );
+
+    Ok(w)
+}
+
+/// Constrains the values of a flat tensor to be the sums of `bits` boolean constrained bits, and so to lie in
+/// `[0, 2^bits)`
+fn decompose_bits<F: PrimeField + TensorType + PartialOrd + std::hash::Hash>(
+    config: &BaseConfig<F>,
+    region: &mut RegionCtx<F>,
+    values: &ValTensor<F>,
+    bits: usize,
+) -> Result<(), CircuitError> {
+    let len = values.len();
+
+    let claimed_bits: Tensor<ValType<F>> = if region.witness_gen() && !values.any_unknowns()? {
+        let int_values = values.int_evals()?;
+        Tensor::from(int_values.into_iter().flat_map(|v| {
+            (0..bits).map(move |b| ValType::Value(Value::known(F::from(((v >> b) & 1) as u64))))
+        }))
+    } else {
+        Tensor::from(vec![ValType::Value(Value::unknown()); len * bits].into_iter())
+    };
+
+    let mut claimed_bits = boolean_identity(config, region, &[claimed_bits.into()], true)?;
+    claimed_bits.reshape(&[len, bits])?;
+
+    let powers: ValTensor<F> =
+        Tensor::from((0..bits).map(|b| ValType::Constant(F::from_u128(1 << b)))).into();
+    let recomposed = einsum(config, region, &[claimed_bits, powers], "ij,j->i")?;
+
+    enforce_equality(config, region, &[values.clone(), recomposed])?;
+    Ok(())
+}
+
 /// layout for nonlinearity check.
 pub(crate) fn nonlinearity<F: PrimeField + TensorType + PartialOrd + std::hash::Hash>(
     config: &BaseConfig<F>,
@@ -4052,6 +4452,7 @@ pub(crate) fn nonlinearity<F: PrimeField + TensorType + PartialOrd + std::hash::
         .into();
 
     region.assign_with_omissions(&config.static_lookups.index, &table_index, &removal_indices)?;
+    region.count_lookups(assigned_len);
 
     if !is_dummy {
         (0..assigned_len)
@@ -4083,6 +4484,71 @@ pub(crate) fn nonlinearity<F: PrimeField + TensorType + PartialOrd + std::hash::
     Ok(output)
 }
 
+/// Elementwise add fed directly into a lookup: the addends are assigned to the lookup input and index columns, and
+/// their sum is looked up in the table of `nl` without being assigned itself, see
+/// [BaseConfig::configure_fused_add_lookup]. The sums are lookup inputs as any, and are bounded by the range of the
+/// table.
+pub(crate) fn add_nonlinearity<F: PrimeField + TensorType + PartialOrd + std::hash::Hash>(
+    config: &BaseConfig<F>,
+    region: &mut RegionCtx<F>,
+    values: &[ValTensor<F>; 2],
+    nl: &LookupOp,
+) -> Result<ValTensor<F>, CircuitError> {
+    let (mut lhs, mut rhs) = (values[0].clone(), values[1].clone());
+    let broadcasted_shape = get_broadcasted_shape(lhs.dims(), rhs.dims())?;
+    lhs.expand(&broadcasted_shape)?;
+    rhs.expand(&broadcasted_shape)?;
+
+    let sums: ValTensor<F> = if !lhs.any_unknowns()? && !rhs.any_unknowns()? {
+        let (lhs, rhs) = (lhs.get_felt_evals()?, rhs.get_felt_evals()?);
+        add(&[lhs, rhs])?.map(Value::known).into()
+    } else {
+        Tensor::new(Some(&vec![Value::<F>::unknown(); lhs.len()]), &[lhs.len()])?.into()
+    };
+    region.add_used_fused_add_lookup(nl.clone(), &[sums.clone()])?;
+
+    let output: ValTensor<F> = sums
+        .get_inner_tensor()?
+        .par_enum_map(|_, e| {
+            Ok::<_, TensorError>(match e.get_felt_eval() {
+                Some(f) => Value::known(nl.f(&[Tensor::from(vec![f].into_iter())])?.output[0]),
+                None => Value::<F>::unknown(),
+            })
+        })?
+        .into();
+
+    region.assign(&config.static_lookups.input, &lhs)?;
+    region.assign(&config.static_lookups.index, &rhs)?;
+    let mut output = region.assign(&config.static_lookups.output, &output)?;
+    region.count_lookups(output.len());
+
+    if !region.is_dummy() {
+        (0..output.len())
+            .map(|i| {
+                let (x, y, z) = config
+                    .static_lookups
+                    .input
+                    .cartesian_coord(region.linear_coord() + i);
+                let selector = config
+                    .static_lookups
+                    .fused_add_selectors
+                    .get(&(nl.clone(), x, y))
+                    .ok_or(CircuitError::LookupNotConfigured(format!(
+                        "{} fused with an add",
+                        Op::<F>::as_string(nl)
+                    )))?;
+                region.enable(Some(selector), z)?;
+                Ok(())
+            })
+            .collect::<Result<Vec<_>, CircuitError>>()?;
+    }
+
+    region.increment(output.len());
+    output.reshape(&broadcasted_shape)?;
+
+    Ok(output)
+}
+
 /// Argmax
 pub(crate) fn argmax<F: PrimeField + TensorType + PartialOrd + std::hash::Hash>(
     config: &BaseConfig<F>,
@@ -4162,7 +4628,8 @@ pub(crate) fn max_comp<F: PrimeField + TensorType + PartialOrd + std::hash::Hash
     values: &[ValTensor<F>; 2],
 ) -> Result<ValTensor<F>, CircuitError> {
     let is_greater = greater(config, region, values)?;
-    let is_less = not(config, region, &[is_greater.clone()])?;
+    // the output of a comparison is boolean by construction
+    let is_less = not(config, region, &[is_greater.clone()], &[])?;
 
     let max_val_p1 = pairwise(
         config,
@@ -4183,7 +4650,8 @@ pub(crate) fn min_comp<F: PrimeField + TensorType + PartialOrd + std::hash::Hash
     values: &[ValTensor<F>; 2],
 ) -> Result<ValTensor<F>, CircuitError> {
     let is_greater = greater(config, region, values)?;
-    let is_less = not(config, region, &[is_greater.clone()])?;
+    // the output of a comparison is boolean by construction
+    let is_less = not(config, region, &[is_greater.clone()], &[])?;
 
     let min_val_p1 = pairwise(config, region, &[values[0].clone(), is_less], BaseOp::Mult)?;
 
@@ -4298,11 +4766,61 @@ pub(crate) fn decompose<F: PrimeField + TensorType + PartialOrd + std::hash::Has
     Ok(combined_output.into())
 }
 
+/// Packs the values of a tensor `limbs` at a time into elements of `limbs` limbs of `limb_bits` bits, the first value
+/// in the least significant limb and the last element padded with zero limbs. Each value is decomposed, bounding it
+/// below `base^legs` in absolute value, and biased by half a limb, such that it fits its limb when
+/// `base^legs <= 2^(limb_bits - 1)`.
+pub(crate) fn pack_limbs<F: PrimeField + TensorType + PartialOrd + std::hash::Hash>(
+    config: &BaseConfig<F>,
+    region: &mut RegionCtx<F>,
+    values: &[ValTensor<F>; 1],
+    limbs: usize,
+    limb_bits: usize,
+) -> Result<ValTensor<F>, CircuitError> {
+    let mut input = values[0].clone();
+    input.flatten();
+    let num_packed = input.len().div_ceil(limbs);
+
+    decompose(
+        config,
+        region,
+        &[input.clone()],
+        &region.base(),
+        &region.legs(),
+    )?;
+
+    let two = F::from(2);
+    let bias = create_constant_tensor(two.pow_vartime([(limb_bits - 1) as u64]), input.len());
+    let mut biased = pairwise(config, region, &[input, bias], BaseOp::Add)?;
+    biased.pad_to_zero_rem(limbs, ValType::Constant(F::ZERO))?;
+
+    let shifts: ValTensor<F> = Tensor::from(
+        (0..num_packed * limbs)
+            .map(|i| ValType::Constant(two.pow_vartime([((i % limbs) * limb_bits) as u64]))),
+    )
+    .into();
+    let mut shifted = pairwise(config, region, &[biased, shifts], BaseOp::Mult)?;
+    shifted.reshape(&[num_packed, limbs])?;
+
+    let mut packed = sum_axes(config, region, &[shifted], &[1])?;
+    packed.reshape(&[num_packed])?;
+    Ok(packed)
+}
+
+/// The sign of the integers the values represent, as -1, 0 or 1, the values being decomposed such that a value
+/// outside of `[-(base^legs - 1), base^legs - 1]` is an error rather than a wrapped sign
 pub(crate) fn sign<F: PrimeField + TensorType + PartialOrd + std::hash::Hash>(
     config: &BaseConfig<F>,
     region: &mut RegionCtx<F>,
     values: &[ValTensor<F>; 1],
 ) -> Result<ValTensor<F>, CircuitError> {
+    if region.witness_gen() && !values[0].any_unknowns()? {
+        let max_abs = max_decomposable(region.base(), region.legs());
+        for v in values[0].get_felt_evals()?.iter() {
+            felt_sign(*v, max_abs).map_err(TensorError::from)?;
+        }
+    }
+
     let mut decomp = decompose(config, region, values, &region.base(), &region.legs())?;
     // get every n elements now, which correspond to the sign bit
 
@@ -4312,6 +4830,7 @@ pub(crate) fn sign<F: PrimeField + TensorType + PartialOrd + std::hash::Hash>(
     Ok(decomp)
 }
 
+/// The absolute values of the integers the values represent, as the values times their [sign]
 pub(crate) fn abs<F: PrimeField + TensorType + PartialOrd + std::hash::Hash>(
     config: &BaseConfig<F>,
     region: &mut RegionCtx<F>,
@@ -1,25 +1,99 @@
 use super::*;
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+use tosubcommand::ToFlags;
 
 use crate::{
-    circuit::{layouts, table::Range, utils},
+    circuit::{
+        layouts,
+        table::{table_evals, Range},
+        utils, DivRounding,
+    },
     fieldutils::{felt_to_integer_rep, integer_rep_to_felt, IntegerRep},
     graph::multiplier_to_scale,
-    tensor::{self, Tensor, TensorError, TensorType},
+    tensor::{self, val::checked_integer_rep, Tensor, TensorError, TensorType},
 };
 
 use super::Op;
+use halo2curves::bn256::Fr as Fp;
 use halo2curves::ff::PrimeField;
 
+/// The number of mismatches [LookupOp::check_table_consistency] reports, further mismatches are only counted
+const MAX_REPORTED_MISMATCHES: usize = 32;
+
+/// An input of a lookup table whose output in the table differs from the output the witness is generated with
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct TableMismatch {
+    /// The input
+    pub input: IntegerRep,
+    /// The output the table holds for the input, `None` if the table doesn't hold the input at its row
+    pub table: Option<IntegerRep>,
+    /// The output the witness holds for the input
+    pub witness: IntegerRep,
+}
+
+/// The outcome of [LookupOp::check_table_consistency]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct TableConsistency {
+    /// The op of the table, as [LookupOp::as_path]
+    pub op: String,
+    /// The range of inputs checked
+    pub range: Range,
+    /// The number of inputs whose table and witness outputs differ
+    pub num_mismatches: usize,
+    /// The first of these mismatches
+    pub mismatches: Vec<TableMismatch>,
+    /// The largest absolute deviation of the table outputs from the f64 reference of the op, in units of the
+    /// quantized output. Rounding to the nearest integer deviates by at most 0.5
+    pub max_deviation: f64,
+    /// The input the largest deviation is at
+    pub max_deviation_input: Option<IntegerRep>,
+    /// The number of inputs left out of the deviation as the reference is undefined at them (outside the domain of
+    /// the op) or exceeds the integer representation
+    pub skipped: usize,
+}
+
+impl TableConsistency {
+    /// Whether the table holds the output of the witness for every input of the range
+    pub fn is_consistent(&self) -> bool {
+        self.num_mismatches == 0
+    }
+}
+
+/// The error function, to machine precision, from its series
+/// `erf(x) = 2 / sqrt(pi) * exp(-x^2) * sum_n 2^n x^(2n + 1) / (1 * 3 * ... * (2n + 1))` whose terms are all positive.
+/// Kept apart from the approximation the tables use, which it serves as a reference for.
+fn reference_erf(x: f64) -> f64 {
+    if x.abs() >= 6.0 {
+        return x.signum();
+    }
+    let mut term = x;
+    let mut sum = x;
+    let mut n = 0.0;
+    while term.abs() > f64::EPSILON * sum.abs() {
+        n += 1.0;
+        term *= 2.0 * x * x / (2.0 * n + 1.0);
+        sum += term;
+    }
+    2.0 / std::f64::consts::PI.sqrt() * (-x * x).exp() * sum
+}
+
 #[allow(missing_docs)]
 /// An enum representing the operations that can be used to express more complex operations via accumulation
 #[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Deserialize, Serialize)]
+#[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), derive(JsonSchema))]
 pub enum LookupOp {
     Div {
         denom: utils::F32,
+        #[serde(default)]
+        rounding: DivRounding,
     },
     Cast {
         scale: utils::F32,
+        #[serde(default)]
+        rounding: DivRounding,
     },
     Ceil {
         scale: utils::F32,
@@ -42,6 +116,8 @@ pub enum LookupOp {
     Recip {
         input_scale: utils::F32,
         output_scale: utils::F32,
+        #[serde(default)]
+        rounding: DivRounding,
     },
     LeakyReLU {
         slope: utils::F32,
@@ -95,6 +171,12 @@ pub enum LookupOp {
         scale: utils::F32,
     },
     KroneckerDelta,
+    EqualsConst {
+        value: IntegerRep,
+    },
+    InSet {
+        values: Vec<IntegerRep>,
+    },
     Pow {
         scale: utils::F32,
         a: utils::F32,
@@ -104,7 +186,41 @@ pub enum LookupOp {
     },
 }
 
+/// The inputs the op of a node looked up, for the ops whose tables are sized to the inputs calibration observed for
+/// their nodes (see [LookupOp::tightens_table_range])
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+#[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), derive(JsonSchema))]
+pub struct NodeLookupRange {
+    /// the index of the node
+    pub node: usize,
+    /// the name of the node in the onnx graph
+    pub name: String,
+    /// the op the node looked up
+    pub op: LookupOp,
+    /// the smallest and largest inputs
+    pub range: Range,
+}
+
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+impl ToFlags for NodeLookupRange {}
+
 impl LookupOp {
+    /// Whether the table of the op is sized to the inputs calibration observed for it rather than to the lookup range:
+    /// the inputs of reciprocals and reciprocal square roots (variances, norms) take a small positive part of the
+    /// range
+    pub fn tightens_table_range(&self) -> bool {
+        matches!(self, LookupOp::Recip { .. } | LookupOp::Rsqrt { .. })
+    }
+
+    /// Indicates the inputs in a set of values, which are sorted and deduplicated such that the sets of the same
+    /// values share their table
+    pub fn in_set(values: impl IntoIterator<Item = IntegerRep>) -> Self {
+        let mut values = values.into_iter().collect::<Vec<_>>();
+        values.sort_unstable();
+        values.dedup();
+        LookupOp::InSet { values }
+    }
+
     /// Returns the range of values that can be represented by the table
     pub fn bit_range(max_len: usize) -> Range {
         let range = (max_len - 1) as f64 / 2_f64;
@@ -121,12 +237,18 @@ impl LookupOp {
             LookupOp::RoundHalfToEven { scale } => format!("round_half_to_even_{}", scale),
             LookupOp::Pow { scale, a } => format!("pow_{}_{}", scale, a),
             LookupOp::KroneckerDelta => "kronecker_delta".into(),
-            LookupOp::Div { denom } => format!("div_{}", denom),
-            LookupOp::Cast { scale } => format!("cast_{}", scale),
+            LookupOp::EqualsConst { value } => format!("equals_const_{}", value),
+            LookupOp::InSet { values } => {
+                let values = values.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+                format!("in_set_{}", values.join("_"))
+            }
+            LookupOp::Div { denom, rounding } => format!("div_{}_{}", denom, rounding),
+            LookupOp::Cast { scale, rounding } => format!("cast_{}_{}", scale, rounding),
             LookupOp::Recip {
                 input_scale,
                 output_scale,
-            } => format!("recip_{}_{}", input_scale, output_scale),
+                rounding,
+            } => format!("recip_{}_{}_{}", input_scale, output_scale, rounding),
             LookupOp::LeakyReLU { slope: a } => format!("leaky_relu_{}", a),
             LookupOp::Sigmoid { scale } => format!("sigmoid_{}", scale),
             LookupOp::Sqrt { scale } => format!("sqrt_{}", scale),
@@ -150,12 +272,150 @@ impl LookupOp {
         }
     }
 
+    /// The output of the op at the integer `x`, computed in f64 without the rounding to an integer of the tables.
+    /// Not finite where the op is undefined.
+    fn reference(&self, x: f64) -> f64 {
+        let scaled = |scale: &utils::F32, g: fn(f64) -> f64| {
+            let scale = f64::from(scale.0);
+            scale * g(x / scale)
+        };
+        match self {
+            LookupOp::Ceil { scale } => scaled(scale, f64::ceil),
+            LookupOp::Floor { scale } => scaled(scale, f64::floor),
+            LookupOp::Round { scale } => scaled(scale, f64::round),
+            LookupOp::RoundHalfToEven { scale } => scaled(scale, f64::round_ties_even),
+            LookupOp::Pow { scale, a } => {
+                let scale = f64::from(scale.0);
+                scale * (x / scale).powf(a.0.into())
+            }
+            LookupOp::KroneckerDelta => {
+                if x == 0.0 {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            LookupOp::EqualsConst { value } => {
+                if x == *value as f64 {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            LookupOp::InSet { values } => {
+                if values.iter().any(|v| x == *v as f64) {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            LookupOp::Div { denom, .. } => x / f64::from(denom.0),
+            LookupOp::Cast { scale, .. } => x / f64::from(scale.0),
+            LookupOp::Recip {
+                input_scale,
+                output_scale,
+                ..
+            } => f64::from(output_scale.0) / (x / f64::from(input_scale.0)),
+            LookupOp::LeakyReLU { slope } => {
+                if x < 0.0 {
+                    f64::from(slope.0) * x
+                } else {
+                    x
+                }
+            }
+            LookupOp::Sigmoid { scale } => scaled(scale, |x| 1.0 / (1.0 + (-x).exp())),
+            LookupOp::Sqrt { scale } => scaled(scale, f64::sqrt),
+            LookupOp::Rsqrt { scale } => scaled(scale, |x| 1.0 / x.sqrt()),
+            LookupOp::Erf { scale } => scaled(scale, reference_erf),
+            LookupOp::Exp { scale } => scaled(scale, f64::exp),
+            LookupOp::Ln { scale } => scaled(scale, f64::ln),
+            LookupOp::Cos { scale } => scaled(scale, f64::cos),
+            LookupOp::ACos { scale } => scaled(scale, f64::acos),
+            LookupOp::Cosh { scale } => scaled(scale, f64::cosh),
+            LookupOp::ACosh { scale } => scaled(scale, f64::acosh),
+            LookupOp::Sin { scale } => scaled(scale, f64::sin),
+            LookupOp::ASin { scale } => scaled(scale, f64::asin),
+            LookupOp::Sinh { scale } => scaled(scale, f64::sinh),
+            LookupOp::ASinh { scale } => scaled(scale, f64::asinh),
+            LookupOp::Tan { scale } => scaled(scale, f64::tan),
+            LookupOp::ATan { scale } => scaled(scale, f64::atan),
+            LookupOp::Tanh { scale } => scaled(scale, f64::tanh),
+            LookupOp::ATanh { scale } => scaled(scale, f64::atanh),
+            LookupOp::HardSwish { scale } => scaled(scale, |x| {
+                if x <= -3.0 {
+                    0.0
+                } else if x >= 3.0 {
+                    x
+                } else {
+                    x * (x + 3.0) / 6.0
+                }
+            }),
+        }
+    }
+
+    /// Checks the table of the op over `range` against the witness: generates the table as it's laid out (loading it
+    /// from the lookup table cache if it's cached there), evaluates the op on each input of the range on its own as
+    /// the witness is generated, and reports the inputs at which the two differ along with the largest deviation of
+    /// the table from an f64 reference of the op.
+    pub fn check_table_consistency(&self, range: Range) -> Result<TableConsistency, CircuitError> {
+        if range.0 > range.1 {
+            return Err(CircuitError::InvalidMinMaxRange(range.0, range.1));
+        }
+        let (inputs, evals) = table_evals::<Fp>(self, range)?;
+
+        let mut report = TableConsistency {
+            op: self.as_path(),
+            range,
+            num_mismatches: 0,
+            mismatches: vec![],
+            max_deviation: 0.0,
+            max_deviation_input: None,
+            skipped: 0,
+        };
+
+        for (row, x) in (range.0..=range.1).enumerate() {
+            let input = integer_rep_to_felt::<Fp>(x);
+            let witness = self.f(&[Tensor::from(vec![input].into_iter())])?.output[0];
+            let table = match (inputs.get(row), evals.get(row)) {
+                (Some(table_input), Some(table_output)) if *table_input == input => {
+                    Some(*table_output)
+                }
+                _ => None,
+            };
+
+            if table != Some(witness) {
+                report.num_mismatches += 1;
+                if report.mismatches.len() < MAX_REPORTED_MISMATCHES {
+                    report.mismatches.push(TableMismatch {
+                        input: x,
+                        table: table.map(felt_to_integer_rep),
+                        witness: felt_to_integer_rep(witness),
+                    });
+                }
+            }
+
+            let reference = self.reference(x as f64);
+            let output = table.unwrap_or(witness);
+            if !reference.is_finite() || reference.abs() >= IntegerRep::MAX as f64 {
+                report.skipped += 1;
+                continue;
+            }
+            let deviation = (felt_to_integer_rep(output) as f64 - reference).abs();
+            if report.max_deviation_input.is_none() || deviation > report.max_deviation {
+                report.max_deviation = deviation;
+                report.max_deviation_input = Some(x);
+            }
+        }
+
+        Ok(report)
+    }
+
     /// Matches a [Op] to an operation in the `tensor::ops` module.
     pub(crate) fn f<F: PrimeField + TensorType + PartialOrd + std::hash::Hash>(
         &self,
         x: &[Tensor<F>],
     ) -> Result<ForwardResult<F>, TensorError> {
-        let x = x[0].clone().map(|x| felt_to_integer_rep(x));
+        let x = x[0].par_enum_map(|_, x| checked_integer_rep(x))?;
         let res =
             match &self {
                 LookupOp::Ceil { scale } => {
@@ -176,19 +436,27 @@ impl LookupOp {
                 LookupOp::KroneckerDelta => {
                     Ok::<_, TensorError>(tensor::ops::nonlinearities::kronecker_delta(&x))
                 }
-                LookupOp::Div { denom } => Ok::<_, TensorError>(
-                    tensor::ops::nonlinearities::const_div(&x, f32::from(*denom).into()),
+                LookupOp::EqualsConst { value } => {
+                    Ok::<_, TensorError>(tensor::ops::nonlinearities::equals_const(&x, *value))
+                }
+                LookupOp::InSet { values } => {
+                    Ok::<_, TensorError>(tensor::ops::nonlinearities::in_set(&x, values))
+                }
+                LookupOp::Div { denom, rounding } => Ok::<_, TensorError>(
+                    tensor::ops::nonlinearities::const_div(&x, f32::from(*denom).into(), *rounding),
                 ),
-                LookupOp::Cast { scale } => Ok::<_, TensorError>(
-                    tensor::ops::nonlinearities::const_div(&x, f32::from(*scale).into()),
+                LookupOp::Cast { scale, rounding } => Ok::<_, TensorError>(
+                    tensor::ops::nonlinearities::const_div(&x, f32::from(*scale).into(), *rounding),
                 ),
                 LookupOp::Recip {
                     input_scale,
                     output_scale,
+                    rounding,
                 } => Ok::<_, TensorError>(tensor::ops::nonlinearities::recip(
                     &x,
                     input_scale.into(),
                     output_scale.into(),
+                    *rounding,
                 )),
                 LookupOp::LeakyReLU { slope: a } => {
                     Ok::<_, TensorError>(tensor::ops::nonlinearities::leakyrelu(&x, a.0.into()))
@@ -252,7 +520,7 @@ impl LookupOp {
                 }
             }?;
 
-        let output = res.map(|x| integer_rep_to_felt(x));
+        let output = res.par_map(|x| integer_rep_to_felt(x));
 
         Ok(ForwardResult { output })
     }
@@ -273,15 +541,22 @@ impl<F: PrimeField + TensorType + PartialOrd + std::hash::Hash> Op<F> for Lookup
             LookupOp::RoundHalfToEven { scale } => format!("ROUND_HALF_TO_EVEN(scale={})", scale),
             LookupOp::Pow { a, scale } => format!("POW(scale={}, exponent={})", scale, a),
             LookupOp::KroneckerDelta => "K_DELTA".into(),
+            LookupOp::EqualsConst { value } => format!("EQUALS_CONST(value={})", value),
+            LookupOp::InSet { values } => format!("IN_SET(values={:?})", values),
             LookupOp::Recip {
                 input_scale,
                 output_scale,
+                rounding,
             } => format!(
-                "RECIP(input_scale={}, output_scale={})",
-                input_scale, output_scale
+                "RECIP(input_scale={}, output_scale={}, rounding={})",
+                input_scale, output_scale, rounding
             ),
-            LookupOp::Div { denom, .. } => format!("DIV(denom={})", denom),
-            LookupOp::Cast { scale } => format!("CAST(scale={})", scale),
+            LookupOp::Div { denom, rounding } => {
+                format!("DIV(denom={}, rounding={})", denom, rounding)
+            }
+            LookupOp::Cast { scale, rounding } => {
+                format!("CAST(scale={}, rounding={})", scale, rounding)
+            }
             LookupOp::Ln { scale } => format!("LN(scale={})", scale),
             LookupOp::LeakyReLU { slope: a } => format!("L_RELU(slope={})", a),
             LookupOp::Sigmoid { scale } => format!("SIGMOID(scale={})", scale),
@@ -322,12 +597,12 @@ impl<F: PrimeField + TensorType + PartialOrd + std::hash::Hash> Op<F> for Lookup
     /// Returns the scale of the output of the operation.
     fn out_scale(&self, inputs_scale: Vec<crate::Scale>) -> Result<crate::Scale, CircuitError> {
         let scale = match self {
-            LookupOp::Cast { scale } => {
+            LookupOp::Cast { scale, .. } => {
                 let in_scale = inputs_scale[0];
                 in_scale + multiplier_to_scale(1. / scale.0 as f64)
             }
             LookupOp::Recip { output_scale, .. } => multiplier_to_scale(output_scale.into()),
-            LookupOp::KroneckerDelta => 0,
+            LookupOp::KroneckerDelta | LookupOp::EqualsConst { .. } | LookupOp::InSet { .. } => 0,
             _ => inputs_scale[0],
         };
         Ok(scale)
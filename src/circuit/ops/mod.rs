@@ -3,8 +3,8 @@ use std::any::Any;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    graph::quantize_tensor,
-    tensor::{self, Tensor, TensorType, ValTensor},
+    graph::{quantize_tensor, QuantRounding},
+    tensor::{self, sparse::sparsity, SparseTensor, Tensor, TensorType, ValTensor},
 };
 use halo2curves::ff::PrimeField;
 
@@ -232,6 +232,9 @@ pub struct Constant<F: PrimeField + TensorType + PartialOrd + std::hash::Hash> {
     ///
     #[serde(skip)]
     pub pre_assigned_val: Option<ValTensor<F>>,
+    /// The sparse representation of the quantized values, set when enough of them are zero
+    #[serde(default)]
+    pub sparse_values: Option<SparseTensor<F>>,
 }
 
 impl<F: PrimeField + TensorType + PartialOrd + std::hash::Hash> Constant<F> {
@@ -241,15 +244,43 @@ impl<F: PrimeField + TensorType + PartialOrd + std::hash::Hash> Constant<F> {
             quantized_values,
             raw_values,
             pre_assigned_val: None,
+            sparse_values: None,
         }
     }
-    /// Rebase the scale of the constant
-    pub fn rebase_scale(&mut self, new_scale: crate::Scale) -> Result<(), CircuitError> {
+    /// Rebase the scale of the constant, requantizing the raw values with `rounding`
+    pub fn rebase_scale(
+        &mut self,
+        new_scale: crate::Scale,
+        rounding: &QuantRounding,
+    ) -> Result<(), CircuitError> {
         let visibility = self.quantized_values.visibility().unwrap();
-        self.quantized_values = quantize_tensor(self.raw_values.clone(), new_scale, &visibility)?;
+        self.quantized_values =
+            quantize_tensor(self.raw_values.clone(), new_scale, &visibility, rounding)?;
+        self.sparse_values = None;
         Ok(())
     }
 
+    /// Represents the quantized values sparsely if at least a `threshold` fraction of them are zero, such that the
+    /// terms of the zeros are elided when laid out. The positions of the zeros become part of the circuit.
+    pub fn sparsify(&mut self, threshold: f32) -> Result<bool, CircuitError> {
+        let is_sparse =
+            !self.quantized_values.is_empty() && sparsity(&self.quantized_values) >= threshold;
+        self.sparse_values = if is_sparse {
+            Some(SparseTensor::from_dense(&self.quantized_values)?)
+        } else {
+            None
+        };
+        Ok(is_sparse)
+    }
+
+    /// The quantized values as a [ValTensor], the zeros being constants if the constant is sparse
+    pub fn quantized_val_tensor(&self) -> Result<ValTensor<F>, CircuitError> {
+        Ok(match &self.sparse_values {
+            Some(sparse) => sparse.to_val_tensor()?,
+            None => self.quantized_values.clone().try_into()?,
+        })
+    }
+
     /// Empty raw value
     pub fn empty_raw_value(&mut self) {
         self.raw_values = Tensor::new(None, &[0]).unwrap();
@@ -286,7 +317,7 @@ impl<
         let value = if let Some(value) = &self.pre_assigned_val {
             value.clone()
         } else {
-            self.quantized_values.clone().try_into()?
+            self.quantized_val_tensor()?
         };
         // we gotta constrain it once if its used multiple times
         Ok(Some(layouts::identity(config, region, &[value])?))
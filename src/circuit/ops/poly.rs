@@ -86,10 +86,20 @@ pub enum PolyOp {
     Resize {
         scale_factor: Vec<usize>,
     },
-    Not,
-    And,
-    Or,
-    Xor,
+    /// Logical ops on tensors of {0,1}, laid out as polynomials of their inputs. The inputs at `boolean_checks` aren't
+    /// known to be boolean and are constrained to {0,1} by the op, the others already are by the op they stem from.
+    Not {
+        boolean_checks: Vec<usize>,
+    },
+    And {
+        boolean_checks: Vec<usize>,
+    },
+    Or {
+        boolean_checks: Vec<usize>,
+    },
+    Xor {
+        boolean_checks: Vec<usize>,
+    },
     Trilu {
         upper: bool,
         k: i32,
@@ -181,10 +191,10 @@ impl<
                 format!("SLICE (axis={}, start={}, end={})", axis, start, end)
             }
             PolyOp::Neg => "NEG".into(),
-            PolyOp::Not => "NOT".into(),
-            PolyOp::And => "AND".into(),
-            PolyOp::Or => "OR".into(),
-            PolyOp::Xor => "XOR".into(),
+            PolyOp::Not { .. } => "NOT".into(),
+            PolyOp::And { .. } => "AND".into(),
+            PolyOp::Or { .. } => "OR".into(),
+            PolyOp::Xor { .. } => "XOR".into(),
             PolyOp::Trilu { upper, k } => format!("TRILU (upper={}, k={})", upper, k),
         }
     }
@@ -205,10 +215,18 @@ impl<
             PolyOp::MeanOfSquares { axes } => {
                 layouts::mean_of_squares_axes(config, region, values[..].try_into()?, axes)?
             }
-            PolyOp::Xor => layouts::xor(config, region, values[..].try_into()?)?,
-            PolyOp::Or => layouts::or(config, region, values[..].try_into()?)?,
-            PolyOp::And => layouts::and(config, region, values[..].try_into()?)?,
-            PolyOp::Not => layouts::not(config, region, values[..].try_into()?)?,
+            PolyOp::Xor { boolean_checks } => {
+                layouts::xor(config, region, values[..].try_into()?, boolean_checks)?
+            }
+            PolyOp::Or { boolean_checks } => {
+                layouts::or(config, region, values[..].try_into()?, boolean_checks)?
+            }
+            PolyOp::And { boolean_checks } => {
+                layouts::and(config, region, values[..].try_into()?, boolean_checks)?
+            }
+            PolyOp::Not { boolean_checks } => {
+                layouts::not(config, region, values[..].try_into()?, boolean_checks)?
+            }
             PolyOp::MoveAxis {
                 source,
                 destination,
@@ -330,7 +348,7 @@ impl<
     fn out_scale(&self, in_scales: Vec<crate::Scale>) -> Result<crate::Scale, CircuitError> {
         let scale = match self {
             PolyOp::MeanOfSquares { .. } => 2 * in_scales[0],
-            PolyOp::Xor | PolyOp::Or | PolyOp::And | PolyOp::Not => 0,
+            PolyOp::Xor { .. } | PolyOp::Or { .. } | PolyOp::And { .. } | PolyOp::Not { .. } => 0,
             PolyOp::Iff => in_scales[1],
             PolyOp::Einsum { .. } => {
                 let mut scale = in_scales[0];
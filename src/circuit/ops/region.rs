@@ -14,14 +14,19 @@ use itertools::Itertools;
 use maybe_rayon::iter::ParallelExtend;
 use std::{
     cell::RefCell,
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     sync::{
         atomic::{AtomicUsize, Ordering},
         Arc, Mutex,
     },
 };
 
-use super::{lookup::LookupOp, CircuitError};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    lookup::{LookupOp, NodeLookupRange},
+    CircuitError, Op,
+};
 
 /// Constants map
 pub type ConstantsMap<F> = HashMap<F, ValType<F>>;
@@ -97,6 +102,13 @@ pub struct RegionSettings {
     pub base: usize,
     /// number of legs for decompositions
     pub legs: usize,
+    /// whether the rows, lookups and equality constraints of each op are profiled
+    pub profile: bool,
+    /// the ranges whose range checks decompose the values in bits instead of looking them up
+    pub bit_range_checks: BTreeSet<Range>,
+    /// the ranges the tables of the ops sized to the inputs calibration observed for them span, see
+    /// [LookupOp::tightens_table_range]
+    pub tight_lookup_ranges: BTreeMap<LookupOp, Range>,
 }
 
 #[allow(unsafe_code)]
@@ -112,6 +124,9 @@ impl RegionSettings {
             check_range,
             base,
             legs,
+            profile: false,
+            bit_range_checks: BTreeSet::new(),
+            tight_lookup_ranges: BTreeMap::new(),
         }
     }
 
@@ -122,6 +137,9 @@ impl RegionSettings {
             check_range: true,
             base,
             legs,
+            profile: false,
+            bit_range_checks: BTreeSet::new(),
+            tight_lookup_ranges: BTreeMap::new(),
         }
     }
 
@@ -132,8 +150,23 @@ impl RegionSettings {
             check_range: false,
             base,
             legs,
+            profile: false,
+            bit_range_checks: BTreeSet::new(),
+            tight_lookup_ranges: BTreeMap::new(),
         }
     }
+
+    /// The same settings with the ops profiled
+    pub fn with_profile(mut self) -> RegionSettings {
+        self.profile = true;
+        self
+    }
+
+    /// The same settings with the range checks of `ranges` decomposing the values in bits
+    pub fn with_bit_range_checks(mut self, ranges: &[Range]) -> RegionSettings {
+        self.bit_range_checks = ranges.iter().copied().collect();
+        self
+    }
 }
 
 #[derive(Debug, Default, Clone)]
@@ -145,10 +178,21 @@ pub struct RegionStatistics {
     pub min_lookup_inputs: IntegerRep,
     /// the current maximum value of the range size
     pub max_range_size: IntegerRep,
-    /// the current set of used lookups
-    pub used_lookups: HashSet<LookupOp>,
-    /// the current set of used range checks
-    pub used_range_checks: HashSet<Range>,
+    /// the current set of used lookups, ordered such that the tables are configured in the same order on every run
+    pub used_lookups: BTreeSet<LookupOp>,
+    /// the lookups fed the sums of adds directly, see [crate::circuit::BaseConfig::configure_fused_add_lookup]
+    pub used_fused_add_lookups: BTreeSet<LookupOp>,
+    /// the current set of used range checks, ordered as the lookups
+    pub used_range_checks: BTreeSet<Range>,
+    /// the number of values looked up in each range check
+    pub range_check_counts: BTreeMap<Range, usize>,
+    /// the name of each node and the range of the inputs it looked up, by node and op, for the ops whose tables are
+    /// sized to their inputs
+    pub tight_lookup_ranges: BTreeMap<(usize, LookupOp), (String, Range)>,
+    /// the number of looked up values, counted when profiling
+    pub num_lookups: usize,
+    /// the number of equality constraints, counted when profiling
+    pub num_equalities: usize,
 }
 
 impl RegionStatistics {
@@ -158,8 +202,22 @@ impl RegionStatistics {
         self.min_lookup_inputs = self.min_lookup_inputs.min(other.min_lookup_inputs);
         self.max_range_size = self.max_range_size.max(other.max_range_size);
         self.used_lookups.extend(other.used_lookups.clone());
+        self.used_fused_add_lookups
+            .extend(other.used_fused_add_lookups.clone());
         self.used_range_checks
             .extend(other.used_range_checks.clone());
+        for (range, count) in &other.range_check_counts {
+            *self.range_check_counts.entry(*range).or_default() += count;
+        }
+        for (key, (name, range)) in &other.tight_lookup_ranges {
+            let entry = self
+                .tight_lookup_ranges
+                .entry(key.clone())
+                .or_insert((name.clone(), *range));
+            entry.1 = (entry.1 .0.min(range.0), entry.1 .1.max(range.1));
+        }
+        self.num_lookups += other.num_lookups;
+        self.num_equalities += other.num_equalities;
     }
 }
 
@@ -168,6 +226,55 @@ unsafe impl Sync for RegionStatistics {}
 #[allow(unsafe_code)]
 unsafe impl Send for RegionStatistics {}
 
+/// The part of a region laid out by an op of a graph
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OpProfile {
+    /// the index of the node of the op
+    pub node: usize,
+    /// the op, as [super::Op::as_string]
+    pub op: String,
+    /// the row of the region the op starts at
+    #[serde(default)]
+    pub start_row: usize,
+    /// the rows the op takes
+    pub rows: usize,
+    /// the cells the op assigns
+    pub assignments: usize,
+    /// the values the op looks up, in lookup tables and range checks
+    pub lookups: usize,
+    /// the equality constraints the op enforces
+    pub equalities: usize,
+}
+
+/// Where a region was at before an op was laid out, such that what the op laid out can be profiled
+#[derive(Clone, Copy, Debug)]
+pub struct ProfileMark {
+    row: usize,
+    linear_coord: usize,
+    lookups: usize,
+    equalities: usize,
+}
+
+/// The node of a graph being laid out in a region, and the row it started at
+#[derive(Clone, Debug)]
+struct CurrentNode {
+    idx: usize,
+    name: String,
+    op: String,
+    start_row: usize,
+}
+
+impl CurrentNode {
+    fn label(&self) -> String {
+        format!("{} ({})", self.idx, self.name)
+    }
+}
+
+/// The number of the largest ops listed when a region overflows its rows
+const NUM_LARGEST_OPS: usize = 3;
+/// The number of cells assigned between the checks of whether the layout was cancelled
+const CANCELLATION_CHUNK: usize = 1 << 16;
+
 #[derive(Debug)]
 /// A context for a region
 pub struct RegionCtx<'a, F: PrimeField + TensorType + PartialOrd + std::hash::Hash> {
@@ -181,6 +288,12 @@ pub struct RegionCtx<'a, F: PrimeField + TensorType + PartialOrd + std::hash::Ha
     settings: RegionSettings,
     assigned_constants: ConstantsMap<F>,
     max_dynamic_input_len: usize,
+    op_profiles: Vec<OpProfile>,
+    current_node: Option<CurrentNode>,
+    node_rows: Vec<(String, String, usize)>,
+    constant_dedup_threshold: usize,
+    constant_cache: HashMap<u64, (Tensor<F>, ValTensor<F>)>,
+    deduped_constant_assignments: usize,
 }
 
 impl<'a, F: PrimeField + TensorType + PartialOrd + std::hash::Hash> RegionCtx<'a, F> {
@@ -266,6 +379,173 @@ impl<'a, F: PrimeField + TensorType + PartialOrd + std::hash::Hash> RegionCtx<'a
         &self.statistics
     }
 
+    /// whether the ops laid out in the region are profiled
+    pub fn is_profiling(&self) -> bool {
+        self.settings.profile
+    }
+
+    /// count looked up values when profiling
+    pub fn count_lookups(&mut self, n: usize) {
+        if self.settings.profile {
+            self.statistics.num_lookups += n;
+        }
+    }
+
+    /// count equality constraints when profiling
+    pub fn count_equalities(&mut self, n: usize) {
+        if self.settings.profile {
+            self.statistics.num_equalities += n;
+        }
+    }
+
+    /// Mark where the region is at before laying out an op, `None` if the region isn't profiling
+    pub fn profile_mark(&self) -> Option<ProfileMark> {
+        if !self.settings.profile {
+            return None;
+        }
+        Some(ProfileMark {
+            row: self.row,
+            linear_coord: self.linear_coord,
+            lookups: self.statistics.num_lookups,
+            equalities: self.statistics.num_equalities,
+        })
+    }
+
+    /// Record what the op of `node` laid out since `mark`
+    pub fn record_op_profile(&mut self, mark: ProfileMark, node: usize, op: String) {
+        self.op_profiles.push(OpProfile {
+            node,
+            op,
+            start_row: mark.row,
+            rows: self.row - mark.row,
+            assignments: self.linear_coord - mark.linear_coord,
+            lookups: self.statistics.num_lookups - mark.lookups,
+            equalities: self.statistics.num_equalities - mark.equalities,
+        });
+    }
+
+    /// The profiles of the ops recorded in the region, in layout order
+    pub fn op_profiles(&self) -> &[OpProfile] {
+        &self.op_profiles
+    }
+
+    /// The number of looked up values, counted when profiling
+    pub fn num_lookups(&self) -> usize {
+        self.statistics.num_lookups
+    }
+
+    /// The number of equality constraints, counted when profiling
+    pub fn num_equalities(&self) -> usize {
+        self.statistics.num_equalities
+    }
+
+    /// Set the node of the graph being laid out, such that the errors of the region can name it
+    pub fn set_current_node(&mut self, idx: usize, name: &str, op: String) {
+        if let Some(node) = self.current_node.take() {
+            self.node_rows
+                .push((node.label(), node.op, self.row - node.start_row));
+        }
+        self.current_node = Some(CurrentNode {
+            idx,
+            name: name.to_string(),
+            op,
+            start_row: self.row,
+        });
+    }
+
+    /// The nodes laid out so far that took the most rows, as `node (name) op: rows` and including the current node
+    pub fn largest_nodes(&self, n: usize) -> Vec<String> {
+        let current = self
+            .current_node
+            .as_ref()
+            .map(|node| (node.label(), node.op.clone(), self.row - node.start_row));
+        self.node_rows
+            .iter()
+            .cloned()
+            .chain(current)
+            .sorted_by(|a, b| b.2.cmp(&a.2))
+            .take(n)
+            .map(|(node, op, rows)| format!("{} {}: {} rows", node, op, rows))
+            .collect()
+    }
+
+    /// Constants with at least `threshold` values that are identical to a constant laid out before reuse its cells, 0
+    /// disables the deduplication
+    pub fn set_constant_dedup_threshold(&mut self, threshold: usize) {
+        self.constant_dedup_threshold = threshold;
+    }
+
+    fn constant_key(&self, values: &Tensor<F>) -> Option<u64> {
+        use std::hash::{Hash, Hasher};
+        let threshold = self.constant_dedup_threshold;
+        if threshold == 0 || values.len() < threshold {
+            return None;
+        }
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        values.dims().hash(&mut hasher);
+        values.iter().for_each(|v| v.hash(&mut hasher));
+        Some(hasher.finish())
+    }
+
+    /// The cells a constant identical to `values` was laid out to, the assignments of `values` are then counted as
+    /// saved
+    pub fn cached_constant(&mut self, values: &Tensor<F>) -> Option<ValTensor<F>> {
+        let key = self.constant_key(values)?;
+        match self.constant_cache.get(&key) {
+            // the values are compared as well, such that a hash collision doesn't alias two constants
+            Some((cached, assigned)) if cached == values => {
+                self.deduped_constant_assignments += values.len();
+                Some(assigned.clone())
+            }
+            _ => None,
+        }
+    }
+
+    /// Caches the cells a constant of `values` was laid out to, for the identical constants laid out after it
+    pub fn cache_constant(&mut self, values: &Tensor<F>, assigned: &ValTensor<F>) {
+        if let Some(key) = self.constant_key(values) {
+            self.constant_cache
+                .entry(key)
+                .or_insert_with(|| (values.clone(), assigned.clone()));
+        }
+    }
+
+    /// The number of assignments saved by reusing the cells of identical constants
+    pub fn deduped_constant_assignments(&self) -> usize {
+        self.deduped_constant_assignments
+    }
+
+    /// Errors if assigning `len` cells to `var` would run past the rows of its columns (2^logrows minus the blinding
+    /// rows, for each block of columns), instead of halo2 failing on the out of bounds assignment
+    fn check_rows(&self, var: &VarTensor, len: usize) -> Result<(), CircuitError> {
+        let capacity = var.num_blocks() * var.block_size();
+        if capacity == 0 || self.linear_coord + len <= capacity {
+            return Ok(());
+        }
+        let num_inner_cols = var.num_inner_cols();
+        let (node, op) = match &self.current_node {
+            Some(node) => (node.label(), node.op.clone()),
+            None => ("unknown".to_string(), "unknown".to_string()),
+        };
+        Err(CircuitError::RowOverflow {
+            node,
+            op,
+            row: (self.linear_coord + len).div_ceil(num_inner_cols),
+            max: capacity / num_inner_cols,
+            largest: self.largest_nodes(NUM_LARGEST_OPS).join(", "),
+        })
+    }
+
+    /// Fails with [CircuitError::Cancelled] if the proof or witness generation was cancelled, checked whenever the `len`
+    /// cells assigned from the current coordinate reach a new chunk of [CANCELLATION_CHUNK] cells
+    fn check_cancelled(&self, len: usize) -> Result<(), CircuitError> {
+        let chunk = |coord: usize| coord / CANCELLATION_CHUNK;
+        if chunk(self.linear_coord) == chunk(self.linear_coord + len) {
+            return Ok(());
+        }
+        crate::pfsys::progress::check_cancelled().map_err(|_| CircuitError::Cancelled)
+    }
+
     /// Create a new region context
     pub fn new(
         region: Region<'a, F>,
@@ -288,6 +568,12 @@ impl<'a, F: PrimeField + TensorType + PartialOrd + std::hash::Hash> RegionCtx<'a
             settings: RegionSettings::all_true(decomp_base, decomp_legs),
             assigned_constants: HashMap::new(),
             max_dynamic_input_len: 0,
+            op_profiles: vec![],
+            current_node: None,
+            node_rows: vec![],
+            constant_dedup_threshold: 0,
+            constant_cache: HashMap::new(),
+            deduped_constant_assignments: 0,
         }
     }
 
@@ -325,6 +611,12 @@ impl<'a, F: PrimeField + TensorType + PartialOrd + std::hash::Hash> RegionCtx<'a
             settings,
             assigned_constants: HashMap::new(),
             max_dynamic_input_len: 0,
+            op_profiles: vec![],
+            current_node: None,
+            node_rows: vec![],
+            constant_dedup_threshold: 0,
+            constant_cache: HashMap::new(),
+            deduped_constant_assignments: 0,
         }
     }
 
@@ -347,6 +639,12 @@ impl<'a, F: PrimeField + TensorType + PartialOrd + std::hash::Hash> RegionCtx<'a
             settings,
             assigned_constants: HashMap::new(),
             max_dynamic_input_len: 0,
+            op_profiles: vec![],
+            current_node: None,
+            node_rows: vec![],
+            constant_dedup_threshold: 0,
+            constant_cache: HashMap::new(),
+            deduped_constant_assignments: 0,
         }
     }
 
@@ -497,16 +795,112 @@ impl<'a, F: PrimeField + TensorType + PartialOrd + std::hash::Hash> RegionCtx<'a
         lookup: LookupOp,
         inputs: &[ValTensor<F>],
     ) -> Result<(), CircuitError> {
+        if lookup.tightens_table_range() {
+            self.update_tight_lookup_range(&lookup, inputs)?;
+        }
         self.statistics.used_lookups.insert(lookup);
         self.update_max_min_lookup_inputs(inputs)
     }
 
+    /// Record the range of the inputs the current node looks up in the table of `lookup`, sized to the inputs of its
+    /// op. When checking ranges an input outside of the range the table spans is an error naming the node.
+    fn update_tight_lookup_range(
+        &mut self,
+        lookup: &LookupOp,
+        inputs: &[ValTensor<F>],
+    ) -> Result<(), CircuitError> {
+        let mut range: Option<Range> = None;
+        for input in inputs {
+            for v in input.int_evals()? {
+                range = Some(range.map_or((v, v), |(lo, hi)| (lo.min(v), hi.max(v))));
+            }
+        }
+        let Some((lo, hi)) = range else {
+            return Ok(());
+        };
+
+        if let (true, Some((min, max))) = (
+            self.settings.check_range,
+            self.settings.tight_lookup_ranges.get(lookup),
+        ) {
+            if let Some(value) = [lo, hi].into_iter().find(|v| !(*min..=*max).contains(v)) {
+                return Err(CircuitError::TightLookupOOR {
+                    node: self
+                        .current_node
+                        .as_ref()
+                        .map_or("unknown".to_string(), CurrentNode::label),
+                    op: Op::<F>::as_string(lookup),
+                    value,
+                    min: *min,
+                    max: *max,
+                });
+            }
+        }
+
+        if let Some(node) = &self.current_node {
+            let entry = self
+                .statistics
+                .tight_lookup_ranges
+                .entry((node.idx, lookup.clone()))
+                .or_insert((node.name.clone(), (lo, hi)));
+            entry.1 = (entry.1 .0.min(lo), entry.1 .1.max(hi));
+        }
+        Ok(())
+    }
+
+    /// add used lookup fed the sums of an add
+    pub fn add_used_fused_add_lookup(
+        &mut self,
+        lookup: LookupOp,
+        sums: &[ValTensor<F>],
+    ) -> Result<(), CircuitError> {
+        self.statistics
+            .used_fused_add_lookups
+            .insert(lookup.clone());
+        self.add_used_lookup(lookup, sums)
+    }
+
     /// add used range check
     pub fn add_used_range_check(&mut self, range: Range) -> Result<(), CircuitError> {
         self.statistics.used_range_checks.insert(range);
         self.update_max_min_lookup_range(range)
     }
 
+    /// count values looked up in a range check
+    pub fn count_range_checks(&mut self, range: Range, n: usize) {
+        *self.statistics.range_check_counts.entry(range).or_default() += n;
+    }
+
+    /// The range checks of `ranges` decompose the values in bits instead of looking them up
+    pub fn set_bit_range_checks(&mut self, ranges: &[Range]) {
+        self.settings.bit_range_checks = ranges.iter().copied().collect();
+    }
+
+    /// The tables of the ops of `ranges` span their range rather than the lookup range, see
+    /// [LookupOp::tightens_table_range]
+    pub fn set_tight_lookup_ranges(&mut self, ranges: BTreeMap<LookupOp, Range>) {
+        self.settings.tight_lookup_ranges = ranges;
+    }
+
+    /// The range of the inputs each node looked up in the tables sized to the inputs of their ops
+    pub fn tight_lookup_ranges(&self) -> Vec<NodeLookupRange> {
+        self.statistics
+            .tight_lookup_ranges
+            .iter()
+            .map(|((node, op), (name, range))| NodeLookupRange {
+                node: *node,
+                name: name.clone(),
+                op: op.clone(),
+                range: *range,
+            })
+            .collect()
+    }
+
+    /// whether the range checks of a range decompose the values in bits
+    pub fn decomposes_range(&self, range: &Range) -> bool {
+        self.settings.bit_range_checks.contains(range)
+    }
+
     /// Get the offset
     pub fn row(&self) -> usize {
         self.row
@@ -543,15 +937,25 @@ impl<'a, F: PrimeField + TensorType + PartialOrd + std::hash::Hash> RegionCtx<'a
     }
 
     /// get used lookups
-    pub fn used_lookups(&self) -> HashSet<LookupOp> {
+    pub fn used_lookups(&self) -> BTreeSet<LookupOp> {
         self.statistics.used_lookups.clone()
     }
 
+    /// get used lookups fed the sums of adds
+    pub fn used_fused_add_lookups(&self) -> BTreeSet<LookupOp> {
+        self.statistics.used_fused_add_lookups.clone()
+    }
+
     /// get used range checks
-    pub fn used_range_checks(&self) -> HashSet<Range> {
+    pub fn used_range_checks(&self) -> BTreeSet<Range> {
         self.statistics.used_range_checks.clone()
     }
 
+    /// get the number of values looked up in each range check
+    pub fn range_check_counts(&self) -> BTreeMap<Range, usize> {
+        self.statistics.range_check_counts.clone()
+    }
+
     /// max lookup inputs
     pub fn max_lookup_inputs(&self) -> IntegerRep {
         self.statistics.max_lookup_inputs
@@ -573,7 +977,9 @@ impl<'a, F: PrimeField + TensorType + PartialOrd + std::hash::Hash> RegionCtx<'a
         var: &VarTensor,
         values: &ValTensor<F>,
     ) -> Result<ValTensor<F>, CircuitError> {
+        self.check_cancelled(values.len())?;
         if let Some(region) = &self.region {
+            self.check_rows(var, values.len())?;
             Ok(var.assign(
                 &mut region.borrow_mut(),
                 self.linear_coord,
@@ -639,7 +1045,9 @@ impl<'a, F: PrimeField + TensorType + PartialOrd + std::hash::Hash> RegionCtx<'a
         values: &ValTensor<F>,
         ommissions: &HashSet<usize>,
     ) -> Result<ValTensor<F>, CircuitError> {
+        self.check_cancelled(values.len())?;
         if let Some(region) = &self.region {
+            self.check_rows(var, values.len().saturating_sub(ommissions.len()))?;
             Ok(var.assign_with_omissions(
                 &mut region.borrow_mut(),
                 self.linear_coord,
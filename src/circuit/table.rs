@@ -149,15 +149,87 @@ pub fn num_cols_required(range_len: IntegerRep, col_size: usize) -> usize {
     (range_len / (col_size as IntegerRep)) as usize + 1
 }
 
-impl<F: PrimeField + TensorType + PartialOrd + std::hash::Hash> Table<F> {
-    fn name(&self) -> String {
-        format!(
-            "{}_{}_{}",
-            self.nonlinearity.as_path(),
-            self.range.0,
-            self.range.1
-        )
+/// The bits the values of a range are shifted by the lower bound of the range and decomposed to when range checked in
+/// bits, and whether they are also bounded from above (the range not being of a power of two values), in which case the
+/// upper bound minus the values is decomposed as well
+pub fn range_decomposition(range: Range) -> (usize, bool) {
+    let size = (range.1 - range.0 + 1) as u128;
+    let bits = ((u128::BITS - size.saturating_sub(1).leading_zeros()) as usize).max(1);
+    (bits, 1u128 << bits != size)
+}
+
+/// The rows the range checks of `count` values in `range` take at most when the values are decomposed in bits: the
+/// values, their shifts, bits and the equalities to their recompositions, and the dot products recomposing them
+pub fn bit_range_check_rows(range: Range, count: usize, num_inner_cols: usize) -> usize {
+    let (bits, bounded_above) = range_decomposition(range);
+    let sides = 1 + bounded_above as usize;
+    let coords = count * (1 + sides * (bits + 2));
+    // the dot products start on a row of their own
+    coords.div_ceil(num_inner_cols) + sides * (count * bits.div_ceil(num_inner_cols) + 1)
+}
+
+/// The rows the range checks of `count` values in `range` take when the values are looked up, including those of the
+/// table of the range
+pub fn lookup_range_check_rows(range: Range, count: usize, num_inner_cols: usize) -> usize {
+    count.div_ceil(num_inner_cols) + (range.1 - range.0 + 1) as usize
+}
+
+/// The name the table of `nonlinearity` over `range` is cached under
+fn table_name(nonlinearity: &LookupOp, range: Range) -> String {
+    format!("{}_{}_{}", nonlinearity.as_path(), range.0, range.1)
+}
+
+/// The inputs of the table of `nonlinearity` over `range` and the outputs the table assigns them, loaded from the
+/// lookup table cache if it holds them and generated (and cached) otherwise
+pub fn table_evals<F: PrimeField + TensorType + PartialOrd + std::hash::Hash>(
+    nonlinearity: &LookupOp,
+    range: Range,
+) -> Result<(Tensor<F>, Tensor<F>), CircuitError> {
+    let (smallest, largest) = range;
+
+    let gen_table = || -> Result<(Tensor<F>, Tensor<F>), crate::tensor::TensorError> {
+        let inputs = Tensor::from(smallest..=largest)
+            .par_enum_map(|_, x| Ok::<_, crate::tensor::TensorError>(integer_rep_to_felt(x)))?;
+        let evals = nonlinearity.f(&[inputs.clone()])?;
+        Ok((inputs, evals.output))
+    };
+
+    if !LOOKUP_CACHE.is_empty() {
+        let cache = std::path::Path::new(&*LOOKUP_CACHE);
+        let cache_path = cache.join(table_name(nonlinearity, range));
+        let input_path = cache_path.join("inputs");
+        let output_path = cache_path.join("outputs");
+        if cache_path.exists() {
+            log::info!("Loading lookup table from cache: {:?}", cache_path);
+            Ok((Tensor::load(&input_path)?, Tensor::load(&output_path)?))
+        } else {
+            log::info!(
+                "Generating lookup table and saving to cache: {:?}",
+                cache_path
+            );
+
+            // mkdir -p cache_path
+            std::fs::create_dir_all(&cache_path).map_err(|e| {
+                CircuitError::TensorError(crate::tensor::TensorError::FileSaveError(e.to_string()))
+            })?;
+
+            let (inputs, evals) = gen_table()?;
+            inputs.save(&input_path)?;
+            evals.save(&output_path)?;
+
+            Ok((inputs, evals))
+        }
+    } else {
+        log::info!(
+            "Generating lookup table {} without cache",
+            nonlinearity.as_path()
+        );
+
+        Ok(gen_table()?)
     }
+}
+
+impl<F: PrimeField + TensorType + PartialOrd + std::hash::Hash> Table<F> {
     /// Configures the table.
     pub fn configure(
         cs: &mut ConstraintSystem<F>,
@@ -220,54 +292,15 @@ impl<F: PrimeField + TensorType + PartialOrd + std::hash::Hash> Table<F> {
         if self.is_assigned {
             return Err(CircuitError::TableAlreadyAssigned);
         }
+        let _span = tracing::info_span!(
+            "table",
+            op = %self.nonlinearity.as_path(),
+            range = self.range.1 - self.range.0 + 1,
+            col_size = self.col_size
+        )
+        .entered();
 
-        let smallest = self.range.0;
-        let largest = self.range.1;
-
-        let gen_table = || -> Result<(Tensor<F>, Tensor<F>), crate::tensor::TensorError> {
-            let inputs = Tensor::from(smallest..=largest)
-                .par_enum_map(|_, x| Ok::<_, crate::tensor::TensorError>(integer_rep_to_felt(x)))?;
-            let evals = self.nonlinearity.f(&[inputs.clone()])?;
-            Ok((inputs, evals.output))
-        };
-
-        let (inputs, evals) = if !LOOKUP_CACHE.is_empty() {
-            let cache = std::path::Path::new(&*LOOKUP_CACHE);
-            let cache_path = cache.join(self.name());
-            let input_path = cache_path.join("inputs");
-            let output_path = cache_path.join("outputs");
-            if cache_path.exists() {
-                log::info!("Loading lookup table from cache: {:?}", cache_path);
-                let (input_cache, output_cache) =
-                    (Tensor::load(&input_path)?, Tensor::load(&output_path)?);
-                (input_cache, output_cache)
-            } else {
-                log::info!(
-                    "Generating lookup table and saving to cache: {:?}",
-                    cache_path
-                );
-
-                // mkdir -p cache_path
-                std::fs::create_dir_all(&cache_path).map_err(|e| {
-                    CircuitError::TensorError(crate::tensor::TensorError::FileSaveError(
-                        e.to_string(),
-                    ))
-                })?;
-
-                let (inputs, evals) = gen_table()?;
-                inputs.save(&input_path)?;
-                evals.save(&output_path)?;
-
-                (inputs, evals)
-            }
-        } else {
-            log::info!(
-                "Generating lookup table {} without cache",
-                self.nonlinearity.as_path()
-            );
-
-            gen_table()?
-        };
+        let (inputs, evals) = table_evals(&self.nonlinearity, self.range)?;
 
         let chunked_inputs = inputs.chunks(self.col_size);
 
@@ -416,6 +449,12 @@ impl<F: PrimeField + TensorType + PartialOrd + std::hash::Hash> RangeCheck<F> {
         if self.is_assigned {
             return Err(CircuitError::TableAlreadyAssigned);
         }
+        let _span = tracing::info_span!(
+            "range_check_table",
+            range = self.range.1 - self.range.0 + 1,
+            col_size = self.col_size
+        )
+        .entered();
 
         let smallest = self.range.0;
         let largest = self.range.1;
@@ -436,14 +475,14 @@ impl<F: PrimeField + TensorType + PartialOrd + std::hash::Hash> RangeCheck<F> {
                 // mkdir -p cache_path
                 std::fs::create_dir_all(&cache_path)?;
 
-                let inputs = Tensor::from(smallest..=largest).map(|x| integer_rep_to_felt(x));
+                let inputs = Tensor::from(smallest..=largest).par_map(|x| integer_rep_to_felt(x));
                 inputs.save(&input_path)?;
                 inputs
             }
         } else {
             log::info!("Generating range check {} without cache", self.as_path());
 
-            Tensor::from(smallest..=largest).map(|x| integer_rep_to_felt(x))
+            Tensor::from(smallest..=largest).par_map(|x| integer_rep_to_felt(x))
         };
 
         let chunked_inputs = inputs.chunks(self.col_size);
@@ -487,6 +487,378 @@ mod matmul_col_ultra_overflow {
     }
 }
 
+#[cfg(test)]
+mod sparse_matmul {
+
+    use super::*;
+    use crate::circuit::region::RegionSettings;
+    use crate::fieldutils::IntegerRep;
+    use crate::graph::Visibility;
+    use crate::tensor::SparseTensor;
+
+    const K: usize = 9;
+    const IN: usize = 20;
+    const OUT: usize = 10;
+
+    #[derive(Clone)]
+    struct SparseMatmulCircuit<F: PrimeField + TensorType + PartialOrd> {
+        inputs: [ValTensor<F>; 2],
+        _marker: PhantomData<F>,
+    }
+
+    impl Circuit<F> for SparseMatmulCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let a = VarTensor::new_advice(cs, K, 1, IN * OUT);
+            let b = VarTensor::new_advice(cs, K, 1, IN * OUT);
+            let output = VarTensor::new_advice(cs, K, 1, IN * OUT);
+            Self::Config::configure(cs, &[a, b], &output, CheckMode::SAFE)
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            layouter
+                .assign_region(
+                    || "",
+                    |region| {
+                        let mut region = RegionCtx::new(region, 0, 1, 128, 2);
+                        config
+                            .layout(
+                                &mut region,
+                                &self.inputs.clone(),
+                                Box::new(PolyOp::Einsum {
+                                    equation: "ij,jk->ik".to_string(),
+                                }),
+                            )
+                            .map_err(|_| Error::Synthesis)
+                    },
+                )
+                .unwrap();
+
+            Ok(())
+        }
+    }
+
+    fn input() -> ValTensor<F> {
+        let mut x: ValTensor<F> =
+            Tensor::from((0..IN).map(|i| Value::known(F::from((i + 1) as u64)))).into();
+        x.reshape(&[1, IN]).unwrap();
+        x
+    }
+
+    /// 90% sparse private weights, with two nonzeros in each column
+    fn weights() -> Tensor<F> {
+        let mut w = Tensor::from((0..IN * OUT).map(|i| {
+            let (row, col) = (i / OUT, i % OUT);
+            if (row + col) % OUT == 0 {
+                F::from((row + 1) as u64)
+            } else {
+                F::ZERO
+            }
+        }));
+        w.reshape(&[IN, OUT]).unwrap();
+        w.set_visibility(&Visibility::Private);
+        w
+    }
+
+    fn layout_rows(weights: ValTensor<F>) -> (usize, Tensor<IntegerRep>) {
+        let config = BaseConfig::dummy(12, 2);
+        let mut region = RegionCtx::new_dummy(0, 2, RegionSettings::all_true(128, 2));
+        let output =
+            layouts::einsum(&config, &mut region, &[input(), weights], "ij,jk->ik").unwrap();
+        (region.row(), output.int_evals().unwrap())
+    }
+
+    #[test]
+    fn sparse_matmul_rows() {
+        let sparse = SparseTensor::from_dense(&weights()).unwrap();
+        assert_eq!(sparse.nnz(), IN * OUT / 10);
+
+        let (dense_rows, dense_output) = layout_rows(weights().try_into().unwrap());
+        let (sparse_rows, sparse_output) = layout_rows(sparse.to_val_tensor().unwrap());
+
+        assert_eq!(sparse_output, dense_output);
+        // each output is a dot product laid out over the two inner columns, of the whole input when dense and of the
+        // nonzero weights of its column only when sparse
+        assert_eq!(dense_rows, OUT * IN.div_ceil(2));
+        assert_eq!(sparse_rows, OUT * (sparse.nnz() / OUT).div_ceil(2));
+        assert_eq!(sparse_rows, 10);
+    }
+
+    #[test]
+    fn sparse_matmulcircuit() {
+        let sparse = SparseTensor::from_dense(&weights()).unwrap();
+        let circuit = SparseMatmulCircuit::<F> {
+            inputs: [input(), sparse.to_val_tensor().unwrap()],
+            _marker: PhantomData,
+        };
+
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied();
+    }
+}
+
+#[cfg(test)]
+#[cfg(all(
+    feature = "ezkl",
+    not(all(target_arch = "wasm32", target_os = "unknown"))
+))]
+mod matmul_overflow {
+
+    use super::*;
+    use crate::circuit::region::RegionSettings;
+
+    #[test]
+    fn matmul_overflow_witness() {
+        let mut config = BaseConfig::dummy(12, 2);
+        let mut region = RegionCtx::new_dummy(0, 2, RegionSettings::all_true(128, 2));
+
+        // each product is 2^140, past the range of the integer representation
+        let mut a: ValTensor<F> =
+            Tensor::from((0..2).map(|_| Value::known(F::from_u128(1 << 70)))).into();
+        a.reshape(&[1, 2]).unwrap();
+        let mut b = a.clone();
+        b.reshape(&[2, 1]).unwrap();
+
+        let output = config
+            .layout(
+                &mut region,
+                &[a, b],
+                Box::new(PolyOp::Einsum {
+                    equation: "ij,jk->ik".to_string(),
+                }),
+            )
+            .unwrap()
+            .unwrap();
+
+        // the lookup on the matmul output fails while the witness is generated, naming the op
+        let res = config.layout(
+            &mut region,
+            &[output],
+            Box::new(LookupOp::Sigmoid { scale: 1.0.into() }),
+        );
+        match res {
+            Err(CircuitError::IntegerRepOverflow(felt, op)) => {
+                assert_eq!(
+                    felt,
+                    format!("{:?}", F::from_u128(1 << 70).square().double())
+                );
+                assert!(op.starts_with("SIGMOID"), "unexpected op {}", op);
+            }
+            res => panic!("expected an overflow error, got {:?}", res),
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(all(
+    feature = "ezkl",
+    not(all(target_arch = "wasm32", target_os = "unknown"))
+))]
+mod widened_accumulation {
+
+    use super::*;
+    use crate::circuit::region::RegionSettings;
+    use crate::fieldutils::IntegerRep;
+    use ops::hybrid::HybridOp;
+
+    const LEN: usize = 16384;
+    // partial sums of 16 products of about 2^48 stay within 2^53
+    const CHUNK_SIZE: usize = 16;
+    // 1024 chunks
+    const SHIFT: u32 = 10;
+
+    /// A dot product of 2^14 products of about 2^48, whose sum lies past the integers f64 represents exactly, and its
+    /// exact value
+    fn operands() -> (Vec<ValTensor<F>>, IntegerRep) {
+        let a: Vec<IntegerRep> = vec![(1 << 24) + 1; LEN];
+        let mut b = a.clone();
+        // such that the sum is 300 past a multiple of the spacing of f64 around it (1024)
+        b[LEN - 1] += 300;
+        let exact = a.iter().zip(&b).map(|(a, b)| a * b).sum::<IntegerRep>();
+        assert_eq!(exact % 1024, 300);
+
+        let tensor = |values: Vec<IntegerRep>| {
+            ValTensor::from_integer_rep_tensor(Tensor::new(Some(&values), &[LEN]).unwrap())
+        };
+        (vec![tensor(a), tensor(b)], exact)
+    }
+
+    /// Divides down by 16 with range checks, as a rebase of the output of the dot product does
+    fn rescale(
+        config: &mut BaseConfig<F>,
+        region: &mut RegionCtx<F>,
+        value: ValTensor<F>,
+    ) -> Result<Option<ValTensor<F>>, CircuitError> {
+        config.layout(
+            region,
+            &[value],
+            Box::new(HybridOp::Div {
+                denom: 16.0.into(),
+                use_range_check_for_int: true,
+                rounding: DivRounding::Round,
+            }),
+        )
+    }
+
+    #[test]
+    fn long_dot_product_only_rescales_when_widened() {
+        let mut config = BaseConfig::dummy(17, 2);
+        let (inputs, exact) = operands();
+
+        // accumulated at the scale of its inputs, the quotient of the rescale is computed off by 300 / 16
+        let mut region = RegionCtx::new_dummy(0, 2, RegionSettings::all_true(128, 2));
+        let output = config
+            .layout(
+                &mut region,
+                &inputs,
+                Box::new(PolyOp::Einsum {
+                    equation: "i,i->".to_string(),
+                }),
+            )
+            .unwrap()
+            .unwrap();
+        assert_eq!(output.int_evals().unwrap()[0], exact);
+        assert!(matches!(
+            rescale(&mut config, &mut region, output),
+            Err(CircuitError::TableOOR(..))
+        ));
+
+        let mut region = RegionCtx::new_dummy(0, 2, RegionSettings::all_true(128, 2));
+        let widened = HybridOp::WidenedEinsum {
+            equation: "i,i->".to_string(),
+            chunk_size: CHUNK_SIZE,
+            shift: SHIFT,
+            use_range_check_for_int: true,
+        };
+        assert_eq!(
+            Op::<F>::out_scale(&widened, vec![7, 7]).unwrap(),
+            14 - SHIFT as i32
+        );
+        let output = config
+            .layout(&mut region, &inputs, Box::new(widened))
+            .unwrap()
+            .unwrap();
+
+        // each of the 1024 partial sums is off by at most half a unit of the output
+        let value = output.int_evals().unwrap()[0];
+        let num_chunks = (LEN / CHUNK_SIZE) as IntegerRep;
+        assert!(
+            (value * (1 << SHIFT) - exact).abs() <= num_chunks * (1 << (SHIFT - 1)),
+            "{} is too far from {}",
+            value,
+            exact >> SHIFT
+        );
+        assert!(value.abs() < crate::circuit::hybrid::MAX_SAFE_ACCUMULATOR);
+        assert!(rescale(&mut config, &mut region, output).is_ok());
+    }
+}
+
+#[cfg(all(
+    test,
+    feature = "ezkl",
+    not(all(target_arch = "wasm32", target_os = "unknown"))
+))]
+mod op_profile {
+
+    use super::*;
+    use crate::circuit::region::RegionSettings;
+
+    const IN: usize = 4;
+    const OUT: usize = 3;
+
+    fn unknowns(dims: &[usize]) -> ValTensor<F> {
+        let len = dims.iter().product();
+        let mut t: ValTensor<F> = Tensor::from((0..len).map(|_| Value::<F>::unknown())).into();
+        t.reshape(dims).unwrap();
+        t
+    }
+
+    fn matmul() -> Box<dyn Op<F>> {
+        Box::new(PolyOp::Einsum {
+            equation: "ij,jk->ik".to_string(),
+        })
+    }
+
+    fn sigmoid() -> Box<dyn Op<F>> {
+        Box::new(LookupOp::Sigmoid { scale: 1.0.into() })
+    }
+
+    #[test]
+    fn two_layer_profile() {
+        let mut config = BaseConfig::dummy(12, 2);
+
+        // the matmul laid out on its own, without profiling
+        let mut region = RegionCtx::new_dummy(0, 2, RegionSettings::all_false(128, 2));
+        config
+            .layout(
+                &mut region,
+                &[unknowns(&[1, IN]), unknowns(&[IN, OUT])],
+                matmul(),
+            )
+            .unwrap();
+        let matmul_rows = region.row();
+        assert!(region.op_profiles().is_empty());
+
+        // a matmul followed by a sigmoid, profiling each layer
+        let settings = RegionSettings::all_false(128, 2).with_profile();
+        let mut region = RegionCtx::new_dummy(0, 2, settings);
+        let mark = region.profile_mark().unwrap();
+        let hidden = config
+            .layout(
+                &mut region,
+                &[unknowns(&[1, IN]), unknowns(&[IN, OUT])],
+                matmul(),
+            )
+            .unwrap()
+            .unwrap();
+        region.record_op_profile(mark, 0, matmul().as_string());
+        let mark = region.profile_mark().unwrap();
+        config.layout(&mut region, &[hidden], sigmoid()).unwrap();
+        region.record_op_profile(mark, 1, sigmoid().as_string());
+
+        let profiles = region.op_profiles();
+        assert_eq!(profiles.len(), 2);
+        let (first, second) = (&profiles[0], &profiles[1]);
+        assert_eq!((first.node, second.node), (0, 1));
+        assert!(
+            second.op.starts_with("SIGMOID"),
+            "unexpected op {}",
+            second.op
+        );
+
+        // profiling doesn't change the layout of the matmul
+        assert_eq!(first.rows, matmul_rows);
+        // the lookups are all in the second layer, one per output of the first
+        assert_eq!((first.lookups, second.lookups), (0, OUT));
+        assert_eq!(region.num_lookups(), OUT);
+        // the layers take up all of the rows of the region
+        assert_eq!(first.rows + second.rows, region.row());
+        let shares = profiles
+            .iter()
+            .map(|p| p.rows as f64 / region.row() as f64)
+            .sum::<f64>();
+        assert!((shares - 1.0).abs() < 1e-9);
+        // the lookup takes a coordinate per value, the matmul accumulates over its inputs
+        assert_eq!(second.assignments, OUT);
+        assert!(first.rows > second.rows);
+        assert_eq!(
+            first.assignments + second.assignments,
+            region.linear_coord()
+        );
+    }
+}
+
 #[cfg(test)]
 mod dot {
     use ops::poly::PolyOp;
@@ -562,6 +934,103 @@ mod dot {
     }
 }
 
+#[cfg(test)]
+mod row_overflow {
+    use ops::poly::PolyOp;
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+
+    const K: usize = 4;
+    const SMALL: usize = 2;
+    const LARGE: usize = 64;
+
+    #[derive(Clone)]
+    struct MyCircuit<F: PrimeField + TensorType + PartialOrd> {
+        small: [ValTensor<F>; 2],
+        large: [ValTensor<F>; 2],
+        error: Arc<Mutex<Option<CircuitError>>>,
+    }
+
+    impl Circuit<F> for MyCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let a = VarTensor::new_advice(cs, K, 1, SMALL);
+            let b = VarTensor::new_advice(cs, K, 1, SMALL);
+            let output = VarTensor::new_advice(cs, K, 1, SMALL);
+
+            Self::Config::configure(cs, &[a, b], &output, CheckMode::SAFE)
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            layouter.assign_region(
+                || "",
+                |region| {
+                    let mut region = RegionCtx::new(region, 0, 1, 128, 2);
+                    let dot = || {
+                        Box::new(PolyOp::Einsum {
+                            equation: "i,i->".to_string(),
+                        })
+                    };
+                    region.set_current_node(0, "small_dot", "EINSUM".to_string());
+                    config
+                        .layout(&mut region, &self.small, dot())
+                        .map_err(|_| Error::Synthesis)?;
+                    region.set_current_node(1, "large_dot", "EINSUM".to_string());
+                    config
+                        .layout(&mut region, &self.large, dot())
+                        .map_err(|e| {
+                            *self.error.lock().unwrap() = Some(e);
+                            Error::Synthesis
+                        })?;
+                    Ok(())
+                },
+            )
+        }
+    }
+
+    #[test]
+    fn overflow_names_node() {
+        let ones =
+            |len: usize| ValTensor::from(Tensor::from((0..len).map(|_| Value::known(F::from(1)))));
+
+        let circuit = MyCircuit::<F> {
+            small: [ones(SMALL), ones(SMALL)],
+            large: [ones(LARGE), ones(LARGE)],
+            error: Arc::new(Mutex::new(None)),
+        };
+
+        assert!(MockProver::run(K as u32, &circuit, vec![]).is_err());
+        let error = circuit.error.lock().unwrap().take();
+        match error {
+            Some(CircuitError::RowOverflow {
+                node,
+                op,
+                row,
+                max,
+                largest,
+            }) => {
+                assert_eq!(node, "1 (large_dot)");
+                assert_eq!(op, "EINSUM");
+                assert!(row > max);
+                assert!(largest.contains("0 (small_dot)"));
+            }
+            e => panic!("expected a row overflow, got {:?}", e),
+        }
+    }
+}
+
 #[cfg(test)]
 mod dot_col_overflow_triple_col {
     use super::*;
@@ -779,6 +1248,41 @@ mod sum {
         let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
         prover.assert_satisfied();
     }
+
+    #[test]
+    fn long_reductions_take_a_row_per_chunk() {
+        use crate::circuit::region::RegionSettings;
+
+        // 2^20 values, a reduction over them summing `num_inner_cols` of them per row into a running sum
+        const LONG_LEN: usize = 1 << 20;
+        let values = (0..LONG_LEN).map(|i| (i % 7) as crate::fieldutils::IntegerRep);
+        let expected = values.clone().sum::<crate::fieldutils::IntegerRep>();
+        let input = ValTensor::from_integer_rep_tensor(
+            Tensor::new(Some(&values.collect::<Vec<_>>()), &[LONG_LEN]).unwrap(),
+        );
+        let ones = ValTensor::from_integer_rep_tensor(
+            Tensor::new(Some(&vec![1; LONG_LEN]), &[LONG_LEN]).unwrap(),
+        );
+
+        for num_inner_cols in [1, 4, 8] {
+            let config = BaseConfig::dummy(21, num_inner_cols);
+            let new_region =
+                || RegionCtx::new_dummy(0, num_inner_cols, RegionSettings::all_true(128, 2));
+
+            let mut region = new_region();
+            let output = layouts::sum(&config, &mut region, &[input.clone()]).unwrap();
+            assert_eq!(output.int_evals().unwrap()[0], expected);
+            // the partial sums of the rows are accumulated in the output column of the same rows, a tree over them
+            // would take rows of its own
+            assert_eq!(region.row(), LONG_LEN / num_inner_cols);
+
+            let mut region = new_region();
+            let output =
+                layouts::dot(&config, &mut region, &[input.clone(), ones.clone()]).unwrap();
+            assert_eq!(output.int_evals().unwrap()[0], expected);
+            assert_eq!(region.row(), LONG_LEN / num_inner_cols);
+        }
+    }
 }
 
 #[cfg(test)]
@@ -1153,6 +1657,89 @@ mod conv {
     }
 }
 
+#[cfg(test)]
+mod padding {
+
+    use super::*;
+    use crate::circuit::layouts;
+    use crate::circuit::region::RegionSettings;
+    use crate::fieldutils::IntegerRep;
+
+    const SIDE: usize = 6;
+    const KERNEL: usize = 3;
+
+    /// The image padded with `padding` witnessed zeros, such that they are laid out like any other value
+    fn image(padding: usize) -> ValTensor<F> {
+        let values = (0..SIDE * SIDE)
+            .map(|i| i as IntegerRep + 1)
+            .collect::<Vec<_>>();
+        let image = Tensor::<IntegerRep>::new(Some(&values), &[1, 1, SIDE, SIDE]).unwrap();
+        let image = crate::tensor::ops::pad(&image, vec![(padding, padding); 2], 2).unwrap();
+        ValTensor::from_integer_rep_tensor(image)
+    }
+
+    /// Lays out `f` in a fresh region, returns its output and the number of cells it assigned
+    fn layout(
+        f: impl FnOnce(&BaseConfig<F>, &mut RegionCtx<F>) -> ValTensor<F>,
+    ) -> (Tensor<IntegerRep>, usize) {
+        let config = BaseConfig::dummy(12, 1);
+        let mut region = RegionCtx::new_dummy(0, 1, RegionSettings::all_true(128, 2));
+        let output = f(&config, &mut region);
+        (output.int_evals().unwrap(), region.linear_coord())
+    }
+
+    #[test]
+    fn padded_conv() {
+        let kernel =
+            Tensor::<IntegerRep>::new(Some(&[1; KERNEL * KERNEL]), &[1, 1, KERNEL, KERNEL])
+                .unwrap();
+        let kernel = ValTensor::from_integer_rep_tensor(kernel);
+        let conv = |image: ValTensor<F>, padding: usize| {
+            let padding = vec![(padding, padding); 2];
+            layout(|config, region| {
+                layouts::conv(
+                    config,
+                    region,
+                    &[image, kernel.clone()],
+                    &padding,
+                    &[1; 2],
+                    1,
+                )
+                .unwrap()
+            })
+        };
+
+        let (padded, padded_rows) = conv(image(0), 1);
+        let (explicit, explicit_rows) = conv(image(1), 0);
+        assert_eq!(padded, explicit);
+
+        // the windows at the border of each dim overlap the padding by one position
+        let unpadded_per_dim = SIDE * KERNEL - 2;
+        // the image is assigned, then each window multiplies the kernel with its unpadded positions only
+        assert_eq!(padded_rows, SIDE * SIDE + unpadded_per_dim.pow(2));
+        assert_eq!(explicit_rows, (SIDE + 2).pow(2) + (SIDE * KERNEL).pow(2));
+    }
+
+    #[test]
+    fn padded_max_pool() {
+        // with as much padding as the pool, the windows at the corners are all padding
+        for padding in [1, KERNEL] {
+            let max_pool = |image: ValTensor<F>, padding: usize| {
+                let padding = vec![(padding, padding); 2];
+                layout(|config, region| {
+                    layouts::max_pool(config, region, &[image], &padding, &[1; 2], &[KERNEL; 2])
+                        .unwrap()
+                })
+            };
+
+            let (padded, padded_rows) = max_pool(image(0), padding);
+            let (explicit, explicit_rows) = max_pool(image(padding), 0);
+            assert_eq!(padded, explicit);
+            assert!(padded_rows < explicit_rows);
+        }
+    }
+}
+
 #[cfg(test)]
 #[cfg(all(
     feature = "ezkl",
@@ -1743,17 +2330,79 @@ mod dynamic_lookup {
 }
 
 #[cfg(test)]
-mod shuffle {
+mod embedding_bag {
     use super::*;
+    use crate::circuit::ops::hybrid::{EmbeddingBagMode, HybridOp};
+    use crate::circuit::region::RegionSettings;
+    use crate::fieldutils::IntegerRep;
 
-    const K: usize = 6;
-    const LEN: usize = 4;
-    const NUM_LOOP: usize = 5;
+    const K: usize = 8;
+    const LEN: usize = 64;
+    const NUM_EMBEDDINGS: usize = 6;
+    const EMBEDDING_DIM: usize = 3;
+
+    fn table() -> Tensor<IntegerRep> {
+        Tensor::new(
+            Some(
+                &(0..(NUM_EMBEDDINGS * EMBEDDING_DIM) as IntegerRep)
+                    .map(|x| 3 * x - 20)
+                    .collect::<Vec<_>>(),
+            ),
+            &[NUM_EMBEDDINGS, EMBEDDING_DIM],
+        )
+        .unwrap()
+    }
+
+    fn indices() -> Tensor<IntegerRep> {
+        Tensor::new(Some(&[0, 2, 2, 5, 1, 0]), &[2, 3]).unwrap()
+    }
+
+    /// The bags of torch.nn.EmbeddingBag, the sum (or rounded mean) of the rows of each bag
+    fn reference(mode: EmbeddingBagMode) -> Vec<IntegerRep> {
+        let (table, indices) = (table(), indices());
+        indices
+            .chunks(3)
+            .flat_map(|bag| {
+                let table = &table;
+                (0..EMBEDDING_DIM).map(move |d| {
+                    let sum = bag
+                        .iter()
+                        .map(|i| table[*i as usize * EMBEDDING_DIM + d])
+                        .sum::<IntegerRep>();
+                    match mode {
+                        EmbeddingBagMode::Sum => sum,
+                        EmbeddingBagMode::Mean => {
+                            (sum as f64 / bag.len() as f64).round() as IntegerRep
+                        }
+                    }
+                })
+            })
+            .collect()
+    }
+
+    fn dummy_layout(
+        indices: Tensor<IntegerRep>,
+        mode: EmbeddingBagMode,
+    ) -> Result<Tensor<IntegerRep>, CircuitError> {
+        let config = BaseConfig::dummy(12, 2);
+        let mut region = RegionCtx::new_dummy(0, 2, RegionSettings::all_true(128, 2));
+        let op = HybridOp::EmbeddingBag { mode, bag_size: 3 };
+        let output = Op::<F>::layout(
+            &op,
+            &mut config.clone(),
+            &mut region,
+            &[
+                ValTensor::from_integer_rep_tensor(table()),
+                ValTensor::from_integer_rep_tensor(indices),
+            ],
+        )?
+        .unwrap();
+        Ok(output.int_evals().unwrap())
+    }
 
     #[derive(Clone)]
     struct MyCircuit<F: PrimeField + TensorType + PartialOrd> {
-        inputs: [[ValTensor<F>; 1]; NUM_LOOP],
-        references: [[ValTensor<F>; 1]; NUM_LOOP],
+        inputs: [ValTensor<F>; 2],
         _marker: PhantomData<F>,
     }
 
@@ -1767,26 +2416,25 @@ mod shuffle {
         }
 
         fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
-            let a = VarTensor::new_advice(cs, K, 2, LEN);
-            let b = VarTensor::new_advice(cs, K, 2, LEN);
-            let c: VarTensor = VarTensor::new_advice(cs, K, 2, LEN);
-
+            let a = VarTensor::new_advice(cs, K, 1, LEN);
+            let b = VarTensor::new_advice(cs, K, 1, LEN);
+            let output = VarTensor::new_advice(cs, K, 1, LEN);
             let d = VarTensor::new_advice(cs, K, 1, LEN);
             let e = VarTensor::new_advice(cs, K, 1, LEN);
-
-            let _constant = VarTensor::constant_cols(cs, K, LEN * NUM_LOOP, false);
+            let f = VarTensor::new_advice(cs, K, 1, LEN);
+            let _constant = VarTensor::constant_cols(cs, K, LEN, false);
 
             let mut config =
-                Self::Config::configure(cs, &[a.clone(), b.clone()], &c, CheckMode::SAFE);
+                Self::Config::configure(cs, &[a.clone(), b.clone()], &output, CheckMode::SAFE);
             config
-                .configure_shuffles(cs, &[a.clone(), b.clone()], &[d.clone(), e.clone()])
+                .configure_dynamic_lookup(cs, &[a, b, output], &[d, e, f])
                 .unwrap();
             config
         }
 
         fn synthesize(
             &self,
-            config: Self::Config,
+            mut config: Self::Config,
             mut layouter: impl Layouter<F>,
         ) -> Result<(), Error> {
             layouter
@@ -1794,20 +2442,194 @@ mod shuffle {
                     || "",
                     |region| {
                         let mut region = RegionCtx::new(region, 0, 1, 128, 2);
-                        for i in 0..NUM_LOOP {
-                            layouts::shuffles(
-                                &config,
-                                &mut region,
-                                &self.inputs[i],
-                                &self.references[i],
-                            )
+                        let op = HybridOp::EmbeddingBag {
+                            mode: EmbeddingBagMode::Sum,
+                            bag_size: 3,
+                        };
+                        op.layout(&mut config, &mut region, &self.inputs)
                             .map_err(|_| Error::Synthesis)?;
-                        }
-                        assert_eq!(
-                            region.shuffle_col_coord(),
-                            NUM_LOOP * self.references[0][0].len()
-                        );
-                        assert_eq!(region.shuffle_index(), NUM_LOOP);
+                        Ok(())
+                    },
+                )
+                .unwrap();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn bags_match_the_reference() {
+        for mode in [EmbeddingBagMode::Sum, EmbeddingBagMode::Mean] {
+            let output = dummy_layout(indices(), mode).unwrap();
+            // the bag axis is kept, as by reductions
+            assert_eq!(output.dims(), &[2, 1, EMBEDDING_DIM]);
+            assert_eq!(output.to_vec(), reference(mode));
+        }
+
+        let circuit = MyCircuit::<F> {
+            inputs: [
+                ValTensor::from_integer_rep_tensor(table()),
+                ValTensor::from_integer_rep_tensor(indices()),
+            ],
+            _marker: PhantomData,
+        };
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn dynamic_offsets() {
+        // flat indices don't split into bags of a static size
+        let flat = Tensor::new(Some(&[0, 2, 2, 5]), &[4]).unwrap();
+        assert!(matches!(
+            dummy_layout(flat, EmbeddingBagMode::Sum),
+            Err(CircuitError::DynamicEmbeddingBagOffsets(_))
+        ));
+    }
+}
+
+#[cfg(test)]
+#[cfg(all(
+    feature = "ezkl",
+    not(all(target_arch = "wasm32", target_os = "unknown"))
+))]
+mod norm_reduce {
+    use super::*;
+    use crate::circuit::ops::hybrid::{HybridOp, NormOrder};
+    use crate::circuit::region::RegionSettings;
+    use crate::fieldutils::IntegerRep;
+
+    // inputs at a scale of 2^4
+    const SCALE: f32 = 16.0;
+    const ROWS: usize = 4;
+    const COLS: usize = 8;
+
+    fn input() -> Tensor<IntegerRep> {
+        Tensor::new(
+            Some(
+                &(0..(ROWS * COLS) as IntegerRep)
+                    .map(|x| (x * 37) % 29 - 14)
+                    .collect::<Vec<_>>(),
+            ),
+            &[ROWS, COLS],
+        )
+        .unwrap()
+    }
+
+    fn dummy_layout(p: NormOrder, keepdims: bool) -> Tensor<IntegerRep> {
+        let config = BaseConfig::dummy(12, 2);
+        let mut region = RegionCtx::new_dummy(0, 2, RegionSettings::all_true(128, 2));
+        let op = HybridOp::NormReduce {
+            p,
+            axes: vec![1],
+            keepdims,
+            scale: SCALE.into(),
+        };
+        Op::<F>::layout(
+            &op,
+            &mut config.clone(),
+            &mut region,
+            &[ValTensor::from_integer_rep_tensor(input())],
+        )
+        .unwrap()
+        .unwrap()
+        .int_evals()
+        .unwrap()
+    }
+
+    #[test]
+    fn l1_is_exact() {
+        let input = input();
+        let norms = dummy_layout(NormOrder::L1, true);
+        assert_eq!(norms.dims(), &[ROWS, 1]);
+        for (row, norm) in input.chunks(COLS).zip(norms.iter()) {
+            assert_eq!(*norm, row.iter().map(|x| x.abs()).sum::<IntegerRep>());
+        }
+    }
+
+    #[test]
+    fn l2_is_within_a_unit_of_the_norm() {
+        let input = input();
+        let norms = dummy_layout(NormOrder::L2, false);
+        assert_eq!(norms.dims(), &[ROWS]);
+        for (row, norm) in input.chunks(COLS).zip(norms.iter()) {
+            // the norm at the scale of the input, of the sum of squares at twice its scale
+            let sum_squared = row.iter().map(|x| x * x).sum::<IntegerRep>() as f64;
+            assert!(
+                (*norm as f64 - sum_squared.sqrt()).abs() <= 1.0,
+                "{} vs {}",
+                norm,
+                sum_squared.sqrt()
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod shuffle {
+    use super::*;
+
+    const K: usize = 6;
+    const LEN: usize = 4;
+    const NUM_LOOP: usize = 5;
+
+    #[derive(Clone)]
+    struct MyCircuit<F: PrimeField + TensorType + PartialOrd> {
+        inputs: [[ValTensor<F>; 1]; NUM_LOOP],
+        references: [[ValTensor<F>; 1]; NUM_LOOP],
+        _marker: PhantomData<F>,
+    }
+
+    impl Circuit<F> for MyCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let a = VarTensor::new_advice(cs, K, 2, LEN);
+            let b = VarTensor::new_advice(cs, K, 2, LEN);
+            let c: VarTensor = VarTensor::new_advice(cs, K, 2, LEN);
+
+            let d = VarTensor::new_advice(cs, K, 1, LEN);
+            let e = VarTensor::new_advice(cs, K, 1, LEN);
+
+            let _constant = VarTensor::constant_cols(cs, K, LEN * NUM_LOOP, false);
+
+            let mut config =
+                Self::Config::configure(cs, &[a.clone(), b.clone()], &c, CheckMode::SAFE);
+            config
+                .configure_shuffles(cs, &[a.clone(), b.clone()], &[d.clone(), e.clone()])
+                .unwrap();
+            config
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            layouter
+                .assign_region(
+                    || "",
+                    |region| {
+                        let mut region = RegionCtx::new(region, 0, 1, 128, 2);
+                        for i in 0..NUM_LOOP {
+                            layouts::shuffles(
+                                &config,
+                                &mut region,
+                                &self.inputs[i],
+                                &self.references[i],
+                            )
+                            .map_err(|_| Error::Synthesis)?;
+                        }
+                        assert_eq!(
+                            region.shuffle_col_coord(),
+                            NUM_LOOP * self.references[0][0].len()
+                        );
+                        assert_eq!(region.shuffle_index(), NUM_LOOP);
 
                         Ok(())
                     },
@@ -2464,29 +3286,198 @@ mod relu {
 }
 
 #[cfg(test)]
-#[cfg(all(
-    feature = "ezkl",
-    not(all(target_arch = "wasm32", target_os = "unknown"))
-))]
-mod lookup_ultra_overflow {
+mod masked_select {
     use super::*;
-    use halo2_proofs::{
-        circuit::{Layouter, SimpleFloorPlanner, Value},
-        plonk::{Circuit, ConstraintSystem, Error},
-        poly::kzg::{
-            commitment::KZGCommitmentScheme,
-            multiopen::{ProverSHPLONK, VerifierSHPLONK},
-            strategy::SingleStrategy,
-        },
-    };
-    use snark_verifier::system::halo2::transcript::evm::EvmTranscript;
+    use crate::circuit::layouts;
+    use crate::circuit::region::RegionSettings;
+    use crate::fieldutils::IntegerRep;
+    use crate::tensor::val::{create_unit_tensor, create_zero_tensor};
+    use ops::base::BaseOp;
+    use ops::hybrid::HybridOp;
+
+    const K: u32 = 9;
+    const LEN: usize = 4;
+
+    fn tensor(values: &[IntegerRep]) -> ValTensor<F> {
+        ValTensor::from_integer_rep_tensor(Tensor::new(Some(values), &[values.len()]).unwrap())
+    }
+
+    fn layout(
+        config: &mut BaseConfig<F>,
+        region: &mut RegionCtx<F>,
+        inputs: &[ValTensor<F>],
+        op: Box<dyn Op<F>>,
+    ) -> ValTensor<F> {
+        config.layout(region, inputs, op).unwrap().unwrap()
+    }
+
+    /// Selects `x` where it differs from `y` and `y` elsewhere, through a mask of logical ops over comparisons.
+    /// Unless `tracked`, the logical ops constrain their inputs to be boolean as they do for untracked sources.
+    fn masked_select(
+        config: &mut BaseConfig<F>,
+        region: &mut RegionCtx<F>,
+        x: &ValTensor<F>,
+        y: &ValTensor<F>,
+        tracked: bool,
+    ) -> (ValTensor<F>, ValTensor<F>) {
+        let checks = |num_inputs: usize| {
+            if tracked {
+                vec![]
+            } else {
+                (0..num_inputs).collect()
+            }
+        };
+        let (x, y) = (x.clone(), y.clone());
+        let gt = layout(
+            config,
+            region,
+            &[x.clone(), y.clone()],
+            Box::new(HybridOp::Greater),
+        );
+        let lt = layout(
+            config,
+            region,
+            &[x.clone(), y.clone()],
+            Box::new(HybridOp::Less),
+        );
+
+        let differs = layout(
+            config,
+            region,
+            &[gt.clone(), lt.clone()],
+            Box::new(PolyOp::Or {
+                boolean_checks: checks(2),
+            }),
+        );
+        let ge = layout(
+            config,
+            region,
+            &[lt],
+            Box::new(PolyOp::Not {
+                boolean_checks: checks(1),
+            }),
+        );
+        // x > y xor x >= y, that is x == y
+        let same = layout(
+            config,
+            region,
+            &[gt, ge],
+            Box::new(PolyOp::Xor {
+                boolean_checks: checks(2),
+            }),
+        );
+        let not_same = layout(
+            config,
+            region,
+            &[same],
+            Box::new(PolyOp::Not {
+                boolean_checks: checks(1),
+            }),
+        );
+        let mask = layout(
+            config,
+            region,
+            &[differs, not_same],
+            Box::new(PolyOp::And {
+                boolean_checks: checks(2),
+            }),
+        );
+
+        let output = layout(config, region, &[mask.clone(), x, y], Box::new(PolyOp::Iff));
+        (mask, output)
+    }
+
+    /// The same mask as laid out before booleans were tracked, with or, not and xor going through iffs
+    fn legacy_mask(
+        config: &BaseConfig<F>,
+        region: &mut RegionCtx<F>,
+        gt: ValTensor<F>,
+        lt: ValTensor<F>,
+    ) -> ValTensor<F> {
+        let or = |region: &mut RegionCtx<F>, a: ValTensor<F>, b: ValTensor<F>| {
+            let b = layouts::boolean_identity(config, region, &[b], true).unwrap();
+            layouts::iff(config, region, &[a.clone(), a, b]).unwrap()
+        };
+        let not = |region: &mut RegionCtx<F>, a: ValTensor<F>| {
+            layouts::iff(
+                config,
+                region,
+                &[a, create_zero_tensor(1), create_unit_tensor(1)],
+            )
+            .unwrap()
+        };
+        let and = |region: &mut RegionCtx<F>, a: ValTensor<F>, b: ValTensor<F>| {
+            layouts::and(config, region, &[a, b], &[0, 1]).unwrap()
+        };
+
+        let differs = or(region, gt.clone(), lt.clone());
+        let ge = not(region, lt);
+        let (gt_not, ge_not) = (not(region, gt.clone()), not(region, ge.clone()));
+        let (gt_and_ge_not, gt_not_and_ge) = (and(region, gt, ge_not), and(region, ge, gt_not));
+        let same = layouts::pairwise(config, region, &[gt_and_ge_not, gt_not_and_ge], BaseOp::Add)
+            .unwrap();
+        let not_same = not(region, same);
+        and(region, differs, not_same)
+    }
+
+    #[test]
+    fn tracked_booleans_take_fewer_rows() {
+        let mut config = BaseConfig::dummy(12, 2);
+        let (x, y) = (tensor(&[1, 0, -1, 1]), tensor(&[0, 0, 1, 1]));
+        let settings = || RegionSettings::all_true(128, 2);
+
+        let mut tracked = RegionCtx::new_dummy(0, 2, settings());
+        let (mask, output) = masked_select(&mut config, &mut tracked, &x, &y, true);
+        assert_eq!(
+            mask.int_evals().unwrap(),
+            Tensor::new(Some(&[1, 0, 1, 0]), &[LEN]).unwrap()
+        );
+        assert_eq!(output.int_evals().unwrap(), x.int_evals().unwrap());
+
+        // constraining the inputs of the logical ops again takes a cell per value of each input of the or, the xor,
+        // the and and both nots
+        let mut checked = RegionCtx::new_dummy(0, 2, settings());
+        let (checked_mask, _) = masked_select(&mut config, &mut checked, &x, &y, false);
+        assert_eq!(checked_mask.int_evals().unwrap(), mask.int_evals().unwrap());
+        assert_eq!(checked.linear_coord() - tracked.linear_coord(), 8 * LEN);
+
+        let mut legacy = RegionCtx::new_dummy(0, 2, settings());
+        let gt = layout(
+            &mut config,
+            &mut legacy,
+            &[x.clone(), y.clone()],
+            Box::new(HybridOp::Greater),
+        );
+        let lt = layout(
+            &mut config,
+            &mut legacy,
+            &[x.clone(), y.clone()],
+            Box::new(HybridOp::Less),
+        );
+        let legacy_mask = legacy_mask(&config, &mut legacy, gt, lt);
+        layout(
+            &mut config,
+            &mut legacy,
+            &[legacy_mask.clone(), x, y],
+            Box::new(PolyOp::Iff),
+        );
+        assert_eq!(legacy_mask.int_evals().unwrap(), mask.int_evals().unwrap());
+        assert!(
+            tracked.row() < legacy.row(),
+            "{} rows tracked, {} before",
+            tracked.row(),
+            legacy.row()
+        );
+        assert!(checked.row() < legacy.row());
+    }
 
     #[derive(Clone)]
-    struct ReLUCircuit<F: PrimeField + TensorType + PartialOrd> {
-        pub input: ValTensor<F>,
+    struct MaskedSelectCircuit {
+        x: ValTensor<F>,
+        y: ValTensor<F>,
     }
 
-    impl Circuit<F> for ReLUCircuit<F> {
+    impl Circuit<F> for MaskedSelectCircuit {
         type Config = BaseConfig<F>;
         type FloorPlanner = SimpleFloorPlanner;
         type Params = TestParams;
@@ -2497,31 +3488,112 @@ mod lookup_ultra_overflow {
 
         fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
             let advices = (0..3)
-                .map(|_| VarTensor::new_advice(cs, 4, 1, 3))
+                .map(|_| VarTensor::new_advice(cs, K as usize, 1, 3))
                 .collect::<Vec<_>>();
 
-            let nl = LookupOp::LeakyReLU { slope: 0.0.into() };
+            let mut config = BaseConfig::configure(
+                cs,
+                &[advices[0].clone(), advices[1].clone()],
+                &advices[2],
+                CheckMode::SAFE,
+            );
 
-            let mut config = BaseConfig::default();
+            // the sign and digits of the comparisons
+            config
+                .configure_range_check(cs, &advices[0], &advices[1], (-1, 1), K as usize)
+                .unwrap();
+            config
+                .configure_range_check(cs, &advices[0], &advices[1], (0, 1), K as usize)
+                .unwrap();
+
+            let _constant = VarTensor::constant_cols(cs, K as usize, 8, false);
 
             config
-                .configure_lookup(
-                    cs,
-                    &advices[0],
-                    &advices[1],
-                    &advices[2],
-                    (-1024, 1024),
-                    4,
-                    &nl,
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.layout_range_checks(&mut layouter).unwrap();
+            layouter
+                .assign_region(
+                    || "",
+                    |region| {
+                        let mut region = RegionCtx::new(region, 0, 1, 2, 2);
+                        masked_select(&mut config, &mut region, &self.x, &self.y, true);
+                        Ok(())
+                    },
                 )
                 .unwrap();
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn masked_select_proves() {
+        let circuit = MaskedSelectCircuit {
+            x: tensor(&[1, 0, -1, 1]),
+            y: tensor(&[0, 0, 1, 1]),
+        };
+
+        let prover = MockProver::run(K, &circuit, vec![]).unwrap();
+        prover.assert_satisfied();
+    }
+}
+
+#[cfg(test)]
+#[cfg(all(
+    feature = "ezkl",
+    not(all(target_arch = "wasm32", target_os = "unknown"))
+))]
+mod fused_add_lookup {
+    use super::*;
+    use crate::circuit::ops::hybrid::HybridOp;
+    use crate::fieldutils::{integer_rep_to_felt, IntegerRep};
+
+    const K: usize = 8;
+    const LEN: usize = 8;
+
+    fn nl() -> LookupOp {
+        LookupOp::LeakyReLU { slope: 0.0.into() }
+    }
+
+    #[derive(Clone)]
+    struct MyCircuit<F: PrimeField + TensorType + PartialOrd> {
+        inputs: [ValTensor<F>; 2],
+        _marker: PhantomData<F>,
+    }
+
+    impl Circuit<F> for MyCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let a = VarTensor::new_advice(cs, K, 1, LEN);
+            let b = VarTensor::new_advice(cs, K, 1, LEN);
+            let output = VarTensor::new_advice(cs, K, 1, LEN);
+
+            let mut config =
+                BaseConfig::configure(cs, &[a.clone(), b.clone()], &output, CheckMode::SAFE);
+            config
+                .configure_lookup(cs, &a, &output, &b, (-64, 64), K, &nl())
+                .unwrap();
+            config.configure_fused_add_lookup(cs, &nl()).unwrap();
             config
         }
 
         fn synthesize(
             &self,
             mut config: Self::Config,
-            mut layouter: impl Layouter<F>, // layouter is our 'write buffer' for the circuit
+            mut layouter: impl Layouter<F>,
         ) -> Result<(), Error> {
             config.layout_tables(&mut layouter).unwrap();
             layouter
@@ -2532,21 +3604,284 @@ mod lookup_ultra_overflow {
                         config
                             .layout(
                                 &mut region,
-                                &[self.input.clone()],
-                                Box::new(LookupOp::LeakyReLU { slope: 0.0.into() }),
+                                &self.inputs,
+                                Box::new(HybridOp::FusedAddLookup { nl: nl() }),
                             )
                             .map_err(|_| Error::Synthesis)
                     },
                 )
                 .unwrap();
-
             Ok(())
         }
     }
 
-    #[test]
-    #[ignore]
-    fn relucircuit() {
+    fn circuit(
+        a: impl Fn(IntegerRep) -> IntegerRep,
+        b: impl Fn(IntegerRep) -> IntegerRep,
+    ) -> MyCircuit<F> {
+        let tensor = |f: &dyn Fn(IntegerRep) -> IntegerRep| {
+            let values = (0..LEN as IntegerRep).map(|i| Value::known(integer_rep_to_felt(f(i))));
+            ValTensor::from(Tensor::from(values))
+        };
+        MyCircuit {
+            inputs: [tensor(&a), tensor(&b)],
+            _marker: PhantomData,
+        }
+    }
+
+    #[test]
+    fn sums_looked_up() {
+        let circuit = circuit(|i| 3 * i - 20, |i| 5 - i);
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn sums_out_of_the_table() {
+        // each addend is in the table but their sums aren't
+        let circuit = circuit(|i| 40 + i, |_| 40);
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
+
+#[cfg(test)]
+#[cfg(all(
+    feature = "ezkl",
+    not(all(target_arch = "wasm32", target_os = "unknown"))
+))]
+mod tight_lookup_tables {
+    use super::*;
+    use crate::circuit::region::RegionSettings;
+    use crate::fieldutils::{integer_rep_to_felt, IntegerRep};
+    use std::collections::BTreeMap;
+
+    const K: usize = 8;
+    const LEN: usize = 8;
+    const LOOKUP_RANGE: (IntegerRep, IntegerRep) = (-512, 512);
+    // the variances the reciprocal is taken of, as calibration would have observed them
+    const TIGHT_RANGE: (IntegerRep, IntegerRep) = (1, 64);
+
+    fn relu() -> LookupOp {
+        LookupOp::LeakyReLU { slope: 0.0.into() }
+    }
+
+    fn recip() -> LookupOp {
+        LookupOp::Recip {
+            input_scale: 1.0.into(),
+            output_scale: 64.0.into(),
+            rounding: Default::default(),
+        }
+    }
+
+    fn tensor(values: impl Iterator<Item = IntegerRep>) -> ValTensor<F> {
+        ValTensor::from(Tensor::from(
+            values.map(|v| Value::known(integer_rep_to_felt(v))),
+        ))
+    }
+
+    #[derive(Clone)]
+    struct MyCircuit<F: PrimeField + TensorType + PartialOrd> {
+        relu_input: ValTensor<F>,
+        recip_input: ValTensor<F>,
+    }
+
+    impl Circuit<F> for MyCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let a = VarTensor::new_advice(cs, K, 1, LEN);
+            let b = VarTensor::new_advice(cs, K, 1, LEN);
+            let output = VarTensor::new_advice(cs, K, 1, LEN);
+
+            let mut config =
+                BaseConfig::configure(cs, &[a.clone(), b.clone()], &output, CheckMode::SAFE);
+            config
+                .configure_lookup(cs, &a, &output, &b, LOOKUP_RANGE, K, &relu())
+                .unwrap();
+            config
+                .configure_lookup(cs, &a, &output, &b, TIGHT_RANGE, K, &recip())
+                .unwrap();
+            config
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.layout_tables(&mut layouter).unwrap();
+            layouter
+                .assign_region(
+                    || "",
+                    |region| {
+                        let mut region = RegionCtx::new(region, 0, 1, 128, 2);
+                        config
+                            .layout(&mut region, &[self.relu_input.clone()], Box::new(relu()))
+                            .map_err(|_| Error::Synthesis)?;
+                        config
+                            .layout(&mut region, &[self.recip_input.clone()], Box::new(recip()))
+                            .map_err(|_| Error::Synthesis)
+                    },
+                )
+                .unwrap();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn tight_tables_shrink() {
+        let mut cs = ConstraintSystem::<F>::default();
+        let config = MyCircuit::<F>::configure(&mut cs);
+        let relu_table = &config.static_lookups.tables[&relu()];
+        let recip_table = &config.static_lookups.tables[&recip()];
+
+        // the lookup range takes several columns of 2^K rows, the inputs of the reciprocal a single one of its own
+        assert!(relu_table.table_inputs.len() > 1);
+        assert_eq!(recip_table.table_inputs.len(), 1);
+        assert_eq!(recip_table.range, TIGHT_RANGE);
+        assert!(!relu_table
+            .table_inputs
+            .contains(&recip_table.table_inputs[0]));
+
+        // both tables are laid out with their own inputs
+        let circuit = MyCircuit::<F> {
+            relu_input: tensor((0..LEN as IntegerRep).map(|i| 100 * i - 400)),
+            recip_input: tensor((0..LEN as IntegerRep).map(|i| 8 * i + 1)),
+        };
+        let prover = MockProver::run(K as u32, &circuit, vec![]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn overflow_names_node() {
+        let mut config = BaseConfig::<F>::dummy(K, 1);
+        let mut region = RegionCtx::new_dummy(0, 1, RegionSettings::all_true(128, 2));
+        region.set_tight_lookup_ranges(BTreeMap::from([(recip(), TIGHT_RANGE)]));
+
+        region.set_current_node(3, "variance", "RECIP".to_string());
+        config
+            .layout(
+                &mut region,
+                &[tensor([2, 64].into_iter())],
+                Box::new(recip()),
+            )
+            .unwrap();
+        let recorded = region.tight_lookup_ranges();
+        assert_eq!(recorded.len(), 1);
+        assert_eq!((recorded[0].node, recorded[0].range), (3, (2, 64)));
+
+        region.set_current_node(4, "norm", "RECIP".to_string());
+        match config.layout(
+            &mut region,
+            &[tensor([5, 100].into_iter())],
+            Box::new(recip()),
+        ) {
+            Err(CircuitError::TightLookupOOR {
+                node,
+                value,
+                min,
+                max,
+                ..
+            }) => {
+                assert_eq!(node, "4 (norm)");
+                assert_eq!((value, min, max), (100, 1, 64));
+            }
+            e => panic!("expected a tight lookup overflow, got {:?}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(all(
+    feature = "ezkl",
+    not(all(target_arch = "wasm32", target_os = "unknown"))
+))]
+mod lookup_ultra_overflow {
+    use super::*;
+    use halo2_proofs::{
+        circuit::{Layouter, SimpleFloorPlanner, Value},
+        plonk::{Circuit, ConstraintSystem, Error},
+        poly::kzg::{
+            commitment::KZGCommitmentScheme,
+            multiopen::{ProverSHPLONK, VerifierSHPLONK},
+            strategy::SingleStrategy,
+        },
+    };
+    use snark_verifier::system::halo2::transcript::evm::EvmTranscript;
+
+    #[derive(Clone)]
+    struct ReLUCircuit<F: PrimeField + TensorType + PartialOrd> {
+        pub input: ValTensor<F>,
+    }
+
+    impl Circuit<F> for ReLUCircuit<F> {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let advices = (0..3)
+                .map(|_| VarTensor::new_advice(cs, 4, 1, 3))
+                .collect::<Vec<_>>();
+
+            let nl = LookupOp::LeakyReLU { slope: 0.0.into() };
+
+            let mut config = BaseConfig::default();
+
+            config
+                .configure_lookup(
+                    cs,
+                    &advices[0],
+                    &advices[1],
+                    &advices[2],
+                    (-1024, 1024),
+                    4,
+                    &nl,
+                )
+                .unwrap();
+            config
+        }
+
+        fn synthesize(
+            &self,
+            mut config: Self::Config,
+            mut layouter: impl Layouter<F>, // layouter is our 'write buffer' for the circuit
+        ) -> Result<(), Error> {
+            config.layout_tables(&mut layouter).unwrap();
+            layouter
+                .assign_region(
+                    || "",
+                    |region| {
+                        let mut region = RegionCtx::new(region, 0, 1, 128, 2);
+                        config
+                            .layout(
+                                &mut region,
+                                &[self.input.clone()],
+                                Box::new(LookupOp::LeakyReLU { slope: 0.0.into() }),
+                            )
+                            .map_err(|_| Error::Synthesis)
+                    },
+                )
+                .unwrap();
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    #[ignore]
+    fn relucircuit() {
         // get some logs fam
         crate::logger::init_logger();
         // parameters
@@ -2591,3 +3926,533 @@ mod lookup_ultra_overflow {
         assert!(prover.is_ok());
     }
 }
+
+#[cfg(test)]
+#[cfg(all(
+    feature = "ezkl",
+    not(all(target_arch = "wasm32", target_os = "unknown"))
+))]
+mod lookup_table_consistency {
+    use super::*;
+    use crate::circuit::utils::F32;
+
+    /// Every lookup op, at the given scale
+    fn lookup_ops(scale: f32) -> Vec<LookupOp> {
+        let scale = F32(scale);
+        vec![
+            LookupOp::Div {
+                denom: scale,
+                rounding: DivRounding::Round,
+            },
+            LookupOp::Cast {
+                scale,
+                rounding: DivRounding::Round,
+            },
+            LookupOp::Ceil { scale },
+            LookupOp::Floor { scale },
+            LookupOp::Round { scale },
+            LookupOp::RoundHalfToEven { scale },
+            LookupOp::Sqrt { scale },
+            LookupOp::Rsqrt { scale },
+            LookupOp::Recip {
+                input_scale: scale,
+                output_scale: scale,
+                rounding: DivRounding::Round,
+            },
+            LookupOp::LeakyReLU { slope: F32(0.01) },
+            LookupOp::Sigmoid { scale },
+            LookupOp::Ln { scale },
+            LookupOp::Exp { scale },
+            LookupOp::Cos { scale },
+            LookupOp::ACos { scale },
+            LookupOp::Cosh { scale },
+            LookupOp::ACosh { scale },
+            LookupOp::Sin { scale },
+            LookupOp::ASin { scale },
+            LookupOp::Sinh { scale },
+            LookupOp::ASinh { scale },
+            LookupOp::Tan { scale },
+            LookupOp::ATan { scale },
+            LookupOp::Tanh { scale },
+            LookupOp::ATanh { scale },
+            LookupOp::Erf { scale },
+            LookupOp::KroneckerDelta,
+            LookupOp::EqualsConst { value: 7 },
+            LookupOp::in_set([-3, 0, 5]),
+            LookupOp::Pow { scale, a: F32(2.0) },
+            LookupOp::Pow { scale, a: F32(0.5) },
+            LookupOp::HardSwish { scale },
+        ]
+    }
+
+    #[test]
+    fn tables_match_the_witness_and_the_reference() {
+        for scale in [1.0, 16.0, 256.0] {
+            for op in lookup_ops(scale) {
+                let report = op.check_table_consistency((-512, 512)).unwrap();
+                assert!(report.is_consistent(), "{:?}", report);
+                // the tables round the reference to the nearest integer
+                assert!(report.max_deviation <= 0.5 + 1e-9, "{:?}", report);
+                assert!(report.skipped < 1025, "{:?}", report);
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_an_empty_range() {
+        let op = LookupOp::Sigmoid { scale: F32(16.0) };
+        assert!(op.check_table_consistency((1, 0)).is_err());
+    }
+}
+
+#[cfg(test)]
+#[cfg(all(
+    feature = "ezkl",
+    not(all(target_arch = "wasm32", target_os = "unknown"))
+))]
+mod div_rounding {
+    use super::*;
+    use crate::circuit::utils::F32;
+    use crate::fieldutils::{felt_to_integer_rep, integer_rep_to_felt, IntegerRep};
+
+    const ROUNDINGS: [DivRounding; 3] =
+        [DivRounding::Floor, DivRounding::Trunc, DivRounding::Round];
+
+    /// The quotient of integers rounded as `rounding`, in integer arithmetic
+    fn int_div(a: IntegerRep, b: IntegerRep, rounding: DivRounding) -> IntegerRep {
+        let trunc = a / b;
+        let inexact = a % b != 0;
+        let negative = (a < 0) != (b < 0);
+        match rounding {
+            DivRounding::Trunc => trunc,
+            DivRounding::Floor if inexact && negative => trunc - 1,
+            DivRounding::Floor => trunc,
+            DivRounding::Round => {
+                let rounded = (2 * a.abs() + b.abs()) / (2 * b.abs());
+                if negative {
+                    -rounded
+                } else {
+                    rounded
+                }
+            }
+        }
+    }
+
+    fn eval(op: &LookupOp, inputs: &[IntegerRep]) -> Vec<IntegerRep> {
+        let inputs = Tensor::from(inputs.iter().map(|x| integer_rep_to_felt::<F>(*x)));
+        let output = op.f(&[inputs]).unwrap().output;
+        output.iter().map(|x| felt_to_integer_rep(*x)).collect()
+    }
+
+    #[test]
+    fn div_and_cast() {
+        let inputs = (-100..=100).collect::<Vec<_>>();
+        for rounding in ROUNDINGS {
+            for denom in [2, 3, 7, 128] {
+                let expected = inputs
+                    .iter()
+                    .map(|x| int_div(*x, denom, rounding))
+                    .collect::<Vec<_>>();
+                let div = LookupOp::Div {
+                    denom: F32(denom as f32),
+                    rounding,
+                };
+                assert_eq!(eval(&div, &inputs), expected, "{:?}", div);
+                let cast = LookupOp::Cast {
+                    scale: F32(denom as f32),
+                    rounding,
+                };
+                assert_eq!(eval(&cast, &inputs), expected, "{:?}", cast);
+            }
+        }
+    }
+
+    #[test]
+    fn recip() {
+        // the inverse of zero is a sentinel, the same for every rounding
+        let inputs = (-100..=100).filter(|x| *x != 0).collect::<Vec<_>>();
+        for rounding in ROUNDINGS {
+            for (input_scale, output_scale) in [(1, 1), (4, 8), (16, 128)] {
+                let expected = inputs
+                    .iter()
+                    .map(|x| int_div(input_scale * output_scale, *x, rounding))
+                    .collect::<Vec<_>>();
+                let recip = LookupOp::Recip {
+                    input_scale: F32(input_scale as f32),
+                    output_scale: F32(output_scale as f32),
+                    rounding,
+                };
+                assert_eq!(eval(&recip, &inputs), expected, "{:?}", recip);
+            }
+        }
+    }
+
+    #[test]
+    fn roundings_have_their_own_tables() {
+        let paths = ROUNDINGS
+            .iter()
+            .map(|rounding| {
+                LookupOp::Div {
+                    denom: F32(2.0),
+                    rounding: *rounding,
+                }
+                .as_path()
+            })
+            .collect::<std::collections::HashSet<_>>();
+        assert_eq!(paths.len(), ROUNDINGS.len());
+    }
+}
+
+#[cfg(test)]
+#[cfg(all(
+    feature = "ezkl",
+    not(all(target_arch = "wasm32", target_os = "unknown"))
+))]
+mod indicator_lookups {
+    use super::*;
+    use crate::fieldutils::{felt_to_integer_rep, integer_rep_to_felt, IntegerRep};
+
+    fn eval(op: &LookupOp, inputs: &[IntegerRep]) -> Vec<IntegerRep> {
+        let inputs = Tensor::from(inputs.iter().map(|x| integer_rep_to_felt::<F>(*x)));
+        let output = op.f(&[inputs]).unwrap().output;
+        output.iter().map(|x| felt_to_integer_rep(*x)).collect()
+    }
+
+    #[test]
+    fn equals_const() {
+        let op = LookupOp::EqualsConst { value: -3 };
+        assert_eq!(eval(&op, &[-4, -3, 0, 3]), vec![0, 1, 0, 0]);
+        assert_eq!(<LookupOp as Op<F>>::out_scale(&op, vec![7]).unwrap(), 0);
+        assert_ne!(op.as_path(), LookupOp::EqualsConst { value: 3 }.as_path());
+    }
+
+    #[test]
+    fn in_set_with_duplicates() {
+        let op = LookupOp::in_set([7, -2, 7, 0, -2]);
+        assert_eq!(
+            op,
+            LookupOp::InSet {
+                values: vec![-2, 0, 7]
+            }
+        );
+        assert_eq!(op.as_path(), LookupOp::in_set([0, 7, -2]).as_path());
+        assert_eq!(eval(&op, &[-2, -1, 0, 1, 7, 8]), vec![1, 0, 1, 0, 1, 0]);
+        // the repeated values of a set built as is indicate the same inputs
+        let repeated = LookupOp::InSet {
+            values: vec![7, -2, 7, 0, -2],
+        };
+        assert_eq!(
+            eval(&repeated, &[-2, -1, 0, 1, 7, 8]),
+            vec![1, 0, 1, 0, 1, 0]
+        );
+    }
+
+    #[test]
+    fn values_outside_the_table_range() {
+        let range = (-16, 16);
+        // the op, and the number of its values in the range
+        let ops = [
+            (LookupOp::EqualsConst { value: 100 }, 0),
+            (LookupOp::in_set([-100, 3, 100]), 1),
+        ];
+        for (op, in_range) in ops {
+            // the tables hold the indicator of the values in the range only
+            let report = op.check_table_consistency(range).unwrap();
+            assert!(report.is_consistent(), "{:?}", report);
+            assert_eq!(report.max_deviation, 0.0, "{:?}", report);
+            let (inputs, outputs) = crate::circuit::table::table_evals::<F>(&op, range).unwrap();
+            assert!(inputs
+                .iter()
+                .all(|i| felt_to_integer_rep(*i).abs() <= range.1));
+            let ones = outputs.iter().filter(|o| **o == F::ONE).count();
+            assert_eq!(ones, in_range, "{:?}", op);
+            // the witness indicates the values outside the range all the same, though they can't be looked up
+            assert_eq!(eval(&op, &[100]), vec![1]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod bit_range_checks {
+    use super::*;
+    use crate::circuit::region::RegionSettings;
+    use crate::circuit::table::{bit_range_check_rows, lookup_range_check_rows, Range};
+    use crate::fieldutils::IntegerRep;
+
+    const NUM_INNER_COLS: usize = 2;
+
+    fn values(range: Range, values: &[IntegerRep]) -> ValTensor<F> {
+        assert!(values.iter().all(|v| range.0 <= *v && *v <= range.1));
+        ValTensor::from_integer_rep_tensor(Tensor::new(Some(values), &[values.len()]).unwrap())
+    }
+
+    /// `count` values spread over a range
+    fn spread(range: Range, count: usize) -> ValTensor<F> {
+        let size = range.1 - range.0 + 1;
+        let spread = (0..count)
+            .map(|i| range.0 + (i as IntegerRep * 7919) % size)
+            .collect::<Vec<_>>();
+        values(range, &spread)
+    }
+
+    /// The rows the range checks of `count` values take in a dummy region, with the values looked up or decomposed
+    /// in bits
+    fn rows(range: Range, count: usize, bits: bool) -> usize {
+        let config = BaseConfig::dummy(17, NUM_INNER_COLS);
+        let mut settings = RegionSettings::all_true(128, 2);
+        if bits {
+            settings = settings.with_bit_range_checks(&[range]);
+        }
+        let mut region = RegionCtx::new_dummy(0, NUM_INNER_COLS, settings);
+        layouts::range_check(&config, &mut region, &[spread(range, count)], &range).unwrap();
+        // the decomposed range needs no table
+        assert_eq!(region.used_range_checks().is_empty(), bits);
+        region.row()
+    }
+
+    #[test]
+    fn crossover() {
+        let table = |range: Range| (range.1 - range.0 + 1) as usize;
+
+        // a few values of a wide range take fewer rows decomposed than the table of the range alone
+        let wide = (-(1 << 19), 1 << 19);
+        let bits = rows(wide, 8, true);
+        assert!(bits < rows(wide, 8, false) + table(wide));
+        assert!(bits <= bit_range_check_rows(wide, 8, NUM_INNER_COLS));
+        assert!(RangeCheckStrategy::Auto.decomposes(wide, 8, NUM_INNER_COLS));
+
+        // many values of a narrow range take fewer rows looked up, with the table
+        let narrow = (0, 255);
+        let lookup = rows(narrow, 1024, false);
+        assert!(lookup + table(narrow) < rows(narrow, 1024, true));
+        assert_eq!(
+            lookup + table(narrow),
+            lookup_range_check_rows(narrow, 1024, NUM_INNER_COLS)
+        );
+        assert!(!RangeCheckStrategy::Auto.decomposes(narrow, 1024, NUM_INNER_COLS));
+
+        // the strategies that aren't estimated
+        assert!(RangeCheckStrategy::Bits.decomposes(narrow, 1024, NUM_INNER_COLS));
+        assert!(!RangeCheckStrategy::Lookup.decomposes(wide, 8, NUM_INNER_COLS));
+    }
+
+    #[test]
+    fn values_out_of_range() {
+        let config = BaseConfig::dummy(12, NUM_INNER_COLS);
+        let range = (-1000, 1000);
+        let settings = RegionSettings::all_true(128, 2).with_bit_range_checks(&[range]);
+        let mut region = RegionCtx::new_dummy(0, NUM_INNER_COLS, settings);
+        let out_of_range =
+            ValTensor::from_integer_rep_tensor(Tensor::new(Some(&[0, 1001]), &[2]).unwrap());
+        assert!(matches!(
+            layouts::range_check(&config, &mut region, &[out_of_range], &range),
+            Err(CircuitError::TableOOR(1001, -1000, 1000))
+        ));
+    }
+
+    const K: u32 = 10;
+
+    #[derive(Clone)]
+    struct BitRangeCheckCircuit {
+        checks: Vec<(Range, ValTensor<F>)>,
+    }
+
+    impl Circuit<F> for BitRangeCheckCircuit {
+        type Config = BaseConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = TestParams;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
+            let advices = (0..3)
+                .map(|_| VarTensor::new_advice(cs, K as usize, 1, 3))
+                .collect::<Vec<_>>();
+            let _constant = VarTensor::constant_cols(cs, K as usize, 64, false);
+
+            // no range check tables
+            BaseConfig::configure(
+                cs,
+                &[advices[0].clone(), advices[1].clone()],
+                &advices[2],
+                CheckMode::SAFE,
+            )
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            layouter
+                .assign_region(
+                    || "",
+                    |region| {
+                        let mut region = RegionCtx::new(region, 0, 1, 2, 2);
+                        let ranges = self.checks.iter().map(|(r, _)| *r).collect::<Vec<_>>();
+                        region.set_bit_range_checks(&ranges);
+                        for (range, values) in &self.checks {
+                            layouts::range_check(&config, &mut region, &[values.clone()], range)
+                                .unwrap();
+                        }
+                        Ok(())
+                    },
+                )
+                .unwrap();
+
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn decomposed_values_satisfy_the_circuit() {
+        // the bounds of a range bounded from above and of a range of a power of two values
+        let bounded = (-1000, 1000);
+        let power_of_two = (0, 255);
+        let circuit = BitRangeCheckCircuit {
+            checks: vec![
+                (bounded, values(bounded, &[-1000, -1, 0, 1000])),
+                (power_of_two, values(power_of_two, &[0, 17, 255])),
+            ],
+        };
+
+        let prover = MockProver::run(K, &circuit, vec![]).unwrap();
+        prover.assert_satisfied();
+    }
+}
+
+#[cfg(test)]
+mod signs {
+    use super::*;
+    use crate::circuit::region::RegionSettings;
+    use crate::fieldutils::{integer_rep_to_felt, FeltRangeError, IntegerRep};
+    use crate::tensor::{TensorError, ValType};
+
+    const NUM_INNER_COLS: usize = 2;
+    // the largest magnitude of base 128 and 2 legs
+    const MAX_ABS: IntegerRep = 128 * 128 - 1;
+
+    fn felts(values: &[F]) -> ValTensor<F> {
+        Tensor::from(values.iter().map(|x| ValType::Value(Value::known(*x)))).into()
+    }
+
+    fn ints(values: &[IntegerRep]) -> ValTensor<F> {
+        ValTensor::from_integer_rep_tensor(Tensor::new(Some(values), &[values.len()]).unwrap())
+    }
+
+    fn region() -> RegionCtx<'static, F> {
+        RegionCtx::new_dummy(0, NUM_INNER_COLS, RegionSettings::all_true(128, 2))
+    }
+
+    fn out_of_range<T: std::fmt::Debug>(res: Result<T, CircuitError>) -> bool {
+        matches!(
+            res,
+            Err(CircuitError::TensorError(TensorError::FeltRange(_)))
+        )
+    }
+
+    #[test]
+    fn signs_about_the_decomposition_range() {
+        let config = BaseConfig::dummy(12, NUM_INNER_COLS);
+        let values = ints(&[-MAX_ABS, -MAX_ABS + 1, -1, 0, 1, MAX_ABS - 1, MAX_ABS]);
+        let signs = layouts::sign(&config, &mut region(), &[values.clone()]).unwrap();
+        assert_eq!(
+            signs.int_evals().unwrap(),
+            Tensor::new(Some(&[-1, -1, -1, 0, 1, 1, 1]), &[7]).unwrap()
+        );
+        let abs = layouts::abs(&config, &mut region(), &[values]).unwrap();
+        assert_eq!(
+            abs.int_evals().unwrap(),
+            Tensor::new(
+                Some(&[MAX_ABS, MAX_ABS - 1, 1, 0, 1, MAX_ABS - 1, MAX_ABS]),
+                &[7]
+            )
+            .unwrap()
+        );
+
+        // base^legs itself doesn't decompose in the legs
+        for value in [MAX_ABS + 1, -MAX_ABS - 1] {
+            assert!(out_of_range(layouts::sign(
+                &config,
+                &mut region(),
+                &[ints(&[value])]
+            )));
+        }
+    }
+
+    #[test]
+    fn abs_and_neg_rows() {
+        let config = BaseConfig::dummy(12, NUM_INNER_COLS);
+        // the most negative value of the decomposition range
+        let values = ints(&[-MAX_ABS, -5, 0, 5, MAX_ABS]);
+        let rows = |op: &dyn Fn(&mut RegionCtx<'static, F>) -> ValTensor<F>| {
+            let mut region = region();
+            let res = op(&mut region);
+            // neither looks a value up in a table
+            assert!(region.used_lookups().is_empty());
+            (region.linear_coord(), res.int_evals().unwrap())
+        };
+
+        let (sign_rows, _) = rows(&|r| layouts::sign(&config, r, &[values.clone()]).unwrap());
+        let (abs_rows, abs) = rows(&|r| layouts::abs(&config, r, &[values.clone()]).unwrap());
+        // the values times their signs, one multiplication each
+        assert_eq!(abs_rows, sign_rows + values.len());
+        assert_eq!(
+            abs,
+            Tensor::new(Some(&[MAX_ABS, 5, 0, 5, MAX_ABS]), &[5]).unwrap()
+        );
+
+        // the most negative value whose negation is an integer of the representation
+        let most_negative = ints(&[-IntegerRep::MAX, -5, 5]);
+        let (neg_rows, neg) =
+            rows(&|r| layouts::neg(&config, r, &[most_negative.clone()]).unwrap());
+        assert_eq!(neg_rows, most_negative.len());
+        assert_eq!(
+            neg,
+            Tensor::new(Some(&[IntegerRep::MAX, 5, -5]), &[3]).unwrap()
+        );
+    }
+
+    #[test]
+    fn huge_positive_value_is_not_negative() {
+        let config = BaseConfig::dummy(12, NUM_INNER_COLS);
+        // a positive value accumulated past IntegerRep::MAX, which wraps to a negative integer
+        let huge = F::from_u128(IntegerRep::MAX as u128) + F::from(7);
+        assert!(out_of_range(layouts::sign(
+            &config,
+            &mut region(),
+            &[felts(&[huge])]
+        )));
+        assert!(matches!(
+            layouts::greater(&config, &mut region(), &[felts(&[huge]), ints(&[0])]),
+            Err(CircuitError::TensorError(TensorError::FeltRange(
+                FeltRangeError::OutOfRange(_, MAX_ABS)
+            )))
+        ));
+        // operands within the range whose difference the decomposition doesn't bound
+        assert!(matches!(
+            layouts::greater(
+                &config,
+                &mut region(),
+                &[ints(&[MAX_ABS]), ints(&[-MAX_ABS])]
+            ),
+            Err(CircuitError::TensorError(TensorError::FeltRange(
+                FeltRangeError::DifferenceOutOfRange(MAX_ABS, _, MAX_ABS)
+            )))
+        ));
+
+        // values and differences within the range compare as integers
+        let (a, b) = (
+            integer_rep_to_felt::<F>(MAX_ABS / 2),
+            integer_rep_to_felt::<F>(-MAX_ABS / 2),
+        );
+        let greater =
+            layouts::greater(&config, &mut region(), &[felts(&[a, b]), felts(&[b, a])]).unwrap();
+        assert_eq!(
+            greater.int_evals().unwrap(),
+            Tensor::new(Some(&[1, 0]), &[2]).unwrap()
+        );
+    }
+}
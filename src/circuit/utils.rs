@@ -1,3 +1,9 @@
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+use schemars::gen::SchemaGenerator;
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+use schemars::schema::Schema;
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 // --------------------------------------------------------------------------------------------
@@ -34,6 +40,21 @@ impl Serialize for F32 {
     }
 }
 
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+impl JsonSchema for F32 {
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn schema_name() -> String {
+        f32::schema_name()
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        f32::json_schema(gen)
+    }
+}
+
 /// This works like `PartialEq` on `f32`, except that `NAN == NAN` is true.
 impl PartialEq for F32 {
     fn eq(&self, other: &Self) -> bool {
@@ -16,8 +16,11 @@ use tosubcommand::{ToFlags, ToSubcommand};
 use crate::{pfsys::ProofType, Commitments, RunArgs};
 
 use crate::circuit::CheckMode;
+use crate::graph::schema::JsonArtifact;
 use crate::graph::TestDataSource;
+use crate::pfsys::vk_description::VkDescriptionFormat;
 use crate::pfsys::TranscriptType;
+use crate::report::ReportFormat;
 
 /// The default path to the .json data file
 pub const DEFAULT_DATA: &str = "input.json";
@@ -27,10 +30,14 @@ pub const DEFAULT_MODEL: &str = "network.onnx";
 pub const DEFAULT_COMPILED_CIRCUIT: &str = "model.compiled";
 /// The default path to the .json witness file
 pub const DEFAULT_WITNESS: &str = "witness.json";
+/// The default path to the .json merkle tree of a dataset
+pub const DEFAULT_MERKLE_TREE: &str = "merkle_tree.json";
 /// The default path to the circuit settings file
 pub const DEFAULT_SETTINGS: &str = "settings.json";
 /// The default path to the proving key file
 pub const DEFAULT_PK: &str = "pk.key";
+/// The default path to the memory-mappable proving key file
+pub const DEFAULT_PK_MMAP: &str = "pk.mmap.key";
 /// The default path to the verification key file
 pub const DEFAULT_VK: &str = "vk.key";
 /// The default path to the proving key file for aggregated proofs
@@ -39,6 +46,12 @@ pub const DEFAULT_PK_AGGREGATED: &str = "pk_aggr.key";
 pub const DEFAULT_VK_AGGREGATED: &str = "vk_aggr.key";
 /// The default path to the proof file
 pub const DEFAULT_PROOF: &str = "proof.json";
+/// The default directory of the commitments of the groups of params
+pub const DEFAULT_PARAM_GROUPS_DIR: &str = "param_groups";
+/// The default directory of the circuits and keys of a family of batch sizes
+pub const DEFAULT_BATCH_FAMILY_DIR: &str = "batch_family";
+/// The default max batch size of a family of batch sizes
+pub const DEFAULT_MAX_BATCH_SIZE: &str = "8";
 /// The default path to the proof file for aggregated proofs
 pub const DEFAULT_PROOF_AGGREGATED: &str = "proof_aggr.json";
 /// Default for whether to split proofs
@@ -57,12 +70,20 @@ pub const DEFAULT_CALLDATA: &str = "calldata.bytes";
 pub const DEFAULT_SOL_CODE_AGGREGATED: &str = "evm_deploy_aggr.sol";
 /// Default solidity code for data attestation
 pub const DEFAULT_SOL_CODE_DA: &str = "evm_deploy_da.sol";
+/// Default solidity code for the verifier of packed instances
+pub const DEFAULT_SOL_CODE_PACKED: &str = "evm_deploy_packed.sol";
+/// Default verifier abi for packed instances
+pub const DEFAULT_VERIFIER_PACKED_ABI: &str = "verifier_packed_abi.json";
 /// Default contract address
 pub const DEFAULT_CONTRACT_ADDRESS: &str = "contract.address";
 /// Default contract address for data attestation
 pub const DEFAULT_CONTRACT_ADDRESS_DA: &str = "contract_da.address";
+/// Default contract address for the verifier of packed instances
+pub const DEFAULT_CONTRACT_ADDRESS_PACKED: &str = "contract_packed.address";
 /// Default contract address for vk
 pub const DEFAULT_CONTRACT_ADDRESS_VK: &str = "contract_vk.address";
+/// Default contract address for the verifier registry
+pub const DEFAULT_CONTRACT_ADDRESS_REGISTRY: &str = "contract_registry.address";
 /// Default check mode
 pub const DEFAULT_CHECKMODE: &str = "safe";
 /// Default calibration target
@@ -71,6 +92,8 @@ pub const DEFAULT_CALIBRATION_TARGET: &str = "resources";
 pub const DEFAULT_AGGREGATED_LOGROWS: &str = "23";
 /// Default optimizer runs
 pub const DEFAULT_OPTIMIZER_RUNS: &str = "1";
+/// Default EVM used to estimate verification gas
+pub const DEFAULT_EVM_BACKEND: &str = "revm";
 /// Default fuzz runs
 pub const DEFAULT_FUZZ_RUNS: &str = "10";
 /// Default calibration file
@@ -81,6 +104,16 @@ pub const DEFAULT_LOOKUP_SAFETY_MARGIN: &str = "2";
 pub const DEFAULT_DISABLE_SELECTOR_COMPRESSION: &str = "false";
 /// Default render reusable verifier
 pub const DEFAULT_RENDER_REUSABLE: &str = "false";
+/// Default render a batch verification entrypoint
+pub const DEFAULT_RENDER_BATCH: &str = "false";
+/// Default for leaving a section out of the witness
+pub const DEFAULT_NO_WITNESS_SECTION: &str = "false";
+/// Default for redacting the witness
+pub const DEFAULT_REDACT_WITNESS: &str = "false";
+/// Default number of field elements above which a tensor of a witness is written to its binary sidecar
+pub const DEFAULT_WITNESS_SIDECAR_THRESHOLD: &str = "1048576";
+/// Default for writing every tensor of the witness to its json
+pub const DEFAULT_NO_WITNESS_SIDECAR: &str = "false";
 /// Default contract deployment type
 pub const DEFAULT_CONTRACT_DEPLOYMENT_TYPE: &str = "verifier";
 /// Default VK sol path
@@ -95,6 +128,26 @@ pub const DEFAULT_USE_REDUCED_SRS_FOR_VERIFICATION: &str = "false";
 pub const DEFAULT_ONLY_RANGE_CHECK_REBASE: &str = "false";
 /// Default commitment
 pub const DEFAULT_COMMITMENT: &str = "kzg";
+/// Default number of measured iterations of the bench command
+pub const DEFAULT_BENCH_ITERATIONS: &str = "5";
+/// Default number of warmup iterations of the bench command
+pub const DEFAULT_BENCH_WARMUP: &str = "1";
+/// Default relative error over which the diff command flags a tensor
+pub const DEFAULT_DIFF_THRESHOLD: &str = "0.05";
+/// Default output directory of the pipeline command
+pub const DEFAULT_PIPELINE_DIR: &str = "pipeline";
+/// Default last stage of the pipeline command
+pub const DEFAULT_PIPELINE_STOP_AFTER: &str = "setup";
+/// Default host the serve command listens on
+pub const DEFAULT_SERVER_HOST: &str = "127.0.0.1";
+/// Default port the serve command listens on
+pub const DEFAULT_SERVER_PORT: &str = "8080";
+/// Default number of requests the serve command works on at once
+pub const DEFAULT_SERVER_WORKERS: &str = "2";
+/// Default number of proofs the prove command creates at once from a witness directory
+pub const DEFAULT_PROVE_WORKERS: &str = "1";
+/// Default aggregation of linked proofs
+pub const DEFAULT_LINK_AGGREGATE: &str = "false";
 
 #[cfg(feature = "python-bindings")]
 /// Converts TranscriptType into a PyObject (Required for TranscriptType to be compatible with Python)
@@ -103,6 +156,7 @@ impl IntoPy<PyObject> for TranscriptType {
         match self {
             TranscriptType::Poseidon => "poseidon".to_object(py),
             TranscriptType::EVM => "evm".to_object(py),
+            TranscriptType::Blake => "blake".to_object(py),
         }
     }
 }
@@ -115,6 +169,7 @@ impl<'source> FromPyObject<'source> for TranscriptType {
         match strval.to_lowercase().as_str() {
             "poseidon" => Ok(TranscriptType::Poseidon),
             "evm" => Ok(TranscriptType::EVM),
+            "blake" => Ok(TranscriptType::Blake),
             _ => Err(PyValueError::new_err("Invalid value for TranscriptType")),
         }
     }
@@ -242,6 +297,208 @@ impl From<&str> for ContractType {
 }
 
 
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, PartialOrd)]
+/// Operations on local SRS files
+pub enum SrsAction {
+    /// Trims an SRS down to the minimal file needed for a given number of logrows
+    Trim,
+    /// Checks an SRS against the known public hashes and for internal consistency
+    Verify,
+    /// Lists the SRS files of the cache directory
+    List,
+    /// Downloads an SRS to the cache directory, resuming an interrupted download
+    Fetch,
+    /// Prints the path of the SRS a settings file (or logrows) would use
+    Locate,
+}
+
+impl std::fmt::Display for SrsAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                SrsAction::Trim => "trim",
+                SrsAction::Verify => "verify",
+                SrsAction::List => "list",
+                SrsAction::Fetch => "fetch",
+                SrsAction::Locate => "locate",
+            }
+        )
+    }
+}
+
+impl ToFlags for SrsAction {
+    fn to_flags(&self) -> Vec<String> {
+        vec![format!("{}", self)]
+    }
+}
+
+impl From<&str> for SrsAction {
+    fn from(s: &str) -> Self {
+        match s {
+            "trim" => SrsAction::Trim,
+            "verify" => SrsAction::Verify,
+            "list" => SrsAction::List,
+            "fetch" => SrsAction::Fetch,
+            "locate" => SrsAction::Locate,
+            _ => {
+                log::error!("Invalid value for SrsAction");
+                log::warn!("Defaulting to verify");
+                SrsAction::Verify
+            }
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, PartialOrd)]
+/// Operations on the project configuration file
+pub enum ConfigAction {
+    /// Prints the configuration merged with the defaults of the cli
+    Show,
+}
+
+impl std::fmt::Display for ConfigAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                ConfigAction::Show => "show",
+            }
+        )
+    }
+}
+
+impl ToFlags for ConfigAction {
+    fn to_flags(&self) -> Vec<String> {
+        vec![format!("{}", self)]
+    }
+}
+
+impl From<&str> for ConfigAction {
+    fn from(s: &str) -> Self {
+        match s {
+            "show" => ConfigAction::Show,
+            _ => {
+                log::error!("Invalid value for ConfigAction");
+                log::warn!("Defaulting to show");
+                ConfigAction::Show
+            }
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Default, Serialize, Deserialize, PartialEq, PartialOrd)]
+/// The EVM used to measure verification gas
+pub enum EvmBackend {
+    /// An in-memory revm instance, no node needed
+    #[default]
+    Revm,
+    /// An anvil (or other) node, spun up locally if no rpc url is given
+    Anvil,
+}
+
+impl std::fmt::Display for EvmBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                EvmBackend::Revm => "revm",
+                EvmBackend::Anvil => "anvil",
+            }
+        )
+    }
+}
+
+impl ToFlags for EvmBackend {
+    fn to_flags(&self) -> Vec<String> {
+        vec![format!("{}", self)]
+    }
+}
+
+impl From<&str> for EvmBackend {
+    fn from(s: &str) -> Self {
+        match s {
+            "revm" => EvmBackend::Revm,
+            "anvil" => EvmBackend::Anvil,
+            _ => {
+                log::error!("Invalid value for EvmBackend");
+                log::warn!("Defaulting to revm");
+                EvmBackend::default()
+            }
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Default, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "kebab-case")]
+/// The stages of the pipeline command, in the order they run
+pub enum PipelineStage {
+    /// Generates the uncalibrated settings
+    GenSettings,
+    /// Calibrates the settings on the data
+    CalibrateSettings,
+    /// Compiles the circuit
+    CompileCircuit,
+    /// Fetches the SRS for the calibrated logrows
+    GetSrs,
+    /// Creates the proving and verification keys
+    #[default]
+    Setup,
+}
+
+impl PipelineStage {
+    /// All the stages, in the order they run
+    pub const ALL: [PipelineStage; 5] = [
+        PipelineStage::GenSettings,
+        PipelineStage::CalibrateSettings,
+        PipelineStage::CompileCircuit,
+        PipelineStage::GetSrs,
+        PipelineStage::Setup,
+    ];
+}
+
+impl std::fmt::Display for PipelineStage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                PipelineStage::GenSettings => "gen-settings",
+                PipelineStage::CalibrateSettings => "calibrate-settings",
+                PipelineStage::CompileCircuit => "compile-circuit",
+                PipelineStage::GetSrs => "get-srs",
+                PipelineStage::Setup => "setup",
+            }
+        )
+    }
+}
+
+impl ToFlags for PipelineStage {
+    fn to_flags(&self) -> Vec<String> {
+        vec![format!("{}", self)]
+    }
+}
+
+impl From<&str> for PipelineStage {
+    fn from(s: &str) -> Self {
+        match s {
+            "gen-settings" => PipelineStage::GenSettings,
+            "calibrate-settings" => PipelineStage::CalibrateSettings,
+            "compile-circuit" => PipelineStage::CompileCircuit,
+            "get-srs" => PipelineStage::GetSrs,
+            "setup" => PipelineStage::Setup,
+            _ => {
+                log::error!("Invalid value for PipelineStage");
+                log::warn!("Defaulting to setup");
+                PipelineStage::default()
+            }
+        }
+    }
+}
+
 #[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, PartialOrd)]
 /// wrapper for H160 to make it easy to parse into flag vals
 pub struct H160Flag {
@@ -333,6 +590,52 @@ impl<'source> FromPyObject<'source> for ContractType {
         }
     }
 }
+
+#[cfg(feature = "python-bindings")]
+/// Converts EvmBackend into a PyObject (Required for EvmBackend to be compatible with Python)
+impl IntoPy<PyObject> for EvmBackend {
+    fn into_py(self, py: Python) -> PyObject {
+        match self {
+            EvmBackend::Revm => "revm".to_object(py),
+            EvmBackend::Anvil => "anvil".to_object(py),
+        }
+    }
+}
+
+#[cfg(feature = "python-bindings")]
+/// Obtains EvmBackend from PyObject (Required for EvmBackend to be compatible with Python)
+impl<'source> FromPyObject<'source> for EvmBackend {
+    fn extract(ob: &'source PyAny) -> PyResult<Self> {
+        let trystr = <PyString as PyTryFrom>::try_from(ob)?;
+        let strval = trystr.to_string();
+        match strval.to_lowercase().as_str() {
+            "revm" => Ok(EvmBackend::Revm),
+            "anvil" => Ok(EvmBackend::Anvil),
+            _ => Err(PyValueError::new_err("Invalid value for EvmBackend")),
+        }
+    }
+}
+#[cfg(feature = "python-bindings")]
+/// Converts PipelineStage into a PyObject (Required for PipelineStage to be compatible with Python)
+impl IntoPy<PyObject> for PipelineStage {
+    fn into_py(self, py: Python) -> PyObject {
+        self.to_string().to_object(py)
+    }
+}
+
+#[cfg(feature = "python-bindings")]
+/// Obtains PipelineStage from PyObject (Required for PipelineStage to be compatible with Python)
+impl<'source> FromPyObject<'source> for PipelineStage {
+    fn extract(ob: &'source PyAny) -> PyResult<Self> {
+        let trystr = <PyString as PyTryFrom>::try_from(ob)?;
+        let strval = trystr.to_string();
+        PipelineStage::ALL
+            .into_iter()
+            .find(|stage| stage.to_string() == strval.to_lowercase())
+            .ok_or_else(|| PyValueError::new_err("Invalid value for PipelineStage"))
+    }
+}
+
 // not wasm
 use lazy_static::lazy_static;
 
@@ -400,6 +703,39 @@ pub struct Cli {
     /// If provided, outputs the completion file for given shell
     #[clap(long = "generate", value_parser)]
     pub generator: Option<Shell>,
+    /// Skips the consistency check of the first powers performed when loading an SRS (the full integrity check is run by get-srs, srs trim and srs verify)
+    #[clap(long, global = true)]
+    pub skip_srs_check: bool,
+    /// Never downloads an SRS: a command needing an SRS that isn't cached fails at once, naming the file it expects
+    #[clap(long, global = true)]
+    pub offline: bool,
+    /// The urls to download an SRS from when the download from the SRS url (`EZKL_SRS_URL`) fails, tried in order,
+    /// each followed by the logrows of the SRS as the SRS url. An interrupted download resumes from the next mirror
+    #[clap(long, global = true, value_delimiter = ',')]
+    pub srs_mirrors: Vec<String>,
+    /// Ignores the fields of the settings, input and witness .json files that ezkl doesn't define instead of failing
+    /// on them, as when loading files written for another version of ezkl
+    #[clap(long, global = true)]
+    pub lenient_json: bool,
+    /// Prints a single JSON object on stdout describing the outcome of the command (its status, the artifacts it wrote,
+    /// key metrics and the error if it failed) and sends the logs to stderr. Whether or not this is set, the cli exits
+    /// with 0 on success, 1 on an internal error, 2 on an error in its inputs (as for invalid arguments), 3 when a
+    /// proof fails to verify and 4 when the command runs out of time
+    #[clap(long, global = true)]
+    pub json: bool,
+    /// Cancels the command once it has run for this many seconds: witness generation, synthesis and proving stop at
+    /// their next check, and the files the command created are removed rather than left partially written
+    #[clap(long, global = true)]
+    pub timeout: Option<u64>,
+    /// The project configuration file providing defaults for the arguments of the commands, if None will look for an
+    /// ezkl.toml in the working directory and its parents. Arguments given on the command line take precedence
+    #[clap(long, global = true, value_hint = clap::ValueHint::FilePath)]
+    pub config: Option<PathBuf>,
+    /// Writes the time spent in each phase of the command (model load, quantization, calibration candidates, table
+    /// generation, synthesis, keygen, proving) to a .json profile readable by speedscope (https://www.speedscope.app),
+    /// the fields of the phases (as row counts and sizes) following their names
+    #[clap(long, global = true, value_hint = clap::ValueHint::FilePath)]
+    pub profile_json: Option<PathBuf>,
     #[command(subcommand)]
     #[allow(missing_docs)]
     pub command: Option<Commands>,
@@ -424,7 +760,7 @@ pub enum Commands {
 
     /// Generates the witness from an input file.
     GenWitness {
-        /// The path to the .json data file
+        /// The path to the .json data file (of floats, or of field elements with an `input_scales` entry declaring them already quantized), a .csv file, a png / jpeg image, or a .npy file / .npz archive holding the input arrays
         #[arg(short = 'D', long, default_value = DEFAULT_DATA, value_hint = clap::ValueHint::FilePath)]
         data: Option<PathBuf>,
         /// The path to the compiled model file (generated using the compile-circuit command)
@@ -439,6 +775,85 @@ pub enum Commands {
         /// Path to the srs file (optional - solely used to generate kzg commits)
         #[arg(short = 'P', long, value_hint = clap::ValueHint::FilePath)]
         srs_path: Option<PathBuf>,
+        /// Path to save the dequantized outputs to as a .npy file (optional - models with several outputs need a .npz archive)
+        #[arg(long, value_hint = clap::ValueHint::FilePath)]
+        dequantized_output: Option<PathBuf>,
+        /// Path to save the outputs to as raw field elements, in the .json data format of already quantized inputs with their scales declared (optional - to feed the inputs of a chained circuit)
+        #[arg(long, value_hint = clap::ValueHint::FilePath)]
+        field_output: Option<PathBuf>,
+        /// Path to a .json spec of the csv columns feeding each model input (optional - without it every column of a .csv data file feeds the first input)
+        #[arg(long, value_hint = clap::ValueHint::FilePath)]
+        csv_mapping: Option<PathBuf>,
+        /// Directory of data files to generate a witness for each of, loading the compiled circuit once (optional - replaces the data file)
+        #[arg(long, requires = "output_dir", value_hint = clap::ValueHint::DirPath)]
+        input_dir: Option<PathBuf>,
+        /// Directory to save the witness of each data file in the input directory to, along with a summary.json of their outputs and errors
+        #[arg(long, requires = "input_dir", value_hint = clap::ValueHint::DirPath)]
+        output_dir: Option<PathBuf>,
+        /// Maximum number of threads to run the forward passes of the input directory on (optional - defaults to the available parallelism)
+        #[arg(long, requires = "input_dir")]
+        threads: Option<usize>,
+        /// Decimal places to print the dequantized floats of the witness with (optional - defaults to as many as f64 needs)
+        #[arg(long, value_hint = clap::ValueHint::Other)]
+        precision: Option<usize>,
+        /// Leave the field elements of the inputs and outputs out of the witness, which can then no longer be proven
+        #[arg(long, default_value = DEFAULT_NO_WITNESS_SECTION, action = clap::ArgAction::SetTrue)]
+        no_felts: Option<bool>,
+        /// Leave the dequantized floats of the inputs and outputs out of the witness
+        #[arg(long, default_value = DEFAULT_NO_WITNESS_SECTION, action = clap::ArgAction::SetTrue)]
+        no_rescaled: Option<bool>,
+        /// Leave the processed inputs, params and outputs (hashes and commitments) out of the witness, which can then no longer be proven if any visibility is hashed or polycommit
+        #[arg(long, default_value = DEFAULT_NO_WITNESS_SECTION, action = clap::ArgAction::SetTrue)]
+        no_processed: Option<bool>,
+        /// Number of field elements above which a tensor of the inputs or outputs is written to a binary sidecar next to the witness (`<output>.bin`) rather than to its json, which is much faster to load. The witness is read with its sidecar by every command
+        #[arg(long, default_value = DEFAULT_WITNESS_SIDECAR_THRESHOLD, value_hint = clap::ValueHint::Other)]
+        sidecar_threshold: Option<usize>,
+        /// Write every tensor of the witness to its json, without a binary sidecar
+        #[arg(long, default_value = DEFAULT_NO_WITNESS_SIDECAR, action = clap::ArgAction::SetTrue)]
+        no_sidecar: Option<bool>,
+        /// Path to the .json merkle tree of the dataset the inputs are proven to belong to (generated using the merkle-tree command, required when the input visibility is merkle)
+        #[arg(long, value_hint = clap::ValueHint::FilePath)]
+        merkle_tree: Option<PathBuf>,
+        /// The hex recoverable secp256k1 signature (r, s and the recovery byte) over the digest of the signed inputs, required when the settings sign inputs. The digest is in the `input_signature` of a witness generated without it
+        #[arg(long, value_hint = clap::ValueHint::Other)]
+        signature: Option<String>,
+        /// The private salt, as a hex or decimal field element, the nullifier of the inputs is derived with, required when the settings expose a nullifier. Proofs over the same inputs and salt share their nullifier
+        #[arg(long, value_hint = clap::ValueHint::Other)]
+        salt: Option<String>,
+        /// The digest of the previous step of the session, as a hex or decimal field element, the outputs are chained to (`0` for the first step), required when the settings expose session digests. The digest of this step is in the `session` of the witness
+        #[arg(long, value_hint = clap::ValueHint::Other)]
+        prev_digest: Option<String>,
+        /// The path to write the session digest of this step to, as a json field element, to pass as the `--prev-digest` of the next step
+        #[arg(long, value_hint = clap::ValueHint::FilePath)]
+        session_digest_output: Option<PathBuf>,
+        /// The public key of the recipient the encrypted outputs are encrypted to, as the `x,y` hex or decimal field elements of a Grumpkin point, required when the settings encrypt outputs. The ephemeral randomness is drawn at random and kept in the witness
+        #[arg(long, num_args = 2, value_delimiter = ',', value_names = ["X", "Y"], value_hint = clap::ValueHint::Other)]
+        recipient_key: Option<Vec<String>>,
+        /// The public key to encrypt the blinders of the polycommit commitments to, as the `x,y` hex or decimal field elements of a Grumpkin point, when the settings blind the commitments. Without it the blinders are kept in the clear in the witness
+        #[arg(long, num_args = 2, value_delimiter = ',', value_names = ["X", "Y"], value_hint = clap::ValueHint::Other)]
+        blinding_key: Option<Vec<String>>,
+        /// Write the witness redacted for sharing with a verifier: the inputs and outputs the visibilities keep private, and the other private values, are left out, such that the witness can no longer be proven but its public instances, hashes and commitments are kept
+        #[arg(long, default_value = DEFAULT_REDACT_WITNESS, action = clap::ArgAction::SetTrue)]
+        redact: Option<bool>,
+        /// The age X25519 public key (`age1...`) to encrypt the full witness to within the redacted witness, such that the holder of its identity can recover it (optional - without it the private values are removed)
+        #[arg(long, requires = "redact", value_hint = clap::ValueHint::Other)]
+        redact_recipient: Option<String>,
+        /// The path to the age identity file (generated using age-keygen) to decrypt the compiled circuit with, when it is encrypted (optional - it is decrypted into memory only)
+        #[arg(long, conflicts_with = "input_dir", value_hint = clap::ValueHint::FilePath)]
+        identity: Option<PathBuf>,
+    },
+
+    /// Builds the merkle tree of a dataset, whose root the inputs are proven to belong to when the input visibility is merkle.
+    MerkleTree {
+        /// The path to the .json dataset file, an array of rows each in the `input_data` format of a .json data file
+        #[arg(short = 'D', long, value_hint = clap::ValueHint::FilePath)]
+        dataset: PathBuf,
+        /// The path to the compiled model file (generated using the compile-circuit command)
+        #[arg(short = 'M', long, default_value = DEFAULT_COMPILED_CIRCUIT, value_hint = clap::ValueHint::FilePath)]
+        compiled_circuit: Option<PathBuf>,
+        /// Path to output the .json merkle tree to
+        #[arg(short = 'O', long, default_value = DEFAULT_MERKLE_TREE, value_hint = clap::ValueHint::FilePath)]
+        output: Option<PathBuf>,
     },
 
     /// Produces the proving hyperparameters, from run-args
@@ -452,11 +867,32 @@ pub enum Commands {
         /// proving arguments
         #[clap(flatten)]
         args: RunArgs,
+        /// Path to a .json spec of the preprocessing applied to png / jpeg inputs (optional - stored in the settings so gen-witness and calibrate-settings apply it)
+        #[arg(long, value_hint = clap::ValueHint::FilePath)]
+        image_preprocessing: Option<PathBuf>,
+        /// Paths to the .json mean and std of each input, as `mean.json,std.json` (optional - stored in the settings so file inputs are normalized as `(x - mean) / std` before quantization)
+        #[arg(long, num_args = 2, value_delimiter = ',', value_names = ["MEAN", "STD"], value_hint = clap::ValueHint::FilePath)]
+        normalize: Option<Vec<PathBuf>>,
+        /// Path to a .json spec of the inputs whose signature is verified in the circuit, as `{"inputs": [0], "curve": "secp256k1", "hash": "poseidon"}` (optional - the recovered public key becomes a public instance)
+        #[arg(long, value_hint = clap::ValueHint::FilePath)]
+        input_signature: Option<PathBuf>,
+        /// Expose the nullifier of the inputs and a private salt as the last public instance, such that a verifier can reject a second proof over the same inputs without learning them
+        #[arg(long, default_value = "false", action = clap::ArgAction::SetTrue)]
+        nullifier: Option<bool>,
+        /// Indices of the private outputs to encrypt to a recipient public key in the circuit, as `0,2` (optional - the public key and the ElGamal ciphertext become public instances, the key is supplied to gen-witness)
+        #[arg(long, value_delimiter = ',', value_hint = clap::ValueHint::Other)]
+        encrypted_outputs: Option<Vec<usize>>,
+        /// Fold a private random blinder into each polycommit commitment, such that a low entropy committed tensor can't be guessed from its commitment. The blinders are kept in the witness to reopen the commitments with
+        #[arg(long, default_value = "false", action = clap::ArgAction::SetTrue)]
+        polycommit_blinding: Option<bool>,
+        /// Expose the digest of the previous step of a session and the Poseidon hash of it and of the commitment of the outputs as public instances, such that the proofs of a sequence of steps can't be reordered (the previous digest is supplied to gen-witness)
+        #[arg(long, default_value = "false", action = clap::ArgAction::SetTrue)]
+        session: Option<bool>,
     },
 
     /// Calibrates the proving scale, lookup bits and logrows from a circuit settings file.
         CalibrateSettings {
-        /// The path to the .json calibration data file.
+        /// The path to the .json calibration data file, a .csv file, a png / jpeg image, or a .npy file / .npz archive holding the input arrays.
         #[arg(short = 'D', long, default_value = DEFAULT_CALIBRATION_FILE, value_hint = clap::ValueHint::FilePath)]
         data: Option<PathBuf>,
         /// The path to the .onnx model file
@@ -489,6 +925,9 @@ pub enum Commands {
         // whether to only range check rebases (instead of trying both range check and lookup)
         #[arg(long, default_value = DEFAULT_ONLY_RANGE_CHECK_REBASE, action = clap::ArgAction::SetTrue)]
         only_range_check_rebase: Option<bool>,
+        /// Path to a .json spec of the csv columns feeding each model input (optional - without it every column of a .csv data file feeds the first input)
+        #[arg(long, value_hint = clap::ValueHint::FilePath)]
+        csv_mapping: Option<PathBuf>,
     },
 
     /// Generates a dummy SRS
@@ -521,6 +960,245 @@ pub enum Commands {
         #[arg(long, default_value = None, value_hint = clap::ValueHint::Other)]
         commitment: Option<Commitments>,
     },
+    /// Estimates the proof size, proving time, EVM verification gas and peak memory of keygen and proving of a circuit without running setup. Outputs JSON.
+    Estimate {
+        /// The path to the .onnx model file, used to generate settings if none exist at settings_path
+        #[arg(short = 'M', long, default_value = DEFAULT_MODEL, value_hint = clap::ValueHint::FilePath)]
+        model: Option<PathBuf>,
+        /// The path to the circuit settings .json file (generated using the gen-settings command)
+        #[arg(short = 'S', long, default_value = DEFAULT_SETTINGS, value_hint = clap::ValueHint::FilePath)]
+        settings_path: Option<PathBuf>,
+        /// The path to output the estimate .json file to
+        #[arg(short = 'O', long, value_hint = clap::ValueHint::FilePath)]
+        output: Option<PathBuf>,
+    },
+    /// Profiles the rows, lookups and equality constraints each op of a model lays out, sorted by rows. Outputs JSON.
+    CircuitProfile {
+        /// The path to the .onnx model file
+        #[arg(short = 'M', long, default_value = DEFAULT_MODEL, value_hint = clap::ValueHint::FilePath)]
+        model: Option<PathBuf>,
+        /// proving arguments
+        #[clap(flatten)]
+        args: RunArgs,
+        /// The path to output the profile .json file to
+        #[arg(short = 'O', long, value_hint = clap::ValueHint::FilePath)]
+        output: Option<PathBuf>,
+    },
+    /// Lists the rescales ezkl inserts on its own in a model to keep its fixed point scales consistent, with the node, the scale before and the scale after each, such that they can be audited and overridden through --rescale-overrides. Outputs JSON.
+    ListRescales {
+        /// The path to the .onnx model file
+        #[arg(short = 'M', long, default_value = DEFAULT_MODEL, value_hint = clap::ValueHint::FilePath)]
+        model: Option<PathBuf>,
+        /// proving arguments
+        #[clap(flatten)]
+        args: RunArgs,
+        /// The path to output the rescales .json file to
+        #[arg(short = 'O', long, value_hint = clap::ValueHint::FilePath)]
+        output: Option<PathBuf>,
+    },
+    /// Inspects a compiled circuit without the onnx model it was compiled from: the nodes of the model with their names, ops, input and output shapes, scales and visibility, the lookups and range checks the circuit requires and what its instances hold. Outputs JSON.
+    Inspect {
+        /// The path to the compiled model file (generated using the compile-circuit command)
+        #[arg(default_value = DEFAULT_COMPILED_CIRCUIT, value_hint = clap::ValueHint::FilePath)]
+        compiled_circuit: Option<PathBuf>,
+        /// Only inspect the node of the main graph at this index
+        #[arg(long, value_hint = clap::ValueHint::Other)]
+        node: Option<usize>,
+        /// The path to output the inspection .json file to
+        #[arg(short = 'O', long, value_hint = clap::ValueHint::FilePath)]
+        output: Option<PathBuf>,
+    },
+    /// Prints the JSON schema of the settings, input or witness .json files, generated from the types ezkl loads them into. Files are loaded strictly against it: unless --lenient-json is set, a field the schema doesn't define is an error.
+    Schema {
+        /// The artifact to print the schema of, one of `settings`, `input` or `witness`
+        #[arg(long, value_enum)]
+        artifact: JsonArtifact,
+        /// The path to output the schema .json file to
+        #[arg(short = 'O', long, value_hint = clap::ValueHint::FilePath)]
+        output: Option<PathBuf>,
+    },
+    /// Writes a Markdown or HTML report of a circuit for audits: the fingerprints of the model and settings, the visibility of the inputs and outputs, the instance layout, the rows and lookups of each op, the lookup tables and, if a proof is given, its size and whether it verifies.
+    Report {
+        /// The path to the circuit settings .json file (generated using the gen-settings command)
+        #[arg(short = 'S', long, default_value = DEFAULT_SETTINGS, value_hint = clap::ValueHint::FilePath)]
+        settings_path: Option<PathBuf>,
+        /// The path to the compiled model file (generated using the compile-circuit command)
+        #[arg(short = 'M', long, default_value = DEFAULT_COMPILED_CIRCUIT, value_hint = clap::ValueHint::FilePath)]
+        compiled_circuit: Option<PathBuf>,
+        /// The path to a proof of the circuit to report on
+        #[arg(long, value_hint = clap::ValueHint::FilePath)]
+        proof_path: Option<PathBuf>,
+        /// The path to the verification key to verify the proof with; the proof isn't verified without it
+        #[arg(long, value_hint = clap::ValueHint::FilePath)]
+        vk_path: Option<PathBuf>,
+        /// The path to SRS, if None will use $EZKL_REPO_PATH/srs/kzg{logrows}.srs
+        #[arg(long, value_hint = clap::ValueHint::FilePath)]
+        srs_path: Option<PathBuf>,
+        /// The format of the report
+        #[arg(long, default_value_t = ReportFormat::default(), value_enum)]
+        format: ReportFormat,
+        /// The path to output the report to
+        #[arg(short = 'O', long, value_hint = clap::ValueHint::FilePath)]
+        output: Option<PathBuf>,
+    },
+    /// Describes a verifying key for verifiers implemented outside of ezkl: the columns of the constraint system and their queries, the polynomial identities of the gates, the lookup and shuffle arguments, the columns of the permutation argument, the fixed commitments and what each instance holds, along with the settings the key was generated with.
+    ExportVkDescription {
+        /// The path to the verification key file (generated using the setup command)
+        #[arg(long, default_value = DEFAULT_VK, value_hint = clap::ValueHint::FilePath)]
+        vk_path: Option<PathBuf>,
+        /// The path to the circuit settings .json file (generated using the gen-settings command)
+        #[arg(short = 'S', long, default_value = DEFAULT_SETTINGS, value_hint = clap::ValueHint::FilePath)]
+        settings_path: Option<PathBuf>,
+        /// The format of the description
+        #[arg(long, default_value_t = VkDescriptionFormat::default(), value_enum)]
+        format: VkDescriptionFormat,
+        /// The path to output the description to
+        #[arg(short = 'O', long, value_hint = clap::ValueHint::FilePath)]
+        output: Option<PathBuf>,
+    },
+    /// Checks that the lookup tables of the ops in a settings file hold the outputs the witness is generated with over the lookup range, and reports the largest deviation of each table from an f64 reference of its op. Outputs JSON and fails if any table is inconsistent.
+    CheckTables {
+        /// The path to the circuit settings .json file (generated using the gen-settings command)
+        #[arg(short = 'S', long, default_value = DEFAULT_SETTINGS, value_hint = clap::ValueHint::FilePath)]
+        settings_path: Option<PathBuf>,
+        /// The path to output the report .json file to
+        #[arg(short = 'O', long, value_hint = clap::ValueHint::FilePath)]
+        output: Option<PathBuf>,
+    },
+    /// Runs gen-witness, setup, prove and verify on a compiled circuit a number of times, reporting the wall time and peak RSS of each phase, the proof size and the rows. Outputs JSON.
+    Bench {
+        /// The path to the compiled model file (generated using the compile-circuit command)
+        #[arg(short = 'M', long, default_value = DEFAULT_COMPILED_CIRCUIT, value_hint = clap::ValueHint::FilePath)]
+        compiled_circuit: Option<PathBuf>,
+        /// The path to the .json data file
+        #[arg(short = 'D', long, default_value = DEFAULT_DATA, value_hint = clap::ValueHint::FilePath)]
+        data: Option<PathBuf>,
+        /// The path to load circuit settings .json file from (generated using the gen-settings command)
+        #[arg(short = 'S', long, default_value = DEFAULT_SETTINGS, value_hint = clap::ValueHint::FilePath)]
+        settings_path: Option<PathBuf>,
+        /// The path to the verification key file, written by setup unless it is skipped
+        #[arg(long, default_value = DEFAULT_VK, value_hint = clap::ValueHint::FilePath)]
+        vk_path: Option<PathBuf>,
+        /// The path to the proving key file, written by setup unless it is skipped
+        #[arg(long, default_value = DEFAULT_PK, value_hint = clap::ValueHint::FilePath)]
+        pk_path: Option<PathBuf>,
+        /// The path to SRS, if None will use $EZKL_REPO_PATH/srs/kzg{logrows}.srs
+        #[arg(long, value_hint = clap::ValueHint::FilePath)]
+        srs_path: Option<PathBuf>,
+        /// The number of measured iterations
+        #[arg(short = 'N', long, default_value = DEFAULT_BENCH_ITERATIONS, value_hint = clap::ValueHint::Other)]
+        iterations: Option<usize>,
+        /// The number of iterations run before the measured ones
+        #[arg(long, default_value = DEFAULT_BENCH_WARMUP, value_hint = clap::ValueHint::Other)]
+        warmup: Option<usize>,
+        /// Reuse the keys at the vk and pk paths instead of running setup
+        #[arg(long, default_value = "false", action = clap::ArgAction::SetTrue)]
+        skip_setup: Option<bool>,
+        /// The path to output the bench .json file to
+        #[arg(short = 'O', long, value_hint = clap::ValueHint::FilePath)]
+        output: Option<PathBuf>,
+    },
+    /// Compares the quantized pass of a witness to the float pass of the model over the same data, reporting the absolute and relative error of each output (and of each node with --verbose) and flagging those over a threshold. Outputs JSON.
+    Diff {
+        /// The path to the .onnx model file
+        #[arg(short = 'M', long, default_value = DEFAULT_MODEL, value_hint = clap::ValueHint::FilePath)]
+        model: Option<PathBuf>,
+        /// The path to load circuit settings .json file from (generated using the gen-settings command)
+        #[arg(short = 'S', long, default_value = DEFAULT_SETTINGS, value_hint = clap::ValueHint::FilePath)]
+        settings_path: Option<PathBuf>,
+        /// The path to the .json data file the witness was generated from
+        #[arg(short = 'D', long, default_value = DEFAULT_DATA, value_hint = clap::ValueHint::FilePath)]
+        data: Option<PathBuf>,
+        /// The path to the .json witness file (generated using the gen-witness command)
+        #[arg(short = 'W', long, default_value = DEFAULT_WITNESS, value_hint = clap::ValueHint::FilePath)]
+        witness: Option<PathBuf>,
+        /// Also compare the output of each node, not only the outputs of the model
+        #[arg(long, default_value = "false", action = clap::ArgAction::SetTrue)]
+        verbose: Option<bool>,
+        /// The relative error (the largest absolute error over the largest float magnitude) over which a tensor is flagged
+        #[arg(long, default_value = DEFAULT_DIFF_THRESHOLD, value_hint = clap::ValueHint::Other)]
+        threshold: Option<f64>,
+        /// Path to a .json spec of the csv columns feeding each model input (optional - without it every column of a .csv data file feeds the first input)
+        #[arg(long, value_hint = clap::ValueHint::FilePath)]
+        csv_mapping: Option<PathBuf>,
+        /// The path to output the diff .json file to
+        #[arg(short = 'O', long, value_hint = clap::ValueHint::FilePath)]
+        output: Option<PathBuf>,
+    },
+    /// Runs gen-settings, calibrate-settings, compile-circuit, get-srs and setup in sequence, writing the artifacts to an output directory with consistent names. Stages whose inputs and outputs are unchanged since their last run are skipped. Outputs JSON.
+    Pipeline {
+        /// The path to the .onnx model file
+        #[arg(short = 'M', long, default_value = DEFAULT_MODEL, value_hint = clap::ValueHint::FilePath)]
+        model: Option<PathBuf>,
+        /// The path to the .json data file, used to calibrate the settings
+        #[arg(short = 'D', long, default_value = DEFAULT_DATA, value_hint = clap::ValueHint::FilePath)]
+        data: Option<PathBuf>,
+        /// The directory to write the artifacts to
+        #[arg(short = 'O', long, default_value = DEFAULT_PIPELINE_DIR, value_hint = clap::ValueHint::DirPath)]
+        output_dir: Option<PathBuf>,
+        /// proving arguments
+        #[clap(flatten)]
+        args: RunArgs,
+        /// Target for calibration. Set to "resources" to optimize for computational resource. Otherwise, set to "accuracy" to optimize for accuracy.
+        #[arg(long = "target", default_value = DEFAULT_CALIBRATION_TARGET, value_hint = clap::ValueHint::Other)]
+        target: CalibrationTarget,
+        /// The path to SRS, if None will use $EZKL_REPO_PATH/srs/kzg{logrows}.srs
+        #[arg(long, value_hint = clap::ValueHint::FilePath)]
+        srs_path: Option<PathBuf>,
+        /// The last stage to run, one of `gen-settings`, `calibrate-settings`, `compile-circuit`, `get-srs` or `setup`
+        #[arg(long, default_value = DEFAULT_PIPELINE_STOP_AFTER, value_hint = clap::ValueHint::Other)]
+        stop_after: PipelineStage,
+    },
+    #[cfg(feature = "server")]
+    /// Serves gen-witness, prove and verify over HTTP, keeping the compiled circuit, proving key and SRS of each set of artifacts loaded between requests. Each subdirectory of the artifacts directory holding a model.compiled, pk.key and vk.key (such as the output of the pipeline command) is a set, selected per request by its fingerprint.
+    Serve {
+        /// The host to listen on
+        #[arg(long, default_value = DEFAULT_SERVER_HOST, value_hint = clap::ValueHint::Hostname)]
+        host: Option<String>,
+        /// The port to listen on
+        #[arg(long, default_value = DEFAULT_SERVER_PORT, value_hint = clap::ValueHint::Other)]
+        port: Option<u16>,
+        /// The directory holding a subdirectory for each set of artifacts
+        #[arg(long, value_hint = clap::ValueHint::DirPath)]
+        artifacts: PathBuf,
+        /// The number of requests worked on at once, the others wait for a worker
+        #[arg(long, default_value = DEFAULT_SERVER_WORKERS, value_hint = clap::ValueHint::Other)]
+        workers: Option<usize>,
+    },
+    /// Manages the SRS files of the cache directory ($EZKL_REPO_PATH/srs), which the other commands use when `--srs-path` is omitted.
+    /// `list` shows the cached files, `fetch` downloads one (resuming an interrupted download), `verify` checks its integrity,
+    /// `trim` produces a minimal SRS for the given logrows and `locate` prints the path a settings file would use.
+    #[command(name = "srs")]
+    Srs {
+        /// The operation to perform, one of `list`, `fetch`, `verify`, `trim` or `locate`
+        #[arg(value_hint = clap::ValueHint::Other)]
+        action: SrsAction,
+        /// The path to the source SRS file, if None will use the cached SRS for the logrows
+        #[arg(long, value_hint = clap::ValueHint::FilePath)]
+        srs_path: Option<PathBuf>,
+        /// Number of logrows to trim to (or of the SRS to fetch, verify or locate)
+        #[arg(long, value_hint = clap::ValueHint::Other)]
+        logrows: Option<u32>,
+        /// The path to the settings file the logrows and commitment are read from, when they aren't given
+        #[arg(short = 'S', long, value_hint = clap::ValueHint::FilePath)]
+        settings_path: Option<PathBuf>,
+        /// The path to output the trimmed SRS (or the json list of the cached files) to, if None will use $EZKL_REPO_PATH/srs/kzg{logrows}.srs
+        #[arg(short = 'O', long, value_hint = clap::ValueHint::FilePath)]
+        output: Option<PathBuf>,
+        /// Commitment used
+        #[arg(long, value_hint = clap::ValueHint::Other)]
+        commitment: Option<Commitments>,
+    },
+    /// Shows the effective project configuration (ezkl.toml), with relative paths resolved against its directory
+    #[command(name = "config")]
+    Config {
+        /// The operation to perform, `show`
+        #[arg(value_hint = clap::ValueHint::Other)]
+        action: ConfigAction,
+        /// The command to show every argument of, with its value from the configuration or its default
+        #[arg(value_hint = clap::ValueHint::Other)]
+        command: Option<String>,
+    },
     /// Loads model and input and runs mock prover (for testing)
     Mock {
         /// The path to the .json witness file (generated using the gen-witness command)
@@ -529,6 +1207,36 @@ pub enum Commands {
         /// The path to the compiled model file (generated using the compile-circuit command)
         #[arg(short = 'M', long, default_value = DEFAULT_COMPILED_CIRCUIT, value_hint = clap::ValueHint::FilePath)]
         model: Option<PathBuf>,
+        /// The hex or decimal secret key the blinders of the polycommit commitments are encrypted to, when they are encrypted in the witness
+        #[arg(long, value_hint = clap::ValueHint::Other)]
+        blinding_secret_key: Option<String>,
+    },
+
+    /// Converts a witness between pure json and json with its large tensors in a binary sidecar (see the --sidecar-threshold of gen-witness), the sidecar of the witness read being next to it
+    ConvertWitness {
+        /// The path to the witness file to convert
+        #[arg(short = 'W', long, default_value = DEFAULT_WITNESS, value_hint = clap::ValueHint::FilePath)]
+        witness: Option<PathBuf>,
+        /// The path to write the converted witness to, its sidecar (if any) to `<output>.bin`
+        #[arg(short = 'O', long, value_hint = clap::ValueHint::FilePath)]
+        output: PathBuf,
+        /// Number of field elements above which a tensor of the inputs or outputs is written to the sidecar (optional - without it every tensor is written to the json)
+        #[arg(long, value_hint = clap::ValueHint::Other)]
+        sidecar_threshold: Option<usize>,
+    },
+
+    /// Validates a witness against a compiled circuit with the mock prover, attributing each failing constraint to
+    /// the node and op of the model it stems from
+    ValidateWitness {
+        /// The path to the .json witness file (generated using the gen-witness command)
+        #[arg(short = 'W', long, default_value = DEFAULT_WITNESS, value_hint = clap::ValueHint::FilePath)]
+        witness: Option<PathBuf>,
+        /// The path to the compiled model file (generated using the compile-circuit command)
+        #[arg(short = 'M', long, default_value = DEFAULT_COMPILED_CIRCUIT, value_hint = clap::ValueHint::FilePath)]
+        model: Option<PathBuf>,
+        /// The hex or decimal secret key the blinders of the polycommit commitments are encrypted to, when they are encrypted in the witness
+        #[arg(long, value_hint = clap::ValueHint::Other)]
+        blinding_secret_key: Option<String>,
     },
 
     /// Mock aggregate proofs
@@ -618,6 +1326,31 @@ pub enum Commands {
         /// The path to load circuit settings .json file from (generated using the gen-settings command)
         #[arg(short = 'S', long, default_value = DEFAULT_SETTINGS, value_hint = clap::ValueHint::FilePath)]
         settings_path: Option<PathBuf>,
+        /// The path to a .safetensors file whose tensors override the initializers of the model of the same names (and shapes and dtypes) before they are quantized, the graph and the layout of the settings being kept. Its sha256 is recorded in the settings, which are saved back to settings_path
+        #[arg(long, value_hint = clap::ValueHint::FilePath)]
+        weights: Option<PathBuf>,
+        /// The age X25519 public key (`age1...`), or a file holding it, to encrypt the compiled circuit to, such that its weights are never written to disk in the clear and it can only be loaded with the identity of the key (--identity)
+        #[arg(long, value_hint = clap::ValueHint::Other)]
+        encrypt_to: Option<String>,
+    },
+    /// Compiles the circuits of a model at the batch sizes of the powers of two up to a max, masking the samples of each batch, at the same logrows such that they share the SRS and the commitments of the params. Setup and prove with --batch-family then create the keys of each member, and prove the samples of some data with the smallest member they fit, padded with the padding sample
+    #[command(name = "compile-batch-family", arg_required_else_help = true)]
+    CompileBatchFamily {
+        /// The path to the .onnx model file
+        #[arg(short = 'M', long, default_value = DEFAULT_MODEL, value_hint = clap::ValueHint::FilePath)]
+        model: Option<PathBuf>,
+        /// The path to load circuit settings .json file from (generated using the gen-settings command), whose run args the members are compiled with at their batch size
+        #[arg(short = 'S', long, default_value = DEFAULT_SETTINGS, value_hint = clap::ValueHint::FilePath)]
+        settings_path: Option<PathBuf>,
+        /// The path to the .json data file of the single sample the batches are padded with, whose outputs are masked out of the instances
+        #[arg(long, value_hint = clap::ValueHint::FilePath)]
+        padding: PathBuf,
+        /// The largest batch size of the family
+        #[arg(long, default_value = DEFAULT_MAX_BATCH_SIZE, value_hint = clap::ValueHint::Other)]
+        max_batch_size: Option<usize>,
+        /// The directory to save the settings and compiled circuits of the members to, along with a family.json of the family
+        #[arg(short = 'O', long, default_value = DEFAULT_BATCH_FAMILY_DIR, value_hint = clap::ValueHint::DirPath)]
+        output_dir: Option<PathBuf>,
     },
     /// Creates pk and vk
     Setup {
@@ -639,6 +1372,24 @@ pub enum Commands {
         /// compress selectors
         #[arg(long, default_value = DEFAULT_DISABLE_SELECTOR_COMPRESSION, action = clap::ArgAction::SetTrue)]
         disable_selector_compression: Option<bool>,
+        /// Directory of a family of batch sizes (generated using the compile-batch-family command) to create the keys of each member in (optional - replaces the compiled circuit and keys)
+        #[arg(long, value_hint = clap::ValueHint::DirPath)]
+        batch_family: Option<PathBuf>,
+        /// The path to the age identity file (generated using age-keygen) to decrypt the compiled circuit with, when it is encrypted. The proving key is then encrypted to the public key of the identity, the verification key is left in the clear
+        #[arg(long, conflicts_with = "batch_family", value_hint = clap::ValueHint::FilePath)]
+        identity: Option<PathBuf>,
+    },
+    /// Converts a proving key to the memory-mappable layout, with page-aligned sections for the verifying key, evaluation domain, fixed columns and permutations, which is mapped and decoded in place when proving (set EZKL_PK_PREFAULT=1 to fault it in upfront)
+    ConvertPk {
+        /// The path to the compiled model file (generated using the compile-circuit command)
+        #[arg(short = 'M', long, default_value = DEFAULT_COMPILED_CIRCUIT, value_hint = clap::ValueHint::FilePath)]
+        compiled_circuit: Option<PathBuf>,
+        /// The path to the proving key file to convert
+        #[arg(long, default_value = DEFAULT_PK, value_hint = clap::ValueHint::FilePath)]
+        pk_path: Option<PathBuf>,
+        /// The path to output the memory-mappable proving key to
+        #[arg(long, default_value = DEFAULT_PK_MMAP, value_hint = clap::ValueHint::FilePath)]
+        output: Option<PathBuf>,
     },
         /// Deploys a test contact that the data attester reads from and creates a data attestation formatted input.json file that contains call data information
     #[command(arg_required_else_help = true)]
@@ -676,6 +1427,18 @@ pub enum Commands {
         /// RPC URL for an Ethereum node, if None will use Anvil but WON'T persist state
         #[arg(short = 'U', long, value_hint = clap::ValueHint::Url)]
         rpc_url: Option<String>,
+    },
+    /// Writes the commitments of each commitment group of the polycommit params of a witness (generated with the verifying key and srs) to <output-dir>/<group>.json, to be swapped into proofs and checked by the verifier group by group
+    CommitParamGroups {
+        /// The path to the witness file
+        #[arg(short = 'W', long, default_value = DEFAULT_WITNESS, value_hint = clap::ValueHint::FilePath)]
+        witness_path: Option<PathBuf>,
+        /// The path to load circuit settings .json file from (generated using the gen-settings command)
+        #[arg(short = 'S', long, default_value = DEFAULT_SETTINGS, value_hint = clap::ValueHint::FilePath)]
+        settings_path: Option<PathBuf>,
+        /// The directory the commitments of the groups are written to
+        #[arg(short = 'O', long, default_value = DEFAULT_PARAM_GROUPS_DIR, value_hint = clap::ValueHint::DirPath)]
+        output_dir: Option<PathBuf>,
     },
         /// Swaps the positions in the transcript that correspond to commitments
     SwapProofCommitments {
@@ -685,6 +1448,9 @@ pub enum Commands {
         /// The path to the witness file
         #[arg(short = 'W', long, default_value = DEFAULT_WITNESS, value_hint = clap::ValueHint::FilePath)]
         witness_path: Option<PathBuf>,
+        /// The paths to the commitments of groups of params (generated using the commit-param-groups command). If set only the commitments of these groups are swapped, and the witness isn't read
+        #[arg(long, value_delimiter = ',', value_hint = clap::ValueHint::FilePath)]
+        group_commitments: Vec<PathBuf>,
     },
 
         /// Loads model, data, and creates proof
@@ -713,9 +1479,33 @@ pub enum Commands {
             value_hint = clap::ValueHint::Other
         )]
         proof_type: ProofType,
+        /// The transcript to use (poseidon, blake or evm). Defaults to poseidon for `for-aggr` proofs and evm otherwise
+        #[arg(long, value_enum, value_hint = clap::ValueHint::Other)]
+        transcript: Option<TranscriptType>,
         /// run sanity checks during calculations (safe or unsafe)
         #[arg(long, default_value = DEFAULT_CHECKMODE, value_hint = clap::ValueHint::Other)]
         check_mode: Option<CheckMode>,
+        /// The hex or decimal secret key the blinders of the polycommit commitments are encrypted to, when they are encrypted in the witness
+        #[arg(long, value_hint = clap::ValueHint::Other)]
+        blinding_secret_key: Option<String>,
+        /// Directory of witness files to create a proof for each of, loading the proving key and SRS once (optional - replaces the witness file)
+        #[arg(long, requires = "proof_dir", value_hint = clap::ValueHint::DirPath)]
+        witness_dir: Option<PathBuf>,
+        /// Directory to save the proof of each witness in the witness directory to, along with a summary.json of their errors
+        #[arg(long, requires = "witness_dir", value_hint = clap::ValueHint::DirPath)]
+        proof_dir: Option<PathBuf>,
+        /// Number of proofs of the witness directory to create at once, lowered to as many as fit in the available memory
+        #[arg(long, requires = "witness_dir", default_value = DEFAULT_PROVE_WORKERS, value_hint = clap::ValueHint::Other)]
+        workers: Option<usize>,
+        /// Directory of a family of batch sizes (generated using the compile-batch-family command) to prove the samples of the data with, using the smallest member they fit (optional - replaces the witness, compiled circuit and proving key)
+        #[arg(long, requires = "data", conflicts_with = "witness_dir", value_hint = clap::ValueHint::DirPath)]
+        batch_family: Option<PathBuf>,
+        /// The path to the .json data file of the samples to prove with the batch family, padded to the batch size of the member
+        #[arg(short = 'D', long, requires = "batch_family", value_hint = clap::ValueHint::FilePath)]
+        data: Option<PathBuf>,
+        /// The path to the age identity file (generated using age-keygen) to decrypt the compiled circuit and proving key with, when they are encrypted (optional - they are decrypted into memory only)
+        #[arg(long, conflicts_with_all = ["witness_dir", "batch_family"], value_hint = clap::ValueHint::FilePath)]
+        identity: Option<PathBuf>,
     },
         /// Encodes a proof into evm calldata
     #[command(name = "encode-evm-calldata")]
@@ -751,6 +1541,15 @@ pub enum Commands {
         /// Whether the to render the verifier as reusable or not. If true, you will need to deploy a VK artifact, passing it as part of the calldata to the verifier.
         #[arg(long, default_value = DEFAULT_RENDER_REUSABLE, action = clap::ArgAction::SetTrue)]
         reusable: Option<bool>,
+        /// Whether to add a `verifyBatch(bytes[] proofs, uint256[][] instances)` entrypoint that verifies several proofs of this circuit in one transaction
+        #[arg(long, default_value = DEFAULT_RENDER_BATCH, action = clap::ArgAction::SetTrue)]
+        batch: Option<bool>,
+        /// If set, writes a Foundry project to this directory with the verifier, a test checking the fixture proof against it, and a deployment script
+        #[arg(long, requires = "fixture_proof_path", value_hint = clap::ValueHint::DirPath)]
+        foundry_dir: Option<PathBuf>,
+        /// The proof (generated using the prove command with the evm transcript) to export as the fixture of the Foundry tests
+        #[arg(long, value_hint = clap::ValueHint::FilePath)]
+        fixture_proof_path: Option<PathBuf>,
     },
         /// Creates an Evm verifier artifact for a single proof to be used by the reusable verifier
     #[command(name = "create-evm-vka")]
@@ -793,6 +1592,22 @@ pub enum Commands {
         /// The path to the witness file. This is needed for proof swapping for kzg commitments.
         #[arg(short = 'W', long, default_value = DEFAULT_WITNESS, value_hint = clap::ValueHint::FilePath)]
         witness: Option<PathBuf>,
+        /// The path to an instance_bindings.json file, binding public instances to storage slots or view calls whose values they must equal
+        #[arg(long, value_hint = clap::ValueHint::FilePath)]
+        instance_bindings: Option<PathBuf>,
+    },
+        /// Creates an Evm contract verifying proofs with packed instances, which re-derives the digests of the packed tensors from their values in calldata, or unpacks the values of the outputs packed to limbs (with instance-limbs) from the instances once the proof is verified
+    #[command(name = "create-evm-packed")]
+    CreateEvmPacked {
+        /// The path to load circuit settings .json file from (generated using the gen-settings command with a pack-public-threshold or instance-limbs)
+        #[arg(short = 'S', long, default_value = DEFAULT_SETTINGS, value_hint = clap::ValueHint::FilePath)]
+        settings_path: Option<PathBuf>,
+        /// The path to output the Solidity code
+        #[arg(long, default_value = DEFAULT_SOL_CODE_PACKED, value_hint = clap::ValueHint::FilePath)]
+        sol_code_path: Option<PathBuf>,
+        /// The path to output the Solidity verifier ABI
+        #[arg(long, default_value = DEFAULT_VERIFIER_PACKED_ABI, value_hint = clap::ValueHint::FilePath)]
+        abi_path: Option<PathBuf>,
     },
 
         /// Creates an Evm verifier for an aggregate proof
@@ -837,6 +1652,75 @@ pub enum Commands {
         /// Reduce SRS logrows to the number of instances rather than the number of logrows used for proofs (only works if the srs were generated in the same ceremony)
         #[arg(long, default_value = DEFAULT_USE_REDUCED_SRS_FOR_VERIFICATION, action = clap::ArgAction::SetTrue)]
         reduced_srs: Option<bool>,
+        /// If set, reject proofs that were not generated with this transcript
+        #[arg(long, value_enum, value_hint = clap::ValueHint::Other)]
+        transcript: Option<TranscriptType>,
+        /// The paths to the commitments of groups of params (generated using the commit-param-groups command) the proof is checked against, each group on its own
+        #[arg(long, value_delimiter = ',', value_hint = clap::ValueHint::FilePath)]
+        group_commitments: Vec<PathBuf>,
+    },
+    /// Checks that the hashed/public or polycommit outputs of each proof of a chain of circuits are the inputs of the next, which must carry the same visibility. Optionally aggregates the proofs (generated with --proof-type=for-aggr) into one proof that also constrains them to match. Outputs the links as JSON.
+    LinkProofs {
+        /// The paths to the proofs of the chain, in order
+        #[arg(long, required = true, value_delimiter = ',', allow_hyphen_values = true, value_hint = clap::ValueHint::FilePath)]
+        proof_paths: Vec<PathBuf>,
+        /// The paths to the circuit settings .json files of the proofs, in the same order
+        #[arg(long, required = true, value_delimiter = ',', allow_hyphen_values = true, value_hint = clap::ValueHint::FilePath)]
+        settings_paths: Vec<PathBuf>,
+        /// Aggregates the linked proofs into one proof binding them
+        #[arg(long, default_value = DEFAULT_LINK_AGGREGATE, action = clap::ArgAction::SetTrue)]
+        aggregate: Option<bool>,
+        /// The path to output the aggregated proof to
+        #[arg(long, default_value = DEFAULT_PROOF_AGGREGATED, value_hint = clap::ValueHint::FilePath)]
+        aggregated_proof_path: Option<PathBuf>,
+        /// The path to output the verification key of the aggregation circuit to, which depends on the links
+        #[arg(long, default_value = DEFAULT_VK_AGGREGATED, value_hint = clap::ValueHint::FilePath)]
+        vk_path: Option<PathBuf>,
+        /// The path to output the proving key of the aggregation circuit to
+        #[arg(long, default_value = DEFAULT_PK_AGGREGATED, value_hint = clap::ValueHint::FilePath)]
+        pk_path: Option<PathBuf>,
+        /// The path to SRS, if None will use $EZKL_REPO_PATH/srs/kzg{logrows}.srs
+        #[arg(long, value_hint = clap::ValueHint::FilePath)]
+        srs_path: Option<PathBuf>,
+        /// logrows used for aggregation circuit
+        #[arg(long, default_value = DEFAULT_AGGREGATED_LOGROWS, value_hint = clap::ValueHint::Other)]
+        logrows: Option<u32>,
+        /// The path to output the links .json file to
+        #[arg(short = 'O', long, value_hint = clap::ValueHint::FilePath)]
+        output: Option<PathBuf>,
+    },
+    /// Checks that the proofs of a session (see `gen-settings --session`) are chained in order, and optionally aggregates them into one proof binding the chain
+    CheckSession {
+        /// The paths to the proofs of the session, in order
+        #[arg(long, required = true, value_delimiter = ',', allow_hyphen_values = true, value_hint = clap::ValueHint::FilePath)]
+        proof_paths: Vec<PathBuf>,
+        /// The paths to the circuit settings .json files of the proofs, in the same order, or a single one for every proof
+        #[arg(long, required = true, value_delimiter = ',', allow_hyphen_values = true, value_hint = clap::ValueHint::FilePath)]
+        settings_paths: Vec<PathBuf>,
+        /// The digest the first proof is chained to, as a hex string, if None the session may continue another
+        #[arg(long, allow_hyphen_values = true, value_hint = clap::ValueHint::Other)]
+        genesis_digest: Option<String>,
+        /// Aggregates the proofs of the session into one proof binding the chain
+        #[arg(long, default_value = DEFAULT_LINK_AGGREGATE, action = clap::ArgAction::SetTrue)]
+        aggregate: Option<bool>,
+        /// The path to output the aggregated proof to
+        #[arg(long, default_value = DEFAULT_PROOF_AGGREGATED, value_hint = clap::ValueHint::FilePath)]
+        aggregated_proof_path: Option<PathBuf>,
+        /// The path to output the verification key of the aggregation circuit to, which depends on the number of proofs
+        #[arg(long, default_value = DEFAULT_VK_AGGREGATED, value_hint = clap::ValueHint::FilePath)]
+        vk_path: Option<PathBuf>,
+        /// The path to output the proving key of the aggregation circuit to
+        #[arg(long, default_value = DEFAULT_PK_AGGREGATED, value_hint = clap::ValueHint::FilePath)]
+        pk_path: Option<PathBuf>,
+        /// The path to SRS, if None will use $EZKL_REPO_PATH/srs/kzg{logrows}.srs
+        #[arg(long, value_hint = clap::ValueHint::FilePath)]
+        srs_path: Option<PathBuf>,
+        /// logrows used for aggregation circuit
+        #[arg(long, default_value = DEFAULT_AGGREGATED_LOGROWS, value_hint = clap::ValueHint::Other)]
+        logrows: Option<u32>,
+        /// The path to output the digests of the steps .json file to
+        #[arg(short = 'O', long, value_hint = clap::ValueHint::FilePath)]
+        output: Option<PathBuf>,
     },
     /// Verifies an aggregate proof, returning accept or reject
     VerifyAggr {
@@ -858,8 +1742,12 @@ pub enum Commands {
         /// commitment
         #[arg(long, default_value = DEFAULT_COMMITMENT, value_hint = clap::ValueHint::Other)]
         commitment: Option<Commitments>,
+        /// If set, reject proofs that were not generated with this transcript
+        #[arg(long, value_enum, value_hint = clap::ValueHint::Other)]
+        transcript: Option<TranscriptType>,
     },
         /// Deploys an evm contract (verifier, reusable verifier, or vk artifact) that is generated by ezkl
+    #[command(alias = "deploy-evm-verifier")]
     DeployEvm {
         /// The path to the Solidity code (generated using the create-evm-verifier command)
         #[arg(long, default_value = DEFAULT_SOL_CODE, value_hint = clap::ValueHint::FilePath)]
@@ -879,6 +1767,44 @@ pub enum Commands {
         /// Contract type to be deployed
         #[arg(long = "contract-type", short = 'C', default_value = DEFAULT_CONTRACT_DEPLOYMENT_TYPE, value_hint = clap::ValueHint::Other)]
         contract: ContractType,
+        /// The address of a verifier registry (deployed using the deploy-evm-registry command) to register the deployed verifier in, under the fingerprint of the circuit settings
+        #[arg(long, value_hint = clap::ValueHint::Other)]
+        registry: Option<H160Flag>,
+        /// The path to load circuit settings .json file from, fingerprinted to register the verifier in the registry
+        #[arg(long, default_value = DEFAULT_SETTINGS, value_hint = clap::ValueHint::FilePath)]
+        settings_path: Option<PathBuf>,
+    },
+    /// Deploys a registry mapping the fingerprints of circuit settings to their verifiers, owned by the deploying account
+    #[command(name = "deploy-evm-registry")]
+    DeployEvmRegistry {
+        /// RPC URL for an Ethereum node, if None will use Anvil but WON'T persist state
+        #[arg(short = 'U', long, value_hint = clap::ValueHint::Url)]
+        rpc_url: Option<String>,
+        #[arg(long, default_value = DEFAULT_CONTRACT_ADDRESS_REGISTRY, value_hint = clap::ValueHint::Other)]
+        /// The path to output the contract address
+        addr_path: Option<PathBuf>,
+        /// The optimizer runs to set on the registry. Lower values optimize for deployment cost, while higher values optimize for gas cost.
+        #[arg(long, default_value = DEFAULT_OPTIMIZER_RUNS, value_hint = clap::ValueHint::Other)]
+        optimizer_runs: usize,
+        /// Private secp256K1 key in hex format, 64 chars, no 0x prefix, of the account signing transactions. If None the private key will be generated by Anvil
+        #[arg(short = 'P', long, value_hint = clap::ValueHint::Other)]
+        private_key: Option<String>,
+    },
+    /// Resolves the verifier registered in a verifier registry for the circuit settings
+    #[command(name = "resolve-evm-verifier", arg_required_else_help = true)]
+    ResolveEvmVerifier {
+        /// The verifier registry's address
+        #[arg(long, value_hint = clap::ValueHint::Other)]
+        registry: H160Flag,
+        /// The path to load circuit settings .json file from (generated using the gen-settings command)
+        #[arg(long, default_value = DEFAULT_SETTINGS, value_hint = clap::ValueHint::FilePath)]
+        settings_path: Option<PathBuf>,
+        /// RPC URL for an Ethereum node, if None will use Anvil but WON'T persist state
+        #[arg(short = 'U', long, value_hint = clap::ValueHint::Url)]
+        rpc_url: Option<String>,
+        /// The path to output the verifier address to
+        #[arg(long, value_hint = clap::ValueHint::FilePath)]
+        addr_path: Option<PathBuf>,
     },
         /// Deploys an evm verifier that allows for data attestation
     #[command(name = "deploy-evm-da")]
@@ -904,6 +1830,44 @@ pub enum Commands {
         /// Private secp256K1 key in hex format, 64 chars, no 0x prefix, of the account signing transactions. If None the private key will be generated by Anvil
         #[arg(short = 'P', long, value_hint = clap::ValueHint::Other)]
         private_key: Option<String>,
+    },
+    /// Deploys the contract verifying proofs with packed instances, which calls a single model verifier (deployed using the deploy-evm command)
+    #[command(name = "deploy-evm-packed", arg_required_else_help = true)]
+    DeployEvmPacked {
+        /// The path to the Solidity code (generated using the create-evm-packed command)
+        #[arg(long, default_value = DEFAULT_SOL_CODE_PACKED, value_hint = clap::ValueHint::FilePath)]
+        sol_code_path: Option<PathBuf>,
+        /// The address of the verifier the proofs are verified with
+        #[arg(long, value_hint = clap::ValueHint::Other)]
+        addr_verifier: H160Flag,
+        /// RPC URL for an Ethereum node, if None will use Anvil but WON'T persist state
+        #[arg(short = 'U', long, value_hint = clap::ValueHint::Url)]
+        rpc_url: Option<String>,
+        #[arg(long, default_value = DEFAULT_CONTRACT_ADDRESS_PACKED, value_hint = clap::ValueHint::FilePath)]
+        /// The path to output the contract address
+        addr_path: Option<PathBuf>,
+        /// The optimizer runs to set on the contract. Lower values optimize for deployment cost, while higher values optimize for gas cost.
+        #[arg(long, default_value = DEFAULT_OPTIMIZER_RUNS, value_hint = clap::ValueHint::Other)]
+        optimizer_runs: usize,
+        /// Private secp256K1 key in hex format, 64 chars, no 0x prefix, of the account signing transactions. If None the private key will be generated by Anvil
+        #[arg(short = 'P', long, value_hint = clap::ValueHint::Other)]
+        private_key: Option<String>,
+    },
+    /// Snapshots the account calls of a deployed data attestation contract at the current block and pins the on-chain sources of the data file to that block
+    #[command(name = "pin-evm-da", arg_required_else_help = true)]
+    PinEvmDataAttestation {
+        /// The data attestation contract's address
+        #[arg(long, value_hint = clap::ValueHint::Other)]
+        addr_da: H160Flag,
+        /// The path to the .json data file with the on-chain sources to pin, it is updated in place with the pinned block
+        #[arg(short = 'D', long, default_value = DEFAULT_DATA, value_hint = clap::ValueHint::FilePath)]
+        data: Option<PathBuf>,
+        /// RPC URL for an Ethereum node, if None will use Anvil but WON'T persist state
+        #[arg(short = 'U', long, value_hint = clap::ValueHint::Url)]
+        rpc_url: Option<String>,
+        /// Private secp256K1 key in hex format, 64 chars, no 0x prefix, of the admin account of the contract. If None the private key will be generated by Anvil
+        #[arg(short = 'P', long, value_hint = clap::ValueHint::Other)]
+        private_key: Option<String>,
     },
         /// Verifies a proof using a local Evm executor, returning accept or reject
     #[command(name = "verify-evm")]
@@ -923,6 +1887,59 @@ pub enum Commands {
         // is the vk rendered seperately, if so specify an address
         #[arg(long, value_hint = clap::ValueHint::Other)]
         addr_vk: Option<H160Flag>,
+        /// The path to the input json file, required when `addr_da` is a StorageAttestation contract so the storage proofs of its slots can be fetched
+        #[arg(long, value_hint = clap::ValueHint::FilePath)]
+        data_path: Option<PathBuf>,
+    },
+    /// Verifies a batch of proofs in one call to a verifier created with `--batch`, returning accept or reject
+    #[command(name = "verify-evm-batch")]
+    VerifyEvmBatch {
+        /// The paths to the proof files (generated using the prove command), all for the same circuit
+        #[arg(long, required = true, num_args = 1.., value_delimiter = ',', value_hint = clap::ValueHint::FilePath)]
+        proof_paths: Vec<PathBuf>,
+        /// The path to verifier contract's address
+        #[arg(long, default_value = DEFAULT_CONTRACT_ADDRESS, value_hint = clap::ValueHint::Other)]
+        addr_verifier: H160Flag,
+        /// RPC URL for an Ethereum node, if None will use Anvil but WON'T persist state
+        #[arg(short = 'U', long, value_hint = clap::ValueHint::Url)]
+        rpc_url: Option<String>,
+        // is the vk rendered seperately, if so specify an address
+        #[arg(long, value_hint = clap::ValueHint::Other)]
+        addr_vk: Option<H160Flag>,
+    },
+    /// Deploys the verifier to a local EVM and reports the gas and calldata needed to verify a proof
+    #[command(name = "estimate-gas")]
+    EstimateGas {
+        /// The path to the proof file (generated using the prove command)
+        #[arg(long, default_value = DEFAULT_PROOF, value_hint = clap::ValueHint::FilePath)]
+        proof_path: Option<PathBuf>,
+        /// The path to the Solidity verifier (generated using the create-evm-verifier command)
+        #[arg(long, default_value = DEFAULT_SOL_CODE, value_hint = clap::ValueHint::FilePath)]
+        sol_code_path: Option<PathBuf>,
+        /// The path to the Solidity vk artifact, required if the verifier is reusable (generated using the create-evm-vka command)
+        #[arg(long, value_hint = clap::ValueHint::FilePath)]
+        vka_path: Option<PathBuf>,
+        /// The EVM to run against, `revm` runs in-process and `anvil` deploys to a node
+        #[arg(long, default_value = DEFAULT_EVM_BACKEND, value_hint = clap::ValueHint::Other)]
+        backend: EvmBackend,
+        /// RPC URL for an Ethereum node (anvil backend only), if None will spin up a local Anvil
+        #[arg(short = 'U', long, value_hint = clap::ValueHint::Url)]
+        rpc_url: Option<String>,
+        /// The number of runs set to the SOLC optimizer.
+        #[arg(long, default_value = DEFAULT_OPTIMIZER_RUNS, value_hint = clap::ValueHint::Other)]
+        optimizer_runs: usize,
+        /// The path to output the gas report .json file to
+        #[arg(short = 'O', long, value_hint = clap::ValueHint::FilePath)]
+        output: Option<PathBuf>,
+        /// If set, verifies through the contract of packed instances at this path (generated using the create-evm-packed command), passing the values of the packed tensors in calldata (anvil backend only)
+        #[arg(long, requires = "witness_path", value_hint = clap::ValueHint::FilePath)]
+        packed_sol_code_path: Option<PathBuf>,
+        /// The path to the witness of the proof, holding the values of the packed tensors
+        #[arg(long, value_hint = clap::ValueHint::FilePath)]
+        witness_path: Option<PathBuf>,
+        /// The path to load circuit settings .json file from, locating the packed tensors in the instances
+        #[arg(long, default_value = DEFAULT_SETTINGS, value_hint = clap::ValueHint::FilePath)]
+        settings_path: Option<PathBuf>,
     },
     #[cfg(not(feature = "no-update"))]
     /// Updates ezkl binary to version specified (or latest if not specified)
@@ -0,0 +1,442 @@
+use crate::commands::Cli;
+use clap::parser::ValueSource;
+use clap::{Arg, ArgAction, ArgMatches, Command, CommandFactory, FromArgMatches, ValueHint};
+use log::debug;
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// The name of the configuration file, looked up from the working directory upwards
+pub const CONFIG_FILE: &str = "ezkl.toml";
+
+/// The configuration the command line was parsed with, if any
+static LOADED: OnceLock<Option<Config>> = OnceLock::new();
+
+#[derive(thiserror::Error, Debug)]
+#[allow(missing_docs)]
+pub enum ConfigError {
+    #[error("failed to read {0}: {1}")]
+    ReadError(PathBuf, std::io::Error),
+    #[error("failed to parse {0}: {1}")]
+    ParseError(PathBuf, toml::de::Error),
+    #[error("{0}: `{1}` is not an ezkl command")]
+    UnknownCommand(PathBuf, String),
+    #[error("{0}: `{2}` is not an argument of `{1}`")]
+    UnknownArgument(PathBuf, String, String),
+    #[error("{0}: `{1}` is not an argument of any command")]
+    UnknownSharedArgument(PathBuf, String),
+    #[error("{0}: invalid value for `{1}`, {2}")]
+    InvalidValue(PathBuf, String, String),
+}
+
+/// A project configuration file (`ezkl.toml`) providing defaults for the arguments of the cli. The keys of the top
+/// level are shared by every command taking that argument, the tables named after a command hold the defaults of that
+/// command and take precedence over the shared ones. Keys are the long names of the arguments (`settings-path` or
+/// `settings_path`), relative paths are resolved against the directory of the file.
+///
+/// ```toml
+/// settings-path = "build/settings.json"
+///
+/// [prove]
+/// pk-path = "build/pk.key"
+/// ```
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// The path of the file
+    pub path: PathBuf,
+    shared: toml::value::Table,
+    commands: BTreeMap<String, toml::value::Table>,
+}
+
+/// The subcommands of the cli, with their arguments built
+fn subcommands() -> Command {
+    let mut cli = Cli::command();
+    cli.build();
+    cli
+}
+
+/// The argument of `command` with the long name `key`, which may use underscores in place of dashes
+fn find_arg<'a>(command: &'a Command, key: &str) -> Option<&'a Arg> {
+    let key = key.replace('_', "-");
+    command
+        .get_arguments()
+        .find(|arg| arg.get_long() == Some(key.as_str()))
+}
+
+/// The value of the key of `table` naming the argument `long`
+fn lookup<'a>(table: &'a toml::value::Table, long: &str) -> Option<&'a toml::Value> {
+    table
+        .iter()
+        .find(|(key, _)| key.replace('_', "-") == long)
+        .map(|(_, value)| value)
+}
+
+/// Whether the values of `arg` are paths, which are resolved against the directory of the configuration
+fn is_path(arg: &Arg) -> bool {
+    matches!(
+        arg.get_value_hint(),
+        ValueHint::FilePath | ValueHint::DirPath | ValueHint::AnyPath | ValueHint::ExecutablePath
+    )
+}
+
+impl Config {
+    /// Looks for a configuration file in `dir` and its ancestors
+    pub fn discover(dir: &Path) -> Option<PathBuf> {
+        dir.ancestors()
+            .map(|dir| dir.join(CONFIG_FILE))
+            .find(|path| path.is_file())
+    }
+
+    /// Loads the configuration at `path`, checking its keys are arguments of the commands they configure
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| ConfigError::ReadError(path.to_path_buf(), e))?;
+        let root: toml::value::Table = toml::from_str(&contents)
+            .map_err(|e| ConfigError::ParseError(path.to_path_buf(), e))?;
+        // resolve the file such that relative paths don't depend on the working directory
+        let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+
+        let cli = subcommands();
+        let mut shared = toml::value::Table::new();
+        let mut commands = BTreeMap::new();
+        for (key, value) in root {
+            match value {
+                toml::Value::Table(table) => {
+                    let command = cli
+                        .find_subcommand(&key)
+                        .ok_or_else(|| ConfigError::UnknownCommand(path.clone(), key.clone()))?;
+                    for (arg, value) in table.iter() {
+                        let found = find_arg(command, arg).ok_or_else(|| {
+                            ConfigError::UnknownArgument(path.clone(), key.clone(), arg.clone())
+                        })?;
+                        check_value(&path, found, arg, value)?;
+                    }
+                    commands.insert(key, table);
+                }
+                value => {
+                    let args = cli
+                        .get_subcommands()
+                        .filter_map(|command| find_arg(command, &key))
+                        .collect::<Vec<_>>();
+                    if args.is_empty() {
+                        return Err(ConfigError::UnknownSharedArgument(path.clone(), key));
+                    }
+                    for arg in args {
+                        check_value(&path, arg, &key, &value)?;
+                    }
+                    shared.insert(key, value);
+                }
+            }
+        }
+        Ok(Config {
+            path,
+            shared,
+            commands,
+        })
+    }
+
+    /// The directory relative paths are resolved against
+    fn dir(&self) -> &Path {
+        self.path.parent().unwrap_or(Path::new("."))
+    }
+
+    /// The value the configuration gives to `arg` of the command `name`, the command's own table taking precedence
+    fn value(&self, name: &str, arg: &Arg) -> Option<&toml::Value> {
+        let long = arg.get_long()?;
+        self.commands
+            .get(name)
+            .and_then(|table| lookup(table, long))
+            .or_else(|| lookup(&self.shared, long))
+    }
+
+    /// The value of `value` as it would be passed on the command line, with relative paths resolved
+    fn render(&self, arg: &Arg, value: &toml::Value) -> String {
+        match value {
+            toml::Value::String(s) if is_path(arg) && Path::new(s).is_relative() => {
+                self.dir().join(s).display().to_string()
+            }
+            toml::Value::String(s) => s.clone(),
+            value => value.to_string(),
+        }
+    }
+
+    /// The flags setting the arguments of the command `name` that the configuration provides and `matches` didn't
+    /// get on the command line
+    fn flags(&self, name: &str, matches: &ArgMatches) -> Vec<OsString> {
+        let cli = subcommands();
+        let command = match cli.find_subcommand(name) {
+            Some(command) => command,
+            None => return vec![],
+        };
+        let mut flags = vec![];
+        for arg in command.get_arguments() {
+            let (long, value) = match (arg.get_long(), self.value(name, arg)) {
+                (Some(long), Some(value)) => (long, value),
+                _ => continue,
+            };
+            if matches!(
+                matches.value_source(arg.get_id().as_str()),
+                Some(ValueSource::CommandLine) | Some(ValueSource::EnvVariable)
+            ) {
+                continue;
+            }
+            match value {
+                toml::Value::Boolean(set) if matches!(arg.get_action(), ArgAction::SetTrue) => {
+                    if *set {
+                        flags.push(format!("--{}", long));
+                    }
+                }
+                toml::Value::Array(values) => {
+                    let values = values.iter().map(|v| self.render(arg, v));
+                    match arg.get_value_delimiter() {
+                        Some(d) => flags.push(format!(
+                            "--{}={}",
+                            long,
+                            values.collect::<Vec<_>>().join(&d.to_string())
+                        )),
+                        None => flags.extend(values.map(|v| format!("--{}={}", long, v))),
+                    }
+                }
+                value => flags.push(format!("--{}={}", long, self.render(arg, value))),
+            }
+        }
+        debug!("arguments from {}: {:?}", self.path.display(), flags);
+        flags.into_iter().map(OsString::from).collect()
+    }
+
+    /// The effective configuration as a toml document. For a command, every argument with a value from the
+    /// configuration or a default is listed, otherwise the shared arguments and those of each configured command are.
+    pub fn show(config: Option<&Config>, command: Option<&str>) -> Result<String, String> {
+        let cli = subcommands();
+        let mut shown = toml::value::Table::new();
+        match command {
+            Some(name) => {
+                let command = cli
+                    .find_subcommand(name)
+                    .ok_or(format!("`{}` is not an ezkl command", name))?;
+                let mut table = toml::value::Table::new();
+                for arg in command.get_arguments() {
+                    let long = match arg.get_long() {
+                        Some(long) => long,
+                        None => continue,
+                    };
+                    let value = match config.and_then(|c| c.value(name, arg).map(|v| (c, v))) {
+                        Some((c, toml::Value::Array(values))) => toml::Value::Array(
+                            values
+                                .iter()
+                                .map(|v| toml::Value::String(c.render(arg, v)))
+                                .collect(),
+                        ),
+                        Some((_, toml::Value::Boolean(b))) => toml::Value::Boolean(*b),
+                        Some((c, value)) => toml::Value::String(c.render(arg, value)),
+                        None => match arg.get_default_values() {
+                            [] => continue,
+                            [value] => toml::Value::String(value.to_string_lossy().into()),
+                            values => toml::Value::Array(
+                                values
+                                    .iter()
+                                    .map(|v| toml::Value::String(v.to_string_lossy().into()))
+                                    .collect(),
+                            ),
+                        },
+                    };
+                    table.insert(long.to_string(), value);
+                }
+                shown.insert(name.to_string(), toml::Value::Table(table));
+            }
+            None => {
+                let config = match config {
+                    Some(config) => config,
+                    None => return Ok(String::new()),
+                };
+                let resolve = |name: &str, table: &toml::value::Table| {
+                    let command = cli.find_subcommand(name);
+                    table
+                        .iter()
+                        .map(|(key, value)| {
+                            let arg = command
+                                .and_then(|c| find_arg(c, key))
+                                .or_else(|| cli.get_subcommands().find_map(|c| find_arg(c, key)));
+                            let value = match (arg, value) {
+                                (Some(arg), toml::Value::String(_)) => {
+                                    toml::Value::String(config.render(arg, value))
+                                }
+                                (Some(arg), toml::Value::Array(values)) => toml::Value::Array(
+                                    values
+                                        .iter()
+                                        .map(|v| toml::Value::String(config.render(arg, v)))
+                                        .collect(),
+                                ),
+                                _ => value.clone(),
+                            };
+                            (key.replace('_', "-"), value)
+                        })
+                        .collect::<toml::value::Table>()
+                };
+                shown = resolve("", &config.shared);
+                for (name, table) in config.commands.iter() {
+                    shown.insert(name.clone(), toml::Value::Table(resolve(name, table)));
+                }
+            }
+        }
+        toml::to_string_pretty(&toml::Value::Table(shown)).map_err(|e| e.to_string())
+    }
+}
+
+/// Checks `value` can be given to `arg` on the command line
+fn check_value(path: &Path, arg: &Arg, key: &str, value: &toml::Value) -> Result<(), ConfigError> {
+    let invalid = |reason: &str| ConfigError::InvalidValue(path.into(), key.into(), reason.into());
+    let scalar = |value: &toml::Value| {
+        matches!(
+            value,
+            toml::Value::String(_)
+                | toml::Value::Integer(_)
+                | toml::Value::Float(_)
+                | toml::Value::Boolean(_)
+        )
+    };
+    if matches!(arg.get_action(), ArgAction::SetTrue) {
+        if !value.is_bool() {
+            return Err(invalid("expected a boolean"));
+        }
+    } else if let toml::Value::Array(values) = value {
+        if !values.iter().all(scalar) {
+            return Err(invalid("expected an array of strings or numbers"));
+        }
+    } else if !scalar(value) {
+        return Err(invalid("expected a string, number or boolean"));
+    }
+    Ok(())
+}
+
+/// The configuration the command line was parsed with, else the one found from the working directory
+pub fn loaded() -> Result<Option<Config>, ConfigError> {
+    match LOADED.get() {
+        Some(config) => Ok(config.clone()),
+        None => std::env::current_dir()
+            .ok()
+            .and_then(|dir| Config::discover(&dir))
+            .map(|path| Config::load(&path))
+            .transpose(),
+    }
+}
+
+/// Parses the command line, filling in the arguments it omits from the configuration given with `--config` or found
+/// from the working directory upwards. Arguments given on the command line always take precedence over the
+/// configuration, which takes precedence over the defaults of the cli. Exits on invalid arguments, as clap does.
+pub fn parse_args() -> Result<(Cli, ArgMatches), ConfigError> {
+    let args = std::env::args_os().collect::<Vec<_>>();
+    // a first lenient pass finds the configuration and the command, arguments required by the command may still be
+    // in the configuration at that point
+    let flags = match Cli::command()
+        .ignore_errors(true)
+        .try_get_matches_from(&args)
+    {
+        Ok(matches) => {
+            let path = match matches.get_one::<PathBuf>("config") {
+                Some(path) => Some(path.clone()),
+                None => std::env::current_dir()
+                    .ok()
+                    .and_then(|dir| Config::discover(&dir)),
+            };
+            let config = path.map(|path| Config::load(&path)).transpose()?;
+            let flags = match (&config, matches.subcommand()) {
+                (Some(config), Some((name, sub_matches))) => config.flags(name, sub_matches),
+                _ => vec![],
+            };
+            let _ = LOADED.set(config);
+            flags
+        }
+        Err(_) => vec![],
+    };
+    let matches = Cli::command().get_matches_from(args.into_iter().chain(flags));
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    Ok((cli, matches))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(dir: &Path, contents: &str) -> Config {
+        let path = dir.join(CONFIG_FILE);
+        std::fs::write(&path, contents).unwrap();
+        Config::load(&path).unwrap()
+    }
+
+    fn flags(config: &Config, args: &[&str]) -> Vec<OsString> {
+        let matches = Cli::command()
+            .ignore_errors(true)
+            .try_get_matches_from(std::iter::once("ezkl").chain(args.iter().copied()))
+            .unwrap();
+        let (name, sub_matches) = matches.subcommand().unwrap();
+        config.flags(name, sub_matches)
+    }
+
+    #[test]
+    fn command_line_overrides_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = config(
+            dir.path(),
+            "pk-path = \"shared.key\"\n[prove]\npk_path = \"prove.key\"\nproof-path = \"proof.json\"\n",
+        );
+        let dir = config.dir().display().to_string();
+        // the command table takes precedence over the shared keys
+        assert_eq!(
+            flags(&config, &["prove"]),
+            vec![
+                OsString::from(format!("--pk-path={}/prove.key", dir)),
+                OsString::from(format!("--proof-path={}/proof.json", dir)),
+            ]
+        );
+        // the command line takes precedence over both
+        assert_eq!(
+            flags(&config, &["prove", "--proof-path", "cli.json"]),
+            vec![OsString::from(format!("--pk-path={}/prove.key", dir))]
+        );
+        // shared keys apply to the commands taking them
+        assert_eq!(
+            flags(&config, &["setup"]),
+            vec![OsString::from(format!("--pk-path={}/shared.key", dir))]
+        );
+        assert_eq!(flags(&config, &["verify"]), Vec::<OsString>::new());
+    }
+
+    #[test]
+    fn relative_paths_resolve_against_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let nested = dir.path().join("project");
+        std::fs::create_dir_all(nested.join("src")).unwrap();
+        let config = config(
+            &nested,
+            "[gen-settings]\nmodel = \"network.onnx\"\nsettings-path = \"/abs/settings.json\"\nlogrows = 12\n",
+        );
+        assert_eq!(
+            Config::discover(&nested.join("src")),
+            Some(nested.join(CONFIG_FILE))
+        );
+        let flags = flags(&config, &["gen-settings"]);
+        assert!(flags.contains(&OsString::from(format!(
+            "--model={}",
+            config.dir().join("network.onnx").display()
+        ))));
+        assert!(flags.contains(&OsString::from("--settings-path=/abs/settings.json")));
+        // only path arguments are resolved
+        assert!(flags.contains(&OsString::from("--logrows=12")));
+    }
+
+    #[test]
+    fn unknown_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(CONFIG_FILE);
+        for contents in [
+            "[prove]\nnot-an-arg = 1\n",
+            "[not-a-command]\n",
+            "not-an-arg = 1\n",
+        ] {
+            std::fs::write(&path, contents).unwrap();
+            assert!(Config::load(&path).is_err(), "{}", contents);
+        }
+    }
+}
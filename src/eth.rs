@@ -1,7 +1,10 @@
-use crate::graph::input::{CallsToAccount, FileSourceInner, GraphData};
-use crate::graph::modules::POSEIDON_INSTANCES;
+use crate::graph::input::{
+    AbiFunction, AbiParam, CallsToAccount, FileSourceInner, GraphData, PinnedBlock, ReturnSelector,
+    SlotsOfAccount, StorageProofSource,
+};
+use crate::graph::modules::{ECDSA_INSTANCES, POSEIDON_INSTANCES, SHA256_INSTANCES};
 use crate::graph::DataSource;
-use crate::graph::GraphSettings;
+use crate::graph::{GraphSettings, GraphWitness};
 use crate::pfsys::evm::EvmVerificationError;
 use crate::pfsys::Snark;
 use alloy::contract::CallBuilder;
@@ -37,6 +40,7 @@ use halo2curves::group::ff::PrimeField;
 use itertools::Itertools;
 use log::{debug, info, warn};
 use reqwest::Client;
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::str::FromStr;
 use std::sync::Arc;
@@ -46,6 +50,11 @@ const ANVIL_DEFAULT_PRIVATE_KEY: &str =
 
 pub const DEFAULT_ANVIL_ENDPOINT: &str = "http://localhost:8545";
 
+/// Ethereum's target gas per block
+pub const BLOCK_GAS_TARGET: u64 = 15_000_000;
+/// Ethereum's gas limit per block
+pub const BLOCK_GAS_LIMIT: u64 = 30_000_000;
+
 // Generate contract bindings OUTSIDE the functions so they are part of library
 abigen!(
     #[allow(missing_docs)]
@@ -66,6 +75,21 @@ abigen!(
     DataAttestation,
     "./abis/DataAttestation.json"
 );
+abigen!(
+    #[allow(missing_docs)]
+    #[sol(rpc)]
+    interface PackedInstances {
+        function verifyProof(bytes calldata proof, uint256[] calldata instances, uint256[] calldata values) external view returns (bool);
+    }
+);
+abigen!(
+    #[allow(missing_docs)]
+    #[sol(rpc)]
+    interface VerifierRegistry {
+        function register(bytes32 fingerprint, address verifier) external;
+        function resolve(bytes32 fingerprint) external view returns (address verifier);
+    }
+);
 abigen!(
     #[allow(missing_docs)]
     #[sol(rpc, bytecode="608060405234801561000f575f80fd5b50610a8b8061001d5f395ff3fe608060405234801561000f575f80fd5b506004361061003f575f3560e01c80630a7e4b9614610043578063b404abab1461006c578063d3dc6d1f1461007f575b5f80fd5b6100566100513660046105b6565b610092565b60405161006391906106f6565b60405180910390f35b61005661007a366004610739565b610203565b61005661008d3660046107c4565b61033c565b606083516001600160401b038111156100ad576100ad6104e8565b6040519080825280602002602001820160405280156100d6578160200160208202803683370190505b5090505f5b84518110156101fb575f8582815181106100f7576100f7610853565b60200260200101518060200190518101906101129190610867565b90505f811280156101295761012682610892565b91505b5f86848151811061013c5761013c610853565b6020026020010151600a610150919061098e565b90505f86858151811061016557610165610853565b60200260200101516001901b90505f61017f8583856103fd565b905082838061019057610190610999565b83870961019e9060026109ad565b106101b1576101ae6001826109c4565b90505b836101bc57806101c5565b6101c581610892565b8787815181106101d7576101d7610853565b602002602001018181525050505050505080806101f3906109d7565b9150506100db565b509392505050565b606081516001600160401b0381111561021e5761021e6104e8565b604051908082528060200260200182016040528015610247578160200160208202803683370190505b5090505f5b8251811015610336575f83828151811061026857610268610853565b6020026020010151121580156102a457505f80516020610a3683398151915283828151811061029957610299610853565b602002602001015111155b6102ed5760405162461bcd60e51b8152602060048201526015602482015274125b9d985b1a5908199a595b1908195b195b595b9d605a1b60448201526064015b60405180910390fd5b8281815181106102ff576102ff610853565b602002602001015182828151811061031957610319610853565b60209081029190910101528061032e816109d7565b91505061024c565b50919050565b606081516001600160401b03811115610357576103576104e8565b604051908082528060200260200182016040528015610380578160200160208202803683370190505b5090505f5b8251811015610336575f80516020610a36833981519152808483815181106103af576103af610853565b602002602001015160070b6103c491906109ef565b6103ce9190610a16565b8282815181106103e0576103e0610853565b6020908102919091010152806103f5816109d7565b915050610385565b5f80805f19858709858702925082811083820303915050805f036104345783828161042a5761042a610999565b04925050506104e1565b80841161047b5760405162461bcd60e51b81526020600482015260156024820152744d6174683a206d756c446976206f766572666c6f7760581b60448201526064016102e4565b5f848688098519600190810187169687900496828603819004959092119093035f82900391909104909201919091029190911760038402600290811880860282030280860282030280860282030280860282030280860282030280860290910302029150505b9392505050565b634e487b7160e01b5f52604160045260245ffd5b604051601f8201601f191681016001600160401b0381118282101715610524576105246104e8565b604052919050565b5f6001600160401b03821115610544576105446104e8565b5060051b60200190565b5f82601f83011261055d575f80fd5b8135602061057261056d8361052c565b6104fc565b82815260059290921b84018101918181019086841115610590575f80fd5b8286015b848110156105ab5780358352918301918301610594565b509695505050505050565b5f805f606084860312156105c8575f80fd5b83356001600160401b03808211156105de575f80fd5b818601915086601f8301126105f1575f80fd5b8135602061060161056d8361052c565b82815260059290921b8401810191818101908a84111561061f575f80fd5b8286015b848110156106a85780358681111561063a575f8081fd5b8701603f81018d1361064b575f8081fd5b84810135604088821115610661576106616104e8565b610673601f8301601f191688016104fc565b8281528f82848601011115610687575f8081fd5b82828501898301375f92810188019290925250845250918301918301610623565b50975050870135925050808211156106be575f80fd5b6106ca8783880161054e565b935060408601359150808211156106df575f80fd5b506106ec8682870161054e565b9150509250925092565b602080825282518282018190525f9190848201906040850190845b8181101561072d57835183529284019291840191600101610711565b50909695505050505050565b5f602080838503121561074a575f80fd5b82356001600160401b0381111561075f575f80fd5b8301601f8101851361076f575f80fd5b803561077d61056d8261052c565b81815260059190911b8201830190838101908783111561079b575f80fd5b928401925b828410156107b9578335825292840192908401906107a0565b979650505050505050565b5f60208083850312156107d5575f80fd5b82356001600160401b038111156107ea575f80fd5b8301601f810185136107fa575f80fd5b803561080861056d8261052c565b81815260059190911b82018301908381019087831115610826575f80fd5b928401925b828410156107b95783358060070b8114610844575f8081fd5b8252928401929084019061082b565b634e487b7160e01b5f52603260045260245ffd5b5f60208284031215610877575f80fd5b5051919050565b634e487b7160e01b5f52601160045260245ffd5b5f600160ff1b82016108a6576108a661087e565b505f0390565b600181815b808511156108e657815f19048211156108cc576108cc61087e565b808516156108d957918102915b93841c93908002906108b1565b509250929050565b5f826108fc57506001610988565b8161090857505f610988565b816001811461091e576002811461092857610944565b6001915050610988565b60ff8411156109395761093961087e565b50506001821b610988565b5060208310610133831016604e8410600b8410161715610967575081810a610988565b61097183836108ac565b805f19048211156109845761098461087e565b0290505b92915050565b5f6104e183836108ee565b634e487b7160e01b5f52601260045260245ffd5b80820281158282048414176109885761098861087e565b808201808211156109885761098861087e565b5f600182016109e8576109e861087e565b5060010190565b8082018281125f831280158216821582161715610a0e57610a0e61087e565b505092915050565b5f82610a3057634e487b7160e01b5f52601260045260245ffd5b50069056fe30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001a26469706673582212200034995b2b5991300d54d46b8b569fdaad34c590716304d33ba67eac46c8a61764736f6c63430008140033")]
@@ -262,11 +286,49 @@ pub enum EthError {
     Svm(String),
     #[error("no contract output found")]
     NoContractOutput,
+    #[error("revm error: {0}")]
+    Revm(String),
+    #[error("could not find the end of the verifier contract")]
+    MalformedVerifier,
+    #[error("a batch of {0} proofs needs {1} gas which exceeds the {2} block gas limit")]
+    BatchTooLarge(usize, u128, u64),
+    #[error("block {0} not found")]
+    BlockNotFound(u64),
+    #[error("failed to parse block hash {0}")]
+    BlockHashParse(String),
+    #[error("block {0} hashes to {1} but {2} was expected")]
+    BlockHashMismatch(u64, String, String),
+    #[error("storage proofs must all be read at the same block, got {0} and {1}")]
+    StorageProofBlockMismatch(u64, u64),
+    #[error("on-chain sources must all be pinned to the same block, got {0:?} and {1:?}")]
+    PinnedSourceMismatch(Option<u64>, Option<u64>),
+    #[error("the proof was read at block {0} but the attestation contract is pinned at {1}")]
+    PinnedBlockMismatch(u64, u64),
+    #[error("the header of block {0} does not chain to the pinned parent hash")]
+    PinnedParentMismatch(u64),
+    #[error("no receipt for the pin transaction")]
+    NoReceipt,
+    #[error("invalid return selector: {0}")]
+    ReturnSelector(String),
+    #[error("the returned data does not match its return selector")]
+    ReturnData,
+    #[error("invalid instance binding: {0}")]
+    InstanceBinding(String),
+    #[error("invalid packed instances: {0}")]
+    PackedInstances(String),
 }
 
 // we have to generate these two contract differently because they are generated dynamically ! and hence the static compilation from above does not suit
 const ATTESTDATA_SOL: &str = include_str!("../contracts/AttestData.sol");
 
+const ATTESTSTORAGE_SOL: &str = include_str!("../contracts/AttestStorage.sol");
+
+const VERIFIER_REGISTRY_SOL: &str = include_str!("../contracts/VerifierRegistry.sol");
+
+const PACKED_INSTANCES_SOL: &str = include_str!("../contracts/PackedInstances.sol");
+
+const LIMB_PACKED_INSTANCES_SOL: &str = include_str!("../contracts/LimbPackedInstances.sol");
+
 pub type EthersClient = Arc<
     FillProvider<
         JoinFill<
@@ -352,6 +414,87 @@ pub async fn deploy_contract_via_solidity(
     Ok(contract)
 }
 
+/// The fingerprint a model's verifier is registered under in a VerifierRegistry, the keccak256 hash
+/// of its circuit settings, including how its image inputs are preprocessed and its file inputs
+/// normalized. The timestamp is left out so regenerating the settings keeps the fingerprint.
+pub fn settings_fingerprint(settings: &GraphSettings) -> Result<B256, EthError> {
+    use alloy::primitives::keccak256;
+
+    let mut settings = settings.clone();
+    settings.timestamp = None;
+    let json = serde_json::to_vec(&settings).map_err(|e| EthError::Io(e.into()))?;
+    Ok(keccak256(json))
+}
+
+/// Deploys a VerifierRegistry owned by the deploying account
+pub async fn deploy_verifier_registry(
+    rpc_url: Option<&str>,
+    runs: usize,
+    private_key: Option<&str>,
+) -> Result<H160, EthError> {
+    let (client, client_address) = setup_eth_backend(rpc_url, private_key).await?;
+
+    let (abi, bytecode, runtime_bytecode) =
+        get_inline_contract_artifacts(VERIFIER_REGISTRY_SOL, "VerifierRegistry", runs).await?;
+
+    let factory = get_sol_contract_factory(
+        abi,
+        bytecode,
+        runtime_bytecode,
+        client,
+        Some((WordToken(client_address.into_word()),)),
+    )?;
+    let contract = factory.deploy().await?;
+
+    Ok(contract)
+}
+
+/// Registers a deployed verifier in a VerifierRegistry under the fingerprint of the settings,
+/// returning the fingerprint
+pub async fn register_verifier(
+    registry: H160,
+    verifier: H160,
+    settings: &GraphSettings,
+    rpc_url: Option<&str>,
+    private_key: Option<&str>,
+) -> Result<B256, EthError> {
+    let (client, client_address) = setup_eth_backend(rpc_url, private_key).await?;
+
+    let fingerprint = settings_fingerprint(settings)?;
+    let contract = VerifierRegistry::new(registry, &client);
+    contract
+        .register(fingerprint, verifier)
+        .from(client_address)
+        .send()
+        .await?
+        .get_receipt()
+        .await?;
+    info!(
+        "registered verifier {:#?} under {:#?}",
+        verifier, fingerprint
+    );
+
+    Ok(fingerprint)
+}
+
+/// Resolves the verifier registered in a VerifierRegistry for the settings
+pub async fn resolve_verifier(
+    registry: H160,
+    settings: &GraphSettings,
+    rpc_url: Option<&str>,
+) -> Result<H160, EthError> {
+    let (client, _) = setup_eth_backend(rpc_url, None).await?;
+
+    let contract = VerifierRegistry::new(registry, &client);
+    let verifier = contract
+        .resolve(settings_fingerprint(settings)?)
+        .call()
+        .await?
+        .verifier;
+
+    Ok(verifier)
+}
+
 ///
 pub async fn deploy_da_verifier_via_solidity(
     settings_path: PathBuf,
@@ -374,7 +517,11 @@ pub async fn deploy_da_verifier_via_solidity(
     let mut instance_shapes = vec![];
     let mut model_instance_offset = 0;
 
-    if settings.run_args.input_visibility.is_hashed() {
+    if settings.run_args.input_visibility.is_sha256_hashed() {
+        instance_shapes.push(SHA256_INSTANCES)
+    } else if settings.run_args.input_visibility.is_hashed()
+        || settings.run_args.input_visibility.is_merkle()
+    {
         instance_shapes.push(POSEIDON_INSTANCES)
     } else if settings.run_args.input_visibility.is_public() {
         for idx in 0..settings.model_input_scales.len() {
@@ -384,11 +531,40 @@ pub async fn deploy_da_verifier_via_solidity(
         }
     }
 
+    // the public key the signed inputs are verified against
+    if settings.input_signature.is_some() {
+        instance_shapes.push(ECDSA_INSTANCES)
+    }
+
     if settings.run_args.param_visibility.is_hashed() {
         return Err(EvmVerificationError::InvalidVisibility.into());
     }
 
-    if settings.run_args.output_visibility.is_hashed() {
+    if !settings.run_args.output_visibilities.is_empty()
+        || !settings.run_args.revealed_outputs.is_empty()
+    {
+        // the public and revealed outputs are in the clear, followed by the hashes of the hashed outputs
+        let run_args = &settings.run_args;
+        let num_outputs = settings.model_output_scales.len();
+        let mut public_idx = model_instance_offset;
+        for idx in 0..num_outputs {
+            if run_args.output_is_public(idx) {
+                let shape = &settings.model_instance_shapes[public_idx];
+                instance_shapes.push(shape.iter().product::<usize>());
+                public_idx += 1;
+            }
+        }
+        for idx in 0..num_outputs {
+            let visibility = run_args.visibility_of_output(idx);
+            if visibility.is_sha256_hashed() {
+                instance_shapes.push(SHA256_INSTANCES)
+            } else if visibility.is_hashed() {
+                instance_shapes.push(POSEIDON_INSTANCES)
+            }
+        }
+    } else if settings.run_args.output_visibility.is_sha256_hashed() {
+        instance_shapes.push(SHA256_INSTANCES)
+    } else if settings.run_args.output_visibility.is_hashed() {
         instance_shapes.push(POSEIDON_INSTANCES)
     } else if settings.run_args.output_visibility.is_public() {
         for idx in model_instance_offset..model_instance_offset + settings.model_output_scales.len()
@@ -401,7 +577,13 @@ pub async fn deploy_da_verifier_via_solidity(
     let mut instance_idx = 0;
     let mut contract_instance_offset = 0;
 
-    if let DataSource::OnChain(source) = input.input_data {
+    // storage proof sources are attested to by the StorageAttestation contract instead
+    let storage_source = storage_proof_source(&input)?;
+
+    if matches!(
+        input.input_data,
+        DataSource::OnChain(_) | DataSource::StorageProof(_)
+    ) {
         if settings.run_args.input_visibility.is_hashed_public() {
             // set scales 1.0
             scales.extend(vec![0; instance_shapes[instance_idx]]);
@@ -414,8 +596,10 @@ pub async fn deploy_da_verifier_via_solidity(
                 instance_idx += 1;
             }
         }
-        for call in source.calls {
-            calls_to_accounts.push(call);
+        if let DataSource::OnChain(source) = input.input_data {
+            for call in source.calls {
+                calls_to_accounts.push(call);
+            }
         }
     } else if let DataSource::File(source) = input.input_data {
         if settings.run_args.input_visibility.is_public() {
@@ -426,7 +610,16 @@ pub async fn deploy_da_verifier_via_solidity(
         }
     }
 
-    if let Some(DataSource::OnChain(source)) = input.output_data {
+    if matches!(
+        input.output_data,
+        Some(DataSource::OnChain(_)) | Some(DataSource::StorageProof(_))
+    ) {
+        // on-chain outputs are attested to with a single visibility
+        if !settings.run_args.output_visibilities.is_empty()
+            || !settings.run_args.revealed_outputs.is_empty()
+        {
+            return Err(EvmVerificationError::InvalidVisibility.into());
+        }
         if settings.run_args.output_visibility.is_hashed_public() {
             // set scales 1.0
             scales.extend(vec![0; instance_shapes[instance_idx]]);
@@ -438,20 +631,94 @@ pub async fn deploy_da_verifier_via_solidity(
                 instance_idx += 1;
             }
         }
-        for call in source.calls {
-            calls_to_accounts.push(call);
+        if let Some(DataSource::OnChain(source)) = input.output_data {
+            for call in source.calls {
+                calls_to_accounts.push(call);
+            }
         }
     }
 
+    if let Some(source) = storage_source {
+        let (abi, bytecode, runtime_bytecode) =
+            get_contract_artifacts(sol_code_path, "StorageAttestation", runs).await?;
+        let (contract_addresses, slots, decimals) = parse_slots_of_accounts(source.accounts)?;
+        let block_hash = match source.block_hash {
+            Some(block_hash) => B256::from_str(&block_hash)
+                .map_err(|_| EthError::BlockHashParse(block_hash.clone()))?,
+            // the contract falls back to the blockhash opcode
+            None => B256::ZERO,
+        };
+
+        let factory = get_sol_contract_factory(
+            abi,
+            bytecode,
+            runtime_bytecode,
+            client.clone(),
+            Some((
+                // uint256 _blockNumber,
+                WordToken(U256::from(source.block).into()),
+                // bytes32 _blockHash,
+                WordToken(block_hash),
+                // address[] memory _contractAddresses,
+                DynSeqToken(
+                    contract_addresses
+                        .iter()
+                        .map(|ca| WordToken(ca.into_word()))
+                        .collect_vec(),
+                ),
+                // bytes32[][] memory _slots,
+                DynSeqToken(
+                    slots
+                        .iter()
+                        .map(|s| DynSeqToken(s.iter().map(|s| WordToken(*s)).collect_vec()))
+                        .collect::<Vec<_>>(),
+                ),
+                // uint256[][] memory _decimals,
+                DynSeqToken(
+                    decimals
+                        .iter()
+                        .map(|ints| {
+                            DynSeqToken(
+                                ints.iter().map(|i| WordToken(B256::from(*i))).collect_vec(),
+                            )
+                        })
+                        .collect::<Vec<_>>(),
+                ),
+                // uint[] memory _scales,
+                DynSeqToken(
+                    scales
+                        .into_iter()
+                        .map(|i| WordToken(U256::from(i).into()))
+                        .collect_vec(),
+                ),
+                //  uint8 _instanceOffset,
+                WordToken(U256::from(contract_instance_offset as u32).into()),
+                // address _admin
+                WordToken(client_address.into_word()),
+            )),
+        )?;
+
+        debug!("slots: {:#?}", slots);
+        debug!("contract_addresses: {:#?}", contract_addresses);
+
+        let contract = factory.deploy().await?;
+        return Ok(contract);
+    }
+
     let (abi, bytecode, runtime_bytecode) =
         get_contract_artifacts(sol_code_path, "DataAttestation", runs).await?;
 
     let (contract_addresses, call_data, decimals) = if !calls_to_accounts.is_empty() {
         parse_calls_to_accounts(calls_to_accounts)?
+    } else if abi.constructor.is_some() {
+        // a contract with no account calls that still needs the full contract, to check instance bindings
+        (vec![], vec![], vec![])
     } else {
         // if calls to accounts is empty then we know need to check that atleast there kzg visibility in the settings file
         let kzg_visibility = settings.run_args.input_visibility.is_polycommit()
-            || settings.run_args.output_visibility.is_polycommit()
+            || settings
+                .run_args
+                .any_output_visibility(|v| v.is_polycommit())
             || settings.run_args.param_visibility.is_polycommit();
         if !kzg_visibility {
             return Err(EthError::OnChainDataSource);
@@ -521,6 +788,56 @@ pub async fn deploy_da_verifier_via_solidity(
     Ok(contract)
 }
 
+type ParsedSlotsOfAccount = (Vec<H160>, Vec<Vec<B256>>, Vec<Vec<U256>>);
+
+fn parse_slots_of_accounts(
+    slots_of_accounts: Vec<SlotsOfAccount>,
+) -> Result<ParsedSlotsOfAccount, EthError> {
+    let mut contract_addresses = vec![];
+    let mut slots = vec![];
+    let mut decimals: Vec<Vec<U256>> = vec![];
+    for val in slots_of_accounts {
+        let contract_address_bytes = hex::decode(&val.address)?;
+        contract_addresses.push(H160::from_slice(&contract_address_bytes));
+        let mut account_slots = vec![];
+        let mut account_decimals = vec![];
+        for (slot, decimal) in &val.slots {
+            account_slots.push(B256::from(U256::from_str_radix(
+                slot.trim_start_matches("0x"),
+                16,
+            )?));
+            account_decimals.push(U256::from(*decimal));
+        }
+        slots.push(account_slots);
+        decimals.push(account_decimals);
+    }
+    Ok((contract_addresses, slots, decimals))
+}
+
+/// Merges the storage proof sources of the input and output data.
+/// Both must be read at the same block as the StorageAttestation contract attests to a single block.
+pub fn storage_proof_source(data: &GraphData) -> Result<Option<StorageProofSource>, EthError> {
+    let mut merged: Option<StorageProofSource> = None;
+    let sources = [Some(&data.input_data), data.output_data.as_ref()];
+    for source in sources.into_iter().flatten() {
+        if let DataSource::StorageProof(source) = source {
+            match &mut merged {
+                Some(merged) => {
+                    if merged.block != source.block {
+                        return Err(EthError::StorageProofBlockMismatch(
+                            merged.block,
+                            source.block,
+                        ));
+                    }
+                    merged.accounts.extend(source.accounts.clone());
+                }
+                None => merged = Some(source.clone()),
+            }
+        }
+    }
+    Ok(merged)
+}
+
 type ParsedCallsToAccount = (Vec<H160>, Vec<Vec<Bytes>>, Vec<Vec<U256>>);
 
 fn parse_calls_to_accounts(
@@ -609,6 +926,148 @@ pub async fn update_account_calls(
     Ok(())
 }
 
+/// Snapshots the account calls of a DataAttestation contract at the block the pin transaction lands in,
+/// then pins the on-chain sources of the data at `input` to that block so witnesses read the snapshotted state.
+pub async fn pin_da_block(
+    addr: H160,
+    input: PathBuf,
+    rpc_url: Option<&str>,
+    private_key: Option<&str>,
+) -> Result<PinnedBlock, EthError> {
+    let mut data = GraphData::from_path(input.clone()).map_err(|_| EthError::GraphData)?;
+
+    let (client, client_address) = setup_eth_backend(rpc_url, private_key).await?;
+
+    let contract = DataAttestation::new(addr, &client);
+
+    let receipt = contract
+        .pinBlock()
+        .from(client_address)
+        .send()
+        .await?
+        .get_receipt()
+        .await?;
+
+    let pinned = PinnedBlock {
+        number: receipt.block_number.ok_or(EthError::NoReceipt)?,
+        hash: receipt.block_hash.ok_or(EthError::NoReceipt)?.to_string(),
+    };
+    info!("pinned data attestation at block {:#?}", pinned);
+
+    let mut pinned_any = false;
+    let sources = [Some(&mut data.input_data), data.output_data.as_mut()];
+    for source in sources.into_iter().flatten() {
+        if let DataSource::OnChain(source) = source {
+            source.block = Some(pinned.number);
+            source.block_hash = Some(pinned.hash.clone());
+            pinned_any = true;
+        }
+    }
+    if !pinned_any {
+        return Err(EthError::OnChainDataSource);
+    }
+    data.save(input).map_err(|_| EthError::GraphData)?;
+
+    Ok(pinned)
+}
+
+/// Resolves the block the on-chain sources of `data` are pinned to, checking it against any recorded block hash.
+/// Returns None if the sources read the latest state.
+pub async fn pinned_block(data: &GraphData) -> Result<Option<PinnedBlock>, EthError> {
+    use alloy::rpc::types::eth::BlockNumberOrTag;
+
+    let mut sources = [Some(&data.input_data), data.output_data.as_ref()]
+        .into_iter()
+        .flatten()
+        .filter_map(|source| match source {
+            DataSource::OnChain(source) => Some(source),
+            _ => None,
+        });
+
+    let first = match sources.next() {
+        Some(first) => first,
+        None => return Ok(None),
+    };
+    // a witness reading two different states would not be reproducible
+    for source in sources {
+        if source.block != first.block {
+            return Err(EthError::PinnedSourceMismatch(first.block, source.block));
+        }
+    }
+    let number = match first.block {
+        Some(number) => number,
+        None => return Ok(None),
+    };
+
+    let (client, _) = setup_eth_backend(Some(&first.rpc), None).await?;
+    let hash = client
+        .get_block_by_number(BlockNumberOrTag::Number(number), false)
+        .await?
+        .and_then(|block| block.header.hash)
+        .ok_or(EthError::BlockNotFound(number))?;
+
+    if let Some(block_hash) = &first.block_hash {
+        let expected =
+            B256::from_str(block_hash).map_err(|_| EthError::BlockHashParse(block_hash.clone()))?;
+        if hash != expected {
+            return Err(EthError::BlockHashMismatch(
+                number,
+                hash.to_string(),
+                expected.to_string(),
+            ));
+        }
+    }
+
+    Ok(Some(PinnedBlock {
+        number,
+        hash: hash.to_string(),
+    }))
+}
+
+/// Checks the block a proof's witness was read at against the snapshot of a DataAttestation contract.
+/// The header of the pinned block must hash to the recorded block hash and chain to the parent hash the
+/// contract recorded when pinning, which holds even once the block is outside the `blockhash` window.
+pub async fn check_pinned_block(
+    addr: H160,
+    pinned: &PinnedBlock,
+    rpc_url: Option<&str>,
+) -> Result<(), EthError> {
+    use alloy::primitives::keccak256;
+    use alloy::rpc::types::eth::BlockNumberOrTag;
+
+    let (client, _) = setup_eth_backend(rpc_url, None).await?;
+    let contract = DataAttestation::new(addr, &client);
+
+    let contract_block = contract.pinnedBlock().call().await?._0;
+    if contract_block != U256::from(pinned.number) {
+        return Err(EthError::PinnedBlockMismatch(
+            pinned.number,
+            contract_block.to::<u64>(),
+        ));
+    }
+    let parent_hash = contract.pinnedParentHash().call().await?._0;
+
+    let block = client
+        .get_block_by_number(BlockNumberOrTag::Number(pinned.number), false)
+        .await?
+        .ok_or(EthError::BlockNotFound(pinned.number))?;
+    let hash = keccak256(rlp_encode_header(&block.header));
+    let expected =
+        B256::from_str(&pinned.hash).map_err(|_| EthError::BlockHashParse(pinned.hash.clone()))?;
+    if hash != expected {
+        return Err(EthError::BlockHashMismatch(
+            pinned.number,
+            hash.to_string(),
+            expected.to_string(),
+        ));
+    }
+    if block.header.parent_hash != parent_hash {
+        return Err(EthError::PinnedParentMismatch(pinned.number));
+    }
+
+    Ok(())
+}
+
 /// Verify a proof using a Solidity verifier contract
 pub async fn verify_proof_via_solidity(
     proof: Snark<Fr, G1Affine>,
@@ -650,11 +1109,11 @@ pub async fn verify_proof_via_solidity(
     info!("estimated verify gas cost: {:#?}", gas);
 
     // if gas is greater than 30 million warn the user that the gas cost is above ethereum's 30 million block gas limit
-    if gas > 30_000_000_u128 {
+    if gas > BLOCK_GAS_LIMIT as u128 {
         warn!(
             "Gas cost of verify transaction is greater than 30 million block gas limit. It will fail on mainnet."
         );
-    } else if gas > 15_000_000_u128 {
+    } else if gas > BLOCK_GAS_TARGET as u128 {
         warn!(
             "Gas cost of verify transaction is greater than 15 million, the target block size for ethereum"
         );
@@ -663,137 +1122,997 @@ pub async fn verify_proof_via_solidity(
     Ok(true)
 }
 
-fn count_decimal_places(num: f32) -> usize {
-    // Convert the number to a string
-    let s = num.to_string();
-
-    // Find the decimal point
-    match s.find('.') {
-        Some(index) => {
-            // Count the number of characters after the decimal point
-            s[index + 1..].len()
+/// Solidity for the `verifyBatch` entrypoint, `VK_ARG` and `VK_PARAM` are filled in for reusable verifiers.
+/// Each proof is checked via a self call so the calldata layout `verifyProof` expects is preserved.
+const VERIFY_BATCH_SOL: &str = r#"
+    /// @notice Verifies a batch of proofs for this circuit in one transaction, true only if every proof verifies.
+    /// @dev The verifier (and vka) are cold loaded once per batch but each proof is still fully verified,
+    ///      so gas grows linearly with the batch size and large batches can exceed the block gas limit.
+    function verifyBatch(VK_PARAMbytes[] calldata proofs, uint256[][] calldata instances) public returns (bool) {
+        require(proofs.length == instances.length, "verifyBatch: length mismatch");
+        for (uint256 i = 0; i < proofs.length; i++) {
+            (bool success, bytes memory ret) = address(this).staticcall(
+                abi.encodeWithSelector(this.verifyProof.selector, VK_ARGproofs[i], instances[i])
+            );
+            if (!success || ret.length < 32 || !abi.decode(ret, (bool))) {
+                return false;
+            }
         }
-        None => 0,
+        return true;
     }
-}
+"#;
 
-///
-pub async fn setup_test_contract<M: 'static + Provider<Http<Client>, Ethereum>>(
-    client: Arc<M>,
-    data: &[Vec<FileSourceInner>],
-) -> Result<(TestReads::TestReadsInstance<Http<Client>, Arc<M>>, Vec<u8>), EthError> {
-    let mut decimals = vec![];
-    let mut scaled_by_decimals_data = vec![];
-    for input in &data[0] {
-        if input.is_float() {
-            let input = input.to_float() as f32;
-            let decimal_places = count_decimal_places(input) as u8;
-            let scaled_by_decimals = input * f32::powf(10., decimal_places.into());
-            scaled_by_decimals_data.push(I256::from_dec_str(
-                &(scaled_by_decimals as i32).to_string(),
-            )?);
-            decimals.push(decimal_places);
-        } else if input.is_field() {
-            let input = input.to_field(0);
-            let hex_str_fr = format!("{:?}", input);
-            // remove the 0x prefix
-            let hex_str_fr = &hex_str_fr[2..];
-            scaled_by_decimals_data.push(I256::from_raw(U256::from_str_radix(hex_str_fr, 16)?));
-            decimals.push(0);
-        }
-    }
+/// Adds a `verifyBatch(bytes[] proofs, uint256[][] instances)` entrypoint to a rendered verifier.
+/// Reusable verifiers take the vka address as a leading argument, as `verifyProof` does.
+pub fn add_verify_batch(verifier_solidity: &str, reusable: bool) -> Result<String, EthError> {
+    let (vk_param, vk_arg) = if reusable {
+        ("address vk, ", "vk, ")
+    } else {
+        ("", "")
+    };
+    let batch = VERIFY_BATCH_SOL
+        .replace("VK_PARAM", vk_param)
+        .replace("VK_ARG", vk_arg);
+
+    // the verifier contract is the last item in the rendered file
+    let end = verifier_solidity
+        .rfind('}')
+        .ok_or(EthError::MalformedVerifier)?;
+    let mut contract = verifier_solidity.to_string();
+    contract.insert_str(end, &batch);
+    Ok(contract)
+}
 
-    // Compile the contract
-    let contract = TestReads::deploy(client, scaled_by_decimals_data).await?;
+/// Adds a `NULLIFIER_INSTANCE_INDEX` constant to a rendered verifier, the index of the nullifier in
+/// the instances, so that a contract calling the verifier can record the nullifiers it has seen.
+pub fn add_nullifier_index(verifier_solidity: &str, index: usize) -> Result<String, EthError> {
+    let constant = format!(
+        "\n    /// @notice The index of the nullifier in the instances of a proof.\n    uint256 public constant NULLIFIER_INSTANCE_INDEX = {};\n",
+        index
+    );
 
-    Ok((contract, decimals))
+    // the verifier contract is the last item in the rendered file
+    let end = verifier_solidity
+        .rfind('}')
+        .ok_or(EthError::MalformedVerifier)?;
+    let mut contract = verifier_solidity.to_string();
+    contract.insert_str(end, &constant);
+    Ok(contract)
 }
 
-/// Verify a proof using a Solidity DataAttestation contract.
-/// Used for testing purposes.
-pub async fn verify_proof_with_data_attestation(
-    proof: Snark<Fr, G1Affine>,
-    addr_verifier: H160,
-    addr_da: H160,
-    addr_vk: Option<H160>,
-    rpc_url: Option<&str>,
-) -> Result<bool, EthError> {
+/// Encodes the calldata for a `verifyBatch` call on a verifier rendered with [add_verify_batch]
+pub fn encode_batch_calldata(
+    vk_address: Option<[u8; 20]>,
+    proofs: &[Snark<Fr, G1Affine>],
+) -> Result<Vec<u8>, EthError> {
     use ethabi::{Function, Param, ParamType, StateMutability, Token};
 
-    let mut public_inputs: Vec<U256> = vec![];
-    let flattened_instances = proof.instances.into_iter().flatten();
+    let param = |name: &str, kind: ParamType| Param {
+        name: name.to_owned(),
+        kind,
+        internal_type: None,
+    };
 
-    for val in flattened_instances.clone() {
-        let bytes = val.to_repr();
-        let u = U256::from_le_slice(bytes.inner().as_slice());
-        public_inputs.push(u);
+    let mut inputs = vec![];
+    let mut tokens = vec![];
+    if let Some(vk_address) = vk_address {
+        inputs.push(param("vk", ParamType::Address));
+        tokens.push(Token::Address(vk_address.into()));
     }
-
-    let encoded_verifier = encode_calldata(
-        addr_vk.as_ref().map(|x| x.0).map(|x| x.0),
-        &proof.proof,
-        &flattened_instances.collect::<Vec<_>>(),
-    );
-
-    debug!("encoded: {:#?}", hex::encode(&encoded_verifier));
-
-    debug!("public_inputs: {:#?}", public_inputs);
-    debug!("proof: {:#?}", Bytes::from(proof.proof.to_vec()));
+    inputs.push(param(
+        "proofs",
+        ParamType::Array(Box::new(ParamType::Bytes)),
+    ));
+    inputs.push(param(
+        "instances",
+        ParamType::Array(Box::new(ParamType::Array(Box::new(ParamType::Uint(256))))),
+    ));
+    tokens.push(Token::Array(
+        proofs
+            .iter()
+            .map(|p| Token::Bytes(p.proof.clone()))
+            .collect(),
+    ));
+    tokens.push(Token::Array(
+        proofs
+            .iter()
+            .map(|p| {
+                Token::Array(
+                    p.instances
+                        .iter()
+                        .flatten()
+                        .map(|i| {
+                            Token::Uint(ethabi::Uint::from_little_endian(i.to_repr().as_ref()))
+                        })
+                        .collect(),
+                )
+            })
+            .collect(),
+    ));
 
     #[allow(deprecated)]
     let func = Function {
-        name: "verifyWithDataAttestation".to_owned(),
-        inputs: vec![
-            Param {
-                name: "verifier".to_owned(),
-                kind: ParamType::Address,
-                internal_type: None,
-            },
-            Param {
-                name: "encoded".to_owned(),
-                kind: ParamType::Bytes,
-                internal_type: None,
-            },
-        ],
-        outputs: vec![Param {
-            name: "success".to_owned(),
-            kind: ParamType::Bool,
-            internal_type: None,
-        }],
+        name: "verifyBatch".to_owned(),
+        inputs,
+        outputs: vec![param("", ParamType::Bool)],
         constant: None,
-        state_mutability: StateMutability::View,
+        state_mutability: StateMutability::NonPayable,
     };
 
-    let encoded = func.encode_input(&[
-        Token::Address(addr_verifier.0 .0.into()),
-        Token::Bytes(encoded_verifier),
-    ])?;
-
-    debug!("encoded: {:#?}", hex::encode(&encoded));
+    Ok(func.encode_input(&tokens)?)
+}
 
-    let encoded: TransactionInput = encoded.into();
+/// Verify a batch of proofs in a single call to a verifier rendered with [add_verify_batch].
+/// Returns the gas the batch needs, erroring if it does not fit within the block gas limit.
+pub async fn verify_proofs_via_solidity_batch(
+    proofs: &[Snark<Fr, G1Affine>],
+    addr: H160,
+    addr_vk: Option<H160>,
+    rpc_url: Option<&str>,
+) -> Result<u128, EthError> {
+    let encoded = encode_batch_calldata(addr_vk.as_ref().map(|x| x.0).map(|x| x.0), proofs)?;
 
     let (client, _) = setup_eth_backend(rpc_url, None).await?;
-    let tx = TransactionRequest::default().to(addr_da).input(encoded);
+    let tx = TransactionRequest::default()
+        .to(addr)
+        .input(TransactionInput::from(encoded));
+    debug!("transaction {:#?}", tx);
+
+    let result = client
+        .call(&tx)
+        .await
+        .map_err(|e| EvmVerificationError::SolidityExecution(e.to_string()))?;
+    let result = result.to_vec().last().ok_or(EthError::NoContractOutput)? == &1u8;
+    if !result {
+        return Err(EvmVerificationError::InvalidProof.into());
+    }
+
+    let gas = client.estimate_gas(&tx).await?;
+    info!("estimated batch verify gas cost: {:#?}", gas);
+    if gas > BLOCK_GAS_LIMIT as u128 {
+        return Err(EthError::BatchTooLarge(proofs.len(), gas, BLOCK_GAS_LIMIT));
+    }
+
+    Ok(gas)
+}
+
+/// A proof fixture for the generated Foundry tests, encoded exactly as it is sent to the verifier on-chain
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalldataFixture {
+    /// hex encoded `verifyProof` calldata
+    pub calldata: String,
+    /// hex encoded proof bytes
+    pub proof: String,
+    /// the flattened instances as hex encoded uint256 words
+    pub instances: Vec<String>,
+}
+
+/// Exports a proof as a [CalldataFixture], using the same calldata encoding as [verify_proof_via_solidity]
+pub fn export_calldata_fixture(
+    proof: &Snark<Fr, G1Affine>,
+    vk_address: Option<[u8; 20]>,
+) -> CalldataFixture {
+    let flattened_instances = proof
+        .instances
+        .iter()
+        .flatten()
+        .cloned()
+        .collect::<Vec<_>>();
+
+    let calldata = encode_calldata(vk_address, &proof.proof, &flattened_instances);
+
+    let instances = flattened_instances
+        .iter()
+        .map(|i| format!("{:#066x}", U256::from_le_slice(i.to_repr().as_ref())))
+        .collect();
+
+    CalldataFixture {
+        calldata: format!("0x{}", hex::encode(calldata)),
+        proof: format!("0x{}", hex::encode(&proof.proof)),
+        instances,
+    }
+}
+
+const FOUNDRY_TOML: &str = r#"[profile.default]
+src = "src"
+test = "test"
+script = "script"
+out = "out"
+libs = []
+optimizer = true
+optimizer_runs = OPTIMIZER_RUNS
+fs_permissions = [{ access = "read", path = "./test/fixtures" }]
+"#;
+
+/// Cheatcodes used by the generated tests and script, declared inline so the project has no dependencies to install.
+const FOUNDRY_VM_SOL: &str = r#"// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.20;
+
+interface Vm {
+    function projectRoot() external view returns (string memory);
+    function readFile(string calldata path) external view returns (string memory);
+    function parseJsonBytes(string calldata json, string calldata key) external pure returns (bytes memory);
+    function parseJsonUintArray(string calldata json, string calldata key) external pure returns (uint256[] memory);
+    function startBroadcast() external;
+    function stopBroadcast() external;
+}
+
+Vm constant vm = Vm(address(uint160(uint256(keccak256("hevm cheat code")))));
+"#;
+
+const FOUNDRY_TEST_SOL: &str = r#"// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.20;
+
+import {Halo2Verifier} from "../src/Halo2Verifier.sol";
+import {vm} from "./Vm.sol";
+
+contract Halo2VerifierTest {
+    uint256 constant ORDER = 0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001;
+
+    Halo2Verifier verifier;
+    bytes fixtureCalldata;
+    bytes proof;
+    uint256[] instances;
+
+    function setUp() public {
+        verifier = new Halo2Verifier();
+        string memory fixture = vm.readFile(string.concat(vm.projectRoot(), "/test/fixtures/proof.json"));
+        fixtureCalldata = vm.parseJsonBytes(fixture, ".calldata");
+        proof = vm.parseJsonBytes(fixture, ".proof");
+        instances = vm.parseJsonUintArray(fixture, ".instances");
+    }
+
+    function verify(bytes memory data) internal returns (bool) {
+        (bool success, bytes memory ret) = address(verifier).call(data);
+        return success && ret.length == 32 && abi.decode(ret, (bool));
+    }
+
+    /// The fixture calldata is the exact encoding ezkl sends on-chain.
+    function testVerifiesFixtureCalldata() public {
+        require(verify(fixtureCalldata), "fixture calldata rejected");
+    }
+
+    function testVerifiesFixtureProof() public {
+        require(verify(abi.encodeCall(Halo2Verifier.verifyProof, (proof, instances))), "fixture proof rejected");
+    }
+
+    function testRejectsTamperedInstance() public {
+        require(instances.length > 0, "fixture has no instances");
+        instances[0] = addmod(instances[0], 1, ORDER);
+        require(!verify(abi.encodeCall(Halo2Verifier.verifyProof, (proof, instances))), "tampered instance accepted");
+    }
+}
+"#;
+
+const FOUNDRY_SCRIPT_SOL: &str = r#"// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.20;
+
+import {Halo2Verifier} from "../src/Halo2Verifier.sol";
+import {vm} from "../test/Vm.sol";
+
+/// forge script script/Deploy.s.sol --rpc-url <RPC_URL> --private-key <PRIVATE_KEY> --broadcast
+contract DeployHalo2Verifier {
+    function run() external returns (Halo2Verifier verifier) {
+        vm.startBroadcast();
+        verifier = new Halo2Verifier();
+        vm.stopBroadcast();
+    }
+}
+"#;
+
+/// Writes a Foundry project to `dir` that deploys the verifier and checks `fixture` against it, so `forge test` passes out of the box.
+/// Only standalone `Halo2Verifier` contracts are supported, a reusable verifier also needs its vka deployed.
+pub fn create_foundry_project(
+    dir: &std::path::Path,
+    verifier_solidity: &str,
+    fixture: &CalldataFixture,
+    optimizer_runs: usize,
+) -> Result<(), EthError> {
+    for sub_dir in ["src", "test/fixtures", "script"] {
+        std::fs::create_dir_all(dir.join(sub_dir))?;
+    }
+
+    std::fs::write(
+        dir.join("foundry.toml"),
+        FOUNDRY_TOML.replace("OPTIMIZER_RUNS", &optimizer_runs.to_string()),
+    )?;
+    std::fs::write(dir.join("src/Halo2Verifier.sol"), verifier_solidity)?;
+    std::fs::write(dir.join("test/Vm.sol"), FOUNDRY_VM_SOL)?;
+    std::fs::write(dir.join("test/Halo2Verifier.t.sol"), FOUNDRY_TEST_SOL)?;
+    std::fs::write(dir.join("script/Deploy.s.sol"), FOUNDRY_SCRIPT_SOL)?;
+    std::fs::write(
+        dir.join("test/fixtures/proof.json"),
+        serde_json::to_string_pretty(fixture).map_err(|e| EthError::Io(e.into()))?,
+    )?;
+
+    info!("foundry project written to {}", dir.display());
+    Ok(())
+}
+
+/// Gas and calldata breakdown for verifying a proof on the EVM
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GasReport {
+    /// gas used by the verify transaction, including the intrinsic transaction cost
+    pub gas_used: u64,
+    /// size of the encoded calldata
+    pub calldata_bytes: usize,
+    /// number of proof bytes in the calldata
+    pub proof_bytes: usize,
+    /// number of public instances in the calldata, with the values of the packed tensors
+    pub num_instances: usize,
+    /// calldata gas spent on the proof bytes
+    pub proof_calldata_gas: u64,
+    /// calldata gas spent on the instances
+    pub instance_calldata_gas: u64,
+    /// whether the verify transaction fits within the 15M block gas target
+    pub fits_block_target: bool,
+    /// whether the verify transaction fits within the 30M block gas limit
+    pub fits_block_limit: bool,
+}
+
+impl GasReport {
+    /// Builds a report for a verify transaction of `proof` and `instances` that used `gas_used` gas
+    pub fn new(calldata: &[u8], proof: &[u8], instances: &[Fr], gas_used: u64) -> Self {
+        let instance_calldata_gas = instances
+            .iter()
+            // instances are abi encoded as 32 byte words, byte order doesn't change the cost
+            .map(|i| calldata_gas(i.to_repr().as_ref()))
+            .sum();
+
+        Self {
+            gas_used,
+            calldata_bytes: calldata.len(),
+            proof_bytes: proof.len(),
+            num_instances: instances.len(),
+            proof_calldata_gas: calldata_gas(proof),
+            instance_calldata_gas,
+            fits_block_target: gas_used <= BLOCK_GAS_TARGET,
+            fits_block_limit: gas_used <= BLOCK_GAS_LIMIT,
+        }
+    }
+}
+
+/// EIP-2028 calldata cost: 4 gas per zero byte and 16 gas per non-zero byte
+fn calldata_gas(data: &[u8]) -> u64 {
+    data.iter().map(|b| if *b == 0 { 4 } else { 16 }).sum()
+}
+
+/// Deploys the verifier (and vk artifact for reusable verifiers) in an in-memory revm instance and
+/// reports the gas used to verify `proof`. No node is needed. The EIP-170 limit on the size of deployed code is
+/// enforced, so a verifier that wouldn't deploy on mainnet fails here too.
+pub fn estimate_verify_gas_revm(
+    verifier_bytecode: Bytes,
+    vka_bytecode: Option<Bytes>,
+    proof: &Snark<Fr, G1Affine>,
+) -> Result<GasReport, EthError> {
+    use revm::db::{CacheDB, EmptyDB};
+    use revm::primitives::{
+        Address, ExecutionResult, HaltReason, Output, TransactTo, MAX_CODE_SIZE,
+    };
+
+    // the default anvil account, balance is irrelevant as gas is free here
+    let caller = Address::from_str("0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266")
+        .map_err(|e| EthError::Revm(e.to_string()))?;
+
+    let mut evm = revm::Evm::builder()
+        .with_db(CacheDB::new(EmptyDB::default()))
+        .modify_block_env(|block| block.gas_limit = revm::primitives::U256::from(u64::MAX))
+        .modify_tx_env(|tx| {
+            tx.caller = caller;
+            tx.gas_limit = u64::MAX;
+        })
+        .build();
+
+    let mut transact = |to: TransactTo, data: Vec<u8>| -> Result<(u64, Output), EthError> {
+        evm.tx_mut().transact_to = to;
+        evm.tx_mut().data = data.into();
+        match evm
+            .transact_commit()
+            .map_err(|e| EthError::Revm(format!("{:?}", e)))?
+        {
+            ExecutionResult::Success {
+                gas_used, output, ..
+            } => Ok((gas_used, output)),
+            ExecutionResult::Revert { output, .. } => {
+                Err(EvmVerificationError::Reverted(hex::encode(output)).into())
+            }
+            ExecutionResult::Halt {
+                reason: HaltReason::CreateContractSizeLimit,
+                ..
+            } => Err(EvmVerificationError::DeploymentFailed(format!(
+                "the runtime bytecode exceeds the {} byte EIP-170 limit",
+                MAX_CODE_SIZE
+            ))
+            .into()),
+            ExecutionResult::Halt { reason, .. } => {
+                Err(EvmVerificationError::SolidityExecution(format!("{:?}", reason)).into())
+            }
+        }
+    };
+
+    let mut deploy = |bytecode: Bytes| -> Result<Address, EthError> {
+        match transact(TransactTo::create(), bytecode.to_vec())? {
+            (_, Output::Create(_, Some(addr))) => Ok(addr),
+            _ => Err(EvmVerificationError::DeploymentFailed("no address returned".into()).into()),
+        }
+    };
+
+    let verifier = deploy(verifier_bytecode)?;
+    let vka = vka_bytecode.map(&mut deploy).transpose()?;
+
+    let calldata = encode_calldata(
+        vka.as_ref().map(|x| x.0).map(|x| x.0),
+        &proof.proof,
+        &proof.instances.iter().flatten().cloned().collect_vec(),
+    );
+
+    let (gas_used, output) = transact(TransactTo::Call(verifier), calldata.clone())?;
+    if output.data().last() != Some(&1u8) {
+        return Err(EvmVerificationError::InvalidProof.into());
+    }
+
+    let instances = proof.instances.iter().flatten().cloned().collect_vec();
+    Ok(GasReport::new(
+        &calldata,
+        &proof.proof,
+        &instances,
+        gas_used,
+    ))
+}
+
+/// Deploys the verifier (and vk artifact for reusable verifiers) to a node and reports the gas
+/// used to verify `proof`. If no `rpc_url` is given a local anvil instance is spun up.
+pub async fn estimate_verify_gas_via_node(
+    sol_code_path: PathBuf,
+    vka_path: Option<PathBuf>,
+    proof: &Snark<Fr, G1Affine>,
+    rpc_url: Option<&str>,
+    runs: usize,
+) -> Result<GasReport, EthError> {
+    // anvil must stay alive until the verify call completes
+    let (_anvil, endpoint) = match rpc_url {
+        Some(rpc_url) => (None, rpc_url.to_string()),
+        None => {
+            let anvil = Anvil::new()
+                .args(["--code-size-limit=41943040", "--disable-block-gas-limit"])
+                .spawn();
+            let endpoint = anvil.endpoint();
+            (Some(anvil), endpoint)
+        }
+    };
+
+    let verifier_name = match vka_path {
+        Some(_) => "Halo2VerifierReusable",
+        None => "Halo2Verifier",
+    };
+    let addr =
+        deploy_contract_via_solidity(sol_code_path, Some(&endpoint), runs, None, verifier_name)
+            .await?;
+    let addr_vk = match vka_path {
+        Some(vka_path) => Some(
+            deploy_contract_via_solidity(
+                vka_path,
+                Some(&endpoint),
+                runs,
+                None,
+                "Halo2VerifyingArtifact",
+            )
+            .await?,
+        ),
+        None => None,
+    };
+
+    let calldata = encode_calldata(
+        addr_vk.as_ref().map(|x| x.0).map(|x| x.0),
+        &proof.proof,
+        &proof.instances.iter().flatten().cloned().collect_vec(),
+    );
+
+    let (client, _) = setup_eth_backend(Some(&endpoint), None).await?;
+    let tx = TransactionRequest::default()
+        .to(addr)
+        .input(TransactionInput::from(calldata.clone()));
+    let result = client
+        .call(&tx)
+        .await
+        .map_err(|e| EvmVerificationError::SolidityExecution(e.to_string()))?;
+    if result.to_vec().last() != Some(&1u8) {
+        return Err(EvmVerificationError::InvalidProof.into());
+    }
+    let gas_used = client.estimate_gas(&tx).await?;
+
+    let instances = proof.instances.iter().flatten().cloned().collect_vec();
+    Ok(GasReport::new(
+        &calldata,
+        &proof.proof,
+        &instances,
+        gas_used as u64,
+    ))
+}
+
+/// Deploys the verifier and the PackedInstances contract wrapping it to a node and reports the gas
+/// used to verify `proof` through the wrapper, with the values of the packed tensors from `witness`
+/// in calldata. If no `rpc_url` is given a local anvil instance is spun up.
+pub async fn estimate_packed_verify_gas_via_node(
+    sol_code_path: PathBuf,
+    packed_sol_code_path: PathBuf,
+    settings: &GraphSettings,
+    proof: &Snark<Fr, G1Affine>,
+    witness: &GraphWitness,
+    rpc_url: Option<&str>,
+    runs: usize,
+) -> Result<GasReport, EthError> {
+    // anvil must stay alive until the verify call completes
+    let (_anvil, endpoint) = match rpc_url {
+        Some(rpc_url) => (None, rpc_url.to_string()),
+        None => {
+            let anvil = Anvil::new()
+                .args(["--code-size-limit=41943040", "--disable-block-gas-limit"])
+                .spawn();
+            let endpoint = anvil.endpoint();
+            (Some(anvil), endpoint)
+        }
+    };
+
+    let verifier =
+        deploy_contract_via_solidity(sol_code_path, Some(&endpoint), runs, None, "Halo2Verifier")
+            .await?;
+    let addr = deploy_packed_verifier_via_solidity(
+        packed_sol_code_path,
+        verifier,
+        Some(&endpoint),
+        runs,
+        None,
+    )
+    .await?;
+
+    let (instances, values) = unpack_instances(settings, proof, witness)?;
+    let calldata = encode_packed_calldata(&proof.proof, &instances, &values);
+
+    let (client, _) = setup_eth_backend(Some(&endpoint), None).await?;
+    let tx = TransactionRequest::default()
+        .to(addr)
+        .input(TransactionInput::from(calldata.clone()));
+    let result = client
+        .call(&tx)
+        .await
+        .map_err(|e| EvmVerificationError::SolidityExecution(e.to_string()))?;
+    if result.to_vec().last() != Some(&1u8) {
+        return Err(EvmVerificationError::InvalidProof.into());
+    }
+    let gas_used = client.estimate_gas(&tx).await?;
+
+    let calldata_instances = [instances, values].concat();
+    Ok(GasReport::new(
+        &calldata,
+        &proof.proof,
+        &calldata_instances,
+        gas_used as u64,
+    ))
+}
+
+fn count_decimal_places(num: f32) -> usize {
+    // Convert the number to a string
+    let s = num.to_string();
+
+    // Find the decimal point
+    match s.find('.') {
+        Some(index) => {
+            // Count the number of characters after the decimal point
+            s[index + 1..].len()
+        }
+        None => 0,
+    }
+}
+
+/// Scales the test data by its decimals into the integers stored by the test contracts
+fn scale_test_data(data: &[Vec<FileSourceInner>]) -> Result<(Vec<I256>, Vec<u8>), EthError> {
+    let mut decimals = vec![];
+    let mut scaled_by_decimals_data = vec![];
+    for input in &data[0] {
+        if input.is_float() {
+            let input = input.to_float() as f32;
+            let decimal_places = count_decimal_places(input) as u8;
+            let scaled_by_decimals = input * f32::powf(10., decimal_places.into());
+            scaled_by_decimals_data.push(I256::from_dec_str(
+                &(scaled_by_decimals as i32).to_string(),
+            )?);
+            decimals.push(decimal_places);
+        } else if input.is_field() {
+            let input = input.to_field(0);
+            let hex_str_fr = format!("{:?}", input);
+            // remove the 0x prefix
+            let hex_str_fr = &hex_str_fr[2..];
+            scaled_by_decimals_data.push(I256::from_raw(U256::from_str_radix(hex_str_fr, 16)?));
+            decimals.push(0);
+        }
+    }
+
+    Ok((scaled_by_decimals_data, decimals))
+}
+
+///
+pub async fn setup_test_contract<M: 'static + Provider<Http<Client>, Ethereum>>(
+    client: Arc<M>,
+    data: &[Vec<FileSourceInner>],
+) -> Result<(TestReads::TestReadsInstance<Http<Client>, Arc<M>>, Vec<u8>), EthError> {
+    let (scaled_by_decimals_data, decimals) = scale_test_data(data)?;
+
+    // Compile the contract
+    let contract = TestReads::deploy(client, scaled_by_decimals_data).await?;
+
+    Ok((contract, decimals))
+}
+
+/// Verify a proof using a Solidity DataAttestation contract.
+/// Used for testing purposes.
+pub async fn verify_proof_with_data_attestation(
+    proof: Snark<Fr, G1Affine>,
+    addr_verifier: H160,
+    addr_da: H160,
+    addr_vk: Option<H160>,
+    rpc_url: Option<&str>,
+) -> Result<bool, EthError> {
+    use ethabi::{Function, Param, ParamType, StateMutability, Token};
+
+    let mut public_inputs: Vec<U256> = vec![];
+    let flattened_instances = proof.instances.into_iter().flatten();
+
+    for val in flattened_instances.clone() {
+        let bytes = val.to_repr();
+        let u = U256::from_le_slice(bytes.inner().as_slice());
+        public_inputs.push(u);
+    }
+
+    let encoded_verifier = encode_calldata(
+        addr_vk.as_ref().map(|x| x.0).map(|x| x.0),
+        &proof.proof,
+        &flattened_instances.collect::<Vec<_>>(),
+    );
+
+    debug!("encoded: {:#?}", hex::encode(&encoded_verifier));
+
+    debug!("public_inputs: {:#?}", public_inputs);
+    debug!("proof: {:#?}", Bytes::from(proof.proof.to_vec()));
+
+    #[allow(deprecated)]
+    let func = Function {
+        name: "verifyWithDataAttestation".to_owned(),
+        inputs: vec![
+            Param {
+                name: "verifier".to_owned(),
+                kind: ParamType::Address,
+                internal_type: None,
+            },
+            Param {
+                name: "encoded".to_owned(),
+                kind: ParamType::Bytes,
+                internal_type: None,
+            },
+        ],
+        outputs: vec![Param {
+            name: "success".to_owned(),
+            kind: ParamType::Bool,
+            internal_type: None,
+        }],
+        constant: None,
+        state_mutability: StateMutability::View,
+    };
+
+    let encoded = func.encode_input(&[
+        Token::Address(addr_verifier.0 .0.into()),
+        Token::Bytes(encoded_verifier),
+    ])?;
+
+    debug!("encoded: {:#?}", hex::encode(&encoded));
+
+    let encoded: TransactionInput = encoded.into();
+
+    let (client, _) = setup_eth_backend(rpc_url, None).await?;
+    let tx = TransactionRequest::default().to(addr_da).input(encoded);
     debug!("transaction {:#?}", tx);
     info!(
         "estimated verify gas cost: {:#?}",
         client.estimate_gas(&tx).await?
     );
 
-    let result = client.call(&tx).await;
-    if let Err(e) = result {
-        return Err(EvmVerificationError::SolidityExecution(e.to_string()).into());
+    let result = client.call(&tx).await;
+    if let Err(e) = result {
+        return Err(EvmVerificationError::SolidityExecution(e.to_string()).into());
+    }
+    let result = result?;
+    debug!("result: {:#?}", result);
+    // decode return bytes value into uint8
+    let result = result.to_vec().last().ok_or(EthError::NoContractOutput)? == &1u8;
+    if !result {
+        return Err(EvmVerificationError::InvalidProof.into());
+    }
+
+    Ok(true)
+}
+
+/// Verify a proof using a Solidity StorageAttestation contract, fetching the storage proofs for `source`
+pub async fn verify_proof_with_storage_attestation(
+    proof: Snark<Fr, G1Affine>,
+    addr_verifier: H160,
+    addr_da: H160,
+    addr_vk: Option<H160>,
+    rpc_url: Option<&str>,
+    source: &StorageProofSource,
+) -> Result<bool, EthError> {
+    use ethabi::{Function, Param, ParamType, StateMutability, Token};
+
+    let flattened_instances = proof.instances.into_iter().flatten();
+
+    let encoded_verifier = encode_calldata(
+        addr_vk.as_ref().map(|x| x.0).map(|x| x.0),
+        &proof.proof,
+        &flattened_instances.collect::<Vec<_>>(),
+    );
+
+    // the storage is proven against the node serving the source, which may differ from the verifying node
+    let (source_client, _) = setup_eth_backend(Some(&source.rpc), None).await?;
+    let storage_proofs = fetch_storage_proofs(source_client, source).await?;
+
+    let nodes =
+        |proof: &Vec<Bytes>| Token::Array(proof.iter().map(|n| Token::Bytes(n.to_vec())).collect());
+
+    let param = |name: &str, kind: ParamType| Param {
+        name: name.to_owned(),
+        kind,
+        internal_type: None,
+    };
+    let bytes_array = || ParamType::Array(Box::new(ParamType::Bytes));
+
+    #[allow(deprecated)]
+    let func = Function {
+        name: "verifyWithStorageProofs".to_owned(),
+        inputs: vec![
+            param("verifier", ParamType::Address),
+            param("encoded", ParamType::Bytes),
+            param("header", ParamType::Bytes),
+            param("accountProofs", ParamType::Array(Box::new(bytes_array()))),
+            param(
+                "storageProofs",
+                ParamType::Array(Box::new(ParamType::Array(Box::new(bytes_array())))),
+            ),
+        ],
+        outputs: vec![param("success", ParamType::Bool)],
+        constant: None,
+        state_mutability: StateMutability::View,
+    };
+
+    let encoded = func.encode_input(&[
+        Token::Address(addr_verifier.0 .0.into()),
+        Token::Bytes(encoded_verifier),
+        Token::Bytes(storage_proofs.header.to_vec()),
+        Token::Array(storage_proofs.account_proofs.iter().map(nodes).collect()),
+        Token::Array(
+            storage_proofs
+                .storage_proofs
+                .iter()
+                .map(|slots| Token::Array(slots.iter().map(nodes).collect()))
+                .collect(),
+        ),
+    ])?;
+
+    debug!("encoded: {:#?}", hex::encode(&encoded));
+
+    let encoded: TransactionInput = encoded.into();
+
+    let (client, _) = setup_eth_backend(rpc_url, None).await?;
+    let tx = TransactionRequest::default().to(addr_da).input(encoded);
+    debug!("transaction {:#?}", tx);
+
+    let result = client.call(&tx).await;
+    if let Err(e) = result {
+        return Err(EvmVerificationError::SolidityExecution(e.to_string()).into());
+    }
+    let result = result?;
+    debug!("result: {:#?}", result);
+    // decode return bytes value into uint8
+    let result = result.to_vec().last().ok_or(EthError::NoContractOutput)? == &1u8;
+    if !result {
+        return Err(EvmVerificationError::InvalidProof.into());
+    }
+
+    info!(
+        "estimated verify gas cost: {:#?}",
+        client.estimate_gas(&tx).await?
+    );
+
+    Ok(true)
+}
+
+/// The eth_getProof proofs for a [StorageProofSource], in the order the StorageAttestation contract checks them
+#[derive(Debug, Clone)]
+pub struct StorageProofs {
+    /// rlp encoded header of the block the proofs are against
+    pub header: Bytes,
+    /// the account proof of each account
+    pub account_proofs: Vec<Vec<Bytes>>,
+    /// the storage proof of each slot of each account
+    pub storage_proofs: Vec<Vec<Vec<Bytes>>>,
+    /// the proven values of each slot, as 32 byte words
+    pub values: Vec<Bytes>,
+    /// the decimals of each value
+    pub decimals: Vec<u8>,
+}
+
+/// Fetches the account and storage proofs for all the slots in `source` at `source.block`
+pub async fn fetch_storage_proofs<M: 'static + Provider<Http<Client>, Ethereum>>(
+    client: Arc<M>,
+    source: &StorageProofSource,
+) -> Result<StorageProofs, EthError> {
+    use alloy::primitives::keccak256;
+    use alloy::rpc::types::eth::{BlockId, BlockNumberOrTag};
+
+    let block = client
+        .get_block_by_number(BlockNumberOrTag::Number(source.block), false)
+        .await?
+        .ok_or(EthError::BlockNotFound(source.block))?;
+
+    let header = rlp_encode_header(&block.header);
+    let hash = keccak256(&header);
+    // re-encoding the header from its json fields must give back the original block hash
+    if Some(hash) != block.header.hash {
+        return Err(EthError::BlockHashMismatch(
+            source.block,
+            hash.to_string(),
+            block.header.hash.unwrap_or_default().to_string(),
+        ));
+    }
+    if let Some(block_hash) = &source.block_hash {
+        let expected =
+            B256::from_str(block_hash).map_err(|_| EthError::BlockHashParse(block_hash.clone()))?;
+        if hash != expected {
+            return Err(EthError::BlockHashMismatch(
+                source.block,
+                hash.to_string(),
+                expected.to_string(),
+            ));
+        }
+    }
+
+    let (addresses, slots, _) = parse_slots_of_accounts(source.accounts.clone())?;
+
+    let mut account_proofs = vec![];
+    let mut storage_proofs = vec![];
+    let mut values = vec![];
+    let mut decimals = vec![];
+    for ((address, slots), account) in addresses.into_iter().zip(slots).zip(&source.accounts) {
+        let proof = client
+            .get_proof(address, slots)
+            .block_id(BlockId::number(source.block))
+            .await?;
+        debug!("storage proof for {}: {:#?}", address, proof);
+        account_proofs.push(proof.account_proof);
+        for (storage_proof, (_, decimal)) in proof.storage_proof.into_iter().zip(&account.slots) {
+            values.push(Bytes::from(
+                storage_proof.value.to_be_bytes::<32>().to_vec(),
+            ));
+            decimals.push(*decimal);
+            storage_proofs.push(storage_proof.proof);
+        }
+    }
+
+    // regroup the storage proofs by account
+    let mut grouped = vec![];
+    let mut storage_proofs = storage_proofs.into_iter();
+    for account in &source.accounts {
+        grouped.push(storage_proofs.by_ref().take(account.slots.len()).collect());
+    }
+
+    Ok(StorageProofs {
+        header: Bytes::from(header),
+        account_proofs,
+        storage_proofs: grouped,
+        values,
+        decimals,
+    })
+}
+
+/// Rlp encodes a header so that it hashes to the block hash, each fork's fields are appended when present
+fn rlp_encode_header(header: &alloy::rpc::types::eth::Header) -> Vec<u8> {
+    let mut fields = vec![
+        rlp_encode_bytes(header.parent_hash.as_slice()),
+        rlp_encode_bytes(header.uncles_hash.as_slice()),
+        rlp_encode_bytes(header.miner.as_slice()),
+        rlp_encode_bytes(header.state_root.as_slice()),
+        rlp_encode_bytes(header.transactions_root.as_slice()),
+        rlp_encode_bytes(header.receipts_root.as_slice()),
+        rlp_encode_bytes(header.logs_bloom.as_slice()),
+        rlp_encode_uint(header.difficulty),
+        rlp_encode_uint(U256::from(header.number.unwrap_or_default())),
+        rlp_encode_uint(U256::from(header.gas_limit)),
+        rlp_encode_uint(U256::from(header.gas_used)),
+        rlp_encode_uint(U256::from(header.timestamp)),
+        rlp_encode_bytes(&header.extra_data),
+        rlp_encode_bytes(header.mix_hash.unwrap_or_default().as_slice()),
+        rlp_encode_bytes(header.nonce.unwrap_or_default().as_slice()),
+    ];
+    if let Some(base_fee) = header.base_fee_per_gas {
+        fields.push(rlp_encode_uint(U256::from(base_fee)));
+    }
+    if let Some(withdrawals_root) = header.withdrawals_root {
+        fields.push(rlp_encode_bytes(withdrawals_root.as_slice()));
+    }
+    if let Some(blob_gas_used) = header.blob_gas_used {
+        fields.push(rlp_encode_uint(U256::from(blob_gas_used)));
+    }
+    if let Some(excess_blob_gas) = header.excess_blob_gas {
+        fields.push(rlp_encode_uint(U256::from(excess_blob_gas)));
+    }
+    if let Some(parent_beacon_block_root) = header.parent_beacon_block_root {
+        fields.push(rlp_encode_bytes(parent_beacon_block_root.as_slice()));
+    }
+    rlp_encode_list(&fields)
+}
+
+fn rlp_length_prefix(len: usize, offset: u8) -> Vec<u8> {
+    if len < 56 {
+        vec![offset + len as u8]
+    } else {
+        let len_bytes = len.to_be_bytes();
+        let first = len_bytes.iter().position(|b| *b != 0).unwrap_or(0);
+        let mut prefix = vec![offset + 55 + (len_bytes.len() - first) as u8];
+        prefix.extend_from_slice(&len_bytes[first..]);
+        prefix
     }
-    let result = result?;
-    debug!("result: {:#?}", result);
-    // decode return bytes value into uint8
-    let result = result.to_vec().last().ok_or(EthError::NoContractOutput)? == &1u8;
-    if !result {
-        return Err(EvmVerificationError::InvalidProof.into());
+}
+
+fn rlp_encode_bytes(bytes: &[u8]) -> Vec<u8> {
+    if bytes.len() == 1 && bytes[0] < 0x80 {
+        return bytes.to_vec();
     }
+    let mut encoded = rlp_length_prefix(bytes.len(), 0x80);
+    encoded.extend_from_slice(bytes);
+    encoded
+}
 
-    Ok(true)
+fn rlp_encode_uint(value: U256) -> Vec<u8> {
+    // integers are encoded as big endian bytes without leading zeros
+    let bytes = value.to_be_bytes::<32>();
+    let first = bytes.iter().position(|b| *b != 0).unwrap_or(bytes.len());
+    rlp_encode_bytes(&bytes[first..])
+}
+
+fn rlp_encode_list(items: &[Vec<u8>]) -> Vec<u8> {
+    let payload = items.concat();
+    let mut encoded = rlp_length_prefix(payload.len(), 0xc0);
+    encoded.extend(payload);
+    encoded
+}
+
+/// Tests storage proof attestation by deploying a contract that stores the network input and or output
+/// data in its storage, returning the slots holding the data and the block they were written at.
+pub async fn test_storage_proof_data<M: 'static + Provider<Http<Client>, Ethereum>>(
+    client: Arc<M>,
+    data: &[Vec<FileSourceInner>],
+) -> Result<(Vec<SlotsOfAccount>, u64), EthError> {
+    use alloy::primitives::keccak256;
+
+    let (contract, decimals) = setup_test_contract(client.clone(), data).await?;
+    let block = client.get_block_number().await?;
+
+    // `arr` is the first variable in the TestReads contract, so its elements start at keccak256(0)
+    let start = U256::from_be_bytes(keccak256(B256::ZERO).0);
+    let slots = data
+        .iter()
+        .flatten()
+        .enumerate()
+        .map(|(i, _)| (format!("{:#x}", start + U256::from(i)), decimals[i]))
+        .collect();
+
+    let slots_of_account = SlotsOfAccount {
+        slots,
+        address: hex::encode(contract.address().0 .0),
+    };
+    info!(
+        "slots_of_account at block {}: {:#?}",
+        block, slots_of_account
+    );
+    Ok((vec![slots_of_account], block))
 }
 
 /// Tests on-chain data storage by deploying a contract that stores the network input and or output
@@ -817,6 +2136,126 @@ pub async fn test_on_chain_data<M: 'static + Provider<Http<Client>, Ethereum>>(
     let calls_to_account = CallsToAccount {
         call_data: calldata,
         address: hex::encode(contract.address().0 .0),
+        returns: vec![],
+    };
+    info!("calls_to_account: {:#?}", calls_to_account);
+    Ok(vec![calls_to_account])
+}
+
+const TEST_ORACLE_SOL: &str = r#"// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.20;
+
+contract TestOracle {
+    struct Answer {
+        string source;
+        int256 price;
+    }
+
+    struct Round {
+        uint256 timestamp;
+        Answer answer;
+        address feed;
+    }
+
+    int256[] public prices;
+
+    constructor(int256[] memory _prices) {
+        prices = _prices;
+    }
+
+    function latestRound(
+        uint256 i
+    )
+        external
+        view
+        returns (uint80 roundId, Round memory round, int256[] memory history)
+    {
+        history = new int256[](2);
+        history[1] = prices[i];
+        round = Round(block.timestamp, Answer("ezkl", prices[i]), address(this));
+        return (uint80(i), round, history);
+    }
+}
+"#;
+
+const TEST_ORACLE_ABI: &str = r#"{
+    "type": "function",
+    "name": "latestRound",
+    "inputs": [{ "name": "i", "type": "uint256" }],
+    "outputs": [
+        { "name": "roundId", "type": "uint80" },
+        {
+            "name": "round",
+            "type": "tuple",
+            "components": [
+                { "name": "timestamp", "type": "uint256" },
+                {
+                    "name": "answer",
+                    "type": "tuple",
+                    "components": [
+                        { "name": "source", "type": "string" },
+                        { "name": "price", "type": "int256" }
+                    ]
+                },
+                { "name": "feed", "type": "address" }
+            ]
+        },
+        { "name": "history", "type": "int256[]" }
+    ],
+    "stateMutability": "view"
+}"#;
+
+/// Tests on-chain data read from view functions with nested return types, by deploying a mock oracle
+/// whose `latestRound` returns each value inside a struct and a dynamic array. The calls alternate
+/// between selecting the value from the struct and from the array.
+pub async fn test_nested_on_chain_data<M: 'static + Provider<Http<Client>, Ethereum>>(
+    client: Arc<M>,
+    data: &[Vec<FileSourceInner>],
+) -> Result<Vec<CallsToAccount>, EthError> {
+    use alloy::primitives::keccak256;
+
+    let (prices, decimals) = scale_test_data(data)?;
+
+    let (abi, bytecode, runtime_bytecode) =
+        get_inline_contract_artifacts(TEST_ORACLE_SOL, "TestOracle", 1).await?;
+
+    let factory = get_sol_contract_factory(
+        abi,
+        bytecode,
+        runtime_bytecode,
+        client,
+        Some((DynSeqToken(
+            prices
+                .iter()
+                .map(|p| WordToken(B256::from(p.into_raw().to_be_bytes::<32>())))
+                .collect_vec(),
+        ),)),
+    )?;
+    let address = factory.deploy().await?;
+
+    let abi: AbiFunction = serde_json::from_str(TEST_ORACLE_ABI)
+        .map_err(|e| EthError::ReturnSelector(e.to_string()))?;
+    let selector = &keccak256("latestRound(uint256)")[..4];
+
+    let mut call_data = vec![];
+    let mut returns = vec![];
+    for i in 0..prices.len() {
+        let call = [selector, &U256::from(i).to_be_bytes::<32>()].concat();
+        call_data.push((hex::encode(call), decimals[i]));
+        let path = match i % 2 {
+            0 => "round.answer.price",
+            _ => "history.1",
+        };
+        returns.push(Some(ReturnSelector {
+            abi: abi.clone(),
+            path: path.to_string(),
+        }));
+    }
+
+    let calls_to_account = CallsToAccount {
+        call_data,
+        address: hex::encode(address.0 .0),
+        returns,
     };
     info!("calls_to_account: {:#?}", calls_to_account);
     Ok(vec![calls_to_account])
@@ -827,7 +2266,9 @@ pub async fn read_on_chain_inputs<M: 'static + Provider<Http<Client>, Ethereum>>
     client: Arc<M>,
     address: H160,
     data: &Vec<CallsToAccount>,
+    block: Option<u64>,
 ) -> Result<(Vec<Bytes>, Vec<u8>), EthError> {
+    use alloy::rpc::types::eth::BlockId;
     // Iterate over all on-chain inputs
 
     let mut fetched_inputs = vec![];
@@ -836,7 +2277,7 @@ pub async fn read_on_chain_inputs<M: 'static + Provider<Http<Client>, Ethereum>>
         // Construct the address
         let contract_address_bytes = hex::decode(&on_chain_data.address)?;
         let contract_address = H160::from_slice(&contract_address_bytes);
-        for (call_data, decimal) in &on_chain_data.call_data {
+        for (j, (call_data, decimal)) in on_chain_data.call_data.iter().enumerate() {
             let call_data_bytes = hex::decode(call_data)?;
             let input: TransactionInput = call_data_bytes.into();
 
@@ -846,8 +2287,15 @@ pub async fn read_on_chain_inputs<M: 'static + Provider<Http<Client>, Ethereum>>
                 .input(input);
             debug!("transaction {:#?}", tx);
 
-            let result = client.call(&tx).await?;
+            let result = match block {
+                Some(block) => client.call(&tx).block_id(BlockId::number(block)).await?,
+                None => client.call(&tx).await?,
+            };
             debug!("return data {:#?}", result);
+            let result = match on_chain_data.return_selector(j) {
+                Some(selector) => select_return_word(&result, &return_steps(selector)?)?,
+                None => result,
+            };
             fetched_inputs.push(result);
             decimals.push(*decimal);
         }
@@ -855,6 +2303,275 @@ pub async fn read_on_chain_inputs<M: 'static + Provider<Http<Client>, Ethereum>>
     Ok((fetched_inputs, decimals))
 }
 
+/// A step locating the attested word in the data returned by a view call, see `selectReturnWord`
+/// in the DataAttestation contract
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReturnStep {
+    /// Move to the static component `offset` bytes into the current tuple
+    Field(usize),
+    /// Follow the offset stored `offset` bytes into the current tuple to a dynamic component
+    DynamicField(usize),
+    /// Move to the static element `index` of the dynamic array at the current position
+    Element {
+        /// index of the element, checked against the length of the array
+        index: usize,
+        /// encoded size of an element
+        stride: usize,
+    },
+    /// Follow the offset of the dynamic element `index` of the dynamic array at the current position
+    DynamicElement(usize),
+    /// Check the unsigned word at the current position fits in an int256
+    Unsigned,
+}
+
+impl ReturnStep {
+    /// Packs the step into a word as `kind << 248 | stride << 128 | arg`, the layout the DataAttestation contract reads
+    pub fn to_word(&self) -> U256 {
+        let (kind, stride, arg) = match *self {
+            ReturnStep::Field(offset) => (0u8, 0, offset),
+            ReturnStep::DynamicField(offset) => (1, 0, offset),
+            ReturnStep::Element { index, stride } => (2, stride, index),
+            ReturnStep::DynamicElement(index) => (3, 0, index),
+            ReturnStep::Unsigned => (4, 0, 0),
+        };
+        (U256::from(kind) << 248) | (U256::from(stride) << 128) | U256::from(arg)
+    }
+}
+
+/// The shape of an ABI type, enough to locate its components in an encoding
+enum AbiType {
+    Number { unsigned: bool },
+    Opaque { dynamic: bool },
+    Tuple(Vec<(String, AbiType)>),
+    FixedArray(Box<AbiType>, usize),
+    Array(Box<AbiType>),
+}
+
+impl AbiType {
+    fn parse(param: &AbiParam) -> Result<Self, EthError> {
+        let invalid = || EthError::ReturnSelector(format!("unsupported type {}", param.ty));
+        let bits = |b: &str| {
+            b.is_empty()
+                || b.parse::<u16>()
+                    .is_ok_and(|n| n % 8 == 0 && (8..=256).contains(&n))
+        };
+        let (base, dims) = param
+            .ty
+            .split_at(param.ty.find('[').unwrap_or(param.ty.len()));
+        let mut ty = match base {
+            "tuple" => AbiType::tuple(&param.components)?,
+            "bool" => AbiType::Number { unsigned: true },
+            "bytes" | "string" => AbiType::Opaque { dynamic: true },
+            "address" | "function" => AbiType::Opaque { dynamic: false },
+            _ => match (
+                base.strip_prefix("uint"),
+                base.strip_prefix("int"),
+                base.strip_prefix("bytes"),
+            ) {
+                (Some(b), _, _) if bits(b) => AbiType::Number { unsigned: true },
+                (_, Some(b), _) if bits(b) => AbiType::Number { unsigned: false },
+                (_, _, Some(b)) if b.parse::<u8>().is_ok_and(|n| (1..=32).contains(&n)) => {
+                    AbiType::Opaque { dynamic: false }
+                }
+                _ => return Err(invalid()),
+            },
+        };
+        // `uint256[2][]` is a dynamic array of `uint256[2]`
+        for dim in dims.split_terminator(']') {
+            let dim = dim.strip_prefix('[').ok_or_else(invalid)?;
+            ty = match dim {
+                "" => AbiType::Array(Box::new(ty)),
+                len => AbiType::FixedArray(Box::new(ty), len.parse().map_err(|_| invalid())?),
+            };
+        }
+        Ok(ty)
+    }
+
+    fn tuple(params: &[AbiParam]) -> Result<Self, EthError> {
+        Ok(AbiType::Tuple(
+            params
+                .iter()
+                .map(|p| Ok((p.name.clone(), AbiType::parse(p)?)))
+                .collect::<Result<_, EthError>>()?,
+        ))
+    }
+
+    fn is_dynamic(&self) -> bool {
+        match self {
+            AbiType::Number { .. } => false,
+            AbiType::Opaque { dynamic } => *dynamic,
+            AbiType::Tuple(components) => components.iter().any(|(_, c)| c.is_dynamic()),
+            AbiType::FixedArray(element, _) => element.is_dynamic(),
+            AbiType::Array(_) => true,
+        }
+    }
+
+    /// The number of bytes the type takes up in the head of an enclosing tuple
+    fn head_size(&self) -> usize {
+        match self {
+            _ if self.is_dynamic() => 32,
+            AbiType::Tuple(components) => components.iter().map(|(_, c)| c.head_size()).sum(),
+            AbiType::FixedArray(element, len) => element.head_size() * len,
+            _ => 32,
+        }
+    }
+}
+
+/// Resolves the path of a return selector against the outputs of its function into the steps
+/// locating the attested word in the returned data
+pub fn return_steps(selector: &ReturnSelector) -> Result<Vec<ReturnStep>, EthError> {
+    let invalid = |segment: &str| {
+        EthError::ReturnSelector(format!("{} has no field {}", selector.path, segment))
+    };
+
+    let mut ty = AbiType::tuple(&selector.abi.outputs)?;
+    let mut steps = vec![];
+    for segment in selector.path.split('.') {
+        let index = |len: usize| {
+            segment
+                .parse::<usize>()
+                .ok()
+                .filter(|i| *i < len)
+                .ok_or_else(|| invalid(segment))
+        };
+        ty = match ty {
+            AbiType::Tuple(mut components) => {
+                let i = match components
+                    .iter()
+                    .position(|(name, _)| !segment.is_empty() && name == segment)
+                {
+                    Some(i) => i,
+                    None => index(components.len())?,
+                };
+                let offset = components[..i].iter().map(|(_, c)| c.head_size()).sum();
+                let (_, component) = components.swap_remove(i);
+                steps.push(match component.is_dynamic() {
+                    true => ReturnStep::DynamicField(offset),
+                    false => ReturnStep::Field(offset),
+                });
+                component
+            }
+            AbiType::FixedArray(element, len) => {
+                let i = index(len)?;
+                steps.push(match element.is_dynamic() {
+                    true => ReturnStep::DynamicField(i * 32),
+                    false => ReturnStep::Field(i * element.head_size()),
+                });
+                *element
+            }
+            AbiType::Array(element) => {
+                // the length is only known once the call is made, so the index is checked then
+                let i = segment.parse::<u32>().map_err(|_| invalid(segment))? as usize;
+                steps.push(match element.is_dynamic() {
+                    true => ReturnStep::DynamicElement(i),
+                    false => ReturnStep::Element {
+                        index: i,
+                        stride: element.head_size(),
+                    },
+                });
+                *element
+            }
+            _ => return Err(invalid(segment)),
+        };
+    }
+
+    match ty {
+        AbiType::Number { unsigned } => {
+            if unsigned {
+                steps.push(ReturnStep::Unsigned);
+            }
+            Ok(steps)
+        }
+        _ => Err(EthError::ReturnSelector(format!(
+            "{} is not an int, uint or bool",
+            selector.path
+        ))),
+    }
+}
+
+/// Follows the steps of a return selector through the data returned by a view call, mirroring
+/// `selectReturnWord` in the DataAttestation contract. The selected word is returned on its own,
+/// so it is quantized like the return of a call returning a single int256.
+pub fn select_return_word(data: &[u8], steps: &[ReturnStep]) -> Result<Bytes, EthError> {
+    fn add(a: usize, b: usize) -> Result<usize, EthError> {
+        a.checked_add(b).ok_or(EthError::ReturnData)
+    }
+    let word = |pos: usize| -> Result<U256, EthError> {
+        data.get(pos..add(pos, 32)?)
+            .map(U256::from_be_slice)
+            .ok_or(EthError::ReturnData)
+    };
+    let offset = |pos: usize| -> Result<usize, EthError> {
+        let offset = word(pos)?;
+        match offset.bit_len() <= 64 {
+            true => usize::try_from(offset.as_limbs()[0]).map_err(|_| EthError::ReturnData),
+            false => Err(EthError::ReturnData),
+        }
+    };
+    let in_bounds = |index: usize, array: usize| -> Result<(), EthError> {
+        match U256::from(index) < word(array)? {
+            true => Ok(()),
+            false => Err(EthError::ReturnData),
+        }
+    };
+
+    let mut base = 0;
+    for step in steps {
+        match *step {
+            ReturnStep::Field(field) => base = add(base, field)?,
+            ReturnStep::DynamicField(field) => base = add(base, offset(add(base, field)?)?)?,
+            ReturnStep::Element { index, stride } => {
+                in_bounds(index, base)?;
+                let element = index.checked_mul(stride).ok_or(EthError::ReturnData)?;
+                base = add(add(base, 32)?, element)?;
+            }
+            ReturnStep::DynamicElement(index) => {
+                in_bounds(index, base)?;
+                // element offsets are relative to the start of the elements, after the length
+                base = add(base, 32)?;
+                base = add(base, offset(add(base, index * 32)?)?)?;
+            }
+            ReturnStep::Unsigned => {
+                if word(base)?.bit(255) {
+                    return Err(EthError::ReturnData);
+                }
+            }
+        }
+    }
+    Ok(Bytes::from(word(base)?.to_be_bytes::<32>().to_vec()))
+}
+
+/// Generates the body of `returnSteps` in the DataAttestation contract, which holds the packed
+/// steps of every call with a return selector, indexed by the call's position across all accounts
+fn return_steps_sol<'a>(
+    calls: impl IntoIterator<Item = &'a CallsToAccount>,
+) -> Result<String, EthError> {
+    let mut body = String::new();
+    let mut counter = 0;
+    for account in calls {
+        for j in 0..account.call_data.len() {
+            if let Some(selector) = account.return_selector(j) {
+                let steps = return_steps(selector)?;
+                body.push_str(&format!(
+                    "        if (counter == {}) {{\n            steps = new uint256[]({});\n",
+                    counter,
+                    steps.len()
+                ));
+                for (k, step) in steps.iter().enumerate() {
+                    body.push_str(&format!(
+                        "            steps[{}] = {:#x};\n",
+                        k,
+                        step.to_word()
+                    ));
+                }
+                body.push_str("        }\n");
+            }
+            counter += 1;
+        }
+    }
+    Ok(body)
+}
+
 ///
 pub async fn evm_quantize<M: 'static + Provider<Http<Client>, Ethereum>>(
     client: Arc<M>,
@@ -913,6 +2630,20 @@ pub async fn evm_quantize<M: 'static + Provider<Http<Client>, Ethereum>>(
     Ok(felts)
 }
 
+/// Compiles a contract whose source ships with ezkl, the source is written to a temporary file for solc
+async fn get_inline_contract_artifacts(
+    source: &str,
+    contract_name: &str,
+    runs: usize,
+) -> Result<(JsonAbi, Bytes, Bytes), EthError> {
+    let sol_code_path =
+        std::env::temp_dir().join(format!("{}-{}.sol", contract_name, std::process::id()));
+    std::fs::write(&sol_code_path, source)?;
+    let artifacts = get_contract_artifacts(sol_code_path.clone(), contract_name, runs).await;
+    std::fs::remove_file(sol_code_path)?;
+    artifacts
+}
+
 /// Generates the contract factory for a solidity verifier. The factory is used to deploy the contract
 fn get_sol_contract_factory<'a, M: 'static + Provider<Http<Client>, Ethereum>, T: TokenSeq<'a>>(
     abi: JsonAbi,
@@ -1015,11 +2746,353 @@ pub async fn get_contract_artifacts(
     Ok((abi, bytecode, runtime_bytecode))
 }
 
+/// Sets the expected kzg commitments in a da verifier, or removes the check if there are none
+fn fix_kzg_commitments(mut contract: String, commitment_bytes: &Option<Vec<u8>>) -> String {
+    // The case where a combination of on-chain data source + kzg commit is provided.
+    if commitment_bytes.is_some() && !commitment_bytes.as_ref().unwrap().is_empty() {
+        let commitment_bytes = commitment_bytes.as_ref().unwrap();
+        let hex_string = hex::encode(commitment_bytes);
+        contract = contract.replace(
+            "bytes constant COMMITMENT_KZG = hex\"\";",
+            &format!("bytes constant COMMITMENT_KZG = hex\"{}\";", hex_string),
+        );
+    } else {
+        // Remove the SwapProofCommitments inheritance and the checkKzgCommits function call if no commitment is provided
+        contract = contract.replace(", SwapProofCommitments", "");
+        contract = contract.replace(
+            "require(checkKzgCommits(encoded), \"Invalid KZG commitments\");",
+            "",
+        );
+    }
+    contract
+}
+
+/// Builds the storage attestation verifier. It extends the DataAttestation contract, which is
+/// left without any view calls of its own.
+pub fn fix_storage_da_sol(commitment_bytes: Option<Vec<u8>>) -> String {
+    // drop the header and import, the contract is appended to AttestData.sol instead
+    let storage_contract = ATTESTSTORAGE_SOL
+        .split_once("import \"./AttestData.sol\";")
+        .map_or(ATTESTSTORAGE_SOL, |(_, contract)| contract);
+    let contract = format!("{}{}", ATTESTDATA_SOL, storage_contract);
+    fix_kzg_commitments(contract, &commitment_bytes)
+}
+
+/// Binds a public instance of the proof to a value stored on-chain, e.g. the Poseidon hash of an
+/// output registered by a data provider. The DataAttestation contract requires the two to be equal.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct InstanceBinding {
+    /// Index of the instance in the flattened public instances of the proof
+    pub instance: usize,
+    /// Where the value the instance is bound to is read from
+    pub source: BindingSource,
+}
+
+/// The on-chain value an instance is bound to, read as a uint256
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BindingSource {
+    /// A storage slot of a contract, read through the contract's `extsload(bytes32)` function
+    Storage {
+        /// Hex encoded address of the contract
+        address: String,
+        /// Hex encoded storage slot
+        slot: String,
+    },
+    /// A view call returning a single uint256
+    Call {
+        /// Hex encoded address of the contract
+        address: String,
+        /// Hex encoded calldata of the call
+        call_data: String,
+    },
+}
+
+impl BindingSource {
+    /// The contract and calldata of the view call reading the bound value
+    fn view_call(&self) -> Result<(H160, Vec<u8>), EthError> {
+        use alloy::primitives::keccak256;
+
+        let parse_address = |address: &str| {
+            H160::from_str(address)
+                .map_err(|_| EthError::InstanceBinding(format!("invalid address {}", address)))
+        };
+        match self {
+            BindingSource::Storage { address, slot } => {
+                let slot = B256::from_str(slot)
+                    .map_err(|_| EthError::InstanceBinding(format!("invalid slot {}", slot)))?;
+                let call_data = [&keccak256("extsload(bytes32)")[..4], slot.as_slice()].concat();
+                Ok((parse_address(address)?, call_data))
+            }
+            BindingSource::Call { address, call_data } => Ok((
+                parse_address(address)?,
+                hex::decode(call_data.trim_start_matches("0x"))?,
+            )),
+        }
+    }
+}
+
+/// Loads the instance bindings from an `instance_bindings.json` file
+pub fn load_instance_bindings(path: &std::path::Path) -> Result<Vec<InstanceBinding>, EthError> {
+    let file = std::fs::File::open(path)?;
+    serde_json::from_reader(std::io::BufReader::new(file))
+        .map_err(|e| EthError::InstanceBinding(e.to_string()))
+}
+
+/// Generates the body of `checkInstanceBindings` in the DataAttestation contract, with a require for each binding
+fn instance_bindings_sol(bindings: &[InstanceBinding]) -> Result<String, EthError> {
+    let mut body = String::new();
+    for binding in bindings {
+        let (address, call_data) = binding.source.view_call()?;
+        body.push_str(&format!(
+            r#"        require(
+            instances[{instance}] ==
+                readBinding({address}, hex"{call_data}"),
+            "Instance {instance} does not match its binding"
+        );
+"#,
+            instance = binding.instance,
+            address = address.to_checksum(None),
+            call_data = hex::encode(call_data),
+        ));
+    }
+    Ok(body)
+}
+
+/// Sets the layout of the instances of the circuit and the Poseidon parameters in the
+/// PackedInstances contract
+pub fn fix_packed_sol(settings: &GraphSettings) -> Result<String, EthError> {
+    use crate::circuit::modules::poseidon::circomlib_params::{MDS, ROUND_CONSTANTS};
+
+    let packed = sorted_packed_tensors(settings)?;
+    let num_instances = settings.total_instances().iter().sum::<usize>();
+
+    let mut body = format!(
+        "        indices = new uint256[]({len});\n        lens = new uint256[]({len});\n",
+        len = packed.len()
+    );
+    for (i, (index, len)) in packed.iter().enumerate() {
+        body.push_str(&format!(
+            "        indices[{i}] = {index};\n        lens[{i}] = {len};\n"
+        ));
+    }
+    // the elements of a fixed size array literal take the type of the first one
+    let array =
+        |elements: Vec<String>| format!("uint256({}), {}", elements[0], elements[1..].join(", "));
+    let constants = ROUND_CONSTANTS
+        .iter()
+        .flatten()
+        .map(|c| format!("{:?}", c))
+        .collect_vec();
+    let mds = MDS
+        .iter()
+        .flatten()
+        .map(|m| format!("{:?}", m))
+        .collect_vec();
+
+    Ok(PACKED_INSTANCES_SOL
+        .replace(
+            "uint256 constant NUM_INSTANCES = 0;",
+            &format!("uint256 constant NUM_INSTANCES = {};", num_instances),
+        )
+        .replace(
+            "returns (uint256[] memory indices, uint256[] memory lens)\n    {}",
+            &format!(
+                "returns (uint256[] memory indices, uint256[] memory lens)\n    {{\n{}    }}",
+                body
+            ),
+        )
+        .replace(
+            "returns (uint256[195] memory c) {}",
+            &format!(
+                "returns (uint256[195] memory c) {{\n        c = [{}];\n    }}",
+                array(constants)
+            ),
+        )
+        .replace(
+            "returns (uint256[9] memory m) {}",
+            &format!(
+                "returns (uint256[9] memory m) {{\n        m = [{}];\n    }}",
+                array(mds)
+            ),
+        ))
+}
+
+/// Sets the layout of the instances of the circuit and of its limb packed outputs in the
+/// LimbPackedInstances contract
+pub fn fix_limb_packed_sol(settings: &GraphSettings) -> Result<String, EthError> {
+    let Some(first) = settings.limb_packed_outputs.first() else {
+        return Err(EthError::PackedInstances(
+            "the settings pack no outputs to limbs, set instance_limbs when generating them".into(),
+        ));
+    };
+    let starts = settings.limb_packed_instance_starts();
+    if starts.len() != settings.limb_packed_outputs.len() {
+        return Err(EthError::PackedInstances(
+            "the instances of a limb packed output are missing from the layout of the settings"
+                .into(),
+        ));
+    }
+    let num_instances = settings.total_instances().iter().sum::<usize>();
+
+    let mut body = format!(
+        "        starts = new uint256[]({len});\n        lens = new uint256[]({len});\n",
+        len = starts.len()
+    );
+    for (i, (start, tensor)) in starts.iter().zip(&settings.limb_packed_outputs).enumerate() {
+        body.push_str(&format!(
+            "        starts[{i}] = {start};\n        lens[{i}] = {len};\n",
+            len = tensor.len
+        ));
+    }
+
+    Ok(LIMB_PACKED_INSTANCES_SOL
+        .replace(
+            "uint256 constant NUM_INSTANCES = 0;",
+            &format!("uint256 constant NUM_INSTANCES = {};", num_instances),
+        )
+        .replace(
+            "uint256 constant LIMBS = 0;",
+            &format!("uint256 constant LIMBS = {};", first.limbs),
+        )
+        .replace(
+            "uint256 constant LIMB_BITS = 0;",
+            &format!("uint256 constant LIMB_BITS = {};", first.limb_bits),
+        )
+        .replace(
+            "returns (uint256[] memory starts, uint256[] memory lens)\n    {}",
+            &format!(
+                "returns (uint256[] memory starts, uint256[] memory lens)\n    {{\n{}    }}",
+                body
+            ),
+        ))
+}
+
+/// The name of the contract of a source created by create-evm-packed, which packs either digests
+/// or limbs
+pub fn packed_contract_name(sol_code_path: &PathBuf) -> Result<&'static str, EthError> {
+    let source = std::fs::read_to_string(sol_code_path)?;
+    Ok(match source.contains("contract LimbPackedInstances") {
+        true => "LimbPackedInstances",
+        false => "PackedInstances",
+    })
+}
+
+/// The index of the digest of each packed tensor in the instances and the index of the tensor in
+/// [GraphSettings::packed_tensors], in the order of the instances
+fn sorted_packed_tensors(settings: &GraphSettings) -> Result<Vec<(usize, usize)>, EthError> {
+    if settings.packed_tensors.is_empty() {
+        return Err(EthError::PackedInstances(
+            "the settings pack no tensors, set pack_public_threshold when generating them".into(),
+        ));
+    }
+    Ok(settings
+        .packed_instance_indices()
+        .into_iter()
+        .zip(settings.packed_tensors.iter().map(|t| t.len))
+        .sorted()
+        .collect())
+}
+
+/// Splits the instances of a proof into those that aren't packed and the values of the packed
+/// tensors, taken from the witness of the proof, as passed to the PackedInstances contract
+pub fn unpack_instances(
+    settings: &GraphSettings,
+    proof: &Snark<Fr, G1Affine>,
+    witness: &GraphWitness,
+) -> Result<(Vec<Fr>, Vec<Fr>), EthError> {
+    let instances = proof.instances.iter().flatten().cloned().collect_vec();
+    let indices = settings.packed_instance_indices();
+    if indices.iter().any(|i| *i >= instances.len()) {
+        return Err(EthError::PackedInstances(format!(
+            "the proof has {} instances, fewer than the settings",
+            instances.len()
+        )));
+    }
+
+    let mut packed = indices
+        .into_iter()
+        .zip(settings.packed_tensors.iter())
+        .collect_vec();
+    packed.sort_by_key(|(index, _)| *index);
+    let mut values = vec![];
+    for (_, tensor) in &packed {
+        let tensors = match tensor.output {
+            true => &witness.outputs,
+            false => &witness.inputs,
+        };
+        let tensor_values = tensors.get(tensor.idx).ok_or_else(|| {
+            EthError::PackedInstances(format!("the witness has no tensor {:?}", tensor))
+        })?;
+        if tensor_values.len() != tensor.len {
+            return Err(EthError::PackedInstances(format!(
+                "the witness has {} values for the packed tensor {:?}",
+                tensor_values.len(),
+                tensor
+            )));
+        }
+        values.extend(tensor_values.iter().cloned());
+    }
+
+    let instances = instances
+        .into_iter()
+        .enumerate()
+        .filter(|(i, _)| !packed.iter().any(|(index, _)| index == i))
+        .map(|(_, instance)| instance)
+        .collect();
+    Ok((instances, values))
+}
+
+/// Encodes a call of `verifyProof` of the PackedInstances contract
+pub fn encode_packed_calldata(proof: &[u8], instances: &[Fr], values: &[Fr]) -> Vec<u8> {
+    use alloy::sol_types::SolCall;
+
+    let words = |elements: &[Fr]| {
+        elements
+            .iter()
+            .map(|e| U256::from_le_slice(e.to_repr().as_ref()))
+            .collect_vec()
+    };
+    PackedInstances::verifyProofCall {
+        proof: proof.to_vec().into(),
+        instances: words(instances),
+        values: words(values),
+    }
+    .abi_encode()
+}
+
+/// Deploys the PackedInstances contract verifying its proofs with `verifier`
+pub async fn deploy_packed_verifier_via_solidity(
+    sol_code_path: PathBuf,
+    verifier: H160,
+    rpc_url: Option<&str>,
+    runs: usize,
+    private_key: Option<&str>,
+) -> Result<H160, EthError> {
+    let (client, _) = setup_eth_backend(rpc_url, private_key).await?;
+
+    let name = packed_contract_name(&sol_code_path)?;
+    let (abi, bytecode, runtime_bytecode) =
+        get_contract_artifacts(sol_code_path, name, runs).await?;
+
+    let factory = get_sol_contract_factory(
+        abi,
+        bytecode,
+        runtime_bytecode,
+        client,
+        Some((WordToken(verifier.into_word()),)),
+    )?;
+    let contract = factory.deploy().await?;
+
+    Ok(contract)
+}
+
 /// Sets the constants stored in the da verifier
 pub fn fix_da_sol(
     input_data: Option<Vec<CallsToAccount>>,
     output_data: Option<Vec<CallsToAccount>>,
     commitment_bytes: Option<Vec<u8>>,
+    instance_bindings: &[InstanceBinding],
 ) -> Result<String, EthError> {
     let mut accounts_len = 0;
     let mut contract = ATTESTDATA_SOL.to_string();
@@ -1042,28 +3115,40 @@ pub fn fix_da_sol(
             &format!("uint256 constant OUTPUT_CALLS = {};", output_calls),
         );
     }
-    contract = contract.replace("AccountCall[]", &format!("AccountCall[{}]", accounts_len));
+    // zero length static arrays don't compile, a contract only checking instance bindings keeps the empty dynamic array
+    if accounts_len > 0 {
+        contract = contract.replace("AccountCall[]", &format!("AccountCall[{}]", accounts_len));
+    }
 
-    // The case where a combination of on-chain data source + kzg commit is provided.
-    if commitment_bytes.is_some() && !commitment_bytes.as_ref().unwrap().is_empty() {
-        let commitment_bytes = commitment_bytes.as_ref().unwrap();
-        let hex_string = hex::encode(commitment_bytes);
+    // calls with nested return types get the steps locating their attested word baked in
+    let steps = return_steps_sol(input_data.iter().chain(output_data.iter()).flatten())?;
+    if !steps.is_empty() {
         contract = contract.replace(
-            "bytes constant COMMITMENT_KZG = hex\"\";",
-            &format!("bytes constant COMMITMENT_KZG = hex\"{}\";", hex_string),
+            ") internal pure returns (uint256[] memory steps) {}",
+            &format!(
+                ") internal pure returns (uint256[] memory steps) {{\n{}    }}",
+                steps
+            ),
         );
-    } else {
-        // Remove the SwapProofCommitments inheritance and the checkKzgCommits function call if no commitment is provided
-        contract = contract.replace(", SwapProofCommitments", "");
+    }
+
+    if !instance_bindings.is_empty() {
         contract = contract.replace(
-            "require(checkKzgCommits(encoded), \"Invalid KZG commitments\");",
-            "",
+            "function checkInstanceBindings(uint256[] memory instances) internal view {}",
+            &format!(
+                "function checkInstanceBindings(uint256[] memory instances) internal view {{\n{}    }}",
+                instance_bindings_sol(instance_bindings)?
+            ),
         );
     }
 
+    contract = fix_kzg_commitments(contract, &commitment_bytes);
+
     // if both input and output data is none then we will only deploy the DataAttest contract, adding in the verifyWithDataAttestation function
+    // instance bindings need the full contract to load the instances
     if input_data.is_none()
         && output_data.is_none()
+        && instance_bindings.is_empty()
         && commitment_bytes.as_ref().is_some()
         && !commitment_bytes.as_ref().unwrap().is_empty()
     {
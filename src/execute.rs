@@ -1,21 +1,49 @@
+use crate::circuit::modules::ecdsa::InputSignature;
+use crate::circuit::modules::elgamal::CurvePoint;
+use crate::circuit::modules::merkle::MerkleTree;
 use crate::circuit::region::RegionSettings;
 use crate::circuit::CheckMode;
+use crate::circuit::CircuitError;
 use crate::commands::CalibrationTarget;
-use crate::eth::{deploy_contract_via_solidity, deploy_da_verifier_via_solidity};
+use crate::eth::{
+    add_nullifier_index, add_verify_batch, create_foundry_project, deploy_contract_via_solidity,
+    deploy_da_verifier_via_solidity, export_calldata_fixture,
+};
 #[allow(unused_imports)]
-use crate::eth::{fix_da_sol, get_contract_artifacts, verify_proof_via_solidity};
-use crate::graph::input::GraphData;
-use crate::graph::{GraphCircuit, GraphSettings, GraphWitness, Model};
+use crate::eth::{
+    fix_da_sol, fix_storage_da_sol, get_contract_artifacts, verify_proof_via_solidity,
+};
+use crate::graph::batch::{batch_sizes, BatchFamily};
+use crate::graph::errors::GraphError;
+use crate::graph::input::{
+    felt_from_str, CsvMapping, DataSource, FileSource, FileSourceInner, GraphData,
+    ImagePreprocessing, InputNormalization, PinnedBlock,
+};
+use crate::graph::link::{check_digest_links, link_tensors, LinkKind};
+use crate::graph::param_groups::{group_commitments, GroupCommitments};
+use crate::graph::schema::JsonArtifact;
+use crate::graph::session::{check_session_chain, session_instance_links};
+use crate::graph::validate::ConstraintFailure;
+use crate::graph::weights::WeightsOverride;
+use crate::graph::{GraphCircuit, GraphSettings, GraphWitness, Model, WitnessFormat};
 use crate::graph::{TestDataSource, TestSources};
-use crate::pfsys::evm::aggregation_kzg::{AggregationCircuit, PoseidonTranscript};
+use crate::pfsys::encryption::{load_pk_decrypted, save_pk_encrypted, Identity, Recipient};
+use crate::pfsys::estimate::MemoryPhase;
+use crate::pfsys::evm::aggregation_kzg::{AggregationCircuit, PoseidonTranscript, SnarkInstance};
+use crate::pfsys::progress::{set_observer, CancellationToken};
+use crate::pfsys::vk_description::{VkDescription, VkDescriptionFormat};
 use crate::pfsys::{
-    create_keys, load_pk, load_vk, save_params, save_pk, Snark, StrategyType, TranscriptType,
+    create_keys, load_pk, load_vk, save_params, save_pk, synthesis_error, Snark, StrategyType,
+    TranscriptType,
 };
 use crate::pfsys::{
-    create_proof_circuit, swap_proof_commitments_polycommit, verify_proof_circuit, ProofSplitCommit,
+    create_proof_circuit, proof_commitments_polycommit, swap_proof_commitments_polycommit,
+    swap_proof_commitments_polycommit_at, verify_proof_circuit, ProofSplitCommit,
 };
 use crate::pfsys::{save_vk, srs::*};
-use crate::tensor::TensorError;
+use crate::profiling::{available_memory, end_memory_phase, phase_peaks, start_memory_phase};
+use crate::report::{CircuitReport, ProofReport, ReportFormat};
+use crate::tensor::{Tensor, TensorError};
 use crate::EZKL_BUF_CAPACITY;
 use crate::{commands::*, EZKLError};
 use crate::{Commitments, RunArgs};
@@ -23,7 +51,7 @@ use colored::Colorize;
 #[cfg(unix)]
 use gag::Gag;
 use halo2_proofs::dev::VerifyFailure;
-use halo2_proofs::plonk::{self, Circuit};
+use halo2_proofs::plonk::{self, Circuit, ProvingKey, VerifyingKey};
 use halo2_proofs::poly::commitment::{CommitmentScheme, Params};
 use halo2_proofs::poly::commitment::{ParamsProver, Verifier};
 use halo2_proofs::poly::ipa::commitment::{IPACommitmentScheme, ParamsIPA};
@@ -37,10 +65,12 @@ use halo2_proofs::poly::kzg::{
     commitment::ParamsKZG, strategy::SingleStrategy as KZGSingleStrategy,
 };
 use halo2_proofs::poly::VerificationStrategy;
-use halo2_proofs::transcript::{EncodedChallenge, TranscriptReadBuffer};
+use halo2_proofs::transcript::{
+    Blake2bRead, Blake2bWrite, Challenge255, EncodedChallenge, TranscriptReadBuffer,
+};
 use halo2_solidity_verifier;
 use halo2curves::bn256::{Bn256, Fr, G1Affine};
-use halo2curves::ff::{FromUniformBytes, WithSmallOrderMulGroup};
+use halo2curves::ff::{Field, FromUniformBytes, WithSmallOrderMulGroup};
 use halo2curves::serde::SerdeObject;
 use indicatif::{ProgressBar, ProgressStyle};
 use instant::Instant;
@@ -53,6 +83,7 @@ use snark_verifier::loader::native::NativeLoader;
 use snark_verifier::system::halo2::compile;
 use snark_verifier::system::halo2::transcript::evm::EvmTranscript;
 use snark_verifier::system::halo2::Config;
+use std::collections::BTreeMap;
 use std::fs::File;
 use std::io::BufWriter;
 use std::io::{Cursor, Write};
@@ -77,6 +108,10 @@ lazy_static! {
     /// The path to the ezkl related data (SRS)
     pub static ref EZKL_SRS_REPO_PATH: String = format!("{}/srs", *EZKL_REPO_PATH);
 
+    /// The url the SRS files are downloaded from, followed by their logrows
+    pub static ref EZKL_SRS_URL: String =
+        std::env::var("EZKL_SRS_URL").unwrap_or_else(|_| PUBLIC_SRS_URL.to_string());
+
 }
 
 /// A wrapper for execution errors
@@ -88,6 +123,344 @@ pub enum ExecutionError {
     /// Prover error
     #[error("[mock] {0}")]
     MockProverError(String),
+    /// The witness fails constraints of the circuit, attributed to the nodes of the model
+    #[error("[validate-witness] the witness fails {} constraints:\n{}", .0.len(), .0.iter().map(|e| e.to_string()).collect::<Vec<_>>().join("\n"))]
+    InvalidWitness(Vec<ConstraintFailure>),
+    /// Lookup tables don't hold the outputs the witness is generated with
+    #[error("[check-tables] the tables of {} are inconsistent with the witness", .0.join(", "))]
+    InconsistentTables(Vec<String>),
+    /// The command ran out of time, its timeout being in seconds
+    #[error("[timeout] cancelled after {0}s")]
+    TimedOut(u64),
+    /// A stage of the pipeline failed
+    #[error("[pipeline] {stage} failed, the artifacts of the earlier stages are intact: {error}")]
+    PipelineStageFailed {
+        /// the stage that failed
+        stage: PipelineStage,
+        /// the error of the stage
+        error: Box<EZKLError>,
+    },
+}
+
+impl ExecutionError {
+    /// Whether the error stems from the inputs rather than from a failure of ezkl itself
+    pub fn is_user_error(&self) -> bool {
+        match self {
+            ExecutionError::PipelineStageFailed { error, .. } => error.is_user_error(),
+            _ => false,
+        }
+    }
+}
+
+/// The outcome of a command, the cli exits with the exit code of the status
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CommandStatus {
+    /// The command succeeded
+    Success,
+    /// The command failed within ezkl
+    InternalError,
+    /// The command failed on its inputs (missing or malformed files, invalid arguments, unsupported models)
+    UserError,
+    /// A proof or witness failed to verify
+    VerificationFailed,
+    /// The command was cancelled or ran out of time
+    Cancelled,
+}
+
+impl CommandStatus {
+    /// The exit code of the cli: 0 on success, 1 on an internal error, 2 on an error in the inputs (as the argument
+    /// parser does on usage errors), 3 when a proof fails to verify and 4 when the command is cancelled (see
+    /// `--timeout`)
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            CommandStatus::Success => 0,
+            CommandStatus::InternalError => 1,
+            CommandStatus::UserError => 2,
+            CommandStatus::VerificationFailed => 3,
+            CommandStatus::Cancelled => 4,
+        }
+    }
+
+    /// The status of `command` failing with `error`
+    pub fn of_error(command: &Commands, error: &EZKLError) -> Self {
+        match error {
+            e if e.is_cancelled() => CommandStatus::Cancelled,
+            EZKLError::ExecutionError(ExecutionError::VerifyError(_))
+            | EZKLError::ExecutionError(ExecutionError::InvalidWitness(_))
+            | EZKLError::GraphError(GraphError::BrokenLink { .. })
+            | EZKLError::GraphError(GraphError::CommitmentGroupMismatch { .. }) => {
+                CommandStatus::VerificationFailed
+            }
+            EZKLError::Halo2Error(_)
+                if matches!(
+                    command,
+                    Commands::Verify { .. } | Commands::VerifyAggr { .. }
+                ) =>
+            {
+                CommandStatus::VerificationFailed
+            }
+            e if e.is_user_error() => CommandStatus::UserError,
+            _ => CommandStatus::InternalError,
+        }
+    }
+}
+
+/// The error of a failed command, as reported by `--json`
+#[derive(Debug, Clone, PartialEq, Serialize, serde::Deserialize)]
+pub struct CommandError {
+    /// The category of the error, see [EZKLError::kind]
+    pub kind: String,
+    /// The error message
+    pub message: String,
+}
+
+/// The outcome of a command, as printed by `--json`
+#[derive(Debug, Clone, PartialEq, Serialize, serde::Deserialize)]
+pub struct CommandOutput {
+    /// The name of the command on the command line
+    pub command: String,
+    /// The status of the command
+    pub status: CommandStatus,
+    /// The exit code of the cli, see [CommandStatus::exit_code]
+    pub exit_code: i32,
+    /// The files written by the command, by role, if it succeeded
+    pub artifacts: BTreeMap<String, PathBuf>,
+    /// Key figures of the outcome: the logrows, rows and assignments of written settings, the size and number of
+    /// instances of a written proof, whether a proof verified, and the peak resident memory of the command and of each
+    /// of its phases (as keygen or prove) in bytes where the platform reports it
+    pub metrics: BTreeMap<String, serde_json::Value>,
+    /// The error, if the command failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<CommandError>,
+}
+
+/// Runs `command`, named `name` on the command line, and collects its outcome for `--json`. The command is cancelled
+/// once it has run for `timeout`, as in [run_with_timeout]
+pub async fn run_json(name: &str, command: Commands, timeout: Option<Duration>) -> CommandOutput {
+    let artifacts = command_artifacts(&command);
+    let verifies = matches!(
+        command,
+        Commands::Verify { .. }
+            | Commands::VerifyAggr { .. }
+            | Commands::Mock { .. }
+            | Commands::ValidateWitness { .. }
+            | Commands::MockAggregate { .. }
+            | Commands::LinkProofs { .. }
+            | Commands::CheckSession { .. }
+    );
+
+    let result = run_with_timeout(command.clone(), timeout).await;
+    let status = match &result {
+        Ok(_) => CommandStatus::Success,
+        Err(e) => CommandStatus::of_error(&command, e),
+    };
+
+    // the paths are relative to the working directory `run` moved to
+    let artifacts: BTreeMap<String, PathBuf> = match status {
+        CommandStatus::Success => artifacts
+            .into_iter()
+            .filter(|(_, path)| path.exists())
+            .map(|(role, path)| (role.to_string(), path))
+            .collect(),
+        _ => BTreeMap::new(),
+    };
+
+    let mut metrics = BTreeMap::new();
+    if let Some(settings) = artifacts.get("settings") {
+        if let Ok(settings) = GraphSettings::load(settings) {
+            metrics.insert("logrows".into(), settings.run_args.logrows.into());
+            metrics.insert("num_rows".into(), settings.num_rows.into());
+            metrics.insert(
+                "total_assignments".into(),
+                settings.total_assignments.into(),
+            );
+        }
+    }
+    if let Some(proof) = artifacts.get("proof") {
+        if let Ok(proof) = Snark::load::<KZGCommitmentScheme<Bn256>>(proof) {
+            metrics.insert("proof_size".into(), proof.proof.len().into());
+            metrics.insert(
+                "num_instances".into(),
+                proof
+                    .instances
+                    .iter()
+                    .map(|i| i.len())
+                    .sum::<usize>()
+                    .into(),
+            );
+        }
+    }
+    // the memory of the phases is measured with their spans, which --json collects
+    let peaks = phase_peaks();
+    if let Some(peak) = peaks.values().max() {
+        metrics.insert("peak_rss".into(), (*peak).into());
+        metrics.insert("phase_peak_rss".into(), serde_json::json!(peaks));
+    }
+    if verifies
+        && matches!(
+            status,
+            CommandStatus::Success | CommandStatus::VerificationFailed
+        )
+    {
+        metrics.insert("verified".into(), (status == CommandStatus::Success).into());
+    }
+
+    CommandOutput {
+        command: name.to_string(),
+        status,
+        exit_code: status.exit_code(),
+        artifacts,
+        metrics,
+        error: result.err().map(|e| CommandError {
+            kind: e.kind().to_string(),
+            message: e.to_string(),
+        }),
+    }
+}
+
+/// The files `command` writes, by role, with the defaults of the paths it wasn't given
+fn command_artifacts(command: &Commands) -> Vec<(&'static str, PathBuf)> {
+    let or = |path: &Option<PathBuf>, default: &str| path.clone().unwrap_or(default.into());
+    match command {
+        Commands::GenSettings { settings_path, .. }
+        | Commands::CalibrateSettings { settings_path, .. } => {
+            vec![("settings", or(settings_path, DEFAULT_SETTINGS))]
+        }
+        Commands::CompileCircuit {
+            compiled_circuit, ..
+        } => vec![(
+            "compiled_circuit",
+            or(compiled_circuit, DEFAULT_COMPILED_CIRCUIT),
+        )],
+        Commands::CompileBatchFamily { output_dir, .. } => {
+            vec![("batch_family", or(output_dir, DEFAULT_BATCH_FAMILY_DIR))]
+        }
+        Commands::GenWitness {
+            input_dir: Some(_),
+            output_dir: Some(output_dir),
+            ..
+        } => vec![("witness_dir", output_dir.clone())],
+        Commands::GenWitness {
+            output,
+            session_digest_output,
+            ..
+        } => {
+            let mut artifacts = vec![("witness", or(output, DEFAULT_WITNESS))];
+            artifacts.extend(
+                session_digest_output
+                    .iter()
+                    .map(|o| ("session_digest", o.clone())),
+            );
+            artifacts
+        }
+        Commands::ConvertWitness { output, .. } => vec![("witness", output.clone())],
+        Commands::GenSrs { srs_path, .. } => vec![("srs", srs_path.clone())],
+        Commands::Srs {
+            action: SrsAction::List,
+            output,
+            ..
+        } => output.iter().map(|o| ("report", o.clone())).collect(),
+        Commands::Srs {
+            action: SrsAction::Trim,
+            output: Some(output),
+            ..
+        } => vec![("srs", output.clone())],
+        Commands::Setup {
+            batch_family: Some(batch_family),
+            ..
+        } => vec![("batch_family", batch_family.clone())],
+        Commands::Setup {
+            vk_path, pk_path, ..
+        } => vec![
+            ("vk", or(vk_path, DEFAULT_VK)),
+            ("pk", or(pk_path, DEFAULT_PK)),
+        ],
+        Commands::SetupAggregate {
+            vk_path, pk_path, ..
+        } => vec![
+            ("vk", or(vk_path, DEFAULT_VK_AGGREGATED)),
+            ("pk", or(pk_path, DEFAULT_PK_AGGREGATED)),
+        ],
+        Commands::Prove {
+            witness_dir: Some(_),
+            proof_dir: Some(proof_dir),
+            ..
+        } => vec![("proof_dir", proof_dir.clone())],
+        Commands::Prove { proof_path, .. } => vec![("proof", or(proof_path, DEFAULT_PROOF))],
+        Commands::CommitParamGroups { output_dir, .. } => {
+            vec![("param_groups", or(output_dir, DEFAULT_PARAM_GROUPS_DIR))]
+        }
+        Commands::Aggregate { proof_path, .. } => {
+            vec![("proof", or(proof_path, DEFAULT_PROOF_AGGREGATED))]
+        }
+        Commands::LinkProofs {
+            aggregate,
+            aggregated_proof_path,
+            vk_path,
+            pk_path,
+            output,
+            ..
+        }
+        | Commands::CheckSession {
+            aggregate,
+            aggregated_proof_path,
+            vk_path,
+            pk_path,
+            output,
+            ..
+        } => {
+            let mut artifacts: Vec<_> = output.iter().map(|o| ("report", o.clone())).collect();
+            if aggregate.unwrap_or_default() {
+                artifacts.extend([
+                    ("proof", or(aggregated_proof_path, DEFAULT_PROOF_AGGREGATED)),
+                    ("vk", or(vk_path, DEFAULT_VK_AGGREGATED)),
+                    ("pk", or(pk_path, DEFAULT_PK_AGGREGATED)),
+                ]);
+            }
+            artifacts
+        }
+        Commands::CreateEvmVerifier {
+            sol_code_path,
+            abi_path,
+            ..
+        } => vec![
+            ("sol_code", or(sol_code_path, DEFAULT_SOL_CODE)),
+            ("abi", or(abi_path, DEFAULT_VERIFIER_ABI)),
+            (
+                "instance_map",
+                instance_map_path(&or(sol_code_path, DEFAULT_SOL_CODE)),
+            ),
+        ],
+        Commands::CreateEvmVerifierAggr {
+            sol_code_path,
+            abi_path,
+            ..
+        } => vec![
+            ("sol_code", or(sol_code_path, DEFAULT_SOL_CODE_AGGREGATED)),
+            ("abi", or(abi_path, DEFAULT_VERIFIER_AGGREGATED_ABI)),
+        ],
+        Commands::Estimate { output, .. }
+        | Commands::CircuitProfile { output, .. }
+        | Commands::CheckTables { output, .. }
+        | Commands::ListRescales { output, .. }
+        | Commands::Inspect { output, .. }
+        | Commands::Schema { output, .. }
+        | Commands::Report { output, .. }
+        | Commands::ExportVkDescription { output, .. }
+        | Commands::Bench { output, .. }
+        | Commands::Diff { output, .. } => output.iter().map(|o| ("report", o.clone())).collect(),
+        Commands::Pipeline { output_dir, .. } => {
+            let dir = or(output_dir, DEFAULT_PIPELINE_DIR);
+            vec![
+                ("settings", dir.join(DEFAULT_SETTINGS)),
+                ("compiled_circuit", dir.join(DEFAULT_COMPILED_CIRCUIT)),
+                ("vk", dir.join(DEFAULT_VK)),
+                ("pk", dir.join(DEFAULT_PK)),
+            ]
+        }
+        _ => vec![],
+    }
 }
 
 lazy_static::lazy_static! {
@@ -98,6 +471,47 @@ lazy_static::lazy_static! {
     };
 }
 
+/// Runs `command` on this thread as [run] does, cancelling it once it has run for `timeout`. Witness generation,
+/// synthesis and proving stop at their next check of the cancellation (see [crate::pfsys::progress]), and the files
+/// the command created before it stopped are removed, those it was to overwrite being left as they were. The prover
+/// only checks between synthesis and its commitments, a proof committing to its witness runs to completion before it
+/// is discarded.
+pub async fn run_with_timeout(
+    command: Commands,
+    timeout: Option<Duration>,
+) -> Result<String, EZKLError> {
+    let Some(timeout) = timeout else {
+        return run(command).await;
+    };
+    // the paths are relative to the working directory `run` moves to
+    let created = command_artifacts(&command)
+        .into_iter()
+        .map(|(_, path)| WORKING_DIR.join(path))
+        .filter(|path| !path.exists())
+        .collect::<Vec<_>>();
+
+    let token = CancellationToken::with_timeout(timeout);
+    let result = {
+        let _observer = set_observer(std::rc::Rc::new(token.clone()));
+        run(command).await
+    };
+    match result {
+        Err(e) if e.is_cancelled() => {
+            for path in created.iter().filter(|path| path.exists()) {
+                if let Err(e) = std::fs::remove_file(path) {
+                    warn!("failed to remove {}: {}", path.display(), e);
+                }
+            }
+            if token.timed_out() {
+                Err(ExecutionError::TimedOut(timeout.as_secs()).into())
+            } else {
+                Err(e)
+            }
+        }
+        result => result,
+    }
+}
+
 /// Run an ezkl command with given args
 pub async fn run(command: Commands) -> Result<String, EZKLError> {
     // set working dir
@@ -121,15 +535,178 @@ pub async fn run(command: Commands) -> Result<String, EZKLError> {
             logrows,
             commitment,
         } => get_srs_cmd(srs_path, settings_path, logrows, commitment).await,
+        Commands::Estimate {
+            model,
+            settings_path,
+            output,
+        } => estimate(
+            model.unwrap_or(DEFAULT_MODEL.into()),
+            settings_path.unwrap_or(DEFAULT_SETTINGS.into()),
+            output,
+        ),
+        Commands::Srs {
+            action,
+            srs_path,
+            logrows,
+            settings_path,
+            output,
+            commitment,
+        } => srs_cmd(action, srs_path, logrows, settings_path, output, commitment).await,
+        Commands::Config { action, command } => config_cmd(action, command),
         Commands::Table { model, args } => table(model.unwrap_or(DEFAULT_MODEL.into()), args),
+        Commands::CircuitProfile {
+            model,
+            args,
+            output,
+        } => circuit_profile(model.unwrap_or(DEFAULT_MODEL.into()), args, output),
+        Commands::ListRescales {
+            model,
+            args,
+            output,
+        } => list_rescales(model.unwrap_or(DEFAULT_MODEL.into()), args, output),
+        Commands::Inspect {
+            compiled_circuit,
+            node,
+            output,
+        } => inspect(
+            compiled_circuit.unwrap_or(DEFAULT_COMPILED_CIRCUIT.into()),
+            node,
+            output,
+        ),
+        Commands::Schema { artifact, output } => schema(artifact, output),
+        Commands::Report {
+            settings_path,
+            compiled_circuit,
+            proof_path,
+            vk_path,
+            srs_path,
+            format,
+            output,
+        } => report(
+            settings_path.unwrap_or(DEFAULT_SETTINGS.into()),
+            compiled_circuit.unwrap_or(DEFAULT_COMPILED_CIRCUIT.into()),
+            proof_path,
+            vk_path,
+            srs_path,
+            format,
+            output,
+        ),
+        Commands::ExportVkDescription {
+            vk_path,
+            settings_path,
+            format,
+            output,
+        } => export_vk_description(
+            vk_path.unwrap_or(DEFAULT_VK.into()),
+            settings_path.unwrap_or(DEFAULT_SETTINGS.into()),
+            format,
+            output,
+        ),
+        Commands::CheckTables {
+            settings_path,
+            output,
+        } => check_tables(settings_path.unwrap_or(DEFAULT_SETTINGS.into()), output),
+        Commands::Bench {
+            compiled_circuit,
+            data,
+            settings_path,
+            vk_path,
+            pk_path,
+            srs_path,
+            iterations,
+            warmup,
+            skip_setup,
+            output,
+        } => {
+            bench(
+                compiled_circuit.unwrap_or(DEFAULT_COMPILED_CIRCUIT.into()),
+                data.unwrap_or(DEFAULT_DATA.into()),
+                settings_path.unwrap_or(DEFAULT_SETTINGS.into()),
+                vk_path.unwrap_or(DEFAULT_VK.into()),
+                pk_path.unwrap_or(DEFAULT_PK.into()),
+                srs_path,
+                iterations.unwrap_or(DEFAULT_BENCH_ITERATIONS.parse().unwrap()),
+                warmup.unwrap_or(DEFAULT_BENCH_WARMUP.parse().unwrap()),
+                skip_setup.unwrap_or(false),
+                output,
+            )
+            .await
+        }
+        Commands::Diff {
+            model,
+            settings_path,
+            data,
+            witness,
+            verbose,
+            threshold,
+            csv_mapping,
+            output,
+        } => diff(
+            model.unwrap_or(DEFAULT_MODEL.into()),
+            settings_path.unwrap_or(DEFAULT_SETTINGS.into()),
+            data.unwrap_or(DEFAULT_DATA.into()),
+            witness.unwrap_or(DEFAULT_WITNESS.into()),
+            verbose.unwrap_or(false),
+            threshold.unwrap_or(DEFAULT_DIFF_THRESHOLD.parse().unwrap()),
+            csv_mapping,
+            output,
+        ),
+        Commands::Pipeline {
+            model,
+            data,
+            output_dir,
+            args,
+            target,
+            srs_path,
+            stop_after,
+        } => pipeline(
+            model.unwrap_or(DEFAULT_MODEL.into()),
+            data.unwrap_or(DEFAULT_DATA.into()),
+            output_dir.unwrap_or(DEFAULT_PIPELINE_DIR.into()),
+            args,
+            target,
+            srs_path,
+            stop_after,
+        )
+        .await
+        .map(|e| serde_json::to_string(&e).unwrap()),
+        #[cfg(feature = "server")]
+        Commands::Serve {
+            host,
+            port,
+            artifacts,
+            workers,
+        } => {
+            crate::server::serve(
+                host.unwrap_or(DEFAULT_SERVER_HOST.into()),
+                port.unwrap_or(DEFAULT_SERVER_PORT.parse().unwrap()),
+                artifacts,
+                workers.unwrap_or(DEFAULT_SERVER_WORKERS.parse().unwrap()),
+            )
+            .await
+        }
         Commands::GenSettings {
             model,
             settings_path,
             args,
+            image_preprocessing,
+            normalize,
+            input_signature,
+            nullifier,
+            encrypted_outputs,
+            polycommit_blinding,
+            session,
         } => gen_circuit_settings(
             model.unwrap_or(DEFAULT_MODEL.into()),
             settings_path.unwrap_or(DEFAULT_SETTINGS.into()),
             args,
+            image_preprocessing,
+            normalize.map(|paths| (paths[0].clone(), paths[1].clone())),
+            input_signature,
+            nullifier.unwrap_or(false),
+            encrypted_outputs,
+            polycommit_blinding.unwrap_or(false),
+            session.unwrap_or(false),
         ),
         Commands::CalibrateSettings {
             model,
@@ -141,9 +718,10 @@ pub async fn run(command: Commands) -> Result<String, EZKLError> {
             scale_rebase_multiplier,
             max_logrows,
             only_range_check_rebase,
+            csv_mapping,
         } => calibrate(
             model.unwrap_or(DEFAULT_MODEL.into()),
-            data.unwrap_or(DEFAULT_DATA.into()),
+            data.unwrap_or(DEFAULT_DATA.into()).into(),
             settings_path.unwrap_or(DEFAULT_SETTINGS.into()),
             target,
             lookup_safety_margin,
@@ -151,6 +729,7 @@ pub async fn run(command: Commands) -> Result<String, EZKLError> {
             scale_rebase_multiplier,
             only_range_check_rebase.unwrap_or(DEFAULT_ONLY_RANGE_CHECK_REBASE.parse().unwrap()),
             max_logrows,
+            csv_mapping,
         )
         .await
         .map(|e| serde_json::to_string(&e).unwrap()),
@@ -160,18 +739,106 @@ pub async fn run(command: Commands) -> Result<String, EZKLError> {
             output,
             vk_path,
             srs_path,
-        } => gen_witness(
+            dequantized_output,
+            field_output,
+            csv_mapping,
+            input_dir,
+            output_dir,
+            threads,
+            precision,
+            no_felts,
+            no_rescaled,
+            no_processed,
+            sidecar_threshold,
+            no_sidecar,
+            merkle_tree,
+            signature,
+            salt,
+            prev_digest,
+            session_digest_output,
+            recipient_key,
+            blinding_key,
+            redact,
+            redact_recipient,
+            identity,
+        } => match (input_dir, output_dir) {
+            (Some(input_dir), Some(output_dir)) => gen_witness_batch(
+                compiled_circuit.unwrap_or(DEFAULT_COMPILED_CIRCUIT.into()),
+                input_dir,
+                output_dir,
+                vk_path,
+                srs_path,
+                threads,
+                csv_mapping,
+                merkle_tree,
+            )
+            .await
+            .map(|e| serde_json::to_string(&e).unwrap()),
+            _ => gen_witness(
+                compiled_circuit.unwrap_or(DEFAULT_COMPILED_CIRCUIT.into()),
+                data.unwrap_or(DEFAULT_DATA.into()).into(),
+                Some(output.unwrap_or(DEFAULT_WITNESS.into())),
+                vk_path,
+                srs_path,
+                dequantized_output,
+                field_output,
+                csv_mapping,
+                WitnessFormat {
+                    precision,
+                    felts: !no_felts.unwrap_or(false),
+                    rescaled: !no_rescaled.unwrap_or(false),
+                    processed: !no_processed.unwrap_or(false),
+                    sidecar_threshold: sidecar_threshold.filter(|_| !no_sidecar.unwrap_or(false)),
+                },
+                merkle_tree,
+                signature,
+                salt,
+                prev_digest,
+                session_digest_output,
+                recipient_key,
+                blinding_key,
+                redact.unwrap_or(false),
+                redact_recipient,
+                identity,
+            )
+            .await
+            .map(|e| serde_json::to_string(&e).unwrap()),
+        },
+        Commands::MerkleTree {
+            dataset,
+            compiled_circuit,
+            output,
+        } => merkle_tree(
             compiled_circuit.unwrap_or(DEFAULT_COMPILED_CIRCUIT.into()),
-            data.unwrap_or(DEFAULT_DATA.into()),
-            Some(output.unwrap_or(DEFAULT_WITNESS.into())),
-            vk_path,
-            srs_path,
-        )
-        .await
-        .map(|e| serde_json::to_string(&e).unwrap()),
-        Commands::Mock { model, witness } => mock(
+            dataset,
+            output.unwrap_or(DEFAULT_MERKLE_TREE.into()),
+        ),
+        Commands::Mock {
+            model,
+            witness,
+            blinding_secret_key,
+        } => mock(
             model.unwrap_or(DEFAULT_MODEL.into()),
             witness.unwrap_or(DEFAULT_WITNESS.into()),
+            blinding_secret_key,
+        ),
+        Commands::ConvertWitness {
+            witness,
+            output,
+            sidecar_threshold,
+        } => convert_witness(
+            witness.unwrap_or(DEFAULT_WITNESS.into()),
+            output,
+            sidecar_threshold,
+        ),
+        Commands::ValidateWitness {
+            model,
+            witness,
+            blinding_secret_key,
+        } => validate_witness(
+            model.unwrap_or(DEFAULT_COMPILED_CIRCUIT.into()),
+            witness.unwrap_or(DEFAULT_WITNESS.into()),
+            blinding_secret_key,
         ),
         Commands::CreateEvmVerifier {
             vk_path,
@@ -180,6 +847,9 @@ pub async fn run(command: Commands) -> Result<String, EZKLError> {
             sol_code_path,
             abi_path,
             reusable,
+            batch,
+            foundry_dir,
+            fixture_proof_path,
         } => {
             create_evm_verifier(
                 vk_path.unwrap_or(DEFAULT_VK.into()),
@@ -188,6 +858,9 @@ pub async fn run(command: Commands) -> Result<String, EZKLError> {
                 sol_code_path.unwrap_or(DEFAULT_SOL_CODE.into()),
                 abi_path.unwrap_or(DEFAULT_VERIFIER_ABI.into()),
                 reusable.unwrap_or(DEFAULT_RENDER_REUSABLE.parse().unwrap()),
+                batch.unwrap_or(DEFAULT_RENDER_BATCH.parse().unwrap()),
+                foundry_dir,
+                fixture_proof_path,
             )
             .await
         }
@@ -224,6 +897,7 @@ pub async fn run(command: Commands) -> Result<String, EZKLError> {
             abi_path,
             data,
             witness,
+            instance_bindings,
         } => {
             create_evm_data_attestation(
                 settings_path.unwrap_or(DEFAULT_SETTINGS.into()),
@@ -231,6 +905,19 @@ pub async fn run(command: Commands) -> Result<String, EZKLError> {
                 abi_path.unwrap_or(DEFAULT_VERIFIER_DA_ABI.into()),
                 data.unwrap_or(DEFAULT_DATA.into()),
                 witness,
+                instance_bindings,
+            )
+            .await
+        }
+        Commands::CreateEvmPacked {
+            settings_path,
+            sol_code_path,
+            abi_path,
+        } => {
+            create_evm_packed(
+                settings_path.unwrap_or(DEFAULT_SETTINGS.into()),
+                sol_code_path.unwrap_or(DEFAULT_SOL_CODE_PACKED.into()),
+                abi_path.unwrap_or(DEFAULT_VERIFIER_PACKED_ABI.into()),
             )
             .await
         }
@@ -258,11 +945,29 @@ pub async fn run(command: Commands) -> Result<String, EZKLError> {
             model,
             compiled_circuit,
             settings_path,
+            weights,
+            encrypt_to,
         } => compile_circuit(
             model.unwrap_or(DEFAULT_MODEL.into()),
             compiled_circuit.unwrap_or(DEFAULT_COMPILED_CIRCUIT.into()),
             settings_path.unwrap_or(DEFAULT_SETTINGS.into()),
+            weights,
+            encrypt_to,
         ),
+        Commands::CompileBatchFamily {
+            model,
+            settings_path,
+            padding,
+            max_batch_size,
+            output_dir,
+        } => compile_batch_family(
+            model.unwrap_or(DEFAULT_MODEL.into()),
+            settings_path.unwrap_or(DEFAULT_SETTINGS.into()),
+            padding,
+            max_batch_size.unwrap_or(DEFAULT_MAX_BATCH_SIZE.parse().unwrap()),
+            output_dir.unwrap_or(DEFAULT_BATCH_FAMILY_DIR.into()),
+        )
+        .map(|e| serde_json::to_string(&e).unwrap()),
         Commands::Setup {
             compiled_circuit,
             srs_path,
@@ -270,14 +975,34 @@ pub async fn run(command: Commands) -> Result<String, EZKLError> {
             pk_path,
             witness,
             disable_selector_compression,
-        } => setup(
+            batch_family,
+            identity,
+        } => {
+            let disable_selector_compression = disable_selector_compression
+                .unwrap_or(DEFAULT_DISABLE_SELECTOR_COMPRESSION.parse().unwrap());
+            match batch_family {
+                Some(batch_family) => {
+                    setup_batch_family(batch_family, srs_path, disable_selector_compression)
+                }
+                None => setup(
+                    compiled_circuit.unwrap_or(DEFAULT_COMPILED_CIRCUIT.into()),
+                    srs_path,
+                    vk_path.unwrap_or(DEFAULT_VK.into()),
+                    pk_path.unwrap_or(DEFAULT_PK.into()),
+                    witness,
+                    disable_selector_compression,
+                    identity,
+                ),
+            }
+        }
+        Commands::ConvertPk {
+            compiled_circuit,
+            pk_path,
+            output,
+        } => convert_pk(
             compiled_circuit.unwrap_or(DEFAULT_COMPILED_CIRCUIT.into()),
-            srs_path,
-            vk_path.unwrap_or(DEFAULT_VK.into()),
             pk_path.unwrap_or(DEFAULT_PK.into()),
-            witness,
-            disable_selector_compression
-                .unwrap_or(DEFAULT_DISABLE_SELECTOR_COMPRESSION.parse().unwrap()),
+            output.unwrap_or(DEFAULT_PK_MMAP.into()),
         ),
         Commands::SetupTestEvmData {
             data,
@@ -302,12 +1027,24 @@ pub async fn run(command: Commands) -> Result<String, EZKLError> {
             data,
             rpc_url,
         } => test_update_account_calls(addr, data.unwrap_or(DEFAULT_DATA.into()), rpc_url).await,
+        Commands::CommitParamGroups {
+            witness_path,
+            settings_path,
+            output_dir,
+        } => commit_param_groups(
+            witness_path.unwrap_or(DEFAULT_WITNESS.into()),
+            settings_path.unwrap_or(DEFAULT_SETTINGS.into()),
+            output_dir.unwrap_or(DEFAULT_PARAM_GROUPS_DIR.into()),
+        )
+        .map(|e| serde_json::to_string(&e).unwrap()),
         Commands::SwapProofCommitments {
             proof_path,
             witness_path,
+            group_commitments,
         } => swap_proof_commitments_cmd(
             proof_path.unwrap_or(DEFAULT_PROOF.into()),
             witness_path.unwrap_or(DEFAULT_WITNESS.into()),
+            group_commitments,
         )
         .map(|e| serde_json::to_string(&e).unwrap()),
 
@@ -318,17 +1055,53 @@ pub async fn run(command: Commands) -> Result<String, EZKLError> {
             proof_path,
             srs_path,
             proof_type,
+            transcript,
             check_mode,
-        } => prove(
-            witness.unwrap_or(DEFAULT_WITNESS.into()),
-            compiled_circuit.unwrap_or(DEFAULT_COMPILED_CIRCUIT.into()),
-            pk_path.unwrap_or(DEFAULT_PK.into()),
-            Some(proof_path.unwrap_or(DEFAULT_PROOF.into())),
-            srs_path,
-            proof_type,
-            check_mode.unwrap_or(DEFAULT_CHECKMODE.parse().unwrap()),
-        )
-        .map(|e| serde_json::to_string(&e).unwrap()),
+            blinding_secret_key,
+            witness_dir,
+            proof_dir,
+            workers,
+            batch_family,
+            data,
+            identity,
+        } => match (batch_family, witness_dir, proof_dir) {
+            (Some(batch_family), _, _) => prove_batch_family(
+                batch_family,
+                data.unwrap_or(DEFAULT_DATA.into()),
+                Some(proof_path.unwrap_or(DEFAULT_PROOF.into())),
+                srs_path,
+                proof_type,
+                transcript,
+            )
+            .await
+            .map(|e| serde_json::to_string(&e).unwrap()),
+            (None, Some(witness_dir), Some(proof_dir)) => prove_batch(
+                compiled_circuit.unwrap_or(DEFAULT_COMPILED_CIRCUIT.into()),
+                witness_dir,
+                proof_dir,
+                pk_path.unwrap_or(DEFAULT_PK.into()),
+                srs_path,
+                proof_type,
+                transcript,
+                check_mode.unwrap_or(DEFAULT_CHECKMODE.parse().unwrap()),
+                blinding_secret_key,
+                workers.unwrap_or(DEFAULT_PROVE_WORKERS.parse().unwrap()),
+            )
+            .map(|e| serde_json::to_string(&e).unwrap()),
+            _ => prove(
+                witness.unwrap_or(DEFAULT_WITNESS.into()),
+                compiled_circuit.unwrap_or(DEFAULT_COMPILED_CIRCUIT.into()),
+                pk_path.unwrap_or(DEFAULT_PK.into()),
+                Some(proof_path.unwrap_or(DEFAULT_PROOF.into())),
+                srs_path,
+                proof_type,
+                transcript,
+                check_mode.unwrap_or(DEFAULT_CHECKMODE.parse().unwrap()),
+                blinding_secret_key,
+                identity,
+            )
+            .map(|e| serde_json::to_string(&e).unwrap()),
+        },
         Commands::MockAggregate {
             aggregation_snarks,
             logrows,
@@ -380,20 +1153,72 @@ pub async fn run(command: Commands) -> Result<String, EZKLError> {
             commitment.into(),
         )
         .map(|e| serde_json::to_string(&e).unwrap()),
+        Commands::LinkProofs {
+            proof_paths,
+            settings_paths,
+            aggregate,
+            aggregated_proof_path,
+            vk_path,
+            pk_path,
+            srs_path,
+            logrows,
+            output,
+        } => link_proofs(
+            proof_paths,
+            settings_paths,
+            aggregate.unwrap_or(DEFAULT_LINK_AGGREGATE.parse().unwrap()),
+            aggregated_proof_path.unwrap_or(DEFAULT_PROOF_AGGREGATED.into()),
+            vk_path.unwrap_or(DEFAULT_VK_AGGREGATED.into()),
+            pk_path.unwrap_or(DEFAULT_PK_AGGREGATED.into()),
+            srs_path,
+            logrows.unwrap_or(DEFAULT_AGGREGATED_LOGROWS.parse().unwrap()),
+            output,
+        ),
+        Commands::CheckSession {
+            proof_paths,
+            settings_paths,
+            genesis_digest,
+            aggregate,
+            aggregated_proof_path,
+            vk_path,
+            pk_path,
+            srs_path,
+            logrows,
+            output,
+        } => check_session(
+            proof_paths,
+            settings_paths,
+            genesis_digest,
+            aggregate.unwrap_or(DEFAULT_LINK_AGGREGATE.parse().unwrap()),
+            aggregated_proof_path.unwrap_or(DEFAULT_PROOF_AGGREGATED.into()),
+            vk_path.unwrap_or(DEFAULT_VK_AGGREGATED.into()),
+            pk_path.unwrap_or(DEFAULT_PK_AGGREGATED.into()),
+            srs_path,
+            logrows.unwrap_or(DEFAULT_AGGREGATED_LOGROWS.parse().unwrap()),
+            output,
+        ),
         Commands::Verify {
             proof_path,
             settings_path,
             vk_path,
             srs_path,
             reduced_srs,
-        } => verify(
-            proof_path.unwrap_or(DEFAULT_PROOF.into()),
-            settings_path.unwrap_or(DEFAULT_SETTINGS.into()),
-            vk_path.unwrap_or(DEFAULT_VK.into()),
-            srs_path,
-            reduced_srs.unwrap_or(DEFAULT_USE_REDUCED_SRS_FOR_VERIFICATION.parse().unwrap()),
-        )
-        .map(|e| serde_json::to_string(&e).unwrap()),
+            transcript,
+            group_commitments,
+        } => {
+            let proof_path = proof_path.unwrap_or(DEFAULT_PROOF.into());
+            let settings_path = settings_path.unwrap_or(DEFAULT_SETTINGS.into());
+            check_group_commitments(&proof_path, &settings_path, &group_commitments)?;
+            verify(
+                proof_path,
+                settings_path,
+                vk_path.unwrap_or(DEFAULT_VK.into()),
+                srs_path,
+                reduced_srs.unwrap_or(DEFAULT_USE_REDUCED_SRS_FOR_VERIFICATION.parse().unwrap()),
+                transcript,
+            )
+            .map(|e| serde_json::to_string(&e).unwrap())
+        }
         Commands::VerifyAggr {
             proof_path,
             vk_path,
@@ -401,6 +1226,7 @@ pub async fn run(command: Commands) -> Result<String, EZKLError> {
             reduced_srs,
             logrows,
             commitment,
+            transcript,
         } => verify_aggr(
             proof_path.unwrap_or(DEFAULT_PROOF_AGGREGATED.into()),
             vk_path.unwrap_or(DEFAULT_VK_AGGREGATED.into()),
@@ -408,6 +1234,7 @@ pub async fn run(command: Commands) -> Result<String, EZKLError> {
             logrows.unwrap_or(DEFAULT_AGGREGATED_LOGROWS.parse().unwrap()),
             reduced_srs.unwrap_or(DEFAULT_USE_REDUCED_SRS_FOR_VERIFICATION.parse().unwrap()),
             commitment.into(),
+            transcript,
         )
         .map(|e| serde_json::to_string(&e).unwrap()),
         Commands::DeployEvm {
@@ -417,6 +1244,8 @@ pub async fn run(command: Commands) -> Result<String, EZKLError> {
             optimizer_runs,
             private_key,
             contract,
+            registry,
+            settings_path,
         } => {
             deploy_evm(
                 sol_code_path.unwrap_or(DEFAULT_SOL_CODE.into()),
@@ -425,6 +1254,36 @@ pub async fn run(command: Commands) -> Result<String, EZKLError> {
                 optimizer_runs,
                 private_key,
                 contract,
+                registry,
+                settings_path.unwrap_or(DEFAULT_SETTINGS.into()),
+            )
+            .await
+        }
+        Commands::DeployEvmRegistry {
+            rpc_url,
+            addr_path,
+            optimizer_runs,
+            private_key,
+        } => {
+            deploy_evm_registry(
+                rpc_url,
+                addr_path.unwrap_or(DEFAULT_CONTRACT_ADDRESS_REGISTRY.into()),
+                optimizer_runs,
+                private_key,
+            )
+            .await
+        }
+        Commands::ResolveEvmVerifier {
+            registry,
+            settings_path,
+            rpc_url,
+            addr_path,
+        } => {
+            resolve_evm_verifier(
+                registry,
+                settings_path.unwrap_or(DEFAULT_SETTINGS.into()),
+                rpc_url,
+                addr_path,
             )
             .await
         }
@@ -448,12 +1307,45 @@ pub async fn run(command: Commands) -> Result<String, EZKLError> {
             )
             .await
         }
+        Commands::DeployEvmPacked {
+            sol_code_path,
+            addr_verifier,
+            rpc_url,
+            addr_path,
+            optimizer_runs,
+            private_key,
+        } => {
+            deploy_packed_evm(
+                sol_code_path.unwrap_or(DEFAULT_SOL_CODE_PACKED.into()),
+                addr_verifier,
+                rpc_url,
+                addr_path.unwrap_or(DEFAULT_CONTRACT_ADDRESS_PACKED.into()),
+                optimizer_runs,
+                private_key,
+            )
+            .await
+        }
+        Commands::PinEvmDataAttestation {
+            addr_da,
+            data,
+            rpc_url,
+            private_key,
+        } => {
+            pin_evm_da(
+                addr_da,
+                data.unwrap_or(DEFAULT_DATA.into()),
+                rpc_url,
+                private_key,
+            )
+            .await
+        }
         Commands::VerifyEvm {
             proof_path,
             addr_verifier,
             rpc_url,
             addr_da,
             addr_vk,
+            data_path,
         } => {
             verify_evm(
                 proof_path.unwrap_or(DEFAULT_PROOF.into()),
@@ -461,6 +1353,38 @@ pub async fn run(command: Commands) -> Result<String, EZKLError> {
                 rpc_url,
                 addr_da,
                 addr_vk,
+                data_path,
+            )
+            .await
+        }
+        Commands::VerifyEvmBatch {
+            proof_paths,
+            addr_verifier,
+            rpc_url,
+            addr_vk,
+        } => verify_evm_batch(proof_paths, addr_verifier, rpc_url, addr_vk).await,
+        Commands::EstimateGas {
+            proof_path,
+            sol_code_path,
+            vka_path,
+            backend,
+            rpc_url,
+            optimizer_runs,
+            output,
+            packed_sol_code_path,
+            witness_path,
+            settings_path,
+        } => {
+            estimate_gas(
+                proof_path.unwrap_or(DEFAULT_PROOF.into()),
+                sol_code_path.unwrap_or(DEFAULT_SOL_CODE.into()),
+                vka_path,
+                backend,
+                rpc_url,
+                optimizer_runs,
+                output,
+                packed_sol_code_path.zip(witness_path),
+                settings_path.unwrap_or(DEFAULT_SETTINGS.into()),
             )
             .await
         }
@@ -549,6 +1473,46 @@ fn srs_exists_check(logrows: u32, srs_path: Option<PathBuf>, commitment: Commitm
     Path::new(&get_srs_path(logrows, srs_path, commitment)).exists()
 }
 
+/// The SRS files of the cache directory, with the commitment and logrows of their name, sorted by name
+fn cached_srs_files() -> Vec<(Commitments, u32, PathBuf)> {
+    let mut files = match std::fs::read_dir(&*EZKL_SRS_REPO_PATH) {
+        Ok(entries) => entries
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                let name = path.file_name()?.to_str()?.strip_suffix(".srs")?;
+                let (commitment, logrows) = if let Some(k) = name.strip_prefix("kzg") {
+                    (Commitments::KZG, k)
+                } else {
+                    (Commitments::IPA, name.strip_prefix("ipa")?)
+                };
+                Some((commitment, logrows.parse().ok()?, path))
+            })
+            .collect::<Vec<_>>(),
+        Err(_) => vec![],
+    };
+    files.sort_by(|a, b| a.2.cmp(&b.2));
+    files
+}
+
+/// The smallest cached SRS of the commitment with more than `logrows` logrows
+fn cached_srs_above(logrows: u32, commitment: Commitments) -> Option<PathBuf> {
+    cached_srs_files()
+        .into_iter()
+        .filter(|(c, k, _)| *c == commitment && *k > logrows)
+        .min_by_key(|(_, k, _)| *k)
+        .map(|(_, _, path)| path)
+}
+
+/// The SRS to read for `logrows`: the given path, else the cached SRS of the logrows, else the smallest larger cached SRS
+/// (which is downsized when loaded). Falls back to the cached path of the logrows when none is cached.
+pub fn find_srs_path(logrows: u32, srs_path: Option<PathBuf>, commitment: Commitments) -> PathBuf {
+    let path = get_srs_path(logrows, srs_path.clone(), commitment);
+    if srs_path.is_some() || path.exists() {
+        return path;
+    }
+    cached_srs_above(logrows, commitment).unwrap_or(path)
+}
+
 pub(crate) fn gen_srs_cmd(
     srs_path: PathBuf,
     logrows: u32,
@@ -567,23 +1531,195 @@ pub(crate) fn gen_srs_cmd(
     Ok(String::new())
 }
 
-async fn fetch_srs(uri: &str) -> Result<Vec<u8>, EZKLError> {
+/// Downloads `uri` to `path`, resuming from the end of `path` if a previous download was interrupted
+async fn fetch_resumable(uri: &str, path: &Path) -> Result<(), EZKLError> {
+    let offset = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
     let pb = {
         let pb = init_spinner();
         pb.set_message("Downloading SRS (this may take a while) ...");
         pb
     };
     let client = reqwest::Client::new();
+    let mut request = client.get(uri);
+    if offset > 0 {
+        info!("resuming the download of {} from byte {}", uri, offset);
+        request = request.header(reqwest::header::RANGE, format!("bytes={}-", offset));
+    }
     // wasm doesn't require it to be mutable
     #[allow(unused_mut)]
-    let mut resp = client.get(uri).body(vec![]).send().await?;
-    let mut buf = vec![];
+    let mut resp = request.send().await?;
+    let mut file = match resp.status() {
+        reqwest::StatusCode::PARTIAL_CONTENT => {
+            std::fs::OpenOptions::new().append(true).open(path)?
+        }
+        // the previous download had already received the whole file
+        reqwest::StatusCode::RANGE_NOT_SATISFIABLE if offset > 0 => {
+            pb.finish_with_message("SRS downloaded.");
+            return Ok(());
+        }
+        // the server ignores ranges, start over
+        status if status.is_success() => File::create(path)?,
+        status => return Err(SrsError::DownloadError(format!("{} ({})", uri, status)).into()),
+    };
+    // write the chunks as they arrive, such that an interrupted download can be resumed
     while let Some(chunk) = resp.chunk().await? {
-        buf.extend(chunk.to_vec());
+        file.write_all(&chunk)?;
     }
+    file.flush()?;
 
     pb.finish_with_message("SRS downloaded.");
-    Ok(std::mem::take(&mut buf))
+    Ok(())
+}
+
+/// Downloads the KZG SRS of `logrows` from [EZKL_SRS_URL] to `path`, or else from its mirrors (see [srs_mirrors]) in
+/// order. The download goes to `{path}.part` until it is complete and checked, such that a download interrupted at a
+/// source is resumed from the next one, or by the next fetch.
+async fn fetch_srs(logrows: u32, path: &Path) -> Result<(), EZKLError> {
+    if offline() {
+        return Err(SrsError::Offline(path.to_path_buf()).into());
+    }
+    let part = PathBuf::from(format!("{}.part", path.display()));
+    let mut failures = vec![];
+    for source in std::iter::once(EZKL_SRS_URL.clone()).chain(srs_mirrors()) {
+        let uri = format!("{}{}", source, logrows);
+        let fetched = match fetch_resumable(&uri, &part).await {
+            Ok(()) => check_fetched_srs(logrows, &uri, &part),
+            Err(e) => Err(e),
+        };
+        match fetched {
+            Ok(()) => {
+                std::fs::rename(&part, path)?;
+                info!("Saved SRS to {}", path.display());
+                return Ok(());
+            }
+            Err(e) => {
+                warn!("failed to fetch the SRS from {}: {}", uri, e);
+                failures.push(format!("{} ({})", uri, e));
+            }
+        }
+    }
+    Err(SrsError::DownloadError(failures.join(", ")).into())
+}
+
+/// Checks a complete download of the KZG SRS of `logrows` from `uri`, removing it if it isn't the SRS such that the
+/// next source starts over
+fn check_fetched_srs(logrows: u32, uri: &str, part: &Path) -> Result<(), EZKLError> {
+    let pb = init_spinner();
+    pb.set_message("Validating SRS (this may take a while) ...");
+    let k = match load_srs_for_cmd::<KZGCommitmentScheme<Bn256>>(&part.to_path_buf()) {
+        Ok(params) => params.k(),
+        Err(e) => {
+            // a complete download that doesn't load is corrupt
+            warn!("removing SRS file at {}", part.display());
+            std::fs::remove_file(part)?;
+            return Err(e);
+        }
+    };
+    if k != logrows {
+        std::fs::remove_file(part)?;
+        return Err(format!("SRS at {} has {} logrows, expected {}", uri, k, logrows).into());
+    }
+    // the srs url may serve other SRS files than the public ones, only the public SRS (and its mirrors) is held to the
+    // public hashes
+    if *EZKL_SRS_URL == PUBLIC_SRS_URL {
+        if let Some(expected) = crate::srs_sha::PUBLIC_SRS_SHA256_HASHES.get(&logrows) {
+            if srs_file_hash(part)? != *expected {
+                warn!("removing SRS file at {}", part.display());
+                std::fs::remove_file(part)?;
+                return Err(
+                    "SRS hash does not match the expected hash. Remote SRS may have been tampered with."
+                        .into(),
+                );
+            }
+        }
+    }
+    pb.finish_with_message("SRS validated.");
+    Ok(())
+}
+
+/// The sha256 hash of a file, read in chunks as SRS files can be larger than the memory
+fn srs_file_hash(path: &Path) -> Result<String, EZKLError> {
+    use sha2::Digest;
+    let mut hasher = sha2::Sha256::new();
+    std::io::copy(&mut std::io::BufReader::new(File::open(path)?), &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// The logrows an SRS file was written with, read from its header
+fn srs_file_logrows(path: &Path) -> Result<u32, EZKLError> {
+    use std::io::Read;
+    let mut k = [0u8; 4];
+    File::open(path)?.read_exact(&mut k)?;
+    Ok(u32::from_le_bytes(k))
+}
+
+/// An SRS file of the cache directory
+#[derive(Debug, Clone, Serialize, serde::Deserialize)]
+pub struct CachedSrs {
+    /// The path of the file
+    pub path: PathBuf,
+    /// The commitment the SRS is for
+    pub commitment: Commitments,
+    /// The logrows the SRS was written with
+    pub logrows: u32,
+    /// The size of the file in bytes
+    pub size: u64,
+    /// The sha256 hash of the file
+    pub sha256: String,
+    /// Whether the file is the public SRS of its logrows
+    pub public: bool,
+}
+
+#[derive(tabled::Tabled)]
+struct CachedSrsRow {
+    file: String,
+    commitment: String,
+    logrows: u32,
+    size: String,
+    sha256: String,
+    public: bool,
+}
+
+/// Lists the SRS files of the cache directory, writing the json list to `output` if given
+fn list_srs(output: Option<PathBuf>) -> Result<String, EZKLError> {
+    let mut cached = vec![];
+    for (commitment, _, path) in cached_srs_files() {
+        let logrows = srs_file_logrows(&path)?;
+        let sha256 = srs_file_hash(&path)?;
+        let public = matches!(commitment, Commitments::KZG)
+            && crate::srs_sha::PUBLIC_SRS_SHA256_HASHES.get(&logrows) == Some(&sha256.as_str());
+        cached.push(CachedSrs {
+            size: std::fs::metadata(&path)?.len(),
+            path,
+            commitment,
+            logrows,
+            sha256,
+            public,
+        });
+    }
+
+    let rows = cached.iter().map(|c| CachedSrsRow {
+        file: c
+            .path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into(),
+        commitment: c.commitment.to_string(),
+        logrows: c.logrows,
+        size: format!("{:.1} MiB", c.size as f64 / (1 << 20) as f64),
+        sha256: c.sha256.clone(),
+        public: c.public,
+    });
+    let mut table = tabled::Table::new(rows);
+    table.with(tabled::settings::Style::modern());
+    info!("SRS files cached in {}:\n {}", *EZKL_SRS_REPO_PATH, table);
+
+    let json = serde_json::to_string_pretty(&cached)?;
+    if let Some(output) = output {
+        std::fs::write(output, &json)?;
+    }
+    Ok(json)
 }
 
 pub(crate) fn get_file_hash(path: &PathBuf) -> Result<String, EZKLError> {
@@ -629,6 +1765,131 @@ fn check_srs_hash(
     Ok(hash)
 }
 
+fn load_srs_for_cmd<Scheme: CommitmentScheme>(
+    path: &PathBuf,
+) -> Result<Scheme::ParamsProver, EZKLError>
+where
+    Scheme::ParamsProver: SrsIntegrity,
+{
+    let params = load_srs_prover::<Scheme>(path.clone())?;
+    params.check_integrity()?;
+    Ok(params)
+}
+
+/// The logrows and commitment of the SRS to operate on, read from the settings when they aren't given
+fn srs_target(
+    logrows: Option<u32>,
+    settings_path: Option<PathBuf>,
+    commitment: Option<Commitments>,
+) -> Result<(u32, Commitments), EZKLError> {
+    let settings = match settings_path {
+        Some(path) if logrows.is_none() || commitment.is_none() => {
+            Some(GraphSettings::load(&path)?)
+        }
+        _ => None,
+    };
+    let logrows = logrows
+        .or(settings.as_ref().map(|s| s.run_args.logrows))
+        .ok_or("the logrows of the SRS are required, pass --logrows or a settings file (-S)")?;
+    let commitment = commitment
+        .or(settings.map(|s| s.run_args.commitment.into()))
+        .unwrap_or(Commitments::from_str(DEFAULT_COMMITMENT).unwrap());
+    Ok((logrows, commitment))
+}
+
+pub(crate) async fn srs_cmd(
+    action: SrsAction,
+    srs_path: Option<PathBuf>,
+    logrows: Option<u32>,
+    settings_path: Option<PathBuf>,
+    output: Option<PathBuf>,
+    commitment: Option<Commitments>,
+) -> Result<String, EZKLError> {
+    let target = || srs_target(logrows, settings_path.clone(), commitment);
+    match action {
+        SrsAction::List => return list_srs(output),
+        SrsAction::Locate => {
+            let (logrows, commitment) = target()?;
+            let path = find_srs_path(logrows, srs_path, commitment);
+            if !path.exists() {
+                warn!(
+                    "no SRS of {} logrows is cached, run `ezkl srs fetch --logrows {}` to download it",
+                    logrows, logrows
+                );
+            }
+            info!("{}", path.display());
+            return Ok(path.display().to_string());
+        }
+        SrsAction::Fetch => {
+            let (logrows, commitment) = target()?;
+            if !matches!(commitment, Commitments::KZG) {
+                return Err(
+                    "only KZG SRS files can be fetched, generate IPA SRS files with gen-srs".into(),
+                );
+            }
+            let path = get_srs_path(logrows, srs_path, commitment);
+            if path.exists() {
+                info!("SRS already exists at {:?}", path);
+            } else {
+                fetch_srs(logrows, &path).await?;
+            }
+        }
+        SrsAction::Verify => {
+            let (logrows, commitment) = target()?;
+            let path = get_srs_path(logrows, srs_path.clone(), commitment);
+            let k = match commitment {
+                Commitments::KZG => load_srs_for_cmd::<KZGCommitmentScheme<Bn256>>(&path)?.k(),
+                Commitments::IPA => load_srs_for_cmd::<IPACommitmentScheme<G1Affine>>(&path)?.k(),
+            };
+            if k != logrows {
+                return Err(
+                    format!("SRS at {:?} has {} logrows, expected {}", path, k, logrows).into(),
+                );
+            }
+            // only the public kzg srs files have known hashes, to which an srs trimmed from a public srs hashes too. The
+            // srs url may serve other SRS files than the public ones, which aren't held to them
+            if matches!(commitment, Commitments::KZG) && *EZKL_SRS_URL == PUBLIC_SRS_URL {
+                if let Some(expected) = crate::srs_sha::PUBLIC_SRS_SHA256_HASHES.get(&logrows) {
+                    let hash = srs_file_hash(&path)?;
+                    if hash != *expected {
+                        return Err(SrsError::IntegrityError(format!(
+                            "the sha256 of {:?} is {}, the public SRS of {} logrows hashes to {}",
+                            path, hash, logrows, expected
+                        ))
+                        .into());
+                    }
+                }
+            }
+            info!("SRS at {:?} is valid ✅", path);
+        }
+        SrsAction::Trim => {
+            let (logrows, commitment) = target()?;
+            // defaults to the smallest cached SRS that is larger
+            let src = srs_path
+                .or_else(|| cached_srs_above(logrows, commitment))
+                .ok_or("trimming requires the path to the source SRS (--srs-path)")?;
+            let dst = output.unwrap_or_else(|| get_srs_path(logrows, None, commitment));
+            match commitment {
+                // the powers kept are checked in full once trimmed, the others aren't worth checking
+                Commitments::KZG => {
+                    let mut params = load_srs_prover::<KZGCommitmentScheme<Bn256>>(src)?;
+                    trim_srs::<KZGCommitmentScheme<Bn256>>(&mut params, logrows)?;
+                    params.check_integrity()?;
+                    save_params::<KZGCommitmentScheme<Bn256>>(&dst, &params)?;
+                }
+                Commitments::IPA => {
+                    let mut params = load_srs_prover::<IPACommitmentScheme<G1Affine>>(src)?;
+                    trim_srs::<IPACommitmentScheme<G1Affine>>(&mut params, logrows)?;
+                    params.check_integrity()?;
+                    save_params::<IPACommitmentScheme<G1Affine>>(&dst, &params)?;
+                }
+            }
+            info!("saved trimmed SRS ({} logrows) to {:?}", logrows, dst);
+        }
+    }
+    Ok(String::new())
+}
+
 pub(crate) async fn get_srs_cmd(
     srs_path: Option<PathBuf>,
     settings_path: Option<PathBuf>,
@@ -668,21 +1929,7 @@ pub(crate) async fn get_srs_cmd(
     if !srs_exists_check(k, srs_path.clone(), commitment) {
         if matches!(commitment, Commitments::KZG) {
             info!("SRS does not exist, downloading...");
-            let srs_uri = format!("{}{}", PUBLIC_SRS_URL, k);
-            let mut reader = Cursor::new(fetch_srs(&srs_uri).await?);
-            // check the SRS
-                let pb = init_spinner();
-                pb.set_message("Validating SRS (this may take a while) ...");
-            let params = ParamsKZG::<Bn256>::read(&mut reader)?;
-                pb.finish_with_message("SRS validated.");
-
-            info!("Saving SRS to disk...");
-            let mut file = std::fs::File::create(get_srs_path(k, srs_path.clone(), commitment))?;
-            let mut buffer = BufWriter::with_capacity(*EZKL_BUF_CAPACITY, &mut file);
-            params.write(&mut buffer)?;
-
-            info!("Saved SRS to disk.");
-
+            fetch_srs(k, &get_srs_path(k, srs_path.clone(), commitment)).await?;
             info!("SRS downloaded");
         } else {
             let path = get_srs_path(k, srs_path.clone(), commitment);
@@ -691,103 +1938,370 @@ pub(crate) async fn get_srs_cmd(
     } else {
         info!("SRS already exists at that path");
     };
-    // check the hash
-    if matches!(commitment, Commitments::KZG) {
+    // check the hash, mirrors may serve other SRS files than the public ones
+    if matches!(commitment, Commitments::KZG) && *EZKL_SRS_URL == PUBLIC_SRS_URL {
         check_srs_hash(k, srs_path.clone(), commitment)?;
     }
 
     Ok(String::new())
 }
 
+pub(crate) fn config_cmd(
+    action: ConfigAction,
+    command: Option<String>,
+) -> Result<String, EZKLError> {
+    match action {
+        ConfigAction::Show => {
+            let config = crate::config::loaded().map_err(|e| e.to_string())?;
+            match &config {
+                Some(config) => info!("configuration from {}", config.path.display()),
+                None => info!("no {} found", crate::config::CONFIG_FILE),
+            }
+            let shown = crate::config::Config::show(config.as_ref(), command.as_deref())?;
+            info!("\n{}", shown);
+            Ok(shown)
+        }
+    }
+}
+
 pub(crate) fn table(model: PathBuf, run_args: RunArgs) -> Result<String, EZKLError> {
     let model = Model::from_run_args(&run_args, &model)?;
     info!("\n {}", model.table_nodes());
     Ok(String::new())
 }
 
-pub(crate) async fn gen_witness(
-    compiled_circuit_path: PathBuf,
-    data: PathBuf,
+pub(crate) fn circuit_profile(
+    model: PathBuf,
+    run_args: RunArgs,
     output: Option<PathBuf>,
-    vk_path: Option<PathBuf>,
-    srs_path: Option<PathBuf>,
-) -> Result<GraphWitness, EZKLError> {
-    // these aren't real values so the sanity checks are mostly meaningless
+) -> Result<String, EZKLError> {
+    let model = Model::from_run_args(&run_args, &model)?;
+    let profile = model.profile(&run_args)?;
+    info!("\n {}", profile.table());
 
-    let mut circuit = GraphCircuit::load(compiled_circuit_path)?;
-    let data: GraphData = GraphData::from_path(data)?;
-    let settings = circuit.settings().clone();
+    let json = serde_json::to_string_pretty(&profile)?;
+    if let Some(output) = output {
+        std::fs::write(output, &json)?;
+    }
+    Ok(json)
+}
 
-    let vk = if let Some(vk) = vk_path {
-        Some(load_vk::<KZGCommitmentScheme<Bn256>, GraphCircuit>(
-            vk,
-            settings.clone(),
-        )?)
-    } else {
-        None
-    };
+pub(crate) fn list_rescales(
+    model: PathBuf,
+    run_args: RunArgs,
+    output: Option<PathBuf>,
+) -> Result<String, EZKLError> {
+    let model = Model::from_run_args(&run_args, &model)?;
+    let rescales = model.implicit_rescales();
+    for rescale in &rescales {
+        let at = match rescale.input {
+            Some(input) => format!("input {} of", input),
+            None => "output of".to_string(),
+        };
+        info!(
+            "{} node {} ({}) rescaled from scale {} to {}",
+            at, rescale.node, rescale.name, rescale.from_scale, rescale.to_scale
+        );
+    }
 
-        let mut input = circuit.load_graph_input(&data).await?;
-    #[cfg(any(not(feature = "ezkl"), target_arch = "wasm32"))]
-    let mut input = circuit.load_graph_input(&data)?;
-
-    // if any of the settings have kzg visibility then we need to load the srs
+    let json = serde_json::to_string_pretty(&rescales)?;
+    if let Some(output) = output {
+        std::fs::write(output, &json)?;
+    }
+    Ok(json)
+}
 
-    let commitment: Commitments = settings.run_args.commitment.into();
+pub(crate) fn inspect(
+    compiled_circuit: PathBuf,
+    node: Option<usize>,
+    output: Option<PathBuf>,
+) -> Result<String, EZKLError> {
+    let circuit = GraphCircuit::load(compiled_circuit)?;
+    let json = match node {
+        Some(idx) => {
+            let node = circuit.node(idx).ok_or(GraphError::MissingNode(idx))?;
+            info!("{:#?}", node);
+            serde_json::to_string_pretty(&node)?
+        }
+        None => {
+            let inspection = circuit.inspect();
+            info!("\n {}", inspection.table());
+            serde_json::to_string_pretty(&inspection)?
+        }
+    };
+    if let Some(output) = output {
+        std::fs::write(output, &json)?;
+    }
+    Ok(json)
+}
 
-    let region_settings =
-        RegionSettings::all_true(settings.run_args.decomp_base, settings.run_args.decomp_legs);
+pub(crate) fn schema(artifact: JsonArtifact, output: Option<PathBuf>) -> Result<String, EZKLError> {
+    let json = serde_json::to_string_pretty(&artifact.schema())?;
+    if let Some(output) = output {
+        std::fs::write(output, &json)?;
+    }
+    Ok(json)
+}
 
-    let start_time = Instant::now();
-    let witness = if settings.module_requires_polycommit() {
-        if get_srs_path(settings.run_args.logrows, srs_path.clone(), commitment).exists() {
-            match Commitments::from(settings.run_args.commitment) {
-                Commitments::KZG => {
-                    let srs: ParamsKZG<Bn256> = load_params_prover::<KZGCommitmentScheme<Bn256>>(
-                        srs_path.clone(),
-                        settings.run_args.logrows,
-                        commitment,
-                    )?;
-                    circuit.forward::<KZGCommitmentScheme<_>>(
-                        &mut input,
-                        vk.as_ref(),
-                        Some(&srs),
-                        region_settings,
-                    )?
+pub(crate) fn report(
+    settings_path: PathBuf,
+    compiled_circuit: PathBuf,
+    proof_path: Option<PathBuf>,
+    vk_path: Option<PathBuf>,
+    srs_path: Option<PathBuf>,
+    format: ReportFormat,
+    output: Option<PathBuf>,
+) -> Result<String, EZKLError> {
+    let settings = GraphSettings::load(&settings_path)?;
+    let compiled = std::fs::read(&compiled_circuit)?;
+    let circuit = GraphCircuit::load(compiled_circuit)?;
+
+    let proof = match proof_path {
+        Some(proof_path) => {
+            let snark = Snark::load::<KZGCommitmentScheme<Bn256>>(&proof_path)?;
+            let (verified, verification_error) = match vk_path {
+                Some(vk_path) => {
+                    match verify(proof_path, settings_path, vk_path, srs_path, false, None) {
+                        Ok(verified) => (Some(verified), None),
+                        Err(e) => {
+                            warn!("the proof didn't verify: {}", e);
+                            (Some(false), Some(e.to_string()))
+                        }
+                    }
                 }
-                Commitments::IPA => {
-                    let srs: ParamsIPA<G1Affine> =
-                        load_params_prover::<IPACommitmentScheme<G1Affine>>(
-                            srs_path.clone(),
-                            settings.run_args.logrows,
-                            commitment,
-                        )?;
-                    circuit.forward::<IPACommitmentScheme<_>>(
-                        &mut input,
-                        vk.as_ref(),
-                        Some(&srs),
-                        region_settings,
-                    )?
+                None => (None, None),
+            };
+            Some(ProofReport {
+                size: snark.proof.len(),
+                num_instances: snark.instances.iter().map(|i| i.len()).sum(),
+                transcript: snark.transcript_type.to_string(),
+                verified,
+                verification_error,
+            })
+        }
+        None => None,
+    };
+
+    let report = CircuitReport::new(&settings, &circuit, &compiled, proof)?.render(format);
+    if let Some(output) = output {
+        std::fs::write(output, &report)?;
+    }
+    Ok(report)
+}
+
+pub(crate) fn export_vk_description(
+    vk_path: PathBuf,
+    settings_path: PathBuf,
+    format: VkDescriptionFormat,
+    output: Option<PathBuf>,
+) -> Result<String, EZKLError> {
+    let settings = GraphSettings::load(&settings_path)?;
+    let vk = match settings.run_args.commitment.into() {
+        Commitments::KZG => {
+            load_vk::<KZGCommitmentScheme<Bn256>, GraphCircuit>(vk_path, settings.clone())?
+        }
+        Commitments::IPA => {
+            load_vk::<IPACommitmentScheme<G1Affine>, GraphCircuit>(vk_path, settings.clone())?
+        }
+    };
+    let description = VkDescription::new(&vk, &settings);
+
+    let rendered = match format {
+        VkDescriptionFormat::Json => serde_json::to_string_pretty(&description)?,
+    };
+    if let Some(output) = output {
+        std::fs::write(output, &rendered)?;
+    }
+    Ok(rendered)
+}
+
+pub(crate) fn check_tables(
+    settings_path: PathBuf,
+    output: Option<PathBuf>,
+) -> Result<String, EZKLError> {
+    let settings = GraphSettings::load(&settings_path)?;
+    let range = settings.run_args.lookup_range;
+
+    let reports = settings
+        .required_lookups
+        .iter()
+        .map(|op| {
+            let report = op.check_table_consistency(range)?;
+            info!(
+                "{}: {} mismatches, max deviation {} at {:?}",
+                report.op, report.num_mismatches, report.max_deviation, report.max_deviation_input
+            );
+            Ok(report)
+        })
+        .collect::<Result<Vec<_>, CircuitError>>()?;
+
+    let json = serde_json::to_string_pretty(&reports)?;
+    if let Some(output) = output {
+        std::fs::write(output, &json)?;
+    }
+
+    let inconsistent = reports
+        .iter()
+        .filter(|r| !r.is_consistent())
+        .map(|r| r.op.clone())
+        .collect::<Vec<_>>();
+    if !inconsistent.is_empty() {
+        return Err(ExecutionError::InconsistentTables(inconsistent).into());
+    }
+    Ok(json)
+}
+
+/// Load the input data of a model, the csv mapping spec lays out how the columns of a csv file feed the model inputs
+/// and png / jpeg images are preprocessed as the spec in the circuit settings
+fn load_graph_data(
+    data: PathBuf,
+    input_names: &[String],
+    csv_mapping: Option<PathBuf>,
+    image_preprocessing: Option<&ImagePreprocessing>,
+) -> Result<GraphData, EZKLError> {
+    let is_image = data
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| ["png", "jpg", "jpeg"].contains(&e.to_lowercase().as_str()));
+    let data = match (csv_mapping, is_image) {
+        (Some(mapping), _) => GraphData::from_csv(data, &CsvMapping::load(&mapping)?)?,
+        (None, true) => {
+            let preprocessing = image_preprocessing.ok_or(
+                "image inputs need an image preprocessing spec in the settings (gen-settings --image-preprocessing)",
+            )?;
+            GraphData::from_image(data, preprocessing)?
+        }
+        (None, false) => GraphData::from_path_with_input_names(data, input_names)?,
+    };
+    Ok(data)
+}
+
+/// The data a model is run on, read from a file or handed over in memory by the bindings
+#[derive(Debug, Clone)]
+pub(crate) enum DataInput {
+    /// A .json, .csv, .npy / .npz or image file
+    Path(PathBuf),
+    /// The flattened values of each input, with their shape
+    Arrays(Vec<(Vec<FileSourceInner>, Vec<usize>)>),
+}
+
+impl From<PathBuf> for DataInput {
+    fn from(path: PathBuf) -> Self {
+        DataInput::Path(path)
+    }
+}
+
+impl DataInput {
+    /// Load the data for a model with the given input shapes, which the arrays must match up to dimensions of size 1.
+    /// When `batched` the arrays may also stack several batches along their first dimension, as calibration data does.
+    fn load(
+        self,
+        input_names: &[String],
+        input_shapes: &[Vec<usize>],
+        batched: bool,
+        csv_mapping: Option<PathBuf>,
+        image_preprocessing: Option<&ImagePreprocessing>,
+    ) -> Result<GraphData, EZKLError> {
+        let arrays = match self {
+            DataInput::Path(path) => {
+                return load_graph_data(path, input_names, csv_mapping, image_preprocessing)
+            }
+            DataInput::Arrays(arrays) => arrays,
+        };
+        if arrays.len() != input_shapes.len() {
+            return Err(GraphError::InvalidInputCount {
+                expected: input_shapes.len(),
+                actual: arrays.len(),
+            }
+            .into());
+        }
+        // dimensions of size 1 are left out of the comparison, numpy arrays often drop the batch dimension
+        let same = |a: &[usize], b: &[usize]| {
+            a.iter()
+                .filter(|d| **d != 1)
+                .eq(b.iter().filter(|d| **d != 1))
+        };
+        for (i, ((data, shape), expected)) in arrays.iter().zip(input_shapes).enumerate() {
+            let expected_len = expected.iter().product::<usize>();
+            let stacked = batched
+                && !shape.is_empty()
+                && !expected.is_empty()
+                && same(&shape[1..], &expected[1..])
+                && expected_len > 0
+                && data.len() % expected_len == 0;
+            let matches = same(shape, expected) || stacked;
+            if !matches {
+                return Err(GraphError::InvalidInputShape {
+                    input: i,
+                    expected: expected.clone(),
+                    actual: shape.clone(),
                 }
+                .into());
             }
-        } else {
-            warn!("SRS for poly commit does not exist (will be ignored)");
-            circuit.forward::<KZGCommitmentScheme<Bn256>>(
-                &mut input,
-                vk.as_ref(),
-                None,
-                region_settings,
-            )?
         }
+        Ok(GraphData::new(DataSource::File(
+            arrays.into_iter().map(|(data, _)| data).collect(),
+        )))
+    }
+}
+
+pub(crate) async fn gen_witness(
+    compiled_circuit_path: PathBuf,
+    data: DataInput,
+    output: Option<PathBuf>,
+    vk_path: Option<PathBuf>,
+    srs_path: Option<PathBuf>,
+    dequantized_output: Option<PathBuf>,
+    field_output: Option<PathBuf>,
+    csv_mapping: Option<PathBuf>,
+    format: WitnessFormat,
+    merkle_tree: Option<PathBuf>,
+    signature: Option<String>,
+    salt: Option<String>,
+    prev_digest: Option<String>,
+    session_digest_output: Option<PathBuf>,
+    recipient_key: Option<Vec<String>>,
+    blinding_key: Option<Vec<String>>,
+    redact: bool,
+    redact_recipient: Option<String>,
+    identity: Option<PathBuf>,
+) -> Result<GraphWitness, EZKLError> {
+    // these aren't real values so the sanity checks are mostly meaningless
+
+    let identity = load_identity(identity)?;
+    let mut circuit = GraphCircuit::load_decrypted(compiled_circuit_path, identity.as_ref())?;
+    let data = data.load(
+        circuit.model().graph.input_names(),
+        &circuit.model().graph.input_shapes()?,
+        false,
+        csv_mapping,
+        circuit.settings().image_preprocessing.as_ref(),
+    )?;
+    let settings = circuit.settings().clone();
+    let merkle_tree = load_merkle_tree(&settings, merkle_tree)?;
+
+    let vk = if let Some(vk) = vk_path {
+        Some(load_vk::<KZGCommitmentScheme<Bn256>, GraphCircuit>(
+            vk,
+            settings.clone(),
+        )?)
     } else {
-        circuit.forward::<KZGCommitmentScheme<Bn256>>(
-            &mut input,
-            vk.as_ref(),
-            None,
-            region_settings,
-        )?
+        None
     };
 
+    // resolve the pinned block before reading so a reorged pin fails instead of silently reading other state
+    let pinned_block = crate::eth::pinned_block(&data).await?;
+
+        let mut input = circuit.load_graph_input(&data).await?;
+    #[cfg(any(not(feature = "ezkl"), target_arch = "wasm32"))]
+    let mut input = circuit.load_graph_input(&data)?;
+
+    // if any of the settings have kzg visibility then we need to load the srs
+    let srs = WitnessSrs::load(&settings, srs_path)?;
+
+    let start_time = Instant::now();
+    let mut witness = srs.forward(&circuit, &mut input, vk.as_ref())?;
+
     // print each variable tuple (symbol, value) as symbol=value
     trace!(
         "witness generation {:?} took {:?}",
@@ -801,8 +2315,83 @@ pub(crate) async fn gen_witness(
         start_time.elapsed()
     );
 
+    witness.pinned_block = pinned_block;
+    if let Some(tree) = &merkle_tree {
+        witness.prove_membership(tree)?;
+    }
+    if let Some(signature) = signature {
+        let digits = signature.strip_prefix("0x").unwrap_or(&signature);
+        let bytes = hex::decode(digits)
+            .map_err(|e| GraphError::InvalidSignature(format!("{}: {}", signature, e)))?;
+        witness.attach_signature(&bytes)?;
+    }
+    if let Some(salt) = salt {
+        let salt = felt_from_str(&salt).ok_or_else(|| {
+            GraphError::InvalidNullifier(format!("{} is not a field element", salt))
+        })?;
+        witness.derive_nullifier(salt)?;
+    }
+    if let Some(prev_digest) = prev_digest {
+        let prev_digest = felt_from_str(&prev_digest).ok_or_else(|| {
+            GraphError::InvalidSession(format!("{} is not a field element", prev_digest))
+        })?;
+        witness.chain_session(prev_digest)?;
+    }
+    if let Some(path) = session_digest_output {
+        let digest = witness
+            .session
+            .as_ref()
+            .and_then(|s| s.next_digest)
+            .ok_or_else(|| {
+                GraphError::InvalidSession(
+                    "the witness has no session digest, chain it to the previous step".into(),
+                )
+            })?;
+        std::fs::write(path, serde_json::to_string(&digest)?)?;
+    }
+    if let Some(key) = recipient_key {
+        let encryption = settings.output_encryption.as_ref().ok_or_else(|| {
+            GraphError::InvalidEncryption("the settings don't encrypt any outputs".into())
+        })?;
+        let public_key = curve_point_from_strs(&key).map_err(GraphError::InvalidEncryption)?;
+        let randomness = Fr::random(rand::rngs::OsRng);
+        witness.encrypt_outputs(encryption, public_key, randomness)?;
+    }
+    if let Some(key) = blinding_key {
+        if !settings.polycommit_blinding {
+            return Err(GraphError::InvalidBlinding(
+                "the settings don't blind the commitments".into(),
+            )
+            .into());
+        }
+        let public_key = curve_point_from_strs(&key).map_err(GraphError::InvalidBlinding)?;
+        witness.encrypt_polycommit_blinders(&public_key, &mut rand::rngs::OsRng)?;
+    }
+
+    if let Some(output_path) = dequantized_output {
+        witness.save_dequantized_outputs(
+            output_path,
+            &settings.model_output_scales,
+            &circuit.model().graph.output_shapes()?,
+        )?;
+    }
+
+    if let Some(output_path) = field_output {
+        witness
+            .field_outputs(&settings.model_output_scales)
+            .save(output_path)?;
+    }
+
+    // the outputs requested in their own files are written in full, only the witness is redacted
+    if redact {
+        witness = match &redact_recipient {
+            Some(recipient) => witness.redact_to(&settings, recipient)?,
+            None => witness.redact(&settings)?,
+        };
+    }
+
     if let Some(output_path) = output {
-        witness.save(output_path)?;
+        witness.save_formatted(output_path, &format)?;
     }
 
     // print the witness in debug
@@ -811,111 +2400,540 @@ pub(crate) async fn gen_witness(
     Ok(witness)
 }
 
-/// Generate a circuit settings file
-pub(crate) fn gen_circuit_settings(
-    model_path: PathBuf,
-    params_output: PathBuf,
-    run_args: RunArgs,
-) -> Result<String, EZKLError> {
-    let circuit = GraphCircuit::from_run_args(&run_args, &model_path)?;
-    let params = circuit.settings();
-    params.save(&params_output)?;
-    Ok(String::new())
+/// Parse the `x,y` hex or decimal field elements of a Grumpkin point
+fn curve_point_from_strs(coordinates: &[String]) -> Result<CurvePoint, String> {
+    let coordinates = coordinates
+        .iter()
+        .map(|c| felt_from_str(c).ok_or_else(|| format!("{} is not a field element", c)))
+        .collect::<Result<Vec<_>, _>>()?;
+    let [x, y] = coordinates[..] else {
+        return Err(format!(
+            "expected the two coordinates of a public key, got {}",
+            coordinates.len()
+        ));
+    };
+    Ok(CurvePoint { x, y })
 }
 
-// not for wasm targets
-pub(crate) fn init_spinner() -> ProgressBar {
-    let pb = indicatif::ProgressBar::new_spinner();
-    pb.set_draw_target(indicatif::ProgressDrawTarget::stdout());
-    pb.enable_steady_tick(Duration::from_millis(200));
-    pb.set_style(
-        ProgressStyle::with_template("[{elapsed_precise}] {spinner:.blue} {msg}")
-            .unwrap()
-            .tick_strings(&[
-                "------ - ✨ ",
-                "------ - ⏳ ",
-                "------ - 🌎 ",
-                "------ - 🔎 ",
-                "------ - 🥹 ",
-                "------ - 🫠 ",
-                "------ - 👾 ",
-            ]),
-    );
-    pb
+/// Decrypt the blinders of the PolyCommit commitments of a witness with the hex or decimal secret
+/// key they were encrypted to, if any
+fn decrypt_blinders(
+    witness: &mut GraphWitness,
+    blinding_secret_key: Option<String>,
+) -> Result<(), GraphError> {
+    if let Some(key) = blinding_secret_key {
+        let secret_key = felt_from_str(&key).ok_or_else(|| {
+            GraphError::InvalidBlinding(format!("{} is not a field element", key))
+        })?;
+        witness.decrypt_polycommit_blinders(&secret_key)?;
+    }
+    Ok(())
 }
 
-// not for wasm targets
-pub(crate) fn init_bar(len: u64) -> ProgressBar {
-    let pb = ProgressBar::new(len);
-    pb.set_draw_target(indicatif::ProgressDrawTarget::stdout());
-    pb.enable_steady_tick(Duration::from_millis(200));
-    let sty = ProgressStyle::with_template(
-        "[{elapsed_precise}] {bar:40.cyan/blue} {pos:>7}/{len:7} {msg}",
-    )
-    .unwrap()
-    .progress_chars("##-");
-    pb.set_style(sty);
-    pb
+/// The srs the polycommit visibilities of a circuit are committed to with during witness generation
+enum WitnessSrs {
+    Kzg(ParamsKZG<Bn256>),
+    Ipa(ParamsIPA<G1Affine>),
+    None,
 }
 
-use colored_json::ToColoredJson;
+impl WitnessSrs {
+    /// Load the srs of the settings commitment, if any of the settings have polycommit visibility
+    fn load(settings: &GraphSettings, srs_path: Option<PathBuf>) -> Result<Self, EZKLError> {
+        let commitment: Commitments = settings.run_args.commitment.into();
+        if !settings.module_requires_polycommit() {
+            return Ok(WitnessSrs::None);
+        }
+        if !find_srs_path(settings.run_args.logrows, srs_path.clone(), commitment).exists() {
+            warn!("SRS for poly commit does not exist (will be ignored)");
+            return Ok(WitnessSrs::None);
+        }
+        Ok(match commitment {
+            Commitments::KZG => WitnessSrs::Kzg(load_params_prover::<KZGCommitmentScheme<Bn256>>(
+                srs_path,
+                settings.run_args.logrows,
+                commitment,
+            )?),
+            Commitments::IPA => {
+                WitnessSrs::Ipa(load_params_prover::<IPACommitmentScheme<G1Affine>>(
+                    srs_path,
+                    settings.run_args.logrows,
+                    commitment,
+                )?)
+            }
+        })
+    }
 
-#[derive(Debug, Clone, Tabled)]
-/// Accuracy tearsheet
-pub struct AccuracyResults {
-    mean_error: f32,
-    median_error: f32,
-    max_error: f32,
-    min_error: f32,
-    mean_abs_error: f32,
-    median_abs_error: f32,
-    max_abs_error: f32,
-    min_abs_error: f32,
-    mean_squared_error: f32,
-    mean_percent_error: f32,
-    mean_abs_percent_error: f32,
+    /// Run the forward pass of the circuit on its loaded inputs
+    fn forward(
+        &self,
+        circuit: &GraphCircuit,
+        input: &mut [Tensor<Fr>],
+        vk: Option<&VerifyingKey<G1Affine>>,
+    ) -> Result<GraphWitness, EZKLError> {
+        let settings = circuit.settings();
+        let region_settings =
+            RegionSettings::all_true(settings.run_args.decomp_base, settings.run_args.decomp_legs);
+        let witness = match self {
+            WitnessSrs::Kzg(srs) => {
+                circuit.forward::<KZGCommitmentScheme<_>>(input, vk, Some(srs), region_settings)?
+            }
+            WitnessSrs::Ipa(srs) => {
+                circuit.forward::<IPACommitmentScheme<_>>(input, vk, Some(srs), region_settings)?
+            }
+            WitnessSrs::None => {
+                circuit.forward::<KZGCommitmentScheme<Bn256>>(input, vk, None, region_settings)?
+            }
+        };
+        Ok(witness)
+    }
 }
 
-impl AccuracyResults {
-    /// Create a new accuracy results struct
-    pub fn new(
-        mut original_preds: Vec<crate::tensor::Tensor<f32>>,
-        mut calibrated_preds: Vec<crate::tensor::Tensor<f32>>,
-    ) -> Result<Self, EZKLError> {
-        let mut errors = vec![];
-        let mut abs_errors = vec![];
-        let mut squared_errors = vec![];
-        let mut percentage_errors = vec![];
-        let mut abs_percentage_errors = vec![];
+/// The outcome of witness generation for one input of a batch
+#[derive(Debug, Clone, Serialize, serde::Deserialize)]
+pub struct WitnessBatchEntry {
+    /// The input file
+    pub input: PathBuf,
+    /// The witness file, if witness generation succeeded
+    pub witness: Option<PathBuf>,
+    /// The rescaled outputs of the forward pass, if witness generation succeeded
+    pub outputs: Option<Vec<Vec<String>>>,
+    /// Why witness generation failed, if it did
+    pub error: Option<String>,
+}
 
-        for (original, calibrated) in original_preds.iter_mut().zip(calibrated_preds.iter_mut()) {
-            original.flatten();
-            calibrated.flatten();
-            let error = (original.clone() - calibrated.clone())?;
-            let abs_error = error.map(|x| x.abs());
-            let squared_error = error.map(|x| x.powi(2));
-            let percentage_error = error.enum_map(|i, x| {
-                // if everything is 0 then we can't divide by 0 so we just return 0
-                let res = if original[i] == 0.0 && x == 0.0 {
-                    0.0
-                } else {
-                    x / original[i]
-                };
-                Ok::<f32, TensorError>(res)
-            })?;
-            let abs_percentage_error = percentage_error.map(|x| x.abs());
+/// The summary of a batched witness generation, saved next to the witnesses
+#[derive(Debug, Clone, Serialize, serde::Deserialize)]
+pub struct WitnessBatchSummary {
+    /// The number of inputs a witness was generated for
+    pub succeeded: usize,
+    /// The number of inputs witness generation failed for
+    pub failed: usize,
+    /// The outcome for each input, in file name order
+    pub entries: Vec<WitnessBatchEntry>,
+}
 
-            errors.extend(error);
-            abs_errors.extend(abs_error);
-            squared_errors.extend(squared_error);
-            percentage_errors.extend(percentage_error);
-            abs_percentage_errors.extend(abs_percentage_error);
-        }
+#[cfg(feature = "python-bindings")]
+impl pyo3::ToPyObject for WitnessBatchEntry {
+    fn to_object(&self, py: pyo3::Python) -> pyo3::PyObject {
+        let dict = pyo3::types::PyDict::new(py);
+        dict.set_item("input", &self.input).unwrap();
+        dict.set_item("witness", &self.witness).unwrap();
+        dict.set_item("outputs", &self.outputs).unwrap();
+        dict.set_item("error", &self.error).unwrap();
+        dict.into()
+    }
+}
 
-        let mean_percent_error =
-            percentage_errors.iter().sum::<f32>() / percentage_errors.len() as f32;
-        let mean_abs_percent_error =
-            abs_percentage_errors.iter().sum::<f32>() / abs_percentage_errors.len() as f32;
+#[cfg(feature = "python-bindings")]
+impl pyo3::ToPyObject for WitnessBatchSummary {
+    fn to_object(&self, py: pyo3::Python) -> pyo3::PyObject {
+        let dict = pyo3::types::PyDict::new(py);
+        dict.set_item("succeeded", self.succeeded).unwrap();
+        dict.set_item("failed", self.failed).unwrap();
+        let entries: Vec<pyo3::PyObject> = self.entries.iter().map(|e| e.to_object(py)).collect();
+        dict.set_item("entries", entries).unwrap();
+        dict.into()
+    }
+}
+
+/// The file name of the summary saved by batched witness generation
+pub const WITNESS_BATCH_SUMMARY: &str = "summary.json";
+
+/// Generate a witness for each input file in a directory, loading the compiled circuit, vk and srs once
+pub(crate) async fn gen_witness_batch(
+    compiled_circuit_path: PathBuf,
+    input_dir: PathBuf,
+    output_dir: PathBuf,
+    vk_path: Option<PathBuf>,
+    srs_path: Option<PathBuf>,
+    threads: Option<usize>,
+    csv_mapping: Option<PathBuf>,
+    merkle_tree: Option<PathBuf>,
+) -> Result<WitnessBatchSummary, EZKLError> {
+    let mut circuit = GraphCircuit::load(compiled_circuit_path)?;
+    let settings = circuit.settings().clone();
+    let merkle_tree = load_merkle_tree(&settings, merkle_tree)?;
+
+    let vk = if let Some(vk) = vk_path {
+        Some(load_vk::<KZGCommitmentScheme<Bn256>, GraphCircuit>(
+            vk,
+            settings.clone(),
+        )?)
+    } else {
+        None
+    };
+
+    let srs = WitnessSrs::load(&settings, srs_path)?;
+
+    let mut inputs = std::fs::read_dir(&input_dir)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    inputs.retain(|p| p.is_file());
+    inputs.sort();
+    std::fs::create_dir_all(&output_dir)?;
+
+    // reading the inputs may hit an rpc so it stays on this task, only the forward passes are spread over threads
+    let mut loaded = Vec::with_capacity(inputs.len());
+    for input in &inputs {
+        let result = async {
+            let data = load_graph_data(
+                input.clone(),
+                circuit.model().graph.input_names(),
+                csv_mapping.clone(),
+                settings.image_preprocessing.as_ref(),
+            )?;
+            let pinned_block = crate::eth::pinned_block(&data).await?;
+            let input = circuit.load_graph_input(&data).await?;
+            Ok::<_, EZKLError>((input, pinned_block))
+        }
+        .await;
+        loaded.push(result.map_err(|e| e.to_string()));
+    }
+
+    let threads = threads
+        .or_else(|| std::thread::available_parallelism().ok().map(|n| n.get()))
+        .unwrap_or(1)
+        .clamp(1, inputs.len().max(1));
+    info!(
+        "generating {} witnesses on {} threads",
+        inputs.len(),
+        threads
+    );
+
+    let start_time = Instant::now();
+    let jobs = std::sync::Mutex::new(inputs.into_iter().zip(loaded).enumerate());
+    let mut entries = std::thread::scope(|scope| {
+        let workers = (0..threads)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut entries = vec![];
+                    loop {
+                        let Some((i, (input, loaded))) = jobs.lock().unwrap().next() else {
+                            break;
+                        };
+                        let entry = witness_batch_entry(
+                            &circuit,
+                            &srs,
+                            vk.as_ref(),
+                            merkle_tree.as_ref(),
+                            &output_dir,
+                            input,
+                            loaded,
+                        );
+                        entries.push((i, entry));
+                    }
+                    entries
+                })
+            })
+            .collect::<Vec<_>>();
+        workers
+            .into_iter()
+            .flat_map(|w| w.join().unwrap())
+            .collect::<Vec<_>>()
+    });
+    entries.sort_by_key(|(i, _)| *i);
+    let entries: Vec<_> = entries.into_iter().map(|(_, e)| e).collect();
+    trace!("batched witness generation took {:?}", start_time.elapsed());
+
+    let failed = entries.iter().filter(|e| e.error.is_some()).count();
+    let summary = WitnessBatchSummary {
+        succeeded: entries.len() - failed,
+        failed,
+        entries,
+    };
+
+    serde_json::to_writer(
+        BufWriter::with_capacity(
+            *EZKL_BUF_CAPACITY,
+            File::create(output_dir.join(WITNESS_BATCH_SUMMARY))?,
+        ),
+        &summary,
+    )?;
+
+    Ok(summary)
+}
+
+/// Run the forward pass for one input of a batch, recording rather than returning any failure
+fn witness_batch_entry(
+    circuit: &GraphCircuit,
+    srs: &WitnessSrs,
+    vk: Option<&VerifyingKey<G1Affine>>,
+    merkle_tree: Option<&MerkleTree>,
+    output_dir: &Path,
+    input: PathBuf,
+    loaded: Result<(Vec<Tensor<Fr>>, Option<PinnedBlock>), String>,
+) -> WitnessBatchEntry {
+    let witness_path = output_dir
+        .join(input.file_stem().unwrap_or_default())
+        .with_extension("json");
+
+    // a panicking forward pass only fails its own input
+    let result = loaded
+        .map_err(EZKLError::from)
+        .and_then(|(mut tensors, pinned_block)| {
+            std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                srs.forward(circuit, &mut tensors, vk)
+            }))
+            .map_err(|_| EZKLError::from("witness generation panicked"))?
+            .and_then(|mut witness| {
+                witness.pinned_block = pinned_block;
+                if let Some(tree) = merkle_tree {
+                    witness.prove_membership(tree)?;
+                }
+                witness.save(witness_path.clone())?;
+                Ok(witness)
+            })
+        });
+
+    match result {
+        Ok(witness) => WitnessBatchEntry {
+            input,
+            witness: Some(witness_path),
+            outputs: witness.pretty_elements.map(|p| p.rescaled_outputs),
+            error: None,
+        },
+        Err(e) => {
+            warn!(
+                "failed to generate the witness of {}: {}",
+                input.display(),
+                e
+            );
+            WitnessBatchEntry {
+                input,
+                witness: None,
+                outputs: None,
+                error: Some(e.to_string()),
+            }
+        }
+    }
+}
+
+/// Load the Merkle tree of the dataset the inputs are proven to belong to, if the input visibility is merkle
+fn load_merkle_tree(
+    settings: &GraphSettings,
+    path: Option<PathBuf>,
+) -> Result<Option<MerkleTree>, EZKLError> {
+    let Some(depth) = settings.run_args.input_visibility.merkle_depth() else {
+        if path.is_some() {
+            warn!("the input visibility isn't merkle, ignoring the merkle tree");
+        }
+        return Ok(None);
+    };
+    let path = path.ok_or_else(|| {
+        GraphError::InvalidMerkle("a witness needs the merkle tree of the dataset".into())
+    })?;
+    let tree: MerkleTree = serde_json::from_reader(std::io::BufReader::with_capacity(
+        *EZKL_BUF_CAPACITY,
+        File::open(path)?,
+    ))?;
+    if tree.depth != depth {
+        return Err(GraphError::InvalidMerkle(format!(
+            "the tree has depth {} but the settings {}",
+            tree.depth, depth
+        ))
+        .into());
+    }
+    Ok(Some(tree))
+}
+
+/// Build the Merkle tree of a dataset, in the .json format of the `input_data` of the rows
+pub(crate) fn merkle_tree(
+    compiled_circuit: PathBuf,
+    dataset: PathBuf,
+    output: PathBuf,
+) -> Result<String, EZKLError> {
+    let mut circuit = GraphCircuit::load(compiled_circuit)?;
+    let rows: Vec<FileSource> = serde_json::from_reader(std::io::BufReader::with_capacity(
+        *EZKL_BUF_CAPACITY,
+        File::open(dataset)?,
+    ))?;
+    let tree = circuit.merkle_tree(&rows)?;
+    info!("merkle root of {} rows: {:?}", tree.leaves.len(), tree.root);
+
+    serde_json::to_writer(
+        BufWriter::with_capacity(*EZKL_BUF_CAPACITY, File::create(output)?),
+        &tree,
+    )?;
+    Ok(serde_json::to_string(&tree.root)?)
+}
+
+/// Generate a circuit settings file
+pub(crate) fn gen_circuit_settings(
+    model_path: PathBuf,
+    params_output: PathBuf,
+    run_args: RunArgs,
+    image_preprocessing: Option<PathBuf>,
+    normalize: Option<(PathBuf, PathBuf)>,
+    input_signature: Option<PathBuf>,
+    nullifier: bool,
+    encrypted_outputs: Option<Vec<usize>>,
+    polycommit_blinding: bool,
+    session: bool,
+) -> Result<String, EZKLError> {
+    let mut circuit = GraphCircuit::from_run_args(&run_args, &model_path)?;
+    if let Some(spec) = input_signature {
+        let signature: InputSignature = serde_json::from_reader(
+            std::io::BufReader::with_capacity(*EZKL_BUF_CAPACITY, File::open(spec)?),
+        )?;
+        circuit.set_input_signature(signature)?;
+    }
+    if nullifier {
+        circuit.set_nullifier()?;
+    }
+    if let Some(outputs) = encrypted_outputs {
+        circuit.set_encrypted_outputs(outputs)?;
+    }
+    if polycommit_blinding {
+        circuit.set_polycommit_blinding()?;
+    }
+    if session {
+        circuit.set_session()?;
+    }
+    let mut params = circuit.settings().clone();
+    if let Some(spec) = image_preprocessing {
+        params.image_preprocessing = Some(ImagePreprocessing::load(&spec)?);
+    }
+    if let Some((mean, std)) = normalize {
+        params.input_normalization = Some(InputNormalization::from_mean_std_files(&mean, &std)?);
+    }
+    params.save(&params_output)?;
+    Ok(String::new())
+}
+
+pub(crate) fn estimate(
+    model: PathBuf,
+    settings_path: PathBuf,
+    output: Option<PathBuf>,
+) -> Result<String, EZKLError> {
+    use crate::pfsys::estimate::{load_or_calibrate, CircuitShape, ProofEstimate};
+
+    let settings = if settings_path.exists() {
+        GraphSettings::load(&settings_path)?
+    } else {
+        info!(
+            "no settings found at {:?}, generating them from the model with default run args",
+            settings_path
+        );
+        GraphCircuit::from_run_args(&RunArgs::default(), &model)?
+            .settings()
+            .clone()
+    };
+
+    let logrows = settings.run_args.logrows;
+    let commitment: Commitments = settings.run_args.commitment.into();
+    let num_instances = settings.total_instances().iter().sum();
+    let sha256_rows = settings.module_sizes.sha256_rows();
+
+    let mut cs = plonk::ConstraintSystem::<Fr>::default();
+    GraphCircuit::configure_with_params(&mut cs, settings);
+    let shape = CircuitShape::from_cs(&cs, logrows);
+
+    let secs_per_unit = load_or_calibrate(Path::new(&*EZKL_REPO_PATH))?;
+    let mut estimate = ProofEstimate::new(shape, commitment, num_instances, secs_per_unit);
+    estimate.sha256_rows = sha256_rows;
+
+    let json = serde_json::to_string_pretty(&estimate)?;
+    info!("estimate: {}", json);
+    if let Some(output) = output {
+        std::fs::write(output, &json)?;
+    }
+    Ok(json)
+}
+
+// not for wasm targets
+pub(crate) fn init_spinner() -> ProgressBar {
+    let pb = indicatif::ProgressBar::new_spinner();
+    pb.set_draw_target(indicatif::ProgressDrawTarget::stdout());
+    pb.enable_steady_tick(Duration::from_millis(200));
+    pb.set_style(
+        ProgressStyle::with_template("[{elapsed_precise}] {spinner:.blue} {msg}")
+            .unwrap()
+            .tick_strings(&[
+                "------ - ✨ ",
+                "------ - ⏳ ",
+                "------ - 🌎 ",
+                "------ - 🔎 ",
+                "------ - 🥹 ",
+                "------ - 🫠 ",
+                "------ - 👾 ",
+            ]),
+    );
+    pb
+}
+
+// not for wasm targets
+pub(crate) fn init_bar(len: u64) -> ProgressBar {
+    let pb = ProgressBar::new(len);
+    pb.set_draw_target(indicatif::ProgressDrawTarget::stdout());
+    pb.enable_steady_tick(Duration::from_millis(200));
+    let sty = ProgressStyle::with_template(
+        "[{elapsed_precise}] {bar:40.cyan/blue} {pos:>7}/{len:7} {msg}",
+    )
+    .unwrap()
+    .progress_chars("##-");
+    pb.set_style(sty);
+    pb
+}
+
+use colored_json::ToColoredJson;
+
+#[derive(Debug, Clone, Tabled)]
+/// Accuracy tearsheet
+pub struct AccuracyResults {
+    mean_error: f32,
+    median_error: f32,
+    max_error: f32,
+    min_error: f32,
+    mean_abs_error: f32,
+    median_abs_error: f32,
+    max_abs_error: f32,
+    min_abs_error: f32,
+    mean_squared_error: f32,
+    mean_percent_error: f32,
+    mean_abs_percent_error: f32,
+}
+
+impl AccuracyResults {
+    /// Create a new accuracy results struct
+    pub fn new(
+        mut original_preds: Vec<crate::tensor::Tensor<f32>>,
+        mut calibrated_preds: Vec<crate::tensor::Tensor<f32>>,
+    ) -> Result<Self, EZKLError> {
+        let mut errors = vec![];
+        let mut abs_errors = vec![];
+        let mut squared_errors = vec![];
+        let mut percentage_errors = vec![];
+        let mut abs_percentage_errors = vec![];
+
+        for (original, calibrated) in original_preds.iter_mut().zip(calibrated_preds.iter_mut()) {
+            original.flatten();
+            calibrated.flatten();
+            let error = (original.clone() - calibrated.clone())?;
+            let abs_error = error.map(|x| x.abs());
+            let squared_error = error.map(|x| x.powi(2));
+            let percentage_error = error.enum_map(|i, x| {
+                // if everything is 0 then we can't divide by 0 so we just return 0
+                let res = if original[i] == 0.0 && x == 0.0 {
+                    0.0
+                } else {
+                    x / original[i]
+                };
+                Ok::<f32, TensorError>(res)
+            })?;
+            let abs_percentage_error = percentage_error.map(|x| x.abs());
+
+            errors.extend(error);
+            abs_errors.extend(abs_error);
+            squared_errors.extend(squared_error);
+            percentage_errors.extend(percentage_error);
+            abs_percentage_errors.extend(abs_percentage_error);
+        }
+
+        let mean_percent_error =
+            percentage_errors.iter().sum::<f32>() / percentage_errors.len() as f32;
+        let mean_abs_percent_error =
+            abs_percentage_errors.iter().sum::<f32>() / abs_percentage_errors.len() as f32;
         let mean_error = errors.iter().sum::<f32>() / errors.len() as f32;
         let median_error = errors[errors.len() / 2];
         let max_error = *errors
@@ -961,7 +2979,7 @@ impl AccuracyResults {
 #[allow(clippy::too_many_arguments)]
 pub(crate) async fn calibrate(
     model_path: PathBuf,
-    data: PathBuf,
+    data: DataInput,
     settings_path: PathBuf,
     target: CalibrationTarget,
     lookup_safety_margin: f64,
@@ -969,6 +2987,7 @@ pub(crate) async fn calibrate(
     scale_rebase_multiplier: Vec<u32>,
     only_range_check_rebase: bool,
     max_logrows: Option<u32>,
+    csv_mapping: Option<PathBuf>,
 ) -> Result<GraphSettings, EZKLError> {
     use log::error;
     use std::collections::HashMap;
@@ -976,15 +2995,20 @@ pub(crate) async fn calibrate(
 
     use crate::fieldutils::IntegerRep;
 
-    let data = GraphData::from_path(data)?;
     // load the pre-generated settings
     let settings = GraphSettings::load(&settings_path)?;
     // now retrieve the run args
     // we load the model to get the input and output shapes
 
     let model = Model::from_run_args(&settings.run_args, &model_path)?;
-
     let input_shapes = model.graph.input_shapes()?;
+    let data = data.load(
+        model.graph.input_names(),
+        &input_shapes,
+        true,
+        csv_mapping,
+        settings.image_preprocessing.as_ref(),
+    )?;
 
     let chunks = data.split_into_batches(input_shapes).await?;
     info!("num calibration batches: {}", chunks.len());
@@ -1061,6 +3085,15 @@ pub(crate) async fn calibrate(
     let mut num_passed = 0;
 
     for (((input_scale, param_scale), scale_rebase_multiplier), div_rebasing) in range_grid {
+        let span = tracing::info_span!(
+            "calibration_candidate",
+            input_scale,
+            param_scale,
+            scale_rebase_multiplier,
+            div_rebasing,
+            logrows = tracing::field::Empty
+        );
+        let _entered = span.enter();
         pb.set_message(format!(
             "i-scale: {}, p-scale: {}, rebase-(x): {}, div-rebase: {}, fail: {}, pass: {}",
             input_scale.to_string().blue(),
@@ -1085,6 +3118,8 @@ pub(crate) async fn calibrate(
             scale_rebase_multiplier,
             div_rebasing,
             lookup_range: (IntegerRep::MIN, IntegerRep::MAX),
+            // the tables are sized to the inputs this calibration observes
+            tight_lookup_ranges: vec![],
             ..settings.run_args.clone()
         };
 
@@ -1109,6 +3144,24 @@ pub(crate) async fn calibrate(
                 continue;
             }
         };
+        // the inputs are normalized as they will be at witness time
+        circuit.settings_mut().input_normalization = settings.input_normalization.clone();
+        // the signature check reserves its rows as it will at proving time
+        if let Some(signature) = &settings.input_signature {
+            circuit.set_input_signature(signature.clone())?;
+        }
+        if settings.nullifier {
+            circuit.set_nullifier()?;
+        }
+        if let Some(encryption) = &settings.output_encryption {
+            circuit.set_encrypted_outputs(encryption.outputs.clone())?;
+        }
+        if settings.polycommit_blinding {
+            circuit.set_polycommit_blinding()?;
+        }
+        if settings.session {
+            circuit.set_session()?;
+        }
 
         let forward_res = chunks
             .iter()
@@ -1174,6 +3227,12 @@ pub(crate) async fn calibrate(
 
         let max_range_size = result.iter().map(|x| x.max_range_size).max().unwrap_or(0);
 
+        circuit.settings_mut().run_args.tight_lookup_ranges =
+            GraphCircuit::calc_tight_lookup_ranges(
+                result.iter().flat_map(|x| &x.tight_lookup_ranges),
+                settings.run_args.tight_lookup_margin,
+            );
+
         let res = circuit.calc_min_logrows(
             (min_lookup_range, max_lookup_range),
             max_range_size,
@@ -1183,6 +3242,7 @@ pub(crate) async fn calibrate(
 
         if res.is_ok() {
             let new_settings = circuit.settings().clone();
+            span.record("logrows", new_settings.run_args.logrows);
 
             let found_run_args = RunArgs {
                 input_scale: new_settings.run_args.input_scale,
@@ -1191,18 +3251,23 @@ pub(crate) async fn calibrate(
                 lookup_range: new_settings.run_args.lookup_range,
                 logrows: new_settings.run_args.logrows,
                 scale_rebase_multiplier: new_settings.run_args.scale_rebase_multiplier,
+                tight_lookup_ranges: new_settings.run_args.tight_lookup_ranges,
                 ..settings.run_args.clone()
             };
 
             let found_settings = GraphSettings {
                 run_args: found_run_args,
                 required_lookups: new_settings.required_lookups,
+                required_fused_add_lookups: new_settings.required_fused_add_lookups,
                 required_range_checks: new_settings.required_range_checks,
                 model_output_scales: new_settings.model_output_scales,
                 model_input_scales: new_settings.model_input_scales,
+                model_input_names: new_settings.model_input_names,
+                model_output_names: new_settings.model_output_names,
                 num_rows: new_settings.num_rows,
                 total_assignments: new_settings.total_assignments,
                 total_const_size: new_settings.total_const_size,
+                deduped_constant_assignments: new_settings.deduped_constant_assignments,
                 total_dynamic_col_size: new_settings.total_dynamic_col_size,
                 ..settings.clone()
             };
@@ -1350,11 +3415,13 @@ pub(crate) async fn calibrate(
 pub(crate) fn mock(
     compiled_circuit_path: PathBuf,
     data_path: PathBuf,
+    blinding_secret_key: Option<String>,
 ) -> Result<String, EZKLError> {
     // mock should catch any issues by default so we set it to safe
     let mut circuit = GraphCircuit::load(compiled_circuit_path)?;
 
-    let data = GraphWitness::from_path(data_path)?;
+    let mut data = GraphWitness::from_path(data_path)?;
+    decrypt_blinders(&mut data, blinding_secret_key)?;
 
     circuit.load_graph_witness(&data)?;
 
@@ -1367,12 +3434,58 @@ pub(crate) fn mock(
         &circuit,
         vec![public_inputs],
     )
-    .map_err(|e| ExecutionError::MockProverError(e.to_string()))?;
+    .map_err(|e| ExecutionError::MockProverError(synthesis_error(e).to_string()))?;
 
     prover.verify().map_err(ExecutionError::VerifyError)?;
     Ok(String::new())
 }
 
+/// Converts the witness at `witness` to `output`, with its tensors above `sidecar_threshold` in a binary sidecar or
+/// in pure json without a threshold
+pub(crate) fn convert_witness(
+    witness: PathBuf,
+    output: PathBuf,
+    sidecar_threshold: Option<usize>,
+) -> Result<String, EZKLError> {
+    let witness = GraphWitness::from_path(witness)?;
+    witness.save_formatted(
+        output,
+        &WitnessFormat {
+            sidecar_threshold,
+            ..Default::default()
+        },
+    )?;
+    Ok(String::new())
+}
+
+pub(crate) fn validate_witness(
+    compiled_circuit_path: PathBuf,
+    data_path: PathBuf,
+    blinding_secret_key: Option<String>,
+) -> Result<String, EZKLError> {
+    let mut circuit = GraphCircuit::load(compiled_circuit_path)?;
+
+    let mut data = GraphWitness::from_path(data_path)?;
+    decrypt_blinders(&mut data, blinding_secret_key)?;
+
+    circuit.load_graph_witness(&data)?;
+    let public_inputs = circuit.prepare_public_inputs(&data)?;
+
+    let failures = crate::graph::validate::validate_witness(&mut circuit, public_inputs)?;
+    if !failures.is_empty() {
+        return Err(ExecutionError::InvalidWitness(failures).into());
+    }
+    info!("the witness satisfies every constraint of the circuit");
+    Ok(String::new())
+}
+
+#[allow(clippy::too_many_arguments)]
+/// The path of the map of the instances written next to the solidity verifier at `sol_code_path`, see
+/// [GraphSettings::instance_map]
+pub fn instance_map_path(sol_code_path: &Path) -> PathBuf {
+    sol_code_path.with_extension("instances.json")
+}
+
 pub(crate) async fn create_evm_verifier(
     vk_path: PathBuf,
     srs_path: Option<PathBuf>,
@@ -1380,6 +3493,9 @@ pub(crate) async fn create_evm_verifier(
     sol_code_path: PathBuf,
     abi_path: PathBuf,
     reusable: bool,
+    batch: bool,
+    foundry_dir: Option<PathBuf>,
+    fixture_proof_path: Option<PathBuf>,
 ) -> Result<String, EZKLError> {
     let settings = GraphSettings::load(&settings_path)?;
     let commitment: Commitments = settings.run_args.commitment.into();
@@ -1391,6 +3507,8 @@ pub(crate) async fn create_evm_verifier(
 
     let num_instance = settings.total_instances();
     let num_instance: usize = num_instance.iter().sum::<usize>();
+    let nullifier_index = settings.nullifier_instance_index();
+    let instance_map = settings.instance_map();
 
     let vk = load_vk::<KZGCommitmentScheme<Bn256>, GraphCircuit>(vk_path, settings)?;
     trace!("params computed");
@@ -1406,8 +3524,40 @@ pub(crate) async fn create_evm_verifier(
     } else {
         (generator.render()?, "Halo2Verifier")
     };
+    let verifier_solidity = if batch {
+        add_verify_batch(&verifier_solidity, reusable)?
+    } else {
+        verifier_solidity
+    };
+    let verifier_solidity = match nullifier_index {
+        Some(index) => add_nullifier_index(&verifier_solidity, index)?,
+        None => verifier_solidity,
+    };
 
     File::create(sol_code_path.clone())?.write_all(verifier_solidity.as_bytes())?;
+    // what each instance the verifier is called with holds, for its callers
+    serde_json::to_writer(
+        File::create(instance_map_path(&sol_code_path))?,
+        &instance_map,
+    )?;
+
+    if let Some(foundry_dir) = foundry_dir {
+        if reusable {
+            return Err(
+                "a foundry harness can only be generated for a non reusable verifier".into(),
+            );
+        }
+        let fixture_proof_path =
+            fixture_proof_path.ok_or("a proof to use as the foundry fixture must be provided")?;
+        let proof = Snark::load::<KZGCommitmentScheme<Bn256>>(&fixture_proof_path)?;
+        proof.check_transcript(Some(TranscriptType::EVM))?;
+        create_foundry_project(
+            &foundry_dir,
+            &verifier_solidity,
+            &export_calldata_fixture(&proof, None),
+            DEFAULT_OPTIMIZER_RUNS.parse().unwrap(),
+        )?;
+    }
 
     // fetch abi of the contract
     let (abi, _, _) = get_contract_artifacts(sol_code_path, name, 0).await?;
@@ -1463,6 +3613,7 @@ pub(crate) async fn create_evm_data_attestation(
     abi_path: PathBuf,
     input: PathBuf,
     witness: Option<PathBuf>,
+    instance_bindings: Option<PathBuf>,
 ) -> Result<String, EZKLError> {
     #[allow(unused_imports)]
     use crate::graph::{DataSource, VarVisibility};
@@ -1476,10 +3627,40 @@ pub(crate) async fn create_evm_data_attestation(
     // if input is not provided, we just instantiate dummy input data
     let data = GraphData::from_path(input).unwrap_or(GraphData::new(DataSource::File(vec![])));
 
+    // storage proof sources are attested by a separate contract, the slots are passed at deployment
+    let storage_proofs = matches!(data.input_data, DataSource::StorageProof(_))
+        || matches!(data.output_data, Some(DataSource::StorageProof(_)));
+    if storage_proofs {
+        if matches!(data.input_data, DataSource::StorageProof(_)) && visibility.input.is_private() {
+            return Err("private input data on chain is not supported on chain".into());
+        }
+        if matches!(data.output_data, Some(DataSource::StorageProof(_)))
+            && visibility.output.is_private()
+        {
+            return Err("private output data on chain is not supported on chain".into());
+        }
+        if matches!(data.output_data, Some(DataSource::StorageProof(_)))
+            && !visibility.outputs.is_empty()
+        {
+            return Err("on chain output data requires a single output visibility".into());
+        }
+    }
+
+    let instance_bindings = match instance_bindings {
+        Some(path) => crate::eth::load_instance_bindings(&path)?,
+        None => vec![],
+    };
+    if storage_proofs && !instance_bindings.is_empty() {
+        return Err("instance bindings are not supported with storage proof sources".into());
+    }
+
     let output_data = if let Some(DataSource::OnChain(source)) = data.output_data {
         if visibility.output.is_private() {
             return Err("private output data on chain is not supported on chain".into());
         }
+        if !visibility.outputs.is_empty() {
+            return Err("on chain output data requires a single output visibility".into());
+        }
         let mut on_chain_output_data = vec![];
         for call in source.calls {
             on_chain_output_data.push(call);
@@ -1506,7 +3687,9 @@ pub(crate) async fn create_evm_data_attestation(
     // if so, then we need to load the witness
 
     let commitment_bytes = if settings.run_args.input_visibility == Visibility::KZGCommit
-        || settings.run_args.output_visibility == Visibility::KZGCommit
+        || settings
+            .run_args
+            .any_output_visibility(|v| *v == Visibility::KZGCommit)
         || settings.run_args.param_visibility == Visibility::KZGCommit
     {
         let witness = GraphWitness::from_path(witness.unwrap_or(DEFAULT_WITNESS.into()))?;
@@ -1522,24 +3705,94 @@ pub(crate) async fn create_evm_data_attestation(
         None
     };
 
-    let output = fix_da_sol(input_data, output_data, commitment_bytes)?;
+    if input_data.is_none()
+        && output_data.is_none()
+        && commitment_bytes.is_none()
+        && instance_bindings.is_empty()
+        && !storage_proofs
+    {
+        return Err("no on-chain data, kzg commitments or instance bindings to attest".into());
+    }
+
+    let (output, contract_name) = if storage_proofs {
+        (fix_storage_da_sol(commitment_bytes), "StorageAttestation")
+    } else {
+        (
+            fix_da_sol(
+                input_data,
+                output_data,
+                commitment_bytes,
+                &instance_bindings,
+            )?,
+            "DataAttestation",
+        )
+    };
     let mut f = File::create(sol_code_path.clone())?;
     let _ = f.write(output.as_bytes());
     // fetch abi of the contract
-    let (abi, _, _) = get_contract_artifacts(sol_code_path, "DataAttestation", 0).await?;
+    let (abi, _, _) = get_contract_artifacts(sol_code_path, contract_name, 0).await?;
     // save abi to file
     serde_json::to_writer(std::fs::File::create(abi_path)?, &abi)?;
 
     Ok(String::new())
 }
 
-pub(crate) async fn deploy_da_evm(
-    data: PathBuf,
+pub(crate) async fn create_evm_packed(
     settings_path: PathBuf,
     sol_code_path: PathBuf,
-    rpc_url: Option<String>,
-    addr_path: PathBuf,
-    runs: usize,
+    abi_path: PathBuf,
+) -> Result<String, EZKLError> {
+    let settings = GraphSettings::load(&settings_path)?;
+    // the outputs packed to limbs are unpacked by a contract of their own
+    let (output, name) = match settings.limb_packed_outputs.is_empty() {
+        true => (crate::eth::fix_packed_sol(&settings)?, "PackedInstances"),
+        false => (
+            crate::eth::fix_limb_packed_sol(&settings)?,
+            "LimbPackedInstances",
+        ),
+    };
+
+    let mut f = File::create(sol_code_path.clone())?;
+    let _ = f.write(output.as_bytes());
+    // fetch abi of the contract
+    let (abi, _, _) = get_contract_artifacts(sol_code_path, name, 0).await?;
+    // save abi to file
+    serde_json::to_writer(std::fs::File::create(abi_path)?, &abi)?;
+
+    Ok(String::new())
+}
+
+pub(crate) async fn deploy_packed_evm(
+    sol_code_path: PathBuf,
+    addr_verifier: H160Flag,
+    rpc_url: Option<String>,
+    addr_path: PathBuf,
+    runs: usize,
+    private_key: Option<String>,
+) -> Result<String, EZKLError> {
+    let contract_address = crate::eth::deploy_packed_verifier_via_solidity(
+        sol_code_path,
+        addr_verifier.into(),
+        rpc_url.as_deref(),
+        runs,
+        private_key.as_deref(),
+    )
+    .await?;
+    info!("Contract deployed at: {}", contract_address);
+
+    let mut f = File::create(addr_path)?;
+    write!(f, "{:#?}", contract_address)?;
+
+    Ok(String::new())
+}
+
+pub(crate) async fn deploy_da_evm(
+    data: PathBuf,
+    settings_path: PathBuf,
+    sol_code_path: PathBuf,
+    rpc_url: Option<String>,
+    addr_path: PathBuf,
+    runs: usize,
     private_key: Option<String>,
 ) -> Result<String, EZKLError> {
     let contract_address = deploy_da_verifier_via_solidity(
@@ -1559,6 +3812,7 @@ pub(crate) async fn deploy_da_evm(
     Ok(String::new())
 }
 
+#[allow(clippy::too_many_arguments)]
 pub(crate) async fn deploy_evm(
     sol_code_path: PathBuf,
     rpc_url: Option<String>,
@@ -1566,7 +3820,13 @@ pub(crate) async fn deploy_evm(
     runs: usize,
     private_key: Option<String>,
     contract: ContractType,
+    registry: Option<H160Flag>,
+    settings_path: PathBuf,
 ) -> Result<String, EZKLError> {
+    // a reusable verifier is shared by many models so it can't be registered under one fingerprint
+    if registry.is_some() && !matches!(contract, ContractType::Verifier { reusable: false }) {
+        return Err("only single model verifiers can be registered".into());
+    }
     let contract_name = match contract {
         ContractType::Verifier { reusable: false } => "Halo2Verifier",
         ContractType::Verifier { reusable: true } => "Halo2VerifierReusable",
@@ -1583,11 +3843,60 @@ pub(crate) async fn deploy_evm(
 
     info!("Contract deployed at: {:#?}", contract_address);
 
+    if let Some(registry) = registry {
+        let settings = GraphSettings::load(&settings_path)?;
+        let fingerprint = crate::eth::register_verifier(
+            registry.into(),
+            contract_address,
+            &settings,
+            rpc_url.as_deref(),
+            private_key.as_deref(),
+        )
+        .await?;
+        info!("Verifier registered under: {:#?}", fingerprint);
+    }
+
     let mut f = File::create(addr_path)?;
     write!(f, "{:#?}", contract_address)?;
     Ok(String::new())
 }
 
+pub(crate) async fn deploy_evm_registry(
+    rpc_url: Option<String>,
+    addr_path: PathBuf,
+    runs: usize,
+    private_key: Option<String>,
+) -> Result<String, EZKLError> {
+    let contract_address =
+        crate::eth::deploy_verifier_registry(rpc_url.as_deref(), runs, private_key.as_deref())
+            .await?;
+
+    info!("Registry deployed at: {:#?}", contract_address);
+
+    let mut f = File::create(addr_path)?;
+    write!(f, "{:#?}", contract_address)?;
+    Ok(String::new())
+}
+
+pub(crate) async fn resolve_evm_verifier(
+    registry: H160Flag,
+    settings_path: PathBuf,
+    rpc_url: Option<String>,
+    addr_path: Option<PathBuf>,
+) -> Result<String, EZKLError> {
+    let settings = GraphSettings::load(&settings_path)?;
+    let verifier =
+        crate::eth::resolve_verifier(registry.into(), &settings, rpc_url.as_deref()).await?;
+
+    info!("Verifier resolved to: {:#?}", verifier);
+
+    if let Some(addr_path) = addr_path {
+        let mut f = File::create(addr_path)?;
+        write!(f, "{:#?}", verifier)?;
+    }
+    Ok(format!("{:#?}", verifier))
+}
+
 /// Encodes the calldata for the EVM verifier (both aggregated and single proof)
 pub(crate) fn encode_evm_calldata(
     proof_path: PathBuf,
@@ -1614,18 +3923,67 @@ pub(crate) fn encode_evm_calldata(
     Ok(encoded)
 }
 
+pub(crate) async fn pin_evm_da(
+    addr_da: H160Flag,
+    data: PathBuf,
+    rpc_url: Option<String>,
+    private_key: Option<String>,
+) -> Result<String, EZKLError> {
+    use crate::eth::pin_da_block;
+
+    let pinned = pin_da_block(
+        addr_da.into(),
+        data,
+        rpc_url.as_deref(),
+        private_key.as_deref(),
+    )
+    .await?;
+    info!(
+        "Pinned data attestation at block {} ({})",
+        pinned.number, pinned.hash
+    );
+
+    Ok(pinned.number.to_string())
+}
+
 pub(crate) async fn verify_evm(
     proof_path: PathBuf,
     addr_verifier: H160Flag,
     rpc_url: Option<String>,
     addr_da: Option<H160Flag>,
     addr_vk: Option<H160Flag>,
+    data_path: Option<PathBuf>,
 ) -> Result<String, EZKLError> {
-    use crate::eth::verify_proof_with_data_attestation;
+    use crate::eth::{
+        check_pinned_block, storage_proof_source, verify_proof_with_data_attestation,
+        verify_proof_with_storage_attestation,
+    };
 
     let proof = Snark::load::<KZGCommitmentScheme<Bn256>>(&proof_path)?;
+    // the solidity verifiers only ever replay a keccak transcript
+    proof.check_transcript(Some(TranscriptType::EVM))?;
 
-    let result = if let Some(addr_da) = addr_da {
+    // a StorageAttestation contract needs the storage proofs of the slots in the data file
+    let storage_source = match &data_path {
+        Some(data_path) => storage_proof_source(&GraphData::from_path(data_path.clone())?)?,
+        None => None,
+    };
+
+    let result = if let (Some(addr_da), Some(source)) = (addr_da, &storage_source) {
+        verify_proof_with_storage_attestation(
+            proof.clone(),
+            addr_verifier.into(),
+            addr_da.into(),
+            addr_vk.map(|s| s.into()),
+            rpc_url.as_deref(),
+            source,
+        )
+        .await?
+    } else if let Some(addr_da) = addr_da {
+        // a proof of pinned data is only attested by a contract snapshotted at the same block
+        if let Some(pinned) = &proof.pinned_block {
+            check_pinned_block(addr_da.into(), pinned, rpc_url.as_deref()).await?;
+        }
         verify_proof_with_data_attestation(
             proof.clone(),
             addr_verifier.into(),
@@ -1653,6 +4011,143 @@ pub(crate) async fn verify_evm(
     Ok(String::new())
 }
 
+pub(crate) async fn verify_evm_batch(
+    proof_paths: Vec<PathBuf>,
+    addr_verifier: H160Flag,
+    rpc_url: Option<String>,
+    addr_vk: Option<H160Flag>,
+) -> Result<String, EZKLError> {
+    use crate::eth::verify_proofs_via_solidity_batch;
+
+    let proofs = proof_paths
+        .iter()
+        .map(|p| {
+            let proof = Snark::load::<KZGCommitmentScheme<Bn256>>(p)?;
+            proof.check_transcript(Some(TranscriptType::EVM))?;
+            Ok(proof)
+        })
+        .collect::<Result<Vec<_>, EZKLError>>()?;
+
+    let gas = verify_proofs_via_solidity_batch(
+        &proofs,
+        addr_verifier.into(),
+        addr_vk.map(|s| s.into()),
+        rpc_url.as_deref(),
+    )
+    .await?;
+
+    // the 21k base transaction cost is paid once for the whole batch
+    let per_proof = (gas - 21_000) / proofs.len() as u128;
+    info!(
+        "Solidity batch verification of {} proofs used {} gas ({} per proof)",
+        proofs.len(),
+        gas,
+        per_proof
+    );
+    info!(
+        "at most {} proofs of this circuit fit within the {} block gas limit",
+        (crate::eth::BLOCK_GAS_LIMIT as u128 - 21_000) / per_proof,
+        crate::eth::BLOCK_GAS_LIMIT
+    );
+
+    Ok(String::new())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn estimate_gas(
+    proof_path: PathBuf,
+    sol_code_path: PathBuf,
+    vka_path: Option<PathBuf>,
+    backend: EvmBackend,
+    rpc_url: Option<String>,
+    optimizer_runs: usize,
+    output: Option<PathBuf>,
+    packed: Option<(PathBuf, PathBuf)>,
+    settings_path: PathBuf,
+) -> Result<String, EZKLError> {
+    use crate::eth::{
+        estimate_packed_verify_gas_via_node, estimate_verify_gas_revm, estimate_verify_gas_via_node,
+    };
+
+    let proof = Snark::load::<KZGCommitmentScheme<Bn256>>(&proof_path)?;
+    proof.check_transcript(Some(TranscriptType::EVM))?;
+
+    let report = match (backend, packed) {
+        (EvmBackend::Anvil, Some((packed_sol_code_path, witness_path))) => {
+            if vka_path.is_some() {
+                return Err("packed instances are verified by a single model verifier".into());
+            }
+            let settings = GraphSettings::load(&settings_path)?;
+            let witness = GraphWitness::from_path(witness_path)?;
+            estimate_packed_verify_gas_via_node(
+                sol_code_path,
+                packed_sol_code_path,
+                &settings,
+                &proof,
+                &witness,
+                rpc_url.as_deref(),
+                optimizer_runs,
+            )
+            .await?
+        }
+        (EvmBackend::Revm, Some(_)) => {
+            return Err("packed instances are only estimated with the anvil backend".into());
+        }
+        (EvmBackend::Revm, None) => {
+            if rpc_url.is_some() {
+                warn!("rpc url is ignored by the revm backend");
+            }
+            let verifier_name = match vka_path {
+                Some(_) => "Halo2VerifierReusable",
+                None => "Halo2Verifier",
+            };
+            let (_, verifier, _) =
+                get_contract_artifacts(sol_code_path, verifier_name, optimizer_runs).await?;
+            let vka = match vka_path {
+                Some(vka_path) => Some(
+                    get_contract_artifacts(vka_path, "Halo2VerifyingArtifact", optimizer_runs)
+                        .await?
+                        .1,
+                ),
+                None => None,
+            };
+            estimate_verify_gas_revm(verifier, vka, &proof)?
+        }
+        (EvmBackend::Anvil, None) => {
+            estimate_verify_gas_via_node(
+                sol_code_path,
+                vka_path,
+                &proof,
+                rpc_url.as_deref(),
+                optimizer_runs,
+            )
+            .await?
+        }
+    };
+
+    info!("verify gas used ({}): {}", backend, report.gas_used);
+    info!(
+        "calldata: {} bytes ({} proof bytes, {} instances)",
+        report.calldata_bytes, report.proof_bytes, report.num_instances
+    );
+    info!(
+        "calldata gas: {} for the proof, {} for the instances",
+        report.proof_calldata_gas, report.instance_calldata_gas
+    );
+    if !report.fits_block_limit {
+        warn!("verification does not fit within the 30M block gas limit");
+    } else if !report.fits_block_target {
+        warn!("verification does not fit within the 15M block gas target");
+    }
+
+    let json = serde_json::to_string_pretty(&report)?;
+    if let Some(output) = output {
+        std::fs::write(output, &json)?;
+    }
+
+    Ok(json)
+}
+
 pub(crate) async fn create_evm_aggregate_verifier(
     vk_path: PathBuf,
     srs_path: Option<PathBuf>,
@@ -1677,7 +4172,7 @@ pub(crate) async fn create_evm_aggregate_verifier(
         .map(|s| s.total_instances().iter().sum::<usize>())
         .sum();
 
-    let num_instance = AggregationCircuit::num_instance(num_instance);
+    let num_instance = AggregationCircuit::num_instance(num_instance, settings.len());
     assert_eq!(num_instance.len(), 1);
     let num_instance = num_instance[0];
 
@@ -1718,13 +4213,129 @@ pub(crate) fn compile_circuit(
     model_path: PathBuf,
     compiled_circuit: PathBuf,
     settings_path: PathBuf,
+    weights: Option<PathBuf>,
+    encrypt_to: Option<String>,
 ) -> Result<String, EZKLError> {
-    let settings = GraphSettings::load(&settings_path)?;
-    let circuit = GraphCircuit::from_settings(&settings, &model_path, CheckMode::UNSAFE)?;
-    circuit.save(compiled_circuit)?;
+    let mut settings = GraphSettings::load(&settings_path)?;
+    if let Some(weights) = weights {
+        // the settings record the hash of the weights, such that the circuits of other weights don't verify against them
+        settings.run_args.weights = Some(WeightsOverride::from_path(weights)?);
+        settings.save(&settings_path)?;
+    }
+    let mut circuit = GraphCircuit::from_settings(&settings, &model_path, CheckMode::UNSAFE)?;
+    circuit.record_op_rows()?;
+    match encrypt_to {
+        Some(recipient) => {
+            let recipient = Recipient::parse(&recipient).map_err(GraphError::from)?;
+            circuit.save_encrypted(compiled_circuit, &recipient)?
+        }
+        None => circuit.save(compiled_circuit)?,
+    }
     Ok(String::new())
 }
 
+/// Compiles the members of a family of batch sizes (see [crate::graph::batch]) to `output_dir`, with the run args of
+/// the settings at each batch size and the logrows the largest member fits in
+pub(crate) fn compile_batch_family(
+    model_path: PathBuf,
+    settings_path: PathBuf,
+    padding: PathBuf,
+    max_batch_size: usize,
+    output_dir: PathBuf,
+) -> Result<BatchFamily, EZKLError> {
+    let settings = GraphSettings::load(&settings_path)?;
+    if settings.input_signature.is_some()
+        || settings.nullifier
+        || settings.output_encryption.is_some()
+        || settings.session
+        || settings.polycommit_blinding
+    {
+        return Err(GraphError::InvalidBatchFamily(
+            "the members of a family don't sign, nullify, encrypt, chain or blind their witnesses"
+                .into(),
+        )
+        .into());
+    }
+    let padding = match GraphData::from_path(padding)?.input_data {
+        DataSource::File(padding) => padding,
+        _ => {
+            return Err(GraphError::InvalidBatchFamily(
+                "the padding sample must hold the values of the inputs".into(),
+            )
+            .into())
+        }
+    };
+    let batch_sizes = batch_sizes(max_batch_size)?;
+    std::fs::create_dir_all(&output_dir)?;
+
+    let member = |batch_size: usize, logrows: u32| -> Result<GraphCircuit, EZKLError> {
+        let mut run_args = settings.run_args.clone();
+        run_args.batch_mask = true;
+        run_args.logrows = logrows;
+        run_args.variables.retain(|(name, _)| name != "batch_size");
+        run_args
+            .variables
+            .push(("batch_size".to_string(), batch_size));
+        run_args.validate().map_err(GraphError::InvalidRunArgs)?;
+
+        let mut circuit = GraphCircuit::from_run_args(&run_args, &model_path)?;
+        // the mask is the last input, of the batch size of the model
+        let input_shapes = circuit.model().graph.input_shapes()?;
+        if input_shapes.last() != Some(&vec![batch_size]) {
+            return Err(GraphError::InvalidBatchFamily(format!(
+                "the model isn't batched by the batch_size variable, its outputs aren't of {} samples",
+                batch_size
+            ))
+            .into());
+        }
+        circuit.settings_mut().image_preprocessing = settings.image_preprocessing.clone();
+        circuit.settings_mut().input_normalization = settings.input_normalization.clone();
+        Ok(circuit)
+    };
+
+    // the smaller members fit the logrows of the largest
+    let max_batch_size = batch_sizes[batch_sizes.len() - 1];
+    let largest = member(max_batch_size, settings.run_args.logrows)?;
+    let input_shapes = largest.model().graph.input_shapes()?;
+    let family = BatchFamily {
+        logrows: std::cmp::max(settings.run_args.logrows, largest.settings().min_logrows()),
+        sample_lens: input_shapes[..input_shapes.len() - 1]
+            .iter()
+            .map(|shape| shape.iter().product::<usize>() / max_batch_size)
+            .collect(),
+        batch_sizes,
+        padding,
+    };
+    if family.num_samples(&family.padding)? != 1 {
+        return Err(
+            GraphError::InvalidBatchFamily("the padding must be a single sample".into()).into(),
+        );
+    }
+
+    for batch_size in &family.batch_sizes {
+        let mut circuit = member(*batch_size, family.logrows)?;
+        if circuit.settings().min_logrows() > family.logrows {
+            return Err(GraphError::InvalidBatchFamily(format!(
+                "the member of batch size {} doesn't fit {} logrows",
+                batch_size, family.logrows
+            ))
+            .into());
+        }
+        circuit
+            .settings()
+            .save(&BatchFamily::settings_path(&output_dir, *batch_size))?;
+        circuit.record_op_rows()?;
+        circuit.save(BatchFamily::compiled_circuit_path(&output_dir, *batch_size))?;
+        info!(
+            "compiled the member of batch size {} of the family at {} logrows",
+            batch_size, family.logrows
+        );
+    }
+
+    family.save(&output_dir)?;
+    Ok(family)
+}
+
 pub(crate) fn setup(
     compiled_circuit: PathBuf,
     srs_path: Option<PathBuf>,
@@ -1732,10 +4343,14 @@ pub(crate) fn setup(
     pk_path: PathBuf,
     witness: Option<PathBuf>,
     disable_selector_compression: bool,
+    identity: Option<PathBuf>,
 ) -> Result<String, EZKLError> {
     // these aren't real values so the sanity checks are mostly meaningless
 
-    let mut circuit = GraphCircuit::load(compiled_circuit)?;
+    let identity = load_identity(identity)?;
+    // the proving key holds the weights of an encrypted circuit in its fixed columns, so it is encrypted too
+    let encrypted = crate::pfsys::encryption::is_encrypted(&compiled_circuit);
+    let mut circuit = GraphCircuit::load_decrypted(compiled_circuit, identity.as_ref())?;
 
     if let Some(witness) = witness {
         let data = GraphWitness::from_path(witness)?;
@@ -1744,6 +4359,7 @@ pub(crate) fn setup(
 
     let logrows = circuit.settings().run_args.logrows;
     let commitment: Commitments = circuit.settings().run_args.commitment.into();
+    warn_memory(&circuit, MemoryPhase::Keygen);
 
     let pk = match commitment {
         Commitments::KZG => {
@@ -1772,7 +4388,58 @@ pub(crate) fn setup(
         }
     };
     save_vk::<G1Affine>(&vk_path, pk.get_vk())?;
-    save_pk::<G1Affine>(&pk_path, &pk)?;
+    match identity {
+        Some(identity) if encrypted => {
+            save_pk_encrypted(&pk_path, &pk, &identity.to_public()).map_err(GraphError::from)?
+        }
+        _ => save_pk::<G1Affine>(&pk_path, &pk)?,
+    }
+    Ok(String::new())
+}
+
+/// Creates the keys of each member of a family of batch sizes in its directory, from the SRS of the logrows they share
+pub(crate) fn setup_batch_family(
+    family_dir: PathBuf,
+    srs_path: Option<PathBuf>,
+    disable_selector_compression: bool,
+) -> Result<String, EZKLError> {
+    let family = BatchFamily::load(&family_dir)?;
+    for batch_size in &family.batch_sizes {
+        setup(
+            BatchFamily::compiled_circuit_path(&family_dir, *batch_size),
+            srs_path.clone(),
+            BatchFamily::vk_path(&family_dir, *batch_size),
+            BatchFamily::pk_path(&family_dir, *batch_size),
+            None,
+            disable_selector_compression,
+            None,
+        )?;
+    }
+    Ok(String::new())
+}
+
+pub(crate) fn convert_pk(
+    compiled_circuit: PathBuf,
+    pk_path: PathBuf,
+    output: PathBuf,
+) -> Result<String, EZKLError> {
+    use crate::pfsys::mmap::convert_pk_to_mmap;
+
+    let circuit = GraphCircuit::load(compiled_circuit)?;
+    let commitment: Commitments = circuit.settings().run_args.commitment.into();
+
+    match commitment {
+        Commitments::KZG => convert_pk_to_mmap::<KZGCommitmentScheme<Bn256>, GraphCircuit>(
+            pk_path,
+            &output,
+            circuit.params(),
+        )?,
+        Commitments::IPA => convert_pk_to_mmap::<IPACommitmentScheme<G1Affine>, GraphCircuit>(
+            pk_path,
+            &output,
+            circuit.params(),
+        )?,
+    };
     Ok(String::new())
 }
 
@@ -1824,6 +4491,92 @@ pub(crate) async fn test_update_account_calls(
     Ok(String::new())
 }
 
+/// The proving key and SRS of a circuit, loaded once to prove any number of its witnesses
+enum ProvingKeys {
+    Kzg(ProvingKey<G1Affine>, ParamsKZG<Bn256>),
+    Ipa(ProvingKey<G1Affine>, ParamsIPA<G1Affine>),
+}
+
+impl ProvingKeys {
+    fn load(
+        circuit: &GraphCircuit,
+        pk_path: PathBuf,
+        srs_path: Option<PathBuf>,
+        identity: Option<&Identity>,
+    ) -> Result<Self, EZKLError> {
+        let logrows = circuit.settings().run_args.logrows;
+        match circuit.settings().run_args.commitment.into() {
+            Commitments::KZG => {
+                let pk = load_pk_decrypted::<KZGCommitmentScheme<Bn256>, GraphCircuit>(
+                    pk_path,
+                    circuit.params(),
+                    identity,
+                )?;
+                let params = load_params_prover::<KZGCommitmentScheme<Bn256>>(
+                    srs_path,
+                    logrows,
+                    Commitments::KZG,
+                )?;
+                Ok(ProvingKeys::Kzg(pk, params))
+            }
+            Commitments::IPA => {
+                let pk = load_pk_decrypted::<IPACommitmentScheme<G1Affine>, GraphCircuit>(
+                    pk_path,
+                    circuit.params(),
+                    identity,
+                )?;
+                let params = load_params_prover::<IPACommitmentScheme<G1Affine>>(
+                    srs_path,
+                    logrows,
+                    Commitments::IPA,
+                )?;
+                Ok(ProvingKeys::Ipa(pk, params))
+            }
+        }
+    }
+}
+
+/// Reads the age identity encrypted artifacts are decrypted with, see [crate::pfsys::encryption]
+fn load_identity(identity: Option<PathBuf>) -> Result<Option<Identity>, EZKLError> {
+    identity
+        .map(|path| Identity::from_file(&path).map_err(|e| EZKLError::from(GraphError::from(e))))
+        .transpose()
+}
+
+/// Warns before `phase` starts when it is predicted to need more memory than is available, with the changes to the
+/// settings that would lower it
+fn warn_memory(circuit: &GraphCircuit, phase: MemoryPhase) {
+    use crate::pfsys::estimate::{plan_memory, CircuitShape};
+
+    let Some(available) = available_memory() else {
+        return;
+    };
+    let settings = circuit.settings();
+    let mut cs = plonk::ConstraintSystem::<Fr>::default();
+    GraphCircuit::configure_with_params(&mut cs, settings.clone());
+    let shape = CircuitShape::from_cs(&cs, settings.run_args.logrows);
+    if let Some(warning) = plan_memory(&shape, phase, settings.fitting_logrows(), available) {
+        warn!("{}", warning);
+    }
+}
+
+/// The strategy and transcript of a proof of `proof_type`, the transcript defaulting to that of the proof type
+fn proof_strategy(
+    proof_type: ProofType,
+    transcript: Option<TranscriptType>,
+) -> Result<(StrategyType, TranscriptType), EZKLError> {
+    let strategy: StrategyType = proof_type.into();
+    let transcript: TranscriptType = transcript.unwrap_or(proof_type.into());
+    if strategy == StrategyType::Accum && transcript != TranscriptType::Poseidon {
+        return Err(format!(
+            "proofs for aggregation must use the poseidon transcript, got {}",
+            transcript
+        )
+        .into());
+    }
+    Ok((strategy, transcript))
+}
+
 #[allow(clippy::too_many_arguments)]
 pub(crate) fn prove(
     data_path: PathBuf,
@@ -1832,37 +4585,111 @@ pub(crate) fn prove(
     proof_path: Option<PathBuf>,
     srs_path: Option<PathBuf>,
     proof_type: ProofType,
+    transcript: Option<TranscriptType>,
     check_mode: CheckMode,
+    blinding_secret_key: Option<String>,
+    identity: Option<PathBuf>,
 ) -> Result<Snark<Fr, G1Affine>, EZKLError> {
+    let _span = tracing::info_span!("prove", proof_type = ?proof_type).entered();
     let data = GraphWitness::from_path(data_path)?;
-    let mut circuit = GraphCircuit::load(compiled_circuit_path)?;
+    let identity = load_identity(identity)?;
+    let circuit = GraphCircuit::load_decrypted(compiled_circuit_path, identity.as_ref())?;
+    proof_strategy(proof_type, transcript)?;
+    warn_memory(&circuit, MemoryPhase::Prove);
+
+    let keys = ProvingKeys::load(&circuit, pk_path, srs_path, identity.as_ref())?;
+    let snark = prove_witness(
+        circuit,
+        data,
+        &keys,
+        proof_type,
+        transcript,
+        check_mode,
+        blinding_secret_key,
+    )?;
 
+    if let Some(proof_path) = proof_path {
+        snark.save(&proof_path)?;
+    }
+
+    Ok(snark)
+}
+
+/// Proves the samples of the data with the smallest member of a family of batch sizes they fit, padded with the
+/// padding sample of the family. The proof verifies with the settings and verification key of the member, and its
+/// instances hold the outputs of the padding zeroed and the mask of the samples last.
+pub(crate) async fn prove_batch_family(
+    family_dir: PathBuf,
+    data: PathBuf,
+    proof_path: Option<PathBuf>,
+    srs_path: Option<PathBuf>,
+    proof_type: ProofType,
+    transcript: Option<TranscriptType>,
+) -> Result<Snark<Fr, G1Affine>, EZKLError> {
+    let family = BatchFamily::load(&family_dir)?;
+    let samples = match GraphData::from_path(data)?.input_data {
+        DataSource::File(samples) => samples,
+        _ => {
+            return Err(GraphError::InvalidBatchFamily(
+                "the samples must be the values of the inputs".into(),
+            )
+            .into())
+        }
+    };
+    let num_samples = family.num_samples(&samples)?;
+    let batch_size = family.member(num_samples)?;
+    info!(
+        "proving {} samples with the member of batch size {} of the family",
+        num_samples, batch_size
+    );
+
+    let session = ProverSession::load(
+        BatchFamily::compiled_circuit_path(&family_dir, batch_size),
+        BatchFamily::pk_path(&family_dir, batch_size),
+        srs_path,
+    )?;
+    proof_strategy(proof_type, transcript)?;
+    let input_shapes = session.circuit.model().graph.input_shapes()?;
+    let padded = family
+        .pad(&samples, batch_size)?
+        .into_iter()
+        .zip(input_shapes)
+        .collect();
+    let witness = session.gen_witness(DataInput::Arrays(padded)).await?;
+    let snark = session.prove(witness, proof_type, transcript, None)?;
+
+    if let Some(proof_path) = proof_path {
+        snark.save(&proof_path)?;
+    }
+    Ok(snark)
+}
+
+/// Creates (and verifies) the proof of a witness of `circuit`
+fn prove_witness(
+    mut circuit: GraphCircuit,
+    mut data: GraphWitness,
+    keys: &ProvingKeys,
+    proof_type: ProofType,
+    transcript: Option<TranscriptType>,
+    check_mode: CheckMode,
+    blinding_secret_key: Option<String>,
+) -> Result<Snark<Fr, G1Affine>, EZKLError> {
+    decrypt_blinders(&mut data, blinding_secret_key)?;
     circuit.load_graph_witness(&data)?;
 
     let pretty_public_inputs = circuit.pretty_public_inputs(&data)?;
     let public_inputs = circuit.prepare_public_inputs(&data)?;
 
-    let circuit_settings = circuit.settings().clone();
-
-    let strategy: StrategyType = proof_type.into();
-    let transcript: TranscriptType = proof_type.into();
+    let (strategy, transcript) = proof_strategy(proof_type, transcript)?;
+    let pinned_block = data.pinned_block.clone();
     let proof_split_commits: Option<ProofSplitCommit> = data.into();
 
-    let commitment = circuit_settings.run_args.commitment.into();
-    let logrows = circuit_settings.run_args.logrows;
+    let commitment = circuit.settings().run_args.commitment.into();
     // creates and verifies the proof
-    let mut snark = match commitment {
-        Commitments::KZG => {
-            let pk =
-                load_pk::<KZGCommitmentScheme<Bn256>, GraphCircuit>(pk_path, circuit.params())?;
-
-            let params = load_params_prover::<KZGCommitmentScheme<Bn256>>(
-                srs_path,
-                logrows,
-                Commitments::KZG,
-            )?;
-            match strategy {
-                StrategyType::Single => create_proof_circuit::<
+    let mut snark = match keys {
+        ProvingKeys::Kzg(pk, params) => match strategy {
+            StrategyType::Single => match transcript {
+                TranscriptType::EVM => create_proof_circuit::<
                     KZGCommitmentScheme<Bn256>,
                     _,
                     ProverSHPLONK<_>,
@@ -1874,119 +4701,595 @@ pub(crate) fn prove(
                 >(
                     circuit,
                     vec![public_inputs],
-                    &params,
-                    &pk,
+                    params,
+                    pk,
                     check_mode,
                     commitment,
                     transcript,
                     proof_split_commits,
                     None,
                 ),
-                StrategyType::Accum => {
-                    let protocol = Some(compile(
-                        &params,
-                        pk.get_vk(),
-                        Config::kzg().with_num_instance(vec![public_inputs.len()]),
-                    ));
-
-                    create_proof_circuit::<
-                        KZGCommitmentScheme<Bn256>,
-                        _,
-                        ProverSHPLONK<_>,
-                        VerifierSHPLONK<_>,
-                        KZGAccumulatorStrategy<_>,
-                        _,
-                        PoseidonTranscript<NativeLoader, _>,
-                        PoseidonTranscript<NativeLoader, _>,
-                    >(
-                        circuit,
-                        vec![public_inputs],
-                        &params,
-                        &pk,
-                        check_mode,
-                        commitment,
-                        transcript,
-                        proof_split_commits,
-                        protocol,
-                    )
-                }
+                TranscriptType::Poseidon => create_proof_circuit::<
+                    KZGCommitmentScheme<Bn256>,
+                    _,
+                    ProverSHPLONK<_>,
+                    VerifierSHPLONK<_>,
+                    KZGSingleStrategy<_>,
+                    _,
+                    PoseidonTranscript<NativeLoader, _>,
+                    PoseidonTranscript<NativeLoader, _>,
+                >(
+                    circuit,
+                    vec![public_inputs],
+                    params,
+                    pk,
+                    check_mode,
+                    commitment,
+                    transcript,
+                    proof_split_commits,
+                    None,
+                ),
+                TranscriptType::Blake => create_proof_circuit::<
+                    KZGCommitmentScheme<Bn256>,
+                    _,
+                    ProverSHPLONK<_>,
+                    VerifierSHPLONK<_>,
+                    KZGSingleStrategy<_>,
+                    _,
+                    Blake2bWrite<_, _, Challenge255<_>>,
+                    Blake2bRead<_, _, Challenge255<_>>,
+                >(
+                    circuit,
+                    vec![public_inputs],
+                    params,
+                    pk,
+                    check_mode,
+                    commitment,
+                    transcript,
+                    proof_split_commits,
+                    None,
+                ),
+            },
+            StrategyType::Accum => {
+                let protocol = Some(compile(
+                    params,
+                    pk.get_vk(),
+                    Config::kzg().with_num_instance(vec![public_inputs.len()]),
+                ));
+
+                create_proof_circuit::<
+                    KZGCommitmentScheme<Bn256>,
+                    _,
+                    ProverSHPLONK<_>,
+                    VerifierSHPLONK<_>,
+                    KZGAccumulatorStrategy<_>,
+                    _,
+                    PoseidonTranscript<NativeLoader, _>,
+                    PoseidonTranscript<NativeLoader, _>,
+                >(
+                    circuit,
+                    vec![public_inputs],
+                    params,
+                    pk,
+                    check_mode,
+                    commitment,
+                    transcript,
+                    proof_split_commits,
+                    protocol,
+                )
+            }
+        },
+        ProvingKeys::Ipa(pk, params) => match strategy {
+            StrategyType::Single => match transcript {
+                TranscriptType::EVM => create_proof_circuit::<
+                    IPACommitmentScheme<G1Affine>,
+                    _,
+                    ProverIPA<_>,
+                    VerifierIPA<_>,
+                    IPASingleStrategy<_>,
+                    _,
+                    EvmTranscript<_, _, _, _>,
+                    EvmTranscript<_, _, _, _>,
+                >(
+                    circuit,
+                    vec![public_inputs],
+                    params,
+                    pk,
+                    check_mode,
+                    commitment,
+                    transcript,
+                    proof_split_commits,
+                    None,
+                ),
+                TranscriptType::Poseidon => create_proof_circuit::<
+                    IPACommitmentScheme<G1Affine>,
+                    _,
+                    ProverIPA<_>,
+                    VerifierIPA<_>,
+                    IPASingleStrategy<_>,
+                    _,
+                    PoseidonTranscript<NativeLoader, _>,
+                    PoseidonTranscript<NativeLoader, _>,
+                >(
+                    circuit,
+                    vec![public_inputs],
+                    params,
+                    pk,
+                    check_mode,
+                    commitment,
+                    transcript,
+                    proof_split_commits,
+                    None,
+                ),
+                TranscriptType::Blake => create_proof_circuit::<
+                    IPACommitmentScheme<G1Affine>,
+                    _,
+                    ProverIPA<_>,
+                    VerifierIPA<_>,
+                    IPASingleStrategy<_>,
+                    _,
+                    Blake2bWrite<_, _, Challenge255<_>>,
+                    Blake2bRead<_, _, Challenge255<_>>,
+                >(
+                    circuit,
+                    vec![public_inputs],
+                    params,
+                    pk,
+                    check_mode,
+                    commitment,
+                    transcript,
+                    proof_split_commits,
+                    None,
+                ),
+            },
+            StrategyType::Accum => {
+                let protocol = Some(compile(
+                    params,
+                    pk.get_vk(),
+                    Config::ipa().with_num_instance(vec![public_inputs.len()]),
+                ));
+                create_proof_circuit::<
+                    IPACommitmentScheme<G1Affine>,
+                    _,
+                    ProverIPA<_>,
+                    VerifierIPA<_>,
+                    IPAAccumulatorStrategy<_>,
+                    _,
+                    PoseidonTranscript<NativeLoader, _>,
+                    PoseidonTranscript<NativeLoader, _>,
+                >(
+                    circuit,
+                    vec![public_inputs],
+                    params,
+                    pk,
+                    check_mode,
+                    commitment,
+                    transcript,
+                    proof_split_commits,
+                    protocol,
+                )
             }
+        },
+    }?;
+
+    snark.pretty_public_inputs = pretty_public_inputs;
+    snark.pinned_block = pinned_block;
+
+    Ok(snark)
+}
+
+/// A compiled circuit with its proving key and SRS loaded in memory, to generate witnesses of and prove and verify
+/// any number of them without reading the artifacts again
+pub(crate) struct ProverSession {
+    circuit: GraphCircuit,
+    keys: ProvingKeys,
+}
+
+impl ProverSession {
+    /// Load the compiled circuit, proving key and SRS of a session
+    pub(crate) fn load(
+        compiled_circuit_path: PathBuf,
+        pk_path: PathBuf,
+        srs_path: Option<PathBuf>,
+    ) -> Result<Self, EZKLError> {
+        let circuit = GraphCircuit::load(compiled_circuit_path)?;
+        let keys = ProvingKeys::load(&circuit, pk_path, srs_path, None)?;
+        Ok(ProverSession { circuit, keys })
+    }
+
+    /// The settings of the circuit of the session
+    pub(crate) fn settings(&self) -> &GraphSettings {
+        self.circuit.settings()
+    }
+
+    /// Run the forward pass of the circuit on the data, the polycommit visibilities committing with the loaded SRS
+    /// and verifying key
+    pub(crate) async fn gen_witness(&self, data: DataInput) -> Result<GraphWitness, EZKLError> {
+        let mut circuit = self.circuit.clone();
+        let data = data.load(
+            circuit.model().graph.input_names(),
+            &circuit.model().graph.input_shapes()?,
+            false,
+            None,
+            circuit.settings().image_preprocessing.as_ref(),
+        )?;
+        let pinned_block = crate::eth::pinned_block(&data).await?;
+        let mut input = circuit.load_graph_input(&data).await?;
+
+        let settings = circuit.settings();
+        let region_settings =
+            RegionSettings::all_true(settings.run_args.decomp_base, settings.run_args.decomp_legs);
+        let mut witness = match &self.keys {
+            ProvingKeys::Kzg(pk, srs) => circuit.forward::<KZGCommitmentScheme<_>>(
+                &mut input,
+                Some(pk.get_vk()),
+                Some(srs),
+                region_settings,
+            )?,
+            ProvingKeys::Ipa(pk, srs) => circuit.forward::<IPACommitmentScheme<_>>(
+                &mut input,
+                Some(pk.get_vk()),
+                Some(srs),
+                region_settings,
+            )?,
+        };
+        witness.pinned_block = pinned_block;
+        Ok(witness)
+    }
+
+    /// Creates (and verifies) the proof of a witness
+    pub(crate) fn prove(
+        &self,
+        witness: GraphWitness,
+        proof_type: ProofType,
+        transcript: Option<TranscriptType>,
+        blinding_secret_key: Option<String>,
+    ) -> Result<Snark<Fr, G1Affine>, EZKLError> {
+        prove_witness(
+            self.circuit.clone(),
+            witness,
+            &self.keys,
+            proof_type,
+            transcript,
+            CheckMode::UNSAFE,
+            blinding_secret_key,
+        )
+    }
+
+    /// Verifies a proof against the verifying key of the loaded proving key
+    pub(crate) fn verify(&self, proof: &Snark<Fr, G1Affine>) -> Result<bool, EZKLError> {
+        let logrows = self.settings().run_args.logrows;
+        match &self.keys {
+            ProvingKeys::Kzg(pk, params) => match proof.transcript_type {
+                TranscriptType::EVM => verify_snark::<
+                    KZGCommitmentScheme<Bn256>,
+                    VerifierSHPLONK<'_, Bn256>,
+                    _,
+                    KZGSingleStrategy<_>,
+                    EvmTranscript<G1Affine, _, _, _>,
+                >(proof, pk.get_vk(), params, logrows),
+                TranscriptType::Poseidon => verify_snark::<
+                    KZGCommitmentScheme<Bn256>,
+                    VerifierSHPLONK<'_, Bn256>,
+                    _,
+                    KZGSingleStrategy<_>,
+                    PoseidonTranscript<NativeLoader, _>,
+                >(proof, pk.get_vk(), params, logrows),
+                TranscriptType::Blake => verify_snark::<
+                    KZGCommitmentScheme<Bn256>,
+                    VerifierSHPLONK<'_, Bn256>,
+                    _,
+                    KZGSingleStrategy<_>,
+                    Blake2bRead<_, _, Challenge255<_>>,
+                >(proof, pk.get_vk(), params, logrows),
+            },
+            ProvingKeys::Ipa(pk, params) => match proof.transcript_type {
+                TranscriptType::EVM => verify_snark::<
+                    IPACommitmentScheme<G1Affine>,
+                    VerifierIPA<_>,
+                    _,
+                    IPASingleStrategy<_>,
+                    EvmTranscript<G1Affine, _, _, _>,
+                >(proof, pk.get_vk(), params, logrows),
+                TranscriptType::Poseidon => verify_snark::<
+                    IPACommitmentScheme<G1Affine>,
+                    VerifierIPA<_>,
+                    _,
+                    IPASingleStrategy<_>,
+                    PoseidonTranscript<NativeLoader, _>,
+                >(proof, pk.get_vk(), params, logrows),
+                TranscriptType::Blake => verify_snark::<
+                    IPACommitmentScheme<G1Affine>,
+                    VerifierIPA<_>,
+                    _,
+                    IPASingleStrategy<_>,
+                    Blake2bRead<_, _, Challenge255<_>>,
+                >(proof, pk.get_vk(), params, logrows),
+            },
+        }
+    }
+}
+
+/// The outcome of proving one witness of a batch
+#[derive(Debug, Clone, Serialize, serde::Deserialize)]
+pub struct ProofBatchEntry {
+    /// The witness file
+    pub witness: PathBuf,
+    /// The proof file, if proving succeeded
+    pub proof: Option<PathBuf>,
+    /// Why proving failed, if it did
+    pub error: Option<String>,
+}
+
+/// The summary of a batched proving, saved next to the proofs
+#[derive(Debug, Clone, Serialize, serde::Deserialize)]
+pub struct ProofBatchSummary {
+    /// The number of witnesses a proof was created for
+    pub succeeded: usize,
+    /// The number of witnesses proving failed for
+    pub failed: usize,
+    /// The outcome for each witness, in file name order
+    pub entries: Vec<ProofBatchEntry>,
+}
+
+#[cfg(feature = "python-bindings")]
+impl pyo3::ToPyObject for ProofBatchEntry {
+    fn to_object(&self, py: pyo3::Python) -> pyo3::PyObject {
+        let dict = pyo3::types::PyDict::new(py);
+        dict.set_item("witness", &self.witness).unwrap();
+        dict.set_item("proof", &self.proof).unwrap();
+        dict.set_item("error", &self.error).unwrap();
+        dict.into()
+    }
+}
+
+#[cfg(feature = "python-bindings")]
+impl pyo3::ToPyObject for ProofBatchSummary {
+    fn to_object(&self, py: pyo3::Python) -> pyo3::PyObject {
+        let dict = pyo3::types::PyDict::new(py);
+        dict.set_item("succeeded", self.succeeded).unwrap();
+        dict.set_item("failed", self.failed).unwrap();
+        let entries: Vec<pyo3::PyObject> = self.entries.iter().map(|e| e.to_object(py)).collect();
+        dict.set_item("entries", entries).unwrap();
+        dict.into()
+    }
+}
+
+/// The file name of the summary saved by batched proving
+pub const PROOF_BATCH_SUMMARY: &str = "summary.json";
+
+/// Prove each witness file in a directory, loading the compiled circuit, proving key and srs once. The proofs are
+/// created on up to `workers` threads, fewer if the available memory doesn't fit as many proofs at once.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn prove_batch(
+    compiled_circuit_path: PathBuf,
+    witness_dir: PathBuf,
+    proof_dir: PathBuf,
+    pk_path: PathBuf,
+    srs_path: Option<PathBuf>,
+    proof_type: ProofType,
+    transcript: Option<TranscriptType>,
+    check_mode: CheckMode,
+    blinding_secret_key: Option<String>,
+    workers: usize,
+) -> Result<ProofBatchSummary, EZKLError> {
+    let circuit = GraphCircuit::load(compiled_circuit_path)?;
+    proof_strategy(proof_type, transcript)?;
+
+    let mut witnesses = std::fs::read_dir(&witness_dir)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    // the summary of a batched witness generation isn't a witness
+    witnesses.retain(|p| {
+        p.is_file() && p.file_name() != Some(std::ffi::OsStr::new(WITNESS_BATCH_SUMMARY))
+    });
+    witnesses.sort();
+
+    let pk_size = std::fs::metadata(&pk_path)?.len();
+    let keys = ProvingKeys::load(&circuit, pk_path, srs_path, None)?;
+    std::fs::create_dir_all(&proof_dir)?;
+
+    let workers = proving_workers(workers, witnesses.len(), pk_size);
+    info!(
+        "proving {} witnesses on {} workers",
+        witnesses.len(),
+        workers
+    );
+
+    let start_time = Instant::now();
+    let jobs = std::sync::Mutex::new(witnesses.into_iter().enumerate());
+    let mut entries = std::thread::scope(|scope| {
+        let workers = (0..workers)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut entries = vec![];
+                    loop {
+                        let Some((i, witness)) = jobs.lock().unwrap().next() else {
+                            break;
+                        };
+                        let entry = proof_batch_entry(
+                            &circuit,
+                            &keys,
+                            &proof_dir,
+                            witness,
+                            proof_type,
+                            transcript,
+                            check_mode,
+                            blinding_secret_key.clone(),
+                        );
+                        entries.push((i, entry));
+                    }
+                    entries
+                })
+            })
+            .collect::<Vec<_>>();
+        workers
+            .into_iter()
+            .flat_map(|w| w.join().unwrap())
+            .collect::<Vec<_>>()
+    });
+    entries.sort_by_key(|(i, _)| *i);
+    let entries: Vec<_> = entries.into_iter().map(|(_, e)| e).collect();
+    trace!("batched proving took {:?}", start_time.elapsed());
+
+    let failed = entries.iter().filter(|e| e.error.is_some()).count();
+    let summary = ProofBatchSummary {
+        succeeded: entries.len() - failed,
+        failed,
+        entries,
+    };
+
+    serde_json::to_writer(
+        BufWriter::with_capacity(
+            *EZKL_BUF_CAPACITY,
+            File::create(proof_dir.join(PROOF_BATCH_SUMMARY))?,
+        ),
+        &summary,
+    )?;
+
+    Ok(summary)
+}
+
+/// The number of proofs to create at once: `workers`, but no more than there are witnesses nor than fit in the
+/// available memory, taking that a proof needs about as much memory as its proving key holds
+fn proving_workers(workers: usize, witnesses: usize, pk_size: u64) -> usize {
+    let mut bounded = workers.clamp(1, witnesses.max(1));
+    if let Some(memory) = available_memory() {
+        let fit = (memory / pk_size.max(1)).max(1) as usize;
+        if fit < bounded {
+            warn!(
+                "the available memory fits {} proofs at once, proving on {} rather than {} workers",
+                fit, fit, bounded
+            );
+            bounded = fit;
         }
-        Commitments::IPA => {
-            let pk =
-                load_pk::<IPACommitmentScheme<G1Affine>, GraphCircuit>(pk_path, circuit.params())?;
+    }
+    bounded
+}
 
-            let params = load_params_prover::<IPACommitmentScheme<G1Affine>>(
-                srs_path,
-                circuit_settings.run_args.logrows,
-                Commitments::IPA,
-            )?;
-            match strategy {
-                StrategyType::Single => create_proof_circuit::<
-                    IPACommitmentScheme<G1Affine>,
-                    _,
-                    ProverIPA<_>,
-                    VerifierIPA<_>,
-                    IPASingleStrategy<_>,
-                    _,
-                    EvmTranscript<_, _, _, _>,
-                    EvmTranscript<_, _, _, _>,
-                >(
-                    circuit,
-                    vec![public_inputs],
-                    &params,
-                    &pk,
-                    check_mode,
-                    commitment,
-                    transcript,
-                    proof_split_commits,
-                    None,
-                ),
-                StrategyType::Accum => {
-                    let protocol = Some(compile(
-                        &params,
-                        pk.get_vk(),
-                        Config::ipa().with_num_instance(vec![public_inputs.len()]),
-                    ));
-                    create_proof_circuit::<
-                        IPACommitmentScheme<G1Affine>,
-                        _,
-                        ProverIPA<_>,
-                        VerifierIPA<_>,
-                        IPAAccumulatorStrategy<_>,
-                        _,
-                        PoseidonTranscript<NativeLoader, _>,
-                        PoseidonTranscript<NativeLoader, _>,
-                    >(
-                        circuit,
-                        vec![public_inputs],
-                        &params,
-                        &pk,
-                        check_mode,
-                        commitment,
-                        transcript,
-                        proof_split_commits,
-                        protocol,
-                    )
-                }
+/// Prove one witness of a batch, recording rather than returning any failure
+#[allow(clippy::too_many_arguments)]
+fn proof_batch_entry(
+    circuit: &GraphCircuit,
+    keys: &ProvingKeys,
+    proof_dir: &Path,
+    witness: PathBuf,
+    proof_type: ProofType,
+    transcript: Option<TranscriptType>,
+    check_mode: CheckMode,
+    blinding_secret_key: Option<String>,
+) -> ProofBatchEntry {
+    let proof_path = proof_dir
+        .join(witness.file_stem().unwrap_or_default())
+        .with_extension("json");
+
+    // a panicking prover only fails its own witness
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let data = GraphWitness::from_path(witness.clone())?;
+        let snark = prove_witness(
+            circuit.clone(),
+            data,
+            keys,
+            proof_type,
+            transcript,
+            check_mode,
+            blinding_secret_key,
+        )?;
+        snark.save(&proof_path)?;
+        Ok::<_, EZKLError>(())
+    }))
+    .map_err(|_| EZKLError::from("proving panicked"))
+    .and_then(|r| r);
+
+    match result {
+        Ok(()) => ProofBatchEntry {
+            witness,
+            proof: Some(proof_path),
+            error: None,
+        },
+        Err(e) => {
+            warn!("failed to prove {}: {}", witness.display(), e);
+            ProofBatchEntry {
+                witness,
+                proof: None,
+                error: Some(e.to_string()),
             }
         }
-    }?;
+    }
+}
 
-    snark.pretty_public_inputs = pretty_public_inputs;
+pub(crate) fn commit_param_groups(
+    witness_path: PathBuf,
+    settings_path: PathBuf,
+    output_dir: PathBuf,
+) -> Result<Vec<PathBuf>, EZKLError> {
+    let settings = GraphSettings::load(&settings_path)?;
+    let witness = GraphWitness::from_path(witness_path)?;
+    std::fs::create_dir_all(&output_dir)?;
 
-    if let Some(proof_path) = proof_path {
-        snark.save(&proof_path)?;
+    let mut paths = vec![];
+    for group in group_commitments(&settings, &witness)? {
+        let path = output_dir.join(format!("{}.json", group.group));
+        info!(
+            "{} commitments of the params of group {} written to {}",
+            group.commitments.len(),
+            group.group,
+            path.display()
+        );
+        group.save(&path)?;
+        paths.push(path);
     }
+    Ok(paths)
+}
 
-    Ok(snark)
+/// Checks that the commitments of the params of a proof are those of the groups of `group_commitments`, each group
+/// on its own: swapping the commitments of a group into the proof leaves it unchanged
+pub(crate) fn check_group_commitments(
+    proof_path: &PathBuf,
+    settings_path: &PathBuf,
+    group_commitments: &[PathBuf],
+) -> Result<(), EZKLError> {
+    if group_commitments.is_empty() {
+        return Ok(());
+    }
+    let settings = GraphSettings::load(settings_path)?;
+    let snark = Snark::load::<KZGCommitmentScheme<Bn256>>(proof_path)?;
+    for path in group_commitments {
+        let group = GroupCommitments::load(path)?;
+        if !settings.param_commitment_groups.contains(&group.group) {
+            return Err(GraphError::InvalidCommitmentGroups(format!(
+                "the circuit doesn't commit to a group {}",
+                group.group
+            ))
+            .into());
+        }
+        let swapped =
+            swap_proof_commitments_polycommit_at(&snark, group.offset, &group.commitments)?;
+        if swapped.proof != snark.proof {
+            return Err(GraphError::CommitmentGroupMismatch { group: group.group }.into());
+        }
+        info!("the params of group {} match the proof", group.group);
+    }
+    Ok(())
 }
 
 pub(crate) fn swap_proof_commitments_cmd(
     proof_path: PathBuf,
     witness: PathBuf,
+    group_commitments: Vec<PathBuf>,
 ) -> Result<Snark<Fr, G1Affine>, EZKLError> {
     let snark = Snark::load::<KZGCommitmentScheme<Bn256>>(&proof_path)?;
+    if !group_commitments.is_empty() {
+        // only the commitments of the groups are swapped, those of the other groups and tensors are left as proven
+        let mut snark_new = snark;
+        for path in &group_commitments {
+            let group = GroupCommitments::load(path)?;
+            snark_new =
+                swap_proof_commitments_polycommit_at(&snark_new, group.offset, &group.commitments)?;
+        }
+        snark_new.save(&proof_path)?;
+        return Ok(snark_new);
+    }
     let witness = GraphWitness::from_path(witness)?;
     let commitments = witness.get_polycommitments();
 
@@ -1994,6 +5297,20 @@ pub(crate) fn swap_proof_commitments_cmd(
 
     if snark_new.proof != *snark.proof {
         log::warn!("swap proof has created a different proof");
+        // blinded commitments of the same tensor only agree if their blinders do
+        let blinded = [
+            &witness.processed_inputs,
+            &witness.processed_params,
+            &witness.processed_outputs,
+        ]
+        .into_iter()
+        .flatten()
+        .any(|p| p.polycommit_blinders.is_some());
+        if blinded {
+            log::warn!(
+                "the commitments of the witness are blinded, prove with the blinders of the commitments swapped in"
+            );
+        }
     }
 
     snark_new.save(&proof_path)?;
@@ -2097,6 +5414,228 @@ pub(crate) fn setup_aggregate(
     Ok(String::new())
 }
 
+/// Checks the links of a chain of proofs, see [crate::graph::link], and aggregates them into one proof constraining
+/// the digests of the linked tensors to match if `aggregate`. The committed tensors are matched by the aggregation
+/// circuit as for `--split-proofs`.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn link_proofs(
+    proof_paths: Vec<PathBuf>,
+    settings_paths: Vec<PathBuf>,
+    aggregate: bool,
+    aggregated_proof_path: PathBuf,
+    vk_path: PathBuf,
+    pk_path: PathBuf,
+    srs_path: Option<PathBuf>,
+    logrows: u32,
+    output: Option<PathBuf>,
+) -> Result<String, EZKLError> {
+    if proof_paths.len() < 2 || proof_paths.len() != settings_paths.len() {
+        return Err(
+            "link-proofs takes the proofs of two or more circuits and the settings of each".into(),
+        );
+    }
+    let snarks = proof_paths
+        .iter()
+        .map(Snark::load::<KZGCommitmentScheme<Bn256>>)
+        .collect::<Result<Vec<_>, _>>()?;
+    let settings = settings_paths
+        .iter()
+        .map(GraphSettings::load)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut chain = vec![];
+    for (i, (from, to)) in snarks.iter().tuple_windows().enumerate() {
+        let links = link_tensors(&settings[i], &settings[i + 1])?;
+        check_digest_links(&links, i, &from.instances.concat(), &to.instances.concat())?;
+
+        if let Some(link) = links.iter().find(|l| l.kind == LinkKind::Polycommit) {
+            let split = from.split.as_ref().ok_or_else(|| {
+                format!(
+                    "proof {} doesn't record the commitments of its outputs, prove it from a witness with polycommit outputs",
+                    i
+                )
+            })?;
+            let range = split.range();
+            let outputs = proof_commitments_polycommit(from, range.end)?;
+            let inputs = proof_commitments_polycommit(to, range.len())?;
+            if outputs[range] != inputs[..] {
+                return Err(GraphError::BrokenLink {
+                    proof: i,
+                    output: link.output,
+                    input: link.input,
+                }
+                .into());
+            }
+        }
+        info!("proof {} is linked to proof {} by {:?}", i, i + 1, links);
+        chain.push(links);
+    }
+
+    if aggregate {
+        let instance_links = chain
+            .iter()
+            .enumerate()
+            .flat_map(|(i, links)| {
+                links.iter().filter_map(|l| l.instances.clone()).flat_map(
+                    move |(outputs, inputs)| {
+                        outputs.zip(inputs).map(move |(o, j)| ((i, o), (i + 1, j)))
+                    },
+                )
+            })
+            .collect_vec();
+        let split_proofs = chain
+            .iter()
+            .flatten()
+            .any(|l| l.kind == LinkKind::Polycommit);
+
+        aggregate_linked_proofs(
+            snarks,
+            instance_links,
+            split_proofs,
+            "Aggregating the linked proofs (may take a while)...",
+            &aggregated_proof_path,
+            &vk_path,
+            &pk_path,
+            srs_path,
+            logrows,
+        )?;
+    }
+
+    let json = serde_json::to_string_pretty(&chain)?;
+    if let Some(output) = output {
+        std::fs::write(output, &json)?;
+    }
+    Ok(json)
+}
+
+/// Aggregates a chain of proofs into one proof constraining the pairs of `instance_links` to be equal, generating the
+/// keys of the aggregation circuit for the links
+#[allow(clippy::too_many_arguments)]
+fn aggregate_linked_proofs(
+    snarks: Vec<Snark<Fr, G1Affine>>,
+    instance_links: Vec<(SnarkInstance, SnarkInstance)>,
+    split_proofs: bool,
+    message: &'static str,
+    aggregated_proof_path: &PathBuf,
+    vk_path: &PathBuf,
+    pk_path: &PathBuf,
+    srs_path: Option<PathBuf>,
+    logrows: u32,
+) -> Result<(), EZKLError> {
+    let pb = init_spinner();
+    pb.set_message(message);
+    let params: ParamsKZG<Bn256> =
+        load_params_prover::<KZGCommitmentScheme<_>>(srs_path, logrows, Commitments::KZG)?;
+    let circuit = AggregationCircuit::new(
+        &ParamsProver::<G1Affine>::get_g(&params)[0].into(),
+        snarks,
+        split_proofs,
+    )?
+    .with_instance_links(instance_links)?;
+    // the links are constraints of the aggregation circuit, so its keys are generated for them
+    let pk =
+        create_keys::<KZGCommitmentScheme<Bn256>, AggregationCircuit>(&circuit, &params, false)?;
+    save_vk::<G1Affine>(vk_path, pk.get_vk())?;
+    save_pk::<G1Affine>(pk_path, &pk)?;
+
+    let public_inputs = circuit.instances();
+    let snark = create_proof_circuit::<
+        KZGCommitmentScheme<Bn256>,
+        _,
+        ProverSHPLONK<_>,
+        VerifierSHPLONK<_>,
+        KZGSingleStrategy<_>,
+        _,
+        EvmTranscript<_, _, _, _>,
+        EvmTranscript<_, _, _, _>,
+    >(
+        circuit,
+        vec![public_inputs],
+        &params,
+        &pk,
+        CheckMode::SAFE,
+        Commitments::KZG,
+        TranscriptType::EVM,
+        None,
+        None,
+    )?;
+    snark.save(aggregated_proof_path)?;
+    pb.finish_with_message("Done.");
+    Ok(())
+}
+
+/// Checks that the proofs of a session are chained in order, see [crate::graph::session], starting from
+/// `genesis_digest` if any, and aggregates them into one proof constraining the digest of each proof to be the
+/// previous digest of the next if `aggregate`. A single settings file is used for every proof.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn check_session(
+    proof_paths: Vec<PathBuf>,
+    settings_paths: Vec<PathBuf>,
+    genesis_digest: Option<String>,
+    aggregate: bool,
+    aggregated_proof_path: PathBuf,
+    vk_path: PathBuf,
+    pk_path: PathBuf,
+    srs_path: Option<PathBuf>,
+    logrows: u32,
+    output: Option<PathBuf>,
+) -> Result<String, EZKLError> {
+    if proof_paths.is_empty()
+        || (settings_paths.len() != 1 && settings_paths.len() != proof_paths.len())
+    {
+        return Err(
+            "check-session takes the proofs of a session and the settings of each, or a single settings file"
+                .into(),
+        );
+    }
+    let genesis = genesis_digest
+        .map(|digest| {
+            felt_from_str(&digest).ok_or_else(|| {
+                GraphError::InvalidSession(format!("{} is not a field element", digest))
+            })
+        })
+        .transpose()?;
+    let snarks = proof_paths
+        .iter()
+        .map(Snark::load::<KZGCommitmentScheme<Bn256>>)
+        .collect::<Result<Vec<_>, _>>()?;
+    let settings = settings_paths
+        .iter()
+        .map(GraphSettings::load)
+        .collect::<Result<Vec<_>, _>>()?;
+    let settings = match &settings[..] {
+        [settings] => vec![settings.clone(); snarks.len()],
+        _ => settings,
+    };
+
+    let instances = snarks
+        .iter()
+        .map(|snark| snark.instances.concat())
+        .collect_vec();
+    let steps = check_session_chain(&settings, &instances, genesis)?;
+    info!("the {} proofs are chained in order", steps.len());
+
+    if aggregate {
+        aggregate_linked_proofs(
+            snarks,
+            session_instance_links(&settings)?,
+            false,
+            "Aggregating the session (may take a while)...",
+            &aggregated_proof_path,
+            &vk_path,
+            &pk_path,
+            srs_path,
+            logrows,
+        )?;
+    }
+
+    let json = serde_json::to_string_pretty(&steps)?;
+    if let Some(output) = output {
+        std::fs::write(output, &json)?;
+    }
+    Ok(json)
+}
+
 #[allow(clippy::too_many_arguments)]
 pub(crate) fn aggregate(
     proof_path: PathBuf,
@@ -2147,6 +5686,10 @@ pub(crate) fn aggregate(
                 snarks,
                 split_proofs,
             )?;
+            info!(
+                "aggregating circuits with vk hashes {:?}",
+                circuit.vk_hashes()
+            );
             let public_inputs = circuit.instances();
             match transcript {
                 TranscriptType::EVM => create_proof_circuit::<
@@ -2169,6 +5712,26 @@ pub(crate) fn aggregate(
                     None,
                     None,
                 ),
+                TranscriptType::Blake => create_proof_circuit::<
+                    KZGCommitmentScheme<Bn256>,
+                    _,
+                    ProverSHPLONK<_>,
+                    VerifierSHPLONK<_>,
+                    KZGSingleStrategy<_>,
+                    _,
+                    Blake2bWrite<_, _, Challenge255<_>>,
+                    Blake2bRead<_, _, Challenge255<_>>,
+                >(
+                    circuit,
+                    vec![public_inputs],
+                    &params,
+                    &pk,
+                    check_mode,
+                    commitment,
+                    transcript,
+                    None,
+                    None,
+                ),
                 TranscriptType::Poseidon => {
                     let protocol = Some(compile(
                         &params,
@@ -2211,6 +5774,10 @@ pub(crate) fn aggregate(
                 snarks,
                 split_proofs,
             )?;
+            info!(
+                "aggregating circuits with vk hashes {:?}",
+                circuit.vk_hashes()
+            );
             let public_inputs = circuit.instances();
 
             match transcript {
@@ -2234,6 +5801,26 @@ pub(crate) fn aggregate(
                     None,
                     None,
                 ),
+                TranscriptType::Blake => create_proof_circuit::<
+                    IPACommitmentScheme<G1Affine>,
+                    _,
+                    ProverIPA<_>,
+                    VerifierIPA<_>,
+                    IPASingleStrategy<_>,
+                    _,
+                    Blake2bWrite<_, _, Challenge255<_>>,
+                    Blake2bRead<_, _, Challenge255<_>>,
+                >(
+                    circuit,
+                    vec![public_inputs],
+                    &params,
+                    &pk,
+                    check_mode,
+                    commitment,
+                    transcript,
+                    None,
+                    None,
+                ),
                 TranscriptType::Poseidon => {
                     let protocol = Some(compile(
                         &params,
@@ -2286,6 +5873,7 @@ pub(crate) fn verify(
     vk_path: PathBuf,
     srs_path: Option<PathBuf>,
     reduced_srs: bool,
+    transcript: Option<TranscriptType>,
 ) -> Result<bool, EZKLError> {
     let circuit_settings = GraphSettings::load(&settings_path)?;
 
@@ -2295,6 +5883,7 @@ pub(crate) fn verify(
     match commitment {
         Commitments::KZG => {
             let proof = Snark::load::<KZGCommitmentScheme<Bn256>>(&proof_path)?;
+            proof.check_transcript(transcript)?;
             let params: ParamsKZG<Bn256> = if reduced_srs {
                 // only need G_0 for the verification with shplonk
                 load_params_verifier::<KZGCommitmentScheme<Bn256>>(srs_path, 1, Commitments::KZG)?
@@ -2328,10 +5917,22 @@ pub(crate) fn verify(
                         _,
                     >(proof_path, circuit_settings, vk_path, &params, logrows)
                 }
+                TranscriptType::Blake => {
+                    verify_commitment::<
+                        KZGCommitmentScheme<Bn256>,
+                        VerifierSHPLONK<'_, Bn256>,
+                        _,
+                        KZGSingleStrategy<_>,
+                        Blake2bRead<_, _, Challenge255<_>>,
+                        GraphCircuit,
+                        _,
+                    >(proof_path, circuit_settings, vk_path, &params, logrows)
+                }
             }
         }
         Commitments::IPA => {
             let proof = Snark::load::<IPACommitmentScheme<G1Affine>>(&proof_path)?;
+            proof.check_transcript(transcript)?;
             let params: ParamsIPA<_> = load_params_verifier::<IPACommitmentScheme<G1Affine>>(
                 srs_path,
                 logrows,
@@ -2360,24 +5961,63 @@ pub(crate) fn verify(
                         _,
                     >(proof_path, circuit_settings, vk_path, &params, logrows)
                 }
+                TranscriptType::Blake => {
+                    verify_commitment::<
+                        IPACommitmentScheme<G1Affine>,
+                        VerifierIPA<_>,
+                        _,
+                        IPASingleStrategy<_>,
+                        Blake2bRead<_, _, Challenge255<_>>,
+                        GraphCircuit,
+                        _,
+                    >(proof_path, circuit_settings, vk_path, &params, logrows)
+                }
             }
         }
     }
 }
 
-fn verify_commitment<
+fn verify_commitment<
+    'a,
+    Scheme: CommitmentScheme,
+    V: Verifier<'a, Scheme>,
+    E: EncodedChallenge<Scheme::Curve>,
+    Strategy: VerificationStrategy<'a, Scheme, V>,
+    TR: TranscriptReadBuffer<Cursor<Vec<u8>>, Scheme::Curve, E>,
+    C: Circuit<<Scheme as CommitmentScheme>::Scalar, Params = Params>,
+    Params,
+>(
+    proof_path: PathBuf,
+    settings: Params,
+    vk_path: PathBuf,
+    params: &'a Scheme::ParamsVerifier,
+    logrows: u32,
+) -> Result<bool, EZKLError>
+where
+    Scheme::Scalar: FromUniformBytes<64>
+        + SerdeObject
+        + Serialize
+        + DeserializeOwned
+        + WithSmallOrderMulGroup<3>,
+    Scheme::Curve: SerdeObject + Serialize + DeserializeOwned,
+    Scheme::ParamsVerifier: 'a,
+{
+    let proof = Snark::load::<Scheme>(&proof_path)?;
+    let vk = load_vk::<Scheme, C>(vk_path, settings)?;
+    verify_snark::<Scheme, V, E, Strategy, TR>(&proof, &vk, params, logrows)
+}
+
+/// Verifies a proof with a verifying key and SRS already in memory
+fn verify_snark<
     'a,
     Scheme: CommitmentScheme,
     V: Verifier<'a, Scheme>,
     E: EncodedChallenge<Scheme::Curve>,
     Strategy: VerificationStrategy<'a, Scheme, V>,
     TR: TranscriptReadBuffer<Cursor<Vec<u8>>, Scheme::Curve, E>,
-    C: Circuit<<Scheme as CommitmentScheme>::Scalar, Params = Params>,
-    Params,
 >(
-    proof_path: PathBuf,
-    settings: Params,
-    vk_path: PathBuf,
+    proof: &Snark<Scheme::Scalar, Scheme::Curve>,
+    vk: &VerifyingKey<Scheme::Curve>,
     params: &'a Scheme::ParamsVerifier,
     logrows: u32,
 ) -> Result<bool, EZKLError>
@@ -2390,14 +6030,10 @@ where
     Scheme::Curve: SerdeObject + Serialize + DeserializeOwned,
     Scheme::ParamsVerifier: 'a,
 {
-    let proof = Snark::load::<Scheme>(&proof_path)?;
-
     let strategy = Strategy::new(params);
-    let vk = load_vk::<Scheme, C>(vk_path, settings)?;
     let now = Instant::now();
 
-    let result =
-        verify_proof_circuit::<V, _, _, _, TR>(&proof, params, &vk, strategy, 1 << logrows);
+    let result = verify_proof_circuit::<V, _, _, _, TR>(proof, params, vk, strategy, 1 << logrows);
 
     let elapsed = now.elapsed();
     info!(
@@ -2409,6 +6045,786 @@ where
     result.map_err(|e: plonk::Error| e.into()).map(|_| true)
 }
 
+/// The phases of an iteration of `ezkl bench`, in the order they run
+pub const BENCH_PHASES: [&str; 4] = ["gen-witness", "setup", "prove", "verify"];
+
+/// The wall times and peak resident memory of a phase of `ezkl bench`
+#[derive(Debug, Clone, Serialize, serde::Deserialize)]
+pub struct BenchPhase {
+    /// The phase, one of [BENCH_PHASES]
+    pub phase: String,
+    /// The wall time of each measured iteration, in seconds
+    pub times: Vec<f64>,
+    /// The mean wall time, in seconds
+    pub mean: f64,
+    /// The fastest wall time, in seconds
+    pub min: f64,
+    /// The slowest wall time, in seconds
+    pub max: f64,
+    /// The peak resident memory reached while the phase ran, in bytes, if the platform reports it
+    pub peak_rss: Option<u64>,
+}
+
+impl BenchPhase {
+    fn new(phase: &str, times: Vec<f64>, peak_rss: Option<u64>) -> Self {
+        let mean = times.iter().sum::<f64>() / times.len() as f64;
+        let min = times.iter().cloned().fold(f64::INFINITY, f64::min);
+        let max = times.iter().cloned().fold(0.0, f64::max);
+        BenchPhase {
+            phase: phase.to_string(),
+            times,
+            mean,
+            min,
+            max,
+            peak_rss,
+        }
+    }
+}
+
+/// The results of `ezkl bench`
+#[derive(Debug, Clone, Serialize, serde::Deserialize)]
+pub struct BenchReport {
+    /// The number of measured iterations
+    pub iterations: usize,
+    /// The number of iterations run before the measured ones
+    pub warmup: usize,
+    /// Whether setup was skipped, reusing existing keys
+    pub skip_setup: bool,
+    /// The logrows of the circuit
+    pub logrows: u32,
+    /// The rows the circuit uses
+    pub num_rows: usize,
+    /// The size of the proof, in bytes
+    pub proof_size: usize,
+    /// The measurements of each phase that ran
+    pub phases: Vec<BenchPhase>,
+}
+
+#[derive(Tabled)]
+struct BenchRow {
+    phase: String,
+    mean: String,
+    min: String,
+    max: String,
+    peak_rss: String,
+}
+
+impl BenchReport {
+    /// Formats the report as a table
+    pub fn table(&self) -> String {
+        let rows = self.phases.iter().map(|p| BenchRow {
+            phase: p.phase.clone(),
+            mean: format!("{:.3}s", p.mean),
+            min: format!("{:.3}s", p.min),
+            max: format!("{:.3}s", p.max),
+            peak_rss: p.peak_rss.map_or("n/a".to_string(), |rss| {
+                format!("{:.1} MiB", rss as f64 / (1 << 20) as f64)
+            }),
+        });
+        let mut table = tabled::Table::new(rows);
+        table.with(tabled::settings::Style::modern());
+        format!(
+            "{}\n{} iterations ({} warmup), {} rows of 2^{}, {} byte proofs",
+            table, self.iterations, self.warmup, self.num_rows, self.logrows, self.proof_size
+        )
+    }
+}
+
+/// Runs gen-witness, setup, prove and verify `iterations` times after `warmup` iterations that aren't measured, and
+/// reports the wall time and peak resident memory of each phase
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn bench(
+    compiled_circuit: PathBuf,
+    data: PathBuf,
+    settings_path: PathBuf,
+    vk_path: PathBuf,
+    pk_path: PathBuf,
+    srs_path: Option<PathBuf>,
+    iterations: usize,
+    warmup: usize,
+    skip_setup: bool,
+    output: Option<PathBuf>,
+) -> Result<String, EZKLError> {
+    if iterations == 0 {
+        return Err("bench needs at least one iteration".into());
+    }
+    if skip_setup && !(vk_path.exists() && pk_path.exists()) {
+        return Err(format!(
+            "--skip-setup reuses the keys at {} and {}, which don't exist",
+            vk_path.display(),
+            pk_path.display()
+        )
+        .into());
+    }
+    let settings = GraphSettings::load(&settings_path)?;
+
+    // the witness and proof of each iteration are scratch files
+    let scratch = std::env::temp_dir().join(format!("ezkl-bench-{}", std::process::id()));
+    std::fs::create_dir_all(&scratch)?;
+    let witness_path = scratch.join(DEFAULT_WITNESS);
+    let proof_path = scratch.join(DEFAULT_PROOF);
+
+    let mut times = vec![vec![]; BENCH_PHASES.len()];
+    let mut peaks = vec![None; BENCH_PHASES.len()];
+    let mut proof_size = 0;
+    for iteration in 0..warmup + iterations {
+        let measured = iteration >= warmup;
+        for (i, phase) in BENCH_PHASES.iter().enumerate() {
+            if *phase == "setup" && skip_setup {
+                continue;
+            }
+            // each phase reports its own peak
+            start_memory_phase();
+            let now = Instant::now();
+            match *phase {
+                "gen-witness" => {
+                    // polycommit visibilities need the vk, which exists after the first setup
+                    gen_witness(
+                        compiled_circuit.clone(),
+                        data.clone().into(),
+                        Some(witness_path.clone()),
+                        vk_path.exists().then(|| vk_path.clone()),
+                        srs_path.clone(),
+                        None,
+                        None,
+                        None,
+                        WitnessFormat::default(),
+                        None,
+                        None,
+                        None,
+                        None,
+                        None,
+                        false,
+                        None,
+                        None,
+                    )
+                    .await?;
+                }
+                "setup" => {
+                    setup(
+                        compiled_circuit.clone(),
+                        srs_path.clone(),
+                        vk_path.clone(),
+                        pk_path.clone(),
+                        None,
+                        DEFAULT_DISABLE_SELECTOR_COMPRESSION.parse().unwrap(),
+                        None,
+                    )?;
+                }
+                "prove" => {
+                    let snark = prove(
+                        witness_path.clone(),
+                        compiled_circuit.clone(),
+                        pk_path.clone(),
+                        Some(proof_path.clone()),
+                        srs_path.clone(),
+                        ProofType::Single,
+                        None,
+                        DEFAULT_CHECKMODE.parse().unwrap(),
+                        None,
+                        None,
+                    )?;
+                    proof_size = snark.proof.len();
+                }
+                _ => {
+                    let verified = verify(
+                        proof_path.clone(),
+                        settings_path.clone(),
+                        vk_path.clone(),
+                        srs_path.clone(),
+                        false,
+                        None,
+                    )?;
+                    if !verified {
+                        return Err("the bench proof failed to verify".into());
+                    }
+                }
+            }
+            let elapsed = now.elapsed().as_secs_f64();
+            let peak = end_memory_phase();
+            if measured {
+                times[i].push(elapsed);
+                peaks[i] = peaks[i].max(peak);
+            }
+        }
+        debug!("bench iteration {} done", iteration);
+    }
+    std::fs::remove_dir_all(&scratch)?;
+
+    let report = BenchReport {
+        iterations,
+        warmup,
+        skip_setup,
+        logrows: settings.run_args.logrows,
+        num_rows: settings.num_rows,
+        proof_size,
+        phases: BENCH_PHASES
+            .iter()
+            .zip(times.into_iter().zip(peaks))
+            .filter(|(_, (times, _))| !times.is_empty())
+            .map(|(phase, (times, peak))| BenchPhase::new(phase, times, peak))
+            .collect(),
+    };
+    info!("\n {}", report.table());
+
+    let json = serde_json::to_string_pretty(&report)?;
+    if let Some(output) = output {
+        std::fs::write(output, &json)?;
+    }
+    Ok(json)
+}
+
+/// The error of a tensor of the quantized pass against the same tensor of the float pass
+#[derive(Debug, Clone, Serialize, serde::Deserialize)]
+pub struct TensorDiff {
+    /// The largest absolute difference between an element of both passes
+    pub max_abs_error: f64,
+    /// The mean absolute difference between the elements of both passes
+    pub mean_abs_error: f64,
+    /// The largest absolute difference relative to the largest magnitude of the float tensor
+    pub rel_error: f64,
+    /// Whether the relative error exceeds the threshold
+    pub flagged: bool,
+}
+
+impl TensorDiff {
+    fn new(quantized: &[f64], float: &[f64], threshold: f64) -> Self {
+        let errors = quantized
+            .iter()
+            .zip(float)
+            .map(|(q, f)| (q - f).abs())
+            .collect::<Vec<_>>();
+        let max_abs_error = errors.iter().cloned().fold(0.0, f64::max);
+        let mean_abs_error = errors.iter().sum::<f64>() / errors.len().max(1) as f64;
+        let magnitude = float.iter().map(|f| f.abs()).fold(0.0, f64::max);
+        let rel_error = max_abs_error / magnitude.max(f64::EPSILON);
+        TensorDiff {
+            max_abs_error,
+            mean_abs_error,
+            rel_error,
+            flagged: rel_error > threshold,
+        }
+    }
+}
+
+/// The error of a model output
+#[derive(Debug, Clone, Serialize, serde::Deserialize)]
+pub struct OutputDiff {
+    /// The index of the output
+    pub output: usize,
+    /// The error of the output
+    #[serde(flatten)]
+    pub diff: TensorDiff,
+}
+
+/// The error of an outlet of a node
+#[derive(Debug, Clone, Serialize, serde::Deserialize)]
+pub struct NodeDiff {
+    /// The index of the node
+    pub node: usize,
+    /// The outlet of the node
+    pub outlet: usize,
+    /// The op of the node
+    pub op: String,
+    /// The error of the outlet
+    #[serde(flatten)]
+    pub diff: TensorDiff,
+}
+
+/// The results of `ezkl diff`
+#[derive(Debug, Clone, Serialize, serde::Deserialize)]
+pub struct DiffReport {
+    /// The relative error over which a tensor is flagged
+    pub threshold: f64,
+    /// The error of each output of the witness
+    pub outputs: Vec<OutputDiff>,
+    /// The error of each node that isn't a constant, if requested
+    pub nodes: Vec<NodeDiff>,
+    /// The number of flagged outputs and nodes
+    pub flagged: usize,
+}
+
+#[derive(Tabled)]
+struct DiffRow {
+    tensor: String,
+    max_abs_error: String,
+    mean_abs_error: String,
+    rel_error: String,
+    flagged: String,
+}
+
+impl DiffRow {
+    fn new(tensor: String, diff: &TensorDiff) -> Self {
+        DiffRow {
+            tensor,
+            max_abs_error: format!("{:.6}", diff.max_abs_error),
+            mean_abs_error: format!("{:.6}", diff.mean_abs_error),
+            rel_error: format!("{:.4}", diff.rel_error),
+            flagged: if diff.flagged {
+                "yes".to_string()
+            } else {
+                String::new()
+            },
+        }
+    }
+}
+
+impl DiffReport {
+    /// Formats the report as a table
+    pub fn table(&self) -> String {
+        let rows = self
+            .outputs
+            .iter()
+            .map(|o| DiffRow::new(format!("output {}", o.output), &o.diff))
+            .chain(self.nodes.iter().map(|n| {
+                DiffRow::new(format!("node {}:{} ({})", n.node, n.outlet, n.op), &n.diff)
+            }));
+        let mut table = tabled::Table::new(rows);
+        table.with(tabled::settings::Style::modern());
+        format!(
+            "{}
+{} tensors over a relative error of {}",
+            table, self.flagged, self.threshold
+        )
+    }
+}
+
+/// Runs the float pass of the model over the data, normalized as the quantized pass sees it
+pub(crate) fn float_forward(
+    model_path: PathBuf,
+    settings_path: PathBuf,
+    data: DataInput,
+) -> Result<Vec<Tensor<f32>>, EZKLError> {
+    let settings = GraphSettings::load(&settings_path)?;
+    let model = Model::from_run_args(&settings.run_args, &model_path)?;
+    let input_shapes = model.graph.input_shapes()?;
+    let mut data = data.load(
+        model.graph.input_names(),
+        &input_shapes,
+        false,
+        None,
+        settings.image_preprocessing.as_ref(),
+    )?;
+    if let (Some(normalization), DataSource::File(inputs)) =
+        (&settings.input_normalization, &mut data.input_data)
+    {
+        for (i, (input, shape)) in inputs.iter_mut().zip(&input_shapes).enumerate() {
+            *input = normalization.apply(i, shape, input)?;
+        }
+    }
+    let mut outputs =
+        Model::run_onnx_predictions(&settings.run_args, &model_path, &[data], input_shapes)?;
+    Ok(outputs.remove(0))
+}
+
+/// Compares the outputs of a witness, and with `verbose` the output of each node of the quantized pass, to those of
+/// the float pass of the model over the same data
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn diff(
+    model_path: PathBuf,
+    settings_path: PathBuf,
+    data: PathBuf,
+    witness_path: PathBuf,
+    verbose: bool,
+    threshold: f64,
+    csv_mapping: Option<PathBuf>,
+    output: Option<PathBuf>,
+) -> Result<String, EZKLError> {
+    use crate::fieldutils::felt_to_f64;
+    use crate::graph::{scale_to_multiplier, DataSource};
+
+    let settings = GraphSettings::load(&settings_path)?;
+    let circuit = GraphCircuit::from_settings(&settings, &model_path, CheckMode::UNSAFE)?;
+    let model = circuit.model();
+    let input_shapes = model.graph.input_shapes()?;
+    let witness = GraphWitness::from_path(witness_path)?;
+
+    // the float pass sees the data as the quantized pass does, after normalization
+    let mut data = load_graph_data(
+        data,
+        model.graph.input_names(),
+        csv_mapping,
+        settings.image_preprocessing.as_ref(),
+    )?;
+    if let (Some(normalization), DataSource::File(inputs)) =
+        (&settings.input_normalization, &mut data.input_data)
+    {
+        for (i, (input, shape)) in inputs.iter_mut().zip(&input_shapes).enumerate() {
+            *input = normalization.apply(i, shape, input)?;
+        }
+    }
+
+    let (float_outputs, float_nodes) = if verbose {
+        let nodes = Model::run_onnx_node_outputs(
+            &settings.run_args,
+            &model_path,
+            &data,
+            input_shapes.clone(),
+        )?;
+        let outputs = model
+            .graph
+            .outputs
+            .iter()
+            .map(|outlet| {
+                nodes
+                    .get(outlet)
+                    .cloned()
+                    .ok_or_else(|| EZKLError::from("the float pass has no value for an output"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        (outputs, nodes)
+    } else {
+        let mut outputs = Model::run_onnx_predictions(
+            &settings.run_args,
+            &model_path,
+            &[data],
+            input_shapes.clone(),
+        )?;
+        (outputs.remove(0), BTreeMap::new())
+    };
+
+    let quantized_outputs = witness.get_float_outputs(&settings.model_output_scales);
+    if quantized_outputs.len() != float_outputs.len() {
+        return Err(format!(
+            "the witness has {} outputs but the model {}",
+            quantized_outputs.len(),
+            float_outputs.len()
+        )
+        .into());
+    }
+    let mut outputs = vec![];
+    for (i, (quantized, float)) in quantized_outputs.iter().zip(&float_outputs).enumerate() {
+        if quantized.len() != float.len() {
+            return Err(format!(
+                "output {} has {} elements in the witness but {} in the float pass",
+                i,
+                quantized.len(),
+                float.len()
+            )
+            .into());
+        }
+        let quantized = quantized.iter().map(|x| *x as f64).collect::<Vec<_>>();
+        let float = float.iter().map(|x| *x as f64).collect::<Vec<_>>();
+        outputs.push(OutputDiff {
+            output: i,
+            diff: TensorDiff::new(&quantized, &float, threshold),
+        });
+    }
+
+    let mut nodes = vec![];
+    if verbose {
+        let inputs = witness
+            .inputs
+            .iter()
+            .zip(&input_shapes)
+            .map(|(input, shape)| Tensor::new(Some(&input[..]), shape))
+            .collect::<Result<Vec<_>, _>>()?;
+        let region_settings =
+            RegionSettings::all_true(settings.run_args.decomp_base, settings.run_args.decomp_legs);
+        let quantized_nodes = model.node_outputs(&settings.run_args, &inputs, region_settings)?;
+        for ((idx, outlet), quantized) in quantized_nodes {
+            let node = &model.graph.nodes[&idx];
+            // nodes the quantized pass reshapes or pads differently aren't comparable
+            let Some(float) = float_nodes.get(&(idx, outlet)) else {
+                continue;
+            };
+            if float.len() != quantized.len() {
+                continue;
+            }
+            let multiplier = scale_to_multiplier(node.out_scales()[outlet]);
+            let quantized = quantized
+                .iter()
+                .map(|x| felt_to_f64(*x) / multiplier)
+                .collect::<Vec<_>>();
+            let float = float.iter().map(|x| *x as f64).collect::<Vec<_>>();
+            nodes.push(NodeDiff {
+                node: idx,
+                outlet,
+                op: node.as_str(),
+                diff: TensorDiff::new(&quantized, &float, threshold),
+            });
+        }
+    }
+
+    let flagged = outputs.iter().filter(|o| o.diff.flagged).count()
+        + nodes.iter().filter(|n| n.diff.flagged).count();
+    let report = DiffReport {
+        threshold,
+        outputs,
+        nodes,
+        flagged,
+    };
+    info!("\n {}", report.table());
+    if flagged > 0 {
+        warn!(
+            "{} tensors exceed a relative error of {}, consider raising the scales",
+            flagged, threshold
+        );
+    }
+
+    let json = serde_json::to_string_pretty(&report)?;
+    if let Some(output) = output {
+        std::fs::write(output, &json)?;
+    }
+    Ok(json)
+}
+
+/// The settings the gen-settings stage of the pipeline writes, calibrate-settings copies them to [DEFAULT_SETTINGS] and
+/// calibrates the copy, such that the fingerprint of each stage is over files only it writes
+pub const PIPELINE_UNCALIBRATED_SETTINGS: &str = "uncalibrated_settings.json";
+/// The manifest of the stages the pipeline ran in an output directory
+pub const PIPELINE_MANIFEST: &str = "pipeline.json";
+
+/// A stage of the pipeline that ran successfully
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PipelineStageRecord {
+    /// the hash of the input files and the arguments of the stage
+    pub fingerprint: String,
+    /// the hash of each file the stage wrote
+    pub outputs: BTreeMap<PathBuf, String>,
+}
+
+/// The stages that last ran successfully in a pipeline output directory, by name
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PipelineManifest {
+    /// the record of each stage
+    pub stages: BTreeMap<String, PipelineStageRecord>,
+}
+
+impl PipelineManifest {
+    /// Loads the manifest, a missing or unreadable manifest reruns every stage
+    fn load(path: &Path) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|manifest| serde_json::from_str(&manifest).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self, path: &Path) -> Result<(), EZKLError> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Whether the stage last ran with the same fingerprint and the files it wrote are unchanged since
+    fn is_up_to_date(&self, stage: PipelineStage, fingerprint: &str) -> bool {
+        self.stages.get(&stage.to_string()).is_some_and(|record| {
+            record.fingerprint == fingerprint
+                && record
+                    .outputs
+                    .iter()
+                    .all(|(path, hash)| get_file_hash(path).is_ok_and(|h| &h == hash))
+        })
+    }
+}
+
+/// The stages the pipeline ran and those it skipped as up to date
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PipelineReport {
+    /// the stages that ran, in order
+    pub ran: Vec<PipelineStage>,
+    /// the stages skipped as up to date, in order
+    pub skipped: Vec<PipelineStage>,
+}
+
+/// The inputs and the artifacts of a pipeline run
+struct Pipeline {
+    model: PathBuf,
+    data: PathBuf,
+    run_args: RunArgs,
+    target: CalibrationTarget,
+    srs_path: Option<PathBuf>,
+    uncalibrated_settings: PathBuf,
+    settings: PathBuf,
+    compiled_circuit: PathBuf,
+    vk_path: PathBuf,
+    pk_path: PathBuf,
+}
+
+impl Pipeline {
+    fn srs(&self) -> Result<PathBuf, EZKLError> {
+        let settings = GraphSettings::load(&self.settings)?;
+        Ok(get_srs_path(
+            settings.run_args.logrows,
+            self.srs_path.clone(),
+            settings.run_args.commitment.into(),
+        ))
+    }
+
+    /// The fingerprint of the inputs of a stage and the files it writes
+    fn stage_inputs(&self, stage: PipelineStage) -> Result<(String, Vec<PathBuf>), EZKLError> {
+        let (inputs, outputs) = match stage {
+            PipelineStage::GenSettings => (
+                vec![
+                    get_file_hash(&self.model)?,
+                    serde_json::to_string(&self.run_args)?,
+                ],
+                vec![self.uncalibrated_settings.clone()],
+            ),
+            PipelineStage::CalibrateSettings => (
+                vec![
+                    get_file_hash(&self.model)?,
+                    get_file_hash(&self.uncalibrated_settings)?,
+                    get_file_hash(&self.data)?,
+                    self.target.to_string(),
+                ],
+                vec![self.settings.clone()],
+            ),
+            PipelineStage::CompileCircuit => (
+                vec![get_file_hash(&self.model)?, get_file_hash(&self.settings)?],
+                vec![self.compiled_circuit.clone()],
+            ),
+            PipelineStage::GetSrs => {
+                let srs = self.srs()?;
+                (vec![srs.display().to_string()], vec![srs])
+            }
+            PipelineStage::Setup => (
+                vec![
+                    get_file_hash(&self.compiled_circuit)?,
+                    get_file_hash(&self.srs()?)?,
+                ],
+                vec![self.vk_path.clone(), self.pk_path.clone()],
+            ),
+        };
+        Ok((sha256::digest(inputs.join("\n")), outputs))
+    }
+
+    async fn run_stage(&self, stage: PipelineStage) -> Result<(), EZKLError> {
+        match stage {
+            PipelineStage::GenSettings => {
+                gen_circuit_settings(
+                    self.model.clone(),
+                    self.uncalibrated_settings.clone(),
+                    self.run_args.clone(),
+                    None,
+                    None,
+                    None,
+                    false,
+                    None,
+                    false,
+                    false,
+                )?;
+            }
+            PipelineStage::CalibrateSettings => {
+                std::fs::copy(&self.uncalibrated_settings, &self.settings)?;
+                calibrate(
+                    self.model.clone(),
+                    self.data.clone().into(),
+                    self.settings.clone(),
+                    self.target,
+                    DEFAULT_LOOKUP_SAFETY_MARGIN.parse().unwrap(),
+                    None,
+                    DEFAULT_SCALE_REBASE_MULTIPLIERS
+                        .split(',')
+                        .map(|x| x.parse().unwrap())
+                        .collect(),
+                    DEFAULT_ONLY_RANGE_CHECK_REBASE.parse().unwrap(),
+                    None,
+                    None,
+                )
+                .await?;
+            }
+            PipelineStage::CompileCircuit => {
+                compile_circuit(
+                    self.model.clone(),
+                    self.compiled_circuit.clone(),
+                    self.settings.clone(),
+                    None,
+                    None,
+                )?;
+            }
+            PipelineStage::GetSrs => {
+                get_srs_cmd(
+                    self.srs_path.clone(),
+                    Some(self.settings.clone()),
+                    None,
+                    None,
+                )
+                .await?;
+            }
+            PipelineStage::Setup => {
+                setup(
+                    self.compiled_circuit.clone(),
+                    self.srs_path.clone(),
+                    self.vk_path.clone(),
+                    self.pk_path.clone(),
+                    None,
+                    DEFAULT_DISABLE_SELECTOR_COMPRESSION.parse().unwrap(),
+                    None,
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+pub(crate) async fn pipeline(
+    model: PathBuf,
+    data: PathBuf,
+    output_dir: PathBuf,
+    run_args: RunArgs,
+    target: CalibrationTarget,
+    srs_path: Option<PathBuf>,
+    stop_after: PipelineStage,
+) -> Result<PipelineReport, EZKLError> {
+    std::fs::create_dir_all(&output_dir)?;
+    let pipeline = Pipeline {
+        model,
+        data,
+        run_args,
+        target,
+        srs_path,
+        uncalibrated_settings: output_dir.join(PIPELINE_UNCALIBRATED_SETTINGS),
+        settings: output_dir.join(DEFAULT_SETTINGS),
+        compiled_circuit: output_dir.join(DEFAULT_COMPILED_CIRCUIT),
+        vk_path: output_dir.join(DEFAULT_VK),
+        pk_path: output_dir.join(DEFAULT_PK),
+    };
+    let manifest_path = output_dir.join(PIPELINE_MANIFEST);
+    let mut manifest = PipelineManifest::load(&manifest_path);
+    let mut report = PipelineReport::default();
+
+    for stage in PipelineStage::ALL.into_iter().filter(|s| *s <= stop_after) {
+        let run = async {
+            let (fingerprint, outputs) = pipeline.stage_inputs(stage)?;
+            if manifest.is_up_to_date(stage, &fingerprint) {
+                info!("{} is up to date, skipping", stage);
+                return Ok(false);
+            }
+            // forget the stage until it succeeds, such that it reruns if it fails midway
+            manifest.stages.remove(&stage.to_string());
+            manifest.save(&manifest_path)?;
+
+            info!("running {}...", stage);
+            pipeline.run_stage(stage).await?;
+
+            let outputs = outputs
+                .into_iter()
+                .map(|path| get_file_hash(&path).map(|hash| (path, hash)))
+                .collect::<Result<_, EZKLError>>()?;
+            manifest.stages.insert(
+                stage.to_string(),
+                PipelineStageRecord {
+                    fingerprint,
+                    outputs,
+                },
+            );
+            manifest.save(&manifest_path)?;
+            Ok::<bool, EZKLError>(true)
+        };
+        let ran = run.await.map_err(|e| ExecutionError::PipelineStageFailed {
+            stage,
+            error: Box::new(e),
+        })?;
+        if ran {
+            report.ran.push(stage);
+        } else {
+            report.skipped.push(stage);
+        }
+    }
+
+    Ok(report)
+}
+
 pub(crate) fn verify_aggr(
     proof_path: PathBuf,
     vk_path: PathBuf,
@@ -2416,10 +6832,12 @@ pub(crate) fn verify_aggr(
     logrows: u32,
     reduced_srs: bool,
     commitment: Commitments,
+    transcript: Option<TranscriptType>,
 ) -> Result<bool, EZKLError> {
     match commitment {
         Commitments::KZG => {
             let proof = Snark::load::<KZGCommitmentScheme<Bn256>>(&proof_path)?;
+            proof.check_transcript(transcript)?;
             let params: ParamsKZG<Bn256> = if reduced_srs {
                 // only need G_0 for the verification with shplonk
                 load_params_verifier::<KZGCommitmentScheme<Bn256>>(srs_path, 1, Commitments::KZG)?
@@ -2440,6 +6858,17 @@ pub(crate) fn verify_aggr(
                     AggregationCircuit,
                     _,
                 >(proof_path, (), vk_path, &params, logrows),
+                TranscriptType::Blake => {
+                    verify_commitment::<
+                        KZGCommitmentScheme<Bn256>,
+                        VerifierSHPLONK<'_, Bn256>,
+                        _,
+                        KZGSingleStrategy<_>,
+                        Blake2bRead<_, _, Challenge255<_>>,
+                        AggregationCircuit,
+                        _,
+                    >(proof_path, (), vk_path, &params, logrows)
+                }
                 TranscriptType::Poseidon => {
                     verify_commitment::<
                         KZGCommitmentScheme<Bn256>,
@@ -2455,6 +6884,7 @@ pub(crate) fn verify_aggr(
         }
         Commitments::IPA => {
             let proof = Snark::load::<IPACommitmentScheme<G1Affine>>(&proof_path)?;
+            proof.check_transcript(transcript)?;
             let params: ParamsIPA<_> = load_params_verifier::<IPACommitmentScheme<G1Affine>>(
                 srs_path,
                 logrows,
@@ -2470,6 +6900,17 @@ pub(crate) fn verify_aggr(
                     AggregationCircuit,
                     _,
                 >(proof_path, (), vk_path, &params, logrows),
+                TranscriptType::Blake => {
+                    verify_commitment::<
+                        IPACommitmentScheme<G1Affine>,
+                        VerifierIPA<_>,
+                        _,
+                        IPASingleStrategy<_>,
+                        Blake2bRead<_, _, Challenge255<_>>,
+                        AggregationCircuit,
+                        _,
+                    >(proof_path, (), vk_path, &params, logrows)
+                }
                 TranscriptType::Poseidon => {
                     verify_commitment::<
                         IPACommitmentScheme<G1Affine>,
@@ -2491,13 +6932,19 @@ pub(crate) fn load_params_verifier<Scheme: CommitmentScheme>(
     srs_path: Option<PathBuf>,
     logrows: u32,
     commitment: Commitments,
-) -> Result<Scheme::ParamsVerifier, EZKLError> {
-    let srs_path = get_srs_path(logrows, srs_path, commitment);
+) -> Result<Scheme::ParamsVerifier, EZKLError>
+where
+    Scheme::ParamsVerifier: SrsIntegrity,
+{
+    let srs_path = find_srs_path(logrows, srs_path, commitment);
     let mut params = load_srs_verifier::<Scheme>(srs_path)?;
     if logrows < params.k() {
         info!("downsizing params to {} logrows", logrows);
         params.downsize(logrows);
     }
+    if !skip_srs_check() {
+        params.check_consistency()?;
+    }
     Ok(params)
 }
 
@@ -2506,12 +6953,18 @@ pub(crate) fn load_params_prover<Scheme: CommitmentScheme>(
     srs_path: Option<PathBuf>,
     logrows: u32,
     commitment: Commitments,
-) -> Result<Scheme::ParamsProver, EZKLError> {
-    let srs_path = get_srs_path(logrows, srs_path, commitment);
+) -> Result<Scheme::ParamsProver, EZKLError>
+where
+    Scheme::ParamsProver: SrsIntegrity,
+{
+    let srs_path = find_srs_path(logrows, srs_path, commitment);
     let mut params = load_srs_prover::<Scheme>(srs_path)?;
     if logrows < params.k() {
         info!("downsizing params to {} logrows", logrows);
         params.downsize(logrows);
     }
+    if !skip_srs_check() {
+        params.check_consistency()?;
+    }
     Ok(params)
 }
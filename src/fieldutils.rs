@@ -44,6 +44,106 @@ pub fn felt_to_integer_rep<F: PrimeField + PartialOrd + Field>(x: F) -> IntegerR
     }
 }
 
+/// Converts a PrimeField element to an [IntegerRep], or returns `None` if the element lies outside of the range of
+/// [IntegerRep] about zero, which [felt_to_integer_rep] would silently wrap.
+pub fn checked_felt_to_integer_rep<F: PrimeField + PartialOrd + Field>(x: F) -> Option<IntegerRep> {
+    // the magnitude of IntegerRep::MIN
+    let min_magnitude = IntegerRep::MAX as u128 + 1;
+    if x > F::from_u128(IntegerRep::MAX as u128) {
+        let rep = (-x).to_repr();
+        let negtmp: &[u8] = rep.as_ref();
+        if negtmp[16..].iter().any(|b| *b != 0) {
+            return None;
+        }
+        let lower_128: u128 = u128::from_le_bytes(negtmp[..16].try_into().unwrap());
+        match lower_128.cmp(&min_magnitude) {
+            std::cmp::Ordering::Less => Some(-(lower_128 as IntegerRep)),
+            std::cmp::Ordering::Equal => Some(IntegerRep::MIN),
+            std::cmp::Ordering::Greater => None,
+        }
+    } else {
+        Some(felt_to_integer_rep(x))
+    }
+}
+
+/// A field element outside of the integer range declared by a circuit
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum FeltRangeError {
+    /// The element is neither an integer of at most `max_abs` nor the negation of one
+    #[error("field element {0} lies outside of the integer range [-{1}, {1}]")]
+    OutOfRange(String, IntegerRep),
+    /// The difference of two elements lies outside of the range, such that its sign isn't that of the comparison
+    #[error("the difference of {0} and {1} lies outside of the integer range [-{2}, {2}]")]
+    DifferenceOutOfRange(IntegerRep, IntegerRep, IntegerRep),
+    /// The range is negative or wraps about the field midpoint, such that a sign isn't defined
+    #[error("the integer range [-{0}, {0}] is empty or wraps around the field")]
+    InvalidRange(IntegerRep),
+}
+
+/// The bound of the integer range `[-max_abs, max_abs]` as a field element, if the range doesn't wrap around the field
+fn range_bound<F: PrimeField + PartialOrd + Field>(
+    max_abs: IntegerRep,
+) -> Result<F, FeltRangeError> {
+    if max_abs < 0 {
+        return Err(FeltRangeError::InvalidRange(max_abs));
+    }
+    let bound = F::from_u128(max_abs as u128);
+    // the bound is reduced in fields smaller than it, and the range wraps when 2·max_abs >= p
+    if lower_128(bound) != max_abs as u128 || (bound != F::ZERO && bound >= -bound) {
+        return Err(FeltRangeError::InvalidRange(max_abs));
+    }
+    Ok(bound)
+}
+
+/// The lower 128 bits of the representation of a PrimeField element.
+fn lower_128<F: PrimeField>(x: F) -> u128 {
+    let rep = x.to_repr();
+    let tmp: &[u8] = rep.as_ref();
+    u128::from_le_bytes(tmp[..16].try_into().unwrap())
+}
+
+/// Converts a PrimeField element to the integer it represents in the range `[-max_abs, max_abs]` a circuit declares
+/// for its values, elements above the field midpoint being negative. Unlike [felt_to_integer_rep], which takes the
+/// elements above [IntegerRep::MAX] to be negative, an element outside of the range is an error rather than a guess.
+pub fn felt_to_integer_rep_in_range<F: PrimeField + PartialOrd + Field>(
+    x: F,
+    max_abs: IntegerRep,
+) -> Result<IntegerRep, FeltRangeError> {
+    let bound: F = range_bound(max_abs)?;
+    if x <= bound {
+        Ok(lower_128(x) as IntegerRep)
+    } else if -x <= bound {
+        Ok(-(lower_128(-x) as IntegerRep))
+    } else {
+        Err(FeltRangeError::OutOfRange(format!("{:?}", x), max_abs))
+    }
+}
+
+/// The sign of the integer a PrimeField element represents in the range `[-max_abs, max_abs]`, as -1, 0 or 1, see
+/// [felt_to_integer_rep_in_range].
+pub fn felt_sign<F: PrimeField + PartialOrd + Field>(
+    x: F,
+    max_abs: IntegerRep,
+) -> Result<IntegerRep, FeltRangeError> {
+    Ok(felt_to_integer_rep_in_range(x, max_abs)?.signum())
+}
+
+/// Compares the integers two PrimeField elements represent in the range `[-max_abs, max_abs]`. Circuits compare by
+/// the sign of `a - b`, which wraps around the field and is that of the comparison only when the difference lies in
+/// the range as well, hence a difference outside of it is an error too.
+pub fn felt_cmp<F: PrimeField + PartialOrd + Field>(
+    a: F,
+    b: F,
+    max_abs: IntegerRep,
+) -> Result<std::cmp::Ordering, FeltRangeError> {
+    let a = felt_to_integer_rep_in_range(a, max_abs)?;
+    let b = felt_to_integer_rep_in_range(b, max_abs)?;
+    match a.checked_sub(b) {
+        Some(diff) if diff.unsigned_abs() <= max_abs as u128 => Ok(a.cmp(&b)),
+        _ => Err(FeltRangeError::DifferenceOutOfRange(a, b, max_abs)),
+    }
+}
+
 #[cfg(test)]
 mod test {
 
@@ -73,4 +173,105 @@ mod test {
             assert_eq!(x, xf);
         }
     }
+
+    #[test]
+    fn checkedfelttointegerrep() {
+        for x in [
+            0,
+            -1,
+            1,
+            IntegerRep::MAX,
+            -IntegerRep::MAX,
+            -(2_i128.pow(100)),
+        ] {
+            let fieldx: F = integer_rep_to_felt::<F>(x);
+            assert_eq!(checked_felt_to_integer_rep(fieldx), Some(x));
+        }
+        let min: F = -F::from_u128(IntegerRep::MAX as u128 + 1);
+        assert_eq!(checked_felt_to_integer_rep(min), Some(IntegerRep::MIN));
+        // past the range of IntegerRep on either side of zero
+        let over = F::from_u128(IntegerRep::MAX as u128) + F::ONE;
+        assert_eq!(checked_felt_to_integer_rep(over), None);
+        assert_eq!(checked_felt_to_integer_rep(-over - F::ONE), None);
+        // about the field midpoint
+        let midpoint = F::from_u128(2).invert().unwrap();
+        assert_eq!(checked_felt_to_integer_rep(midpoint), None);
+        assert_eq!(checked_felt_to_integer_rep(-midpoint), None);
+    }
+
+    #[test]
+    fn feltsignandcmpaboutrangebounds() {
+        use rand::Rng;
+        use std::cmp::Ordering;
+
+        for max_abs in [0, 1, 2_i128.pow(28) - 1, IntegerRep::MAX] {
+            // the boundary region of the range on either side of zero, and random values within it
+            let boundary = (0..4)
+                .filter(|k| *k <= max_abs)
+                .flat_map(|k| [max_abs - k, -(max_abs - k)]);
+            let random = (0..64).map(|_| rand::rngs::OsRng.gen_range(-max_abs..=max_abs));
+            let values = boundary.chain(random).collect::<Vec<_>>();
+            for x in &values {
+                let fieldx: F = integer_rep_to_felt(*x);
+                assert_eq!(felt_to_integer_rep_in_range(fieldx, max_abs), Ok(*x));
+                assert_eq!(felt_sign(fieldx, max_abs), Ok(x.signum()));
+                for y in &values {
+                    let fieldy: F = integer_rep_to_felt(*y);
+                    let cmp = felt_cmp(fieldx, fieldy, max_abs);
+                    match x.checked_sub(*y) {
+                        Some(diff) if diff.unsigned_abs() <= max_abs as u128 => {
+                            assert_eq!(cmp, Ok(x.cmp(y)))
+                        }
+                        _ => assert_eq!(
+                            cmp,
+                            Err(FeltRangeError::DifferenceOutOfRange(*x, *y, max_abs))
+                        ),
+                    }
+                }
+            }
+
+            // just past the range on either side of zero
+            let over = F::from_u128(max_abs as u128) + F::ONE;
+            assert!(matches!(
+                felt_sign(over, max_abs),
+                Err(FeltRangeError::OutOfRange(_, _))
+            ));
+            assert!(matches!(
+                felt_sign(-over, max_abs),
+                Err(FeltRangeError::OutOfRange(_, _))
+            ));
+            assert!(felt_cmp(over, F::ZERO, max_abs).is_err());
+        }
+
+        // negative ranges hold no values
+        assert_eq!(
+            felt_sign(F::ZERO, -1),
+            Err(FeltRangeError::InvalidRange(-1))
+        );
+        assert_eq!(felt_cmp(F::ONE, F::ZERO, 1), Ok(Ordering::Greater));
+        assert_eq!(felt_cmp(-F::ONE, F::ZERO, 1), Ok(Ordering::Less));
+        assert_eq!(
+            felt_cmp(F::ONE, -F::ONE, 1),
+            Err(FeltRangeError::DifferenceOutOfRange(1, -1, 1))
+        );
+    }
+
+    #[test]
+    fn hugepositivefeltisnotnegative() {
+        // a positive value accumulated past IntegerRep::MAX, which felt_to_integer_rep takes to be negative
+        let huge = F::from_u128(IntegerRep::MAX as u128) + F::from(7);
+        assert_eq!(felt_to_integer_rep(huge).signum(), -1);
+
+        // it lies past any range of IntegerRep and is an error rather than a negative sign
+        assert!(matches!(
+            felt_sign(huge, IntegerRep::MAX),
+            Err(FeltRangeError::OutOfRange(_, IntegerRep::MAX))
+        ));
+        assert!(felt_cmp(huge, F::ZERO, IntegerRep::MAX).is_err());
+
+        // and large values within the range keep their sign
+        let large: F = integer_rep_to_felt(2_i128.pow(120));
+        assert_eq!(felt_sign(large, IntegerRep::MAX), Ok(1));
+        assert_eq!(felt_sign(-large, IntegerRep::MAX), Ok(-1));
+    }
 }
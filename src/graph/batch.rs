@@ -0,0 +1,226 @@
+//! Families of circuits of one model at the batch sizes of the powers of two up to a max, such that a proof only
+//! lays out as many samples as fit the smallest batch of the family, rather than the largest (see
+//! `compile-batch-family`).
+//!
+//! The circuits of a family are compiled with a [crate::RunArgs::batch_mask] and at the same logrows, such that they
+//! share the srs and the commitments of the params of the model. The samples proven are padded to the batch size of
+//! the member they are proven with by the padding sample of the family, whose outputs are zeroed by the mask in the
+//! instances, the mask itself being exposed as the last output.
+
+use super::errors::GraphError;
+use super::input::{FileSource, FileSourceInner};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// The file the family is saved to in its directory
+pub const FAMILY_FILE: &str = "family.json";
+
+/// The batch sizes of a family, the powers of two up to `max_batch_size`
+pub fn batch_sizes(max_batch_size: usize) -> Result<Vec<usize>, GraphError> {
+    if max_batch_size == 0 {
+        return Err(GraphError::InvalidBatchFamily(
+            "the max batch size must be >= 1".into(),
+        ));
+    }
+    Ok(std::iter::successors(Some(1usize), |n| n.checked_mul(2))
+        .take_while(|n| *n <= max_batch_size)
+        .collect())
+}
+
+/// A family of circuits of one model at several batch sizes, saved with their settings, compiled circuits and keys in
+/// a directory
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BatchFamily {
+    /// the batch sizes of the members, in increasing order
+    pub batch_sizes: Vec<usize>,
+    /// the logrows all the members are laid out in
+    pub logrows: u32,
+    /// the number of values of a sample in each input of the model, the mask aside
+    pub sample_lens: Vec<usize>,
+    /// the sample the batches are padded with, as the values of each input of the model
+    pub padding: FileSource,
+}
+
+impl BatchFamily {
+    /// The path of the settings of the member of `batch_size` in the directory of the family
+    pub fn settings_path(dir: &Path, batch_size: usize) -> PathBuf {
+        dir.join(format!("settings_{}.json", batch_size))
+    }
+
+    /// The path of the compiled circuit of the member of `batch_size` in the directory of the family
+    pub fn compiled_circuit_path(dir: &Path, batch_size: usize) -> PathBuf {
+        dir.join(format!("network_{}.compiled", batch_size))
+    }
+
+    /// The path of the proving key of the member of `batch_size` in the directory of the family
+    pub fn pk_path(dir: &Path, batch_size: usize) -> PathBuf {
+        dir.join(format!("pk_{}.key", batch_size))
+    }
+
+    /// The path of the verification key of the member of `batch_size` in the directory of the family
+    pub fn vk_path(dir: &Path, batch_size: usize) -> PathBuf {
+        dir.join(format!("vk_{}.key", batch_size))
+    }
+
+    /// The largest batch size of the family
+    pub fn max_batch_size(&self) -> usize {
+        self.batch_sizes.last().copied().unwrap_or_default()
+    }
+
+    /// The batch size of the smallest member `num_samples` fit
+    pub fn member(&self, num_samples: usize) -> Result<usize, GraphError> {
+        if num_samples == 0 {
+            return Err(GraphError::InvalidBatchFamily("no samples to prove".into()));
+        }
+        self.batch_sizes
+            .iter()
+            .copied()
+            .find(|n| *n >= num_samples)
+            .ok_or_else(|| {
+                GraphError::InvalidBatchFamily(format!(
+                    "{} samples don't fit the max batch size {}",
+                    num_samples,
+                    self.max_batch_size()
+                ))
+            })
+    }
+
+    /// The number of samples of the values of each input of the model
+    pub fn num_samples(&self, data: &FileSource) -> Result<usize, GraphError> {
+        if data.len() != self.sample_lens.len() {
+            return Err(GraphError::InvalidBatchFamily(format!(
+                "{} inputs for a model of {} inputs",
+                data.len(),
+                self.sample_lens.len()
+            )));
+        }
+        let mut num_samples = None;
+        for (i, (values, sample_len)) in data.iter().zip(&self.sample_lens).enumerate() {
+            if *sample_len == 0 || values.len() % sample_len != 0 {
+                return Err(GraphError::InvalidBatchFamily(format!(
+                    "input {} of {} values isn't made of samples of {} values",
+                    i,
+                    values.len(),
+                    sample_len
+                )));
+            }
+            let samples = values.len() / sample_len;
+            if num_samples.is_some_and(|n| n != samples) {
+                return Err(GraphError::InvalidBatchFamily(format!(
+                    "input {} has {} samples, the inputs before it {}",
+                    i,
+                    samples,
+                    num_samples.unwrap_or_default()
+                )));
+            }
+            num_samples = Some(samples);
+        }
+        Ok(num_samples.unwrap_or_default())
+    }
+
+    /// Pads the samples of `data` to `batch_size` with the padding sample, and appends the mask of the samples
+    pub fn pad(&self, data: &FileSource, batch_size: usize) -> Result<FileSource, GraphError> {
+        let num_samples = self.num_samples(data)?;
+        if num_samples > batch_size {
+            return Err(GraphError::InvalidBatchFamily(format!(
+                "{} samples don't fit the batch size {}",
+                num_samples, batch_size
+            )));
+        }
+        let mut padded = data
+            .iter()
+            .zip(&self.padding)
+            .map(|(values, padding)| {
+                let mut values = values.clone();
+                for _ in num_samples..batch_size {
+                    values.extend(padding.iter().cloned());
+                }
+                values
+            })
+            .collect::<FileSource>();
+        padded.push(
+            (0..batch_size)
+                .map(|i| FileSourceInner::Bool(i < num_samples))
+                .collect(),
+        );
+        Ok(padded)
+    }
+
+    /// Saves the family to its directory
+    pub fn save(&self, dir: &Path) -> Result<(), GraphError> {
+        let path = dir.join(FAMILY_FILE);
+        let json = serde_json::to_string_pretty(self)?;
+        std::fs::write(&path, json)
+            .map_err(|e| GraphError::ReadWriteFileError(path.display().to_string(), e.to_string()))
+    }
+
+    /// Loads the family of a directory
+    pub fn load(dir: &Path) -> Result<Self, GraphError> {
+        let path = dir.join(FAMILY_FILE);
+        let json = std::fs::read_to_string(&path).map_err(|e| {
+            GraphError::ReadWriteFileError(path.display().to_string(), e.to_string())
+        })?;
+        Ok(serde_json::from_str(&json)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn family() -> BatchFamily {
+        BatchFamily {
+            batch_sizes: batch_sizes(8).unwrap(),
+            logrows: 17,
+            sample_lens: vec![2, 1],
+            padding: vec![
+                vec![FileSourceInner::Float(0.5), FileSourceInner::Float(-0.5)],
+                vec![FileSourceInner::Float(0.0)],
+            ],
+        }
+    }
+
+    fn samples(n: usize) -> FileSource {
+        let floats = |len: usize| (0..len).map(|i| FileSourceInner::Float(i as f64)).collect();
+        vec![floats(2 * n), floats(n)]
+    }
+
+    #[test]
+    fn smallest_member_fitting_the_samples() {
+        assert_eq!(batch_sizes(8).unwrap(), vec![1, 2, 4, 8]);
+        assert_eq!(batch_sizes(12).unwrap(), vec![1, 2, 4, 8]);
+        assert!(batch_sizes(0).is_err());
+
+        let family = family();
+        assert_eq!(family.member(1).unwrap(), 1);
+        assert_eq!(family.member(3).unwrap(), 4);
+        assert_eq!(family.member(5).unwrap(), 8);
+        assert_eq!(family.member(8).unwrap(), 8);
+        assert!(family.member(0).is_err());
+        assert!(family.member(9).is_err());
+    }
+
+    #[test]
+    fn samples_padded_and_masked() {
+        let family = family();
+        assert_eq!(family.num_samples(&samples(5)).unwrap(), 5);
+        // the inputs hold whole samples, as many in each
+        assert!(family.num_samples(&samples(5)[..1].to_vec()).is_err());
+        let mut uneven = samples(5);
+        uneven[1].pop();
+        assert!(family.num_samples(&uneven).is_err());
+
+        let padded = family.pad(&samples(5), 8).unwrap();
+        assert_eq!(family.num_samples(&padded[..2].to_vec()).unwrap(), 8);
+        assert_eq!(padded[0][..10], samples(5)[0][..]);
+        assert_eq!(
+            padded[0][10..],
+            [0.5, -0.5, 0.5, -0.5, 0.5, -0.5].map(FileSourceInner::Float)
+        );
+        assert_eq!(
+            padded[2],
+            [true, true, true, true, true, false, false, false].map(FileSourceInner::Bool)
+        );
+        assert!(family.pad(&samples(5), 4).is_err());
+    }
+}
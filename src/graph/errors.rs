@@ -75,6 +75,9 @@ pub enum GraphError {
     /// Bad conversion
     #[error("invalid conversion: {0}")]
     InvalidConversion(#[from] Infallible),
+    /// A value computed by a node lies outside of the range of the integer representation
+    #[error("field element {0} computed by node {2} ({1}) overflows the integer representation, try lowering the scales")]
+    IntegerRepOverflow(String, String, usize),
     /// Circuit error
     #[error("[circuit] {0}")]
     CircuitError(#[from] crate::circuit::CircuitError),
@@ -107,6 +110,9 @@ pub enum GraphError {
     /// Missing instances
     #[error("missing instances")]
     MissingInstances,
+    /// Instances that aren't those of a circuit, see [crate::graph::instances]
+    #[error("invalid instances: {0}")]
+    InvalidInstances(String),
     /// Missing constants
     #[error("missing constants")]
     MissingConstants,
@@ -143,4 +149,123 @@ pub enum GraphError {
     /// Invalid RunArg
     #[error("invalid RunArgs: {0}")]
     InvalidRunArgs(String),
+    /// Invalid NumPy array file
+    #[error("invalid numpy file: {0}")]
+    InvalidNpy(String),
+    /// Invalid csv file or csv mapping spec
+    #[error("invalid csv: {0}")]
+    InvalidCsv(String),
+    /// Invalid image file or image preprocessing spec
+    #[error("invalid image: {0}")]
+    InvalidImage(String),
+    /// Invalid input normalization
+    #[error("invalid input normalization: {0}")]
+    InvalidNormalization(String),
+    /// Invalid already quantized field element inputs
+    #[error("invalid field element inputs: {0}")]
+    InvalidFieldInput(String),
+    /// Module errors
+    #[error("[module] {0}")]
+    ModuleError(#[from] crate::circuit::modules::errors::ModuleError),
+    /// Invalid merkle tree or inclusion path
+    #[error("invalid merkle inclusion: {0}")]
+    InvalidMerkle(String),
+    /// Invalid signature over the inputs
+    #[error("invalid input signature: {0}")]
+    InvalidSignature(String),
+    /// An output violates the bounds asserted on it
+    #[error("output {0} is out of bounds: {1}")]
+    OutputOutOfBounds(usize, String),
+    /// Invalid nullifier of the inputs
+    #[error("invalid nullifier: {0}")]
+    InvalidNullifier(String),
+    /// Invalid encryption of the outputs
+    #[error("invalid encryption: {0}")]
+    InvalidEncryption(String),
+    /// Invalid blinding of the PolyCommit commitments
+    #[error("invalid blinding: {0}")]
+    InvalidBlinding(String),
+    /// Invalid redaction of a witness, or a redacted witness used where the full witness is needed
+    #[error("invalid redaction: {0}")]
+    InvalidRedaction(String),
+    /// The layout was abandoned as the proof was cancelled
+    #[error("cancelled")]
+    Cancelled,
+    /// A witness doesn't match the settings it was generated with
+    #[error("inconsistent witness: {0}")]
+    InconsistentWitness(String),
+    /// An input array doesn't have the shape of the model input it feeds
+    #[error("input {input} has the wrong shape: expected {expected:?}, got {actual:?}")]
+    InvalidInputShape {
+        /// the index of the input
+        input: usize,
+        /// the shape of the model input
+        expected: Vec<usize>,
+        /// the shape of the array
+        actual: Vec<usize>,
+    },
+    /// The number of input arrays differs from the number of model inputs
+    #[error("the model has {expected} inputs but {actual} were given")]
+    InvalidInputCount {
+        /// the number of model inputs
+        expected: usize,
+        /// the number of arrays
+        actual: usize,
+    },
+    /// A settings, input or witness .json doesn't deserialize into its type
+    #[error("invalid json at `{path}`: {reason}")]
+    InvalidJson {
+        /// the path of the offending value, as `run_args.logrows`
+        path: String,
+        /// why it is invalid
+        reason: String,
+    },
+    /// The outputs of a circuit can't be linked to the inputs of the next, see [crate::graph::link]
+    #[error("the circuits can't be linked: {0}")]
+    UnlinkableCircuits(String),
+    /// A linked output of a proof isn't the input of the next proof it is linked to
+    #[error("output {output} of proof {proof} doesn't match input {input} of proof {}", .proof + 1)]
+    BrokenLink {
+        /// the index of the proof in the chain
+        proof: usize,
+        /// the output of the proof
+        output: usize,
+        /// the input of the next proof
+        input: usize,
+    },
+    /// The commitment groups of the params are invalid, see [crate::graph::param_groups]
+    #[error("invalid commitment groups: {0}")]
+    InvalidCommitmentGroups(String),
+    /// The commitments of a group of params of a proof aren't those it is checked against
+    #[error("the commitments of the params of group {group} don't match those of the proof")]
+    CommitmentGroupMismatch {
+        /// the name of the group
+        group: String,
+    },
+    /// The values of a public output don't fit the limbs of its instances, or the instances aren't a packing of values,
+    /// see [crate::graph::limbs]
+    #[error("invalid limb packing: {0}")]
+    InvalidLimbPacking(String),
+    /// The binary sidecar of a witness doesn't hold the tensors its json indexes, see [crate::graph::sidecar]
+    #[error("invalid witness sidecar: {0}")]
+    InvalidWitnessSidecar(String),
+    /// Invalid session digests, see [crate::graph::session]
+    #[error("invalid session: {0}")]
+    InvalidSession(String),
+    /// The previous digest of a proof of a session isn't the digest of the proof it follows
+    #[error("proof {proof} isn't chained to the proof before it in the session")]
+    BrokenSessionChain {
+        /// the index of the proof in the session
+        proof: usize,
+    },
+    /// Samples or members of a family of batch sizes that don't fit it, see [crate::graph::batch]
+    #[error("invalid batch family: {0}")]
+    InvalidBatchFamily(String),
+    /// Weights overriding the initializers of a model that don't match them, see [crate::graph::weights]
+    #[error("invalid weights: {}", .0.join("; "))]
+    InvalidWeights(Vec<String>),
+    /// An encrypted compiled circuit can't be decrypted, see [crate::pfsys::encryption]
+    #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+    #[error("[encryption] {0}")]
+    Encryption(#[from] crate::pfsys::encryption::EncryptionError),
 }
@@ -1,7 +1,10 @@
 use super::errors::GraphError;
-use super::quantize_float;
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+use super::schema::FeltSchema;
+use super::schema::{from_json_slice, JsonArtifact};
+use super::{quantize_float_rounded, QuantRounding};
 use crate::circuit::InputType;
-use crate::fieldutils::integer_rep_to_felt;
+use crate::fieldutils::{integer_rep_to_felt, IntegerRep};
 #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
 use crate::graph::postgres::Client;
 #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
@@ -14,6 +17,12 @@ use pyo3::prelude::*;
 use pyo3::types::PyDict;
 #[cfg(feature = "python-bindings")]
 use pyo3::ToPyObject;
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+use schemars::gen::SchemaGenerator;
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+use schemars::schema::{InstanceType, Schema, SchemaObject, SubschemaValidation};
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+use schemars::JsonSchema;
 use serde::ser::SerializeStruct;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::io::BufReader;
@@ -92,6 +101,10 @@ impl<'de> Deserialize<'de> for FileSourceInner {
         if let Ok(t) = field_try {
             return Ok(FileSourceInner::Field(t));
         }
+        let string_try: Result<String, _> = serde_json::from_str(this_json.get());
+        if let Some(t) = string_try.ok().as_deref().and_then(felt_from_str) {
+            return Ok(FileSourceInner::Field(t));
+        }
 
         Err(serde::de::Error::custom(
             "failed to deserialize FileSourceInner",
@@ -99,6 +112,72 @@ impl<'de> Deserialize<'de> for FileSourceInner {
     }
 }
 
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+impl JsonSchema for FileSourceInner {
+    fn schema_name() -> String {
+        "FileSourceInner".to_string()
+    }
+
+    /// A float, a bool, or a field element as in [felt_from_str]
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        any_of(vec![
+            gen.subschema_for::<f64>(),
+            gen.subschema_for::<bool>(),
+            gen.subschema_for::<FeltSchema>(),
+            SchemaObject {
+                instance_type: Some(InstanceType::String.into()),
+                format: Some("0x prefixed big-endian hex or decimal".to_string()),
+                ..Default::default()
+            }
+            .into(),
+        ])
+    }
+}
+
+/// The schema of a value matching any of `schemas`
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+fn any_of(schemas: Vec<Schema>) -> Schema {
+    SchemaObject {
+        subschemas: Some(Box::new(SubschemaValidation {
+            any_of: Some(schemas),
+            ..Default::default()
+        })),
+        ..Default::default()
+    }
+    .into()
+}
+
+/// Parse a field element from a big-endian `0x` hex string or a decimal string, where a leading `-`
+/// negates the element. Unprefixed 64 digit hex strings are the little-endian serialization of
+/// witness files, parsed by [Fp]'s own deserializer.
+pub fn felt_from_str(s: &str) -> Option<Fp> {
+    use halo2curves::ff::PrimeField;
+
+    let s = s.trim();
+    if let Some(digits) = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        if digits.is_empty() || digits.len() > 64 {
+            return None;
+        }
+        let mut repr = [0u8; 32];
+        hex::decode_to_slice(format!("{:0>64}", digits), &mut repr).ok()?;
+        repr.reverse();
+        return Option::from(Fp::from_repr(repr));
+    }
+    let (negative, digits) = match s.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, s),
+    };
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    // from_str_vartime rejects leading zeros
+    let felt = match digits.trim_start_matches('0') {
+        "" => Fp::from(0u64),
+        digits => Fp::from_str_vartime(digits)?,
+    };
+    Some(if negative { -felt } else { felt })
+}
+
 /// Elements of inputs coming from a file
 pub type FileSource = Vec<Vec<FileSourceInner>>;
 
@@ -127,8 +206,20 @@ impl FileSourceInner {
 
     /// Convert to a field element
     pub fn to_field(&self, scale: crate::Scale) -> Fp {
+        self.to_field_rounded(scale, &QuantRounding::HalfAwayFromZero, 0)
+    }
+
+    /// Convert to a field element, rounding floats with `rounding` as the value at `index` of the input
+    pub fn to_field_rounded(
+        &self,
+        scale: crate::Scale,
+        rounding: &QuantRounding,
+        index: usize,
+    ) -> Fp {
         match self {
-            FileSourceInner::Float(f) => integer_rep_to_felt(quantize_float(f, 0.0, scale).unwrap()),
+            FileSourceInner::Float(f) => {
+                integer_rep_to_felt(quantize_float_rounded(f, 0.0, scale, rounding, index).unwrap())
+            }
             FileSourceInner::Bool(f) => {
                 if *f {
                     Fp::one()
@@ -162,12 +253,65 @@ pub struct OnChainSource {
     pub calls: Vec<CallsToAccount>,
     /// RPC url
     pub rpc: RPCUrl,
+    /// The block to make the calls at, if None the calls are made against the latest state.
+    /// Reading at a past block requires an archive node.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block: Option<u64>,
+    /// Hex encoded hash of `block`, witness generation fails if the node has a different block at that height
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_hash: Option<String>,
 }
 
 impl OnChainSource {
     /// Create a new OnChainSource
     pub fn new(calls: Vec<CallsToAccount>, rpc: RPCUrl) -> Self {
-        OnChainSource { calls, rpc }
+        OnChainSource {
+            calls,
+            rpc,
+            block: None,
+            block_hash: None,
+        }
+    }
+}
+
+/// The block on-chain data was read at, recorded in the witness and proof so the read can be reproduced
+#[derive(Clone, Debug, Deserialize, Serialize, Default, PartialOrd, PartialEq)]
+pub struct PinnedBlock {
+    /// The block number
+    pub number: u64,
+    /// Hex encoded hash of the block
+    pub hash: String,
+}
+
+/// Inner elements of inputs/outputs read from historical contract storage.
+/// Unlike [OnChainSource] the values are attested with eth_getProof account and storage proofs
+/// against the hash of `block`, rather than by calling view functions at verification time.
+#[derive(Clone, Debug, Deserialize, Serialize, Default, PartialOrd, PartialEq)]
+pub struct StorageProofSource {
+    /// Vector of storage slots to read, per account
+    pub accounts: Vec<SlotsOfAccount>,
+    /// The block the storage is read and proven at
+    pub block: u64,
+    /// Hex encoded hash of `block`, if None the attestation contract uses `blockhash` which only reaches back 256 blocks
+    pub block_hash: Option<String>,
+    /// RPC url, must serve eth_getProof for `block`
+    pub rpc: RPCUrl,
+}
+
+impl StorageProofSource {
+    /// Create a new StorageProofSource
+    pub fn new(
+        accounts: Vec<SlotsOfAccount>,
+        block: u64,
+        block_hash: Option<String>,
+        rpc: RPCUrl,
+    ) -> Self {
+        StorageProofSource {
+            accounts,
+            block,
+            block_hash,
+            rpc,
+        }
     }
 }
 
@@ -269,15 +413,18 @@ impl PostgresSource {
 
 impl OnChainSource {
     #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
-    /// Create dummy local on-chain data to test the OnChain data source
+    /// Create dummy local on-chain data to test the OnChain data source, if `nested` the data is read
+    /// from a mock oracle returning it inside nested types
     pub async fn test_from_file_data(
         data: &FileSource,
         scales: Vec<crate::Scale>,
         mut shapes: Vec<Vec<usize>>,
         rpc: Option<&str>,
+        nested: bool,
     ) -> Result<(Vec<Tensor<Fp>>, Self), GraphError> {
         use crate::eth::{
-            evm_quantize, read_on_chain_inputs, test_on_chain_data, DEFAULT_ANVIL_ENDPOINT,
+            evm_quantize, read_on_chain_inputs, test_nested_on_chain_data, test_on_chain_data,
+            DEFAULT_ANVIL_ENDPOINT,
         };
         use log::debug;
 
@@ -293,10 +440,13 @@ impl OnChainSource {
             }
         }
 
-        let calls_to_accounts = test_on_chain_data(client.clone(), data).await?;
+        let calls_to_accounts = match nested {
+            true => test_nested_on_chain_data(client.clone(), data).await?,
+            false => test_on_chain_data(client.clone(), data).await?,
+        };
         debug!("Calls to accounts: {:?}", calls_to_accounts);
         let inputs =
-            read_on_chain_inputs(client.clone(), client_address, &calls_to_accounts).await?;
+            read_on_chain_inputs(client.clone(), client_address, &calls_to_accounts, None).await?;
         debug!("Inputs: {:?}", inputs);
 
         let mut quantized_evm_inputs = vec![];
@@ -335,6 +485,80 @@ impl OnChainSource {
     }
 }
 
+impl StorageProofSource {
+    #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+    /// Create dummy local storage to test the StorageProof data source, the storage is snapshotted at the latest block
+    pub async fn test_from_file_data(
+        data: &FileSource,
+        scales: Vec<crate::Scale>,
+        mut shapes: Vec<Vec<usize>>,
+        rpc: Option<&str>,
+    ) -> Result<(Vec<Tensor<Fp>>, Self), GraphError> {
+        use crate::eth::{
+            evm_quantize, fetch_storage_proofs, test_storage_proof_data, DEFAULT_ANVIL_ENDPOINT,
+        };
+        use log::debug;
+
+        let (client, _) = crate::eth::setup_eth_backend(rpc, None).await?;
+
+        let mut scales = scales;
+        // set scales to 1 where data is a field element
+        for (idx, i) in data.iter().enumerate() {
+            if i.iter().all(|e| e.is_field()) {
+                scales[idx] = 0;
+                shapes[idx] = vec![i.len()];
+            }
+        }
+
+        let (accounts, block) = test_storage_proof_data(client.clone(), data).await?;
+        debug!("Slots of accounts: {:?}", accounts);
+
+        let used_rpc = rpc.unwrap_or(DEFAULT_ANVIL_ENDPOINT).to_string();
+        let source = StorageProofSource::new(accounts, block, None, used_rpc);
+
+        let proofs = fetch_storage_proofs(client.clone(), &source).await?;
+
+        let mut quantized_evm_inputs = vec![];
+        let mut prev = 0;
+        for (idx, i) in data.iter().enumerate() {
+            quantized_evm_inputs.extend(
+                evm_quantize(
+                    client.clone(),
+                    vec![scales[idx]; i.len()],
+                    &(
+                        proofs.values[prev..prev + i.len()].to_vec(),
+                        proofs.decimals[prev..prev + i.len()].to_vec(),
+                    ),
+                )
+                .await?,
+            );
+            prev += i.len();
+        }
+
+        let mut inputs: Vec<Tensor<Fp>> = vec![];
+        for (input, shape) in [quantized_evm_inputs].iter().zip(shapes) {
+            let mut t: Tensor<Fp> = input.iter().cloned().collect();
+            t.reshape(&shape)?;
+            inputs.push(t);
+        }
+
+        Ok((inputs, source))
+    }
+}
+
+/// Defines the storage slots of an account to read the on-chain input data from.
+/// Like [CallsToAccount] the values are included as part of the first elements in the publicInputs,
+/// but they are attested to with storage proofs by the StorageAttestation contract.
+#[derive(Clone, Debug, Deserialize, Serialize, Default, PartialOrd, PartialEq)]
+pub struct SlotsOfAccount {
+    /// A vector of tuples, where index 0 of tuples
+    /// is the hex encoded storage slot to read from the address. The slot must hold a single int256.
+    /// The second index of the tuple is the number of decimals for f32 conversion.
+    pub slots: Vec<(String, Decimals)>,
+    /// Address of the contract to read the storage of.
+    pub address: String,
+}
+
 /// Defines the view only calls to accounts to fetch the on-chain input data.
 /// This data will be included as part of the first elements in the publicInputs
 /// for the sol evm verifier and will be  verifyWithDataAttestation.sol
@@ -345,10 +569,55 @@ pub struct CallsToAccount {
     /// read the data from the address. This call must return a single
     /// elementary type (<https://docs.soliditylang.org/en/v0.8.20/abi-spec.html#types>).
     /// The second index of the tuple is the number of decimals for f32 conversion.
-    /// We don't support dynamic types currently.
+    /// Calls returning nested or dynamic types need a [ReturnSelector] in `returns`.
     pub call_data: Vec<(Call, Decimals)>,
     /// Address of the contract to read the data from.
     pub address: String,
+    /// The return layouts of the calls, indexed like `call_data`. A missing entry means
+    /// the call returns a single int256.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub returns: Vec<Option<ReturnSelector>>,
+}
+
+impl CallsToAccount {
+    /// The return selector of the call at `index`, if any.
+    pub fn return_selector(&self, index: usize) -> Option<&ReturnSelector> {
+        self.returns.get(index).and_then(|r| r.as_ref())
+    }
+}
+
+/// Selects the attested field out of the data returned by a view call with a nested return type.
+#[derive(Clone, Debug, Deserialize, Serialize, Default, PartialOrd, PartialEq)]
+pub struct ReturnSelector {
+    /// The JSON ABI fragment of the called function. Only its `outputs` are used.
+    pub abi: AbiFunction,
+    /// Dot separated path to the attested field, where each segment is a component name or index,
+    /// e.g. `0.price` or `rounds.2.answer`. The field must be an int, uint or bool.
+    pub path: String,
+}
+
+/// The part of a JSON ABI function fragment needed to decode the data it returns.
+#[derive(Clone, Debug, Deserialize, Serialize, Default, PartialOrd, PartialEq)]
+pub struct AbiFunction {
+    /// Name of the function.
+    #[serde(default)]
+    pub name: String,
+    /// The return parameters of the function.
+    pub outputs: Vec<AbiParam>,
+}
+
+/// A parameter of a JSON ABI fragment.
+#[derive(Clone, Debug, Deserialize, Serialize, Default, PartialOrd, PartialEq)]
+pub struct AbiParam {
+    /// Name of the parameter, empty for unnamed parameters.
+    #[serde(default)]
+    pub name: String,
+    /// The canonical ABI type, e.g. `uint256`, `tuple` or `tuple[2][]`.
+    #[serde(rename = "type")]
+    pub ty: String,
+    /// The components of a `tuple` type.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub components: Vec<AbiParam>,
 }
 /// Enum that defines source of the inputs/outputs to the EZKL model
 #[derive(Clone, Debug, Serialize, PartialOrd, PartialEq)]
@@ -358,6 +627,8 @@ pub enum DataSource {
     File(FileSource),
     /// On-chain data source. The first element is the calls to the account, and the second is the RPC url.
     OnChain(OnChainSource),
+    /// Historical on-chain storage, attested to with storage proofs.
+    StorageProof(StorageProofSource),
     /// Postgres DB
     #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
     DB(PostgresSource),
@@ -401,6 +672,12 @@ impl From<OnChainSource> for DataSource {
     }
 }
 
+impl From<StorageProofSource> for DataSource {
+    fn from(data: StorageProofSource) -> Self {
+        DataSource::StorageProof(data)
+    }
+}
+
 // !!! ALWAYS USE JSON SERIALIZATION FOR GRAPH INPUT
 // UNTAGGED ENUMS WONT WORK :( as highlighted here:
 impl<'de> Deserialize<'de> for DataSource {
@@ -419,6 +696,10 @@ impl<'de> Deserialize<'de> for DataSource {
         if let Ok(t) = second_try {
             return Ok(DataSource::OnChain(t));
         }
+        let storage_try: Result<StorageProofSource, _> = serde_json::from_str(this_json.get());
+        if let Ok(t) = storage_try {
+            return Ok(DataSource::StorageProof(t));
+        }
         #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
         {
             let third_try: Result<PostgresSource, _> = serde_json::from_str(this_json.get());
@@ -431,14 +712,727 @@ impl<'de> Deserialize<'de> for DataSource {
     }
 }
 
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+impl JsonSchema for DataSource {
+    fn schema_name() -> String {
+        "DataSource".to_string()
+    }
+
+    /// The arrays of a file source, else an on-chain, storage proof or postgres source, whose fields are checked
+    /// when the source is loaded
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        any_of(vec![
+            gen.subschema_for::<FileSource>(),
+            SchemaObject {
+                instance_type: Some(InstanceType::Object.into()),
+                ..Default::default()
+            }
+            .into(),
+        ])
+    }
+}
+
+/// The magic string every .npy file starts with
+const NPY_MAGIC: &[u8] = b"\x93NUMPY";
+
+/// An array read from or written to a NumPy .npy file
+#[derive(Clone, Debug, PartialEq)]
+pub struct NpyArray {
+    /// The shape of the array
+    pub shape: Vec<usize>,
+    /// The elements of the array in row-major order
+    pub data: Vec<FileSourceInner>,
+}
+
+impl NpyArray {
+    /// Parse a .npy file holding a little-endian f32, f64, i64 or bool array
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, GraphError> {
+        let invalid = |msg: &str| GraphError::InvalidNpy(msg.to_string());
+
+        if bytes.len() < 10 || &bytes[..6] != NPY_MAGIC {
+            return Err(invalid("missing magic string"));
+        }
+        // version 1.0 stores the header length in two bytes, later versions in four
+        let (header_start, header_len) = match bytes[6] {
+            1 => (10, u16::from_le_bytes([bytes[8], bytes[9]]) as usize),
+            2 | 3 if bytes.len() >= 12 => (
+                12,
+                u32::from_le_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]) as usize,
+            ),
+            v => return Err(invalid(&format!("unsupported version {}", v))),
+        };
+        let header = bytes
+            .get(header_start..header_start + header_len)
+            .ok_or(invalid("truncated header"))?;
+        let header = std::str::from_utf8(header).map_err(|_| invalid("header is not utf-8"))?;
+
+        let descr = npy_header_value(header, "descr")?.trim_matches(|c| c == '\'' || c == '"');
+        let fortran_order = match npy_header_value(header, "fortran_order")? {
+            "True" => true,
+            "False" => false,
+            v => return Err(invalid(&format!("invalid fortran_order {}", v))),
+        };
+        let shape = npy_header_value(header, "shape")?
+            .trim_matches(|c| c == '(' || c == ')')
+            .split(',')
+            .map(str::trim)
+            .filter(|d| !d.is_empty())
+            .map(|d| d.parse::<usize>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| invalid("invalid shape"))?;
+
+        let (width, read): (usize, fn(&[u8]) -> FileSourceInner) = match descr {
+            "<f4" => (4, |b| {
+                FileSourceInner::Float(f32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64)
+            }),
+            "<f8" => (8, |b| {
+                FileSourceInner::Float(f64::from_le_bytes(b.try_into().unwrap()))
+            }),
+            "<i8" => (8, |b| {
+                FileSourceInner::Float(i64::from_le_bytes(b.try_into().unwrap()) as f64)
+            }),
+            "|b1" => (1, |b| FileSourceInner::Bool(b[0] != 0)),
+            d => return Err(invalid(&format!("unsupported dtype {}", d))),
+        };
+
+        let len = shape.iter().product::<usize>();
+        let body = bytes
+            .get(header_start + header_len..header_start + header_len + len * width)
+            .ok_or(invalid("truncated data"))?;
+        let mut data = body.chunks_exact(width).map(read).collect::<Vec<_>>();
+
+        if fortran_order {
+            // column-major arrays vary the first axis fastest, gather each row-major element from its column-major offset
+            let mut strides = vec![1; shape.len()];
+            for i in 1..shape.len() {
+                strides[i] = strides[i - 1] * shape[i - 1];
+            }
+            data = (0..len)
+                .map(|mut idx| {
+                    let mut offset = 0;
+                    for (dim, stride) in shape.iter().zip(strides.iter()).rev() {
+                        offset += (idx % dim) * stride;
+                        idx /= dim;
+                    }
+                    data[offset].clone()
+                })
+                .collect();
+        }
+
+        Ok(NpyArray { shape, data })
+    }
+
+    /// Serialize the array as a version 1.0 .npy file, bool arrays are stored as bools and anything else as f64
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let is_bool = !self.data.is_empty() && self.data.iter().all(|e| e.is_bool());
+        let descr = if is_bool { "|b1" } else { "<f8" };
+        let shape = match self.shape.as_slice() {
+            [d] => format!("({},)", d),
+            dims => format!(
+                "({})",
+                dims.iter()
+                    .map(|d| d.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        };
+        let mut header = format!(
+            "{{'descr': '{}', 'fortran_order': False, 'shape': {}, }}",
+            descr, shape
+        );
+        // the header is padded with spaces and ends in a newline so that the data is 64 byte aligned
+        let unpadded = NPY_MAGIC.len() + 4 + header.len() + 1;
+        header.push_str(&" ".repeat((64 - unpadded % 64) % 64));
+        header.push('\n');
+
+        let mut bytes = NPY_MAGIC.to_vec();
+        bytes.extend([1, 0]);
+        bytes.extend((header.len() as u16).to_le_bytes());
+        bytes.extend(header.as_bytes());
+        for e in self.data.iter() {
+            match e {
+                FileSourceInner::Bool(b) if is_bool => bytes.push(*b as u8),
+                e => bytes.extend(e.to_float().to_le_bytes()),
+            }
+        }
+        bytes
+    }
+
+    /// Load an array from a .npy file
+    pub fn load(path: &std::path::Path) -> Result<Self, GraphError> {
+        let bytes = std::fs::read(path).map_err(|e| {
+            GraphError::ReadWriteFileError(path.display().to_string(), e.to_string())
+        })?;
+        Self::from_bytes(&bytes)
+    }
+
+    /// Save the array to a .npy file
+    pub fn save(&self, path: &std::path::Path) -> Result<(), GraphError> {
+        std::fs::write(path, self.to_bytes())
+            .map_err(|e| GraphError::ReadWriteFileError(path.display().to_string(), e.to_string()))
+    }
+}
+
+/// Get the raw value of a key in the python dict literal of a .npy header
+fn npy_header_value<'a>(header: &'a str, key: &str) -> Result<&'a str, GraphError> {
+    let missing = || GraphError::InvalidNpy(format!("header is missing {}", key));
+    let start = header.find(&format!("'{}':", key)).ok_or_else(missing)? + key.len() + 3;
+    let value = header[start..].trim_start();
+    let end = if value.starts_with('(') {
+        value.find(')').map(|i| i + 1)
+    } else {
+        value.find([',', '}'])
+    }
+    .ok_or_else(missing)?;
+    Ok(value[..end].trim())
+}
+
+/// Load the arrays of a NumPy .npz archive, keyed by their names, in the order they are stored
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+pub fn load_npz(path: &std::path::Path) -> Result<Vec<(String, NpyArray)>, GraphError> {
+    let file_err = |e: std::io::Error| {
+        GraphError::ReadWriteFileError(path.display().to_string(), e.to_string())
+    };
+    let file = std::fs::File::open(path).map_err(file_err)?;
+    let mut archive = zip::ZipArchive::new(BufReader::with_capacity(*EZKL_BUF_CAPACITY, file))
+        .map_err(|e| GraphError::InvalidNpy(e.to_string()))?;
+
+    let mut arrays = vec![];
+    for i in 0..archive.len() {
+        let mut entry = archive
+            .by_index(i)
+            .map_err(|e| GraphError::InvalidNpy(e.to_string()))?;
+        let name = entry.name().trim_end_matches(".npy").to_string();
+        let mut bytes = vec![];
+        entry.read_to_end(&mut bytes).map_err(file_err)?;
+        arrays.push((name, NpyArray::from_bytes(&bytes)?));
+    }
+    Ok(arrays)
+}
+
+/// Save named arrays to an uncompressed NumPy .npz archive
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+pub fn save_npz(path: &std::path::Path, arrays: &[(String, NpyArray)]) -> Result<(), GraphError> {
+    use std::io::Write;
+
+    let file_err = |e: std::io::Error| {
+        GraphError::ReadWriteFileError(path.display().to_string(), e.to_string())
+    };
+    let file = std::fs::File::create(path).map_err(file_err)?;
+    let mut archive = zip::ZipWriter::new(BufWriter::with_capacity(*EZKL_BUF_CAPACITY, file));
+    let options =
+        zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+    for (name, array) in arrays {
+        archive
+            .start_file(format!("{}.npy", name), options)
+            .map_err(|e| GraphError::InvalidNpy(e.to_string()))?;
+        archive.write_all(&array.to_bytes()).map_err(file_err)?;
+    }
+    archive
+        .finish()
+        .map_err(|e| GraphError::InvalidNpy(e.to_string()))?;
+    Ok(())
+}
+
+/// Order the arrays of a .npz archive by the model inputs they feed.
+/// Positional arrays (`arr_0`, `arr_1`, ... as written by `np.savez`) are mapped by position, named arrays
+/// by the names of the model's inputs, falling back to the archive order when the names are unknown.
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+fn order_npz_arrays(
+    mut arrays: Vec<(String, NpyArray)>,
+    input_names: &[String],
+) -> Result<Vec<NpyArray>, GraphError> {
+    let positions = arrays
+        .iter()
+        .map(|(name, _)| name.strip_prefix("arr_")?.parse::<usize>().ok())
+        .collect::<Option<Vec<_>>>();
+
+    if let Some(positions) = positions {
+        let mut indexed = positions.into_iter().zip(arrays).collect::<Vec<_>>();
+        indexed.sort_by_key(|(position, _)| *position);
+        return Ok(indexed.into_iter().map(|(_, (_, array))| array).collect());
+    }
+    if input_names.is_empty() {
+        return Ok(arrays.into_iter().map(|(_, array)| array).collect());
+    }
+
+    input_names
+        .iter()
+        .map(|input| {
+            let idx = arrays
+                .iter()
+                .position(|(name, _)| name == input)
+                .ok_or_else(|| {
+                    GraphError::InvalidNpy(format!("no array named {} in the archive", input))
+                })?;
+            Ok(arrays.swap_remove(idx).1)
+        })
+        .collect()
+}
+
+/// How the columns of a csv file feed the model inputs
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct CsvMapping {
+    /// The columns feeding each model input, in the order of the model inputs. If empty every column feeds the first input
+    #[serde(default)]
+    pub inputs: Vec<CsvInput>,
+    /// How empty, `NA` and `NaN` cells are handled
+    #[serde(default)]
+    pub missing: MissingValues,
+    /// The character separating the columns
+    #[serde(default = "default_csv_delimiter")]
+    pub delimiter: char,
+}
+
+fn default_csv_delimiter() -> char {
+    ','
+}
+
+impl Default for CsvMapping {
+    fn default() -> Self {
+        CsvMapping {
+            inputs: vec![],
+            missing: MissingValues::default(),
+            delimiter: default_csv_delimiter(),
+        }
+    }
+}
+
+impl CsvMapping {
+    /// Load a mapping spec from a json file
+    pub fn load(path: &std::path::Path) -> Result<Self, GraphError> {
+        let spec = std::fs::read_to_string(path).map_err(|e| {
+            GraphError::ReadWriteFileError(path.display().to_string(), e.to_string())
+        })?;
+        serde_json::from_str(&spec).map_err(|e| GraphError::InvalidCsv(e.to_string()))
+    }
+}
+
+/// The columns of a csv file feeding a model input
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct CsvInput {
+    /// The header names of the columns, in the order their values are laid out in each row of the input
+    pub columns: Vec<String>,
+    /// The type the cells are coerced to
+    #[serde(default)]
+    pub dtype: CsvType,
+}
+
+/// The type the cells of a csv column are coerced to
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum CsvType {
+    /// Any number, `true` and `false` are read as 1 and 0
+    #[default]
+    Float,
+    /// Whole numbers
+    Int,
+    /// `true` / `false` or 1 / 0
+    Bool,
+}
+
+impl CsvType {
+    /// Coerce a trimmed cell to the type, filling in missing values as configured
+    fn coerce(&self, cell: &str, missing: &MissingValues) -> Result<FileSourceInner, String> {
+        let is_missing =
+            cell.is_empty() || cell.eq_ignore_ascii_case("na") || cell.eq_ignore_ascii_case("nan");
+        let value = if is_missing {
+            match missing {
+                MissingValues::Error => return Err("missing value".to_string()),
+                MissingValues::Fill(fill) => *fill,
+            }
+        } else if cell.eq_ignore_ascii_case("true") {
+            1.0
+        } else if cell.eq_ignore_ascii_case("false") {
+            0.0
+        } else {
+            cell.parse::<f64>()
+                .map_err(|_| format!("cannot parse {} as a number", cell))?
+        };
+
+        match self {
+            CsvType::Float => Ok(FileSourceInner::Float(value)),
+            CsvType::Int if value.fract() == 0.0 => Ok(FileSourceInner::Float(value)),
+            CsvType::Bool if value == 0.0 || value == 1.0 => {
+                Ok(FileSourceInner::Bool(value == 1.0))
+            }
+            CsvType::Int => Err(format!("{} is not an integer", value)),
+            CsvType::Bool => Err(format!("{} is not a bool", value)),
+        }
+    }
+}
+
+/// How missing values in a csv file are handled
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum MissingValues {
+    /// Fail to load the file
+    #[default]
+    Error,
+    /// Replace missing values with a fill value
+    Fill(f64),
+}
+
+/// Split csv text into rows of fields, supporting quoted fields with escaped quotes and line breaks
+fn parse_csv(text: &str, delimiter: char) -> Result<Vec<Vec<String>>, GraphError> {
+    let mut rows = vec![];
+    let mut row = vec![];
+    let mut field = String::new();
+    let mut quoted = false;
+
+    let mut chars = text.trim_start_matches('\u{feff}').chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' => quoted = !quoted,
+            c if quoted => field.push(c),
+            '\r' => {}
+            '\n' => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            c if c == delimiter => row.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    if quoted {
+        return Err(GraphError::InvalidCsv(
+            "unterminated quoted field".to_string(),
+        ));
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+
+    // blank lines hold no row
+    Ok(rows
+        .into_iter()
+        .filter(|r| !(r.len() == 1 && r[0].trim().is_empty()))
+        .collect())
+}
+
+/// Preprocessing applied to png / jpeg inputs before quantization, mirroring the usual torchvision transforms:
+/// resize, center crop, scale the pixels to a range, then normalize each channel.
+/// It is stored in the circuit settings so the settings fingerprint commits to it.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct ImagePreprocessing {
+    /// Resize the image before cropping, with bilinear resampling
+    #[serde(default)]
+    pub resize: Option<ImageResize>,
+    /// Crop the `[height, width]` center of the resized image
+    #[serde(default)]
+    pub center_crop: Option<[u32; 2]>,
+    /// The channels of the input tensor
+    #[serde(default)]
+    pub channel_order: ChannelOrder,
+    /// Where the channels sit in the input tensor
+    #[serde(default)]
+    pub layout: ImageLayout,
+    /// The range pixels are scaled to before normalization
+    #[serde(default)]
+    pub range: PixelRange,
+    /// The per-channel mean subtracted from the scaled pixels, 0 if empty
+    #[serde(default)]
+    pub mean: Vec<f64>,
+    /// The per-channel standard deviation the pixels are divided by after subtracting the mean, 1 if empty
+    #[serde(default)]
+    pub std: Vec<f64>,
+}
+
+/// How an image is resized
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageResize {
+    /// Resize the shorter edge to a length keeping the aspect ratio, as torchvision's `Resize(int)`
+    Shorter(u32),
+    /// Resize to `[height, width]`
+    Exact([u32; 2]),
+}
+
+/// The channels of an image input
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ChannelOrder {
+    ///
+    #[default]
+    Rgb,
+    ///
+    Bgr,
+    /// A single luma channel, converted from rgb as PIL's `convert("L")`
+    Gray,
+}
+
+/// Where the channels sit in an image input
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageLayout {
+    /// Channels first, as torchvision tensors
+    #[default]
+    Chw,
+    /// Channels last
+    Hwc,
+}
+
+/// The range pixels are scaled to
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PixelRange {
+    /// [0, 1], as torchvision's `ToTensor`
+    #[default]
+    ZeroOne,
+    /// [-1, 1]
+    MinusOneOne,
+}
+
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+impl ImagePreprocessing {
+    /// Load a preprocessing spec from a json file
+    pub fn load(path: &std::path::Path) -> Result<Self, GraphError> {
+        let spec = std::fs::read_to_string(path).map_err(|e| {
+            GraphError::ReadWriteFileError(path.display().to_string(), e.to_string())
+        })?;
+        serde_json::from_str(&spec).map_err(|e| GraphError::InvalidImage(e.to_string()))
+    }
+
+    /// Decode a png / jpeg image and preprocess it into the elements of an input tensor
+    pub fn apply(&self, bytes: &[u8]) -> Result<Vec<FileSourceInner>, GraphError> {
+        use image::imageops::FilterType;
+        use image::{DynamicImage, GenericImageView, ImageBuffer, Luma};
+
+        let channels = match self.channel_order {
+            ChannelOrder::Gray => 1,
+            ChannelOrder::Rgb | ChannelOrder::Bgr => 3,
+        };
+        for (name, values) in [("mean", &self.mean), ("std", &self.std)] {
+            if !values.is_empty() && values.len() != channels {
+                return Err(GraphError::InvalidImage(format!(
+                    "{} has {} values for {} channels",
+                    name,
+                    values.len(),
+                    channels
+                )));
+            }
+        }
+
+        let rgb = image::load_from_memory(bytes)
+            .map_err(|e| GraphError::InvalidImage(e.to_string()))?
+            .to_rgb8();
+        let mut image = match self.channel_order {
+            // the ITU-R 601-2 luma transform in PIL's fixed point arithmetic
+            ChannelOrder::Gray => {
+                DynamicImage::ImageLuma8(ImageBuffer::from_fn(rgb.width(), rgb.height(), |x, y| {
+                    let [r, g, b] = rgb.get_pixel(x, y).0.map(u32::from);
+                    Luma([((r * 19595 + g * 38470 + b * 7471 + 0x8000) >> 16) as u8])
+                }))
+            }
+            ChannelOrder::Rgb | ChannelOrder::Bgr => DynamicImage::ImageRgb8(rgb),
+        };
+
+        let (width, height) = image.dimensions();
+        let resized = match self.resize {
+            // torchvision truncates the scaled length of the longer edge
+            Some(ImageResize::Shorter(size)) if width <= height => {
+                Some((size, (size as u64 * height as u64 / width as u64) as u32))
+            }
+            Some(ImageResize::Shorter(size)) => {
+                Some(((size as u64 * width as u64 / height as u64) as u32, size))
+            }
+            Some(ImageResize::Exact([height, width])) => Some((width, height)),
+            None => None,
+        };
+        if let Some((width, height)) = resized {
+            image = image.resize_exact(width, height, FilterType::Triangle);
+        }
+
+        if let Some([crop_height, crop_width]) = self.center_crop {
+            let (width, height) = image.dimensions();
+            if crop_height > height || crop_width > width {
+                return Err(GraphError::InvalidImage(format!(
+                    "cannot crop {}x{} from a {}x{} image",
+                    crop_height, crop_width, height, width
+                )));
+            }
+            // torchvision rounds the offsets half to even
+            let top = ((height - crop_height) as f64 / 2.0).round_ties_even() as u32;
+            let left = ((width - crop_width) as f64 / 2.0).round_ties_even() as u32;
+            image = image.crop_imm(left, top, crop_width, crop_height);
+        }
+
+        let (width, height) = (image.width() as usize, image.height() as usize);
+        let pixels = match self.channel_order {
+            ChannelOrder::Gray => image.to_luma8().into_raw(),
+            ChannelOrder::Rgb | ChannelOrder::Bgr => image.to_rgb8().into_raw(),
+        };
+        let value = |y: usize, x: usize, c: usize| {
+            let source = match self.channel_order {
+                ChannelOrder::Bgr => 2 - c,
+                ChannelOrder::Rgb | ChannelOrder::Gray => c,
+            };
+            let pixel = pixels[(y * width + x) * channels + source] as f64 / 255.0;
+            let pixel = match self.range {
+                PixelRange::ZeroOne => pixel,
+                PixelRange::MinusOneOne => pixel * 2.0 - 1.0,
+            };
+            let mean = self.mean.get(c).copied().unwrap_or(0.0);
+            let std = self.std.get(c).copied().unwrap_or(1.0);
+            FileSourceInner::Float((pixel - mean) / std)
+        };
+
+        let mut data = Vec::with_capacity(width * height * channels);
+        match self.layout {
+            ImageLayout::Chw => {
+                for c in 0..channels {
+                    for y in 0..height {
+                        for x in 0..width {
+                            data.push(value(y, x, c));
+                        }
+                    }
+                }
+            }
+            ImageLayout::Hwc => {
+                for y in 0..height {
+                    for x in 0..width {
+                        for c in 0..channels {
+                            data.push(value(y, x, c));
+                        }
+                    }
+                }
+            }
+        }
+        Ok(data)
+    }
+}
+
+/// A per-input affine normalization `x * scale + offset`, applied to the float elements of file
+/// inputs before they are quantized, for models trained on normalized data. An empty vector leaves
+/// its input as it is, a single value applies to the whole input, and otherwise there is one value per
+/// channel (dimension 1) or per element of the input.
+///
+/// It is stored in the circuit settings, so the settings fingerprint a verifier is registered under
+/// commits to it, and the proof attests to the model run on the normalized inputs.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), derive(JsonSchema))]
+pub struct InputNormalization {
+    /// The factor each input is multiplied by
+    #[serde(default)]
+    pub scale: Vec<Vec<f64>>,
+    /// The offset added to each input after scaling it
+    #[serde(default)]
+    pub offset: Vec<Vec<f64>>,
+}
+
+impl InputNormalization {
+    /// The normalization `(x - mean) / std` of each input, where an input's mean or std may be a
+    /// single value broadcast over the other, or empty for a mean of 0 or a std of 1
+    pub fn from_mean_std(mean: &[Vec<f64>], std: &[Vec<f64>]) -> Result<Self, GraphError> {
+        if mean.len() != std.len() {
+            return Err(GraphError::InvalidNormalization(format!(
+                "{} inputs have a mean but {} have a std",
+                mean.len(),
+                std.len()
+            )));
+        }
+        let mut normalization = InputNormalization::default();
+        for (i, (mean, std)) in mean.iter().zip(std).enumerate() {
+            let len = match (mean.len(), std.len()) {
+                (m, s) if m == s || s <= 1 => m,
+                (m, s) if m <= 1 => s,
+                (m, s) => {
+                    return Err(GraphError::InvalidNormalization(format!(
+                        "input {} has {} mean values but {} std values",
+                        i, m, s
+                    )))
+                }
+            };
+            let at = |values: &[f64], j: usize| values.get(j).or(values.first()).copied();
+            let (mut scale, mut offset) = (vec![], vec![]);
+            for j in 0..len {
+                let (m, s) = (at(mean, j).unwrap_or(0.0), at(std, j).unwrap_or(1.0));
+                if s == 0.0 {
+                    return Err(GraphError::InvalidNormalization(format!(
+                        "input {} has a std of 0",
+                        i
+                    )));
+                }
+                scale.push(1.0 / s);
+                offset.push(-m / s);
+            }
+            normalization.scale.push(scale);
+            normalization.offset.push(offset);
+        }
+        Ok(normalization)
+    }
+
+    /// Load the normalization `(x - mean) / std` from json files holding the mean and std of each input
+    #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+    pub fn from_mean_std_files(
+        mean: &std::path::Path,
+        std: &std::path::Path,
+    ) -> Result<Self, GraphError> {
+        let load = |path: &std::path::Path| -> Result<Vec<Vec<f64>>, GraphError> {
+            let values = std::fs::read_to_string(path).map_err(|e| {
+                GraphError::ReadWriteFileError(path.display().to_string(), e.to_string())
+            })?;
+            serde_json::from_str(&values)
+                .map_err(|e| GraphError::InvalidNormalization(format!("{}: {}", path.display(), e)))
+        };
+        Self::from_mean_std(&load(mean)?, &load(std)?)
+    }
+
+    /// Normalize the elements of the input `idx` of `shape`. Field elements are already quantized and
+    /// bools aren't scaled, so only floats are normalized.
+    pub fn apply(
+        &self,
+        idx: usize,
+        shape: &[usize],
+        data: &[FileSourceInner],
+    ) -> Result<Vec<FileSourceInner>, GraphError> {
+        let empty = vec![];
+        let scale = self.scale.get(idx).unwrap_or(&empty);
+        let offset = self.offset.get(idx).unwrap_or(&empty);
+
+        let channels = shape.get(1).copied().unwrap_or(1);
+        let inner = shape.iter().skip(2).product::<usize>();
+        let value = |values: &[f64], i: usize, default: f64| match values.len() {
+            0 => Ok(default),
+            1 => Ok(values[0]),
+            len if len == data.len() => Ok(values[i]),
+            len if len == channels && shape.len() > 1 => Ok(values[(i / inner) % channels]),
+            len => Err(GraphError::InvalidNormalization(format!(
+                "input {} of shape {:?} has {} normalization values",
+                idx, shape, len
+            ))),
+        };
+
+        data.iter()
+            .enumerate()
+            .map(|(i, x)| match x {
+                FileSourceInner::Float(f) => Ok(FileSourceInner::Float(
+                    f * value(scale, i, 1.0)? + value(offset, i, 0.0)?,
+                )),
+                x => Ok(x.clone()),
+            })
+            .collect()
+    }
+}
+
 /// Input to graph as a datasource
 /// Always use JSON serialization for GraphData. Seriously.
 #[derive(Clone, Debug, Deserialize, Default, PartialEq)]
+#[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), derive(JsonSchema))]
 pub struct GraphData {
     /// Inputs to the model / computational graph (can be empty vectors if inputs are coming from on-chain).
     pub input_data: DataSource,
     /// Outputs of the model / computational graph (can be empty vectors if outputs are coming from on-chain).
     pub output_data: Option<DataSource>,
+    /// The scale each input was quantized at, declaring the input data already quantized field
+    /// elements that are used as they are, as when chaining circuits.
+    #[serde(default)]
+    pub input_scales: Option<Vec<crate::Scale>>,
 }
 
 impl UnwindSafe for GraphData {}
@@ -480,11 +1474,96 @@ impl GraphData {
         GraphData {
             input_data,
             output_data: None,
+            input_scales: None,
+        }
+    }
+
+    /// Already quantized field element inputs, declared quantized at `scales`
+    pub fn new_field_inputs(input_data: Vec<Vec<Fp>>, scales: Vec<crate::Scale>) -> Self {
+        GraphData {
+            input_scales: Some(scales),
+            ..GraphData::new(DataSource::File(
+                input_data
+                    .into_iter()
+                    .map(|input| input.into_iter().map(FileSourceInner::Field).collect())
+                    .collect(),
+            ))
+        }
+    }
+
+    /// Check the declared scales of already quantized inputs against the input `scales` of the
+    /// circuit, returning the inputs with every element read as a field element.
+    /// Returns None if the inputs aren't declared quantized.
+    pub fn field_inputs(&self, scales: &[crate::Scale]) -> Result<Option<DataSource>, GraphError> {
+        let Some(declared) = &self.input_scales else {
+            return Ok(None);
+        };
+        if declared != scales {
+            return Err(GraphError::InvalidFieldInput(format!(
+                "inputs are declared at scales {:?} but the settings quantize them at {:?}",
+                declared, scales
+            )));
         }
+        let DataSource::File(data) = &self.input_data else {
+            return Err(GraphError::InvalidFieldInput(
+                "only file inputs can be declared quantized".to_string(),
+            ));
+        };
+
+        // integral floats are the integer reps of field elements, exact up to 2^53
+        let as_felt = |x: &FileSourceInner| match x {
+            FileSourceInner::Field(f) => Ok(*f),
+            FileSourceInner::Bool(b) => Ok(Fp::from(*b as u64)),
+            FileSourceInner::Float(f) if f.fract() == 0.0 && f.abs() < 2f64.powi(53) => {
+                Ok(integer_rep_to_felt(*f as IntegerRep))
+            }
+            FileSourceInner::Float(f) => Err(GraphError::InvalidFieldInput(format!(
+                "{} isn't an exact integer, give large elements as strings",
+                f
+            ))),
+        };
+        let felts = data
+            .iter()
+            .map(|input| {
+                input
+                    .iter()
+                    .map(|x| as_felt(x).map(FileSourceInner::Field))
+                    .collect()
+            })
+            .collect::<Result<FileSource, _>>()?;
+        Ok(Some(DataSource::File(felts)))
     }
 
     /// Load the model input from a file
     pub fn from_path(path: std::path::PathBuf) -> Result<Self, GraphError> {
+        Self::from_path_with_input_names(path, &[])
+    }
+
+    /// Load the model input from a file, mapping the arrays of a .npz archive to the model inputs with the same names.
+    /// A .npy file holds the array of the first input and every column of a .csv file feeds the first input,
+    /// every other extension is parsed as json.
+    pub fn from_path_with_input_names(
+        path: std::path::PathBuf,
+        input_names: &[String],
+    ) -> Result<Self, GraphError> {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("npy") => {
+                let array = NpyArray::load(&path)?;
+                return Ok(GraphData::new(DataSource::File(vec![array.data])));
+            }
+            #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+            Some("npz") => {
+                let arrays = order_npz_arrays(load_npz(&path)?, input_names)?;
+                return Ok(GraphData::new(DataSource::File(
+                    arrays.into_iter().map(|a| a.data).collect(),
+                )));
+            }
+            Some("csv") => return Self::from_csv(path, &CsvMapping::default()),
+            _ => {}
+        }
+        #[cfg(any(not(feature = "ezkl"), target_arch = "wasm32"))]
+        let _ = input_names;
+
         let reader = std::fs::File::open(&path).map_err(|e| {
             GraphError::ReadWriteFileError(path.display().to_string(), e.to_string())
         })?;
@@ -493,8 +1572,73 @@ impl GraphData {
         reader.read_to_string(&mut buf).map_err(|e| {
             GraphError::ReadWriteFileError(path.display().to_string(), e.to_string())
         })?;
-        let graph_input = serde_json::from_str(&buf)?;
-        Ok(graph_input)
+        from_json_slice(buf.as_bytes(), JsonArtifact::Input)
+    }
+
+    /// Load the model input from a csv file with a header row, feeding its columns to the model inputs as laid out by the mapping.
+    /// Each row holds one batch element, so a model with a batch dimension of n reads its inputs from n rows
+    /// and calibration data can hold several batches of rows.
+    pub fn from_csv(path: std::path::PathBuf, mapping: &CsvMapping) -> Result<Self, GraphError> {
+        let text = std::fs::read_to_string(&path).map_err(|e| {
+            GraphError::ReadWriteFileError(path.display().to_string(), e.to_string())
+        })?;
+        let mut rows = parse_csv(&text, mapping.delimiter)?.into_iter();
+        let header = rows
+            .next()
+            .ok_or(GraphError::InvalidCsv("missing header row".to_string()))?
+            .iter()
+            .map(|h| h.trim().to_string())
+            .collect::<Vec<_>>();
+        let rows = rows.collect::<Vec<_>>();
+
+        let inputs = if mapping.inputs.is_empty() {
+            vec![CsvInput {
+                columns: header.clone(),
+                dtype: CsvType::default(),
+            }]
+        } else {
+            mapping.inputs.clone()
+        };
+
+        let mut input_data = vec![];
+        for input in inputs.iter() {
+            let indices = input
+                .columns
+                .iter()
+                .map(|column| {
+                    header.iter().position(|h| h == column).ok_or_else(|| {
+                        GraphError::InvalidCsv(format!("no column named {}", column))
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let mut values = Vec::with_capacity(rows.len() * indices.len());
+            for (i, row) in rows.iter().enumerate() {
+                for (column, idx) in input.columns.iter().zip(indices.iter()) {
+                    let cell = row.get(*idx).map(|c| c.trim()).unwrap_or_default();
+                    let value = input.dtype.coerce(cell, &mapping.missing).map_err(|e| {
+                        GraphError::InvalidCsv(format!("row {} column {}: {}", i + 1, column, e))
+                    })?;
+                    values.push(value);
+                }
+            }
+            input_data.push(values);
+        }
+
+        Ok(GraphData::new(DataSource::File(input_data)))
+    }
+
+    /// Load the model input from a png / jpeg image, preprocessed into the elements of the first input
+    #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+    pub fn from_image(
+        path: std::path::PathBuf,
+        preprocessing: &ImagePreprocessing,
+    ) -> Result<Self, GraphError> {
+        let bytes = std::fs::read(&path).map_err(|e| {
+            GraphError::ReadWriteFileError(path.display().to_string(), e.to_string())
+        })?;
+        let data = preprocessing.apply(&bytes)?;
+        Ok(GraphData::new(DataSource::File(vec![data])))
     }
 
     /// Save the model input to a file
@@ -519,11 +1663,11 @@ impl GraphData {
         let iterable = match self {
             GraphData {
                 input_data: DataSource::File(data),
-                output_data: _,
+                ..
             } => data.clone(),
             GraphData {
-                input_data: DataSource::OnChain(_),
-                output_data: _,
+                input_data: DataSource::OnChain(_) | DataSource::StorageProof(_),
+                ..
             } => {
                 return Err(GraphError::InvalidDims(
                     0,
@@ -533,7 +1677,7 @@ impl GraphData {
             #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
             GraphData {
                 input_data: DataSource::DB(data),
-                output_data: _,
+                ..
             } => data.fetch_and_format_as_file().await?,
         };
 
@@ -583,7 +1727,10 @@ impl GraphData {
         // create a new GraphWitness for each batch
         let batches = input_batches
             .into_iter()
-            .map(GraphData::new)
+            .map(|input_data| GraphData {
+                input_scales: self.input_scales.clone(),
+                ..GraphData::new(input_data)
+            })
             .collect::<Vec<GraphData>>();
 
         Ok(batches)
@@ -600,6 +1747,16 @@ impl ToPyObject for CallsToAccount {
     }
 }
 
+#[cfg(feature = "python-bindings")]
+impl ToPyObject for SlotsOfAccount {
+    fn to_object(&self, py: Python) -> PyObject {
+        let dict = PyDict::new(py);
+        dict.set_item("account", &self.address).unwrap();
+        dict.set_item("slots", &self.slots).unwrap();
+        dict.to_object(py)
+    }
+}
+
 #[cfg(feature = "python-bindings")]
 impl ToPyObject for DataSource {
     fn to_object(&self, py: Python) -> PyObject {
@@ -609,6 +1766,17 @@ impl ToPyObject for DataSource {
                 let dict = PyDict::new(py);
                 dict.set_item("rpc_url", &source.rpc).unwrap();
                 dict.set_item("calls_to_accounts", &source.calls).unwrap();
+                dict.set_item("block", source.block).unwrap();
+                dict.set_item("block_hash", &source.block_hash).unwrap();
+                dict.to_object(py)
+            }
+            DataSource::StorageProof(source) => {
+                let dict = PyDict::new(py);
+                dict.set_item("rpc_url", &source.rpc).unwrap();
+                dict.set_item("block", source.block).unwrap();
+                dict.set_item("block_hash", &source.block_hash).unwrap();
+                dict.set_item("slots_of_accounts", &source.accounts)
+                    .unwrap();
                 dict.to_object(py)
             }
             DataSource::DB(source) => {
@@ -644,6 +1812,10 @@ impl Serialize for GraphData {
         let mut state = serializer.serialize_struct("GraphData", 4)?;
         state.serialize_field("input_data", &self.input_data)?;
         state.serialize_field("output_data", &self.output_data)?;
+        match &self.input_scales {
+            Some(scales) => state.serialize_field("input_scales", scales)?,
+            None => state.skip_field("input_scales")?,
+        }
         state.end()
     }
 }
@@ -670,6 +1842,25 @@ mod tests {
         assert_eq!(expect, source);
     }
 
+    #[test]
+    fn test_storage_proof_source_serialization_round_trip() {
+        let source = DataSource::from(StorageProofSource::new(
+            vec![SlotsOfAccount {
+                slots: vec![("0x0".to_string(), 18)],
+                address: "b794f5ea0ba39494ce839613fffba74279579268".to_string(),
+            }],
+            19_000_000,
+            None,
+            "http://localhost:8545".to_string(),
+        ));
+
+        let serialized = serde_json::to_string(&source).unwrap();
+        let expect = serde_json::from_str::<DataSource>(&serialized)
+            .map_err(|e| e.to_string())
+            .unwrap();
+        assert_eq!(expect, source);
+    }
+
     #[test]
     // this is for backwards compatibility with the old format
     fn test_graph_input_serialization_round_trip() {
@@ -691,6 +1882,148 @@ mod tests {
         assert_eq!(graph_input3, file);
     }
 
+    #[test]
+    fn test_npy_round_trip() {
+        let array = NpyArray {
+            shape: vec![2, 3],
+            data: [0.5, -1.25, 3.0, 0.1, 2.0, -7.0]
+                .into_iter()
+                .map(FileSourceInner::Float)
+                .collect(),
+        };
+        let bytes = array.to_bytes();
+        // the data of a version 1.0 file starts on a 64 byte boundary
+        assert_eq!((bytes.len() - 6 * 8) % 64, 0);
+        assert_eq!(NpyArray::from_bytes(&bytes).unwrap(), array);
+
+        let bools = NpyArray {
+            shape: vec![3],
+            data: [true, false, true]
+                .into_iter()
+                .map(FileSourceInner::Bool)
+                .collect(),
+        };
+        assert_eq!(NpyArray::from_bytes(&bools.to_bytes()).unwrap(), bools);
+    }
+
+    #[test]
+    fn test_npy_fortran_order() {
+        let header = "{'descr': '<i8', 'fortran_order': True, 'shape': (2, 3), }\n";
+        let mut bytes = NPY_MAGIC.to_vec();
+        bytes.extend([1, 0]);
+        bytes.extend((header.len() as u16).to_le_bytes());
+        bytes.extend(header.as_bytes());
+        // [[1, 2, 3], [4, 5, 6]] stored column by column
+        for x in [1i64, 4, 2, 5, 3, 6] {
+            bytes.extend(x.to_le_bytes());
+        }
+
+        let array = NpyArray::from_bytes(&bytes).unwrap();
+        assert_eq!(array.shape, vec![2, 3]);
+        let data = array.data.iter().map(|e| e.to_float()).collect::<Vec<_>>();
+        assert_eq!(data, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+    }
+
+    #[test]
+    fn test_parse_csv() {
+        let text = "a,\"b, quoted\",c\r\n1,\"say \"\"hi\"\"\",\n\n2,\"multi\nline\",NA";
+        let rows = parse_csv(text, ',').unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                vec!["a", "b, quoted", "c"],
+                vec!["1", "say \"hi\"", ""],
+                vec!["2", "multi\nline", "NA"],
+            ]
+        );
+        assert!(parse_csv("a,\"b", ',').is_err());
+
+        let fill = MissingValues::Fill(0.5);
+        assert_eq!(
+            CsvType::Float.coerce("NA", &fill),
+            Ok(FileSourceInner::Float(0.5))
+        );
+        assert!(CsvType::Float.coerce("", &MissingValues::Error).is_err());
+        assert_eq!(
+            CsvType::Bool.coerce("1", &fill),
+            Ok(FileSourceInner::Bool(true))
+        );
+        assert!(CsvType::Int.coerce("1.5", &fill).is_err());
+    }
+
+    #[test]
+    fn test_input_normalization() {
+        // a per-channel mean with a single std, and an input left as it is
+        let normalization =
+            InputNormalization::from_mean_std(&[vec![1.0, -2.0], vec![]], &[vec![4.0], vec![]])
+                .unwrap();
+        assert_eq!(normalization.scale, vec![vec![0.25, 0.25], vec![]]);
+        assert_eq!(normalization.offset, vec![vec![-0.25, 0.5], vec![]]);
+
+        let data = [1.0, 5.0, -2.0, 2.0].map(FileSourceInner::Float);
+        let normalized = normalization.apply(0, &[1, 2, 2], &data).unwrap();
+        assert_eq!(normalized, [0.0, 1.0, 0.0, 1.0].map(FileSourceInner::Float));
+        assert_eq!(normalization.apply(1, &[1, 2, 2], &data).unwrap(), data);
+
+        // bools are left as they are, and values must broadcast over the input
+        let bools = [FileSourceInner::Bool(true), FileSourceInner::Float(9.0)];
+        let normalized = normalization.apply(0, &[2], &bools).unwrap();
+        assert_eq!(
+            normalized,
+            [FileSourceInner::Bool(true), FileSourceInner::Float(2.75)]
+        );
+        assert!(normalization.apply(0, &[1, 3], &data[..3]).is_err());
+
+        assert!(InputNormalization::from_mean_std(&[vec![0.0]], &[vec![0.0]]).is_err());
+        assert!(InputNormalization::from_mean_std(&[vec![0.0; 2]], &[vec![1.0; 3]]).is_err());
+    }
+
+    #[test]
+    fn test_field_inputs() {
+        let felt = Fp::from(258u64);
+        assert_eq!(felt_from_str("0x102"), Some(felt));
+        assert_eq!(felt_from_str(&format!("{:?}", felt)), Some(felt));
+        assert_eq!(felt_from_str("258"), Some(felt));
+        assert_eq!(felt_from_str("-258"), Some(-felt));
+        assert_eq!(felt_from_str("000"), Some(Fp::from(0u64)));
+        assert_eq!(felt_from_str("0x"), None);
+        assert_eq!(felt_from_str("1.5"), None);
+
+        // every format reads as the same field element, with the declared scales kept
+        let json = format!(
+            r#"{{"input_data": [[{}, "0x102", "258", 258, true]], "input_scales": [3]}}"#,
+            serde_json::to_string(&felt).unwrap()
+        );
+        let data: GraphData = serde_json::from_str(&json).unwrap();
+        assert_eq!(data.input_scales, Some(vec![3]));
+        let expected = vec![vec![felt, felt, felt, felt, Fp::from(1u64)]];
+        assert_eq!(
+            data.field_inputs(&[3]).unwrap(),
+            GraphData::new_field_inputs(expected, vec![3])
+                .field_inputs(&[3])
+                .unwrap()
+        );
+        assert!(data.field_inputs(&[4]).is_err());
+        assert_eq!(
+            GraphData::new(data.input_data.clone())
+                .field_inputs(&[3])
+                .unwrap(),
+            None
+        );
+
+        // the declared scales survive serialization, and are left out of float inputs
+        let round_trip: GraphData =
+            serde_json::from_str(&serde_json::to_string(&data).unwrap()).unwrap();
+        assert_eq!(round_trip, data);
+        let floats =
+            serde_json::to_string(&GraphData::new(DataSource::from(vec![vec![1.0]]))).unwrap();
+        assert!(!floats.contains("input_scales"));
+
+        let fractional: GraphData =
+            serde_json::from_str(r#"{"input_data": [[1.5]], "input_scales": [0]}"#).unwrap();
+        assert!(fractional.field_inputs(&[0]).is_err());
+    }
+
     //  test for the compatibility with the serialized elements from the mclbn256 library
     #[test]
     fn test_python_compat() {
@@ -0,0 +1,178 @@
+//! Introspection of compiled circuits, without the onnx model they were compiled from: the nodes of the model with
+//! their ops, shapes, scales and visibility, the lookups and range checks the circuit requires and what its instances
+//! hold, see [super::GraphCircuit::inspect].
+
+use super::vars::Visibility;
+use super::{InstanceSpan, Outlet};
+use crate::circuit::lookup::LookupOp;
+use crate::circuit::ops::Op;
+use crate::circuit::table::Range;
+use halo2curves::bn256::Fr as Fp;
+#[cfg(feature = "python-bindings")]
+use pyo3::prelude::*;
+#[cfg(feature = "python-bindings")]
+use pyo3::types::PyDict;
+#[cfg(feature = "python-bindings")]
+use pyo3::ToPyObject;
+use serde::{Deserialize, Serialize};
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+use tabled::{Table, Tabled};
+
+/// A node of the model of a compiled circuit, see [super::Model::nodes]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NodeInfo {
+    /// the index of the node, which subgraphs index their nodes apart from
+    pub idx: usize,
+    /// the index of the subgraph node the node is in, `None` in the main graph
+    pub subgraph: Option<usize>,
+    /// the name of the node in the onnx graph
+    pub name: String,
+    /// the op of the node, as `as_string()`
+    pub op: String,
+    /// the outlets of the nodes the node takes as inputs
+    pub inputs: Vec<Outlet>,
+    /// the shapes of the inputs
+    pub input_shapes: Vec<Vec<usize>>,
+    /// the shapes of the outputs
+    pub out_dims: Vec<Vec<usize>>,
+    /// the scales of the outputs
+    pub out_scales: Vec<crate::Scale>,
+    /// the visibility of the values of the node: that of the inputs for an input of the model, of the params for a
+    /// constant and of the output for an output of the model, `None` for the other nodes, whose values are private
+    pub visibility: Option<Visibility>,
+}
+
+/// What a compiled circuit lays out and exposes, as listed by `ezkl inspect`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CircuitInspection {
+    /// the names of the inputs of the model in the onnx graph
+    pub input_names: Vec<String>,
+    /// the names of the nodes of the outputs of the model
+    pub output_names: Vec<String>,
+    /// the nodes of the model, those of a subgraph following the subgraph node
+    pub nodes: Vec<NodeInfo>,
+    /// the ops the circuit looks up
+    pub required_lookups: Vec<LookupOp>,
+    /// the ranges the circuit checks
+    pub required_range_checks: Vec<Range>,
+    /// what the instances hold, in order
+    pub instance_layout: Vec<InstanceSpan>,
+}
+
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+#[derive(Tabled)]
+struct NodeRow {
+    idx: String,
+    name: String,
+    op: String,
+    inputs: String,
+    out_dims: String,
+    out_scales: String,
+    visibility: String,
+}
+
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+#[derive(Tabled)]
+struct InstanceRow {
+    start: usize,
+    len: usize,
+    holds: String,
+}
+
+impl CircuitInspection {
+    /// The ops the circuit looks up, as `as_string()`
+    pub fn lookup_names(&self) -> Vec<String> {
+        self.required_lookups
+            .iter()
+            .map(Op::<Fp>::as_string)
+            .collect()
+    }
+
+    /// Formats the nodes, lookups and instance layout as tables
+    #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+    pub fn table(&self) -> String {
+        let rows = self.nodes.iter().map(|n| NodeRow {
+            idx: match n.subgraph {
+                Some(subgraph) => format!("{}/{}", subgraph, n.idx),
+                None => n.idx.to_string(),
+            },
+            name: n.name.clone(),
+            op: n.op.clone(),
+            inputs: format!("{:?}", n.inputs),
+            out_dims: format!("{:?}", n.out_dims),
+            out_scales: format!("{:?}", n.out_scales),
+            visibility: n
+                .visibility
+                .as_ref()
+                .map(|v| v.to_string())
+                .unwrap_or_default(),
+        });
+        let mut nodes = Table::new(rows);
+        nodes.with(tabled::settings::Style::modern());
+
+        let spans = self.instance_layout.iter().map(|s| InstanceRow {
+            start: s.start,
+            len: s.len,
+            holds: s.label.clone(),
+        });
+        let mut instances = Table::new(spans);
+        instances.with(tabled::settings::Style::modern());
+
+        format!(
+            "{}\nlookups: {:?}\nrange checks: {:?}\n{}",
+            nodes,
+            self.lookup_names(),
+            self.required_range_checks,
+            instances
+        )
+    }
+}
+
+#[cfg(feature = "python-bindings")]
+impl ToPyObject for NodeInfo {
+    fn to_object(&self, py: Python) -> PyObject {
+        let dict = PyDict::new(py);
+        dict.set_item("idx", self.idx).unwrap();
+        dict.set_item("subgraph", self.subgraph).unwrap();
+        dict.set_item("name", &self.name).unwrap();
+        dict.set_item("op", &self.op).unwrap();
+        dict.set_item("inputs", &self.inputs).unwrap();
+        dict.set_item("input_shapes", &self.input_shapes).unwrap();
+        dict.set_item("out_dims", &self.out_dims).unwrap();
+        dict.set_item("out_scales", &self.out_scales).unwrap();
+        dict.set_item(
+            "visibility",
+            self.visibility.as_ref().map(|v| v.to_string()),
+        )
+        .unwrap();
+        dict.to_object(py)
+    }
+}
+
+#[cfg(feature = "python-bindings")]
+impl ToPyObject for CircuitInspection {
+    fn to_object(&self, py: Python) -> PyObject {
+        let dict = PyDict::new(py);
+        dict.set_item("input_names", &self.input_names).unwrap();
+        dict.set_item("output_names", &self.output_names).unwrap();
+        dict.set_item("nodes", &self.nodes).unwrap();
+        dict.set_item("required_lookups", self.lookup_names())
+            .unwrap();
+        dict.set_item("required_range_checks", &self.required_range_checks)
+            .unwrap();
+        let instances = self
+            .instance_layout
+            .iter()
+            .map(|s| {
+                let span = PyDict::new(py);
+                span.set_item("start", s.start).unwrap();
+                span.set_item("len", s.len).unwrap();
+                span.set_item("label", &s.label).unwrap();
+                span.set_item("digest_of", s.digest_of).unwrap();
+                span.to_object(py)
+            })
+            .collect::<Vec<_>>();
+        dict.set_item("instance_layout", instances).unwrap();
+        dict.to_object(py)
+    }
+}
@@ -0,0 +1,359 @@
+//! What each instance of a circuit holds, and the instances of proofs decoded into the named, dequantized inputs and
+//! outputs of the model, such that the consumers of a proof don't reverse-engineer its flat array of field elements.
+//!
+//! The map of the instances (see [super::GraphSettings::instance_map]) labels each instance with its role, the input
+//! or output of the model it belongs to, its position in it and its scale. It follows the order of
+//! [super::GraphSettings::instance_layout], which lists the same instances as runs.
+
+use super::errors::GraphError;
+use super::limbs::unpack_limbs;
+use super::utilities::dequantize;
+use super::{GraphSettings, InstanceSpan};
+use halo2curves::bn256::Fr as Fp;
+use serde::{Deserialize, Serialize};
+
+/// What an instance of a circuit holds
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InstanceRole {
+    /// the merkle root of the dataset the inputs belong to
+    MerkleRoot,
+    /// (a limb of) the digest of a hashed input
+    InputDigest,
+    /// (a coordinate of) the public key of the signer of the inputs
+    SignerPublicKey,
+    /// (a limb of) the digest of the params
+    ParamsDigest,
+    /// a value of a public input
+    InputValue,
+    /// a value of a public output
+    OutputValue,
+    /// several values of a public output, packed as limbs (see [super::limbs])
+    PackedOutputValues,
+    /// (a limb of) the digest of a hashed output
+    OutputDigest,
+    /// (a coordinate of) the public key the outputs are encrypted to
+    RecipientPublicKey,
+    /// a value of the ciphertext of the encrypted outputs
+    Ciphertext,
+    /// the digest of the previous step of a session
+    PreviousSessionDigest,
+    /// the session digest of the outputs
+    SessionDigest,
+    /// the nullifier of the inputs
+    Nullifier,
+    /// an instance the layout doesn't account for
+    Unlabelled,
+}
+
+/// An instance of a circuit and what it holds
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstanceLabel {
+    /// the index of the instance, the instance columns being concatenated
+    pub index: usize,
+    /// what the instance holds
+    pub role: InstanceRole,
+    /// the name of the input or output of the model the instance belongs to, if any
+    pub tensor: Option<String>,
+    /// the index of the value in the shape of the instances of its tensor, for values, else the offset of the instance
+    /// in its run (as the limb of a digest)
+    pub position: Vec<usize>,
+    /// the scale the values of the instance are quantized at, for the values of inputs and outputs
+    pub scale: Option<crate::Scale>,
+}
+
+/// A run of instances being laid out, see [GraphSettings::instance_layout]
+#[derive(Clone, Debug)]
+pub(crate) struct InstanceRun {
+    pub(crate) len: usize,
+    pub(crate) label: String,
+    pub(crate) role: InstanceRole,
+    /// the model input (`false`) or output (`true`) and its index, if the instances hold its digest
+    pub(crate) digest_of: Option<(bool, usize)>,
+    /// the model input (`false`) or output (`true`) and its index, if the instances belong to it
+    pub(crate) tensor: Option<(bool, usize)>,
+    /// the shape of the instances
+    pub(crate) shape: Vec<usize>,
+}
+
+impl InstanceRun {
+    /// A run of instances that don't belong to an input or output of the model
+    pub(crate) fn new(len: usize, label: String, role: InstanceRole) -> Self {
+        InstanceRun {
+            len,
+            label,
+            role,
+            digest_of: None,
+            tensor: None,
+            shape: vec![len],
+        }
+    }
+
+    /// The run of the digest of a model input (`false`) or output (`true`)
+    pub(crate) fn digest(len: usize, label: String, role: InstanceRole, of: (bool, usize)) -> Self {
+        InstanceRun {
+            digest_of: Some(of),
+            tensor: Some(of),
+            ..Self::new(len, label, role)
+        }
+    }
+
+    /// The run of the values of a model input (`false`) or output (`true`), of the shape of its instances
+    pub(crate) fn values(
+        shape: Vec<usize>,
+        label: String,
+        role: InstanceRole,
+        of: (bool, usize),
+    ) -> Self {
+        InstanceRun {
+            tensor: Some(of),
+            shape: shape.clone(),
+            ..Self::new(shape.iter().product(), label, role)
+        }
+    }
+
+    /// The labels of the instances of the run, laid out at `span`
+    pub(crate) fn labels(
+        &self,
+        span: &InstanceSpan,
+        settings: &GraphSettings,
+    ) -> Vec<InstanceLabel> {
+        let tensor = self
+            .tensor
+            .map(|(output, idx)| settings.tensor_name(output, idx));
+        let scale = match (self.role, self.tensor) {
+            (InstanceRole::InputValue, Some((_, idx))) => {
+                settings.model_input_scales.get(idx).copied()
+            }
+            (InstanceRole::OutputValue | InstanceRole::PackedOutputValues, Some((_, idx))) => {
+                settings.model_output_scales.get(idx).copied()
+            }
+            _ => None,
+        };
+        (0..span.len)
+            .map(|offset| InstanceLabel {
+                index: span.start + offset,
+                role: self.role,
+                tensor: tensor.clone(),
+                position: unravel(offset, &self.shape),
+                scale,
+            })
+            .collect()
+    }
+}
+
+/// The index in `shape` of the value at `offset` in the row-major order of its values
+fn unravel(mut offset: usize, shape: &[usize]) -> Vec<usize> {
+    if shape.iter().product::<usize>() <= offset {
+        return vec![offset];
+    }
+    let mut position = vec![0; shape.len()];
+    for (i, dim) in shape.iter().enumerate().rev() {
+        position[i] = offset % dim;
+        offset /= dim;
+    }
+    position
+}
+
+/// An input or output of the model decoded from the instances of a proof
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DecodedTensor {
+    /// the name of the input or output
+    pub name: String,
+    /// the index of the input or output
+    pub idx: usize,
+    /// the shape of the values, that of their instances (flattened for the outputs packed as limbs)
+    pub shape: Vec<usize>,
+    /// the scale the values were quantized at
+    pub scale: crate::Scale,
+    /// the dequantized values, in row-major order
+    pub values: Vec<f64>,
+}
+
+/// The public inputs and outputs of the model decoded from the instances of a proof, in order. The inputs and outputs
+/// that are hashed, encrypted or private aren't among them.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct DecodedInstances {
+    /// the public inputs
+    pub inputs: Vec<DecodedTensor>,
+    /// the public outputs
+    pub outputs: Vec<DecodedTensor>,
+}
+
+impl DecodedInstances {
+    /// The output of a name, if it is public
+    pub fn output(&self, name: &str) -> Option<&DecodedTensor> {
+        self.outputs.iter().find(|t| t.name == name)
+    }
+}
+
+/// Decodes the instances of a proof of a circuit of `settings` into its named, dequantized public inputs and outputs,
+/// the instance columns being concatenated
+pub fn decode_instances(
+    instances: &[Fp],
+    settings: &GraphSettings,
+) -> Result<DecodedInstances, GraphError> {
+    let total = settings.total_instances().iter().sum::<usize>();
+    if instances.len() != total {
+        return Err(GraphError::InvalidInstances(format!(
+            "expected {} instances, got {}",
+            total,
+            instances.len()
+        )));
+    }
+
+    let mut decoded = DecodedInstances::default();
+    for (span, run) in settings.instance_runs() {
+        let Some((output, idx)) = run.tensor else {
+            continue;
+        };
+        let dequantized = |felts: &[Fp], scale| {
+            felts
+                .iter()
+                .map(|felt| dequantize(*felt, scale, 0.0))
+                .collect::<Vec<_>>()
+        };
+        let slice = &instances[span.start..span.start + span.len];
+        let (shape, scale, values) = match run.role {
+            InstanceRole::InputValue => {
+                let scale = settings.model_input_scales[idx];
+                (run.shape, scale, dequantized(slice, scale))
+            }
+            InstanceRole::OutputValue => {
+                let scale = settings.model_output_scales[idx];
+                (run.shape, scale, dequantized(slice, scale))
+            }
+            InstanceRole::PackedOutputValues => {
+                let tensor = settings.limb_packed_output(idx).ok_or_else(|| {
+                    GraphError::InvalidInstances(format!("output {} isn't packed", idx))
+                })?;
+                let scale = settings.model_output_scales[idx];
+                let values = unpack_limbs(tensor, slice)?;
+                (vec![tensor.len], scale, dequantized(&values, scale))
+            }
+            _ => continue,
+        };
+        let tensor = DecodedTensor {
+            name: settings.tensor_name(output, idx),
+            idx,
+            shape,
+            scale,
+            values,
+        };
+        match output {
+            true => decoded.outputs.push(tensor),
+            false => decoded.inputs.push(tensor),
+        }
+    }
+    Ok(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fieldutils::{integer_rep_to_felt, IntegerRep};
+    use crate::graph::limbs::{limb_bits, pack_limbs, LimbPackedTensor};
+    use crate::graph::Visibility;
+
+    fn settings() -> GraphSettings {
+        let mut settings = GraphSettings {
+            model_input_names: vec!["x".into()],
+            model_output_names: vec!["logits".into(), "probs".into()],
+            model_input_scales: vec![7],
+            model_output_scales: vec![2, 4],
+            model_instance_shapes: vec![vec![2], vec![2, 2], vec![3]],
+            ..Default::default()
+        };
+        settings.run_args.input_visibility = Visibility::Public;
+        settings
+    }
+
+    #[test]
+    fn instances_labelled_and_decoded() {
+        let settings = settings();
+        let map = settings.instance_map();
+        assert_eq!(map.len(), 2 + 4 + 3);
+        assert_eq!(map[2].role, InstanceRole::OutputValue);
+        assert_eq!(map[3].tensor.as_deref(), Some("logits"));
+        assert_eq!(map[3].position, vec![0, 1]);
+        assert_eq!(map[5].position, vec![1, 1]);
+        assert_eq!(map[6].scale, Some(4));
+        assert_eq!(map[0].scale, Some(7));
+
+        let felts = |values: &[IntegerRep]| {
+            values
+                .iter()
+                .map(|v| integer_rep_to_felt::<Fp>(*v))
+                .collect::<Vec<_>>()
+        };
+        let instances = [
+            felts(&[128, -64]),
+            felts(&[1, 2, -3, 4]),
+            felts(&[16, 8, 4]),
+        ]
+        .concat();
+        let decoded = decode_instances(&instances, &settings).unwrap();
+        assert_eq!(decoded.inputs[0].name, "x");
+        assert_eq!(decoded.inputs[0].values, vec![1.0, -0.5]);
+        let logits = decoded.output("logits").unwrap();
+        assert_eq!(logits.shape, vec![2, 2]);
+        assert_eq!(logits.values, vec![0.25, 0.5, -0.75, 1.0]);
+        assert_eq!(
+            decoded.output("probs").unwrap().values,
+            vec![1.0, 0.5, 0.25]
+        );
+
+        assert!(matches!(
+            decode_instances(&instances[1..], &settings),
+            Err(GraphError::InvalidInstances(_))
+        ));
+    }
+
+    #[test]
+    fn hashed_and_packed_outputs_decoded() {
+        let mut settings = settings();
+        settings.run_args.input_visibility = Visibility::Private;
+        // the first output is hashed, the second packed two values per instance
+        settings.run_args.output_visibilities = vec![
+            Visibility::Hashed {
+                hash_is_public: true,
+                outlets: vec![],
+            },
+            Visibility::Public,
+        ];
+        let packed = LimbPackedTensor {
+            idx: 1,
+            len: 3,
+            limbs: 2,
+            limb_bits: limb_bits(16384, 2),
+        };
+        settings.model_instance_shapes = vec![vec![packed.num_instances()]];
+        settings.limb_packed_outputs = vec![packed.clone()];
+        // the digest of the hashed output is the single instance of the poseidon module
+        settings.module_sizes =
+            serde_json::from_value(serde_json::json!({"polycommit": [], "poseidon": [0, [1]]}))
+                .unwrap();
+
+        let map = settings.instance_map();
+        let roles = map.iter().map(|l| l.role).collect::<Vec<_>>();
+        assert_eq!(
+            roles,
+            vec![
+                InstanceRole::PackedOutputValues,
+                InstanceRole::PackedOutputValues,
+                InstanceRole::OutputDigest,
+            ]
+        );
+        assert_eq!(map[2].tensor.as_deref(), Some("logits"));
+        assert_eq!(map[2].scale, None);
+
+        let values = [16, -8, 4].map(integer_rep_to_felt::<Fp>);
+        let mut instances = pack_limbs(&packed, &values).unwrap();
+        instances.push(Fp::from(42));
+        let decoded = decode_instances(&instances, &settings).unwrap();
+        assert!(decoded.output("logits").is_none());
+        let probs = decoded.output("probs").unwrap();
+        assert_eq!(probs.shape, vec![3]);
+        assert_eq!(probs.values, vec![1.0, -0.5, 0.25]);
+    }
+}
@@ -0,0 +1,200 @@
+//! Public outputs packed several values to an instance, as the limbs of the instance (see
+//! [crate::RunArgs::instance_limbs]).
+//!
+//! A value `v` of a packed output is bounded in the circuit by the decomposition of the run args, to
+//! `|v| < decomp_base^decomp_legs`, and biased to `v + 2^(w - 1)` in `[0, 2^w)` for limbs of `w` bits. The biased values
+//! are packed `limbs` at a time into instances of at most [MAX_PACKED_BITS] bits, the first value in the least
+//! significant limb, below the modulus of the field such that the packing is injective. The last instance of an output
+//! is padded with zero limbs.
+
+use super::errors::GraphError;
+use crate::fieldutils::{felt_to_integer_rep, integer_rep_to_felt, IntegerRep};
+use halo2curves::bn256::Fr as Fp;
+use halo2curves::ff::{Field, PrimeField};
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// The bits of an instance the limbs can take, the modulus of the field being of 254 bits
+pub const MAX_PACKED_BITS: usize = 253;
+
+/// A public output packed several values to an instance
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), derive(JsonSchema))]
+pub struct LimbPackedTensor {
+    /// the index of the output
+    pub idx: usize,
+    /// the number of values of the output
+    pub len: usize,
+    /// the number of values packed to an instance
+    pub limbs: usize,
+    /// the bits of a limb
+    pub limb_bits: usize,
+}
+
+impl LimbPackedTensor {
+    /// The number of instances the values of the output are packed to
+    pub fn num_instances(&self) -> usize {
+        self.len.div_ceil(self.limbs)
+    }
+
+    fn bias(&self) -> IntegerRep {
+        1 << (self.limb_bits - 1)
+    }
+}
+
+/// The bits of a limb holding the values of a decomposition of `legs` legs of base `base`, biased by half the limb
+pub fn limb_bits(base: usize, legs: usize) -> usize {
+    // base^legs <= 2^(legs * ceil(log2(base)))
+    let base_bits = (usize::BITS - base.saturating_sub(1).leading_zeros()) as usize;
+    legs * base_bits + 1
+}
+
+/// The multiplier of the limb at `limb` of an instance
+pub fn limb_shift(limb: usize, limb_bits: usize) -> Fp {
+    Fp::from(2).pow_vartime([(limb * limb_bits) as u64])
+}
+
+/// Packs the values of a packed output into its instances. A value that doesn't fit a limb is an error, as the circuit
+/// isn't satisfied by it.
+pub fn pack_limbs(tensor: &LimbPackedTensor, values: &[Fp]) -> Result<Vec<Fp>, GraphError> {
+    if values.len() != tensor.len {
+        return Err(GraphError::InvalidLimbPacking(format!(
+            "output {} has {} values but the settings pack {}",
+            tensor.idx,
+            values.len(),
+            tensor.len
+        )));
+    }
+    let bias = tensor.bias();
+    values
+        .chunks(tensor.limbs)
+        .map(|chunk| {
+            chunk
+                .iter()
+                .enumerate()
+                .try_fold(Fp::ZERO, |packed, (limb, value)| {
+                    let value = felt_to_integer_rep(*value);
+                    if !(-bias..bias).contains(&value) {
+                        return Err(GraphError::InvalidLimbPacking(format!(
+                            "value {} of output {} doesn't fit a limb of {} bits, raise the decomposition",
+                            value, tensor.idx, tensor.limb_bits
+                        )));
+                    }
+                    Ok(packed
+                        + integer_rep_to_felt::<Fp>(value + bias)
+                            * limb_shift(limb, tensor.limb_bits))
+                })
+        })
+        .collect()
+}
+
+/// The bits `start..start + len` of a little-endian representation
+fn bits(bytes: &[u8], start: usize, len: usize) -> u128 {
+    (0..len).fold(0, |acc, b| {
+        let bit = start + b;
+        acc | ((((bytes[bit / 8] >> (bit % 8)) & 1) as u128) << b)
+    })
+}
+
+/// The values of a packed output, unpacked from its instances. Instances that aren't a packing of values (as of
+/// non-zero padding limbs) are an error.
+pub fn unpack_limbs(tensor: &LimbPackedTensor, instances: &[Fp]) -> Result<Vec<Fp>, GraphError> {
+    if instances.len() != tensor.num_instances() {
+        return Err(GraphError::InvalidLimbPacking(format!(
+            "output {} is packed to {} instances, not {}",
+            tensor.idx,
+            tensor.num_instances(),
+            instances.len()
+        )));
+    }
+    let bias = tensor.bias();
+    let mut values = instances
+        .iter()
+        .flat_map(|instance| {
+            let repr = instance.to_repr();
+            (0..tensor.limbs)
+                .map(|limb| {
+                    let biased = bits(repr.as_ref(), limb * tensor.limb_bits, tensor.limb_bits);
+                    integer_rep_to_felt(biased as IntegerRep - bias)
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    values.truncate(tensor.len);
+
+    if pack_limbs(tensor, &values)? != instances {
+        return Err(GraphError::InvalidLimbPacking(format!(
+            "the instances of output {} aren't a packing of its values",
+            tensor.idx
+        )));
+    }
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn packed(len: usize, limbs: usize) -> LimbPackedTensor {
+        LimbPackedTensor {
+            idx: 0,
+            len,
+            limbs,
+            limb_bits: limb_bits(16384, 2),
+        }
+    }
+
+    #[test]
+    fn limbs_fit_the_decomposition() {
+        assert_eq!(limb_bits(16384, 2), 29);
+        assert_eq!(limb_bits(10, 2), 9);
+        assert_eq!(limb_bits(2, 8), 9);
+    }
+
+    #[test]
+    fn packed_values_round_trip() {
+        let max = (1 << 28) - 1;
+        let values = [0, 1, -1, 42, -max, max, 7]
+            .into_iter()
+            .map(integer_rep_to_felt::<Fp>)
+            .collect::<Vec<_>>();
+
+        for limbs in [2, 3, 8] {
+            let tensor = packed(values.len(), limbs);
+            let instances = pack_limbs(&tensor, &values).unwrap();
+            assert_eq!(instances.len(), values.len().div_ceil(limbs));
+            assert_eq!(unpack_limbs(&tensor, &instances).unwrap(), values);
+        }
+
+        // the first value is in the lowest limb, biased by half the limb
+        let tensor = packed(2, 2);
+        let instances = pack_limbs(&tensor, &values[1..3]).unwrap();
+        let bias = Fp::from(1 << 28);
+        assert_eq!(
+            instances,
+            vec![(Fp::ONE + bias) + (bias - Fp::ONE) * limb_shift(1, 29)]
+        );
+    }
+
+    #[test]
+    fn invalid_packings() {
+        // a value out of the decomposition isn't packed
+        let tensor = packed(2, 2);
+        let out_of_range = [Fp::ZERO, integer_rep_to_felt(1 << 28)];
+        assert!(pack_limbs(&tensor, &out_of_range).is_err());
+
+        // nor are instances with bits set in the padding limb unpacked
+        let tensor = packed(3, 2);
+        let mut instances = pack_limbs(&tensor, &[Fp::ONE, Fp::ONE, Fp::ONE]).unwrap();
+        instances[1] += limb_shift(1, 29);
+        assert!(matches!(
+            unpack_limbs(&tensor, &instances),
+            Err(GraphError::InvalidLimbPacking(_))
+        ));
+        // or with bits above the last limb
+        instances[1] -= limb_shift(1, 29);
+        instances[0] += limb_shift(2, 29);
+        assert!(unpack_limbs(&tensor, &instances).is_err());
+    }
+}
@@ -0,0 +1,238 @@
+//! Chains of circuits, where the outputs of a model are the inputs of the next, proven separately without revealing
+//! the tensors passed between them.
+//!
+//! The linked outputs of a circuit are those whose visibility is `hashed/public` (of either hash function) or
+//! `polycommit`, and they are linked in order to the inputs of the next circuit, which must then all have the same
+//! visibility. A hashed tensor is bound to both proofs by its digest, an instance of each, and a committed tensor by
+//! the KZG commitments of the advice columns it is assigned to, which only coincide when both circuits share their
+//! logrows and columns (and blinders, see `--polycommit-blinding`).
+
+use super::errors::GraphError;
+use super::{GraphSettings, Visibility};
+use halo2curves::bn256::Fr as Fp;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+/// How a tensor passed from a circuit to the next is bound to both proofs
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LinkKind {
+    /// the poseidon digest of the tensor is an instance of both proofs
+    Poseidon,
+    /// the sha256 digest of the tensor is an instance of both proofs
+    Sha256,
+    /// the tensor is committed to by the advice commitments of both proofs
+    Polycommit,
+}
+
+impl LinkKind {
+    fn of(visibility: &Visibility) -> Option<Self> {
+        if visibility.is_polycommit() {
+            Some(LinkKind::Polycommit)
+        } else if !visibility.is_hashed_public() {
+            None
+        } else if visibility.is_sha256_hashed() {
+            Some(LinkKind::Sha256)
+        } else {
+            Some(LinkKind::Poseidon)
+        }
+    }
+}
+
+/// An output of a circuit linked to an input of the next
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TensorLink {
+    /// the output of the first circuit
+    pub output: usize,
+    /// the input of the next circuit
+    pub input: usize,
+    /// how the tensor is bound to both proofs
+    pub kind: LinkKind,
+    /// the instances holding the digest of the output in the first proof and of the input in the next, for digests
+    pub instances: Option<(Range<usize>, Range<usize>)>,
+}
+
+/// The instances of the digest of a model input or output among those of a proof
+fn digest_instances(
+    settings: &GraphSettings,
+    output: bool,
+    idx: usize,
+) -> Result<Range<usize>, GraphError> {
+    settings
+        .instance_layout()
+        .into_iter()
+        .find(|span| span.digest_of == Some((output, idx)))
+        .map(|span| span.start..span.start + span.len)
+        .ok_or_else(|| {
+            let tensor = if output { "output" } else { "input" };
+            GraphError::UnlinkableCircuits(format!(
+                "no instances hold the digest of {} {}",
+                tensor, idx
+            ))
+        })
+}
+
+/// Links the hashed and committed outputs of a circuit to the inputs of the next, in order
+pub fn link_tensors(
+    from: &GraphSettings,
+    to: &GraphSettings,
+) -> Result<Vec<TensorLink>, GraphError> {
+    let outputs = (0..from.model_output_scales.len())
+        .filter_map(|i| LinkKind::of(from.run_args.visibility_of_output(i)).map(|kind| (i, kind)))
+        .collect::<Vec<_>>();
+    let input_kind = LinkKind::of(&to.run_args.input_visibility).ok_or_else(|| {
+        GraphError::UnlinkableCircuits(format!(
+            "the inputs of the next circuit are {}, they must be hashed/public or polycommit",
+            to.run_args.input_visibility
+        ))
+    })?;
+    if outputs.is_empty() || outputs.len() != to.model_input_scales.len() {
+        return Err(GraphError::UnlinkableCircuits(format!(
+            "{} outputs of the first circuit are hashed/public or polycommit but the next circuit has {} inputs",
+            outputs.len(),
+            to.model_input_scales.len()
+        )));
+    }
+
+    outputs
+        .into_iter()
+        .enumerate()
+        .map(|(input, (output, kind))| {
+            if kind != input_kind {
+                return Err(GraphError::UnlinkableCircuits(format!(
+                    "output {} is linked by {:?} but input {} by {:?}",
+                    output, kind, input, input_kind
+                )));
+            }
+            let (output_scale, input_scale) =
+                (from.model_output_scales[output], to.model_input_scales[input]);
+            if output_scale != input_scale {
+                return Err(GraphError::UnlinkableCircuits(format!(
+                    "output {} has scale {} but input {} has scale {}",
+                    output, output_scale, input, input_scale
+                )));
+            }
+            let instances = match kind {
+                LinkKind::Polycommit => {
+                    if from.run_args.logrows != to.run_args.logrows
+                        || from.run_args.num_inner_cols != to.run_args.num_inner_cols
+                    {
+                        return Err(GraphError::UnlinkableCircuits(
+                            "committed tensors are only linked between circuits of the same logrows and columns"
+                                .to_string(),
+                        ));
+                    }
+                    None
+                }
+                LinkKind::Poseidon | LinkKind::Sha256 => Some((
+                    digest_instances(from, true, output)?,
+                    digest_instances(to, false, input)?,
+                )),
+            };
+            Ok(TensorLink {
+                output,
+                input,
+                kind,
+                instances,
+            })
+        })
+        .collect()
+}
+
+/// Checks that the digests of the linked outputs among the instances of proof `proof` of a chain are those of the
+/// inputs among the instances of the next. Committed tensors are checked against the commitments of the proofs.
+pub fn check_digest_links(
+    links: &[TensorLink],
+    proof: usize,
+    from_instances: &[Fp],
+    to_instances: &[Fp],
+) -> Result<(), GraphError> {
+    for link in links {
+        if let Some((output, input)) = &link.instances {
+            if from_instances.get(output.clone()) != to_instances.get(input.clone()) {
+                return Err(GraphError::BrokenLink {
+                    proof,
+                    output: link.output,
+                    input: link.input,
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RunArgs;
+
+    fn settings(input_visibility: Visibility, output_visibility: Visibility) -> GraphSettings {
+        GraphSettings {
+            run_args: RunArgs {
+                input_visibility,
+                output_visibility,
+                ..Default::default()
+            },
+            model_input_scales: vec![7],
+            model_output_scales: vec![7],
+            // the digests and public outputs are laid out in a single instance here
+            model_instance_shapes: vec![vec![1]],
+            ..Default::default()
+        }
+    }
+
+    fn hashed() -> Visibility {
+        Visibility::Hashed {
+            hash_is_public: true,
+            outlets: vec![],
+        }
+    }
+
+    #[test]
+    fn hashed_output_links_to_hashed_input() {
+        let encoder = settings(Visibility::Private, hashed());
+        let head = settings(hashed(), Visibility::Public);
+
+        let links = link_tensors(&encoder, &head).unwrap();
+        assert_eq!(
+            links,
+            vec![TensorLink {
+                output: 0,
+                input: 0,
+                kind: LinkKind::Poseidon,
+                instances: Some((0..1, 0..1)),
+            }]
+        );
+
+        let digest = Fp::from(42);
+        assert!(check_digest_links(&links, 0, &[digest], &[digest]).is_ok());
+        assert!(matches!(
+            check_digest_links(&links, 0, &[digest], &[Fp::from(43)]),
+            Err(GraphError::BrokenLink {
+                proof: 0,
+                output: 0,
+                input: 0
+            })
+        ));
+    }
+
+    #[test]
+    fn mismatched_circuits_dont_link() {
+        let encoder = settings(Visibility::Private, hashed());
+
+        // the inputs the digest would be checked against aren't hashed
+        let public = settings(Visibility::Public, Visibility::Public);
+        assert!(matches!(
+            link_tensors(&encoder, &public),
+            Err(GraphError::UnlinkableCircuits(_))
+        ));
+
+        // a hashed tensor isn't linked to a committed one
+        let committed = settings(Visibility::KZGCommit, Visibility::Public);
+        assert!(link_tensors(&encoder, &committed).is_err());
+
+        // nor to a tensor of another scale
+        let mut rescaled = settings(hashed(), Visibility::Public);
+        rescaled.model_input_scales = vec![8];
+        assert!(link_tensors(&encoder, &rescaled).is_err());
+    }
+}
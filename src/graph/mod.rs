@@ -1,18 +1,52 @@
+/// Families of circuits of a model at several batch sizes, proving the samples with the smallest that fits them.
+#[cfg(not(all(feature = "verifier-only", not(feature = "ezkl"))))]
+pub mod batch;
 /// Representations of a computational graph's inputs.
 pub mod input;
+/// Introspection of the nodes, lookups and instances of compiled circuits.
+#[cfg(not(all(feature = "verifier-only", not(feature = "ezkl"))))]
+pub mod inspect;
+/// Maps of what each instance of a circuit holds, and the instances of proofs decoded into named, dequantized tensors.
+pub mod instances;
+/// Public outputs packed several values to an instance, as its limbs.
+pub mod limbs;
+/// Linking of the outputs of a circuit to the inputs of the next, for chains of separately proven models
+#[cfg(not(all(feature = "verifier-only", not(feature = "ezkl"))))]
+pub mod link;
 /// Crate for defining a computational graph and building a ZK-circuit from it.
+#[cfg(not(all(feature = "verifier-only", not(feature = "ezkl"))))]
 pub mod model;
 /// Representations of a computational graph's modules.
 pub mod modules;
 /// Inner elements of a computational graph that represent a single operation / constraints.
+#[cfg(not(all(feature = "verifier-only", not(feature = "ezkl"))))]
 pub mod node;
+/// Groups of params committed to separately, whose commitments are swapped into proofs and checked on their own.
+#[cfg(not(all(feature = "verifier-only", not(feature = "ezkl"))))]
+pub mod param_groups;
+/// Per-op breakdowns of the rows, lookups and equality constraints of a circuit.
+#[cfg(not(all(feature = "verifier-only", not(feature = "ezkl"))))]
+pub mod profile;
 /// postgres helper functions
 #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
 pub mod postgres;
 /// Helper functions
 pub mod utilities;
+/// Validation of witnesses against a circuit, attributing the constraints they fail to the nodes of the model.
+#[cfg(not(all(feature = "verifier-only", not(feature = "ezkl"))))]
+pub mod validate;
 /// Representations of a computational graph's variables.
 pub mod vars;
+/// JSON schemas of the settings, input and witness files, which are loaded strictly against them
+pub mod schema;
+/// Sessions of proofs chained by the digests of their outputs, such that they can't be reordered
+#[cfg(not(all(feature = "verifier-only", not(feature = "ezkl"))))]
+pub mod session;
+/// Binary sidecars holding the large tensors of witnesses next to their json
+#[cfg(not(all(feature = "verifier-only", not(feature = "ezkl"))))]
+pub mod sidecar;
+/// Weights of models overridden from safetensors files when they are compiled
+pub mod weights;
 
 /// errors for the graph
 pub mod errors;
@@ -23,6 +57,7 @@ use colored_json::ToColoredJson;
 use gag::Gag;
 use halo2_proofs::plonk::VerifyingKey;
 use halo2_proofs::poly::commitment::CommitmentScheme;
+use halo2_proofs::poly::kzg::commitment::KZGCommitmentScheme;
 pub use input::DataSource;
 use itertools::Itertools;
 #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
@@ -30,15 +65,40 @@ use tosubcommand::ToFlags;
 
 use self::errors::GraphError;
 #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
-use self::input::OnChainSource;
-use self::input::{FileSource, GraphData};
-use self::modules::{GraphModules, ModuleConfigs, ModuleForwardResult, ModuleSizes};
-use crate::circuit::lookup::LookupOp;
+use self::input::{save_npz, FileSourceInner, NpyArray, OnChainSource, StorageProofSource};
+use self::input::{FileSource, GraphData, ImagePreprocessing, InputNormalization, PinnedBlock};
+#[cfg(not(all(feature = "verifier-only", not(feature = "ezkl"))))]
+use self::inspect::{CircuitInspection, NodeInfo};
+use self::instances::{InstanceLabel, InstanceRole, InstanceRun};
+use self::modules::{
+    GraphModules, ModuleConfigs, ModuleElGamal, ModuleForwardResult, ModuleMerkle, ModuleNullifier,
+    ModuleSession, ModuleSizes, ECDSA_INSTANCES, ELGAMAL_HEADER_INSTANCES, NULLIFIER_INSTANCES,
+    SESSION_INSTANCES, SHA256_INSTANCES,
+};
+#[cfg(not(all(feature = "verifier-only", not(feature = "ezkl"))))]
+use self::profile::ModelLayout;
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+use self::schema::FeltSchema;
+use self::schema::{from_json_slice, JsonArtifact};
+#[cfg(not(all(feature = "verifier-only", not(feature = "ezkl"))))]
+use self::sidecar::{SidecarField, WitnessSidecar};
+use crate::circuit::lookup::{LookupOp, NodeLookupRange};
+use crate::circuit::modules::ecdsa::{
+    EcdsaSignature, InputSignature, InputSignatureWitness, SignatureHash,
+};
+use crate::circuit::modules::elgamal::{
+    CurvePoint, ElGamalCiphertext, EncryptionWitness, OutputEncryption,
+};
+use crate::circuit::modules::merkle::MerkleTree;
+use crate::circuit::modules::nullifier::NullifierWitness;
+use crate::circuit::modules::polycommit::PolyCommitBlinders;
+use crate::circuit::modules::session::SessionWitness;
 use crate::circuit::modules::ModulePlanner;
-use crate::circuit::region::{ConstantsMap, RegionSettings};
+use crate::circuit::region::{ConstantsMap, OpProfile, RegionSettings};
 use crate::circuit::table::{num_cols_required, Range, Table, RESERVED_BLINDING_ROWS_PAD};
 use crate::circuit::{CheckMode, InputType};
-use crate::fieldutils::{felt_to_f64, IntegerRep};
+use crate::fieldutils::{felt_to_f64, felt_to_integer_rep, IntegerRep};
+use crate::pfsys::progress::ProvingPhase;
 use crate::pfsys::PrettyElements;
 use crate::tensor::{Tensor, ValTensor};
 use crate::{RunArgs, EZKL_BUF_CAPACITY};
@@ -52,8 +112,10 @@ use halo2curves::ff::{Field, PrimeField};
 #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
 use lazy_static::lazy_static;
 use log::{debug, error, trace, warn};
-use maybe_rayon::prelude::{IntoParallelRefIterator, ParallelIterator};
+use maybe_rayon::prelude::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
+#[cfg(not(all(feature = "verifier-only", not(feature = "ezkl"))))]
 pub use model::*;
+#[cfg(not(all(feature = "verifier-only", not(feature = "ezkl"))))]
 pub use node::*;
 #[cfg(feature = "python-bindings")]
 use pyo3::prelude::*;
@@ -61,6 +123,8 @@ use pyo3::prelude::*;
 use pyo3::types::PyDict;
 #[cfg(feature = "python-bindings")]
 use pyo3::ToPyObject;
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::ops::Deref;
 pub use utilities::*;
@@ -112,20 +176,79 @@ thread_local!(
     pub static GLOBAL_SETTINGS: RefCell<Option<GraphSettings>> = const { RefCell::new(None) }
 );
 
+thread_local!(
+    /// The error the layout of the model last failed with, which halo2 only gets to report as a synthesis error
+    static LAYOUT_ERROR: RefCell<Option<GraphError>> = const { RefCell::new(None) }
+);
+
+/// Records the error the layout of the model failed with, such that it can be surfaced past halo2
+pub(crate) fn set_layout_error(e: Option<GraphError>) {
+    LAYOUT_ERROR.with(|error| *error.borrow_mut() = e);
+}
+
+/// Takes the error the layout of the model last failed with on this thread, if any
+pub fn take_layout_error() -> Option<GraphError> {
+    LAYOUT_ERROR.with(|error| error.borrow_mut().take())
+}
+
+#[cfg(not(all(feature = "verifier-only", not(feature = "ezkl"))))]
+thread_local!(
+    /// What the layout of the model region last laid out, which halo2 doesn't report
+    static MODEL_LAYOUT: RefCell<Option<ModelLayout>> = const { RefCell::new(None) }
+);
+
+/// Records what the layout of the model region laid out
+#[cfg(not(all(feature = "verifier-only", not(feature = "ezkl"))))]
+pub(crate) fn set_model_layout(layout: ModelLayout) {
+    MODEL_LAYOUT.with(|l| *l.borrow_mut() = Some(layout));
+}
+
+/// Takes what the layout of the model region last laid out on this thread, if any, to check it against a
+/// [profile::LayoutEstimate]
+#[cfg(not(all(feature = "verifier-only", not(feature = "ezkl"))))]
+pub fn take_model_layout() -> Option<ModelLayout> {
+    MODEL_LAYOUT.with(|l| l.borrow_mut().take())
+}
+
 /// Result from a forward pass
+#[cfg(not(all(feature = "verifier-only", not(feature = "ezkl"))))]
 #[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), derive(JsonSchema))]
 pub struct GraphWitness {
     /// The inputs of the forward pass
+    #[cfg_attr(
+        all(feature = "ezkl", not(target_arch = "wasm32")),
+        schemars(with = "Vec<Vec<FeltSchema>>")
+    )]
     pub inputs: Vec<Vec<Fp>>,
     /// The prettified outputs of the forward pass, we use a String to maximize compatibility with Python and JS clients
     pub pretty_elements: Option<PrettyElements>,
     /// The output of the forward pass
+    #[cfg_attr(
+        all(feature = "ezkl", not(target_arch = "wasm32")),
+        schemars(with = "Vec<Vec<FeltSchema>>")
+    )]
     pub outputs: Vec<Vec<Fp>>,
+    /// The scale of each output, to dequantize it with (see [crate::RunArgs::output_scales])
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub output_scales: Vec<crate::Scale>,
     /// Any hashes of inputs generated during the forward pass
+    #[cfg_attr(
+        all(feature = "ezkl", not(target_arch = "wasm32")),
+        schemars(with = "Option<serde_json::Value>")
+    )]
     pub processed_inputs: Option<ModuleForwardResult>,
     /// Any hashes of params generated during the forward pass
+    #[cfg_attr(
+        all(feature = "ezkl", not(target_arch = "wasm32")),
+        schemars(with = "Option<serde_json::Value>")
+    )]
     pub processed_params: Option<ModuleForwardResult>,
     /// Any hashes of outputs generated during the forward pass
+    #[cfg_attr(
+        all(feature = "ezkl", not(target_arch = "wasm32")),
+        schemars(with = "Option<serde_json::Value>")
+    )]
     pub processed_outputs: Option<ModuleForwardResult>,
     /// max lookup input
     pub max_lookup_inputs: IntegerRep,
@@ -133,8 +256,263 @@ pub struct GraphWitness {
     pub min_lookup_inputs: IntegerRep,
     /// max range check size
     pub max_range_size: IntegerRep,
+    /// The range of the inputs of each node to the lookups whose tables are sized to the inputs of their nodes, see
+    /// [crate::RunArgs::tight_lookup_ranges]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tight_lookup_ranges: Vec<NodeLookupRange>,
+    /// The block any on-chain inputs and outputs were read at
+    #[serde(default)]
+    #[cfg_attr(
+        all(feature = "ezkl", not(target_arch = "wasm32")),
+        schemars(with = "Option<serde_json::Value>")
+    )]
+    pub pinned_block: Option<PinnedBlock>,
+    /// The digest of the signed inputs and the signature over it, when the settings sign inputs
+    #[serde(default)]
+    #[cfg_attr(
+        all(feature = "ezkl", not(target_arch = "wasm32")),
+        schemars(with = "Option<serde_json::Value>")
+    )]
+    pub input_signature: Option<InputSignatureWitness>,
+    /// The commitment of the inputs and their nullifier, when the settings expose a nullifier
+    #[serde(default)]
+    #[cfg_attr(
+        all(feature = "ezkl", not(target_arch = "wasm32")),
+        schemars(with = "Option<serde_json::Value>")
+    )]
+    pub nullifier: Option<NullifierWitness>,
+    /// The ciphertext of the encrypted outputs, when the settings encrypt outputs to a recipient
+    #[serde(default)]
+    #[cfg_attr(
+        all(feature = "ezkl", not(target_arch = "wasm32")),
+        schemars(with = "Option<serde_json::Value>")
+    )]
+    pub output_encryption: Option<EncryptionWitness>,
+    /// The commitment of the outputs and the digests chaining them to the previous step, when the settings expose
+    /// session digests
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(
+        all(feature = "ezkl", not(target_arch = "wasm32")),
+        schemars(with = "Option<serde_json::Value>")
+    )]
+    pub session: Option<SessionWitness>,
+    /// What was left out of the witness when it was redacted, see [GraphWitness::redact]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redaction: Option<WitnessRedaction>,
+    /// The index of the tensors stored in the binary sidecar of the witness file, read back into the witness (and
+    /// cleared) by [GraphWitness::from_path], see [sidecar]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sidecar: Option<WitnessSidecar>,
+}
+
+/// The private values left out of a witness redacted for sharing with a verifier
+#[cfg(not(all(feature = "verifier-only", not(feature = "ezkl"))))]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), derive(JsonSchema))]
+pub struct WitnessRedaction {
+    /// The fields left out, as `inputs[0]` or `nullifier.salt`
+    pub redacted: Vec<String>,
+    /// The full witness as json encrypted with age to an X25519 recipient, ascii armored, if it was kept
+    pub encrypted_witness: Option<String>,
+}
+
+/// Which sections of a witness are written to json, and how its dequantized floats are printed.
+/// A witness written without its field elements, or without its processed elements when the settings
+/// hash or commit to any of the inputs, params or outputs, reports the forward pass but can't be proven.
+#[cfg(not(all(feature = "verifier-only", not(feature = "ezkl"))))]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct WitnessFormat {
+    /// The decimal places the dequantized floats are printed with, as many as f64 needs if None
+    pub precision: Option<usize>,
+    /// Whether to include the field elements of the inputs and outputs
+    pub felts: bool,
+    /// Whether to include the dequantized floats of the inputs and outputs
+    pub rescaled: bool,
+    /// Whether to include the processed inputs, params and outputs (their hashes and commitments)
+    pub processed: bool,
+    /// The number of field elements above which a tensor of the inputs or outputs is stored in the binary sidecar of
+    /// the witness file rather than in its json (see [sidecar]), all are in the json if None. Only applies to
+    /// witnesses saved to a file with their field elements
+    #[serde(default)]
+    pub sidecar_threshold: Option<usize>,
+}
+
+#[cfg(not(all(feature = "verifier-only", not(feature = "ezkl"))))]
+impl Default for WitnessFormat {
+    fn default() -> Self {
+        WitnessFormat {
+            precision: None,
+            felts: true,
+            rescaled: true,
+            processed: true,
+            sidecar_threshold: None,
+        }
+    }
+}
+
+/// A witness serialized in a [WitnessFormat], with the fields in the order of [GraphWitness] so the
+/// default format writes the same json
+#[cfg(not(all(feature = "verifier-only", not(feature = "ezkl"))))]
+#[derive(Serialize)]
+struct FormattedWitness<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    inputs: Option<Vec<&'a [Fp]>>,
+    pretty_elements: Option<FormattedPrettyElements<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    outputs: Option<Vec<&'a [Fp]>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    output_scales: &'a Vec<crate::Scale>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    processed_inputs: Option<&'a Option<ModuleForwardResult>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    processed_params: Option<&'a Option<ModuleForwardResult>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    processed_outputs: Option<&'a Option<ModuleForwardResult>>,
+    max_lookup_inputs: IntegerRep,
+    min_lookup_inputs: IntegerRep,
+    max_range_size: IntegerRep,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tight_lookup_ranges: &'a Vec<NodeLookupRange>,
+    pinned_block: &'a Option<PinnedBlock>,
+    input_signature: &'a Option<InputSignatureWitness>,
+    nullifier: &'a Option<NullifierWitness>,
+    output_encryption: &'a Option<EncryptionWitness>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    session: Option<&'a SessionWitness>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    redaction: Option<&'a WitnessRedaction>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sidecar: Option<&'a WitnessSidecar>,
+}
+
+/// The tensors of `field` of a witness, those stored in its `sidecar` left empty
+#[cfg(not(all(feature = "verifier-only", not(feature = "ezkl"))))]
+fn without_sidecar<'a, T>(
+    tensors: &'a [Vec<T>],
+    field: SidecarField,
+    sidecar: Option<&WitnessSidecar>,
+    in_json: impl Fn(&sidecar::SidecarTensor) -> bool,
+) -> Vec<&'a [T]> {
+    tensors
+        .iter()
+        .enumerate()
+        .map(
+            |(idx, tensor)| match sidecar.and_then(|s| s.tensor(field, idx)) {
+                Some(stored) if !in_json(stored) => &[],
+                _ => tensor.as_slice(),
+            },
+        )
+        .collect()
+}
+
+/// [PrettyElements] serialized in a [WitnessFormat]
+#[cfg(not(all(feature = "verifier-only", not(feature = "ezkl"))))]
+#[derive(Serialize)]
+struct FormattedPrettyElements<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rescaled_inputs: Option<Vec<Vec<String>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    inputs: Option<Vec<&'a [String]>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    processed_inputs: Option<&'a Vec<Vec<String>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    processed_params: Option<&'a Vec<Vec<String>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    processed_outputs: Option<&'a Vec<Vec<String>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rescaled_outputs: Option<Vec<Vec<String>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    outputs: Option<Vec<&'a [String]>>,
+}
+
+#[cfg(not(all(feature = "verifier-only", not(feature = "ezkl"))))]
+impl<'a> FormattedPrettyElements<'a> {
+    fn new(
+        pretty: &'a PrettyElements,
+        format: &WitnessFormat,
+        sidecar: Option<&WitnessSidecar>,
+    ) -> Self {
+        let felts = |tensors: &'a [Vec<String>], field| {
+            format
+                .felts
+                .then(|| without_sidecar(tensors, field, sidecar, |t| !t.pretty))
+        };
+        FormattedPrettyElements {
+            rescaled_inputs: Self::rescaled(&pretty.rescaled_inputs, format),
+            inputs: felts(&pretty.inputs, SidecarField::Inputs),
+            processed_inputs: format.processed.then_some(&pretty.processed_inputs),
+            processed_params: format.processed.then_some(&pretty.processed_params),
+            processed_outputs: format.processed.then_some(&pretty.processed_outputs),
+            rescaled_outputs: Self::rescaled(&pretty.rescaled_outputs, format),
+            outputs: felts(&pretty.outputs, SidecarField::Outputs),
+        }
+    }
+
+    /// The dequantized floats printed with the precision of the format
+    fn rescaled(values: &[Vec<String>], format: &WitnessFormat) -> Option<Vec<Vec<String>>> {
+        if !format.rescaled {
+            return None;
+        }
+        let Some(precision) = format.precision else {
+            return Some(values.to_vec());
+        };
+        let round = |x: &String| match x.parse::<f64>() {
+            Ok(x) => format!("{:.*}", precision, x),
+            Err(_) => x.clone(),
+        };
+        Some(
+            values
+                .iter()
+                .map(|v| v.iter().map(round).collect())
+                .collect(),
+        )
+    }
+}
+
+#[cfg(not(all(feature = "verifier-only", not(feature = "ezkl"))))]
+impl<'a> FormattedWitness<'a> {
+    /// The witness in `format`, the tensors of `sidecar` left out as they are stored in it. The sidecar the witness
+    /// itself refers to, if it wasn't read back, is kept without one.
+    fn new(
+        witness: &'a GraphWitness,
+        format: &WitnessFormat,
+        sidecar: Option<&'a WitnessSidecar>,
+    ) -> Self {
+        let sidecar = sidecar.or(witness.sidecar.as_ref());
+        let pretty_elements = witness
+            .pretty_elements
+            .as_ref()
+            .map(|pretty| FormattedPrettyElements::new(pretty, format, sidecar));
+        let felts = |tensors: &'a [Vec<Fp>], field| {
+            format
+                .felts
+                .then(|| without_sidecar(tensors, field, sidecar, |_| false))
+        };
+
+        FormattedWitness {
+            inputs: felts(&witness.inputs, SidecarField::Inputs),
+            pretty_elements,
+            outputs: felts(&witness.outputs, SidecarField::Outputs),
+            output_scales: &witness.output_scales,
+            processed_inputs: format.processed.then_some(&witness.processed_inputs),
+            processed_params: format.processed.then_some(&witness.processed_params),
+            processed_outputs: format.processed.then_some(&witness.processed_outputs),
+            max_lookup_inputs: witness.max_lookup_inputs,
+            min_lookup_inputs: witness.min_lookup_inputs,
+            max_range_size: witness.max_range_size,
+            tight_lookup_ranges: &witness.tight_lookup_ranges,
+            pinned_block: &witness.pinned_block,
+            input_signature: &witness.input_signature,
+            nullifier: &witness.nullifier,
+            output_encryption: &witness.output_encryption,
+            session: witness.session.as_ref(),
+            redaction: witness.redaction.as_ref(),
+            sidecar,
+        }
+    }
 }
 
+#[cfg(not(all(feature = "verifier-only", not(feature = "ezkl"))))]
 impl GraphWitness {
     ///
     pub fn get_float_outputs(&self, scales: &[crate::Scale]) -> Vec<Tensor<f32>> {
@@ -154,6 +532,7 @@ impl GraphWitness {
         GraphWitness {
             inputs,
             outputs,
+            output_scales: vec![],
             pretty_elements: None,
             processed_inputs: None,
             processed_params: None,
@@ -161,6 +540,14 @@ impl GraphWitness {
             max_lookup_inputs: 0,
             min_lookup_inputs: 0,
             max_range_size: 0,
+            tight_lookup_ranges: vec![],
+            pinned_block: None,
+            input_signature: None,
+            nullifier: None,
+            output_encryption: None,
+            session: None,
+            redaction: None,
+            sidecar: None,
         }
     }
 
@@ -226,8 +613,9 @@ impl GraphWitness {
         }
 
         if let Some(processed_outputs) = self.processed_outputs.clone() {
+            let (output_visibility, _) = visibility.processed_outputs(self.outputs.len());
             pretty_elements.processed_outputs = processed_outputs
-                .get_result(visibility.output)
+                .get_result(output_visibility)
                 .iter()
                 // gets printed as hex string
                 .map(|x| x.iter().map(|y| format!("{:?}", y)).collect())
@@ -237,6 +625,379 @@ impl GraphWitness {
         self.pretty_elements = Some(pretty_elements);
     }
 
+    /// Check the witness against the settings of the circuit it was generated for: the number of inputs and
+    /// outputs, the lookup range, and the hashes of the inputs and outputs, recomputed from the elements of the
+    /// witness as the forward pass computes them. The params, which a witness doesn't hold, the PolyCommit
+    /// commitments, which take the verifying key and the srs, and the hashes of outputs that replace the outputs
+    /// aren't checked, nor are the hashes of a redacted witness.
+    pub fn check_consistency(&self, settings: &GraphSettings) -> Result<(), GraphError> {
+        if self.inputs.len() != settings.model_input_scales.len() {
+            return Err(GraphError::InconsistentWitness(format!(
+                "the witness has {} inputs but the settings have {}",
+                self.inputs.len(),
+                settings.model_input_scales.len()
+            )));
+        }
+        if self.outputs.len() != settings.model_output_scales.len() {
+            return Err(GraphError::InconsistentWitness(format!(
+                "the witness has {} outputs but the settings have {}",
+                self.outputs.len(),
+                settings.model_output_scales.len()
+            )));
+        }
+        let (min, max) = settings.run_args.lookup_range;
+        if self.min_lookup_inputs < min || self.max_lookup_inputs > max {
+            return Err(GraphError::InconsistentWitness(format!(
+                "the lookup inputs span {}..{} but the lookup range is {}..{}",
+                self.min_lookup_inputs, self.max_lookup_inputs, min, max
+            )));
+        }
+
+        // the private values a redacted witness was processed from are gone, its hashes can't be checked against them
+        if self.redaction.is_some() {
+            return Ok(());
+        }
+        let visibility = VarVisibility::from_args(&settings.run_args)?;
+        if visibility.input.requires_processing() {
+            let mut outlets = visibility.input.overwrites_inputs();
+            if outlets.is_empty() {
+                outlets = (0..self.inputs.len()).collect();
+            }
+            Self::check_processed(
+                "inputs",
+                &self.inputs,
+                &outlets,
+                &visibility.input,
+                self.processed_inputs.as_ref(),
+                settings,
+            )?;
+        }
+        let (output_visibility, outlets) = visibility.processed_outputs(self.outputs.len());
+        if output_visibility.requires_processing()
+            && output_visibility.overwrites_inputs().is_empty()
+        {
+            Self::check_processed(
+                "outputs",
+                &self.outputs,
+                &outlets,
+                &output_visibility,
+                self.processed_outputs.as_ref(),
+                settings,
+            )?;
+        }
+        Ok(())
+    }
+
+    fn check_processed(
+        name: &str,
+        elements: &[Vec<Fp>],
+        outlets: &[usize],
+        visibility: &Visibility,
+        processed: Option<&ModuleForwardResult>,
+        settings: &GraphSettings,
+    ) -> Result<(), GraphError> {
+        let processed = processed.ok_or_else(|| {
+            GraphError::InconsistentWitness(format!(
+                "the {} are {} but weren't processed",
+                name, visibility
+            ))
+        })?;
+        let tensors = outlets
+            .iter()
+            .map(|outlet| {
+                elements
+                    .get(*outlet)
+                    .map(|e| Tensor::from(e.clone().into_iter()))
+                    .ok_or_else(|| {
+                        GraphError::InconsistentWitness(format!(
+                            "the {} have no outlet {}",
+                            name, outlet
+                        ))
+                    })
+            })
+            .collect::<Result<Vec<Tensor<Fp>>, GraphError>>()?;
+        let expected = GraphModules::forward::<KZGCommitmentScheme<bn256::Bn256>>(
+            &tensors,
+            visibility,
+            settings.run_args.poseidon_params,
+            false,
+            None,
+            None,
+        )?;
+        if expected.poseidon_hash != processed.poseidon_hash
+            || expected.sha256_hash != processed.sha256_hash
+            || expected.merkle_leaf != processed.merkle_leaf
+        {
+            return Err(GraphError::InconsistentWitness(format!(
+                "the processed {} don't match the {}",
+                name, name
+            )));
+        }
+        Ok(())
+    }
+
+    /// Set the path of the leaf the inputs hash to, in the tree of the dataset they are proven to belong to
+    pub fn prove_membership(&mut self, tree: &MerkleTree) -> Result<(), GraphError> {
+        let not_merkle =
+            || GraphError::InvalidMerkle("the inputs aren't proven to belong to a dataset".into());
+        let processed_inputs = self.processed_inputs.as_mut().ok_or_else(not_merkle)?;
+        let leaf = processed_inputs.merkle_leaf.ok_or_else(not_merkle)?;
+        let index = tree.leaves.iter().position(|l| *l == leaf).ok_or_else(|| {
+            GraphError::InvalidMerkle("the inputs aren't a row of the dataset".into())
+        })?;
+        processed_inputs.merkle_path = Some(ModuleMerkle::path(tree, index)?);
+        Ok(())
+    }
+
+    /// Attach a 65 byte `r || s || v` signature over the digest of the signed inputs, recovering the
+    /// public key it verifies against
+    pub fn attach_signature(&mut self, signature: &[u8]) -> Result<(), GraphError> {
+        let input_signature = self.input_signature.as_mut().ok_or_else(|| {
+            GraphError::InvalidSignature("the settings don't sign any inputs".into())
+        })?;
+        input_signature.signature =
+            Some(EcdsaSignature::recover(&input_signature.digest, signature)?);
+        Ok(())
+    }
+
+    /// Derive the nullifier of the committed inputs with a private salt, the same inputs and salt
+    /// always derive the same nullifier
+    pub fn derive_nullifier(&mut self, salt: Fp) -> Result<(), GraphError> {
+        let nullifier = self.nullifier.as_mut().ok_or_else(|| {
+            GraphError::InvalidNullifier("the settings don't expose a nullifier".into())
+        })?;
+        nullifier.salt = Some(salt);
+        nullifier.nullifier = Some(ModuleNullifier::nullifier(nullifier.commitment, salt));
+        Ok(())
+    }
+
+    /// Chain the committed outputs to the digest of the previous step of the session, the digest of the first step
+    /// being [session::GENESIS_DIGEST] unless the session starts from another
+    pub fn chain_session(&mut self, prev_digest: Fp) -> Result<(), GraphError> {
+        let session = self.session.as_mut().ok_or_else(|| {
+            GraphError::InvalidSession("the settings don't expose session digests".into())
+        })?;
+        session.prev_digest = Some(prev_digest);
+        session.next_digest = Some(ModuleSession::next_digest(prev_digest, session.commitment));
+        Ok(())
+    }
+
+    /// Encrypt the encrypted outputs to the public key of a recipient with some private randomness,
+    /// the recipient decrypts them with [GraphSettings::decrypt_outputs]
+    pub fn encrypt_outputs(
+        &mut self,
+        encryption: &OutputEncryption,
+        public_key: CurvePoint,
+        randomness: Fp,
+    ) -> Result<(), GraphError> {
+        let message = encryption
+            .outputs
+            .iter()
+            .map(|o| {
+                self.outputs.get(*o).cloned().ok_or_else(|| {
+                    GraphError::InvalidEncryption(format!("the witness has no output {}", o))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?
+            .concat();
+        let ciphertext = ModuleElGamal::encrypt(&public_key, &randomness, &message)?;
+        self.output_encryption = Some(EncryptionWitness {
+            public_key,
+            randomness,
+            ciphertext,
+        });
+        Ok(())
+    }
+
+    /// The blinders folded into the PolyCommit commitments, one for each committed tensor in the
+    /// order of [GraphWitness::get_polycommitments]. Fails if they are encrypted.
+    pub fn get_polycommit_blinders(&self) -> Result<Vec<Fp>, GraphError> {
+        let mut blinders = vec![];
+        for processed in [
+            &self.processed_inputs,
+            &self.processed_params,
+            &self.processed_outputs,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            if let Some(processed_blinders) = &processed.polycommit_blinders {
+                blinders.extend(processed_blinders.raw().ok_or_else(|| {
+                    GraphError::InvalidBlinding(
+                        "the blinders are encrypted, decrypt them with the blinding secret key"
+                            .into(),
+                    )
+                })?);
+            }
+        }
+        Ok(blinders)
+    }
+
+    /// Encrypt the blinders of the PolyCommit commitments to the public key of their holder, each
+    /// set of blinders with fresh randomness
+    pub fn encrypt_polycommit_blinders(
+        &mut self,
+        public_key: &CurvePoint,
+        rng: &mut impl rand::RngCore,
+    ) -> Result<(), GraphError> {
+        let mut encrypted = false;
+        for processed in [
+            &mut self.processed_inputs,
+            &mut self.processed_params,
+            &mut self.processed_outputs,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            if let Some(blinders) = &processed.polycommit_blinders {
+                let randomness = Fp::random(&mut *rng);
+                processed.polycommit_blinders = Some(blinders.encrypt(public_key, &randomness)?);
+                encrypted = true;
+            }
+        }
+        if !encrypted {
+            return Err(GraphError::InvalidBlinding(
+                "the witness has no blinders to encrypt".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Decrypt the blinders of the PolyCommit commitments with the secret key of their holder
+    pub fn decrypt_polycommit_blinders(&mut self, secret_key: &Fp) -> Result<(), GraphError> {
+        for processed in [
+            &mut self.processed_inputs,
+            &mut self.processed_params,
+            &mut self.processed_outputs,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            if let Some(blinders) = &processed.polycommit_blinders {
+                processed.polycommit_blinders = Some(blinders.decrypt(secret_key)?);
+            }
+        }
+        Ok(())
+    }
+
+    /// A copy of the witness that can be shared with a verifier. The inputs and outputs the visibilities of the
+    /// settings keep private are emptied along with their prettified elements, as are the leaf and path of the
+    /// inputs to their dataset, the blinders of the commitments in the clear, the salt of the nullifier and the
+    /// randomness of the encrypted outputs. The public instances, hashes and commitments are kept such that the
+    /// instances of a proof can still be derived from it, but the redacted witness can no longer be proven.
+    pub fn redact(&self, settings: &GraphSettings) -> Result<GraphWitness, GraphError> {
+        if self.redaction.is_some() {
+            return Err(GraphError::InvalidRedaction(
+                "the witness is already redacted".into(),
+            ));
+        }
+        let run_args = &settings.run_args;
+        let public_inputs = |_: usize| run_args.input_visibility.is_public();
+        let public_outputs = |i: usize| run_args.output_is_public(i);
+        let mut witness = self.clone();
+        let mut redacted = vec![];
+
+        redact_tensors("inputs", &mut witness.inputs, public_inputs, &mut redacted);
+        redact_tensors(
+            "outputs",
+            &mut witness.outputs,
+            public_outputs,
+            &mut redacted,
+        );
+        if let Some(pretty) = &mut witness.pretty_elements {
+            for (name, tensors) in [
+                (
+                    "pretty_elements.rescaled_inputs",
+                    &mut pretty.rescaled_inputs,
+                ),
+                ("pretty_elements.inputs", &mut pretty.inputs),
+            ] {
+                redact_tensors(name, tensors, public_inputs, &mut redacted);
+            }
+            for (name, tensors) in [
+                (
+                    "pretty_elements.rescaled_outputs",
+                    &mut pretty.rescaled_outputs,
+                ),
+                ("pretty_elements.outputs", &mut pretty.outputs),
+            ] {
+                redact_tensors(name, tensors, public_outputs, &mut redacted);
+            }
+        }
+
+        for (name, processed) in [
+            ("processed_inputs", &mut witness.processed_inputs),
+            ("processed_params", &mut witness.processed_params),
+            ("processed_outputs", &mut witness.processed_outputs),
+        ] {
+            let Some(processed) = processed else {
+                continue;
+            };
+            if processed.merkle_leaf.take().is_some() {
+                redacted.push(format!("{}.merkle_leaf", name));
+            }
+            // the root is an instance, the position of the leaf in the dataset isn't
+            if let Some(path) = &mut processed.merkle_path {
+                path.index = 0;
+                path.siblings.clear();
+                redacted.push(format!("{}.merkle_path", name));
+            }
+            if matches!(
+                processed.polycommit_blinders,
+                Some(PolyCommitBlinders::Raw(_))
+            ) {
+                processed.polycommit_blinders = None;
+                redacted.push(format!("{}.polycommit_blinders", name));
+            }
+        }
+        if let Some(nullifier) = &mut witness.nullifier {
+            if nullifier.salt.take().is_some() {
+                redacted.push("nullifier.salt".to_string());
+            }
+        }
+        if let Some(encryption) = &mut witness.output_encryption {
+            encryption.randomness = Fp::zero();
+            redacted.push("output_encryption.randomness".to_string());
+        }
+
+        witness.redaction = Some(WitnessRedaction {
+            redacted,
+            encrypted_witness: None,
+        });
+        Ok(witness)
+    }
+
+    /// [GraphWitness::redact], keeping the full witness encrypted with age to the X25519 `recipient` public key
+    /// (`age1...`), such that the holder of its identity can recover it with [GraphWitness::unredact] or the age cli
+    #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+    pub fn redact_to(
+        &self,
+        settings: &GraphSettings,
+        recipient: &str,
+    ) -> Result<GraphWitness, GraphError> {
+        let mut witness = self.redact(settings)?;
+        let encrypted = encrypt_to_recipient(&serde_json::to_vec(self)?, recipient)?;
+        if let Some(redaction) = &mut witness.redaction {
+            redaction.encrypted_witness = Some(encrypted);
+        }
+        Ok(witness)
+    }
+
+    /// The full witness kept in a redacted witness, decrypted with the age X25519 `identity` secret key
+    /// (`AGE-SECRET-KEY-1...`) of the recipient it was encrypted to
+    #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+    pub fn unredact(&self, identity: &str) -> Result<GraphWitness, GraphError> {
+        let encrypted = self
+            .redaction
+            .as_ref()
+            .and_then(|r| r.encrypted_witness.as_ref())
+            .ok_or_else(|| {
+                GraphError::InvalidRedaction("the witness doesn't keep the full witness".into())
+            })?;
+        let witness = decrypt_with_identity(encrypted, identity)?;
+        Ok(serde_json::from_slice(&witness)?)
+    }
+
     ///
     pub fn get_polycommitments(&self) -> Vec<G1Affine> {
         let mut commitments = vec![];
@@ -258,6 +1019,57 @@ impl GraphWitness {
         commitments
     }
 
+    /// The outputs of the forward pass dequantized at the scales of the model outputs
+    pub fn dequantized_outputs(&self, scales: &[crate::Scale]) -> Vec<Vec<f64>> {
+        self.outputs
+            .iter()
+            .zip(scales.iter())
+            .map(|(output, scale)| output.iter().map(|x| dequantize(*x, *scale, 0.)).collect())
+            .collect()
+    }
+
+    /// Save the dequantized outputs of the forward pass to a .npy file.
+    /// Models with several outputs are saved to a .npz archive of `arr_0`, `arr_1`, ... instead.
+    #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+    pub fn save_dequantized_outputs(
+        &self,
+        path: std::path::PathBuf,
+        scales: &[crate::Scale],
+        shapes: &[Vec<usize>],
+    ) -> Result<(), GraphError> {
+        let arrays = self
+            .dequantized_outputs(scales)
+            .into_iter()
+            .zip(shapes.iter())
+            .map(|(output, shape)| NpyArray {
+                shape: shape.clone(),
+                data: output.into_iter().map(FileSourceInner::Float).collect(),
+            })
+            .collect::<Vec<_>>();
+
+        if path.extension().is_some_and(|e| e == "npz") {
+            let named = arrays
+                .into_iter()
+                .enumerate()
+                .map(|(i, array)| (format!("arr_{}", i), array))
+                .collect::<Vec<_>>();
+            return save_npz(&path, &named);
+        }
+        match arrays.as_slice() {
+            [array] => array.save(&path),
+            _ => Err(GraphError::InvalidNpy(format!(
+                "cannot save {} outputs to a single .npy file, use a .npz archive",
+                arrays.len()
+            ))),
+        }
+    }
+
+    /// The outputs of the forward pass as already quantized field element inputs at their output
+    /// `scales`, for chaining them into the inputs of another circuit
+    pub fn field_outputs(&self, scales: &[crate::Scale]) -> GraphData {
+        GraphData::new_field_inputs(self.outputs.clone(), scales.to_vec())
+    }
+
     /// Export the ezkl witness as json
     pub fn as_json(&self) -> Result<String, GraphError> {
         let serialized = match serde_json::to_string(&self) {
@@ -267,14 +1079,15 @@ impl GraphWitness {
         Ok(serialized)
     }
 
-    /// Load the model input from a file
+    /// Load the witness from a file, with the tensors of its binary sidecar if it has one
     pub fn from_path(path: std::path::PathBuf) -> Result<Self, GraphError> {
-        let file = std::fs::File::open(path.clone()).map_err(|e| {
+        let bytes = std::fs::read(&path).map_err(|e| {
             GraphError::ReadWriteFileError(path.display().to_string(), e.to_string())
         })?;
-
-        let reader = std::io::BufReader::with_capacity(*EZKL_BUF_CAPACITY, file);
-        serde_json::from_reader(reader).map_err(|e| e.into())
+        let mut witness: GraphWitness = from_json_slice(&bytes, JsonArtifact::Witness)?;
+        let dir = path.parent().unwrap_or(std::path::Path::new(""));
+        sidecar::read_sidecar(&mut witness, dir)?;
+        Ok(witness)
     }
 
     /// Save the model input to a file
@@ -288,6 +1101,37 @@ impl GraphWitness {
         serde_json::to_writer(writer, &self).map_err(|e| e.into())
     }
 
+    /// Export the witness as json in a [WitnessFormat], all its tensors in the json
+    pub fn as_json_formatted(&self, format: &WitnessFormat) -> Result<String, GraphError> {
+        Ok(serde_json::to_string(&FormattedWitness::new(
+            self, format, None,
+        ))?)
+    }
+
+    /// Save the witness to a file in a [WitnessFormat], its tensors above the sidecar threshold of the format to the
+    /// binary sidecar of the file
+    pub fn save_formatted(
+        &self,
+        path: std::path::PathBuf,
+        format: &WitnessFormat,
+    ) -> Result<(), GraphError> {
+        let file = std::fs::File::create(path.clone()).map_err(|e| {
+            GraphError::ReadWriteFileError(path.display().to_string(), e.to_string())
+        })?;
+        // use buf writer
+        let writer = std::io::BufWriter::with_capacity(*EZKL_BUF_CAPACITY, file);
+
+        let sidecar = match format.sidecar_threshold {
+            Some(threshold) if format.felts => sidecar::write_sidecar(self, &path, threshold)?,
+            _ => None,
+        };
+        serde_json::to_writer(
+            writer,
+            &FormattedWitness::new(self, format, sidecar.as_ref()),
+        )
+        .map_err(|e| e.into())
+    }
+
     ///
     pub fn get_input_tensor(&self) -> Vec<Tensor<Fp>> {
         self.inputs
@@ -308,6 +1152,7 @@ impl GraphWitness {
 }
 
 #[cfg(feature = "python-bindings")]
+#[cfg(not(all(feature = "verifier-only", not(feature = "ezkl"))))]
 impl ToPyObject for GraphWitness {
     fn to_object(&self, py: Python) -> PyObject {
         // Create a Python dictionary
@@ -330,6 +1175,9 @@ impl ToPyObject for GraphWitness {
 
         dict.set_item("inputs", inputs).unwrap();
         dict.set_item("outputs", outputs).unwrap();
+        if !self.output_scales.is_empty() {
+            dict.set_item("output_scales", &self.output_scales).unwrap();
+        }
         dict.set_item("max_lookup_inputs", self.max_lookup_inputs)
             .unwrap();
         dict.set_item("min_lookup_inputs", self.min_lookup_inputs)
@@ -345,6 +1193,20 @@ impl ToPyObject for GraphWitness {
             if let Some(processed_inputs_polycommit) = &processed_inputs.polycommit {
                 insert_polycommit_pydict(dict_inputs, processed_inputs_polycommit).unwrap();
             }
+            if let Some(blinders) = &processed_inputs.polycommit_blinders {
+                insert_polycommit_blinders_pydict(dict_inputs, blinders).unwrap();
+            }
+            if let Some(processed_inputs_sha256_hash) = &processed_inputs.sha256_hash {
+                insert_sha256_hash_pydict(dict_inputs, processed_inputs_sha256_hash).unwrap();
+            }
+            if let Some(processed_inputs_merkle_leaf) = &processed_inputs.merkle_leaf {
+                insert_merkle_pydict(
+                    dict_inputs,
+                    processed_inputs_merkle_leaf,
+                    processed_inputs.merkle_path.as_ref(),
+                )
+                .unwrap();
+            }
 
             dict.set_item("processed_inputs", dict_inputs).unwrap();
         }
@@ -356,6 +1218,12 @@ impl ToPyObject for GraphWitness {
             if let Some(processed_params_polycommit) = &processed_params.polycommit {
                 insert_polycommit_pydict(dict_inputs, processed_params_polycommit).unwrap();
             }
+            if let Some(blinders) = &processed_params.polycommit_blinders {
+                insert_polycommit_blinders_pydict(dict_params, blinders).unwrap();
+            }
+            if let Some(processed_params_sha256_hash) = &processed_params.sha256_hash {
+                insert_sha256_hash_pydict(dict_params, processed_params_sha256_hash).unwrap();
+            }
 
             dict.set_item("processed_params", dict_params).unwrap();
         }
@@ -367,10 +1235,114 @@ impl ToPyObject for GraphWitness {
             if let Some(processed_outputs_polycommit) = &processed_outputs.polycommit {
                 insert_polycommit_pydict(dict_inputs, processed_outputs_polycommit).unwrap();
             }
+            if let Some(blinders) = &processed_outputs.polycommit_blinders {
+                insert_polycommit_blinders_pydict(dict_outputs, blinders).unwrap();
+            }
+            if let Some(processed_outputs_sha256_hash) = &processed_outputs.sha256_hash {
+                insert_sha256_hash_pydict(dict_outputs, processed_outputs_sha256_hash).unwrap();
+            }
 
             dict.set_item("processed_outputs", dict_outputs).unwrap();
         }
 
+        if let Some(pinned_block) = &self.pinned_block {
+            let dict_pinned_block = PyDict::new(py);
+            dict_pinned_block
+                .set_item("number", pinned_block.number)
+                .unwrap();
+            dict_pinned_block
+                .set_item("hash", &pinned_block.hash)
+                .unwrap();
+            dict.set_item("pinned_block", dict_pinned_block).unwrap();
+        }
+
+        if let Some(input_signature) = &self.input_signature {
+            let dict_signature = PyDict::new(py);
+            dict_signature
+                .set_item("digest", input_signature.digest.to_hex())
+                .unwrap();
+            if let Some(signature) = &input_signature.signature {
+                let dict_ecdsa = PyDict::new(py);
+                dict_ecdsa.set_item("r", signature.r.to_hex()).unwrap();
+                dict_ecdsa.set_item("s", signature.s.to_hex()).unwrap();
+                let public_key: Vec<String> =
+                    signature.public_key.iter().map(|c| c.to_hex()).collect();
+                dict_ecdsa.set_item("public_key", public_key).unwrap();
+                dict_signature.set_item("signature", dict_ecdsa).unwrap();
+            }
+            dict.set_item("input_signature", dict_signature).unwrap();
+        }
+
+        if let Some(nullifier) = &self.nullifier {
+            let dict_nullifier = PyDict::new(py);
+            dict_nullifier
+                .set_item("commitment", field_to_string(&nullifier.commitment))
+                .unwrap();
+            // a redacted witness keeps the nullifier without its salt
+            if let Some(salt) = &nullifier.salt {
+                dict_nullifier
+                    .set_item("salt", field_to_string(salt))
+                    .unwrap();
+            }
+            if let Some(value) = &nullifier.nullifier {
+                dict_nullifier
+                    .set_item("nullifier", field_to_string(value))
+                    .unwrap();
+            }
+            dict.set_item("nullifier", dict_nullifier).unwrap();
+        }
+
+        if let Some(session) = &self.session {
+            let dict_session = PyDict::new(py);
+            dict_session
+                .set_item("commitment", field_to_string(&session.commitment))
+                .unwrap();
+            if let Some(prev_digest) = &session.prev_digest {
+                dict_session
+                    .set_item("prev_digest", field_to_string(prev_digest))
+                    .unwrap();
+            }
+            if let Some(next_digest) = &session.next_digest {
+                dict_session
+                    .set_item("next_digest", field_to_string(next_digest))
+                    .unwrap();
+            }
+            dict.set_item("session", dict_session).unwrap();
+        }
+
+        if let Some(encryption) = &self.output_encryption {
+            let dict_encryption = PyDict::new(py);
+            let point = |p: &CurvePoint| vec![field_to_string(&p.x), field_to_string(&p.y)];
+            dict_encryption
+                .set_item("public_key", point(&encryption.public_key))
+                .unwrap();
+            dict_encryption
+                .set_item("randomness", field_to_string(&encryption.randomness))
+                .unwrap();
+            dict_encryption
+                .set_item("c1", point(&encryption.ciphertext.c1))
+                .unwrap();
+            let c2: Vec<String> = encryption
+                .ciphertext
+                .c2
+                .iter()
+                .map(field_to_string)
+                .collect();
+            dict_encryption.set_item("c2", c2).unwrap();
+            dict.set_item("output_encryption", dict_encryption).unwrap();
+        }
+
+        if let Some(redaction) = &self.redaction {
+            let dict_redaction = PyDict::new(py);
+            dict_redaction
+                .set_item("redacted", &redaction.redacted)
+                .unwrap();
+            dict_redaction
+                .set_item("encrypted_witness", &redaction.encrypted_witness)
+                .unwrap();
+            dict.set_item("redaction", dict_redaction).unwrap();
+        }
+
         dict.to_object(py)
     }
 }
@@ -383,6 +1355,33 @@ fn insert_poseidon_hash_pydict(pydict: &PyDict, poseidon_hash: &Vec<Fp>) -> Resu
     Ok(())
 }
 
+#[cfg(feature = "python-bindings")]
+fn insert_sha256_hash_pydict(pydict: &PyDict, sha256_hash: &[Fp]) -> Result<(), PyErr> {
+    let sha256_hash: Vec<String> = sha256_hash.iter().map(field_to_string).collect();
+    pydict.set_item("sha256_hash", sha256_hash)?;
+
+    Ok(())
+}
+
+#[cfg(feature = "python-bindings")]
+fn insert_merkle_pydict(
+    pydict: &PyDict,
+    leaf: &Fp,
+    path: Option<&crate::circuit::modules::merkle::MerklePath>,
+) -> Result<(), PyErr> {
+    pydict.set_item("merkle_leaf", field_to_string(leaf))?;
+    if let Some(path) = path {
+        let dict_path = PyDict::new(pydict.py());
+        dict_path.set_item("index", path.index)?;
+        let siblings: Vec<String> = path.siblings.iter().map(field_to_string).collect();
+        dict_path.set_item("siblings", siblings)?;
+        dict_path.set_item("root", field_to_string(&path.root))?;
+        pydict.set_item("merkle_path", dict_path)?;
+    }
+
+    Ok(())
+}
+
 #[cfg(feature = "python-bindings")]
 fn insert_polycommit_pydict(pydict: &PyDict, commits: &Vec<Vec<G1Affine>>) -> Result<(), PyErr> {
     use crate::bindings::python::PyG1Affine;
@@ -395,8 +1394,130 @@ fn insert_polycommit_pydict(pydict: &PyDict, commits: &Vec<Vec<G1Affine>>) -> Re
     Ok(())
 }
 
+#[cfg(feature = "python-bindings")]
+fn insert_polycommit_blinders_pydict(
+    pydict: &PyDict,
+    blinders: &PolyCommitBlinders,
+) -> Result<(), PyErr> {
+    match blinders {
+        PolyCommitBlinders::Raw(blinders) => {
+            let blinders: Vec<String> = blinders.iter().map(field_to_string).collect();
+            pydict.set_item("polycommit_blinders", blinders)?;
+        }
+        PolyCommitBlinders::Encrypted(ciphertext) => {
+            let dict_ciphertext = PyDict::new(pydict.py());
+            let c1 = vec![
+                field_to_string(&ciphertext.c1.x),
+                field_to_string(&ciphertext.c1.y),
+            ];
+            dict_ciphertext.set_item("c1", c1)?;
+            let c2: Vec<String> = ciphertext.c2.iter().map(field_to_string).collect();
+            dict_ciphertext.set_item("c2", c2)?;
+            pydict.set_item("encrypted_polycommit_blinders", dict_ciphertext)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Empties the tensors of a witness that aren't kept, recording them in `redacted` as `name[i]`
+#[cfg(not(all(feature = "verifier-only", not(feature = "ezkl"))))]
+fn redact_tensors<T>(
+    name: &str,
+    tensors: &mut [Vec<T>],
+    keep: impl Fn(usize) -> bool,
+    redacted: &mut Vec<String>,
+) {
+    for (i, tensor) in tensors.iter_mut().enumerate() {
+        if !keep(i) {
+            tensor.clear();
+            redacted.push(format!("{}[{}]", name, i));
+        }
+    }
+}
+
+/// Encrypts `plaintext` with age to an X25519 `age1...` public key, as an ascii armored string
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+fn encrypt_to_recipient(plaintext: &[u8], recipient: &str) -> Result<String, GraphError> {
+    use std::io::Write;
+
+    let invalid = |e: std::io::Error| GraphError::InvalidRedaction(e.to_string());
+    let key: age::x25519::Recipient = recipient
+        .trim()
+        .parse()
+        .map_err(|e: &str| GraphError::InvalidRedaction(format!("{}: {}", recipient, e)))?;
+    let recipients: Vec<Box<dyn age::Recipient + Send>> = vec![Box::new(key)];
+    let encryptor = age::Encryptor::with_recipients(recipients)
+        .ok_or_else(|| GraphError::InvalidRedaction("no recipient to encrypt to".into()))?;
+    let mut encrypted = vec![];
+    let armor =
+        age::armor::ArmoredWriter::wrap_output(&mut encrypted, age::armor::Format::AsciiArmor)
+            .map_err(invalid)?;
+    let mut writer = encryptor.wrap_output(armor).map_err(invalid)?;
+    writer.write_all(plaintext).map_err(invalid)?;
+    writer
+        .finish()
+        .and_then(|armor| armor.finish())
+        .map_err(invalid)?;
+    String::from_utf8(encrypted).map_err(|e| GraphError::InvalidRedaction(e.to_string()))
+}
+
+/// Decrypts an ascii armored age ciphertext with an X25519 `AGE-SECRET-KEY-1...` secret key
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+fn decrypt_with_identity(encrypted: &str, identity: &str) -> Result<Vec<u8>, GraphError> {
+    use std::io::Read;
+
+    let invalid = |e: String| GraphError::InvalidRedaction(e);
+    let identity: age::x25519::Identity = identity
+        .trim()
+        .parse()
+        .map_err(|e: &str| invalid(format!("invalid identity: {}", e)))?;
+    let armor = age::armor::ArmoredReader::new(encrypted.as_bytes());
+    let decryptor = match age::Decryptor::new(armor).map_err(|e| invalid(e.to_string()))? {
+        age::Decryptor::Recipients(decryptor) => decryptor,
+        _ => return Err(invalid("the witness is encrypted with a passphrase".into())),
+    };
+    let mut reader = decryptor
+        .decrypt(std::iter::once(&identity as &dyn age::Identity))
+        .map_err(|e| invalid(e.to_string()))?;
+    let mut plaintext = vec![];
+    reader
+        .read_to_end(&mut plaintext)
+        .map_err(|e| invalid(e.to_string()))?;
+    Ok(plaintext)
+}
+
+/// A public input or output packed into a digest instance, see [RunArgs::pack_public_threshold]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), derive(JsonSchema))]
+pub struct PackedTensor {
+    /// whether the tensor is an output of the model, else it is an input
+    pub output: bool,
+    /// the index of the input or output
+    pub idx: usize,
+    /// the number of values hashed to the digest
+    pub len: usize,
+    /// the index of the digest among the digests of the inputs, which are the first instances, or
+    /// among those of the outputs counting back from the last of them
+    pub digest: usize,
+}
+
+/// A run of consecutive instances of a circuit and what they hold, see [GraphSettings::instance_layout]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstanceSpan {
+    /// the index of the first instance
+    pub start: usize,
+    /// the number of instances
+    pub len: usize,
+    /// what the instances hold, as `output 0` or `poseidon digest of input 1`
+    pub label: String,
+    /// the model input (`false`) or output (`true`) and its index, if the instances hold its digest
+    pub digest_of: Option<(bool, usize)>,
+}
+
 /// model parameters
 #[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), derive(JsonSchema))]
 pub struct GraphSettings {
     /// run args
     pub run_args: RunArgs,
@@ -406,6 +1527,9 @@ pub struct GraphSettings {
     pub total_assignments: usize,
     /// total const size
     pub total_const_size: usize,
+    /// the assignments saved by laying out identical constants once
+    #[serde(default)]
+    pub deduped_constant_assignments: usize,
     /// total dynamic column size
     pub total_dynamic_col_size: usize,
     /// max dynamic column input length
@@ -422,10 +1546,27 @@ pub struct GraphSettings {
     pub model_output_scales: Vec<crate::Scale>,
     /// model input scales
     pub model_input_scales: Vec<crate::Scale>,
+    /// the names of the inputs of the model in the onnx graph
+    #[serde(default)]
+    pub model_input_names: Vec<String>,
+    /// the names of the nodes of the outputs of the model
+    #[serde(default)]
+    pub model_output_names: Vec<String>,
     /// the of instance cells used by modules
     pub module_sizes: ModuleSizes,
     /// required_lookups
+    #[cfg_attr(
+        all(feature = "ezkl", not(target_arch = "wasm32")),
+        schemars(with = "Vec<serde_json::Value>")
+    )]
     pub required_lookups: Vec<LookupOp>,
+    /// the required lookups fed the sums of adds (see [crate::RunArgs::fuse_add_lookups]), among the required lookups
+    #[serde(default)]
+    #[cfg_attr(
+        all(feature = "ezkl", not(target_arch = "wasm32")),
+        schemars(with = "Vec<serde_json::Value>")
+    )]
+    pub required_fused_add_lookups: Vec<LookupOp>,
     /// required range_checks
     pub required_range_checks: Vec<Range>,
     /// check mode
@@ -436,6 +1577,42 @@ pub struct GraphSettings {
     pub num_blinding_factors: Option<usize>,
     /// unix time timestamp
     pub timestamp: Option<u128>,
+    /// preprocessing applied to image inputs before quantization
+    #[serde(default)]
+    #[cfg_attr(
+        all(feature = "ezkl", not(target_arch = "wasm32")),
+        schemars(with = "Option<serde_json::Value>")
+    )]
+    pub image_preprocessing: Option<ImagePreprocessing>,
+    /// normalization applied to file inputs before quantization
+    #[serde(default)]
+    pub input_normalization: Option<InputNormalization>,
+    /// inputs authenticated by a signature verified in the circuit
+    #[serde(default)]
+    pub input_signature: Option<InputSignature>,
+    /// whether a nullifier of the inputs and a private salt is exposed as the last instance
+    #[serde(default)]
+    pub nullifier: bool,
+    /// outputs encrypted to a recipient public key in the circuit, the ciphertext is exposed before
+    /// the nullifier
+    #[serde(default)]
+    pub output_encryption: Option<OutputEncryption>,
+    /// whether the digest of the previous step of a session and the digest chaining the outputs to it are exposed,
+    /// right before the nullifier
+    #[serde(default)]
+    pub session: bool,
+    /// whether a private blinder is folded into each PolyCommit commitment
+    #[serde(default)]
+    pub polycommit_blinding: bool,
+    /// the public inputs and outputs packed into a digest of their values
+    #[serde(default)]
+    pub packed_tensors: Vec<PackedTensor>,
+    /// the groups the polycommit params are committed to in, in order, see [param_groups]
+    #[serde(default)]
+    pub param_commitment_groups: Vec<String>,
+    /// the public outputs packed several values to an instance, see [limbs]
+    #[serde(default)]
+    pub limb_packed_outputs: Vec<limbs::LimbPackedTensor>,
 }
 
 impl GraphSettings {
@@ -510,6 +1687,32 @@ impl GraphSettings {
             .ceil() as u32
     }
 
+    /// The fewest logrows the circuit is laid out in: the model, its instances and its modules without wrapping, and
+    /// the columns of its dynamic lookups and shuffles, never fewer than [MIN_LOGROWS]
+    pub fn min_logrows(&self) -> u32 {
+        [
+            MIN_LOGROWS,
+            self.fitting_logrows(),
+            self.dynamic_lookup_and_shuffle_logrows(),
+        ]
+        .into_iter()
+        .max()
+        .unwrap_or(MIN_LOGROWS)
+    }
+
+    /// The fewest logrows the model, its instances and its modules are laid out in without wrapping, the lookup tables
+    /// aside as they are split across more columns at fewer rows
+    pub fn fitting_logrows(&self) -> u32 {
+        [
+            self.model_constraint_logrows_with_blinding(),
+            self.log2_total_instances_with_blinding(),
+            self.module_constraint_logrows_with_blinding(),
+        ]
+        .into_iter()
+        .max()
+        .unwrap_or_default()
+    }
+
     fn constants_logrows(&self) -> u32 {
         (self.total_const_size as f64 / self.run_args.num_inner_cols as f64)
             .log2()
@@ -525,7 +1728,362 @@ impl GraphSettings {
             .collect();
         instances.extend(self.module_sizes.num_instances());
 
-        instances
+        instances
+    }
+
+    /// the index of the nullifier in the instances, if any, it is always the last instance
+    pub fn nullifier_instance_index(&self) -> Option<usize> {
+        self.nullifier
+            .then(|| self.total_instances().iter().sum::<usize>() - 1)
+    }
+
+    /// the range of the digest of the previous step of the session and of the digest of this step in the
+    /// instances, if any, they come right before the nullifier
+    pub fn session_instance_range(&self) -> Option<std::ops::Range<usize>> {
+        let end = self.total_instances().iter().sum::<usize>() - self.nullifier as usize;
+        self.session.then(|| end - SESSION_INSTANCES..end)
+    }
+
+    /// the range of the public key of the recipient and of the ciphertext of the encrypted outputs
+    /// in the instances, if any, they come right before the session digests and the nullifier
+    pub fn encryption_instance_range(&self) -> Option<std::ops::Range<usize>> {
+        let encryption = self.output_encryption.as_ref()?;
+        let len = ELGAMAL_HEADER_INSTANCES + encryption.lens.iter().sum::<usize>();
+        let end = self.total_instances().iter().sum::<usize>()
+            - self.session_instance_range().map_or(0, |r| r.len())
+            - self.nullifier as usize;
+        Some(end - len..end)
+    }
+
+    /// Decrypt the encrypted outputs of the instances of a proof with the secret key of the
+    /// recipient, returning them in the order of [OutputEncryption::outputs]
+    pub fn decrypt_outputs(
+        &self,
+        instances: &[Fp],
+        secret_key: &Fp,
+    ) -> Result<Vec<Vec<Fp>>, GraphError> {
+        let (Some(encryption), Some(range)) =
+            (&self.output_encryption, self.encryption_instance_range())
+        else {
+            return Err(GraphError::InvalidEncryption(
+                "the settings don't encrypt any outputs".into(),
+            ));
+        };
+        let ciphertext = instances.get(range.start + 2..range.end).ok_or_else(|| {
+            GraphError::InvalidEncryption(format!(
+                "expected {} instances, got {}",
+                range.end,
+                instances.len()
+            ))
+        })?;
+        let message =
+            ModuleElGamal::decrypt(&ElGamalCiphertext::from_instances(ciphertext)?, secret_key)?;
+        let mut offset = 0;
+        Ok(encryption
+            .lens
+            .iter()
+            .map(|len| {
+                offset += len;
+                message[offset - len..offset].to_vec()
+            })
+            .collect())
+    }
+
+    /// Find the packed tensors, the hashed/public tensors of the packing threshold: every input if
+    /// one of them has more values than the threshold, and the outputs that do
+    fn pack_tensors(
+        &mut self,
+        input_lens: &[usize],
+        output_lens: &[usize],
+    ) -> Result<(), GraphError> {
+        self.packed_tensors = vec![];
+        let Some(threshold) = self.run_args.pack_public_threshold else {
+            return Ok(());
+        };
+        let packs = |v: &Visibility| {
+            v.is_hashed() && v.is_hashed_public() && v.overwrites_inputs().is_empty()
+        };
+
+        // each input with values is hashed to a digest of its own
+        if packs(&self.run_args.input_visibility) && input_lens.iter().any(|len| *len > threshold) {
+            let hashed = input_lens.iter().enumerate().filter(|(_, len)| **len > 0);
+            for (digest, (idx, len)) in hashed.enumerate() {
+                self.packed_tensors.push(PackedTensor {
+                    output: false,
+                    idx,
+                    len: *len,
+                    digest,
+                });
+            }
+        }
+
+        let (visibility, outlets) =
+            VarVisibility::from_args(&self.run_args)?.processed_outputs(output_lens.len());
+        if packs(&visibility) {
+            let hashed = outlets
+                .into_iter()
+                .filter(|o| output_lens[*o] > 0)
+                .collect_vec();
+            for (k, idx) in hashed.iter().enumerate() {
+                if output_lens[*idx] > threshold {
+                    self.packed_tensors.push(PackedTensor {
+                        output: true,
+                        idx: *idx,
+                        len: output_lens[*idx],
+                        digest: hashed.len() - 1 - k,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Find the public outputs packed several values to an instance (see [RunArgs::instance_limbs]),
+    /// whose instances are then shaped as their packed values
+    fn limb_pack_outputs(&mut self, output_lens: &[usize]) {
+        self.limb_packed_outputs = vec![];
+        let Some(limbs) = self.run_args.instance_limbs else {
+            return;
+        };
+        let public = (0..output_lens.len())
+            .filter(|i| self.run_args.output_is_public(*i))
+            .collect_vec();
+        // the instances of the public outputs follow those of the public inputs
+        let offset = self.model_instance_shapes.len() - public.len();
+        for (k, idx) in public.into_iter().enumerate() {
+            let tensor = limbs::LimbPackedTensor {
+                idx,
+                len: output_lens[idx],
+                limbs,
+                limb_bits: self.run_args.instance_limb_bits(),
+            };
+            self.model_instance_shapes[offset + k] = vec![tensor.num_instances()];
+            self.limb_packed_outputs.push(tensor);
+        }
+    }
+
+    /// The packing of output `idx` to the limbs of its instances, if it is packed
+    pub fn limb_packed_output(&self, idx: usize) -> Option<&limbs::LimbPackedTensor> {
+        self.limb_packed_outputs.iter().find(|t| t.idx == idx)
+    }
+
+    fn limb_packed_label(tensor: &limbs::LimbPackedTensor) -> String {
+        format!(
+            "output {} packed {} values per instance",
+            tensor.idx, tensor.limbs
+        )
+    }
+
+    /// The index in the instances of the first instance of each of the limb packed outputs
+    pub fn limb_packed_instance_starts(&self) -> Vec<usize> {
+        let layout = self.instance_layout();
+        self.limb_packed_outputs
+            .iter()
+            .filter_map(|t| {
+                let label = Self::limb_packed_label(t);
+                layout.iter().find(|s| s.label == label).map(|s| s.start)
+            })
+            .collect()
+    }
+
+    /// The index in the instances of the digest of each of the packed tensors. The digests of the
+    /// outputs are followed by the ciphertext of the encrypted outputs, the session digests and the nullifier, if any.
+    pub fn packed_instance_indices(&self) -> Vec<usize> {
+        let outputs_end = self.total_instances().iter().sum::<usize>()
+            - self.encryption_instance_range().map_or(0, |r| r.len())
+            - self.session_instance_range().map_or(0, |r| r.len())
+            - self.nullifier as usize;
+        self.packed_tensors
+            .iter()
+            .map(|t| match t.output {
+                true => outputs_end - 1 - t.digest,
+                false => t.digest,
+            })
+            .collect()
+    }
+
+    /// The instances of the circuit in the order of [GraphCircuit::prepare_public_inputs], each
+    /// run of instances labelled with what it holds. The params of the model are hashed as a
+    /// single tensor, and instances the layout doesn't account for are labelled `unlabelled`.
+    pub fn instance_layout(&self) -> Vec<InstanceSpan> {
+        self.instance_runs()
+            .into_iter()
+            .map(|(span, _)| span)
+            .collect()
+    }
+
+    /// Each instance of the circuit labelled with its role, the input or output of the model it belongs to, its
+    /// position in it and its scale, in the order of [GraphSettings::instance_layout]
+    pub fn instance_map(&self) -> Vec<InstanceLabel> {
+        self.instance_runs()
+            .iter()
+            .flat_map(|(span, run)| run.labels(span, self))
+            .collect()
+    }
+
+    /// The name of the model input (`false`) or output (`true`) at `idx`, as `output 0` for settings that don't
+    /// record the names
+    pub fn tensor_name(&self, output: bool, idx: usize) -> String {
+        let (names, kind) = match output {
+            true => (&self.model_output_names, "output"),
+            false => (&self.model_input_names, "input"),
+        };
+        names
+            .get(idx)
+            .cloned()
+            .unwrap_or_else(|| format!("{} {}", kind, idx))
+    }
+
+    /// The runs of instances of [GraphSettings::instance_layout], with what they hold
+    pub(crate) fn instance_runs(&self) -> Vec<(InstanceSpan, InstanceRun)> {
+        let run_args = &self.run_args;
+        let num_inputs = self.model_input_scales.len();
+        let num_outputs = self.model_output_scales.len();
+        let packed = |output: bool, idx: usize| {
+            self.packed_tensors
+                .iter()
+                .any(|t| t.output == output && t.idx == idx)
+        };
+        // the digests of a hashed tensor, and what they are called
+        let digest = |visibility: &Visibility| {
+            if visibility.is_sha256_hashed() {
+                Some((SHA256_INSTANCES, "sha256 digest"))
+            } else if visibility.is_poseidon_hashed() {
+                Some((1, "poseidon digest"))
+            } else {
+                None
+            }
+        };
+
+        let mut runs: Vec<InstanceRun> = vec![];
+        if run_args.input_visibility.is_merkle() {
+            runs.push(InstanceRun::new(
+                1,
+                "merkle root of the dataset the inputs belong to".into(),
+                InstanceRole::MerkleRoot,
+            ));
+        } else if let Some((len, name)) = digest(&run_args.input_visibility) {
+            for i in 0..num_inputs {
+                let prefix = if packed(false, i) { "packed " } else { "" };
+                runs.push(InstanceRun::digest(
+                    len,
+                    format!("{} of {}input {}", name, prefix, i),
+                    InstanceRole::InputDigest,
+                    (false, i),
+                ));
+            }
+        }
+        if self.input_signature.is_some() {
+            runs.push(InstanceRun::new(
+                ECDSA_INSTANCES,
+                "public key of the signer of the inputs".into(),
+                InstanceRole::SignerPublicKey,
+            ));
+        }
+        if let Some((len, name)) = digest(&run_args.param_visibility) {
+            runs.push(InstanceRun::new(
+                len,
+                format!("{} of the params", name),
+                InstanceRole::ParamsDigest,
+            ));
+        }
+        let mut shapes = self.model_instance_shapes.iter();
+        if run_args.input_visibility.is_public() {
+            for (i, shape) in shapes.by_ref().take(num_inputs).enumerate() {
+                runs.push(InstanceRun::values(
+                    shape.clone(),
+                    format!("input {}", i),
+                    InstanceRole::InputValue,
+                    (false, i),
+                ));
+            }
+        }
+        for i in 0..num_outputs {
+            if run_args.output_is_public(i) {
+                if let Some(shape) = shapes.next() {
+                    let (label, role) = match self.limb_packed_output(i) {
+                        Some(t) => (Self::limb_packed_label(t), InstanceRole::PackedOutputValues),
+                        None if run_args.revealed_outputs.contains(&i) => {
+                            (format!("revealed output {}", i), InstanceRole::OutputValue)
+                        }
+                        None => (format!("output {}", i), InstanceRole::OutputValue),
+                    };
+                    runs.push(InstanceRun::values(shape.clone(), label, role, (true, i)));
+                }
+            }
+        }
+        for i in 0..num_outputs {
+            if let Some((len, name)) = digest(run_args.visibility_of_output(i)) {
+                let prefix = if packed(true, i) { "packed " } else { "" };
+                runs.push(InstanceRun::digest(
+                    len,
+                    format!("{} of {}output {}", name, prefix, i),
+                    InstanceRole::OutputDigest,
+                    (true, i),
+                ));
+            }
+        }
+        if let Some(encryption) = &self.output_encryption {
+            runs.push(InstanceRun::new(
+                ELGAMAL_HEADER_INSTANCES,
+                "public key of the recipient of the encrypted outputs".into(),
+                InstanceRole::RecipientPublicKey,
+            ));
+            runs.push(InstanceRun::new(
+                encryption.lens.iter().sum(),
+                format!("ciphertext of outputs {:?}", encryption.outputs),
+                InstanceRole::Ciphertext,
+            ));
+        }
+        if self.session {
+            runs.push(InstanceRun::new(
+                1,
+                "digest of the previous step of the session".into(),
+                InstanceRole::PreviousSessionDigest,
+            ));
+            runs.push(InstanceRun::new(
+                1,
+                "session digest of the outputs".into(),
+                InstanceRole::SessionDigest,
+            ));
+        }
+        if self.nullifier {
+            runs.push(InstanceRun::new(
+                NULLIFIER_INSTANCES,
+                "nullifier".into(),
+                InstanceRole::Nullifier,
+            ));
+        }
+
+        let total = self.total_instances().iter().sum::<usize>();
+        let mut spans = vec![];
+        let mut start = 0;
+        for run in runs {
+            if run.len > 0 && start + run.len <= total {
+                let span = InstanceSpan {
+                    start,
+                    len: run.len,
+                    label: run.label.clone(),
+                    digest_of: run.digest_of,
+                };
+                start += run.len;
+                spans.push((span, run));
+            }
+        }
+        if start < total {
+            let run =
+                InstanceRun::new(total - start, "unlabelled".into(), InstanceRole::Unlabelled);
+            spans.push((
+                InstanceSpan {
+                    start,
+                    len: run.len,
+                    label: run.label.clone(),
+                    digest_of: None,
+                },
+                run,
+            ));
+        }
+        spans
     }
 
     /// calculate the log2 of the total number of instances
@@ -556,12 +2114,10 @@ impl GraphSettings {
     }
     /// load params from file
     pub fn load(path: &std::path::PathBuf) -> Result<Self, std::io::Error> {
-        // buf reader
-        let reader =
-            std::io::BufReader::with_capacity(*EZKL_BUF_CAPACITY, std::fs::File::open(path)?);
-        serde_json::from_reader(reader).map_err(|e| {
-            error!("failed to load settings file at {}", e);
-            std::io::Error::new(std::io::ErrorKind::Other, e)
+        let bytes = std::fs::read(path)?;
+        from_json_slice(&bytes, JsonArtifact::Settings).map_err(|e| {
+            error!("failed to load settings file at {}: {}", path.display(), e);
+            std::io::Error::new(std::io::ErrorKind::Other, e.to_string())
         })
     }
 
@@ -604,8 +2160,13 @@ impl GraphSettings {
     /// if any visibility is encrypted or hashed
     pub fn module_requires_fixed(&self) -> bool {
         self.run_args.input_visibility.is_hashed()
-            || self.run_args.output_visibility.is_hashed()
+            || self.run_args.input_visibility.is_merkle()
+            || self.run_args.any_output_visibility(|v| v.is_hashed())
             || self.run_args.param_visibility.is_hashed()
+            || self.input_signature.is_some()
+            || self.nullifier
+            || self.output_encryption.is_some()
+            || self.session
     }
 
     /// requires dynamic lookup
@@ -621,7 +2182,7 @@ impl GraphSettings {
     /// any kzg visibility
     pub fn module_requires_polycommit(&self) -> bool {
         self.run_args.input_visibility.is_polycommit()
-            || self.run_args.output_visibility.is_polycommit()
+            || self.run_args.any_output_visibility(|v| v.is_polycommit())
             || self.run_args.param_visibility.is_polycommit()
     }
 }
@@ -634,16 +2195,93 @@ pub struct GraphConfig {
     circuit_size: CircuitSize,
 }
 
+impl GraphConfig {
+    /// Configures the columns, modules and gates of the circuit of a model from its settings alone, such that the
+    /// constraint system of a verifying key can be rebuilt without the model (see [crate::pfsys::verifier])
+    pub fn configure(cs: &mut ConstraintSystem<Fp>, params: GraphSettings) -> Self {
+        let mut params = params;
+        params.set_num_blinding_factors(cs.blinding_factors());
+        GLOBAL_SETTINGS.with(|settings| {
+            *settings.borrow_mut() = Some(params.clone());
+        });
+        let visibility = match VarVisibility::from_args(&params.run_args) {
+            Ok(v) => v,
+            Err(e) => {
+                log::error!("failed to create visibility: {:?}", e);
+                log::warn!("using default visibility");
+                VarVisibility::default()
+            }
+        };
+
+        let mut module_configs = ModuleConfigs::from_visibility(
+            cs,
+            params.module_sizes.clone(),
+            params.run_args.logrows as usize,
+        );
+
+        let mut vars = ModelVars::new(cs, &params);
+
+        module_configs.configure_complex_modules(
+            cs,
+            visibility,
+            params.module_sizes.clone(),
+            params.input_signature.as_ref(),
+            params.nullifier,
+            params.output_encryption.is_some(),
+            params.session,
+            params.run_args.poseidon_params,
+        );
+
+        vars.instantiate_instance(
+            cs,
+            params.model_instance_shapes.clone(),
+            params.run_args.input_scale,
+            module_configs.instance,
+        );
+
+        let model_config = ModelConfig::configure(cs, vars, &params).unwrap();
+
+        debug!(
+            "degree: {}, log2_ceil of degrees: {:?}",
+            cs.degree(),
+            (cs.degree() as f32).log2().ceil()
+        );
+
+        let circuit_size = CircuitSize::from_cs(cs, params.run_args.logrows);
+
+        #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+        debug!(
+            "circuit size: \n {}",
+            circuit_size
+                .as_json()
+                .unwrap()
+                .to_colored_json_auto()
+                .unwrap()
+        );
+
+        GraphConfig {
+            model_config,
+            module_configs,
+            circuit_size,
+        }
+    }
+}
+
 /// Defines the circuit for a computational graph / model loaded from a `.onnx` file.
+#[cfg(not(all(feature = "verifier-only", not(feature = "ezkl"))))]
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct CoreCircuit {
     /// The model / graph of computations.
     pub model: Model,
     /// The settings of the model.
     pub settings: GraphSettings,
+    /// The rows of the model region laid out by each op, by start row, such that the constraints a witness fails
+    /// can be attributed to the nodes of the model. Recorded when the circuit is compiled.
+    pub op_rows: Vec<OpProfile>,
 }
 
 /// Defines the circuit for a computational graph / model loaded from a `.onnx` file.
+#[cfg(not(all(feature = "verifier-only", not(feature = "ezkl"))))]
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct GraphCircuit {
     /// Core circuit
@@ -652,19 +2290,254 @@ pub struct GraphCircuit {
     pub graph_witness: GraphWitness,
 }
 
+#[cfg(not(all(feature = "verifier-only", not(feature = "ezkl"))))]
 impl GraphCircuit {
     /// Settings for the graph
     pub fn settings(&self) -> &GraphSettings {
         &self.core.settings
     }
+    /// The nodes of the model, see [Model::nodes]
+    pub fn nodes(&self) -> Vec<NodeInfo> {
+        self.core.model.nodes()
+    }
+    /// The node of the model at `idx`, see [Model::node]
+    pub fn node(&self, idx: usize) -> Option<NodeInfo> {
+        self.core.model.node(idx)
+    }
+    /// The ops the circuit looks up
+    pub fn required_lookups(&self) -> &[LookupOp] {
+        &self.core.settings.required_lookups
+    }
+    /// What the instances of the circuit hold, see [GraphSettings::instance_layout]
+    pub fn instance_layout(&self) -> Vec<InstanceSpan> {
+        self.core.settings.instance_layout()
+    }
+    /// The nodes, lookups and instances of the circuit, without the onnx model it was compiled from
+    pub fn inspect(&self) -> CircuitInspection {
+        CircuitInspection {
+            input_names: self.core.model.graph.input_names().to_vec(),
+            output_names: self.core.model.graph.output_names(),
+            nodes: self.nodes(),
+            required_lookups: self.required_lookups().to_vec(),
+            required_range_checks: self.core.settings.required_range_checks.clone(),
+            instance_layout: self.instance_layout(),
+        }
+    }
+    /// Records the rows each op of the model is laid out in, see [CoreCircuit::op_rows]
+    pub fn record_op_rows(&mut self) -> Result<(), GraphError> {
+        let mut ops = self.core.model.profile(&self.core.settings.run_args)?.ops;
+        // an op taking no rows comes before the op laid out from the same row
+        ops.sort_by_key(|op| (op.start_row, op.rows));
+        self.core.op_rows = ops;
+        Ok(())
+    }
     /// Settings for the graph (mutable)
     pub fn settings_mut(&mut self) -> &mut GraphSettings {
         &mut self.core.settings
     }
+    /// Sign a set of inputs, reserving the rows of their hash and of the verification of the
+    /// signature, and the instances of the public key it verifies against. The signed inputs must
+    /// be witnessed as they are: private, hashed into a public hash, or proven to belong to a dataset.
+    pub fn set_input_signature(&mut self, signature: InputSignature) -> Result<(), GraphError> {
+        let visibility = &self.settings().run_args.input_visibility;
+        let signable = matches!(visibility, Visibility::Private)
+            || visibility.is_merkle()
+            || (visibility.is_hashed_public() && visibility.overwrites_inputs().is_empty());
+        if !signable {
+            return Err(GraphError::InvalidSignature(format!(
+                "inputs with {} visibility can't be signed",
+                visibility
+            )));
+        }
+        if self.settings().input_signature.is_some() {
+            return Err(GraphError::InvalidSignature(
+                "the settings already sign inputs".into(),
+            ));
+        }
+        if signature.hash == SignatureHash::Poseidon
+            && self.settings().run_args.poseidon_params != PoseidonParams::Ezkl
+        {
+            return Err(GraphError::InvalidSignature(
+                "poseidon digests of the signed inputs hash with the ezkl poseidon parameters"
+                    .into(),
+            ));
+        }
+
+        let shapes = self.model().graph.input_shapes()?;
+        let distinct = signature
+            .inputs
+            .iter()
+            .collect::<std::collections::HashSet<_>>()
+            .len();
+        if signature.inputs.is_empty()
+            || distinct != signature.inputs.len()
+            || signature.inputs.iter().any(|i| *i >= shapes.len())
+        {
+            return Err(GraphError::InvalidSignature(format!(
+                "the signed inputs {:?} must be distinct indices of the {} inputs",
+                signature.inputs,
+                shapes.len()
+            )));
+        }
+
+        let signed = signature
+            .inputs
+            .iter()
+            .map(|i| shapes[*i].clone())
+            .collect();
+        let settings = self.settings_mut();
+        GraphModules::num_signature_constraints(&signature, signed, &mut settings.module_sizes);
+        settings.num_rows =
+            std::cmp::max(settings.num_rows, settings.module_sizes.max_constraints());
+        settings.input_signature = Some(signature);
+        Ok(())
+    }
+
+    /// Expose the nullifier of the inputs and of a private salt, reserving the rows of the
+    /// commitment of the inputs and of the nullifier, and its instance. The inputs must be witnessed
+    /// as they are: private, hashed into a public hash, or proven to belong to a dataset.
+    pub fn set_nullifier(&mut self) -> Result<(), GraphError> {
+        let visibility = &self.settings().run_args.input_visibility;
+        let nullifiable = matches!(visibility, Visibility::Private)
+            || visibility.is_merkle()
+            || (visibility.is_hashed() && visibility.overwrites_inputs().is_empty());
+        if !nullifiable {
+            return Err(GraphError::InvalidNullifier(format!(
+                "inputs with {} visibility can't be nullified",
+                visibility
+            )));
+        }
+        if self.settings().nullifier {
+            return Err(GraphError::InvalidNullifier(
+                "the settings already expose a nullifier".into(),
+            ));
+        }
+        if self.settings().run_args.poseidon_params != PoseidonParams::Ezkl {
+            return Err(GraphError::InvalidNullifier(
+                "nullifiers hash with the ezkl poseidon parameters".into(),
+            ));
+        }
+
+        let shapes = self.model().graph.input_shapes()?;
+        let settings = self.settings_mut();
+        GraphModules::num_nullifier_constraints(shapes, &mut settings.module_sizes);
+        settings.num_rows =
+            std::cmp::max(settings.num_rows, settings.module_sizes.max_constraints());
+        settings.nullifier = true;
+        Ok(())
+    }
+
+    /// Fold a private blinder into each PolyCommit commitment, reserving the row of the blinder in
+    /// the committed columns. The blinders are drawn with the witness, see
+    /// [GraphWitness::get_polycommit_blinders].
+    pub fn set_polycommit_blinding(&mut self) -> Result<(), GraphError> {
+        if !self.settings().module_requires_polycommit() {
+            return Err(GraphError::InvalidBlinding(
+                "no visibility is polycommit, there are no commitments to blind".into(),
+            ));
+        }
+        if self.settings().polycommit_blinding {
+            return Err(GraphError::InvalidBlinding(
+                "the settings already blind the commitments".into(),
+            ));
+        }
+
+        let settings = self.settings_mut();
+        GraphModules::num_blinding_constraints(&mut settings.module_sizes);
+        settings.polycommit_blinding = true;
+        Ok(())
+    }
+
+    /// Encrypt private outputs to a recipient public key, reserving the rows of the encryption and
+    /// the instances of the public key and of the ciphertext. The public key is supplied with the
+    /// witness, see [GraphWitness::encrypt_outputs].
+    pub fn set_encrypted_outputs(&mut self, outputs: Vec<usize>) -> Result<(), GraphError> {
+        if self.settings().output_encryption.is_some() {
+            return Err(GraphError::InvalidEncryption(
+                "the settings already encrypt outputs".into(),
+            ));
+        }
+        let shapes = self.model().graph.output_shapes()?;
+        let distinct = outputs
+            .iter()
+            .collect::<std::collections::HashSet<_>>()
+            .len();
+        if outputs.is_empty()
+            || distinct != outputs.len()
+            || outputs.iter().any(|o| *o >= shapes.len())
+        {
+            return Err(GraphError::InvalidEncryption(format!(
+                "the encrypted outputs {:?} must be distinct indices of the {} outputs",
+                outputs,
+                shapes.len()
+            )));
+        }
+        let run_args = &self.settings().run_args;
+        if let Some(o) = outputs
+            .iter()
+            .find(|o| !matches!(run_args.visibility_of_output(**o), Visibility::Private))
+        {
+            return Err(GraphError::InvalidEncryption(format!(
+                "output {} with {} visibility can't be encrypted, it must be private",
+                o,
+                run_args.visibility_of_output(*o)
+            )));
+        }
+
+        let lens = outputs
+            .iter()
+            .map(|o| shapes[*o].iter().product())
+            .collect();
+        let encryption = OutputEncryption { outputs, lens };
+        let settings = self.settings_mut();
+        GraphModules::num_encryption_constraints(&encryption, &mut settings.module_sizes);
+        settings.num_rows =
+            std::cmp::max(settings.num_rows, settings.module_sizes.max_constraints());
+        settings.output_encryption = Some(encryption);
+        Ok(())
+    }
+
+    /// Expose the digest of the previous step of a session and the digest chaining the commitment of the outputs to
+    /// it, reserving the rows of the commitment and of the digest, and their instances. The outputs must be committed
+    /// to as they are, not overwritten by their hashes.
+    pub fn set_session(&mut self) -> Result<(), GraphError> {
+        let run_args = &self.settings().run_args;
+        if run_args.any_output_visibility(|v| !v.overwrites_inputs().is_empty()) {
+            return Err(GraphError::InvalidSession(
+                "outputs overwritten by their hashes can't be chained to a session".into(),
+            ));
+        }
+        if self.settings().session {
+            return Err(GraphError::InvalidSession(
+                "the settings already expose session digests".into(),
+            ));
+        }
+        if run_args.poseidon_params != PoseidonParams::Ezkl {
+            return Err(GraphError::InvalidSession(
+                "session digests hash with the ezkl poseidon parameters".into(),
+            ));
+        }
+
+        let shapes = self.model().graph.output_shapes()?;
+        let settings = self.settings_mut();
+        GraphModules::num_session_constraints(shapes, &mut settings.module_sizes);
+        settings.num_rows =
+            std::cmp::max(settings.num_rows, settings.module_sizes.max_constraints());
+        settings.session = true;
+        Ok(())
+    }
     /// The model
     pub fn model(&self) -> &Model {
         &self.core.model
     }
+
+    /// The commitment group of each constant of the model, see [param_groups::param_group_indices]
+    pub fn param_group_indices(&self) -> Result<Vec<usize>, GraphError> {
+        param_groups::param_group_indices(
+            &self.model().const_nodes(),
+            &self.settings().run_args.commitment_groups,
+        )
+    }
     ///
     pub fn save(&self, path: std::path::PathBuf) -> Result<(), GraphError> {
         let f = std::fs::File::create(&path).map_err(|e| {
@@ -675,12 +2548,46 @@ impl GraphCircuit {
         Ok(())
     }
 
+    /// Saves the circuit to `path` encrypted to `recipient`, see [crate::pfsys::encryption]
+    #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+    pub fn save_encrypted(
+        &self,
+        path: std::path::PathBuf,
+        recipient: &crate::pfsys::encryption::Recipient,
+    ) -> Result<(), GraphError> {
+        crate::pfsys::encryption::write_encrypted(&path, recipient, |writer| {
+            bincode::serialize_into(writer, &self)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))
+        })?;
+        Ok(())
+    }
+
+    /// Loads the circuit at `path`, decrypting it into memory with `identity` if it is encrypted
+    #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+    pub fn load_decrypted(
+        path: std::path::PathBuf,
+        identity: Option<&crate::pfsys::encryption::Identity>,
+    ) -> Result<Self, GraphError> {
+        let identity = match identity {
+            Some(identity) if crate::pfsys::encryption::is_encrypted(&path) => identity,
+            _ => return Self::load(path),
+        };
+        let bytes = crate::pfsys::encryption::read_decrypted(&path, identity)?;
+        let _span =
+            tracing::info_span!("model_load", format = "encrypted", bytes = bytes.len()).entered();
+        Ok(bincode::deserialize(&bytes)?)
+    }
+
     ///
     pub fn load(path: std::path::PathBuf) -> Result<Self, GraphError> {
+        #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+        crate::pfsys::encryption::ensure_plaintext(&path)?;
         // read bytes from file
         let f = std::fs::File::open(&path).map_err(|e| {
             GraphError::ReadWriteFileError(path.display().to_string(), e.to_string())
         })?;
+        let bytes = f.metadata().map_or(0, |m| m.len());
+        let _span = tracing::info_span!("model_load", format = "compiled", bytes).entered();
         let reader = std::io::BufReader::with_capacity(*EZKL_BUF_CAPACITY, f);
         let result: GraphCircuit = bincode::deserialize_from(reader)?;
 
@@ -696,6 +2603,10 @@ pub enum TestDataSource {
     /// The data is loaded from the chain
     #[default]
     OnChain,
+    /// The data is loaded from contract storage and attested with storage proofs
+    StorageProof,
+    /// The data is loaded from the chain, through view functions returning nested types
+    NestedOnChain,
 }
 
 impl std::fmt::Display for TestDataSource {
@@ -703,6 +2614,8 @@ impl std::fmt::Display for TestDataSource {
         match self {
             TestDataSource::File => write!(f, "file"),
             TestDataSource::OnChain => write!(f, "on-chain"),
+            TestDataSource::StorageProof => write!(f, "storage-proof"),
+            TestDataSource::NestedOnChain => write!(f, "nested-on-chain"),
         }
     }
 }
@@ -715,6 +2628,8 @@ impl From<String> for TestDataSource {
         match value.to_lowercase().as_str() {
             "file" => TestDataSource::File,
             "on-chain" => TestDataSource::OnChain,
+            "storage-proof" => TestDataSource::StorageProof,
+            "nested-on-chain" => TestDataSource::NestedOnChain,
             _ => {
                 error!("invalid data source: {}", value);
                 warn!("using default data source: on-chain");
@@ -744,6 +2659,7 @@ pub struct TestOnChainData {
     pub data_sources: TestSources,
 }
 
+#[cfg(not(all(feature = "verifier-only", not(feature = "ezkl"))))]
 impl GraphCircuit {
     ///
     pub fn new(model: Model, run_args: &RunArgs) -> Result<GraphCircuit, GraphError> {
@@ -754,38 +2670,75 @@ impl GraphCircuit {
             inputs.push(t);
         }
 
+        let settings = Self::gen_settings(&model, run_args)?;
+
+        let core = CoreCircuit {
+            model,
+            settings: settings.clone(),
+            op_rows: vec![],
+        };
+
+        Ok(GraphCircuit {
+            core,
+            graph_witness: GraphWitness::new(inputs, vec![]),
+        })
+    }
+
+    /// The settings of the circuit of a model, with the rows and instances of its modules
+    pub(crate) fn gen_settings(
+        model: &Model,
+        run_args: &RunArgs,
+    ) -> Result<GraphSettings, GraphError> {
         // dummy module settings, must load from GraphData after
         let mut settings = model.gen_params(run_args, run_args.check_mode)?;
 
-        let mut num_params = 0;
-        if !model.const_shapes().is_empty() {
-            for shape in model.const_shapes() {
-                num_params += shape.iter().product::<usize>();
-            }
+        // the params are committed to by group, see [param_groups]
+        let param_lens = model
+            .const_shapes()
+            .iter()
+            .map(|shape| shape.iter().product::<usize>())
+            .collect::<Vec<_>>();
+        let param_groups =
+            param_groups::param_group_indices(&model.const_nodes(), &run_args.commitment_groups)?;
+        if run_args.param_visibility.is_polycommit() {
+            settings.param_commitment_groups = param_groups::committed_groups(
+                &param_groups,
+                &param_lens,
+                &run_args.commitment_groups,
+            )?;
         }
 
+        // the run args of the settings have the packed tensors hashed
         let sizes = GraphModules::num_constraints_and_instances(
             model.graph.input_shapes()?,
-            vec![vec![num_params]],
+            param_groups::group_lens(&param_groups, &param_lens)
+                .into_iter()
+                .map(|len| vec![len])
+                .collect(),
             model.graph.output_shapes()?,
-            VarVisibility::from_args(run_args)?,
+            VarVisibility::from_args(&settings.run_args)?,
+            run_args.poseidon_params,
         );
 
         // number of instances used by modules
         settings.module_sizes = sizes.clone();
 
+        let lens = |shapes: Vec<Vec<usize>>| {
+            shapes
+                .iter()
+                .map(|shape| shape.iter().product())
+                .collect::<Vec<usize>>()
+        };
+        settings.pack_tensors(
+            &lens(model.graph.input_shapes()?),
+            &lens(model.graph.output_shapes()?),
+        )?;
+        settings.limb_pack_outputs(&lens(model.graph.output_shapes()?));
+
         // as they occupy independent rows
         settings.num_rows = std::cmp::max(settings.num_rows, sizes.max_constraints());
 
-        let core = CoreCircuit {
-            model,
-            settings: settings.clone(),
-        };
-
-        Ok(GraphCircuit {
-            core,
-            graph_witness: GraphWitness::new(inputs, vec![]),
-        })
+        Ok(settings)
     }
 
     ///
@@ -808,6 +2761,7 @@ impl GraphCircuit {
         let core = CoreCircuit {
             model,
             settings: settings.clone(),
+            op_rows: vec![],
         };
 
         Ok(GraphCircuit {
@@ -818,6 +2772,11 @@ impl GraphCircuit {
 
     /// load inputs and outputs for the model
     pub fn load_graph_witness(&mut self, data: &GraphWitness) -> Result<(), GraphError> {
+        if data.redaction.is_some() {
+            return Err(GraphError::InvalidRedaction(
+                "the witness is redacted, prove with the full witness".into(),
+            ));
+        }
         self.graph_witness = data.clone();
         // load the module settings
         Ok(())
@@ -829,11 +2788,46 @@ impl GraphCircuit {
         // as they are configured in that order as Column<Instances>
         let mut public_inputs: Vec<Fp> = vec![];
 
+        if self.settings().run_args.input_visibility.is_merkle()
+            && !matches!(&data.processed_inputs, Some(p) if p.merkle_path.is_some())
+        {
+            return Err(GraphError::InvalidMerkle(
+                "the witness has no path of the inputs to the dataset root".into(),
+            ));
+        }
+
+        // the blinded commitments are only proven with their blinders in the clear
+        if self.settings().polycommit_blinding {
+            let committed = self.settings().module_sizes.num_polycommits();
+            let blinders = data.get_polycommit_blinders()?;
+            if blinders.len() != committed {
+                return Err(GraphError::InvalidBlinding(format!(
+                    "the witness has {} blinders for {} commitments",
+                    blinders.len(),
+                    committed
+                )));
+            }
+        }
+
         // we first process the inputs
         if let Some(processed_inputs) = &data.processed_inputs {
             public_inputs.extend(processed_inputs.get_instances().into_iter().flatten());
         }
 
+        // then the public key the signed inputs are verified against
+        if self.settings().input_signature.is_some() {
+            let signature = data
+                .input_signature
+                .as_ref()
+                .and_then(|s| s.signature.as_ref())
+                .ok_or_else(|| {
+                    GraphError::InvalidSignature(
+                        "the witness has no signature over the signed inputs".into(),
+                    )
+                })?;
+            public_inputs.extend(signature.public_key_instances());
+        }
+
         // we then process the params
         if let Some(processed_params) = &data.processed_params {
             public_inputs.extend(processed_params.get_instances().into_iter().flatten());
@@ -844,14 +2838,58 @@ impl GraphCircuit {
             public_inputs.extend(self.graph_witness.inputs.clone().into_iter().flatten())
         }
 
-        // if the outputs are public, we add them to the public inputs
-        if self.settings().run_args.output_visibility.is_public() {
-            public_inputs.extend(self.graph_witness.outputs.clone().into_iter().flatten());
-        // if the outputs are processed, we add the processed outputs to the public inputs
-        } else if let Some(processed_outputs) = &data.processed_outputs {
+        // the public outputs are added to the public inputs, followed by the processed outputs
+        let run_args = &self.settings().run_args;
+        for (i, output) in self.graph_witness.outputs.iter().enumerate() {
+            if run_args.output_is_public(i) {
+                match self.settings().limb_packed_output(i) {
+                    Some(tensor) => public_inputs.extend(limbs::pack_limbs(tensor, output)?),
+                    None => public_inputs.extend(output.clone()),
+                }
+            }
+        }
+        if let Some(processed_outputs) = &data.processed_outputs {
             public_inputs.extend(processed_outputs.get_instances().into_iter().flatten());
         }
 
+        // then the recipient public key and the ciphertext of the encrypted outputs
+        if self.settings().output_encryption.is_some() {
+            let encryption = data.output_encryption.as_ref().ok_or_else(|| {
+                GraphError::InvalidEncryption(
+                    "the witness has no ciphertext, encrypt the outputs to a recipient key".into(),
+                )
+            })?;
+            public_inputs.extend(encryption.instances());
+        }
+
+        // then the digest of the previous step of the session and the digest of this step
+        if self.settings().session {
+            let digests = data
+                .session
+                .as_ref()
+                .and_then(|s| s.prev_digest.zip(s.next_digest))
+                .ok_or_else(|| {
+                    GraphError::InvalidSession(
+                        "the witness has no session digests, chain it to the previous step".into(),
+                    )
+                })?;
+            public_inputs.extend([digests.0, digests.1]);
+        }
+
+        // the nullifier is the last instance
+        if self.settings().nullifier {
+            let nullifier = data
+                .nullifier
+                .as_ref()
+                .and_then(|n| n.nullifier)
+                .ok_or_else(|| {
+                    GraphError::InvalidNullifier(
+                        "the witness has no nullifier, derive it with a salt".into(),
+                    )
+                })?;
+            public_inputs.push(nullifier);
+        }
+
         if public_inputs.len() < 11 {
             debug!("public inputs: {:?}", public_inputs);
         } else {
@@ -889,10 +2927,18 @@ impl GraphCircuit {
             public_inputs.processed_params = elements.processed_params.clone();
         }
 
-        if self.settings().run_args.output_visibility.is_public() {
-            public_inputs.rescaled_outputs = elements.rescaled_outputs.clone();
-            public_inputs.outputs = elements.outputs.clone();
-        } else if data.processed_outputs.is_some() {
+        let run_args = &self.settings().run_args;
+        let public = |elements: &[Vec<String>]| {
+            elements
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| run_args.output_is_public(*i))
+                .map(|(_, e)| e.clone())
+                .collect_vec()
+        };
+        public_inputs.rescaled_outputs = public(&elements.rescaled_outputs);
+        public_inputs.outputs = public(&elements.outputs);
+        if data.processed_outputs.is_some() {
             public_inputs.processed_outputs = elements.processed_outputs.clone();
         }
 
@@ -911,7 +2957,9 @@ impl GraphCircuit {
         let shapes = self.model().graph.input_shapes()?;
         let scales = self.model().graph.get_input_scales();
         let input_types = self.model().graph.get_input_types()?;
-        self.process_data_source(&data.input_data, shapes, scales, input_types)
+        let field_inputs = data.field_inputs(&scales)?;
+        let input_data = field_inputs.as_ref().unwrap_or(&data.input_data);
+        self.process_data_source(input_data, shapes, scales, input_types)
     }
 
     ///
@@ -924,7 +2972,8 @@ impl GraphCircuit {
         let input_types = self.model().graph.get_input_types()?;
         debug!("input scales: {:?}", scales);
 
-        match &data.input_data {
+        let field_inputs = data.field_inputs(&scales)?;
+        match field_inputs.as_ref().unwrap_or(&data.input_data) {
             DataSource::File(file_data) => {
                 self.load_file_data(file_data, &shapes, scales, input_types)
             }
@@ -943,7 +2992,9 @@ impl GraphCircuit {
         let input_types = self.model().graph.get_input_types()?;
         debug!("input scales: {:?}", scales);
 
-        self.process_data_source(&data.input_data, shapes, scales, input_types)
+        let field_inputs = data.field_inputs(&scales)?;
+        let input_data = field_inputs.as_ref().unwrap_or(&data.input_data);
+        self.process_data_source(input_data, shapes, scales, input_types)
             .await
     }
 
@@ -960,7 +3011,9 @@ impl GraphCircuit {
             DataSource::File(file_data) => {
                 self.load_file_data(file_data, &shapes, scales, input_types)
             }
-            DataSource::OnChain(_) => Err(GraphError::OnChainDataSource),
+            DataSource::OnChain(_) | DataSource::StorageProof(_) => {
+                Err(GraphError::OnChainDataSource)
+            }
         }
     }
 
@@ -983,6 +3036,15 @@ impl GraphCircuit {
                 self.load_on_chain_data(source.clone(), &shapes, per_item_scale)
                     .await
             }
+            DataSource::StorageProof(source) => {
+                let mut per_item_scale = vec![];
+                for (i, shape) in shapes.iter().enumerate() {
+                    per_item_scale.extend(vec![scales[i]; shape.iter().product::<usize>()]);
+                }
+
+                self.load_storage_proof_data(source, &shapes, per_item_scale)
+                    .await
+            }
             DataSource::File(file_data) => {
                 self.load_file_data(file_data, &shapes, scales, input_types)
             }
@@ -1003,7 +3065,9 @@ impl GraphCircuit {
     ) -> Result<Vec<Tensor<Fp>>, GraphError> {
         use crate::eth::{evm_quantize, read_on_chain_inputs, setup_eth_backend};
         let (client, client_address) = setup_eth_backend(Some(&source.rpc), None).await?;
-        let inputs = read_on_chain_inputs(client.clone(), client_address, &source.calls).await?;
+        let inputs =
+            read_on_chain_inputs(client.clone(), client_address, &source.calls, source.block)
+                .await?;
         // quantize the supplied data using the provided scale + QuantizeData.sol
         let quantized_evm_inputs = evm_quantize(client, scales, &inputs).await?;
         // on-chain data has already been quantized at this point. Just need to reshape it and push into tensor vector
@@ -1017,6 +3081,30 @@ impl GraphCircuit {
         Ok(inputs)
     }
 
+    /// Prepare data read from contract storage at a historical block
+    #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+    pub async fn load_storage_proof_data(
+        &mut self,
+        source: &StorageProofSource,
+        shapes: &Vec<Vec<usize>>,
+        scales: Vec<crate::Scale>,
+    ) -> Result<Vec<Tensor<Fp>>, GraphError> {
+        use crate::eth::{evm_quantize, fetch_storage_proofs, setup_eth_backend};
+        let (client, _) = setup_eth_backend(Some(&source.rpc), None).await?;
+        // the values are read from the storage proofs so the prover sees exactly what gets attested
+        let proofs = fetch_storage_proofs(client.clone(), source).await?;
+        let quantized_evm_inputs =
+            evm_quantize(client, scales, &(proofs.values, proofs.decimals)).await?;
+        let mut inputs: Vec<Tensor<Fp>> = vec![];
+        for (input, shape) in [quantized_evm_inputs].iter().zip(shapes) {
+            let mut t: Tensor<Fp> = input.iter().cloned().collect();
+            t.reshape(shape)?;
+            inputs.push(t);
+        }
+
+        Ok(inputs)
+    }
+
     ///
     pub fn load_file_data(
         &mut self,
@@ -1025,20 +3113,37 @@ impl GraphCircuit {
         scales: Vec<crate::Scale>,
         input_types: Vec<InputType>,
     ) -> Result<Vec<Tensor<Fp>>, GraphError> {
+        let _span = tracing::info_span!(
+            "quantization",
+            inputs = file_data.len(),
+            elements = file_data.iter().map(|d| d.len()).sum::<usize>()
+        )
+        .entered();
         // quantize the supplied data using the provided scale.
         let mut data: Vec<Tensor<Fp>> = vec![];
-        for (((d, shape), scale), input_type) in file_data
+        for (i, (((d, shape), scale), input_type)) in file_data
             .iter()
             .zip(shapes)
             .zip(scales)
             .zip(input_types.iter())
+            .enumerate()
         {
+            let normalized;
+            let d = match &self.settings().input_normalization {
+                Some(normalization) => {
+                    normalized = normalization.apply(i, shape, d)?;
+                    &normalized
+                }
+                None => d,
+            };
+            let rounding = self.settings().run_args.rounding;
             let t: Vec<Fp> = d
                 .par_iter()
-                .map(|x| {
+                .enumerate()
+                .map(|(j, x)| {
                     let mut x = x.clone();
                     x.as_type(input_type);
-                    x.to_field(scale)
+                    x.to_field_rounded(scale, &rounding, j)
                 })
                 .collect();
 
@@ -1050,6 +3155,46 @@ impl GraphCircuit {
         Ok(data)
     }
 
+    /// Build the Merkle tree of a dataset, each row holding the data of every input, quantized as file data is
+    pub fn merkle_tree(&mut self, dataset: &[FileSource]) -> Result<MerkleTree, GraphError> {
+        let depth = self
+            .settings()
+            .run_args
+            .input_visibility
+            .merkle_depth()
+            .ok_or_else(|| {
+                GraphError::InvalidMerkle("the input visibility must be merkle".into())
+            })?;
+        if depth >= usize::BITS as usize || dataset.len() > 1 << depth {
+            return Err(GraphError::InvalidMerkle(format!(
+                "{} rows don't fit in a tree of depth {}",
+                dataset.len(),
+                depth
+            )));
+        }
+
+        let shapes = self.model().graph.input_shapes()?;
+        let scales = self.model().graph.get_input_scales();
+        let input_types = self.model().graph.get_input_types()?;
+
+        let mut leaves = Vec::with_capacity(dataset.len());
+        for (i, row) in dataset.iter().enumerate() {
+            if row.len() != shapes.len() {
+                return Err(GraphError::InvalidMerkle(format!(
+                    "row {} has {} inputs, the model has {}",
+                    i,
+                    row.len(),
+                    shapes.len()
+                )));
+            }
+            let inputs = self.load_file_data(row, &shapes, scales.clone(), input_types.clone())?;
+            let message = inputs.iter().flat_map(|x| x.to_vec()).collect();
+            leaves.push(ModuleMerkle::leaf(message)?);
+        }
+
+        Ok(ModuleMerkle::tree(leaves, depth)?)
+    }
+
     ///
     pub fn load_witness_file_data(
         &mut self,
@@ -1073,6 +3218,44 @@ impl GraphCircuit {
         )
     }
 
+    /// The ranges the tables of the reciprocals and reciprocal square roots are sized to (see
+    /// [LookupOp::tightens_table_range]): the inputs observed for each node over the calibration data, with each bound
+    /// scaled away from zero by `margin`. A zero margin sizes the tables to the lookup range.
+    pub fn calc_tight_lookup_ranges<'a>(
+        observed: impl IntoIterator<Item = &'a NodeLookupRange>,
+        margin: f32,
+    ) -> Vec<NodeLookupRange> {
+        if margin == 0.0 {
+            return vec![];
+        }
+        let mut ranges = std::collections::BTreeMap::<(usize, LookupOp), NodeLookupRange>::new();
+        for node in observed {
+            ranges
+                .entry((node.node, node.op.clone()))
+                .and_modify(|r| {
+                    r.range = (r.range.0.min(node.range.0), r.range.1.max(node.range.1))
+                })
+                .or_insert_with(|| node.clone());
+        }
+
+        let margin = margin as f64;
+        let lower = |bound: IntegerRep| match bound >= 0 {
+            true => (bound as f64 / margin).floor() as IntegerRep,
+            false => (bound as f64 * margin).floor() as IntegerRep,
+        };
+        let upper = |bound: IntegerRep| match bound >= 0 {
+            true => (bound as f64 * margin).ceil() as IntegerRep,
+            false => (bound as f64 / margin).ceil() as IntegerRep,
+        };
+        ranges
+            .into_values()
+            .map(|mut node| {
+                node.range = (lower(node.range.0), upper(node.range.1));
+                node
+            })
+            .collect()
+    }
+
     fn calc_num_cols(range_len: IntegerRep, max_logrows: u32) -> usize {
         let max_col_size = Table::<Fp>::cal_col_size(max_logrows as usize, RESERVED_BLINDING_ROWS);
         num_cols_required(range_len, max_col_size)
@@ -1261,6 +3444,8 @@ impl GraphCircuit {
         let original_inputs = inputs.to_vec();
 
         let visibility = VarVisibility::from_args(&self.settings().run_args)?;
+        let poseidon_params = self.settings().run_args.poseidon_params;
+        let blinding = self.settings().polycommit_blinding;
         let mut processed_inputs = None;
         let mut processed_params = None;
         let mut processed_outputs = None;
@@ -1272,8 +3457,14 @@ impl GraphCircuit {
                 for outlet in &module_outlets {
                     module_inputs.push(inputs[*outlet].clone());
                 }
-                let res =
-                    GraphModules::forward::<Scheme>(&module_inputs, &visibility.input, vk, srs)?;
+                let res = GraphModules::forward::<Scheme>(
+                    &module_inputs,
+                    &visibility.input,
+                    poseidon_params,
+                    blinding,
+                    vk,
+                    srs,
+                )?;
                 processed_inputs = Some(res.clone());
                 let module_results = res.get_result(visibility.input.clone());
 
@@ -1284,6 +3475,8 @@ impl GraphCircuit {
                 processed_inputs = Some(GraphModules::forward::<Scheme>(
                     inputs,
                     &visibility.input,
+                    poseidon_params,
+                    blinding,
                     vk,
                     srs,
                 )?);
@@ -1293,10 +3486,13 @@ impl GraphCircuit {
         if visibility.params.requires_processing() {
             let params = self.model().get_all_params();
             if !params.is_empty() {
-                let flattened_params = Tensor::new(Some(&params), &[params.len()])?.combine()?;
+                let grouped_params =
+                    param_groups::grouped_param_tensors(&params, &self.param_group_indices()?)?;
                 processed_params = Some(GraphModules::forward::<Scheme>(
-                    &[flattened_params],
+                    &grouped_params,
                     &visibility.params,
+                    poseidon_params,
+                    blinding,
                     vk,
                     srs,
                 )?);
@@ -1307,32 +3503,61 @@ impl GraphCircuit {
             self.model()
                 .forward(inputs, &self.settings().run_args, region_settings)?;
 
-        if visibility.output.requires_processing() {
-            let module_outlets = visibility.output.overwrites_inputs();
-            if !module_outlets.is_empty() {
-                let mut module_inputs = vec![];
-                for outlet in &module_outlets {
-                    module_inputs.push(model_results.outputs[*outlet].clone());
-                }
-                let res =
-                    GraphModules::forward::<Scheme>(&module_inputs, &visibility.output, vk, srs)?;
-                processed_outputs = Some(res.clone());
-                let module_results = res.get_result(visibility.output.clone());
+        self.check_output_bounds(&model_results.outputs)?;
+
+        // with per output visibilities only the outputs of the processed visibility are processed
+        let (output_visibility, module_outlets) =
+            visibility.processed_outputs(model_results.outputs.len());
+        if output_visibility.requires_processing() {
+            let mut module_inputs = vec![];
+            for outlet in &module_outlets {
+                module_inputs.push(model_results.outputs[*outlet].clone());
+            }
+            let res = GraphModules::forward::<Scheme>(
+                &module_inputs,
+                &output_visibility,
+                poseidon_params,
+                blinding,
+                vk,
+                srs,
+            )?;
+            processed_outputs = Some(res.clone());
 
+            if !output_visibility.overwrites_inputs().is_empty() {
+                let module_results = res.get_result(output_visibility.clone());
                 for (i, outlet) in module_outlets.iter().enumerate() {
                     model_results.outputs[*outlet] =
                         Tensor::from(module_results[i].clone().into_iter());
                 }
-            } else {
-                processed_outputs = Some(GraphModules::forward::<Scheme>(
-                    &model_results.outputs,
-                    &visibility.output,
-                    vk,
-                    srs,
-                )?);
             }
         }
 
+        let input_signature = match &self.settings().input_signature {
+            Some(signature) => Some(InputSignatureWitness {
+                digest: GraphModules::signature_digest(&original_inputs, signature)?,
+                signature: None,
+            }),
+            None => None,
+        };
+
+        let nullifier = match self.settings().nullifier {
+            true => Some(NullifierWitness {
+                commitment: GraphModules::nullifier_commitment(&original_inputs)?,
+                salt: None,
+                nullifier: None,
+            }),
+            false => None,
+        };
+
+        let session = match self.settings().session {
+            true => Some(SessionWitness {
+                commitment: GraphModules::session_commitment(&model_results.outputs)?,
+                prev_digest: None,
+                next_digest: None,
+            }),
+            false => None,
+        };
+
         let mut witness = GraphWitness {
             inputs: original_inputs
                 .iter()
@@ -1344,12 +3569,21 @@ impl GraphCircuit {
                 .iter()
                 .map(|t| t.deref().to_vec())
                 .collect_vec(),
+            output_scales: self.model().graph.get_output_scales()?,
             processed_inputs,
             processed_params,
             processed_outputs,
             max_lookup_inputs: model_results.max_lookup_inputs,
             min_lookup_inputs: model_results.min_lookup_inputs,
             max_range_size: model_results.max_range_size,
+            tight_lookup_ranges: model_results.tight_lookup_ranges,
+            pinned_block: None,
+            input_signature,
+            nullifier,
+            output_encryption: None,
+            session,
+            redaction: None,
+            sidecar: None,
         };
 
         witness.generate_rescaled_elements(
@@ -1367,6 +3601,41 @@ impl GraphCircuit {
         Ok(witness)
     }
 
+    /// Fails if an output violates the bounds asserted on it in the circuit, before a witness that
+    /// can't be proven is generated
+    fn check_output_bounds(&self, outputs: &[Tensor<Fp>]) -> Result<(), GraphError> {
+        let output_scales = self.model().graph.get_output_scales()?;
+        for bound in &self.settings().run_args.output_bounds {
+            let scale = output_scales[bound.output];
+            let (lower, upper) = bound.quantized(scale);
+            for value in outputs[bound.output].iter() {
+                let int_value = felt_to_integer_rep(*value);
+                let rescaled = int_value as f64 / scale_to_multiplier(scale);
+                if lower.is_some_and(|l| int_value < l) {
+                    return Err(GraphError::OutputOutOfBounds(
+                        bound.output,
+                        format!(
+                            "{} is below the lower bound {}",
+                            rescaled,
+                            bound.lower.unwrap()
+                        ),
+                    ));
+                }
+                if upper.is_some_and(|u| int_value > u) {
+                    return Err(GraphError::OutputOutOfBounds(
+                        bound.output,
+                        format!(
+                            "{} is above the upper bound {}",
+                            rescaled,
+                            bound.upper.unwrap()
+                        ),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// Create a new circuit from a set of input data and [RunArgs].
     #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
     pub fn from_run_args(
@@ -1406,10 +3675,7 @@ impl GraphCircuit {
         let input_shapes = self.model().graph.input_shapes()?;
         let output_shapes = self.model().graph.output_shapes()?;
 
-        if matches!(
-            test_on_chain_data.data_sources.input,
-            TestDataSource::OnChain
-        ) {
+        if !matches!(test_on_chain_data.data_sources.input, TestDataSource::File) {
             // if not public then fail
             if self.settings().run_args.input_visibility.is_private() {
                 return Err(GraphError::OnChainDataSource);
@@ -1424,37 +3690,77 @@ impl GraphCircuit {
             // Get the flatten length of input_data
             // if the input source is a field then set scale to 0
 
-            let datam: (Vec<Tensor<Fp>>, OnChainSource) = OnChainSource::test_from_file_data(
-                input_data,
-                input_scales,
-                input_shapes,
-                test_on_chain_data.rpc.as_deref(),
-            )
-            .await?;
-            data.input_data = datam.1.into();
+            data.input_data = match test_on_chain_data.data_sources.input {
+                TestDataSource::StorageProof => StorageProofSource::test_from_file_data(
+                    input_data,
+                    input_scales,
+                    input_shapes,
+                    test_on_chain_data.rpc.as_deref(),
+                )
+                .await?
+                .1
+                .into(),
+                _ => OnChainSource::test_from_file_data(
+                    input_data,
+                    input_scales,
+                    input_shapes,
+                    test_on_chain_data.rpc.as_deref(),
+                    matches!(
+                        test_on_chain_data.data_sources.input,
+                        TestDataSource::NestedOnChain
+                    ),
+                )
+                .await?
+                .1
+                .into(),
+            };
         }
-        if matches!(
-            test_on_chain_data.data_sources.output,
-            TestDataSource::OnChain
-        ) {
+        if !matches!(test_on_chain_data.data_sources.output, TestDataSource::File) {
             // if not public then fail
-            if self.settings().run_args.output_visibility.is_private() {
+            if !self.settings().run_args.output_visibilities.is_empty()
+                || self.settings().run_args.output_visibility.is_private()
+            {
                 return Err(GraphError::OnChainDataSource);
             }
 
             let output_data = match &data.output_data {
                 Some(DataSource::File(output_data)) => output_data,
-                Some(DataSource::OnChain(_)) => return Err(GraphError::OnChainDataSource),
+                Some(DataSource::OnChain(_)) | Some(DataSource::StorageProof(_)) => {
+                    return Err(GraphError::OnChainDataSource)
+                }
                 _ => return Err(GraphError::MissingDataSource),
             };
-            let datum: (Vec<Tensor<Fp>>, OnChainSource) = OnChainSource::test_from_file_data(
-                output_data,
-                output_scales,
-                output_shapes,
-                test_on_chain_data.rpc.as_deref(),
-            )
-            .await?;
-            data.output_data = Some(datum.1.into());
+            data.output_data = Some(match test_on_chain_data.data_sources.output {
+                TestDataSource::StorageProof => StorageProofSource::test_from_file_data(
+                    output_data,
+                    output_scales,
+                    output_shapes,
+                    test_on_chain_data.rpc.as_deref(),
+                )
+                .await?
+                .1
+                .into(),
+                _ => OnChainSource::test_from_file_data(
+                    output_data,
+                    output_scales,
+                    output_shapes,
+                    test_on_chain_data.rpc.as_deref(),
+                    matches!(
+                        test_on_chain_data.data_sources.output,
+                        TestDataSource::NestedOnChain
+                    ),
+                )
+                .await?
+                .1
+                .into(),
+            });
+        }
+        // the attestation contract proves against a single block, the input storage is unchanged
+        // since it was written so it can be proven at the later block the output was written at
+        if let (DataSource::StorageProof(input), Some(DataSource::StorageProof(output))) =
+            (&mut data.input_data, &data.output_data)
+        {
+            input.block = output.block;
         }
         // Save the updated GraphData struct to the data_path
         data.save(test_on_chain_data.data)?;
@@ -1516,6 +3822,7 @@ impl CircuitSize {
     }
 }
 
+#[cfg(not(all(feature = "verifier-only", not(feature = "ezkl"))))]
 impl Circuit<Fp> for GraphCircuit {
     type Config = GraphConfig;
     type FloorPlanner = ModulePlanner;
@@ -1531,64 +3838,7 @@ impl Circuit<Fp> for GraphCircuit {
     }
 
     fn configure_with_params(cs: &mut ConstraintSystem<Fp>, params: Self::Params) -> Self::Config {
-        let mut params = params.clone();
-        params.set_num_blinding_factors(cs.blinding_factors());
-        GLOBAL_SETTINGS.with(|settings| {
-            *settings.borrow_mut() = Some(params.clone());
-        });
-        let visibility = match VarVisibility::from_args(&params.run_args) {
-            Ok(v) => v,
-            Err(e) => {
-                log::error!("failed to create visibility: {:?}", e);
-                log::warn!("using default visibility");
-                VarVisibility::default()
-            }
-        };
-
-        let mut module_configs = ModuleConfigs::from_visibility(
-            cs,
-            params.module_sizes.clone(),
-            params.run_args.logrows as usize,
-        );
-
-        let mut vars = ModelVars::new(cs, &params);
-
-        module_configs.configure_complex_modules(cs, visibility, params.module_sizes.clone());
-
-        vars.instantiate_instance(
-            cs,
-            params.model_instance_shapes.clone(),
-            params.run_args.input_scale,
-            module_configs.instance,
-        );
-
-        let base = Model::configure(cs, &vars, &params).unwrap();
-
-        let model_config = ModelConfig { base, vars };
-
-        debug!(
-            "degree: {}, log2_ceil of degrees: {:?}",
-            cs.degree(),
-            (cs.degree() as f32).log2().ceil()
-        );
-
-        let circuit_size = CircuitSize::from_cs(cs, params.run_args.logrows);
-
-        #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
-        debug!(
-            "circuit size: \n {}",
-            circuit_size
-                .as_json()
-                .unwrap()
-                .to_colored_json_auto()
-                .unwrap()
-        );
-
-        GraphConfig {
-            model_config,
-            module_configs,
-            circuit_size,
-        }
+        GraphConfig::configure(cs, params)
     }
 
     fn configure(_: &mut ConstraintSystem<Fp>) -> Self::Config {
@@ -1610,10 +3860,41 @@ impl Circuit<Fp> for GraphCircuit {
             return Err(PlonkError::Synthesis);
         }
 
+        let _span = tracing::info_span!(
+            "synthesis",
+            logrows = self.settings().run_args.logrows,
+            rows = self.settings().num_rows
+        )
+        .entered();
         trace!("Setting input in synthesize");
         let input_vis = &self.settings().run_args.input_visibility;
-        let output_vis = &self.settings().run_args.output_visibility;
+        let run_args = &self.settings().run_args;
         let mut graph_modules = GraphModules::new();
+        graph_modules.set_merkle_path(
+            self.graph_witness
+                .processed_inputs
+                .as_ref()
+                .and_then(|p| p.merkle_path.clone()),
+        );
+        graph_modules.set_signature(
+            self.graph_witness
+                .input_signature
+                .as_ref()
+                .and_then(|s| s.signature.clone()),
+        );
+        graph_modules.set_salt(self.graph_witness.nullifier.as_ref().and_then(|n| n.salt));
+        graph_modules.set_encryption(self.graph_witness.output_encryption.clone());
+        graph_modules.set_prev_digest(
+            self.graph_witness
+                .session
+                .as_ref()
+                .and_then(|s| s.prev_digest),
+        );
+        graph_modules.set_blinders(self.settings().polycommit_blinding.then(|| {
+            self.graph_witness
+                .get_polycommit_blinders()
+                .unwrap_or_default()
+        }));
 
         let mut constants = ConstantsMap::new();
 
@@ -1636,8 +3917,9 @@ impl Circuit<Fp> for GraphCircuit {
             .graph_witness
             .get_output_tensor()
             .iter_mut()
-            .map(|i| {
-                i.set_visibility(output_vis);
+            .enumerate()
+            .map(|(idx, i)| {
+                i.set_visibility(run_args.visibility_of_output(idx));
                 ValTensor::try_from(i.clone()).map_err(|e| {
                     log::error!("failed to convert output to valtensor: {:?}", e);
                     PlonkError::Synthesis
@@ -1679,31 +3961,55 @@ impl Circuit<Fp> for GraphCircuit {
             )?;
         }
 
+        if let Some(signature) = &self.settings().input_signature {
+            trace!("running input signature layout");
+            graph_modules.layout_signature(
+                &mut layouter,
+                &config.module_configs,
+                &mut inputs,
+                signature,
+                &mut instance_offset,
+                &mut constants,
+            )?;
+        }
+
+        if let Some(index) = self.settings().nullifier_instance_index() {
+            trace!("running nullifier layout");
+            graph_modules.layout_nullifier(
+                &mut layouter,
+                &config.module_configs,
+                &mut inputs,
+                index,
+                &mut constants,
+            )?;
+        }
+
         // now we need to assign the flattened params to the model
         let mut model = self.model().clone();
         let param_visibility = &self.settings().run_args.param_visibility;
         trace!("running params module layout");
         if !self.model().get_all_params().is_empty() && param_visibility.requires_processing() {
-            // now we need to flatten the params
+            // now we need to flatten the params, by commitment group
             let consts = self.model().get_all_params();
+            let param_groups = self.param_group_indices().map_err(|e| {
+                log::error!("failed to group params: {}", e);
+                PlonkError::Synthesis
+            })?;
 
-            let mut flattened_params = {
-                let mut t = Tensor::new(Some(&consts), &[consts.len()])
-                    .map_err(|_| {
-                        log::error!("failed to flatten params");
-                        PlonkError::Synthesis
-                    })?
-                    .combine()
-                    .map_err(|_| {
-                        log::error!("failed to combine params");
-                        PlonkError::Synthesis
-                    })?;
-                t.set_visibility(param_visibility);
-                vec![t.try_into().map_err(|_| {
-                    log::error!("failed to convert params to valtensor");
+            let mut flattened_params = param_groups::grouped_param_tensors(&consts, &param_groups)
+                .map_err(|_| {
+                    log::error!("failed to flatten params");
                     PlonkError::Synthesis
-                })?]
-            };
+                })?
+                .into_iter()
+                .map(|mut t| {
+                    t.set_visibility(param_visibility);
+                    t.try_into().map_err(|_| {
+                        log::error!("failed to convert params to valtensor");
+                        PlonkError::Synthesis
+                    })
+                })
+                .collect::<Result<Vec<ValTensor<Fp>>, _>>()?;
 
             // now do stuff to the model params
             graph_modules.layout(
@@ -1717,7 +4023,13 @@ impl Circuit<Fp> for GraphCircuit {
 
             let shapes = self.model().const_shapes();
             trace!("replacing processed consts");
-            let split_params = split_valtensor(&flattened_params[0], shapes).map_err(|_| {
+            let split_params = param_groups::ungroup_params(
+                &flattened_params,
+                &param_groups,
+                &shapes,
+                split_valtensor,
+            )
+            .map_err(|_| {
                 log::error!("failed to split params");
                 PlonkError::Synthesis
             })?;
@@ -1749,41 +4061,719 @@ impl Circuit<Fp> for GraphCircuit {
             })?;
         trace!("running output module layout");
 
-        let output_visibility = &self.settings().run_args.output_visibility;
-        let outlets = output_visibility.overwrites_inputs();
+        let visibility = VarVisibility::from_args(run_args).map_err(|e| {
+            log::error!("failed to create visibility: {:?}", e);
+            PlonkError::Synthesis
+        })?;
+        // with per output visibilities only the outputs of the processed visibility are processed
+        let (output_visibility, outlets) = visibility.processed_outputs(outputs.len());
 
         instance_offset += vars.get_instance_len();
 
-        if !outlets.is_empty() {
-            let mut output_outlets = vec![];
-            for outlet in &outlets {
-                output_outlets.push(outputs[*outlet].clone());
-            }
-            // this will re-enter module 0
-            graph_modules.layout(
+        let mut output_outlets = vec![];
+        for outlet in &outlets {
+            output_outlets.push(outputs[*outlet].clone());
+        }
+        // this will re-enter module 0
+        graph_modules.layout(
+            &mut layouter,
+            &mut config.module_configs,
+            &mut output_outlets,
+            &output_visibility,
+            &mut instance_offset,
+            &mut constants,
+        )?;
+
+        // replace outputs with the outlets
+        for (i, outlet) in outlets.iter().enumerate() {
+            outputs[*outlet] = output_outlets[i].clone();
+        }
+
+        if let Some(encryption) = &self.settings().output_encryption {
+            trace!("running output encryption layout");
+            graph_modules.layout_encryption(
                 &mut layouter,
-                &mut config.module_configs,
-                &mut output_outlets,
-                &self.settings().run_args.output_visibility,
+                &config.module_configs,
+                &outputs,
+                encryption,
                 &mut instance_offset,
                 &mut constants,
             )?;
+        }
 
-            // replace outputs with the outlets
-            for (i, outlet) in outlets.iter().enumerate() {
-                outputs[*outlet] = output_outlets[i].clone();
-            }
-        } else {
-            graph_modules.layout(
+        if let Some(range) = self.settings().session_instance_range() {
+            trace!("running session layout");
+            graph_modules.layout_session(
                 &mut layouter,
-                &mut config.module_configs,
-                &mut outputs,
-                &self.settings().run_args.output_visibility,
-                &mut instance_offset,
+                &config.module_configs,
+                &outputs,
+                range.start,
                 &mut constants,
             )?;
         }
 
+        // halo2 goes on to commit to the witness once the circuit is synthesized
+        let observed = crate::pfsys::progress::report(ProvingPhase::Synthesis, 1.0)
+            .and_then(|_| crate::pfsys::progress::report(ProvingPhase::Commit, 0.0));
+        if observed.is_err() {
+            set_layout_error(Some(GraphError::Cancelled));
+            return Err(PlonkError::Synthesis);
+        }
+
         Ok(())
     }
 }
+
+#[cfg(test)]
+#[cfg(not(all(feature = "verifier-only", not(feature = "ezkl"))))]
+mod tests {
+    use super::*;
+
+    fn witness() -> GraphWitness {
+        let mut witness = GraphWitness::new(vec![vec![Fp::from(1)]], vec![vec![Fp::from(3)]]);
+        witness.pretty_elements = Some(PrettyElements {
+            rescaled_inputs: vec![vec!["0.25".to_string()]],
+            inputs: vec![vec!["0x01".to_string()]],
+            processed_inputs: vec![vec!["0x02".to_string()]],
+            rescaled_outputs: vec![vec!["0.3333333333333333".to_string()]],
+            outputs: vec![vec!["0x03".to_string()]],
+            ..Default::default()
+        });
+        witness.processed_inputs = Some(ModuleForwardResult {
+            poseidon_hash: Some(vec![Fp::from(2)]),
+            polycommit: None,
+            sha256_hash: None,
+            merkle_leaf: None,
+            merkle_path: None,
+            polycommit_blinders: None,
+        });
+        witness.max_lookup_inputs = 3;
+        witness.min_lookup_inputs = -1;
+        witness
+    }
+
+    #[test]
+    fn test_witness_format_default() {
+        // the default format writes the witness as it serializes, so it can still be proven
+        let witness = witness();
+        let json = witness
+            .as_json_formatted(&WitnessFormat::default())
+            .unwrap();
+        assert_eq!(json, serde_json::to_string(&witness).unwrap());
+        assert_eq!(
+            serde_json::from_str::<GraphWitness>(&json).unwrap(),
+            witness
+        );
+    }
+
+    #[test]
+    fn test_witness_format_snapshots() {
+        let felt = |x: u64| crate::pfsys::field_to_string(&Fp::from(x));
+        let snapshots = [
+            (
+                None,
+                true,
+                true,
+                true,
+                r#"{"inputs":[["{one}"]],"pretty_elements":{"rescaled_inputs":[["0.25"]],"inputs":[["0x01"]],"processed_inputs":[["0x02"]],"processed_params":[],"processed_outputs":[],"rescaled_outputs":[["0.3333333333333333"]],"outputs":[["0x03"]]},"outputs":[["{three}"]],"processed_inputs":{"poseidon_hash":["{two}"],"polycommit":null,"sha256_hash":null,"merkle_leaf":null,"merkle_path":null,"polycommit_blinders":null},"processed_params":null,"processed_outputs":null,"max_lookup_inputs":3,"min_lookup_inputs":-1,"max_range_size":0,"pinned_block":null,"input_signature":null,"nullifier":null,"output_encryption":null}"#,
+            ),
+            (
+                None,
+                true,
+                true,
+                false,
+                r#"{"inputs":[["{one}"]],"pretty_elements":{"rescaled_inputs":[["0.25"]],"inputs":[["0x01"]],"rescaled_outputs":[["0.3333333333333333"]],"outputs":[["0x03"]]},"outputs":[["{three}"]],"max_lookup_inputs":3,"min_lookup_inputs":-1,"max_range_size":0,"pinned_block":null,"input_signature":null,"nullifier":null,"output_encryption":null}"#,
+            ),
+            (
+                None,
+                true,
+                false,
+                true,
+                r#"{"inputs":[["{one}"]],"pretty_elements":{"inputs":[["0x01"]],"processed_inputs":[["0x02"]],"processed_params":[],"processed_outputs":[],"outputs":[["0x03"]]},"outputs":[["{three}"]],"processed_inputs":{"poseidon_hash":["{two}"],"polycommit":null,"sha256_hash":null,"merkle_leaf":null,"merkle_path":null,"polycommit_blinders":null},"processed_params":null,"processed_outputs":null,"max_lookup_inputs":3,"min_lookup_inputs":-1,"max_range_size":0,"pinned_block":null,"input_signature":null,"nullifier":null,"output_encryption":null}"#,
+            ),
+            (
+                None,
+                true,
+                false,
+                false,
+                r#"{"inputs":[["{one}"]],"pretty_elements":{"inputs":[["0x01"]],"outputs":[["0x03"]]},"outputs":[["{three}"]],"max_lookup_inputs":3,"min_lookup_inputs":-1,"max_range_size":0,"pinned_block":null,"input_signature":null,"nullifier":null,"output_encryption":null}"#,
+            ),
+            (
+                None,
+                false,
+                true,
+                true,
+                r#"{"pretty_elements":{"rescaled_inputs":[["0.25"]],"processed_inputs":[["0x02"]],"processed_params":[],"processed_outputs":[],"rescaled_outputs":[["0.3333333333333333"]]},"processed_inputs":{"poseidon_hash":["{two}"],"polycommit":null,"sha256_hash":null,"merkle_leaf":null,"merkle_path":null,"polycommit_blinders":null},"processed_params":null,"processed_outputs":null,"max_lookup_inputs":3,"min_lookup_inputs":-1,"max_range_size":0,"pinned_block":null,"input_signature":null,"nullifier":null,"output_encryption":null}"#,
+            ),
+            (
+                None,
+                false,
+                true,
+                false,
+                r#"{"pretty_elements":{"rescaled_inputs":[["0.25"]],"rescaled_outputs":[["0.3333333333333333"]]},"max_lookup_inputs":3,"min_lookup_inputs":-1,"max_range_size":0,"pinned_block":null,"input_signature":null,"nullifier":null,"output_encryption":null}"#,
+            ),
+            (
+                None,
+                false,
+                false,
+                true,
+                r#"{"pretty_elements":{"processed_inputs":[["0x02"]],"processed_params":[],"processed_outputs":[]},"processed_inputs":{"poseidon_hash":["{two}"],"polycommit":null,"sha256_hash":null,"merkle_leaf":null,"merkle_path":null,"polycommit_blinders":null},"processed_params":null,"processed_outputs":null,"max_lookup_inputs":3,"min_lookup_inputs":-1,"max_range_size":0,"pinned_block":null,"input_signature":null,"nullifier":null,"output_encryption":null}"#,
+            ),
+            (
+                None,
+                false,
+                false,
+                false,
+                r#"{"pretty_elements":{},"max_lookup_inputs":3,"min_lookup_inputs":-1,"max_range_size":0,"pinned_block":null,"input_signature":null,"nullifier":null,"output_encryption":null}"#,
+            ),
+            (
+                Some(2),
+                true,
+                true,
+                true,
+                r#"{"inputs":[["{one}"]],"pretty_elements":{"rescaled_inputs":[["0.25"]],"inputs":[["0x01"]],"processed_inputs":[["0x02"]],"processed_params":[],"processed_outputs":[],"rescaled_outputs":[["0.33"]],"outputs":[["0x03"]]},"outputs":[["{three}"]],"processed_inputs":{"poseidon_hash":["{two}"],"polycommit":null,"sha256_hash":null,"merkle_leaf":null,"merkle_path":null,"polycommit_blinders":null},"processed_params":null,"processed_outputs":null,"max_lookup_inputs":3,"min_lookup_inputs":-1,"max_range_size":0,"pinned_block":null,"input_signature":null,"nullifier":null,"output_encryption":null}"#,
+            ),
+            (
+                Some(2),
+                true,
+                true,
+                false,
+                r#"{"inputs":[["{one}"]],"pretty_elements":{"rescaled_inputs":[["0.25"]],"inputs":[["0x01"]],"rescaled_outputs":[["0.33"]],"outputs":[["0x03"]]},"outputs":[["{three}"]],"max_lookup_inputs":3,"min_lookup_inputs":-1,"max_range_size":0,"pinned_block":null,"input_signature":null,"nullifier":null,"output_encryption":null}"#,
+            ),
+            (
+                Some(2),
+                true,
+                false,
+                true,
+                r#"{"inputs":[["{one}"]],"pretty_elements":{"inputs":[["0x01"]],"processed_inputs":[["0x02"]],"processed_params":[],"processed_outputs":[],"outputs":[["0x03"]]},"outputs":[["{three}"]],"processed_inputs":{"poseidon_hash":["{two}"],"polycommit":null,"sha256_hash":null,"merkle_leaf":null,"merkle_path":null,"polycommit_blinders":null},"processed_params":null,"processed_outputs":null,"max_lookup_inputs":3,"min_lookup_inputs":-1,"max_range_size":0,"pinned_block":null,"input_signature":null,"nullifier":null,"output_encryption":null}"#,
+            ),
+            (
+                Some(2),
+                true,
+                false,
+                false,
+                r#"{"inputs":[["{one}"]],"pretty_elements":{"inputs":[["0x01"]],"outputs":[["0x03"]]},"outputs":[["{three}"]],"max_lookup_inputs":3,"min_lookup_inputs":-1,"max_range_size":0,"pinned_block":null,"input_signature":null,"nullifier":null,"output_encryption":null}"#,
+            ),
+            (
+                Some(2),
+                false,
+                true,
+                true,
+                r#"{"pretty_elements":{"rescaled_inputs":[["0.25"]],"processed_inputs":[["0x02"]],"processed_params":[],"processed_outputs":[],"rescaled_outputs":[["0.33"]]},"processed_inputs":{"poseidon_hash":["{two}"],"polycommit":null,"sha256_hash":null,"merkle_leaf":null,"merkle_path":null,"polycommit_blinders":null},"processed_params":null,"processed_outputs":null,"max_lookup_inputs":3,"min_lookup_inputs":-1,"max_range_size":0,"pinned_block":null,"input_signature":null,"nullifier":null,"output_encryption":null}"#,
+            ),
+            (
+                Some(2),
+                false,
+                true,
+                false,
+                r#"{"pretty_elements":{"rescaled_inputs":[["0.25"]],"rescaled_outputs":[["0.33"]]},"max_lookup_inputs":3,"min_lookup_inputs":-1,"max_range_size":0,"pinned_block":null,"input_signature":null,"nullifier":null,"output_encryption":null}"#,
+            ),
+            (
+                Some(2),
+                false,
+                false,
+                true,
+                r#"{"pretty_elements":{"processed_inputs":[["0x02"]],"processed_params":[],"processed_outputs":[]},"processed_inputs":{"poseidon_hash":["{two}"],"polycommit":null,"sha256_hash":null,"merkle_leaf":null,"merkle_path":null,"polycommit_blinders":null},"processed_params":null,"processed_outputs":null,"max_lookup_inputs":3,"min_lookup_inputs":-1,"max_range_size":0,"pinned_block":null,"input_signature":null,"nullifier":null,"output_encryption":null}"#,
+            ),
+            (
+                Some(2),
+                false,
+                false,
+                false,
+                r#"{"pretty_elements":{},"max_lookup_inputs":3,"min_lookup_inputs":-1,"max_range_size":0,"pinned_block":null,"input_signature":null,"nullifier":null,"output_encryption":null}"#,
+            ),
+        ];
+
+        let witness = witness();
+        for (precision, felts, rescaled, processed, snapshot) in snapshots {
+            let format = WitnessFormat {
+                precision,
+                felts,
+                rescaled,
+                processed,
+                sidecar_threshold: None,
+            };
+            let expected = snapshot
+                .replace("{one}", &felt(1))
+                .replace("{two}", &felt(2))
+                .replace("{three}", &felt(3));
+            assert_eq!(
+                witness.as_json_formatted(&format).unwrap(),
+                expected,
+                "{:?}",
+                format
+            );
+        }
+    }
+
+    #[test]
+    fn test_witness_consistency() {
+        use crate::circuit::modules::Module;
+        use crate::graph::modules::ModulePoseidon;
+
+        let settings = GraphSettings {
+            run_args: RunArgs {
+                input_visibility: Visibility::Hashed {
+                    hash_is_public: true,
+                    outlets: vec![],
+                },
+                lookup_range: (-4, 4),
+                ..Default::default()
+            },
+            model_input_scales: vec![2],
+            model_output_scales: vec![2],
+            ..Default::default()
+        };
+        let mut witness = witness();
+        witness.processed_inputs = Some(ModuleForwardResult {
+            poseidon_hash: Some(ModulePoseidon::run(vec![Fp::from(1)]).unwrap()[0].clone()),
+            ..Default::default()
+        });
+        witness.check_consistency(&settings).unwrap();
+
+        let mut tampered = witness.clone();
+        tampered.inputs[0][0] = Fp::from(2);
+        let mut unprocessed = witness.clone();
+        unprocessed.processed_inputs = None;
+        let mut missing_output = witness.clone();
+        missing_output.outputs.clear();
+        let mut out_of_range = witness.clone();
+        out_of_range.max_lookup_inputs = 5;
+        for witness in [tampered, unprocessed, missing_output, out_of_range] {
+            assert!(matches!(
+                witness.check_consistency(&settings),
+                Err(GraphError::InconsistentWitness(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_witness_redaction() {
+        let felt = |x: u64| crate::pfsys::field_to_string(&Fp::from(x));
+        // the inputs are private and the outputs public by default
+        let settings = GraphSettings {
+            model_input_scales: vec![2],
+            model_output_scales: vec![2],
+            ..Default::default()
+        };
+        let mut witness = witness();
+        witness.nullifier = Some(NullifierWitness {
+            commitment: Fp::from(5),
+            salt: Some(Fp::from(7)),
+            nullifier: Some(Fp::from(11)),
+        });
+        let redacted = witness.redact(&settings).unwrap();
+
+        assert_eq!(redacted.inputs, vec![Vec::<Fp>::new()]);
+        assert_eq!(redacted.outputs, witness.outputs);
+        let pretty = redacted.pretty_elements.as_ref().unwrap();
+        assert!(pretty.inputs[0].is_empty() && pretty.rescaled_inputs[0].is_empty());
+        assert_eq!(
+            pretty.outputs,
+            witness.pretty_elements.as_ref().unwrap().outputs
+        );
+        assert_eq!(redacted.processed_inputs, witness.processed_inputs);
+        let nullifier = redacted.nullifier.as_ref().unwrap();
+        assert_eq!(nullifier.salt, None);
+        assert_eq!(nullifier.nullifier, Some(Fp::from(11)));
+        assert_eq!(
+            redacted.redaction.as_ref().unwrap().redacted,
+            vec![
+                "inputs[0]",
+                "pretty_elements.rescaled_inputs[0]",
+                "pretty_elements.inputs[0]",
+                "nullifier.salt"
+            ]
+        );
+
+        // none of the private values are written, the public ones are
+        let json = serde_json::to_string(&redacted).unwrap();
+        for private in [felt(1), felt(7), "0x01".to_string(), "0.25".to_string()] {
+            assert!(!json.contains(&private), "{} in {}", private, json);
+        }
+        for public in [felt(2), felt(3), felt(11), "0.3333333333333333".to_string()] {
+            assert!(json.contains(&public), "{} not in {}", public, json);
+        }
+
+        assert_eq!(
+            serde_json::from_str::<GraphWitness>(&json).unwrap(),
+            redacted
+        );
+        redacted.check_consistency(&settings).unwrap();
+        assert!(matches!(
+            redacted.redact(&settings),
+            Err(GraphError::InvalidRedaction(_))
+        ));
+    }
+
+    #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+    #[test]
+    fn test_witness_redaction_encrypted() {
+        use age::secrecy::ExposeSecret;
+
+        let identity = age::x25519::Identity::generate();
+        let witness = witness();
+        let redacted = witness
+            .redact_to(&GraphSettings::default(), &identity.to_public().to_string())
+            .unwrap();
+        let redaction = redacted.redaction.clone().unwrap();
+        assert!(redaction
+            .encrypted_witness
+            .unwrap()
+            .starts_with("-----BEGIN AGE ENCRYPTED FILE-----"));
+        assert!(!serde_json::to_string(&redacted).unwrap().contains("0.25"));
+
+        // only the identity of the recipient recovers the full witness
+        let secret = identity.to_string();
+        assert_eq!(redacted.unredact(secret.expose_secret()).unwrap(), witness);
+        let other = age::x25519::Identity::generate().to_string();
+        assert!(matches!(
+            redacted.unredact(other.expose_secret()),
+            Err(GraphError::InvalidRedaction(_))
+        ));
+        assert!(matches!(
+            witness.unredact(secret.expose_secret()),
+            Err(GraphError::InvalidRedaction(_))
+        ));
+    }
+
+    #[test]
+    fn test_witness_sidecar() {
+        let tmp_dir = tempfile::Builder::new()
+            .prefix("sidecar")
+            .tempdir()
+            .unwrap();
+        let hybrid = tmp_dir.path().join("hybrid.json");
+        let pure = tmp_dir.path().join("pure.json");
+
+        // a large input whose pretty felts are those of its field elements, beside the small ones
+        let mut witness = witness();
+        witness.inputs.push((0..8).map(Fp::from).collect());
+        if let Some(pretty) = &mut witness.pretty_elements {
+            pretty.inputs.push(
+                witness.inputs[1]
+                    .iter()
+                    .map(|x| format!("{:?}", x))
+                    .collect(),
+            );
+            pretty.rescaled_inputs.push(vec!["0".to_string(); 8]);
+        }
+        let format = WitnessFormat {
+            sidecar_threshold: Some(4),
+            ..Default::default()
+        };
+        witness.save_formatted(hybrid.clone(), &format).unwrap();
+
+        let json: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(&hybrid).unwrap()).unwrap();
+        assert_eq!(json["inputs"][0].as_array().unwrap().len(), 1);
+        assert_eq!(json["inputs"][1], serde_json::json!([]));
+        assert_eq!(json["pretty_elements"]["inputs"][1], serde_json::json!([]));
+        assert_eq!(json["sidecar"]["path"], "hybrid.json.bin");
+        let bin = sidecar::sidecar_path(&hybrid);
+        assert_eq!(
+            std::fs::metadata(&bin).unwrap().len(),
+            8 * sidecar::FELT_BYTES as u64
+        );
+        assert_eq!(GraphWitness::from_path(hybrid.clone()).unwrap(), witness);
+
+        // converted back to pure json it is the json of the witness, which still loads without a sidecar
+        GraphWitness::from_path(hybrid.clone())
+            .unwrap()
+            .save_formatted(pure.clone(), &WitnessFormat::default())
+            .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&pure).unwrap(),
+            serde_json::to_string(&witness).unwrap()
+        );
+        assert_eq!(GraphWitness::from_path(pure).unwrap(), witness);
+
+        // pretty felts that aren't those of the field elements are kept in the json
+        let mut custom = witness.clone();
+        custom.pretty_elements.as_mut().unwrap().inputs[1][0] = "custom".to_string();
+        custom.save_formatted(hybrid.clone(), &format).unwrap();
+        assert_eq!(GraphWitness::from_path(hybrid.clone()).unwrap(), custom);
+
+        // a sidecar without the tensors the json indexes is an error
+        let bytes = std::fs::read(&bin).unwrap();
+        std::fs::write(&bin, &bytes[..bytes.len() - 1]).unwrap();
+        assert!(matches!(
+            GraphWitness::from_path(hybrid),
+            Err(GraphError::InvalidWitnessSidecar(_))
+        ));
+    }
+
+    #[test]
+    fn test_run_args_default_poseidon_params() {
+        // settings written before the poseidon parameters could be selected hash as they did
+        let mut run_args = serde_json::to_value(RunArgs {
+            poseidon_params: PoseidonParams::Circomlib,
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(run_args["poseidon_params"], "circomlib");
+        run_args.as_object_mut().unwrap().remove("poseidon_params");
+        let run_args: RunArgs = serde_json::from_value(run_args).unwrap();
+        assert_eq!(run_args.poseidon_params, PoseidonParams::Ezkl);
+    }
+
+    #[test]
+    fn test_packed_instances() {
+        let hashed = Visibility::Hashed {
+            hash_is_public: true,
+            outlets: vec![],
+        };
+        let run_args = RunArgs {
+            input_visibility: Visibility::Public,
+            output_visibility: Visibility::Public,
+            poseidon_params: PoseidonParams::Circomlib,
+            pack_public_threshold: Some(8),
+            ..Default::default()
+        };
+        let (input_lens, output_lens) = ([4, 16], [2, 32, 3]);
+
+        // a single large input packs every input, only the large outputs are packed
+        let packed = run_args
+            .with_packed_instances(&input_lens, &output_lens)
+            .unwrap();
+        assert_eq!(packed.input_visibility, hashed);
+        assert_eq!(
+            packed.output_visibilities,
+            vec![Visibility::Public, hashed.clone(), Visibility::Public]
+        );
+        assert_eq!(
+            packed.with_packed_instances(&input_lens, &output_lens),
+            Ok(packed.clone())
+        );
+
+        let shapes = |lens: &[usize]| lens.iter().map(|len| vec![*len]).collect_vec();
+        let mut settings = GraphSettings {
+            run_args: packed.clone(),
+            model_instance_shapes: vec![vec![2], vec![3]],
+            module_sizes: GraphModules::num_constraints_and_instances(
+                shapes(&input_lens),
+                vec![vec![0]],
+                shapes(&output_lens),
+                VarVisibility::from_args(&packed).unwrap(),
+                PoseidonParams::Circomlib,
+            ),
+            ..Default::default()
+        };
+        settings.pack_tensors(&input_lens, &output_lens).unwrap();
+        assert_eq!(settings.packed_tensors.len(), 3);
+        // the input digests come first, the output digest after the public outputs
+        assert_eq!(settings.total_instances().iter().sum::<usize>(), 8);
+        assert_eq!(settings.packed_instance_indices(), vec![0, 1, 7]);
+        settings.model_input_scales = vec![0; 2];
+        settings.model_output_scales = vec![0; 3];
+        let layout = settings
+            .instance_layout()
+            .into_iter()
+            .map(|s| (s.start, s.len, s.label))
+            .collect_vec();
+        assert_eq!(
+            layout,
+            vec![
+                (0, 1, "poseidon digest of packed input 0".to_string()),
+                (1, 1, "poseidon digest of packed input 1".to_string()),
+                (2, 2, "output 0".to_string()),
+                (4, 3, "output 2".to_string()),
+                (7, 1, "poseidon digest of packed output 1".to_string()),
+            ]
+        );
+        let digests = settings
+            .instance_layout()
+            .into_iter()
+            .filter_map(|s| s.digest_of)
+            .collect_vec();
+        assert_eq!(digests, vec![(false, 0), (false, 1), (true, 1)]);
+
+        // small tensors aren't packed
+        let small = run_args.with_packed_instances(&[4], &[2]).unwrap();
+        assert_eq!(small, run_args);
+
+        // the digests are re-derived on chain with the circomlib parameters
+        let ezkl = RunArgs {
+            poseidon_params: PoseidonParams::Ezkl,
+            ..run_args.clone()
+        };
+        assert!(ezkl.validate().is_err());
+        // the outputs are hashed or committed to with a single visibility
+        let committed = RunArgs {
+            output_visibilities: vec![
+                Visibility::Public,
+                Visibility::Public,
+                Visibility::KZGCommit,
+            ],
+            ..run_args
+        };
+        assert!(committed
+            .with_packed_instances(&input_lens, &output_lens)
+            .is_err());
+    }
+
+    #[test]
+    fn test_limb_packed_outputs() {
+        let run_args = RunArgs {
+            input_visibility: Visibility::Public,
+            output_visibilities: vec![Visibility::Public, Visibility::Private, Visibility::Public],
+            instance_limbs: Some(2),
+            ..Default::default()
+        };
+        assert!(run_args.validate().is_ok());
+        let output_lens = [3, 4, 10];
+        let mut settings = GraphSettings {
+            run_args: run_args.clone(),
+            model_instance_shapes: vec![vec![4], vec![3], vec![2, 5]],
+            model_input_scales: vec![0],
+            model_output_scales: vec![0; 3],
+            ..Default::default()
+        };
+        settings.limb_pack_outputs(&output_lens);
+
+        // the public outputs take half their instances, rounded up, the public input is left as is
+        assert_eq!(
+            settings.model_instance_shapes,
+            vec![vec![4], vec![2], vec![5]]
+        );
+        assert_eq!(settings.limb_packed_output(2).unwrap().limb_bits, 29);
+        assert!(settings.limb_packed_output(1).is_none());
+        assert_eq!(settings.limb_packed_instance_starts(), vec![4, 6]);
+        let layout = settings
+            .instance_layout()
+            .into_iter()
+            .map(|s| (s.start, s.len, s.label))
+            .collect_vec();
+        assert_eq!(
+            layout,
+            vec![
+                (0, 4, "input 0".to_string()),
+                (4, 2, "output 0 packed 2 values per instance".to_string()),
+                (6, 5, "output 2 packed 2 values per instance".to_string()),
+            ]
+        );
+
+        // the limbs of the default decomposition fit 8 to an instance, and are compared exactly
+        for invalid in [
+            RunArgs {
+                instance_limbs: Some(9),
+                ..run_args.clone()
+            },
+            RunArgs {
+                instance_limbs: Some(1),
+                ..run_args.clone()
+            },
+            RunArgs {
+                output_visibilities: vec![],
+                tolerance: crate::circuit::Tolerance {
+                    val: 1.0,
+                    ..Default::default()
+                },
+                ..run_args
+            },
+        ] {
+            assert!(invalid.validate().is_err());
+        }
+    }
+
+    #[test]
+    fn test_revealed_outputs() {
+        let hashed = Visibility::Hashed {
+            hash_is_public: true,
+            outlets: vec![],
+        };
+        let run_args = RunArgs {
+            input_visibility: Visibility::Private,
+            output_visibilities: vec![hashed.clone(), Visibility::Public, hashed],
+            revealed_outputs: vec![2],
+            ..Default::default()
+        };
+        assert!(run_args.validate().is_ok());
+        assert!(run_args.output_is_public(2));
+        assert!(!run_args.output_is_public(0));
+        let settings = GraphSettings {
+            run_args: run_args.clone(),
+            model_instance_shapes: vec![vec![3], vec![2, 2]],
+            model_input_scales: vec![0],
+            model_output_scales: vec![0; 3],
+            ..Default::default()
+        };
+
+        // the revealed output is in the clear with the public outputs, and hashed with the hashed outputs
+        let layout = settings
+            .instance_layout()
+            .into_iter()
+            .map(|s| (s.start, s.len, s.label))
+            .collect_vec();
+        assert_eq!(
+            layout,
+            vec![
+                (0, 3, "output 1".to_string()),
+                (3, 4, "revealed output 2".to_string()),
+                (7, 1, "poseidon digest of output 0".to_string()),
+                (8, 1, "poseidon digest of output 2".to_string()),
+            ]
+        );
+
+        // only the outputs hashed in public are revealed, once
+        for revealed_outputs in [vec![1], vec![2, 2]] {
+            assert!(RunArgs {
+                revealed_outputs,
+                ..run_args.clone()
+            }
+            .validate()
+            .is_err());
+        }
+    }
+
+    #[test]
+    fn test_tight_lookup_ranges() {
+        let recip = LookupOp::Recip {
+            input_scale: 1.0.into(),
+            output_scale: 128.0.into(),
+            rounding: Default::default(),
+        };
+        let rsqrt = LookupOp::Rsqrt {
+            scale: 128.0.into(),
+        };
+        let observed = |node: usize, op: &LookupOp, range: Range| NodeLookupRange {
+            node,
+            name: format!("node_{}", node),
+            op: op.clone(),
+            range,
+        };
+        // two calibration batches, the variances of node 2 positive and the inputs of node 5 of either sign
+        let batches = [
+            vec![observed(2, &recip, (3, 40)), observed(5, &rsqrt, (-6, 10))],
+            vec![
+                observed(2, &recip, (5, 60)),
+                observed(7, &recip, (100, 200)),
+            ],
+        ];
+
+        let ranges = GraphCircuit::calc_tight_lookup_ranges(batches.iter().flatten(), 2.0);
+        // each bound is scaled away from zero by the margin
+        assert_eq!(
+            ranges.iter().map(|r| (r.node, r.range)).collect::<Vec<_>>(),
+            vec![(2, (1, 120)), (5, (-12, 20)), (7, (50, 400))]
+        );
+        assert!(GraphCircuit::calc_tight_lookup_ranges(batches.iter().flatten(), 0.0).is_empty());
+
+        // the table of an op covers the ranges of its nodes within the lookup range
+        let run_args = RunArgs {
+            lookup_range: (-256, 256),
+            tight_lookup_ranges: ranges,
+            ..Default::default()
+        };
+        let tables = run_args.tight_table_ranges();
+        assert_eq!(tables[&recip], (1, 256));
+        assert_eq!(tables[&rsqrt], (-12, 20));
+
+        assert!(RunArgs {
+            tight_lookup_margin: 0.5,
+            ..Default::default()
+        }
+        .validate()
+        .is_err());
+    }
+}
@@ -1,23 +1,39 @@
 use super::errors::GraphError;
 use super::extract_const_quantized_values;
+use super::inspect::NodeInfo;
+use super::multiplier_to_scale;
 use super::node::*;
+use super::profile::{CircuitProfile, LayoutEstimate, LookupRequirement, ModelLayout};
 use super::scale_to_multiplier;
+use super::set_layout_error;
+use super::set_model_layout;
 use super::vars::*;
+use super::GraphCircuit;
 use super::GraphSettings;
-use crate::circuit::hybrid::HybridOp;
+use super::RESERVED_BLINDING_ROWS;
+use crate::circuit::hybrid::{EmbeddingBagMode, HybridOp, NormOrder};
+use crate::circuit::layouts;
+use crate::circuit::poly::PolyOp;
 use crate::circuit::region::ConstantsMap;
+use crate::circuit::region::OpProfile;
 use crate::circuit::region::RegionCtx;
 use crate::circuit::region::RegionSettings;
-use crate::circuit::table::Range;
+use crate::circuit::table::{num_cols_required, Range};
+use crate::circuit::CircuitError;
 use crate::circuit::Input;
 use crate::circuit::InputType;
+use crate::circuit::RangeCheckStrategy;
 use crate::circuit::Unknown;
 use crate::fieldutils::IntegerRep;
+use crate::pfsys::progress::{self, ProvingPhase};
 use crate::tensor::ValType;
 use crate::{
-    circuit::{lookup::LookupOp, BaseConfig as PolyConfig, CheckMode, Op},
+    circuit::{
+        lookup::{LookupOp, NodeLookupRange},
+        BaseConfig as PolyConfig, CheckMode, Op,
+    },
     tensor::{Tensor, ValTensor},
-    RunArgs,
+    RunArgs, AUTO_NUM_INNER_COLS,
 };
 use halo2curves::bn256::Fr as Fp;
 
@@ -25,10 +41,7 @@ use halo2curves::bn256::Fr as Fp;
 use super::input::GraphData;
 #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
 use colored::Colorize;
-use halo2_proofs::{
-    circuit::{Layouter, Value},
-    plonk::ConstraintSystem,
-};
+use halo2_proofs::circuit::{Layouter, Value};
 use halo2curves::ff::Field;
 use itertools::Itertools;
 use log::error;
@@ -36,9 +49,9 @@ use log::{debug, info, trace};
 use serde::Deserialize;
 use serde::Serialize;
 use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
 use std::collections::HashMap;
-use std::collections::HashSet;
 use std::fs;
 use std::io::Read;
 use std::path::PathBuf;
@@ -48,7 +61,8 @@ use tabled::Table;
 use tract_onnx;
 #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
 use tract_onnx::prelude::{
-    Framework, Graph, InferenceFact, InferenceModelExt, SymbolValues, TypedFact, TypedOp,
+    Framework, Graph, InferenceFact, InferenceModel, InferenceModelExt, SymbolValues, TypedFact,
+    TypedOp,
 };
 #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
 use tract_onnx::tract_core::internal::DatumType;
@@ -71,6 +85,8 @@ pub struct ForwardResult {
     pub min_lookup_inputs: IntegerRep,
     /// The max range check size
     pub max_range_size: IntegerRep,
+    /// The range of the inputs of each node to the lookups whose tables are sized to the inputs of their nodes
+    pub tight_lookup_ranges: Vec<NodeLookupRange>,
 }
 
 impl From<DummyPassRes> for ForwardResult {
@@ -80,22 +96,46 @@ impl From<DummyPassRes> for ForwardResult {
             max_lookup_inputs: res.max_lookup_inputs,
             min_lookup_inputs: res.min_lookup_inputs,
             max_range_size: res.max_range_size,
+            tight_lookup_ranges: res.tight_lookup_ranges,
         }
     }
 }
 
-/// A circuit configuration for the entirety of a model loaded from an Onnx file.
-#[derive(Clone, Debug)]
-pub struct ModelConfig {
-    /// The base configuration for the circuit
-    pub base: PolyConfig<Fp>,
-    /// A wrapper for holding all columns that will be assigned to by the model
-    pub vars: ModelVars<Fp>,
-}
-
 /// Representation of execution graph
 pub type NodeGraph = BTreeMap<usize, NodeType>;
 
+/// The numbers of inner columns tried when they are picked automatically
+pub const INNER_COLS_CANDIDATES: [usize; 6] = [1, 2, 4, 8, 16, 32];
+
+/// The rows and area (rows × inner columns) the dummy layout of a model takes with a number of inner columns
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct InnerColsCandidate {
+    /// the number of inner columns
+    pub num_inner_cols: usize,
+    /// the number of rows of the layout
+    pub num_rows: usize,
+    /// the number of rows times the number of inner columns
+    pub area: usize,
+    /// whether the rows fit in the logrows, leaving room for the blinding rows
+    pub fits: bool,
+}
+
+/// A rescale ezkl inserted on its own to keep the fixed point scales of the model consistent: the output of a node
+/// divided down to the scale of the model, or an input of a node multiplied up to the scale of its other inputs
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ImplicitRescale {
+    /// the index of the node, which subgraphs index their nodes apart from
+    pub node: usize,
+    /// the name of the node in the onnx graph
+    pub name: String,
+    /// the index of the input rescaled, `None` if the output of the node is rescaled
+    pub input: Option<usize>,
+    /// the scale before the rescale
+    pub from_scale: crate::Scale,
+    /// the scale after the rescale
+    pub to_scale: crate::Scale,
+}
+
 /// A struct for loading from an Onnx file and converting a computational graph to a circuit.
 #[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
 pub struct DummyPassRes {
@@ -116,17 +156,31 @@ pub struct DummyPassRes {
     /// total const size
     pub total_const_size: usize,
     /// lookup ops
-    pub lookup_ops: HashSet<LookupOp>,
+    pub lookup_ops: BTreeSet<LookupOp>,
+    /// lookup ops fed the sums of adds
+    pub fused_add_lookup_ops: BTreeSet<LookupOp>,
     /// range checks
-    pub range_checks: HashSet<Range>,
+    pub range_checks: BTreeSet<Range>,
+    /// number of values looked up in each range check
+    pub range_check_counts: BTreeMap<Range, usize>,
     /// max lookup inputs
     pub max_lookup_inputs: IntegerRep,
     /// min lookup inputs
     pub min_lookup_inputs: IntegerRep,
     /// min range check
     pub max_range_size: IntegerRep,
+    /// the range of the inputs of each node to the lookups whose tables are sized to the inputs of their nodes
+    pub tight_lookup_ranges: Vec<NodeLookupRange>,
     /// outputs
     pub outputs: Vec<Tensor<Fp>>,
+    /// number of looked up values, counted when profiling
+    pub num_lookups: usize,
+    /// number of equality constraints, counted when profiling
+    pub num_equalities: usize,
+    /// the profiles of the ops, recorded when profiling
+    pub op_profiles: Vec<OpProfile>,
+    /// assignments saved by laying out identical constants once
+    pub deduped_constant_assignments: usize,
 }
 
 /// A struct for loading from an Onnx file and converting a computational graph to a circuit.
@@ -241,6 +295,9 @@ pub enum NodeType {
         inputs: Vec<Outlet>,
         /// the subgraph's idx within the parent graph
         idx: usize,
+        /// the subgraph's node name in the onnx graph
+        #[serde(default)]
+        name: String,
         /// output mappings
         output_mappings: Vec<Vec<OutputMapping>>,
         /// input mappings
@@ -292,6 +349,14 @@ impl NodeType {
         }
     }
 
+    /// Returns the node's name in the onnx graph.
+    pub fn name(&self) -> &str {
+        match self {
+            NodeType::Node(n) => &n.name,
+            NodeType::SubGraph { name, .. } => name,
+        }
+    }
+
     /// Returns a string representation of the operation.
     pub fn as_str(&self) -> String {
         match self {
@@ -315,6 +380,13 @@ impl NodeType {
             NodeType::SubGraph { .. } => false,
         }
     }
+    /// Returns true if the output is constrained to {0,1}, see [Node::out_boolean].
+    pub fn out_boolean(&self) -> bool {
+        match self {
+            NodeType::Node(n) => n.out_boolean,
+            NodeType::SubGraph { .. } => false,
+        }
+    }
     /// Returns true if the operation is a const.
     pub fn is_constant(&self) -> bool {
         match self {
@@ -379,6 +451,9 @@ pub struct ParsedNodes {
     pub nodes: BTreeMap<usize, NodeType>,
     inputs: Vec<usize>,
     outputs: Vec<Outlet>,
+    /// The names of the computational graph's inputs
+    #[serde(default)]
+    input_names: Vec<String>,
 }
 
 impl ParsedNodes {
@@ -388,6 +463,24 @@ impl ParsedNodes {
         input_nodes.len()
     }
 
+    /// Returns the names of the computational graph's inputs
+    pub fn input_names(&self) -> &[String] {
+        &self.input_names
+    }
+
+    /// Returns the names of the nodes of the computational graph's outputs
+    pub fn output_names(&self) -> Vec<String> {
+        self.outputs
+            .iter()
+            .map(|(node, _)| {
+                self.nodes
+                    .get(node)
+                    .map(|n| n.name().to_string())
+                    .unwrap_or_default()
+            })
+            .collect()
+    }
+
     /// Input types
     pub fn get_input_types(&self) -> Result<Vec<InputType>, GraphError> {
         self.inputs
@@ -473,6 +566,785 @@ impl ParsedNodes {
             })
             .collect::<Result<Vec<_>, GraphError>>()
     }
+
+    /// Fuses the gathers of rows of an embedding table summed within bags of indices, as a static EmbeddingBag is
+    /// exported, into [HybridOp::EmbeddingBag] ops. A gather is fused into the sum when the sum is its only use and
+    /// reduces the last axis of its indices, the bags.
+    pub fn fuse_embedding_bags(&mut self) {
+        let fusable = self
+            .nodes
+            .iter()
+            .filter_map(|(idx, node)| {
+                let NodeType::Node(sum) = node else {
+                    return None;
+                };
+                let SupportedOp::Linear(PolyOp::Sum { axes }) = &sum.opkind else {
+                    return None;
+                };
+                let [(gather_idx, 0)] = sum.inputs[..] else {
+                    return None;
+                };
+                let Some(NodeType::Node(gather)) = self.nodes.get(&gather_idx) else {
+                    return None;
+                };
+                let SupportedOp::Hybrid(HybridOp::Gather {
+                    dim: 0,
+                    constant_idx: None,
+                }) = gather.opkind
+                else {
+                    return None;
+                };
+                if gather.num_uses != 1 || self.outputs.iter().any(|(o, _)| *o == gather_idx) {
+                    return None;
+                }
+                let [(table, table_slot), (index, index_slot)] = gather.inputs[..] else {
+                    return None;
+                };
+                let table_dims = self.nodes.get(&table)?.out_dims().get(table_slot)?.clone();
+                let index_dims = self.nodes.get(&index)?.out_dims().get(index_slot)?.clone();
+                let bag_size = *index_dims.last()?;
+                if table_dims.len() != 2 || axes[..] != [index_dims.len() - 1] {
+                    return None;
+                }
+                Some((*idx, gather_idx, gather.inputs.clone(), bag_size))
+            })
+            .collect::<Vec<_>>();
+
+        for (idx, gather_idx, inputs, bag_size) in fusable {
+            debug!("fusing the gather of node {} into node {}", gather_idx, idx);
+            if let Some(NodeType::Node(sum)) = self.nodes.get_mut(&idx) {
+                sum.opkind = SupportedOp::Hybrid(HybridOp::EmbeddingBag {
+                    mode: EmbeddingBagMode::Sum,
+                    bag_size,
+                });
+                sum.inputs = inputs;
+            }
+            self.nodes.remove(&gather_idx);
+        }
+    }
+
+    /// Fuses the ops tract expands the ONNX ReduceL1 and ReduceL2 into, an absolute value or a square summed over
+    /// axes (and the square root of the sum), into [HybridOp::NormReduce] ops, such that the squares of a ReduceL2 are
+    /// summed before being rescaled. The ops are fused when each is the only use of the one before and the norm is at
+    /// the scale of the input, the reduced axes of a `keepdims=0` reduction being removed by the nodes following it.
+    pub fn fuse_norm_reductions(&mut self) {
+        let single_use = |idx: usize| match self.nodes.get(&idx) {
+            Some(NodeType::Node(node))
+                if node.num_uses == 1 && !self.outputs.iter().any(|(o, _)| *o == idx) =>
+            {
+                Some(node)
+            }
+            _ => None,
+        };
+
+        let fusable = self
+            .nodes
+            .iter()
+            .filter_map(|(idx, node)| {
+                let NodeType::Node(last) = node else {
+                    return None;
+                };
+                let (p, sum) = match (&last.opkind, &last.inputs[..]) {
+                    (SupportedOp::Linear(PolyOp::Sum { .. }), _) => (NormOrder::L1, last),
+                    (SupportedOp::Nonlinear(LookupOp::Sqrt { .. }), [(sum_idx, 0)]) => {
+                        (NormOrder::L2, single_use(*sum_idx)?)
+                    }
+                    _ => return None,
+                };
+                let SupportedOp::Linear(PolyOp::Sum { axes }) = &sum.opkind else {
+                    return None;
+                };
+                let [(elementwise_idx, 0)] = sum.inputs[..] else {
+                    return None;
+                };
+                let elementwise = single_use(elementwise_idx)?;
+                let elementwise_op = match &elementwise.opkind {
+                    SupportedOp::RebaseScale(rebased) => &rebased.inner,
+                    op => op,
+                };
+                match (p, elementwise_op) {
+                    (NormOrder::L1, SupportedOp::Linear(PolyOp::Abs))
+                    | (NormOrder::L2, SupportedOp::Linear(PolyOp::Pow(2))) => {}
+                    _ => return None,
+                }
+
+                let [(input, slot)] = elementwise.inputs[..] else {
+                    return None;
+                };
+                let in_scale = *self.nodes.get(&input)?.out_scales().get(slot)?;
+                if last.out_scale != in_scale {
+                    return None;
+                }
+                let mut removed = vec![elementwise_idx];
+                if p == NormOrder::L2 {
+                    removed.push(sum.idx);
+                }
+                Some((
+                    *idx,
+                    p,
+                    axes.clone(),
+                    in_scale,
+                    elementwise.inputs.clone(),
+                    removed,
+                ))
+            })
+            .collect::<Vec<_>>();
+
+        for (idx, p, axes, in_scale, inputs, removed) in fusable {
+            debug!(
+                "fusing nodes {:?} into the {} norm of node {}",
+                removed, p, idx
+            );
+            if let Some(NodeType::Node(norm)) = self.nodes.get_mut(&idx) {
+                norm.opkind = SupportedOp::Hybrid(HybridOp::NormReduce {
+                    p,
+                    axes,
+                    keepdims: true,
+                    scale: (scale_to_multiplier(in_scale) as f32).into(),
+                });
+                norm.inputs = inputs;
+            }
+            for removed in removed {
+                self.nodes.remove(&removed);
+            }
+        }
+    }
+
+    /// Folds the negations added to or subtracted from another operand into the add or sub, `a + -b` into `a - b`,
+    /// `-a + b` into `b - a` and `a - -b` into `a + b`, such that the negation takes no rows. A negation is folded when
+    /// the add or sub is its only use.
+    pub fn fold_negations(&mut self) {
+        let negated = |idx: usize| match self.nodes.get(&idx) {
+            Some(NodeType::Node(node))
+                if matches!(node.opkind, SupportedOp::Linear(PolyOp::Neg))
+                    && node.num_uses == 1
+                    && !self.outputs.iter().any(|(o, _)| *o == idx) =>
+            {
+                match node.inputs[..] {
+                    [input] => Some(input),
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+
+        let foldable = self
+            .nodes
+            .iter()
+            .filter_map(|(idx, node)| {
+                let NodeType::Node(node) = node else {
+                    return None;
+                };
+                let [a, b] = node.inputs[..] else {
+                    return None;
+                };
+                let (op, neg_idx, inputs) = match (&node.opkind, negated(a.0), negated(b.0)) {
+                    (SupportedOp::Linear(PolyOp::Add), _, Some(b_input)) => {
+                        (PolyOp::Sub, b.0, vec![a, b_input])
+                    }
+                    (SupportedOp::Linear(PolyOp::Add), Some(a_input), None) => {
+                        (PolyOp::Sub, a.0, vec![b, a_input])
+                    }
+                    (SupportedOp::Linear(PolyOp::Sub), _, Some(b_input)) => {
+                        (PolyOp::Add, b.0, vec![a, b_input])
+                    }
+                    _ => return None,
+                };
+                Some((*idx, op, neg_idx, inputs))
+            })
+            .collect::<Vec<_>>();
+
+        for (idx, op, neg_idx, inputs) in foldable {
+            debug!("folding the negation of node {} into node {}", neg_idx, idx);
+            if let Some(NodeType::Node(node)) = self.nodes.get_mut(&idx) {
+                node.opkind = SupportedOp::Linear(op);
+                node.inputs = inputs;
+            }
+            self.nodes.remove(&neg_idx);
+        }
+    }
+
+    /// The input, factor and constant of a node scaling its input by a constant, as a multiplication by a scalar
+    /// constant or an identity reinterpreting its input at another scale, either of them rebased. Scalings that
+    /// broadcast their input, or by constants that are hashed or committed to or used elsewhere, aren't folded.
+    fn constant_scaling(&self, idx: usize) -> Option<(Outlet, f64, Option<usize>)> {
+        let Some(NodeType::Node(node)) = self.nodes.get(&idx) else {
+            return None;
+        };
+        let op = match &node.opkind {
+            SupportedOp::RebaseScale(rebased) => &*rebased.inner,
+            op => op,
+        };
+        let (input, factor, constant) = match (op, &node.inputs[..]) {
+            (SupportedOp::Linear(PolyOp::Identity { out_scale }), [input]) => {
+                let in_scale = *self.nodes.get(&input.0)?.out_scales().get(input.1)?;
+                let factor = out_scale.map_or(1.0, |scale| scale_to_multiplier(in_scale - scale));
+                (*input, factor, None)
+            }
+            (SupportedOp::Linear(PolyOp::Mult), [a, b]) => {
+                let scalar = |(input, _): Outlet| match self.nodes.get(&input) {
+                    Some(NodeType::Node(n)) if n.num_uses == 1 => match &n.opkind {
+                        SupportedOp::Constant(c)
+                            if c.raw_values.len() == 1
+                                && c.quantized_values
+                                    .visibility()
+                                    .is_some_and(|v| !v.requires_processing()) =>
+                        {
+                            Some(c.raw_values[0] as f64)
+                        }
+                        _ => None,
+                    },
+                    _ => None,
+                };
+                match (scalar(*a), scalar(*b)) {
+                    (None, Some(factor)) => (*a, factor, Some(b.0)),
+                    (Some(factor), None) => (*b, factor, Some(a.0)),
+                    _ => return None,
+                }
+            }
+            _ => return None,
+        };
+        let in_dims = self.nodes.get(&input.0)?.out_dims().get(input.1)?.clone();
+        (in_dims == node.out_dims).then_some((input, factor, constant))
+    }
+
+    /// The constant weights of a conv or matmul that can be scaled in place of its input (`data`) or, if `data` is
+    /// `None`, of its output, along with its bias. The weights have to be used by it alone and not be hashed or
+    /// committed to.
+    fn scalable_weights(&self, idx: usize, data: Option<Outlet>) -> Option<Vec<usize>> {
+        let Some(NodeType::Node(node)) = self.nodes.get(&idx) else {
+            return None;
+        };
+        let op = match &node.opkind {
+            SupportedOp::RebaseScale(rebased) => &*rebased.inner,
+            op => op,
+        };
+        let is_constant =
+            |(input, _): &Outlet| self.nodes.get(input).is_some_and(|n| n.is_constant());
+        let (operand, weights) = match (op, &node.inputs[..]) {
+            (SupportedOp::Linear(PolyOp::Conv { .. }), [input, kernel, bias @ ..]) => {
+                let mut weights = vec![kernel.0];
+                if data.is_none() {
+                    weights.extend(bias.iter().map(|(b, _)| *b));
+                }
+                (*input, weights)
+            }
+            (SupportedOp::Linear(PolyOp::Einsum { .. }), [a, b]) => {
+                match (is_constant(a), is_constant(b)) {
+                    (false, true) => (*a, vec![b.0]),
+                    (true, false) => (*b, vec![a.0]),
+                    _ => return None,
+                }
+            }
+            _ => return None,
+        };
+        if data.is_some_and(|data| data != operand) {
+            return None;
+        }
+        weights
+            .iter()
+            .all(|idx| match self.nodes.get(idx) {
+                Some(NodeType::Node(n)) if n.num_uses == 1 => match &n.opkind {
+                    SupportedOp::Constant(c) => {
+                        !c.raw_values.is_empty()
+                            && c.raw_values.len() == c.quantized_values.len()
+                            && c.quantized_values
+                                .visibility()
+                                .is_some_and(|v| !v.requires_processing())
+                    }
+                    _ => false,
+                },
+                _ => false,
+            })
+            .then_some(weights)
+    }
+
+    /// The weights of the conv or matmul a chain of scalings from `input` to `last` follows, or else feeds, the chain
+    /// having to be the only use of the conv or matmul it follows, or the conv or matmul it feeds the only use of it
+    fn absorbing_weights(&self, input: Outlet, last: usize) -> Option<Vec<usize>> {
+        let single_use = |idx: usize| {
+            self.nodes.get(&idx).is_some_and(|n| n.num_uses() == 1)
+                && !self.outputs.iter().any(|(o, _)| *o == idx)
+        };
+        let followed = if single_use(input.0) {
+            self.scalable_weights(input.0, None)
+        } else {
+            None
+        };
+        followed.or_else(|| {
+            if !single_use(last) {
+                return None;
+            }
+            let (consumer, _) = self
+                .nodes
+                .iter()
+                .find(|(_, n)| n.inputs().contains(&(last, 0)))?;
+            self.scalable_weights(*consumer, Some((last, 0)))
+        })
+    }
+
+    /// Folds the chains of multiplications and divisions by scalar constants, as the normalization of the inputs of a
+    /// model, when [RunArgs::fold_constant_scalings] is set. The scalings of a chain (each the only use of the one
+    /// before) are folded into its last node, which reinterprets its input at another scale where their product is a
+    /// power of two, or else scales it by the product, quantized as a single constant, before rebasing it once. Where
+    /// the product isn't a power of two but the weights of a conv or matmul the chain follows or feeds can absorb it
+    /// without losing precision, the weights are scaled and the chain only reinterprets its input, a chain that leaves
+    /// its input as it is being removed.
+    pub fn fold_constant_scalings(&mut self, run_args: &RunArgs) -> Result<(), GraphError> {
+        if !run_args.fold_constant_scalings {
+            return Ok(());
+        }
+        let is_output =
+            |nodes: &ParsedNodes, idx: usize| nodes.outputs.iter().any(|(o, _)| *o == idx);
+
+        let scalings = self
+            .nodes
+            .keys()
+            .filter_map(|idx| Some((*idx, self.constant_scaling(*idx)?)))
+            .collect::<BTreeMap<_, _>>();
+        // the scalings whose only use is another scaling, folded into the chain ending after them
+        let folded = scalings
+            .keys()
+            .copied()
+            .filter(|idx| {
+                self.nodes[idx].num_uses() == 1
+                    && !is_output(self, *idx)
+                    && scalings.values().any(|(input, _, _)| input.0 == *idx)
+            })
+            .collect::<BTreeSet<_>>();
+        let chains = scalings
+            .keys()
+            .filter(|idx| !folded.contains(idx))
+            .map(|last| {
+                let mut chain = vec![*last];
+                let mut input = scalings[last].0;
+                while folded.contains(&input.0) {
+                    chain.push(input.0);
+                    input = scalings[&input.0].0;
+                }
+                chain.reverse();
+                chain
+            })
+            .collect::<Vec<_>>();
+
+        for chain in chains {
+            let last = chain[chain.len() - 1];
+            // the input of the chain is read again as an earlier chain may have been removed from before it
+            let Some((input, _, _)) = self.constant_scaling(chain[0]) else {
+                continue;
+            };
+            let (mut factor, mut constants) = (1.0, vec![]);
+            for idx in &chain {
+                let Some((_, f, constant)) = self.constant_scaling(*idx) else {
+                    continue;
+                };
+                factor *= f;
+                constants.extend(constant);
+            }
+            let in_scale = self.nodes[&input.0].out_scales()[input.1];
+            let out_scale = self.nodes[&last].out_scales()[0];
+
+            let shift = factor.abs().log2().round() as crate::Scale;
+            let is_shift = factor > 0.0
+                && (factor - scale_to_multiplier(shift)).abs() <= f32::EPSILON as f64 * factor;
+            // the weights absorb the factor and the power of two the chain reinterprets its input with
+            let gain = factor * scale_to_multiplier(out_scale - in_scale);
+            let weights = if is_shift || gain.abs() < 1.0 {
+                None
+            } else {
+                self.absorbing_weights(input, last)
+            };
+            if chain.len() < 2 && weights.is_none() {
+                continue;
+            }
+
+            debug!(
+                "folding the constant scalings of nodes {:?} by {} into node {}",
+                chain, factor, last
+            );
+            for idx in &chain[..chain.len() - 1] {
+                self.nodes.remove(idx);
+            }
+            let (opkind, inputs) = if is_shift || weights.is_some() {
+                let reinterpreted_scale = if is_shift {
+                    in_scale - shift
+                } else {
+                    out_scale
+                };
+                let identity = SupportedOp::Linear(PolyOp::Identity {
+                    out_scale: (reinterpreted_scale != in_scale).then_some(reinterpreted_scale),
+                });
+                for constant in &constants {
+                    self.nodes.remove(constant);
+                }
+                if let Some(weights) = &weights {
+                    self.scale_weights(weights, gain, run_args)?;
+                }
+                let opkind = RescaleTo::rescale(
+                    identity,
+                    out_scale,
+                    reinterpreted_scale,
+                    run_args.div_rebasing,
+                    run_args.div_rounding,
+                );
+                (opkind, vec![input])
+            } else {
+                // the product is quantized as the constant of the first multiplication of the chain
+                let constant = constants[0];
+                for idx in &constants[1..] {
+                    self.nodes.remove(idx);
+                }
+                let Some(NodeType::Node(n)) = self.nodes.get_mut(&constant) else {
+                    return Err(GraphError::MissingNode(constant));
+                };
+                let SupportedOp::Constant(c) = &mut n.opkind else {
+                    return Err(GraphError::OpMismatch(constant, "constant".into()));
+                };
+                let constant_scale = c.quantized_values.scale().ok_or(GraphError::MissingScale)?;
+                c.raw_values = Tensor::from(std::iter::once(factor as f32));
+                c.rebase_scale(constant_scale, &run_args.rounding)?;
+                n.out_dims = vec![1];
+
+                let mult = SupportedOp::Linear(PolyOp::Mult);
+                let mult_scale = in_scale + constant_scale;
+                let opkind = if mult_scale > out_scale {
+                    RebaseScale::rebase(
+                        mult,
+                        out_scale,
+                        mult_scale,
+                        1,
+                        run_args.div_rebasing,
+                        run_args.div_rounding,
+                    )
+                } else {
+                    RebaseScale::rebase_up(
+                        mult,
+                        out_scale,
+                        mult_scale,
+                        run_args.div_rebasing,
+                        run_args.div_rounding,
+                    )
+                };
+                (opkind, vec![input, (constant, 0)])
+            };
+
+            // a chain that leaves its input as it is is removed, its uses using its input in its place
+            let removable = matches!(
+                opkind,
+                SupportedOp::Linear(PolyOp::Identity { out_scale: None })
+            ) && !is_output(self, last)
+                && matches!(self.nodes.get(&input.0), Some(NodeType::Node(_)));
+            if removable {
+                let uses = self.nodes[&last].num_uses();
+                self.nodes.remove(&last);
+                for node in self.nodes.values_mut() {
+                    let inputs = match node {
+                        NodeType::Node(n) => &mut n.inputs,
+                        NodeType::SubGraph { inputs, .. } => inputs,
+                    };
+                    for i in inputs.iter_mut().filter(|i| **i == (last, 0)) {
+                        *i = input;
+                    }
+                }
+                if let Some(NodeType::Node(n)) = self.nodes.get_mut(&input.0) {
+                    n.num_uses += uses - 1;
+                }
+            } else if let Some(NodeType::Node(n)) = self.nodes.get_mut(&last) {
+                n.opkind = opkind;
+                n.inputs = inputs;
+            }
+        }
+        Ok(())
+    }
+
+    /// Scales constant weights by a factor, requantizing them at their scale
+    fn scale_weights(
+        &mut self,
+        weights: &[usize],
+        factor: f64,
+        run_args: &RunArgs,
+    ) -> Result<(), GraphError> {
+        for idx in weights {
+            if let Some(NodeType::Node(n)) = self.nodes.get_mut(idx) {
+                if let SupportedOp::Constant(c) = &mut n.opkind {
+                    let sparse = c.sparse_values.is_some();
+                    let scale = c.quantized_values.scale().ok_or(GraphError::MissingScale)?;
+                    c.raw_values = c.raw_values.map(|x| (x as f64 * factor) as f32);
+                    c.rebase_scale(scale, &run_args.rounding)?;
+                    let threshold = c
+                        .quantized_values
+                        .visibility()
+                        .and_then(|v| run_args.sparsity_threshold_of(&v));
+                    if let (true, Some(threshold)) = (sparse, threshold) {
+                        c.sparsify(threshold)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Fuses the elementwise adds feeding a lookup, as the residual add and activation ending a resnet block, into
+    /// [HybridOp::FusedAddLookup] ops when [RunArgs::fuse_add_lookups] is set. An add is fused into the lookup when
+    /// the lookup is its only use, and only if the table of the lookup range fits in a single column, the fused
+    /// lookups holding the second addend in the column the column index of the input is otherwise in.
+    pub fn fuse_add_lookups(&mut self, run_args: &RunArgs) {
+        let (lo, hi) = run_args.lookup_range;
+        let col_size = crate::circuit::table::Table::<Fp>::cal_col_size(
+            run_args.logrows as usize,
+            RESERVED_BLINDING_ROWS,
+        );
+        if !run_args.fuse_add_lookups || num_cols_required((hi - lo).abs(), col_size) != 1 {
+            return;
+        }
+
+        let fusable = self
+            .nodes
+            .iter()
+            .filter_map(|(idx, node)| {
+                let NodeType::Node(lookup) = node else {
+                    return None;
+                };
+                let SupportedOp::Nonlinear(nl) = &lookup.opkind else {
+                    return None;
+                };
+                let [(add_idx, 0)] = lookup.inputs[..] else {
+                    return None;
+                };
+                let Some(NodeType::Node(add)) = self.nodes.get(&add_idx) else {
+                    return None;
+                };
+                if !matches!(add.opkind, SupportedOp::Linear(PolyOp::Add))
+                    || add.inputs.len() != 2
+                    || add.num_uses != 1
+                    || self.outputs.iter().any(|(o, _)| *o == add_idx)
+                {
+                    return None;
+                }
+                Some((*idx, add_idx, add.inputs.clone(), nl.clone()))
+            })
+            .collect::<Vec<_>>();
+
+        for (idx, add_idx, inputs, nl) in fusable {
+            debug!("fusing the add of node {} into node {}", add_idx, idx);
+            if let Some(NodeType::Node(lookup)) = self.nodes.get_mut(&idx) {
+                lookup.opkind = SupportedOp::Hybrid(HybridOp::FusedAddLookup { nl });
+                lookup.inputs = inputs;
+            }
+            self.nodes.remove(&add_idx);
+        }
+    }
+
+    /// Rescales the outputs to the scales of the run args (see [RunArgs::output_scales]), appending a node that
+    /// rescales each output not already at its scale and exposing it in place of the output.
+    pub fn rescale_outputs(&mut self, run_args: &RunArgs) -> Result<(), GraphError> {
+        let mut rescaled = vec![];
+        for output_scale in &run_args.output_scales {
+            let output = match output_scale.output.parse::<usize>() {
+                Ok(i) if i < self.outputs.len() => Some(i),
+                Ok(_) => None,
+                Err(_) => self.outputs.iter().position(|(idx, _)| {
+                    matches!(self.nodes.get(idx), Some(NodeType::Node(n)) if n.name == output_scale.output)
+                }),
+            }
+            .ok_or_else(|| {
+                GraphError::InvalidRunArgs(format!(
+                    "output scale of output {} but the model has no such output",
+                    output_scale.output
+                ))
+            })?;
+            if rescaled.contains(&output) {
+                return Err(GraphError::InvalidRunArgs(format!(
+                    "output {} has more than one output scale",
+                    output
+                )));
+            }
+            rescaled.push(output);
+
+            let (idx, slot) = self.outputs[output];
+            let node = self.nodes.get(&idx).ok_or(GraphError::MissingNode(idx))?;
+            let out_scale = node.out_scales()[slot];
+            if out_scale == output_scale.scale {
+                continue;
+            }
+            let out_dims = node.out_dims()[slot].clone();
+            let name = match node {
+                NodeType::Node(n) => format!("{}_rescaled", n.name),
+                NodeType::SubGraph { .. } => format!("output_{}_rescaled", output),
+            };
+
+            let rescaled_idx = self.nodes.keys().last().map_or(0, |last| last + 1);
+            let rescale = Node {
+                opkind: RescaleTo::rescale(
+                    SupportedOp::Linear(PolyOp::Identity { out_scale: None }),
+                    output_scale.scale,
+                    out_scale,
+                    run_args.div_rebasing,
+                    run_args.div_rounding,
+                ),
+                out_scale: output_scale.scale,
+                inputs: vec![(idx, slot)],
+                out_dims,
+                idx: rescaled_idx,
+                num_uses: 1,
+                name,
+                out_boolean: false,
+            };
+            self.nodes.insert(rescaled_idx, NodeType::Node(rescale));
+            self.outputs[output] = (rescaled_idx, 0);
+
+            // an output is used at least once, now by its rescale if by no other node
+            let uses = self
+                .nodes
+                .values()
+                .flat_map(|n| n.inputs())
+                .filter(|(input, _)| *input == idx)
+                .count();
+            if let Some(NodeType::Node(n)) = self.nodes.get_mut(&idx) {
+                n.num_uses = std::cmp::max(uses, 1);
+            }
+        }
+        Ok(())
+    }
+
+    /// Masks the samples of the batch with a last boolean input of the batch size (see [RunArgs::batch_mask]): each
+    /// output is multiplied along its first axis by the mask, reshaped to broadcast across the other axes, and the mask
+    /// is exposed as a last output, such that the instances tell which samples were proven.
+    pub fn mask_batch(&mut self, run_args: &RunArgs) -> Result<(), GraphError> {
+        if !run_args.batch_mask {
+            return Ok(());
+        }
+        let output_shapes = self.output_shapes()?;
+        let batch_size = match output_shapes.first().and_then(|shape| shape.first()) {
+            Some(batch_size) => *batch_size,
+            None => {
+                return Err(GraphError::InvalidRunArgs(
+                    "batch_mask requires outputs with a batch axis".into(),
+                ))
+            }
+        };
+        if let Some(shape) = output_shapes
+            .iter()
+            .find(|shape| shape.first() != Some(&batch_size))
+        {
+            return Err(GraphError::InvalidRunArgs(format!(
+                "batch_mask requires outputs of the same batch size {}, got an output of shape {:?}",
+                batch_size, shape
+            )));
+        }
+
+        let mut next_idx = self.nodes.keys().last().map_or(0, |last| last + 1);
+        let mut push = |nodes: &mut BTreeMap<usize, NodeType>, mut node: Node| {
+            node.idx = next_idx;
+            nodes.insert(next_idx, NodeType::Node(node));
+            next_idx += 1;
+            next_idx - 1
+        };
+
+        let mask = push(
+            &mut self.nodes,
+            Node {
+                opkind: SupportedOp::Input(Input {
+                    scale: 0,
+                    datum_type: InputType::Bool,
+                }),
+                out_scale: 0,
+                inputs: vec![],
+                out_dims: vec![batch_size],
+                idx: 0,
+                num_uses: 1,
+                name: "batch_mask".into(),
+                out_boolean: true,
+            },
+        );
+
+        for (output, shape) in output_shapes.iter().enumerate() {
+            let (idx, slot) = self.outputs[output];
+            let node = self.nodes.get(&idx).ok_or(GraphError::MissingNode(idx))?;
+            let out_scale = node.out_scales()[slot];
+            let name = node.name().to_string();
+
+            // the mask of the batch broadcast across the other axes of the output
+            let mut broadcast = vec![batch_size];
+            broadcast.resize(shape.len(), 1);
+            let reshaped = if broadcast.len() > 1 {
+                push(
+                    &mut self.nodes,
+                    Node {
+                        opkind: SupportedOp::Linear(PolyOp::Reshape(broadcast.clone())),
+                        out_scale: 0,
+                        inputs: vec![(mask, 0)],
+                        out_dims: broadcast,
+                        idx: 0,
+                        num_uses: 1,
+                        name: format!("{}_batch_mask", name),
+                        out_boolean: true,
+                    },
+                )
+            } else {
+                mask
+            };
+
+            let masked = push(
+                &mut self.nodes,
+                Node {
+                    opkind: SupportedOp::Linear(PolyOp::Mult),
+                    out_scale,
+                    inputs: vec![(idx, slot), (reshaped, 0)],
+                    out_dims: shape.clone(),
+                    idx: 0,
+                    num_uses: 1,
+                    name: format!("{}_masked", name),
+                    out_boolean: false,
+                },
+            );
+            self.outputs[output] = (masked, 0);
+
+            // an output is used at least once, now by its mask if by no other node
+            let uses = self
+                .nodes
+                .values()
+                .flat_map(|n| n.inputs())
+                .filter(|(input, _)| *input == idx)
+                .count();
+            if let Some(NodeType::Node(n)) = self.nodes.get_mut(&idx) {
+                n.num_uses = std::cmp::max(uses, 1);
+            }
+        }
+
+        let exposed = push(
+            &mut self.nodes,
+            Node {
+                opkind: SupportedOp::Linear(PolyOp::Identity { out_scale: None }),
+                out_scale: 0,
+                inputs: vec![(mask, 0)],
+                out_dims: vec![batch_size],
+                idx: 0,
+                num_uses: 1,
+                name: "batch_mask_output".into(),
+                out_boolean: true,
+            },
+        );
+        self.outputs.push((exposed, 0));
+        let uses = self
+            .nodes
+            .values()
+            .flat_map(|n| n.inputs())
+            .filter(|(input, _)| *input == mask)
+            .count();
+        if let Some(NodeType::Node(n)) = self.nodes.get_mut(&mask) {
+            n.num_uses = uses;
+        }
+        self.inputs.push(mask);
+        self.input_names.push("batch_mask".into());
+
+        debug!(
+            "masked {} outputs with a batch mask of {} samples",
+            output_shapes.len(),
+            batch_size
+        );
+        Ok(())
+    }
 }
 
 impl Model {
@@ -486,6 +1358,48 @@ impl Model {
 
         let graph = Self::load_onnx_model(reader, run_args, &visibility)?;
 
+        // the public tensors to pack are only known from the shapes of the graph
+        let run_args = &Self::with_packed_instances(&graph, run_args)?;
+        let visibility = VarVisibility::from_args(run_args)?;
+
+        if !visibility.outputs.is_empty()
+            || !run_args.output_bounds.is_empty()
+            || !run_args.revealed_outputs.is_empty()
+        {
+            run_args.validate().map_err(GraphError::InvalidRunArgs)?;
+        }
+        if let Some(bound) = run_args
+            .output_bounds
+            .iter()
+            .find(|b| b.output >= graph.outputs.len())
+        {
+            return Err(GraphError::InvalidRunArgs(format!(
+                "a bound on output {} of a model with {} outputs",
+                bound.output,
+                graph.outputs.len()
+            )));
+        }
+        if let Some(idx) = run_args
+            .revealed_outputs
+            .iter()
+            .find(|idx| **idx >= graph.outputs.len())
+        {
+            return Err(GraphError::InvalidRunArgs(format!(
+                "output {} of a model with {} outputs is revealed",
+                idx,
+                graph.outputs.len()
+            )));
+        }
+        if !visibility.outputs.is_empty() {
+            if visibility.outputs.len() != graph.outputs.len() {
+                return Err(GraphError::InvalidRunArgs(format!(
+                    "{} output visibilities for the {} outputs of the model",
+                    visibility.outputs.len(),
+                    graph.outputs.len()
+                )));
+            }
+        }
+
         let om = Model { graph, visibility };
 
         debug!("\n {}", om.table_nodes());
@@ -520,23 +1434,9 @@ impl Model {
         Ok(result)
     }
 
-    /// Generate model parameters for the circuit
-    pub fn gen_params(
-        &self,
-        run_args: &RunArgs,
-        check_mode: CheckMode,
-    ) -> Result<GraphSettings, GraphError> {
-        let instance_shapes = self.instance_shapes()?;
-        #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
-        debug!(
-            "{} {} {}",
-            "model has".blue(),
-            instance_shapes.len().to_string().blue(),
-            "instances".blue()
-        );
-
-        let inputs: Vec<ValTensor<Fp>> = self
-            .graph
+    /// Unknown values (or random constants if the inputs are fixed) of the shapes of the model inputs, for dummy layouts
+    fn dummy_inputs(&self) -> Result<Vec<ValTensor<Fp>>, GraphError> {
+        self.graph
             .input_shapes()?
             .iter()
             .map(|shape| {
@@ -555,47 +1455,382 @@ impl Model {
                 t.reshape(shape)?;
                 Ok(t)
             })
-            .collect::<Result<Vec<_>, GraphError>>()?;
+            .collect::<Result<Vec<_>, GraphError>>()
+    }
+
+    /// Profiles the rows, lookups and equality constraints each op of the model lays out, using a dummy layout
+    pub fn profile(&self, run_args: &RunArgs) -> Result<CircuitProfile, GraphError> {
+        let run_args = &self.with_num_inner_cols(run_args)?;
+        let inputs = self.dummy_inputs()?;
 
         let res = self.dummy_layout(
             run_args,
             &inputs,
-            RegionSettings::all_false(run_args.decomp_base, run_args.decomp_legs),
+            RegionSettings::all_false(run_args.decomp_base, run_args.decomp_legs).with_profile(),
         )?;
 
-        // if we're using percentage tolerance, we need to add the necessary range check ops for it.
+        Ok(CircuitProfile::new(
+            res.num_rows,
+            res.linear_coord,
+            res.num_lookups,
+            res.num_equalities,
+            res.op_profiles,
+        ))
+    }
 
-        Ok(GraphSettings {
-            run_args: run_args.clone(),
-            model_instance_shapes: instance_shapes,
-            module_sizes: crate::graph::modules::ModuleSizes::default(),
-            num_rows: res.num_rows,
+    /// Estimates the rows, lookups, instances and logrows of the circuit of the model from a dry run of its layout,
+    /// without keygen. The run args are resolved as for the settings of the circuit, such that the estimate is what
+    /// the real layout of the model lays out.
+    pub fn estimate_layout(&self, run_args: &RunArgs) -> Result<LayoutEstimate, GraphError> {
+        let settings = GraphCircuit::gen_settings(self, run_args)?;
+        let run_args = &settings.run_args;
+        let res = self.dummy_layout(
+            run_args,
+            &self.dummy_inputs()?,
+            RegionSettings::all_false(run_args.decomp_base, run_args.decomp_legs).with_profile(),
+        )?;
+
+        let tight_ranges = run_args.tight_table_ranges();
+        let requirements = |ops: BTreeSet<LookupOp>| {
+            ops.into_iter()
+                .map(|op| LookupRequirement {
+                    range: tight_ranges
+                        .get(&op)
+                        .copied()
+                        .unwrap_or(run_args.lookup_range),
+                    op,
+                })
+                .collect()
+        };
+        let mut ops = res.op_profiles;
+        // an op taking no rows comes before the op laid out from the same row
+        ops.sort_by_key(|op| (op.start_row, op.rows));
+
+        Ok(LayoutEstimate {
+            total_rows: settings.num_rows,
+            model_rows: res.num_rows,
             total_assignments: res.linear_coord,
-            required_lookups: res.lookup_ops.into_iter().collect(),
-            max_dynamic_input_len: res.max_dynamic_input_len,
-            required_range_checks: res.range_checks.into_iter().collect(),
-            model_output_scales: self.graph.get_output_scales()?,
-            model_input_scales: self.graph.get_input_scales(),
+            ops,
+            lookups: requirements(res.lookup_ops),
+            fused_add_lookups: requirements(res.fused_add_lookup_ops),
+            range_checks: res.range_checks.into_iter().collect(),
             num_dynamic_lookups: res.num_dynamic_lookups,
-            total_dynamic_col_size: res.dynamic_lookup_col_coord,
             num_shuffles: res.num_shuffles,
-            total_shuffle_col_size: res.shuffle_col_coord,
-            total_const_size: res.total_const_size,
-            check_mode,
-            version: env!("CARGO_PKG_VERSION").to_string(),
-            num_blinding_factors: None,
-            // unix time timestamp
-            #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
-            timestamp: Some(
-                instant::SystemTime::now()
-                    .duration_since(instant::SystemTime::UNIX_EPOCH)?
+            num_instances: settings.total_instances(),
+            min_logrows: settings.min_logrows(),
+        })
+    }
+
+    /// Lists every rescale inserted on its own in the model and its subgraphs, in the order of the nodes. The rescales
+    /// of the outputs can be suppressed or replaced through the rescale overrides of the run args.
+    pub fn implicit_rescales(&self) -> Vec<ImplicitRescale> {
+        let mut rescales = vec![];
+        for node in self.graph.nodes.values() {
+            match node {
+                NodeType::Node(n) => {
+                    let (opkind, rebase) = match &n.opkind {
+                        SupportedOp::RebaseScale(op) => (&*op.inner, Some(op)),
+                        opkind => (opkind, None),
+                    };
+                    if let SupportedOp::Rescaled(op) = opkind {
+                        for (input, multiplier) in op.scale.iter().filter(|(_, m)| *m > 1) {
+                            let from_scale = n
+                                .inputs
+                                .get(*input)
+                                .and_then(|(idx, outlet)| {
+                                    self.graph
+                                        .nodes
+                                        .get(idx)?
+                                        .out_scales()
+                                        .get(*outlet)
+                                        .copied()
+                                })
+                                .unwrap_or_default();
+                            rescales.push(ImplicitRescale {
+                                node: n.idx,
+                                name: n.name.clone(),
+                                input: Some(*input),
+                                from_scale,
+                                to_scale: from_scale + multiplier_to_scale(*multiplier as f64),
+                            });
+                        }
+                    }
+                    if let Some(op) = rebase {
+                        rescales.push(ImplicitRescale {
+                            node: n.idx,
+                            name: n.name.clone(),
+                            input: None,
+                            from_scale: op.original_scale,
+                            to_scale: op.target_scale,
+                        });
+                    }
+                }
+                NodeType::SubGraph { model, .. } => rescales.extend(model.implicit_rescales()),
+            }
+        }
+        rescales
+    }
+
+    /// Lists the nodes of the model and of its subgraphs, in the order of the nodes, with their ops, shapes, scales and
+    /// visibility
+    pub fn nodes(&self) -> Vec<NodeInfo> {
+        self.node_infos(None)
+    }
+
+    /// The node of the main graph at `idx`, if any
+    pub fn node(&self, idx: usize) -> Option<NodeInfo> {
+        self.graph
+            .nodes
+            .get(&idx)
+            .map(|node| self.node_info(node, None))
+    }
+
+    fn node_infos(&self, subgraph: Option<usize>) -> Vec<NodeInfo> {
+        let mut infos = vec![];
+        for node in self.graph.nodes.values() {
+            infos.push(self.node_info(node, subgraph));
+            if let NodeType::SubGraph { model, idx, .. } = node {
+                infos.extend(model.node_infos(Some(*idx)));
+            }
+        }
+        infos
+    }
+
+    fn node_info(&self, node: &NodeType, subgraph: Option<usize>) -> NodeInfo {
+        let idx = node.idx();
+        let inputs = node.inputs();
+        let input_shapes = inputs
+            .iter()
+            .map(|(input, outlet)| {
+                self.graph
+                    .nodes
+                    .get(input)
+                    .and_then(|n| n.out_dims().get(*outlet).cloned())
+                    .unwrap_or_default()
+            })
+            .collect();
+        // the inputs and outputs of a subgraph are those of the iterations of its body, not of the model
+        let output = self.graph.outputs.iter().position(|(n, _)| *n == idx);
+        let visibility = if node.is_constant() {
+            Some(self.visibility.params.clone())
+        } else if subgraph.is_some() {
+            None
+        } else if node.is_input() {
+            Some(self.visibility.input.clone())
+        } else {
+            output.map(|output| self.visibility.output_of(output).clone())
+        };
+        NodeInfo {
+            idx,
+            subgraph,
+            name: node.name().to_string(),
+            op: node.as_str(),
+            inputs,
+            input_shapes,
+            out_dims: node.out_dims(),
+            out_scales: node.out_scales(),
+            visibility,
+        }
+    }
+
+    /// The rows and area the dummy layout of the model takes with each of [INNER_COLS_CANDIDATES] inner columns, and
+    /// whether they fit in the logrows of the run args
+    pub fn inner_cols_candidates(
+        &self,
+        run_args: &RunArgs,
+    ) -> Result<Vec<InnerColsCandidate>, GraphError> {
+        let inputs = self.dummy_inputs()?;
+        let usable_rows = (1usize << run_args.logrows).saturating_sub(RESERVED_BLINDING_ROWS);
+        INNER_COLS_CANDIDATES
+            .iter()
+            .map(|num_inner_cols| {
+                let mut run_args = run_args.clone();
+                run_args.num_inner_cols = *num_inner_cols;
+                let res = self.dummy_layout(
+                    &run_args,
+                    &inputs,
+                    RegionSettings::all_false(run_args.decomp_base, run_args.decomp_legs),
+                )?;
+                Ok(InnerColsCandidate {
+                    num_inner_cols: *num_inner_cols,
+                    num_rows: res.num_rows,
+                    area: res.num_rows * num_inner_cols,
+                    fits: res.num_rows <= usable_rows,
+                })
+            })
+            .collect()
+    }
+
+    /// Picks the number of inner columns of [INNER_COLS_CANDIDATES] that takes the least area while fitting in the
+    /// logrows of the run args, fewer columns winning ties. If none fit, picks the one that takes the fewest rows.
+    pub fn tune_num_inner_cols(&self, run_args: &RunArgs) -> Result<usize, GraphError> {
+        let candidates = self.inner_cols_candidates(run_args)?;
+        debug!("inner column candidates: {:?}", candidates);
+
+        let fitting = candidates
+            .iter()
+            .filter(|c| c.fits)
+            .min_by_key(|c| (c.area, c.num_inner_cols));
+        let best = fitting.or_else(|| {
+            let fewest_rows = candidates
+                .iter()
+                .min_by_key(|c| (c.num_rows, c.num_inner_cols));
+            log::warn!(
+                "no number of inner columns fits the model in {} logrows, picking the one that takes the fewest rows",
+                run_args.logrows
+            );
+            fewest_rows
+        });
+        let num_inner_cols = best
+            .map(|c| c.num_inner_cols)
+            .unwrap_or(RunArgs::default().num_inner_cols);
+        info!("picked {} inner columns", num_inner_cols);
+        Ok(num_inner_cols)
+    }
+
+    /// The run args, with the number of inner columns tuned if they are to be picked automatically
+    fn with_num_inner_cols(&self, run_args: &RunArgs) -> Result<RunArgs, GraphError> {
+        let mut run_args = run_args.clone();
+        if run_args.num_inner_cols == AUTO_NUM_INNER_COLS {
+            run_args.num_inner_cols = self.tune_num_inner_cols(&run_args)?;
+        }
+        Ok(run_args)
+    }
+
+    /// Generate model parameters for the circuit
+    pub fn gen_params(
+        &self,
+        run_args: &RunArgs,
+        check_mode: CheckMode,
+    ) -> Result<GraphSettings, GraphError> {
+        let run_args = &self.with_num_inner_cols(run_args)?;
+        let run_args = &Self::with_packed_instances(&self.graph, run_args)?;
+        let instance_shapes = self.instance_shapes(run_args)?;
+        #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+        debug!(
+            "{} {} {}",
+            "model has".blue(),
+            instance_shapes.len().to_string().blue(),
+            "instances".blue()
+        );
+
+        let inputs = self.dummy_inputs()?;
+        let run_args = &self.with_bit_range_checks(run_args, &inputs)?;
+
+        let res = self.dummy_layout(
+            run_args,
+            &inputs,
+            RegionSettings::all_false(run_args.decomp_base, run_args.decomp_legs),
+        )?;
+
+        if res.deduped_constant_assignments > 0 {
+            info!(
+                "laying out identical constants once saves {} assignments",
+                res.deduped_constant_assignments
+            );
+        }
+
+        // if we're using percentage tolerance, we need to add the necessary range check ops for it.
+
+        Ok(GraphSettings {
+            run_args: run_args.clone(),
+            model_instance_shapes: instance_shapes,
+            module_sizes: crate::graph::modules::ModuleSizes::default(),
+            num_rows: res.num_rows,
+            total_assignments: res.linear_coord,
+            required_lookups: res.lookup_ops.into_iter().collect(),
+            required_fused_add_lookups: res.fused_add_lookup_ops.into_iter().collect(),
+            max_dynamic_input_len: res.max_dynamic_input_len,
+            required_range_checks: res.range_checks.into_iter().collect(),
+            model_output_scales: self.graph.get_output_scales()?,
+            model_input_scales: self.graph.get_input_scales(),
+            model_input_names: self.graph.input_names().to_vec(),
+            model_output_names: self.graph.output_names(),
+            num_dynamic_lookups: res.num_dynamic_lookups,
+            total_dynamic_col_size: res.dynamic_lookup_col_coord,
+            num_shuffles: res.num_shuffles,
+            total_shuffle_col_size: res.shuffle_col_coord,
+            total_const_size: res.total_const_size,
+            deduped_constant_assignments: res.deduped_constant_assignments,
+            check_mode,
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            num_blinding_factors: None,
+            // unix time timestamp
+            #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+            timestamp: Some(
+                instant::SystemTime::now()
+                    .duration_since(instant::SystemTime::UNIX_EPOCH)?
                     .as_millis(),
             ),
             #[cfg(any(not(feature = "ezkl"), target_arch = "wasm32"))]
             timestamp: None,
+            image_preprocessing: None,
+            input_normalization: None,
+            input_signature: None,
+            nullifier: false,
+            output_encryption: None,
+            session: false,
+            polycommit_blinding: false,
+            packed_tensors: vec![],
+            param_commitment_groups: vec![],
+            limb_packed_outputs: vec![],
         })
     }
 
+    /// The run args with the ranges whose range checks decompose the values in bits resolved from the
+    /// [RunArgs::range_check_strategy], given the number of values the lookup layout of the model checks to each range
+    fn with_bit_range_checks(
+        &self,
+        run_args: &RunArgs,
+        inputs: &[ValTensor<Fp>],
+    ) -> Result<RunArgs, GraphError> {
+        let mut run_args = run_args.clone();
+        run_args.bit_range_checks = vec![];
+        if run_args.range_check_strategy == RangeCheckStrategy::Lookup {
+            return Ok(run_args);
+        }
+
+        let res = self.dummy_layout(
+            &run_args,
+            inputs,
+            RegionSettings::all_false(run_args.decomp_base, run_args.decomp_legs),
+        )?;
+        run_args.bit_range_checks = res
+            .range_check_counts
+            .into_iter()
+            .filter(|(range, count)| {
+                run_args
+                    .range_check_strategy
+                    .decomposes(*range, *count, run_args.num_inner_cols)
+            })
+            .map(|(range, _)| range)
+            .collect();
+        if !run_args.bit_range_checks.is_empty() {
+            info!(
+                "decomposing the range checks of {:?} in bits",
+                run_args.bit_range_checks
+            );
+        }
+        Ok(run_args)
+    }
+
+    /// The run args with the public inputs and outputs of more values than
+    /// [RunArgs::pack_public_threshold] packed, see [RunArgs::with_packed_instances]
+    fn with_packed_instances(
+        graph: &ParsedNodes,
+        run_args: &RunArgs,
+    ) -> Result<RunArgs, GraphError> {
+        let lens = |shapes: Vec<Vec<usize>>| {
+            shapes
+                .iter()
+                .map(|shape| shape.iter().product())
+                .collect::<Vec<usize>>()
+        };
+        run_args
+            .with_packed_instances(&lens(graph.input_shapes()?), &lens(graph.output_shapes()?))
+            .map_err(GraphError::InvalidRunArgs)
+    }
+
     /// Runs a forward pass on sample data !
     /// # Arguments
     /// * `reader` - A reader for an Onnx file.
@@ -615,6 +1850,39 @@ impl Model {
         Ok(res.into())
     }
 
+    /// Keeps the outputs of the onnx model named `names`, in order. The typed model is translated in the order of the
+    /// nodes its outputs depend on, such that the nodes that don't feed the kept outputs are removed before
+    /// quantization.
+    #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+    fn select_outputs(model: &mut InferenceModel, names: &[String]) -> Result<(), GraphError> {
+        let available = model
+            .outputs
+            .iter()
+            .map(|o| match model.outlet_label(*o) {
+                Some(label) => label.to_string(),
+                None => model.node(o.node).name.clone(),
+            })
+            .collect_vec();
+        for (i, name) in names.iter().enumerate() {
+            if !available.contains(name) {
+                return Err(GraphError::InvalidRunArgs(format!(
+                    "{} isn't an output of the model, whose outputs are {}",
+                    name,
+                    available.join(", ")
+                )));
+            }
+            if names[..i].contains(name) {
+                return Err(GraphError::InvalidRunArgs(format!(
+                    "output {} is kept twice",
+                    name
+                )));
+            }
+        }
+        model.set_output_names(names)?;
+        debug!("kept outputs {:?} out of {:?}", names, available);
+        Ok(())
+    }
+
     /// Loads an Onnx model from a specified path.
     /// # Arguments
     /// * `reader` - A reader for an Onnx file.
@@ -627,7 +1895,15 @@ impl Model {
     ) -> Result<TractResult, GraphError> {
         use tract_onnx::tract_hir::internal::GenericFactoid;
 
-        let mut model = tract_onnx::onnx().model_for_read(reader)?;
+        let mut model = match &run_args.weights {
+            Some(weights) => {
+                let onnx = tract_onnx::onnx();
+                let mut proto = onnx.proto_model_for_read(reader)?;
+                super::weights::override_initializers(&mut proto, &weights.load()?)?;
+                onnx.model_for_proto_model(&proto)?
+            }
+            None => tract_onnx::onnx().model_for_read(reader)?,
+        };
 
         let variables: std::collections::HashMap<String, usize> =
             std::collections::HashMap::from_iter(run_args.variables.clone());
@@ -650,6 +1926,10 @@ impl Model {
             model.set_input_fact(i, fact)?;
         }
 
+        if !run_args.outputs.is_empty() {
+            Self::select_outputs(&mut model, &run_args.outputs)?;
+        }
+
         for (i, _) in model.clone().outputs.iter().enumerate() {
             model.set_output_fact(i, InferenceFact::default())?;
         }
@@ -682,27 +1962,56 @@ impl Model {
         visibility: &VarVisibility,
     ) -> Result<ParsedNodes, GraphError> {
         let start_time = instant::Instant::now();
+        let _span = tracing::info_span!("model_load", format = "onnx").entered();
 
         let (model, symbol_values) = Self::load_onnx_using_tract(reader, run_args)?;
 
+        if let Some(o) = run_args
+            .rescale_overrides
+            .iter()
+            .find(|o| o.node >= model.nodes.len())
+        {
+            return Err(GraphError::InvalidRunArgs(format!(
+                "rescale override of node {} but the model has {} nodes",
+                o.node,
+                model.nodes.len()
+            )));
+        }
+
         let scales = VarScales::from_args(run_args);
-        let nodes = Self::nodes_from_graph(
-            &model,
-            run_args,
-            &scales,
-            visibility,
-            &symbol_values,
-            None,
-            None,
-        )?;
+        let nodes =
+            tracing::info_span!("quantization", nodes = model.nodes.len()).in_scope(|| {
+                Self::nodes_from_graph(
+                    &model,
+                    run_args,
+                    &scales,
+                    visibility,
+                    &symbol_values,
+                    None,
+                    None,
+                )
+            })?;
 
         debug!("\n {}", model);
 
-        let parsed_nodes = ParsedNodes {
+        let mut parsed_nodes = ParsedNodes {
             nodes,
             inputs: model.inputs.iter().map(|o| o.node).collect(),
             outputs: model.outputs.iter().map(|o| (o.node, o.slot)).collect(),
+            input_names: model
+                .inputs
+                .iter()
+                .map(|o| model.node(o.node).name.clone())
+                .collect(),
         };
+        parsed_nodes.fuse_embedding_bags();
+        parsed_nodes.fuse_norm_reductions();
+        parsed_nodes.fold_constant_scalings(run_args)?;
+        parsed_nodes.fold_negations();
+        parsed_nodes.fuse_add_lookups(run_args);
+        parsed_nodes.rescale_outputs(run_args)?;
+        parsed_nodes.mask_batch(run_args)?;
+        Self::empty_raw_values(&mut parsed_nodes.nodes);
 
         let duration = start_time.elapsed();
         trace!("model loading took: {:?}", duration);
@@ -860,6 +2169,11 @@ impl Model {
                         nodes: subgraph_nodes,
                         inputs: model.inputs.iter().map(|o| o.node).collect(),
                         outputs: model.outputs.iter().map(|o| (o.node, o.slot)).collect(),
+                        input_names: model
+                            .inputs
+                            .iter()
+                            .map(|o| model.node(o.node).name.clone())
+                            .collect(),
                     };
 
                     let om = Model {
@@ -890,6 +2204,7 @@ impl Model {
                             model: om,
                             inputs: n.inputs.iter().map(|i| (i.node, i.slot)).collect_vec(),
                             idx: i,
+                            name: n.name.clone(),
                             output_mappings,
                             input_mappings,
                             out_dims,
@@ -921,6 +2236,7 @@ impl Model {
                                     n.out_scale,
                                     1,
                                     run_args.div_rebasing,
+                                    run_args.div_rounding,
                                 )
                             } else {
                                 RebaseScale::rebase_up(
@@ -928,6 +2244,7 @@ impl Model {
                                     scales[&i],
                                     n.out_scale,
                                     run_args.div_rebasing,
+                                    run_args.div_rounding,
                                 )
                             };
                             n.out_scale = scales[&i];
@@ -939,21 +2256,48 @@ impl Model {
         }
         Self::remove_unused_nodes(&mut nodes);
 
+        Self::sparsify_constants(&mut nodes, run_args)?;
+
         Ok(nodes)
     }
 
+    #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+    /// Represents sparsely the private or fixed constants with at least the fraction of zeros of their visibility (see
+    /// [RunArgs::sparsity_threshold_of]), the constants of subgraphs are sparsified when their nodes are loaded
+    fn sparsify_constants(
+        nodes: &mut BTreeMap<usize, NodeType>,
+        run_args: &RunArgs,
+    ) -> Result<(), GraphError> {
+        for (idx, node) in nodes.iter_mut() {
+            if let NodeType::Node(n) = node {
+                if let SupportedOp::Constant(c) = &mut n.opkind {
+                    let threshold = c
+                        .quantized_values
+                        .visibility()
+                        .and_then(|v| run_args.sparsity_threshold_of(&v));
+                    let Some(threshold) = threshold else {
+                        continue;
+                    };
+                    if c.sparsify(threshold)? {
+                        debug!(
+                            "constant {} is sparse, {} of {} values are nonzero",
+                            idx,
+                            c.sparse_values.as_ref().map_or(0, |s| s.nnz()),
+                            c.quantized_values.len()
+                        );
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
     #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
     /// Removes all nodes that are consts with 0 uses
     fn remove_unused_nodes(nodes: &mut BTreeMap<usize, NodeType>) {
         // remove all nodes that are consts with 0 uses now
         nodes.retain(|_, n| match n {
-            NodeType::Node(n) => match &mut n.opkind {
-                SupportedOp::Constant(c) => {
-                    c.empty_raw_value();
-                    n.num_uses > 0
-                }
-                _ => n.num_uses > 0,
-            },
+            NodeType::Node(n) => n.num_uses > 0,
             NodeType::SubGraph { model, .. } => {
                 Self::remove_unused_nodes(&mut model.graph.nodes);
                 true
@@ -961,6 +2305,22 @@ impl Model {
         });
     }
 
+    #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+    /// Empties the raw values of the constants once the graph passes (which fold constant scalings into them) have
+    /// run, the circuit only needing their quantized values
+    fn empty_raw_values(nodes: &mut BTreeMap<usize, NodeType>) {
+        for node in nodes.values_mut() {
+            match node {
+                NodeType::Node(n) => {
+                    if let SupportedOp::Constant(c) = &mut n.opkind {
+                        c.empty_raw_value();
+                    }
+                }
+                NodeType::SubGraph { model, .. } => Self::empty_raw_values(&mut model.graph.nodes),
+            }
+        }
+    }
+
     #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
     /// Run tract onnx model on sample data !
     pub fn run_onnx_predictions(
@@ -999,6 +2359,50 @@ impl Model {
         Ok(outputs)
     }
 
+    #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+    /// Run tract onnx model on sample data, returning the value of every outlet of every node by node index and outlet.
+    /// Outlets whose values aren't numbers are left out.
+    pub fn run_onnx_node_outputs(
+        run_args: &RunArgs,
+        model_path: &std::path::Path,
+        data: &GraphData,
+        input_shapes: Vec<Vec<usize>>,
+    ) -> Result<BTreeMap<(usize, usize), Tensor<f32>>, GraphError> {
+        use tract_onnx::prelude::OutletId;
+        use tract_onnx::tract_core::internal::IntoArcTensor;
+
+        let mut file = std::fs::File::open(model_path).map_err(|e| {
+            GraphError::ReadWriteFileError(model_path.display().to_string(), e.to_string())
+        })?;
+
+        let (mut model, _) = Model::load_onnx_using_tract(&mut file, run_args)?;
+
+        let datum_types: Vec<DatumType> = model
+            .input_outlets()?
+            .iter()
+            .map(|o| model.node(o.node).outputs[o.slot].fact.datum_type)
+            .collect();
+
+        let outlets = model
+            .nodes()
+            .iter()
+            .flat_map(|n| (0..n.outputs.len()).map(move |slot| OutletId::new(n.id, slot)))
+            .collect::<Vec<_>>();
+        model.set_output_outlets(&outlets)?;
+
+        let runnable_model = model.into_runnable()?;
+        let result = runnable_model.run(data.to_tract_data(&input_shapes, &datum_types)?)?;
+        Ok(outlets
+            .into_iter()
+            .zip(result)
+            .filter_map(|(outlet, t)| {
+                crate::graph::utilities::extract_tensor_value(t.into_arc_tensor())
+                    .ok()
+                    .map(|t| ((outlet.node, outlet.slot), t))
+            })
+            .collect())
+    }
+
     /// Creates a `Model` from parsed run_args
     /// # Arguments
     /// * `params` - A [GraphSettings] struct holding parsed CLI arguments.
@@ -1010,60 +2414,6 @@ impl Model {
         Model::new(&mut file, run_args)
     }
 
-    /// Configures a model for the circuit
-    /// # Arguments
-    /// * `meta` - The constraint system.
-    /// * `vars` - The variables for the circuit.
-    /// * `settings` - [GraphSettings]
-    pub fn configure(
-        meta: &mut ConstraintSystem<Fp>,
-        vars: &ModelVars<Fp>,
-        settings: &GraphSettings,
-    ) -> Result<PolyConfig<Fp>, GraphError> {
-        debug!("configuring model");
-
-        let lookup_range = settings.run_args.lookup_range;
-        let logrows = settings.run_args.logrows as usize;
-        let required_lookups = settings.required_lookups.clone();
-        let required_range_checks = settings.required_range_checks.clone();
-
-        let mut base_gate = PolyConfig::configure(
-            meta,
-            vars.advices[0..2].try_into()?,
-            &vars.advices[2],
-            settings.check_mode,
-        );
-        // set scale for HybridOp::RangeCheck and call self.conf_lookup on that op for percentage tolerance case
-        let input = &vars.advices[0];
-        let output = &vars.advices[2];
-        let index = &vars.advices[1];
-        for op in required_lookups {
-            base_gate.configure_lookup(meta, input, output, index, lookup_range, logrows, &op)?;
-        }
-
-        for range in required_range_checks {
-            base_gate.configure_range_check(meta, input, index, range, logrows)?;
-        }
-
-        if settings.requires_dynamic_lookup() {
-            base_gate.configure_dynamic_lookup(
-                meta,
-                vars.advices[0..3].try_into()?,
-                vars.advices[3..6].try_into()?,
-            )?;
-        }
-
-        if settings.requires_shuffle() {
-            base_gate.configure_shuffles(
-                meta,
-                vars.advices[0..2].try_into()?,
-                vars.advices[3..5].try_into()?,
-            )?;
-        }
-
-        Ok(base_gate)
-    }
-
     /// Assigns values to the regions created when calling `configure`.
     /// # Arguments
     /// * `config` - [ModelConfig] holding all node configs.
@@ -1083,6 +2433,7 @@ impl Model {
         constants: &mut ConstantsMap<Fp>,
     ) -> Result<Vec<ValTensor<Fp>>, GraphError> {
         info!("model layout...");
+        set_layout_error(None);
 
         let start_time = instant::Instant::now();
 
@@ -1111,10 +2462,14 @@ impl Model {
         config.base.layout_range_checks(layouter)?;
 
         let original_constants = constants.clone();
+        // the planner lays the region out twice, once to measure its shape and once to assign it
+        let mut pass = 0;
 
         let outputs = layouter.assign_region(
             || "model",
             |region| {
+                let done = pass.min(1) as f64;
+                pass += 1;
                 let mut thread_safe_region = RegionCtx::new_with_constants(
                     region,
                     0,
@@ -1123,17 +2478,32 @@ impl Model {
                     run_args.decomp_legs,
                     original_constants.clone(),
                 );
+                thread_safe_region.set_constant_dedup_threshold(run_args.constant_dedup_threshold);
+                thread_safe_region.set_bit_range_checks(&run_args.bit_range_checks);
+                thread_safe_region.set_tight_lookup_ranges(run_args.tight_table_ranges());
                 // we need to do this as this loop is called multiple times
                 vars.set_instance_idx(instance_idx);
 
+                let mut observe = |fraction: f64| {
+                    progress::report(ProvingPhase::Synthesis, (done + fraction) / 2.0)
+                        .map_err(|_| GraphError::Cancelled)
+                };
                 let outputs = self
-                    .layout_nodes(&mut config, &mut thread_safe_region, &mut results)
+                    .layout_nodes_observed(
+                        &mut config,
+                        &mut thread_safe_region,
+                        &mut results,
+                        &mut observe,
+                    )
                     .map_err(|e| {
                         error!("{}", e);
+                        set_layout_error(Some(e));
                         halo2_proofs::plonk::Error::Synthesis
                     })?;
 
-                if run_args.output_visibility.is_public() || run_args.output_visibility.is_fixed() {
+                if run_args.any_output_visibility(|v| v.is_public() || v.is_fixed())
+                    || !run_args.revealed_outputs.is_empty()
+                {
                     let output_scales = self.graph.get_output_scales().map_err(|e| {
                         error!("{}", e);
                         halo2_proofs::plonk::Error::Synthesis
@@ -1141,11 +2511,17 @@ impl Model {
                     let res = outputs
                         .iter()
                         .enumerate()
+                        .filter(|(i, _)| {
+                            run_args.output_is_public(*i)
+                                || run_args.visibility_of_output(*i).is_fixed()
+                        })
                         .map(|(i, output)| {
                             let mut tolerance = run_args.tolerance;
                             tolerance.scale = scale_to_multiplier(output_scales[i]).into();
 
-                            let comparators = if run_args.output_visibility == Visibility::Public {
+                            // the revealed outputs are constrained to their instances as the public outputs, and
+                            // hashed by the modules after the model
+                            let comparators = if run_args.output_is_public(i) {
                                 let res = vars
                                     .instance
                                     .as_ref()
@@ -1161,6 +2537,16 @@ impl Model {
                                 witnessed_outputs[i].clone()
                             };
 
+                            if let Some(limbs) = Self::instance_limbs_of(run_args, i) {
+                                return Self::layout_limb_packed_output(
+                                    &config.base,
+                                    &mut thread_safe_region,
+                                    output,
+                                    comparators,
+                                    limbs,
+                                    run_args.instance_limb_bits(),
+                                );
+                            }
                             config
                                 .base
                                 .layout(
@@ -1176,9 +2562,29 @@ impl Model {
                         halo2_proofs::plonk::Error::Synthesis
                     })?;
                 }
+                self.layout_output_bounds(
+                    &config.base,
+                    &mut thread_safe_region,
+                    run_args,
+                    &outputs,
+                )
+                .map_err(|e| {
+                    error!("{}", e);
+                    halo2_proofs::plonk::Error::Synthesis
+                })?;
                 // Then number of columns in the circuits
                 #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
                 thread_safe_region.debug_report();
+                set_model_layout(ModelLayout {
+                    rows: thread_safe_region.row(),
+                    assignments: thread_safe_region.linear_coord(),
+                    lookups: thread_safe_region.used_lookups().into_iter().collect(),
+                    fused_add_lookups: thread_safe_region
+                        .used_fused_add_lookups()
+                        .into_iter()
+                        .collect(),
+                    range_checks: thread_safe_region.used_range_checks().into_iter().collect(),
+                });
 
                 *constants = thread_safe_region.assigned_constants().clone();
 
@@ -1192,11 +2598,75 @@ impl Model {
         Ok(outputs)
     }
 
+    /// The number of values of output `idx` packed to an instance, if it is packed (see [RunArgs::instance_limbs])
+    fn instance_limbs_of(run_args: &RunArgs, idx: usize) -> Option<usize> {
+        run_args
+            .instance_limbs
+            .filter(|_| run_args.output_is_public(idx))
+    }
+
+    /// Constrains the instances of a public output packed several values to an instance to the packing of its values,
+    /// see [crate::graph::limbs]
+    fn layout_limb_packed_output(
+        config: &PolyConfig<Fp>,
+        region: &mut RegionCtx<Fp>,
+        output: &ValTensor<Fp>,
+        instances: ValTensor<Fp>,
+        limbs: usize,
+        limb_bits: usize,
+    ) -> Result<Option<ValTensor<Fp>>, GraphError> {
+        let packed = layouts::pack_limbs(config, region, &[output.clone()], limbs, limb_bits)?;
+        Ok(Some(layouts::enforce_equality(
+            config,
+            region,
+            &[packed, instances],
+        )?))
+    }
+
+    /// Asserts the bounds declared in the run args on the outputs of the model
+    fn layout_output_bounds(
+        &self,
+        config: &PolyConfig<Fp>,
+        region: &mut RegionCtx<Fp>,
+        run_args: &RunArgs,
+        outputs: &[ValTensor<Fp>],
+    ) -> Result<(), GraphError> {
+        if run_args.output_bounds.is_empty() {
+            return Ok(());
+        }
+        let output_scales = self.graph.get_output_scales()?;
+        for bound in &run_args.output_bounds {
+            let (lower, upper) = bound.quantized(output_scales[bound.output]);
+            layouts::enforce_bounds(
+                config,
+                region,
+                &[outputs[bound.output].clone()],
+                lower,
+                upper,
+            )?;
+        }
+        Ok(())
+    }
+
     fn layout_nodes(
         &self,
         config: &mut ModelConfig,
         region: &mut RegionCtx<Fp>,
         results: &mut BTreeMap<usize, Vec<ValTensor<Fp>>>,
+    ) -> Result<Vec<ValTensor<Fp>>, GraphError> {
+        // the forward pass reports no progress, but stops between its nodes once cancelled
+        self.layout_nodes_observed(config, region, results, &mut |_| {
+            progress::check_cancelled().map_err(|_| GraphError::Cancelled)
+        })
+    }
+
+    /// Lays out the nodes, calling `observe` with the fraction of the nodes laid out before each of them
+    fn layout_nodes_observed(
+        &self,
+        config: &mut ModelConfig,
+        region: &mut RegionCtx<Fp>,
+        results: &mut BTreeMap<usize, Vec<ValTensor<Fp>>>,
+        observe: &mut dyn FnMut(f64) -> Result<(), GraphError>,
     ) -> Result<Vec<ValTensor<Fp>>, GraphError> {
         // index over results to get original inputs
         let orig_inputs: BTreeMap<usize, _> = results
@@ -1205,7 +2675,9 @@ impl Model {
             .filter(|(idx, _)| self.graph.inputs.contains(idx))
             .collect();
 
-        for (idx, node) in self.graph.nodes.iter() {
+        let num_nodes = self.graph.nodes.len();
+        for (i, (idx, node)) in self.graph.nodes.iter().enumerate() {
+            observe(i as f64 / num_nodes as f64)?;
             debug!("laying out {}: {}", idx, node.as_str(),);
             // Then number of columns in the circuits
             #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
@@ -1239,6 +2711,13 @@ impl Model {
 
             match &node {
                 NodeType::Node(n) => {
+                    // the constants that aren't pre-assigned are laid out once for identical values
+                    let dedup_values = match &n.opkind {
+                        SupportedOp::Constant(c) if c.pre_assigned_val.is_none() => {
+                            Some(&c.quantized_values)
+                        }
+                        _ => None,
+                    };
                     let res = if node.is_constant() && node.num_uses() == 1 {
                         log::debug!("node {} is a constant with 1 use", n.idx);
                         let mut node = n.clone();
@@ -1246,15 +2725,35 @@ impl Model {
                             .opkind
                             .get_mutable_constant()
                             .ok_or(GraphError::MissingConstants)?;
-                        Some(c.quantized_values.clone().try_into()?)
+                        Some(c.quantized_val_tensor()?)
+                    } else if let Some(cached) =
+                        dedup_values.and_then(|values| region.cached_constant(values))
+                    {
+                        debug!("node {} reuses the cells of an identical constant", n.idx);
+                        Some(cached)
                     } else {
-                        config
+                        region.set_current_node(n.idx, &n.name, n.opkind.as_string());
+                        let mark = region.profile_mark();
+                        let res = config
                             .base
                             .layout(region, &values, n.opkind.clone_dyn())
-                            .map_err(|e| {
-                                error!("{}", e);
-                                halo2_proofs::plonk::Error::Synthesis
-                            })?
+                            .map_err(|e| match e {
+                                CircuitError::IntegerRepOverflow(felt, op) => {
+                                    GraphError::IntegerRepOverflow(felt, op, n.idx)
+                                }
+                                e @ CircuitError::RowOverflow { .. } => e.into(),
+                                e => {
+                                    error!("{}", e);
+                                    halo2_proofs::plonk::Error::Synthesis.into()
+                                }
+                            })?;
+                        if let Some(mark) = mark {
+                            region.record_op_profile(mark, n.idx, n.opkind.as_string());
+                        }
+                        if let (Some(values), Some(assigned)) = (dedup_values, &res) {
+                            region.cache_constant(values, assigned);
+                        }
+                        res
                     };
 
                     if let Some(mut vt) = res {
@@ -1420,43 +2919,65 @@ impl Model {
         };
 
         let mut region = RegionCtx::new_dummy(0, run_args.num_inner_cols, region_settings);
+        region.set_constant_dedup_threshold(run_args.constant_dedup_threshold);
+        region.set_bit_range_checks(&run_args.bit_range_checks);
+        region.set_tight_lookup_ranges(run_args.tight_table_ranges());
 
         let outputs = self.layout_nodes(&mut model_config, &mut region, &mut results)?;
 
-        if self.visibility.output.is_public() || self.visibility.output.is_fixed() {
-            let output_scales = self.graph.get_output_scales()?;
-            let res = outputs
-                .iter()
-                .enumerate()
-                .map(|(i, output)| {
-                    let mut comparator: ValTensor<Fp> = (0..output.len())
-                        .map(|_| {
-                            if !self.visibility.output.is_fixed() {
-                                ValType::Value(Value::<Fp>::unknown())
-                            } else {
-                                ValType::Constant(Fp::random(&mut rand::thread_rng()))
-                            }
-                        })
-                        .collect::<Vec<_>>()
-                        .into();
-                    comparator.reshape(output.dims())?;
-
-                    let mut tolerance = run_args.tolerance;
-                    tolerance.scale = scale_to_multiplier(output_scales[i]).into();
-
-                    dummy_config.layout(
-                        &mut region,
-                        &[output.clone(), comparator],
-                        Box::new(HybridOp::RangeCheck(tolerance)),
-                    )
-                })
-                .collect::<Result<Vec<_>, _>>();
-            res?;
-        } else if !self.visibility.output.is_private() {
-            for output in &outputs {
+        let output_scales = self.graph.get_output_scales()?;
+        for (i, output) in outputs.iter().enumerate() {
+            let visibility = self.visibility.output_of(i);
+            let public = visibility.is_public() || run_args.revealed_outputs.contains(&i);
+            if public || visibility.is_fixed() {
+                let mut comparator: ValTensor<Fp> = (0..output.len())
+                    .map(|_| {
+                        if !visibility.is_fixed() {
+                            ValType::Value(Value::<Fp>::unknown())
+                        } else {
+                            ValType::Constant(Fp::random(&mut rand::thread_rng()))
+                        }
+                    })
+                    .collect::<Vec<_>>()
+                    .into();
+                comparator.reshape(output.dims())?;
+
+                let mut tolerance = run_args.tolerance;
+                tolerance.scale = scale_to_multiplier(output_scales[i]).into();
+
+                let mark = region.profile_mark();
+                let op = HybridOp::RangeCheck(tolerance);
+                match Self::instance_limbs_of(run_args, i) {
+                    Some(limbs) => {
+                        let mut packed = comparator.clone();
+                        packed.flatten();
+                        packed = packed.get_slice(&[0..output.len().div_ceil(limbs)])?;
+                        Self::layout_limb_packed_output(
+                            &dummy_config,
+                            &mut region,
+                            output,
+                            packed,
+                            limbs,
+                            run_args.instance_limb_bits(),
+                        )?;
+                    }
+                    None => {
+                        dummy_config.layout(
+                            &mut region,
+                            &[output.clone(), comparator],
+                            Box::new(op.clone()),
+                        )?;
+                    }
+                }
+                if let Some(mark) = mark {
+                    let node = self.graph.outputs[i].0;
+                    region.record_op_profile(mark, node, Op::<Fp>::as_string(&op));
+                }
+            } else if !visibility.is_private() {
                 region.update_constants(output.create_constants_map());
             }
         }
+        self.layout_output_bounds(&dummy_config, &mut region, run_args, &outputs)?;
 
         let duration = start_time.elapsed();
         trace!("dummy model layout took: {:?}", duration);
@@ -1479,20 +3000,70 @@ impl Model {
             max_dynamic_input_len: region.max_dynamic_input_len(),
             total_const_size: region.total_constants(),
             lookup_ops: region.used_lookups(),
+            fused_add_lookup_ops: region.used_fused_add_lookups(),
             range_checks: region.used_range_checks(),
+            range_check_counts: region.range_check_counts(),
             max_lookup_inputs: region.max_lookup_inputs(),
             min_lookup_inputs: region.min_lookup_inputs(),
             max_range_size: region.max_range_size(),
+            tight_lookup_ranges: region.tight_lookup_ranges(),
             num_dynamic_lookups: region.dynamic_lookup_index(),
             dynamic_lookup_col_coord: region.dynamic_lookup_col_coord(),
             num_shuffles: region.shuffle_index(),
             shuffle_col_coord: region.shuffle_col_coord(),
             outputs,
+            num_lookups: region.num_lookups(),
+            num_equalities: region.num_equalities(),
+            op_profiles: region.op_profiles().to_vec(),
+            deduped_constant_assignments: region.deduped_constant_assignments(),
         };
 
         Ok(res)
     }
 
+    /// Runs the quantized forward pass, returning the output of every node that isn't a constant by node index and
+    /// outlet
+    pub fn node_outputs(
+        &self,
+        run_args: &RunArgs,
+        inputs: &[Tensor<Fp>],
+        region_settings: RegionSettings,
+    ) -> Result<BTreeMap<(usize, usize), Tensor<Fp>>, GraphError> {
+        let mut results = BTreeMap::<usize, Vec<ValTensor<Fp>>>::new();
+        for (input_idx, input) in self.graph.inputs.iter().zip(inputs) {
+            let input = input.map(|elem| ValType::Value(Value::known(elem)));
+            results.insert(*input_idx, vec![input.into()]);
+        }
+
+        let mut model_config = ModelConfig {
+            base: PolyConfig::dummy(run_args.logrows as usize, run_args.num_inner_cols),
+            vars: ModelVars::new_dummy(),
+        };
+        let mut region = RegionCtx::new_dummy(0, run_args.num_inner_cols, region_settings);
+        region.set_constant_dedup_threshold(run_args.constant_dedup_threshold);
+        region.set_bit_range_checks(&run_args.bit_range_checks);
+        region.set_tight_lookup_ranges(run_args.tight_table_ranges());
+        self.layout_nodes(&mut model_config, &mut region, &mut results)?;
+
+        Ok(results
+            .into_iter()
+            .filter(|(idx, _)| {
+                self.graph
+                    .nodes
+                    .get(idx)
+                    .is_some_and(|node| !node.is_constant())
+            })
+            .flat_map(|(idx, outlets)| {
+                outlets
+                    .into_iter()
+                    .enumerate()
+                    .filter_map(move |(outlet, v)| {
+                        v.get_felt_evals().ok().map(|t| ((idx, outlet), t))
+                    })
+            })
+            .collect())
+    }
+
     /// Retrieves all constants from the model.
     pub fn get_all_params(&self) -> Vec<Tensor<Fp>> {
         let mut params = vec![];
@@ -1529,6 +3100,34 @@ impl Model {
         const_shapes
     }
 
+    /// The nodes each constant of the computational graph is grouped by in the commitment groups of the params (in
+    /// order of indexing): the node of the constant and those it is an input of, or the node of the subgraph a
+    /// constant is in
+    pub fn const_nodes(&self) -> Vec<Vec<usize>> {
+        let mut const_nodes = vec![];
+        for (idx, node) in self.graph.nodes.iter() {
+            match node {
+                NodeType::Node(_) => {
+                    if extract_const_quantized_values(node.opkind()).is_some() {
+                        let mut nodes = vec![*idx];
+                        nodes.extend(
+                            self.graph
+                                .nodes
+                                .iter()
+                                .filter(|(_, n)| n.inputs().iter().any(|(input, _)| input == idx))
+                                .map(|(consumer, _)| *consumer),
+                        );
+                        const_nodes.push(nodes);
+                    }
+                }
+                NodeType::SubGraph { model, .. } => {
+                    const_nodes.extend(vec![vec![*idx]; model.const_shapes().len()]);
+                }
+            }
+        }
+        const_nodes
+    }
+
     /// Replaces all constants in the model with the provided values (in order of indexing), returns the number of consts
     pub fn replace_consts(&mut self, consts: &[ValTensor<Fp>]) -> usize {
         let mut const_idx = 0;
@@ -1556,14 +3155,896 @@ impl Model {
     }
 
     /// Shapes of the computational graph's public inputs (if any)
-    pub fn instance_shapes(&self) -> Result<Vec<Vec<usize>>, GraphError> {
+    pub fn instance_shapes(&self, run_args: &RunArgs) -> Result<Vec<Vec<usize>>, GraphError> {
         let mut instance_shapes = vec![];
         if self.visibility.input.is_public() {
             instance_shapes.extend(self.graph.input_shapes()?);
         }
-        if self.visibility.output.is_public() {
-            instance_shapes.extend(self.graph.output_shapes()?);
+        let output_shapes = self.graph.output_shapes()?;
+        for (i, shape) in output_shapes.into_iter().enumerate() {
+            if self.visibility.output_of(i).is_public() || run_args.revealed_outputs.contains(&i) {
+                instance_shapes.push(shape);
+            }
         }
         Ok(instance_shapes)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuit::Constant;
+    use crate::fieldutils::integer_rep_to_felt;
+
+    const LEN: usize = 32;
+
+    fn node(idx: usize, opkind: SupportedOp, inputs: Vec<Outlet>, num_uses: usize) -> NodeType {
+        NodeType::Node(Node {
+            opkind,
+            out_scale: 0,
+            inputs,
+            out_dims: vec![LEN],
+            idx,
+            num_uses,
+            name: format!("node_{}", idx),
+            out_boolean: false,
+        })
+    }
+
+    fn mask() -> SupportedOp {
+        let raw = Tensor::from((0..LEN).map(|i| (i % 2) as f32));
+        let mut quantized = raw.map(|x| Fp::from(x as u64));
+        quantized.set_visibility(&Visibility::Fixed);
+        quantized.set_scale(0);
+        SupportedOp::Constant(Constant::new(quantized, raw))
+    }
+
+    /// The input added to two identical masks, each used twice
+    fn model() -> Model {
+        let add = || SupportedOp::Linear(PolyOp::Add);
+        let input = SupportedOp::Input(Input {
+            scale: 0,
+            datum_type: InputType::Int,
+        });
+        let nodes = BTreeMap::from([
+            (0, node(0, input, vec![], 4)),
+            (1, node(1, mask(), vec![], 2)),
+            (2, node(2, mask(), vec![], 2)),
+            (3, node(3, add(), vec![(0, 0), (1, 0)], 1)),
+            (4, node(4, add(), vec![(0, 0), (1, 0)], 1)),
+            (5, node(5, add(), vec![(0, 0), (2, 0)], 1)),
+            (6, node(6, add(), vec![(0, 0), (2, 0)], 1)),
+        ]);
+        Model {
+            graph: ParsedNodes {
+                nodes,
+                inputs: vec![0],
+                outputs: vec![(3, 0), (4, 0), (5, 0), (6, 0)],
+                input_names: vec![],
+            },
+            visibility: VarVisibility {
+                input: Visibility::Private,
+                params: Visibility::Fixed,
+                output: Visibility::Private,
+                outputs: vec![],
+            },
+        }
+    }
+
+    #[test]
+    fn identical_constants_laid_out_once() {
+        let model = model();
+        let inputs = vec![ValTensor::from(Tensor::from(
+            (0..LEN).map(|i| Value::known(Fp::from(i as u64))),
+        ))];
+        let layout = |threshold: usize| {
+            let run_args = RunArgs {
+                constant_dedup_threshold: threshold,
+                ..Default::default()
+            };
+            let settings = RegionSettings::all_true(run_args.decomp_base, run_args.decomp_legs);
+            model.dummy_layout(&run_args, &inputs, settings).unwrap()
+        };
+
+        let deduped = layout(LEN);
+        let assigned = layout(0);
+        // the second mask reuses the cells of the first instead of being assigned
+        assert_eq!(deduped.deduped_constant_assignments, LEN);
+        assert_eq!(assigned.deduped_constant_assignments, 0);
+        assert_eq!(assigned.linear_coord - deduped.linear_coord, LEN);
+        assert_eq!(deduped.outputs, assigned.outputs);
+        // constants smaller than the threshold are assigned each time
+        assert_eq!(layout(LEN + 1).deduped_constant_assignments, 0);
+    }
+
+    #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+    #[test]
+    fn fixed_params_sparsified_from_their_threshold() {
+        let half_zeros = (0..LEN).map(|i| (i % 2) as f32).collect::<Vec<_>>();
+        let sparsified = |run_args: &RunArgs| {
+            let mut nodes = BTreeMap::from([
+                (0, constant(0, half_zeros.clone(), Visibility::Fixed)),
+                (1, constant(1, half_zeros.clone(), Visibility::Private)),
+            ]);
+            Model::sparsify_constants(&mut nodes, run_args).unwrap();
+            nodes
+                .values()
+                .map(|node| match node {
+                    NodeType::Node(Node {
+                        opkind: SupportedOp::Constant(c),
+                        ..
+                    }) => c.sparse_values.is_some(),
+                    _ => unreachable!(),
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // params are dense unless a threshold is set
+        assert_eq!(sparsified(&RunArgs::default()), vec![false, false]);
+        // the positions of the zeros of fixed params are in the circuit anyway, those of private params would become
+        // public in the verifying key and are only sparsified from sparsity_threshold
+        assert_eq!(
+            sparsified(&RunArgs {
+                fixed_sparsity_threshold: Some(0.5),
+                ..Default::default()
+            }),
+            vec![true, false]
+        );
+        assert_eq!(
+            sparsified(&RunArgs {
+                sparsity_threshold: Some(0.5),
+                ..Default::default()
+            }),
+            vec![true, true]
+        );
+    }
+
+    #[test]
+    fn embedding_bags_fused() {
+        let with_dims = |mut node: NodeType, dims: Vec<usize>| {
+            if let NodeType::Node(n) = &mut node {
+                n.out_dims = dims;
+            }
+            node
+        };
+        let input = || {
+            SupportedOp::Input(Input {
+                scale: 0,
+                datum_type: InputType::F32,
+            })
+        };
+        let gather = SupportedOp::Hybrid(HybridOp::Gather {
+            dim: 0,
+            constant_idx: None,
+        });
+        let sum = SupportedOp::Linear(PolyOp::Sum { axes: vec![1] });
+        let graph = ParsedNodes {
+            nodes: BTreeMap::from([
+                (0, with_dims(node(0, input(), vec![], 1), vec![6, 3])),
+                (1, with_dims(node(1, input(), vec![], 1), vec![2, 4])),
+                (
+                    2,
+                    with_dims(node(2, gather, vec![(0, 0), (1, 0)], 1), vec![2, 4, 3]),
+                ),
+                (3, with_dims(node(3, sum, vec![(2, 0)], 1), vec![2, 1, 3])),
+            ]),
+            inputs: vec![0, 1],
+            outputs: vec![(3, 0)],
+            input_names: vec![],
+        };
+
+        let mut fused = graph.clone();
+        fused.fuse_embedding_bags();
+        assert_eq!(fused.nodes.len(), 3);
+        let NodeType::Node(bag) = &fused.nodes[&3] else {
+            panic!("the sum isn't a node");
+        };
+        assert!(matches!(
+            bag.opkind,
+            SupportedOp::Hybrid(HybridOp::EmbeddingBag {
+                mode: EmbeddingBagMode::Sum,
+                bag_size: 4
+            })
+        ));
+        assert_eq!(bag.inputs, vec![(0, 0), (1, 0)]);
+
+        // a gather that is also an output is kept
+        let mut output_gather = ParsedNodes {
+            outputs: vec![(2, 0), (3, 0)],
+            ..graph
+        };
+        let unfused = output_gather.clone();
+        output_gather.fuse_embedding_bags();
+        assert_eq!(output_gather, unfused);
+    }
+
+    #[test]
+    fn norm_reductions_fused() {
+        let input = SupportedOp::Input(Input {
+            scale: 0,
+            datum_type: InputType::F32,
+        });
+        let sum = || SupportedOp::Linear(PolyOp::Sum { axes: vec![1] });
+        let sqrt = SupportedOp::Nonlinear(LookupOp::Sqrt { scale: 1.0.into() });
+        // the ReduceL1 and ReduceL2 of the input, as tract expands them
+        let graph = ParsedNodes {
+            nodes: BTreeMap::from([
+                (0, node(0, input, vec![], 2)),
+                (
+                    1,
+                    node(1, SupportedOp::Linear(PolyOp::Abs), vec![(0, 0)], 1),
+                ),
+                (2, node(2, sum(), vec![(1, 0)], 1)),
+                (
+                    3,
+                    node(3, SupportedOp::Linear(PolyOp::Pow(2)), vec![(0, 0)], 1),
+                ),
+                (4, node(4, sum(), vec![(3, 0)], 1)),
+                (5, node(5, sqrt, vec![(4, 0)], 1)),
+            ]),
+            inputs: vec![0],
+            outputs: vec![(2, 0), (5, 0)],
+            input_names: vec![],
+        };
+
+        let mut fused = graph.clone();
+        fused.fuse_norm_reductions();
+        assert_eq!(
+            fused.nodes.keys().copied().collect::<Vec<_>>(),
+            vec![0, 2, 5]
+        );
+        for (idx, order) in [(2, NormOrder::L1), (5, NormOrder::L2)] {
+            let NodeType::Node(norm) = &fused.nodes[&idx] else {
+                panic!("the norm isn't a node");
+            };
+            assert!(
+                matches!(
+                    &norm.opkind,
+                    SupportedOp::Hybrid(HybridOp::NormReduce { p, axes, keepdims: true, scale })
+                        if *p == order && axes[..] == [1] && scale.0 == 1.0
+                ),
+                "{:?}",
+                norm.opkind
+            );
+            assert_eq!(norm.inputs, vec![(0, 0)]);
+        }
+
+        // a sum of squares that is also an output is kept, as is a norm not at the scale of its input
+        let mut output_sum = ParsedNodes {
+            outputs: vec![(2, 0), (4, 0), (5, 0)],
+            ..graph.clone()
+        };
+        output_sum.fuse_norm_reductions();
+        assert_eq!(output_sum.nodes.len(), 5);
+        let mut rescaled = graph;
+        if let Some(NodeType::Node(sqrt)) = rescaled.nodes.get_mut(&5) {
+            sqrt.out_scale = 1;
+        }
+        rescaled.fuse_norm_reductions();
+        assert_eq!(rescaled.nodes.len(), 5);
+    }
+
+    #[test]
+    fn negations_folded() {
+        let input = || {
+            SupportedOp::Input(Input {
+                scale: 0,
+                datum_type: InputType::Int,
+            })
+        };
+        let (neg, add, sub) = (
+            || SupportedOp::Linear(PolyOp::Neg),
+            || SupportedOp::Linear(PolyOp::Add),
+            SupportedOp::Linear(PolyOp::Sub),
+        );
+        // x + -y, -x + y and x - -y
+        let graph = ParsedNodes {
+            nodes: BTreeMap::from([
+                (0, node(0, input(), vec![], 3)),
+                (1, node(1, input(), vec![], 3)),
+                (2, node(2, neg(), vec![(1, 0)], 1)),
+                (3, node(3, add(), vec![(0, 0), (2, 0)], 1)),
+                (4, node(4, neg(), vec![(0, 0)], 1)),
+                (5, node(5, add(), vec![(4, 0), (1, 0)], 1)),
+                (6, node(6, neg(), vec![(1, 0)], 1)),
+                (7, node(7, sub, vec![(0, 0), (6, 0)], 1)),
+            ]),
+            inputs: vec![0, 1],
+            outputs: vec![(3, 0), (5, 0), (7, 0)],
+            input_names: vec![],
+        };
+
+        let mut folded = graph.clone();
+        folded.fold_negations();
+        assert_eq!(
+            folded.nodes.keys().copied().collect::<Vec<_>>(),
+            vec![0, 1, 3, 5, 7]
+        );
+        for (idx, op, inputs) in [
+            (3, "SUB", vec![(0, 0), (1, 0)]),
+            (5, "SUB", vec![(1, 0), (0, 0)]),
+            (7, "ADD", vec![(0, 0), (1, 0)]),
+        ] {
+            let NodeType::Node(n) = &folded.nodes[&idx] else {
+                panic!("node {} isn't a node", idx);
+            };
+            assert_eq!(n.opkind.as_string(), op);
+            assert_eq!(n.inputs, inputs);
+        }
+
+        let model = |graph: ParsedNodes| Model {
+            graph,
+            visibility: VarVisibility {
+                input: Visibility::Private,
+                params: Visibility::Fixed,
+                output: Visibility::Private,
+                outputs: vec![],
+            },
+        };
+        let run_args = RunArgs::default();
+        let values = |offset: IntegerRep| {
+            ValTensor::from(Tensor::from(
+                (0..LEN as IntegerRep).map(|i| Value::known(integer_rep_to_felt(i - offset))),
+            ))
+        };
+        let inputs = vec![values(20), values(7)];
+        let layout = |graph: &ParsedNodes| {
+            let settings = RegionSettings::all_true(run_args.decomp_base, run_args.decomp_legs);
+            model(graph.clone())
+                .dummy_layout(&run_args, &inputs, settings)
+                .unwrap()
+        };
+        let (folded_res, unfolded_res) = (layout(&folded), layout(&graph));
+        assert_eq!(folded_res.outputs, unfolded_res.outputs);
+        // the negations take no rows
+        assert_eq!(folded_res.linear_coord + 3 * LEN, unfolded_res.linear_coord);
+
+        // a negation that is also an output or used twice is kept
+        let mut output_neg = ParsedNodes {
+            outputs: vec![(2, 0), (3, 0), (5, 0), (7, 0)],
+            ..graph.clone()
+        };
+        output_neg.fold_negations();
+        assert!(output_neg.nodes.contains_key(&2));
+        let mut reused = graph.clone();
+        if let Some(NodeType::Node(n)) = reused.nodes.get_mut(&4) {
+            n.num_uses = 2;
+        }
+        reused.fold_negations();
+        assert!(reused.nodes.contains_key(&4));
+        assert_eq!(reused.nodes.len(), 6);
+    }
+
+    /// A constant of scale 7 used once
+    fn constant(idx: usize, raw: Vec<f32>, visibility: Visibility) -> NodeType {
+        let raw = Tensor::from(raw.into_iter());
+        let mut quantized = raw.map(|x| integer_rep_to_felt((x * 128.0).round() as IntegerRep));
+        quantized.set_visibility(&visibility);
+        quantized.set_scale(7);
+        let dims = raw.dims().to_vec();
+        let mut c = node(
+            idx,
+            SupportedOp::Constant(Constant::new(quantized, raw)),
+            vec![],
+            1,
+        );
+        c.bump_scale(7);
+        if let NodeType::Node(n) = &mut c {
+            n.out_dims = dims;
+        }
+        c
+    }
+
+    /// An input of scale 7 normalized as an image, times 0.5 (an identity reinterpreting it at scale 8), times 255
+    /// and divided by 127.5, then added to the input
+    fn normalized_input(visibility: Visibility) -> ParsedNodes {
+        let run_args = RunArgs::default();
+        let input = SupportedOp::Input(Input {
+            scale: 7,
+            datum_type: InputType::F32,
+        });
+        let rebased_mult = |scale| {
+            RebaseScale::rebase(
+                SupportedOp::Linear(PolyOp::Mult),
+                7,
+                scale,
+                1,
+                run_args.div_rebasing,
+                run_args.div_rounding,
+            )
+        };
+        let scaled = |mut node: NodeType, scale| {
+            node.bump_scale(scale);
+            node
+        };
+        let half = SupportedOp::Linear(PolyOp::Identity { out_scale: Some(8) });
+        ParsedNodes {
+            nodes: BTreeMap::from([
+                (0, scaled(node(0, input, vec![], 2), 7)),
+                (1, scaled(node(1, half, vec![(0, 0)], 1), 8)),
+                (2, constant(2, vec![255.0], visibility.clone())),
+                (
+                    3,
+                    scaled(node(3, rebased_mult(15), vec![(1, 0), (2, 0)], 1), 7),
+                ),
+                (4, constant(4, vec![1.0 / 127.5], visibility)),
+                (
+                    5,
+                    scaled(node(5, rebased_mult(14), vec![(3, 0), (4, 0)], 1), 7),
+                ),
+                (
+                    6,
+                    scaled(
+                        node(6, SupportedOp::Linear(PolyOp::Add), vec![(0, 0), (5, 0)], 1),
+                        7,
+                    ),
+                ),
+            ]),
+            inputs: vec![0],
+            outputs: vec![(6, 0)],
+            input_names: vec![],
+        }
+    }
+
+    #[test]
+    fn constant_scalings_folded() {
+        let run_args = RunArgs {
+            fold_constant_scalings: true,
+            ..Default::default()
+        };
+        let graph = normalized_input(Visibility::Fixed);
+        let mut folded = graph.clone();
+        folded.fold_constant_scalings(&run_args).unwrap();
+
+        // the normalization leaves the input as it is, and the input is added to itself
+        let mut expected = graph.clone();
+        for idx in 1..6 {
+            expected.nodes.remove(&idx);
+        }
+        if let Some(NodeType::Node(add)) = expected.nodes.get_mut(&6) {
+            add.inputs = vec![(0, 0), (0, 0)];
+        }
+        assert_eq!(folded, expected);
+
+        let model = |graph: ParsedNodes| Model {
+            graph,
+            visibility: VarVisibility {
+                input: Visibility::Private,
+                params: Visibility::Fixed,
+                output: Visibility::Private,
+                outputs: vec![],
+            },
+        };
+        let values = (0..LEN as IntegerRep).map(|i| Value::known(integer_rep_to_felt(i - 20)));
+        let inputs = vec![ValTensor::from(Tensor::from(values))];
+        let layout = |graph: &ParsedNodes| {
+            let settings = RegionSettings::all_true(run_args.decomp_base, run_args.decomp_legs);
+            model(graph.clone())
+                .dummy_layout(&run_args, &inputs, settings)
+                .unwrap()
+        };
+        let (folded_res, unfolded_res) = (layout(&folded), layout(&graph));
+        let doubled = (0..LEN as IntegerRep)
+            .map(|i| integer_rep_to_felt(2 * (i - 20)))
+            .collect::<Vec<_>>();
+        assert_eq!(folded_res.outputs[0][..], doubled[..]);
+        // the multiplications and their rescales take no rows
+        assert!(folded_res.linear_coord < unfolded_res.linear_coord);
+
+        // nor are they folded unless asked to
+        let mut unfolded = graph;
+        unfolded
+            .fold_constant_scalings(&RunArgs::default())
+            .unwrap();
+        assert_eq!(unfolded, normalized_input(Visibility::Fixed));
+    }
+
+    #[test]
+    fn constant_scalings_kept() {
+        let run_args = RunArgs {
+            fold_constant_scalings: true,
+            ..Default::default()
+        };
+        // the constants committed to are kept, the identity being alone
+        let mut committed = normalized_input(Visibility::KZGCommit);
+        committed.fold_constant_scalings(&run_args).unwrap();
+        assert_eq!(committed, normalized_input(Visibility::KZGCommit));
+
+        // a multiplication that is also an output ends the chain before it, which multiplies by a single constant
+        let mut output = ParsedNodes {
+            outputs: vec![(6, 0), (3, 0)],
+            ..normalized_input(Visibility::Fixed)
+        };
+        output.fold_constant_scalings(&run_args).unwrap();
+        assert_eq!(
+            output.nodes.keys().copied().collect::<Vec<_>>(),
+            vec![0, 2, 3, 4, 5, 6]
+        );
+        let NodeType::Node(mult) = &output.nodes[&3] else {
+            panic!("the multiplication isn't a node");
+        };
+        assert_eq!(mult.inputs, vec![(0, 0), (2, 0)]);
+        assert!(
+            matches!(&mult.opkind, SupportedOp::RebaseScale(r) if r.multiplier == 128.0),
+            "{:?}",
+            mult.opkind
+        );
+        let NodeType::Node(c) = &output.nodes[&2] else {
+            panic!("the constant isn't a node");
+        };
+        let c = c.opkind.get_constant().unwrap();
+        assert_eq!(c.raw_values[..], [127.5]);
+        assert_eq!(
+            c.quantized_values[..],
+            [integer_rep_to_felt(127 * 128 + 64)]
+        );
+    }
+
+    #[test]
+    fn constant_scalings_folded_into_weights() {
+        let run_args = RunArgs {
+            fold_constant_scalings: true,
+            ..Default::default()
+        };
+        let input = SupportedOp::Input(Input {
+            scale: 7,
+            datum_type: InputType::F32,
+        });
+        let rebased = |op, scale| {
+            RebaseScale::rebase(
+                op,
+                7,
+                scale,
+                1,
+                run_args.div_rebasing,
+                run_args.div_rounding,
+            )
+        };
+        let mult = rebased(SupportedOp::Linear(PolyOp::Mult), 14);
+        let einsum = rebased(
+            SupportedOp::Linear(PolyOp::Einsum {
+                equation: "i,i->i".into(),
+            }),
+            14,
+        );
+        let weights = (0..LEN).map(|i| i as f32 / 8.0).collect::<Vec<_>>();
+        // the input tripled before it is multiplied by the weights
+        let mut graph = ParsedNodes {
+            nodes: BTreeMap::from([
+                (0, node(0, input, vec![], 1)),
+                (1, constant(1, vec![3.0], Visibility::Private)),
+                (2, node(2, mult, vec![(0, 0), (1, 0)], 1)),
+                (3, constant(3, weights, Visibility::Private)),
+                (4, node(4, einsum, vec![(2, 0), (3, 0)], 1)),
+            ]),
+            inputs: vec![0],
+            outputs: vec![(4, 0)],
+            input_names: vec![],
+        };
+        for idx in [0, 2, 4] {
+            if let Some(node) = graph.nodes.get_mut(&idx) {
+                node.bump_scale(7);
+            }
+        }
+
+        graph.fold_constant_scalings(&run_args).unwrap();
+        assert_eq!(
+            graph.nodes.keys().copied().collect::<Vec<_>>(),
+            vec![0, 3, 4]
+        );
+        assert_eq!(graph.nodes[&4].inputs(), vec![(0, 0), (3, 0)]);
+        let c = graph.nodes[&3].opkind();
+        let c = c.get_constant().unwrap();
+        let tripled = (0..LEN as IntegerRep)
+            .map(|i| integer_rep_to_felt(i * 48))
+            .collect::<Vec<_>>();
+        assert_eq!(c.quantized_values[..], tripled[..]);
+    }
+
+    /// A residual block: the input plus the input masked, activated with a lookup
+    fn residual_block() -> Model {
+        let input = SupportedOp::Input(Input {
+            scale: 0,
+            datum_type: InputType::Int,
+        });
+        let (mult, add) = (
+            SupportedOp::Linear(PolyOp::Mult),
+            SupportedOp::Linear(PolyOp::Add),
+        );
+        let relu = SupportedOp::Nonlinear(LookupOp::LeakyReLU { slope: 0.0.into() });
+        let nodes = BTreeMap::from([
+            (0, node(0, input, vec![], 2)),
+            (1, node(1, mask(), vec![], 1)),
+            (2, node(2, mult, vec![(0, 0), (1, 0)], 1)),
+            (3, node(3, add, vec![(0, 0), (2, 0)], 1)),
+            (4, node(4, relu, vec![(3, 0)], 1)),
+        ]);
+        Model {
+            graph: ParsedNodes {
+                nodes,
+                inputs: vec![0],
+                outputs: vec![(4, 0)],
+                input_names: vec![],
+            },
+            visibility: VarVisibility {
+                input: Visibility::Private,
+                params: Visibility::Fixed,
+                output: Visibility::Private,
+                outputs: vec![],
+            },
+        }
+    }
+
+    #[test]
+    fn residual_adds_fused_into_lookups() {
+        let run_args = RunArgs {
+            fuse_add_lookups: true,
+            lookup_range: (-256, 256),
+            logrows: 12,
+            ..Default::default()
+        };
+        let unfused = residual_block();
+        let mut fused = residual_block();
+        fused.graph.fuse_add_lookups(&run_args);
+        assert_eq!(fused.graph.nodes.len(), 4);
+        assert!(matches!(
+            fused.graph.nodes[&4].opkind(),
+            SupportedOp::Hybrid(HybridOp::FusedAddLookup { .. })
+        ));
+        assert_eq!(fused.graph.nodes[&4].inputs(), vec![(0, 0), (2, 0)]);
+
+        // the values range over positive and negative sums
+        let values = (0..LEN as IntegerRep).map(|i| Value::known(integer_rep_to_felt(i - 20)));
+        let inputs = vec![ValTensor::from(Tensor::from(values))];
+        let layout = |model: &Model| {
+            let settings = RegionSettings::all_true(run_args.decomp_base, run_args.decomp_legs);
+            model.dummy_layout(&run_args, &inputs, settings).unwrap()
+        };
+        let (fused, unfused) = (layout(&fused), layout(&unfused));
+        assert_eq!(fused.outputs, unfused.outputs);
+        // the sums aren't assigned before they are looked up
+        assert_eq!(fused.linear_coord + LEN, unfused.linear_coord);
+        assert_eq!(fused.fused_add_lookup_ops, fused.lookup_ops);
+        assert!(unfused.fused_add_lookup_ops.is_empty());
+    }
+
+    #[test]
+    fn adds_kept_when_the_table_spans_columns() {
+        let mut run_args = RunArgs {
+            fuse_add_lookups: true,
+            lookup_range: (-4096, 4096),
+            logrows: 12,
+            ..Default::default()
+        };
+        let mut model = residual_block();
+        model.graph.fuse_add_lookups(&run_args);
+        assert_eq!(model.graph, residual_block().graph);
+
+        // nor are they fused unless asked to
+        run_args.fuse_add_lookups = false;
+        run_args.lookup_range = (-256, 256);
+        model.graph.fuse_add_lookups(&run_args);
+        assert_eq!(model.graph, residual_block().graph);
+    }
+
+    #[test]
+    fn outputs_rescaled_to_their_own_scales() {
+        // the square of an input of scale 7, output twice at scale 14
+        let input = SupportedOp::Input(Input {
+            scale: 7,
+            datum_type: InputType::F32,
+        });
+        let square = |idx| {
+            let mut square = node(
+                idx,
+                SupportedOp::Linear(PolyOp::Mult),
+                vec![(0, 0), (0, 0)],
+                1,
+            );
+            square.bump_scale(14);
+            square
+        };
+        let mut input = node(0, input, vec![], 4);
+        input.bump_scale(7);
+        let mut graph = ParsedNodes {
+            nodes: BTreeMap::from([(0, input), (1, square(1)), (2, square(2))]),
+            inputs: vec![0],
+            outputs: vec![(1, 0), (2, 0)],
+            input_names: vec![],
+        };
+
+        let rescale = |graph: &mut ParsedNodes, output_scales: &[&str]| {
+            let run_args = RunArgs {
+                output_scales: output_scales.iter().map(|o| o.parse().unwrap()).collect(),
+                ..Default::default()
+            };
+            graph.rescale_outputs(&run_args)
+        };
+        // outputs are named by index or by the node computing them
+        assert!(rescale(&mut graph.clone(), &["2:10"]).is_err());
+        assert!(rescale(&mut graph.clone(), &["node_0:10"]).is_err());
+        assert!(rescale(&mut graph.clone(), &["0:10", "node_1:0"]).is_err());
+        // an output already at its scale is left as it is
+        let mut unchanged = graph.clone();
+        rescale(&mut unchanged, &["0:14"]).unwrap();
+        assert_eq!(unchanged, graph);
+
+        rescale(&mut graph, &["0:10", "node_2:0"]).unwrap();
+        assert_eq!(graph.get_output_scales().unwrap(), vec![10, 0]);
+        assert_eq!(graph.outputs, vec![(3, 0), (4, 0)]);
+
+        let model = Model {
+            graph,
+            visibility: VarVisibility {
+                input: Visibility::Private,
+                params: Visibility::Fixed,
+                output: Visibility::Private,
+                outputs: vec![],
+            },
+        };
+        let run_args = RunArgs::default();
+        let inputs = vec![ValTensor::from(Tensor::from(
+            (0..LEN).map(|_| Value::known(Fp::from(1600))),
+        ))];
+        let settings = RegionSettings::all_true(run_args.decomp_base, run_args.decomp_legs);
+        let res = model.dummy_layout(&run_args, &inputs, settings).unwrap();
+
+        // 12.5^2 keeps its precision at scale 10 and is rounded at scale 0
+        let dequantized = |output: usize, scale| {
+            res.outputs[output]
+                .iter()
+                .map(|x| dequantize(*x, scale, 0.0))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(dequantized(0, 10), vec![156.25; LEN]);
+        assert_eq!(dequantized(1, 0), vec![156.0; LEN]);
+    }
+
+    #[test]
+    fn outputs_masked_by_batch() {
+        // the square of a batch of 4 samples of 8 values
+        let shaped = |mut node: NodeType, dims: Vec<usize>| {
+            if let NodeType::Node(n) = &mut node {
+                n.out_dims = dims;
+            }
+            node
+        };
+        let input = SupportedOp::Input(Input {
+            scale: 0,
+            datum_type: InputType::F32,
+        });
+        let square = node(
+            1,
+            SupportedOp::Linear(PolyOp::Mult),
+            vec![(0, 0), (0, 0)],
+            1,
+        );
+        let mut graph = ParsedNodes {
+            nodes: BTreeMap::from([
+                (0, shaped(node(0, input, vec![], 2), vec![4, 8])),
+                (1, shaped(square, vec![4, 8])),
+            ]),
+            inputs: vec![0],
+            outputs: vec![(1, 0)],
+            input_names: vec!["input".into()],
+        };
+        let run_args = RunArgs {
+            batch_mask: true,
+            ..Default::default()
+        };
+
+        let mut unmasked = graph.clone();
+        unmasked.mask_batch(&RunArgs::default()).unwrap();
+        assert_eq!(unmasked, graph);
+
+        // outputs of another batch size can't be masked
+        let mut mismatched = graph.clone();
+        mismatched.nodes.insert(
+            2,
+            node(
+                2,
+                SupportedOp::Linear(PolyOp::Mult),
+                vec![(0, 0), (0, 0)],
+                1,
+            ),
+        );
+        mismatched.outputs.push((2, 0));
+        assert!(mismatched.mask_batch(&run_args).is_err());
+
+        graph.mask_batch(&run_args).unwrap();
+        assert_eq!(graph.input_names(), ["input", "batch_mask"]);
+        assert_eq!(graph.input_shapes().unwrap(), vec![vec![4, 8], vec![4]]);
+        assert_eq!(graph.output_shapes().unwrap(), vec![vec![4, 8], vec![4]]);
+        assert_eq!(
+            graph.get_input_types().unwrap(),
+            vec![InputType::F32, InputType::Bool]
+        );
+
+        let model = Model {
+            graph,
+            visibility: VarVisibility {
+                input: Visibility::Private,
+                params: Visibility::Fixed,
+                output: Visibility::Public,
+                outputs: vec![],
+            },
+        };
+        let mut samples = Tensor::from((0..32).map(|_| Fp::from(3)));
+        samples.reshape(&[4, 8]).unwrap();
+        let inputs = vec![
+            samples,
+            Tensor::from([1, 1, 0, 1].into_iter().map(Fp::from)),
+        ];
+        let settings = RegionSettings::all_true(run_args.decomp_base, run_args.decomp_legs);
+        let res = model.forward(&inputs, &run_args, settings).unwrap();
+
+        // the padding sample is zeroed, and the mask exposed as it is
+        let expected = (0..32)
+            .map(|i| Fp::from(if i / 8 == 2 { 0 } else { 9 }))
+            .collect::<Vec<_>>();
+        assert_eq!(res.outputs[0].iter().cloned().collect::<Vec<_>>(), expected);
+        assert_eq!(res.outputs[1], inputs[1]);
+    }
+
+    #[test]
+    fn nodes_inspected_from_compiled_model() {
+        let mut model = model();
+        model.visibility.input = Visibility::Public;
+        model.visibility.outputs = vec![
+            Visibility::Public,
+            Visibility::Hashed {
+                hash_is_public: true,
+                outlets: vec![],
+            },
+            Visibility::Private,
+            Visibility::Private,
+        ];
+        // the nodes are listed from the compiled model as from the model it was compiled from
+        let compiled: Model = bincode::deserialize(&bincode::serialize(&model).unwrap()).unwrap();
+        assert_eq!(compiled.nodes(), model.nodes());
+        assert_eq!(compiled.graph.output_names()[1], "node_4");
+
+        let add = |idx: usize, mask: usize, visibility: serde_json::Value| {
+            serde_json::json!({
+                "idx": idx,
+                "subgraph": null,
+                "name": format!("node_{}", idx),
+                "op": "ADD",
+                "inputs": [[0, 0], [mask, 0]],
+                "input_shapes": [[LEN], [LEN]],
+                "out_dims": [[LEN]],
+                "out_scales": [0],
+                "visibility": visibility,
+            })
+        };
+        let leaf = |idx: usize, op: &str, visibility: &str| {
+            serde_json::json!({
+                "idx": idx,
+                "subgraph": null,
+                "name": format!("node_{}", idx),
+                "op": op,
+                "inputs": [],
+                "input_shapes": [],
+                "out_dims": [[LEN]],
+                "out_scales": [0],
+                "visibility": visibility,
+            })
+        };
+        let expected = serde_json::json!([
+            leaf(0, "Input", "Public"),
+            leaf(1, "CONST (scale=0)", "Fixed"),
+            leaf(2, "CONST (scale=0)", "Fixed"),
+            add(3, 1, serde_json::json!("Public")),
+            add(
+                4,
+                1,
+                serde_json::json!({"Hashed": {"hash_is_public": true, "outlets": []}})
+            ),
+            add(5, 2, serde_json::json!("Private")),
+            add(6, 2, serde_json::json!("Private")),
+        ]);
+        assert_eq!(serde_json::to_value(compiled.nodes()).unwrap(), expected);
+        assert_eq!(compiled.node(4), Some(compiled.nodes().remove(4)));
+        assert!(compiled.node(LEN).is_none());
+    }
+}
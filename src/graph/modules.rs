@@ -1,29 +1,74 @@
-use crate::circuit::modules::polycommit::{PolyCommitChip, PolyCommitConfig};
+use crate::circuit::modules::ecdsa::{
+    EcdsaChip, EcdsaConfig, EcdsaSignature, InputSignature, SignatureHash, Word, PUBLIC_KEY_LIMBS,
+    WITNESS_LIMBS,
+};
+use crate::circuit::modules::elgamal::{
+    ElGamalChip, ElGamalConfig, EncryptionWitness, OutputEncryption, HEADER_INSTANCES, WITNESS_LEN,
+};
+use crate::circuit::modules::merkle::{MerkleChip, MerkleConfig, MerklePath};
+use crate::circuit::modules::nullifier::{NullifierChip, NullifierConfig};
+use crate::circuit::modules::polycommit::{PolyCommitBlinders, PolyCommitChip, PolyCommitConfig};
+use crate::circuit::modules::poseidon::circomlib::{CircomlibChip, CircomlibConfig};
 use crate::circuit::modules::poseidon::spec::{PoseidonSpec, POSEIDON_RATE, POSEIDON_WIDTH};
 use crate::circuit::modules::poseidon::{PoseidonChip, PoseidonConfig};
+use crate::circuit::modules::session::{SessionChip, SessionConfig};
+use crate::circuit::modules::sha256::{Sha256Chip, Sha256Config};
 use crate::circuit::modules::Module;
 use crate::circuit::region::ConstantsMap;
-use crate::tensor::{Tensor, ValTensor};
-use halo2_proofs::circuit::Layouter;
+use crate::tensor::{Tensor, ValTensor, ValType};
+use halo2_proofs::circuit::{Layouter, Value};
 use halo2_proofs::plonk::{Column, ConstraintSystem, Error, Instance, VerifyingKey};
 use halo2_proofs::poly::commitment::CommitmentScheme;
 use halo2curves::bn256::{Fr as Fp, G1Affine};
+use halo2curves::ff::Field;
 use itertools::Itertools;
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use super::errors::GraphError;
-use super::{VarVisibility, Visibility};
+use super::utilities::split_valtensor;
+use super::{PoseidonParams, VarVisibility, Visibility};
 
 /// poseidon len to hash in tree
 pub const POSEIDON_LEN_GRAPH: usize = 32;
 /// Poseidon number of instances
 pub const POSEIDON_INSTANCES: usize = 1;
+/// SHA-256 number of instances, the digest is split into two 128 bit halves
+pub const SHA256_INSTANCES: usize = 2;
+/// ECDSA number of instances, the limbs of the coordinates of the public key
+pub const ECDSA_INSTANCES: usize = PUBLIC_KEY_LIMBS;
+/// Nullifier number of instances
+pub const NULLIFIER_INSTANCES: usize = 1;
+/// Session number of instances, the digest of the previous step and the digest of this step
+pub const SESSION_INSTANCES: usize = 2;
+/// ElGamal number of instances preceding the ciphertext of the encrypted outputs, the coordinates
+/// of the public key and of the ephemeral key
+pub const ELGAMAL_HEADER_INSTANCES: usize = HEADER_INSTANCES;
 
 /// Poseidon module type
 pub type ModulePoseidon =
     PoseidonChip<PoseidonSpec, POSEIDON_WIDTH, POSEIDON_RATE, POSEIDON_LEN_GRAPH>;
 /// Poseidon module config
 pub type ModulePoseidonConfig = PoseidonConfig<POSEIDON_WIDTH, POSEIDON_RATE>;
+/// Poseidon module type with the circomlib parameters
+pub type ModuleCircomlib = CircomlibChip;
+/// Poseidon module config with the circomlib parameters
+pub type ModuleCircomlibConfig = CircomlibConfig;
+/// SHA-256 module type
+pub type ModuleSha256 = Sha256Chip;
+/// Merkle inclusion module type, the leaves are hashed like the Poseidon module
+pub type ModuleMerkle = MerkleChip<PoseidonSpec, POSEIDON_WIDTH, POSEIDON_RATE, POSEIDON_LEN_GRAPH>;
+/// ECDSA module type
+pub type ModuleEcdsa = EcdsaChip;
+/// Nullifier module type, the inputs are committed to like the Poseidon module
+pub type ModuleNullifier =
+    NullifierChip<PoseidonSpec, POSEIDON_WIDTH, POSEIDON_RATE, POSEIDON_LEN_GRAPH>;
+/// Session module type, the outputs are committed to like the Poseidon module
+pub type ModuleSession =
+    SessionChip<PoseidonSpec, POSEIDON_WIDTH, POSEIDON_RATE, POSEIDON_LEN_GRAPH>;
+/// ElGamal module type, the masks are hashed like the nullifier
+pub type ModuleElGamal = ElGamalChip<PoseidonSpec, POSEIDON_WIDTH, POSEIDON_RATE>;
 
 ///
 #[derive(Clone, Debug, Default)]
@@ -32,6 +77,20 @@ pub struct ModuleConfigs {
     polycommit: Vec<PolyCommitConfig>,
     /// Poseidon
     poseidon: Option<ModulePoseidonConfig>,
+    /// Poseidon with the circomlib parameters, used instead of poseidon for the hashed visibilities
+    circomlib: Option<ModuleCircomlibConfig>,
+    /// SHA-256
+    sha256: Option<Sha256Config>,
+    /// Merkle inclusion, hashing in the poseidon columns
+    merkle: Option<MerkleConfig<POSEIDON_WIDTH, POSEIDON_RATE>>,
+    /// ECDSA verification of the signed inputs
+    ecdsa: Option<EcdsaConfig>,
+    /// Nullifier of the inputs, hashing in the poseidon columns
+    nullifier: Option<NullifierConfig<POSEIDON_WIDTH, POSEIDON_RATE>>,
+    /// ElGamal encryption of the outputs, hashing the masks in the poseidon columns
+    elgamal: Option<ElGamalConfig<POSEIDON_WIDTH, POSEIDON_RATE>>,
+    /// Session digests chaining the outputs to the previous step, hashing in the poseidon columns
+    session: Option<SessionConfig<POSEIDON_WIDTH, POSEIDON_RATE>>,
     /// Instance
     pub instance: Option<Column<Instance>>,
 }
@@ -60,16 +119,42 @@ impl ModuleConfigs {
         cs: &mut ConstraintSystem<Fp>,
         visibility: VarVisibility,
         module_size: ModuleSizes,
+        input_signature: Option<&InputSignature>,
+        nullifier: bool,
+        encryption: bool,
+        session: bool,
+        poseidon_params: PoseidonParams,
     ) {
-        if (visibility.input.is_hashed()
-            || visibility.output.is_hashed()
-            || visibility.params.is_hashed())
+        // the outputs are processed with a single visibility, see [VarVisibility::processed_outputs]
+        let (output, _) = visibility.processed_outputs(visibility.outputs.len());
+        let vars = [&visibility.input, &output, &visibility.params];
+        let poseidon = vars.iter().filter(|v| v.is_poseidon_hashed()).collect_vec();
+        let sha256 = vars.iter().filter(|v| v.is_sha256_hashed()).collect_vec();
+        let merkle_depth = visibility.input.merkle_depth();
+
+        if poseidon_params == PoseidonParams::Circomlib
+            && !poseidon.is_empty()
             && module_size.poseidon.1[0] > 0
         {
-            if visibility.input.is_hashed_public()
-                || visibility.output.is_hashed_public()
-                || visibility.params.is_hashed_public()
-            {
+            // merkle inclusion proofs are only supported with the ezkl parameters
+            if poseidon.iter().any(|v| v.is_hashed_public()) {
+                if let Some(inst) = self.instance {
+                    self.circomlib = Some(ModuleCircomlib::configure_with_optional_instance(
+                        cs,
+                        Some(inst),
+                    ));
+                } else {
+                    let circomlib = ModuleCircomlib::configure(cs, ());
+                    self.instance = circomlib.instance;
+                    self.circomlib = Some(circomlib);
+                }
+            } else {
+                self.circomlib = Some(ModuleCircomlib::configure_with_optional_instance(cs, None));
+            }
+        } else if (!poseidon.is_empty() || merkle_depth.is_some()) && module_size.poseidon.1[0] > 0
+        {
+            // the merkle root is always public
+            if merkle_depth.is_some() || poseidon.iter().any(|v| v.is_hashed_public()) {
                 if let Some(inst) = self.instance {
                     self.poseidon = Some(ModulePoseidon::configure_with_optional_instance(
                         cs,
@@ -80,13 +165,135 @@ impl ModuleConfigs {
                     self.instance = poseidon.instance;
                     self.poseidon = Some(poseidon);
                 }
-            } else if visibility.input.is_hashed_private()
-                || visibility.output.is_hashed_private()
-                || visibility.params.is_hashed_private()
-            {
+            } else {
                 self.poseidon = Some(ModulePoseidon::configure_with_optional_instance(cs, None));
             }
+
+            if let (Some(depth), Some(poseidon)) = (merkle_depth, &self.poseidon) {
+                self.merkle = Some(ModuleMerkle::configure_with_poseidon(
+                    cs,
+                    poseidon.clone(),
+                    depth,
+                    self.instance,
+                ));
+            }
         };
+
+        if !sha256.is_empty() && module_size.sha256.1 > 0 {
+            if sha256.iter().any(|v| v.is_hashed_public()) {
+                if let Some(inst) = self.instance {
+                    self.sha256 = Some(ModuleSha256::configure_with_optional_instance(
+                        cs,
+                        Some(inst),
+                    ));
+                } else {
+                    let sha256 = ModuleSha256::configure(cs, ());
+                    self.instance = sha256.instance;
+                    self.sha256 = Some(sha256);
+                }
+            } else {
+                self.sha256 = Some(ModuleSha256::configure_with_optional_instance(cs, None));
+            }
+        }
+
+        if let Some(signature) = input_signature {
+            // the signed inputs are hashed privately, in the columns of the hashed visibilities if any
+            match signature.hash {
+                SignatureHash::Poseidon if self.poseidon.is_none() => {
+                    self.poseidon =
+                        Some(ModulePoseidon::configure_with_optional_instance(cs, None));
+                }
+                SignatureHash::Sha256 if self.sha256.is_none() => {
+                    self.sha256 = Some(ModuleSha256::configure_with_optional_instance(cs, None));
+                }
+                _ => {}
+            }
+            // the public key is always public
+            let instance = match self.instance {
+                Some(inst) => inst,
+                None => {
+                    let inst = cs.instance_column();
+                    cs.enable_equality(inst);
+                    self.instance = Some(inst);
+                    inst
+                }
+            };
+            self.ecdsa = Some(ModuleEcdsa::configure_with_optional_instance(
+                cs,
+                signature.hash,
+                Some(instance),
+            ));
+        }
+
+        if nullifier {
+            // the inputs are committed to privately, in the poseidon columns if any
+            if self.poseidon.is_none() {
+                self.poseidon = Some(ModulePoseidon::configure_with_optional_instance(cs, None));
+            }
+            // the nullifier is always public
+            let instance = match self.instance {
+                Some(inst) => inst,
+                None => {
+                    let inst = cs.instance_column();
+                    cs.enable_equality(inst);
+                    self.instance = Some(inst);
+                    inst
+                }
+            };
+            if let Some(poseidon) = &self.poseidon {
+                self.nullifier = Some(ModuleNullifier::configure_with_poseidon(
+                    poseidon.clone(),
+                    Some(instance),
+                ));
+            }
+        }
+
+        if encryption {
+            // the masks are hashed privately, in the poseidon columns if any
+            if self.poseidon.is_none() {
+                self.poseidon = Some(ModulePoseidon::configure_with_optional_instance(cs, None));
+            }
+            // the public key and the ciphertext are always public
+            let instance = match self.instance {
+                Some(inst) => inst,
+                None => {
+                    let inst = cs.instance_column();
+                    cs.enable_equality(inst);
+                    self.instance = Some(inst);
+                    inst
+                }
+            };
+            if let Some(poseidon) = &self.poseidon {
+                self.elgamal = Some(ModuleElGamal::configure_with_poseidon(
+                    cs,
+                    poseidon.clone(),
+                    Some(instance),
+                ));
+            }
+        }
+
+        if session {
+            // the outputs are committed to privately, in the poseidon columns if any
+            if self.poseidon.is_none() {
+                self.poseidon = Some(ModulePoseidon::configure_with_optional_instance(cs, None));
+            }
+            // the digests are always public
+            let instance = match self.instance {
+                Some(inst) => inst,
+                None => {
+                    let inst = cs.instance_column();
+                    cs.enable_equality(inst);
+                    self.instance = Some(inst);
+                    inst
+                }
+            };
+            if let Some(poseidon) = &self.poseidon {
+                self.session = Some(ModuleSession::configure_with_poseidon(
+                    poseidon.clone(),
+                    Some(instance),
+                ));
+            }
+        }
     }
 }
 
@@ -97,18 +304,39 @@ pub struct ModuleForwardResult {
     pub poseidon_hash: Option<Vec<Fp>>,
     /// The outputs of the forward pass for PolyCommit
     pub polycommit: Option<Vec<Vec<G1Affine>>>,
+    /// The outputs of the forward pass for SHA-256, the two 128 bit halves of each digest
+    #[serde(default)]
+    pub sha256_hash: Option<Vec<Fp>>,
+    /// The leaf the inputs hash to, when they are proven to belong to a dataset
+    #[serde(default)]
+    pub merkle_leaf: Option<Fp>,
+    /// The path of the leaf to the root of the dataset, see [crate::graph::GraphWitness::prove_membership]
+    #[serde(default)]
+    pub merkle_path: Option<MerklePath>,
+    /// The blinders folded into the PolyCommit commitments, one for each commitment
+    #[serde(default)]
+    pub polycommit_blinders: Option<PolyCommitBlinders>,
 }
 
 impl ModuleForwardResult {
     /// Get the result
     pub fn get_result(&self, vis: Visibility) -> Vec<Vec<Fp>> {
-        if vis.is_hashed() {
+        if vis.is_sha256_hashed() {
+            self.sha256_hash
+                .clone()
+                .unwrap()
+                .chunks(SHA256_INSTANCES)
+                .map(|x| x.to_vec())
+                .collect()
+        } else if vis.is_poseidon_hashed() {
             self.poseidon_hash
                 .clone()
                 .unwrap()
                 .into_iter()
                 .map(|x| vec![x])
                 .collect()
+        } else if vis.is_merkle() {
+            self.merkle_path.iter().map(|p| vec![p.root]).collect()
         } else {
             vec![]
         }
@@ -118,6 +346,13 @@ impl ModuleForwardResult {
     pub fn get_instances(&self) -> Vec<Vec<Fp>> {
         if let Some(poseidon) = &self.poseidon_hash {
             poseidon.iter().map(|x| vec![*x]).collect()
+        } else if let Some(sha256) = &self.sha256_hash {
+            sha256
+                .chunks(SHA256_INSTANCES)
+                .map(|x| x.to_vec())
+                .collect()
+        } else if let Some(path) = &self.merkle_path {
+            vec![vec![path.root]]
         } else {
             vec![]
         }
@@ -125,10 +360,27 @@ impl ModuleForwardResult {
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), derive(JsonSchema))]
 ///
 pub struct ModuleSizes {
     polycommit: Vec<usize>,
     poseidon: (usize, Vec<usize>),
+    /// rows and instances of the sha256 module
+    #[serde(default)]
+    sha256: (usize, usize),
+    /// rows and instances of the verification of the input signature
+    #[serde(default)]
+    ecdsa: (usize, usize),
+    /// instances of the nullifier, its rows are in the poseidon columns
+    #[serde(default)]
+    nullifier: usize,
+    /// rows and instances of the encryption of the outputs, its masks are hashed in the poseidon
+    /// columns
+    #[serde(default)]
+    elgamal: (usize, usize),
+    /// instances of the session digests, their rows are in the poseidon columns
+    #[serde(default)]
+    session: usize,
 }
 
 impl ModuleSizes {
@@ -140,17 +392,53 @@ impl ModuleSizes {
                 0,
                 vec![0; crate::circuit::modules::poseidon::NUM_INSTANCE_COLUMNS],
             ),
+            sha256: (0, 0),
+            ecdsa: (0, 0),
+            nullifier: 0,
+            elgamal: (0, 0),
+            session: 0,
         }
     }
 
-    /// Get the number of constraints
+    /// Get the number of constraints, the modules are laid out in their own columns so the
+    /// largest one determines the number of rows
     pub fn max_constraints(&self) -> usize {
-        self.poseidon.0
+        self.poseidon
+            .0
+            .max(self.sha256.0)
+            .max(self.ecdsa.0)
+            .max(self.elgamal.0)
+    }
+    /// Get the number of PolyCommit commitments, one for each committed tensor
+    pub fn num_polycommits(&self) -> usize {
+        self.polycommit.len()
+    }
+    /// Get the number of rows used by the sha256 module
+    pub fn sha256_rows(&self) -> usize {
+        self.sha256.0
     }
     /// Get the number of instances
     pub fn num_instances(&self) -> Vec<usize> {
         // concat
-        self.poseidon.1.clone()
+        let mut instances = self.poseidon.1.clone();
+        if self.sha256.1 > 0 {
+            instances.push(self.sha256.1);
+        }
+        if self.ecdsa.1 > 0 {
+            instances.push(self.ecdsa.1);
+        }
+        if self.elgamal.1 > 0 {
+            instances.push(self.elgamal.1);
+        }
+        // the session digests come right before the nullifier
+        if self.session > 0 {
+            instances.push(self.session);
+        }
+        // the nullifier is the last instance
+        if self.nullifier > 0 {
+            instances.push(self.nullifier);
+        }
+        instances
     }
 }
 
@@ -158,11 +446,66 @@ impl ModuleSizes {
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct GraphModules {
     polycommit_idx: usize,
+    merkle_path: Option<MerklePath>,
+    #[serde(default)]
+    signature: Option<EcdsaSignature>,
+    #[serde(default)]
+    salt: Option<Fp>,
+    #[serde(default)]
+    encryption: Option<EncryptionWitness>,
+    #[serde(default)]
+    blinders: Option<Vec<Fp>>,
+    #[serde(default)]
+    prev_digest: Option<Fp>,
 }
 impl GraphModules {
     ///
     pub fn new() -> GraphModules {
-        GraphModules { polycommit_idx: 0 }
+        GraphModules {
+            polycommit_idx: 0,
+            merkle_path: None,
+            signature: None,
+            salt: None,
+            encryption: None,
+            blinders: None,
+            prev_digest: None,
+        }
+    }
+
+    /// Set the path the inputs are proven to belong to the dataset with, if none the path is laid out
+    /// as unknown values (as when generating keys)
+    pub fn set_merkle_path(&mut self, path: Option<MerklePath>) {
+        self.merkle_path = path;
+    }
+
+    /// Set the signature over the signed inputs, if none it is laid out as unknown values
+    pub fn set_signature(&mut self, signature: Option<EcdsaSignature>) {
+        self.signature = signature;
+    }
+
+    /// Set the salt the nullifier of the inputs is derived with, if none it is laid out as an
+    /// unknown value
+    pub fn set_salt(&mut self, salt: Option<Fp>) {
+        self.salt = salt;
+    }
+
+    /// Set the public key and the randomness the outputs are encrypted with, if none they are laid
+    /// out as unknown values
+    pub fn set_encryption(&mut self, encryption: Option<EncryptionWitness>) {
+        self.encryption = encryption;
+    }
+
+    /// Set the blinders folded into the PolyCommit commitments, in the order of the commitments. If
+    /// some, every committed tensor is blinded, with unknown values if the slice holds no blinders
+    /// (as when generating keys)
+    pub fn set_blinders(&mut self, blinders: Option<Vec<Fp>>) {
+        self.blinders = blinders;
+    }
+
+    /// Set the digest of the previous step of the session the outputs are chained to, if none it is laid out as an
+    /// unknown value
+    pub fn set_prev_digest(&mut self, prev_digest: Option<Fp>) {
+        self.prev_digest = prev_digest;
     }
 
     ///
@@ -172,9 +515,68 @@ impl GraphModules {
 }
 
 impl GraphModules {
+    fn num_merkle_constraints(depth: usize, shapes: Vec<Vec<usize>>, sizes: &mut ModuleSizes) {
+        // all the inputs hash to a single leaf
+        let total_len = shapes.iter().map(|s| s.iter().product::<usize>()).sum();
+        sizes.poseidon.0 += ModuleMerkle::num_rows_with_depth(total_len, depth);
+        // 1 constraint for the root
+        sizes.poseidon.1[0] += 1;
+    }
+
+    /// Reserve the rows of the hash of the signed inputs and of the verification of their signature
+    pub fn num_signature_constraints(
+        signature: &InputSignature,
+        shapes: Vec<Vec<usize>>,
+        sizes: &mut ModuleSizes,
+    ) {
+        // the signed inputs hash to a single digest
+        let total_len = shapes.iter().map(|s| s.iter().product::<usize>()).sum();
+        match signature.hash {
+            SignatureHash::Poseidon => sizes.poseidon.0 += ModulePoseidon::num_rows(total_len),
+            SignatureHash::Sha256 => sizes.sha256.0 += ModuleSha256::num_rows(total_len),
+        }
+        sizes.ecdsa.0 += ModuleEcdsa::rows(signature.hash);
+        // the limbs of the public key
+        sizes.ecdsa.1 += ECDSA_INSTANCES;
+    }
+
+    /// Reserve the rows of the commitment of the inputs and of their nullifier, and its instance
+    pub fn num_nullifier_constraints(shapes: Vec<Vec<usize>>, sizes: &mut ModuleSizes) {
+        // all the inputs hash to a single commitment
+        let total_len = shapes.iter().map(|s| s.iter().product::<usize>()).sum();
+        sizes.poseidon.0 += ModuleNullifier::num_rows(total_len);
+        sizes.nullifier += NULLIFIER_INSTANCES;
+    }
+
+    /// Reserve the rows of the commitment of the outputs and of the session digest, and the instances of the previous
+    /// digest and of the digest
+    pub fn num_session_constraints(shapes: Vec<Vec<usize>>, sizes: &mut ModuleSizes) {
+        // all the outputs hash to a single commitment
+        let total_len = shapes.iter().map(|s| s.iter().product::<usize>()).sum();
+        sizes.poseidon.0 += ModuleSession::num_rows(total_len);
+        sizes.session += SESSION_INSTANCES;
+    }
+
+    /// Reserve the row of the blinder of each PolyCommit commitment, which follows the committed
+    /// tensor in its column
+    pub fn num_blinding_constraints(sizes: &mut ModuleSizes) {
+        sizes.polycommit.iter_mut().for_each(|size| *size += 1);
+    }
+
+    /// Reserve the rows of the encryption of the outputs and of their masks, and the instances of
+    /// the public key and of the ciphertext
+    pub fn num_encryption_constraints(encryption: &OutputEncryption, sizes: &mut ModuleSizes) {
+        // the outputs are encrypted as a single message
+        let total_len = encryption.lens.iter().sum();
+        sizes.poseidon.0 += ModuleElGamal::poseidon_rows(total_len);
+        sizes.elgamal.0 += ModuleElGamal::num_rows(total_len);
+        sizes.elgamal.1 += ELGAMAL_HEADER_INSTANCES + total_len;
+    }
+
     fn num_constraint_given_shapes(
         visibility: Visibility,
         shapes: Vec<Vec<usize>>,
+        poseidon_params: PoseidonParams,
         sizes: &mut ModuleSizes,
     ) {
         for shape in shapes {
@@ -183,8 +585,14 @@ impl GraphModules {
                 if visibility.is_polycommit() {
                     // 1 constraint for each polycommit commitment
                     sizes.polycommit.push(total_len);
+                } else if visibility.is_sha256_hashed() {
+                    sizes.sha256.0 += ModuleSha256::num_rows(total_len);
+                    sizes.sha256.1 += SHA256_INSTANCES;
                 } else if visibility.is_hashed() {
-                    sizes.poseidon.0 += ModulePoseidon::num_rows(total_len);
+                    sizes.poseidon.0 += match poseidon_params {
+                        PoseidonParams::Ezkl => ModulePoseidon::num_rows(total_len),
+                        PoseidonParams::Circomlib => ModuleCircomlib::num_rows(total_len),
+                    };
                     // 1 constraints for hash
                     sizes.poseidon.1[0] += 1;
                 }
@@ -197,12 +605,39 @@ impl GraphModules {
         params_shapes: Vec<Vec<usize>>,
         output_shapes: Vec<Vec<usize>>,
         visibility: VarVisibility,
+        poseidon_params: PoseidonParams,
     ) -> ModuleSizes {
         let mut module_sizes = ModuleSizes::new();
 
-        Self::num_constraint_given_shapes(visibility.input, input_shapes, &mut module_sizes);
-        Self::num_constraint_given_shapes(visibility.params, params_shapes, &mut module_sizes);
-        Self::num_constraint_given_shapes(visibility.output, output_shapes, &mut module_sizes);
+        if let Some(depth) = visibility.input.merkle_depth() {
+            Self::num_merkle_constraints(depth, input_shapes, &mut module_sizes);
+        } else {
+            Self::num_constraint_given_shapes(
+                visibility.input,
+                input_shapes,
+                poseidon_params,
+                &mut module_sizes,
+            );
+        }
+        Self::num_constraint_given_shapes(
+            visibility.params,
+            params_shapes,
+            poseidon_params,
+            &mut module_sizes,
+        );
+        if visibility.outputs.is_empty() {
+            Self::num_constraint_given_shapes(
+                visibility.output,
+                output_shapes,
+                poseidon_params,
+                &mut module_sizes,
+            );
+        } else {
+            // only the outputs of the processed visibility are hashed or committed to
+            let (output, outlets) = visibility.processed_outputs(output_shapes.len());
+            let shapes = outlets.iter().map(|o| output_shapes[*o].clone()).collect();
+            Self::num_constraint_given_shapes(output, shapes, poseidon_params, &mut module_sizes);
+        }
 
         module_sizes
     }
@@ -245,6 +680,13 @@ impl GraphModules {
 
             // layout the module
             inputs.iter_mut().for_each(|x| {
+                // fold the blinder of the commitment into the committed column
+                if let Some(blinders) = &self.blinders {
+                    let blinder = blinders
+                        .get(self.polycommit_idx)
+                        .map_or(Value::unknown(), |b| Value::known(*b));
+                    x.push(Tensor::from([ValType::from(blinder)].into_iter()).into());
+                }
                 // create the module
                 let chip = PolyCommitChip::new(configs.polycommit[self.polycommit_idx].clone());
                 // reserve module 2 onwards for polycommit modules
@@ -263,9 +705,82 @@ impl GraphModules {
             });
         }
 
+        if let (Some(depth), false) = (element_visibility.merkle_depth(), values.is_empty()) {
+            if let Some(config) = &configs.merkle {
+                // the merkle module hashes in the poseidon columns of module 0
+                layouter.assign_region(|| "_enter_module_0", |_| Ok(()))?;
+                let chip = ModuleMerkle::new(config.clone());
+
+                let shapes = values.iter().map(|v| v.dims().to_vec()).collect_vec();
+                let message = values
+                    .iter()
+                    .map(|v| v.get_inner_tensor().cloned())
+                    .collect::<Result<Vec<_>, _>>()
+                    .and_then(|t| Tensor::new(Some(&t), &[t.len()])?.combine())
+                    .map_err(|e| {
+                        log::error!("failed to concatenate the merkle leaf inputs: {:?}", e);
+                        Error::Synthesis
+                    })?;
+
+                let (siblings, bits) = match &self.merkle_path {
+                    Some(path) => (
+                        path.siblings.iter().map(|x| Value::known(*x)).collect_vec(),
+                        ModuleMerkle::index_bits(path.index, depth)
+                            .into_iter()
+                            .map(Value::known)
+                            .collect_vec(),
+                    ),
+                    None => (vec![Value::unknown(); depth], vec![Value::unknown(); depth]),
+                };
+                let to_valtensor = |v: Vec<Value<Fp>>| -> ValTensor<Fp> {
+                    Tensor::from(v.into_iter().map(ValType::from)).into()
+                };
+
+                let mut inputs = vec![message.into(), to_valtensor(siblings), to_valtensor(bits)];
+                Self::layout_module(&chip, layouter, &mut inputs, instance_offset, constants)?;
+
+                // replace the inputs with the cells hashed into the leaf
+                let split = split_valtensor(&inputs[0], shapes).map_err(|e| {
+                    log::error!("failed to split the merkle leaf inputs: {:?}", e);
+                    Error::Synthesis
+                })?;
+                values.iter_mut().zip(split).for_each(|(x, y)| *x = y);
+            } else {
+                log::error!("Merkle config not initialized");
+                return Err(Error::Synthesis);
+            }
+        }
+
         // If the module is hashed, then we need to hash the inputs
-        if element_visibility.is_hashed() && !values.is_empty() {
-            if let Some(config) = &mut configs.poseidon {
+        if element_visibility.is_sha256_hashed() && !values.is_empty() {
+            if let Some(config) = &mut configs.sha256 {
+                // reserve module 1 for sha256 modules
+                layouter.assign_region(|| "_enter_module_1", |_| Ok(()))?;
+                let chip = ModuleSha256::new(config.clone());
+                let mut inputs = values.iter_mut().map(|x| vec![x.clone()]).collect_vec();
+                inputs.iter_mut().for_each(|x| {
+                    Self::layout_module(&chip, layouter, x, instance_offset, constants).unwrap();
+                });
+                values.iter_mut().enumerate().for_each(|(i, x)| {
+                    x.clone_from(&inputs[i][0]);
+                });
+            } else {
+                log::error!("SHA-256 config not initialized");
+                return Err(Error::Synthesis);
+            }
+        } else if element_visibility.is_hashed() && !values.is_empty() {
+            if let Some(config) = &configs.circomlib {
+                // reserve module 0 for poseidon modules
+                layouter.assign_region(|| "_enter_module_0", |_| Ok(()))?;
+                let chip = ModuleCircomlib::new(config.clone());
+                let mut inputs = values.iter_mut().map(|x| vec![x.clone()]).collect_vec();
+                inputs.iter_mut().for_each(|x| {
+                    Self::layout_module(&chip, layouter, x, instance_offset, constants).unwrap();
+                });
+                values.iter_mut().enumerate().for_each(|(i, x)| {
+                    x.clone_from(&inputs[i][0]);
+                });
+            } else if let Some(config) = &mut configs.poseidon {
                 // reserve module 0 for poseidon modules
                 layouter.assign_region(|| "_enter_module_0", |_| Ok(()))?;
                 // create the module
@@ -290,31 +805,320 @@ impl GraphModules {
         Ok(())
     }
 
+    /// Layout the verification of the signature over the signed inputs, replacing them with the
+    /// cells the digest is computed from
+    pub fn layout_signature(
+        &self,
+        layouter: &mut impl Layouter<Fp>,
+        configs: &ModuleConfigs,
+        inputs: &mut [ValTensor<Fp>],
+        signature: &InputSignature,
+        instance_offset: &mut usize,
+        constants: &mut ConstantsMap<Fp>,
+    ) -> Result<(), Error> {
+        let Some(ecdsa) = &configs.ecdsa else {
+            log::error!("ECDSA config not initialized");
+            return Err(Error::Synthesis);
+        };
+
+        let shapes = signature
+            .inputs
+            .iter()
+            .map(|i| inputs[*i].dims().to_vec())
+            .collect_vec();
+        let message: ValTensor<Fp> = signature
+            .inputs
+            .iter()
+            .map(|i| inputs[*i].get_inner_tensor().cloned())
+            .collect::<Result<Vec<_>, _>>()
+            .and_then(|t| Tensor::new(Some(&t), &[t.len()])?.combine())
+            .map_err(|e| {
+                log::error!("failed to concatenate the signed inputs: {:?}", e);
+                Error::Synthesis
+            })?
+            .into();
+
+        let (message, digest) = match (signature.hash, &configs.poseidon, &configs.sha256) {
+            (SignatureHash::Poseidon, Some(config), _) => {
+                // reserve module 0 for poseidon modules
+                layouter.assign_region(|| "_enter_module_0", |_| Ok(()))?;
+                let chip = ModulePoseidon::new(config.clone());
+                let (message, zero) = chip.layout_inputs(layouter, &[message], constants)?;
+                let digest = chip.hash_assigned(layouter, message.clone(), &zero)?;
+                (message, vec![digest])
+            }
+            (SignatureHash::Sha256, _, Some(config)) => {
+                // reserve module 1 for sha256 modules
+                layouter.assign_region(|| "_enter_module_1", |_| Ok(()))?;
+                let chip = ModuleSha256::new(config.clone());
+                chip.layout_inputs(layouter, &[message], constants)?
+            }
+            _ => {
+                log::error!("{:?} config not initialized", signature.hash);
+                return Err(Error::Synthesis);
+            }
+        };
+
+        // replace the inputs with the cells hashed into the digest
+        let message: Tensor<ValType<Fp>> = message.into_iter().map(ValType::from).into();
+        let split = split_valtensor(&message.into(), shapes).map_err(|e| {
+            log::error!("failed to split the signed inputs: {:?}", e);
+            Error::Synthesis
+        })?;
+        for (i, x) in signature.inputs.iter().zip(split) {
+            inputs[*i] = x;
+        }
+
+        // the verification lays out in its own columns
+        layouter.assign_region(|| "_enter_module_0", |_| Ok(()))?;
+        let chip = ModuleEcdsa::new(ecdsa.clone());
+        let witness = match &self.signature {
+            Some(signature) => signature
+                .witness_limbs()
+                .into_iter()
+                .map(Value::known)
+                .collect_vec(),
+            None => vec![Value::unknown(); WITNESS_LIMBS],
+        };
+        let to_valtensor =
+            |v: Vec<ValType<Fp>>| -> ValTensor<Fp> { Tensor::from(v.into_iter()).into() };
+        let mut inputs = vec![
+            to_valtensor(digest.into_iter().map(ValType::from).collect()),
+            to_valtensor(witness.into_iter().map(ValType::from).collect()),
+        ];
+        Self::layout_module(&chip, layouter, &mut inputs, instance_offset, constants)
+    }
+
+    /// Layout the nullifier of the inputs, replacing them with the cells their commitment is hashed
+    /// from. The nullifier is constrained to the instance at `instance_offset`.
+    pub fn layout_nullifier(
+        &self,
+        layouter: &mut impl Layouter<Fp>,
+        configs: &ModuleConfigs,
+        inputs: &mut [ValTensor<Fp>],
+        instance_offset: usize,
+        constants: &mut ConstantsMap<Fp>,
+    ) -> Result<(), Error> {
+        let Some(config) = &configs.nullifier else {
+            log::error!("Nullifier config not initialized");
+            return Err(Error::Synthesis);
+        };
+
+        let shapes = inputs.iter().map(|v| v.dims().to_vec()).collect_vec();
+        let message = inputs
+            .iter()
+            .map(|v| v.get_inner_tensor().cloned())
+            .collect::<Result<Vec<_>, _>>()
+            .and_then(|t| Tensor::new(Some(&t), &[t.len()])?.combine())
+            .map_err(|e| {
+                log::error!("failed to concatenate the nullified inputs: {:?}", e);
+                Error::Synthesis
+            })?;
+        let salt = match self.salt {
+            Some(salt) => Value::known(salt),
+            None => Value::unknown(),
+        };
+
+        // the nullifier hashes in the poseidon columns of module 0
+        layouter.assign_region(|| "_enter_module_0", |_| Ok(()))?;
+        let chip = ModuleNullifier::new(config.clone());
+        let mut x = vec![
+            message.into(),
+            Tensor::from([ValType::from(salt)].into_iter()).into(),
+        ];
+        let mut offset = instance_offset;
+        Self::layout_module(&chip, layouter, &mut x, &mut offset, constants)?;
+
+        // replace the inputs with the cells hashed into the commitment
+        let split = split_valtensor(&x[0], shapes).map_err(|e| {
+            log::error!("failed to split the nullified inputs: {:?}", e);
+            Error::Synthesis
+        })?;
+        inputs.iter_mut().zip(split).for_each(|(x, y)| *x = y);
+        Ok(())
+    }
+
+    /// Layout the encryption of the encrypted outputs to the public key of the recipient. The public
+    /// key and the ciphertext are constrained to the instances from `instance_offset`.
+    pub fn layout_encryption(
+        &self,
+        layouter: &mut impl Layouter<Fp>,
+        configs: &ModuleConfigs,
+        outputs: &[ValTensor<Fp>],
+        encryption: &OutputEncryption,
+        instance_offset: &mut usize,
+        constants: &mut ConstantsMap<Fp>,
+    ) -> Result<(), Error> {
+        let Some(config) = &configs.elgamal else {
+            log::error!("ElGamal config not initialized");
+            return Err(Error::Synthesis);
+        };
+
+        let message = encryption
+            .outputs
+            .iter()
+            .map(|o| outputs[*o].get_inner_tensor().cloned())
+            .collect::<Result<Vec<_>, _>>()
+            .and_then(|t| Tensor::new(Some(&t), &[t.len()])?.combine())
+            .map_err(|e| {
+                log::error!("failed to concatenate the encrypted outputs: {:?}", e);
+                Error::Synthesis
+            })?;
+        let message_len = message.len();
+        let witness = match &self.encryption {
+            Some(encryption) => encryption
+                .witness()
+                .into_iter()
+                .map(Value::known)
+                .collect_vec(),
+            None => vec![Value::unknown(); WITNESS_LEN],
+        };
+
+        // the scalar multiplications lay out in their own columns and the masks hash in the
+        // poseidon columns of module 0
+        layouter.assign_region(|| "_enter_module_0", |_| Ok(()))?;
+        let chip = ModuleElGamal::new(config.clone());
+        let mut x = vec![
+            message.into(),
+            Tensor::from(witness.into_iter().map(ValType::from)).into(),
+        ];
+        Self::layout_module(&chip, layouter, &mut x, instance_offset, constants)?;
+        // the ciphertext of the message follows the public key and the ephemeral key
+        *instance_offset += message_len;
+        Ok(())
+    }
+
+    /// Layout the session digest of the outputs. The digest of the previous step is constrained to the instance at
+    /// `instance_offset` and the digest it chains to to the instance that follows it.
+    pub fn layout_session(
+        &self,
+        layouter: &mut impl Layouter<Fp>,
+        configs: &ModuleConfigs,
+        outputs: &[ValTensor<Fp>],
+        instance_offset: usize,
+        constants: &mut ConstantsMap<Fp>,
+    ) -> Result<(), Error> {
+        let Some(config) = &configs.session else {
+            log::error!("Session config not initialized");
+            return Err(Error::Synthesis);
+        };
+
+        let message = outputs
+            .iter()
+            .map(|v| v.get_inner_tensor().cloned())
+            .collect::<Result<Vec<_>, _>>()
+            .and_then(|t| Tensor::new(Some(&t), &[t.len()])?.combine())
+            .map_err(|e| {
+                log::error!("failed to concatenate the session outputs: {:?}", e);
+                Error::Synthesis
+            })?;
+        let prev_digest = match self.prev_digest {
+            Some(prev_digest) => Value::known(prev_digest),
+            None => Value::unknown(),
+        };
+
+        // the digests hash in the poseidon columns of module 0
+        layouter.assign_region(|| "_enter_module_0", |_| Ok(()))?;
+        let chip = ModuleSession::new(config.clone());
+        let mut x = vec![
+            message.into(),
+            Tensor::from([ValType::from(prev_digest)].into_iter()).into(),
+        ];
+        let mut offset = instance_offset;
+        Self::layout_module(&chip, layouter, &mut x, &mut offset, constants)
+    }
+
+    /// The commitment of the outputs their session digest is chained from
+    pub fn session_commitment(outputs: &[Tensor<Fp>]) -> Result<Fp, GraphError> {
+        let message = outputs.iter().flat_map(|x| x.to_vec()).collect_vec();
+        Ok(ModuleSession::commitment(message)?)
+    }
+
+    /// The commitment of the inputs their nullifier is derived from
+    pub fn nullifier_commitment(inputs: &[Tensor<Fp>]) -> Result<Fp, GraphError> {
+        let message = inputs.iter().flat_map(|x| x.to_vec()).collect_vec();
+        Ok(ModuleNullifier::commitment(message)?)
+    }
+
+    /// The digest of the signed inputs, as signed by the signer
+    pub fn signature_digest(
+        inputs: &[Tensor<Fp>],
+        signature: &InputSignature,
+    ) -> Result<Word, GraphError> {
+        let message = signature
+            .inputs
+            .iter()
+            .map(|i| {
+                inputs
+                    .get(*i)
+                    .map(|x| x.to_vec())
+                    .ok_or_else(|| GraphError::InvalidSignature(format!("no input {} to sign", i)))
+            })
+            .collect::<Result<Vec<_>, _>>()?
+            .concat();
+        let output = match signature.hash {
+            SignatureHash::Poseidon => ModulePoseidon::run(message)?.remove(0),
+            SignatureHash::Sha256 => ModuleSha256::run(message)?.remove(0),
+        };
+        Ok(Word::from_hash(signature.hash, &output))
+    }
+
     /// Run forward pass
     pub fn forward<Scheme: CommitmentScheme<Scalar = Fp, Curve = G1Affine>>(
         inputs: &[Tensor<Scheme::Scalar>],
         element_visibility: &Visibility,
+        poseidon_params: PoseidonParams,
+        blinding: bool,
         vk: Option<&VerifyingKey<G1Affine>>,
         srs: Option<&Scheme::ParamsProver>,
     ) -> Result<ModuleForwardResult, GraphError> {
         let mut poseidon_hash = None;
         let mut polycommit = None;
+        let mut polycommit_blinders = None;
+        let mut sha256_hash = None;
+        let mut merkle_leaf = None;
 
-        if element_visibility.is_hashed() {
+        if element_visibility.is_sha256_hashed() {
+            let digests = inputs
+                .iter()
+                .map(|x| Ok(ModuleSha256::run(x.to_vec())?.remove(0)))
+                .collect::<Result<Vec<_>, GraphError>>()?;
+            sha256_hash = Some(digests.concat());
+        } else if element_visibility.is_hashed() {
             let field_elements = inputs.iter().fold(vec![], |mut acc, x| {
-                let res = ModulePoseidon::run(x.to_vec()).unwrap()[0].clone();
+                let res = match poseidon_params {
+                    PoseidonParams::Ezkl => ModulePoseidon::run(x.to_vec()),
+                    PoseidonParams::Circomlib => ModuleCircomlib::run(x.to_vec()),
+                }
+                .unwrap()[0]
+                    .clone();
                 acc.extend(res);
                 acc
             });
             poseidon_hash = Some(field_elements);
         }
 
+        if element_visibility.is_merkle() {
+            let message = inputs.iter().flat_map(|x| x.to_vec()).collect_vec();
+            merkle_leaf = Some(ModuleMerkle::leaf(message)?);
+        }
+
         if element_visibility.is_polycommit() {
+            // a fresh blinder is drawn for each committed tensor, whether or not it is committed to
+            // here
+            let blinders = blinding.then(|| {
+                inputs
+                    .iter()
+                    .map(|_| Fp::random(rand::rngs::OsRng))
+                    .collect_vec()
+            });
             if let Some(vk) = vk {
                 if let Some(srs) = srs {
-                    let commitments = inputs.iter().fold(vec![], |mut acc, x| {
+                    let commitments = inputs.iter().enumerate().fold(vec![], |mut acc, (i, x)| {
+                        let mut message = x.to_vec();
+                        message.extend(blinders.as_ref().map(|b| b[i]));
                         let res = PolyCommitChip::commit::<Scheme>(
-                            x.to_vec(),
+                            message,
                             (vk.cs().blinding_factors() + 1) as u32,
                             srs,
                         );
@@ -330,11 +1134,16 @@ impl GraphModules {
                     "no verifying key provided for polycommit. processed value will be none"
                 );
             }
+            polycommit_blinders = blinders.map(PolyCommitBlinders::Raw);
         }
 
         Ok(ModuleForwardResult {
             poseidon_hash,
             polycommit,
+            sha256_hash,
+            merkle_leaf,
+            merkle_path: None,
+            polycommit_blinders,
         })
     }
 }
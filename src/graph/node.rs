@@ -2,6 +2,10 @@ use super::scale_to_multiplier;
 #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
 use super::utilities::node_output_shapes;
 #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+use super::QuantRounding;
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+use super::RescaleTarget;
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
 use super::VarScales;
 #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
 use super::Visibility;
@@ -10,6 +14,7 @@ use crate::circuit::lookup::LookupOp;
 use crate::circuit::poly::PolyOp;
 use crate::circuit::CircuitError;
 use crate::circuit::Constant;
+use crate::circuit::DivRounding;
 use crate::circuit::Input;
 use crate::circuit::Op;
 use crate::circuit::Unknown;
@@ -121,6 +126,7 @@ impl RebaseScale {
         op_out_scale: crate::Scale,
         scale_rebase_multiplier: u32,
         div_rebasing: bool,
+        div_rounding: DivRounding,
     ) -> SupportedOp {
         if (op_out_scale > (global_scale * scale_rebase_multiplier as i32))
             && !inner.is_constant()
@@ -138,6 +144,7 @@ impl RebaseScale {
                     rebase_op: HybridOp::Div {
                         denom: crate::circuit::utils::F32((multiplier) as f32),
                         use_range_check_for_int: !div_rebasing,
+                        rounding: div_rounding,
                     },
                     original_scale: op.original_scale,
                 })
@@ -149,6 +156,7 @@ impl RebaseScale {
                     rebase_op: HybridOp::Div {
                         denom: crate::circuit::utils::F32(multiplier as f32),
                         use_range_check_for_int: !div_rebasing,
+                        rounding: div_rounding,
                     },
                     original_scale: op_out_scale,
                 })
@@ -164,6 +172,7 @@ impl RebaseScale {
         target_scale: crate::Scale,
         op_out_scale: crate::Scale,
         div_rebasing: bool,
+        div_rounding: DivRounding,
     ) -> SupportedOp {
         if (op_out_scale < (target_scale)) && !inner.is_constant() && !inner.is_input() {
             let multiplier = scale_to_multiplier(op_out_scale - target_scale);
@@ -177,6 +186,7 @@ impl RebaseScale {
                     rebase_op: HybridOp::Div {
                         denom: crate::circuit::utils::F32((multiplier) as f32),
                         use_range_check_for_int: !div_rebasing,
+                        rounding: div_rounding,
                     },
                 })
             } else {
@@ -188,6 +198,7 @@ impl RebaseScale {
                     rebase_op: HybridOp::Div {
                         denom: crate::circuit::utils::F32(multiplier as f32),
                         use_range_check_for_int: !div_rebasing,
+                        rounding: div_rounding,
                     },
                 })
             }
@@ -233,6 +244,98 @@ impl Op<Fp> for RebaseScale {
     }
 }
 
+/// An explicit requantization of the output of an operation to a scale, as requested by a rescale override of the
+/// node in place of the rescale ezkl would insert on its own.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RescaleTo {
+    /// The operation whose output is rescaled.
+    pub inner: Box<SupportedOp>,
+    /// The scale the output is rescaled to.
+    pub scale: crate::Scale,
+    /// The scale of the output of the operation.
+    pub original_scale: crate::Scale,
+    /// Whether the output is divided down with a lookup rather than a range check
+    pub div_rebasing: bool,
+    /// How the output is rounded when divided down
+    pub div_rounding: DivRounding,
+}
+
+impl RescaleTo {
+    /// Rescales the output of `inner` from `op_out_scale` to `scale`. Inputs and constants are quantized at their
+    /// scale in the first place and are left as they are, as are outputs already at the scale.
+    pub fn rescale(
+        inner: SupportedOp,
+        scale: crate::Scale,
+        op_out_scale: crate::Scale,
+        div_rebasing: bool,
+        div_rounding: DivRounding,
+    ) -> SupportedOp {
+        if op_out_scale == scale || inner.is_constant() || inner.is_input() {
+            return inner;
+        }
+        SupportedOp::RescaleTo(RescaleTo {
+            inner: Box::new(inner),
+            scale,
+            original_scale: op_out_scale,
+            div_rebasing,
+            div_rounding,
+        })
+    }
+}
+
+impl Op<Fp> for RescaleTo {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_string(&self) -> String {
+        format!(
+            "RESCALED TO (scale={}, from={}) ({})",
+            self.scale,
+            self.original_scale,
+            self.inner.as_string()
+        )
+    }
+
+    fn out_scale(&self, _: Vec<crate::Scale>) -> Result<crate::Scale, CircuitError> {
+        Ok(self.scale)
+    }
+
+    fn layout(
+        &self,
+        config: &mut crate::circuit::BaseConfig<Fp>,
+        region: &mut crate::circuit::region::RegionCtx<Fp>,
+        values: &[crate::tensor::ValTensor<Fp>],
+    ) -> Result<Option<crate::tensor::ValTensor<Fp>>, CircuitError> {
+        let original_res = self
+            .inner
+            .layout(config, region, values)?
+            .ok_or(CircuitError::MissingLayout(self.as_string()))?;
+        let scale_diff = self.original_scale - self.scale;
+        if scale_diff > 0 {
+            let rebase_op = HybridOp::Div {
+                denom: crate::circuit::utils::F32(scale_to_multiplier(scale_diff) as f32),
+                use_range_check_for_int: !self.div_rebasing,
+                rounding: self.div_rounding,
+            };
+            rebase_op.layout(config, region, &[original_res])
+        } else {
+            let multiplier = scale_to_multiplier(-scale_diff) as u128;
+            let mut res = crate::circuit::layouts::rescale(
+                config,
+                region,
+                &[original_res],
+                &[(0, multiplier)],
+            )?;
+            Ok(res.pop())
+        }
+    }
+
+    fn clone_dyn(&self) -> Box<dyn Op<Fp>> {
+        Box::new(self.clone()) // Forward to the derive(Clone) impl
+    }
+}
+
 /// A single operation in a [crate::graph::Model].
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum SupportedOp {
@@ -252,6 +355,8 @@ pub enum SupportedOp {
     Rescaled(Rescaled),
     ///
     RebaseScale(RebaseScale),
+    ///
+    RescaleTo(RescaleTo),
 }
 
 impl SupportedOp {
@@ -260,6 +365,7 @@ impl SupportedOp {
         match self {
             SupportedOp::Nonlinear(_) => true,
             SupportedOp::RebaseScale(op) => op.inner.is_lookup(),
+            SupportedOp::RescaleTo(op) => op.inner.is_lookup(),
             _ => false,
         }
     }
@@ -325,6 +431,7 @@ impl SupportedOp {
             SupportedOp::Unknown(op) => op,
             SupportedOp::Rescaled(op) => op,
             SupportedOp::RebaseScale(op) => op,
+            SupportedOp::RescaleTo(op) => op,
         }
     }
 
@@ -340,6 +447,63 @@ impl SupportedOp {
             _ => false,
         }
     }
+
+    /// check if the operation outputs a tensor constrained to {0,1} whatever its inputs, as a comparison or a
+    /// boolean input does
+    pub fn is_boolean_source(&self) -> bool {
+        match self {
+            SupportedOp::Input(op) => matches!(op.datum_type, crate::circuit::InputType::Bool),
+            // the values of fixed constants are known to the verifier, not those of private or public parameters
+            SupportedOp::Constant(op) => {
+                matches!(
+                    op.quantized_values.visibility(),
+                    Some(super::Visibility::Fixed)
+                ) && op
+                    .quantized_values
+                    .iter()
+                    .all(|x| *x == Fp::zero() || *x == Fp::one())
+            }
+            SupportedOp::Linear(op) => matches!(
+                op,
+                PolyOp::Not { .. } | PolyOp::And { .. } | PolyOp::Or { .. } | PolyOp::Xor { .. }
+            ),
+            SupportedOp::Nonlinear(op) => matches!(op, LookupOp::EqualsConst { .. }),
+            SupportedOp::Hybrid(op) => matches!(
+                op,
+                HybridOp::Greater
+                    | HybridOp::GreaterEqual
+                    | HybridOp::Less
+                    | HybridOp::LessEqual
+                    | HybridOp::Equals
+            ),
+            // rescaling the inputs of a comparison doesn't change its output
+            SupportedOp::Rescaled(op) => op.inner.is_boolean_source(),
+            _ => false,
+        }
+    }
+
+    /// The inputs whose values the operation only moves around, as a reshape or a slice does, such that its output
+    /// is boolean when they all are. Empty for operations computing new values.
+    pub fn forwarded_inputs(&self, num_inputs: usize) -> Vec<usize> {
+        match self {
+            SupportedOp::Linear(PolyOp::Concat { .. }) => (0..num_inputs).collect(),
+            SupportedOp::Linear(
+                PolyOp::Reshape(_)
+                | PolyOp::Flatten(_)
+                | PolyOp::MoveAxis { .. }
+                | PolyOp::MultiBroadcastTo { .. }
+                | PolyOp::Slice { .. }
+                | PolyOp::Downsample { .. }
+                | PolyOp::Pad(_)
+                | PolyOp::Trilu { .. }
+                | PolyOp::GatherElements { .. }
+                | PolyOp::GatherND { .. }
+                | PolyOp::Identity { out_scale: None },
+            )
+            | SupportedOp::Hybrid(HybridOp::Gather { .. }) => vec![0],
+            _ => vec![],
+        }
+    }
 }
 
 impl From<Box<dyn Op<Fp>>> for SupportedOp {
@@ -373,6 +537,9 @@ impl From<Box<dyn Op<Fp>>> for SupportedOp {
         if let Some(op) = value.as_any().downcast_ref::<RebaseScale>() {
             return SupportedOp::RebaseScale(op.clone());
         };
+        if let Some(op) = value.as_any().downcast_ref::<RescaleTo>() {
+            return SupportedOp::RescaleTo(op.clone());
+        };
 
         log::error!("Unsupported op type");
         log::warn!("defaulting to Unknown");
@@ -439,6 +606,13 @@ pub struct Node {
     pub idx: usize,
     /// The node's num of uses
     pub num_uses: usize,
+    /// The node's name in the onnx graph
+    #[serde(default)]
+    pub name: String,
+    /// Whether the node's output is constrained to {0,1}, by its own op or by those it forwards the values of (see
+    /// [SupportedOp::forwarded_inputs]), such that logical ops don't constrain it again
+    #[serde(default)]
+    pub out_boolean: bool,
 }
 
 #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
@@ -581,6 +755,7 @@ impl Node {
                         constant,
                         in_scales.clone(),
                         &run_args.param_visibility,
+                        &run_args.rounding,
                         input_node.num_uses(),
                     )?;
                     input_node.replace_opkind(constant.clone_dyn().into());
@@ -595,13 +770,25 @@ impl Node {
         let mut out_scale = opkind.out_scale(in_scales.clone())?;
         // rescale the inputs if necessary to get consistent fixed points, we select the largest scale (highest precision)
         let global_scale = scales.get_max();
-        opkind = RebaseScale::rebase(
-            opkind,
-            global_scale,
-            out_scale,
-            scales.rebase_multiplier,
-            run_args.div_rebasing,
-        );
+        opkind = match run_args.rescale_override(idx) {
+            // the output keeps the scale of the op
+            Some(RescaleTarget::Keep) => opkind,
+            Some(RescaleTarget::Scale(scale)) => RescaleTo::rescale(
+                opkind,
+                scale,
+                out_scale,
+                run_args.div_rebasing,
+                run_args.div_rounding,
+            ),
+            None => RebaseScale::rebase(
+                opkind,
+                global_scale,
+                out_scale,
+                scales.rebase_multiplier,
+                run_args.div_rebasing,
+                run_args.div_rounding,
+            ),
+        };
 
         out_scale = opkind.out_scale(in_scales)?;
 
@@ -614,6 +801,17 @@ impl Node {
             out_dims = vec![1];
         }
 
+        let forwarded = opkind.forwarded_inputs(input_ids.len());
+        let out_boolean = opkind.is_boolean_source()
+            || (!forwarded.is_empty()
+                && forwarded.iter().all(|i| {
+                    let (input_idx, outlet) = input_ids[*i];
+                    inputs
+                        .iter()
+                        .find(|x| x.idx() == input_idx)
+                        .is_some_and(|x| outlet == 0 && x.out_boolean())
+                }));
+
         Ok(Node {
             idx,
             opkind,
@@ -621,6 +819,8 @@ impl Node {
             out_dims,
             out_scale,
             num_uses,
+            name: node.name.clone(),
+            out_boolean,
         })
     }
 
@@ -639,6 +839,7 @@ fn rescale_const_with_single_use(
     constant: &mut Constant<Fp>,
     in_scales: Vec<crate::Scale>,
     param_visibility: &Visibility,
+    rounding: &QuantRounding,
     num_uses: usize,
 ) -> Result<(), GraphError> {
     if num_uses == 1 {
@@ -647,7 +848,7 @@ fn rescale_const_with_single_use(
         if scale_max > &current_scale {
             let raw_values = constant.raw_values.clone();
             constant.quantized_values =
-                super::quantize_tensor(raw_values, *scale_max, param_visibility)?;
+                super::quantize_tensor(raw_values, *scale_max, param_visibility, rounding)?;
         }
     }
 
@@ -0,0 +1,335 @@
+//! Groups of params committed to separately, such that the commitments of a group can be swapped into a proof and
+//! checked by the verifier without those of the other groups.
+//!
+//! The params of a polycommit circuit are committed to by group, each group being laid out in its own polycommit
+//! module: the params of the nodes of the [CommitmentGroup]s of the run args are committed to in their groups, as
+//! they are listed, and those of the other nodes in the [DEFAULT_COMMITMENT_GROUP], last. Without groups all the
+//! params are in the default group, committed to as a single tensor as before the groups.
+
+use super::errors::GraphError;
+use super::{CommitmentGroup, GraphSettings, GraphWitness, DEFAULT_COMMITMENT_GROUP};
+use crate::tensor::{Tensor, TensorType};
+use halo2curves::bn256::{Fr as Fp, G1Affine};
+use serde::{Deserialize, Serialize};
+
+/// The group of each constant of a model, given the nodes each constant is grouped by (see
+/// [super::Model::const_nodes]), as the index of the group among `groups`: the constants of the nodes of no group
+/// are in the default group, of index `groups.len()`
+pub fn param_group_indices(
+    const_nodes: &[Vec<usize>],
+    groups: &[CommitmentGroup],
+) -> Result<Vec<usize>, GraphError> {
+    const_nodes
+        .iter()
+        .enumerate()
+        .map(|(param, nodes)| {
+            let mut in_groups = groups
+                .iter()
+                .enumerate()
+                .filter(|(_, group)| nodes.iter().any(|n| group.nodes.contains(n)));
+            match (in_groups.next(), in_groups.next()) {
+                (None, _) => Ok(groups.len()),
+                (Some((idx, _)), None) => Ok(idx),
+                (Some((_, first)), Some((_, second))) => {
+                    Err(GraphError::InvalidCommitmentGroups(format!(
+                        "the constant of node {} is in both groups {} and {}",
+                        nodes[0], first.name, second.name
+                    )))
+                }
+            }
+        })
+        .collect()
+}
+
+/// The indices of the groups holding params, in order
+fn present_groups(indices: &[usize]) -> Vec<usize> {
+    let mut present = indices.to_vec();
+    present.sort_unstable();
+    present.dedup();
+    present
+}
+
+/// The groups the params are committed to in, in order, given the group of each constant (see
+/// [param_group_indices]) and its number of values. A group of the run args without params is an error, the default
+/// group is only committed to if it holds params.
+pub fn committed_groups(
+    indices: &[usize],
+    lens: &[usize],
+    groups: &[CommitmentGroup],
+) -> Result<Vec<String>, GraphError> {
+    let name = |idx: usize| {
+        groups
+            .get(idx)
+            .map_or(DEFAULT_COMMITMENT_GROUP, |group| &group.name)
+            .to_string()
+    };
+    let present = present_groups(indices);
+    if let Some((_, group)) = groups
+        .iter()
+        .enumerate()
+        .find(|(idx, _)| !present.contains(idx))
+    {
+        return Err(GraphError::InvalidCommitmentGroups(format!(
+            "no params are in group {}",
+            group.name
+        )));
+    }
+    present
+        .into_iter()
+        .zip(group_lens(indices, lens))
+        .map(|(group, len)| {
+            // an empty tensor isn't committed to, and its group would have no polycommit module
+            match len {
+                0 => Err(GraphError::InvalidCommitmentGroups(format!(
+                    "the params of group {} have no values",
+                    name(group)
+                ))),
+                _ => Ok(name(group)),
+            }
+        })
+        .collect()
+}
+
+/// The params of each committed group, flattened in the order of the constants, given the group of each constant
+/// (see [param_group_indices])
+pub fn group_params<T: TensorType>(params: &[Tensor<T>], indices: &[usize]) -> Vec<Vec<T>> {
+    present_groups(indices)
+        .into_iter()
+        .map(|group| {
+            params
+                .iter()
+                .zip(indices)
+                .filter(|(_, idx)| **idx == group)
+                .flat_map(|(param, _)| param.iter().cloned())
+                .collect()
+        })
+        .collect()
+}
+
+/// The number of values of the params of each committed group, given the group of each constant (see
+/// [param_group_indices]) and its number of values
+pub fn group_lens(indices: &[usize], lens: &[usize]) -> Vec<usize> {
+    present_groups(indices)
+        .into_iter()
+        .map(|group| {
+            indices
+                .iter()
+                .zip(lens)
+                .filter(|(idx, _)| **idx == group)
+                .map(|(_, len)| len)
+                .sum()
+        })
+        .collect()
+}
+
+/// The constants in their order, the inverse of [group_params] given the shape of each constant, where `split`
+/// splits the params of a group into tensors of the shapes of its constants
+pub fn ungroup_params<T: Clone>(
+    grouped: &[T],
+    indices: &[usize],
+    shapes: &[Vec<usize>],
+    split: impl Fn(&T, Vec<Vec<usize>>) -> Result<Vec<T>, GraphError>,
+) -> Result<Vec<T>, GraphError> {
+    let mut params = vec![None; indices.len()];
+    for (group, values) in present_groups(indices).into_iter().zip(grouped) {
+        let members = (0..indices.len())
+            .filter(|i| indices[*i] == group)
+            .collect::<Vec<_>>();
+        let split = split(values, members.iter().map(|i| shapes[*i].clone()).collect())?;
+        for (i, param) in members.into_iter().zip(split) {
+            params[i] = Some(param);
+        }
+    }
+    params
+        .into_iter()
+        .map(|param| {
+            param.ok_or_else(|| {
+                GraphError::InvalidCommitmentGroups("the params of a group are missing".to_string())
+            })
+        })
+        .collect()
+}
+
+/// The commitments of the params of a group, as written by `commit-param-groups`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GroupCommitments {
+    /// the name of the group
+    pub group: String,
+    /// the index of the first commitment of the group among the polycommit commitments of a proof, those of the
+    /// inputs coming before those of the params
+    pub offset: usize,
+    /// the commitments of the group, one per column of the committed params
+    pub commitments: Vec<G1Affine>,
+}
+
+impl GroupCommitments {
+    /// Save the commitments to a file
+    pub fn save(&self, path: &std::path::PathBuf) -> Result<(), GraphError> {
+        let f = std::fs::File::create(path).map_err(|e| {
+            GraphError::ReadWriteFileError(path.display().to_string(), e.to_string())
+        })?;
+        serde_json::to_writer(std::io::BufWriter::new(f), self)?;
+        Ok(())
+    }
+
+    /// Load the commitments from a file
+    pub fn load(path: &std::path::PathBuf) -> Result<Self, GraphError> {
+        let bytes = std::fs::read(path).map_err(|e| {
+            GraphError::ReadWriteFileError(path.display().to_string(), e.to_string())
+        })?;
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+}
+
+/// The commitments of each group of params of a witness, in the order of the groups of the settings
+pub fn group_commitments(
+    settings: &GraphSettings,
+    witness: &GraphWitness,
+) -> Result<Vec<GroupCommitments>, GraphError> {
+    let commitments = |processed: &Option<super::modules::ModuleForwardResult>| {
+        processed
+            .as_ref()
+            .and_then(|p| p.polycommit.clone())
+            .unwrap_or_default()
+    };
+    let params = commitments(&witness.processed_params);
+    if params.is_empty() || params.len() != settings.param_commitment_groups.len() {
+        return Err(GraphError::InvalidCommitmentGroups(format!(
+            "the witness commits to {} groups of params but the settings to {}, was it generated with the verifying key and srs?",
+            params.len(),
+            settings.param_commitment_groups.len()
+        )));
+    }
+
+    let mut offset = commitments(&witness.processed_inputs)
+        .iter()
+        .map(Vec::len)
+        .sum::<usize>();
+    Ok(settings
+        .param_commitment_groups
+        .iter()
+        .zip(params)
+        .map(|(group, commitments)| {
+            let group = GroupCommitments {
+                group: group.clone(),
+                offset,
+                commitments,
+            };
+            offset += group.commitments.len();
+            group
+        })
+        .collect())
+}
+
+/// The params of a model flattened by group, ready to be committed to
+pub(crate) fn grouped_param_tensors(
+    params: &[Tensor<Fp>],
+    indices: &[usize],
+) -> Result<Vec<Tensor<Fp>>, GraphError> {
+    group_params(params, indices)
+        .into_iter()
+        .map(|group| Ok(Tensor::new(Some(&group), &[group.len()])?))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn group(name: &str, nodes: Vec<usize>) -> CommitmentGroup {
+        CommitmentGroup {
+            name: name.to_string(),
+            nodes,
+        }
+    }
+
+    #[test]
+    fn params_grouped_by_node() {
+        // constants 0 and 2 feed node 5, constant 1 feeds node 7 and constant 3 is in a subgraph (node 9)
+        let const_nodes = vec![vec![0, 5], vec![1, 7], vec![2, 5], vec![9]];
+        let lens = vec![2, 1, 3, 1];
+        let groups = vec![group("head", vec![5])];
+
+        let indices = param_group_indices(&const_nodes, &groups).unwrap();
+        assert_eq!(indices, vec![0, 1, 0, 1]);
+        assert_eq!(
+            committed_groups(&indices, &lens, &groups).unwrap(),
+            vec!["head".to_string(), DEFAULT_COMMITMENT_GROUP.to_string()]
+        );
+
+        let params: Vec<Tensor<usize>> = vec![
+            Tensor::new(Some(&[1, 2]), &[2]).unwrap(),
+            Tensor::new(Some(&[3]), &[1]).unwrap(),
+            Tensor::new(Some(&[4, 5, 6]), &[3, 1]).unwrap(),
+            Tensor::new(Some(&[7]), &[1]).unwrap(),
+        ];
+        let grouped = group_params(&params, &indices);
+        assert_eq!(grouped, vec![vec![1, 2, 4, 5, 6], vec![3, 7]]);
+
+        let shapes = params.iter().map(|p| p.dims().to_vec()).collect::<Vec<_>>();
+        let grouped = grouped
+            .into_iter()
+            .map(|g| Tensor::new(Some(&g), &[g.len()]).unwrap())
+            .collect::<Vec<_>>();
+        fn split(
+            group: &Tensor<usize>,
+            shapes: Vec<Vec<usize>>,
+        ) -> Result<Vec<Tensor<usize>>, GraphError> {
+            let mut start = 0;
+            shapes
+                .into_iter()
+                .map(|shape| -> Result<Tensor<usize>, GraphError> {
+                    let len = shape.iter().product::<usize>();
+                    let mut t = group.get_slice(&[start..start + len])?;
+                    t.reshape(&shape)?;
+                    start += len;
+                    Ok(t)
+                })
+                .collect()
+        }
+        assert_eq!(
+            ungroup_params(&grouped, &indices, &shapes, split).unwrap(),
+            params
+        );
+    }
+
+    #[test]
+    fn without_groups_params_are_committed_together() {
+        let const_nodes = vec![vec![0, 2], vec![1, 2]];
+        let indices = param_group_indices(&const_nodes, &[]).unwrap();
+        assert_eq!(indices, vec![0, 0]);
+        assert_eq!(
+            committed_groups(&indices, &[2, 2], &[]).unwrap(),
+            vec![DEFAULT_COMMITMENT_GROUP.to_string()]
+        );
+
+        // a model whose params are all grouped doesn't commit to the default group
+        let groups = vec![group("all", vec![2])];
+        let indices = param_group_indices(&const_nodes, &groups).unwrap();
+        assert_eq!(
+            committed_groups(&indices, &[2, 2], &groups).unwrap(),
+            vec!["all".to_string()]
+        );
+    }
+
+    #[test]
+    fn invalid_groups() {
+        let const_nodes = vec![vec![0, 2], vec![1, 3]];
+
+        // the constant of node 0 feeds node 2 of the first group but is itself in the second
+        let overlapping = vec![group("a", vec![2]), group("b", vec![0])];
+        assert!(matches!(
+            param_group_indices(&const_nodes, &overlapping),
+            Err(GraphError::InvalidCommitmentGroups(_))
+        ));
+
+        // no constant feeds node 4
+        let empty = vec![group("a", vec![4])];
+        let indices = param_group_indices(&const_nodes, &empty).unwrap();
+        assert!(committed_groups(&indices, &[1, 1], &empty).is_err());
+
+        // nor are groups of empty constants committed to
+        let indices = param_group_indices(&const_nodes, &[group("a", vec![2])]).unwrap();
+        assert!(committed_groups(&indices, &[0, 1], &[group("a", vec![2])]).is_err());
+    }
+}
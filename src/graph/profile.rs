@@ -0,0 +1,178 @@
+use crate::circuit::lookup::LookupOp;
+use crate::circuit::region::OpProfile;
+use crate::circuit::table::Range;
+use serde::{Deserialize, Serialize};
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+use tabled::{Table, Tabled};
+
+/// The rows, lookups and equality constraints of a circuit, broken down by the op (and node) that laid them out
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct CircuitProfile {
+    /// the number of rows of the circuit
+    pub total_rows: usize,
+    /// the number of cells assigned in the circuit
+    pub total_assignments: usize,
+    /// the number of looked up values of the circuit
+    pub total_lookups: usize,
+    /// the number of equality constraints of the circuit
+    pub total_equalities: usize,
+    /// the profiles of the ops, sorted by the rows they take
+    pub ops: Vec<OpProfile>,
+}
+
+/// A lookup the circuit of a model requires, with the range of the inputs its table spans
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LookupRequirement {
+    /// the op looked up
+    pub op: LookupOp,
+    /// the smallest and largest inputs of its table, the lookup range unless the table is sized to the inputs of its
+    /// nodes (see [LookupOp::tightens_table_range])
+    pub range: Range,
+}
+
+/// The shape of the circuit of a model as laid out by a dry run of its layout, without keygen, see
+/// [crate::graph::Model::estimate_layout]. The model rows, assignments, lookups and range checks are those the real
+/// layout of the model region lays out.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct LayoutEstimate {
+    /// the number of rows of the circuit, the model region or the modules if they take more
+    pub total_rows: usize,
+    /// the number of rows of the model region
+    pub model_rows: usize,
+    /// the number of cells assigned in the model region
+    pub total_assignments: usize,
+    /// the rows, lookups and equality constraints of each op, in the order they are laid out
+    pub ops: Vec<OpProfile>,
+    /// the distinct lookups of the circuit
+    pub lookups: Vec<LookupRequirement>,
+    /// the distinct lookups fed the sums of adds
+    pub fused_add_lookups: Vec<LookupRequirement>,
+    /// the distinct range checks of the circuit
+    pub range_checks: Vec<Range>,
+    /// the number of dynamic lookups
+    pub num_dynamic_lookups: usize,
+    /// the number of shuffles
+    pub num_shuffles: usize,
+    /// the number of instances of each instance column
+    pub num_instances: Vec<usize>,
+    /// the fewest logrows the circuit is laid out in, the lookup tables aside as they are split across more columns at
+    /// fewer rows
+    pub min_logrows: u32,
+}
+
+/// What the real layout of the model region laid out, recorded such that it can be checked against a
+/// [LayoutEstimate], see [crate::graph::take_model_layout]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct ModelLayout {
+    /// the rows of the model region
+    pub rows: usize,
+    /// the cells assigned in the model region
+    pub assignments: usize,
+    /// the ops looked up
+    pub lookups: Vec<LookupOp>,
+    /// the ops looked up fed the sums of adds
+    pub fused_add_lookups: Vec<LookupOp>,
+    /// the ranges checked
+    pub range_checks: Vec<Range>,
+}
+
+impl LayoutEstimate {
+    /// Whether the real layout of the model region laid out what the estimate predicts
+    pub fn matches(&self, layout: &ModelLayout) -> bool {
+        self.model_rows == layout.rows
+            && self.total_assignments == layout.assignments
+            && self.lookups.iter().map(|l| &l.op).eq(layout.lookups.iter())
+            && self
+                .fused_add_lookups
+                .iter()
+                .map(|l| &l.op)
+                .eq(layout.fused_add_lookups.iter())
+            && self.range_checks == layout.range_checks
+    }
+}
+
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+#[derive(Tabled)]
+struct ProfileRow {
+    node: usize,
+    op: String,
+    rows: usize,
+    share: String,
+    assignments: usize,
+    lookups: usize,
+    equalities: usize,
+}
+
+impl CircuitProfile {
+    /// Creates a profile, sorting the ops by the rows they take (most first)
+    pub fn new(
+        total_rows: usize,
+        total_assignments: usize,
+        total_lookups: usize,
+        total_equalities: usize,
+        mut ops: Vec<OpProfile>,
+    ) -> Self {
+        ops.sort_by(|a, b| b.rows.cmp(&a.rows).then(a.node.cmp(&b.node)));
+        CircuitProfile {
+            total_rows,
+            total_assignments,
+            total_lookups,
+            total_equalities,
+            ops,
+        }
+    }
+
+    /// The fraction of the rows of the circuit taken by an op
+    pub fn row_share(&self, op: &OpProfile) -> f64 {
+        if self.total_rows == 0 {
+            return 0.0;
+        }
+        op.rows as f64 / self.total_rows as f64
+    }
+
+    /// Formats the profile as a table
+    #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+    pub fn table(&self) -> String {
+        let rows = self.ops.iter().map(|op| ProfileRow {
+            node: op.node,
+            op: op.op.clone(),
+            rows: op.rows,
+            share: format!("{:.2}%", 100.0 * self.row_share(op)),
+            assignments: op.assignments,
+            lookups: op.lookups,
+            equalities: op.equalities,
+        });
+        let mut table = Table::new(rows);
+        table.with(tabled::settings::Style::modern());
+        format!(
+            "{}\ntotal: {} rows, {} assignments, {} lookups, {} equalities",
+            table,
+            self.total_rows,
+            self.total_assignments,
+            self.total_lookups,
+            self.total_equalities
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn op(node: usize, rows: usize) -> OpProfile {
+        OpProfile {
+            node,
+            op: format!("OP{}", node),
+            rows,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn profile_sorted_by_rows() {
+        let profile = CircuitProfile::new(10, 0, 0, 0, vec![op(0, 2), op(1, 5), op(2, 2)]);
+        let nodes = profile.ops.iter().map(|op| op.node).collect::<Vec<_>>();
+        assert_eq!(nodes, vec![1, 0, 2]);
+        assert_eq!(profile.row_share(&profile.ops[0]), 0.5);
+    }
+}
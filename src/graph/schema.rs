@@ -0,0 +1,249 @@
+use super::errors::GraphError;
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+use super::input::GraphData;
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+use super::{GraphSettings, GraphWitness};
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+use log::warn;
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+use schemars::gen::SchemaGenerator;
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+use schemars::schema::{InstanceType, Schema, SchemaObject};
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+use tosubcommand::ToFlags;
+
+static LENIENT_JSON: AtomicBool = AtomicBool::new(false);
+
+/// Ignores the fields of the settings, input and witness .json files their types don't define instead of failing on
+/// them (the `--lenient-json` flag)
+pub fn set_lenient_json(lenient: bool) {
+    LENIENT_JSON.store(lenient, Ordering::Relaxed);
+}
+
+/// Whether unknown fields are ignored, either by flag or through the `EZKL_LENIENT_JSON` env var
+pub fn lenient_json() -> bool {
+    LENIENT_JSON.load(Ordering::Relaxed) || std::env::var("EZKL_LENIENT_JSON").is_ok()
+}
+
+/// The .json artifacts ezkl reads, which are loaded strictly and whose schemas are exported by `ezkl schema`
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize, Serialize, PartialOrd)]
+#[cfg_attr(
+    all(feature = "ezkl", not(target_arch = "wasm32")),
+    derive(clap::ValueEnum)
+)]
+pub enum JsonArtifact {
+    /// the circuit settings, see [GraphSettings]
+    Settings,
+    /// the input data, see [GraphData]
+    Input,
+    /// the witness, see [GraphWitness]
+    Witness,
+}
+
+impl std::fmt::Display for JsonArtifact {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                JsonArtifact::Settings => "settings",
+                JsonArtifact::Input => "input",
+                JsonArtifact::Witness => "witness",
+            }
+        )
+    }
+}
+
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+impl ToFlags for JsonArtifact {
+    fn to_flags(&self) -> Vec<String> {
+        vec![format!("{}", self)]
+    }
+}
+
+impl JsonArtifact {
+    /// The JSON schema of the artifact, generated from the type it is deserialized into
+    #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+    pub fn schema(&self) -> serde_json::Value {
+        let schema = match self {
+            JsonArtifact::Settings => schemars::schema_for!(GraphSettings),
+            JsonArtifact::Input => schemars::schema_for!(GraphData),
+            JsonArtifact::Witness => schemars::schema_for!(GraphWitness),
+        };
+        // a schema is a tree of maps with string keys, which always serializes
+        serde_json::to_value(schema).unwrap_or_default()
+    }
+
+    /// The fields that aren't part of the type of the artifact but are still accepted, as the `input_shapes` the
+    /// scripts generating the inputs of the examples write
+    #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+    fn legacy_fields(&self) -> &'static [&'static str] {
+        match self {
+            JsonArtifact::Input => &["input_shapes"],
+            JsonArtifact::Settings | JsonArtifact::Witness => &[],
+        }
+    }
+}
+
+/// Deserializes an artifact from json. Unless [lenient_json], a field the type of the artifact doesn't define is an
+/// error, reported before any error it causes (as a misspelt field that is then missing), and errors point at the
+/// path of the offending value as `run_args.logrows`.
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+pub fn from_json_slice<T: DeserializeOwned>(
+    bytes: &[u8],
+    artifact: JsonArtifact,
+) -> Result<T, GraphError> {
+    let mut unknown = vec![];
+    let mut deserializer = serde_json::Deserializer::from_slice(bytes);
+    let ignored = serde_ignored::Deserializer::new(&mut deserializer, |path| {
+        let path = path.to_string();
+        if !artifact.legacy_fields().contains(&path.as_str()) {
+            unknown.push(path);
+        }
+    });
+    let result: Result<T, _> = serde_path_to_error::deserialize(ignored);
+
+    if !unknown.is_empty() {
+        if !lenient_json() {
+            return Err(GraphError::InvalidJson {
+                path: unknown.swap_remove(0),
+                reason: format!(
+                    "unknown field of the {} (pass --lenient-json or set EZKL_LENIENT_JSON to ignore it)",
+                    artifact
+                ),
+            });
+        }
+        warn!(
+            "ignoring the unknown fields {:?} of the {}",
+            unknown, artifact
+        );
+    }
+    let value = result.map_err(|e| GraphError::InvalidJson {
+        path: e.path().to_string(),
+        reason: e.into_inner().to_string(),
+    })?;
+    deserializer.end().map_err(|e| GraphError::InvalidJson {
+        path: ".".to_string(),
+        reason: e.to_string(),
+    })?;
+    Ok(value)
+}
+
+/// Deserializes an artifact from json, ignoring the fields its type doesn't define as the strict checks and the
+/// paths of the errors are only part of the ezkl builds
+#[cfg(any(not(feature = "ezkl"), target_arch = "wasm32"))]
+pub fn from_json_slice<T: DeserializeOwned>(
+    bytes: &[u8],
+    _artifact: JsonArtifact,
+) -> Result<T, GraphError> {
+    serde_json::from_slice(bytes).map_err(|e| GraphError::InvalidJson {
+        path: ".".to_string(),
+        reason: e.to_string(),
+    })
+}
+
+/// The schema of a field element, serialized as a 0x prefixed little-endian hex string
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+pub(crate) struct FeltSchema;
+
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+impl JsonSchema for FeltSchema {
+    fn schema_name() -> String {
+        "Felt".to_string()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            format: Some("0x prefixed little-endian hex".to_string()),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+#[cfg(all(test, feature = "ezkl", not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use crate::graph::input::DataSource;
+
+    #[test]
+    fn settings_schema_lists_run_args() {
+        let schema = JsonArtifact::Settings.schema();
+        assert!(schema["properties"]["run_args"].is_object());
+        assert!(schema["definitions"]["RunArgs"]["properties"]["logrows"].is_object());
+        let input = JsonArtifact::Input.schema();
+        assert!(input["properties"]["input_data"].is_object());
+        assert!(input["required"]
+            .as_array()
+            .unwrap()
+            .contains(&serde_json::json!("input_data")));
+    }
+
+    #[test]
+    fn misspelt_input_field_is_reported_at_its_path() {
+        let err = from_json_slice::<GraphData>(br#"{"input_datas": [[1.0]]}"#, JsonArtifact::Input)
+            .unwrap_err();
+        assert!(
+            matches!(&err, GraphError::InvalidJson { path, .. } if path == "input_datas"),
+            "{}",
+            err
+        );
+        assert!(err.to_string().contains("`input_datas`"));
+
+        // the fields of the example inputs are still accepted
+        let data: GraphData = from_json_slice(
+            br#"{"input_shapes": [[1]], "input_data": [[1.0]]}"#,
+            JsonArtifact::Input,
+        )
+        .unwrap();
+        assert_eq!(data.input_data, DataSource::from(vec![vec![1.0]]));
+    }
+
+    #[test]
+    fn invalid_nested_value_is_reported_at_its_path() {
+        let mut settings = serde_json::to_value(GraphSettings::default()).unwrap();
+        settings["run_args"]["logrows"] = serde_json::json!("seventeen");
+        let err = from_json_slice::<GraphSettings>(
+            &serde_json::to_vec(&settings).unwrap(),
+            JsonArtifact::Settings,
+        )
+        .unwrap_err();
+        assert!(
+            matches!(&err, GraphError::InvalidJson { path, .. } if path == "run_args.logrows"),
+            "{}",
+            err
+        );
+
+        settings["run_args"]["logrows"] = serde_json::json!(17);
+        settings["run_args"]["logrowz"] = serde_json::json!(17);
+        let bytes = serde_json::to_vec(&settings).unwrap();
+        let err = from_json_slice::<GraphSettings>(&bytes, JsonArtifact::Settings).unwrap_err();
+        assert!(
+            matches!(&err, GraphError::InvalidJson { path, .. } if path == "run_args.logrowz"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn unknown_witness_field_is_reported_at_its_path() {
+        let mut witness = serde_json::to_value(GraphWitness::default()).unwrap();
+        witness["pretty_elements"] = serde_json::json!({ "rescaled_input": [] });
+        let err = from_json_slice::<GraphWitness>(
+            &serde_json::to_vec(&witness).unwrap(),
+            JsonArtifact::Witness,
+        )
+        .unwrap_err();
+        assert!(
+            matches!(&err, GraphError::InvalidJson { path, .. } if path == "pretty_elements.rescaled_input"),
+            "{}",
+            err
+        );
+    }
+}
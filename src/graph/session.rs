@@ -0,0 +1,159 @@
+//! Sessions of proofs, one per step of a sequence of inferences, chained by the digests of their outputs such that
+//! they can't be reordered (see `--session`).
+//!
+//! The circuit of a step takes the digest of the previous step as a public instance, and exposes the Poseidon hash of
+//! it and of the commitment of its outputs as the digest of the step, right after it. The first step of a session is
+//! chained to [GENESIS_DIGEST] unless it continues another session. A sequence of proofs is a session when the
+//! previous digest of each proof is the digest of the proof before it, which only holds in the order the witnesses
+//! were chained.
+
+use super::errors::GraphError;
+use super::GraphSettings;
+use halo2curves::bn256::Fr as Fp;
+use halo2curves::ff::Field;
+use serde::{Deserialize, Serialize};
+
+/// The digest the first step of a session is chained to
+pub const GENESIS_DIGEST: Fp = Fp::ZERO;
+
+/// The digests of a step of a session, as exposed by its proof
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionStep {
+    /// the index of the proof in the session
+    pub proof: usize,
+    /// the digest of the previous step the outputs are chained to
+    pub prev_digest: Fp,
+    /// the digest of the step
+    pub next_digest: Fp,
+}
+
+/// The previous digest and the digest of the step among the instances of a proof
+pub fn session_digests(settings: &GraphSettings, instances: &[Fp]) -> Result<(Fp, Fp), GraphError> {
+    let range = settings.session_instance_range().ok_or_else(|| {
+        GraphError::InvalidSession("the settings don't expose session digests".into())
+    })?;
+    match instances.get(range.clone()) {
+        Some([prev_digest, next_digest]) => Ok((*prev_digest, *next_digest)),
+        _ => Err(GraphError::InvalidSession(format!(
+            "expected {} instances, got {}",
+            range.end,
+            instances.len()
+        ))),
+    }
+}
+
+/// Checks that the proofs of a session, each with its settings and instances, are chained in order: the previous
+/// digest of each proof is the digest of the proof before it, and that of the first proof is `genesis` if any.
+pub fn check_session_chain(
+    settings: &[GraphSettings],
+    instances: &[Vec<Fp>],
+    genesis: Option<Fp>,
+) -> Result<Vec<SessionStep>, GraphError> {
+    if settings.len() != instances.len() {
+        return Err(GraphError::InvalidSession(format!(
+            "{} settings for {} proofs",
+            settings.len(),
+            instances.len()
+        )));
+    }
+
+    let mut steps: Vec<SessionStep> = vec![];
+    for (proof, (settings, instances)) in settings.iter().zip(instances).enumerate() {
+        let (prev_digest, next_digest) = session_digests(settings, instances)?;
+        let expected = match steps.last() {
+            Some(step) => Some(step.next_digest),
+            None => genesis,
+        };
+        if expected.is_some_and(|digest| digest != prev_digest) {
+            return Err(GraphError::BrokenSessionChain { proof });
+        }
+        steps.push(SessionStep {
+            proof,
+            prev_digest,
+            next_digest,
+        });
+    }
+    Ok(steps)
+}
+
+/// The pairs of instances of the proofs of a session an aggregation circuit constrains to be equal to verify the
+/// chain at once, the digest of each proof and the previous digest of the next, as `(proof, instance)`
+pub fn session_instance_links(
+    settings: &[GraphSettings],
+) -> Result<Vec<((usize, usize), (usize, usize))>, GraphError> {
+    let ranges = settings
+        .iter()
+        .map(|s| {
+            s.session_instance_range().ok_or_else(|| {
+                GraphError::InvalidSession("the settings don't expose session digests".into())
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(ranges
+        .windows(2)
+        .enumerate()
+        .map(|(i, pair)| ((i, pair[0].start + 1), (i + 1, pair[1].start)))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::modules::ModuleSession;
+
+    fn settings() -> GraphSettings {
+        GraphSettings {
+            session: true,
+            // the digests are laid out as a single instance here
+            model_instance_shapes: vec![vec![2]],
+            ..Default::default()
+        }
+    }
+
+    /// The instances of a session of steps with the given output commitments
+    fn session(commitments: &[u64]) -> Vec<Vec<Fp>> {
+        let mut prev_digest = GENESIS_DIGEST;
+        commitments
+            .iter()
+            .map(|c| {
+                let next_digest = ModuleSession::next_digest(prev_digest, Fp::from(*c));
+                let instances = vec![prev_digest, next_digest];
+                prev_digest = next_digest;
+                instances
+            })
+            .collect()
+    }
+
+    #[test]
+    fn chained_proofs_are_a_session() {
+        let instances = session(&[3, 5, 7]);
+        let settings = (0..3).map(|_| settings()).collect::<Vec<_>>();
+
+        let steps = check_session_chain(&settings, &instances, Some(GENESIS_DIGEST)).unwrap();
+        assert_eq!(steps.len(), 3);
+        assert_eq!(steps[2].next_digest, instances[2][1]);
+        assert_eq!(
+            session_instance_links(&settings).unwrap(),
+            vec![((0, 1), (1, 0)), ((1, 1), (2, 0))]
+        );
+
+        // a session continuing another isn't chained to the genesis digest
+        assert!(check_session_chain(&settings[1..], &instances[1..], None).is_ok());
+        assert!(matches!(
+            check_session_chain(&settings[1..], &instances[1..], Some(GENESIS_DIGEST)),
+            Err(GraphError::BrokenSessionChain { proof: 0 })
+        ));
+    }
+
+    #[test]
+    fn reordered_proofs_break_the_chain() {
+        let mut instances = session(&[3, 5, 7]);
+        instances.swap(1, 2);
+        let settings = (0..3).map(|_| settings()).collect::<Vec<_>>();
+
+        assert!(matches!(
+            check_session_chain(&settings, &instances, Some(GENESIS_DIGEST)),
+            Err(GraphError::BrokenSessionChain { proof: 1 })
+        ));
+    }
+}
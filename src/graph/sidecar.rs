@@ -0,0 +1,238 @@
+//! Witnesses whose large tensors are stored in a binary sidecar file next to their json, which is much faster to
+//! write and load than the hex strings of the json for models with millions of inputs or outputs.
+//!
+//! A tensor of the inputs or outputs with more field elements than the [super::WitnessFormat::sidecar_threshold] is
+//! written to the sidecar as an array of its field elements, each as the [FELT_BYTES] of its little-endian
+//! representation, and left empty in the json. The json indexes the tensors of the sidecar by their byte offset and
+//! number of elements in its `sidecar` field, and the felt strings of the pretty elements of a tensor of the sidecar
+//! are left out when they are those of its field elements, as they can be rebuilt from them. A witness is loaded with
+//! its sidecar by [GraphWitness::from_path], such that it is whole again.
+
+use super::errors::GraphError;
+use super::GraphWitness;
+use crate::EZKL_BUF_CAPACITY;
+use halo2curves::bn256::Fr as Fp;
+use halo2curves::ff::PrimeField;
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// The bytes of a field element in the sidecar
+pub const FELT_BYTES: usize = 32;
+
+/// The tensors of a witness that can be stored in its sidecar
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), derive(JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum SidecarField {
+    /// the inputs of the forward pass
+    Inputs,
+    /// the outputs of the forward pass
+    Outputs,
+}
+
+impl SidecarField {
+    fn name(self) -> &'static str {
+        match self {
+            SidecarField::Inputs => "inputs",
+            SidecarField::Outputs => "outputs",
+        }
+    }
+
+    fn tensors(self, witness: &GraphWitness) -> &Vec<Vec<Fp>> {
+        match self {
+            SidecarField::Inputs => &witness.inputs,
+            SidecarField::Outputs => &witness.outputs,
+        }
+    }
+
+    fn pretty(self, witness: &GraphWitness) -> Option<&Vec<Vec<String>>> {
+        witness.pretty_elements.as_ref().map(|pretty| match self {
+            SidecarField::Inputs => &pretty.inputs,
+            SidecarField::Outputs => &pretty.outputs,
+        })
+    }
+}
+
+/// A tensor of a witness stored in its sidecar
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), derive(JsonSchema))]
+pub struct SidecarTensor {
+    /// the tensors the tensor is one of
+    pub field: SidecarField,
+    /// the index of the tensor among them
+    pub idx: usize,
+    /// the offset in bytes of its first field element in the sidecar
+    pub offset: u64,
+    /// its number of field elements
+    pub len: usize,
+    /// whether the felt strings of its pretty elements were left out of the json, to be rebuilt from its field
+    /// elements
+    pub pretty: bool,
+}
+
+/// The index of the tensors of a witness stored in its sidecar
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), derive(JsonSchema))]
+pub struct WitnessSidecar {
+    /// the file name of the sidecar, in the directory of the witness
+    pub path: String,
+    /// the tensors stored in the sidecar
+    pub tensors: Vec<SidecarTensor>,
+}
+
+impl WitnessSidecar {
+    /// The tensor at `idx` of `field`, if it is stored in the sidecar
+    pub fn tensor(&self, field: SidecarField, idx: usize) -> Option<&SidecarTensor> {
+        self.tensors
+            .iter()
+            .find(|t| t.field == field && t.idx == idx)
+    }
+}
+
+/// The path of the sidecar of the witness at `path`, the path of the witness with a `.bin` suffix
+pub fn sidecar_path(path: &Path) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(".bin");
+    PathBuf::from(path)
+}
+
+/// The felt strings of the pretty elements of a tensor
+fn pretty_felts(tensor: &[Fp]) -> impl Iterator<Item = String> + '_ {
+    tensor.iter().map(|x| format!("{:?}", x))
+}
+
+/// Writes the tensors of the inputs and outputs of `witness` with more than `threshold` field elements to the sidecar
+/// of the witness at `path`, returning their index, or None (without writing a sidecar) if no tensor is as large
+pub(crate) fn write_sidecar(
+    witness: &GraphWitness,
+    path: &Path,
+    threshold: usize,
+) -> Result<Option<WitnessSidecar>, GraphError> {
+    let large = [SidecarField::Inputs, SidecarField::Outputs]
+        .into_iter()
+        .flat_map(|field| {
+            field
+                .tensors(witness)
+                .iter()
+                .enumerate()
+                .filter(|(_, tensor)| tensor.len() > threshold)
+                .map(move |(idx, tensor)| (field, idx, tensor))
+        })
+        .collect::<Vec<_>>();
+    if large.is_empty() {
+        return Ok(None);
+    }
+
+    let bin_path = sidecar_path(path);
+    let io_err = |e: std::io::Error| {
+        GraphError::ReadWriteFileError(bin_path.display().to_string(), e.to_string())
+    };
+    let file = std::fs::File::create(&bin_path).map_err(io_err)?;
+    let mut writer = std::io::BufWriter::with_capacity(*EZKL_BUF_CAPACITY, file);
+
+    let mut offset = 0;
+    let mut tensors = vec![];
+    for (field, idx, tensor) in large {
+        for felt in tensor {
+            writer.write_all(felt.to_repr().as_ref()).map_err(io_err)?;
+        }
+        let pretty = field
+            .pretty(witness)
+            .and_then(|pretty| pretty.get(idx))
+            .is_some_and(|pretty| {
+                pretty.len() == tensor.len()
+                    && pretty
+                        .iter()
+                        .zip(pretty_felts(tensor))
+                        .all(|(a, b)| *a == b)
+            });
+        tensors.push(SidecarTensor {
+            field,
+            idx,
+            offset,
+            len: tensor.len(),
+            pretty,
+        });
+        offset += (tensor.len() * FELT_BYTES) as u64;
+    }
+    writer.flush().map_err(io_err)?;
+
+    Ok(Some(WitnessSidecar {
+        path: bin_path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        tensors,
+    }))
+}
+
+/// Reads the tensors of the sidecar of `witness` back into it, the sidecar being in `dir`, after which the witness
+/// no longer refers to a sidecar
+pub(crate) fn read_sidecar(witness: &mut GraphWitness, dir: &Path) -> Result<(), GraphError> {
+    let Some(sidecar) = witness.sidecar.take() else {
+        return Ok(());
+    };
+    let bin_path = dir.join(&sidecar.path);
+    let bytes = std::fs::read(&bin_path).map_err(|e| {
+        GraphError::ReadWriteFileError(bin_path.display().to_string(), e.to_string())
+    })?;
+    let invalid = |tensor: &SidecarTensor, reason: &str| {
+        GraphError::InvalidWitnessSidecar(format!(
+            "{}[{}] of {}: {}",
+            tensor.field.name(),
+            tensor.idx,
+            bin_path.display(),
+            reason
+        ))
+    };
+
+    for tensor in &sidecar.tensors {
+        let start = tensor.offset as usize;
+        let end = start + tensor.len * FELT_BYTES;
+        let chunk = bytes
+            .get(start..end)
+            .ok_or_else(|| invalid(tensor, "the sidecar is too short"))?;
+        let felts = chunk
+            .chunks_exact(FELT_BYTES)
+            .map(|repr| {
+                let mut felt = <Fp as PrimeField>::Repr::default();
+                felt.as_mut().copy_from_slice(repr);
+                Option::from(Fp::from_repr(felt))
+                    .ok_or_else(|| invalid(tensor, "a value isn't a field element"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let tensors = match tensor.field {
+            SidecarField::Inputs => &mut witness.inputs,
+            SidecarField::Outputs => &mut witness.outputs,
+        };
+        match tensors.get_mut(tensor.idx) {
+            Some(slot) if slot.is_empty() => *slot = felts,
+            _ => return Err(invalid(tensor, "the json has no empty tensor to fill")),
+        }
+
+        if tensor.pretty {
+            let filled = &tensors[tensor.idx];
+            let pretty = witness.pretty_elements.as_mut().and_then(|pretty| {
+                match tensor.field {
+                    SidecarField::Inputs => &mut pretty.inputs,
+                    SidecarField::Outputs => &mut pretty.outputs,
+                }
+                .get_mut(tensor.idx)
+            });
+            match pretty {
+                Some(slot) if slot.is_empty() => *slot = pretty_felts(filled).collect(),
+                _ => {
+                    return Err(invalid(
+                        tensor,
+                        "the json has no empty pretty elements to fill",
+                    ))
+                }
+            }
+        }
+    }
+    Ok(())
+}
@@ -1,7 +1,9 @@
 use super::errors::GraphError;
 #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
 use super::VarScales;
-use super::{Rescaled, SupportedOp, Visibility};
+use super::{QuantRounding, Visibility};
+#[cfg(not(all(feature = "verifier-only", not(feature = "ezkl"))))]
+use super::{Rescaled, SupportedOp};
 #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
 use crate::circuit::hybrid::HybridOp;
 #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
@@ -55,6 +57,24 @@ pub fn quantize_float(
     elem: &f64,
     shift: f64,
     scale: crate::Scale,
+) -> Result<IntegerRep, TensorError> {
+    quantize_float_rounded(elem, shift, scale, &QuantRounding::HalfAwayFromZero, 0)
+}
+
+/// Quantizes a float using a fixed point representation, rounding with `rounding`.
+/// Arguments
+///
+/// * `elem` - the float to quantize.
+/// * `shift` - offset used in the fixed point representation.
+/// * `scale` - `2^scale` used in the fixed point representation.
+/// * `rounding` - how the scaled float is rounded to an integer.
+/// * `index` - the position of the float in the tensor it is quantized in, which stochastic rounding derives its noise from.
+pub fn quantize_float_rounded(
+    elem: &f64,
+    shift: f64,
+    scale: crate::Scale,
+    rounding: &QuantRounding,
+    index: usize,
 ) -> Result<IntegerRep, TensorError> {
     let mult = scale_to_multiplier(scale);
     let max_value = ((IntegerRep::MAX as f64 - shift) / mult).round(); // the maximum value that can be represented w/o sig bit truncation
@@ -64,7 +84,7 @@ pub fn quantize_float(
     }
 
     // we parallelize the quantization process as it seems to be quite slow at times
-    let scaled = (mult * *elem + shift).round() as IntegerRep;
+    let scaled = rounding.round(mult * *elem + shift, index) as IntegerRep;
 
     Ok(scaled)
 }
@@ -263,6 +283,74 @@ fn load_op<C: tract_onnx::prelude::Op + Clone>(
     Ok(op.clone())
 }
 
+/// The inputs of a logical op that aren't constrained to {0,1} by the nodes they stem from, and that the op then
+/// constrains itself
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+fn boolean_checks(inputs: &[super::NodeType]) -> Vec<usize> {
+    inputs
+        .iter()
+        .enumerate()
+        .filter(|(_, input)| !input.out_boolean())
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// The chunk size and shift of an einsum widened by [crate::RunArgs::widen_accumulation], if it sums over a single
+/// index longer than a chunk. Unless set, the chunks are the longest whose partial sums stay within
+/// [MAX_SAFE_ACCUMULATOR](crate::circuit::hybrid::MAX_SAFE_ACCUMULATOR), bounding the constant operands by their
+/// largest value and the others by the lookup range. Shifting by `ceil(log2(num_chunks))` keeps the sum of the
+/// shifted partial sums within the same bound.
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+fn widened_accumulation(
+    equation: &str,
+    inputs: &[super::NodeType],
+    input_dims: &[Vec<usize>],
+    run_args: &crate::RunArgs,
+) -> Option<(usize, u32)> {
+    use crate::circuit::hybrid::MAX_SAFE_ACCUMULATOR;
+    use crate::circuit::layouts::einsum_contracted_index;
+    use crate::fieldutils::felt_to_integer_rep;
+
+    if !run_args.widen_accumulation {
+        return None;
+    }
+    let index = einsum_contracted_index(equation)?;
+    let len = equation
+        .split("->")
+        .next()?
+        .split(',')
+        .zip(input_dims)
+        .find_map(|(input_eq, dims)| input_eq.find(index).and_then(|axis| dims.get(axis)))
+        .copied()?;
+
+    let chunk_size = run_args.accumulation_chunk_size.unwrap_or_else(|| {
+        let lookup_bound = std::cmp::max(
+            run_args.lookup_range.0.saturating_abs(),
+            run_args.lookup_range.1.saturating_abs(),
+        );
+        let product_bound = inputs
+            .iter()
+            .map(|input| match input.opkind().get_constant() {
+                Some(c) => c
+                    .quantized_values
+                    .iter()
+                    .map(|v| felt_to_integer_rep(*v).saturating_abs())
+                    .max()
+                    .unwrap_or(0),
+                None => lookup_bound,
+            })
+            .fold(1, |bound: IntegerRep, b| bound.saturating_mul(b.max(1)));
+        usize::try_from(MAX_SAFE_ACCUMULATOR / product_bound)
+            .unwrap_or(usize::MAX)
+            .max(1)
+    });
+    if chunk_size >= len {
+        return None;
+    }
+    let num_chunks = len.div_ceil(chunk_size);
+    Some((chunk_size, num_chunks.next_power_of_two().trailing_zeros()))
+}
+
 /// Matches an onnx node to a [crate::circuit::Op].
 /// Arguments
 /// * `idx` - the index of the node in the graph.
@@ -298,7 +386,7 @@ pub fn new_op_from_onnx(
         let constant = constant.get_mutable_constant();
         if let Some(c) = constant {
             inputs[index].bump_scale(scale);
-            c.rebase_scale(scale)?;
+            c.rebase_scale(scale, &run_args.rounding)?;
             inputs[index].replace_opkind(SupportedOp::Constant(c.clone()));
             Ok(SupportedOp::Linear(PolyOp::Identity {
                 out_scale: Some(scale),
@@ -376,7 +464,8 @@ pub fn new_op_from_onnx(
             let range = (start..end).step_by(delta).collect::<Vec<_>>();
             let raw_value = range.iter().map(|x| *x as f32).collect::<Tensor<_>>();
             // Quantize the raw value (integers)
-            let quantized_value = quantize_tensor(raw_value.clone(), 0, &Visibility::Fixed)?;
+            let quantized_value =
+                quantize_tensor(raw_value.clone(), 0, &Visibility::Fixed, &run_args.rounding)?;
 
             let c = crate::circuit::ops::Constant::new(quantized_value, raw_value);
             // Create a constant op
@@ -673,6 +762,7 @@ pub fn new_op_from_onnx(
                 raw_value.clone(),
                 constant_scale,
                 &run_args.param_visibility,
+                &run_args.rounding,
             )?;
             let c = crate::circuit::ops::Constant::new(quantized_value, raw_value);
             // Create a constant op
@@ -807,6 +897,7 @@ pub fn new_op_from_onnx(
                 input_scale: (scale_to_multiplier(in_scale) as f32).into(),
                 output_scale: (scale_to_multiplier(max_scale) as f32).into(),
                 use_range_check_for_int: true,
+                rounding: run_args.div_rounding,
             })
         }
 
@@ -937,6 +1028,7 @@ pub fn new_op_from_onnx(
                                     input_scales[0],
                                 )
                                     as f32),
+                                rounding: run_args.div_rounding,
                             }),
                         )?
                     } else {
@@ -1028,10 +1120,22 @@ pub fn new_op_from_onnx(
                 }
             };
 
-            let axes = &op.axes;
-            SupportedOp::Linear(PolyOp::Einsum {
-                equation: axes.to_string(),
-            })
+            let equation = op.axes.to_string();
+            match widened_accumulation(&equation, inputs, &input_dims, run_args) {
+                Some((chunk_size, shift)) => {
+                    debug!(
+                        "widening einsum {} of node {} into chunks of {} shifted by {}",
+                        equation, idx, chunk_size, shift
+                    );
+                    SupportedOp::Hybrid(HybridOp::WidenedEinsum {
+                        equation,
+                        chunk_size,
+                        shift,
+                        use_range_check_for_int: !run_args.div_rebasing,
+                    })
+                }
+                None => SupportedOp::Linear(PolyOp::Einsum { equation }),
+            }
         }
         "Softmax" => {
             // Extract the slope layer hyperparams
@@ -1167,11 +1271,52 @@ pub fn new_op_from_onnx(
                 group,
             })
         }
-        "Not" => SupportedOp::Linear(PolyOp::Not),
-        "And" => SupportedOp::Linear(PolyOp::And),
-        "Or" => SupportedOp::Linear(PolyOp::Or),
-        "Xor" => SupportedOp::Linear(PolyOp::Xor),
-        "Equals" => SupportedOp::Hybrid(HybridOp::Equals),
+        "Not" => SupportedOp::Linear(PolyOp::Not {
+            boolean_checks: boolean_checks(inputs),
+        }),
+        "And" => SupportedOp::Linear(PolyOp::And {
+            boolean_checks: boolean_checks(inputs),
+        }),
+        "Or" => SupportedOp::Linear(PolyOp::Or {
+            boolean_checks: boolean_checks(inputs),
+        }),
+        "Xor" => SupportedOp::Linear(PolyOp::Xor {
+            boolean_checks: boolean_checks(inputs),
+        }),
+        "Equals" => {
+            // an equality with a single constant exactly representable at the scale of the other input is the indicator
+            // of the quantized constant, looked up directly instead of compared
+            let output_dims = node_output_shapes(&node, symbol_values)?;
+            let indicated = |i: usize| {
+                let other = 1 - i;
+                let opkind = inputs[i].opkind();
+                let c = opkind.get_constant()?;
+                if inputs[other].opkind().is_constant()
+                    || c.raw_values.len() != 1
+                    || output_dims[0] != input_dims[other]
+                {
+                    return None;
+                }
+                let value = c.raw_values[0] as f64 * scale_to_multiplier(input_scales[other]);
+                if value.fract() != 0.0 || value.abs() >= IntegerRep::MAX as f64 {
+                    return None;
+                }
+                Some((i, value as IntegerRep))
+            };
+            let indicated = if inputs.len() == 2 {
+                indicated(1).or_else(|| indicated(0))
+            } else {
+                None
+            };
+            match indicated {
+                Some((i, value)) => {
+                    inputs[i].decrement_use();
+                    deleted_indices.push(i);
+                    SupportedOp::Nonlinear(LookupOp::EqualsConst { value })
+                }
+                _ => SupportedOp::Hybrid(HybridOp::Equals),
+            }
+        }
         "Deconv" => {
             let deconv_node: &Deconv = match node.op().downcast_ref::<Deconv>() {
                 Some(b) => b,
@@ -1365,6 +1510,7 @@ pub fn new_op_from_onnx(
 }
 
 /// Extracts the raw values from a [crate::circuit::ops::Constant] op.
+#[cfg(not(all(feature = "verifier-only", not(feature = "ezkl"))))]
 pub fn extract_const_raw_values(op: SupportedOp) -> Option<Tensor<f32>> {
     match op {
         SupportedOp::Constant(crate::circuit::ops::Constant { raw_values, .. }) => Some(raw_values),
@@ -1373,6 +1519,7 @@ pub fn extract_const_raw_values(op: SupportedOp) -> Option<Tensor<f32>> {
 }
 
 /// Extracts the quantized values from a [crate::circuit::ops::Constant] op.
+#[cfg(not(all(feature = "verifier-only", not(feature = "ezkl"))))]
 pub fn extract_const_quantized_values(op: SupportedOp) -> Option<Tensor<Fp>> {
     match op {
         SupportedOp::Constant(crate::circuit::ops::Constant {
@@ -1387,13 +1534,12 @@ pub fn quantize_tensor<F: PrimeField + TensorType + PartialOrd>(
     const_value: Tensor<f32>,
     scale: crate::Scale,
     visibility: &Visibility,
+    rounding: &QuantRounding,
 ) -> Result<Tensor<F>, TensorError> {
-    let mut value: Tensor<F> = const_value.par_enum_map(|_, x| {
-        Ok::<_, TensorError>(crate::fieldutils::integer_rep_to_felt::<F>(quantize_float(
-            &(x).into(),
-            0.0,
-            scale,
-        )?))
+    let mut value: Tensor<F> = const_value.par_enum_map(|i, x| {
+        Ok::<_, TensorError>(crate::fieldutils::integer_rep_to_felt::<F>(
+            quantize_float_rounded(&(x).into(), 0.0, scale, rounding, i)?,
+        ))
     })?;
 
     value.set_scale(scale);
@@ -1420,6 +1566,7 @@ pub(crate) fn split_valtensor(
 }
 
 ///
+#[cfg(not(all(feature = "verifier-only", not(feature = "ezkl"))))]
 pub fn homogenize_input_scales(
     op: Box<dyn Op<Fp>>,
     input_scales: Vec<crate::Scale>,
@@ -1502,4 +1649,74 @@ pub mod tests {
         assert_eq!(split[2].dims(), vec![5, 2]);
         assert_eq!(split[2].len(), 10);
     }
+
+    const LEN: usize = 10_000;
+
+    /// The sum of the quantized values of `raw` minus the sum of the values, at scale 0
+    fn quantization_bias(raw: &Tensor<f32>, rounding: &QuantRounding) -> f64 {
+        let quantized =
+            quantize_tensor::<Fp>(raw.clone(), 0, &Visibility::Fixed, rounding).unwrap();
+        let quantized_sum: IntegerRep = quantized
+            .iter()
+            .map(|x| crate::fieldutils::felt_to_integer_rep(*x))
+            .sum();
+        quantized_sum as f64 - raw.iter().map(|x| *x as f64).sum::<f64>()
+    }
+
+    #[test]
+    fn test_quantization_bias() {
+        use rand::Rng;
+        // half of the values are ties
+        let raw: Tensor<f32> = (0..LEN)
+            .map(|_| rand::rngs::OsRng.gen_range(-1000..4000) as f32 / 2.0)
+            .into();
+
+        // each tie rounds away from zero, most of them up
+        let away = quantization_bias(&raw, &QuantRounding::HalfAwayFromZero);
+        assert!(away > LEN as f64 / 8.0, "bias {}", away);
+        // the ties round to the even neighbour, up as often as down
+        let even = quantization_bias(&raw, &QuantRounding::HalfToEven);
+        assert!(even.abs() < LEN as f64 / 50.0, "bias {}", even);
+        let stochastic = quantization_bias(&raw, &QuantRounding::Stochastic(7));
+        assert!(stochastic.abs() < LEN as f64 / 50.0, "bias {}", stochastic);
+
+        // away from ties only stochastic rounding is unbiased
+        let raw: Tensor<f32> = (0..LEN)
+            .map(|_| rand::rngs::OsRng.gen_range(0.0..0.4))
+            .into();
+        let even = quantization_bias(&raw, &QuantRounding::HalfToEven);
+        assert!(even < -(LEN as f64) / 10.0, "bias {}", even);
+        let stochastic = quantization_bias(&raw, &QuantRounding::Stochastic(7));
+        assert!(stochastic.abs() < LEN as f64 / 50.0, "bias {}", stochastic);
+    }
+
+    #[test]
+    fn test_stochastic_rounding_reproducible() {
+        let raw: Tensor<f32> = (0..LEN).map(|i| (i as f32).sin() * 100.0).into();
+        let quantize = |rounding: QuantRounding| {
+            quantize_tensor::<Fp>(raw.clone(), 3, &Visibility::Fixed, &rounding).unwrap()
+        };
+        // the same seed quantizes the same way, however the work is split across threads
+        assert_eq!(
+            quantize(QuantRounding::Stochastic(42)),
+            quantize(QuantRounding::Stochastic(42))
+        );
+        assert_ne!(
+            quantize(QuantRounding::Stochastic(42)),
+            quantize(QuantRounding::Stochastic(43))
+        );
+
+        // the mode and seed roundtrip through the settings
+        let rounding: QuantRounding = "stochastic:42".parse().unwrap();
+        assert_eq!(rounding, QuantRounding::Stochastic(42));
+        assert_eq!(
+            rounding.to_string().parse::<QuantRounding>().unwrap(),
+            rounding
+        );
+        let json = serde_json::to_string(&rounding).unwrap();
+        assert_eq!(
+            serde_json::from_str::<QuantRounding>(&json).unwrap(),
+            rounding
+        );
+    }
 }
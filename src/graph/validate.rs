@@ -0,0 +1,181 @@
+//! Validation of a witness against a compiled circuit with the mock prover, without proving.
+//!
+//! The mock prover reports the constraints a witness fails by column and row. The rows of the region the model is
+//! laid out in are attributed to the ops that laid them out, as recorded in the compiled circuit (see
+//! [super::CoreCircuit::op_rows]), such that each failure names the node of the model it stems from.
+
+use super::errors::GraphError;
+use super::GraphCircuit;
+use crate::circuit::region::OpProfile;
+use crate::circuit::table::Range;
+use halo2_proofs::dev::{FailureLocation, MockProver, VerifyFailure};
+use halo2curves::bn256::Fr as Fp;
+use serde::{Deserialize, Serialize};
+
+/// The name of the region the nodes of the model are laid out in
+const MODEL_REGION: &str = "('model')";
+
+/// A constraint a witness fails, attributed to the op of the model that laid it out
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConstraintFailure {
+    /// the node of the op, if the failure is in the rows of an op of the model
+    pub node: Option<usize>,
+    /// the op, as [crate::circuit::ops::Op::as_string]
+    pub op: Option<String>,
+    /// the row of the model region, or of the circuit if the failure is outside of any region
+    pub row: Option<usize>,
+    /// what failed
+    pub reason: String,
+}
+
+impl std::fmt::Display for ConstraintFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.node, &self.op, self.row) {
+            (Some(node), Some(op), Some(row)) => {
+                write!(f, "node {} ({}) row {}: {}", node, op, row, self.reason)
+            }
+            (_, _, Some(row)) => write!(f, "row {} (outside the model): {}", row, self.reason),
+            _ => write!(f, "{}", self.reason),
+        }
+    }
+}
+
+/// The op laid out in a row of the model region, given the ops by start row
+fn op_at_row(op_rows: &[OpProfile], row: usize) -> Option<&OpProfile> {
+    let idx = op_rows.partition_point(|op| op.start_row <= row);
+    op_rows[..idx]
+        .last()
+        .filter(|op| row < op.start_row + op.rows)
+}
+
+/// Attributes a failure of the mock prover to the op of the model whose rows it is in. Lookup inputs are reported
+/// against the lookup range of the circuit, the mock prover doesn't report the value looked up.
+pub fn attribute_failure(
+    failure: &VerifyFailure,
+    op_rows: &[OpProfile],
+    lookup_range: Range,
+) -> ConstraintFailure {
+    let (location, reason) = match failure {
+        VerifyFailure::ConstraintNotSatisfied {
+            constraint,
+            location,
+            cell_values,
+        } => {
+            let cells = cell_values
+                .iter()
+                .map(|(cell, value)| format!("{} = {}", cell, value))
+                .collect::<Vec<_>>();
+            (
+                Some(location),
+                format!("{} not satisfied ({})", constraint, cells.join(", ")),
+            )
+        }
+        VerifyFailure::Lookup { location, .. } => (
+            Some(location),
+            format!(
+                "lookup input out of range (range=[{},{}])",
+                lookup_range.0, lookup_range.1
+            ),
+        ),
+        VerifyFailure::Shuffle { location, .. } => {
+            (Some(location), "shuffle not satisfied".to_string())
+        }
+        VerifyFailure::Permutation { column, location } => (
+            Some(location),
+            format!("copy constraint on {} not satisfied", column),
+        ),
+        failure => (None, failure.to_string()),
+    };
+
+    let unattributed = |row, reason| ConstraintFailure {
+        node: None,
+        op: None,
+        row,
+        reason,
+    };
+    match location {
+        Some(FailureLocation::InRegion { region, offset }) => {
+            let region = region.to_string();
+            if !region.contains(MODEL_REGION) {
+                return unattributed(None, format!("{} in {}", reason, region));
+            }
+            match op_at_row(op_rows, *offset) {
+                Some(op) => ConstraintFailure {
+                    node: Some(op.node),
+                    op: Some(op.op.clone()),
+                    row: Some(*offset),
+                    reason,
+                },
+                None => unattributed(Some(*offset), reason),
+            }
+        }
+        Some(FailureLocation::OutsideRegion { row }) => unattributed(Some(*row), reason),
+        None => unattributed(None, reason),
+    }
+}
+
+/// Runs the mock prover on the witness loaded in a circuit, returning the constraints it fails attributed to the
+/// nodes of the model. Circuits compiled before the rows of the ops were recorded are profiled first.
+pub fn validate_witness(
+    circuit: &mut GraphCircuit,
+    public_inputs: Vec<Fp>,
+) -> Result<Vec<ConstraintFailure>, GraphError> {
+    if circuit.core.op_rows.is_empty() {
+        circuit.record_op_rows()?;
+    }
+    let run_args = &circuit.settings().run_args;
+    let prover = MockProver::run(run_args.logrows, &*circuit, vec![public_inputs])
+        .map_err(|e| super::take_layout_error().unwrap_or(GraphError::Halo2Error(e)))?;
+
+    Ok(match prover.verify() {
+        Ok(()) => vec![],
+        Err(failures) => failures
+            .iter()
+            .map(|failure| attribute_failure(failure, &circuit.core.op_rows, run_args.lookup_range))
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn op(node: usize, start_row: usize, rows: usize) -> OpProfile {
+        OpProfile {
+            node,
+            op: format!("OP{}", node),
+            start_row,
+            rows,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn rows_attributed_to_the_op_laying_them_out() {
+        // an op taking no rows (as a reshape) is never blamed
+        let ops = vec![op(0, 0, 10), op(1, 10, 0), op(2, 10, 5), op(4, 20, 3)];
+        let node = |row| op_at_row(&ops, row).map(|op| op.node);
+        assert_eq!(node(0), Some(0));
+        assert_eq!(node(9), Some(0));
+        assert_eq!(node(10), Some(2));
+        assert_eq!(node(14), Some(2));
+        // rows between ops are laid out by the model itself
+        assert_eq!(node(15), None);
+        assert_eq!(node(22), Some(4));
+        assert_eq!(node(23), None);
+    }
+
+    #[test]
+    fn failure_display() {
+        let failure = ConstraintFailure {
+            node: Some(14),
+            op: Some("SIGMOID(scale=128)".to_string()),
+            row: Some(20312),
+            reason: "lookup input out of range (range=[-65536,65535])".to_string(),
+        };
+        assert_eq!(
+            failure.to_string(),
+            "node 14 (SIGMOID(scale=128)) row 20312: lookup input out of range (range=[-65536,65535])"
+        );
+    }
+}
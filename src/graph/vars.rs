@@ -1,5 +1,6 @@
 use std::fmt::Display;
 
+use crate::circuit::BaseConfig as PolyConfig;
 use crate::tensor::TensorType;
 use crate::tensor::{ValTensor, VarTensor};
 use crate::RunArgs;
@@ -13,6 +14,8 @@ use pyo3::{
     PyTryFrom, Python, ToPyObject,
 };
 
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
 use tosubcommand::ToFlags;
@@ -23,6 +26,7 @@ use super::*;
 
 /// Label enum to track whether model input, model parameters, and model output are public, private, or hashed
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), derive(JsonSchema))]
 pub enum Visibility {
     /// Mark an item as private to the prover (not in the proof submitted for verification)
     #[default]
@@ -42,6 +46,20 @@ pub enum Visibility {
     KZGCommit,
     /// assigned as a constant in the circuit
     Fixed,
+    /// Mark an item as publicly committed to with a SHA-256 hash of its serialization, see [Visibility::Hashed]
+    /// for the meaning of the fields
+    Sha256Hashed {
+        ///
+        hash_is_public: bool,
+        ///
+        outlets: Vec<usize>,
+    },
+    /// Mark an item as private, its membership of a committed dataset is proven against a public Poseidon
+    /// Merkle root (the root is sent in the proof submitted for verification)
+    Merkle {
+        /// the depth of the tree of the dataset
+        depth: usize,
+    },
 }
 
 impl Display for Visibility {
@@ -61,6 +79,17 @@ impl Display for Visibility {
                     write!(f, "hashed/private/{}", outlets.iter().join(","))
                 }
             }
+            Visibility::Sha256Hashed {
+                hash_is_public,
+                outlets,
+            } => {
+                if *hash_is_public {
+                    write!(f, "hashed/sha256/public")
+                } else {
+                    write!(f, "hashed/sha256/private/{}", outlets.iter().join(","))
+                }
+            }
+            Visibility::Merkle { depth } => write!(f, "merkle/{}", depth),
         }
     }
 }
@@ -74,6 +103,24 @@ impl ToFlags for Visibility {
 
 impl<'a> From<&'a str> for Visibility {
     fn from(s: &'a str) -> Self {
+        if let Some(depth) = s.strip_prefix("merkle/") {
+            return Visibility::Merkle {
+                depth: depth.parse::<usize>().unwrap(),
+            };
+        }
+        if s.contains("hashed/sha256/private") {
+            let (_, outlets) = s.split_at(s.rfind('/').unwrap());
+            let outlets = outlets
+                .trim_start_matches('/')
+                .split(',')
+                .map(|s| s.parse::<usize>().unwrap())
+                .collect_vec();
+
+            return Visibility::Sha256Hashed {
+                hash_is_public: false,
+                outlets,
+            };
+        }
         if s.contains("hashed/private") {
             // split on last occurrence of '/'
             let (_, outlets) = s.split_at(s.rfind('/').unwrap());
@@ -97,6 +144,10 @@ impl<'a> From<&'a str> for Visibility {
                 hash_is_public: true,
                 outlets: vec![],
             },
+            "hashed/sha256" | "hashed/sha256/public" => Visibility::Sha256Hashed {
+                hash_is_public: true,
+                outlets: vec![],
+            },
             _ => {
                 log::error!("Invalid value for Visibility: {}", s);
                 log::warn!("Defaulting to private");
@@ -130,6 +181,22 @@ impl IntoPy<PyObject> for Visibility {
                     format!("hashed/private/{}", outlets).to_object(py)
                 }
             }
+            Visibility::Sha256Hashed {
+                hash_is_public,
+                outlets,
+            } => {
+                if hash_is_public {
+                    "hashed/sha256/public".to_object(py)
+                } else {
+                    let outlets = outlets
+                        .iter()
+                        .map(|o| o.to_string())
+                        .collect_vec()
+                        .join(",");
+                    format!("hashed/sha256/private/{}", outlets).to_object(py)
+                }
+            }
+            Visibility::Merkle { depth } => format!("merkle/{}", depth).to_object(py),
         }
     }
 }
@@ -143,6 +210,27 @@ impl<'source> FromPyObject<'source> for Visibility {
 
         let strval = strval.as_str();
 
+        if let Some(depth) = strval.strip_prefix("merkle/") {
+            let depth = depth
+                .parse::<usize>()
+                .map_err(|_| PyValueError::new_err("Invalid value for Visibility"))?;
+            return Ok(Visibility::Merkle { depth });
+        }
+
+        if strval.contains("hashed/sha256/private") {
+            let (_, outlets) = strval.split_at(strval.rfind('/').unwrap());
+            let outlets = outlets
+                .trim_start_matches('/')
+                .split(',')
+                .map(|s| s.parse::<usize>().unwrap())
+                .collect_vec();
+
+            return Ok(Visibility::Sha256Hashed {
+                hash_is_public: false,
+                outlets,
+            });
+        }
+
         if strval.contains("hashed/private") {
             // split on last occurence of '/'
             let (_, outlets) = strval.split_at(strval.rfind('/').unwrap());
@@ -170,6 +258,10 @@ impl<'source> FromPyObject<'source> for Visibility {
                 hash_is_public: true,
                 outlets: vec![],
             }),
+            "hashed/sha256" | "hashed/sha256/public" => Ok(Visibility::Sha256Hashed {
+                hash_is_public: true,
+                outlets: vec![],
+            }),
             "fixed" => Ok(Visibility::Fixed),
             _ => Err(PyValueError::new_err("Invalid value for Visibility")),
         }
@@ -183,58 +275,545 @@ impl Visibility {
     }
     #[allow(missing_docs)]
     pub fn is_private(&self) -> bool {
-        matches!(&self, Visibility::Private) || self.is_hashed_private()
+        matches!(&self, Visibility::Private) || self.is_hashed_private() || self.is_merkle()
     }
 
     #[allow(missing_docs)]
     pub fn is_public(&self) -> bool {
         matches!(&self, Visibility::Public)
     }
-    #[allow(missing_docs)]
+    /// Whether the item is hashed, with any hash function
     pub fn is_hashed(&self) -> bool {
+        self.is_poseidon_hashed() || self.is_sha256_hashed()
+    }
+    #[allow(missing_docs)]
+    pub fn is_poseidon_hashed(&self) -> bool {
         matches!(&self, Visibility::Hashed { .. })
     }
     #[allow(missing_docs)]
+    pub fn is_sha256_hashed(&self) -> bool {
+        matches!(&self, Visibility::Sha256Hashed { .. })
+    }
+    #[allow(missing_docs)]
+    pub fn is_merkle(&self) -> bool {
+        matches!(&self, Visibility::Merkle { .. })
+    }
+    /// The depth of the tree the item is proven to belong to, if it is
+    pub fn merkle_depth(&self) -> Option<usize> {
+        match self {
+            Visibility::Merkle { depth } => Some(*depth),
+            _ => None,
+        }
+    }
+    #[allow(missing_docs)]
     pub fn is_polycommit(&self) -> bool {
         matches!(&self, Visibility::KZGCommit)
     }
 
     #[allow(missing_docs)]
     pub fn is_hashed_public(&self) -> bool {
-        if let Visibility::Hashed {
-            hash_is_public: true,
-            ..
-        } = self
-        {
-            return true;
-        }
-        false
+        matches!(
+            self,
+            Visibility::Hashed {
+                hash_is_public: true,
+                ..
+            } | Visibility::Sha256Hashed {
+                hash_is_public: true,
+                ..
+            }
+        )
     }
     #[allow(missing_docs)]
     pub fn is_hashed_private(&self) -> bool {
-        if let Visibility::Hashed {
-            hash_is_public: false,
-            ..
-        } = self
-        {
-            return true;
-        }
-        false
+        matches!(
+            self,
+            Visibility::Hashed {
+                hash_is_public: false,
+                ..
+            } | Visibility::Sha256Hashed {
+                hash_is_public: false,
+                ..
+            }
+        )
     }
 
     #[allow(missing_docs)]
     pub fn requires_processing(&self) -> bool {
-        matches!(&self, Visibility::Hashed { .. }) | matches!(&self, Visibility::KZGCommit)
+        self.is_hashed() | self.is_merkle() | matches!(&self, Visibility::KZGCommit)
     }
     #[allow(missing_docs)]
     pub fn overwrites_inputs(&self) -> Vec<usize> {
-        if let Visibility::Hashed { outlets, .. } = self {
+        if let Visibility::Hashed { outlets, .. } | Visibility::Sha256Hashed { outlets, .. } = self
+        {
             return outlets.clone();
         }
         vec![]
     }
 }
 
+/// Inclusive bounds asserted in the circuit on every element of an output, so that the output can
+/// be kept private or hashed while its range is proven
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, PartialOrd)]
+#[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), derive(JsonSchema))]
+pub struct OutputBound {
+    /// the index of the bounded output
+    pub output: usize,
+    /// the lower bound, as a float
+    pub lower: Option<f64>,
+    /// the upper bound, as a float
+    pub upper: Option<f64>,
+}
+
+impl OutputBound {
+    /// The bounds converted at the scale of the output, rounded inwards
+    pub fn quantized(&self, scale: crate::Scale) -> (Option<IntegerRep>, Option<IntegerRep>) {
+        let multiplier = scale_to_multiplier(scale);
+        (
+            self.lower.map(|l| (l * multiplier).ceil() as IntegerRep),
+            self.upper.map(|u| (u * multiplier).floor() as IntegerRep),
+        )
+    }
+}
+
+impl Display for OutputBound {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let bound = |b: Option<f64>| b.map(|b| b.to_string()).unwrap_or_default();
+        write!(
+            f,
+            "{}:{}:{}",
+            self.output,
+            bound(self.lower),
+            bound(self.upper)
+        )
+    }
+}
+
+impl std::str::FromStr for OutputBound {
+    type Err = String;
+
+    /// Parses `output:lower:upper`, eg. `0::0.2` bounds the first output above by 0.2
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || {
+            format!(
+                "invalid output bound {}, expected output:lower:upper with either bound possibly empty",
+                s
+            )
+        };
+        let parts = s.split(':').collect_vec();
+        if parts.len() != 3 {
+            return Err(err());
+        }
+        let bound = |b: &str| {
+            if b.is_empty() {
+                Ok(None)
+            } else {
+                b.parse::<f64>().map(Some).map_err(|_| err())
+            }
+        };
+        let output_bound = OutputBound {
+            output: parts[0].parse::<usize>().map_err(|_| err())?,
+            lower: bound(parts[1])?,
+            upper: bound(parts[2])?,
+        };
+        if output_bound.lower.is_none() && output_bound.upper.is_none() {
+            return Err(err());
+        }
+        Ok(output_bound)
+    }
+}
+
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+impl ToFlags for OutputBound {
+    fn to_flags(&self) -> Vec<String> {
+        vec![format!("{}", self)]
+    }
+}
+
+#[cfg(feature = "python-bindings")]
+/// Converts OutputBound into a PyObject (Required for OutputBound to be compatible with Python)
+impl IntoPy<PyObject> for OutputBound {
+    fn into_py(self, py: Python) -> PyObject {
+        self.to_string().to_object(py)
+    }
+}
+
+#[cfg(feature = "python-bindings")]
+/// Obtains OutputBound from PyObject (Required for OutputBound to be compatible with Python)
+impl<'source> FromPyObject<'source> for OutputBound {
+    fn extract(ob: &'source PyAny) -> PyResult<Self> {
+        let trystr = <PyString as PyTryFrom>::try_from(ob)?;
+        trystr.to_string().parse().map_err(PyValueError::new_err)
+    }
+}
+
+/// What the output of a node is requantized to, in place of the rescale ezkl inserts on its own
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, PartialOrd)]
+#[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), derive(JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum RescaleTarget {
+    /// The output keeps the scale the op gives it, the automatic rescale is suppressed to keep the extra precision
+    Keep,
+    /// The output is rescaled to the scale, dividing it down or multiplying it up
+    Scale(crate::Scale),
+}
+
+/// Overrides the automatic rescale of the output of a node, the node being indexed as in the table of the model
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, PartialOrd)]
+#[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), derive(JsonSchema))]
+pub struct RescaleOverride {
+    /// the index of the node
+    pub node: usize,
+    /// what the output of the node is requantized to
+    pub target: RescaleTarget,
+}
+
+impl Display for RescaleOverride {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.target {
+            RescaleTarget::Keep => write!(f, "{}:keep", self.node),
+            RescaleTarget::Scale(scale) => write!(f, "{}:{}", self.node, scale),
+        }
+    }
+}
+
+impl std::str::FromStr for RescaleOverride {
+    type Err = String;
+
+    /// Parses `node:keep` or `node:scale`, eg. `4:keep` keeps the precision of the output of node 4 and `4:9`
+    /// rescales it to scale 9
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || {
+            format!(
+                "invalid rescale override {}, expected node:keep or node:scale",
+                s
+            )
+        };
+        let (node, target) = s.split_once(':').ok_or_else(err)?;
+        let target = match target {
+            "keep" => RescaleTarget::Keep,
+            scale => RescaleTarget::Scale(scale.parse().map_err(|_| err())?),
+        };
+        Ok(RescaleOverride {
+            node: node.parse().map_err(|_| err())?,
+            target,
+        })
+    }
+}
+
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+impl ToFlags for RescaleOverride {
+    fn to_flags(&self) -> Vec<String> {
+        vec![format!("{}", self)]
+    }
+}
+
+#[cfg(feature = "python-bindings")]
+/// Converts RescaleOverride into a PyObject (Required for RescaleOverride to be compatible with Python)
+impl IntoPy<PyObject> for RescaleOverride {
+    fn into_py(self, py: Python) -> PyObject {
+        self.to_string().to_object(py)
+    }
+}
+
+#[cfg(feature = "python-bindings")]
+/// Obtains RescaleOverride from PyObject (Required for RescaleOverride to be compatible with Python)
+impl<'source> FromPyObject<'source> for RescaleOverride {
+    fn extract(ob: &'source PyAny) -> PyResult<Self> {
+        let trystr = <PyString as PyTryFrom>::try_from(ob)?;
+        trystr.to_string().parse().map_err(PyValueError::new_err)
+    }
+}
+
+/// The scale an output of the model is rescaled to before it is exposed, hashed or compared, in place of the scale of
+/// the node computing it
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, PartialOrd)]
+#[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), derive(JsonSchema))]
+pub struct OutputScale {
+    /// the output, by its index or by the name of the node computing it
+    pub output: String,
+    /// the scale the output is rescaled to
+    pub scale: crate::Scale,
+}
+
+impl Display for OutputScale {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}:{}", self.output, self.scale)
+    }
+}
+
+impl std::str::FromStr for OutputScale {
+    type Err = String;
+
+    /// Parses `output:scale`, the output being an index or the name of a node, eg. `1:0` rescales the second output
+    /// to scale 0
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || format!("invalid output scale {}, expected output:scale", s);
+        let (output, scale) = s.rsplit_once(':').ok_or_else(err)?;
+        if output.is_empty() {
+            return Err(err());
+        }
+        Ok(OutputScale {
+            output: output.to_string(),
+            scale: scale.parse().map_err(|_| err())?,
+        })
+    }
+}
+
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+impl ToFlags for OutputScale {
+    fn to_flags(&self) -> Vec<String> {
+        vec![format!("{}", self)]
+    }
+}
+
+#[cfg(feature = "python-bindings")]
+/// Converts OutputScale into a PyObject (Required for OutputScale to be compatible with Python)
+impl IntoPy<PyObject> for OutputScale {
+    fn into_py(self, py: Python) -> PyObject {
+        self.to_string().to_object(py)
+    }
+}
+
+#[cfg(feature = "python-bindings")]
+/// Obtains OutputScale from PyObject (Required for OutputScale to be compatible with Python)
+impl<'source> FromPyObject<'source> for OutputScale {
+    fn extract(ob: &'source PyAny) -> PyResult<Self> {
+        let trystr = <PyString as PyTryFrom>::try_from(ob)?;
+        trystr.to_string().parse().map_err(PyValueError::new_err)
+    }
+}
+
+/// The commitment group of the params of the nodes of no [CommitmentGroup]
+pub const DEFAULT_COMMITMENT_GROUP: &str = "default";
+
+/// A named group of nodes whose params are committed to separately from those of the other nodes, such that the
+/// commitments of a group can be swapped into a proof without those of the others (see `swap-proof-commitments`)
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, PartialOrd)]
+#[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), derive(JsonSchema))]
+pub struct CommitmentGroup {
+    /// the name of the group
+    pub name: String,
+    /// the nodes of the group, indexed as in the table of the model
+    pub nodes: Vec<usize>,
+}
+
+impl Display for CommitmentGroup {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}:{}", self.name, self.nodes.iter().join("+"))
+    }
+}
+
+impl std::str::FromStr for CommitmentGroup {
+    type Err = String;
+
+    /// Parses `name:nodes` with the nodes joined by `+` and inclusive ranges of nodes as `first-last`, eg.
+    /// `head:12-14+17` groups nodes 12, 13, 14 and 17 as `head`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || {
+            format!(
+                "invalid commitment group {}, expected name:nodes as head:12-14+17",
+                s
+            )
+        };
+        let (name, nodes) = s.split_once(':').ok_or_else(err)?;
+        let node = |n: &str| n.parse::<usize>().map_err(|_| err());
+        let mut group = CommitmentGroup {
+            name: name.to_string(),
+            nodes: vec![],
+        };
+        for range in nodes.split('+') {
+            match range.split_once('-') {
+                Some((first, last)) => group.nodes.extend(node(first)?..=node(last)?),
+                None => group.nodes.push(node(range)?),
+            }
+        }
+        Ok(group)
+    }
+}
+
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+impl ToFlags for CommitmentGroup {
+    fn to_flags(&self) -> Vec<String> {
+        vec![format!("{}", self)]
+    }
+}
+
+#[cfg(feature = "python-bindings")]
+/// Converts CommitmentGroup into a PyObject (Required for CommitmentGroup to be compatible with Python)
+impl IntoPy<PyObject> for CommitmentGroup {
+    fn into_py(self, py: Python) -> PyObject {
+        self.to_string().to_object(py)
+    }
+}
+
+#[cfg(feature = "python-bindings")]
+/// Obtains CommitmentGroup from PyObject (Required for CommitmentGroup to be compatible with Python)
+impl<'source> FromPyObject<'source> for CommitmentGroup {
+    fn extract(ob: &'source PyAny) -> PyResult<Self> {
+        let trystr = <PyString as PyTryFrom>::try_from(ob)?;
+        trystr.to_string().parse().map_err(PyValueError::new_err)
+    }
+}
+
+/// The parameter set of the Poseidon hash of the hashed visibilities, in the circuit and in the
+/// witness. Settings without one hash with the ezkl parameters.
+#[derive(
+    Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, Default, Copy,
+)]
+#[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), derive(JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum PoseidonParams {
+    #[default]
+    /// width 2 and rate 1, hashing blocks of elements with the length of the block as domain
+    Ezkl,
+    /// width 3 and rate 2 BN254 parameters of circomlib, hashing the elements as a binary tree of
+    /// `Poseidon(left, right)` zero padded at each level, as circomlib `Poseidon(2)` computes it
+    Circomlib,
+}
+
+impl std::fmt::Display for PoseidonParams {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PoseidonParams::Ezkl => write!(f, "ezkl"),
+            PoseidonParams::Circomlib => write!(f, "circomlib"),
+        }
+    }
+}
+
+impl std::str::FromStr for PoseidonParams {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "ezkl" => Ok(PoseidonParams::Ezkl),
+            "circomlib" => Ok(PoseidonParams::Circomlib),
+            _ => Err(format!(
+                "invalid poseidon parameters {}, accepts ezkl or circomlib",
+                s
+            )),
+        }
+    }
+}
+
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+impl ToFlags for PoseidonParams {
+    fn to_flags(&self) -> Vec<String> {
+        vec![format!("{}", self)]
+    }
+}
+
+#[cfg(feature = "python-bindings")]
+/// Converts PoseidonParams into a PyObject (Required for PoseidonParams to be compatible with Python)
+impl IntoPy<PyObject> for PoseidonParams {
+    fn into_py(self, py: Python) -> PyObject {
+        self.to_string().to_object(py)
+    }
+}
+
+#[cfg(feature = "python-bindings")]
+/// Obtains PoseidonParams from PyObject (Required for PoseidonParams to be compatible with Python)
+impl<'source> FromPyObject<'source> for PoseidonParams {
+    fn extract(ob: &'source PyAny) -> PyResult<Self> {
+        let trystr = <PyString as PyTryFrom>::try_from(ob)?;
+        trystr.to_string().parse().map_err(PyValueError::new_err)
+    }
+}
+
+/// How floats are rounded to the nearest integers when quantizing the inputs and parameters of a
+/// model at their scale.
+#[derive(
+    Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, Default, Copy,
+)]
+#[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), derive(JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum QuantRounding {
+    #[default]
+    /// round half away from zero, which biases the sum of many quantized values away from zero
+    HalfAwayFromZero,
+    /// round half to even, such that ties round up as often as they round down
+    HalfToEven,
+    /// round down or up with probability proportional to the distance to the other integer, such
+    /// that the quantized values are unbiased on average. The noise is derived from the seed, the
+    /// value and its position, such that a seed always quantizes a tensor the same way.
+    Stochastic(u64),
+}
+
+impl QuantRounding {
+    /// Rounds `x`, the value at `index` of the tensor it is quantized in, to an integer
+    pub fn round(&self, x: f64, index: usize) -> f64 {
+        match self {
+            QuantRounding::HalfAwayFromZero => x.round(),
+            QuantRounding::HalfToEven => x.round_ties_even(),
+            QuantRounding::Stochastic(seed) => (x + unit_noise(*seed, index, x)).floor(),
+        }
+    }
+}
+
+/// A number in [0, 1) which is a hash of the seed, the index and the value (splitmix64)
+fn unit_noise(seed: u64, index: usize, x: f64) -> f64 {
+    let mut z = seed.wrapping_add((index as u64 + 1).wrapping_mul(0x9E3779B97F4A7C15))
+        ^ x.to_bits().rotate_left(32);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^= z >> 31;
+    (z >> 11) as f64 / (1u64 << 53) as f64
+}
+
+impl std::fmt::Display for QuantRounding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QuantRounding::HalfAwayFromZero => write!(f, "half_away_from_zero"),
+            QuantRounding::HalfToEven => write!(f, "half_to_even"),
+            QuantRounding::Stochastic(seed) => write!(f, "stochastic:{}", seed),
+        }
+    }
+}
+
+impl std::str::FromStr for QuantRounding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.to_lowercase();
+        match lower.split_once(':') {
+            None if lower == "half_away_from_zero" => Ok(QuantRounding::HalfAwayFromZero),
+            None if lower == "half_to_even" => Ok(QuantRounding::HalfToEven),
+            None if lower == "stochastic" => Ok(QuantRounding::Stochastic(0)),
+            Some(("stochastic", seed)) => seed
+                .parse()
+                .map(QuantRounding::Stochastic)
+                .map_err(|_| format!("invalid stochastic rounding seed {}", seed)),
+            _ => Err(format!(
+                "invalid rounding {}, accepts half_away_from_zero, half_to_even or stochastic:<seed>",
+                s
+            )),
+        }
+    }
+}
+
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+impl ToFlags for QuantRounding {
+    fn to_flags(&self) -> Vec<String> {
+        vec![format!("{}", self)]
+    }
+}
+
+#[cfg(feature = "python-bindings")]
+/// Converts QuantRounding into a PyObject (Required for QuantRounding to be compatible with Python)
+impl IntoPy<PyObject> for QuantRounding {
+    fn into_py(self, py: Python) -> PyObject {
+        self.to_string().to_object(py)
+    }
+}
+
+#[cfg(feature = "python-bindings")]
+/// Obtains QuantRounding from PyObject (Required for QuantRounding to be compatible with Python)
+impl<'source> FromPyObject<'source> for QuantRounding {
+    fn extract(ob: &'source PyAny) -> PyResult<Self> {
+        let trystr = <PyString as PyTryFrom>::try_from(ob)?;
+        trystr.to_string().parse().map_err(PyValueError::new_err)
+    }
+}
+
 /// Represents the scale of the model input, model parameters.
 #[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, PartialOrd)]
 pub struct VarScales {
@@ -282,6 +861,9 @@ pub struct VarVisibility {
     pub params: Visibility,
     /// Output of the model or computational graph
     pub output: Visibility,
+    /// Per output visibilities overriding `output`, one for each output if not empty
+    #[serde(default)]
+    pub outputs: Vec<Visibility>,
 }
 impl std::fmt::Display for VarVisibility {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
@@ -299,6 +881,7 @@ impl Default for VarVisibility {
             input: Visibility::Private,
             params: Visibility::Private,
             output: Visibility::Public,
+            outputs: vec![],
         }
     }
 }
@@ -315,16 +898,24 @@ impl VarVisibility {
             return Err(GraphError::ParamsPublicVisibility);
         }
 
-        if !output_vis.is_public()
+        if params_vis.is_merkle() || args.any_output_visibility(|v| v.is_merkle()) {
+            return Err(GraphError::InvalidMerkle(
+                "only inputs can be proven to belong to a dataset".to_string(),
+            ));
+        }
+
+        let output_is_private = !args.any_output_visibility(|v| {
+            v.is_public() || v.is_fixed() || v.is_hashed() || v.is_polycommit()
+        });
+
+        if output_is_private
             & !params_vis.is_public()
             & !input_vis.is_public()
-            & !output_vis.is_fixed()
             & !params_vis.is_fixed()
             & !input_vis.is_fixed()
-            & !output_vis.is_hashed()
             & !params_vis.is_hashed()
             & !input_vis.is_hashed()
-            & !output_vis.is_polycommit()
+            & !input_vis.is_merkle()
             & !params_vis.is_polycommit()
             & !input_vis.is_polycommit()
         {
@@ -334,8 +925,39 @@ impl VarVisibility {
             input: input_vis.clone(),
             params: params_vis.clone(),
             output: output_vis.clone(),
+            outputs: args.output_visibilities.clone(),
         })
     }
+
+    /// The visibility of the output at `idx`
+    pub fn output_of(&self, idx: usize) -> &Visibility {
+        self.outputs.get(idx).unwrap_or(&self.output)
+    }
+
+    /// The visibility the outputs are hashed or committed to with, and the indices of the
+    /// outputs it applies to out of `num_outputs`. With per output visibilities these are the
+    /// outputs with the single hashed or polycommit visibility among them, if any.
+    pub fn processed_outputs(&self, num_outputs: usize) -> (Visibility, Vec<usize>) {
+        if self.outputs.is_empty() {
+            let outlets = self.output.overwrites_inputs();
+            if outlets.is_empty() {
+                return (self.output.clone(), (0..num_outputs).collect());
+            }
+            return (self.output.clone(), outlets);
+        }
+        let visibility = self
+            .outputs
+            .iter()
+            .find(|v| v.requires_processing())
+            .cloned()
+            .unwrap_or(Visibility::Private);
+        let outlets = self
+            .outputs
+            .iter()
+            .positions(|v| *v == visibility)
+            .collect();
+        (visibility, outlets)
+    }
 }
 
 /// A wrapper for holding all columns that will be assigned to by a model.
@@ -473,3 +1095,77 @@ impl<F: PrimeField + TensorType + PartialOrd + std::hash::Hash> ModelVars<F> {
         }
     }
 }
+
+/// A circuit configuration for the entirety of a model loaded from an Onnx file.
+#[derive(Clone, Debug)]
+pub struct ModelConfig {
+    /// The base configuration for the circuit
+    pub base: PolyConfig<Fp>,
+    /// A wrapper for holding all columns that will be assigned to by the model
+    pub vars: ModelVars<Fp>,
+}
+
+impl ModelConfig {
+    /// Configures the gates, lookups and range checks of a model over its columns, from its settings alone such
+    /// that the constraint system can be rebuilt without the model, as when reading a verifying key
+    /// # Arguments
+    /// * `meta` - The constraint system.
+    /// * `vars` - The variables for the circuit.
+    /// * `settings` - [GraphSettings]
+    pub fn configure(
+        meta: &mut ConstraintSystem<Fp>,
+        vars: ModelVars<Fp>,
+        settings: &GraphSettings,
+    ) -> Result<Self, GraphError> {
+        debug!("configuring model");
+
+        let lookup_range = settings.run_args.lookup_range;
+        let tight_table_ranges = settings.run_args.tight_table_ranges();
+        let logrows = settings.run_args.logrows as usize;
+        let required_lookups = settings.required_lookups.clone();
+        let required_range_checks = settings.required_range_checks.clone();
+
+        let mut base_gate = PolyConfig::configure(
+            meta,
+            vars.advices[0..2].try_into()?,
+            &vars.advices[2],
+            settings.check_mode,
+        );
+        // set scale for HybridOp::RangeCheck and call self.conf_lookup on that op for percentage tolerance case
+        let input = &vars.advices[0];
+        let output = &vars.advices[2];
+        let index = &vars.advices[1];
+        for op in required_lookups {
+            let range = tight_table_ranges.get(&op).copied().unwrap_or(lookup_range);
+            base_gate.configure_lookup(meta, input, output, index, range, logrows, &op)?;
+        }
+        for op in &settings.required_fused_add_lookups {
+            base_gate.configure_fused_add_lookup(meta, op)?;
+        }
+
+        for range in required_range_checks {
+            base_gate.configure_range_check(meta, input, index, range, logrows)?;
+        }
+
+        if settings.requires_dynamic_lookup() {
+            base_gate.configure_dynamic_lookup(
+                meta,
+                vars.advices[0..3].try_into()?,
+                vars.advices[3..6].try_into()?,
+            )?;
+        }
+
+        if settings.requires_shuffle() {
+            base_gate.configure_shuffles(
+                meta,
+                vars.advices[0..2].try_into()?,
+                vars.advices[3..5].try_into()?,
+            )?;
+        }
+
+        Ok(ModelConfig {
+            base: base_gate,
+            vars,
+        })
+    }
+}
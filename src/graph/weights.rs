@@ -0,0 +1,333 @@
+//! Weights of a model overridden from a safetensors file when it is compiled, such that fine-tuned weights are
+//! compiled into the graph of the original onnx model without exporting it again (see `compile-circuit --weights`).
+//!
+//! The tensors of the file replace the initializers of the onnx model of the same names before the model is loaded
+//! and quantized, each with the shape and dtype of the initializer it replaces. The sha256 of the file is recorded
+//! in the run args of the settings, such that the settings (and their fingerprint) commit to the weights, and the
+//! file is checked against it whenever the model is loaded again.
+
+use super::errors::GraphError;
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+use std::path::Path;
+use std::path::PathBuf;
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+use tosubcommand::ToFlags;
+
+/// The safetensors dtypes with the size of their elements and the onnx data types of the initializers they override
+const DTYPES: [(&str, usize, i32); 13] = [
+    ("F32", 4, 1),
+    ("U8", 1, 2),
+    ("I8", 1, 3),
+    ("U16", 2, 4),
+    ("I16", 2, 5),
+    ("I32", 4, 6),
+    ("I64", 8, 7),
+    ("BOOL", 1, 9),
+    ("F16", 2, 10),
+    ("F64", 8, 11),
+    ("U32", 4, 12),
+    ("U64", 8, 13),
+    ("BF16", 2, 16),
+];
+
+/// The safetensors file the initializers of a model are overridden from, with its sha256
+#[derive(Clone, Debug, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), derive(JsonSchema))]
+pub struct WeightsOverride {
+    /// the path of the safetensors file
+    pub path: PathBuf,
+    /// the hex sha256 of the file
+    pub sha256: String,
+}
+
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+impl ToFlags for WeightsOverride {}
+
+impl WeightsOverride {
+    /// The override of the weights of a file, recording its sha256
+    #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+    pub fn from_path(path: PathBuf) -> Result<Self, GraphError> {
+        let bytes = read(&path)?;
+        Ok(WeightsOverride {
+            sha256: sha256::digest(&bytes[..]),
+            path,
+        })
+    }
+
+    /// The tensors of the file, checked against the recorded sha256
+    #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+    pub fn load(&self) -> Result<BTreeMap<String, SafeTensor>, GraphError> {
+        let bytes = read(&self.path)?;
+        let sha256 = sha256::digest(&bytes[..]);
+        if sha256 != self.sha256 {
+            return Err(GraphError::InvalidWeights(vec![format!(
+                "{} has sha256 {}, the settings record {}",
+                self.path.display(),
+                sha256,
+                self.sha256
+            )]));
+        }
+        parse_safetensors(&bytes)
+    }
+}
+
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+fn read(path: &Path) -> Result<Vec<u8>, GraphError> {
+    std::fs::read(path)
+        .map_err(|e| GraphError::ReadWriteFileError(path.display().to_string(), e.to_string()))
+}
+
+/// A tensor of a safetensors file, its data as the little-endian bytes of its elements
+#[derive(Clone, Debug, PartialEq)]
+pub struct SafeTensor {
+    /// the safetensors dtype of the elements, eg. `F32`
+    pub dtype: String,
+    /// the shape of the tensor
+    pub shape: Vec<usize>,
+    /// the bytes of the elements
+    pub data: Vec<u8>,
+}
+
+#[derive(Deserialize)]
+struct HeaderEntry {
+    dtype: String,
+    shape: Vec<usize>,
+    data_offsets: (usize, usize),
+}
+
+/// Parses the tensors of a safetensors file: the little-endian u64 length of its json header, the header mapping
+/// the name of each tensor to its dtype, shape and byte range in the data that follows, and the data
+pub fn parse_safetensors(bytes: &[u8]) -> Result<BTreeMap<String, SafeTensor>, GraphError> {
+    let invalid = |reason: String| GraphError::InvalidWeights(vec![reason]);
+    let header_len = bytes
+        .get(..8)
+        .map(|len| u64::from_le_bytes(len.try_into().unwrap_or_default()) as usize)
+        .ok_or_else(|| invalid("the file is too short for a safetensors header".into()))?;
+    let header = bytes
+        .get(8..8usize.saturating_add(header_len))
+        .ok_or_else(|| invalid("the file is shorter than its header".into()))?;
+    let data = &bytes[8 + header_len..];
+
+    let header: BTreeMap<String, serde_json::Value> = serde_json::from_slice(header)?;
+    let mut tensors = BTreeMap::new();
+    let mut problems = vec![];
+    for (name, entry) in header {
+        if name == "__metadata__" {
+            continue;
+        }
+        let entry: HeaderEntry = match serde_json::from_value(entry) {
+            Ok(entry) => entry,
+            Err(e) => {
+                problems.push(format!("{}: {}", name, e));
+                continue;
+            }
+        };
+        let Some((_, size, _)) = DTYPES.iter().find(|(dtype, ..)| *dtype == entry.dtype) else {
+            problems.push(format!("{}: unsupported dtype {}", name, entry.dtype));
+            continue;
+        };
+        let (start, end) = entry.data_offsets;
+        let len = entry.shape.iter().product::<usize>() * size;
+        match data.get(start..end) {
+            Some(bytes) if bytes.len() == len => {
+                tensors.insert(
+                    name,
+                    SafeTensor {
+                        dtype: entry.dtype,
+                        shape: entry.shape,
+                        data: bytes.to_vec(),
+                    },
+                );
+            }
+            _ => problems.push(format!(
+                "{}: bytes {}..{} don't hold {} elements of {} of the {} bytes of data",
+                name,
+                start,
+                end,
+                entry.shape.iter().product::<usize>(),
+                entry.dtype,
+                data.len()
+            )),
+        }
+    }
+    if !problems.is_empty() {
+        return Err(GraphError::InvalidWeights(problems));
+    }
+    Ok(tensors)
+}
+
+/// Overrides the initializers of an onnx model with the tensors of the same names, which must have their shapes
+/// and dtypes. Every tensor that doesn't override an initializer is reported, and none is overridden then.
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+pub fn override_initializers(
+    model: &mut tract_onnx::pb::ModelProto,
+    tensors: &BTreeMap<String, SafeTensor>,
+) -> Result<(), GraphError> {
+    let Some(graph) = model.graph.as_mut() else {
+        return Err(GraphError::InvalidWeights(vec![
+            "the onnx model has no graph".into(),
+        ]));
+    };
+
+    let mut problems = vec![];
+    let mut overrides = vec![];
+    for (name, tensor) in tensors {
+        let Some(idx) = graph.initializer.iter().position(|i| &i.name == name) else {
+            problems.push(format!("{} isn't an initializer of the model", name));
+            continue;
+        };
+        let initializer = &graph.initializer[idx];
+        let shape = initializer
+            .dims
+            .iter()
+            .map(|d| *d as usize)
+            .collect::<Vec<_>>();
+        if shape != tensor.shape {
+            problems.push(format!(
+                "{} has shape {:?}, the initializer {:?}",
+                name, tensor.shape, shape
+            ));
+        }
+        let data_type = DTYPES
+            .iter()
+            .find(|(dtype, ..)| *dtype == tensor.dtype)
+            .map(|(.., data_type)| *data_type);
+        if data_type != Some(initializer.data_type) {
+            let initializer_dtype = DTYPES
+                .iter()
+                .find(|(.., data_type)| *data_type == initializer.data_type)
+                .map_or(
+                    format!("onnx type {}", initializer.data_type),
+                    |(dtype, ..)| dtype.to_string(),
+                );
+            problems.push(format!(
+                "{} has dtype {}, the initializer {}",
+                name, tensor.dtype, initializer_dtype
+            ));
+        }
+        if !initializer.external_data.is_empty() {
+            problems.push(format!(
+                "{} is an initializer stored outside the model, which can't be overridden",
+                name
+            ));
+        }
+        overrides.push((idx, tensor));
+    }
+    if !problems.is_empty() {
+        return Err(GraphError::InvalidWeights(problems));
+    }
+
+    for (idx, tensor) in overrides {
+        let initializer = &mut graph.initializer[idx];
+        // the values of an initializer are read from its raw data when it has any
+        initializer.float_data.clear();
+        initializer.int32_data.clear();
+        initializer.int64_data.clear();
+        initializer.double_data.clear();
+        initializer.uint64_data.clear();
+        initializer.raw_data = tensor.data.clone();
+    }
+    log::debug!("overrode the initializers {:?}", tensors.keys());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The bytes of a safetensors file of f32 tensors
+    fn safetensors(tensors: &[(&str, Vec<usize>, Vec<f32>)]) -> Vec<u8> {
+        let mut header = serde_json::Map::new();
+        let mut data = vec![];
+        for (name, shape, values) in tensors {
+            let start = data.len();
+            data.extend(values.iter().flat_map(|v| v.to_le_bytes()));
+            header.insert(
+                name.to_string(),
+                serde_json::json!({"dtype": "F32", "shape": shape, "data_offsets": [start, data.len()]}),
+            );
+        }
+        header.insert("__metadata__".into(), serde_json::json!({"format": "pt"}));
+        let header = serde_json::to_vec(&header).unwrap();
+        let mut bytes = (header.len() as u64).to_le_bytes().to_vec();
+        bytes.extend(header);
+        bytes.extend(data);
+        bytes
+    }
+
+    #[test]
+    fn safetensors_parsed() {
+        let bytes = safetensors(&[
+            ("dense.weight", vec![2, 2], vec![1.0, 2.0, 3.0, 4.0]),
+            ("dense.bias", vec![2], vec![0.5, -0.5]),
+        ]);
+        let tensors = parse_safetensors(&bytes).unwrap();
+        assert_eq!(tensors.len(), 2);
+        assert_eq!(tensors["dense.bias"].shape, vec![2]);
+        assert_eq!(
+            tensors["dense.bias"].data,
+            [0.5f32, -0.5]
+                .iter()
+                .flat_map(|v| v.to_le_bytes())
+                .collect::<Vec<_>>()
+        );
+
+        // the data of a tensor must hold its elements
+        let mut short = bytes.clone();
+        short.truncate(bytes.len() - 4);
+        assert!(matches!(
+            parse_safetensors(&short),
+            Err(GraphError::InvalidWeights(problems)) if problems.len() == 1
+        ));
+        assert!(parse_safetensors(&bytes[..4]).is_err());
+    }
+
+    #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+    #[test]
+    fn initializers_overridden_by_name() {
+        use tract_onnx::pb::{GraphProto, ModelProto, TensorProto};
+
+        let initializer = |name: &str, dims: Vec<i64>| TensorProto {
+            name: name.into(),
+            dims,
+            data_type: 1,
+            float_data: vec![0.0; 4],
+            ..Default::default()
+        };
+        let mut model = ModelProto {
+            graph: Some(GraphProto {
+                initializer: vec![
+                    initializer("dense.weight", vec![2, 2]),
+                    initializer("dense.bias", vec![2]),
+                ],
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        // every problem is listed, and nothing is overridden
+        let wrong = parse_safetensors(&safetensors(&[
+            ("dense.bias", vec![3], vec![1.0, 2.0, 3.0]),
+            ("dense.scale", vec![2], vec![1.0, 2.0]),
+        ]))
+        .unwrap();
+        let unchanged = model.clone();
+        match override_initializers(&mut model, &wrong) {
+            Err(GraphError::InvalidWeights(problems)) => assert_eq!(problems.len(), 2),
+            other => panic!("expected invalid weights, got {:?}", other),
+        }
+        assert_eq!(model, unchanged);
+
+        let bias =
+            parse_safetensors(&safetensors(&[("dense.bias", vec![2], vec![1.0, 2.0])])).unwrap();
+        override_initializers(&mut model, &bias).unwrap();
+        let initializers = &model.graph.as_ref().unwrap().initializer;
+        assert_eq!(initializers[0], unchanged.graph.unwrap().initializer[0]);
+        assert!(initializers[1].float_data.is_empty());
+        assert_eq!(initializers[1].raw_data, bias["dense.bias"].data);
+    }
+}
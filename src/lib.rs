@@ -21,6 +21,11 @@
     missing_debug_implementations,
     unsafe_code
 )]
+// the verifier-only build leaves out the model and witness code of the graph module, which uses most of what remains
+#![cfg_attr(
+    all(feature = "verifier-only", not(feature = "ezkl")),
+    allow(dead_code, unused)
+)]
 // we allow this for our dynamic range based indexing scheme
 #![allow(clippy::single_range_in_vec_init)]
 #![feature(buf_read_has_data_left)]
@@ -80,6 +85,80 @@ pub enum EZKLError {
     SrsError(#[from] pfsys::srs::SrsError),
 }
 
+impl EZKLError {
+    /// The category of the error, as in the prefix of its message
+    pub fn kind(&self) -> &'static str {
+        match self {
+            EZKLError::AggregationError(_) => "aggregation",
+            #[cfg(all(
+                feature = "ezkl",
+                not(all(target_arch = "wasm32", target_os = "unknown"))
+            ))]
+            EZKLError::EthError(_) => "eth",
+            EZKLError::GraphError(_) => "graph",
+            EZKLError::PfsysError(_) => "pfsys",
+            EZKLError::CircuitError(_) => "circuit",
+            EZKLError::TensorError(_) => "tensor",
+            EZKLError::ModuleError(_) => "module",
+            EZKLError::IoError(_) => "io",
+            EZKLError::JsonError(_) => "json",
+            EZKLError::Utf8Error(_) => "utf8",
+            #[cfg(all(
+                feature = "ezkl",
+                not(all(target_arch = "wasm32", target_os = "unknown"))
+            ))]
+            EZKLError::ReqwestError(_) => "reqwest",
+            EZKLError::FmtError(_) => "fmt",
+            EZKLError::Halo2Error(_) => "halo2",
+            EZKLError::UncategorizedError(_) => "uncategorized",
+            #[cfg(all(
+                feature = "ezkl",
+                not(all(target_arch = "wasm32", target_os = "unknown"))
+            ))]
+            EZKLError::ExecutionError(_) => "execute",
+            EZKLError::SrsError(_) => "srs",
+        }
+    }
+
+    /// Whether the error stems from the inputs (missing or malformed files, invalid arguments, unsupported models)
+    /// rather than from a failure of ezkl itself
+    pub fn is_user_error(&self) -> bool {
+        match self {
+            EZKLError::GraphError(_)
+            | EZKLError::TensorError(_)
+            | EZKLError::IoError(_)
+            | EZKLError::JsonError(_)
+            | EZKLError::Utf8Error(_)
+            | EZKLError::SrsError(_)
+            | EZKLError::UncategorizedError(_) => true,
+            #[cfg(all(
+                feature = "ezkl",
+                not(all(target_arch = "wasm32", target_os = "unknown"))
+            ))]
+            EZKLError::ExecutionError(e) => e.is_user_error(),
+            _ => false,
+        }
+    }
+
+    /// Whether the command was abandoned as it was cancelled or ran out of time, rather than failing
+    pub fn is_cancelled(&self) -> bool {
+        use circuit::CircuitError;
+        use graph::errors::GraphError;
+        match self {
+            EZKLError::PfsysError(pfsys::errors::PfsysError::Cancelled)
+            | EZKLError::GraphError(GraphError::Cancelled)
+            | EZKLError::GraphError(GraphError::CircuitError(CircuitError::Cancelled))
+            | EZKLError::CircuitError(CircuitError::Cancelled) => true,
+            #[cfg(all(
+                feature = "ezkl",
+                not(all(target_arch = "wasm32", target_os = "unknown"))
+            ))]
+            EZKLError::ExecutionError(execute::ExecutionError::TimedOut(_)) => true,
+            _ => false,
+        }
+    }
+}
+
 impl From<&str> for EZKLError {
     fn from(s: &str) -> Self {
         EZKLError::UncategorizedError(s.to_string())
@@ -94,16 +173,24 @@ impl From<String> for EZKLError {
 
 use std::str::FromStr;
 
-use circuit::{table::Range, CheckMode, Tolerance};
+use circuit::lookup::{LookupOp, NodeLookupRange};
+use circuit::{table::Range, CheckMode, DivRounding, RangeCheckStrategy, Tolerance};
 #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
 use clap::Args;
 #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
 use fieldutils::IntegerRep;
-use graph::Visibility;
+use graph::limbs::{limb_bits, MAX_PACKED_BITS};
+use graph::weights::WeightsOverride;
+use graph::{
+    CommitmentGroup, OutputBound, OutputScale, PoseidonParams, QuantRounding, RescaleOverride,
+    RescaleTarget, Visibility, DEFAULT_COMMITMENT_GROUP,
+};
 use halo2_proofs::poly::{
     ipa::commitment::IPACommitmentScheme, kzg::commitment::KZGCommitmentScheme,
 };
 use halo2curves::bn256::{Bn256, G1Affine};
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
 use tosubcommand::ToFlags;
@@ -112,7 +199,8 @@ use tosubcommand::ToFlags;
 #[cfg(any(
     feature = "ios-bindings",
     all(target_arch = "wasm32", target_os = "unknown"),
-    feature = "python-bindings"
+    feature = "python-bindings",
+    feature = "c-bindings"
 ))]
 pub mod bindings;
 /// Methods for configuring tensor operations and assigning values to them in a Halo2 circuit.
@@ -120,6 +208,9 @@ pub mod circuit;
 /// CLI commands.
 #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
 pub mod commands;
+/// Project configuration file providing defaults for the arguments of the cli
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+pub mod config;
 #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
 // abigen doesn't generate docs for this module
 #[allow(missing_docs)]
@@ -132,7 +223,7 @@ pub mod execute;
 /// Utilities for converting from Halo2 Field types to integers (and vice-versa).
 pub mod fieldutils;
 /// Methods for loading onnx format models and automatically laying them out in
-/// a Halo2 circuit.
+/// a Halo2 circuit. Only the settings, columns and modules of circuits are kept with the `verifier-only` feature.
 #[cfg(any(feature = "onnx", not(feature = "ezkl")))]
 pub mod graph;
 /// beautiful logging
@@ -143,6 +234,18 @@ pub mod graph;
 pub mod logger;
 /// Tools for proofs and verification used by cli
 pub mod pfsys;
+/// Collects the durations of the tracing spans of a run into a profile readable by speedscope
+#[cfg(all(
+    feature = "ezkl",
+    not(all(target_arch = "wasm32", target_os = "unknown"))
+))]
+pub mod profiling;
+/// Markdown and html reports of a circuit and a proof of it, for audits
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+pub mod report;
+/// HTTP proving service keeping the artifacts of circuits loaded between requests
+#[cfg(all(feature = "server", not(target_arch = "wasm32")))]
+pub mod server;
 /// srs sha hashes
 #[cfg(all(
     feature = "ezkl",
@@ -173,6 +276,11 @@ lazy_static! {
     pub static ref EZKL_KEY_FORMAT: String = std::env::var("EZKL_KEY_FORMAT")
         .unwrap_or("raw-bytes".to_string());
 
+    /// Whether memory-mapped proving keys should be faulted into memory upfront
+    pub static ref EZKL_PK_PREFAULT: bool = std::env::var("EZKL_PK_PREFAULT")
+        .map(|v| v == "1" || v.to_lowercase() == "true")
+        .unwrap_or(false);
+
 }
 
 #[cfg(any(not(feature = "ezkl"), target_arch = "wasm32"))]
@@ -184,6 +292,7 @@ const EZKL_BUF_CAPACITY: &usize = &8000;
 #[derive(
     Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, Default, Copy,
 )]
+#[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), derive(JsonSchema))]
 /// Commitment scheme
 pub enum Commitments {
     #[default]
@@ -253,8 +362,15 @@ impl From<String> for Commitments {
     }
 }
 
+/// The number of inner columns that has them picked automatically when the circuit settings are generated
+pub const AUTO_NUM_INNER_COLS: usize = 0;
+
+/// The default number of values above which identical constants are laid out once
+pub const DEFAULT_CONSTANT_DEDUP_THRESHOLD: usize = 16;
+
 /// Parameters specific to a proving run
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, PartialOrd)]
+#[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), derive(JsonSchema))]
 #[cfg_attr(
     all(feature = "ezkl", not(target_arch = "wasm32")),
     derive(Args, ToFlags)
@@ -278,8 +394,8 @@ pub struct RunArgs {
     /// The log_2 number of rows
     #[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), arg(short = 'K', long, default_value = "17", value_hint = clap::ValueHint::Other))]
     pub logrows: u32,
-    /// The log_2 number of rows
-    #[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), arg(short = 'N', long, default_value = "2", value_hint = clap::ValueHint::Other))]
+    /// The number of inner columns of the advice blocks, `auto` (or 0) picks the number that takes the least area (rows × columns) while fitting in logrows
+    #[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), arg(short = 'N', long, default_value = "2", value_parser = parse_num_inner_cols, value_hint = clap::ValueHint::Other))]
     pub num_inner_cols: usize,
     /// Hand-written parser for graph variables, eg. batch_size=1
     #[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), arg(short = 'V', long, value_parser = parse_key_val::<String, usize>, default_value = "batch_size->1", value_delimiter = ',', value_hint = clap::ValueHint::Other))]
@@ -317,6 +433,115 @@ pub struct RunArgs {
     #[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), arg(long, default_value = "2", value_hint = clap::ValueHint::Other))]
     /// the number of legs used for decompositions
     pub decomp_legs: usize,
+    /// Names of the outputs of the onnx model to keep, in order, eg. head_logits. The nodes that don't feed them are removed before quantization, such that they are neither laid out nor counted against the rows, and the outputs of the circuit (their visibilities, indices and instances) are the kept ones. All the outputs are kept if empty
+    #[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), arg(long, value_delimiter = ',', value_hint = clap::ValueHint::Other))]
+    #[serde(default)]
+    pub outputs: Vec<String>,
+    /// Per output visibilities overriding output_visibility, one for each output, eg. public,hashed/public,private. Accepts public, private, fixed, hashed/public, hashed/sha256/public and polycommit, with at most one of the hashed or polycommit visibilities
+    #[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), arg(long, value_delimiter = ',', value_hint = clap::ValueHint::Other))]
+    #[serde(default)]
+    pub output_visibilities: Vec<Visibility>,
+    /// Outputs hashed in public (hashed/public or hashed/sha256/public) that are also exposed in the clear, by index, eg. 0,2. Their values are public instances they are constrained equal to, after the public inputs and with the public outputs, and they are hashed along with the other hashed outputs, such that a later proof can reference the hashes of the full outputs while the revealed values are read from the instances
+    #[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), arg(long, value_delimiter = ',', value_hint = clap::ValueHint::Other))]
+    #[serde(default)]
+    pub revealed_outputs: Vec<usize>,
+    /// Inclusive bounds asserted in the circuit on outputs, as output:lower:upper with either bound possibly empty, eg. 0::0.2. The bounds are in float and converted at the output scale, a witness violating them can't be generated nor proven
+    #[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), arg(long, value_delimiter = ',', allow_hyphen_values = true, value_hint = clap::ValueHint::Other))]
+    #[serde(default)]
+    pub output_bounds: Vec<OutputBound>,
+    /// The parameters of the Poseidon hash of the hashed visibilities (ezkl or circomlib), circomlib hashes as the BN254 `Poseidon(2)` of circomlib such that the hashes match those of circom circuits
+    #[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), arg(long, default_value = "ezkl", value_hint = clap::ValueHint::Other))]
+    #[serde(default)]
+    pub poseidon_params: PoseidonParams,
+    /// Constant params with at least this fraction of zeros (between 0 and 1) are represented sparsely, and the terms of their zeros are elided from the matmul and conv layouts. The positions of the zeros then become part of the circuit, and so are public
+    #[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), arg(long, value_hint = clap::ValueHint::Other))]
+    #[serde(default)]
+    pub sparsity_threshold: Option<f32>,
+    /// Fixed params with at least this fraction of zeros (between 0 and 1) are represented sparsely when sparsity_threshold is unset. The positions of the zeros of fixed params are in the circuit anyway, unlike those of private params which only sparsity_threshold sparsifies. Unset by default, such that circuits are unchanged unless it is set
+    #[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), arg(long, value_hint = clap::ValueHint::Other))]
+    #[serde(default)]
+    pub fixed_sparsity_threshold: Option<f32>,
+    /// How the inputs and params are rounded when quantized: half_away_from_zero, half_to_even, or stochastic:<seed> which rounds up or down at random (reproducibly for a seed) such that the quantized values are unbiased
+    #[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), arg(long, default_value = "half_away_from_zero", value_hint = clap::ValueHint::Other))]
+    #[serde(default)]
+    pub rounding: QuantRounding,
+    /// Constants with at least this many values that are identical to a constant laid out before reuse its cells through copy constraints instead of being assigned again, 0 disables the deduplication (and is the value for the settings from before it, such that their circuits are unchanged)
+    #[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), arg(long, default_value = "16", value_hint = clap::ValueHint::Other))]
+    #[serde(default)]
+    pub constant_dedup_threshold: usize,
+    /// Overrides of the rescales ezkl inserts on its own at the outputs of nodes, as node:keep to keep the precision of the output of a node or node:scale to rescale it to a scale, eg. 4:keep,7:9. The nodes are indexed as in the table of the model, and the rescales inserted are listed by the list-rescales command
+    #[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), arg(long, value_delimiter = ',', value_hint = clap::ValueHint::Other))]
+    #[serde(default)]
+    pub rescale_overrides: Vec<RescaleOverride>,
+    /// How the quotients of the divisions (of the rescales and casts of the model) and of the reciprocals are rounded to integers: round (to the nearest integer, halves away from zero), floor (as Python's `//`) or trunc (towards zero, as Rust and C). Only round divides with range checks, floor and trunc are always looked up
+    #[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), arg(long, default_value = "round", value_hint = clap::ValueHint::Other))]
+    #[serde(default)]
+    pub div_rounding: DivRounding,
+    /// Public inputs and outputs with more than this many values are packed: they are hashed/public instead, exposing a single circomlib Poseidon digest per tensor, and the verifier created by create-evm-packed re-derives the digests from the values passed in calldata. The inputs share a visibility so a single large input packs them all. Requires the circomlib poseidon_params
+    #[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), arg(long, value_hint = clap::ValueHint::Other))]
+    #[serde(default)]
+    pub pack_public_threshold: Option<usize>,
+    /// Splits the contractions (dot products) too long to be accumulated exactly at the scale of their inputs into chunks, whose partial sums are divided by a power of two (with the rescale lookups or range checks) before being added up. The accumulated values are then bounded by 2^53 whatever the length of the contraction, at the cost of the shift of the output scale and of an error of at most half a unit of the output per chunk
+    #[cfg_attr(
+        all(feature = "ezkl", not(target_arch = "wasm32")),
+        arg(long, default_value = "false")
+    )]
+    #[serde(default)]
+    pub widen_accumulation: bool,
+    /// The length of the chunks of the widened contractions, see widen_accumulation. By default the longest chunks whose partial sums stay within 2^53, given the largest values of the constant operands and the lookup range for the others
+    #[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), arg(long, value_hint = clap::ValueHint::Other))]
+    #[serde(default)]
+    pub accumulation_chunk_size: Option<usize>,
+    /// Named groups of nodes whose polycommit params are committed to separately, as name:nodes with the nodes joined by + and inclusive ranges as first-last, eg. head:12-14+17. The params of the nodes of no group are committed to in the `default` group, and the commitments of each group can be swapped into a proof and checked by the verifier on their own (see commit-param-groups). Requires the polycommit param_visibility
+    #[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), arg(long, value_delimiter = ',', value_hint = clap::ValueHint::Other))]
+    #[serde(default)]
+    pub commitment_groups: Vec<CommitmentGroup>,
+    /// Packs the values of the public outputs this many at a time into each of their instances, as limbs of the bits of the decomposition of decomp_base and decomp_legs, which the values are range checked to in the circuit. The verifier then takes about 1/instance_limbs of the instances of the outputs, and the contract created by create-evm-packed unpacks their values. Requires a zero tolerance
+    #[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), arg(long, value_hint = clap::ValueHint::Other))]
+    #[serde(default)]
+    pub instance_limbs: Option<usize>,
+    /// How the range checks (of the divisions, reciprocals, decompositions and comparisons) are laid out: lookup looks the values up in a table of each range, bits decomposes them in boolean constrained bits without tables, and auto decomposes the values of the ranges whose table would take more rows than the decompositions, such as wide ranges few values are checked to
+    #[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), arg(long, default_value = "lookup", value_hint = clap::ValueHint::Other))]
+    #[serde(default)]
+    pub range_check_strategy: RangeCheckStrategy,
+    /// The ranges whose range checks decompose the values in bits, as resolved from range_check_strategy when the settings are generated
+    #[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), arg(skip))]
+    #[serde(default)]
+    pub bit_range_checks: Vec<Range>,
+    /// The scales the outputs are rescaled to before they are exposed, hashed or compared, as output:scale with the output given by its index or the name of the node computing it, eg. 0:14,count:0. The rescales (a division or a multiplication) are inserted only where the scale of an output differs, and the witness records the scale of each output to dequantize it with
+    #[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), arg(long, value_delimiter = ',', allow_hyphen_values = true, value_hint = clap::ValueHint::Other))]
+    #[serde(default)]
+    pub output_scales: Vec<OutputScale>,
+    /// Fuse elementwise adds into the lookups they feed (as the residual add and activation of a resnet block), such that the sums are looked up without being assigned. Only lookups whose table fits in a single column, the lookup range being smaller than the rows of the circuit, are fused
+    #[cfg_attr(
+        all(feature = "ezkl", not(target_arch = "wasm32")),
+        arg(long, default_value = "false")
+    )]
+    #[serde(default)]
+    pub fuse_add_lookups: bool,
+    /// Fold chains of multiplications and divisions by scalar constants (as the normalization of the inputs) into a single multiplication, or into the weights of the conv or matmul they follow or feed, such that the intermediate rescales are elided. Values with other uses and constants that are hashed or committed to are kept
+    #[cfg_attr(
+        all(feature = "ezkl", not(target_arch = "wasm32")),
+        arg(long, default_value = "false")
+    )]
+    #[serde(default)]
+    pub fold_constant_scalings: bool,
+    /// The margin calibration sizes the tables of the reciprocals and reciprocal square roots with around the inputs it observed for each of their nodes (variances and norms taking a small positive part of the lookup range), each bound of the observed range scaled away from zero by the margin. 0 sizes their tables to the lookup range as those of the other ops
+    #[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), arg(long, default_value = "2", value_hint = clap::ValueHint::Other))]
+    #[serde(default)]
+    pub tight_lookup_margin: f32,
+    /// The ranges of the inputs of the reciprocals and reciprocal square roots of each node, with the tight_lookup_margin, as observed when calibrating. The tables of their ops are sized to the ranges of their nodes, those of the ops without ranges to the lookup range
+    #[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), arg(skip))]
+    #[serde(default)]
+    pub tight_lookup_ranges: Vec<NodeLookupRange>,
+    /// Takes a last boolean input masking the samples of the batch, 1 for the samples proven and 0 for those padding the batch, which multiplies the outputs along their first (batch) axis and is exposed as a last output, such that the outputs of the padding are zero in the instances (see compile-batch-family). Requires public outputs
+    #[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), arg(skip))]
+    #[serde(default)]
+    pub batch_mask: bool,
+    /// The safetensors file the initializers of the model are overridden from, by name, before they are quantized, with its sha256 such that the settings commit to the weights (see compile-circuit --weights). The file is checked against the hash whenever the model is loaded
+    #[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), arg(skip))]
+    #[serde(default)]
+    pub weights: Option<WeightsOverride>,
 }
 
 impl Default for RunArgs {
@@ -339,6 +564,31 @@ impl Default for RunArgs {
             commitment: None,
             decomp_base: 16384,
             decomp_legs: 2,
+            outputs: vec![],
+            output_visibilities: vec![],
+            revealed_outputs: vec![],
+            output_bounds: vec![],
+            poseidon_params: PoseidonParams::default(),
+            sparsity_threshold: None,
+            fixed_sparsity_threshold: None,
+            rounding: QuantRounding::default(),
+            constant_dedup_threshold: DEFAULT_CONSTANT_DEDUP_THRESHOLD,
+            rescale_overrides: vec![],
+            div_rounding: DivRounding::default(),
+            pack_public_threshold: None,
+            widen_accumulation: false,
+            accumulation_chunk_size: None,
+            commitment_groups: vec![],
+            instance_limbs: None,
+            range_check_strategy: RangeCheckStrategy::default(),
+            bit_range_checks: vec![],
+            output_scales: vec![],
+            fuse_add_lookups: false,
+            fold_constant_scalings: false,
+            tight_lookup_margin: 2.0,
+            tight_lookup_ranges: vec![],
+            batch_mask: false,
+            weights: None,
         }
     }
 }
@@ -361,15 +611,258 @@ impl RunArgs {
         if self.logrows < 1 {
             return Err("logrows must be >= 1".into());
         }
-        if self.num_inner_cols < 1 {
-            return Err("num_inner_cols must be >= 1".into());
-        }
-        if self.tolerance.val > 0.0 && self.output_visibility != Visibility::Public {
+        if self.tolerance.val > 0.0 && self.any_output_visibility(|v| *v != Visibility::Public) {
             return Err("tolerance > 0.0 requires output_visibility to be public".into());
         }
+        for visibility in &self.output_visibilities {
+            let allowed = match visibility {
+                Visibility::Hashed { hash_is_public, .. }
+                | Visibility::Sha256Hashed { hash_is_public, .. } => *hash_is_public,
+                Visibility::Merkle { .. } => false,
+                _ => true,
+            };
+            if !allowed {
+                return Err(format!(
+                    "an output can't have the {} visibility",
+                    visibility
+                ));
+            }
+        }
+        let mut processed = self
+            .output_visibilities
+            .iter()
+            .filter(|v| v.requires_processing());
+        if let Some(first) = processed.next() {
+            if processed.any(|v| v != first) {
+                return Err(
+                    "the outputs can only be hashed or committed to with a single visibility"
+                        .into(),
+                );
+            }
+        }
+        for (i, idx) in self.revealed_outputs.iter().enumerate() {
+            if !self.visibility_of_output(*idx).is_hashed_public() {
+                return Err(format!(
+                    "output {} is revealed but has the {} visibility, only the outputs hashed in public can also be revealed",
+                    idx,
+                    self.visibility_of_output(*idx)
+                ));
+            }
+            if self.revealed_outputs[..i].contains(idx) {
+                return Err(format!("output {} is revealed twice", idx));
+            }
+        }
+        for bound in &self.output_bounds {
+            if let (Some(lower), Some(upper)) = (bound.lower, bound.upper) {
+                if lower > upper {
+                    return Err(format!(
+                        "the lower bound of output {} is greater than its upper bound",
+                        bound.output
+                    ));
+                }
+            }
+        }
+        if self.poseidon_params == PoseidonParams::Circomlib && self.input_visibility.is_merkle() {
+            return Err("merkle inclusion proofs hash with the ezkl poseidon parameters".into());
+        }
+        if let Some(threshold) = self.sparsity_threshold {
+            if !(0.0..=1.0).contains(&threshold) {
+                return Err("sparsity_threshold must be between 0 and 1".into());
+            }
+        }
+        if let Some(threshold) = self.fixed_sparsity_threshold {
+            if !(0.0..=1.0).contains(&threshold) {
+                return Err("fixed_sparsity_threshold must be between 0 and 1".into());
+            }
+        }
+        if self.pack_public_threshold.is_some() && self.poseidon_params != PoseidonParams::Circomlib
+        {
+            return Err(
+                "packed instances are hashed with the circomlib poseidon parameters, which the evm verifier re-derives them with"
+                    .into(),
+            );
+        }
+        if self.tight_lookup_margin != 0.0 && self.tight_lookup_margin < 1.0 {
+            return Err("tight_lookup_margin must be 0 or >= 1".into());
+        }
+        if self.batch_mask && self.any_output_visibility(|v| *v != Visibility::Public) {
+            return Err("batch_mask requires output_visibility to be public".into());
+        }
+        if self.accumulation_chunk_size == Some(0) {
+            return Err("accumulation_chunk_size must be >= 1".into());
+        }
+        for (i, o) in self.rescale_overrides.iter().enumerate() {
+            if self.rescale_overrides[..i].iter().any(|p| p.node == o.node) {
+                return Err(format!(
+                    "node {} has more than one rescale override",
+                    o.node
+                ));
+            }
+        }
+        for (i, o) in self.output_scales.iter().enumerate() {
+            if self.output_scales[..i].iter().any(|p| p.output == o.output) {
+                return Err(format!(
+                    "output {} has more than one output scale",
+                    o.output
+                ));
+            }
+        }
+        if !self.commitment_groups.is_empty() && !self.param_visibility.is_polycommit() {
+            return Err("commitment_groups require the polycommit param_visibility".into());
+        }
+        for (i, group) in self.commitment_groups.iter().enumerate() {
+            let valid_name = group
+                .name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+            if group.name.is_empty() || !valid_name || group.name == DEFAULT_COMMITMENT_GROUP {
+                return Err(format!(
+                    "invalid commitment group name {:?}, names are made of letters, digits, _ and - and can't be {}",
+                    group.name, DEFAULT_COMMITMENT_GROUP
+                ));
+            }
+            for previous in &self.commitment_groups[..i] {
+                if previous.name == group.name {
+                    return Err(format!("commitment group {} is defined twice", group.name));
+                }
+                if let Some(node) = group.nodes.iter().find(|n| previous.nodes.contains(n)) {
+                    return Err(format!(
+                        "node {} is in both commitment groups {} and {}",
+                        node, previous.name, group.name
+                    ));
+                }
+            }
+        }
+        if let Some(limbs) = self.instance_limbs {
+            if limbs < 2 {
+                return Err("instance_limbs must be >= 2".into());
+            }
+            if limbs * self.instance_limb_bits() > MAX_PACKED_BITS {
+                return Err(format!(
+                    "{} limbs of {} bits don't fit an instance of {} bits, lower instance_limbs or the decomposition",
+                    limbs,
+                    self.instance_limb_bits(),
+                    MAX_PACKED_BITS
+                ));
+            }
+            if self.tolerance.val > 0.0 {
+                return Err(
+                    "the values packed by instance_limbs are checked with a zero tolerance".into(),
+                );
+            }
+            if self.pack_public_threshold.is_some() {
+                return Err(
+                    "instance_limbs and pack_public_threshold pack the instances for contracts of their own, set either"
+                        .into(),
+                );
+            }
+        }
         Ok(())
     }
 
+    /// The range the table of each op of the [RunArgs::tight_lookup_ranges] spans, covering the ranges of its nodes
+    /// within the lookup range. The tables of the other ops span the lookup range.
+    pub fn tight_table_ranges(&self) -> std::collections::BTreeMap<LookupOp, Range> {
+        let mut ranges = std::collections::BTreeMap::<LookupOp, Range>::new();
+        for node in &self.tight_lookup_ranges {
+            let range = ranges.entry(node.op.clone()).or_insert(node.range);
+            *range = (range.0.min(node.range.0), range.1.max(node.range.1));
+        }
+        for range in ranges.values_mut() {
+            *range = (
+                range.0.max(self.lookup_range.0),
+                range.1.min(self.lookup_range.1),
+            );
+        }
+        ranges
+    }
+
+    /// The fraction of zeros from which the constant params of a visibility are represented sparsely. Private params
+    /// are only sparsified from sparsity_threshold, the positions of their zeros becoming public, while fixed params
+    /// fall back to fixed_sparsity_threshold
+    pub fn sparsity_threshold_of(&self, visibility: &Visibility) -> Option<f32> {
+        match visibility {
+            Visibility::Private => self.sparsity_threshold,
+            Visibility::Fixed => self.sparsity_threshold.or(self.fixed_sparsity_threshold),
+            _ => None,
+        }
+    }
+
+    /// The bits of the limbs the public outputs are packed to by [RunArgs::instance_limbs], holding a value of the
+    /// decomposition biased by half a limb
+    pub fn instance_limb_bits(&self) -> usize {
+        limb_bits(self.decomp_base, self.decomp_legs)
+    }
+
+    /// The run args with the public inputs and outputs of more than [RunArgs::pack_public_threshold]
+    /// values made hashed/public, given the number of values of each input and output. Packed
+    /// tensors are left hashed/public, and so packing the packed run args changes nothing.
+    pub fn with_packed_instances(
+        &self,
+        input_lens: &[usize],
+        output_lens: &[usize],
+    ) -> Result<RunArgs, String> {
+        let Some(threshold) = self.pack_public_threshold else {
+            return Ok(self.clone());
+        };
+        let packed = Visibility::Hashed {
+            hash_is_public: true,
+            outlets: vec![],
+        };
+
+        let mut run_args = self.clone();
+        if self.input_visibility.is_public() && input_lens.iter().any(|len| *len > threshold) {
+            run_args.input_visibility = packed.clone();
+        }
+        let packs =
+            |idx: usize| self.visibility_of_output(idx).is_public() && output_lens[idx] > threshold;
+        let num_outputs = output_lens.len();
+        if (0..num_outputs).any(packs) {
+            if self.output_visibilities.is_empty() && (0..num_outputs).all(packs) {
+                run_args.output_visibility = packed;
+            } else {
+                run_args.output_visibilities = (0..num_outputs)
+                    .map(|idx| match packs(idx) {
+                        true => packed.clone(),
+                        false => self.visibility_of_output(idx).clone(),
+                    })
+                    .collect();
+            }
+        }
+        run_args.validate()?;
+        Ok(run_args)
+    }
+
+    /// The visibility of the output at `idx`, from output_visibilities if it is set
+    pub fn visibility_of_output(&self, idx: usize) -> &Visibility {
+        self.output_visibilities
+            .get(idx)
+            .unwrap_or(&self.output_visibility)
+    }
+
+    /// Whether the values of the output at `idx` are public instances: it is public, or hashed in public and revealed
+    /// (see [RunArgs::revealed_outputs])
+    pub fn output_is_public(&self, idx: usize) -> bool {
+        self.visibility_of_output(idx).is_public() || self.revealed_outputs.contains(&idx)
+    }
+
+    /// The rescale override of the output of the node at `idx`, if any
+    pub fn rescale_override(&self, idx: usize) -> Option<RescaleTarget> {
+        self.rescale_overrides
+            .iter()
+            .find(|o| o.node == idx)
+            .map(|o| o.target)
+    }
+
+    /// Whether the visibility of any output satisfies the predicate
+    pub fn any_output_visibility(&self, f: impl Fn(&Visibility) -> bool) -> bool {
+        if self.output_visibilities.is_empty() {
+            f(&self.output_visibility)
+        } else {
+            self.output_visibilities.iter().any(f)
+        }
+    }
+
     /// Export the ezkl configuration as json
     pub fn as_json(&self) -> Result<String, Box<dyn std::error::Error>> {
         let serialized = match serde_json::to_string(&self) {
@@ -386,6 +879,18 @@ impl RunArgs {
     }
 }
 
+/// Parse a number of inner columns, `auto` being [AUTO_NUM_INNER_COLS]
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+fn parse_num_inner_cols(
+    s: &str,
+) -> Result<usize, Box<dyn std::error::Error + Send + Sync + 'static>> {
+    if s == "auto" {
+        Ok(AUTO_NUM_INNER_COLS)
+    } else {
+        Ok(s.parse()?)
+    }
+}
+
 /// Parse a single key-value pair
 #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
 fn parse_key_val<T, U>(
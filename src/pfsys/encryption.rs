@@ -0,0 +1,376 @@
+//! Encryption at rest of the compiled circuit and the proving key, which hold the weights of a model.
+//!
+//! Artifacts are encrypted with [age](https://age-encryption.org) to an X25519 recipient, the `age1...` public key of
+//! an identity as generated by `age-keygen`. They are decrypted with that identity into anonymous memory only, so the
+//! plaintext weights never reach the disk, and are otherwise loaded as usual.
+use super::mmap::{read_mapped_pk, MMAP_PK_MAGIC};
+use super::{serde_format_from_str, PfsysError};
+use crate::EZKL_KEY_FORMAT;
+use halo2_proofs::plonk::{Circuit, ProvingKey};
+use halo2_proofs::poly::commitment::CommitmentScheme;
+use halo2curves::ff::{FromUniformBytes, PrimeField};
+use halo2curves::serde::SerdeObject;
+use halo2curves::CurveAffine;
+use log::{debug, info};
+use memmap2::MmapMut;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// The first bytes of a file in the binary age format
+pub const AGE_HEADER: &[u8] = b"age-encryption.org/v1\n";
+
+/// Errors encrypting or decrypting an artifact
+#[derive(Debug, Error)]
+pub enum EncryptionError {
+    /// The recipient isn't an X25519 `age1...` public key, nor a file holding one
+    #[error("invalid recipient {0}: {1}")]
+    InvalidRecipient(String, String),
+    /// The identity file doesn't hold an X25519 `AGE-SECRET-KEY-1...` secret key
+    #[error("invalid identity {0}: {1}")]
+    InvalidIdentity(String, String),
+    /// The artifact is encrypted to a recipient other than the identity
+    #[error(
+        "the identity at {identity} can't decrypt {path}: it is encrypted to another recipient"
+    )]
+    WrongIdentity {
+        /// the path of the artifact
+        path: String,
+        /// the path of the identity
+        identity: String,
+    },
+    /// The artifact is encrypted and no identity was given to decrypt it
+    #[error(
+        "{0} is encrypted, it can only be loaded with the identity it is encrypted to (--identity)"
+    )]
+    MissingIdentity(String),
+    /// The artifact is corrupted or isn't encrypted with an X25519 recipient
+    #[error("failed to decrypt {0}: {1}")]
+    Decrypt(String, String),
+    /// Reading or writing the artifact failed
+    #[error("[io] ({0}) {1}")]
+    Io(String, String),
+}
+
+/// An X25519 public key artifacts are encrypted to
+#[derive(Clone)]
+pub struct Recipient(age::x25519::Recipient);
+
+impl Recipient {
+    /// Parses an `age1...` public key, or reads it from a file as written by `age-keygen -y`
+    pub fn parse(recipient: &str) -> Result<Self, EncryptionError> {
+        let invalid =
+            |e: &str| EncryptionError::InvalidRecipient(recipient.to_string(), e.to_string());
+        let key = if Path::new(recipient).is_file() {
+            let contents =
+                std::fs::read_to_string(recipient).map_err(|e| invalid(&e.to_string()))?;
+            key_line(&contents)
+                .ok_or_else(|| invalid("the file holds no public key"))?
+                .to_string()
+        } else {
+            recipient.trim().to_string()
+        };
+        key.parse().map(Recipient).map_err(invalid)
+    }
+}
+
+impl std::fmt::Display for Recipient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// An X25519 secret key, read from the file it is kept in, artifacts are decrypted with
+pub struct Identity {
+    key: age::x25519::Identity,
+    path: PathBuf,
+}
+
+impl Identity {
+    /// Reads the `AGE-SECRET-KEY-1...` secret key of a file as written by `age-keygen`
+    pub fn from_file(path: &Path) -> Result<Self, EncryptionError> {
+        let invalid =
+            |e: &str| EncryptionError::InvalidIdentity(path.display().to_string(), e.to_string());
+        let contents = std::fs::read_to_string(path).map_err(|e| invalid(&e.to_string()))?;
+        let key = key_line(&contents)
+            .ok_or_else(|| invalid("the file holds no secret key"))?
+            .parse()
+            .map_err(invalid)?;
+        Ok(Identity {
+            key,
+            path: path.to_path_buf(),
+        })
+    }
+
+    /// Generates a new identity kept at `path`, in the format of `age-keygen`
+    pub fn generate(path: &Path) -> Result<Self, EncryptionError> {
+        use age::secrecy::ExposeSecret;
+
+        let key = age::x25519::Identity::generate();
+        let contents = format!(
+            "# public key: {}\n{}\n",
+            key.to_public(),
+            key.to_string().expose_secret()
+        );
+        std::fs::write(path, contents)
+            .map_err(|e| EncryptionError::Io(path.display().to_string(), e.to_string()))?;
+        Ok(Identity {
+            key,
+            path: path.to_path_buf(),
+        })
+    }
+
+    /// The recipient artifacts decrypted by the identity are encrypted to
+    pub fn to_public(&self) -> Recipient {
+        Recipient(self.key.to_public())
+    }
+}
+
+/// The first line of a key file that isn't a comment
+fn key_line(contents: &str) -> Option<&str> {
+    contents
+        .lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty() && !l.starts_with('#'))
+}
+
+/// Returns true if the file at `path` is encrypted with age
+pub fn is_encrypted(path: &Path) -> bool {
+    let mut header = [0u8; AGE_HEADER.len()];
+    match File::open(path) {
+        Ok(mut f) => f.read_exact(&mut header).is_ok() && header == AGE_HEADER,
+        Err(_) => false,
+    }
+}
+
+/// Errors if the file at `path` is encrypted, for the loaders of plaintext artifacts
+pub fn ensure_plaintext(path: &Path) -> Result<(), EncryptionError> {
+    if is_encrypted(path) {
+        return Err(EncryptionError::MissingIdentity(path.display().to_string()));
+    }
+    Ok(())
+}
+
+/// Writes the artifact serialized by `write` to `path`, encrypted to `recipient`. The plaintext is encrypted as it is
+/// serialized and is never written to the disk.
+pub fn write_encrypted(
+    path: &Path,
+    recipient: &Recipient,
+    write: impl FnOnce(&mut dyn Write) -> io::Result<()>,
+) -> Result<(), EncryptionError> {
+    let io_err = |e: io::Error| EncryptionError::Io(path.display().to_string(), e.to_string());
+    let recipients: Vec<Box<dyn age::Recipient + Send>> = vec![Box::new(recipient.0.clone())];
+    let encryptor = age::Encryptor::with_recipients(recipients).ok_or_else(|| {
+        EncryptionError::InvalidRecipient(
+            recipient.to_string(),
+            "no recipient to encrypt to".into(),
+        )
+    })?;
+    let f = File::create(path).map_err(io_err)?;
+    let mut writer = encryptor
+        .wrap_output(BufWriter::with_capacity(*crate::EZKL_BUF_CAPACITY, f))
+        .map_err(io_err)?;
+    write(&mut writer).map_err(io_err)?;
+    writer.finish().and_then(|mut f| f.flush()).map_err(io_err)
+}
+
+/// The plaintext of an artifact, decrypted into anonymous memory
+pub struct Decrypted {
+    map: MmapMut,
+    len: usize,
+}
+
+impl Deref for Decrypted {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.map[..self.len]
+    }
+}
+
+/// Decrypts the artifact at `path` with `identity` into anonymous memory, which isn't backed by any file
+pub fn read_decrypted(path: &Path, identity: &Identity) -> Result<Decrypted, EncryptionError> {
+    let display = path.display().to_string();
+    let io_err = |e: io::Error| EncryptionError::Io(path.display().to_string(), e.to_string());
+    let f = File::open(path).map_err(io_err)?;
+    // the ciphertext is the plaintext plus a header and a tag per chunk, its length bounds that of the plaintext
+    let capacity = f.metadata().map_err(io_err)?.len() as usize;
+    let decryptor =
+        match age::Decryptor::new(BufReader::with_capacity(*crate::EZKL_BUF_CAPACITY, f)) {
+            Ok(age::Decryptor::Recipients(decryptor)) => decryptor,
+            Ok(_) => {
+                return Err(EncryptionError::Decrypt(
+                    display,
+                    "it is encrypted with a passphrase".into(),
+                ))
+            }
+            Err(e) => return Err(EncryptionError::Decrypt(display, e.to_string())),
+        };
+    let mut reader = decryptor
+        .decrypt(std::iter::once(&identity.key as &dyn age::Identity))
+        .map_err(|e| match e {
+            age::DecryptError::NoMatchingKeys => EncryptionError::WrongIdentity {
+                path: display.clone(),
+                identity: identity.path.display().to_string(),
+            },
+            e => EncryptionError::Decrypt(display.clone(), e.to_string()),
+        })?;
+
+    let mut map = MmapMut::map_anon(capacity.max(1)).map_err(io_err)?;
+    let mut len = 0;
+    loop {
+        match reader.read(&mut map[len..]) {
+            Ok(0) => break,
+            Ok(n) => len += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(EncryptionError::Decrypt(display, e.to_string())),
+        }
+        if len == map.len() {
+            let mut rest = [0u8; 1];
+            if reader.read(&mut rest).map_err(io_err)? != 0 {
+                return Err(EncryptionError::Decrypt(
+                    display,
+                    "the plaintext is longer than the ciphertext".into(),
+                ));
+            }
+            break;
+        }
+    }
+    debug!("decrypted {} bytes of {}", len, display);
+    Ok(Decrypted { map, len })
+}
+
+/// Saves a [ProvingKey] to `path`, encrypted to `recipient`.
+pub fn save_pk_encrypted<C: SerdeObject + CurveAffine>(
+    path: &PathBuf,
+    pk: &ProvingKey<C>,
+    recipient: &Recipient,
+) -> Result<(), EncryptionError>
+where
+    C::ScalarExt: FromUniformBytes<64> + SerdeObject,
+{
+    debug!("saving encrypted proving key 💾");
+    write_encrypted(path, recipient, |mut writer| {
+        pk.write(&mut writer, serde_format_from_str(&EZKL_KEY_FORMAT))
+    })?;
+    info!("done saving encrypted proving key ✅");
+    Ok(())
+}
+
+/// Loads a [ProvingKey], decrypting it with `identity` if it is encrypted. Both the regular and the memory-mappable
+/// layouts are read from the decrypted memory.
+pub fn load_pk_decrypted<Scheme: CommitmentScheme, C: Circuit<Scheme::Scalar>>(
+    path: PathBuf,
+    params: <C as Circuit<Scheme::Scalar>>::Params,
+    identity: Option<&Identity>,
+) -> Result<ProvingKey<Scheme::Curve>, PfsysError>
+where
+    C: Circuit<Scheme::Scalar>,
+    Scheme::Curve: SerdeObject + CurveAffine,
+    Scheme::Scalar: PrimeField + SerdeObject + FromUniformBytes<64>,
+{
+    let identity = match identity {
+        Some(identity) if is_encrypted(&path) => identity,
+        _ => return super::load_pk::<Scheme, C>(path, params),
+    };
+    let _span = tracing::info_span!("pk_load", format = "encrypted").entered();
+    debug!("decrypting proving key from {:?}", path);
+    let bytes = read_decrypted(&path, identity)?;
+    let pk = if bytes.starts_with(MMAP_PK_MAGIC) {
+        read_mapped_pk::<Scheme, C>(&bytes, params)?
+    } else {
+        ProvingKey::<Scheme::Curve>::read::<_, C>(
+            &mut &bytes[..],
+            serde_format_from_str(&EZKL_KEY_FORMAT),
+            params,
+        )
+        .map_err(|e| PfsysError::LoadPk(format!("{}", e)))?
+    };
+    info!("loaded encrypted proving key ✅");
+    Ok(pk)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// bytes standing in for the weights of a model, the little endian bytes of an f32 pattern
+    fn weights() -> Vec<u8> {
+        (0..4096)
+            .flat_map(|i| (0.123f32 * i as f32).to_le_bytes())
+            .collect()
+    }
+
+    fn encrypt(dir: &TempDir, recipient: &Recipient, plaintext: &[u8]) -> PathBuf {
+        let path = dir.path().join("artifact.age");
+        write_encrypted(&path, recipient, |w| w.write_all(plaintext)).unwrap();
+        path
+    }
+
+    #[test]
+    fn encrypted_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let identity = Identity::generate(&dir.path().join("key.txt")).unwrap();
+        let path = encrypt(&dir, &identity.to_public(), &weights());
+
+        assert!(is_encrypted(&path));
+        assert!(ensure_plaintext(&path).is_err());
+        let decrypted = read_decrypted(&path, &identity).unwrap();
+        assert_eq!(&decrypted[..], &weights()[..]);
+
+        // an empty plaintext round trips too
+        let empty = encrypt(&dir, &identity.to_public(), &[]);
+        assert!(read_decrypted(&empty, &identity).unwrap().is_empty());
+    }
+
+    #[test]
+    fn encrypted_artifact_has_no_plaintext() {
+        let dir = TempDir::new().unwrap();
+        let identity = Identity::generate(&dir.path().join("key.txt")).unwrap();
+        let path = encrypt(&dir, &identity.to_public(), &weights());
+
+        let ciphertext = std::fs::read(&path).unwrap();
+        assert!(ciphertext.starts_with(AGE_HEADER));
+        for weight in weights().chunks(8).filter(|w| w.iter().any(|b| *b != 0)) {
+            assert!(!ciphertext.windows(weight.len()).any(|w| w == weight));
+        }
+    }
+
+    #[test]
+    fn wrong_identity_is_rejected() {
+        let dir = TempDir::new().unwrap();
+        let identity = Identity::generate(&dir.path().join("key.txt")).unwrap();
+        let other = Identity::generate(&dir.path().join("other.txt")).unwrap();
+        let path = encrypt(&dir, &identity.to_public(), &weights());
+
+        let err = read_decrypted(&path, &other).err().unwrap();
+        assert!(matches!(err, EncryptionError::WrongIdentity { .. }));
+        assert!(err.to_string().contains("other.txt"));
+    }
+
+    #[test]
+    fn recipients_and_identities_are_parsed() {
+        let dir = TempDir::new().unwrap();
+        let key = dir.path().join("key.txt");
+        let identity = Identity::generate(&key).unwrap();
+        let public = identity.to_public().to_string();
+        let file = dir.path().join("recipient.txt");
+        std::fs::write(&file, format!("# created by age-keygen -y\n{}\n", public)).unwrap();
+
+        assert_eq!(Recipient::parse(&public).unwrap().to_string(), public);
+        let parsed = Recipient::parse(file.to_str().unwrap()).unwrap();
+        assert_eq!(parsed.to_string(), public);
+        assert!(Recipient::parse("age1notakey").is_err());
+        assert_eq!(
+            Identity::from_file(&key).unwrap().to_public().to_string(),
+            public
+        );
+        assert!(matches!(
+            Identity::from_file(&file),
+            Err(EncryptionError::InvalidIdentity(..))
+        ));
+    }
+}
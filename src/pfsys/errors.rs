@@ -12,16 +12,49 @@ pub enum PfsysError {
     /// Halo2 error
     #[error("[halo2] {0}")]
     Halo2Error(#[from] halo2_proofs::plonk::Error),
+    /// The layout of the model failed while halo2 synthesized the circuit
+    #[error("[graph] {0}")]
+    LayoutError(#[from] crate::graph::errors::GraphError),
     /// Failed to write point to transcript
     #[error("failed to write point to transcript: {0}")]
     WritePoint(String),
+    /// Failed to read a point from the transcript of a proof
+    #[error("failed to read point from transcript: {0}")]
+    ReadPoint(String),
     /// Invalid commitment scheme
     #[error("invalid commitment scheme")]
     InvalidCommitmentScheme,
     /// Failed to load vk from file
     #[error("failed to load vk from file: {0}")]
     LoadVk(String),
+    /// Failed to read the srs
+    #[error("failed to load the srs: {0}")]
+    LoadSrs(String),
+    /// Failed to read the settings of the circuit
+    #[error("failed to load the settings: {0}")]
+    LoadSettings(String),
+    /// The proof has a different number of instances than its circuit
+    #[error("the proof has {actual} instances but the settings expect {expected}")]
+    InstanceCount {
+        /// the number of instances of the circuit
+        expected: usize,
+        /// the number of instances passed with the proof
+        actual: usize,
+    },
     /// Failed to load pk from file
     #[error("failed to load pk from file: {0}")]
     LoadPk(String),
+    /// Failed to map or parse a memory-mappable pk
+    #[error("failed to map pk: {0}")]
+    MmapPk(String),
+    /// The proof was generated with a different transcript than the one expected
+    #[error("proof was generated with the {0} transcript but {1} was expected")]
+    TranscriptMismatch(String, String),
+    /// The proof was cancelled through its progress observer
+    #[error("proving was cancelled")]
+    Cancelled,
+    /// An encrypted artifact can't be decrypted, see [crate::pfsys::encryption]
+    #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+    #[error("[encryption] {0}")]
+    Encryption(#[from] crate::pfsys::encryption::EncryptionError),
 }
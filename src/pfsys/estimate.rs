@@ -0,0 +1,623 @@
+use crate::circuit::CheckMode;
+use crate::pfsys::{create_keys, create_proof_circuit, srs::gen_srs, TranscriptType};
+use crate::Commitments;
+use halo2_proofs::circuit::{Layouter, SimpleFloorPlanner, Value};
+use halo2_proofs::plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Fixed, Selector};
+use halo2_proofs::poly::kzg::commitment::KZGCommitmentScheme;
+use halo2_proofs::poly::kzg::multiopen::{ProverSHPLONK, VerifierSHPLONK};
+use halo2_proofs::poly::kzg::strategy::SingleStrategy;
+use halo2_proofs::poly::Rotation;
+use halo2curves::bn256::{Bn256, Fr};
+use halo2curves::ff::Field;
+use instant::Instant;
+use log::{debug, info};
+use serde::{Deserialize, Serialize};
+use snark_verifier::system::halo2::transcript::evm::EvmTranscript;
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Size in bytes of a compressed commitment in the transcript
+const POINT_BYTES: usize = 32;
+/// Size in bytes of a scalar in the transcript
+const SCALAR_BYTES: usize = 32;
+
+/// Base cost of an EVM transaction
+const GAS_TX_BASE: u64 = 21_000;
+/// Cost of a calldata byte (assumes non-zero bytes, which is the common case for proofs)
+const GAS_CALLDATA_BYTE: u64 = 16;
+/// Cost of the 2-pairing check performed by the KZG verifier
+const GAS_PAIRING: u64 = 45_000 + 2 * 34_000;
+/// Cost of one term of the verifier msm (an ecMul and an ecAdd)
+const GAS_MSM_TERM: u64 = 6_000 + 150;
+/// Cost of the field arithmetic attributable to a single evaluation in the proof
+const GAS_PER_EVALUATION: u64 = 400;
+/// Cost of evaluating the lagrange basis for a single public instance
+const GAS_PER_INSTANCE: u64 = 350;
+
+/// Size in bytes of a field element of a polynomial held by the prover
+const FIELD_BYTES: f64 = 32.0;
+/// Size in bytes of a point of the SRS, which holds the powers of tau and their lagrange basis
+const SRS_POINT_BYTES: f64 = 64.0;
+/// Resident memory of the process before keygen or proving, for the binary, the compiled model and the tables
+/// (fitted on the bundled examples)
+const BASE_MEMORY_BYTES: f64 = (96 << 20) as f64;
+/// Multiplier of the polynomials held at once for the buffers of keygen and the prover beyond them (fitted on the
+/// bundled examples)
+const MEMORY_OVERHEAD: f64 = 1.5;
+
+/// logrows of the circuit used for the proving time micro-benchmark
+const CALIBRATION_LOGROWS: u32 = 10;
+/// name of the file the micro-benchmark result is cached to
+const CALIBRATION_FILE: &str = "estimate_calibration.json";
+
+/// The shape of a circuit as seen by the prover, derived from its [ConstraintSystem]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct CircuitShape {
+    /// log2 of the number of rows
+    pub logrows: u32,
+    /// the degree of the constraint system
+    pub degree: usize,
+    /// number of advice columns
+    pub num_advice_columns: usize,
+    /// number of fixed columns
+    pub num_fixed_columns: usize,
+    /// number of selectors (these become fixed columns at setup)
+    pub num_selectors: usize,
+    /// number of instance columns
+    pub num_instance_columns: usize,
+    /// number of columns participating in the permutation argument
+    pub num_permutation_columns: usize,
+    /// number of lookup arguments
+    pub num_lookups: usize,
+    /// number of shuffle arguments
+    pub num_shuffles: usize,
+    /// number of advice queries
+    pub num_advice_queries: usize,
+    /// number of fixed queries
+    pub num_fixed_queries: usize,
+    /// number of instance queries
+    pub num_instance_queries: usize,
+    /// number of distinct rotations queried across all columns
+    pub num_rotations: usize,
+}
+
+impl CircuitShape {
+    /// Derive the shape from a configured constraint system
+    pub fn from_cs<F: Field>(cs: &ConstraintSystem<F>, logrows: u32) -> Self {
+        let rotations = cs
+            .advice_queries()
+            .iter()
+            .map(|(_, r)| r.0)
+            .chain(cs.fixed_queries().iter().map(|(_, r)| r.0))
+            .chain(cs.instance_queries().iter().map(|(_, r)| r.0))
+            .collect::<HashSet<_>>();
+
+        CircuitShape {
+            logrows,
+            degree: cs.degree(),
+            num_advice_columns: cs.num_advice_columns(),
+            num_fixed_columns: cs.num_fixed_columns(),
+            num_selectors: cs.num_selectors(),
+            num_instance_columns: cs.num_instance_columns(),
+            num_permutation_columns: cs.permutation().get_columns().len(),
+            num_lookups: cs.lookups().len(),
+            num_shuffles: cs.shuffles().len(),
+            num_advice_queries: cs.advice_queries().len(),
+            num_fixed_queries: cs.fixed_queries().len(),
+            num_instance_queries: cs.instance_queries().len(),
+            num_rotations: rotations.len(),
+        }
+    }
+
+    /// number of permutation product polynomials, columns are chunked by `degree - 2`
+    fn num_permutation_products(&self) -> usize {
+        if self.num_permutation_columns == 0 {
+            return 0;
+        }
+        let chunk_len = self.degree.saturating_sub(2).max(1);
+        self.num_permutation_columns.div_ceil(chunk_len)
+    }
+
+    /// number of pieces the quotient polynomial is split into
+    fn num_quotient_pieces(&self) -> usize {
+        self.degree.saturating_sub(1).max(1)
+    }
+
+    /// commitments per lookup argument and evaluations per lookup argument
+    fn lookup_cost(&self) -> (usize, usize) {
+        #[cfg(feature = "mv-lookup")]
+        {
+            // multiplicities and the log-derivative accumulator, phi is opened at x and wx
+            (2, 3)
+        }
+        #[cfg(not(feature = "mv-lookup"))]
+        {
+            // permuted input, permuted table and the grand product
+            (3, 5)
+        }
+    }
+
+    /// Number of commitments written to the transcript, excluding the opening argument
+    pub fn num_commitments(&self) -> usize {
+        let (lookup_commitments, _) = self.lookup_cost();
+        self.num_advice_columns
+            + self.num_lookups * lookup_commitments
+            + self.num_shuffles
+            + self.num_permutation_products()
+            // random polynomial of the vanishing argument
+            + 1
+            + self.num_quotient_pieces()
+    }
+
+    /// Number of evaluations written to the transcript, excluding the opening argument
+    pub fn num_evaluations(&self, commitment: Commitments) -> usize {
+        let (_, lookup_evals) = self.lookup_cost();
+        let products = self.num_permutation_products();
+        let instance_evals = match commitment {
+            // the KZG provers do not open the instance columns
+            Commitments::KZG => 0,
+            Commitments::IPA => self.num_instance_queries,
+        };
+        self.num_advice_queries
+            + instance_evals
+            + self.num_fixed_queries
+            // selectors are queried at the current row once converted to fixed columns
+            + self.num_selectors
+            // random polynomial
+            + 1
+            + self.num_permutation_columns
+            // z(x), z(wx) per product and z(w^last x) for all but the last product
+            + (3 * products).saturating_sub(1)
+            + self.num_lookups * lookup_evals
+            + self.num_shuffles * 2
+    }
+
+    /// Size in bytes of the multi-opening argument
+    fn opening_bytes(&self, commitment: Commitments) -> usize {
+        match commitment {
+            // SHPLONK writes two commitments
+            Commitments::KZG => 2 * POINT_BYTES,
+            Commitments::IPA => {
+                // q' commitment, one evaluation per point set, then s(X), the L/R rounds and two scalars
+                let point_sets = self.num_rotations.max(1) + 1;
+                (2 + 2 * self.logrows as usize) * POINT_BYTES + (point_sets + 2) * SCALAR_BYTES
+            }
+        }
+    }
+
+    /// Size in bytes of a proof for this shape
+    pub fn proof_size(&self, commitment: Commitments) -> usize {
+        self.num_commitments() * POINT_BYTES
+            + self.num_evaluations(commitment) * SCALAR_BYTES
+            + self.opening_bytes(commitment)
+    }
+
+    /// Polynomials of the proving key: the fixed columns, the selectors (as fixed columns without compression) and
+    /// the permutation
+    fn num_pk_polys(&self) -> usize {
+        self.num_fixed_columns + self.num_selectors + self.num_permutation_columns
+    }
+
+    /// Polynomials committed to by the prover
+    fn num_prover_polys(&self) -> usize {
+        let (lookup_commitments, _) = self.lookup_cost();
+        self.num_advice_columns
+            + self.num_lookups * lookup_commitments
+            + self.num_shuffles
+            + self.num_permutation_products()
+    }
+
+    /// Resident bytes of a polynomial, held in its values, its coefficients and over the extended domain
+    fn poly_bytes(&self) -> f64 {
+        let n = (1u64 << self.logrows) as f64;
+        let extended = n * self.num_quotient_pieces().next_power_of_two() as f64;
+        FIELD_BYTES * (2.0 * n + extended)
+    }
+
+    /// Predicted peak resident memory of `phase` in bytes, with the SRS loaded
+    pub fn memory(&self, phase: MemoryPhase) -> u64 {
+        let n = (1u64 << self.logrows) as f64;
+        let srs = 2.0 * n * SRS_POINT_BYTES;
+        // the lagrange polynomials of the first, last and usable rows over the extended domain
+        let polys = match phase {
+            MemoryPhase::Keygen => self.num_pk_polys() + 3,
+            // the quotient and the random polynomial of the vanishing argument
+            MemoryPhase::Prove => self.num_pk_polys() + 3 + self.num_prover_polys() + 2,
+        };
+        (BASE_MEMORY_BYTES + srs + MEMORY_OVERHEAD * polys as f64 * self.poly_bytes()) as u64
+    }
+
+    /// Abstract units of proving work: every committed polynomial costs an msm over `n` rows
+    /// and every polynomial in the quotient costs a pass over the extended domain
+    pub fn work_units(&self) -> f64 {
+        let n = (1u64 << self.logrows) as f64;
+        let extended = self.num_quotient_pieces().next_power_of_two() as f64;
+        let quotient_polys = self.num_advice_columns
+            + self.num_fixed_columns
+            + self.num_selectors
+            + self.num_lookups
+            + self.num_shuffles
+            + self.num_permutation_products();
+        n * (self.num_commitments() as f64 + extended * quotient_polys as f64)
+    }
+}
+
+/// Estimated proof size and costs for a circuit
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct ProofEstimate {
+    /// the shape the estimate was derived from
+    pub shape: CircuitShape,
+    /// the commitment scheme
+    pub commitment: Commitments,
+    /// total number of public instances
+    pub num_instances: usize,
+    /// the size of the proof in bytes
+    pub proof_size_bytes: usize,
+    /// estimated proving time in seconds
+    pub proving_time_secs: f64,
+    /// estimated EVM verification gas, only meaningful for KZG
+    pub verifier_gas: Option<u64>,
+    /// rows taken by the sha256 hashing module, which dominate the circuit when it is used
+    #[serde(default)]
+    pub sha256_rows: usize,
+    /// predicted peak resident memory of keygen in bytes
+    #[serde(default)]
+    pub keygen_memory_bytes: u64,
+    /// predicted peak resident memory of proving in bytes
+    #[serde(default)]
+    pub prove_memory_bytes: u64,
+}
+
+impl ProofEstimate {
+    /// Estimate the proof for a shape using the calibrated seconds per unit of work
+    pub fn new(
+        shape: CircuitShape,
+        commitment: Commitments,
+        num_instances: usize,
+        secs_per_unit: f64,
+    ) -> Self {
+        let proof_size_bytes = shape.proof_size(commitment);
+        let proving_time_secs = shape.work_units() * secs_per_unit;
+        let verifier_gas = match commitment {
+            Commitments::KZG => Some(estimate_verifier_gas(
+                &shape,
+                proof_size_bytes,
+                num_instances,
+            )),
+            Commitments::IPA => None,
+        };
+        ProofEstimate {
+            keygen_memory_bytes: shape.memory(MemoryPhase::Keygen),
+            prove_memory_bytes: shape.memory(MemoryPhase::Prove),
+            shape,
+            commitment,
+            num_instances,
+            proof_size_bytes,
+            proving_time_secs,
+            verifier_gas,
+            sha256_rows: 0,
+        }
+    }
+}
+
+/// A phase whose peak memory is predicted from the shape of the circuit
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MemoryPhase {
+    /// generating the proving and verifying keys
+    Keygen,
+    /// creating a proof, with the proving key loaded
+    Prove,
+}
+
+impl std::fmt::Display for MemoryPhase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MemoryPhase::Keygen => write!(f, "keygen"),
+            MemoryPhase::Prove => write!(f, "proving"),
+        }
+    }
+}
+
+/// Formats bytes in GiB
+fn gib(bytes: f64) -> String {
+    format!("{:.1} GiB", bytes / (1u64 << 30) as f64)
+}
+
+/// Warns when `phase` is predicted to need more than the `available` memory, suggesting the changes to the settings
+/// that lower it: the fewest logrows fitting the model (`fitting_logrows`), fewer columns, and for proving a
+/// memory-mapped proving key. None if the phase fits.
+pub fn plan_memory(
+    shape: &CircuitShape,
+    phase: MemoryPhase,
+    fitting_logrows: u32,
+    available: u64,
+) -> Option<String> {
+    let predicted = shape.memory(phase);
+    if predicted <= available {
+        return None;
+    }
+    let mut suggestions = vec![];
+    if fitting_logrows < shape.logrows {
+        let smaller = CircuitShape {
+            logrows: fitting_logrows,
+            ..shape.clone()
+        };
+        suggestions.push(format!(
+            "regenerate the settings with --logrows {} which the model fits in (predicted {})",
+            fitting_logrows,
+            gib(smaller.memory(phase) as f64)
+        ));
+    }
+    if phase == MemoryPhase::Prove && shape.num_advice_columns > 1 {
+        suggestions.push(format!(
+            "lay the model out on fewer columns with a lower --num-inner-cols, its {} advice columns hold {}",
+            shape.num_advice_columns,
+            gib(MEMORY_OVERHEAD * shape.num_advice_columns as f64 * shape.poly_bytes())
+        ));
+    }
+    if shape.num_lookups > 0 {
+        let (lookup_commitments, _) = shape.lookup_cost();
+        let polys = match phase {
+            MemoryPhase::Keygen => 0,
+            MemoryPhase::Prove => shape.num_lookups * lookup_commitments,
+        };
+        suggestions.push(format!(
+            "narrow the --lookup-range such that the tables take fewer rows or columns ({} lookups{})",
+            shape.num_lookups,
+            match polys {
+                0 => String::new(),
+                _ => format!(
+                    " holding {}",
+                    gib(MEMORY_OVERHEAD * polys as f64 * shape.poly_bytes())
+                ),
+            }
+        ));
+    }
+    if phase == MemoryPhase::Prove {
+        suggestions.push(
+            "convert the proving key with convert-pk such that it is memory-mapped rather than resident"
+                .to_string(),
+        );
+    }
+    Some(format!(
+        "{} is predicted to need {} but {} is available, to lower it: {}",
+        phase,
+        gib(predicted as f64),
+        gib(available as f64),
+        suggestions.join("; ")
+    ))
+}
+
+/// Estimate the gas used to verify a KZG proof on chain
+fn estimate_verifier_gas(shape: &CircuitShape, proof_size: usize, num_instances: usize) -> u64 {
+    let calldata_bytes = proof_size + num_instances * SCALAR_BYTES;
+    // every proof commitment plus the fixed and permutation commitments of the vk enter the msm
+    let msm_terms = shape.num_commitments()
+        + shape.num_fixed_columns
+        + shape.num_selectors
+        + shape.num_permutation_columns
+        + 2;
+    GAS_TX_BASE
+        + GAS_CALLDATA_BYTE * calldata_bytes as u64
+        + GAS_PAIRING
+        + GAS_MSM_TERM * msm_terms as u64
+        + GAS_PER_EVALUATION * shape.num_evaluations(Commitments::KZG) as u64
+        + GAS_PER_INSTANCE * num_instances as u64
+}
+
+/// The cached result of the proving time micro-benchmark
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Calibration {
+    secs_per_unit: f64,
+}
+
+#[derive(Clone, Default)]
+struct CalibrationCircuit;
+
+impl Circuit<Fr> for CalibrationCircuit {
+    type Config = ([Column<Advice>; 3], Column<Fixed>, Selector);
+    type FloorPlanner = SimpleFloorPlanner;
+    type Params = ();
+
+    fn without_witnesses(&self) -> Self {
+        Self
+    }
+
+    fn configure(cs: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let advices = [cs.advice_column(), cs.advice_column(), cs.advice_column()];
+        let fixed = cs.fixed_column();
+        let s = cs.selector();
+        for a in advices {
+            cs.enable_equality(a);
+        }
+        cs.create_gate("c = a * b + k", |meta| {
+            let s = meta.query_selector(s);
+            let a = meta.query_advice(advices[0], Rotation::cur());
+            let b = meta.query_advice(advices[1], Rotation::cur());
+            let c = meta.query_advice(advices[2], Rotation::cur());
+            let k = meta.query_fixed(fixed, Rotation::cur());
+            vec![s * (a * b + k - c)]
+        });
+        (advices, fixed, s)
+    }
+
+    fn synthesize(
+        &self,
+        (advices, fixed, s): Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        // leave room for the blinding rows
+        let rows = (1 << CALIBRATION_LOGROWS) - 16;
+        layouter.assign_region(
+            || "calibration",
+            |mut region| {
+                for row in 0..rows {
+                    let (a, b, k) = (Fr::from(row as u64), Fr::from(3), Fr::from(7));
+                    s.enable(&mut region, row)?;
+                    region.assign_advice(|| "a", advices[0], row, || Value::known(a))?;
+                    region.assign_advice(|| "b", advices[1], row, || Value::known(b))?;
+                    region.assign_advice(|| "c", advices[2], row, || Value::known(a * b + k))?;
+                    region.assign_fixed(|| "k", fixed, row, || Value::known(k))?;
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+/// Run the proving time micro-benchmark, returns the seconds per unit of work
+fn run_calibration() -> Result<f64, crate::EZKLError> {
+    info!("calibrating the proving cost model, this only runs once...");
+    let params = gen_srs::<KZGCommitmentScheme<Bn256>>(CALIBRATION_LOGROWS);
+    let circuit = CalibrationCircuit;
+    let pk = create_keys::<KZGCommitmentScheme<Bn256>, _>(&circuit, &params, true)?;
+
+    let mut cs = ConstraintSystem::<Fr>::default();
+    CalibrationCircuit::configure(&mut cs);
+    let shape = CircuitShape::from_cs(&cs, CALIBRATION_LOGROWS);
+
+    let now = Instant::now();
+    create_proof_circuit::<
+        KZGCommitmentScheme<Bn256>,
+        _,
+        ProverSHPLONK<_>,
+        VerifierSHPLONK<_>,
+        SingleStrategy<_>,
+        _,
+        EvmTranscript<_, _, _, _>,
+        EvmTranscript<_, _, _, _>,
+    >(
+        circuit,
+        vec![],
+        &params,
+        &pk,
+        CheckMode::UNSAFE,
+        Commitments::KZG,
+        TranscriptType::EVM,
+        None,
+        None,
+    )?;
+    let elapsed = now.elapsed().as_secs_f64();
+    debug!("calibration proof took {}s", elapsed);
+
+    Ok(elapsed / shape.work_units())
+}
+
+/// Load the calibrated seconds per unit of work from `dir`, running and caching the
+/// micro-benchmark if it has not been run on this machine yet
+pub fn load_or_calibrate(dir: &Path) -> Result<f64, crate::EZKLError> {
+    let path = dir.join(CALIBRATION_FILE);
+    if let Ok(contents) = std::fs::read_to_string(&path) {
+        if let Ok(calibration) = serde_json::from_str::<Calibration>(&contents) {
+            return Ok(calibration.secs_per_unit);
+        }
+    }
+
+    let secs_per_unit = run_calibration()?;
+    std::fs::create_dir_all(dir)?;
+    std::fs::write(
+        &path,
+        serde_json::to_string(&Calibration { secs_per_unit })?,
+    )?;
+    Ok(secs_per_unit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimated_proof_size_matches_actual() {
+        let params = gen_srs::<KZGCommitmentScheme<Bn256>>(CALIBRATION_LOGROWS);
+        let pk = create_keys::<KZGCommitmentScheme<Bn256>, _>(&CalibrationCircuit, &params, true)
+            .unwrap();
+        let snark = create_proof_circuit::<
+            KZGCommitmentScheme<Bn256>,
+            _,
+            ProverSHPLONK<_>,
+            VerifierSHPLONK<_>,
+            SingleStrategy<_>,
+            _,
+            EvmTranscript<_, _, _, _>,
+            EvmTranscript<_, _, _, _>,
+        >(
+            CalibrationCircuit,
+            vec![],
+            &params,
+            &pk,
+            CheckMode::SAFE,
+            Commitments::KZG,
+            TranscriptType::EVM,
+            None,
+            None,
+        )
+        .unwrap();
+
+        let mut cs = ConstraintSystem::<Fr>::default();
+        CalibrationCircuit::configure(&mut cs);
+        let shape = CircuitShape::from_cs(&cs, CALIBRATION_LOGROWS);
+        let estimated = shape.proof_size(Commitments::KZG) as f64;
+        let actual = snark.proof.len() as f64;
+        assert!((estimated - actual).abs() / actual < 0.2);
+    }
+
+    #[test]
+    fn test_estimate_scales_with_rows() {
+        let small = CircuitShape {
+            logrows: 10,
+            degree: 4,
+            num_advice_columns: 3,
+            num_permutation_columns: 3,
+            ..Default::default()
+        };
+        let large = CircuitShape {
+            logrows: 11,
+            ..small.clone()
+        };
+        let small_estimate = ProofEstimate::new(small, Commitments::KZG, 1, 1e-6);
+        let large_estimate = ProofEstimate::new(large, Commitments::KZG, 1, 1e-6);
+        // proof size does not depend on the number of rows for KZG, proving time does
+        assert_eq!(
+            small_estimate.proof_size_bytes,
+            large_estimate.proof_size_bytes
+        );
+        assert!(
+            (large_estimate.proving_time_secs / small_estimate.proving_time_secs - 2.0).abs()
+                < 1e-9
+        );
+        assert!(small_estimate.verifier_gas.unwrap() > GAS_TX_BASE + GAS_PAIRING);
+    }
+
+    #[test]
+    fn test_memory_plan() {
+        let shape = CircuitShape {
+            logrows: 20,
+            degree: 5,
+            num_advice_columns: 8,
+            num_fixed_columns: 4,
+            num_permutation_columns: 9,
+            num_lookups: 2,
+            ..Default::default()
+        };
+        let keygen = shape.memory(MemoryPhase::Keygen);
+        let prove = shape.memory(MemoryPhase::Prove);
+        assert!(keygen > BASE_MEMORY_BYTES as u64 && prove > keygen);
+        // past the base memory, a row more doubles the memory
+        let doubled = CircuitShape {
+            logrows: 21,
+            ..shape.clone()
+        }
+        .memory(MemoryPhase::Prove);
+        let ratio = (doubled as f64 - BASE_MEMORY_BYTES) / (prove as f64 - BASE_MEMORY_BYTES);
+        assert!((ratio - 2.0).abs() < 1e-6);
+
+        assert_eq!(plan_memory(&shape, MemoryPhase::Prove, 18, prove), None);
+        let warning = plan_memory(&shape, MemoryPhase::Prove, 18, prove / 2).unwrap();
+        assert!(warning.contains("--logrows 18"));
+        assert!(warning.contains("--num-inner-cols"));
+        assert!(warning.contains("convert-pk"));
+        // keygen doesn't hold the advice, nor does the model fit fewer rows
+        let warning = plan_memory(&shape, MemoryPhase::Keygen, 20, keygen / 2).unwrap();
+        assert!(!warning.contains("--logrows") && !warning.contains("--num-inner-cols"));
+    }
+}
@@ -1,6 +1,6 @@
 #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
 use crate::graph::CircuitSize;
-use crate::pfsys::{Snark, SnarkWitness};
+use crate::pfsys::{Snark, SnarkWitness, TranscriptType};
 #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
 use colored_json::ToColoredJson;
 use halo2_proofs::circuit::AssignedCell;
@@ -25,7 +25,7 @@ use log::debug;
 use log::trace;
 use rand::rngs::OsRng;
 use snark_verifier::loader::native::NativeLoader;
-use snark_verifier::loader::EcPointLoader;
+use snark_verifier::loader::{EcPointLoader, ScalarLoader};
 use snark_verifier::{
     loader,
     pcs::{
@@ -76,11 +76,25 @@ pub enum AggregationError {
     /// proof creation errors
     #[error("Failed to create proof")]
     ProofCreate,
+    /// only proofs with a poseidon transcript can be verified inside the aggregation circuit
+    #[error("cannot aggregate a proof generated with the {0} transcript, re-prove it with --transcript=poseidon")]
+    UnsupportedTranscript(TranscriptType),
+    /// the snark protocol does not commit to a verifying key
+    #[error("snark protocol is missing the verifying key hash (transcript initial state)")]
+    MissingVkHash,
+    /// an instance link points past the instances of the aggregated snarks
+    #[error("the linked instance {1} of snark {0} doesn't exist")]
+    InvalidInstanceLink(usize, usize),
 }
 
+/// An instance of an aggregated snark, as the index of the snark and of the instance among its instances
+pub type SnarkInstance = (usize, usize);
+
 type AggregationResult<'a> = (
     // accumulator
     KzgAccumulator<G1Affine, Rc<Halo2Loader<'a>>>,
+    // the verifying key hash of each snark
+    Vec<AssignedCell<Fr, Fr>>,
     // the set of assigned cells
     Vec<Vec<AssignedCell<Fr, Fr>>>,
 );
@@ -97,13 +111,14 @@ type LoadedProof<'a> = verifier::plonk::PlonkProof<
     KzgAs<Bn256, Bdfg21>,
 >;
 
-/// Aggregate one or more application snarks of the same shape into a KzgAccumulator
+/// Aggregate one or more application snarks, each carrying its own protocol (and so its own shape and logrows), into a KzgAccumulator
 pub fn aggregate<'a>(
     svk: &Svk,
     loader: &Rc<Halo2Loader<'a>>,
     snarks: &[SnarkWitness<Fr, G1Affine>],
     as_proof: Value<&'_ [u8]>,
     split_proofs: bool,
+    instance_links: &[(SnarkInstance, SnarkInstance)],
 ) -> Result<AggregationResult<'a>, plonk::Error> {
     let assign_instances = |instances: &[Vec<Value<Fr>>]| {
         instances
@@ -118,12 +133,22 @@ pub fn aggregate<'a>(
     };
 
     let mut accumulators = vec![];
+    let mut vk_hashes = vec![];
     let mut snark_instances = vec![];
     let mut proofs: Vec<LoadedProof<'_>> = vec![];
+    let mut loaded_instances = vec![];
 
     for snark in snarks.iter() {
         let protocol = snark.protocol.as_ref().unwrap().loaded(loader);
         let instances = assign_instances(&snark.instances);
+        loaded_instances.push(instances.iter().flatten().cloned().collect_vec());
+
+        // the vk hash seeds the in-circuit transcript, so exposing this cell binds the snark's vk
+        let vk_hash = protocol
+            .transcript_initial_state
+            .clone()
+            .ok_or(plonk::Error::Synthesis)?;
+        vk_hashes.push(vk_hash.into_assigned());
 
         // get assigned cells
         snark_instances.extend(instances.iter().map(|instance| {
@@ -174,12 +199,31 @@ pub fn aggregate<'a>(
             .map_err(|_| plonk::Error::Synthesis)?;
         accumulators.append(&mut accum);
     }
+    // the instances of linked snarks hold the same values, as the digests of a tensor one passes to the next
+    for ((snark, instance), (linked_snark, linked_instance)) in instance_links {
+        let (Some(lhs), Some(rhs)) = (
+            loaded_instances.get(*snark).and_then(|i| i.get(*instance)),
+            loaded_instances
+                .get(*linked_snark)
+                .and_then(|i| i.get(*linked_instance)),
+        ) else {
+            log::error!("Failed to load linked instances");
+            return Err(plonk::Error::Synthesis);
+        };
+        loader
+            .assert_eq("assert linked instances match", lhs, rhs)
+            .map_err(|e| {
+                log::error!("Failed to match linked instances: {:?}", e);
+                plonk::Error::Synthesis
+            })?;
+    }
+
     let accumulator = {
         let mut transcript = PoseidonTranscript::<Rc<Halo2Loader>, _>::new(loader, as_proof);
         let proof = As::read_proof(&Default::default(), &accumulators, &mut transcript).unwrap();
         As::verify(&Default::default(), &accumulators, &proof).map_err(|_| plonk::Error::Synthesis)
     }?;
-    Ok((accumulator, snark_instances))
+    Ok((accumulator, vk_hashes, snark_instances))
 }
 
 /// The Halo2 Config for the aggregation circuit
@@ -246,9 +290,11 @@ impl AggregationConfig {
 pub struct AggregationCircuit {
     svk: Svk,
     snarks: Vec<SnarkWitness<Fr, G1Affine>>,
+    vk_hashes: Vec<Fr>,
     instances: Vec<Fr>,
     as_proof: Value<Vec<u8>>,
     split_proof: bool,
+    instance_links: Vec<(SnarkInstance, SnarkInstance)>,
 }
 
 impl AggregationCircuit {
@@ -261,8 +307,20 @@ impl AggregationCircuit {
         let snarks = snarks.into_iter().collect_vec();
 
         let mut accumulators = vec![];
+        let mut vk_hashes = vec![];
 
         for snark in snarks.iter() {
+            if snark.transcript_type != TranscriptType::Poseidon {
+                return Err(AggregationError::UnsupportedTranscript(
+                    snark.transcript_type,
+                ));
+            }
+            let vk_hash = snark
+                .protocol
+                .as_ref()
+                .and_then(|protocol| protocol.transcript_initial_state)
+                .ok_or(AggregationError::MissingVkHash)?;
+            vk_hashes.push(vk_hash);
             trace!("Aggregating with snark instances {:?}", snark.instances);
             let mut transcript = PoseidonTranscript::<NativeLoader, _>::new(snark.proof.as_slice());
             let proof = PlonkSuccinctVerifier::read_proof(
@@ -303,12 +361,34 @@ impl AggregationCircuit {
         Ok(Self {
             svk: *svk,
             snarks: snarks.into_iter().map_into().collect(),
+            vk_hashes,
             instances,
             as_proof: Value::known(as_proof),
             split_proof,
+            instance_links: vec![],
         })
     }
 
+    /// Constrains pairs of instances of the aggregated snarks to be equal, binding the snarks of a chain of circuits
+    /// by the digests of the tensors one passes to the next, see [crate::graph::link]
+    pub fn with_instance_links(
+        mut self,
+        instance_links: Vec<(SnarkInstance, SnarkInstance)>,
+    ) -> Result<Self, AggregationError> {
+        for (snark, instance) in instance_links.iter().flat_map(|(a, b)| [a, b]) {
+            let num_instances = self
+                .snarks
+                .get(*snark)
+                .map(|s| s.instances.iter().map(|i| i.len()).sum::<usize>())
+                .unwrap_or_default();
+            if *instance >= num_instances {
+                return Err(AggregationError::InvalidInstanceLink(*snark, *instance));
+            }
+        }
+        self.instance_links = instance_links;
+        Ok(self)
+    }
+
     ///
     pub fn num_limbs() -> usize {
         LIMBS
@@ -324,9 +404,15 @@ impl AggregationCircuit {
     }
 
     /// Number of instance variables for the aggregation circuit, used in generating verifier.
-    pub fn num_instance(orginal_circuit_instances: usize) -> Vec<usize> {
+    /// Laid out as the accumulator limbs, one vk hash per aggregated snark, then the snark instances.
+    pub fn num_instance(orginal_circuit_instances: usize, num_snarks: usize) -> Vec<usize> {
         let accumulation_instances = 4 * LIMBS;
-        vec![accumulation_instances + orginal_circuit_instances]
+        vec![accumulation_instances + num_snarks + orginal_circuit_instances]
+    }
+
+    /// The verifying key hash of each aggregated snark, in aggregation order.
+    pub fn vk_hashes(&self) -> &[Fr] {
+        &self.vk_hashes
     }
 
     /// Instance variables for the aggregation circuit, fed to verifier.
@@ -340,6 +426,7 @@ impl AggregationCircuit {
 
         // reduce from Vec<Vec<Vec<Value<Fr>>>> to Vec<Vec<Value<Fr>>>
         let mut instances: Vec<Fr> = self.instances.clone();
+        instances.extend(self.vk_hashes.iter().cloned());
         for snark_instance in snark_instances.iter_mut() {
             for instance in snark_instance.iter_mut() {
                 let mut felt_evals = vec![];
@@ -371,9 +458,11 @@ impl Circuit<Fr> for AggregationCircuit {
                 .iter()
                 .map(SnarkWitness::without_witnesses)
                 .collect(),
+            vk_hashes: self.vk_hashes.clone(),
             instances: Vec::new(),
             as_proof: Value::unknown(),
             split_proof: self.split_proof,
+            instance_links: self.instance_links.clone(),
         }
     }
 
@@ -395,19 +484,20 @@ impl Circuit<Fr> for AggregationCircuit {
 
         range_chip.load_table(&mut layouter)?;
 
-        let (accumulator_limbs, snark_instances) = layouter.assign_region(
+        let (accumulator_limbs, vk_hashes, snark_instances) = layouter.assign_region(
             || "",
             |region| {
                 let ctx = RegionCtx::new(region, 0);
 
                 let ecc_chip = config.ecc_chip();
                 let loader = Halo2Loader::new(ecc_chip, ctx);
-                let (accumulator, snark_instances) = aggregate(
+                let (accumulator, vk_hashes, snark_instances) = aggregate(
                     &self.svk,
                     &loader,
                     &self.snarks,
                     self.as_proof(),
                     self.split_proof,
+                    &self.instance_links,
                 )?;
 
                 let accumulator_limbs = [accumulator.lhs, accumulator.rhs]
@@ -421,7 +511,7 @@ impl Circuit<Fr> for AggregationCircuit {
                     .into_iter()
                     .flatten();
 
-                Ok((accumulator_limbs, snark_instances))
+                Ok((accumulator_limbs, vk_hashes, snark_instances))
             },
         )?;
 
@@ -431,6 +521,11 @@ impl Circuit<Fr> for AggregationCircuit {
             instance_offset += 1;
         }
 
+        for vk_hash in vk_hashes.into_iter() {
+            main_gate.expose_public(layouter.namespace(|| ""), vk_hash, instance_offset)?;
+            instance_offset += 1;
+        }
+
         for instance in snark_instances.into_iter() {
             for elem in instance.into_iter() {
                 main_gate.expose_public(layouter.namespace(|| ""), elem, instance_offset)?;
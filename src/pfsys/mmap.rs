@@ -0,0 +1,665 @@
+use super::{serde_format_from_str, PfsysError};
+use crate::EZKL_KEY_FORMAT;
+use halo2_proofs::plonk::{Circuit, ProvingKey, VerifyingKey};
+use halo2_proofs::poly::commitment::CommitmentScheme;
+use halo2curves::ff::{FromUniformBytes, PrimeField};
+use halo2curves::serde::SerdeObject;
+use halo2curves::CurveAffine;
+use log::{debug, info};
+use memmap2::{Mmap, MmapOptions};
+use std::fs::File;
+use std::io::{self, BufWriter, Read, Seek, SeekFrom, Write};
+use std::ops::Range;
+use std::path::PathBuf;
+
+/// Magic bytes at the start of a memory-mappable proving key
+pub const MMAP_PK_MAGIC: &[u8; 8] = b"EZKLPKMM";
+
+/// Version of the memory-mappable proving key layout
+pub const MMAP_PK_VERSION: u32 = 2;
+
+/// Alignment of every section in the file, sections never share a page
+pub const MMAP_PK_ALIGNMENT: u64 = 4096;
+
+/// fixed size of the header, the section table follows it
+const HEADER_LEN: usize = 8 + 4 + 4 + 4;
+/// size of a single entry in the section table
+const SECTION_ENTRY_LEN: usize = 4 + 8 + 8;
+/// size of the length prefix of a serialized polynomial, and of a serialized list of polynomials
+const LEN_PREFIX: u64 = 4;
+
+/// The sections stored in a memory-mappable proving key. Laid end to end they are the serialized [ProvingKey].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PkSection {
+    /// The verifying key, allows reading the vk without touching the rest of the file
+    VerifyingKey,
+    /// The evaluation domain data: the Lagrange polynomials of the first, last and active rows over the extended domain
+    Domain,
+    /// The fixed columns: their values, their polynomials and, with `precompute-coset`, their extended cosets
+    FixedColumns,
+    /// The permutation argument: the permutations of the columns and their polynomials
+    Permutations,
+}
+
+impl PkSection {
+    /// All sections, in the order they are stored
+    pub const ALL: [PkSection; 4] = [
+        PkSection::VerifyingKey,
+        PkSection::Domain,
+        PkSection::FixedColumns,
+        PkSection::Permutations,
+    ];
+
+    fn id(&self) -> u32 {
+        match self {
+            PkSection::VerifyingKey => 0,
+            PkSection::Domain => 1,
+            PkSection::FixedColumns => 2,
+            PkSection::Permutations => 3,
+        }
+    }
+}
+
+fn serde_format_to_id(format: halo2_proofs::SerdeFormat) -> u32 {
+    match format {
+        halo2_proofs::SerdeFormat::Processed => 0,
+        halo2_proofs::SerdeFormat::RawBytes => 1,
+        halo2_proofs::SerdeFormat::RawBytesUnchecked => 2,
+    }
+}
+
+fn serde_format_from_id(id: u32) -> Result<halo2_proofs::SerdeFormat, PfsysError> {
+    match id {
+        0 => Ok(halo2_proofs::SerdeFormat::Processed),
+        1 => Ok(halo2_proofs::SerdeFormat::RawBytes),
+        2 => Ok(halo2_proofs::SerdeFormat::RawBytesUnchecked),
+        _ => Err(PfsysError::MmapPk(format!(
+            "unknown serde format id {}",
+            id
+        ))),
+    }
+}
+
+fn align_up(pos: u64) -> u64 {
+    pos.div_ceil(MMAP_PK_ALIGNMENT) * MMAP_PK_ALIGNMENT
+}
+
+fn pad_to_alignment<W: Write + Seek>(writer: &mut W) -> Result<u64, io::Error> {
+    let pos = writer.stream_position()?;
+    let aligned = align_up(pos);
+    writer.write_all(&vec![0u8; (aligned - pos) as usize])?;
+    Ok(aligned)
+}
+
+/// bytes taken by a scalar serialized in `format`
+fn scalar_len<F: PrimeField + SerdeObject>(format: halo2_proofs::SerdeFormat) -> io::Result<u64> {
+    match format {
+        halo2_proofs::SerdeFormat::Processed => Ok(F::Repr::default().as_ref().len() as u64),
+        _ => {
+            let mut bytes = vec![];
+            F::ZERO.write_raw(&mut bytes)?;
+            Ok(bytes.len() as u64)
+        }
+    }
+}
+
+/// Offsets into the serialized [ProvingKey] at which the [PkSection::Domain], [PkSection::FixedColumns] and
+/// [PkSection::Permutations] sections start. They only depend on the size of the circuit: the key is written as its
+/// vk, then three polynomials over the extended domain, then two lists (three with `precompute-coset`) of a
+/// polynomial per fixed column, then the permutation argument.
+fn section_offsets<C: SerdeObject + CurveAffine>(
+    pk: &ProvingKey<C>,
+    format: halo2_proofs::SerdeFormat,
+) -> io::Result<Vec<u64>>
+where
+    C::ScalarExt: FromUniformBytes<64> + SerdeObject,
+{
+    let vk = pk.get_vk();
+    let mut vk_bytes = vec![];
+    vk.write(&mut vk_bytes, format)?;
+
+    let scalar_len = scalar_len::<C::ScalarExt>(format)?;
+    let poly_len = |len: u64| LEN_PREFIX + len * scalar_len;
+    let n = 1u64 << vk.get_domain().k();
+    let extended = vk.get_domain().extended_len() as u64;
+    let num_fixed = vk.cs().num_fixed_columns() as u64;
+
+    let domain_len = 3 * poly_len(extended);
+    let mut fixed_len = 2 * (LEN_PREFIX + num_fixed * poly_len(n));
+    if cfg!(feature = "precompute-coset") {
+        fixed_len += LEN_PREFIX + num_fixed * poly_len(extended);
+    }
+
+    let domain_start = vk_bytes.len() as u64;
+    Ok(vec![
+        domain_start,
+        domain_start + domain_len,
+        domain_start + domain_len + fixed_len,
+    ])
+}
+
+/// Writes the serialized [ProvingKey] through to a file, padding to the next page whenever the key reaches the
+/// start of one of its sections
+struct SectionWriter<'a, W: Write + Seek> {
+    inner: &'a mut W,
+    /// bytes of the key written so far
+    written: u64,
+    /// offsets into the key of the sections left to start
+    offsets: Vec<u64>,
+    /// start within the file of the section being written
+    start: u64,
+    /// byte ranges within the file of the sections written so far
+    sections: Vec<Range<u64>>,
+}
+
+impl<'a, W: Write + Seek> SectionWriter<'a, W> {
+    fn new(inner: &'a mut W, mut offsets: Vec<u64>) -> io::Result<Self> {
+        offsets.reverse();
+        let start = inner.stream_position()?;
+        Ok(Self {
+            inner,
+            written: 0,
+            offsets,
+            start,
+            sections: vec![],
+        })
+    }
+
+    fn start_sections(&mut self) -> io::Result<()> {
+        while self.offsets.last() == Some(&self.written) {
+            self.offsets.pop();
+            let end = self.inner.stream_position()?;
+            self.sections.push(self.start..end);
+            self.start = pad_to_alignment(self.inner)?;
+        }
+        Ok(())
+    }
+
+    fn finish(mut self) -> io::Result<Vec<Range<u64>>> {
+        self.start_sections()?;
+        if !self.offsets.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "the proving key ended before all of its sections",
+            ));
+        }
+        let end = self.inner.stream_position()?;
+        self.sections.push(self.start..end);
+        Ok(self.sections)
+    }
+}
+
+impl<W: Write + Seek> Write for SectionWriter<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.start_sections()?;
+        let len = match self.offsets.last() {
+            Some(offset) => buf.len().min((offset - self.written) as usize),
+            None => buf.len(),
+        };
+        let written = self.inner.write(&buf[..len])?;
+        self.written += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// The header of a memory-mappable proving key
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct MmapPkHeader {
+    /// serialization format used for the sections
+    format: halo2_proofs::SerdeFormat,
+    /// byte ranges of the sections, in the order of [PkSection::ALL]
+    sections: Vec<Range<u64>>,
+}
+
+impl MmapPkHeader {
+    fn write<W: Write>(&self, writer: &mut W) -> Result<(), io::Error> {
+        writer.write_all(MMAP_PK_MAGIC)?;
+        writer.write_all(&MMAP_PK_VERSION.to_le_bytes())?;
+        writer.write_all(&serde_format_to_id(self.format).to_le_bytes())?;
+        writer.write_all(&(self.sections.len() as u32).to_le_bytes())?;
+        for (section, range) in PkSection::ALL.iter().zip(self.sections.iter()) {
+            writer.write_all(&section.id().to_le_bytes())?;
+            writer.write_all(&range.start.to_le_bytes())?;
+            writer.write_all(&(range.end - range.start).to_le_bytes())?;
+        }
+        Ok(())
+    }
+
+    fn read(bytes: &[u8]) -> Result<Self, PfsysError> {
+        let err = |msg: &str| PfsysError::MmapPk(msg.to_string());
+        if bytes.len() < HEADER_LEN || &bytes[..8] != MMAP_PK_MAGIC {
+            return Err(err("not a memory-mappable proving key"));
+        }
+        let read_u32 = |at: usize| u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap());
+        let read_u64 = |at: usize| u64::from_le_bytes(bytes[at..at + 8].try_into().unwrap());
+
+        let version = read_u32(8);
+        if version != MMAP_PK_VERSION {
+            return Err(PfsysError::MmapPk(format!(
+                "unsupported layout version {}",
+                version
+            )));
+        }
+        let format = serde_format_from_id(read_u32(12))?;
+        let num_sections = read_u32(16) as usize;
+        if num_sections != PkSection::ALL.len()
+            || bytes.len() < HEADER_LEN + num_sections * SECTION_ENTRY_LEN
+        {
+            return Err(err("malformed section table"));
+        }
+
+        let mut sections = vec![];
+        for (i, section) in PkSection::ALL.iter().enumerate() {
+            let at = HEADER_LEN + i * SECTION_ENTRY_LEN;
+            if read_u32(at) != section.id() {
+                return Err(err("unexpected section order"));
+            }
+            let start = read_u64(at + 4);
+            let len = read_u64(at + 12);
+            match start.checked_add(len) {
+                Some(end) if start % MMAP_PK_ALIGNMENT == 0 && end <= bytes.len() as u64 => {
+                    sections.push(start..end)
+                }
+                _ => return Err(err("section out of bounds")),
+            }
+        }
+
+        Ok(MmapPkHeader { format, sections })
+    }
+
+    fn section<'a>(&self, bytes: &'a [u8], section: PkSection) -> &'a [u8] {
+        let range = &self.sections[section.id() as usize];
+        &bytes[range.start as usize..range.end as usize]
+    }
+
+    /// Decodes the [ProvingKey] from its sections, read in place one after the other
+    fn read_pk<Scheme: CommitmentScheme, C: Circuit<Scheme::Scalar>>(
+        &self,
+        bytes: &[u8],
+        params: <C as Circuit<Scheme::Scalar>>::Params,
+    ) -> Result<ProvingKey<Scheme::Curve>, PfsysError>
+    where
+        C: Circuit<Scheme::Scalar>,
+        Scheme::Curve: SerdeObject + CurveAffine,
+        Scheme::Scalar: PrimeField + SerdeObject + FromUniformBytes<64>,
+    {
+        let mut reader = self
+            .section(bytes, PkSection::VerifyingKey)
+            .chain(self.section(bytes, PkSection::Domain))
+            .chain(self.section(bytes, PkSection::FixedColumns))
+            .chain(self.section(bytes, PkSection::Permutations));
+        ProvingKey::<Scheme::Curve>::read::<_, C>(&mut reader, self.format, params)
+            .map_err(|e| PfsysError::LoadPk(format!("{}", e)))
+    }
+}
+
+/// Returns true if the file at `path` is a memory-mappable proving key
+pub fn is_mmap_pk(path: &PathBuf) -> bool {
+    let mut magic = [0u8; 8];
+    match File::open(path) {
+        Ok(mut f) => f.read_exact(&mut magic).is_ok() && &magic == MMAP_PK_MAGIC,
+        Err(_) => false,
+    }
+}
+
+/// A proving key in the memory-mappable layout, mapped into memory. Its sections are borrowed straight from the
+/// mapping, the kernel faults their pages in as they are read.
+#[derive(Debug)]
+pub struct MappedPk {
+    mmap: Mmap,
+    header: MmapPkHeader,
+}
+
+impl MappedPk {
+    /// Maps the proving key at `path`, with `prefault` all of its pages are read in upfront
+    #[allow(unsafe_code)]
+    pub fn open(path: &PathBuf, prefault: bool) -> Result<Self, PfsysError> {
+        let f = File::open(path).map_err(|e| PfsysError::MmapPk(format!("{}", e)))?;
+        let mut options = MmapOptions::new();
+        if prefault {
+            options.populate();
+        }
+        // SAFETY: the mapping is read-only, callers must not truncate or rewrite the key file while it is mapped
+        let mmap = unsafe { options.map(&f) }.map_err(|e| PfsysError::MmapPk(format!("{}", e)))?;
+        let header = MmapPkHeader::read(&mmap)?;
+        Ok(Self { mmap, header })
+    }
+
+    /// The bytes of a section, as they are serialized
+    pub fn section(&self, section: PkSection) -> &[u8] {
+        self.header.section(&self.mmap, section)
+    }
+
+    /// Decodes the [ProvingKey] from the mapped sections
+    pub fn read_pk<Scheme: CommitmentScheme, C: Circuit<Scheme::Scalar>>(
+        &self,
+        params: <C as Circuit<Scheme::Scalar>>::Params,
+    ) -> Result<ProvingKey<Scheme::Curve>, PfsysError>
+    where
+        C: Circuit<Scheme::Scalar>,
+        Scheme::Curve: SerdeObject + CurveAffine,
+        Scheme::Scalar: PrimeField + SerdeObject + FromUniformBytes<64>,
+    {
+        self.header.read_pk::<Scheme, C>(&self.mmap, params)
+    }
+
+    /// Decodes the [VerifyingKey] from its section, no other section is touched
+    pub fn read_vk<Scheme: CommitmentScheme, C: Circuit<Scheme::Scalar>>(
+        &self,
+        params: <C as Circuit<Scheme::Scalar>>::Params,
+    ) -> Result<VerifyingKey<Scheme::Curve>, PfsysError>
+    where
+        C: Circuit<Scheme::Scalar>,
+        Scheme::Curve: SerdeObject + CurveAffine,
+        Scheme::Scalar: PrimeField + SerdeObject + FromUniformBytes<64>,
+    {
+        let mut reader = self.section(PkSection::VerifyingKey);
+        VerifyingKey::<Scheme::Curve>::read::<_, C>(&mut reader, self.header.format, params)
+            .map_err(|e| PfsysError::LoadVk(format!("{}", e)))
+    }
+}
+
+/// Saves a [ProvingKey] to `path` in the memory-mappable layout.
+///
+/// The file starts with a header and a section table, then the serialized key follows split into its
+/// [PkSection]s, each starting on a page boundary. The offsets of the sections only depend on the size of the
+/// circuit, and concatenated they are the key in the regular serialized format.
+pub fn save_pk_mmap<C: SerdeObject + CurveAffine>(
+    path: &PathBuf,
+    pk: &ProvingKey<C>,
+) -> Result<(), io::Error>
+where
+    C::ScalarExt: FromUniformBytes<64> + SerdeObject,
+{
+    debug!("saving memory-mappable proving key 💾");
+    let format = serde_format_from_str(&EZKL_KEY_FORMAT);
+    let offsets = section_offsets(pk, format)?;
+    let f = File::create(path)?;
+    let mut writer = BufWriter::with_capacity(*crate::EZKL_BUF_CAPACITY, f);
+
+    // reserve the first page for the header, it is filled in once the sections are written
+    writer.write_all(&vec![0u8; MMAP_PK_ALIGNMENT as usize])?;
+
+    let mut sections = SectionWriter::new(&mut writer, offsets)?;
+    pk.write(&mut sections, format)?;
+    let sections = sections.finish()?;
+
+    let header = MmapPkHeader { format, sections };
+    writer.seek(SeekFrom::Start(0))?;
+    header.write(&mut writer)?;
+    writer.flush()?;
+    info!("done saving memory-mappable proving key ✅");
+    Ok(())
+}
+
+/// Loads a [ProvingKey] saved with [save_pk_mmap] by mapping the file into memory.
+///
+/// The key is decoded from the mapped sections in place, without reading the file into a buffer first. Pages are
+/// faulted in on demand as the key is decoded; set `prefault` to populate the whole mapping upfront for
+/// latency-sensitive runs.
+pub fn load_pk_mmap<Scheme: CommitmentScheme, C: Circuit<Scheme::Scalar>>(
+    path: PathBuf,
+    params: <C as Circuit<Scheme::Scalar>>::Params,
+    prefault: bool,
+) -> Result<ProvingKey<Scheme::Curve>, PfsysError>
+where
+    C: Circuit<Scheme::Scalar>,
+    Scheme::Curve: SerdeObject + CurveAffine,
+    Scheme::Scalar: PrimeField + SerdeObject + FromUniformBytes<64>,
+{
+    debug!(
+        "mapping proving key from {:?} (prefault: {})",
+        path, prefault
+    );
+    let pk = MappedPk::open(&path, prefault)?.read_pk::<Scheme, C>(params)?;
+    info!("loaded memory-mapped proving key ✅");
+    Ok(pk)
+}
+
+/// Decodes the [ProvingKey] from the bytes of a proving key in the memory-mappable layout, wherever they are
+/// mapped from.
+pub(crate) fn read_mapped_pk<Scheme: CommitmentScheme, C: Circuit<Scheme::Scalar>>(
+    bytes: &[u8],
+    params: <C as Circuit<Scheme::Scalar>>::Params,
+) -> Result<ProvingKey<Scheme::Curve>, PfsysError>
+where
+    C: Circuit<Scheme::Scalar>,
+    Scheme::Curve: SerdeObject + CurveAffine,
+    Scheme::Scalar: PrimeField + SerdeObject + FromUniformBytes<64>,
+{
+    MmapPkHeader::read(bytes)?.read_pk::<Scheme, C>(bytes, params)
+}
+
+/// Loads the [VerifyingKey] section of a proving key saved with [save_pk_mmap], only the
+/// pages of the vk section are touched.
+pub fn load_vk_from_mmap_pk<Scheme: CommitmentScheme, C: Circuit<Scheme::Scalar>>(
+    path: PathBuf,
+    params: <C as Circuit<Scheme::Scalar>>::Params,
+) -> Result<VerifyingKey<Scheme::Curve>, PfsysError>
+where
+    C: Circuit<Scheme::Scalar>,
+    Scheme::Curve: SerdeObject + CurveAffine,
+    Scheme::Scalar: PrimeField + SerdeObject + FromUniformBytes<64>,
+{
+    MappedPk::open(&path, false)?.read_vk::<Scheme, C>(params)
+}
+
+/// Converts a proving key in the regular serialized format at `src` to the memory-mappable
+/// layout at `dst`. Keys that are already memory-mappable are rejected.
+pub fn convert_pk_to_mmap<Scheme: CommitmentScheme, C: Circuit<Scheme::Scalar>>(
+    src: PathBuf,
+    dst: &PathBuf,
+    params: <C as Circuit<Scheme::Scalar>>::Params,
+) -> Result<(), PfsysError>
+where
+    C: Circuit<Scheme::Scalar>,
+    Scheme::Curve: SerdeObject + CurveAffine,
+    Scheme::Scalar: PrimeField + SerdeObject + FromUniformBytes<64>,
+{
+    if is_mmap_pk(&src) {
+        return Err(PfsysError::MmapPk(format!(
+            "{:?} is already memory-mappable",
+            src
+        )));
+    }
+    let pk = super::load_pk::<Scheme, C>(src, params)?;
+    save_pk_mmap(dst, &pk).map_err(|e| PfsysError::MmapPk(format!("{}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pfsys::{save_pk, srs};
+    use halo2_proofs::circuit::{Layouter, SimpleFloorPlanner, Value};
+    use halo2_proofs::plonk::{
+        create_proof, keygen_pk, keygen_vk, Advice, Column, ConstraintSystem, Error, Fixed,
+        Selector,
+    };
+    use halo2_proofs::poly::kzg::commitment::KZGCommitmentScheme;
+    use halo2_proofs::poly::kzg::multiopen::ProverSHPLONK;
+    use halo2_proofs::poly::Rotation;
+    use halo2_proofs::transcript::TranscriptWriterBuffer;
+    use halo2curves::bn256::{Bn256, Fr, G1Affine};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use snark_verifier::system::halo2::transcript::evm::EvmTranscript;
+    use tempfile::Builder;
+
+    const K: u32 = 5;
+
+    #[derive(Clone, Default)]
+    struct AffineCircuit {
+        x: Value<Fr>,
+    }
+
+    impl Circuit<Fr> for AffineCircuit {
+        type Config = (Column<Advice>, Column<Advice>, Column<Fixed>, Selector);
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = ();
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<Fr>) -> Self::Config {
+            let a = cs.advice_column();
+            let b = cs.advice_column();
+            let c = cs.fixed_column();
+            let s = cs.selector();
+            cs.enable_equality(a);
+            cs.create_gate("b = a * a + c", |meta| {
+                let s = meta.query_selector(s);
+                let a = meta.query_advice(a, Rotation::cur());
+                let b = meta.query_advice(b, Rotation::cur());
+                let c = meta.query_fixed(c, Rotation::cur());
+                vec![s * (a.clone() * a + c - b)]
+            });
+            (a, b, c, s)
+        }
+
+        fn synthesize(
+            &self,
+            (a, b, c, s): Self::Config,
+            mut layouter: impl Layouter<Fr>,
+        ) -> Result<(), Error> {
+            layouter.assign_region(
+                || "affine",
+                |mut region| {
+                    s.enable(&mut region, 0)?;
+                    let constant = Fr::from(7);
+                    region.assign_fixed(|| "c", c, 0, || Value::known(constant))?;
+                    region.assign_advice(|| "a", a, 0, || self.x)?;
+                    region.assign_advice(
+                        || "b",
+                        b,
+                        0,
+                        || self.x * self.x + Value::known(constant),
+                    )?;
+                    Ok(())
+                },
+            )
+        }
+    }
+
+    fn prove_with(
+        params: &<KZGCommitmentScheme<Bn256> as CommitmentScheme>::ParamsProver,
+        pk: &ProvingKey<G1Affine>,
+    ) -> Vec<u8> {
+        let circuit = AffineCircuit {
+            x: Value::known(Fr::from(3)),
+        };
+        let mut rng = StdRng::from_seed([0u8; 32]);
+        let mut transcript =
+            <EvmTranscript<G1Affine, _, _, _> as TranscriptWriterBuffer<_, _, _>>::init(vec![]);
+        create_proof::<KZGCommitmentScheme<Bn256>, ProverSHPLONK<_>, _, _, _, _>(
+            params,
+            pk,
+            &[circuit],
+            &[&[]],
+            &mut rng,
+            &mut transcript,
+        )
+        .unwrap();
+        transcript.finalize()
+    }
+
+    #[test]
+    fn test_mmap_pk_proves_identically() {
+        let tmp_dir = Builder::new().prefix("mmap_pk").tempdir().unwrap();
+        let eager_path = tmp_dir.path().join("key.pk");
+        let mapped_path = tmp_dir.path().join("key.mmap.pk");
+
+        let params = srs::gen_srs::<KZGCommitmentScheme<Bn256>>(K);
+        let circuit = AffineCircuit::default();
+        let vk = keygen_vk(&params, &circuit).unwrap();
+        let pk = keygen_pk(&params, vk, &circuit).unwrap();
+        save_pk::<G1Affine>(&eager_path, &pk).unwrap();
+
+        // the legacy format converts to the mappable one and is flagged correctly
+        convert_pk_to_mmap::<KZGCommitmentScheme<Bn256>, AffineCircuit>(
+            eager_path.clone(),
+            &mapped_path,
+            (),
+        )
+        .unwrap();
+        assert!(!is_mmap_pk(&eager_path));
+        assert!(is_mmap_pk(&mapped_path));
+
+        let eager =
+            crate::pfsys::load_pk::<KZGCommitmentScheme<Bn256>, AffineCircuit>(eager_path, ())
+                .unwrap();
+        let mapped = load_pk_mmap::<KZGCommitmentScheme<Bn256>, AffineCircuit>(
+            mapped_path.clone(),
+            (),
+            false,
+        )
+        .unwrap();
+        let prefaulted = load_pk_mmap::<KZGCommitmentScheme<Bn256>, AffineCircuit>(
+            mapped_path.clone(),
+            (),
+            true,
+        )
+        .unwrap();
+
+        let eager_proof = prove_with(&params, &eager);
+        assert_eq!(eager_proof, prove_with(&params, &mapped));
+        assert_eq!(eager_proof, prove_with(&params, &prefaulted));
+
+        let vk = load_vk_from_mmap_pk::<KZGCommitmentScheme<Bn256>, AffineCircuit>(mapped_path, ())
+            .unwrap();
+        assert_eq!(vk.transcript_repr(), eager.get_vk().transcript_repr());
+    }
+
+    #[test]
+    fn test_mmap_pk_sections_are_the_serialized_key() {
+        let tmp_dir = Builder::new().prefix("mmap_pk").tempdir().unwrap();
+        let eager_path = tmp_dir.path().join("key.pk");
+        let mapped_path = tmp_dir.path().join("key.mmap.pk");
+
+        let params = srs::gen_srs::<KZGCommitmentScheme<Bn256>>(K);
+        let circuit = AffineCircuit::default();
+        let vk = keygen_vk(&params, &circuit).unwrap();
+        let pk = keygen_pk(&params, vk, &circuit).unwrap();
+        save_pk::<G1Affine>(&eager_path, &pk).unwrap();
+        save_pk_mmap(&mapped_path, &pk).unwrap();
+
+        let mapped = MappedPk::open(&mapped_path, false).unwrap();
+        let mut concatenated = vec![];
+        for section in PkSection::ALL {
+            let range = &mapped.header.sections[section.id() as usize];
+            assert_eq!(range.start % MMAP_PK_ALIGNMENT, 0);
+            concatenated.extend_from_slice(mapped.section(section));
+        }
+        assert_eq!(concatenated, std::fs::read(&eager_path).unwrap());
+
+        // the domain holds three polynomials over the extended domain and the fixed columns list their count first
+        let extended = pk.get_vk().get_domain().extended_len();
+        assert_eq!(
+            mapped.section(PkSection::Domain).len(),
+            3 * (4 + extended * 32)
+        );
+        let num_fixed = pk.get_vk().cs().num_fixed_columns() as u32;
+        assert_eq!(
+            mapped.section(PkSection::FixedColumns)[..4],
+            num_fixed.to_be_bytes()
+        );
+    }
+
+    #[test]
+    fn test_mmap_pk_header_rejects_garbage() {
+        assert!(MmapPkHeader::read(b"not a key").is_err());
+        let mut bytes = vec![];
+        MmapPkHeader {
+            format: halo2_proofs::SerdeFormat::RawBytes,
+            sections: vec![4096..4100, 8192..9000, 12288..12300, 16384..16400],
+        }
+        .write(&mut bytes)
+        .unwrap();
+        // sections point past the end of the buffer
+        assert!(MmapPkHeader::read(&bytes).is_err());
+    }
+}
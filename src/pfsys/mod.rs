@@ -7,9 +7,33 @@ pub mod srs;
 /// errors related to pfsys
 pub mod errors;
 
+/// Progress reporting and cancellation of proofs
+pub mod progress;
+
+/// Proof size and proving cost estimation
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+pub mod estimate;
+
+/// Memory-mappable proving key layout and loaders
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+pub mod mmap;
+
+/// Encryption at rest of the compiled circuit and the proving key
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+pub mod encryption;
+
+/// A description of verifying keys for verifiers implemented outside of the crate
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+pub mod vk_description;
+
+/// Verification of proofs from the bytes of their keys, settings and srs, the part of the crate the `verifier-only`
+/// feature is meant for
+pub mod verifier;
+
 pub use errors::PfsysError;
 
 use crate::circuit::CheckMode;
+#[cfg(not(all(feature = "verifier-only", not(feature = "ezkl"))))]
 use crate::graph::GraphWitness;
 use crate::pfsys::evm::aggregation_kzg::PoseidonTranscript;
 use crate::{Commitments, EZKL_BUF_CAPACITY, EZKL_KEY_FORMAT};
@@ -23,7 +47,10 @@ use halo2_proofs::poly::commitment::{CommitmentScheme, Params, ParamsProver, Pro
 use halo2_proofs::poly::ipa::commitment::IPACommitmentScheme;
 use halo2_proofs::poly::kzg::commitment::KZGCommitmentScheme;
 use halo2_proofs::poly::VerificationStrategy;
-use halo2_proofs::transcript::{EncodedChallenge, TranscriptReadBuffer, TranscriptWriterBuffer};
+use halo2_proofs::transcript::{
+    Blake2bRead, Blake2bWrite, Challenge255, EncodedChallenge, TranscriptReadBuffer,
+    TranscriptWriterBuffer,
+};
 use halo2curves::ff::{FromUniformBytes, PrimeField, WithSmallOrderMulGroup};
 use halo2curves::serde::SerdeObject;
 use halo2curves::CurveAffine;
@@ -33,6 +60,8 @@ use log::{debug, info, trace};
 use rand::rngs::OsRng;
 #[cfg(feature = "det-prove")]
 use rand::rngs::StdRng;
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+use schemars::JsonSchema;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use snark_verifier::loader::native::NativeLoader;
@@ -211,6 +240,7 @@ pub enum TranscriptType {
     Poseidon,
     #[default]
     EVM,
+    Blake,
 }
 
 impl std::fmt::Display for TranscriptType {
@@ -221,6 +251,7 @@ impl std::fmt::Display for TranscriptType {
             match self {
                 TranscriptType::Poseidon => "poseidon",
                 TranscriptType::EVM => "evm",
+                TranscriptType::Blake => "blake",
             }
         )
     }
@@ -238,6 +269,7 @@ impl ToPyObject for TranscriptType {
         match self {
             TranscriptType::Poseidon => "Poseidon".to_object(py),
             TranscriptType::EVM => "EVM".to_object(py),
+            TranscriptType::Blake => "Blake".to_object(py),
         }
     }
 }
@@ -281,6 +313,7 @@ pub fn string_to_field<F: PrimeField + SerdeObject + Serialize + DeserializeOwne
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[cfg_attr(all(feature = "ezkl", not(target_arch = "wasm32")), derive(JsonSchema))]
 /// Contains the instances of the circuit in human readable form
 pub struct PrettyElements {
     /// the inputs as rescaled floats -- represented as a String for maximum compatibility with Python and JS
@@ -324,6 +357,9 @@ where
     pub timestamp: Option<u128>,
     /// commitment
     pub commitment: Option<Commitments>,
+    /// the block any on-chain data in the witness was read at
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pinned_block: Option<crate::graph::input::PinnedBlock>,
 }
 
 #[cfg(feature = "python-bindings")]
@@ -385,6 +421,20 @@ where
                     .as_millis(),
             ),
             commitment,
+            pinned_block: None,
+        }
+    }
+
+    /// Checks that the proof was produced with the `expected` transcript, if one was requested
+    pub fn check_transcript(&self, expected: Option<TranscriptType>) -> Result<(), PfsysError> {
+        match expected {
+            Some(expected) if expected != self.transcript_type => {
+                Err(PfsysError::TranscriptMismatch(
+                    self.transcript_type.to_string(),
+                    expected.to_string(),
+                ))
+            }
+            _ => Ok(()),
         }
     }
 
@@ -430,6 +480,14 @@ pub struct ProofSplitCommit {
     end: usize,
 }
 
+impl ProofSplitCommit {
+    /// The indices of the commitments of the outputs among the advice commitments of the proof
+    pub fn range(&self) -> std::ops::Range<usize> {
+        self.start..self.end
+    }
+}
+
+#[cfg(not(all(feature = "verifier-only", not(feature = "ezkl"))))]
 impl From<GraphWitness> for Option<ProofSplitCommit> {
     fn from(witness: GraphWitness) -> Self {
         let mut elem_offset = 0;
@@ -526,29 +584,46 @@ where
     }
 }
 
+/// The error halo2 failed to synthesize a circuit with, or the layout error of the model it stands for
+pub fn synthesis_error(e: halo2_proofs::plonk::Error) -> PfsysError {
+    match crate::graph::take_layout_error() {
+        Some(crate::graph::errors::GraphError::Cancelled)
+        | Some(crate::graph::errors::GraphError::CircuitError(
+            crate::circuit::CircuitError::Cancelled,
+        )) => PfsysError::Cancelled,
+        Some(layout_error) => layout_error.into(),
+        None => e.into(),
+    }
+}
+
 /// Creates a [VerifyingKey] and [ProvingKey] for a [crate::graph::GraphCircuit] (`circuit`) with specific [CommitmentScheme] parameters (`params`).
 pub fn create_keys<Scheme: CommitmentScheme, C: Circuit<Scheme::Scalar>>(
     circuit: &C,
     params: &'_ Scheme::ParamsProver,
     disable_selector_compression: bool,
-) -> Result<ProvingKey<Scheme::Curve>, halo2_proofs::plonk::Error>
+) -> Result<ProvingKey<Scheme::Curve>, PfsysError>
 where
     C: Circuit<Scheme::Scalar>,
     <Scheme as CommitmentScheme>::Scalar: FromUniformBytes<64>,
 {
+    let _span = tracing::info_span!("keygen", logrows = params.k()).entered();
     //	Real proof
     let empty_circuit = <C as Circuit<Scheme::Scalar>>::without_witnesses(circuit);
 
     // Initialize verifying key
     let now = Instant::now();
     trace!("preparing VK");
-    let vk = keygen_vk_custom(params, &empty_circuit, !disable_selector_compression)?;
+    let vk = tracing::info_span!("keygen_vk")
+        .in_scope(|| keygen_vk_custom(params, &empty_circuit, !disable_selector_compression))
+        .map_err(synthesis_error)?;
     let elapsed = now.elapsed();
     info!("VK took {}.{}", elapsed.as_secs(), elapsed.subsec_millis());
 
     // Initialize the proving key
     let now = Instant::now();
-    let pk = keygen_pk(params, vk, &empty_circuit)?;
+    let pk = tracing::info_span!("keygen_pk")
+        .in_scope(|| keygen_pk(params, vk, &empty_circuit))
+        .map_err(synthesis_error)?;
     let elapsed = now.elapsed();
     info!("PK took {}.{}", elapsed.as_secs(), elapsed.subsec_millis());
     Ok(pk)
@@ -610,15 +685,27 @@ where
     // not wasm32 unknown
     let now = Instant::now();
 
-    create_proof::<Scheme, P, _, _, TW, _>(
-        params,
-        pk,
-        &[circuit],
-        pi_inner,
-        &mut rng,
-        &mut transcript,
-    )?;
+    // halo2 synthesizes the circuit then commits to it, the time of the span outside of its synthesis span is that of
+    // the FFTs and MSMs of the commitments and openings
+    tracing::info_span!(
+        "create_proof",
+        logrows = params.k(),
+        instances = instances.iter().map(|i| i.len()).sum::<usize>()
+    )
+    .in_scope(|| {
+        create_proof::<Scheme, P, _, _, TW, _>(
+            params,
+            pk,
+            &[circuit],
+            pi_inner,
+            &mut rng,
+            &mut transcript,
+        )
+    })
+    .map_err(synthesis_error)?;
+    progress::report(progress::ProvingPhase::Commit, 1.0)?;
     let proof = transcript.finalize();
+    let _span = tracing::info_span!("finalize", proof_bytes = proof.len()).entered();
     let hex_proof = format!("0x{}", hex::encode(&proof));
 
     let checkable_pf = Snark::new(
@@ -663,6 +750,30 @@ pub fn swap_proof_commitments<
     snark: &Snark<Scheme::Scalar, Scheme::Curve>,
     commitments: &[Scheme::Curve],
 ) -> Result<Snark<Scheme::Scalar, Scheme::Curve>, PfsysError>
+where
+    Scheme::Scalar: SerdeObject
+        + PrimeField
+        + FromUniformBytes<64>
+        + WithSmallOrderMulGroup<3>
+        + Ord
+        + Serialize
+        + DeserializeOwned,
+    Scheme::Curve: Serialize + DeserializeOwned,
+{
+    swap_proof_commitments_at::<Scheme, E, TW>(snark, 0, commitments)
+}
+
+/// Swaps the proof commitments from the `offset`-th on to a new set in the proof, leaving the commitments before and
+/// after them untouched
+pub fn swap_proof_commitments_at<
+    Scheme: CommitmentScheme,
+    E: EncodedChallenge<Scheme::Curve>,
+    TW: TranscriptWriterBuffer<Vec<u8>, Scheme::Curve, E>,
+>(
+    snark: &Snark<Scheme::Scalar, Scheme::Curve>,
+    offset: usize,
+    commitments: &[Scheme::Curve],
+) -> Result<Snark<Scheme::Scalar, Scheme::Curve>, PfsysError>
 where
     Scheme::Scalar: SerdeObject
         + PrimeField
@@ -674,10 +785,23 @@ where
     Scheme::Curve: Serialize + DeserializeOwned,
 {
     let proof_first_bytes = get_proof_commitments::<Scheme, E, TW>(commitments)?;
+    // the points of a transcript are all encoded in the same number of bytes
+    let start = match commitments.len() {
+        0 => 0,
+        n => offset * proof_first_bytes.len() / n,
+    };
+    let end = start + proof_first_bytes.len();
+    if end > snark.proof.len() {
+        return Err(PfsysError::WritePoint(format!(
+            "the proof has no commitments {}..{}",
+            offset,
+            offset + commitments.len()
+        )));
+    }
 
     let mut snark_new = snark.clone();
     // swap the proof bytes for the new ones
-    snark_new.proof[..proof_first_bytes.len()].copy_from_slice(&proof_first_bytes);
+    snark_new.proof[start..end].copy_from_slice(&proof_first_bytes);
     snark_new.create_hex_proof();
 
     Ok(snark_new)
@@ -723,31 +847,50 @@ where
 pub fn swap_proof_commitments_polycommit(
     snark: &Snark<Fr, G1Affine>,
     commitments: &[G1Affine],
+) -> Result<Snark<Fr, G1Affine>, PfsysError> {
+    swap_proof_commitments_polycommit_at(snark, 0, commitments)
+}
+
+/// Swap the proof commitments from the `offset`-th on to a new set in the proof, as those of a group of params
+pub fn swap_proof_commitments_polycommit_at(
+    snark: &Snark<Fr, G1Affine>,
+    offset: usize,
+    commitments: &[G1Affine],
 ) -> Result<Snark<Fr, G1Affine>, PfsysError> {
     let proof = match snark.commitment {
         Some(Commitments::KZG) => match snark.transcript_type {
-            TranscriptType::EVM => swap_proof_commitments::<
+            TranscriptType::EVM => swap_proof_commitments_at::<
                 KZGCommitmentScheme<Bn256>,
                 _,
                 EvmTranscript<G1Affine, _, _, _>,
-            >(snark, commitments)?,
-            TranscriptType::Poseidon => swap_proof_commitments::<
+            >(snark, offset, commitments)?,
+            TranscriptType::Poseidon => swap_proof_commitments_at::<
                 KZGCommitmentScheme<Bn256>,
                 _,
                 PoseidonTranscript<NativeLoader, _>,
-            >(snark, commitments)?,
+            >(snark, offset, commitments)?,
+            TranscriptType::Blake => swap_proof_commitments_at::<
+                KZGCommitmentScheme<Bn256>,
+                _,
+                Blake2bWrite<_, _, Challenge255<_>>,
+            >(snark, offset, commitments)?,
         },
         Some(Commitments::IPA) => match snark.transcript_type {
-            TranscriptType::EVM => swap_proof_commitments::<
+            TranscriptType::EVM => swap_proof_commitments_at::<
                 IPACommitmentScheme<G1Affine>,
                 _,
                 EvmTranscript<G1Affine, _, _, _>,
-            >(snark, commitments)?,
-            TranscriptType::Poseidon => swap_proof_commitments::<
+            >(snark, offset, commitments)?,
+            TranscriptType::Poseidon => swap_proof_commitments_at::<
                 IPACommitmentScheme<G1Affine>,
                 _,
                 PoseidonTranscript<NativeLoader, _>,
-            >(snark, commitments)?,
+            >(snark, offset, commitments)?,
+            TranscriptType::Blake => swap_proof_commitments_at::<
+                IPACommitmentScheme<G1Affine>,
+                _,
+                Blake2bWrite<_, _, Challenge255<_>>,
+            >(snark, offset, commitments)?,
         },
         None => {
             return Err(PfsysError::InvalidCommitmentScheme);
@@ -757,6 +900,47 @@ pub fn swap_proof_commitments_polycommit(
     Ok(proof)
 }
 
+/// Reads the first `n` points of a proof, its first advice commitments
+pub fn read_proof_commitments<
+    C: CurveAffine,
+    E: EncodedChallenge<C>,
+    TR: TranscriptReadBuffer<Cursor<Vec<u8>>, C, E>,
+>(
+    proof: &[u8],
+    n: usize,
+) -> Result<Vec<C>, PfsysError> {
+    let mut transcript = TR::init(Cursor::new(proof.to_vec()));
+    (0..n)
+        .map(|_| {
+            transcript
+                .read_point()
+                .map_err(|e| PfsysError::ReadPoint(format!("{}", e)))
+        })
+        .collect()
+}
+
+/// Reads the first `n` advice commitments of a KZG proof, with the transcript it was created with. These are the
+/// polycommit commitments of the inputs, then of the params and of the outputs, see [ProofSplitCommit].
+pub fn proof_commitments_polycommit(
+    snark: &Snark<Fr, G1Affine>,
+    n: usize,
+) -> Result<Vec<G1Affine>, PfsysError> {
+    match snark.commitment {
+        Some(Commitments::KZG) | None => match snark.transcript_type {
+            TranscriptType::EVM => {
+                read_proof_commitments::<_, _, EvmTranscript<G1Affine, _, _, _>>(&snark.proof, n)
+            }
+            TranscriptType::Poseidon => {
+                read_proof_commitments::<_, _, PoseidonTranscript<NativeLoader, _>>(&snark.proof, n)
+            }
+            TranscriptType::Blake => {
+                read_proof_commitments::<_, _, Blake2bRead<_, _, Challenge255<_>>>(&snark.proof, n)
+            }
+        },
+        Some(Commitments::IPA) => Err(PfsysError::InvalidCommitmentScheme),
+    }
+}
+
 /// A wrapper around halo2's verify_proof
 pub fn verify_proof_circuit<
     'params,
@@ -816,7 +1000,7 @@ where
     Ok(vk)
 }
 
-/// Loads a [ProvingKey] at `path`.
+/// Loads a [ProvingKey] at `path`. Keys saved in the memory-mappable layout are mapped rather than read.
 pub fn load_pk<Scheme: CommitmentScheme, C: Circuit<Scheme::Scalar>>(
     path: PathBuf,
     params: <C as Circuit<Scheme::Scalar>>::Params,
@@ -826,6 +1010,14 @@ where
     Scheme::Curve: SerdeObject + CurveAffine,
     Scheme::Scalar: PrimeField + SerdeObject + FromUniformBytes<64>,
 {
+    let _span = tracing::info_span!("pk_load").entered();
+    #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+    encryption::ensure_plaintext(&path)?;
+    #[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+    if mmap::is_mmap_pk(&path) {
+        return mmap::load_pk_mmap::<Scheme, C>(path, params, *crate::EZKL_PK_PREFAULT);
+    }
+
     debug!("loading proving key from {:?}", path);
     let f = File::open(path.clone()).map_err(|e| PfsysError::LoadPk(format!("{}", e)))?;
     let mut reader = BufReader::with_capacity(*EZKL_BUF_CAPACITY, f);
@@ -933,4 +1125,31 @@ mod tests {
         assert_eq!(snark.proof, snark2.proof);
         assert_eq!(snark.transcript_type, snark2.transcript_type);
     }
+
+    #[test]
+    fn test_snark_transcript_is_enforced() {
+        let tmp_dir = Builder::new().prefix("example").tempdir().unwrap();
+        let fname = tmp_dir.path().join("proof.json");
+        let snark = Snark::<Fr, G1Affine> {
+            proof: vec![1, 2, 3, 4],
+            instances: vec![vec![Fr::from(1)]],
+            transcript_type: TranscriptType::Blake,
+            protocol: None,
+            hex_proof: None,
+            split: None,
+            pretty_public_inputs: None,
+            timestamp: None,
+            commitment: None,
+        };
+        snark.save(&fname).unwrap();
+        let snark = Snark::<Fr, G1Affine>::load::<KZGCommitmentScheme<Bn256>>(&fname).unwrap();
+
+        assert_eq!(snark.transcript_type, TranscriptType::Blake);
+        assert!(snark.check_transcript(None).is_ok());
+        assert!(snark.check_transcript(Some(TranscriptType::Blake)).is_ok());
+        assert!(matches!(
+            snark.check_transcript(Some(TranscriptType::EVM)),
+            Err(PfsysError::TranscriptMismatch(_, _))
+        ));
+    }
 }
@@ -0,0 +1,254 @@
+use super::errors::PfsysError;
+use instant::Instant;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The phases of a proof, in the order they run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProvingPhase {
+    /// Reading the witness, the proving key and the SRS
+    Load,
+    /// Laying out the circuit with the witness
+    Synthesis,
+    /// Committing to the witness and computing the proof, which halo2 reports no progress for
+    Commit,
+    /// The proof is complete
+    Done,
+}
+
+impl ProvingPhase {
+    /// The name the phase is reported with
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProvingPhase::Load => "load",
+            ProvingPhase::Synthesis => "synthesis",
+            ProvingPhase::Commit => "commit",
+            ProvingPhase::Done => "done",
+        }
+    }
+}
+
+/// Receives the progress of the proofs made on a thread, and decides whether they go on
+pub trait ProgressObserver {
+    /// Called at the boundaries of the phases and as synthesis lays out the nodes of the model, `fraction` is the
+    /// fraction of the phase that is complete
+    fn progress(&self, phase: ProvingPhase, fraction: f64);
+    /// Whether the proof should be abandoned, checked whenever progress is reported
+    fn is_cancelled(&self) -> bool;
+}
+
+/// A flag cancelling the proofs observed by any of its clones, which may be set from another thread, or which sets
+/// itself once its timeout has elapsed
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+    /// when the token was created, and the time it cancels the proofs after
+    timeout: Option<(Instant, Duration)>,
+}
+
+impl CancellationToken {
+    /// A token that isn't cancelled yet
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A token cancelling the proofs it observes once `timeout` has elapsed from now, or when cancelled before
+    pub fn with_timeout(timeout: Duration) -> Self {
+        CancellationToken {
+            cancelled: Arc::default(),
+            timeout: Some((Instant::now(), timeout)),
+        }
+    }
+
+    /// Cancels the proofs observed by the token, they stop at their next progress report
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether the timeout of the token has elapsed
+    pub fn timed_out(&self) -> bool {
+        self.timeout
+            .is_some_and(|(start, timeout)| start.elapsed() >= timeout)
+    }
+
+    /// A guard cancelling the token when dropped, as when the future awaiting the proof is dropped unfinished
+    pub fn cancel_on_drop(&self) -> CancelOnDrop {
+        CancelOnDrop(self.clone())
+    }
+}
+
+impl ProgressObserver for CancellationToken {
+    fn progress(&self, _phase: ProvingPhase, _fraction: f64) {}
+
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed) || self.timed_out()
+    }
+}
+
+/// Cancels its token when dropped, see [CancellationToken::cancel_on_drop]
+#[derive(Debug)]
+pub struct CancelOnDrop(CancellationToken);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.cancel();
+    }
+}
+
+thread_local!(
+    /// The observer of the proofs made on this thread
+    static OBSERVER: RefCell<Option<Rc<dyn ProgressObserver>>> = const { RefCell::new(None) }
+);
+
+/// Restores the previous observer of the thread when dropped, see [set_observer]
+pub struct ObserverGuard(Option<Rc<dyn ProgressObserver>>);
+
+impl Drop for ObserverGuard {
+    fn drop(&mut self) {
+        OBSERVER.with(|observer| *observer.borrow_mut() = self.0.take());
+    }
+}
+
+/// Sets `observer` to receive the progress reported on this thread until the guard is dropped, as for a future polled
+/// on a single thread
+pub fn set_observer(observer: Rc<dyn ProgressObserver>) -> ObserverGuard {
+    ObserverGuard(OBSERVER.with(|o| o.borrow_mut().replace(observer)))
+}
+
+/// Runs `f` with `observer` receiving the progress reported on this thread
+pub fn with_observer<T>(observer: Rc<dyn ProgressObserver>, f: impl FnOnce() -> T) -> T {
+    let _restore = set_observer(observer);
+    f()
+}
+
+/// Reports progress to the observer of this thread, if any. Fails with [PfsysError::Cancelled] if the observer
+/// cancelled the proof.
+pub fn report(phase: ProvingPhase, fraction: f64) -> Result<(), PfsysError> {
+    // the observer is cloned out such that it may itself prove without re-entering the borrow
+    let observer = OBSERVER.with(|o| o.borrow().clone());
+    match observer {
+        Some(observer) => {
+            observer.progress(phase, fraction.clamp(0.0, 1.0));
+            if observer.is_cancelled() {
+                Err(PfsysError::Cancelled)
+            } else {
+                Ok(())
+            }
+        }
+        None => Ok(()),
+    }
+}
+
+/// Fails with [PfsysError::Cancelled] if the observer of this thread cancelled the proof, without reporting progress,
+/// as between the nodes of a forward pass and the rows of a layout
+pub fn check_cancelled() -> Result<(), PfsysError> {
+    let observer = OBSERVER.with(|o| o.borrow().clone());
+    match observer {
+        Some(observer) if observer.is_cancelled() => Err(PfsysError::Cancelled),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    struct Recorder {
+        events: RefCell<Vec<(ProvingPhase, f64)>>,
+        cancel_at: Option<ProvingPhase>,
+        cancelled: Cell<bool>,
+    }
+
+    impl ProgressObserver for Recorder {
+        fn progress(&self, phase: ProvingPhase, fraction: f64) {
+            self.events.borrow_mut().push((phase, fraction));
+            if self.cancel_at == Some(phase) {
+                self.cancelled.set(true);
+            }
+        }
+
+        fn is_cancelled(&self) -> bool {
+            self.cancelled.get()
+        }
+    }
+
+    fn recorder(cancel_at: Option<ProvingPhase>) -> Rc<Recorder> {
+        Rc::new(Recorder {
+            events: RefCell::new(vec![]),
+            cancel_at,
+            cancelled: Cell::new(false),
+        })
+    }
+
+    #[test]
+    fn reports_to_the_observer_in_scope() {
+        let observer = recorder(None);
+        with_observer(observer.clone(), || {
+            report(ProvingPhase::Load, 0.0).unwrap();
+            report(ProvingPhase::Synthesis, 2.0).unwrap();
+        });
+        // out of scope nothing is observed
+        report(ProvingPhase::Done, 1.0).unwrap();
+        assert_eq!(
+            *observer.events.borrow(),
+            vec![(ProvingPhase::Load, 0.0), (ProvingPhase::Synthesis, 1.0)]
+        );
+    }
+
+    #[test]
+    fn cancellation() {
+        let observer = recorder(Some(ProvingPhase::Synthesis));
+        let reported = with_observer(observer.clone(), || {
+            report(ProvingPhase::Load, 1.0)?;
+            report(ProvingPhase::Synthesis, 0.5)?;
+            report(ProvingPhase::Commit, 0.0)
+        });
+        assert!(matches!(reported, Err(PfsysError::Cancelled)));
+        assert_eq!(observer.events.borrow().len(), 2);
+    }
+
+    #[test]
+    fn cancellation_token() {
+        let token = CancellationToken::new();
+        let guard = token.cancel_on_drop();
+        let cancelled = std::thread::spawn({
+            let token = token.clone();
+            move || {
+                with_observer(Rc::new(token.clone()), || {
+                    report(ProvingPhase::Load, 1.0)?;
+                    while !token.is_cancelled() {
+                        std::thread::yield_now();
+                    }
+                    report(ProvingPhase::Synthesis, 0.0)
+                })
+            }
+        });
+        drop(guard);
+        assert!(matches!(
+            cancelled.join().unwrap(),
+            Err(PfsysError::Cancelled)
+        ));
+    }
+
+    #[test]
+    fn timeout() {
+        let token = CancellationToken::with_timeout(Duration::from_millis(50));
+        let _observer = set_observer(Rc::new(token.clone()));
+        check_cancelled().unwrap();
+        assert!(!token.timed_out());
+
+        std::thread::sleep(Duration::from_millis(60));
+        assert!(token.timed_out());
+        assert!(matches!(check_cancelled(), Err(PfsysError::Cancelled)));
+        assert!(matches!(
+            report(ProvingPhase::Synthesis, 0.5),
+            Err(PfsysError::Cancelled)
+        ));
+        // a token without a timeout only stops when cancelled
+        assert!(!CancellationToken::new().timed_out());
+    }
+}
@@ -1,10 +1,21 @@
+use halo2_proofs::arithmetic::best_multiexp;
 use halo2_proofs::poly::commitment::CommitmentScheme;
 use halo2_proofs::poly::commitment::Params;
 use halo2_proofs::poly::commitment::ParamsProver;
+use halo2_proofs::poly::ipa::commitment::ParamsIPA;
+use halo2_proofs::poly::kzg::commitment::ParamsKZG;
+use halo2curves::bn256::{Bn256, Fr, G1Affine};
+use halo2curves::ff::Field;
+use halo2curves::group::prime::PrimeCurveAffine;
+use halo2curves::group::Curve;
+use halo2curves::pairing::Engine;
 use log::debug;
+use rand::rngs::OsRng;
 use std::fs::File;
 use std::io::BufReader;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::RwLock;
 
 /// for now we use the urls of the powers of tau ceremony from <https://github.com/han0110/halo2-kzg-srs>
 pub const PUBLIC_SRS_URL: &str =
@@ -24,6 +35,149 @@ pub enum SrsError {
     LoadError(PathBuf),
     #[error("failed to read srs {0}")]
     ReadError(String),
+    #[error("srs integrity check failed: {0}")]
+    IntegrityError(String),
+    #[error("cannot trim srs with {0} logrows to {1} logrows")]
+    TrimError(u32, u32),
+    #[error("no srs at {0} and downloads are disabled (--offline), copy the srs file there or fetch it online first")]
+    Offline(PathBuf),
+}
+
+static SKIP_SRS_CHECK: AtomicBool = AtomicBool::new(false);
+
+/// Disables the consistency check performed when loading an SRS (the `--skip-srs-check` flag)
+pub fn set_skip_srs_check(skip: bool) {
+    SKIP_SRS_CHECK.store(skip, Ordering::Relaxed);
+}
+
+/// Whether the consistency check on load is disabled, either by flag or through the `EZKL_SKIP_SRS_CHECK` env var
+pub fn skip_srs_check() -> bool {
+    SKIP_SRS_CHECK.load(Ordering::Relaxed) || std::env::var("EZKL_SKIP_SRS_CHECK").is_ok()
+}
+
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+
+/// Disables the download of SRS files, a missing SRS being an error naming the file expected (the `--offline` flag)
+pub fn set_offline(offline: bool) {
+    OFFLINE.store(offline, Ordering::Relaxed);
+}
+
+/// Whether SRS files are never downloaded, either by flag or through the `EZKL_OFFLINE` env var
+pub fn offline() -> bool {
+    OFFLINE.load(Ordering::Relaxed) || std::env::var("EZKL_OFFLINE").is_ok()
+}
+
+static SRS_MIRRORS: RwLock<Vec<String>> = RwLock::new(Vec::new());
+
+/// Sets the urls an SRS is downloaded from, in order, when the download from the SRS url fails (the `--srs-mirrors`
+/// flag). As the SRS url, each is followed by the logrows of the SRS.
+pub fn set_srs_mirrors(mirrors: Vec<String>) {
+    *SRS_MIRRORS.write().unwrap_or_else(|e| e.into_inner()) = mirrors;
+}
+
+/// The mirrors of the SRS url, either by flag or through the comma separated `EZKL_SRS_MIRRORS` env var
+pub fn srs_mirrors() -> Vec<String> {
+    let mirrors = SRS_MIRRORS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone();
+    if !mirrors.is_empty() {
+        return mirrors;
+    }
+    std::env::var("EZKL_SRS_MIRRORS")
+        .map(|m| {
+            m.split(',')
+                .map(str::trim)
+                .filter(|m| !m.is_empty())
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Structural consistency checks for loaded SRS parameters
+pub trait SrsIntegrity {
+    /// Checks the shape of the parameters and the structure of their first powers, cheap enough to run on every load
+    fn check_consistency(&self) -> Result<(), SrsError>;
+    /// Checks the parameters are internally consistent over all their powers, catching truncated or corrupted files.
+    /// Costs multi-scalar multiplications over the whole SRS, so it is run when an SRS is downloaded, trimmed or
+    /// verified rather than on every load
+    fn check_integrity(&self) -> Result<(), SrsError>;
+}
+
+impl SrsIntegrity for ParamsKZG<Bn256> {
+    /// Checks there are 2^k powers and e(g, τh) = e(τg, h).
+    fn check_consistency(&self) -> Result<(), SrsError> {
+        let g = self.get_g();
+        if g.len() < 2 || g.len() != self.n() as usize {
+            return Err(SrsError::IntegrityError(format!(
+                "expected {} powers of tau, found {}",
+                self.n(),
+                g.len()
+            )));
+        }
+        let h = self.g2();
+        let s_h = self.s_g2();
+        if bool::from(h.is_identity()) || bool::from(s_h.is_identity()) {
+            return Err(SrsError::IntegrityError(
+                "g2 points are the identity".into(),
+            ));
+        }
+
+        if Bn256::pairing(&g[0], &s_h) != Bn256::pairing(&g[1], &h) {
+            return Err(SrsError::IntegrityError(
+                "e(g, τh) != e(τg, h), the srs was not generated from a single τ".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Checks [SrsIntegrity::check_consistency], then that every consecutive pair of powers is
+    /// consistent using a random linear combination: e(Σ rⁱ·τⁱg, τh) = e(Σ rⁱ·τⁱ⁺¹g, h).
+    fn check_integrity(&self) -> Result<(), SrsError> {
+        self.check_consistency()?;
+        let g = self.get_g();
+        let h = self.g2();
+        let s_h = self.s_g2();
+        let r = Fr::random(OsRng);
+        let scalars = std::iter::successors(Some(Fr::ONE), |acc| Some(*acc * r))
+            .take(g.len() - 1)
+            .collect::<Vec<_>>();
+        let lhs = best_multiexp(&scalars, &g[..g.len() - 1]).to_affine();
+        let rhs = best_multiexp(&scalars, &g[1..]).to_affine();
+        if Bn256::pairing(&lhs, &s_h) != Bn256::pairing(&rhs, &h) {
+            return Err(SrsError::IntegrityError(
+                "consecutive powers of tau are inconsistent, the file may be corrupted".into(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl SrsIntegrity for ParamsIPA<G1Affine> {
+    /// IPA parameters have no trapdoor structure to check, the generators are validated on read.
+    fn check_consistency(&self) -> Result<(), SrsError> {
+        Ok(())
+    }
+
+    /// See [SrsIntegrity::check_consistency].
+    fn check_integrity(&self) -> Result<(), SrsError> {
+        Ok(())
+    }
+}
+
+/// Trims `params` down to `logrows`, the result only contains the powers needed for circuits of that size.
+pub fn trim_srs<Scheme: CommitmentScheme>(
+    params: &mut Scheme::ParamsProver,
+    logrows: u32,
+) -> Result<(), SrsError> {
+    if logrows > params.k() {
+        return Err(SrsError::TrimError(params.k(), logrows));
+    }
+    if logrows < params.k() {
+        params.downsize(logrows);
+    }
+    Ok(())
 }
 
 /// Loads the [CommitmentScheme::ParamsVerifier] at `path`.
@@ -45,3 +199,166 @@ pub fn load_srs_prover<Scheme: CommitmentScheme>(
     let mut reader = BufReader::new(f);
     Params::<'_, Scheme::Curve>::read(&mut reader).map_err(|e| SrsError::ReadError(e.to_string()))
 }
+
+#[cfg(test)]
+#[cfg(all(feature = "ezkl", not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+    use halo2_proofs::circuit::{Layouter, SimpleFloorPlanner, Value};
+    use halo2_proofs::plonk::{
+        create_proof, keygen_pk, keygen_vk, verify_proof, Advice, Circuit, Column,
+        ConstraintSystem, Error, Selector,
+    };
+    use halo2_proofs::poly::kzg::commitment::KZGCommitmentScheme;
+    use halo2_proofs::poly::kzg::multiopen::{ProverSHPLONK, VerifierSHPLONK};
+    use halo2_proofs::poly::kzg::strategy::SingleStrategy;
+    use halo2_proofs::poly::Rotation;
+    use halo2_proofs::transcript::{
+        Blake2bRead, Blake2bWrite, Challenge255, TranscriptReadBuffer, TranscriptWriterBuffer,
+    };
+    use std::io::{Read, Seek, SeekFrom, Write};
+    use tempfile::Builder;
+
+    #[derive(Clone, Default)]
+    struct SquareCircuit {
+        x: Value<Fr>,
+    }
+
+    impl Circuit<Fr> for SquareCircuit {
+        type Config = (Column<Advice>, Column<Advice>, Selector);
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = ();
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(cs: &mut ConstraintSystem<Fr>) -> Self::Config {
+            let a = cs.advice_column();
+            let b = cs.advice_column();
+            let s = cs.selector();
+            cs.create_gate("b = a * a", |meta| {
+                let s = meta.query_selector(s);
+                let a = meta.query_advice(a, Rotation::cur());
+                let b = meta.query_advice(b, Rotation::cur());
+                vec![s * (a.clone() * a - b)]
+            });
+            (a, b, s)
+        }
+
+        fn synthesize(
+            &self,
+            (a, b, s): Self::Config,
+            mut layouter: impl Layouter<Fr>,
+        ) -> Result<(), Error> {
+            layouter.assign_region(
+                || "square",
+                |mut region| {
+                    s.enable(&mut region, 0)?;
+                    region.assign_advice(|| "a", a, 0, || self.x)?;
+                    region.assign_advice(|| "b", b, 0, || self.x * self.x)?;
+                    Ok(())
+                },
+            )
+        }
+    }
+
+    #[test]
+    fn test_trimmed_srs_proves_against_untrimmed_vk() {
+        let tmp_dir = Builder::new().prefix("srs_trim").tempdir().unwrap();
+        let full_path = tmp_dir.path().join("full.srs");
+        let trimmed_path = tmp_dir.path().join("trimmed.srs");
+
+        let full = gen_srs::<KZGCommitmentScheme<Bn256>>(8);
+        crate::pfsys::save_params::<KZGCommitmentScheme<Bn256>>(&full_path, &full).unwrap();
+
+        let mut trimmed = load_srs_prover::<KZGCommitmentScheme<Bn256>>(full_path.clone()).unwrap();
+        trim_srs::<KZGCommitmentScheme<Bn256>>(&mut trimmed, 5).unwrap();
+        crate::pfsys::save_params::<KZGCommitmentScheme<Bn256>>(&trimmed_path, &trimmed).unwrap();
+        assert!(
+            std::fs::metadata(&trimmed_path).unwrap().len()
+                < std::fs::metadata(&full_path).unwrap().len()
+        );
+        // cannot grow an srs
+        assert!(trim_srs::<KZGCommitmentScheme<Bn256>>(&mut trimmed, 6).is_err());
+
+        let trimmed = load_srs_prover::<KZGCommitmentScheme<Bn256>>(trimmed_path).unwrap();
+        trimmed.check_integrity().unwrap();
+
+        // the vk is derived from the untrimmed srs, downsized in memory
+        let mut untrimmed = load_srs_prover::<KZGCommitmentScheme<Bn256>>(full_path).unwrap();
+        untrimmed.downsize(5);
+        let vk = keygen_vk(&untrimmed, &SquareCircuit::default()).unwrap();
+
+        // the proof is generated with the trimmed srs
+        let pk = keygen_pk(
+            &trimmed,
+            keygen_vk(&trimmed, &SquareCircuit::default()).unwrap(),
+            &SquareCircuit::default(),
+        )
+        .unwrap();
+        assert_eq!(vk.transcript_repr(), pk.get_vk().transcript_repr());
+
+        let circuit = SquareCircuit {
+            x: Value::known(Fr::from(5)),
+        };
+        let mut transcript = Blake2bWrite::<_, G1Affine, Challenge255<_>>::init(vec![]);
+        create_proof::<KZGCommitmentScheme<Bn256>, ProverSHPLONK<_>, _, _, _, _>(
+            &trimmed,
+            &pk,
+            &[circuit],
+            &[&[]],
+            OsRng,
+            &mut transcript,
+        )
+        .unwrap();
+        let proof = transcript.finalize();
+
+        let mut transcript = Blake2bRead::<_, G1Affine, Challenge255<_>>::init(&proof[..]);
+        verify_proof::<KZGCommitmentScheme<Bn256>, VerifierSHPLONK<_>, _, _, _>(
+            untrimmed.verifier_params(),
+            &vk,
+            SingleStrategy::new(&untrimmed),
+            &[&[]],
+            &mut transcript,
+            untrimmed.n(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_corrupted_srs_is_detected() {
+        let tmp_dir = Builder::new().prefix("srs_corrupt").tempdir().unwrap();
+        let path = tmp_dir.path().join("kzg.srs");
+        let srs = gen_srs::<KZGCommitmentScheme<Bn256>>(4);
+        srs.check_integrity().unwrap();
+        crate::pfsys::save_params::<KZGCommitmentScheme<Bn256>>(&path, &srs).unwrap();
+
+        // flip a byte inside one of the powers of tau, past the k header and the first two points
+        let offset = 4 + 32 * 5 + 7;
+        let mut f = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(&path)
+            .unwrap();
+        let mut byte = [0u8; 1];
+        f.seek(SeekFrom::Start(offset)).unwrap();
+        f.read_exact(&mut byte).unwrap();
+        f.seek(SeekFrom::Start(offset)).unwrap();
+        f.write_all(&[byte[0] ^ 0x01]).unwrap();
+        drop(f);
+
+        // either the point no longer decodes or the full check catches it, the check on load only covers the first
+        // powers
+        match load_srs_prover::<KZGCommitmentScheme<Bn256>>(path) {
+            Ok(params) => {
+                params.check_consistency().unwrap();
+                assert!(matches!(
+                    params.check_integrity(),
+                    Err(SrsError::IntegrityError(_))
+                ))
+            }
+            Err(e) => assert!(matches!(e, SrsError::ReadError(_))),
+        }
+    }
+}
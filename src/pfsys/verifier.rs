@@ -0,0 +1,201 @@
+use super::errors::PfsysError;
+use super::evm::aggregation_kzg::PoseidonTranscript;
+use super::{verify_proof_circuit, Snark, TranscriptType};
+use crate::circuit::modules::ModulePlanner;
+use crate::graph::{GraphConfig, GraphSettings};
+use crate::{Commitments, EZKL_KEY_FORMAT};
+use halo2_proofs::circuit::Layouter;
+use halo2_proofs::plonk::{Circuit, ConstraintSystem, Error as PlonkError, VerifyingKey};
+use halo2_proofs::poly::commitment::{Params, ParamsProver};
+use halo2_proofs::poly::ipa::{
+    commitment::{IPACommitmentScheme, ParamsIPA},
+    multiopen::VerifierIPA,
+    strategy::SingleStrategy as IPASingleStrategy,
+};
+use halo2_proofs::poly::kzg::{
+    commitment::{KZGCommitmentScheme, ParamsKZG},
+    multiopen::VerifierSHPLONK,
+    strategy::SingleStrategy as KZGSingleStrategy,
+};
+use halo2_proofs::poly::VerificationStrategy;
+use halo2_proofs::transcript::{Blake2bRead, Challenge255};
+use halo2curves::bn256::{Bn256, Fr, G1Affine};
+use snark_verifier::loader::native::NativeLoader;
+use snark_verifier::system::halo2::transcript::evm::EvmTranscript;
+use std::io::BufReader;
+
+/// The parameters of the commitment scheme a circuit was set up with
+#[derive(Debug, Clone)]
+pub enum VerifierSrs {
+    /// The KZG parameters
+    KZG(ParamsKZG<Bn256>),
+    /// The IPA parameters
+    IPA(ParamsIPA<G1Affine>),
+}
+
+/// What proofs of a circuit are verified against besides its verifying key
+#[derive(Debug, Clone)]
+pub struct VerifierParams {
+    /// The settings of the circuit, which its constraint system is re-derived from when its verifying key is read and
+    /// which lay out its instances
+    pub settings: GraphSettings,
+    /// The parameters of the commitment scheme of the settings
+    pub srs: VerifierSrs,
+    /// The transcript the proofs were created with, [TranscriptType::EVM] as for `prove` unless set otherwise
+    pub transcript: TranscriptType,
+}
+
+impl VerifierParams {
+    /// Reads the settings .json of a circuit and its srs, in the format of the commitment scheme of the settings
+    pub fn from_bytes(settings: &[u8], srs: &[u8]) -> Result<Self, PfsysError> {
+        let settings: GraphSettings = serde_json::from_slice(settings)
+            .map_err(|e| PfsysError::LoadSettings(format!("{}", e)))?;
+        let mut reader = BufReader::new(srs);
+        let commitment: Commitments = settings.run_args.commitment.into();
+        let srs = match commitment {
+            Commitments::KZG => VerifierSrs::KZG(
+                ParamsKZG::<Bn256>::read(&mut reader)
+                    .map_err(|e| PfsysError::LoadSrs(format!("{}", e)))?,
+            ),
+            Commitments::IPA => VerifierSrs::IPA(
+                ParamsIPA::<G1Affine>::read(&mut reader)
+                    .map_err(|e| PfsysError::LoadSrs(format!("{}", e)))?,
+            ),
+        };
+        Ok(VerifierParams {
+            settings,
+            srs,
+            transcript: TranscriptType::default(),
+        })
+    }
+}
+
+/// The circuit of a model as far as reading its verifying key goes: the constraint system is re-derived from the
+/// settings alone, with the same columns and gates as [crate::graph::GraphCircuit], and nothing is ever laid out
+#[derive(Clone, Debug, Default)]
+pub struct VerifierCircuit {
+    settings: GraphSettings,
+}
+
+impl Circuit<Fr> for VerifierCircuit {
+    type Config = GraphConfig;
+    type FloorPlanner = ModulePlanner;
+    type Params = GraphSettings;
+
+    fn without_witnesses(&self) -> Self {
+        self.clone()
+    }
+
+    fn params(&self) -> Self::Params {
+        self.settings.clone()
+    }
+
+    fn configure_with_params(cs: &mut ConstraintSystem<Fr>, params: Self::Params) -> Self::Config {
+        GraphConfig::configure(cs, params)
+    }
+
+    fn configure(_: &mut ConstraintSystem<Fr>) -> Self::Config {
+        unimplemented!("you should call configure_with_params instead")
+    }
+
+    fn synthesize(&self, _: Self::Config, _: impl Layouter<Fr>) -> Result<(), PlonkError> {
+        // only the constraint system is needed to read a verifying key, there is no witness to lay out
+        Err(PlonkError::Synthesis)
+    }
+}
+
+/// Verifies the bytes of a proof against its instances, laid out as the first instances of the proof .json, with the
+/// bytes of the verifying key of the circuit. Unlike the rest of the crate this neither reads files nor needs the
+/// onnx model, such that it can be built with `--no-default-features --features verifier-only`.
+pub fn verify_proof_bytes(
+    vk_bytes: &[u8],
+    proof_bytes: &[u8],
+    instances: &[Fr],
+    params: &VerifierParams,
+) -> Result<(), PfsysError> {
+    let settings = &params.settings;
+    let expected = settings.total_instances().iter().sum::<usize>();
+    if instances.len() != expected {
+        return Err(PfsysError::InstanceCount {
+            expected,
+            actual: instances.len(),
+        });
+    }
+
+    let mut reader = BufReader::new(vk_bytes);
+    let vk = VerifyingKey::<G1Affine>::read::<_, VerifierCircuit>(
+        &mut reader,
+        super::serde_format_from_str(&EZKL_KEY_FORMAT),
+        settings.clone(),
+    )
+    .map_err(|e| PfsysError::LoadVk(format!("{}", e)))?;
+
+    // the instances of a circuit are in a single column
+    let snark = Snark::new(
+        None,
+        vec![instances.to_vec()],
+        proof_bytes.to_vec(),
+        None,
+        params.transcript,
+        None,
+        None,
+        settings.run_args.commitment,
+    );
+    let orig_n = 1 << settings.run_args.logrows;
+
+    match &params.srs {
+        VerifierSrs::KZG(srs) => {
+            let strategy = KZGSingleStrategy::new(srs.verifier_params());
+            match params.transcript {
+                TranscriptType::EVM => verify_proof_circuit::<
+                    VerifierSHPLONK<'_, Bn256>,
+                    KZGCommitmentScheme<Bn256>,
+                    KZGSingleStrategy<_>,
+                    _,
+                    EvmTranscript<G1Affine, _, _, _>,
+                >(&snark, srs, &vk, strategy, orig_n),
+                TranscriptType::Poseidon => verify_proof_circuit::<
+                    VerifierSHPLONK<'_, Bn256>,
+                    KZGCommitmentScheme<Bn256>,
+                    KZGSingleStrategy<_>,
+                    _,
+                    PoseidonTranscript<NativeLoader, _>,
+                >(&snark, srs, &vk, strategy, orig_n),
+                TranscriptType::Blake => verify_proof_circuit::<
+                    VerifierSHPLONK<'_, Bn256>,
+                    KZGCommitmentScheme<Bn256>,
+                    KZGSingleStrategy<_>,
+                    _,
+                    Blake2bRead<_, _, Challenge255<_>>,
+                >(&snark, srs, &vk, strategy, orig_n),
+            }
+        }
+        VerifierSrs::IPA(srs) => {
+            let strategy = IPASingleStrategy::new(srs.verifier_params());
+            match params.transcript {
+                TranscriptType::EVM => verify_proof_circuit::<
+                    VerifierIPA<_>,
+                    IPACommitmentScheme<G1Affine>,
+                    IPASingleStrategy<_>,
+                    _,
+                    EvmTranscript<G1Affine, _, _, _>,
+                >(&snark, srs, &vk, strategy, orig_n),
+                TranscriptType::Poseidon => verify_proof_circuit::<
+                    VerifierIPA<_>,
+                    IPACommitmentScheme<G1Affine>,
+                    IPASingleStrategy<_>,
+                    _,
+                    PoseidonTranscript<NativeLoader, _>,
+                >(&snark, srs, &vk, strategy, orig_n),
+                TranscriptType::Blake => verify_proof_circuit::<
+                    VerifierIPA<_>,
+                    IPACommitmentScheme<G1Affine>,
+                    IPASingleStrategy<_>,
+                    _,
+                    Blake2bRead<_, _, Challenge255<_>>,
+                >(&snark, srs, &vk, strategy, orig_n),
+            }
+        }
+    }?;
+    Ok(())
+}
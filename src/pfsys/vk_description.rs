@@ -0,0 +1,250 @@
+//! A description of a verifying key and of the instances of its circuit, for verifiers implemented outside of the
+//! crate.
+//!
+//! The description holds what a verifier needs besides the proof that the raw bytes of a key don't make explicit: the
+//! columns of the constraint system and where they are queried, the polynomial identities of its gates, the
+//! expressions of its lookup and shuffle arguments, the columns of its permutation argument and what each instance
+//! holds, run by run and instance by instance. The settings the key was generated with are included, such that the
+//! constraint system can be re-derived from the raw key bytes as [crate::pfsys::verifier::verify_proof_bytes] does.
+
+use crate::graph::instances::InstanceLabel;
+use crate::graph::{GraphSettings, InstanceSpan};
+use halo2_proofs::plonk::{Any, Column, ColumnType, Expression, VerifyingKey};
+use halo2_proofs::poly::Rotation;
+use halo2curves::bn256::{Fr as Fp, G1Affine};
+use serde::{Deserialize, Serialize};
+use tosubcommand::ToFlags;
+
+/// The formats a verifying key is described in
+#[derive(
+    Default, Copy, Clone, Debug, PartialEq, Eq, Deserialize, Serialize, PartialOrd, clap::ValueEnum,
+)]
+pub enum VkDescriptionFormat {
+    /// A json document
+    #[default]
+    Json,
+}
+
+impl std::fmt::Display for VkDescriptionFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                VkDescriptionFormat::Json => "json",
+            }
+        )
+    }
+}
+
+impl ToFlags for VkDescriptionFormat {
+    fn to_flags(&self) -> Vec<String> {
+        vec![format!("{}", self)]
+    }
+}
+
+/// The type of a column of the constraint system
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColumnKind {
+    /// a column assigned by the prover
+    Advice,
+    /// a column fixed at setup, the selectors included once compressed
+    Fixed,
+    /// a column of public instances
+    Instance,
+}
+
+/// A column of the constraint system
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ColumnDescription {
+    /// the type of the column
+    pub kind: ColumnKind,
+    /// the index of the column among the columns of its type
+    pub index: usize,
+}
+
+impl<C: ColumnType> From<Column<C>> for ColumnDescription
+where
+    Column<C>: Into<Column<Any>>,
+{
+    fn from(column: Column<C>) -> Self {
+        let column: Column<Any> = column.into();
+        let kind = match column.column_type() {
+            Any::Advice(_) => ColumnKind::Advice,
+            Any::Fixed => ColumnKind::Fixed,
+            Any::Instance => ColumnKind::Instance,
+        };
+        ColumnDescription {
+            kind,
+            index: column.index(),
+        }
+    }
+}
+
+/// A query of a column at a rotation, in the order the evaluations are read from the proof
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueryDescription {
+    /// the index of the column queried among the columns of its type
+    pub column: usize,
+    /// the rotation the column is queried at
+    pub rotation: i32,
+}
+
+impl<C: ColumnType> From<&(Column<C>, Rotation)> for QueryDescription {
+    fn from((column, rotation): &(Column<C>, Rotation)) -> Self {
+        QueryDescription {
+            column: column.index(),
+            rotation: rotation.0,
+        }
+    }
+}
+
+/// A gate of the constraint system, whose polynomials vanish on every row
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GateDescription {
+    /// the name of the gate
+    pub name: String,
+    /// the polynomial identities of the gate, as [Expression::identifier]
+    pub polynomials: Vec<String>,
+}
+
+/// A lookup argument, each row of its inputs being a row of its table
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LookupDescription {
+    /// the expressions of each of the inputs looked up, a single input without `mv-lookup`
+    pub inputs: Vec<Vec<String>>,
+    /// the expressions of the table
+    pub table: Vec<String>,
+}
+
+/// A shuffle argument, its inputs being a permutation of its shuffle
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShuffleDescription {
+    /// the expressions of the input
+    pub input: Vec<String>,
+    /// the expressions the input is a shuffle of
+    pub shuffle: Vec<String>,
+}
+
+/// A description of a verifying key and of the instances of its circuit, as written by `export-vk-description`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct VkDescription {
+    /// log2 of the number of rows
+    pub logrows: u32,
+    /// the degree of the constraint system
+    pub degree: usize,
+    /// the number of rows at the end of each column reserved for blinding
+    pub blinding_factors: usize,
+    /// number of advice columns
+    pub num_advice_columns: usize,
+    /// number of fixed columns, the compressed selectors included
+    pub num_fixed_columns: usize,
+    /// number of instance columns
+    pub num_instance_columns: usize,
+    /// the queries of the advice columns
+    pub advice_queries: Vec<QueryDescription>,
+    /// the queries of the fixed columns
+    pub fixed_queries: Vec<QueryDescription>,
+    /// the queries of the instance columns
+    pub instance_queries: Vec<QueryDescription>,
+    /// the gates
+    pub gates: Vec<GateDescription>,
+    /// the lookup arguments
+    pub lookups: Vec<LookupDescription>,
+    /// the shuffle arguments
+    pub shuffles: Vec<ShuffleDescription>,
+    /// the columns of the permutation argument, in the order of their permutation commitments
+    pub permutation_columns: Vec<ColumnDescription>,
+    /// the commitments of the fixed columns
+    pub fixed_commitments: Vec<G1Affine>,
+    /// the commitments of the permutation of each column of the permutation argument
+    pub permutation_commitments: Vec<G1Affine>,
+    /// the digest of the key the transcript is initialized with
+    pub transcript_repr: Fp,
+    /// the number of instances of each instance column
+    pub num_instances: Vec<usize>,
+    /// what the instances hold, in order
+    pub instances: Vec<InstanceSpan>,
+    /// each instance with its role, the input or output of the model it belongs to, its position in it and its scale
+    pub instance_map: Vec<InstanceLabel>,
+    /// the settings the key was generated with
+    pub settings: GraphSettings,
+}
+
+/// The identifiers of a list of expressions
+fn identifiers(expressions: &[Expression<Fp>]) -> Vec<String> {
+    expressions.iter().map(Expression::identifier).collect()
+}
+
+impl VkDescription {
+    /// Describe a verifying key generated with `settings`
+    pub fn new(vk: &VerifyingKey<G1Affine>, settings: &GraphSettings) -> Self {
+        let cs = vk.cs();
+
+        #[cfg(feature = "mv-lookup")]
+        let lookups = cs
+            .lookups()
+            .iter()
+            .map(|lookup| LookupDescription {
+                inputs: lookup
+                    .input_expressions()
+                    .iter()
+                    .map(|input| identifiers(input))
+                    .collect(),
+                table: identifiers(lookup.table_expressions()),
+            })
+            .collect();
+        #[cfg(not(feature = "mv-lookup"))]
+        let lookups = cs
+            .lookups()
+            .iter()
+            .map(|lookup| LookupDescription {
+                inputs: vec![identifiers(lookup.input_expressions())],
+                table: identifiers(lookup.table_expressions()),
+            })
+            .collect();
+
+        VkDescription {
+            logrows: settings.run_args.logrows,
+            degree: cs.degree(),
+            blinding_factors: cs.blinding_factors(),
+            num_advice_columns: cs.num_advice_columns(),
+            num_fixed_columns: cs.num_fixed_columns(),
+            num_instance_columns: cs.num_instance_columns(),
+            advice_queries: cs.advice_queries().iter().map(Into::into).collect(),
+            fixed_queries: cs.fixed_queries().iter().map(Into::into).collect(),
+            instance_queries: cs.instance_queries().iter().map(Into::into).collect(),
+            gates: cs
+                .gates()
+                .iter()
+                .map(|gate| GateDescription {
+                    name: gate.name().to_string(),
+                    polynomials: identifiers(gate.polynomials()),
+                })
+                .collect(),
+            lookups,
+            shuffles: cs
+                .shuffles()
+                .iter()
+                .map(|shuffle| ShuffleDescription {
+                    input: identifiers(shuffle.input_expressions()),
+                    shuffle: identifiers(shuffle.shuffle_expressions()),
+                })
+                .collect(),
+            permutation_columns: cs
+                .permutation()
+                .get_columns()
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            fixed_commitments: vk.fixed_commitments().clone(),
+            permutation_commitments: vk.permutation().commitments().to_vec(),
+            transcript_repr: vk.transcript_repr(),
+            num_instances: settings.total_instances(),
+            instances: settings.instance_layout(),
+            instance_map: settings.instance_map(),
+            settings: settings.clone(),
+        }
+    }
+}
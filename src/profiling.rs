@@ -0,0 +1,425 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::thread::ThreadId;
+
+use instant::Instant;
+use serde_json::{json, Value};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::Subscriber;
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+/// The name of a span in the profile: the name of its callsite followed by its fields, as `synthesis logrows=17`
+struct FrameName(String);
+
+/// Appends the fields of a span to its frame name
+struct FieldVisitor<'a>(&'a mut String);
+
+impl Visit for FieldVisitor<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        let _ = write!(self.0, " {}={}", field.name(), value);
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        let _ = write!(self.0, " {}={:?}", field.name(), value);
+    }
+}
+
+/// A span being entered (`open`) or exited on a thread, `at` nanoseconds after profiling started
+struct Event {
+    open: bool,
+    frame: usize,
+    at: u64,
+}
+
+/// The events of the spans entered on a thread, which nest as the spans are exited in the reverse order of entry
+#[derive(Default)]
+struct ThreadEvents {
+    name: String,
+    events: Vec<Event>,
+    /// The events of the spans entered and not yet exited
+    open: Vec<usize>,
+}
+
+#[derive(Default)]
+struct Profile {
+    frames: Vec<String>,
+    frame_ids: HashMap<String, usize>,
+    threads: Vec<ThreadEvents>,
+    thread_ids: HashMap<ThreadId, usize>,
+}
+
+impl Profile {
+    fn frame(&mut self, name: &str) -> usize {
+        if let Some(id) = self.frame_ids.get(name) {
+            return *id;
+        }
+        self.frames.push(name.to_string());
+        self.frame_ids
+            .insert(name.to_string(), self.frames.len() - 1);
+        self.frames.len() - 1
+    }
+
+    fn thread(&mut self) -> &mut ThreadEvents {
+        let thread = std::thread::current();
+        let idx = match self.thread_ids.get(&thread.id()) {
+            Some(idx) => *idx,
+            None => {
+                let name = match thread.name() {
+                    Some(name) => name.to_string(),
+                    None => format!("{:?}", thread.id()),
+                };
+                self.threads.push(ThreadEvents {
+                    name,
+                    ..Default::default()
+                });
+                self.thread_ids.insert(thread.id(), self.threads.len() - 1);
+                self.threads.len() - 1
+            }
+        };
+        &mut self.threads[idx]
+    }
+
+    fn enter(&mut self, name: &str, at: u64) {
+        let frame = self.frame(name);
+        let thread = self.thread();
+        thread.open.push(thread.events.len());
+        thread.events.push(Event {
+            open: true,
+            frame,
+            at,
+        });
+    }
+
+    /// Closes the last span entered on the thread, under the name it has by now as fields may have been recorded
+    /// since it was entered
+    fn exit(&mut self, name: &str, at: u64) {
+        let frame = self.frame(name);
+        let thread = self.thread();
+        if let Some(open) = thread.open.pop() {
+            thread.events[open].frame = frame;
+            thread.events.push(Event {
+                open: false,
+                frame,
+                at,
+            });
+        }
+    }
+
+    /// The profile in the evented format of speedscope, with one profile per thread and the spans still entered
+    /// closed at `end`
+    fn speedscope(&self, end: u64) -> Value {
+        let profiles = self
+            .threads
+            .iter()
+            .map(|thread| {
+                let event = |open: bool, frame: usize, at: u64| {
+                    json!({"type": if open { "O" } else { "C" }, "frame": frame, "at": at})
+                };
+                let mut events: Vec<Value> = thread
+                    .events
+                    .iter()
+                    .map(|e| event(e.open, e.frame, e.at))
+                    .collect();
+                for open in thread.open.iter().rev() {
+                    events.push(event(false, thread.events[*open].frame, end));
+                }
+                let end = if thread.open.is_empty() {
+                    thread.events.last().map_or(0, |e| e.at)
+                } else {
+                    end
+                };
+                json!({
+                    "type": "evented",
+                    "name": thread.name,
+                    "unit": "nanoseconds",
+                    "startValue": thread.events.first().map_or(0, |e| e.at),
+                    "endValue": end,
+                    "events": events,
+                })
+            })
+            .collect::<Vec<_>>();
+        json!({
+            "$schema": "https://www.speedscope.app/file-format-schema.json",
+            "exporter": format!("ezkl {}", env!("CARGO_PKG_VERSION")),
+            "name": "ezkl",
+            "activeProfileIndex": 0,
+            "shared": {
+                "frames": self.frames.iter().map(|name| json!({ "name": name })).collect::<Vec<_>>(),
+            },
+            "profiles": profiles,
+        })
+    }
+}
+
+/// A tracing layer recording when the spans are entered and exited on each thread
+struct ProfileLayer {
+    profile: Arc<Mutex<Profile>>,
+    start: Instant,
+}
+
+impl ProfileLayer {
+    fn now(&self) -> u64 {
+        self.start.elapsed().as_nanos() as u64
+    }
+}
+
+impl<S> Layer<S> for ProfileLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            let mut name = attrs.metadata().name().to_string();
+            attrs.record(&mut FieldVisitor(&mut name));
+            span.extensions_mut().insert(FrameName(name));
+        }
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            if let Some(FrameName(name)) = span.extensions_mut().get_mut::<FrameName>() {
+                values.record(&mut FieldVisitor(name));
+            }
+        }
+    }
+
+    fn on_enter(&self, id: &Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            if let Some(FrameName(name)) = span.extensions().get::<FrameName>() {
+                let at = self.now();
+                self.profile.lock().unwrap().enter(name, at);
+            }
+        }
+    }
+
+    fn on_exit(&self, id: &Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            if let Some(FrameName(name)) = span.extensions().get::<FrameName>() {
+                let at = self.now();
+                self.profile.lock().unwrap().exit(name, at);
+            }
+        }
+    }
+}
+
+/// The peak resident memory of the process since it was last reset, in bytes. Only Linux reports it.
+pub fn peak_rss() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|l| l.starts_with("VmHWM:"))?;
+    let kb = line.split_whitespace().nth(1)?.parse::<u64>().ok()?;
+    Some(kb * 1024)
+}
+
+/// Resets the peak resident memory to the current resident memory
+fn reset_peak_rss() {
+    // a no-op where it isn't supported (before Linux 4.0 and on other platforms)
+    let _ = std::fs::write("/proc/self/clear_refs", "5");
+}
+
+/// The memory available to start new processes without swapping, in bytes. Only Linux reports it.
+pub fn available_memory() -> Option<u64> {
+    let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+    let line = meminfo.lines().find(|l| l.starts_with("MemAvailable:"))?;
+    let kb = line.split_whitespace().nth(1)?.parse::<u64>().ok()?;
+    Some(kb * 1024)
+}
+
+/// The peak resident memory of the phases being measured so far, the innermost last
+static OPEN_PHASES: Mutex<Vec<u64>> = Mutex::new(Vec::new());
+
+/// The peak resident memory of each span that was exited, by the name of its callsite
+static PHASE_PEAKS: Mutex<BTreeMap<String, u64>> = Mutex::new(BTreeMap::new());
+
+/// Starts measuring the peak resident memory of a phase, nested in the phases being measured. The peak is reset for
+/// the phase to report its own, the peak so far going to the phases it is nested in.
+pub fn start_memory_phase() {
+    let mut open = OPEN_PHASES.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(peak) = peak_rss() {
+        for phase in open.iter_mut() {
+            *phase = (*phase).max(peak);
+        }
+    }
+    reset_peak_rss();
+    open.push(0);
+}
+
+/// Ends the innermost phase being measured, returning its peak resident memory in bytes if the platform reports it
+pub fn end_memory_phase() -> Option<u64> {
+    let mut open = OPEN_PHASES.lock().unwrap_or_else(|e| e.into_inner());
+    let phase = open.pop()?;
+    let peak = phase.max(peak_rss()?);
+    if let Some(parent) = open.last_mut() {
+        *parent = (*parent).max(peak);
+    }
+    Some(peak)
+}
+
+/// The peak resident memory of each phase (span) of the run, in bytes, by the name of the span, once the
+/// [Profiler] is started
+pub fn phase_peaks() -> BTreeMap<String, u64> {
+    PHASE_PEAKS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
+/// A tracing layer measuring the peak resident memory of each span while it is entered
+struct MemoryLayer;
+
+impl<S> Layer<S> for MemoryLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_enter(&self, _id: &Id, _ctx: Context<'_, S>) {
+        start_memory_phase();
+    }
+
+    fn on_exit(&self, id: &Id, ctx: Context<'_, S>) {
+        if let (Some(peak), Some(span)) = (end_memory_phase(), ctx.span(id)) {
+            let mut peaks = PHASE_PEAKS.lock().unwrap_or_else(|e| e.into_inner());
+            let phase = peaks.entry(span.name().to_string()).or_default();
+            *phase = (*phase).max(peak);
+        }
+    }
+}
+
+/// Collects the durations of the spans of all threads from [Profiler::start], to write them as a profile
+pub struct Profiler {
+    profile: Arc<Mutex<Profile>>,
+    start: Instant,
+}
+
+impl Profiler {
+    /// Installs the subscriber collecting the spans of the process, and the peak resident memory of each (see
+    /// [phase_peaks]). Without it the spans are disabled and cost no more than checking that no subscriber is
+    /// installed. Errors if a global subscriber is already installed
+    pub fn start() -> Result<Self, tracing::subscriber::SetGlobalDefaultError> {
+        let profile = Arc::new(Mutex::new(Profile::default()));
+        let start = Instant::now();
+        let layer = ProfileLayer {
+            profile: profile.clone(),
+            start,
+        };
+        tracing::subscriber::set_global_default(
+            tracing_subscriber::registry().with(layer).with(MemoryLayer),
+        )?;
+        Ok(Profiler { profile, start })
+    }
+
+    /// The spans collected so far, in the evented JSON format of speedscope (https://www.speedscope.app)
+    pub fn speedscope(&self) -> Value {
+        let end = self.start.elapsed().as_nanos() as u64;
+        self.profile.lock().unwrap().speedscope(end)
+    }
+
+    /// Writes the spans collected so far to a .json profile readable by speedscope
+    pub fn write(&self, path: &Path) -> Result<(), std::io::Error> {
+        std::fs::write(path, serde_json::to_vec(&self.speedscope())?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The (parent, child) pairs of the spans of a profile, by the names of their callsites
+    fn nesting(profile: &Value) -> Vec<(String, String)> {
+        let frames = profile["shared"]["frames"].as_array().unwrap();
+        let name = |frame: &Value| {
+            let name = frames[frame.as_u64().unwrap() as usize]["name"]
+                .as_str()
+                .unwrap();
+            name.split(' ').next().unwrap().to_string()
+        };
+        let mut pairs = vec![];
+        for thread in profile["profiles"].as_array().unwrap() {
+            let mut stack = vec![];
+            for event in thread["events"].as_array().unwrap() {
+                if event["type"] == "O" {
+                    if let Some(parent) = stack.last() {
+                        pairs.push((parent.clone(), name(&event["frame"])));
+                    }
+                    stack.push(name(&event["frame"]));
+                } else {
+                    assert_eq!(stack.pop(), Some(name(&event["frame"])));
+                }
+            }
+            assert!(stack.is_empty());
+        }
+        pairs
+    }
+
+    #[test]
+    fn nests_spans_with_their_recorded_fields() {
+        let profile = Arc::new(Mutex::new(Profile::default()));
+        let layer = ProfileLayer {
+            profile: profile.clone(),
+            start: Instant::now(),
+        };
+        tracing::subscriber::with_default(tracing_subscriber::registry().with(layer), || {
+            let _prove = tracing::info_span!("prove").entered();
+            let synthesis =
+                tracing::info_span!("synthesis", logrows = 4, rows = tracing::field::Empty);
+            synthesis.in_scope(|| {
+                synthesis.record("rows", 12);
+            });
+            let _finalize = tracing::info_span!("finalize").entered();
+        });
+        let value = profile.lock().unwrap().speedscope(u64::MAX);
+
+        assert_eq!(
+            nesting(&value),
+            vec![
+                ("prove".to_string(), "synthesis".to_string()),
+                ("prove".to_string(), "finalize".to_string())
+            ]
+        );
+        // the open event takes the name the span has when it's exited, with the fields recorded while it was entered
+        let frames = value["shared"]["frames"].as_array().unwrap();
+        let events = value["profiles"][0]["events"].as_array().unwrap();
+        assert_eq!(events.len(), 6);
+        assert_eq!(
+            frames[events[1]["frame"].as_u64().unwrap() as usize]["name"],
+            "synthesis logrows=4 rows=12"
+        );
+        assert!(events
+            .windows(2)
+            .all(|w| w[0]["at"].as_u64() <= w[1]["at"].as_u64()));
+    }
+
+    #[test]
+    fn nested_phases_report_their_peaks() {
+        start_memory_phase();
+        start_memory_phase();
+        let held = vec![1u8; 64 << 20];
+        let inner = end_memory_phase();
+        drop(held);
+        start_memory_phase();
+        let after = end_memory_phase();
+        let outer = end_memory_phase();
+        if let (Some(inner), Some(after), Some(outer)) = (inner, after, outer) {
+            // the outer phase reports the peak of the phases nested in it, though they reset the peak
+            assert!(outer >= inner && outer >= after);
+            assert!(inner >= 64 << 20);
+        }
+    }
+
+    #[test]
+    fn closes_the_spans_still_entered() {
+        let mut profile = Profile::default();
+        profile.enter("prove", 1);
+        profile.enter("create_proof", 2);
+        profile.exit("create_proof", 3);
+        let value = profile.speedscope(10);
+        let events = value["profiles"][0]["events"].as_array().unwrap();
+        assert_eq!(events.len(), 4);
+        assert_eq!(events[3]["type"], "C");
+        assert_eq!(value["profiles"][0]["startValue"], 1);
+        assert_eq!(value["profiles"][0]["endValue"], 10);
+    }
+}
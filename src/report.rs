@@ -0,0 +1,461 @@
+use crate::circuit::ops::Op;
+use crate::circuit::table::Range;
+use crate::fieldutils::IntegerRep;
+use crate::graph::profile::CircuitProfile;
+use crate::graph::{GraphCircuit, GraphSettings, InstanceSpan};
+use crate::EZKLError;
+use halo2curves::bn256::Fr as Fp;
+use serde::{Deserialize, Serialize};
+use tosubcommand::ToFlags;
+
+/// The template of the markdown report
+const REPORT_MD: &str = include_str!("../templates/report.md");
+/// The template of the html report
+const REPORT_HTML: &str = include_str!("../templates/report.html");
+
+/// The formats a report is rendered in
+#[derive(
+    Default, Copy, Clone, Debug, PartialEq, Eq, Deserialize, Serialize, PartialOrd, clap::ValueEnum,
+)]
+pub enum ReportFormat {
+    /// GitHub flavored markdown
+    #[default]
+    Markdown,
+    /// A standalone html page
+    Html,
+}
+
+impl std::fmt::Display for ReportFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                ReportFormat::Markdown => "markdown",
+                ReportFormat::Html => "html",
+            }
+        )
+    }
+}
+
+impl ToFlags for ReportFormat {
+    fn to_flags(&self) -> Vec<String> {
+        vec![format!("{}", self)]
+    }
+}
+
+/// A lookup table or range check of a circuit
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TableReport {
+    /// the op of the table, or `range check`
+    pub name: String,
+    /// the inclusive range of the inputs of the table
+    pub range: Range,
+}
+
+impl TableReport {
+    /// The number of inputs in the range of the table
+    pub fn num_values(&self) -> IntegerRep {
+        self.range.1 - self.range.0 + 1
+    }
+}
+
+/// A proof of a circuit, and whether it verified
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ProofReport {
+    /// the size of the proof, in bytes
+    pub size: usize,
+    /// the number of instances the proof is verified against
+    pub num_instances: usize,
+    /// the transcript the proof was created with
+    pub transcript: String,
+    /// whether the proof verified against the verifying key, if one was given
+    pub verified: Option<bool>,
+    /// why the proof didn't verify
+    pub verification_error: Option<String>,
+}
+
+/// What a circuit proves and how, for audits
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CircuitReport {
+    /// the ezkl version the settings were generated with
+    pub version: String,
+    /// the sha256 digest of the compiled circuit
+    pub model_fingerprint: String,
+    /// the fingerprint of the settings, as in [crate::eth::settings_fingerprint]
+    pub settings_fingerprint: String,
+    /// the settings of the circuit
+    pub settings: GraphSettings,
+    /// the rows, lookups and equality constraints laid out by each op of the model
+    pub profile: CircuitProfile,
+    /// the lookup tables and range checks of the circuit
+    pub tables: Vec<TableReport>,
+    /// the instances of the circuit, labelled with what they hold
+    pub instances: Vec<InstanceSpan>,
+    /// the proof the report is about, if any
+    pub proof: Option<ProofReport>,
+}
+
+impl CircuitReport {
+    /// Reports on a circuit from its settings and the compiled circuit, the bytes of the compiled
+    /// circuit being fingerprinted
+    pub fn new(
+        settings: &GraphSettings,
+        circuit: &GraphCircuit,
+        compiled_circuit: &[u8],
+        proof: Option<ProofReport>,
+    ) -> Result<Self, EZKLError> {
+        let profile = circuit.model().profile(&settings.run_args)?;
+        let lookups = settings.required_lookups.iter().map(|op| TableReport {
+            name: Op::<Fp>::as_string(op),
+            range: settings.run_args.lookup_range,
+        });
+        let range_checks = settings.required_range_checks.iter().map(|r| TableReport {
+            name: "range check".to_string(),
+            range: *r,
+        });
+
+        Ok(CircuitReport {
+            version: settings.version.clone(),
+            model_fingerprint: sha256::digest(compiled_circuit),
+            settings_fingerprint: format!("{:?}", crate::eth::settings_fingerprint(settings)?),
+            settings: settings.clone(),
+            profile,
+            tables: lookups.chain(range_checks).collect(),
+            instances: settings.instance_layout(),
+            proof,
+        })
+    }
+
+    /// Renders the report in a format
+    pub fn render(&self, format: ReportFormat) -> String {
+        match format {
+            ReportFormat::Markdown => self.render_with::<Markdown>(),
+            ReportFormat::Html => self.render_with::<Html>(),
+        }
+    }
+
+    fn render_with<R: Renderer>(&self) -> String {
+        let settings = &self.settings;
+        let run_args = &settings.run_args;
+        let commitment: crate::Commitments = run_args.commitment.into();
+
+        let summary = R::table(
+            &["Property", "Value"],
+            &[
+                row(["logrows".into(), run_args.logrows.to_string()]),
+                row(["rows".into(), self.profile.total_rows.to_string()]),
+                row([
+                    "assignments".into(),
+                    self.profile.total_assignments.to_string(),
+                ]),
+                row(["lookups".into(), self.profile.total_lookups.to_string()]),
+                row([
+                    "equality constraints".into(),
+                    self.profile.total_equalities.to_string(),
+                ]),
+                row(["commitment".into(), commitment.to_string()]),
+                row(["check mode".into(), settings.check_mode.to_string()]),
+                row([
+                    "instances".into(),
+                    settings.total_instances().iter().sum::<usize>().to_string(),
+                ]),
+            ],
+        );
+
+        let mut visibility = vec![
+            row(["inputs".into(), run_args.input_visibility.to_string()]),
+            row(["params".into(), run_args.param_visibility.to_string()]),
+        ];
+        for i in 0..settings.model_output_scales.len() {
+            let revealed = match run_args.revealed_outputs.contains(&i) {
+                true => " (revealed)",
+                false => "",
+            };
+            visibility.push(row([
+                format!("output {}", i),
+                format!("{}{}", run_args.visibility_of_output(i), revealed),
+            ]));
+        }
+        if let Some(signature) = &settings.input_signature {
+            visibility.push(row([
+                "signed inputs".into(),
+                format!("{:?}", signature.inputs),
+            ]));
+        }
+        if let Some(encryption) = &settings.output_encryption {
+            visibility.push(row([
+                "encrypted outputs".into(),
+                format!("{:?}", encryption.outputs),
+            ]));
+        }
+        if settings.session {
+            visibility.push(row(["session digests".into(), "exposed".into()]));
+        }
+        if settings.nullifier {
+            visibility.push(row(["nullifier".into(), "exposed".into()]));
+        }
+        for tensor in &settings.packed_tensors {
+            let kind = if tensor.output { "output" } else { "input" };
+            visibility.push(row([
+                format!("{} {}", kind, tensor.idx),
+                format!("packed, {} values", tensor.len),
+            ]));
+        }
+        for tensor in &settings.limb_packed_outputs {
+            visibility.push(row([
+                format!("output {}", tensor.idx),
+                format!(
+                    "public, {} values packed {} per instance",
+                    tensor.len, tensor.limbs
+                ),
+            ]));
+        }
+        let visibility = R::table(&["Item", "Visibility"], &visibility);
+
+        let instances = R::table(
+            &["Indices", "Count", "Holds"],
+            &self
+                .instances
+                .iter()
+                .map(|s| {
+                    row([
+                        format!("{}..{}", s.start, s.start + s.len),
+                        s.len.to_string(),
+                        s.label.clone(),
+                    ])
+                })
+                .collect::<Vec<_>>(),
+        );
+
+        let ops = R::table(
+            &[
+                "Node",
+                "Op",
+                "Rows",
+                "Share",
+                "Assignments",
+                "Lookups",
+                "Equalities",
+            ],
+            &self
+                .profile
+                .ops
+                .iter()
+                .map(|op| {
+                    vec![
+                        op.node.to_string(),
+                        op.op.clone(),
+                        op.rows.to_string(),
+                        format!("{:.2}%", 100.0 * self.profile.row_share(op)),
+                        op.assignments.to_string(),
+                        op.lookups.to_string(),
+                        op.equalities.to_string(),
+                    ]
+                })
+                .collect::<Vec<_>>(),
+        );
+
+        let tables = match self.tables.is_empty() {
+            true => R::paragraph("The circuit has no lookup tables or range checks."),
+            false => R::table(
+                &["Table", "Range", "Values"],
+                &self
+                    .tables
+                    .iter()
+                    .map(|t| {
+                        row([
+                            t.name.clone(),
+                            format!("[{}, {}]", t.range.0, t.range.1),
+                            t.num_values().to_string(),
+                        ])
+                    })
+                    .collect::<Vec<_>>(),
+            ),
+        };
+
+        let proof = match &self.proof {
+            None => R::paragraph("No proof was given."),
+            Some(proof) => {
+                let verification = match (proof.verified, &proof.verification_error) {
+                    (None, _) => "not checked, no verifying key was given".to_string(),
+                    (Some(true), _) => "verified".to_string(),
+                    (Some(false), Some(e)) => format!("failed: {}", e),
+                    (Some(false), None) => "failed".to_string(),
+                };
+                R::table(
+                    &["Property", "Value"],
+                    &[
+                        row(["size".into(), format!("{} bytes", proof.size)]),
+                        row(["instances".into(), proof.num_instances.to_string()]),
+                        row(["transcript".into(), proof.transcript.clone()]),
+                        row(["verification".into(), verification]),
+                    ],
+                )
+            }
+        };
+
+        let sections = [
+            ("version", R::escape(&self.version)),
+            ("model_fingerprint", R::code(&self.model_fingerprint)),
+            ("settings_fingerprint", R::code(&self.settings_fingerprint)),
+            ("summary", summary),
+            ("visibility", visibility),
+            ("instances", instances),
+            ("ops", ops),
+            ("tables", tables),
+            ("proof", proof),
+        ];
+        sections
+            .iter()
+            .fold(R::TEMPLATE.to_string(), |document, (name, section)| {
+                document.replace(&format!("{{{{{}}}}}", name), section)
+            })
+    }
+}
+
+fn row<const N: usize>(cells: [String; N]) -> Vec<String> {
+    cells.to_vec()
+}
+
+/// A format the sections of a report are rendered in. A format is added with its template in
+/// `templates/` and a renderer of its tables and text.
+trait Renderer {
+    /// The document, whose `{{section}}` placeholders are replaced by the rendered sections
+    const TEMPLATE: &'static str;
+    /// Escapes text for the format
+    fn escape(text: &str) -> String;
+    /// Renders text as code
+    fn code(text: &str) -> String;
+    /// Renders a paragraph of text
+    fn paragraph(text: &str) -> String;
+    /// Renders a table, escaping its cells
+    fn table(headers: &[&str], rows: &[Vec<String>]) -> String;
+}
+
+struct Markdown;
+
+impl Renderer for Markdown {
+    const TEMPLATE: &'static str = REPORT_MD;
+
+    fn escape(text: &str) -> String {
+        text.replace('|', "\\|")
+    }
+
+    fn code(text: &str) -> String {
+        format!("`{}`", text)
+    }
+
+    fn paragraph(text: &str) -> String {
+        Self::escape(text)
+    }
+
+    fn table(headers: &[&str], rows: &[Vec<String>]) -> String {
+        let line = |cells: Vec<String>| format!("| {} |", cells.join(" | "));
+        let mut lines = vec![
+            line(headers.iter().map(|h| Self::escape(h)).collect()),
+            line(headers.iter().map(|_| "---".to_string()).collect()),
+        ];
+        lines.extend(
+            rows.iter()
+                .map(|r| line(r.iter().map(|c| Self::escape(c)).collect())),
+        );
+        lines.join("\n")
+    }
+}
+
+struct Html;
+
+impl Renderer for Html {
+    const TEMPLATE: &'static str = REPORT_HTML;
+
+    fn escape(text: &str) -> String {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+    }
+
+    fn code(text: &str) -> String {
+        format!("<code>{}</code>", Self::escape(text))
+    }
+
+    fn paragraph(text: &str) -> String {
+        format!("<p>{}</p>", Self::escape(text))
+    }
+
+    fn table(headers: &[&str], rows: &[Vec<String>]) -> String {
+        let line = |tag: &str, cells: Vec<String>| {
+            let cells = cells
+                .iter()
+                .map(|c| format!("<{}>{}</{}>", tag, Self::escape(c), tag))
+                .collect::<String>();
+            format!("<tr>{}</tr>", cells)
+        };
+        let mut lines = vec!["<table>".to_string()];
+        lines.push(line("th", headers.iter().map(|h| h.to_string()).collect()));
+        lines.extend(rows.iter().map(|r| line("td", r.clone())));
+        lines.push("</table>".to_string());
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RunArgs;
+
+    fn example_report(proof: Option<ProofReport>) -> CircuitReport {
+        let mut circuit = GraphCircuit::from_run_args(
+            &RunArgs::default(),
+            std::path::Path::new("examples/onnx/1l_relu/network.onnx"),
+        )
+        .unwrap();
+        // the timestamp would change the fingerprint of the compiled circuit on every run
+        circuit.settings_mut().timestamp = None;
+        let compiled = bincode::serialize(&circuit).unwrap();
+        let settings = circuit.settings().clone();
+        CircuitReport::new(&settings, &circuit, &compiled, proof).unwrap()
+    }
+
+    #[test]
+    fn markdown_report_of_example_model() {
+        let markdown = example_report(None).render(ReportFormat::Markdown);
+
+        // set EZKL_UPDATE_SNAPSHOTS to write the snapshot after an intended change of the report
+        let snapshot = "tests/assets/report.md";
+        if std::env::var("EZKL_UPDATE_SNAPSHOTS").is_ok()
+            || !std::path::Path::new(snapshot).exists()
+        {
+            std::fs::write(snapshot, &markdown).unwrap();
+        }
+        assert_eq!(markdown, std::fs::read_to_string(snapshot).unwrap());
+    }
+
+    #[test]
+    fn report_renders_proof_and_escapes_cells() {
+        let report = example_report(Some(ProofReport {
+            size: 1024,
+            num_instances: 4,
+            transcript: "evm".into(),
+            verified: Some(false),
+            verification_error: Some("<constraint> | not satisfied".into()),
+        }));
+
+        let markdown = report.render(ReportFormat::Markdown);
+        assert!(!markdown.contains("{{"));
+        assert!(markdown.contains("| size | 1024 bytes |"));
+        assert!(markdown.contains("| verification | failed: <constraint> \\| not satisfied |"));
+        let outputs = &report.instances[0];
+        assert_eq!((outputs.start, outputs.label.as_str()), (0, "output 0"));
+        assert!(markdown.contains(&format!(
+            "| 0..{} | {} | output 0 |",
+            outputs.len, outputs.len
+        )));
+
+        let html = report.render(ReportFormat::Html);
+        assert!(!html.contains("{{"));
+        assert!(html.contains("<td>failed: &lt;constraint&gt; | not satisfied</td>"));
+        assert!(html.contains("<tr><th>Item</th><th>Visibility</th></tr>"));
+    }
+}
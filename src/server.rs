@@ -0,0 +1,508 @@
+use crate::circuit::region::RegionSettings;
+use crate::commands::{DEFAULT_COMPILED_CIRCUIT, DEFAULT_PK, DEFAULT_VK};
+use crate::execute::{get_file_hash, load_params_prover};
+use crate::graph::input::GraphData;
+use crate::graph::{GraphCircuit, GraphWitness};
+use crate::pfsys::evm::aggregation_kzg::PoseidonTranscript;
+use crate::pfsys::{
+    create_proof_circuit, load_pk, verify_proof_circuit, ProofSplitCommit, Snark, TranscriptType,
+};
+use crate::{Commitments, EZKLError};
+use axum::extract::rejection::JsonRejection;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use halo2_proofs::plonk::ProvingKey;
+use halo2_proofs::poly::commitment::ParamsProver;
+use halo2_proofs::poly::kzg::commitment::{KZGCommitmentScheme, ParamsKZG};
+use halo2_proofs::poly::kzg::multiopen::{ProverSHPLONK, VerifierSHPLONK};
+use halo2_proofs::poly::kzg::strategy::SingleStrategy;
+use halo2_proofs::poly::VerificationStrategy;
+use halo2_proofs::transcript::{Blake2bRead, Blake2bWrite, Challenge255};
+use halo2curves::bn256::{Bn256, Fr, G1Affine};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use snark_verifier::loader::native::NativeLoader;
+use snark_verifier::system::halo2::transcript::evm::EvmTranscript;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use thiserror::Error;
+use tokio::sync::Semaphore;
+
+/// The SRS a set of artifacts may hold, without it the SRS of its logrows is loaded from $EZKL_REPO_PATH/srs
+pub const SERVER_SRS: &str = "kzg.srs";
+
+/// An error of a request, returned as a JSON [ErrorResponse]
+#[derive(Debug, Error)]
+pub enum ServerError {
+    /// No set of artifacts has the requested fingerprint
+    #[error("no artifacts with fingerprint {0}")]
+    UnknownArtifacts(String),
+    /// The body of the request is malformed
+    #[error("invalid request: {0}")]
+    InvalidRequest(String),
+    /// A worker failed before completing the request
+    #[error("worker failed: {0}")]
+    WorkerFailed(String),
+    /// Loading the artifacts, generating the witness, proving or verifying failed
+    #[error("{0}")]
+    Execution(#[from] EZKLError),
+}
+
+impl ServerError {
+    /// The kind of the error, as reported in [ErrorResponse::error]
+    fn kind(&self) -> &'static str {
+        match self {
+            ServerError::UnknownArtifacts(_) => "unknown_artifacts",
+            ServerError::InvalidRequest(_) => "invalid_request",
+            ServerError::WorkerFailed(_) => "worker_failed",
+            ServerError::Execution(_) => "execution_failed",
+        }
+    }
+
+    fn status(&self) -> StatusCode {
+        match self {
+            ServerError::UnknownArtifacts(_) => StatusCode::NOT_FOUND,
+            ServerError::InvalidRequest(_) => StatusCode::BAD_REQUEST,
+            ServerError::WorkerFailed(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            ServerError::Execution(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        }
+    }
+}
+
+impl From<JsonRejection> for ServerError {
+    fn from(rejection: JsonRejection) -> Self {
+        ServerError::InvalidRequest(rejection.body_text())
+    }
+}
+
+impl From<crate::graph::errors::GraphError> for ServerError {
+    fn from(e: crate::graph::errors::GraphError) -> Self {
+        ServerError::Execution(e.into())
+    }
+}
+
+impl IntoResponse for ServerError {
+    fn into_response(self) -> Response {
+        let body = ErrorResponse {
+            error: self.kind().to_string(),
+            message: self.to_string(),
+        };
+        (self.status(), Json(body)).into_response()
+    }
+}
+
+/// The body of a failed request
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ErrorResponse {
+    /// the kind of the error, one of `unknown_artifacts`, `invalid_request`, `worker_failed` or `execution_failed`
+    pub error: String,
+    /// what went wrong
+    pub message: String,
+}
+
+/// A set of artifacts the server can use, as listed by `GET /artifacts`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArtifactsEntry {
+    /// the fingerprint requests select the set by
+    pub fingerprint: String,
+    /// the name of the subdirectory of the set
+    pub name: String,
+    /// whether the set is loaded in memory
+    pub loaded: bool,
+}
+
+/// The body of `POST /gen-witness`, which responds with the [GraphWitness]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenWitnessRequest {
+    /// the fingerprint of the artifacts
+    pub artifacts: String,
+    /// the input data
+    pub input: GraphData,
+}
+
+/// The body of `POST /prove`, which responds with the [Snark]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProveRequest {
+    /// the fingerprint of the artifacts
+    pub artifacts: String,
+    /// the witness, as returned by `POST /gen-witness`
+    pub witness: GraphWitness,
+    /// the transcript of the proof, evm if none is given
+    #[serde(default)]
+    pub transcript: Option<TranscriptType>,
+}
+
+/// The body of `POST /verify`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerifyRequest {
+    /// the fingerprint of the artifacts
+    pub artifacts: String,
+    /// the proof, as returned by `POST /prove`
+    pub proof: Snark<Fr, G1Affine>,
+}
+
+/// The response of `POST /verify`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VerifyResponse {
+    /// whether the proof verified
+    pub verified: bool,
+}
+
+/// The compiled circuit, proving key and SRS of a set of artifacts
+struct LoadedArtifacts {
+    circuit: GraphCircuit,
+    pk: ProvingKey<G1Affine>,
+    params: ParamsKZG<Bn256>,
+}
+
+/// A subdirectory of the artifacts directory, loaded on its first request and kept in memory after
+struct ArtifactSet {
+    name: String,
+    dir: PathBuf,
+    loaded: Mutex<Option<Arc<LoadedArtifacts>>>,
+}
+
+impl ArtifactSet {
+    /// The fingerprint of a set is the hash of its compiled circuit and verification key, which change with the model,
+    /// the settings and the keys
+    fn fingerprint(dir: &Path) -> Result<String, EZKLError> {
+        let compiled = get_file_hash(&dir.join(DEFAULT_COMPILED_CIRCUIT))?;
+        let vk = get_file_hash(&dir.join(DEFAULT_VK))?;
+        Ok(sha256::digest(format!("{}{}", compiled, vk)))
+    }
+
+    /// Whether the set is loaded, without waiting for a load in progress
+    fn is_loaded(&self) -> bool {
+        self.loaded.try_lock().is_ok_and(|loaded| loaded.is_some())
+    }
+
+    /// Loads the set if it isn't loaded yet, this blocks
+    fn load(&self) -> Result<Arc<LoadedArtifacts>, EZKLError> {
+        let mut loaded = self.loaded.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(artifacts) = loaded.as_ref() {
+            return Ok(artifacts.clone());
+        }
+
+        info!("loading the artifacts of {}...", self.name);
+        let circuit = GraphCircuit::load(self.dir.join(DEFAULT_COMPILED_CIRCUIT))?;
+        let run_args = &circuit.settings().run_args;
+        let commitment: Commitments = run_args.commitment.into();
+        if !matches!(commitment, Commitments::KZG) {
+            return Err(format!("{} doesn't use the kzg commitment", self.name).into());
+        }
+        let srs_path = Some(self.dir.join(SERVER_SRS)).filter(|path| path.exists());
+        let params = load_params_prover::<KZGCommitmentScheme<Bn256>>(
+            srs_path,
+            run_args.logrows,
+            Commitments::KZG,
+        )?;
+        let pk = load_pk::<KZGCommitmentScheme<Bn256>, GraphCircuit>(
+            self.dir.join(DEFAULT_PK),
+            circuit.params(),
+        )?;
+
+        let artifacts = Arc::new(LoadedArtifacts {
+            circuit,
+            pk,
+            params,
+        });
+        *loaded = Some(artifacts.clone());
+        Ok(artifacts)
+    }
+}
+
+#[derive(Clone)]
+struct ServerState {
+    artifacts: Arc<BTreeMap<String, Arc<ArtifactSet>>>,
+    workers: Arc<Semaphore>,
+}
+
+impl ServerState {
+    fn artifact_set(&self, fingerprint: &str) -> Result<Arc<ArtifactSet>, ServerError> {
+        self.artifacts
+            .get(fingerprint)
+            .cloned()
+            .ok_or_else(|| ServerError::UnknownArtifacts(fingerprint.to_string()))
+    }
+
+    /// Runs blocking work once a worker is free
+    async fn run<T: Send + 'static>(
+        &self,
+        work: impl FnOnce() -> Result<T, ServerError> + Send + 'static,
+    ) -> Result<T, ServerError> {
+        let _permit = self
+            .workers
+            .acquire()
+            .await
+            .map_err(|e| ServerError::WorkerFailed(e.to_string()))?;
+        tokio::task::spawn_blocking(work)
+            .await
+            .map_err(|e| ServerError::WorkerFailed(e.to_string()))?
+    }
+
+    async fn load(&self, fingerprint: &str) -> Result<Arc<LoadedArtifacts>, ServerError> {
+        let set = self.artifact_set(fingerprint)?;
+        self.run(move || Ok(set.load()?)).await
+    }
+}
+
+/// Finds the sets of artifacts, the subdirectories holding a compiled circuit, a proving key and a verification key
+fn find_artifacts(dir: &Path) -> Result<BTreeMap<String, Arc<ArtifactSet>>, EZKLError> {
+    let mut artifacts = BTreeMap::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let complete = [DEFAULT_COMPILED_CIRCUIT, DEFAULT_PK, DEFAULT_VK]
+            .iter()
+            .all(|file| path.join(file).is_file());
+        if !complete {
+            continue;
+        }
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let fingerprint = ArtifactSet::fingerprint(&path)?;
+        info!("serving {} as {}", name, fingerprint);
+        if let Some(duplicate) = artifacts.insert(
+            fingerprint.clone(),
+            Arc::new(ArtifactSet {
+                name: name.clone(),
+                dir: path,
+                loaded: Mutex::new(None),
+            }),
+        ) {
+            warn!(
+                "{} and {} have the same fingerprint, serving {}",
+                duplicate.name, name, name
+            );
+        }
+    }
+    if artifacts.is_empty() {
+        return Err(format!(
+            "no subdirectory of {} holds a {}, {} and {}",
+            dir.display(),
+            DEFAULT_COMPILED_CIRCUIT,
+            DEFAULT_PK,
+            DEFAULT_VK
+        )
+        .into());
+    }
+    Ok(artifacts)
+}
+
+async fn list_artifacts(State(state): State<ServerState>) -> Json<Vec<ArtifactsEntry>> {
+    Json(
+        state
+            .artifacts
+            .iter()
+            .map(|(fingerprint, set)| ArtifactsEntry {
+                fingerprint: fingerprint.clone(),
+                name: set.name.clone(),
+                loaded: set.is_loaded(),
+            })
+            .collect(),
+    )
+}
+
+async fn gen_witness(
+    State(state): State<ServerState>,
+    request: Result<Json<GenWitnessRequest>, JsonRejection>,
+) -> Result<Json<GraphWitness>, ServerError> {
+    let Json(request) = request?;
+    let artifacts = state.load(&request.artifacts).await?;
+
+    let mut circuit = artifacts.circuit.clone();
+    let mut input = circuit.load_graph_input(&request.input).await?;
+    let witness = state
+        .run(move || {
+            let run_args = &circuit.settings().run_args;
+            let region_settings =
+                RegionSettings::all_true(run_args.decomp_base, run_args.decomp_legs);
+            let srs = circuit
+                .settings()
+                .module_requires_polycommit()
+                .then_some(&artifacts.params);
+            Ok(circuit.forward::<KZGCommitmentScheme<Bn256>>(
+                &mut input,
+                Some(artifacts.pk.get_vk()),
+                srs,
+                region_settings,
+            )?)
+        })
+        .await?;
+    Ok(Json(witness))
+}
+
+async fn prove(
+    State(state): State<ServerState>,
+    request: Result<Json<ProveRequest>, JsonRejection>,
+) -> Result<Json<Snark<Fr, G1Affine>>, ServerError> {
+    let Json(request) = request?;
+    let artifacts = state.load(&request.artifacts).await?;
+
+    let snark = state
+        .run(move || {
+            let witness = request.witness;
+            let mut circuit = artifacts.circuit.clone();
+            circuit.load_graph_witness(&witness)?;
+            let pretty_public_inputs = circuit.pretty_public_inputs(&witness)?;
+            let public_inputs = circuit.prepare_public_inputs(&witness)?;
+            let check_mode = circuit.settings().run_args.check_mode;
+            let pinned_block = witness.pinned_block.clone();
+            let split: Option<ProofSplitCommit> = witness.into();
+
+            let transcript = request.transcript.unwrap_or_default();
+            let (params, pk) = (&artifacts.params, &artifacts.pk);
+            let mut snark = match transcript {
+                TranscriptType::EVM => create_proof_circuit::<
+                    KZGCommitmentScheme<Bn256>,
+                    _,
+                    ProverSHPLONK<_>,
+                    VerifierSHPLONK<_>,
+                    SingleStrategy<_>,
+                    _,
+                    EvmTranscript<_, _, _, _>,
+                    EvmTranscript<_, _, _, _>,
+                >(
+                    circuit,
+                    vec![public_inputs],
+                    params,
+                    pk,
+                    check_mode,
+                    Commitments::KZG,
+                    transcript,
+                    split,
+                    None,
+                ),
+                TranscriptType::Poseidon => create_proof_circuit::<
+                    KZGCommitmentScheme<Bn256>,
+                    _,
+                    ProverSHPLONK<_>,
+                    VerifierSHPLONK<_>,
+                    SingleStrategy<_>,
+                    _,
+                    PoseidonTranscript<NativeLoader, _>,
+                    PoseidonTranscript<NativeLoader, _>,
+                >(
+                    circuit,
+                    vec![public_inputs],
+                    params,
+                    pk,
+                    check_mode,
+                    Commitments::KZG,
+                    transcript,
+                    split,
+                    None,
+                ),
+                TranscriptType::Blake => create_proof_circuit::<
+                    KZGCommitmentScheme<Bn256>,
+                    _,
+                    ProverSHPLONK<_>,
+                    VerifierSHPLONK<_>,
+                    SingleStrategy<_>,
+                    _,
+                    Blake2bWrite<_, _, Challenge255<_>>,
+                    Blake2bRead<_, _, Challenge255<_>>,
+                >(
+                    circuit,
+                    vec![public_inputs],
+                    params,
+                    pk,
+                    check_mode,
+                    Commitments::KZG,
+                    transcript,
+                    split,
+                    None,
+                ),
+            }
+            .map_err(EZKLError::from)?;
+            snark.pretty_public_inputs = pretty_public_inputs;
+            snark.pinned_block = pinned_block;
+            Ok(snark)
+        })
+        .await?;
+    Ok(Json(snark))
+}
+
+async fn verify(
+    State(state): State<ServerState>,
+    request: Result<Json<VerifyRequest>, JsonRejection>,
+) -> Result<Json<VerifyResponse>, ServerError> {
+    let Json(request) = request?;
+    let artifacts = state.load(&request.artifacts).await?;
+
+    let verified = state
+        .run(move || {
+            let proof = request.proof;
+            let params = artifacts.params.verifier_params();
+            let vk = artifacts.pk.get_vk();
+            let n = 1 << artifacts.circuit.settings().run_args.logrows;
+            let strategy = SingleStrategy::new(params);
+            let result = match proof.transcript_type {
+                TranscriptType::EVM => verify_proof_circuit::<
+                    VerifierSHPLONK<'_, Bn256>,
+                    _,
+                    _,
+                    _,
+                    EvmTranscript<G1Affine, _, _, _>,
+                >(&proof, params, vk, strategy, n)
+                .map(|_| ()),
+                TranscriptType::Poseidon => verify_proof_circuit::<
+                    VerifierSHPLONK<'_, Bn256>,
+                    _,
+                    _,
+                    _,
+                    PoseidonTranscript<NativeLoader, _>,
+                >(&proof, params, vk, strategy, n)
+                .map(|_| ()),
+                TranscriptType::Blake => verify_proof_circuit::<
+                    VerifierSHPLONK<'_, Bn256>,
+                    _,
+                    _,
+                    _,
+                    Blake2bRead<_, _, Challenge255<_>>,
+                >(&proof, params, vk, strategy, n)
+                .map(|_| ()),
+            };
+            if let Err(e) = &result {
+                info!("the proof failed to verify: {}", e);
+            }
+            Ok(result.is_ok())
+        })
+        .await?;
+    Ok(Json(VerifyResponse { verified }))
+}
+
+/// The routes of the server, over the sets of artifacts in `artifacts_dir`, working on `workers` requests at once
+fn router(artifacts_dir: &Path, workers: usize) -> Result<Router, EZKLError> {
+    if workers == 0 {
+        return Err("the server needs at least one worker".into());
+    }
+    let state = ServerState {
+        artifacts: Arc::new(find_artifacts(artifacts_dir)?),
+        workers: Arc::new(Semaphore::new(workers)),
+    };
+    Ok(Router::new()
+        .route("/artifacts", get(list_artifacts))
+        .route("/gen-witness", post(gen_witness))
+        .route("/prove", post(prove))
+        .route("/verify", post(verify))
+        .with_state(state))
+}
+
+pub(crate) async fn serve(
+    host: String,
+    port: u16,
+    artifacts_dir: PathBuf,
+    workers: usize,
+) -> Result<String, EZKLError> {
+    let router = router(&artifacts_dir, workers)?;
+    let listener = tokio::net::TcpListener::bind((host.as_str(), port)).await?;
+    info!("listening on {}", listener.local_addr()?);
+    axum::serve(listener, router).await?;
+    Ok(String::new())
+}
@@ -1,6 +1,7 @@
 use thiserror::Error;
 
 use super::ops::DecompositionError;
+use crate::fieldutils::FeltRangeError;
 
 /// A wrapper for tensor related errors.
 #[derive(Debug, Error)]
@@ -26,6 +27,9 @@ pub enum TensorError {
     /// Overflow
     #[error("unsigned integer overflow or underflow error in op: {0}")]
     Overflow(String),
+    /// A field element lies outside of the range of the integer representation
+    #[error("field element {0} overflows the integer representation")]
+    IntegerRepOverflow(String),
     /// Unset visibility
     #[error("unset visibility")]
     UnsetVisibility,
@@ -38,4 +42,7 @@ pub enum TensorError {
     /// Decomposition error
     #[error("decomposition error: {0}")]
     DecompositionError(#[from] DecompositionError),
+    /// A field element lies outside of the integer range of the circuit
+    #[error("{0}")]
+    FeltRange(#[from] FeltRangeError),
 }
@@ -2,12 +2,18 @@
 pub mod errors;
 /// Implementations of common operations on tensors.
 pub mod ops;
+/// A compressed sparse row representation of tensors.
+pub mod sparse;
+/// The storage shared between tensors and their views.
+mod storage;
 /// A wrapper around a tensor of circuit variables / advices.
 pub mod val;
 /// A wrapper around a tensor of Halo2 Value types.
 pub mod var;
 
 pub use errors::TensorError;
+pub use sparse::SparseTensor;
+use storage::Storage;
 
 use halo2curves::ff::PrimeField;
 use maybe_rayon::{
@@ -74,6 +80,10 @@ lazy_static::lazy_static! {
     };
 }
 
+/// The number of elements below which the parallel maps of a [Tensor] run sequentially, splitting the work of smaller
+/// tensors across threads costs more than it saves
+pub const PAR_MAP_THRESHOLD: usize = 1 << 12;
+
 /// The (inner) type of tensor elements.
 pub trait TensorType: Clone + Debug + 'static {
     /// Returns the zero value.
@@ -285,11 +295,13 @@ impl TensorType for halo2curves::bn256::Fr {
 }
 
 /// A generic multi-dimensional array representation of a Tensor.
-/// The `inner` attribute contains a vector of values whereas `dims` corresponds to the dimensionality of the array
+/// The `inner` attribute contains the values whereas `dims` corresponds to the dimensionality of the array
 /// and as such determines how we index, query for values, or slice a Tensor.
+/// The values are a view over storage shared with the tensors it was sliced, transposed or broadcast from, and
+/// are only copied when mutated while shared.
 #[derive(Clone, Debug, Eq, Serialize, Deserialize, PartialOrd, Ord)]
 pub struct Tensor<T: TensorType> {
-    inner: Vec<T>,
+    inner: Storage<T>,
     dims: Vec<usize>,
     scale: Option<crate::Scale>,
     visibility: Option<Visibility>,
@@ -399,7 +411,7 @@ impl<T: Clone + TensorType + std::marker::Send + std::marker::Sync>
     type Iter = maybe_rayon::vec::IntoIter<T>;
     type Item = T;
     fn into_par_iter(self) -> Self::Iter {
-        self.inner.into_par_iter()
+        self.inner.into_vec().into_par_iter()
     }
 }
 
@@ -472,14 +484,14 @@ impl<T: Clone + TensorType> Tensor<T> {
                     )));
                 }
                 Ok(Tensor {
-                    inner: Vec::from(v),
+                    inner: Vec::from(v).into(),
                     dims: Vec::from(dims),
                     scale: None,
                     visibility: None,
                 })
             }
             None => Ok(Tensor {
-                inner: vec![T::zero().unwrap(); total_dims],
+                inner: vec![T::zero().unwrap(); total_dims].into(),
                 dims: Vec::from(dims),
                 scale: None,
                 visibility: None,
@@ -551,7 +563,7 @@ impl<T: Clone + TensorType> Tensor<T> {
     /// ```
     pub fn get(&self, indices: &[usize]) -> T {
         let index = self.get_index(indices);
-        self[index].clone()
+        self.inner.get(index).clone()
     }
 
     /// Get a mutable array index from rows / columns indices.
@@ -588,7 +600,7 @@ impl<T: Clone + TensorType> Tensor<T> {
     /// assert_eq!(a.pad_to_zero_rem(9, 0).unwrap(), expected);
     /// ```
     pub fn pad_to_zero_rem(&self, n: usize, pad: T) -> Result<Tensor<T>, TensorError> {
-        let mut inner = self.inner.clone();
+        let mut inner = self.inner.to_vec();
         let remainder = self.len() % n;
         if remainder != 0 {
             inner.resize(self.len() + n - remainder, pad);
@@ -608,7 +620,7 @@ impl<T: Clone + TensorType> Tensor<T> {
     /// assert_eq!(a.get_flat_index(flat_index), 5);
     /// ```
     pub fn get_flat_index(&self, index: usize) -> T {
-        self[index].clone()
+        self.inner.get(index).clone()
     }
 
     /// Display a tensor
@@ -658,23 +670,26 @@ impl<T: Clone + TensorType> Tensor<T> {
             full_indices.push(0..self.dims()[indices.len() + i])
         }
 
-        let cartesian_coord: Vec<Vec<usize>> = full_indices
+        if let Some(range) = full_indices
             .iter()
-            .cloned()
-            .multi_cartesian_product()
-            .collect();
-
-        let res: Vec<T> = cartesian_coord
-            .par_iter()
-            .map(|e| {
-                let index = self.get_index(e);
-                self[index].clone()
-            })
-            .collect();
+            .zip(self.dims.iter())
+            .find(|(range, d)| range.start > range.end || range.end > **d)
+        {
+            return Err(TensorError::DimError(format!(
+                "The slice {:?} is out of the bounds of the tensor's {:?}",
+                range.0, self.dims
+            )));
+        }
 
         let dims: Vec<usize> = full_indices.iter().map(|e| e.end - e.start).collect();
 
-        Tensor::new(Some(&res), &dims)
+        // the slice is a view over the tensor's storage
+        Ok(Tensor {
+            inner: self.inner.slice(&self.dims, &full_indices),
+            dims,
+            scale: None,
+            visibility: None,
+        })
     }
 
     /// Set a slice of the Tensor.
@@ -780,7 +795,7 @@ impl<T: Clone + TensorType> Tensor<T> {
     /// ```
     pub fn get_every_n(&self, n: usize) -> Result<Tensor<T>, TensorError> {
         let mut inner: Vec<T> = vec![];
-        for (i, elem) in self.inner.clone().into_iter().enumerate() {
+        for (i, elem) in self.inner.iter().cloned().enumerate() {
             if i % n == 0 {
                 inner.push(elem.clone());
             }
@@ -803,7 +818,7 @@ impl<T: Clone + TensorType> Tensor<T> {
     /// ```
     pub fn exclude_every_n(&self, n: usize) -> Result<Tensor<T>, TensorError> {
         let mut inner: Vec<T> = vec![];
-        for (i, elem) in self.inner.clone().into_iter().enumerate() {
+        for (i, elem) in self.inner.iter().cloned().enumerate() {
             if i % n != 0 {
                 inner.push(elem.clone());
             }
@@ -833,7 +848,7 @@ impl<T: Clone + TensorType> Tensor<T> {
     ) -> Result<Tensor<T>, TensorError> {
         let mut inner: Vec<T> = vec![];
         let mut offset = initial_offset;
-        for (i, elem) in self.inner.clone().into_iter().enumerate() {
+        for (i, elem) in self.inner.iter().cloned().enumerate() {
             if (i + offset + 1) % n == 0 {
                 inner.extend(vec![elem; 1 + num_repeats]);
                 offset += num_repeats;
@@ -870,8 +885,7 @@ impl<T: Clone + TensorType> Tensor<T> {
             }
         }
 
-        let old_inner = self.inner.clone();
-        for (i, elem) in old_inner.into_iter().enumerate() {
+        for (i, elem) in self.inner.iter().enumerate() {
             if !indices_to_remove.contains(&i) {
                 inner.push(elem.clone());
             }
@@ -901,7 +915,7 @@ impl<T: Clone + TensorType> Tensor<T> {
         indices: &mut [usize],
         is_sorted: bool,
     ) -> Result<Tensor<T>, TensorError> {
-        let mut inner: Vec<T> = self.inner.clone();
+        let mut inner: Vec<T> = self.inner.to_vec();
         // time it
         if !is_sorted {
             indices.par_sort_unstable();
@@ -981,58 +995,11 @@ impl<T: Clone + TensorType> Tensor<T> {
         assert!(source < self.dims.len());
         assert!(destination < self.dims.len());
 
-        let mut new_dims = self.dims.clone();
-        new_dims.remove(source);
-        new_dims.insert(destination, self.dims[source]);
-
-        // now reconfigure the elements appropriately in the new array
-        //  eg. if we have a 3x3x3 array and we want to move the 0th axis to the 2nd position
-        //  we need to move the elements at 0, 1, 2, 3, 4, 5, 6, 7, 8 to 0, 3, 6, 1, 4, 7, 2, 5, 8
-        //  so we need to move the elements at 0, 1, 2 to 0, 3, 6
-        //  and the elements at 3, 4, 5 to 1, 4, 7
-        //  and the elements at 6, 7, 8 to 2, 5, 8
-        let cartesian_coords = new_dims
-            .iter()
-            .map(|d| 0..*d)
-            .multi_cartesian_product()
-            .collect::<Vec<Vec<usize>>>();
-
-        let mut output = Tensor::new(None, &new_dims)?;
-
-        for coord in cartesian_coords {
-            let mut old_coord = vec![0; self.dims.len()];
-
-            // now fetch the old index
-            for (i, c) in coord.iter().enumerate() {
-                if i == destination {
-                    old_coord[source] = *c;
-                } else if i == source && source < destination {
-                    old_coord[source + 1] = *c;
-                } else if i == source && source > destination {
-                    old_coord[source - 1] = *c;
-                } else if (i < source && source < destination)
-                    || (i < destination && source > destination)
-                    || (i > source && source > destination)
-                    || (i > destination && source < destination)
-                {
-                    old_coord[i] = *c;
-                } else if i > source && source < destination {
-                    old_coord[i + 1] = *c;
-                } else if i > destination && source > destination {
-                    old_coord[i - 1] = *c;
-                } else {
-                    return Err(TensorError::DimError(
-                        "Unknown condition for moving the axis".to_string(),
-                    ));
-                }
-            }
-
-            let value = self.get(&old_coord);
-
-            output.set(&coord, value);
-        }
+        let mut permutation = (0..self.dims.len()).collect::<Vec<_>>();
+        permutation.remove(source);
+        permutation.insert(destination, source);
 
-        Ok(output)
+        Ok(self.permute(&permutation))
     }
 
     /// Swap axes of the tensor
@@ -1061,41 +1028,22 @@ impl<T: Clone + TensorType> Tensor<T> {
     pub fn swap_axes(&mut self, source: usize, destination: usize) -> Result<Self, TensorError> {
         assert!(source < self.dims.len());
         assert!(destination < self.dims.len());
-        let mut new_dims = self.dims.clone();
-        new_dims[source] = self.dims[destination];
-        new_dims[destination] = self.dims[source];
-
-        // now reconfigure the elements appropriately in the new array
-        //  eg. if we have a 3x3x3 array and we want to move the 0th axis to the 2nd position
-        //  we need to move the elements at 0, 1, 2, 3, 4, 5, 6, 7, 8 to 0, 3, 6, 1, 4, 7, 2, 5, 8
-        //  so we need to move the elements at 0, 1, 2 to 0, 3, 6
-        //  and the elements at 3, 4, 5 to 1, 4, 7
-        //  and the elements at 6, 7, 8 to 2, 5, 8
-        let cartesian_coords = new_dims
-            .iter()
-            .map(|d| 0..*d)
-            .multi_cartesian_product()
-            .collect::<Vec<Vec<usize>>>();
 
-        let mut output = Tensor::new(None, &new_dims)?;
+        let mut permutation = (0..self.dims.len()).collect::<Vec<_>>();
+        permutation.swap(source, destination);
 
-        for coord in cartesian_coords {
-            let mut old_coord = vec![0; self.dims.len()];
+        Ok(self.permute(&permutation))
+    }
 
-            // now fetch the old index
-            for (i, c) in coord.iter().enumerate() {
-                if i == destination {
-                    old_coord[source] = *c;
-                } else if i == source {
-                    old_coord[destination] = *c;
-                } else {
-                    old_coord[i] = *c;
-                }
-            }
-            output.set(&coord, self.get(&old_coord));
+    /// The view of the tensor with its axes permuted, the i-th axis of the view is the `permutation[i]`-th axis of the
+    /// tensor
+    fn permute(&self, permutation: &[usize]) -> Self {
+        Tensor {
+            inner: self.inner.permute(&self.dims, permutation),
+            dims: permutation.iter().map(|p| self.dims[*p]).collect(),
+            scale: None,
+            visibility: None,
         }
-
-        Ok(output)
     }
 
     /// Broadcasts the tensor to a given shape
@@ -1121,8 +1069,8 @@ impl<T: Clone + TensorType> Tensor<T> {
             return Ok(self.clone());
         }
 
-        for d in self.dims() {
-            if !(shape.contains(d) || *d == 1) {
+        for (i, d) in self.dims().iter().enumerate() {
+            if !(shape.contains(d) || *d == 1) || (*d != 1 && shape[i] > *d) {
                 return Err(TensorError::DimError(format!(
                     "The current dimension {} must be contained in the new shape {:?} or be 1",
                     d, shape
@@ -1130,29 +1078,13 @@ impl<T: Clone + TensorType> Tensor<T> {
             }
         }
 
-        let cartesian_coords = shape
-            .iter()
-            .map(|d| 0..*d)
-            .multi_cartesian_product()
-            .collect::<Vec<Vec<usize>>>();
-
-        let mut output = Tensor::new(None, shape)?;
-
-        for coord in cartesian_coords {
-            let mut new_coord = Vec::with_capacity(self.dims().len());
-            for (i, c) in coord.iter().enumerate() {
-                if i < self.dims().len() && self.dims()[i] == 1 {
-                    new_coord.push(0);
-                } else if i >= self.dims().len() {
-                    // do nothing at this point does not exist in the original tensor
-                } else {
-                    new_coord.push(*c);
-                }
-            }
-            output.set(&coord, self.get(&new_coord));
-        }
-
-        Ok(output)
+        // the broadcast tensor is a view over the tensor's storage
+        Ok(Tensor {
+            inner: self.inner.broadcast(&self.dims, shape),
+            dims: shape.to_vec(),
+            scale: None,
+            visibility: None,
+        })
     }
 
     ///Flatten the tensor shape
@@ -1184,6 +1116,35 @@ impl<T: Clone + TensorType> Tensor<T> {
         t
     }
 
+    /// Maps a function to tensors in parallel, the output is in the same order as [Tensor::map]'s. Tensors with fewer
+    /// than [PAR_MAP_THRESHOLD] elements are mapped sequentially.
+    /// ```
+    /// use ezkl::tensor::Tensor;
+    /// use ezkl::fieldutils::IntegerRep;
+    /// let mut a = Tensor::<IntegerRep>::new(Some(&[1, 4]), &[2]).unwrap();
+    /// let mut c = a.par_map(|x| IntegerRep::pow(x,2));
+    /// assert_eq!(c, Tensor::from([1, 16].into_iter()))
+    /// ```
+    pub fn par_map<
+        F: Fn(T) -> G + std::marker::Send + std::marker::Sync,
+        G: TensorType + std::marker::Send + std::marker::Sync,
+    >(
+        &self,
+        f: F,
+    ) -> Tensor<G>
+    where
+        T: std::marker::Send + std::marker::Sync,
+    {
+        if self.len() < PAR_MAP_THRESHOLD {
+            return self.map(f);
+        }
+        let vec: Vec<G> = self.inner.par_iter().map(|e| f(e.clone())).collect();
+        let mut t = Tensor::from(vec.into_iter());
+        // safe to unwrap as we know the dims are correct
+        t.reshape(self.dims()).unwrap();
+        t
+    }
+
     /// Maps a function to tensors and enumerates
     /// ```
     /// use ezkl::tensor::{Tensor, TensorError};
@@ -1208,7 +1169,8 @@ impl<T: Clone + TensorType> Tensor<T> {
         Ok(t)
     }
 
-    /// Maps a function to tensors and enumerates in parallel
+    /// Maps a function to tensors and enumerates in parallel, the output is in the same order as [Tensor::enum_map]'s.
+    /// Tensors with fewer than [PAR_MAP_THRESHOLD] elements are mapped sequentially.
     /// ```
     /// use ezkl::tensor::{Tensor, TensorError};
     /// use ezkl::fieldutils::IntegerRep;
@@ -1227,6 +1189,9 @@ impl<T: Clone + TensorType> Tensor<T> {
     where
         T: std::marker::Send + std::marker::Sync,
     {
+        if self.len() < PAR_MAP_THRESHOLD {
+            return self.enum_map(f);
+        }
         let vec: Result<Vec<G>, E> = self
             .inner
             .par_iter()
@@ -1331,9 +1296,9 @@ impl<T: Clone + TensorType> Tensor<Tensor<T>> {
     pub fn combine(&self) -> Result<Tensor<T>, TensorError> {
         let mut dims = 0;
         let mut inner = Vec::new();
-        for t in self.inner.clone().into_iter() {
+        for t in self.inner.iter() {
             dims += t.len();
-            inner.extend(t.inner);
+            inner.extend(t.inner.iter().cloned());
         }
         Tensor::new(Some(&inner), &[dims])
     }
@@ -1801,6 +1766,77 @@ mod tests {
         assert_eq!(a.get_slice(&[0..2, 0..1]).unwrap(), b);
     }
 
+    #[test]
+    fn tensor_view_aliasing() {
+        let mut a = Tensor::<IntegerRep>::new(Some(&[1, 2, 3, 4, 5, 6]), &[2, 3]).unwrap();
+        let mut slice = a.get_slice(&[1..2]).unwrap();
+        let mut transposed = a.swap_axes(0, 1).unwrap();
+        let mut broadcast = a.get_slice(&[0..1]).unwrap().expand(&[2, 3]).unwrap();
+
+        // writing to the views leaves the tensor they view untouched
+        slice.set(&[0, 0], 10);
+        transposed.set(&[0, 1], 20);
+        broadcast.set(&[1, 2], 30);
+        assert_eq!(a, Tensor::new(Some(&[1, 2, 3, 4, 5, 6]), &[2, 3]).unwrap());
+        assert_eq!(slice, Tensor::new(Some(&[10, 5, 6]), &[1, 3]).unwrap());
+        assert_eq!(
+            transposed,
+            Tensor::new(Some(&[1, 20, 2, 5, 3, 6]), &[3, 2]).unwrap()
+        );
+        assert_eq!(
+            broadcast,
+            Tensor::new(Some(&[1, 2, 3, 1, 2, 30]), &[2, 3]).unwrap()
+        );
+
+        // and writing to the tensor leaves the views untouched
+        let view = a.get_slice(&[0..1]).unwrap();
+        a.set(&[0, 0], 40);
+        assert_eq!(view, Tensor::new(Some(&[1, 2, 3]), &[1, 3]).unwrap());
+        assert_eq!(a.get(&[0, 0]), 40);
+    }
+
+    #[test]
+    fn tensor_views_of_views() {
+        let a = Tensor::<IntegerRep>::new(Some(&(0..24).collect::<Vec<_>>()), &[2, 3, 4]).unwrap();
+        let mut b = a.swap_axes(0, 2).unwrap().get_slice(&[1..3, 1..2]).unwrap();
+        assert_eq!(b.dims(), &[2, 1, 2]);
+        assert_eq!(b, Tensor::new(Some(&[5, 17, 6, 18]), &[2, 1, 2]).unwrap());
+        b.reshape(&[4]).unwrap();
+        assert_eq!(b.get_slice(&[1..3]).unwrap()[..], [17, 6]);
+        assert_eq!(b.get_flat_index(3), 18);
+    }
+
+    #[test]
+    fn tensor_slice_out_of_bounds() {
+        let a = Tensor::<IntegerRep>::new(Some(&[1, 2, 3, 4, 5, 6]), &[2, 3]).unwrap();
+        assert!(a.get_slice(&[0..3]).is_err());
+        assert!(a.get_slice(&[0..1, 2..4]).is_err());
+    }
+
+    #[test]
+    fn tensor_par_map_determinism() {
+        // above the threshold such that the maps run in parallel
+        let len = 4 * PAR_MAP_THRESHOLD + 3;
+        let a = Tensor::<IntegerRep>::from(0..len as IntegerRep);
+        let f = |x: IntegerRep| x * x - 7 * x;
+        let expected = a.map(f);
+        for _ in 0..4 {
+            assert_eq!(a.par_map(f), expected);
+            let b = a
+                .par_enum_map::<_, _, TensorError>(|i, x| Ok(f(x) + i as IntegerRep))
+                .unwrap();
+            let c = a
+                .enum_map::<_, _, TensorError>(|i, x| Ok(f(x) + i as IntegerRep))
+                .unwrap();
+            assert_eq!(b, c);
+        }
+        // the dims of the input are kept
+        let mut d = a.get_slice(&[0..4 * PAR_MAP_THRESHOLD]).unwrap();
+        d.reshape(&[4, PAR_MAP_THRESHOLD]).unwrap();
+        assert_eq!(d.par_map(f).dims(), &[4, PAR_MAP_THRESHOLD]);
+        assert_eq!(d.par_map(f), d.map(f));
+    }
+
     #[test]
     #[cfg(feature = "metal")]
     fn tensor_metal_int() {
@@ -15,6 +15,13 @@ pub enum DecompositionError {
     TooLarge(IntegerRep, usize, usize),
 }
 
+/// The largest magnitude `n` digits of base `base` decompose, `base^n - 1`, saturating at [IntegerRep::MAX]
+pub fn max_decomposable(base: usize, n: usize) -> IntegerRep {
+    (base as IntegerRep)
+        .checked_pow(n as u32)
+        .map_or(IntegerRep::MAX, |max| max - 1)
+}
+
 /// Helper function to get the base decomp of an integer
 /// # Arguments
 /// * `x` - IntegerRep
@@ -26,8 +33,8 @@ pub fn get_rep(
     base: usize,
     n: usize,
 ) -> Result<Vec<IntegerRep>, DecompositionError> {
-    // check if x is too large
-    if x.abs() > (base.pow(n as u32) as IntegerRep) {
+    // check if x is too large, base^n itself would lose its leading digit
+    if x.unsigned_abs() > max_decomposable(base, n) as u128 {
         return Err(DecompositionError::TooLarge(*x, base, n));
     }
     let mut rep = vec![0; n + 1];
@@ -1420,6 +1427,7 @@ pub fn slice<T: TensorType + Send + Sync>(
 /// Activation functions
 pub mod nonlinearities {
     use super::*;
+    use crate::circuit::DivRounding;
 
     /// Ceiling operator.
     /// # Arguments
@@ -1582,6 +1590,50 @@ pub mod nonlinearities {
         .unwrap()
     }
 
+    /// Indicates the elements of a tensor of integers equal to a value, with 1 and 0 otherwise.
+    /// # Arguments
+    /// * `a` - Tensor
+    /// * `value` - Single value
+    /// # Examples
+    /// ```
+    /// use ezkl::tensor::Tensor;
+    /// use ezkl::fieldutils::IntegerRep;
+    /// use ezkl::tensor::ops::nonlinearities::equals_const;
+    /// let x = Tensor::<IntegerRep>::new(
+    ///    Some(&[2, 7, 2, -7, 7, 0]),
+    ///  &[2, 3],
+    /// ).unwrap();
+    /// let result = equals_const(&x, 7);
+    /// let expected = Tensor::<IntegerRep>::new(Some(&[0, 1, 0, 0, 1, 0]), &[2, 3]).unwrap();
+    /// assert_eq!(result, expected);
+    /// ```
+    pub fn equals_const(a: &Tensor<IntegerRep>, value: IntegerRep) -> Tensor<IntegerRep> {
+        a.par_enum_map(|_, a_i| Ok::<_, TensorError>((a_i == value) as IntegerRep))
+            .unwrap()
+    }
+
+    /// Indicates the elements of a tensor of integers that are in a set of values, with 1 and 0 otherwise.
+    /// # Arguments
+    /// * `a` - Tensor
+    /// * `values` - The values of the set, possibly repeated
+    /// # Examples
+    /// ```
+    /// use ezkl::tensor::Tensor;
+    /// use ezkl::fieldutils::IntegerRep;
+    /// use ezkl::tensor::ops::nonlinearities::in_set;
+    /// let x = Tensor::<IntegerRep>::new(
+    ///    Some(&[2, 7, 3, -7, 7, 0]),
+    ///  &[2, 3],
+    /// ).unwrap();
+    /// let result = in_set(&x, &[7, 0, 7]);
+    /// let expected = Tensor::<IntegerRep>::new(Some(&[0, 1, 0, 0, 1, 1]), &[2, 3]).unwrap();
+    /// assert_eq!(result, expected);
+    /// ```
+    pub fn in_set(a: &Tensor<IntegerRep>, values: &[IntegerRep]) -> Tensor<IntegerRep> {
+        a.par_enum_map(|_, a_i| Ok::<_, TensorError>(values.contains(&a_i) as IntegerRep))
+            .unwrap()
+    }
+
     /// Elementwise applies sigmoid to a tensor of integers.
     /// # Arguments
     ///
@@ -2354,24 +2406,36 @@ pub mod nonlinearities {
     ///
     /// * `a` - Tensor
     /// * `b` - Single value
+    /// * `rounding` - How the quotients are rounded
     /// # Examples
     /// ```
     /// use ezkl::tensor::Tensor;
     /// use ezkl::fieldutils::IntegerRep;
+    /// use ezkl::circuit::DivRounding;
     /// use ezkl::tensor::ops::nonlinearities::const_div;
     /// let x = Tensor::<IntegerRep>::new(
-    ///     Some(&[2, 1, 2, 7, 1, 1]),
+    ///     Some(&[2, 1, 2, 7, 1, -3]),
     ///     &[2, 3],
     /// ).unwrap();
     /// let k = 2.0;
-    /// let result = const_div(&x, k);
-    /// let expected = Tensor::<IntegerRep>::new(Some(&[1, 1, 1, 4, 1, 1]), &[2, 3]).unwrap();
+    /// let result = const_div(&x, k, DivRounding::Round);
+    /// let expected = Tensor::<IntegerRep>::new(Some(&[1, 1, 1, 4, 1, -2]), &[2, 3]).unwrap();
+    /// assert_eq!(result, expected);
+    /// let result = const_div(&x, k, DivRounding::Floor);
+    /// let expected = Tensor::<IntegerRep>::new(Some(&[1, 0, 1, 3, 0, -2]), &[2, 3]).unwrap();
+    /// assert_eq!(result, expected);
+    /// let result = const_div(&x, k, DivRounding::Trunc);
+    /// let expected = Tensor::<IntegerRep>::new(Some(&[1, 0, 1, 3, 0, -1]), &[2, 3]).unwrap();
     /// assert_eq!(result, expected);
     /// ```
-    pub fn const_div(a: &Tensor<IntegerRep>, denom: f64) -> Tensor<IntegerRep> {
+    pub fn const_div(
+        a: &Tensor<IntegerRep>,
+        denom: f64,
+        rounding: DivRounding,
+    ) -> Tensor<IntegerRep> {
         a.par_enum_map(|_, a_i| {
             let d_inv_x = (a_i as f64) / (denom);
-            Ok::<_, TensorError>(d_inv_x.round() as IntegerRep)
+            Ok::<_, TensorError>(rounding.apply(d_inv_x) as IntegerRep)
         })
         .unwrap()
     }
@@ -2381,26 +2445,44 @@ pub mod nonlinearities {
     ///
     /// * `a` - Tensor
     /// * `b` - Single value
+    /// * `rounding` - How the inverses are rounded
     /// # Examples
     /// ```
     /// use ezkl::tensor::Tensor;
     /// use ezkl::fieldutils::IntegerRep;
+    /// use ezkl::circuit::DivRounding;
     /// use ezkl::tensor::ops::nonlinearities::recip;
     /// let x = Tensor::<IntegerRep>::new(
-    ///     Some(&[2, 1, 2, 7, 1, 1]),
+    ///     Some(&[2, 1, 2, 7, 1, -3]),
     ///     &[2, 3],
     /// ).unwrap();
     /// let k = 2_f64;
-    /// let result = recip(&x, 1.0, k);
-    /// let expected = Tensor::<IntegerRep>::new(Some(&[1, 2, 1, 0, 2, 2]), &[2, 3]).unwrap();
+    /// let result = recip(&x, 1.0, k, DivRounding::Round);
+    /// let expected = Tensor::<IntegerRep>::new(Some(&[1, 2, 1, 0, 2, -1]), &[2, 3]).unwrap();
+    /// assert_eq!(result, expected);
+    /// let result = recip(&x, 1.0, k, DivRounding::Floor);
+    /// let expected = Tensor::<IntegerRep>::new(Some(&[1, 2, 1, 0, 2, -1]), &[2, 3]).unwrap();
+    /// assert_eq!(result, expected);
+    /// let result = recip(&x, 1.0, k, DivRounding::Trunc);
+    /// let expected = Tensor::<IntegerRep>::new(Some(&[1, 2, 1, 0, 2, 0]), &[2, 3]).unwrap();
     /// assert_eq!(result, expected);
     /// ```
-    pub fn recip(a: &Tensor<IntegerRep>, input_scale: f64, out_scale: f64) -> Tensor<IntegerRep> {
+    pub fn recip(
+        a: &Tensor<IntegerRep>,
+        input_scale: f64,
+        out_scale: f64,
+        rounding: DivRounding,
+    ) -> Tensor<IntegerRep> {
         a.par_enum_map(|_, a_i| {
             let rescaled = (a_i as f64) / input_scale;
-            let denom = (1_f64) / (rescaled + f64::EPSILON);
-            let d_inv_x = out_scale * denom;
-            Ok::<_, TensorError>(d_inv_x.round() as IntegerRep)
+            // the epsilon keeps the inverse of zero finite, but shifts exact inverses below the integers they're at,
+            // which only rounding to the nearest integer is insensitive to
+            let d_inv_x = if a_i == 0 || rounding == DivRounding::Round {
+                out_scale * ((1_f64) / (rescaled + f64::EPSILON))
+            } else {
+                out_scale / rescaled
+            };
+            Ok::<_, TensorError>(rounding.apply(d_inv_x) as IntegerRep)
         })
         .unwrap()
     }
@@ -0,0 +1,155 @@
+use super::*;
+
+/// A tensor in compressed sparse row (CSR) format. The rows are the leading dimensions of the tensor flattened and
+/// the columns its last dimension, only the nonzero values and their columns are stored.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SparseTensor<T: TensorType> {
+    /// the offsets in `indices` and `values` at which each row starts, followed by the number of nonzeros
+    row_offsets: Vec<usize>,
+    /// the column of each nonzero value
+    indices: Vec<usize>,
+    /// the nonzero values
+    values: Vec<T>,
+    dims: Vec<usize>,
+    scale: Option<crate::Scale>,
+    visibility: Option<Visibility>,
+}
+
+/// The fraction of the values of a tensor that are zero
+pub fn sparsity<T: TensorType + PartialEq>(tensor: &Tensor<T>) -> f32 {
+    if tensor.is_empty() {
+        return 0.0;
+    }
+    let zero = T::zero();
+    let num_zeros = tensor.iter().filter(|x| Some(*x) == zero.as_ref()).count();
+    num_zeros as f32 / tensor.len() as f32
+}
+
+impl<T: TensorType + PartialEq> SparseTensor<T> {
+    /// Compresses a dense tensor, keeping its scale and visibility
+    pub fn from_dense(tensor: &Tensor<T>) -> Result<Self, TensorError> {
+        let zero = T::zero().ok_or(TensorError::Unsupported)?;
+        let num_cols = tensor.dims().last().copied().unwrap_or(1).max(1);
+
+        let mut row_offsets = vec![0];
+        let mut indices = vec![];
+        let mut values = vec![];
+        for row in tensor.chunks(num_cols) {
+            for (col, x) in row.iter().enumerate() {
+                if *x != zero {
+                    indices.push(col);
+                    values.push(x.clone());
+                }
+            }
+            row_offsets.push(values.len());
+        }
+
+        Ok(SparseTensor {
+            row_offsets,
+            indices,
+            values,
+            dims: tensor.dims().to_vec(),
+            scale: tensor.scale(),
+            visibility: tensor.visibility(),
+        })
+    }
+
+    /// Decompresses to the dense tensor
+    pub fn to_dense(&self) -> Result<Tensor<T>, TensorError> {
+        let zero = T::zero().ok_or(TensorError::Unsupported)?;
+        self.expand(|x| x.clone(), zero)
+    }
+}
+
+impl<T: TensorType> SparseTensor<T> {
+    /// The dimensions of the dense tensor
+    pub fn dims(&self) -> &[usize] {
+        &self.dims
+    }
+
+    /// The number of nonzero values
+    pub fn nnz(&self) -> usize {
+        self.values.len()
+    }
+
+    /// The fraction of the values of the dense tensor that are zero
+    pub fn sparsity(&self) -> f32 {
+        let len = self.dims.iter().product::<usize>();
+        if len == 0 {
+            return 0.0;
+        }
+        1.0 - self.nnz() as f32 / len as f32
+    }
+
+    /// The flat indices in the dense tensor of the nonzero values, in ascending order
+    pub fn nonzero_indices(&self) -> Vec<usize> {
+        let num_cols = self.dims.last().copied().unwrap_or(1).max(1);
+        self.row_offsets
+            .windows(2)
+            .enumerate()
+            .flat_map(|(row, bounds)| {
+                self.indices[bounds[0]..bounds[1]]
+                    .iter()
+                    .map(move |col| row * num_cols + col)
+            })
+            .collect()
+    }
+
+    /// Expands to a dense tensor, mapping the nonzero values with `f` and filling the rest with `fill`
+    pub fn expand<U: TensorType>(
+        &self,
+        f: impl Fn(&T) -> U,
+        fill: U,
+    ) -> Result<Tensor<U>, TensorError> {
+        let mut dense = vec![fill; self.dims.iter().product::<usize>()];
+        for (idx, x) in self.nonzero_indices().into_iter().zip(self.values.iter()) {
+            dense[idx] = f(x);
+        }
+        let mut dense = Tensor::new(Some(&dense), &self.dims)?;
+        if let Some(scale) = self.scale {
+            dense.set_scale(scale);
+        }
+        if let Some(visibility) = &self.visibility {
+            dense.set_visibility(visibility);
+        }
+        Ok(dense)
+    }
+}
+
+impl<F: PrimeField + TensorType + PartialOrd> SparseTensor<F> {
+    /// Converts to a [ValTensor] in which the zeros are constants, such that the layouts elide the terms they contribute
+    /// to, and the nonzero values are constants or witnessed according to the visibility
+    pub fn to_val_tensor(&self) -> Result<ValTensor<F>, TensorError> {
+        let visibility = self
+            .visibility
+            .clone()
+            .ok_or(TensorError::UnsetVisibility)?;
+        let inner = self.expand(
+            |x| {
+                if visibility.is_fixed() {
+                    ValType::Constant(*x)
+                } else {
+                    Value::known(*x).into()
+                }
+            },
+            ValType::Constant(F::ZERO),
+        )?;
+        Ok(ValTensor::from(inner))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sparse_roundtrip() {
+        let mut a = Tensor::<IntegerRep>::new(Some(&[0, 2, 0, 0, 0, 0, 3, 0, 4]), &[3, 3]).unwrap();
+        a.set_visibility(&Visibility::Fixed);
+        let sparse = SparseTensor::from_dense(&a).unwrap();
+        assert_eq!(sparse.nnz(), 3);
+        assert_eq!(sparse.nonzero_indices(), vec![1, 6, 8]);
+        assert_eq!(sparse.sparsity(), sparsity(&a));
+        assert_eq!(sparse.to_dense().unwrap(), a);
+    }
+}
@@ -0,0 +1,329 @@
+use std::ops::{Deref, DerefMut, Range};
+use std::sync::{Arc, OnceLock};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A strided view over storage. The elements of the view are the storage elements met while walking `dims` in row-major
+/// order from `offset`, moving by `strides` along each dimension (a zero stride broadcasts along that dimension).
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct View {
+    offset: usize,
+    dims: Vec<usize>,
+    strides: Vec<usize>,
+}
+
+impl View {
+    /// The view of `dims` laid out contiguously from `offset`
+    fn contiguous(offset: usize, dims: &[usize]) -> Self {
+        let mut strides = vec![1; dims.len()];
+        for i in (0..dims.len().saturating_sub(1)).rev() {
+            strides[i] = strides[i + 1] * dims[i + 1];
+        }
+        View {
+            offset,
+            dims: dims.to_vec(),
+            strides,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.dims.iter().product()
+    }
+
+    /// Whether the elements of the view are contiguous in the storage, in order
+    fn is_contiguous(&self) -> bool {
+        if self.len() == 0 {
+            return true;
+        }
+        let mut expected = 1;
+        for (d, s) in self.dims.iter().zip(self.strides.iter()).rev() {
+            // the stride of a dimension of size 1 is never walked
+            if *d != 1 && *s != expected {
+                return false;
+            }
+            expected *= d;
+        }
+        true
+    }
+
+    /// The storage index of the element at `index` in the view
+    fn storage_index(&self, mut index: usize) -> usize {
+        let mut storage_index = self.offset;
+        for (d, s) in self.dims.iter().zip(self.strides.iter()).rev() {
+            storage_index += (index % d) * s;
+            index /= d;
+        }
+        storage_index
+    }
+}
+
+/// The storage of a [super::Tensor], shared between the tensors viewing it. Slicing, swapping axes and broadcasting
+/// create new views over the same storage instead of copying it, the storage is copied when a view is mutated while
+/// shared (copy on write). Reading a view whose elements aren't contiguous as a slice gathers them once.
+#[derive(Clone)]
+pub(crate) struct Storage<T> {
+    data: Arc<Vec<T>>,
+    view: View,
+    gathered: OnceLock<Arc<Vec<T>>>,
+}
+
+impl<T: Clone> From<Vec<T>> for Storage<T> {
+    fn from(data: Vec<T>) -> Self {
+        let view = View::contiguous(0, &[data.len()]);
+        Storage {
+            data: Arc::new(data),
+            view,
+            gathered: OnceLock::new(),
+        }
+    }
+}
+
+impl<T: Clone> Storage<T> {
+    /// The number of elements of the view
+    pub(crate) fn len(&self) -> usize {
+        self.view.len()
+    }
+
+    /// The element at `index` in the view, read without gathering the view
+    pub(crate) fn get(&self, index: usize) -> &T {
+        if let Some(gathered) = self.gathered.get() {
+            &gathered[index]
+        } else {
+            &self.data[self.view.storage_index(index)]
+        }
+    }
+
+    /// Whether the storage is shared with another tensor
+    pub(crate) fn is_shared(&self) -> bool {
+        Arc::strong_count(&self.data) > 1
+    }
+
+    /// The elements of the view, moved out of the storage if it isn't shared
+    pub(crate) fn into_vec(self) -> Vec<T> {
+        let whole = self.view.is_contiguous()
+            && self.view.offset == 0
+            && self.view.len() == self.data.len();
+        if whole {
+            match Arc::try_unwrap(self.data) {
+                Ok(data) => data,
+                Err(data) => data.to_vec(),
+            }
+        } else {
+            self.deref().to_vec()
+        }
+    }
+
+    /// The same elements viewed with `dims`, which must have as many elements as the view. This doesn't copy unless the
+    /// view isn't contiguous and has other dims.
+    fn with_dims(&self, dims: &[usize]) -> Self {
+        if self.view.dims == dims {
+            self.clone()
+        } else if self.view.is_contiguous() {
+            Storage {
+                data: self.data.clone(),
+                view: View::contiguous(self.view.offset, dims),
+                gathered: OnceLock::new(),
+            }
+        } else {
+            let mut gathered = Storage::from(self.deref().to_vec());
+            gathered.view = View::contiguous(0, dims);
+            gathered
+        }
+    }
+
+    fn with_view(&self, view: View) -> Self {
+        Storage {
+            data: self.data.clone(),
+            view,
+            gathered: OnceLock::new(),
+        }
+    }
+
+    /// The view of the `ranges` of a tensor of `dims` over this storage, `ranges` has a range for each dim
+    pub(crate) fn slice(&self, dims: &[usize], ranges: &[Range<usize>]) -> Self {
+        let base = self.with_dims(dims);
+        let mut view = base.view.clone();
+        for (i, range) in ranges.iter().enumerate() {
+            view.offset += range.start * view.strides[i];
+            view.dims[i] = range.end - range.start;
+        }
+        base.with_view(view)
+    }
+
+    /// The view of a tensor of `dims` over this storage with the axes permuted, the i-th axis of the view is the
+    /// `permutation[i]`-th axis of the tensor
+    pub(crate) fn permute(&self, dims: &[usize], permutation: &[usize]) -> Self {
+        let base = self.with_dims(dims);
+        let view = View {
+            offset: base.view.offset,
+            dims: permutation.iter().map(|p| base.view.dims[*p]).collect(),
+            strides: permutation.iter().map(|p| base.view.strides[*p]).collect(),
+        };
+        base.with_view(view)
+    }
+
+    /// The view of a tensor of `dims` over this storage broadcast to `shape`. The dimensions of size 1, and those of
+    /// `shape` past the dimensions of the tensor, are broadcast.
+    pub(crate) fn broadcast(&self, dims: &[usize], shape: &[usize]) -> Self {
+        let base = self.with_dims(dims);
+        let strides = shape
+            .iter()
+            .enumerate()
+            .map(|(i, d)| match dims.get(i) {
+                Some(1) if *d != 1 => 0,
+                Some(_) => base.view.strides[i],
+                None => 0,
+            })
+            .collect();
+        let view = View {
+            offset: base.view.offset,
+            dims: shape.to_vec(),
+            strides,
+        };
+        base.with_view(view)
+    }
+}
+
+impl<T: Clone> Deref for Storage<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        if self.view.is_contiguous() {
+            let start = self.view.offset;
+            &self.data[start..start + self.view.len()]
+        } else {
+            self.gathered
+                .get_or_init(|| {
+                    Arc::new(
+                        (0..self.view.len())
+                            .map(|i| self.data[self.view.storage_index(i)].clone())
+                            .collect(),
+                    )
+                })
+                .as_slice()
+        }
+    }
+}
+
+impl<T: Clone> DerefMut for Storage<T> {
+    /// Copies the elements of the view to storage of its own if the view isn't contiguous or the storage is shared
+    fn deref_mut(&mut self) -> &mut [T] {
+        if !self.view.is_contiguous() || self.is_shared() {
+            *self = Storage::from(self.deref().to_vec());
+        }
+        let start = self.view.offset;
+        let end = start + self.view.len();
+        // the storage isn't shared at this point
+        let data = Arc::get_mut(&mut self.data).expect("storage is not shared");
+        &mut data[start..end]
+    }
+}
+
+impl<T: Clone> IntoIterator for Storage<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.into_vec().into_iter()
+    }
+}
+
+impl<T: Clone + std::fmt::Debug> std::fmt::Debug for Storage<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.deref().fmt(f)
+    }
+}
+
+impl<T: Clone + PartialEq> PartialEq for Storage<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.deref() == other.deref()
+    }
+}
+
+impl<T: Clone + Eq> Eq for Storage<T> {}
+
+impl<T: Clone + PartialOrd> PartialOrd for Storage<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        self.deref().partial_cmp(other.deref())
+    }
+}
+
+impl<T: Clone + Ord> Ord for Storage<T> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.deref().cmp(other.deref())
+    }
+}
+
+/// Serialized as the sequence of the elements of the view, as a [Vec] would be
+impl<T: Clone + Serialize> Serialize for Storage<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.deref().serialize(serializer)
+    }
+}
+
+impl<'de, T: Clone + Deserialize<'de>> Deserialize<'de> for Storage<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Storage::from(Vec::<T>::deserialize(deserializer)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn storage(len: usize) -> Storage<usize> {
+        Storage::from((0..len).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn views_share_storage() {
+        let a = storage(6);
+        let b = a.slice(&[2, 3], &[1..2, 0..3]);
+        let c = a.permute(&[2, 3], &[1, 0]);
+        let d = a.broadcast(&[1, 6], &[2, 6]);
+        assert!(Arc::ptr_eq(&a.data, &b.data));
+        assert!(Arc::ptr_eq(&a.data, &c.data));
+        assert!(Arc::ptr_eq(&a.data, &d.data));
+        assert_eq!(&b[..], &[3, 4, 5]);
+        assert_eq!(&c[..], &[0, 3, 1, 4, 2, 5]);
+        assert_eq!(&d[..], &[0, 1, 2, 3, 4, 5, 0, 1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn copy_on_write() {
+        let a = storage(6);
+        let mut b = a.slice(&[2, 3], &[1..2, 0..3]);
+        let mut c = a.permute(&[2, 3], &[1, 0]);
+        b[0] = 10;
+        c[0] = 20;
+        // writing to the views leaves the storage they shared untouched
+        assert_eq!(&a[..], &[0, 1, 2, 3, 4, 5]);
+        assert_eq!(&b[..], &[10, 4, 5]);
+        assert_eq!(&c[..], &[20, 3, 1, 4, 2, 5]);
+        assert!(!Arc::ptr_eq(&a.data, &b.data));
+        assert!(!Arc::ptr_eq(&a.data, &c.data));
+    }
+
+    #[test]
+    fn write_in_place_if_not_shared() {
+        let mut a = storage(6);
+        let ptr = Arc::as_ptr(&a.data);
+        a[0] = 10;
+        assert_eq!(Arc::as_ptr(&a.data), ptr);
+        assert_eq!(&a[..], &[10, 1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn views_of_views() {
+        let a = storage(24);
+        // transpose a 2x3x4 tensor to 4x3x2 then take the middle row of each matrix
+        let b = a.permute(&[2, 3, 4], &[2, 1, 0]);
+        let c = b.slice(&[4, 3, 2], &[0..4, 1..2, 0..2]);
+        assert!(Arc::ptr_eq(&a.data, &c.data));
+        assert_eq!(&c[..], &[4, 16, 5, 17, 6, 18, 7, 19]);
+        // reshaping a view that isn't contiguous gathers it
+        let d = c.slice(&[8], &[2..4]);
+        assert_eq!(&d[..], &[5, 17]);
+        assert_eq!(d.get(1), &17);
+    }
+}
@@ -1,4 +1,7 @@
-use crate::{circuit::region::ConstantsMap, fieldutils::felt_to_integer_rep};
+use crate::{
+    circuit::region::ConstantsMap,
+    fieldutils::{checked_felt_to_integer_rep, felt_to_integer_rep, felt_to_integer_rep_in_range},
+};
 use maybe_rayon::slice::Iter;
 
 use super::{
@@ -8,6 +11,14 @@ use super::{
 use halo2_proofs::{arithmetic::Field, circuit::Cell, plonk::Instance};
 use maybe_rayon::iter::{FilterMap, IntoParallelIterator, ParallelIterator};
 
+/// Converts a field element to an [IntegerRep], failing if it lies outside of the range of the integer representation
+pub(crate) fn checked_integer_rep<F: PrimeField + PartialOrd>(
+    x: F,
+) -> Result<IntegerRep, TensorError> {
+    checked_felt_to_integer_rep(x)
+        .ok_or_else(|| TensorError::IntegerRepOverflow(format!("{:?}", x)))
+}
+
 pub(crate) fn create_constant_tensor<
     F: PrimeField + TensorType + std::marker::Send + std::marker::Sync + PartialOrd,
 >(
@@ -532,8 +543,10 @@ impl<F: PrimeField + TensorType + PartialOrd + std::hash::Hash> ValTensor<F> {
             .into())
     }
 
-    /// Decompose the inner values into base `base` and `n` legs.
+    /// Decompose the inner values into base `base` and `n` legs. The sign of a value is that of the integer it represents
+    /// within the range of the decomposition, a value outside of it is an error.
     pub fn decompose(&self, base: usize, n: usize) -> Result<Self, TensorError> {
+        let max_abs = crate::tensor::ops::max_decomposable(base, n);
         let res = self
             .get_inner()?
             .par_iter()
@@ -544,11 +557,12 @@ impl<F: PrimeField + TensorType + PartialOrd + std::hash::Hash> ValTensor<F> {
                     Ok::<_, TensorError>(vec![Value::<F>::unknown(); n + 1])
                 } else {
                     let mut res = vec![Value::unknown(); n + 1];
-                    let mut int_rep = 0;
+                    let mut int_rep = Ok(0);
 
                     x.map(|f| {
-                        int_rep = crate::fieldutils::felt_to_integer_rep(f);
+                        int_rep = felt_to_integer_rep_in_range(f, max_abs);
                     });
+                    let int_rep = int_rep?;
                     let decompe = crate::tensor::ops::get_rep(&int_rep, base, n)?;
 
                     for (i, x) in decompe.iter().enumerate() {
@@ -568,10 +582,22 @@ impl<F: PrimeField + TensorType + PartialOrd + std::hash::Hash> ValTensor<F> {
         Ok(tensor.into())
     }
 
-    /// Calls `int_evals` on the inner tensor.
+    /// Calls `int_evals` on the inner tensor. Fails if a value overflows the integer representation.
     pub fn int_evals(&self) -> Result<Tensor<IntegerRep>, TensorError> {
+        self.int_evals_with(checked_integer_rep)
+    }
+
+    /// Calls `int_evals` on the inner tensor, wrapping the values that overflow the integer representation.
+    pub fn unchecked_int_evals(&self) -> Result<Tensor<IntegerRep>, TensorError> {
+        self.int_evals_with(|f| Ok(felt_to_integer_rep(f)))
+    }
+
+    fn int_evals_with(
+        &self,
+        to_int: impl Fn(F) -> Result<IntegerRep, TensorError>,
+    ) -> Result<Tensor<IntegerRep>, TensorError> {
         // finally convert to vector of integers
-        let mut integer_evals: Vec<IntegerRep> = vec![];
+        let mut integer_evals: Vec<Result<IntegerRep, TensorError>> = vec![];
         match self {
             ValTensor::Value {
                 inner: v, dims: _, ..
@@ -579,25 +605,25 @@ impl<F: PrimeField + TensorType + PartialOrd + std::hash::Hash> ValTensor<F> {
                 // we have to push to an externally created vector or else vaf.map() returns an evaluation wrapped in Value<> (which we don't want)
                 let _ = v.map(|vaf| match vaf {
                     ValType::Value(v) => v.map(|f| {
-                        integer_evals.push(crate::fieldutils::felt_to_integer_rep(f));
+                        integer_evals.push(to_int(f));
                     }),
                     ValType::AssignedValue(v) => v.map(|f| {
-                        integer_evals.push(crate::fieldutils::felt_to_integer_rep(f.evaluate()));
+                        integer_evals.push(to_int(f.evaluate()));
                     }),
                     ValType::PrevAssigned(v) | ValType::AssignedConstant(v, ..) => {
                         v.value_field().map(|f| {
-                            integer_evals
-                                .push(crate::fieldutils::felt_to_integer_rep(f.evaluate()));
+                            integer_evals.push(to_int(f.evaluate()));
                         })
                     }
                     ValType::Constant(v) => {
-                        integer_evals.push(crate::fieldutils::felt_to_integer_rep(v));
+                        integer_evals.push(to_int(v));
                         Value::unknown()
                     }
                 });
             }
             _ => return Err(TensorError::WrongMethod),
         };
+        let integer_evals = integer_evals.into_iter().collect::<Result<Vec<_>, _>>()?;
         let mut tensor: Tensor<IntegerRep> = integer_evals.into_iter().into();
         match tensor.reshape(self.dims()) {
             _ => {}
@@ -1104,7 +1130,7 @@ impl<F: PrimeField + TensorType + PartialOrd + std::hash::Hash> ValTensor<F> {
     }
     /// A [String] representation of the [ValTensor] for display, for example in showing intermediate values in a computational graph.
     pub fn show(&self) -> String {
-        let r = match self.int_evals() {
+        let r = match self.unchecked_int_evals() {
             Ok(v) => v,
             Err(_) => return "ValTensor not PrevAssigned".into(),
         };
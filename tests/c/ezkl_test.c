@@ -0,0 +1,92 @@
+// Verifies the proof and generates the witness of the test assets through the C ABI of ezkl.
+// Usage: ezkl_test <assets dir>
+
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "ezkl.h"
+
+#define CHECK(cond)                                                     \
+    do {                                                                \
+        if (!(cond)) {                                                  \
+            const char *message = ezkl_last_error_message();            \
+            fprintf(stderr, "%s:%d: check failed: %s (last error: %s)\n", \
+                    __FILE__, __LINE__, #cond,                          \
+                    message ? message : "none");                        \
+            exit(1);                                                    \
+        }                                                               \
+    } while (0)
+
+static unsigned char *read_file(const char *dir, const char *name, size_t *len) {
+    char path[4096];
+    snprintf(path, sizeof(path), "%s/%s", dir, name);
+    FILE *f = fopen(path, "rb");
+    if (!f) {
+        fprintf(stderr, "failed to open %s\n", path);
+        exit(1);
+    }
+    fseek(f, 0, SEEK_END);
+    *len = (size_t)ftell(f);
+    fseek(f, 0, SEEK_SET);
+    unsigned char *bytes = malloc(*len ? *len : 1);
+    CHECK(bytes != NULL);
+    CHECK(fread(bytes, 1, *len, f) == *len);
+    fclose(f);
+    return bytes;
+}
+
+int main(int argc, char **argv) {
+    CHECK(argc == 2);
+    const char *dir = argv[1];
+
+    size_t proof_len, vk_len, settings_len, srs_len, compiled_len, input_len;
+    unsigned char *proof = read_file(dir, "proof.json", &proof_len);
+    unsigned char *vk = read_file(dir, "vk.key", &vk_len);
+    unsigned char *settings = read_file(dir, "settings.json", &settings_len);
+    unsigned char *srs = read_file(dir, "kzg", &srs_len);
+    unsigned char *compiled = read_file(dir, "model.compiled", &compiled_len);
+    unsigned char *input = read_file(dir, "input.json", &input_len);
+
+    // the proof verifies, and a successful call leaves no error
+    CHECK(ezkl_verify(proof, proof_len, vk, vk_len, settings, settings_len, srs, srs_len) == EZKL_OK);
+    CHECK(ezkl_last_error_message() == NULL);
+
+    // truncated settings are an error with a message
+    CHECK(ezkl_verify(proof, proof_len, vk, vk_len, settings, settings_len / 2, srs, srs_len) ==
+          EZKL_ERROR);
+    CHECK(ezkl_last_error_message() != NULL);
+    CHECK(strlen(ezkl_last_error_message()) > 0);
+    // as is a null buffer with a length
+    CHECK(ezkl_verify(NULL, proof_len, vk, vk_len, settings, settings_len, srs, srs_len) ==
+          EZKL_ERROR);
+
+    // the length of the witness is negotiated with a null buffer, then a buffer one byte short
+    size_t witness_len = 0;
+    CHECK(ezkl_gen_witness(compiled, compiled_len, input, input_len, NULL, 0, &witness_len) ==
+          EZKL_BUFFER_TOO_SMALL);
+    CHECK(witness_len > 0);
+    unsigned char *witness = malloc(witness_len);
+    CHECK(witness != NULL);
+    size_t written = 0;
+    CHECK(ezkl_gen_witness(compiled, compiled_len, input, input_len, witness, witness_len - 1,
+                           &written) == EZKL_BUFFER_TOO_SMALL);
+    CHECK(written == witness_len);
+    CHECK(ezkl_gen_witness(compiled, compiled_len, input, input_len, witness, witness_len,
+                           &written) == EZKL_OK);
+    CHECK(written == witness_len);
+    CHECK(witness[0] == '{');
+    CHECK(witness[witness_len - 1] == '}');
+    CHECK(ezkl_gen_witness(compiled, compiled_len, input, input_len, witness, witness_len, NULL) ==
+          EZKL_ERROR);
+
+    free(witness);
+    free(proof);
+    free(vk);
+    free(settings);
+    free(srs);
+    free(compiled);
+    free(input);
+    printf("ezkl C ABI test passed\n");
+    return 0;
+}
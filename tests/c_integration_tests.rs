@@ -0,0 +1,46 @@
+#[cfg(feature = "c-bindings-test")]
+#[cfg(test)]
+mod c_tests {
+    use std::path::PathBuf;
+    use std::process::Command;
+
+    /// target/<profile>, where the build script writes the header and cargo the library
+    fn profile_dir() -> PathBuf {
+        // the test runs from target/<profile>/deps
+        let exe = std::env::current_exe().unwrap();
+        exe.parent().unwrap().parent().unwrap().to_path_buf()
+    }
+
+    #[test]
+    fn c_abi_verifies_and_generates_witness() {
+        let profile = profile_dir();
+        let deps = profile.join("deps");
+        assert!(
+            profile.join("ezkl.h").exists(),
+            "the header wasn't generated"
+        );
+
+        let test_dir = tempfile::tempdir().unwrap();
+        let exe = test_dir.path().join("ezkl_test");
+        let status = Command::new(std::env::var("CC").unwrap_or("cc".to_string()))
+            .arg("tests/c/ezkl_test.c")
+            .arg(format!("-I{}", profile.display()))
+            .arg(format!("-L{}", profile.display()))
+            .arg(format!("-L{}", deps.display()))
+            .arg("-lezkl")
+            .arg("-o")
+            .arg(&exe)
+            .status()
+            .expect("failed to execute the C compiler");
+        assert!(status.success());
+
+        let library_path = format!("{}:{}", profile.display(), deps.display());
+        let status = Command::new(&exe)
+            .arg("tests/assets")
+            .env("LD_LIBRARY_PATH", &library_path)
+            .env("DYLD_LIBRARY_PATH", &library_path)
+            .status()
+            .expect("failed to execute the C test");
+        assert!(status.success());
+    }
+}
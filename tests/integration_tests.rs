@@ -5,19 +5,23 @@ mod native_tests {
     use ezkl::circuit::Tolerance;
     use ezkl::fieldutils::{felt_to_integer_rep, integer_rep_to_felt, IntegerRep};
     // use ezkl::circuit::table::RESERVED_BLINDING_ROWS_PAD;
-    use ezkl::graph::input::{FileSource, FileSourceInner, GraphData};
-    use ezkl::graph::{DataSource, GraphSettings, GraphWitness};
-    use ezkl::pfsys::Snark;
+    use ezkl::graph::input::{
+        load_npz, FileSource, FileSourceInner, GraphData, InputNormalization, NpyArray,
+    };
+    use ezkl::graph::{DataSource, GraphCircuit, GraphSettings, GraphWitness, OutputBound};
+    use ezkl::pfsys::evm::aggregation_kzg::{AggregationCircuit, AggregationError};
+    use ezkl::pfsys::{Snark, TranscriptType};
     use ezkl::Commitments;
+    use halo2_proofs::dev::MockProver;
     use halo2_proofs::poly::kzg::commitment::KZGCommitmentScheme;
-    use halo2curves::bn256::Bn256;
+    use halo2curves::bn256::{Bn256, G1Affine};
     use lazy_static::lazy_static;
     use rand::Rng;
     use std::env::var;
     use std::io::{Read, Write};
     use std::path::PathBuf;
     use std::process::{Child, Command};
-    use std::sync::Once;
+    use std::sync::{Arc, Mutex, Once};
     static COMPILE: Once = Once::new();
     #[allow(dead_code)]
     static COMPILE_WASM: Once = Once::new();
@@ -448,9 +452,21 @@ mod native_tests {
             use test_case::test_case;
             use crate::native_tests::aggr_prove_and_verify;
             use crate::native_tests::kzg_aggr_mock_prove_and_verify;
+            use crate::native_tests::kzg_aggr_transcripts;
             use tempdir::TempDir;
             use ezkl::Commitments;
 
+            #[cfg(not(feature="icicle"))]
+            #[test]
+            fn kzg_aggr_transcripts_() {
+                let test = "1l_mlp";
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new(test).unwrap();
+                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
+                kzg_aggr_transcripts(path, test.to_string());
+                test_dir.close().unwrap();
+            }
+
             #[cfg(not(feature="icicle"))]
             seq!(N in 0..=20 {
 
@@ -516,6 +532,46 @@ mod native_tests {
             use crate::native_tests::run_js_tests;
             use crate::native_tests::render_circuit;
             use crate::native_tests::model_serialization_different_binaries;
+            use crate::native_tests::numpy_input_round_trip;
+            use crate::native_tests::csv_input_round_trip;
+            use crate::native_tests::image_input;
+            use crate::native_tests::witness_batch;
+            use crate::native_tests::diff;
+            use crate::native_tests::input_normalization;
+            use crate::native_tests::field_input_round_trip;
+            use crate::native_tests::witness_format;
+            use crate::native_tests::witness_redaction;
+            use crate::native_tests::sha256_hashed;
+            use crate::native_tests::merkle_inclusion;
+            use crate::native_tests::input_signature;
+            use crate::native_tests::output_disclosure;
+            use crate::native_tests::revealed_outputs;
+            use crate::native_tests::kept_outputs;
+            use crate::native_tests::output_bounds;
+            use crate::native_tests::nullifier;
+            use crate::native_tests::circomlib_poseidon;
+            use crate::native_tests::output_encryption;
+            use crate::native_tests::polycommit_blinding;
+            use crate::native_tests::param_commitment_groups;
+            use crate::native_tests::circuit_profile;
+            use crate::native_tests::check_tables;
+            use crate::native_tests::rescale_overrides;
+            use crate::native_tests::auto_inner_cols;
+            use crate::native_tests::row_overflow;
+            use crate::native_tests::layout_estimate;
+            use crate::native_tests::inspect_compiled;
+            use crate::native_tests::bench;
+            use crate::native_tests::pipeline;
+            #[cfg(feature = "server")]
+            use crate::native_tests::serve;
+            use crate::native_tests::json_output;
+            use crate::native_tests::profile_json;
+            use crate::native_tests::memory_estimate;
+            use crate::native_tests::deterministic_artifacts;
+            use crate::native_tests::prove_batch;
+            use crate::native_tests::srs_cache;
+            use crate::native_tests::srs_mirrors;
+            use crate::native_tests::project_config;
             use rand::Rng;
             use tempdir::TempDir;
             use ezkl::Commitments;
@@ -531,1295 +587,7552 @@ mod native_tests {
                 test_dir.close().unwrap();
             }
 
-            seq!(N in 0..=5 {
-            #(#[test_case(ACCURACY_CAL_TESTS[N])])*
-            fn mock_accuracy_cal_tests(test: &str) {
+            #[test_case("1l_mlp")]
+            #[test_case("less")]
+            #[test_case("boolean")]
+            #[test_case("bitshift")]
+            fn numpy_input_round_trip_(test: &str) {
                 crate::native_tests::init_binary();
                 let test_dir = TempDir::new(test).unwrap();
-                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-                mock(path, test.to_string(), "public", "fixed", "public", 1, "accuracy", None, 0.0);
+                let path = test_dir.path().to_str().unwrap();
+                crate::native_tests::mv_test_(path, test);
+                numpy_input_round_trip(path, test.to_string());
                 test_dir.close().unwrap();
             }
-        });
-
-            seq!(N in 0..=93 {
 
-            #(#[test_case(TESTS[N])])*
-            #[ignore]
-            fn render_circuit_(test: &str) {
+            #[test_case("less")]
+            #[test_case("tutorial")]
+            fn csv_input_round_trip_(test: &str) {
                 crate::native_tests::init_binary();
                 let test_dir = TempDir::new(test).unwrap();
-                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-                render_circuit(path, test.to_string());
+                let path = test_dir.path().to_str().unwrap();
+                crate::native_tests::mv_test_(path, test);
+                csv_input_round_trip(path, test.to_string());
                 test_dir.close().unwrap();
             }
 
-            #(#[test_case(TESTS[N])])*
-            fn accuracy_measurement_div_rebase_(test: &str) {
+            #[test]
+            fn image_input_() {
                 crate::native_tests::init_binary();
-                crate::native_tests::setup_py_env();
+                let test = "2l_relu_sigmoid_conv";
                 let test_dir = TempDir::new(test).unwrap();
-                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-                accuracy_measurement(path, test.to_string(), "private", "private", "public", 1, "accuracy", 2.6, true);
+                let path = test_dir.path().to_str().unwrap();
+                crate::native_tests::mv_test_(path, test);
+                image_input(path, test.to_string());
                 test_dir.close().unwrap();
             }
 
-            #(#[test_case(TESTS[N])])*
-            fn accuracy_measurement_public_outputs_(test: &str) {
+            #[test_case("1l_mlp")]
+            #[test_case("tutorial")]
+            fn witness_batch_(test: &str) {
                 crate::native_tests::init_binary();
-                crate::native_tests::setup_py_env();
                 let test_dir = TempDir::new(test).unwrap();
-                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-                accuracy_measurement(path, test.to_string(), "private", "private", "public", 1, "accuracy", 2.6, false);
+                let path = test_dir.path().to_str().unwrap();
+                crate::native_tests::mv_test_(path, test);
+                witness_batch(path, test.to_string());
                 test_dir.close().unwrap();
             }
 
-            #(#[test_case(TESTS[N])])*
-            fn accuracy_measurement_fixed_params_(test: &str) {
+            #[test_case("1l_mlp")]
+            fn diff_(test: &str) {
                 crate::native_tests::init_binary();
-                crate::native_tests::setup_py_env();
                 let test_dir = TempDir::new(test).unwrap();
-                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-                accuracy_measurement(path, test.to_string(), "private", "fixed", "private", 1, "accuracy", 2.6 , false);
+                let path = test_dir.path().to_str().unwrap();
+                crate::native_tests::mv_test_(path, test);
+                diff(path, test.to_string());
                 test_dir.close().unwrap();
             }
 
-            #(#[test_case(TESTS[N])])*
-            fn accuracy_measurement_public_inputs_(test: &str) {
+            #[test_case("1l_mlp")]
+            #[test_case("2l_relu_sigmoid_conv")]
+            fn input_normalization_(test: &str) {
                 crate::native_tests::init_binary();
-                crate::native_tests::setup_py_env();
                 let test_dir = TempDir::new(test).unwrap();
-                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-                accuracy_measurement(path, test.to_string(), "public", "private", "private", 1, "accuracy", 2.6, false);
+                let path = test_dir.path().to_str().unwrap();
+                crate::native_tests::mv_test_(path, test);
+                input_normalization(path, test.to_string());
                 test_dir.close().unwrap();
             }
 
-
-            #(#[test_case(TESTS[N])])*
-            fn resources_accuracy_measurement_public_outputs_(test: &str) {
+            #[test_case("1l_mlp")]
+            #[test_case("2l_relu_sigmoid_conv")]
+            fn field_input_round_trip_(test: &str) {
                 crate::native_tests::init_binary();
-                crate::native_tests::setup_py_env();
                 let test_dir = TempDir::new(test).unwrap();
-                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-                accuracy_measurement(path, test.to_string(), "private", "private", "public", 1, "resources", 3.1, false);
+                let path = test_dir.path().to_str().unwrap();
+                crate::native_tests::mv_test_(path, test);
+                field_input_round_trip(path, test.to_string());
                 test_dir.close().unwrap();
             }
 
-            #(#[test_case(TESTS[N])])*
-            fn mock_public_outputs_(test: &str) {
+            #[test_case("1l_mlp")]
+            #[test_case("2l_relu_sigmoid_conv")]
+            fn witness_format_(test: &str) {
                 crate::native_tests::init_binary();
                 let test_dir = TempDir::new(test).unwrap();
-                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-                mock(path, test.to_string(), "private", "private", "public", 1, "resources", None, 0.0);
+                let path = test_dir.path().to_str().unwrap();
+                crate::native_tests::mv_test_(path, test);
+                witness_format(path, test.to_string());
                 test_dir.close().unwrap();
             }
 
-            #(#[test_case(TESTS[N])])*
-            fn mock_tolerance_public_outputs_(test: &str) {
+            #[test_case("1l_mlp")]
+            fn witness_redaction_(test: &str) {
                 crate::native_tests::init_binary();
                 let test_dir = TempDir::new(test).unwrap();
-                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-                // gen random number between 0.0 and 1.0
-                let tolerance = rand::thread_rng().gen_range(0.0..1.0) * 100.0;
-                mock(path, test.to_string(), "private", "private", "public", 1, "resources", None, tolerance);
+                let path = test_dir.path().to_str().unwrap();
+                crate::native_tests::mv_test_(path, test);
+                witness_redaction(path, test.to_string());
                 test_dir.close().unwrap();
             }
 
-
-
-            #(#[test_case(TESTS[N])])*
-            fn mock_large_batch_public_outputs_(test: &str) {
-                // currently variable output rank is not supported in ONNX
-                if test != "gather_nd" && test != "lstm_large"  && test != "lstm_medium" {
-                    crate::native_tests::init_binary();
-                    let test_dir = TempDir::new(test).unwrap();
-                    let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-                    let large_batch_dir = &format!("large_batches_{}", test);
-                    crate::native_tests::mk_data_batches_(path, test, &large_batch_dir, 10);
-                    mock(path, large_batch_dir.to_string(), "private", "private", "public", 10, "resources", None, 0.0);
-                    test_dir.close().unwrap();
-                }
-            }
-
-            #(#[test_case(TESTS[N])])*
-            fn mock_public_inputs_(test: &str) {
+            #[test_case("1l_mlp")]
+            #[test_case("2l_relu_sigmoid_conv")]
+            fn sha256_hashed_(test: &str) {
                 crate::native_tests::init_binary();
                 let test_dir = TempDir::new(test).unwrap();
-                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-                mock(path, test.to_string(), "public", "private", "private", 1, "resources", None, 0.0);
+                let path = test_dir.path().to_str().unwrap();
+                crate::native_tests::mv_test_(path, test);
+                sha256_hashed(path, test.to_string());
                 test_dir.close().unwrap();
             }
 
-            #(#[test_case(TESTS[N])])*
-            fn mock_hashed_params_public_inputs_(test: &str) {
+            #[test_case("1l_mlp")]
+            #[test_case("2l_relu_sigmoid_conv")]
+            fn merkle_inclusion_(test: &str) {
                 crate::native_tests::init_binary();
                 let test_dir = TempDir::new(test).unwrap();
-                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-                mock(path, test.to_string(), "public", "hashed", "private", 1, "resources", None, 0.0);
+                let path = test_dir.path().to_str().unwrap();
+                crate::native_tests::mv_test_(path, test);
+                merkle_inclusion(path, test.to_string());
                 test_dir.close().unwrap();
             }
 
-            #(#[test_case(TESTS[N])])*
-            fn mock_fixed_inputs_(test: &str) {
+            #[test_case("1l_mlp")]
+            #[test_case("2l_relu_sigmoid_conv")]
+            fn input_signature_(test: &str) {
                 crate::native_tests::init_binary();
                 let test_dir = TempDir::new(test).unwrap();
-                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-                mock(path, test.to_string(), "fixed", "private", "private", 1, "resources", None, 0.0);
+                let path = test_dir.path().to_str().unwrap();
+                crate::native_tests::mv_test_(path, test);
+                input_signature(path, test.to_string());
                 test_dir.close().unwrap();
             }
 
-            #(#[test_case(TESTS[N])])*
-            fn mock_fixed_outputs_(test: &str) {
+            #[test_case("tutorial")]
+            #[test_case("rounding_ops")]
+            fn output_disclosure_(test: &str) {
                 crate::native_tests::init_binary();
                 let test_dir = TempDir::new(test).unwrap();
-                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-                mock(path, test.to_string(), "private", "private", "fixed", 1, "resources", None, 0.0);
+                let path = test_dir.path().to_str().unwrap();
+                crate::native_tests::mv_test_(path, test);
+                output_disclosure(path, test.to_string());
                 test_dir.close().unwrap();
             }
 
-            #(#[test_case(TESTS[N])])*
-            fn mock_fixed_params_(test: &str) {
+            #[test_case("1l_mlp")]
+            #[test_case("tutorial")]
+            fn revealed_outputs_(test: &str) {
                 crate::native_tests::init_binary();
                 let test_dir = TempDir::new(test).unwrap();
-                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-                mock(path, test.to_string(), "private", "fixed", "private", 1, "resources", None, 0.0);
+                let path = test_dir.path().to_str().unwrap();
+                crate::native_tests::mv_test_(path, test);
+                revealed_outputs(path, test.to_string());
                 test_dir.close().unwrap();
             }
 
-            #(#[test_case(TESTS[N])])*
-            fn mock_hashed_input_(test: &str) {
+            #[test_case("tutorial", "output")]
+            fn kept_outputs_(test: &str, kept: &str) {
                 crate::native_tests::init_binary();
                 let test_dir = TempDir::new(test).unwrap();
-                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-                mock(path, test.to_string(), "hashed", "private", "public", 1, "resources", None, 0.0);
+                let path = test_dir.path().to_str().unwrap();
+                crate::native_tests::mv_test_(path, test);
+                kept_outputs(path, test.to_string(), kept);
                 test_dir.close().unwrap();
             }
 
-            #(#[test_case(TESTS[N])])*
-            fn mock_kzg_input_(test: &str) {
+            #[test_case("1l_mlp")]
+            #[test_case("1l_relu")]
+            fn output_bounds_(test: &str) {
                 crate::native_tests::init_binary();
                 let test_dir = TempDir::new(test).unwrap();
-                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-                mock(path, test.to_string(), "polycommit", "private", "public", 1, "resources", None, 0.0);
+                let path = test_dir.path().to_str().unwrap();
+                crate::native_tests::mv_test_(path, test);
+                output_bounds(path, test.to_string());
                 test_dir.close().unwrap();
             }
 
-
-            #(#[test_case(TESTS[N])])*
-            fn mock_hashed_params_(test: &str) {
+            #[test_case("1l_mlp", "private")]
+            #[test_case("1l_relu", "hashed/public")]
+            fn nullifier_(test: &str, input_visibility: &str) {
                 crate::native_tests::init_binary();
                 let test_dir = TempDir::new(test).unwrap();
-                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-                mock(path, test.to_string(), "private", "hashed", "public", 1, "resources", None, 0.0);
+                let path = test_dir.path().to_str().unwrap();
+                crate::native_tests::mv_test_(path, test);
+                nullifier(path, test.to_string(), input_visibility);
                 test_dir.close().unwrap();
             }
 
-
-            #(#[test_case(TESTS[N])])*
-            fn mock_kzg_params_(test: &str) {
+            #[test_case("1l_mlp")]
+            fn session_(test: &str) {
                 crate::native_tests::init_binary();
                 let test_dir = TempDir::new(test).unwrap();
-                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-                mock(path, test.to_string(), "private", "polycommit", "public", 1, "resources", None, 0.0);
+                let path = test_dir.path().to_str().unwrap();
+                crate::native_tests::mv_test_(path, test);
+                session(path, test.to_string());
                 test_dir.close().unwrap();
             }
 
-            #(#[test_case(TESTS[N])])*
-            fn mock_hashed_output_(test: &str) {
+            #[test_case("1l_mlp")]
+            #[test_case("1l_conv")]
+            fn circomlib_poseidon_(test: &str) {
                 crate::native_tests::init_binary();
                 let test_dir = TempDir::new(test).unwrap();
-                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-                mock(path, test.to_string(), "public", "private", "hashed", 1, "resources", None, 0.0);
+                let path = test_dir.path().to_str().unwrap();
+                crate::native_tests::mv_test_(path, test);
+                circomlib_poseidon(path, test.to_string());
                 test_dir.close().unwrap();
             }
 
-
-            #(#[test_case(TESTS[N])])*
-            fn mock_kzg_output_(test: &str) {
+            #[test_case("1l_mlp")]
+            fn verifier_only_(test: &str) {
                 crate::native_tests::init_binary();
                 let test_dir = TempDir::new(test).unwrap();
-                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-                mock(path, test.to_string(), "public", "private", "polycommit", 1, "resources", None, 0.0);
+                let path = test_dir.path().to_str().unwrap();
+                crate::native_tests::mv_test_(path, test);
+                verifier_only(path, test.to_string());
                 test_dir.close().unwrap();
             }
 
-            #(#[test_case(TESTS[N])])*
-            fn mock_hashed_output_fixed_params_(test: &str) {
+            #[test_case("1l_mlp")]
+            #[test_case("2l_relu_sigmoid_small")]
+            fn output_encryption_(test: &str) {
                 crate::native_tests::init_binary();
                 let test_dir = TempDir::new(test).unwrap();
-                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-                mock(path, test.to_string(), "public", "fixed", "hashed", 1, "resources", None, 0.0);
+                let path = test_dir.path().to_str().unwrap();
+                crate::native_tests::mv_test_(path, test);
+                output_encryption(path, test.to_string());
                 test_dir.close().unwrap();
             }
 
-
-            #(#[test_case(TESTS[N])])*
-            fn mock_hashed_output_kzg_params_(test: &str) {
+            #[test_case("1l_mlp")]
+            fn polycommit_blinding_(test: &str) {
                 crate::native_tests::init_binary();
                 let test_dir = TempDir::new(test).unwrap();
-                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-                mock(path, test.to_string(), "public", "polycommit", "hashed", 1, "resources", None, 0.0);
+                let path = test_dir.path().to_str().unwrap();
+                crate::native_tests::mv_test_(path, test);
+                polycommit_blinding(path, test.to_string());
                 test_dir.close().unwrap();
             }
 
-
-            #(#[test_case(TESTS[N])])*
-            fn mock_kzg_all_(test: &str) {
+            #[test_case("1l_mlp")]
+            fn param_commitment_groups_(test: &str) {
                 crate::native_tests::init_binary();
                 let test_dir = TempDir::new(test).unwrap();
-                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-                mock(path, test.to_string(), "polycommit", "polycommit", "polycommit", 1, "resources", None, 0.0);
+                let path = test_dir.path().to_str().unwrap();
+                crate::native_tests::mv_test_(path, test);
+                param_commitment_groups(path, test.to_string());
                 test_dir.close().unwrap();
             }
 
-
-            #(#[test_case(TESTS[N])])*
-            fn mock_hashed_input_output_(test: &str) {
+            #[test_case("1l_relu")]
+            fn link_proofs_(test: &str) {
                 crate::native_tests::init_binary();
                 let test_dir = TempDir::new(test).unwrap();
-                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-                mock(path, test.to_string(), "hashed", "private", "hashed", 1, "resources", None, 0.0);
+                let path = test_dir.path().to_str().unwrap();
+                crate::native_tests::mv_test_(path, test);
+                link_proofs(path, test.to_string());
                 test_dir.close().unwrap();
             }
 
-            #(#[test_case(TESTS[N])])*
-            fn mock_hashed_input_params_(test: &str) {
+            #[test_case("1l_relu")]
+            fn validate_witness_(test: &str) {
                 crate::native_tests::init_binary();
                 let test_dir = TempDir::new(test).unwrap();
-                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-                // needs an extra row for the large model
-                mock(path, test.to_string(),"hashed", "hashed", "public", 1, "resources", None, 0.0);
+                let path = test_dir.path().to_str().unwrap();
+                crate::native_tests::mv_test_(path, test);
+                validate_witness(path, test.to_string());
                 test_dir.close().unwrap();
             }
 
-            #(#[test_case(TESTS[N])])*
-            fn mock_hashed_all_(test: &str) {
+            #[test_case("2l_relu_fc")]
+            fn circuit_profile_(test: &str) {
                 crate::native_tests::init_binary();
                 let test_dir = TempDir::new(test).unwrap();
-                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-                // needs an extra row for the large model
-                mock(path, test.to_string(),"hashed", "hashed", "hashed", 1, "resources", None, 0.0);
+                let path = test_dir.path().to_str().unwrap();
+                crate::native_tests::mv_test_(path, test);
+                circuit_profile(path, test.to_string());
                 test_dir.close().unwrap();
             }
 
-            #(#[test_case(TESTS[N])])*
-            fn kzg_prove_and_verify_single_col(test: &str) {
+            #[test_case("1l_sigmoid")]
+            #[test_case("1l_erf")]
+            fn check_tables_(test: &str) {
                 crate::native_tests::init_binary();
                 let test_dir = TempDir::new(test).unwrap();
-                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-               prove_and_verify(path, test.to_string(), "safe", "private", "private", "public", 1, None, false, "single", Commitments::KZG, 2);
-               test_dir.close().unwrap();
+                let path = test_dir.path().to_str().unwrap();
+                crate::native_tests::mv_test_(path, test);
+                check_tables(path, test.to_string());
+                test_dir.close().unwrap();
             }
 
-            #(#[test_case(TESTS[N])])*
-            fn kzg_prove_and_verify_triple_col(test: &str) {
+            #[test_case("1l_mlp")]
+            fn prove_timeout_(test: &str) {
                 crate::native_tests::init_binary();
                 let test_dir = TempDir::new(test).unwrap();
-                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-               prove_and_verify(path, test.to_string(), "safe", "private", "private", "public", 3, None, false, "single", Commitments::KZG, 2);
-               test_dir.close().unwrap();
+                let path = test_dir.path().to_str().unwrap();
+                crate::native_tests::mv_test_(path, test);
+                prove_timeout(path, test.to_string(), 0);
+                test_dir.close().unwrap();
             }
 
-            #(#[test_case(TESTS[N])])*
-            fn kzg_prove_and_verify_quadruple_col(test: &str) {
+            #[test_case("1l_mlp")]
+            fn decode_instances_(test: &str) {
                 crate::native_tests::init_binary();
                 let test_dir = TempDir::new(test).unwrap();
-                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-               prove_and_verify(path, test.to_string(), "safe", "private", "private", "public", 4, None, false, "single", Commitments::KZG, 2);
-               test_dir.close().unwrap();
+                let path = test_dir.path().to_str().unwrap();
+                crate::native_tests::mv_test_(path, test);
+                decode_instances(path, test.to_string());
+                test_dir.close().unwrap();
             }
 
-            #(#[test_case(TESTS[N])])*
-            fn kzg_prove_and_verify_octuple_col(test: &str) {
+            #[test_case("1l_mlp")]
+            fn vk_description_(test: &str) {
                 crate::native_tests::init_binary();
                 let test_dir = TempDir::new(test).unwrap();
-                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-               prove_and_verify(path, test.to_string(), "safe", "private", "private", "public", 8, None, false, "single", Commitments::KZG, 2);
-               test_dir.close().unwrap();
+                let path = test_dir.path().to_str().unwrap();
+                crate::native_tests::mv_test_(path, test);
+                vk_description(path, test.to_string());
+                test_dir.close().unwrap();
             }
 
-            #(#[test_case(TESTS[N])])*
-            fn kzg_prove_and_verify_(test: &str) {
+            #[test_case("1l_mlp")]
+            fn rescale_overrides_(test: &str) {
                 crate::native_tests::init_binary();
                 let test_dir = TempDir::new(test).unwrap();
-                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-               prove_and_verify(path, test.to_string(), "safe", "private", "private", "public", 1, None, false, "single", Commitments::KZG, 2);
-               test_dir.close().unwrap();
+                let path = test_dir.path().to_str().unwrap();
+                crate::native_tests::mv_test_(path, test);
+                rescale_overrides(path, test.to_string());
+                test_dir.close().unwrap();
             }
 
-            #(#[test_case(TESTS[N])])*
-            fn kzg_prove_and_verify_tight_lookup_(test: &str) {
+            #[test_case("1l_conv")]
+            fn auto_inner_cols_(test: &str) {
                 crate::native_tests::init_binary();
                 let test_dir = TempDir::new(test).unwrap();
-                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-               prove_and_verify(path, test.to_string(), "safe", "private", "private", "public", 1, None, false, "single", Commitments::KZG, 1);
-               test_dir.close().unwrap();
+                let path = test_dir.path().to_str().unwrap();
+                crate::native_tests::mv_test_(path, test);
+                auto_inner_cols(path, test.to_string());
+                test_dir.close().unwrap();
             }
 
-            #(#[test_case(TESTS[N])])*
-            fn ipa_prove_and_verify_(test: &str) {
+            #[test_case("1l_conv")]
+            fn row_overflow_(test: &str) {
                 crate::native_tests::init_binary();
                 let test_dir = TempDir::new(test).unwrap();
-                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-               prove_and_verify(path, test.to_string(), "safe", "private", "private", "public", 1, None, false, "single", Commitments::IPA, 2);
-               test_dir.close().unwrap();
+                let path = test_dir.path().to_str().unwrap();
+                crate::native_tests::mv_test_(path, test);
+                row_overflow(path, test.to_string());
+                test_dir.close().unwrap();
+            }
+
+            #[test_case("1l_mlp")]
+            #[test_case("1l_conv")]
+            #[test_case("1l_sigmoid")]
+            #[test_case("1l_max_pool")]
+            #[test_case("1l_softmax")]
+            #[test_case("1l_var")]
+            #[test_case("2l_relu_fc")]
+            #[test_case("layernorm")]
+            fn layout_estimate_(test: &str) {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new(test).unwrap();
+                let path = test_dir.path().to_str().unwrap();
+                crate::native_tests::mv_test_(path, test);
+                layout_estimate(path, test.to_string());
+                test_dir.close().unwrap();
+            }
+
+            #[test_case("1l_mlp")]
+            #[test_case("lstm")]
+            fn inspect_compiled_(test: &str) {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new(test).unwrap();
+                let path = test_dir.path().to_str().unwrap();
+                crate::native_tests::mv_test_(path, test);
+                inspect_compiled(path, test.to_string());
+                test_dir.close().unwrap();
+            }
+
+            #[test_case("1l_relu")]
+            fn bench_(test: &str) {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new(test).unwrap();
+                let path = test_dir.path().to_str().unwrap();
+                crate::native_tests::mv_test_(path, test);
+                bench(path, test.to_string());
+                test_dir.close().unwrap();
+            }
+
+            #[test_case("1l_relu")]
+            fn pipeline_(test: &str) {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new(test).unwrap();
+                let path = test_dir.path().to_str().unwrap();
+                crate::native_tests::mv_test_(path, test);
+                pipeline(path, test.to_string());
+                test_dir.close().unwrap();
+            }
+
+            #[test_case("1l_relu")]
+            fn prove_batch_(test: &str) {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new(test).unwrap();
+                let path = test_dir.path().to_str().unwrap();
+                crate::native_tests::mv_test_(path, test);
+                prove_batch(path, test.to_string());
+                test_dir.close().unwrap();
+            }
+
+            #[test_case("1l_mlp")]
+            fn batch_family_(test: &str) {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new(test).unwrap();
+                let path = test_dir.path().to_str().unwrap();
+                crate::native_tests::mv_test_(path, test);
+                batch_family(path, test.to_string());
+                test_dir.close().unwrap();
+            }
+
+            #[test_case("1l_mlp")]
+            fn compile_weights_(test: &str) {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new(test).unwrap();
+                let path = test_dir.path().to_str().unwrap();
+                crate::native_tests::mv_test_(path, test);
+                compile_weights(path, test.to_string());
+                test_dir.close().unwrap();
+            }
+
+            #[test_case("1l_mlp")]
+            fn encrypted_artifacts_(test: &str) {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new(test).unwrap();
+                let path = test_dir.path().to_str().unwrap();
+                crate::native_tests::mv_test_(path, test);
+                encrypted_artifacts(path, test.to_string());
+                test_dir.close().unwrap();
+            }
+
+            #[test_case("1l_relu")]
+            fn profile_json_(test: &str) {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new(test).unwrap();
+                let path = test_dir.path().to_str().unwrap();
+                crate::native_tests::mv_test_(path, test);
+                profile_json(path, test.to_string());
+                test_dir.close().unwrap();
+            }
+
+            #[test_case("1l_mlp")]
+            #[test_case("1l_conv")]
+            #[test_case("lstm")]
+            fn memory_estimate_(test: &str) {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new(test).unwrap();
+                let path = test_dir.path().to_str().unwrap();
+                crate::native_tests::mv_test_(path, test);
+                memory_estimate(path, test.to_string());
+                test_dir.close().unwrap();
+            }
+
+            #[test_case("1l_relu")]
+            #[test_case("1l_conv")]
+            fn deterministic_artifacts_(test: &str) {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new(test).unwrap();
+                let path = test_dir.path().to_str().unwrap();
+                crate::native_tests::mv_test_(path, test);
+                deterministic_artifacts(path, test.to_string());
+                test_dir.close().unwrap();
+            }
+
+            #[test_case("1l_relu")]
+            fn json_output_(test: &str) {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new(test).unwrap();
+                let path = test_dir.path().to_str().unwrap();
+                crate::native_tests::mv_test_(path, test);
+                json_output(path, test.to_string());
+                test_dir.close().unwrap();
+            }
+
+            #[test_case("1l_relu")]
+            fn project_config_(test: &str) {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new(test).unwrap();
+                let path = test_dir.path().to_str().unwrap();
+                crate::native_tests::mv_test_(path, test);
+                project_config(path, test.to_string());
+                test_dir.close().unwrap();
+            }
+
+            #[test]
+            fn srs_cache_() {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new("srs_cache").unwrap();
+                srs_cache(test_dir.path().to_str().unwrap());
+                test_dir.close().unwrap();
+            }
+
+            #[test]
+            fn srs_mirrors_() {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new("srs_mirrors").unwrap();
+                srs_mirrors(test_dir.path().to_str().unwrap());
+                test_dir.close().unwrap();
+            }
+
+            #[cfg(feature = "server")]
+            #[test_case("1l_relu")]
+            fn serve_(test: &str) {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new(test).unwrap();
+                let path = test_dir.path().to_str().unwrap();
+                crate::native_tests::mv_test_(path, test);
+                serve(path, test.to_string());
+                test_dir.close().unwrap();
             }
 
+            seq!(N in 0..=5 {
+            #(#[test_case(ACCURACY_CAL_TESTS[N])])*
+            fn mock_accuracy_cal_tests(test: &str) {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new(test).unwrap();
+                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
+                mock(path, test.to_string(), "public", "fixed", "public", 1, "accuracy", None, 0.0);
+                test_dir.close().unwrap();
+            }
+        });
+
+            seq!(N in 0..=93 {
+
             #(#[test_case(TESTS[N])])*
-            fn kzg_prove_and_verify_public_input_(test: &str) {
+            #[ignore]
+            fn render_circuit_(test: &str) {
                 crate::native_tests::init_binary();
                 let test_dir = TempDir::new(test).unwrap();
                 let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-               prove_and_verify(path, test.to_string(), "safe", "public", "private", "public", 1, None, false, "single", Commitments::KZG, 2);
-               test_dir.close().unwrap();
+                render_circuit(path, test.to_string());
+                test_dir.close().unwrap();
             }
 
             #(#[test_case(TESTS[N])])*
-            fn kzg_prove_and_verify_fixed_params_(test: &str) {
+            fn accuracy_measurement_div_rebase_(test: &str) {
                 crate::native_tests::init_binary();
+                crate::native_tests::setup_py_env();
                 let test_dir = TempDir::new(test).unwrap();
                 let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-               prove_and_verify(path, test.to_string(), "safe", "private", "fixed", "public", 1, None, false, "single", Commitments::KZG, 2);
-               test_dir.close().unwrap();
+                accuracy_measurement(path, test.to_string(), "private", "private", "public", 1, "accuracy", 2.6, true);
+                test_dir.close().unwrap();
             }
 
             #(#[test_case(TESTS[N])])*
-            fn kzg_prove_and_verify_hashed_output(test: &str) {
+            fn accuracy_measurement_public_outputs_(test: &str) {
                 crate::native_tests::init_binary();
+                crate::native_tests::setup_py_env();
                 let test_dir = TempDir::new(test).unwrap();
                 let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-               prove_and_verify(path, test.to_string(), "safe", "private", "private", "hashed", 1, None, false, "single", Commitments::KZG, 2);
-               test_dir.close().unwrap();
+                accuracy_measurement(path, test.to_string(), "private", "private", "public", 1, "accuracy", 2.6, false);
+                test_dir.close().unwrap();
             }
 
             #(#[test_case(TESTS[N])])*
-            fn kzg_prove_and_verify_kzg_output(test: &str) {
+            fn accuracy_measurement_fixed_params_(test: &str) {
                 crate::native_tests::init_binary();
+                crate::native_tests::setup_py_env();
                 let test_dir = TempDir::new(test).unwrap();
                 let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-               prove_and_verify(path, test.to_string(), "safe", "private", "private", "polycommit", 1, None, false, "single", Commitments::KZG, 2);
-               test_dir.close().unwrap();
+                accuracy_measurement(path, test.to_string(), "private", "fixed", "private", 1, "accuracy", 2.6 , false);
+                test_dir.close().unwrap();
             }
 
             #(#[test_case(TESTS[N])])*
-            fn ipa_prove_and_verify_ipa_output(test: &str) {
+            fn accuracy_measurement_public_inputs_(test: &str) {
                 crate::native_tests::init_binary();
+                crate::native_tests::setup_py_env();
                 let test_dir = TempDir::new(test).unwrap();
                 let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-               prove_and_verify(path, test.to_string(), "safe", "private", "private", "polycommit", 1, None, false, "single", Commitments::IPA, 2);
-               test_dir.close().unwrap();
+                accuracy_measurement(path, test.to_string(), "public", "private", "private", 1, "accuracy", 2.6, false);
+                test_dir.close().unwrap();
             }
 
-            });
 
-            seq!(N in 0..=45 {
+            #(#[test_case(TESTS[N])])*
+            fn resources_accuracy_measurement_public_outputs_(test: &str) {
+                crate::native_tests::init_binary();
+                crate::native_tests::setup_py_env();
+                let test_dir = TempDir::new(test).unwrap();
+                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
+                accuracy_measurement(path, test.to_string(), "private", "private", "public", 1, "resources", 3.1, false);
+                test_dir.close().unwrap();
+            }
 
-                #(#[test_case(WASM_TESTS[N])])*
-                fn kzg_prove_and_verify_with_overflow_(test: &str) {
-                    crate::native_tests::init_binary();
-                    // crate::native_tests::init_wasm();
-                    let test_dir = TempDir::new(test).unwrap();
-                    env_logger::init();
-                    let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-                    prove_and_verify(path, test.to_string(), "safe", "private", "private", "public", 1, None, true, "single", Commitments::KZG, 2);
-                    #[cfg(not(feature = "icicle"))]
-                    run_js_tests(path, test.to_string(), "testWasm", false);
-                    test_dir.close().unwrap();
-                }
+            #(#[test_case(TESTS[N])])*
+            fn mock_public_outputs_(test: &str) {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new(test).unwrap();
+                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
+                mock(path, test.to_string(), "private", "private", "public", 1, "resources", None, 0.0);
+                test_dir.close().unwrap();
+            }
 
-                #(#[test_case(WASM_TESTS[N])])*
-                fn kzg_prove_and_verify_with_overflow_hashed_inputs_(test: &str) {
-                    crate::native_tests::init_binary();
-                    // crate::native_tests::init_wasm();
-                    let test_dir = TempDir::new(test).unwrap();
-                    env_logger::init();
-                    let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-                    prove_and_verify(path, test.to_string(), "safe", "hashed", "private", "public", 1, None, true, "single", Commitments::KZG, 2);
-                    #[cfg(not(feature = "icicle"))]
-                    run_js_tests(path, test.to_string(), "testWasm", false);
-                    test_dir.close().unwrap();
-                }
+            #(#[test_case(TESTS[N])])*
+            fn mock_tolerance_public_outputs_(test: &str) {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new(test).unwrap();
+                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
+                // gen random number between 0.0 and 1.0
+                let tolerance = rand::thread_rng().gen_range(0.0..1.0) * 100.0;
+                mock(path, test.to_string(), "private", "private", "public", 1, "resources", None, tolerance);
+                test_dir.close().unwrap();
+            }
 
-                #(#[test_case(WASM_TESTS[N])])*
-                fn kzg_prove_and_verify_with_overflow_fixed_params_(test: &str) {
+
+
+            #(#[test_case(TESTS[N])])*
+            fn mock_large_batch_public_outputs_(test: &str) {
+                // currently variable output rank is not supported in ONNX
+                if test != "gather_nd" && test != "lstm_large"  && test != "lstm_medium" {
                     crate::native_tests::init_binary();
-                    // crate::native_tests::init_wasm();
                     let test_dir = TempDir::new(test).unwrap();
-                    env_logger::init();
                     let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-                    prove_and_verify(path, test.to_string(), "safe", "private", "fixed", "public", 1, None, true, "single", Commitments::KZG, 2);
-                    #[cfg(not(feature = "icicle"))]
-                    run_js_tests(path, test.to_string(), "testWasm", false);
+                    let large_batch_dir = &format!("large_batches_{}", test);
+                    crate::native_tests::mk_data_batches_(path, test, &large_batch_dir, 10);
+                    mock(path, large_batch_dir.to_string(), "private", "private", "public", 10, "resources", None, 0.0);
                     test_dir.close().unwrap();
                 }
+            }
 
-            });
-
-            seq!(N in 0..=5 {
-
-            #(#[test_case(LARGE_TESTS[N])])*
-            #[ignore]
-            fn large_prove_and_verify_(test: &str) {
+            #(#[test_case(TESTS[N])])*
+            fn mock_public_inputs_(test: &str) {
                 crate::native_tests::init_binary();
                 let test_dir = TempDir::new(test).unwrap();
                 let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-                prove_and_verify(path, test.to_string(), "unsafe", "private", "fixed", "public", 1, None, false, "single", Commitments::KZG, 2);
+                mock(path, test.to_string(), "public", "private", "private", 1, "resources", None, 0.0);
                 test_dir.close().unwrap();
             }
 
-            #(#[test_case(LARGE_TESTS[N])])*
-            #[ignore]
-            fn large_mock_(test: &str) {
+            #(#[test_case(TESTS[N])])*
+            fn mock_hashed_params_public_inputs_(test: &str) {
                 crate::native_tests::init_binary();
                 let test_dir = TempDir::new(test).unwrap();
                 let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-                mock(path, test.to_string(), "private", "fixed", "public", 1, "resources", None, 0.0);
+                mock(path, test.to_string(), "public", "hashed", "private", 1, "resources", None, 0.0);
                 test_dir.close().unwrap();
             }
-        });
-    }
-    };
-}
 
-    macro_rules! test_func_evm {
-    () => {
-        #[cfg(test)]
-        mod tests_evm {
-            use seq_macro::seq;
-            use crate::native_tests::TESTS_EVM;
-            use crate::native_tests::TESTS;
-            use crate::native_tests::TESTS_EVM_AGGR;
-            use test_case::test_case;
-            use crate::native_tests::kzg_evm_prove_and_verify;
-            use crate::native_tests::kzg_evm_prove_and_verify_reusable_verifier;
-
-            use crate::native_tests::kzg_evm_on_chain_input_prove_and_verify;
-            use crate::native_tests::kzg_evm_aggr_prove_and_verify;
-            use tempdir::TempDir;
-            use crate::native_tests::Hardfork;
-            use crate::native_tests::run_js_tests;
-            use ezkl::logger::init_logger;
-            use crate::native_tests::lazy_static;
+            #(#[test_case(TESTS[N])])*
+            fn mock_fixed_inputs_(test: &str) {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new(test).unwrap();
+                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
+                mock(path, test.to_string(), "fixed", "private", "private", 1, "resources", None, 0.0);
+                test_dir.close().unwrap();
+            }
 
-            // Global variables to store verifier hashes and identical verifiers
-            lazy_static! {
-                // create a new variable of type
-                static ref REUSABLE_VERIFIER_ADDR: std::sync::Mutex<Option<String>> = std::sync::Mutex::new(None);
+            #(#[test_case(TESTS[N])])*
+            fn mock_fixed_outputs_(test: &str) {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new(test).unwrap();
+                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
+                mock(path, test.to_string(), "private", "private", "fixed", 1, "resources", None, 0.0);
+                test_dir.close().unwrap();
             }
 
+            #(#[test_case(TESTS[N])])*
+            fn mock_fixed_params_(test: &str) {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new(test).unwrap();
+                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
+                mock(path, test.to_string(), "private", "fixed", "private", 1, "resources", None, 0.0);
+                test_dir.close().unwrap();
+            }
 
-            /// Currently only on chain inputs that return a non-negative value are supported.
-            const TESTS_ON_CHAIN_INPUT: [&str; 17] = [
-                "1l_mlp",
-                "1l_average",
-                "1l_reshape",
-                "1l_sigmoid",
-                "1l_div",
-                "1l_sqrt",
-                "1l_prelu",
-                "1l_var",
-                "1l_leakyrelu",
-                "1l_gelu_noappx",
-                "1l_relu",
-                "1l_tanh",
-                "2l_relu_sigmoid_small",
-                "2l_relu_small",
-                "2l_relu_fc",
-                "min",
-                "max"
-            ];
+            #(#[test_case(TESTS[N])])*
+            fn mock_hashed_input_(test: &str) {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new(test).unwrap();
+                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
+                mock(path, test.to_string(), "hashed", "private", "public", 1, "resources", None, 0.0);
+                test_dir.close().unwrap();
+            }
 
-            seq!(N in 0..=16 {
-                #(#[test_case((TESTS_ON_CHAIN_INPUT[N],Hardfork::Latest))])*
-                #(#[test_case((TESTS_ON_CHAIN_INPUT[N],Hardfork::Paris))])*
-                #(#[test_case((TESTS_ON_CHAIN_INPUT[N],Hardfork::London))])*
-                #(#[test_case((TESTS_ON_CHAIN_INPUT[N],Hardfork::Shanghai))])*
-                fn kzg_evm_on_chain_input_prove_and_verify_(test: (&str,Hardfork)) {
-                    let (test,hardfork) = test;
-                    crate::native_tests::init_binary();
-                    let test_dir = TempDir::new(test).unwrap();
-                    let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-                    let _anvil_child = crate::native_tests::start_anvil(true, hardfork);
-                    kzg_evm_on_chain_input_prove_and_verify(path, test.to_string(), "on-chain", "file", "public", "private", "private");
-                    // test_dir.close().unwrap();
-                }
+            #(#[test_case(TESTS[N])])*
+            fn mock_kzg_input_(test: &str) {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new(test).unwrap();
+                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
+                mock(path, test.to_string(), "polycommit", "private", "public", 1, "resources", None, 0.0);
+                test_dir.close().unwrap();
+            }
 
-                #(#[test_case(TESTS_ON_CHAIN_INPUT[N])])*
-                fn kzg_evm_on_chain_output_prove_and_verify_(test: &str) {
-                    crate::native_tests::init_binary();
-                    let test_dir = TempDir::new(test).unwrap();
-                    let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-                    let _anvil_child = crate::native_tests::start_anvil(true, Hardfork::Latest);
-                    kzg_evm_on_chain_input_prove_and_verify(path, test.to_string(), "file", "on-chain", "private", "public", "private");
-                    // test_dir.close().unwrap();
-                }
 
-                #(#[test_case(TESTS_ON_CHAIN_INPUT[N])])*
-                fn kzg_evm_on_chain_input_output_prove_and_verify_(test: &str) {
-                    crate::native_tests::init_binary();
-                    let test_dir = TempDir::new(test).unwrap();
-                    let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-                    let _anvil_child = crate::native_tests::start_anvil(true, Hardfork::Latest);
-                    kzg_evm_on_chain_input_prove_and_verify(path, test.to_string(), "on-chain", "on-chain", "public", "public", "private");
-                    test_dir.close().unwrap();
-                }
+            #(#[test_case(TESTS[N])])*
+            fn mock_hashed_params_(test: &str) {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new(test).unwrap();
+                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
+                mock(path, test.to_string(), "private", "hashed", "public", 1, "resources", None, 0.0);
+                test_dir.close().unwrap();
+            }
 
-                #(#[test_case(TESTS_ON_CHAIN_INPUT[N])])*
-                fn kzg_evm_on_chain_input_output_hashed_prove_and_verify_(test: &str) {
-                    crate::native_tests::init_binary();
-                    let test_dir = TempDir::new(test).unwrap();
-                    let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-                    let _anvil_child = crate::native_tests::start_anvil(true, Hardfork::Latest);
-                    kzg_evm_on_chain_input_prove_and_verify(path, test.to_string(), "on-chain", "on-chain", "hashed", "hashed", "private");
-                    test_dir.close().unwrap();
-                }
-                #(#[test_case(TESTS_ON_CHAIN_INPUT[N])])*
-                fn kzg_evm_on_chain_input_kzg_output_kzg_params_prove_and_verify_(test: &str) {
-                    crate::native_tests::init_binary();
-                    let test_dir = TempDir::new(test).unwrap();
-                    let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-                    let _anvil_child = crate::native_tests::start_anvil(true, Hardfork::Latest);
-                    kzg_evm_on_chain_input_prove_and_verify(path, test.to_string(), "on-chain", "file", "public", "polycommit", "polycommit");
-                    test_dir.close().unwrap();
-                }
-                #(#[test_case(TESTS_ON_CHAIN_INPUT[N])])*
-                fn kzg_evm_on_chain_output_kzg_input_kzg_params_prove_and_verify_(test: &str) {
-                    crate::native_tests::init_binary();
-                    let test_dir = TempDir::new(test).unwrap();
-                    let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-                    let _anvil_child = crate::native_tests::start_anvil(true, Hardfork::Latest);
-                    kzg_evm_on_chain_input_prove_and_verify(path, test.to_string(), "file", "on-chain", "polycommit", "public", "polycommit");
-                    test_dir.close().unwrap();
-                }
-                #(#[test_case(TESTS_ON_CHAIN_INPUT[N])])*
-                fn kzg_evm_on_chain_all_kzg_params_prove_and_verify_(test: &str) {
-                    crate::native_tests::init_binary();
-                    let test_dir = TempDir::new(test).unwrap();
-                    let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-                    let _anvil_child = crate::native_tests::start_anvil(true, Hardfork::Latest);
-                    kzg_evm_on_chain_input_prove_and_verify(path, test.to_string(), "file", "file", "polycommit", "polycommit", "polycommit");
-                    test_dir.close().unwrap();
-                }
-            });
 
+            #(#[test_case(TESTS[N])])*
+            fn mock_kzg_params_(test: &str) {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new(test).unwrap();
+                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
+                mock(path, test.to_string(), "private", "polycommit", "public", 1, "resources", None, 0.0);
+                test_dir.close().unwrap();
+            }
 
-            seq!(N in 0..=17 {
-                // these take a particularly long time to run
-                #(#[test_case(TESTS_EVM_AGGR[N])])*
-                #[ignore]
-                fn kzg_evm_aggr_prove_and_verify_(test: &str) {
-                    crate::native_tests::init_binary();
-                    let test_dir = TempDir::new(test).unwrap();
-                    let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-                    let _anvil_child = crate::native_tests::start_anvil(false, Hardfork::Latest);
-                    kzg_evm_aggr_prove_and_verify(path, test.to_string(), "private", "private", "public");
-                    test_dir.close().unwrap();
-                }
+            #(#[test_case(TESTS[N])])*
+            fn mock_hashed_output_(test: &str) {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new(test).unwrap();
+                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
+                mock(path, test.to_string(), "public", "private", "hashed", 1, "resources", None, 0.0);
+                test_dir.close().unwrap();
+            }
 
-            });
 
-            seq!(N in 0..=93 {
-                #(#[test_case(TESTS[N])])*
-                fn kzg_evm_prove_and_verify_reusable_verifier_(test: &str) {
-                    crate::native_tests::init_binary();
-                    let test_dir = TempDir::new(test).unwrap();
-                    let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-                    let _anvil_child = crate::native_tests::start_anvil(false, Hardfork::Latest);
-                    init_logger();
-                    log::error!("Running kzg_evm_prove_and_verify_reusable_verifier_ for test: {}", test);
-                    // default vis
-                    let reusable_verifier_address: String = kzg_evm_prove_and_verify_reusable_verifier(2, path, test.to_string(), "private", "private", "public", &mut REUSABLE_VERIFIER_ADDR.lock().unwrap(), false);
-                    // public/public vis
-                    let reusable_verifier_address: String = kzg_evm_prove_and_verify_reusable_verifier(2, path, test.to_string(), "public", "private", "public", &mut Some(reusable_verifier_address), false);
-                    // hashed input
-                    let reusable_verifier_address: String = kzg_evm_prove_and_verify_reusable_verifier(2, path, test.to_string(), "hashed", "private", "public", &mut Some(reusable_verifier_address), false);
+            #(#[test_case(TESTS[N])])*
+            fn mock_kzg_output_(test: &str) {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new(test).unwrap();
+                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
+                mock(path, test.to_string(), "public", "private", "polycommit", 1, "resources", None, 0.0);
+                test_dir.close().unwrap();
+            }
 
-                    match REUSABLE_VERIFIER_ADDR.try_lock() {
-                        Ok(mut addr) => {
-                            *addr = Some(reusable_verifier_address.clone());
-                            log::error!("Reusing the same verifeir deployed at address: {}", reusable_verifier_address);
-                        }
-                        Err(_) => {
-                            log::error!("Failed to acquire lock on REUSABLE_VERIFIER_ADDR");
-                        }
-                    }
+            #(#[test_case(TESTS[N])])*
+            fn mock_hashed_output_fixed_params_(test: &str) {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new(test).unwrap();
+                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
+                mock(path, test.to_string(), "public", "fixed", "hashed", 1, "resources", None, 0.0);
+                test_dir.close().unwrap();
+            }
 
-                    test_dir.close().unwrap();
 
-                }
+            #(#[test_case(TESTS[N])])*
+            fn mock_hashed_output_kzg_params_(test: &str) {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new(test).unwrap();
+                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
+                mock(path, test.to_string(), "public", "polycommit", "hashed", 1, "resources", None, 0.0);
+                test_dir.close().unwrap();
+            }
 
-                #(#[test_case(TESTS[N])])*
-                fn kzg_evm_prove_and_verify_reusable_verifier_with_overflow_(test: &str) {
-                    // verifier too big to fit on chain with overflow calibration target
-                    if test == "1l_eltwise_div" || test == "lenet_5" || test == "ltsf" || test == "lstm_large" {
-                        return;
-                    }
-                    crate::native_tests::init_binary();
-                    let test_dir = TempDir::new(test).unwrap();
-                    let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-                    let _anvil_child = crate::native_tests::start_anvil(false, Hardfork::Latest);
-                    init_logger();
-                    log::error!("Running kzg_evm_prove_and_verify_reusable_verifier_with_overflow_ for test: {}", test);
-                    // default vis
-                    let reusable_verifier_address: String = kzg_evm_prove_and_verify_reusable_verifier(2, path, test.to_string(), "private", "private", "public", &mut REUSABLE_VERIFIER_ADDR.lock().unwrap(), true);
-                    // public/public vis
-                    let reusable_verifier_address: String = kzg_evm_prove_and_verify_reusable_verifier(2, path, test.to_string(), "public", "private", "public", &mut Some(reusable_verifier_address), true);
-                    // hashed input
-                    let reusable_verifier_address: String = kzg_evm_prove_and_verify_reusable_verifier(2, path, test.to_string(), "hashed", "private", "public", &mut Some(reusable_verifier_address), true);
 
-                    match REUSABLE_VERIFIER_ADDR.try_lock() {
-                        Ok(mut addr) => {
-                            *addr = Some(reusable_verifier_address.clone());
-                            log::error!("Reusing the same verifeir deployed at address: {}", reusable_verifier_address);
-                        }
-                        Err(_) => {
-                            log::error!("Failed to acquire lock on REUSABLE_VERIFIER_ADDR");
-                        }
-                    }
+            #(#[test_case(TESTS[N])])*
+            fn mock_kzg_all_(test: &str) {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new(test).unwrap();
+                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
+                mock(path, test.to_string(), "polycommit", "polycommit", "polycommit", 1, "resources", None, 0.0);
+                test_dir.close().unwrap();
+            }
 
-                    test_dir.close().unwrap();
 
-                }
-            });
+            #(#[test_case(TESTS[N])])*
+            fn mock_hashed_input_output_(test: &str) {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new(test).unwrap();
+                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
+                mock(path, test.to_string(), "hashed", "private", "hashed", 1, "resources", None, 0.0);
+                test_dir.close().unwrap();
+            }
 
+            #(#[test_case(TESTS[N])])*
+            fn mock_hashed_input_params_(test: &str) {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new(test).unwrap();
+                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
+                // needs an extra row for the large model
+                mock(path, test.to_string(),"hashed", "hashed", "public", 1, "resources", None, 0.0);
+                test_dir.close().unwrap();
+            }
 
-            seq!(N in 0..=22 {
+            #(#[test_case(TESTS[N])])*
+            fn mock_hashed_all_(test: &str) {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new(test).unwrap();
+                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
+                // needs an extra row for the large model
+                mock(path, test.to_string(),"hashed", "hashed", "hashed", 1, "resources", None, 0.0);
+                test_dir.close().unwrap();
+            }
 
-                #(#[test_case(TESTS_EVM[N])])*
-                fn kzg_evm_prove_and_verify_(test: &str) {
-                    crate::native_tests::init_binary();
-                    let test_dir = TempDir::new(test).unwrap();
-                    let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-                    let _anvil_child = crate::native_tests::start_anvil(false, Hardfork::Latest);
-                    kzg_evm_prove_and_verify(2, path, test.to_string(), "private", "private", "public");
-                    #[cfg(not(feature = "icicle"))]
-                    run_js_tests(path, test.to_string(), "testBrowserEvmVerify", false);
-                    test_dir.close().unwrap();
+            #(#[test_case(TESTS[N])])*
+            fn kzg_prove_and_verify_single_col(test: &str) {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new(test).unwrap();
+                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
+               prove_and_verify(path, test.to_string(), "safe", "private", "private", "public", 1, None, false, "single", Commitments::KZG, 2);
+               test_dir.close().unwrap();
+            }
 
-                }
+            #(#[test_case(TESTS[N])])*
+            fn kzg_prove_and_verify_triple_col(test: &str) {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new(test).unwrap();
+                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
+               prove_and_verify(path, test.to_string(), "safe", "private", "private", "public", 3, None, false, "single", Commitments::KZG, 2);
+               test_dir.close().unwrap();
+            }
 
+            #(#[test_case(TESTS[N])])*
+            fn kzg_prove_and_verify_quadruple_col(test: &str) {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new(test).unwrap();
+                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
+               prove_and_verify(path, test.to_string(), "safe", "private", "private", "public", 4, None, false, "single", Commitments::KZG, 2);
+               test_dir.close().unwrap();
+            }
 
-                #(#[test_case(TESTS_EVM[N])])*
-                fn kzg_evm_hashed_input_prove_and_verify_(test: &str) {
-                    crate::native_tests::init_binary();
-                    let test_dir = TempDir::new(test).unwrap();
-                    let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-                    let mut _anvil_child = crate::native_tests::start_anvil(false, Hardfork::Latest);
-                    kzg_evm_prove_and_verify(2, path, test.to_string(), "hashed", "private", "private");
-                    #[cfg(not(feature = "icicle"))]
-                    run_js_tests(path, test.to_string(), "testBrowserEvmVerify", false);
-                    test_dir.close().unwrap();
-                }
+            #(#[test_case(TESTS[N])])*
+            fn kzg_prove_and_verify_octuple_col(test: &str) {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new(test).unwrap();
+                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
+               prove_and_verify(path, test.to_string(), "safe", "private", "private", "public", 8, None, false, "single", Commitments::KZG, 2);
+               test_dir.close().unwrap();
+            }
 
+            #(#[test_case(TESTS[N])])*
+            fn kzg_prove_and_verify_(test: &str) {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new(test).unwrap();
+                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
+               prove_and_verify(path, test.to_string(), "safe", "private", "private", "public", 1, None, false, "single", Commitments::KZG, 2);
+               test_dir.close().unwrap();
+            }
 
-                #(#[test_case((TESTS_EVM[N], Hardfork::Latest))])*
-                #(#[test_case((TESTS_EVM[N], Hardfork::Paris))])*
-                #(#[test_case((TESTS_EVM[N], Hardfork::London))])*
-                #(#[test_case((TESTS_EVM[N], Hardfork::Shanghai))])*
-                fn kzg_evm_kzg_input_prove_and_verify_(test: (&str, Hardfork)) {
-                    let (test,hardfork) = test;
-                    crate::native_tests::init_binary();
-                    let test_dir = TempDir::new(test).unwrap();
-                    let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-                    let mut _anvil_child = crate::native_tests::start_anvil(false, hardfork);
-                    kzg_evm_prove_and_verify(2, path, test.to_string(), "polycommit", "private", "public");
-                    #[cfg(not(feature = "icicle"))]
-                    run_js_tests(path, test.to_string(), "testBrowserEvmVerify", false);
-                    test_dir.close().unwrap();
-                }
+            #(#[test_case(TESTS[N])])*
+            fn kzg_prove_and_verify_tight_lookup_(test: &str) {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new(test).unwrap();
+                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
+               prove_and_verify(path, test.to_string(), "safe", "private", "private", "public", 1, None, false, "single", Commitments::KZG, 1);
+               test_dir.close().unwrap();
+            }
 
+            #(#[test_case(TESTS[N])])*
+            fn ipa_prove_and_verify_(test: &str) {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new(test).unwrap();
+                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
+               prove_and_verify(path, test.to_string(), "safe", "private", "private", "public", 1, None, false, "single", Commitments::IPA, 2);
+               test_dir.close().unwrap();
+            }
 
-                #(#[test_case(TESTS_EVM[N])])*
-                fn kzg_evm_hashed_params_prove_and_verify_(test: &str) {
-                    crate::native_tests::init_binary();
-                    let test_dir = TempDir::new(test).unwrap();
-                    let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-                    let _anvil_child = crate::native_tests::start_anvil(false, Hardfork::Latest);
-                    kzg_evm_prove_and_verify(2, path, test.to_string(), "private", "hashed", "public");
-                    #[cfg(not(feature = "icicle"))]
-                    run_js_tests(path, test.to_string(), "testBrowserEvmVerify", false);
-                    test_dir.close().unwrap();
+            #(#[test_case(TESTS[N])])*
+            fn kzg_prove_and_verify_public_input_(test: &str) {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new(test).unwrap();
+                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
+               prove_and_verify(path, test.to_string(), "safe", "public", "private", "public", 1, None, false, "single", Commitments::KZG, 2);
+               test_dir.close().unwrap();
+            }
 
-                }
+            #(#[test_case(TESTS[N])])*
+            fn kzg_prove_and_verify_fixed_params_(test: &str) {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new(test).unwrap();
+                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
+               prove_and_verify(path, test.to_string(), "safe", "private", "fixed", "public", 1, None, false, "single", Commitments::KZG, 2);
+               test_dir.close().unwrap();
+            }
 
-                #(#[test_case(TESTS_EVM[N])])*
-                fn kzg_evm_hashed_output_prove_and_verify_(test: &str) {
-                    crate::native_tests::init_binary();
-                    let test_dir = TempDir::new(test).unwrap();
-                    let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-                    let _anvil_child = crate::native_tests::start_anvil(false, Hardfork::Latest);
-                    kzg_evm_prove_and_verify(2, path, test.to_string(), "private", "private", "hashed");
-                    #[cfg(not(feature = "icicle"))]
-                    run_js_tests(path, test.to_string(), "testBrowserEvmVerify", false);
-                    test_dir.close().unwrap();
-                }
+            #(#[test_case(TESTS[N])])*
+            fn kzg_prove_and_verify_hashed_output(test: &str) {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new(test).unwrap();
+                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
+               prove_and_verify(path, test.to_string(), "safe", "private", "private", "hashed", 1, None, false, "single", Commitments::KZG, 2);
+               test_dir.close().unwrap();
+            }
 
+            #(#[test_case(TESTS[N])])*
+            fn kzg_prove_and_verify_kzg_output(test: &str) {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new(test).unwrap();
+                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
+               prove_and_verify(path, test.to_string(), "safe", "private", "private", "polycommit", 1, None, false, "single", Commitments::KZG, 2);
+               test_dir.close().unwrap();
+            }
 
-                #(#[test_case(TESTS_EVM[N])])*
-                fn kzg_evm_kzg_params_prove_and_verify_(test: &str) {
+            #(#[test_case(TESTS[N])])*
+            fn ipa_prove_and_verify_ipa_output(test: &str) {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new(test).unwrap();
+                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
+               prove_and_verify(path, test.to_string(), "safe", "private", "private", "polycommit", 1, None, false, "single", Commitments::IPA, 2);
+               test_dir.close().unwrap();
+            }
+
+            });
+
+            seq!(N in 0..=45 {
+
+                #(#[test_case(WASM_TESTS[N])])*
+                fn kzg_prove_and_verify_with_overflow_(test: &str) {
                     crate::native_tests::init_binary();
+                    // crate::native_tests::init_wasm();
                     let test_dir = TempDir::new(test).unwrap();
+                    env_logger::init();
                     let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-                    let _anvil_child = crate::native_tests::start_anvil(false, Hardfork::Latest);
-                    kzg_evm_prove_and_verify(2, path, test.to_string(), "private", "polycommit", "public");
+                    prove_and_verify(path, test.to_string(), "safe", "private", "private", "public", 1, None, true, "single", Commitments::KZG, 2);
                     #[cfg(not(feature = "icicle"))]
-                    run_js_tests(path, test.to_string(), "testBrowserEvmVerify", false);
+                    run_js_tests(path, test.to_string(), "testWasm", false);
                     test_dir.close().unwrap();
                 }
 
-
-                #(#[test_case(TESTS_EVM[N])])*
-                fn kzg_evm_kzg_output_prove_and_verify_(test: &str) {
+                #(#[test_case(WASM_TESTS[N])])*
+                fn kzg_prove_and_verify_with_overflow_hashed_inputs_(test: &str) {
                     crate::native_tests::init_binary();
+                    // crate::native_tests::init_wasm();
                     let test_dir = TempDir::new(test).unwrap();
+                    env_logger::init();
                     let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-                    let _anvil_child = crate::native_tests::start_anvil(false, Hardfork::Latest);
-                    kzg_evm_prove_and_verify(2, path, test.to_string(), "private", "private", "polycommit");
+                    prove_and_verify(path, test.to_string(), "safe", "hashed", "private", "public", 1, None, true, "single", Commitments::KZG, 2);
                     #[cfg(not(feature = "icicle"))]
-                    run_js_tests(path, test.to_string(), "testBrowserEvmVerify", false);
+                    run_js_tests(path, test.to_string(), "testWasm", false);
                     test_dir.close().unwrap();
                 }
 
-                #(#[test_case(TESTS_EVM[N])])*
-                fn kzg_evm_kzg_all_prove_and_verify_(test: &str) {
+                #(#[test_case(WASM_TESTS[N])])*
+                fn kzg_prove_and_verify_with_overflow_fixed_params_(test: &str) {
                     crate::native_tests::init_binary();
+                    // crate::native_tests::init_wasm();
                     let test_dir = TempDir::new(test).unwrap();
+                    env_logger::init();
                     let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
-                    let _anvil_child = crate::native_tests::start_anvil(false, Hardfork::Latest);
-                    kzg_evm_prove_and_verify(2, path, test.to_string(), "polycommit", "polycommit", "polycommit");
+                    prove_and_verify(path, test.to_string(), "safe", "private", "fixed", "public", 1, None, true, "single", Commitments::KZG, 2);
                     #[cfg(not(feature = "icicle"))]
-                    run_js_tests(path, test.to_string(), "testBrowserEvmVerify", false);
+                    run_js_tests(path, test.to_string(), "testWasm", false);
                     test_dir.close().unwrap();
                 }
 
+            });
 
+            seq!(N in 0..=5 {
 
+            #(#[test_case(LARGE_TESTS[N])])*
+            #[ignore]
+            fn large_prove_and_verify_(test: &str) {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new(test).unwrap();
+                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
+                prove_and_verify(path, test.to_string(), "unsafe", "private", "fixed", "public", 1, None, false, "single", Commitments::KZG, 2);
+                test_dir.close().unwrap();
+            }
 
-            });
+            #(#[test_case(LARGE_TESTS[N])])*
+            #[ignore]
+            fn large_prove_timeout_(test: &str) {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new(test).unwrap();
+                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
+                prove_timeout(path, test.to_string(), 2);
+                test_dir.close().unwrap();
+            }
+
+            #(#[test_case(LARGE_TESTS[N])])*
+            #[ignore]
+            fn large_mock_(test: &str) {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new(test).unwrap();
+                let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
+                mock(path, test.to_string(), "private", "fixed", "public", 1, "resources", None, 0.0);
+                test_dir.close().unwrap();
+            }
+        });
+    }
+    };
+}
+
+    macro_rules! test_func_evm {
+    () => {
+        #[cfg(test)]
+        mod tests_evm {
+            use seq_macro::seq;
+            use crate::native_tests::TESTS_EVM;
+            use crate::native_tests::TESTS;
+            use crate::native_tests::TESTS_EVM_AGGR;
+            use test_case::test_case;
+            use crate::native_tests::kzg_evm_prove_and_verify;
+            use crate::native_tests::kzg_evm_prove_and_verify_reusable_verifier;
+            use crate::native_tests::kzg_evm_reusable_verifier_two_models;
+            use crate::native_tests::estimate_accuracy;
+
+            use crate::native_tests::kzg_evm_on_chain_input_prove_and_verify;
+            use crate::native_tests::kzg_evm_aggr_prove_and_verify;
+            use crate::native_tests::kzg_evm_aggr_heterogeneous_prove_and_verify;
+            use tempdir::TempDir;
+            use crate::native_tests::Hardfork;
+            use crate::native_tests::run_js_tests;
+            use ezkl::logger::init_logger;
+            use crate::native_tests::lazy_static;
+
+            // Global variables to store verifier hashes and identical verifiers
+            lazy_static! {
+                // create a new variable of type
+                static ref REUSABLE_VERIFIER_ADDR: std::sync::Mutex<Option<String>> = std::sync::Mutex::new(None);
+            }
+
+
+            /// Currently only on chain inputs that return a non-negative value are supported.
+            const TESTS_ON_CHAIN_INPUT: [&str; 17] = [
+                "1l_mlp",
+                "1l_average",
+                "1l_reshape",
+                "1l_sigmoid",
+                "1l_div",
+                "1l_sqrt",
+                "1l_prelu",
+                "1l_var",
+                "1l_leakyrelu",
+                "1l_gelu_noappx",
+                "1l_relu",
+                "1l_tanh",
+                "2l_relu_sigmoid_small",
+                "2l_relu_small",
+                "2l_relu_fc",
+                "min",
+                "max"
+            ];
+
+            seq!(N in 0..=16 {
+                #(#[test_case((TESTS_ON_CHAIN_INPUT[N],Hardfork::Latest))])*
+                #(#[test_case((TESTS_ON_CHAIN_INPUT[N],Hardfork::Paris))])*
+                #(#[test_case((TESTS_ON_CHAIN_INPUT[N],Hardfork::London))])*
+                #(#[test_case((TESTS_ON_CHAIN_INPUT[N],Hardfork::Shanghai))])*
+                fn kzg_evm_on_chain_input_prove_and_verify_(test: (&str,Hardfork)) {
+                    let (test,hardfork) = test;
+                    crate::native_tests::init_binary();
+                    let test_dir = TempDir::new(test).unwrap();
+                    let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
+                    let _anvil_child = crate::native_tests::start_anvil(true, hardfork);
+                    kzg_evm_on_chain_input_prove_and_verify(path, test.to_string(), "on-chain", "file", "public", "private", "private");
+                    // test_dir.close().unwrap();
+                }
+
+                #(#[test_case(TESTS_ON_CHAIN_INPUT[N])])*
+                fn kzg_evm_on_chain_output_prove_and_verify_(test: &str) {
+                    crate::native_tests::init_binary();
+                    let test_dir = TempDir::new(test).unwrap();
+                    let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
+                    let _anvil_child = crate::native_tests::start_anvil(true, Hardfork::Latest);
+                    kzg_evm_on_chain_input_prove_and_verify(path, test.to_string(), "file", "on-chain", "private", "public", "private");
+                    // test_dir.close().unwrap();
+                }
+
+                #(#[test_case(TESTS_ON_CHAIN_INPUT[N])])*
+                fn kzg_evm_on_chain_input_output_prove_and_verify_(test: &str) {
+                    crate::native_tests::init_binary();
+                    let test_dir = TempDir::new(test).unwrap();
+                    let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
+                    let _anvil_child = crate::native_tests::start_anvil(true, Hardfork::Latest);
+                    kzg_evm_on_chain_input_prove_and_verify(path, test.to_string(), "on-chain", "on-chain", "public", "public", "private");
+                    test_dir.close().unwrap();
+                }
+
+                #(#[test_case(TESTS_ON_CHAIN_INPUT[N])])*
+                fn kzg_evm_on_chain_input_output_hashed_prove_and_verify_(test: &str) {
+                    crate::native_tests::init_binary();
+                    let test_dir = TempDir::new(test).unwrap();
+                    let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
+                    let _anvil_child = crate::native_tests::start_anvil(true, Hardfork::Latest);
+                    kzg_evm_on_chain_input_prove_and_verify(path, test.to_string(), "on-chain", "on-chain", "hashed", "hashed", "private");
+                    test_dir.close().unwrap();
+                }
+                #(#[test_case(TESTS_ON_CHAIN_INPUT[N])])*
+                fn kzg_evm_on_chain_input_kzg_output_kzg_params_prove_and_verify_(test: &str) {
+                    crate::native_tests::init_binary();
+                    let test_dir = TempDir::new(test).unwrap();
+                    let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
+                    let _anvil_child = crate::native_tests::start_anvil(true, Hardfork::Latest);
+                    kzg_evm_on_chain_input_prove_and_verify(path, test.to_string(), "on-chain", "file", "public", "polycommit", "polycommit");
+                    test_dir.close().unwrap();
+                }
+                #(#[test_case(TESTS_ON_CHAIN_INPUT[N])])*
+                fn kzg_evm_on_chain_output_kzg_input_kzg_params_prove_and_verify_(test: &str) {
+                    crate::native_tests::init_binary();
+                    let test_dir = TempDir::new(test).unwrap();
+                    let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
+                    let _anvil_child = crate::native_tests::start_anvil(true, Hardfork::Latest);
+                    kzg_evm_on_chain_input_prove_and_verify(path, test.to_string(), "file", "on-chain", "polycommit", "public", "polycommit");
+                    test_dir.close().unwrap();
+                }
+                #(#[test_case(TESTS_ON_CHAIN_INPUT[N])])*
+                fn kzg_evm_on_chain_all_kzg_params_prove_and_verify_(test: &str) {
+                    crate::native_tests::init_binary();
+                    let test_dir = TempDir::new(test).unwrap();
+                    let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
+                    let _anvil_child = crate::native_tests::start_anvil(true, Hardfork::Latest);
+                    kzg_evm_on_chain_input_prove_and_verify(path, test.to_string(), "file", "file", "polycommit", "polycommit", "polycommit");
+                    test_dir.close().unwrap();
+                }
+                #(#[test_case(TESTS_ON_CHAIN_INPUT[N])])*
+                fn kzg_evm_storage_proof_input_prove_and_verify_(test: &str) {
+                    crate::native_tests::init_binary();
+                    let test_dir = TempDir::new(test).unwrap();
+                    let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
+                    let _anvil_child = crate::native_tests::start_anvil(true, Hardfork::Latest);
+                    kzg_evm_on_chain_input_prove_and_verify(path, test.to_string(), "storage-proof", "file", "public", "private", "private");
+                    test_dir.close().unwrap();
+                }
+                #(#[test_case(TESTS_ON_CHAIN_INPUT[N])])*
+                fn kzg_evm_nested_on_chain_input_prove_and_verify_(test: &str) {
+                    crate::native_tests::init_binary();
+                    let test_dir = TempDir::new(test).unwrap();
+                    let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
+                    let _anvil_child = crate::native_tests::start_anvil(true, Hardfork::Latest);
+                    kzg_evm_on_chain_input_prove_and_verify(path, test.to_string(), "nested-on-chain", "file", "public", "private", "private");
+                    test_dir.close().unwrap();
+                }
+                #(#[test_case(TESTS_ON_CHAIN_INPUT[N])])*
+                fn kzg_evm_pinned_on_chain_input_prove_and_verify_(test: &str) {
+                    crate::native_tests::init_binary();
+                    let test_dir = TempDir::new(test).unwrap();
+                    let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
+                    let _anvil_child = crate::native_tests::start_anvil(true, Hardfork::Latest);
+                    kzg_evm_pinned_on_chain_input_prove_and_verify(path, test.to_string());
+                    test_dir.close().unwrap();
+                }
+            });
+
+
+            seq!(N in 0..=17 {
+                // these take a particularly long time to run
+                #(#[test_case(TESTS_EVM_AGGR[N])])*
+                #[ignore]
+                fn kzg_evm_aggr_prove_and_verify_(test: &str) {
+                    crate::native_tests::init_binary();
+                    let test_dir = TempDir::new(test).unwrap();
+                    let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
+                    let _anvil_child = crate::native_tests::start_anvil(false, Hardfork::Latest);
+                    kzg_evm_aggr_prove_and_verify(path, test.to_string(), "private", "private", "public");
+                    test_dir.close().unwrap();
+                }
+
+            });
+
+            #[test]
+            fn kzg_evm_reusable_verifier_two_models_() {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new("reusable_two_models").unwrap();
+                let path = test_dir.path().to_str().unwrap();
+                crate::native_tests::mv_test_(path, "1l_mlp");
+                crate::native_tests::mv_test_(path, "1l_relu");
+                let _anvil_child = crate::native_tests::start_anvil(false, Hardfork::Latest);
+                kzg_evm_reusable_verifier_two_models(path, "1l_mlp", "1l_relu");
+                test_dir.close().unwrap();
+            }
+
+            #[test]
+            fn kzg_evm_verify_batch_() {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new("verify_batch").unwrap();
+                let path = test_dir.path().to_str().unwrap();
+                crate::native_tests::mv_test_(path, "1l_mlp");
+                let _anvil_child = crate::native_tests::start_anvil(false, Hardfork::Latest);
+                kzg_evm_verify_batch(path, "1l_mlp", 4);
+                test_dir.close().unwrap();
+            }
+
+            #[test]
+            fn kzg_evm_estimate_gas_() {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new("estimate_gas").unwrap();
+                let path = test_dir.path().to_str().unwrap();
+                crate::native_tests::mv_test_(path, "1l_mlp");
+                let _anvil_child = crate::native_tests::start_anvil(false, Hardfork::Latest);
+                kzg_evm_estimate_gas(path, "1l_mlp");
+                test_dir.close().unwrap();
+            }
+
+            #[test_case("1l_mlp")]
+            #[test_case("1l_relu")]
+            #[test_case("2l_relu_sigmoid_small")]
+            #[test_case("1l_conv")]
+            fn estimate_accuracy_(test: &str) {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new(test).unwrap();
+                let path = test_dir.path().to_str().unwrap();
+                crate::native_tests::mv_test_(path, test);
+                // the verifier gas is measured against the verifier run in revm
+                estimate_accuracy(path, test.to_string());
+                test_dir.close().unwrap();
+            }
+
+            #[test]
+            fn kzg_evm_packed_instances_() {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new("packed_instances").unwrap();
+                let path = test_dir.path().to_str().unwrap();
+                crate::native_tests::mv_test_(path, "1l_mlp");
+                let _anvil_child = crate::native_tests::start_anvil(false, Hardfork::Latest);
+                kzg_evm_packed_instances(path, "1l_mlp");
+                test_dir.close().unwrap();
+            }
+
+            #[test]
+            fn kzg_evm_limb_packed_instances_() {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new("limb_packed_instances").unwrap();
+                let path = test_dir.path().to_str().unwrap();
+                crate::native_tests::mv_test_(path, "1l_mlp");
+                let _anvil_child = crate::native_tests::start_anvil(false, Hardfork::Latest);
+                kzg_evm_limb_packed_instances(path, "1l_mlp");
+                test_dir.close().unwrap();
+            }
+
+            #[test]
+            fn kzg_evm_registry_() {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new("registry").unwrap();
+                let path = test_dir.path().to_str().unwrap();
+                crate::native_tests::mv_test_(path, "1l_mlp");
+                crate::native_tests::mv_test_(path, "1l_relu");
+                let _anvil_child = crate::native_tests::start_anvil(false, Hardfork::Latest);
+                kzg_evm_registry(path, &["1l_mlp", "1l_relu"]);
+                test_dir.close().unwrap();
+            }
+
+            #[test]
+            fn kzg_evm_instance_bindings_() {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new("instance_bindings").unwrap();
+                let path = test_dir.path().to_str().unwrap();
+                crate::native_tests::mv_test_(path, "1l_mlp");
+                let _anvil_child = crate::native_tests::start_anvil(false, Hardfork::Latest);
+                kzg_evm_instance_bindings(path, "1l_mlp");
+                test_dir.close().unwrap();
+            }
+
+            #[test]
+            fn kzg_evm_foundry_harness_() {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new("foundry_harness").unwrap();
+                let path = test_dir.path().to_str().unwrap();
+                crate::native_tests::mv_test_(path, "1l_mlp");
+                kzg_evm_foundry_harness(path, "1l_mlp");
+                test_dir.close().unwrap();
+            }
+
+            #[test]
+            #[ignore]
+            fn kzg_evm_aggr_heterogeneous_prove_and_verify_() {
+                crate::native_tests::init_binary();
+                let test_dir = TempDir::new("aggr_heterogeneous").unwrap();
+                let path = test_dir.path().to_str().unwrap();
+                crate::native_tests::mv_test_(path, "1l_mlp");
+                crate::native_tests::mv_test_(path, "1l_conv");
+                let _anvil_child = crate::native_tests::start_anvil(false, Hardfork::Latest);
+                kzg_evm_aggr_heterogeneous_prove_and_verify(path, "1l_mlp", "1l_conv");
+                test_dir.close().unwrap();
+            }
+
+            seq!(N in 0..=93 {
+                #(#[test_case(TESTS[N])])*
+                fn kzg_evm_prove_and_verify_reusable_verifier_(test: &str) {
+                    crate::native_tests::init_binary();
+                    let test_dir = TempDir::new(test).unwrap();
+                    let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
+                    let _anvil_child = crate::native_tests::start_anvil(false, Hardfork::Latest);
+                    init_logger();
+                    log::error!("Running kzg_evm_prove_and_verify_reusable_verifier_ for test: {}", test);
+                    // default vis
+                    let reusable_verifier_address: String = kzg_evm_prove_and_verify_reusable_verifier(2, path, test.to_string(), "private", "private", "public", &mut REUSABLE_VERIFIER_ADDR.lock().unwrap(), false);
+                    // public/public vis
+                    let reusable_verifier_address: String = kzg_evm_prove_and_verify_reusable_verifier(2, path, test.to_string(), "public", "private", "public", &mut Some(reusable_verifier_address), false);
+                    // hashed input
+                    let reusable_verifier_address: String = kzg_evm_prove_and_verify_reusable_verifier(2, path, test.to_string(), "hashed", "private", "public", &mut Some(reusable_verifier_address), false);
+
+                    match REUSABLE_VERIFIER_ADDR.try_lock() {
+                        Ok(mut addr) => {
+                            *addr = Some(reusable_verifier_address.clone());
+                            log::error!("Reusing the same verifeir deployed at address: {}", reusable_verifier_address);
+                        }
+                        Err(_) => {
+                            log::error!("Failed to acquire lock on REUSABLE_VERIFIER_ADDR");
+                        }
+                    }
+
+                    test_dir.close().unwrap();
+
+                }
+
+                #(#[test_case(TESTS[N])])*
+                fn kzg_evm_prove_and_verify_reusable_verifier_with_overflow_(test: &str) {
+                    // verifier too big to fit on chain with overflow calibration target
+                    if test == "1l_eltwise_div" || test == "lenet_5" || test == "ltsf" || test == "lstm_large" {
+                        return;
+                    }
+                    crate::native_tests::init_binary();
+                    let test_dir = TempDir::new(test).unwrap();
+                    let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
+                    let _anvil_child = crate::native_tests::start_anvil(false, Hardfork::Latest);
+                    init_logger();
+                    log::error!("Running kzg_evm_prove_and_verify_reusable_verifier_with_overflow_ for test: {}", test);
+                    // default vis
+                    let reusable_verifier_address: String = kzg_evm_prove_and_verify_reusable_verifier(2, path, test.to_string(), "private", "private", "public", &mut REUSABLE_VERIFIER_ADDR.lock().unwrap(), true);
+                    // public/public vis
+                    let reusable_verifier_address: String = kzg_evm_prove_and_verify_reusable_verifier(2, path, test.to_string(), "public", "private", "public", &mut Some(reusable_verifier_address), true);
+                    // hashed input
+                    let reusable_verifier_address: String = kzg_evm_prove_and_verify_reusable_verifier(2, path, test.to_string(), "hashed", "private", "public", &mut Some(reusable_verifier_address), true);
+
+                    match REUSABLE_VERIFIER_ADDR.try_lock() {
+                        Ok(mut addr) => {
+                            *addr = Some(reusable_verifier_address.clone());
+                            log::error!("Reusing the same verifeir deployed at address: {}", reusable_verifier_address);
+                        }
+                        Err(_) => {
+                            log::error!("Failed to acquire lock on REUSABLE_VERIFIER_ADDR");
+                        }
+                    }
+
+                    test_dir.close().unwrap();
+
+                }
+            });
+
+
+            seq!(N in 0..=22 {
+
+                #(#[test_case(TESTS_EVM[N])])*
+                fn kzg_evm_prove_and_verify_(test: &str) {
+                    crate::native_tests::init_binary();
+                    let test_dir = TempDir::new(test).unwrap();
+                    let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
+                    let _anvil_child = crate::native_tests::start_anvil(false, Hardfork::Latest);
+                    kzg_evm_prove_and_verify(2, path, test.to_string(), "private", "private", "public");
+                    #[cfg(not(feature = "icicle"))]
+                    run_js_tests(path, test.to_string(), "testBrowserEvmVerify", false);
+                    test_dir.close().unwrap();
+
+                }
+
+
+                #(#[test_case(TESTS_EVM[N])])*
+                fn kzg_evm_hashed_input_prove_and_verify_(test: &str) {
+                    crate::native_tests::init_binary();
+                    let test_dir = TempDir::new(test).unwrap();
+                    let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
+                    let mut _anvil_child = crate::native_tests::start_anvil(false, Hardfork::Latest);
+                    kzg_evm_prove_and_verify(2, path, test.to_string(), "hashed", "private", "private");
+                    #[cfg(not(feature = "icicle"))]
+                    run_js_tests(path, test.to_string(), "testBrowserEvmVerify", false);
+                    test_dir.close().unwrap();
+                }
+
+
+                #(#[test_case((TESTS_EVM[N], Hardfork::Latest))])*
+                #(#[test_case((TESTS_EVM[N], Hardfork::Paris))])*
+                #(#[test_case((TESTS_EVM[N], Hardfork::London))])*
+                #(#[test_case((TESTS_EVM[N], Hardfork::Shanghai))])*
+                fn kzg_evm_kzg_input_prove_and_verify_(test: (&str, Hardfork)) {
+                    let (test,hardfork) = test;
+                    crate::native_tests::init_binary();
+                    let test_dir = TempDir::new(test).unwrap();
+                    let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
+                    let mut _anvil_child = crate::native_tests::start_anvil(false, hardfork);
+                    kzg_evm_prove_and_verify(2, path, test.to_string(), "polycommit", "private", "public");
+                    #[cfg(not(feature = "icicle"))]
+                    run_js_tests(path, test.to_string(), "testBrowserEvmVerify", false);
+                    test_dir.close().unwrap();
+                }
+
+
+                #(#[test_case(TESTS_EVM[N])])*
+                fn kzg_evm_hashed_params_prove_and_verify_(test: &str) {
+                    crate::native_tests::init_binary();
+                    let test_dir = TempDir::new(test).unwrap();
+                    let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
+                    let _anvil_child = crate::native_tests::start_anvil(false, Hardfork::Latest);
+                    kzg_evm_prove_and_verify(2, path, test.to_string(), "private", "hashed", "public");
+                    #[cfg(not(feature = "icicle"))]
+                    run_js_tests(path, test.to_string(), "testBrowserEvmVerify", false);
+                    test_dir.close().unwrap();
+
+                }
+
+                #(#[test_case(TESTS_EVM[N])])*
+                fn kzg_evm_hashed_output_prove_and_verify_(test: &str) {
+                    crate::native_tests::init_binary();
+                    let test_dir = TempDir::new(test).unwrap();
+                    let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
+                    let _anvil_child = crate::native_tests::start_anvil(false, Hardfork::Latest);
+                    kzg_evm_prove_and_verify(2, path, test.to_string(), "private", "private", "hashed");
+                    #[cfg(not(feature = "icicle"))]
+                    run_js_tests(path, test.to_string(), "testBrowserEvmVerify", false);
+                    test_dir.close().unwrap();
+                }
+
+
+                #(#[test_case(TESTS_EVM[N])])*
+                fn kzg_evm_kzg_params_prove_and_verify_(test: &str) {
+                    crate::native_tests::init_binary();
+                    let test_dir = TempDir::new(test).unwrap();
+                    let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
+                    let _anvil_child = crate::native_tests::start_anvil(false, Hardfork::Latest);
+                    kzg_evm_prove_and_verify(2, path, test.to_string(), "private", "polycommit", "public");
+                    #[cfg(not(feature = "icicle"))]
+                    run_js_tests(path, test.to_string(), "testBrowserEvmVerify", false);
+                    test_dir.close().unwrap();
+                }
+
+
+                #(#[test_case(TESTS_EVM[N])])*
+                fn kzg_evm_kzg_output_prove_and_verify_(test: &str) {
+                    crate::native_tests::init_binary();
+                    let test_dir = TempDir::new(test).unwrap();
+                    let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
+                    let _anvil_child = crate::native_tests::start_anvil(false, Hardfork::Latest);
+                    kzg_evm_prove_and_verify(2, path, test.to_string(), "private", "private", "polycommit");
+                    #[cfg(not(feature = "icicle"))]
+                    run_js_tests(path, test.to_string(), "testBrowserEvmVerify", false);
+                    test_dir.close().unwrap();
+                }
+
+                #(#[test_case(TESTS_EVM[N])])*
+                fn kzg_evm_kzg_all_prove_and_verify_(test: &str) {
+                    crate::native_tests::init_binary();
+                    let test_dir = TempDir::new(test).unwrap();
+                    let path = test_dir.path().to_str().unwrap(); crate::native_tests::mv_test_(path, test);
+                    let _anvil_child = crate::native_tests::start_anvil(false, Hardfork::Latest);
+                    kzg_evm_prove_and_verify(2, path, test.to_string(), "polycommit", "polycommit", "polycommit");
+                    #[cfg(not(feature = "icicle"))]
+                    run_js_tests(path, test.to_string(), "testBrowserEvmVerify", false);
+                    test_dir.close().unwrap();
+                }
+
+
+
+
+            });
+    }
+    };
+}
+
+    macro_rules! test_func_examples {
+    () => {
+        #[cfg(test)]
+        mod tests_examples {
+            use seq_macro::seq;
+            use crate::native_tests::EXAMPLES;
+            use test_case::test_case;
+            use crate::native_tests::run_example as run;
+            seq!(N in 0..=1 {
+            #(#[test_case(EXAMPLES[N])])*
+            fn example_(test: &str) {
+                run(test.to_string());
+            }
+            });
+    }
+    };
+}
+
+    test_func!();
+    test_func_aggr!();
+    test_func_evm!();
+    test_func_examples!();
+
+    // Encode an array as a .npy file with the narrowest of the supported dtypes that holds its values exactly
+    fn npy_bytes(shape: &[usize], data: &[FileSourceInner]) -> Vec<u8> {
+        let floats = data.iter().map(|e| e.to_float()).collect::<Vec<_>>();
+        let (descr, encode): (&str, fn(f64) -> Vec<u8>) = if data.iter().all(|e| e.is_bool()) {
+            ("|b1", |f| vec![f as u8])
+        } else if floats.iter().all(|f| f.fract() == 0.0) {
+            ("<i8", |f| (f as i64).to_le_bytes().to_vec())
+        } else if floats.iter().all(|f| *f as f32 as f64 == *f) {
+            ("<f4", |f| (f as f32).to_le_bytes().to_vec())
+        } else {
+            ("<f8", |f| f.to_le_bytes().to_vec())
+        };
+
+        let shape = shape.iter().map(|d| format!("{},", d)).collect::<String>();
+        let mut header = format!(
+            "{{'descr': '{}', 'fortran_order': False, 'shape': ({}), }}",
+            descr, shape
+        );
+        header.push_str(&" ".repeat((64 - (header.len() + 11) % 64) % 64));
+        header.push('\n');
+
+        let mut bytes = b"\x93NUMPY\x01\x00".to_vec();
+        bytes.extend((header.len() as u16).to_le_bytes());
+        bytes.extend(header.as_bytes());
+        bytes.extend(floats.into_iter().flat_map(encode));
+        bytes
+    }
+
+    // Feed the inputs of an example as a .npy file or .npz archive and check they produce the witness of its json input
+    fn numpy_input_round_trip(test_dir: &str, example_name: String) {
+        let mut tolerance = 0.0;
+        gen_circuit_settings_and_witness(
+            test_dir,
+            example_name.clone(),
+            "public",
+            "private",
+            "public",
+            1,
+            "resources",
+            None,
+            2,
+            false,
+            &mut tolerance,
+            Commitments::KZG,
+            2,
+        );
+
+        let dir = format!("{}/{}", test_dir, example_name);
+        let circuit = GraphCircuit::load(format!("{}/network.compiled", dir).into()).unwrap();
+        let input_shapes = circuit.model().graph.input_shapes().unwrap();
+        let input_names = circuit.model().graph.input_names().to_vec();
+        let num_outputs = circuit.model().graph.output_shapes().unwrap().len();
+
+        let data = GraphData::from_path(format!("{}/input.json", dir).into()).unwrap();
+        let arrays = match data.input_data {
+            DataSource::File(data) => data
+                .iter()
+                .zip(input_shapes.iter())
+                .map(|(input, shape)| npy_bytes(shape, input))
+                .collect::<Vec<_>>(),
+            _ => panic!("expected file data"),
+        };
+
+        // multi-input models get an archive storing the arrays in reverse, so they can only be matched by name
+        let input_path = if arrays.len() == 1 {
+            let path = format!("{}/input.npy", dir);
+            std::fs::write(&path, &arrays[0]).unwrap();
+            path
+        } else {
+            let path = format!("{}/input.npz", dir);
+            let mut archive = zip::ZipWriter::new(std::fs::File::create(&path).unwrap());
+            for (name, bytes) in input_names.iter().zip(arrays.iter()).rev() {
+                archive
+                    .start_file(
+                        format!("{}.npy", name),
+                        zip::write::SimpleFileOptions::default(),
+                    )
+                    .unwrap();
+                archive.write_all(bytes).unwrap();
+            }
+            archive.finish().unwrap();
+            path
+        };
+        let output_path = if num_outputs == 1 {
+            format!("{}/output.npy", dir)
+        } else {
+            format!("{}/output.npz", dir)
+        };
+
+        let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+            .args([
+                "gen-witness",
+                "-D",
+                &input_path,
+                "-M",
+                &format!("{}/network.compiled", dir),
+                "-O",
+                &format!("{}/witness_npy.json", dir),
+                "--dequantized-output",
+                &output_path,
+            ])
+            .status()
+            .expect("failed to execute process");
+        assert!(status.success());
+
+        let witness = GraphWitness::from_path(format!("{}/witness.json", dir).into()).unwrap();
+        let npy_witness =
+            GraphWitness::from_path(format!("{}/witness_npy.json", dir).into()).unwrap();
+        assert_eq!(witness, npy_witness);
+
+        // the dequantized outputs are the rescaled outputs of the witness
+        let outputs = if num_outputs == 1 {
+            vec![NpyArray::load(output_path.as_ref()).unwrap()]
+        } else {
+            load_npz(output_path.as_ref())
+                .unwrap()
+                .into_iter()
+                .map(|(_, array)| array)
+                .collect()
+        };
+        let rescaled_outputs = witness.pretty_elements.unwrap().rescaled_outputs;
+        assert_eq!(outputs.len(), rescaled_outputs.len());
+        for (array, expected) in outputs.iter().zip(rescaled_outputs) {
+            let found = array
+                .data
+                .iter()
+                .map(|e| e.to_float().to_string())
+                .collect::<Vec<_>>();
+            assert_eq!(found, expected);
+        }
+    }
+
+    // Feed the inputs of a multi-input example from one csv file with shuffled columns and check they produce the witness of its json input
+    fn csv_input_round_trip(test_dir: &str, example_name: String) {
+        let mut tolerance = 0.0;
+        gen_circuit_settings_and_witness(
+            test_dir,
+            example_name.clone(),
+            "public",
+            "private",
+            "public",
+            1,
+            "resources",
+            None,
+            2,
+            false,
+            &mut tolerance,
+            Commitments::KZG,
+            2,
+        );
+
+        let dir = format!("{}/{}", test_dir, example_name);
+        let data = GraphData::from_path(format!("{}/input.json", dir).into()).unwrap();
+        let inputs = match data.input_data {
+            DataSource::File(data) => data,
+            _ => panic!("expected file data"),
+        };
+        assert!(inputs.len() > 1);
+
+        // name every element of every input, then lay the columns out in a scrambled order next to an unused one
+        let mut columns = inputs
+            .iter()
+            .enumerate()
+            .flat_map(|(i, input)| {
+                input
+                    .iter()
+                    .enumerate()
+                    .map(move |(j, e)| (format!("input{}_{}", i, j), e.to_float()))
+            })
+            .collect::<Vec<_>>();
+        columns.reverse();
+        let mid = columns.len() / 2;
+        columns.rotate_left(mid);
+        columns.insert(1, ("notes".to_string(), f64::NAN));
+
+        let write_csv = |path: &str, rows: usize, blank: Option<&str>| {
+            let header = columns
+                .iter()
+                .map(|(name, _)| name.clone())
+                .collect::<Vec<_>>();
+            let mut csv = header.join(",") + "\n";
+            for _ in 0..rows {
+                let row = columns
+                    .iter()
+                    .map(|(name, value)| match name.as_str() {
+                        "notes" => "\"unused, quoted\"".to_string(),
+                        n if Some(n) == blank => String::new(),
+                        _ => value.to_string(),
+                    })
+                    .collect::<Vec<_>>();
+                csv += &(row.join(",") + "\n");
+            }
+            std::fs::write(path, csv).unwrap();
+        };
+        let write_mapping = |path: &str, missing: serde_json::Value| {
+            let inputs = inputs
+                .iter()
+                .enumerate()
+                .map(|(i, input)| {
+                    let columns = (0..input.len())
+                        .map(|j| format!("input{}_{}", i, j))
+                        .collect::<Vec<_>>();
+                    serde_json::json!({ "columns": columns })
+                })
+                .collect::<Vec<_>>();
+            let mapping = serde_json::json!({ "inputs": inputs, "missing": missing });
+            std::fs::write(path, mapping.to_string()).unwrap();
+        };
+
+        let gen_witness = |data: &str, mapping: &str, output: &str| {
+            Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args([
+                    "gen-witness",
+                    "-D",
+                    data,
+                    "-M",
+                    &format!("{}/network.compiled", dir),
+                    "-O",
+                    output,
+                    "--csv-mapping",
+                    mapping,
+                ])
+                .status()
+                .expect("failed to execute process")
+        };
+        let witness = GraphWitness::from_path(format!("{}/witness.json", dir).into()).unwrap();
+
+        let csv_path = format!("{}/input.csv", dir);
+        let mapping_path = format!("{}/mapping.json", dir);
+        write_csv(&csv_path, 1, None);
+        write_mapping(&mapping_path, serde_json::json!("error"));
+        let witness_path = format!("{}/witness_csv.json", dir);
+        assert!(gen_witness(&csv_path, &mapping_path, &witness_path).success());
+        assert_eq!(
+            witness,
+            GraphWitness::from_path(witness_path.into()).unwrap()
+        );
+
+        // a missing value fails to load unless it is filled in
+        let (blank, fill) = columns
+            .iter()
+            .find(|(name, _)| name != "notes")
+            .cloned()
+            .unwrap();
+        write_csv(&csv_path, 1, Some(&blank));
+        let witness_path = format!("{}/witness_missing.json", dir);
+        assert!(!gen_witness(&csv_path, &mapping_path, &witness_path).success());
+        write_mapping(&mapping_path, serde_json::json!({ "fill": fill }));
+        assert!(gen_witness(&csv_path, &mapping_path, &witness_path).success());
+        assert_eq!(
+            witness,
+            GraphWitness::from_path(witness_path.into()).unwrap()
+        );
+
+        // calibration data holds one batch per row
+        let batches_path = format!("{}/calibration.csv", dir);
+        write_csv(&batches_path, 3, None);
+        let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+            .args([
+                "calibrate-settings",
+                "--data",
+                &batches_path,
+                "-M",
+                &format!("{}/network.onnx", dir),
+                &format!("--settings-path={}/settings.json", dir),
+                "--csv-mapping",
+                &mapping_path,
+            ])
+            .status()
+            .expect("failed to execute process");
+        assert!(status.success());
+    }
+
+    // Feed an example with 3x8x8 inputs from png and jpeg images, preprocessed by the spec stored in its settings
+    fn image_input(test_dir: &str, example_name: String) {
+        use ezkl::graph::input::ImagePreprocessing;
+
+        let dir = format!("{}/{}", test_dir, example_name);
+        let png_path = format!("{}/image.png", dir);
+        let jpeg_path = format!("{}/image.jpg", dir);
+        let image = image::RgbImage::from_fn(12, 10, |x, y| {
+            image::Rgb([(x * 20) as u8, (y * 25) as u8, ((x * y) % 256) as u8])
+        });
+        image.save(&png_path).unwrap();
+        image.save(&jpeg_path).unwrap();
+
+        let spec_path = format!("{}/preprocessing.json", dir);
+        let spec = serde_json::json!({
+            "resize": { "shorter": 9 },
+            "center_crop": [8, 8],
+            "mean": [0.5, 0.4, 0.3],
+            "std": [0.2, 0.25, 0.3],
+        });
+        std::fs::write(&spec_path, spec.to_string()).unwrap();
+
+        let run = |args: &[&str]| {
+            Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args(args)
+                .stdout(std::process::Stdio::null())
+                .status()
+                .expect("failed to execute process")
+        };
+        let settings_path = format!("{}/settings.json", dir);
+        let compiled_path = format!("{}/network.compiled", dir);
+        let model_path = format!("{}/network.onnx", dir);
+        assert!(run(&[
+            "gen-settings",
+            "-M",
+            &model_path,
+            "-O",
+            &settings_path,
+            "--image-preprocessing",
+            &spec_path,
+        ])
+        .success());
+        assert!(run(&[
+            "calibrate-settings",
+            "--data",
+            &png_path,
+            "-M",
+            &model_path,
+            "-O",
+            &settings_path,
+        ])
+        .success());
+        assert!(run(&[
+            "compile-circuit",
+            "-M",
+            &model_path,
+            "--compiled-circuit",
+            &compiled_path,
+            "--settings-path",
+            &settings_path,
+        ])
+        .success());
+
+        // the spec survives calibration and is committed to by the settings fingerprint
+        let settings = GraphSettings::load(&settings_path.clone().into()).unwrap();
+        let preprocessing: ImagePreprocessing = serde_json::from_value(spec).unwrap();
+        assert_eq!(settings.image_preprocessing, Some(preprocessing.clone()));
+        let unprocessed = GraphSettings {
+            image_preprocessing: None,
+            ..settings.clone()
+        };
+        assert_ne!(
+            ezkl::eth::settings_fingerprint(&settings).unwrap(),
+            ezkl::eth::settings_fingerprint(&unprocessed).unwrap()
+        );
+
+        // the image produces the witness of its preprocessed tensor
+        let tensor_path = format!("{}/image.json", dir);
+        GraphData::from_image(png_path.clone().into(), &preprocessing)
+            .unwrap()
+            .save(tensor_path.clone().into())
+            .unwrap();
+        for (data, witness) in [
+            (&png_path, "witness_png.json"),
+            (&tensor_path, "witness_tensor.json"),
+            (&jpeg_path, "witness_jpeg.json"),
+        ] {
+            let witness = format!("{}/{}", dir, witness);
+            assert!(run(&[
+                "gen-witness",
+                "-D",
+                data,
+                "-M",
+                &compiled_path,
+                "-O",
+                &witness
+            ])
+            .success());
+        }
+        assert_eq!(
+            GraphWitness::from_path(format!("{}/witness_png.json", dir).into()).unwrap(),
+            GraphWitness::from_path(format!("{}/witness_tensor.json", dir).into()).unwrap()
+        );
+    }
+
+    fn witness_batch(test_dir: &str, example_name: String) {
+        use ezkl::execute::{WitnessBatchSummary, WITNESS_BATCH_SUMMARY};
+
+        let mut tolerance = 0.0;
+        gen_circuit_settings_and_witness(
+            test_dir,
+            example_name.clone(),
+            "public",
+            "private",
+            "public",
+            1,
+            "resources",
+            None,
+            2,
+            false,
+            &mut tolerance,
+            Commitments::KZG,
+            2,
+        );
+
+        // a malformed input sorted between valid ones
+        let dir = format!("{}/{}", test_dir, example_name);
+        let input_dir = format!("{}/batch_inputs", dir);
+        let output_dir = format!("{}/batch_witnesses", dir);
+        std::fs::create_dir_all(&input_dir).unwrap();
+        for name in ["a", "c", "d"] {
+            std::fs::copy(
+                format!("{}/input.json", dir),
+                format!("{}/{}.json", input_dir, name),
+            )
+            .unwrap();
+        }
+        std::fs::write(format!("{}/b.json", input_dir), "{\"input_data\": [[1.0,").unwrap();
+
+        let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+            .args([
+                "gen-witness",
+                "-M",
+                &format!("{}/network.compiled", dir),
+                "--input-dir",
+                &input_dir,
+                "--output-dir",
+                &output_dir,
+                "--threads",
+                "2",
+            ])
+            .status()
+            .expect("failed to execute process");
+        assert!(status.success());
+
+        let summary: WitnessBatchSummary = serde_json::from_reader(
+            std::fs::File::open(format!("{}/{}", output_dir, WITNESS_BATCH_SUMMARY)).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(summary.succeeded, 3);
+        assert_eq!(summary.failed, 1);
+        assert_eq!(summary.entries.len(), 4);
+
+        let witness = GraphWitness::from_path(format!("{}/witness.json", dir).into()).unwrap();
+        let rescaled_outputs = witness.pretty_elements.clone().unwrap().rescaled_outputs;
+        for (entry, name) in summary.entries.iter().zip(["a", "b", "c", "d"]) {
+            assert_eq!(
+                entry.input,
+                PathBuf::from(format!("{}/{}.json", input_dir, name))
+            );
+            let witness_path = PathBuf::from(format!("{}/{}.json", output_dir, name));
+            if name == "b" {
+                assert!(entry.error.is_some());
+                assert!(entry.witness.is_none() && entry.outputs.is_none());
+                assert!(!witness_path.exists());
+            } else {
+                assert!(entry.error.is_none());
+                assert_eq!(entry.witness.as_ref(), Some(&witness_path));
+                assert_eq!(entry.outputs.as_ref(), Some(&rescaled_outputs));
+                assert_eq!(GraphWitness::from_path(witness_path).unwrap(), witness);
+            }
+        }
+    }
+
+    fn diff(test_dir: &str, example_name: String) {
+        use ezkl::execute::DiffReport;
+
+        let dir = format!("{}/{}", test_dir, example_name);
+        let model = format!("{}/network.onnx", dir);
+        let data = format!("{}/input.json", dir);
+        let ezkl = |args: &[&str]| {
+            Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args(args)
+                .status()
+                .expect("failed to execute process")
+        };
+        let report = |input_scale: &str| -> DiffReport {
+            let settings = format!("{}/settings_{}.json", dir, input_scale);
+            let compiled = format!("{}/network_{}.compiled", dir, input_scale);
+            let witness = format!("{}/witness_{}.json", dir, input_scale);
+            let output = format!("{}/diff_{}.json", dir, input_scale);
+            assert!(ezkl(&[
+                "gen-settings",
+                "-M",
+                &model,
+                "-O",
+                &settings,
+                "--input-scale",
+                input_scale,
+            ])
+            .success());
+            assert!(ezkl(&[
+                "compile-circuit",
+                "-M",
+                &model,
+                "--compiled-circuit",
+                &compiled,
+                "--settings-path",
+                &settings,
+            ])
+            .success());
+            assert!(ezkl(&["gen-witness", "-D", &data, "-M", &compiled, "-O", &witness]).success());
+            assert!(ezkl(&[
+                "diff",
+                "-M",
+                &model,
+                "-S",
+                &settings,
+                "-D",
+                &data,
+                "-W",
+                &witness,
+                "--verbose",
+                "-O",
+                &output,
+            ])
+            .success());
+            serde_json::from_reader(std::fs::File::open(output).unwrap()).unwrap()
+        };
+
+        // an input quantized in halves can't carry the data, its node is flagged
+        let starved = report("1");
+        assert!(starved.flagged > 0);
+        assert!(!starved.nodes.is_empty());
+        let input_node = starved
+            .nodes
+            .iter()
+            .find(|n| n.op.contains("Input"))
+            .unwrap();
+        assert!(input_node.diff.flagged, "{:?}", input_node);
+
+        // at the default scales the same input isn't flagged and the outputs are closer to the float ones
+        let fine = report("7");
+        let input_node = fine.nodes.iter().find(|n| n.op.contains("Input")).unwrap();
+        assert!(!input_node.diff.flagged, "{:?}", input_node);
+        for (fine, starved) in fine.outputs.iter().zip(&starved.outputs) {
+            assert!(fine.diff.max_abs_error < starved.diff.max_abs_error);
+        }
+    }
+
+    fn input_normalization(test_dir: &str, example_name: String) {
+        let dir = format!("{}/{}", test_dir, example_name);
+        let model_path = format!("{}/network.onnx", dir);
+        let shapes = ezkl::graph::Model::from_run_args(&Default::default(), model_path.as_ref())
+            .unwrap()
+            .graph
+            .input_shapes()
+            .unwrap();
+
+        // a per-channel mean and power of two std, so normalizing in the settings and by hand round alike
+        let channels = |shape: &[usize]| shape.get(1).copied().unwrap_or(1);
+        let mean = shapes
+            .iter()
+            .map(|s| (0..channels(s)).map(|c| 0.1 * c as f64 - 0.2).collect())
+            .collect::<Vec<Vec<f64>>>();
+        let std = shapes
+            .iter()
+            .map(|s| {
+                (0..channels(s))
+                    .map(|c| 2f64.powi(c as i32 % 4 - 1))
+                    .collect()
+            })
+            .collect::<Vec<Vec<f64>>>();
+        let mean_path = format!("{}/mean.json", dir);
+        let std_path = format!("{}/std.json", dir);
+        std::fs::write(&mean_path, serde_json::to_string(&mean).unwrap()).unwrap();
+        std::fs::write(&std_path, serde_json::to_string(&std).unwrap()).unwrap();
+
+        let input_path = format!("{}/input.json", dir);
+        let data = GraphData::from_path(input_path.clone().into()).unwrap();
+        let inputs = match data.input_data {
+            DataSource::File(data) => data,
+            _ => panic!("expected file data"),
+        };
+        let prenormalized = inputs
+            .iter()
+            .zip(&shapes)
+            .enumerate()
+            .map(|(i, (input, shape))| {
+                let inner = shape.iter().skip(2).product::<usize>();
+                input
+                    .iter()
+                    .enumerate()
+                    .map(|(j, x)| {
+                        let c = (j / inner) % channels(shape);
+                        FileSourceInner::Float((x.to_float() - mean[i][c]) / std[i][c])
+                    })
+                    .collect()
+            })
+            .collect::<FileSource>();
+        let prenormalized_path = format!("{}/input_prenormalized.json", dir);
+        GraphData::new(DataSource::File(prenormalized))
+            .save(prenormalized_path.clone().into())
+            .unwrap();
+
+        let run = |args: &[&str]| {
+            Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args(args)
+                .stdout(std::process::Stdio::null())
+                .status()
+                .expect("failed to execute process")
+        };
+        let normalize = format!("{},{}", mean_path, std_path);
+        for (name, data, normalize) in [
+            ("normalized", &input_path, Some(normalize.as_str())),
+            ("prenormalized", &prenormalized_path, None),
+        ] {
+            let settings_path = format!("{}/settings_{}.json", dir, name);
+            let compiled_path = format!("{}/network_{}.compiled", dir, name);
+            let mut args: Vec<&str> = vec!["gen-settings", "-M", &model_path, "-O", &settings_path];
+            if let Some(normalize) = normalize {
+                args.extend(["--normalize", normalize]);
+            }
+            assert!(run(&args).success());
+            assert!(run(&[
+                "calibrate-settings",
+                "--data",
+                data,
+                "-M",
+                &model_path,
+                "-O",
+                &settings_path,
+            ])
+            .success());
+            assert!(run(&[
+                "compile-circuit",
+                "-M",
+                &model_path,
+                "--compiled-circuit",
+                &compiled_path,
+                "--settings-path",
+                &settings_path,
+            ])
+            .success());
+            assert!(run(&[
+                "gen-witness",
+                "-D",
+                data,
+                "-M",
+                &compiled_path,
+                "-O",
+                &format!("{}/witness_{}.json", dir, name),
+            ])
+            .success());
+        }
+
+        // the normalization survives calibration
+        let settings =
+            GraphSettings::load(&format!("{}/settings_normalized.json", dir).into()).unwrap();
+        let normalization = InputNormalization::from_mean_std(&mean, &std).unwrap();
+        assert_eq!(settings.input_normalization, Some(normalization));
+
+        assert_eq!(
+            GraphWitness::from_path(format!("{}/witness_normalized.json", dir).into()).unwrap(),
+            GraphWitness::from_path(format!("{}/witness_prenormalized.json", dir).into()).unwrap()
+        );
+    }
+
+    fn field_input_round_trip(test_dir: &str, example_name: String) {
+        let mut tolerance = 0.0;
+        gen_circuit_settings_and_witness(
+            test_dir,
+            example_name.clone(),
+            "public",
+            "private",
+            "public",
+            1,
+            "resources",
+            None,
+            2,
+            false,
+            &mut tolerance,
+            Commitments::KZG,
+            2,
+        );
+
+        let dir = format!("{}/{}", test_dir, example_name);
+        let compiled_path = format!("{}/network.compiled", dir);
+        let settings = GraphCircuit::load(compiled_path.clone().into())
+            .unwrap()
+            .settings()
+            .clone();
+        let witness = GraphWitness::from_path(format!("{}/witness.json", dir).into()).unwrap();
+
+        // the inputs quantized by the float path, in each of the field element formats
+        let inputs = witness
+            .inputs
+            .iter()
+            .map(|input| {
+                input
+                    .iter()
+                    .enumerate()
+                    .map(|(j, felt)| match j % 3 {
+                        0 => serde_json::to_value(felt).unwrap(),
+                        1 => serde_json::Value::String(format!("{:?}", felt)),
+                        _ => serde_json::Value::String(felt_to_integer_rep(*felt).to_string()),
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let write_inputs = |path: &str, scales: &[ezkl::Scale]| {
+            let data = serde_json::json!({ "input_data": inputs, "input_scales": scales });
+            std::fs::write(path, data.to_string()).unwrap();
+        };
+
+        let gen_witness = |data: &str, output: &str, field_output: &str| {
+            Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args([
+                    "gen-witness",
+                    "-D",
+                    data,
+                    "-M",
+                    &compiled_path,
+                    "-O",
+                    output,
+                    "--field-output",
+                    field_output,
+                ])
+                .status()
+                .expect("failed to execute process")
+        };
+        let felts_path = format!("{}/input_felts.json", dir);
+        let witness_path = format!("{}/witness_felts.json", dir);
+        let outputs_path = format!("{}/output_felts.json", dir);
+        write_inputs(&felts_path, &settings.model_input_scales);
+        assert!(gen_witness(&felts_path, &witness_path, &outputs_path).success());
+        assert_eq!(
+            GraphWitness::from_path(witness_path.clone().into()).unwrap(),
+            witness
+        );
+
+        // the field outputs read back as the outputs at their scales
+        let outputs = GraphData::from_path(outputs_path.clone().into()).unwrap();
+        assert_eq!(
+            outputs.input_scales,
+            Some(settings.model_output_scales.clone())
+        );
+        let outputs = match outputs.field_inputs(&settings.model_output_scales) {
+            Ok(Some(DataSource::File(outputs))) => outputs,
+            _ => panic!("expected field element outputs"),
+        };
+        let outputs = outputs
+            .iter()
+            .map(|output| output.iter().map(|x| x.to_field(0)).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(outputs, witness.outputs);
+
+        // inputs declared at other scales than the settings are rejected
+        let wrong_scales = settings
+            .model_input_scales
+            .iter()
+            .map(|s| s + 1)
+            .collect::<Vec<_>>();
+        write_inputs(&felts_path, &wrong_scales);
+        assert!(!gen_witness(&felts_path, &witness_path, &outputs_path).success());
+    }
+
+    fn witness_format(test_dir: &str, example_name: String) {
+        let mut tolerance = 0.0;
+        gen_circuit_settings_and_witness(
+            test_dir,
+            example_name.clone(),
+            "public",
+            "private",
+            "public",
+            1,
+            "resources",
+            None,
+            2,
+            false,
+            &mut tolerance,
+            Commitments::KZG,
+            2,
+        );
+
+        let dir = format!("{}/{}", test_dir, example_name);
+        let witness = GraphWitness::from_path(format!("{}/witness.json", dir).into()).unwrap();
+        let gen_witness = |output: &str, flags: &[&str]| {
+            let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args([
+                    "gen-witness",
+                    "-D",
+                    &format!("{}/input.json", dir),
+                    "-M",
+                    &format!("{}/network.compiled", dir),
+                    "-O",
+                    output,
+                ])
+                .args(flags)
+                .status()
+                .expect("failed to execute process");
+            assert!(status.success());
+            let json = std::fs::read_to_string(output).unwrap();
+            serde_json::from_str::<serde_json::Value>(&json).unwrap()
+        };
+
+        // rounded floats only
+        let report = gen_witness(
+            &format!("{}/witness_report.json", dir),
+            &["--precision", "3", "--no-felts", "--no-processed"],
+        );
+        for key in ["inputs", "outputs", "processed_inputs"] {
+            assert!(report.get(key).is_none(), "{}", key);
+        }
+        let pretty = &report["pretty_elements"];
+        assert!(pretty.get("inputs").is_none() && pretty.get("processed_inputs").is_none());
+        let expected = witness.pretty_elements.as_ref().unwrap();
+        let rounded = |values: &[Vec<String>]| {
+            values
+                .iter()
+                .map(|v| {
+                    v.iter()
+                        .map(|x| format!("{:.3}", x.parse::<f64>().unwrap()))
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            pretty["rescaled_outputs"],
+            serde_json::to_value(rounded(&expected.rescaled_outputs)).unwrap()
+        );
+        assert_eq!(
+            pretty["rescaled_inputs"],
+            serde_json::to_value(rounded(&expected.rescaled_inputs)).unwrap()
+        );
+
+        // raw felts only, which can still be proven
+        let felts_path = format!("{}/witness_felts.json", dir);
+        let felts = gen_witness(&felts_path, &["--no-rescaled"]);
+        assert!(felts["pretty_elements"].get("rescaled_outputs").is_none());
+        assert_eq!(
+            felts["outputs"],
+            serde_json::to_value(&witness.outputs).unwrap()
+        );
+        let felts = GraphWitness::from_path(felts_path.into()).unwrap();
+        assert_eq!(felts.inputs, witness.inputs);
+        assert_eq!(felts.processed_inputs, witness.processed_inputs);
+    }
+
+    fn witness_redaction(test_dir: &str, example_name: String) {
+        let mut tolerance = 0.0;
+        gen_circuit_settings_and_witness(
+            test_dir,
+            example_name.clone(),
+            "hashed",
+            "private",
+            "private",
+            1,
+            "resources",
+            None,
+            2,
+            false,
+            &mut tolerance,
+            Commitments::KZG,
+            2,
+        );
+
+        let dir = format!("{}/{}", test_dir, example_name);
+        let witness = GraphWitness::from_path(format!("{}/witness.json", dir).into()).unwrap();
+        let redacted_path = format!("{}/witness_redacted.json", dir);
+        let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+            .args([
+                "gen-witness",
+                "-D",
+                &format!("{}/input.json", dir),
+                "-M",
+                &format!("{}/network.compiled", dir),
+                "-O",
+                &redacted_path,
+                "--redact",
+            ])
+            .status()
+            .expect("failed to execute process");
+        assert!(status.success());
+
+        // the private inputs and outputs are gone, the hashes of the inputs are kept
+        let json = std::fs::read_to_string(&redacted_path).unwrap();
+        for felt in witness.inputs.iter().chain(&witness.outputs).flatten() {
+            let felt = serde_json::to_string(felt).unwrap();
+            assert!(!json.contains(&felt), "{} in the redacted witness", felt);
+        }
+        let redacted = GraphWitness::from_path(redacted_path.clone().into()).unwrap();
+        assert!(redacted
+            .inputs
+            .iter()
+            .chain(&redacted.outputs)
+            .all(|t| t.is_empty()));
+        let pretty = redacted.pretty_elements.as_ref().unwrap();
+        for tensors in [
+            &pretty.rescaled_inputs,
+            &pretty.inputs,
+            &pretty.rescaled_outputs,
+            &pretty.outputs,
+        ] {
+            assert!(tensors.iter().all(|t| t.is_empty()));
+        }
+        assert_eq!(redacted.processed_inputs, witness.processed_inputs);
+        assert!(redacted.redaction.is_some());
+
+        // the full witness proves, the redacted one doesn't
+        let mock = |witness: &str| {
+            Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args([
+                    "mock",
+                    "-W",
+                    witness,
+                    "-M",
+                    &format!("{}/network.compiled", dir),
+                ])
+                .status()
+                .expect("failed to execute process")
+        };
+        assert!(mock(&format!("{}/witness.json", dir)).success());
+        assert!(!mock(&redacted_path).success());
+    }
+
+    fn sha256_hashed(test_dir: &str, example_name: String) {
+        use halo2curves::ff::PrimeField;
+        use sha2::{Digest, Sha256};
+
+        let mut tolerance = 0.0;
+        gen_circuit_settings_and_witness(
+            test_dir,
+            example_name.clone(),
+            "hashed/sha256",
+            "private",
+            "public",
+            1,
+            "resources",
+            None,
+            2,
+            false,
+            &mut tolerance,
+            Commitments::KZG,
+            2,
+        );
+
+        let dir = format!("{}/{}", test_dir, example_name);
+        let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+            .args([
+                "mock",
+                "-W",
+                &format!("{}/witness.json", dir),
+                "-M",
+                &format!("{}/network.compiled", dir),
+            ])
+            .status()
+            .expect("failed to execute process");
+        assert!(status.success());
+
+        // the circuit hashes each quantized input as big-endian i64s
+        let witness = GraphWitness::from_path(format!("{}/witness.json", dir).into()).unwrap();
+        let expected = witness
+            .inputs
+            .iter()
+            .flat_map(|input| {
+                let bytes = input
+                    .iter()
+                    .flat_map(|x| (felt_to_integer_rep(*x) as i64).to_be_bytes())
+                    .collect::<Vec<_>>();
+                let digest = Sha256::digest(&bytes);
+                [
+                    u128::from_be_bytes(digest[..16].try_into().unwrap()),
+                    u128::from_be_bytes(digest[16..].try_into().unwrap()),
+                ]
+                .map(halo2curves::bn256::Fr::from_u128)
+            })
+            .collect::<Vec<_>>();
+        let processed = witness.processed_inputs.unwrap();
+        assert_eq!(processed.sha256_hash, Some(expected));
+        assert!(processed.poseidon_hash.is_none());
+
+        let settings = GraphSettings::load(&format!("{}/settings.json", dir).into()).unwrap();
+        assert!(settings.module_sizes.sha256_rows() > 0);
+    }
+
+    fn merkle_inclusion(test_dir: &str, example_name: String) {
+        use halo2curves::ff::Field;
+
+        let dir = format!("{}/{}", test_dir, example_name);
+        let ezkl = |args: &[&str]| {
+            Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args(args)
+                .stdout(std::process::Stdio::null())
+                .status()
+                .expect("failed to execute process")
+        };
+
+        assert!(ezkl(&[
+            "gen-settings",
+            "-M",
+            &format!("{}/network.onnx", dir),
+            &format!("--settings-path={}/settings.json", dir),
+            "--input-visibility=merkle/3",
+            "--param-visibility=private",
+            "--output-visibility=public",
+        ])
+        .success());
+        assert!(ezkl(&[
+            "calibrate-settings",
+            "--data",
+            &format!("{}/input.json", dir),
+            "-M",
+            &format!("{}/network.onnx", dir),
+            &format!("--settings-path={}/settings.json", dir),
+            "--target=resources",
+        ])
+        .success());
+        assert!(ezkl(&[
+            "compile-circuit",
+            "-M",
+            &format!("{}/network.onnx", dir),
+            "--compiled-circuit",
+            &format!("{}/network.compiled", dir),
+            &format!("--settings-path={}/settings.json", dir),
+        ])
+        .success());
+
+        let gen_witness = |tree: Option<&str>| {
+            let mut args = vec![
+                "gen-witness".to_string(),
+                "-D".to_string(),
+                format!("{}/input.json", dir),
+                "-M".to_string(),
+                format!("{}/network.compiled", dir),
+                "-O".to_string(),
+                format!("{}/witness.json", dir),
+            ];
+            if let Some(tree) = tree {
+                args.push(format!("--merkle-tree={}", tree));
+            }
+            ezkl(&args.iter().map(|a| a.as_str()).collect::<Vec<_>>())
+        };
+        let mock = || {
+            ezkl(&[
+                "mock",
+                "-W",
+                &format!("{}/witness.json", dir),
+                "-M",
+                &format!("{}/network.compiled", dir),
+            ])
+        };
+
+        // without the tree of the dataset there is no path to prove
+        assert!(!gen_witness(None).success());
+
+        // the input sits among shifted copies of itself, at a non-zero index
+        let input: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(format!("{}/input.json", dir)).unwrap())
+                .unwrap();
+        let row = input["input_data"].clone();
+        let shifted = |offset: f64| {
+            serde_json::Value::Array(
+                row.as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|input| {
+                        input
+                            .as_array()
+                            .unwrap()
+                            .iter()
+                            .map(|x| serde_json::json!(x.as_f64().unwrap() + offset))
+                            .collect()
+                    })
+                    .collect(),
+            )
+        };
+        let dataset = vec![shifted(1.0), shifted(-1.0), row.clone(), shifted(2.0)];
+        let dataset_path = format!("{}/dataset.json", dir);
+        std::fs::write(&dataset_path, serde_json::json!(dataset).to_string()).unwrap();
+
+        let tree_path = format!("{}/merkle_tree.json", dir);
+        assert!(ezkl(&[
+            "merkle-tree",
+            "-D",
+            &dataset_path,
+            "-M",
+            &format!("{}/network.compiled", dir),
+            "-O",
+            &tree_path,
+        ])
+        .success());
+
+        assert!(gen_witness(Some(&tree_path)).success());
+        assert!(mock().success());
+
+        let witness_path: PathBuf = format!("{}/witness.json", dir).into();
+        let mut witness = GraphWitness::from_path(witness_path.clone()).unwrap();
+        let path = witness
+            .processed_inputs
+            .as_ref()
+            .and_then(|p| p.merkle_path.clone())
+            .unwrap();
+        assert_eq!(path.index, 2);
+        assert_eq!(path.siblings.len(), 3);
+
+        // a wrong sibling no longer leads to the committed root
+        witness
+            .processed_inputs
+            .as_mut()
+            .and_then(|p| p.merkle_path.as_mut())
+            .unwrap()
+            .siblings[1] += halo2curves::bn256::Fr::ONE;
+        witness.save(witness_path).unwrap();
+        assert!(!mock().success());
+    }
+
+    fn input_signature(test_dir: &str, example_name: String) {
+        use k256::ecdsa::SigningKey;
+
+        let dir = format!("{}/{}", test_dir, example_name);
+        let ezkl = |args: &[&str]| {
+            Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args(args)
+                .stdout(std::process::Stdio::null())
+                .status()
+                .expect("failed to execute process")
+        };
+
+        let spec_path = format!("{}/input_signature.json", dir);
+        std::fs::write(&spec_path, r#"{"inputs": [0], "hash": "poseidon"}"#).unwrap();
+        assert!(ezkl(&[
+            "gen-settings",
+            "-M",
+            &format!("{}/network.onnx", dir),
+            &format!("--settings-path={}/settings.json", dir),
+            "--input-visibility=private",
+            "--param-visibility=private",
+            "--output-visibility=public",
+            &format!("--input-signature={}", spec_path),
+        ])
+        .success());
+        assert!(ezkl(&[
+            "calibrate-settings",
+            "--data",
+            &format!("{}/input.json", dir),
+            "-M",
+            &format!("{}/network.onnx", dir),
+            &format!("--settings-path={}/settings.json", dir),
+            "--target=resources",
+        ])
+        .success());
+        assert!(ezkl(&[
+            "compile-circuit",
+            "-M",
+            &format!("{}/network.onnx", dir),
+            "--compiled-circuit",
+            &format!("{}/network.compiled", dir),
+            &format!("--settings-path={}/settings.json", dir),
+        ])
+        .success());
+
+        let witness_path = format!("{}/witness.json", dir);
+        let gen_witness = |signature: Option<&str>| {
+            let mut args = vec![
+                "gen-witness".to_string(),
+                "-D".to_string(),
+                format!("{}/input.json", dir),
+                "-M".to_string(),
+                format!("{}/network.compiled", dir),
+                "-O".to_string(),
+                witness_path.clone(),
+            ];
+            if let Some(signature) = signature {
+                args.push(format!("--signature={}", signature));
+            }
+            ezkl(&args.iter().map(|a| a.as_str()).collect::<Vec<_>>())
+        };
+        let mock = || {
+            ezkl(&[
+                "mock",
+                "-W",
+                &witness_path,
+                "-M",
+                &format!("{}/network.compiled", dir),
+            ])
+        };
+
+        // the unsigned witness carries the digest to sign, but can't be proven
+        assert!(gen_witness(None).success());
+        assert!(!mock().success());
+        let witness = GraphWitness::from_path(witness_path.clone().into()).unwrap();
+        let digest = witness.input_signature.unwrap().digest;
+
+        let key = SigningKey::from_slice(&[7u8; 32]).unwrap();
+        let (signature, recovery_id) = key.sign_prehash_recoverable(&digest.0).unwrap();
+        let mut bytes = signature.to_bytes().to_vec();
+        bytes.push(recovery_id.to_byte());
+        let signature = format!("0x{}", hex::encode(&bytes));
+
+        assert!(gen_witness(Some(&signature)).success());
+        assert!(mock().success());
+
+        // a signature by another key recovers another public key, which the instances expose
+        let witness = GraphWitness::from_path(witness_path.clone().into()).unwrap();
+        let public_key = witness
+            .input_signature
+            .unwrap()
+            .signature
+            .unwrap()
+            .public_key;
+        let (other, other_id) = SigningKey::from_slice(&[8u8; 32])
+            .unwrap()
+            .sign_prehash_recoverable(&digest.0)
+            .unwrap();
+        let mut other_bytes = other.to_bytes().to_vec();
+        other_bytes.push(other_id.to_byte());
+        assert!(gen_witness(Some(&format!("0x{}", hex::encode(&other_bytes)))).success());
+        let mut witness = GraphWitness::from_path(witness_path.clone().into()).unwrap();
+        let signature = witness
+            .input_signature
+            .as_mut()
+            .and_then(|s| s.signature.as_mut())
+            .unwrap();
+        assert_ne!(signature.public_key, public_key);
+
+        // a tampered signature no longer verifies against the public key
+        signature.s.0[31] ^= 1;
+        witness.save(witness_path.into()).unwrap();
+        assert!(!mock().success());
+    }
+
+    fn output_disclosure(test_dir: &str, example_name: String) {
+        use halo2curves::ff::Field;
+
+        let dir = format!("{}/{}", test_dir, example_name);
+        let ezkl = |args: &[&str]| {
+            Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args(args)
+                .stdout(std::process::Stdio::null())
+                .status()
+                .expect("failed to execute process")
+        };
+
+        // the first output is revealed, the last of three kept private and the others hashed
+        let data = GraphData::from_path(format!("{}/input.json", dir).into()).unwrap();
+        let num_outputs = match data.output_data {
+            Some(DataSource::File(outputs)) => outputs.len(),
+            _ => panic!("the example has no file outputs"),
+        };
+        let visibilities = (0..num_outputs)
+            .map(|i| match i {
+                0 => "public",
+                2 => "private",
+                _ => "hashed/public",
+            })
+            .collect::<Vec<_>>();
+
+        assert!(ezkl(&[
+            "gen-settings",
+            "-M",
+            &format!("{}/network.onnx", dir),
+            &format!("--settings-path={}/settings.json", dir),
+            "--input-visibility=private",
+            "--param-visibility=private",
+            &format!("--output-visibilities={}", visibilities.join(",")),
+        ])
+        .success());
+        assert!(ezkl(&[
+            "calibrate-settings",
+            "--data",
+            &format!("{}/input.json", dir),
+            "-M",
+            &format!("{}/network.onnx", dir),
+            &format!("--settings-path={}/settings.json", dir),
+            "--target=resources",
+        ])
+        .success());
+        assert!(ezkl(&[
+            "compile-circuit",
+            "-M",
+            &format!("{}/network.onnx", dir),
+            "--compiled-circuit",
+            &format!("{}/network.compiled", dir),
+            &format!("--settings-path={}/settings.json", dir),
+        ])
+        .success());
+
+        let witness_path = format!("{}/witness.json", dir);
+        assert!(ezkl(&[
+            "gen-witness",
+            "-D",
+            &format!("{}/input.json", dir),
+            "-M",
+            &format!("{}/network.compiled", dir),
+            "-O",
+            &witness_path,
+        ])
+        .success());
+        let mock = || {
+            ezkl(&[
+                "mock",
+                "-W",
+                &witness_path,
+                "-M",
+                &format!("{}/network.compiled", dir),
+            ])
+        };
+        assert!(mock().success());
+
+        // only the public output is an instance of the model, the hashed ones are module instances
+        let settings = GraphSettings::load(&format!("{}/settings.json", dir).into()).unwrap();
+        let output_shapes = settings.model_instance_shapes.clone();
+        assert_eq!(output_shapes.len(), 1);
+        let witness = GraphWitness::from_path(witness_path.clone().into()).unwrap();
+        assert_eq!(
+            output_shapes[0].iter().product::<usize>(),
+            witness.outputs[0].len()
+        );
+        let hashes = witness
+            .processed_outputs
+            .as_ref()
+            .and_then(|p| p.poseidon_hash.clone())
+            .unwrap();
+        assert_eq!(
+            hashes.len(),
+            visibilities
+                .iter()
+                .filter(|v| **v == "hashed/public")
+                .count()
+        );
+
+        // the instances are the public output in the clear followed by the hashes
+        let mut circuit = GraphCircuit::load(format!("{}/network.compiled", dir).into()).unwrap();
+        circuit.load_graph_witness(&witness).unwrap();
+        let instances = circuit.prepare_public_inputs(&witness).unwrap();
+        assert_eq!(
+            instances.len(),
+            settings.total_instances().iter().sum::<usize>()
+        );
+        let mut expected = witness.outputs[0].clone();
+        expected.extend(hashes);
+        assert_eq!(instances, expected);
+
+        // a wrong hash of a hashed output no longer verifies
+        let mut tampered = witness.clone();
+        if let Some(p) = tampered.processed_outputs.as_mut() {
+            p.poseidon_hash.as_mut().unwrap()[0] += halo2curves::bn256::Fr::ONE;
+        }
+        tampered.save(witness_path.clone().into()).unwrap();
+        assert!(!mock().success());
+
+        // nor does a wrong public output
+        let mut tampered = witness.clone();
+        tampered.outputs[0][0] += halo2curves::bn256::Fr::ONE;
+        tampered.save(witness_path.into()).unwrap();
+        assert!(!mock().success());
+    }
+
+    fn revealed_outputs(test_dir: &str, example_name: String) {
+        use halo2curves::ff::Field;
+
+        let dir = format!("{}/{}", test_dir, example_name);
+        let ezkl = |args: &[&str]| {
+            Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args(args)
+                .stdout(std::process::Stdio::null())
+                .status()
+                .expect("failed to execute process")
+        };
+
+        // every output is hashed in public, and the first one is also revealed
+        assert!(ezkl(&[
+            "gen-settings",
+            "-M",
+            &format!("{}/network.onnx", dir),
+            &format!("--settings-path={}/settings.json", dir),
+            "--input-visibility=private",
+            "--param-visibility=private",
+            "--output-visibility=hashed/public",
+            "--revealed-outputs=0",
+        ])
+        .success());
+        assert!(ezkl(&[
+            "calibrate-settings",
+            "--data",
+            &format!("{}/input.json", dir),
+            "-M",
+            &format!("{}/network.onnx", dir),
+            &format!("--settings-path={}/settings.json", dir),
+            "--target=resources",
+        ])
+        .success());
+        assert!(ezkl(&[
+            "compile-circuit",
+            "-M",
+            &format!("{}/network.onnx", dir),
+            "--compiled-circuit",
+            &format!("{}/network.compiled", dir),
+            &format!("--settings-path={}/settings.json", dir),
+        ])
+        .success());
+
+        let witness_path = format!("{}/witness.json", dir);
+        assert!(ezkl(&[
+            "gen-witness",
+            "-D",
+            &format!("{}/input.json", dir),
+            "-M",
+            &format!("{}/network.compiled", dir),
+            "-O",
+            &witness_path,
+        ])
+        .success());
+        let mock = || {
+            ezkl(&[
+                "mock",
+                "-W",
+                &witness_path,
+                "-M",
+                &format!("{}/network.compiled", dir),
+            ])
+        };
+        assert!(mock().success());
+
+        // the instances are the revealed output in the clear followed by the hashes of every output
+        let settings = GraphSettings::load(&format!("{}/settings.json", dir).into()).unwrap();
+        assert_eq!(settings.model_instance_shapes.len(), 1);
+        let witness = GraphWitness::from_path(witness_path.clone().into()).unwrap();
+        let hashes = witness
+            .processed_outputs
+            .as_ref()
+            .and_then(|p| p.poseidon_hash.clone())
+            .unwrap();
+        assert_eq!(hashes.len(), witness.outputs.len());
+        let mut circuit = GraphCircuit::load(format!("{}/network.compiled", dir).into()).unwrap();
+        circuit.load_graph_witness(&witness).unwrap();
+        let instances = circuit.prepare_public_inputs(&witness).unwrap();
+        let mut expected = witness.outputs[0].clone();
+        expected.extend(hashes);
+        assert_eq!(instances, expected);
+
+        // neither a wrong revealed output nor a wrong hash of it verifies
+        let mut tampered = witness.clone();
+        tampered.outputs[0][0] += halo2curves::bn256::Fr::ONE;
+        tampered.save(witness_path.clone().into()).unwrap();
+        assert!(!mock().success());
+
+        let mut tampered = witness.clone();
+        if let Some(p) = tampered.processed_outputs.as_mut() {
+            p.poseidon_hash.as_mut().unwrap()[0] += halo2curves::bn256::Fr::ONE;
+        }
+        tampered.save(witness_path.into()).unwrap();
+        assert!(!mock().success());
+    }
+
+    fn kept_outputs(test_dir: &str, example_name: String, kept: &str) {
+        let dir = format!("{}/{}", test_dir, example_name);
+        let ezkl = |args: &[&str]| {
+            Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args(args)
+                .stdout(std::process::Stdio::null())
+                .status()
+                .expect("failed to execute process")
+        };
+        // compiles the model keeping the outputs given, and generates its witness
+        let compile = |name: &str, outputs: Option<&str>| {
+            let settings_path = format!("{}/{}_settings.json", dir, name);
+            let compiled = format!("{}/{}.compiled", dir, name);
+            let witness_path = format!("{}/{}_witness.json", dir, name);
+            let mut args = vec![
+                "gen-settings".to_string(),
+                "-M".to_string(),
+                format!("{}/network.onnx", dir),
+                format!("--settings-path={}", settings_path),
+                "--output-visibility=public".to_string(),
+            ];
+            if let Some(outputs) = outputs {
+                args.push(format!("--outputs={}", outputs));
+            }
+            if !ezkl(&args.iter().map(|a| a.as_str()).collect::<Vec<_>>()).success() {
+                return None;
+            }
+            assert!(ezkl(&[
+                "compile-circuit",
+                "-M",
+                &format!("{}/network.onnx", dir),
+                "--compiled-circuit",
+                &compiled,
+                &format!("--settings-path={}", settings_path),
+            ])
+            .success());
+            assert!(ezkl(&[
+                "gen-witness",
+                "-D",
+                &format!("{}/input.json", dir),
+                "-M",
+                &compiled,
+                "-O",
+                &witness_path,
+            ])
+            .success());
+            assert!(ezkl(&["mock", "-W", &witness_path, "-M", &compiled]).success());
+            Some((
+                GraphSettings::load(&settings_path.into()).unwrap(),
+                GraphWitness::from_path(witness_path.into()).unwrap(),
+            ))
+        };
+
+        let (full_settings, full_witness) = compile("full", None).unwrap();
+        let (settings, witness) = compile("kept", Some(kept)).unwrap();
+
+        // the nodes feeding only the other outputs are neither laid out nor exposed
+        assert!(full_settings.model_output_scales.len() > 1);
+        assert_eq!(settings.model_output_scales.len(), 1);
+        assert_eq!(settings.run_args.outputs, vec![kept.to_string()]);
+        assert!(
+            settings.num_rows < full_settings.num_rows,
+            "{} rows kept out of {}",
+            settings.num_rows,
+            full_settings.num_rows
+        );
+        assert!(settings.total_assignments < full_settings.total_assignments);
+        let labels = |settings: &GraphSettings| {
+            settings
+                .instance_layout()
+                .into_iter()
+                .map(|s| s.label)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(labels(&settings), vec!["output 0".to_string()]);
+        assert_eq!(
+            labels(&full_settings).len(),
+            full_settings.model_output_scales.len()
+        );
+
+        // the kept output has the values it has in the full model
+        assert_eq!(witness.outputs.len(), 1);
+        assert_eq!(witness.outputs[0], full_witness.outputs[0]);
+        assert_eq!(
+            witness.pretty_elements.unwrap().rescaled_outputs[0],
+            full_witness.pretty_elements.unwrap().rescaled_outputs[0]
+        );
+
+        // an output the model doesn't have can't be kept
+        assert!(compile("missing", Some("not_an_output")).is_none());
+    }
+
+    fn output_bounds(test_dir: &str, example_name: String) {
+        let dir = format!("{}/{}", test_dir, example_name);
+        let ezkl = |args: &[&str]| {
+            Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args(args)
+                .stdout(std::process::Stdio::null())
+                .status()
+                .expect("failed to execute process")
+        };
+
+        // bound the hashed output loosely around the outputs of the model
+        let data = GraphData::from_path(format!("{}/input.json", dir).into()).unwrap();
+        let outputs = match data.output_data {
+            Some(DataSource::File(outputs)) => {
+                outputs[0].iter().map(|o| o.to_float()).collect::<Vec<_>>()
+            }
+            _ => panic!("the example has no file outputs"),
+        };
+        let min = outputs.iter().cloned().fold(f64::INFINITY, f64::min);
+        let max = outputs.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+
+        assert!(ezkl(&[
+            "gen-settings",
+            "-M",
+            &format!("{}/network.onnx", dir),
+            &format!("--settings-path={}/settings.json", dir),
+            "--input-visibility=private",
+            "--param-visibility=private",
+            "--output-visibility=hashed/public",
+            &format!("--output-bounds=0:{}:{}", min - 1.0, max + 1.0),
+        ])
+        .success());
+        assert!(ezkl(&[
+            "calibrate-settings",
+            "--data",
+            &format!("{}/input.json", dir),
+            "-M",
+            &format!("{}/network.onnx", dir),
+            &format!("--settings-path={}/settings.json", dir),
+            "--target=resources",
+        ])
+        .success());
+        let compile = |settings: &str, compiled: &str| {
+            ezkl(&[
+                "compile-circuit",
+                "-M",
+                &format!("{}/network.onnx", dir),
+                "--compiled-circuit",
+                compiled,
+                &format!("--settings-path={}", settings),
+            ])
+        };
+        let settings_path = format!("{}/settings.json", dir);
+        let compiled = format!("{}/network.compiled", dir);
+        assert!(compile(&settings_path, &compiled).success());
+
+        let gen_witness = |compiled: &str, witness: &str| {
+            Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args([
+                    "gen-witness",
+                    "-D",
+                    &format!("{}/input.json", dir),
+                    "-M",
+                    compiled,
+                    "-O",
+                    witness,
+                ])
+                .output()
+                .expect("failed to execute process")
+        };
+        let mock = |compiled: &str, witness: &str| ezkl(&["mock", "-W", witness, "-M", compiled]);
+        let witness_path = format!("{}/witness.json", dir);
+        assert!(gen_witness(&compiled, &witness_path).status.success());
+        assert!(mock(&compiled, &witness_path).success());
+
+        // an upper bound below every output rejects the witness before it is generated
+        let mut settings = GraphSettings::load(&settings_path.clone().into()).unwrap();
+        settings.run_args.output_bounds = vec![OutputBound {
+            output: 0,
+            lower: None,
+            upper: Some(min - 1.0),
+        }];
+        let violated_settings = format!("{}/violated_settings.json", dir);
+        settings.save(&violated_settings.clone().into()).unwrap();
+        let violated = format!("{}/violated.compiled", dir);
+        assert!(compile(&violated_settings, &violated).success());
+        let res = gen_witness(&violated, &format!("{}/violated_witness.json", dir));
+        assert!(!res.status.success());
+        assert!(String::from_utf8_lossy(&res.stdout).contains("out of bounds"));
+
+        // and a witness of the loosely bounded circuit fails to be proven against it
+        assert!(!mock(&violated, &witness_path).success());
+    }
+
+    fn nullifier(test_dir: &str, example_name: String, input_visibility: &str) {
+        use halo2curves::ff::Field;
+
+        let dir = format!("{}/{}", test_dir, example_name);
+        let ezkl = |args: &[&str]| {
+            Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args(args)
+                .stdout(std::process::Stdio::null())
+                .status()
+                .expect("failed to execute process")
+        };
+
+        assert!(ezkl(&[
+            "gen-settings",
+            "-M",
+            &format!("{}/network.onnx", dir),
+            &format!("--settings-path={}/settings.json", dir),
+            &format!("--input-visibility={}", input_visibility),
+            "--param-visibility=private",
+            "--output-visibility=public",
+            "--nullifier",
+        ])
+        .success());
+        assert!(ezkl(&[
+            "calibrate-settings",
+            "--data",
+            &format!("{}/input.json", dir),
+            "-M",
+            &format!("{}/network.onnx", dir),
+            &format!("--settings-path={}/settings.json", dir),
+            "--target=resources",
+        ])
+        .success());
+        assert!(ezkl(&[
+            "compile-circuit",
+            "-M",
+            &format!("{}/network.onnx", dir),
+            "--compiled-circuit",
+            &format!("{}/network.compiled", dir),
+            &format!("--settings-path={}/settings.json", dir),
+        ])
+        .success());
+
+        let gen_witness = |salt: Option<&str>, witness: &str| {
+            let mut args = vec![
+                "gen-witness".to_string(),
+                "-D".to_string(),
+                format!("{}/input.json", dir),
+                "-M".to_string(),
+                format!("{}/network.compiled", dir),
+                "-O".to_string(),
+                witness.to_string(),
+            ];
+            if let Some(salt) = salt {
+                args.push(format!("--salt={}", salt));
+            }
+            ezkl(&args.iter().map(|a| a.as_str()).collect::<Vec<_>>())
+        };
+        let mock = |witness: &str| {
+            ezkl(&[
+                "mock",
+                "-W",
+                witness,
+                "-M",
+                &format!("{}/network.compiled", dir),
+            ])
+        };
+
+        // the unsalted witness carries the commitment of the inputs, but can't be proven
+        let unsalted = format!("{}/unsalted.json", dir);
+        assert!(gen_witness(None, &unsalted).success());
+        assert!(!mock(&unsalted).success());
+
+        let first = format!("{}/first.json", dir);
+        let second = format!("{}/second.json", dir);
+        assert!(gen_witness(Some("1"), &first).success());
+        assert!(gen_witness(Some("0x02"), &second).success());
+        assert!(mock(&first).success());
+        assert!(mock(&second).success());
+
+        // the same inputs salted differently have the same commitment and different nullifiers
+        let first_witness = GraphWitness::from_path(first.clone().into()).unwrap();
+        let second_witness = GraphWitness::from_path(second.clone().into()).unwrap();
+        let first_nullifier = first_witness.nullifier.clone().unwrap();
+        let second_nullifier = second_witness.nullifier.clone().unwrap();
+        assert_eq!(first_nullifier.commitment, second_nullifier.commitment);
+        assert_ne!(first_nullifier.nullifier, second_nullifier.nullifier);
+
+        // the nullifier is the last instance
+        let settings = GraphSettings::load(&format!("{}/settings.json", dir).into()).unwrap();
+        let mut circuit = GraphCircuit::load(format!("{}/network.compiled", dir).into()).unwrap();
+        circuit.load_graph_witness(&first_witness).unwrap();
+        let instances = circuit.prepare_public_inputs(&first_witness).unwrap();
+        let index = settings.nullifier_instance_index().unwrap();
+        assert_eq!(index, instances.len() - 1);
+        assert_eq!(Some(instances[index]), first_nullifier.nullifier);
+
+        // a nullifier the salt doesn't derive no longer verifies
+        let mut tampered = first_witness.clone();
+        if let Some(n) = tampered.nullifier.as_mut() {
+            n.nullifier = n.nullifier.map(|x| x + halo2curves::bn256::Fr::ONE);
+        }
+        tampered.save(first.clone().into()).unwrap();
+        assert!(!mock(&first).success());
+    }
+
+    fn session(test_dir: &str, example_name: String) {
+        let dir = format!("{}/{}", test_dir, example_name);
+        let ezkl = |args: &[&str]| {
+            Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args(args)
+                .stdout(std::process::Stdio::null())
+                .status()
+                .expect("failed to execute process")
+        };
+        let settings_path = format!("{}/settings.json", dir);
+
+        assert!(ezkl(&[
+            "gen-settings",
+            "-M",
+            &format!("{}/network.onnx", dir),
+            &format!("--settings-path={}", settings_path),
+            "--input-visibility=private",
+            "--param-visibility=private",
+            "--output-visibility=public",
+            "--session",
+        ])
+        .success());
+        assert!(ezkl(&[
+            "compile-circuit",
+            "-M",
+            &format!("{}/network.onnx", dir),
+            "--compiled-circuit",
+            &format!("{}/network.compiled", dir),
+            &format!("--settings-path={}", settings_path),
+        ])
+        .success());
+        init_params(settings_path.clone().into());
+        assert!(ezkl(&[
+            "setup",
+            "-M",
+            &format!("{}/network.compiled", dir),
+            "--pk-path",
+            &format!("{}/key.pk", dir),
+            "--vk-path",
+            &format!("{}/key.vk", dir),
+        ])
+        .success());
+
+        // each step is chained to the digest the previous one wrote out
+        let mut prev_digest = "0x00".to_string();
+        for step in 0..3 {
+            let witness = format!("{}/witness_{}.json", dir, step);
+            let digest = format!("{}/digest_{}.json", dir, step);
+            assert!(ezkl(&[
+                "gen-witness",
+                "-D",
+                &format!("{}/input.json", dir),
+                "-M",
+                &format!("{}/network.compiled", dir),
+                "-O",
+                &witness,
+                &format!("--prev-digest={}", prev_digest),
+                &format!("--session-digest-output={}", digest),
+            ])
+            .success());
+            assert!(ezkl(&[
+                "prove",
+                "-W",
+                &witness,
+                "-M",
+                &format!("{}/network.compiled", dir),
+                "--proof-path",
+                &format!("{}/step_{}.pf", dir, step),
+                "--pk-path",
+                &format!("{}/key.pk", dir),
+            ])
+            .success());
+            let digest: halo2curves::bn256::Fr =
+                serde_json::from_str(&std::fs::read_to_string(digest).unwrap()).unwrap();
+            prev_digest = format!("{:?}", digest);
+        }
+
+        let check = |order: [usize; 3]| {
+            ezkl(&[
+                "check-session",
+                "--proof-paths",
+                &order
+                    .iter()
+                    .map(|step| format!("{}/step_{}.pf", dir, step))
+                    .collect::<Vec<_>>()
+                    .join(","),
+                "--settings-paths",
+                &settings_path,
+                "--genesis-digest=0x00",
+            ])
+        };
+        assert!(check([0, 1, 2]).success());
+        // the same proofs out of order aren't a session
+        assert!(!check([0, 2, 1]).success());
+        assert!(!check([1, 2, 0]).success());
+    }
+
+    // a proof of the full crate verifies with the crate built with only the verifier-only feature
+    fn verifier_only(test_dir: &str, example_name: String) {
+        prove_and_verify(
+            test_dir,
+            example_name.clone(),
+            "safe",
+            "private",
+            "fixed",
+            "public",
+            1,
+            None,
+            false,
+            "single",
+            Commitments::KZG,
+            2,
+        );
+
+        let dir = format!("{}/{}", test_dir, example_name);
+        let settings = GraphSettings::load(&format!("{}/settings.json", dir).into()).unwrap();
+        std::fs::copy(
+            ezkl::execute::get_srs_path(settings.run_args.logrows, None, Commitments::KZG),
+            format!("{}/kzg.srs", dir),
+        )
+        .unwrap();
+
+        let status = Command::new("cargo")
+            .args([
+                "test",
+                "--release",
+                "--no-default-features",
+                "--features",
+                "verifier-only",
+                "--test",
+                "verifier_only_tests",
+                "--",
+                "--include-ignored",
+            ])
+            .env("EZKL_VERIFIER_ONLY_DIR", &dir)
+            .status()
+            .expect("failed to execute process");
+        assert!(status.success());
+    }
+
+    fn circomlib_poseidon(test_dir: &str, example_name: String) {
+        use ezkl::circuit::modules::Module;
+        use ezkl::graph::modules::ModuleCircomlib;
+        use ezkl::graph::PoseidonParams;
+
+        let dir = format!("{}/{}", test_dir, example_name);
+        let ezkl = |args: &[&str]| {
+            Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args(args)
+                .stdout(std::process::Stdio::null())
+                .status()
+                .expect("failed to execute process")
+        };
+
+        assert!(ezkl(&[
+            "gen-settings",
+            "-M",
+            &format!("{}/network.onnx", dir),
+            &format!("--settings-path={}/settings.json", dir),
+            "--input-visibility=hashed/public",
+            "--param-visibility=private",
+            "--output-visibility=hashed/public",
+            "--poseidon-params=circomlib",
+        ])
+        .success());
+        assert!(ezkl(&[
+            "calibrate-settings",
+            "--data",
+            &format!("{}/input.json", dir),
+            "-M",
+            &format!("{}/network.onnx", dir),
+            &format!("--settings-path={}/settings.json", dir),
+            "--target=resources",
+        ])
+        .success());
+        let settings = GraphSettings::load(&format!("{}/settings.json", dir).into()).unwrap();
+        assert_eq!(settings.run_args.poseidon_params, PoseidonParams::Circomlib);
+
+        assert!(ezkl(&[
+            "compile-circuit",
+            "-M",
+            &format!("{}/network.onnx", dir),
+            "--compiled-circuit",
+            &format!("{}/network.compiled", dir),
+            &format!("--settings-path={}/settings.json", dir),
+        ])
+        .success());
+        let witness_path = format!("{}/witness.json", dir);
+        assert!(ezkl(&[
+            "gen-witness",
+            "-D",
+            &format!("{}/input.json", dir),
+            "-M",
+            &format!("{}/network.compiled", dir),
+            "-O",
+            &witness_path,
+        ])
+        .success());
+        assert!(ezkl(&[
+            "mock",
+            "-W",
+            &witness_path,
+            "-M",
+            &format!("{}/network.compiled", dir),
+        ])
+        .success());
+
+        // the public hashes are the circomlib hashes of the quantized inputs and outputs
+        let witness = GraphWitness::from_path(witness_path.clone().into()).unwrap();
+        let circomlib = |x: &[Vec<halo2curves::bn256::Fr>]| {
+            x.iter()
+                .flat_map(|x| ModuleCircomlib::run(x.clone()).unwrap().remove(0))
+                .collect::<Vec<_>>()
+        };
+        let processed_inputs = witness.processed_inputs.clone().unwrap();
+        assert_eq!(
+            processed_inputs.poseidon_hash,
+            Some(circomlib(&witness.inputs))
+        );
+        let processed_outputs = witness.processed_outputs.clone().unwrap();
+        assert_eq!(
+            processed_outputs.poseidon_hash,
+            Some(circomlib(&witness.outputs))
+        );
+
+        // a hash with the ezkl parameters no longer verifies
+        let mut tampered = witness.clone();
+        if let Some(processed) = tampered.processed_inputs.as_mut() {
+            processed.poseidon_hash = Some(
+                witness
+                    .inputs
+                    .iter()
+                    .flat_map(|x| {
+                        ezkl::graph::modules::ModulePoseidon::run(x.clone())
+                            .unwrap()
+                            .remove(0)
+                    })
+                    .collect(),
+            );
+        }
+        tampered.save(witness_path.clone().into()).unwrap();
+        assert!(!ezkl(&[
+            "mock",
+            "-W",
+            &witness_path,
+            "-M",
+            &format!("{}/network.compiled", dir),
+        ])
+        .success());
+    }
+
+    fn output_encryption(test_dir: &str, example_name: String) {
+        use ezkl::graph::modules::ModuleElGamal;
+        use halo2curves::ff::Field;
+
+        let dir = format!("{}/{}", test_dir, example_name);
+        let ezkl = |args: &[&str]| {
+            Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args(args)
+                .stdout(std::process::Stdio::null())
+                .status()
+                .expect("failed to execute process")
+        };
+
+        assert!(ezkl(&[
+            "gen-settings",
+            "-M",
+            &format!("{}/network.onnx", dir),
+            &format!("--settings-path={}/settings.json", dir),
+            "--input-visibility=public",
+            "--param-visibility=private",
+            "--output-visibility=private",
+            "--encrypted-outputs=0",
+        ])
+        .success());
+        assert!(ezkl(&[
+            "calibrate-settings",
+            "--data",
+            &format!("{}/input.json", dir),
+            "-M",
+            &format!("{}/network.onnx", dir),
+            &format!("--settings-path={}/settings.json", dir),
+            "--target=resources",
+        ])
+        .success());
+        assert!(ezkl(&[
+            "compile-circuit",
+            "-M",
+            &format!("{}/network.onnx", dir),
+            "--compiled-circuit",
+            &format!("{}/network.compiled", dir),
+            &format!("--settings-path={}/settings.json", dir),
+        ])
+        .success());
+
+        let witness_path = format!("{}/witness.json", dir);
+        let mock = || {
+            ezkl(&[
+                "mock",
+                "-W",
+                &witness_path,
+                "-M",
+                &format!("{}/network.compiled", dir),
+            ])
+        };
+
+        // a witness of the encrypting circuit needs the key of the recipient
+        let gen_witness = |key: Option<String>| {
+            let mut args = vec![
+                "gen-witness".to_string(),
+                "-D".to_string(),
+                format!("{}/input.json", dir),
+                "-M".to_string(),
+                format!("{}/network.compiled", dir),
+                "-O".to_string(),
+                witness_path.clone(),
+            ];
+            if let Some(key) = key {
+                args.push(format!("--recipient-key={}", key));
+            }
+            ezkl(&args.iter().map(|a| a.as_str()).collect::<Vec<_>>())
+        };
+        assert!(gen_witness(None).success());
+        assert!(!mock().success());
+
+        let (secret_key, public_key) = ModuleElGamal::gen_keys(&mut rand::rngs::OsRng);
+        assert!(gen_witness(Some(format!("{:?},{:?}", public_key.x, public_key.y))).success());
+        assert!(mock().success());
+
+        // the recipient decrypts the output from the instances
+        let witness = GraphWitness::from_path(witness_path.clone().into()).unwrap();
+        let settings = GraphSettings::load(&format!("{}/settings.json", dir).into()).unwrap();
+        let mut circuit = GraphCircuit::load(format!("{}/network.compiled", dir).into()).unwrap();
+        circuit.load_graph_witness(&witness).unwrap();
+        let instances = circuit.prepare_public_inputs(&witness).unwrap();
+        assert_eq!(
+            settings.decrypt_outputs(&instances, &secret_key).unwrap(),
+            vec![witness.outputs[0].clone()]
+        );
+        // and another key doesn't
+        let (other_key, _) = ModuleElGamal::gen_keys(&mut rand::rngs::OsRng);
+        assert_ne!(
+            settings.decrypt_outputs(&instances, &other_key).ok(),
+            Some(vec![witness.outputs[0].clone()])
+        );
+
+        // a ciphertext the randomness doesn't derive no longer verifies
+        let mut tampered = witness.clone();
+        if let Some(encryption) = tampered.output_encryption.as_mut() {
+            encryption.ciphertext.c2[0] += halo2curves::bn256::Fr::ONE;
+        }
+        tampered.save(witness_path.clone().into()).unwrap();
+        assert!(!mock().success());
+    }
+
+    // the dry run of the layout estimates exactly what the real layout of the model lays out, in the fewest logrows it
+    // estimates
+    fn layout_estimate(test_dir: &str, example_name: String) {
+        use ezkl::graph::{take_model_layout, Model};
+        use ezkl::pfsys::{create_keys, srs::gen_srs};
+        use ezkl::RunArgs;
+
+        let model_path: PathBuf = format!("{}/{}/network.onnx", test_dir, example_name).into();
+        let mut run_args = RunArgs {
+            lookup_range: (-1024, 1024),
+            ..Default::default()
+        };
+        let model = Model::from_run_args(&run_args, &model_path).unwrap();
+        let estimate = model.estimate_layout(&run_args).unwrap();
+        assert!(estimate.model_rows > 0 && estimate.model_rows <= estimate.total_rows);
+        assert!(estimate
+            .ops
+            .windows(2)
+            .all(|w| w[0].start_row <= w[1].start_row));
+        let rows: usize = estimate.ops.iter().map(|op| op.rows).sum();
+        assert!(rows <= estimate.model_rows);
+        assert!(estimate
+            .lookups
+            .iter()
+            .all(|l| l.range.0 >= run_args.lookup_range.0 && l.range.1 <= run_args.lookup_range.1));
+
+        run_args.logrows = estimate.min_logrows;
+        assert_eq!(
+            model.estimate_layout(&run_args).unwrap().total_rows,
+            estimate.total_rows
+        );
+        let circuit = GraphCircuit::new(model, &run_args).unwrap();
+        let settings = circuit.settings();
+        assert_eq!(settings.num_rows, estimate.total_rows);
+        assert_eq!(settings.total_instances(), estimate.num_instances);
+
+        let params = gen_srs::<KZGCommitmentScheme<Bn256>>(estimate.min_logrows);
+        create_keys::<KZGCommitmentScheme<Bn256>, GraphCircuit>(&circuit, &params, true).unwrap();
+        let layout = take_model_layout().unwrap();
+        assert!(
+            estimate.matches(&layout),
+            "estimated {:?}, laid out {:?}",
+            estimate,
+            layout
+        );
+    }
+
+    fn inspect_compiled(test_dir: &str, example_name: String) {
+        use ezkl::graph::inspect::{CircuitInspection, NodeInfo};
+        use ezkl::graph::Visibility;
+
+        let dir = format!("{}/{}", test_dir, example_name);
+        let ezkl = |args: &[&str]| {
+            Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args(args)
+                .stdout(std::process::Stdio::null())
+                .status()
+                .expect("failed to execute process")
+        };
+        let compiled = format!("{}/network.compiled", dir);
+
+        assert!(ezkl(&[
+            "gen-settings",
+            "-M",
+            &format!("{}/network.onnx", dir),
+            &format!("--settings-path={}/settings.json", dir),
+            "--input-visibility=public",
+            "--param-visibility=fixed",
+            "--output-visibility=hashed/public",
+        ])
+        .success());
+        assert!(ezkl(&[
+            "compile-circuit",
+            "-M",
+            &format!("{}/network.onnx", dir),
+            "--compiled-circuit",
+            &compiled,
+            &format!("--settings-path={}/settings.json", dir),
+        ])
+        .success());
+
+        // the onnx model isn't needed to inspect the compiled circuit
+        std::fs::remove_file(format!("{}/network.onnx", dir)).unwrap();
+        let output = format!("{}/inspection.json", dir);
+        assert!(ezkl(&["inspect", &compiled, "-O", &output]).success());
+        let inspection: CircuitInspection =
+            serde_json::from_str(&std::fs::read_to_string(&output).unwrap()).unwrap();
+
+        let settings = GraphSettings::load(&format!("{}/settings.json", dir).into()).unwrap();
+        let circuit = GraphCircuit::load(compiled.clone().into()).unwrap();
+        assert_eq!(inspection, circuit.inspect());
+        assert_eq!(inspection.instance_layout, settings.instance_layout());
+        assert_eq!(inspection.required_lookups, settings.required_lookups);
+        assert_eq!(
+            inspection.required_range_checks,
+            settings.required_range_checks
+        );
+        assert_eq!(
+            inspection.input_names.len(),
+            settings.model_input_scales.len()
+        );
+        assert_eq!(
+            inspection.output_names.len(),
+            settings.model_output_scales.len()
+        );
+
+        // the nodes keep their onnx names, and those of a subgraph follow the subgraph node
+        assert!(inspection.nodes.iter().all(|n| !n.name.is_empty()));
+        for node in inspection.nodes.iter().filter(|n| n.subgraph.is_some()) {
+            assert!(inspection.nodes.iter().any(|n| n.subgraph.is_none()
+                && Some(n.idx) == node.subgraph
+                && n.op == "SUBGRAPH"));
+        }
+        let main = inspection
+            .nodes
+            .iter()
+            .filter(|n| n.subgraph.is_none())
+            .collect::<Vec<_>>();
+        for node in &main {
+            assert_eq!(node.input_shapes.len(), node.inputs.len());
+            for ((input, outlet), shape) in node.inputs.iter().zip(&node.input_shapes) {
+                let input = main.iter().find(|n| n.idx == *input).unwrap();
+                assert_eq!(&input.out_dims[*outlet], shape);
+            }
+        }
+        let inputs = main.iter().filter(|n| n.op == "Input").collect::<Vec<_>>();
+        assert_eq!(inputs.len(), settings.model_input_scales.len());
+        assert!(inputs
+            .iter()
+            .all(|n| n.visibility == Some(Visibility::Public)));
+        assert!(main
+            .iter()
+            .filter(|n| n.op.starts_with("CONST"))
+            .all(|n| n.visibility == Some(Visibility::Fixed)));
+        assert_eq!(
+            main.iter()
+                .filter(|n| matches!(n.visibility, Some(Visibility::Hashed { .. })))
+                .count(),
+            settings.model_output_scales.len()
+        );
+
+        // a single node is inspected on its own
+        let first = &inspection.nodes[0];
+        assert!(ezkl(&[
+            "inspect",
+            &compiled,
+            &format!("--node={}", first.idx),
+            "-O",
+            &output
+        ])
+        .success());
+        let node: NodeInfo =
+            serde_json::from_str(&std::fs::read_to_string(&output).unwrap()).unwrap();
+        assert_eq!(&node, first);
+        assert!(!ezkl(&["inspect", &compiled, "--node=100000"]).success());
+    }
+
+    fn row_overflow(test_dir: &str, example_name: String) {
+        use ezkl::circuit::{CheckMode, CircuitError};
+        use ezkl::graph::errors::GraphError;
+        use ezkl::graph::NodeType;
+        use ezkl::pfsys::{create_keys, srs::gen_srs, PfsysError};
+
+        let model_path: PathBuf = format!("{}/{}/network.onnx", test_dir, example_name).into();
+        let circuit = GraphCircuit::from_run_args(&Default::default(), &model_path).unwrap();
+        let mut settings = circuit.settings().clone();
+
+        // halve the rows the model needs and allocate a single block of columns, which the model overflows
+        settings.run_args.logrows = (settings.num_rows as f64).log2().ceil() as u32 - 1;
+        settings.total_assignments = 0;
+        let circuit = GraphCircuit::new_from_settings(
+            circuit.model().clone(),
+            settings.clone(),
+            CheckMode::UNSAFE,
+        )
+        .unwrap();
+
+        let params = gen_srs::<KZGCommitmentScheme<Bn256>>(settings.run_args.logrows);
+        let err = match create_keys::<KZGCommitmentScheme<Bn256>, GraphCircuit>(
+            &circuit, &params, true,
+        ) {
+            Err(err) => err,
+            Ok(_) => panic!("keygen succeeded on a circuit that overflows its rows"),
+        };
+        let message = err.to_string();
+        let PfsysError::LayoutError(GraphError::CircuitError(CircuitError::RowOverflow {
+            node,
+            row,
+            max,
+            largest,
+            ..
+        })) = err
+        else {
+            panic!("expected a row overflow, got {}", message);
+        };
+        assert!(row > max);
+        assert!(!largest.is_empty());
+
+        // the error names the node whose rows run past the last row, by index and onnx name
+        let idx: usize = node.split(' ').next().unwrap().parse().unwrap();
+        let name = match &circuit.model().graph.nodes[&idx] {
+            NodeType::Node(n) => n.name.clone(),
+            _ => panic!("node {} is a subgraph", idx),
+        };
+        assert!(!name.is_empty());
+        assert!(message.contains(&name), "{} doesn't name {}", message, name);
+
+        let profile = circuit.model().profile(&settings.run_args).unwrap();
+        let mut ops = profile.ops.clone();
+        ops.sort_by_key(|op| op.node);
+        let start: usize = ops
+            .iter()
+            .filter(|op| op.node < idx)
+            .map(|op| op.rows)
+            .sum();
+        let end: usize = start
+            + ops
+                .iter()
+                .filter(|op| op.node == idx)
+                .map(|op| op.rows)
+                .sum::<usize>();
+        assert!(
+            start <= max && max <= end,
+            "node {} spans rows {}..{}, the circuit has {}",
+            node,
+            start,
+            end,
+            max
+        );
+    }
+
+    fn auto_inner_cols(test_dir: &str, example_name: String) {
+        use ezkl::graph::{INNER_COLS_CANDIDATES, RESERVED_BLINDING_ROWS};
+
+        let dir = format!("{}/{}", test_dir, example_name);
+        let settings_path = format!("{}/settings.json", dir);
+        let gen_settings = |num_inner_cols: &str, logrows: u32| {
+            let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args([
+                    "gen-settings",
+                    "-M",
+                    &format!("{}/network.onnx", dir),
+                    &format!("--settings-path={}", settings_path),
+                    &format!("--num-inner-cols={}", num_inner_cols),
+                    &format!("--logrows={}", logrows),
+                ])
+                .status()
+                .expect("failed to execute process");
+            assert!(status.success());
+            let settings = GraphSettings::load(&settings_path.clone().into()).unwrap();
+            let fits = settings.num_rows + RESERVED_BLINDING_ROWS <= 1 << logrows;
+            (settings, fits)
+        };
+
+        // pick logrows a single inner column doesn't fit in
+        let (single, _) = gen_settings("1", 17);
+        let logrows = ((single.num_rows + RESERVED_BLINDING_ROWS) as f64)
+            .log2()
+            .ceil() as u32
+            - 1;
+
+        let (tuned, fits) = gen_settings("auto", logrows);
+        let chosen = tuned.run_args.num_inner_cols;
+        assert!(
+            fits,
+            "{} inner columns don't fit in {} logrows",
+            chosen, logrows
+        );
+        assert!(chosen > 1);
+        // the settings record the choice, not auto
+        assert!(INNER_COLS_CANDIDATES.contains(&chosen));
+
+        // the next fewer columns take less area but don't fit
+        let position = INNER_COLS_CANDIDATES
+            .iter()
+            .position(|c| *c == chosen)
+            .unwrap();
+        let fewer = INNER_COLS_CANDIDATES[position - 1];
+        let (adjacent, adjacent_fits) = gen_settings(&fewer.to_string(), logrows);
+        assert!(
+            !adjacent_fits || adjacent.num_rows * fewer > tuned.num_rows * chosen,
+            "{} inner columns are no worse than the {} picked",
+            fewer,
+            chosen
+        );
+    }
+
+    fn bench(test_dir: &str, example_name: String) {
+        use ezkl::execute::{BenchReport, BENCH_PHASES};
+
+        gen_circuit_settings_and_witness(
+            test_dir,
+            example_name.clone(),
+            "private",
+            "private",
+            "public",
+            1,
+            "resources",
+            None,
+            1,
+            false,
+            &mut 0.0,
+            Commitments::KZG,
+            2,
+        );
+        let dir = format!("{}/{}", test_dir, example_name);
+        let settings_path = format!("{}/settings.json", dir);
+        init_params(settings_path.clone().into());
+        let settings = GraphSettings::load(&settings_path.clone().into()).unwrap();
+
+        let output = format!("{}/bench.json", dir);
+        let run = |skip_setup: bool| {
+            let mut args = vec![
+                "bench".to_string(),
+                "-M".to_string(),
+                format!("{}/network.compiled", dir),
+                "-D".to_string(),
+                format!("{}/input.json", dir),
+                format!("--settings-path={}", settings_path),
+                format!("--vk-path={}/key.vk", dir),
+                format!("--pk-path={}/key.pk", dir),
+                "-N".to_string(),
+                "1".to_string(),
+                "--warmup=0".to_string(),
+                "-O".to_string(),
+                output.clone(),
+            ];
+            if skip_setup {
+                args.push("--skip-setup".to_string());
+            }
+            let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args(args)
+                .status()
+                .expect("failed to execute process");
+            assert!(status.success());
+            serde_json::from_str::<serde_json::Value>(&std::fs::read_to_string(&output).unwrap())
+                .unwrap()
+        };
+
+        for skip_setup in [false, true] {
+            let json = run(skip_setup);
+            // the schema: every field is present with its type
+            for key in ["iterations", "warmup", "logrows", "num_rows", "proof_size"] {
+                assert!(json[key].is_u64(), "{} is {}", key, json[key]);
+            }
+            assert!(json["skip_setup"].is_boolean());
+            assert!(json["phases"].is_array());
+            for phase in json["phases"].as_array().unwrap() {
+                assert!(phase["phase"].is_string());
+                assert_eq!(phase["times"].as_array().unwrap().len(), 1);
+                for key in ["mean", "min", "max"] {
+                    assert!(phase[key].is_f64(), "{} is {}", key, phase[key]);
+                }
+                assert!(phase["peak_rss"].is_u64() || phase["peak_rss"].is_null());
+            }
+
+            let report: BenchReport = serde_json::from_value(json).unwrap();
+            assert_eq!(report.iterations, 1);
+            assert_eq!(report.warmup, 0);
+            assert_eq!(report.skip_setup, skip_setup);
+            assert_eq!(report.logrows, settings.run_args.logrows);
+            assert_eq!(report.num_rows, settings.num_rows);
+            assert!(report.proof_size > 0);
+            let phases = report
+                .phases
+                .iter()
+                .map(|p| p.phase.as_str())
+                .collect::<Vec<_>>();
+            let expected = BENCH_PHASES
+                .iter()
+                .filter(|p| !skip_setup || **p != "setup")
+                .cloned()
+                .collect::<Vec<_>>();
+            assert_eq!(phases, expected);
+        }
+    }
+
+    fn pipeline(test_dir: &str, example_name: String) {
+        let dir = format!("{}/{}", test_dir, example_name);
+        let out = format!("{}/pipeline", dir);
+        let run = |data: &str| {
+            Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args([
+                    "pipeline",
+                    "-M",
+                    &format!("{}/network.onnx", dir),
+                    "-D",
+                    data,
+                    "-O",
+                    &out,
+                ])
+                .output()
+                .expect("failed to execute process")
+        };
+        let modified = |artifact: &str| {
+            std::fs::metadata(format!("{}/{}", out, artifact))
+                .unwrap()
+                .modified()
+                .unwrap()
+        };
+
+        // a failing stage is named and leaves the artifacts of the earlier stages intact
+        let output = run(&format!("{}/missing.json", dir));
+        assert!(!output.status.success());
+        let log = format!(
+            "{}{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+        assert!(log.contains("calibrate-settings failed"), "{}", log);
+        let uncalibrated = modified("uncalibrated_settings.json");
+
+        // the rerun picks up from the failed stage
+        let output = run(&format!("{}/input.json", dir));
+        assert!(output.status.success());
+        assert_eq!(modified("uncalibrated_settings.json"), uncalibrated);
+        for artifact in ["settings.json", "model.compiled", "vk.key", "pk.key"] {
+            assert!(std::path::Path::new(&format!("{}/{}", out, artifact)).exists());
+        }
+
+        // a run over unchanged inputs and outputs is up to date
+        let (compiled, pk) = (modified("model.compiled"), modified("pk.key"));
+        let output = run(&format!("{}/input.json", dir));
+        assert!(output.status.success());
+        assert_eq!(modified("model.compiled"), compiled);
+        assert_eq!(modified("pk.key"), pk);
+
+        // prove from the produced artifacts
+        let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+            .args([
+                "gen-witness",
+                "-D",
+                &format!("{}/input.json", dir),
+                "-M",
+                &format!("{}/model.compiled", out),
+                "-O",
+                &format!("{}/witness.json", out),
+            ])
+            .status()
+            .expect("failed to execute process");
+        assert!(status.success());
+
+        let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+            .args([
+                "prove",
+                "-W",
+                &format!("{}/witness.json", out),
+                "-M",
+                &format!("{}/model.compiled", out),
+                "--proof-path",
+                &format!("{}/proof.pf", out),
+                "--pk-path",
+                &format!("{}/pk.key", out),
+            ])
+            .status()
+            .expect("failed to execute process");
+        assert!(status.success());
+
+        let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+            .args([
+                "verify",
+                &format!("--settings-path={}/settings.json", out),
+                "--proof-path",
+                &format!("{}/proof.pf", out),
+                "--vk-path",
+                &format!("{}/vk.key", out),
+            ])
+            .status()
+            .expect("failed to execute process");
+        assert!(status.success());
+    }
+
+    #[cfg(feature = "server")]
+    fn serve(test_dir: &str, example_name: String) {
+        use ezkl::server::{ArtifactsEntry, ErrorResponse, VerifyResponse};
+        use serde_json::{json, Value};
+
+        /// Stops the server when the test ends, including when it fails
+        struct Server(Child);
+        impl Drop for Server {
+            fn drop(&mut self) {
+                let _ = self.0.kill();
+            }
+        }
+
+        let dir = format!("{}/{}", test_dir, example_name);
+        let artifacts = format!("{}/artifacts", dir);
+        let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+            .args([
+                "pipeline",
+                "-M",
+                &format!("{}/network.onnx", dir),
+                "-D",
+                &format!("{}/input.json", dir),
+                "-O",
+                &format!("{}/{}", artifacts, example_name),
+            ])
+            .status()
+            .expect("failed to execute process");
+        assert!(status.success());
+
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let _server = Server(
+            Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args([
+                    "serve",
+                    "--port",
+                    &port.to_string(),
+                    "--artifacts",
+                    &artifacts,
+                    "--workers",
+                    "2",
+                ])
+                .spawn()
+                .expect("failed to start the server"),
+        );
+        let url = format!("http://127.0.0.1:{}", port);
+        let input: Value =
+            serde_json::from_str(&std::fs::read_to_string(format!("{}/input.json", dir)).unwrap())
+                .unwrap();
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(async {
+            let client = reqwest::Client::new();
+            let list = || async {
+                let response = client.get(format!("{}/artifacts", url)).send().await?;
+                Ok::<_, reqwest::Error>(
+                    serde_json::from_str::<Vec<ArtifactsEntry>>(&response.text().await?).unwrap(),
+                )
+            };
+            let post = |endpoint: &str, body: Value| {
+                let request = client
+                    .post(format!("{}/{}", url, endpoint))
+                    .header("content-type", "application/json")
+                    .body(body.to_string());
+                async move {
+                    let response = request.send().await.unwrap();
+                    let status = response.status().as_u16();
+                    let body: Value = serde_json::from_str(&response.text().await.unwrap()).unwrap();
+                    (status, body)
+                }
+            };
+
+            // wait for the server to listen
+            let mut listed = None;
+            for _ in 0..120 {
+                if let Ok(entries) = list().await {
+                    listed = Some(entries);
+                    break;
+                }
+                std::thread::sleep(std::time::Duration::from_millis(500));
+            }
+            let listed = listed.expect("the server didn't start");
+            assert_eq!(listed.len(), 1);
+            assert_eq!(listed[0].name, example_name);
+            assert!(!listed[0].loaded);
+            let fingerprint = listed[0].fingerprint.clone();
+
+            let (status, witness) = post(
+                "gen-witness",
+                json!({ "artifacts": fingerprint, "input": input }),
+            )
+            .await;
+            assert_eq!(status, 200, "{}", witness);
+            assert!(list().await.unwrap()[0].loaded);
+
+            // concurrent requests share the loaded artifacts
+            let ((status_a, proof_a), (status_b, proof_b)) = tokio::join!(
+                post(
+                    "prove",
+                    json!({ "artifacts": fingerprint, "witness": witness }),
+                ),
+                post(
+                    "prove",
+                    json!({ "artifacts": fingerprint, "witness": witness, "transcript": "Poseidon" }),
+                ),
+            );
+            assert_eq!(status_a, 200, "{}", proof_a);
+            assert_eq!(status_b, 200, "{}", proof_b);
+            for proof in [&proof_a, &proof_b] {
+                let (status, verified) =
+                    post("verify", json!({ "artifacts": fingerprint, "proof": proof })).await;
+                assert_eq!(status, 200, "{}", verified);
+                let verified: VerifyResponse = serde_json::from_value(verified).unwrap();
+                assert!(verified.verified);
+            }
+
+            // a tampered proof doesn't verify
+            let mut tampered = proof_a.clone();
+            let byte = tampered["proof"][0].as_u64().unwrap();
+            tampered["proof"][0] = json!((byte + 1) % 256);
+            let (status, verified) =
+                post("verify", json!({ "artifacts": fingerprint, "proof": tampered })).await;
+            assert_eq!(status, 200, "{}", verified);
+            let verified: VerifyResponse = serde_json::from_value(verified).unwrap();
+            assert!(!verified.verified);
+
+            // the errors are structured
+            let (status, error) =
+                post("prove", json!({ "artifacts": "unknown", "witness": witness })).await;
+            assert_eq!(status, 404);
+            let error: ErrorResponse = serde_json::from_value(error).unwrap();
+            assert_eq!(error.error, "unknown_artifacts");
+            let (status, error) = post("verify", json!({ "artifacts": fingerprint })).await;
+            assert_eq!(status, 400);
+            let error: ErrorResponse = serde_json::from_value(error).unwrap();
+            assert_eq!(error.error, "invalid_request");
+        });
+    }
+
+    fn prove_batch(test_dir: &str, example_name: String) {
+        use ezkl::execute::{ProofBatchSummary, PROOF_BATCH_SUMMARY};
+        use ezkl::pfsys::Snark;
+        use halo2_proofs::poly::kzg::commitment::KZGCommitmentScheme;
+        use halo2curves::bn256::Bn256;
+        use halo2curves::ff::Field;
+
+        let dir = format!("{}/{}", test_dir, example_name);
+        let out = format!("{}/artifacts", dir);
+        let witness_dir = format!("{}/batch_witnesses", dir);
+        let proof_dir = format!("{}/batch_proofs", dir);
+        let ezkl = |args: &[&str]| {
+            Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args(args)
+                .status()
+                .expect("failed to execute process")
+        };
+
+        let status = ezkl(&[
+            "pipeline",
+            "-M",
+            &format!("{}/network.onnx", dir),
+            "-D",
+            &format!("{}/input.json", dir),
+            "-O",
+            &out,
+        ]);
+        assert!(status.success());
+        std::fs::create_dir_all(&witness_dir).unwrap();
+        for name in ["a", "c"] {
+            let status = ezkl(&[
+                "gen-witness",
+                "-D",
+                &format!("{}/input.json", dir),
+                "-M",
+                &format!("{}/model.compiled", out),
+                "-O",
+                &format!("{}/{}.json", witness_dir, name),
+            ]);
+            assert!(status.success());
+        }
+        // a witness of a wrong output, sorted between the valid ones, doesn't satisfy the circuit
+        let mut invalid =
+            GraphWitness::from_path(format!("{}/a.json", witness_dir).into()).unwrap();
+        invalid.outputs[0][0] += halo2curves::bn256::Fr::ONE;
+        invalid
+            .save(format!("{}/b.json", witness_dir).into())
+            .unwrap();
+
+        let status = ezkl(&[
+            "prove",
+            "-M",
+            &format!("{}/model.compiled", out),
+            "--pk-path",
+            &format!("{}/pk.key", out),
+            "--witness-dir",
+            &witness_dir,
+            "--proof-dir",
+            &proof_dir,
+            "--workers",
+            "2",
+        ]);
+        assert!(status.success());
+
+        let summary: ProofBatchSummary = serde_json::from_reader(
+            std::fs::File::open(format!("{}/{}", proof_dir, PROOF_BATCH_SUMMARY)).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(summary.succeeded, 2);
+        assert_eq!(summary.failed, 1);
+        assert_eq!(summary.entries.len(), 3);
+
+        // each proof of the batch verifies on its own, with the instances a single proof of its witness has
+        let status = ezkl(&[
+            "prove",
+            "-W",
+            &format!("{}/a.json", witness_dir),
+            "-M",
+            &format!("{}/model.compiled", out),
+            "--pk-path",
+            &format!("{}/pk.key", out),
+            "--proof-path",
+            &format!("{}/proof.json", out),
+        ]);
+        assert!(status.success());
+        let single =
+            Snark::load::<KZGCommitmentScheme<Bn256>>(&format!("{}/proof.json", out).into())
+                .unwrap();
+        for (entry, name) in summary.entries.iter().zip(["a", "b", "c"]) {
+            assert_eq!(
+                entry.witness,
+                PathBuf::from(format!("{}/{}.json", witness_dir, name))
+            );
+            let proof_path = PathBuf::from(format!("{}/{}.json", proof_dir, name));
+            if name == "b" {
+                assert!(entry.error.is_some());
+                assert!(entry.proof.is_none());
+                assert!(!proof_path.exists());
+                continue;
+            }
+            assert!(entry.error.is_none());
+            assert_eq!(entry.proof.as_ref(), Some(&proof_path));
+            let proof = Snark::load::<KZGCommitmentScheme<Bn256>>(&proof_path).unwrap();
+            assert_eq!(proof.instances, single.instances);
+            let status = ezkl(&[
+                "verify",
+                &format!("--settings-path={}/settings.json", out),
+                "--proof-path",
+                proof_path.to_str().unwrap(),
+                "--vk-path",
+                &format!("{}/vk.key", out),
+            ]);
+            assert!(status.success());
+        }
+    }
+
+    fn batch_family(test_dir: &str, example_name: String) {
+        use ezkl::graph::batch::BatchFamily;
+        use ezkl::pfsys::Snark;
+        use halo2_proofs::poly::kzg::commitment::KZGCommitmentScheme;
+        use halo2curves::bn256::{Bn256, Fr};
+        use halo2curves::ff::Field;
+
+        let dir = format!("{}/{}", test_dir, example_name);
+        let family_dir = PathBuf::from(format!("{}/family", dir));
+        let ezkl = |args: &[&str]| {
+            Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args(args)
+                .status()
+                .expect("failed to execute process")
+        };
+        // 5 and 9 copies of the sample of the example, which also pads the batches
+        mk_data_batches_(test_dir, &example_name, "samples_5", 5);
+        mk_data_batches_(test_dir, &example_name, "samples_9", 9);
+
+        let status = ezkl(&[
+            "gen-settings",
+            "-M",
+            &format!("{}/network.onnx", dir),
+            "-O",
+            &format!("{}/settings.json", dir),
+        ]);
+        assert!(status.success());
+        let status = ezkl(&[
+            "compile-batch-family",
+            "-M",
+            &format!("{}/network.onnx", dir),
+            "-S",
+            &format!("{}/settings.json", dir),
+            "--padding",
+            &format!("{}/input.json", dir),
+            "--max-batch-size",
+            "8",
+            "-O",
+            family_dir.to_str().unwrap(),
+        ]);
+        assert!(status.success());
+
+        let family = BatchFamily::load(&family_dir).unwrap();
+        assert_eq!(family.batch_sizes, vec![1, 2, 4, 8]);
+        assert_eq!(family.member(5).unwrap(), 8);
+        // the members share the logrows, and so the srs
+        for batch_size in &family.batch_sizes {
+            let settings: serde_json::Value = serde_json::from_reader(
+                std::fs::File::open(BatchFamily::settings_path(&family_dir, *batch_size)).unwrap(),
+            )
+            .unwrap();
+            assert_eq!(settings["run_args"]["logrows"], family.logrows);
+        }
+        init_params(BatchFamily::settings_path(&family_dir, 8));
+
+        let status = ezkl(&["setup", "--batch-family", family_dir.to_str().unwrap()]);
+        assert!(status.success());
+        for batch_size in &family.batch_sizes {
+            assert!(BatchFamily::pk_path(&family_dir, *batch_size).exists());
+            assert!(BatchFamily::vk_path(&family_dir, *batch_size).exists());
+        }
+
+        let proof_path = format!("{}/proof.json", dir);
+        let status = ezkl(&[
+            "prove",
+            "--batch-family",
+            family_dir.to_str().unwrap(),
+            "-D",
+            &format!("{}/samples_5/input.json", test_dir),
+            "--proof-path",
+            &proof_path,
+        ]);
+        assert!(status.success());
+        // 5 samples are proven with the member of batch size 8
+        let status = ezkl(&[
+            "verify",
+            "--settings-path",
+            BatchFamily::settings_path(&family_dir, 8).to_str().unwrap(),
+            "--proof-path",
+            &proof_path,
+            "--vk-path",
+            BatchFamily::vk_path(&family_dir, 8).to_str().unwrap(),
+        ]);
+        assert!(status.success());
+
+        // the outputs of the 4 values of each sample, then the mask of the samples
+        let proof = Snark::load::<KZGCommitmentScheme<Bn256>>(&proof_path.into()).unwrap();
+        let instances = proof.instances.concat();
+        assert_eq!(instances.len(), 8 * 4 + 8);
+        let (outputs, mask) = instances.split_at(8 * 4);
+        let expected_mask = (0..8)
+            .map(|i| if i < 5 { Fr::ONE } else { Fr::ZERO })
+            .collect::<Vec<_>>();
+        assert_eq!(mask, &expected_mask[..]);
+        // the samples proven are the same sample, and the outputs of the padding are masked out
+        for sample in outputs.chunks(4).take(5) {
+            assert_eq!(sample, &outputs[..4]);
+        }
+        assert!(outputs[..4].iter().any(|x| *x != Fr::ZERO));
+        assert!(outputs[5 * 4..].iter().all(|x| *x == Fr::ZERO));
+
+        // more samples than the largest member fits aren't proven
+        let status = ezkl(&[
+            "prove",
+            "--batch-family",
+            family_dir.to_str().unwrap(),
+            "-D",
+            &format!("{}/samples_9/input.json", test_dir),
+            "--proof-path",
+            &format!("{}/proof_9.json", dir),
+        ]);
+        assert!(!status.success());
+    }
+
+    fn compile_weights(test_dir: &str, example_name: String) {
+        use ezkl::graph::{GraphCircuit, GraphSettings, GraphWitness};
+
+        let dir = format!("{}/{}", test_dir, example_name);
+        let ezkl = |args: &[&str]| {
+            Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args(args)
+                .status()
+                .expect("failed to execute process")
+        };
+        // a safetensors file of a single f32 tensor
+        let safetensors = |name: &str, shape: &[usize], values: &[f32]| {
+            let path = format!("{}/{}.safetensors", dir, name.replace('.', "_"));
+            let header = serde_json::to_vec(&serde_json::json!({
+                name: {"dtype": "F32", "shape": shape, "data_offsets": [0, 4 * values.len()]}
+            }))
+            .unwrap();
+            let mut bytes = (header.len() as u64).to_le_bytes().to_vec();
+            bytes.extend(header);
+            bytes.extend(values.iter().flat_map(|v| v.to_le_bytes()));
+            std::fs::write(&path, bytes).unwrap();
+            path
+        };
+        let compile = |settings: &str, compiled: &str, weights: Option<&str>| {
+            let mut args = vec![
+                "compile-circuit",
+                "-M",
+                &format!("{}/network.onnx", dir),
+                "-S",
+                settings,
+                "--compiled-circuit",
+                compiled,
+            ]
+            .into_iter()
+            .map(String::from)
+            .collect::<Vec<_>>();
+            if let Some(weights) = weights {
+                args.extend(["--weights".to_string(), weights.to_string()]);
+            }
+            ezkl(&args.iter().map(String::as_str).collect::<Vec<_>>())
+        };
+
+        let settings = format!("{}/settings.json", dir);
+        let settings_weights = format!("{}/settings_weights.json", dir);
+        let status = ezkl(&[
+            "gen-settings",
+            "-M",
+            &format!("{}/network.onnx", dir),
+            "-O",
+            &settings,
+        ]);
+        assert!(status.success());
+        std::fs::copy(&settings, &settings_weights).unwrap();
+
+        let compiled = format!("{}/network.compiled", dir);
+        let compiled_weights = format!("{}/network_weights.compiled", dir);
+        assert!(compile(&settings, &compiled, None).success());
+        // overrides the bias of the dense layer
+        let bias = safetensors("dense.bias", &[4], &[1.0, 2.0, 3.0, 4.0]);
+        assert!(compile(&settings_weights, &compiled_weights, Some(&bias)).success());
+
+        // the settings commit to the weights
+        let weights = GraphSettings::load(&settings_weights.clone().into())
+            .unwrap()
+            .run_args
+            .weights
+            .unwrap();
+        assert_eq!(weights.sha256.len(), 64);
+        assert!(GraphSettings::load(&settings.clone().into())
+            .unwrap()
+            .run_args
+            .weights
+            .is_none());
+
+        // the layout is that of the original weights
+        let original = GraphCircuit::load(compiled.clone().into()).unwrap();
+        let overridden = GraphCircuit::load(compiled_weights.clone().into()).unwrap();
+        assert_eq!(original.inspect().nodes, overridden.inspect().nodes);
+        assert_eq!(original.settings().num_rows, overridden.settings().num_rows);
+
+        // the outputs are those of the weights
+        let mut outputs = vec![];
+        for (compiled, witness) in [
+            (&compiled, "witness.json"),
+            (&compiled_weights, "witness_weights.json"),
+        ] {
+            let witness = format!("{}/{}", dir, witness);
+            let status = ezkl(&[
+                "gen-witness",
+                "-D",
+                &format!("{}/input.json", dir),
+                "-M",
+                compiled,
+                "-O",
+                &witness,
+            ]);
+            assert!(status.success());
+            let status = ezkl(&["mock", "-W", &witness, "-M", compiled]);
+            assert!(status.success());
+            outputs.push(GraphWitness::from_path(witness.into()).unwrap().outputs);
+        }
+        assert_ne!(outputs[0], outputs[1]);
+
+        // weights with names or shapes the initializers don't have aren't compiled
+        let unknown = safetensors("dense.scale", &[4], &[1.0; 4]);
+        assert!(!compile(&settings_weights, &compiled_weights, Some(&unknown)).success());
+        let misshapen = safetensors("dense.weight", &[3, 4], &[1.0; 12]);
+        assert!(!compile(&settings_weights, &compiled_weights, Some(&misshapen)).success());
+    }
+
+    fn encrypted_artifacts(test_dir: &str, example_name: String) {
+        use ezkl::graph::GraphCircuit;
+        use ezkl::pfsys::encryption::{is_encrypted, Identity, AGE_HEADER};
+        use std::collections::HashSet;
+        use std::path::Path;
+
+        let dir = format!("{}/{}", test_dir, example_name);
+        let ezkl = |args: &[&str]| {
+            Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args(args)
+                .output()
+                .expect("failed to execute process")
+        };
+        let log = |output: &std::process::Output| {
+            format!(
+                "{}{}",
+                String::from_utf8_lossy(&output.stdout),
+                String::from_utf8_lossy(&output.stderr)
+            )
+        };
+        let key = format!("{}/key.txt", dir);
+        let other_key = format!("{}/other_key.txt", dir);
+        let identity = Identity::generate(Path::new(&key)).unwrap();
+        Identity::generate(Path::new(&other_key)).unwrap();
+
+        let settings = format!("{}/settings.json", dir);
+        let output = ezkl(&[
+            "gen-settings",
+            "-M",
+            &format!("{}/network.onnx", dir),
+            "-O",
+            &settings,
+        ]);
+        assert!(output.status.success());
+        let compile = |compiled: &str, encrypt_to: Option<&str>| {
+            let model = format!("{}/network.onnx", dir);
+            let mut args = vec![
+                "compile-circuit",
+                "-M",
+                &model,
+                "-S",
+                &settings,
+                "--compiled-circuit",
+                compiled,
+            ];
+            if let Some(recipient) = encrypt_to {
+                args.extend(["--encrypt-to", recipient]);
+            }
+            ezkl(&args)
+        };
+        let plaintext = format!("{}/network.compiled", dir);
+        let encrypted = format!("{}/network.compiled.age", dir);
+        assert!(compile(&plaintext, None).status.success());
+        let recipient = identity.to_public().to_string();
+        assert!(compile(&encrypted, Some(&recipient)).status.success());
+
+        // the encrypted circuit holds none of the bytes of the plaintext circuit, its weights included: no chunk of
+        // the plaintext that isn't a run of a single byte is found among the windows of the ciphertext
+        let ciphertext = std::fs::read(&encrypted).unwrap();
+        assert!(ciphertext.starts_with(AGE_HEADER));
+        let windows = ciphertext.windows(16).collect::<HashSet<_>>();
+        let chunks = std::fs::read(&plaintext).unwrap();
+        let chunks = chunks
+            .chunks_exact(16)
+            .filter(|c| c.iter().any(|b| *b != c[0]))
+            .collect::<Vec<_>>();
+        assert!(!chunks.is_empty());
+        assert!(!chunks.iter().any(|c| windows.contains(c)));
+
+        // it decrypts in memory to the plaintext circuit, and isn't loaded without its identity
+        let decrypted =
+            GraphCircuit::load_decrypted(encrypted.clone().into(), Some(&identity)).unwrap();
+        let original = GraphCircuit::load(plaintext.clone().into()).unwrap();
+        assert_eq!(decrypted.inspect().nodes, original.inspect().nodes);
+        assert!(GraphCircuit::load(encrypted.clone().into()).is_err());
+        init_params(settings.clone().into());
+
+        let vk = format!("{}/key.vk", dir);
+        let pk = format!("{}/key.pk", dir);
+        let setup = |identity: Option<&str>| {
+            let mut args = vec![
+                "setup",
+                "-M",
+                &encrypted,
+                "--vk-path",
+                &vk,
+                "--pk-path",
+                &pk,
+            ];
+            if let Some(identity) = identity {
+                args.extend(["--identity", identity]);
+            }
+            ezkl(&args)
+        };
+        let output = setup(None);
+        assert!(!output.status.success());
+        assert!(log(&output).contains("is encrypted"), "{}", log(&output));
+        let output = setup(Some(&other_key));
+        assert!(!output.status.success());
+        assert!(
+            log(&output).contains("it is encrypted to another recipient"),
+            "{}",
+            log(&output)
+        );
+        assert!(setup(Some(&key)).status.success());
+        // the proving key holds the weights in its fixed columns and is encrypted too, the vk is left in the clear
+        assert!(is_encrypted(Path::new(&pk)));
+        assert!(!is_encrypted(Path::new(&vk)));
+
+        let witness = format!("{}/witness.json", dir);
+        let output = ezkl(&[
+            "gen-witness",
+            "-D",
+            &format!("{}/input.json", dir),
+            "-M",
+            &encrypted,
+            "-O",
+            &witness,
+            "--identity",
+            &key,
+        ]);
+        assert!(output.status.success(), "{}", log(&output));
+
+        let proof = format!("{}/proof.json", dir);
+        let prove = |identity: &str| {
+            ezkl(&[
+                "prove",
+                "-W",
+                &witness,
+                "-M",
+                &encrypted,
+                "--pk-path",
+                &pk,
+                "--proof-path",
+                &proof,
+                "--identity",
+                identity,
+            ])
+        };
+        assert!(!prove(&other_key).status.success());
+        let output = prove(&key);
+        assert!(output.status.success(), "{}", log(&output));
+        let output = ezkl(&[
+            "verify",
+            "--settings-path",
+            &settings,
+            "--proof-path",
+            &proof,
+            "--vk-path",
+            &vk,
+        ]);
+        assert!(output.status.success(), "{}", log(&output));
+    }
+
+    fn json_output(test_dir: &str, example_name: String) {
+        use ezkl::pfsys::Snark;
+        use halo2_proofs::poly::kzg::commitment::KZGCommitmentScheme;
+        use halo2curves::bn256::Bn256;
+        use halo2curves::ff::Field;
+        use serde_json::{json, Value};
+
+        let dir = format!("{}/{}", test_dir, example_name);
+        let out = format!("{}/artifacts", dir);
+        let ezkl = |args: &[&str]| {
+            Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args(args)
+                .output()
+                .expect("failed to execute process")
+        };
+
+        /// Replaces the figures and messages, which vary between runs and versions, and makes the paths relative to
+        /// the test dir
+        fn redact(value: &mut Value, dir: &str) {
+            match value {
+                Value::Number(_) => *value = json!("<number>"),
+                Value::String(s) => {
+                    if let Some(relative) = s.strip_prefix(&format!("{}/", dir)) {
+                        *s = relative.to_string();
+                    }
+                }
+                Value::Array(values) => values.iter_mut().for_each(|v| redact(v, dir)),
+                Value::Object(map) => {
+                    for (key, v) in map.iter_mut() {
+                        match key.as_str() {
+                            "exit_code" => {}
+                            "message" => *v = json!("<message>"),
+                            // the spans of a command vary between versions, each reports its peak
+                            "phase_peak_rss" => {
+                                assert!(v.as_object().is_some_and(|phases| !phases.is_empty()));
+                                *v = json!("<phases>")
+                            }
+                            _ => redact(v, dir),
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        // stdout holds the JSON object and nothing else
+        let check = |output: std::process::Output, snapshot: &str| {
+            let mut value: Value = serde_json::from_slice(&output.stdout)
+                .unwrap_or_else(|e| panic!("{}: {}", e, String::from_utf8_lossy(&output.stdout)));
+            assert_eq!(
+                Some(value["exit_code"].as_i64().unwrap() as i32),
+                output.status.code()
+            );
+            redact(&mut value, &dir);
+            let expected: Value = serde_json::from_str(
+                &std::fs::read_to_string(format!(
+                    "{}/tests/assets/json_output/{}.json",
+                    env!("CARGO_MANIFEST_DIR"),
+                    snapshot
+                ))
+                .unwrap(),
+            )
+            .unwrap();
+            assert_eq!(value, expected, "{}", snapshot);
+        };
+
+        let output = ezkl(&[
+            "--json",
+            "gen-settings",
+            "-M",
+            &format!("{}/network.onnx", dir),
+            "-O",
+            &format!("{}/settings.json", dir),
+        ]);
+        assert_eq!(output.status.code(), Some(0));
+        check(output, "gen_settings");
+
+        let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+            .args([
+                "pipeline",
+                "-M",
+                &format!("{}/network.onnx", dir),
+                "-D",
+                &format!("{}/input.json", dir),
+                "-O",
+                &out,
+            ])
+            .status()
+            .expect("failed to execute process");
+        assert!(status.success());
+        let output = ezkl(&[
+            "gen-witness",
+            "-D",
+            &format!("{}/input.json", dir),
+            "-M",
+            &format!("{}/model.compiled", out),
+            "-O",
+            &format!("{}/witness.json", out),
+        ]);
+        assert!(output.status.success());
+
+        let output = ezkl(&[
+            "prove",
+            "--json",
+            "-W",
+            &format!("{}/witness.json", out),
+            "-M",
+            &format!("{}/model.compiled", out),
+            "--proof-path",
+            &format!("{}/proof.json", out),
+            "--pk-path",
+            &format!("{}/pk.key", out),
+        ]);
+        assert_eq!(output.status.code(), Some(0));
+        check(output, "prove");
+
+        // a proof of other instances fails to verify, with its own exit code
+        let proof_path = std::path::PathBuf::from(format!("{}/proof.json", out));
+        let mut proof = Snark::load::<KZGCommitmentScheme<Bn256>>(&proof_path).unwrap();
+        proof.instances[0][0] += halo2curves::bn256::Fr::ONE;
+        proof.save(&proof_path).unwrap();
+        let output = ezkl(&[
+            "--json",
+            "verify",
+            &format!("--settings-path={}/settings.json", out),
+            "--proof-path",
+            proof_path.to_str().unwrap(),
+            "--vk-path",
+            &format!("{}/vk.key", out),
+        ]);
+        assert_eq!(output.status.code(), Some(3));
+        check(output, "verify_failure");
+    }
+
+    fn profile_json(test_dir: &str, example_name: String) {
+        use serde_json::Value;
+
+        let dir = format!("{}/{}", test_dir, example_name);
+        let out = format!("{}/artifacts", dir);
+        let ezkl = |args: &[&str]| {
+            Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args(args)
+                .status()
+                .expect("failed to execute process")
+        };
+        /// The (parent, child) pairs of the spans of a speedscope profile, by the names of their callsites
+        fn nesting(path: &str) -> Vec<(String, String)> {
+            let profile: Value =
+                serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+            let frames = profile["shared"]["frames"].as_array().unwrap();
+            let name = |event: &Value| {
+                let frame = &frames[event["frame"].as_u64().unwrap() as usize];
+                frame["name"]
+                    .as_str()
+                    .unwrap()
+                    .split(' ')
+                    .next()
+                    .unwrap()
+                    .to_string()
+            };
+            let mut pairs = vec![];
+            for thread in profile["profiles"].as_array().unwrap() {
+                let mut stack: Vec<String> = vec![];
+                for event in thread["events"].as_array().unwrap() {
+                    if event["type"] == "O" {
+                        if let Some(parent) = stack.last() {
+                            pairs.push((parent.clone(), name(event)));
+                        }
+                        stack.push(name(event));
+                    } else {
+                        assert_eq!(stack.pop(), Some(name(event)));
+                    }
+                }
+            }
+            pairs
+        }
+        let nested = |pairs: &[(String, String)], parent: &str, child: &str| {
+            pairs.iter().any(|(p, c)| p == parent && c == child)
+        };
+
+        let pipeline_profile = format!("{}/pipeline_profile.json", dir);
+        let status = ezkl(&[
+            "pipeline",
+            "-M",
+            &format!("{}/network.onnx", dir),
+            "-D",
+            &format!("{}/input.json", dir),
+            "-O",
+            &out,
+            "--profile-json",
+            &pipeline_profile,
+        ]);
+        assert!(status.success());
+        let pairs = nesting(&pipeline_profile);
+        assert!(nested(&pairs, "calibration_candidate", "model_load"));
+        assert!(nested(&pairs, "model_load", "quantization"));
+        assert!(nested(&pairs, "keygen", "keygen_vk"));
+        assert!(nested(&pairs, "keygen_pk", "synthesis"));
+
+        let status = ezkl(&[
+            "gen-witness",
+            "-D",
+            &format!("{}/input.json", dir),
+            "-M",
+            &format!("{}/model.compiled", out),
+            "-O",
+            &format!("{}/witness.json", out),
+        ]);
+        assert!(status.success());
+        let prove_profile = format!("{}/prove_profile.json", dir);
+        let status = ezkl(&[
+            "prove",
+            "-W",
+            &format!("{}/witness.json", out),
+            "-M",
+            &format!("{}/model.compiled", out),
+            "--pk-path",
+            &format!("{}/pk.key", out),
+            "--proof-path",
+            &format!("{}/proof.json", out),
+            "--profile-json",
+            &prove_profile,
+        ]);
+        assert!(status.success());
+        let pairs = nesting(&prove_profile);
+        for (parent, child) in [
+            ("ezkl", "prove"),
+            ("prove", "model_load"),
+            ("prove", "pk_load"),
+            ("prove", "create_proof"),
+            ("create_proof", "synthesis"),
+            ("prove", "finalize"),
+        ] {
+            assert!(
+                nested(&pairs, parent, child),
+                "{} not nested in {}: {:?}",
+                child,
+                parent,
+                pairs
+            );
+        }
+        // the tables are generated as the circuit is synthesized
+        assert!(
+            nested(&pairs, "synthesis", "table")
+                || nested(&pairs, "synthesis", "range_check_table")
+        );
+        // the phases carry their sizes
+        let profile = std::fs::read_to_string(&prove_profile).unwrap();
+        assert!(profile.contains("synthesis logrows="));
+        assert!(profile.contains("finalize proof_bytes="));
+    }
+
+    /// Checks the memory `ezkl estimate` predicts for keygen and proving against the peak resident memory of both
+    /// phases, as reported by --json, within a generous factor
+    fn memory_estimate(test_dir: &str, example_name: String) {
+        use serde_json::Value;
+
+        let dir = format!("{}/{}", test_dir, example_name);
+        let settings_path = format!("{}/settings.json", dir);
+        let compiled = format!("{}/network.compiled", dir);
+        let ezkl = |args: &[&str]| {
+            let output = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args(args)
+                .output()
+                .expect("failed to execute process");
+            assert!(output.status.success(), "{:?}", args);
+            output
+        };
+        // the peak of a phase of a command run with --json
+        let phase_peak = |output: std::process::Output, phase: &str| {
+            let value: Value = serde_json::from_slice(&output.stdout).unwrap();
+            value["metrics"]["phase_peak_rss"][phase].as_f64().unwrap()
+        };
+
+        ezkl(&[
+            "gen-settings",
+            "-M",
+            &format!("{}/network.onnx", dir),
+            "-O",
+            &settings_path,
+        ]);
+        ezkl(&[
+            "compile-circuit",
+            "-M",
+            &format!("{}/network.onnx", dir),
+            "--compiled-circuit",
+            &compiled,
+            "--settings-path",
+            &settings_path,
+        ]);
+        init_params(settings_path.clone().into());
+        ezkl(&[
+            "gen-witness",
+            "-D",
+            &format!("{}/input.json", dir),
+            "-M",
+            &compiled,
+            "-O",
+            &format!("{}/witness.json", dir),
+        ]);
+        let keygen = phase_peak(
+            ezkl(&[
+                "--json",
+                "setup",
+                "-M",
+                &compiled,
+                "--pk-path",
+                &format!("{}/key.pk", dir),
+                "--vk-path",
+                &format!("{}/key.vk", dir),
+            ]),
+            "keygen",
+        );
+        let prove = phase_peak(
+            ezkl(&[
+                "--json",
+                "prove",
+                "-W",
+                &format!("{}/witness.json", dir),
+                "-M",
+                &compiled,
+                "--proof-path",
+                &format!("{}/proof.json", dir),
+                "--pk-path",
+                &format!("{}/key.pk", dir),
+            ]),
+            "prove",
+        );
+
+        let estimate = format!("{}/estimate.json", dir);
+        ezkl(&["estimate", "-S", &settings_path, "-O", &estimate]);
+        let estimate: Value =
+            serde_json::from_str(&std::fs::read_to_string(&estimate).unwrap()).unwrap();
+        for (measured, predicted) in [
+            (keygen, &estimate["keygen_memory_bytes"]),
+            (prove, &estimate["prove_memory_bytes"]),
+        ] {
+            let ratio = predicted.as_f64().unwrap() / measured;
+            assert!(
+                (0.25..4.0).contains(&ratio),
+                "predicted {} bytes but {} were used",
+                predicted,
+                measured
+            );
+        }
+    }
+
+    /// Generates the settings, compiles the circuit and generates the keys twice, each in a process of its own such that
+    /// the hash maps are seeded differently, and checks the artifacts are byte-identical
+    fn deterministic_artifacts(test_dir: &str, example_name: String) {
+        let dir = format!("{}/{}", test_dir, example_name);
+        let ezkl = |args: &[&str]| {
+            Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args(args)
+                .status()
+                .expect("failed to execute process")
+        };
+        let generate = |run: &str| {
+            let out = format!("{}/{}", dir, run);
+            std::fs::create_dir_all(&out).unwrap();
+            let settings_path = format!("{}/settings.json", out);
+            assert!(ezkl(&[
+                "gen-settings",
+                "-M",
+                &format!("{}/network.onnx", dir),
+                "-O",
+                &settings_path,
+            ])
+            .success());
+            assert!(ezkl(&[
+                "compile-circuit",
+                "-M",
+                &format!("{}/network.onnx", dir),
+                "--compiled-circuit",
+                &format!("{}/network.compiled", out),
+                "--settings-path",
+                &settings_path,
+            ])
+            .success());
+            init_params(settings_path.clone().into());
+            assert!(ezkl(&[
+                "setup",
+                "-M",
+                &format!("{}/network.compiled", out),
+                "--pk-path",
+                &format!("{}/key.pk", out),
+                "--vk-path",
+                &format!("{}/key.vk", out),
+            ])
+            .success());
+            out
+        };
+
+        let first = generate("first");
+        let second = generate("second");
+        for artifact in ["settings.json", "network.compiled", "key.vk"] {
+            let read = |out: &str| std::fs::read(format!("{}/{}", out, artifact)).unwrap();
+            assert!(
+                read(&first) == read(&second),
+                "{} differs between runs",
+                artifact
+            );
+        }
+    }
+
+    /// Serves `srs` over http in place of the public SRS. The first download is cut off halfway through, later requests
+    /// honour the range header. Returns the url and the start of the range of each request.
+    fn mock_srs_server(srs: Vec<u8>) -> (String, Arc<Mutex<Vec<Option<usize>>>>) {
+        use std::io::{BufRead, BufReader, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!(
+            "http://{}/perpetual-powers-of-tau-raw-",
+            listener.local_addr().unwrap()
+        );
+        let requests = Arc::new(Mutex::new(vec![]));
+        let recorded = requests.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut start = None;
+                for line in BufReader::new(&stream).lines() {
+                    let line = line.unwrap();
+                    if line.is_empty() {
+                        break;
+                    }
+                    if let Some(range) = line.to_lowercase().strip_prefix("range: bytes=") {
+                        start = range.trim_end_matches('-').parse::<usize>().ok();
+                    }
+                }
+                let first = recorded.lock().unwrap().is_empty();
+                recorded.lock().unwrap().push(start);
+                match start {
+                    // announce the whole file but hang up halfway through
+                    None if first => {
+                        let header =
+                            format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n", srs.len());
+                        stream.write_all(header.as_bytes()).unwrap();
+                        stream.write_all(&srs[..srs.len() / 2]).unwrap();
+                    }
+                    None => {
+                        let header =
+                            format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n", srs.len());
+                        stream.write_all(header.as_bytes()).unwrap();
+                        stream.write_all(&srs).unwrap();
+                    }
+                    Some(start) => {
+                        let header = format!(
+                            "HTTP/1.1 206 Partial Content\r\nContent-Length: {}\r\nContent-Range: bytes {}-{}/{}\r\n\r\n",
+                            srs.len() - start,
+                            start,
+                            srs.len() - 1,
+                            srs.len()
+                        );
+                        stream.write_all(header.as_bytes()).unwrap();
+                        stream.write_all(&srs[start..]).unwrap();
+                    }
+                }
+                stream.flush().unwrap();
+            }
+        });
+        (url, requests)
+    }
+
+    fn srs_cache(test_dir: &str) {
+        use sha2::Digest;
+
+        let repo = format!("{}/ezkl", test_dir);
+        let cached =
+            |logrows: u32| std::path::PathBuf::from(format!("{}/srs/kzg{}.srs", repo, logrows));
+        let served = format!("{}/served.srs", test_dir);
+        let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+            .args(["gen-srs", "--logrows", "10", "--srs-path", &served])
+            .status()
+            .expect("failed to execute process");
+        assert!(status.success());
+        let srs = std::fs::read(&served).unwrap();
+        let (url, requests) = mock_srs_server(srs.clone());
+
+        let ezkl = |args: &[&str]| {
+            Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args(args)
+                .env("EZKL_REPO_PATH", &repo)
+                .env("EZKL_SRS_URL", &url)
+                .output()
+                .expect("failed to execute process")
+        };
+
+        // the first download is interrupted and leaves the part it received
+        let output = ezkl(&["srs", "fetch", "--logrows", "10"]);
+        assert!(!output.status.success());
+        assert!(!cached(10).exists());
+        let part = format!("{}.part", cached(10).display());
+        let received = std::fs::metadata(&part).unwrap().len() as usize;
+        assert!(received > 0 && received < srs.len());
+
+        // the next fetch resumes from the end of the part
+        let output = ezkl(&["srs", "fetch", "--logrows", "10"]);
+        assert!(output.status.success());
+        assert_eq!(*requests.lock().unwrap(), vec![None, Some(received)]);
+        assert!(!std::path::Path::new(&part).exists());
+        assert_eq!(std::fs::read(cached(10)).unwrap(), srs);
+
+        // fetching a cached SRS doesn't download it again
+        assert!(ezkl(&["srs", "fetch", "--logrows", "10"]).status.success());
+        assert_eq!(requests.lock().unwrap().len(), 2);
+
+        assert!(ezkl(&["srs", "verify", "--logrows", "10"]).status.success());
+        // flip a byte of the fifth power of tau, after the logrows header
+        let mut corrupted = srs.clone();
+        corrupted[4 + 64 * 5 + 10] ^= 0xff;
+        let corrupted_path = format!("{}/corrupted.srs", test_dir);
+        std::fs::write(&corrupted_path, corrupted).unwrap();
+        let output = ezkl(&[
+            "srs",
+            "verify",
+            "--logrows",
+            "10",
+            "--srs-path",
+            &corrupted_path,
+        ]);
+        assert!(!output.status.success());
+        // with the public srs url the locally generated SRS doesn't hash to the public SRS of its logrows
+        let output = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+            .args(["srs", "verify", "--logrows", "10"])
+            .env("EZKL_REPO_PATH", &repo)
+            .env_remove("EZKL_SRS_URL")
+            .output()
+            .expect("failed to execute process");
+        assert!(!output.status.success());
+
+        // smaller logrows use the cached SRS, which trims to a file of their own
+        let output = ezkl(&["srs", "locate", "--logrows", "8"]);
+        assert!(output.status.success());
+        assert!(String::from_utf8_lossy(&output.stdout).contains(&cached(10).display().to_string()));
+        assert!(ezkl(&["srs", "trim", "--logrows", "8"]).status.success());
+        let output = ezkl(&["srs", "locate", "--logrows", "8"]);
+        assert!(String::from_utf8_lossy(&output.stdout).contains(&cached(8).display().to_string()));
+
+        let list = format!("{}/list.json", test_dir);
+        assert!(ezkl(&["srs", "list", "-O", &list]).status.success());
+        let list: Vec<serde_json::Value> =
+            serde_json::from_str(&std::fs::read_to_string(&list).unwrap()).unwrap();
+        assert_eq!(list.len(), 2);
+        assert_eq!(list[0]["logrows"], 10);
+        assert_eq!(list[0]["size"], srs.len());
+        assert_eq!(
+            list[0]["sha256"],
+            format!("{:x}", sha2::Sha256::digest(&srs))
+        );
+        assert_eq!(list[1]["logrows"], 8);
+    }
+
+    fn srs_mirrors(test_dir: &str) {
+        let repo = format!("{}/ezkl", test_dir);
+        let cached = std::path::PathBuf::from(format!("{}/srs/kzg10.srs", repo));
+        let served = format!("{}/served.srs", test_dir);
+        let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+            .args(["gen-srs", "--logrows", "10", "--srs-path", &served])
+            .status()
+            .expect("failed to execute process");
+        assert!(status.success());
+        let srs = std::fs::read(&served).unwrap();
+        // the first mirror hangs up halfway through, the second honours the range of the resumed download
+        let (first, first_requests) = mock_srs_server(srs.clone());
+        let (second, second_requests) = mock_srs_server(srs.clone());
+        let dead = {
+            let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            format!("http://{}/srs-", listener.local_addr().unwrap())
+        };
+
+        let ezkl = |args: &[&str]| {
+            Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args(args)
+                .env("EZKL_REPO_PATH", &repo)
+                .env("EZKL_SRS_URL", &dead)
+                .output()
+                .expect("failed to execute process")
+        };
+
+        // offline, a missing SRS fails at once and names the file expected
+        let mirrors = format!("{},{}", first, second);
+        let output = ezkl(&[
+            "--offline",
+            "--srs-mirrors",
+            &mirrors,
+            "get-srs",
+            "--logrows",
+            "10",
+        ]);
+        assert!(!output.status.success());
+        let logs = format!(
+            "{}{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+        assert!(logs.contains("kzg10.srs"), "{}", logs);
+        assert!(first_requests.lock().unwrap().is_empty());
+
+        // the SRS url is down, the download moves on to the mirrors and resumes from the part the first one sent
+        let output = ezkl(&["--srs-mirrors", &mirrors, "srs", "fetch", "--logrows", "10"]);
+        assert!(output.status.success());
+        assert_eq!(*first_requests.lock().unwrap(), vec![None]);
+        let resumed = second_requests.lock().unwrap().clone();
+        assert!(matches!(resumed[..], [Some(start)] if start > 0 && start < srs.len()));
+        assert_eq!(std::fs::read(&cached).unwrap(), srs);
+
+        // without a mirror up every source failing is an error
+        std::fs::remove_file(&cached).unwrap();
+        assert!(!ezkl(&["srs", "fetch", "--logrows", "10"]).status.success());
+        // fetched again from the second mirror, the cached SRS is used offline
+        assert!(
+            ezkl(&["--srs-mirrors", &second, "srs", "fetch", "--logrows", "10"])
+                .status
+                .success()
+        );
+        assert!(ezkl(&["--offline", "get-srs", "--logrows", "10"])
+            .status
+            .success());
+    }
+
+    fn project_config(test_dir: &str, example_name: String) {
+        let dir = std::path::PathBuf::from(format!("{}/{}", test_dir, example_name))
+            .canonicalize()
+            .unwrap();
+        let nested = dir.join("nested");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(
+            dir.join("ezkl.toml"),
+            "settings-path = \"shared.json\"\n[gen-settings]\nmodel = \"network.onnx\"\nsettings_path = \"config.json\"\n",
+        )
+        .unwrap();
+        let ezkl = |cwd: &std::path::Path, args: &[&str]| {
+            Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args(args)
+                .current_dir(cwd)
+                .output()
+                .expect("failed to execute process")
+        };
+
+        // the configuration is found from a subdirectory and its paths resolve against its own directory
+        assert!(ezkl(&nested, &["gen-settings"]).status.success());
+        assert!(dir.join("config.json").exists());
+
+        // the command line overrides the configuration, relative paths on it resolve against the working directory
+        let output = ezkl(&nested, &["gen-settings", "-O", "cli.json"]);
+        assert!(output.status.success());
+        assert!(nested.join("cli.json").exists());
+
+        // an explicit configuration is used from anywhere
+        let output = ezkl(
+            std::path::Path::new(test_dir),
+            &[
+                "--config",
+                dir.join("ezkl.toml").to_str().unwrap(),
+                "config",
+                "show",
+                "gen-settings",
+            ],
+        );
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains(&format!(
+            "model = \"{}\"",
+            dir.join("network.onnx").display()
+        )));
+        assert!(stdout.contains(&format!(
+            "settings-path = \"{}\"",
+            dir.join("config.json").display()
+        )));
+        // the arguments the configuration doesn't set show their defaults
+        assert!(stdout.contains("input-scale = \"7\""));
+
+        // unknown arguments are rejected rather than ignored
+        std::fs::write(dir.join("ezkl.toml"), "[prove]\nnot-an-argument = 1\n").unwrap();
+        let output = ezkl(&nested, &["gen-settings"]);
+        assert_eq!(output.status.code(), Some(2));
+    }
+
+    fn circuit_profile(test_dir: &str, example_name: String) {
+        use ezkl::graph::profile::CircuitProfile;
+
+        let dir = format!("{}/{}", test_dir, example_name);
+        let profile_path = format!("{}/profile.json", dir);
+        let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+            .args([
+                "circuit-profile",
+                "-M",
+                &format!("{}/network.onnx", dir),
+                "-O",
+                &profile_path,
+            ])
+            .status()
+            .expect("failed to execute process");
+        assert!(status.success());
+
+        let profile: CircuitProfile =
+            serde_json::from_str(&std::fs::read_to_string(profile_path).unwrap()).unwrap();
+
+        // both layers are profiled, the ops sorted by the rows they take
+        assert!(profile.ops.len() >= 2);
+        assert!(profile.ops.windows(2).all(|w| w[0].rows >= w[1].rows));
+        // the ops account for no more than the whole circuit
+        let rows: usize = profile.ops.iter().map(|op| op.rows).sum();
+        let lookups: usize = profile.ops.iter().map(|op| op.lookups).sum();
+        assert!(rows > 0 && rows <= profile.total_rows);
+        assert!(lookups <= profile.total_lookups);
+        let shares: f64 = profile.ops.iter().map(|op| profile.row_share(op)).sum();
+        assert!(shares <= 1.0 + 1e-9);
+    }
+
+    fn rescale_overrides(test_dir: &str, example_name: String) {
+        use ezkl::execute::DiffReport;
+        use ezkl::graph::{GraphSettings, ImplicitRescale};
+
+        let dir = format!("{}/{}", test_dir, example_name);
+        let model = format!("{}/network.onnx", dir);
+        let data = format!("{}/input.json", dir);
+        let ezkl = |args: &[&str], overrides: &[String]| {
+            let mut command = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR));
+            command.args(args);
+            if !overrides.is_empty() {
+                command.arg(format!("--rescale-overrides={}", overrides.join(",")));
+            }
+            command
+                .stdout(std::process::Stdio::null())
+                .status()
+                .expect("failed to execute process")
+        };
+        // the rescales of the outputs of nodes inserted with the overrides
+        let output_rescales = |overrides: &[String]| -> Vec<ImplicitRescale> {
+            let output = format!("{}/rescales.json", dir);
+            assert!(ezkl(&["list-rescales", "-M", &model, "-O", &output], overrides).success());
+            let rescales: Vec<ImplicitRescale> =
+                serde_json::from_str(&std::fs::read_to_string(output).unwrap()).unwrap();
+            rescales.into_iter().filter(|r| r.input.is_none()).collect()
+        };
+        // the output scale and the diff against the float pass of the witness with the overrides
+        let run = |name: &str, overrides: &[String]| -> (i32, DiffReport) {
+            let settings = format!("{}/settings_{}.json", dir, name);
+            let compiled = format!("{}/network_{}.compiled", dir, name);
+            let witness = format!("{}/witness_{}.json", dir, name);
+            let output = format!("{}/diff_{}.json", dir, name);
+            assert!(ezkl(&["gen-settings", "-M", &model, "-O", &settings], overrides).success());
+            assert!(ezkl(
+                &[
+                    "compile-circuit",
+                    "-M",
+                    &model,
+                    "--compiled-circuit",
+                    &compiled,
+                    "--settings-path",
+                    &settings,
+                ],
+                &[]
+            )
+            .success());
+            assert!(ezkl(
+                &["gen-witness", "-D", &data, "-M", &compiled, "-O", &witness],
+                &[]
+            )
+            .success());
+            assert!(ezkl(
+                &[
+                    "diff", "-M", &model, "-S", &settings, "-D", &data, "-W", &witness, "-O",
+                    &output
+                ],
+                &[]
+            )
+            .success());
+            let settings: GraphSettings =
+                serde_json::from_str(&std::fs::read_to_string(settings).unwrap()).unwrap();
+            let report = serde_json::from_str(&std::fs::read_to_string(output).unwrap()).unwrap();
+            (settings.model_output_scales[0], report)
+        };
+
+        // the product of the input and the weights, both at scale 7, is rescaled down from 14 to 7
+        let rebase = output_rescales(&[])
+            .last()
+            .expect("no rescale of an output was inserted")
+            .clone();
+        assert!(rebase.from_scale > rebase.to_scale);
+        let (default_scale, default_diff) = run("default", &[]);
+
+        // suppressing the rescale keeps the precision of the node, and the rescale moves to the node after it
+        let keep = vec![format!("{}:keep", rebase.node)];
+        let moved = output_rescales(&keep);
+        assert!(!moved.iter().any(|r| r.node == rebase.node));
+        let next = moved.last().expect("the rescale wasn't moved").clone();
+        assert!(next.node > rebase.node);
+        assert_eq!(next.from_scale, rebase.from_scale);
+        let (keep_scale, _) = run("keep", &keep);
+        assert_eq!(keep_scale, default_scale);
+
+        // suppressing the rescales up to the output leaves it at the scale of the product, which then differs from
+        // the float pass by the quantization of the input and params only: neither by the rounding of the rescale
+        // nor by the coarser quantization of the bias at the default scale, half a step of the default scale each
+        let mut kept = keep;
+        while let Some(r) = output_rescales(&kept).last() {
+            assert!(kept.len() < 16, "the rescales of {:?} keep moving", kept);
+            kept.push(format!("{}:keep", r.node));
+        }
+        let (precise_scale, precise_diff) = run("precise", &kept);
+        assert_eq!(precise_scale, rebase.from_scale);
+        let step = 1.0 / f64::powi(2.0, default_scale);
+        assert!(
+            precise_diff.outputs[0].diff.max_abs_error
+                <= default_diff.outputs[0].diff.max_abs_error + step
+        );
+
+        // an explicit rescale replaces the implicit one, and isn't listed among them
+        let mut to = kept.clone();
+        to[0] = format!("{}:{}", rebase.node, rebase.to_scale + 2);
+        assert!(output_rescales(&to).is_empty());
+        let (to_scale, _) = run("to", &to);
+        assert_eq!(to_scale, rebase.to_scale + 2);
+
+        // overrides of nodes not in the model, or several of a node, are rejected
+        let output = format!("{}/rescales.json", dir);
+        let list = ["list-rescales", "-M", &model, "-O", &output];
+        assert!(!ezkl(&list, &["1000:keep".to_string()]).success());
+        assert!(!ezkl(&list, &[kept[0].clone(), kept[0].clone()]).success());
+    }
+
+    fn check_tables(test_dir: &str, example_name: String) {
+        use ezkl::circuit::lookup::TableConsistency;
+
+        let dir = format!("{}/{}", test_dir, example_name);
+        let settings_path = format!("{}/settings.json", dir);
+        let report_path = format!("{}/tables.json", dir);
+        let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+            .args([
+                "gen-settings",
+                "-M",
+                &format!("{}/network.onnx", dir),
+                "-O",
+                &settings_path,
+            ])
+            .status()
+            .expect("failed to execute process");
+        assert!(status.success());
+
+        let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+            .args(["check-tables", "-S", &settings_path, "-O", &report_path])
+            .status()
+            .expect("failed to execute process");
+        assert!(status.success());
+
+        let reports: Vec<TableConsistency> =
+            serde_json::from_str(&std::fs::read_to_string(report_path).unwrap()).unwrap();
+        // the model has a lookup, whose table holds the witness outputs rounded from the reference
+        assert!(!reports.is_empty());
+        for report in reports {
+            assert!(report.is_consistent(), "{:?}", report);
+            assert!(report.max_deviation <= 0.5 + 1e-9, "{:?}", report);
+        }
+    }
+
+    // commands running out of their timeout exit promptly with the status of a cancellation, leaving no artifacts
+    fn prove_timeout(test_dir: &str, example_name: String, timeout: u64) {
+        gen_circuit_settings_and_witness(
+            test_dir,
+            example_name.clone(),
+            "private",
+            "fixed",
+            "public",
+            1,
+            "resources",
+            None,
+            1,
+            false,
+            &mut 0.0,
+            Commitments::KZG,
+            2,
+        );
+        let dir = format!("{}/{}", test_dir, example_name);
+        init_params(format!("{}/settings.json", dir).into());
+        let ezkl = |args: &[&str]| {
+            Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args(args)
+                .status()
+                .expect("failed to execute process")
+        };
+        assert!(ezkl(&[
+            "setup",
+            "-M",
+            &format!("{}/network.compiled", dir),
+            "--pk-path",
+            &format!("{}/key.pk", dir),
+            "--vk-path",
+            &format!("{}/key.vk", dir),
+        ])
+        .success());
+
+        let timeout = timeout.to_string();
+        let witness_path = format!("{}/timed_out_witness.json", dir);
+        let status = ezkl(&[
+            "--timeout",
+            &timeout,
+            "gen-witness",
+            "-D",
+            &format!("{}/input.json", dir),
+            "-M",
+            &format!("{}/network.compiled", dir),
+            "-O",
+            &witness_path,
+        ]);
+        // the forward pass of the small models completes within the timeout of the large ones
+        if timeout == "0" {
+            assert_eq!(status.code(), Some(4));
+            assert!(!std::path::Path::new(&witness_path).exists());
+        }
+
+        let proof_path = format!("{}/timed_out.pf", dir);
+        let start = std::time::Instant::now();
+        let status = ezkl(&[
+            "--timeout",
+            &timeout,
+            "prove",
+            "-W",
+            &format!("{}/witness.json", dir),
+            "-M",
+            &format!("{}/network.compiled", dir),
+            "--proof-path",
+            &proof_path,
+            "--pk-path",
+            &format!("{}/key.pk", dir),
+        ]);
+        assert_eq!(status.code(), Some(4));
+        // loading the proving key and laying out the node running at the timeout take the rest
+        assert!(start.elapsed().as_secs() < timeout.parse::<u64>().unwrap() + 30);
+        assert!(!std::path::Path::new(&proof_path).exists());
+    }
+
+    // the instances of a proof are decoded into the named, dequantized inputs and outputs of the model
+    fn decode_instances(test_dir: &str, example_name: String) {
+        use ezkl::graph::instances::{decode_instances, InstanceRole};
+        use ezkl::graph::{dequantize, scale_to_multiplier, GraphCircuit, GraphWitness};
+        use ezkl::pfsys::Snark;
+        use halo2_proofs::poly::kzg::commitment::KZGCommitmentScheme;
+        use halo2curves::bn256::Bn256;
+
+        prove_and_verify(
+            test_dir,
+            example_name.clone(),
+            "safe",
+            "public",
+            "private",
+            "public",
+            1,
+            None,
+            false,
+            "single",
+            Commitments::KZG,
+            2,
+        );
+
+        let dir = format!("{}/{}", test_dir, example_name);
+        let settings = GraphSettings::load(&format!("{}/settings.json", dir).into()).unwrap();
+        let proof =
+            Snark::load::<KZGCommitmentScheme<Bn256>>(&format!("{}/proof.pf", dir).into()).unwrap();
+        let decoded = decode_instances(&proof.instances.concat(), &settings).unwrap();
+
+        // named as the onnx graph names them
+        let inspection = GraphCircuit::load(format!("{}/network.compiled", dir).into())
+            .unwrap()
+            .inspect();
+        let names = |tensors: &[ezkl::graph::instances::DecodedTensor]| {
+            tensors.iter().map(|t| t.name.clone()).collect::<Vec<_>>()
+        };
+        assert_eq!(names(&decoded.inputs), inspection.input_names);
+        assert_eq!(names(&decoded.outputs), inspection.output_names);
+
+        // the inputs are those of the data, up to their quantization
+        let data: serde_json::Value =
+            serde_json::from_reader(std::fs::File::open(format!("{}/input.json", dir)).unwrap())
+                .unwrap();
+        let input = &decoded.inputs[0];
+        let expected: Vec<f64> = serde_json::from_value(data["input_data"][0].clone()).unwrap();
+        assert_eq!(input.values.len(), expected.len());
+        for (value, expected) in input.values.iter().zip(expected) {
+            assert!((value - expected).abs() <= 1.0 / scale_to_multiplier(input.scale));
+        }
+
+        // the outputs are the dequantized outputs of the witness
+        let witness = GraphWitness::from_path(format!("{}/witness.json", dir).into()).unwrap();
+        for (output, (felts, scale)) in decoded
+            .outputs
+            .iter()
+            .zip(witness.outputs.iter().zip(&settings.model_output_scales))
+        {
+            assert_eq!(output.scale, *scale);
+            let expected = felts
+                .iter()
+                .map(|x| dequantize(*x, *scale, 0.0))
+                .collect::<Vec<_>>();
+            assert_eq!(output.values, expected);
+        }
+
+        // each instance is labelled with the value it holds
+        let map = settings.instance_map();
+        assert_eq!(map.len(), proof.instances.concat().len());
+        let last = map.last().unwrap();
+        assert_eq!(last.role, InstanceRole::OutputValue);
+        assert_eq!(last.tensor.as_ref(), inspection.output_names.last());
+        assert_eq!(
+            &last.position,
+            &decoded
+                .outputs
+                .last()
+                .unwrap()
+                .shape
+                .iter()
+                .map(|d| d - 1)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    // an external verifier is handed the description of the key and its raw bytes, which verify a proof
+    fn vk_description(test_dir: &str, example_name: String) {
+        use ezkl::pfsys::verifier::{verify_proof_bytes, VerifierParams};
+        use ezkl::pfsys::vk_description::VkDescription;
+        use halo2_proofs::plonk::VerifyingKey;
+        use halo2curves::bn256::{Fr, G1Affine};
+
+        prove_and_verify(
+            test_dir,
+            example_name.clone(),
+            "safe",
+            "private",
+            "private",
+            "public",
+            1,
+            None,
+            false,
+            "single",
+            Commitments::KZG,
+            2,
+        );
+
+        let dir = format!("{}/{}", test_dir, example_name);
+        let settings_path = format!("{}/settings.json", dir);
+        let vk_path = format!("{}/key.vk", dir);
+        let description_path = format!("{}/vk_description.json", dir);
+        let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+            .args([
+                "export-vk-description",
+                "--vk-path",
+                &vk_path,
+                "-S",
+                &settings_path,
+                "--format",
+                "json",
+                "-O",
+                &description_path,
+            ])
+            .status()
+            .expect("failed to execute process");
+        assert!(status.success());
+
+        let description: VkDescription =
+            serde_json::from_str(&std::fs::read_to_string(description_path).unwrap()).unwrap();
+        let settings = GraphSettings::load(&settings_path.into()).unwrap();
+        assert_eq!(description.settings, settings);
+        assert_eq!(description.instances, settings.instance_layout());
+        assert_eq!(description.instance_map, settings.instance_map());
+        assert!(!description.gates.is_empty());
+        assert!(!description.lookups.is_empty());
+        assert_eq!(
+            description.permutation_columns.len(),
+            description.permutation_commitments.len()
+        );
+
+        // the key read from its raw bytes with the settings of the description is the key described
+        let vk_bytes = std::fs::read(&vk_path).unwrap();
+        let vk = VerifyingKey::<G1Affine>::read::<_, GraphCircuit>(
+            &mut vk_bytes.as_slice(),
+            halo2_proofs::SerdeFormat::RawBytes,
+            description.settings.clone(),
+        )
+        .unwrap();
+        assert_eq!(VkDescription::new(&vk, &description.settings), description);
+
+        // and verifies the proof with nothing but the description, the raw key bytes and the srs
+        let srs = std::fs::read(ezkl::execute::get_srs_path(
+            description.logrows,
+            None,
+            Commitments::KZG,
+        ))
+        .unwrap();
+        let params =
+            VerifierParams::from_bytes(&serde_json::to_vec(&description.settings).unwrap(), &srs)
+                .unwrap();
+        let snark =
+            Snark::load::<KZGCommitmentScheme<Bn256>>(&format!("{}/proof.pf", dir).into()).unwrap();
+        let instances = snark.instances.concat();
+        assert_eq!(
+            instances.len(),
+            description.num_instances.iter().sum::<usize>()
+        );
+        assert!(verify_proof_bytes(&vk_bytes, &snark.proof, &instances, &params).is_ok());
+
+        let mut tampered = instances.clone();
+        tampered[0] += Fr::from(1);
+        assert!(verify_proof_bytes(&vk_bytes, &snark.proof, &tampered, &params).is_err());
+    }
+
+    fn param_commitment_groups(test_dir: &str, example_name: String) {
+        use ezkl::graph::{NodeType, SupportedOp};
+        use halo2curves::ff::Field;
+
+        let dir = format!("{}/{}", test_dir, example_name);
+        let settings_path = format!("{}/settings.json", dir);
+        let compiled = format!("{}/network.compiled", dir);
+        let ezkl = |args: &[&str]| {
+            Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args(args)
+                .stdout(std::process::Stdio::null())
+                .status()
+                .expect("failed to execute process")
+        };
+        let compile = |groups: &str| {
+            let mut args = vec![
+                "gen-settings".to_string(),
+                "-M".to_string(),
+                format!("{}/network.onnx", dir),
+                format!("--settings-path={}", settings_path),
+                "--param-visibility=polycommit".to_string(),
+            ];
+            if !groups.is_empty() {
+                args.push(format!("--commitment-groups={}", groups));
+            }
+            assert!(ezkl(&args.iter().map(|a| a.as_str()).collect::<Vec<_>>()).success());
+            assert!(ezkl(&[
+                "compile-circuit",
+                "-M",
+                &format!("{}/network.onnx", dir),
+                "--compiled-circuit",
+                &compiled,
+                &format!("--settings-path={}", settings_path),
+            ])
+            .success());
+        };
+
+        // the last constant of the model is the head, the others the backbone
+        compile("");
+        let const_nodes = GraphCircuit::load(compiled.clone().into())
+            .unwrap()
+            .model()
+            .const_nodes();
+        assert!(const_nodes.len() > 1);
+        let (backbone, head) = (const_nodes[0][0], const_nodes.last().unwrap()[0]);
+        compile(&format!("head:{}", head));
+        let settings = GraphSettings::load(&settings_path.clone().into()).unwrap();
+        assert_eq!(settings.param_commitment_groups, vec!["head", "default"]);
+
+        init_params(settings_path.clone().into());
+        assert!(ezkl(&[
+            "setup",
+            "-M",
+            &compiled,
+            "--pk-path",
+            &format!("{}/key.pk", dir),
+            "--vk-path",
+            &format!("{}/key.vk", dir),
+            "--disable-selector-compression",
+        ])
+        .success());
+        let commit = |groups_dir: &str| {
+            let witness = format!("{}/{}_witness.json", dir, groups_dir);
+            assert!(ezkl(&[
+                "gen-witness",
+                "-D",
+                &format!("{}/input.json", dir),
+                "-M",
+                &compiled,
+                "-O",
+                &witness,
+                &format!("--vk-path={}/key.vk", dir),
+            ])
+            .success());
+            assert!(ezkl(&[
+                "commit-param-groups",
+                "-W",
+                &witness,
+                "-S",
+                &settings_path,
+                "-O",
+                &format!("{}/{}", dir, groups_dir),
+            ])
+            .success());
+            witness
+        };
+        let verify = |groups: &[String]| {
+            ezkl(&[
+                "verify",
+                &format!("--settings-path={}", settings_path),
+                "--proof-path",
+                &format!("{}/proof.pf", dir),
+                "--vk-path",
+                &format!("{}/key.vk", dir),
+                &format!("--group-commitments={}", groups.join(",")),
+            ])
+        };
+
+        let witness = commit("groups");
+        let group =
+            |groups_dir: &str, group: &str| format!("{}/{}/{}.json", dir, groups_dir, group);
+        assert!(ezkl(&[
+            "prove",
+            "-W",
+            &witness,
+            "-M",
+            &compiled,
+            "--proof-path",
+            &format!("{}/proof.pf", dir),
+            "--pk-path",
+            &format!("{}/key.pk", dir),
+        ])
+        .success());
+
+        // only the commitments of the head are swapped in, and the proof checks against both groups
+        assert!(ezkl(&[
+            "swap-proof-commitments",
+            "--proof-path",
+            &format!("{}/proof.pf", dir),
+            &format!("--group-commitments={}", group("groups", "head")),
+        ])
+        .success());
+        let groups = [group("groups", "head"), group("groups", "default")];
+        assert!(verify(&groups).success());
+
+        // once a weight of the backbone changes the head still checks but the backbone no longer does
+        let mut circuit = GraphCircuit::load(compiled.clone().into()).unwrap();
+        match circuit.core.model.graph.nodes.get_mut(&backbone) {
+            Some(NodeType::Node(node)) => match &mut node.opkind {
+                SupportedOp::Constant(constant) => {
+                    constant.quantized_values[0] += halo2curves::bn256::Fr::ONE
+                }
+                op => panic!("node {} isn't a constant: {:?}", backbone, op),
+            },
+            _ => panic!("node {} isn't a constant", backbone),
+        }
+        circuit.save(compiled.clone().into()).unwrap();
+        commit("changed");
+        assert!(verify(&[group("changed", "head")]).success());
+        assert_eq!(
+            verify(&[group("changed", "head"), group("changed", "default")]).code(),
+            Some(3)
+        );
+    }
+
+    fn polycommit_blinding(test_dir: &str, example_name: String) {
+        use ezkl::circuit::modules::polycommit::{PolyCommitBlinders, PolyCommitChip};
+        use ezkl::graph::modules::ModuleElGamal;
+
+        let dir = format!("{}/{}", test_dir, example_name);
+        let settings_path = format!("{}/settings.json", dir);
+        let ezkl = |args: &[&str]| {
+            Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args(args)
+                .stdout(std::process::Stdio::null())
+                .status()
+                .expect("failed to execute process")
+        };
+
+        assert!(ezkl(&[
+            "gen-settings",
+            "-M",
+            &format!("{}/network.onnx", dir),
+            &format!("--settings-path={}", settings_path),
+            "--input-visibility=polycommit",
+            "--param-visibility=private",
+            "--output-visibility=public",
+            "--polycommit-blinding",
+        ])
+        .success());
+        assert!(ezkl(&[
+            "calibrate-settings",
+            "--data",
+            &format!("{}/input.json", dir),
+            "-M",
+            &format!("{}/network.onnx", dir),
+            &format!("--settings-path={}", settings_path),
+            "--target=resources",
+        ])
+        .success());
+        assert!(ezkl(&[
+            "compile-circuit",
+            "-M",
+            &format!("{}/network.onnx", dir),
+            "--compiled-circuit",
+            &format!("{}/network.compiled", dir),
+            &format!("--settings-path={}", settings_path),
+        ])
+        .success());
+        init_params(settings_path.clone().into());
+        assert!(ezkl(&[
+            "setup",
+            "-M",
+            &format!("{}/network.compiled", dir),
+            "--pk-path",
+            &format!("{}/key.pk", dir),
+            "--vk-path",
+            &format!("{}/key.vk", dir),
+            "--disable-selector-compression",
+        ])
+        .success());
+
+        let gen_witness = |witness: &str, blinding_key: Option<String>| {
+            let mut args = vec![
+                "gen-witness".to_string(),
+                "-D".to_string(),
+                format!("{}/input.json", dir),
+                "-M".to_string(),
+                format!("{}/network.compiled", dir),
+                "-O".to_string(),
+                witness.to_string(),
+                format!("--vk-path={}/key.vk", dir),
+            ];
+            if let Some(key) = blinding_key {
+                args.push(format!("--blinding-key={}", key));
+            }
+            ezkl(&args.iter().map(|a| a.as_str()).collect::<Vec<_>>())
+        };
+        let mock = |witness: &str, blinding_secret_key: Option<String>| {
+            let mut args = vec![
+                "mock".to_string(),
+                "-W".to_string(),
+                witness.to_string(),
+                "-M".to_string(),
+                format!("{}/network.compiled", dir),
+            ];
+            if let Some(key) = blinding_secret_key {
+                args.push(format!("--blinding-secret-key={}", key));
+            }
+            ezkl(&args.iter().map(|a| a.as_str()).collect::<Vec<_>>())
+        };
+
+        let first = format!("{}/first.json", dir);
+        let second = format!("{}/second.json", dir);
+        assert!(gen_witness(&first, None).success());
+        assert!(gen_witness(&second, None).success());
+        assert!(mock(&first, None).success());
+
+        // the same inputs blinded differently have different commitments
+        let first_witness = GraphWitness::from_path(first.clone().into()).unwrap();
+        let second_witness = GraphWitness::from_path(second.clone().into()).unwrap();
+        let commitment = first_witness.get_polycommitments();
+        assert!(!commitment.is_empty());
+        assert_ne!(commitment, second_witness.get_polycommitments());
+
+        // the commitment reopens to the inputs with the blinder, and only with it
+        let settings = GraphSettings::load(&settings_path.clone().into()).unwrap();
+        let srs = ezkl::pfsys::srs::load_srs_prover::<KZGCommitmentScheme<Bn256>>(
+            ezkl::execute::get_srs_path(settings.run_args.logrows, None, Commitments::KZG),
+        )
+        .unwrap();
+        let vk = ezkl::pfsys::load_vk::<KZGCommitmentScheme<Bn256>, GraphCircuit>(
+            format!("{}/key.vk", dir).into(),
+            settings.clone(),
+        )
+        .unwrap();
+        let blinders = first_witness.get_polycommit_blinders().unwrap();
+        assert_eq!(blinders.len(), first_witness.inputs.len());
+        let opens = |blinder: Option<halo2curves::bn256::Fr>| {
+            PolyCommitChip::verify_opening::<KZGCommitmentScheme<Bn256>>(
+                &commitment,
+                first_witness.inputs[0].clone(),
+                blinder,
+                (vk.cs().blinding_factors() + 1) as u32,
+                &srs,
+            )
+        };
+        let other_blinders = second_witness.get_polycommit_blinders().unwrap();
+        assert!(opens(Some(blinders[0])));
+        assert!(!opens(None));
+        assert!(!opens(Some(other_blinders[0])));
+
+        // the blinded commitments are proven and verify
+        assert!(ezkl(&[
+            "prove",
+            "-W",
+            &first,
+            "-M",
+            &format!("{}/network.compiled", dir),
+            "--proof-path",
+            &format!("{}/proof.pf", dir),
+            "--pk-path",
+            &format!("{}/key.pk", dir),
+        ])
+        .success());
+        assert!(ezkl(&[
+            "swap-proof-commitments",
+            "--proof-path",
+            &format!("{}/proof.pf", dir),
+            "--witness-path",
+            &first,
+        ])
+        .success());
+        assert!(ezkl(&[
+            "verify",
+            &format!("--settings-path={}", settings_path),
+            "--proof-path",
+            &format!("{}/proof.pf", dir),
+            "--vk-path",
+            &format!("{}/key.vk", dir),
+        ])
+        .success());
+
+        // blinders encrypted in the witness are decrypted with the secret key to be proven
+        let (secret_key, public_key) = ModuleElGamal::gen_keys(&mut rand::rngs::OsRng);
+        let encrypted = format!("{}/encrypted.json", dir);
+        assert!(gen_witness(
+            &encrypted,
+            Some(format!("{:?},{:?}", public_key.x, public_key.y))
+        )
+        .success());
+        let encrypted_witness = GraphWitness::from_path(encrypted.clone().into()).unwrap();
+        assert!(matches!(
+            encrypted_witness
+                .processed_inputs
+                .as_ref()
+                .and_then(|p| p.polycommit_blinders.as_ref()),
+            Some(PolyCommitBlinders::Encrypted(_))
+        ));
+        assert!(!mock(&encrypted, None).success());
+        assert!(mock(&encrypted, Some(format!("{:?}", secret_key))).success());
+    }
+
+    // an encoder and a head proven separately, the head consuming the output of the encoder through its digest
+    fn link_proofs(test_dir: &str, example_name: String) {
+        let dir = format!("{}/{}", test_dir, example_name);
+        let ezkl = |args: &[&str]| {
+            Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args(args)
+                .stdout(std::process::Stdio::null())
+                .status()
+                .expect("failed to execute process")
+        };
+
+        for (name, input_visibility, output_visibility) in [
+            ("encoder", "private", "hashed/public"),
+            ("head", "hashed/public", "public"),
+        ] {
+            let settings_path = format!("{}/{}_settings.json", dir, name);
+            assert!(ezkl(&[
+                "gen-settings",
+                "-M",
+                &format!("{}/network.onnx", dir),
+                &format!("--settings-path={}", settings_path),
+                &format!("--input-visibility={}", input_visibility),
+                "--param-visibility=private",
+                &format!("--output-visibility={}", output_visibility),
+            ])
+            .success());
+            assert!(ezkl(&[
+                "compile-circuit",
+                "-M",
+                &format!("{}/network.onnx", dir),
+                "--compiled-circuit",
+                &format!("{}/{}.compiled", dir, name),
+                &format!("--settings-path={}", settings_path),
+            ])
+            .success());
+            init_params(settings_path.clone().into());
+            assert!(ezkl(&[
+                "setup",
+                "-M",
+                &format!("{}/{}.compiled", dir, name),
+                "--pk-path",
+                &format!("{}/{}.pk", dir, name),
+                "--vk-path",
+                &format!("{}/{}.vk", dir, name),
+            ])
+            .success());
+        }
+        let prove = |name: &str, data: &str| {
+            let witness = format!("{}/{}_witness.json", dir, name);
+            assert!(ezkl(&[
+                "gen-witness",
+                "-D",
+                data,
+                "-M",
+                &format!("{}/{}.compiled", dir, name),
+                "-O",
+                &witness,
+            ])
+            .success());
+            assert!(ezkl(&[
+                "prove",
+                "-W",
+                &witness,
+                "-M",
+                &format!("{}/{}.compiled", dir, name),
+                "--proof-path",
+                &format!("{}/{}.pf", dir, name),
+                "--pk-path",
+                &format!("{}/{}.pk", dir, name),
+            ])
+            .success());
+            witness
+        };
+        let link = || {
+            ezkl(&[
+                "link-proofs",
+                "--proof-paths",
+                &format!("{}/encoder.pf,{}/head.pf", dir, dir),
+                "--settings-paths",
+                &format!("{}/encoder_settings.json,{}/head_settings.json", dir, dir),
+                "-O",
+                &format!("{}/links.json", dir),
+            ])
+        };
+
+        let encoder_witness = prove("encoder", &format!("{}/input.json", dir));
+        let encoder_witness = GraphWitness::from_path(encoder_witness.into()).unwrap();
+        let head_settings =
+            GraphSettings::load(&format!("{}/head_settings.json", dir).into()).unwrap();
+        // the head is fed the quantized outputs of the encoder as field elements
+        let write_head_input = |outputs: &[halo2curves::bn256::Fr]| {
+            let data = serde_json::json!({
+                "input_data": [outputs],
+                "input_scales": head_settings.model_input_scales,
+            });
+            let path = format!("{}/head_input.json", dir);
+            std::fs::write(&path, data.to_string()).unwrap();
+            path
+        };
+
+        prove("head", &write_head_input(&encoder_witness.outputs[0]));
+        assert!(link().success());
+        let links: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(format!("{}/links.json", dir)).unwrap())
+                .unwrap();
+        assert_eq!(links[0][0]["kind"], "Poseidon");
+
+        // the head proven on a tampered intermediate tensor still verifies but isn't linked to the encoder
+        let mut tampered = encoder_witness.outputs[0].clone();
+        tampered[0] += halo2curves::bn256::Fr::from(1);
+        prove("head", &write_head_input(&tampered));
+        assert!(ezkl(&[
+            "verify",
+            &format!("--settings-path={}/head_settings.json", dir),
+            "--proof-path",
+            &format!("{}/head.pf", dir),
+            "--vk-path",
+            &format!("{}/head.vk", dir),
+        ])
+        .success());
+        assert_eq!(link().code(), Some(3));
+    }
+
+    fn validate_witness(test_dir: &str, example_name: String) {
+        use halo2curves::ff::Field;
+        use serde_json::Value;
+
+        let dir = format!("{}/{}", test_dir, example_name);
+        gen_circuit_settings_and_witness(
+            test_dir,
+            example_name.clone(),
+            "private",
+            "private",
+            "public",
+            1,
+            "resources",
+            None,
+            2,
+            false,
+            &mut 0.0,
+            Commitments::KZG,
+            2,
+        );
+        let compiled = format!("{}/network.compiled", dir);
+        let validate = |witness: &str| {
+            Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args(["--json", "validate-witness", "-W", witness, "-M", &compiled])
+                .output()
+                .expect("failed to execute process")
+        };
+        assert_eq!(
+            validate(&format!("{}/witness.json", dir)).status.code(),
+            Some(0)
+        );
+
+        // the witness is read with its tensors in a binary sidecar, and converts back to the same json
+        let convert = |witness: &str, output: &str, threshold: Option<&str>| {
+            let mut args = vec!["convert-witness", "-W", witness, "-O", output];
+            args.extend(threshold.iter().flat_map(|t| ["--sidecar-threshold", t]));
+            Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args(args)
+                .status()
+                .expect("failed to execute process")
+        };
+        let hybrid = format!("{}/witness_sidecar.json", dir);
+        let pure = format!("{}/witness_pure.json", dir);
+        assert!(convert(&format!("{}/witness.json", dir), &hybrid, Some("0")).success());
+        assert!(std::path::Path::new(&format!("{}.bin", hybrid)).exists());
+        assert_eq!(validate(&hybrid).status.code(), Some(0));
+        assert!(convert(&hybrid, &pure, None).success());
+        assert_eq!(
+            GraphWitness::from_path(pure.into()).unwrap(),
+            GraphWitness::from_path(format!("{}/witness.json", dir).into()).unwrap()
+        );
+
+        // the outputs are compared to the public instances last, by the op of the output node
+        let circuit = GraphCircuit::load(compiled.clone().into()).unwrap();
+        let output_check = circuit.core.op_rows.last().unwrap().clone();
+        let mut witness = GraphWitness::from_path(format!("{}/witness.json", dir).into()).unwrap();
+        witness.outputs[0][0] += halo2curves::bn256::Fr::ONE;
+        let corrupted = format!("{}/witness_corrupted.json", dir);
+        witness.save(corrupted.clone().into()).unwrap();
+
+        let output = validate(&corrupted);
+        assert_eq!(output.status.code(), Some(3));
+        let value: Value = serde_json::from_slice(&output.stdout).unwrap();
+        let message = value["error"]["message"].as_str().unwrap();
+        assert!(
+            message.contains(&format!(
+                "node {} ({}) row ",
+                output_check.node, output_check.op
+            )),
+            "{}",
+            message
+        );
+    }
+
+    fn model_serialization_different_binaries(test_dir: &str, example_name: String) {
+        let status = Command::new("cargo")
+            .args([
+                "run",
+                "--bin",
+                "ezkl",
+                "--",
+                "gen-settings",
+                "-M",
+                format!("{}/{}/network.onnx", test_dir, example_name).as_str(),
+                &format!(
+                    "--settings-path={}/{}/settings.json",
+                    test_dir, example_name
+                ),
+            ])
+            .status()
+            .expect("failed to execute process");
+        assert!(status.success());
+
+        let status = Command::new("cargo")
+            .args([
+                "run",
+                "--bin",
+                "ezkl",
+                "--",
+                "compile-circuit",
+                "-M",
+                format!("{}/{}/network.onnx", test_dir, example_name).as_str(),
+                "--compiled-circuit",
+                format!("{}/{}/network.compiled", test_dir, example_name).as_str(),
+                &format!(
+                    "--settings-path={}/{}/settings.json",
+                    test_dir, example_name
+                ),
+            ])
+            .status()
+            .expect("failed to execute process");
+        assert!(status.success());
+
+        // now alter binary slightly
+        // create new temp cargo.toml with a different version
+        // cpy old cargo.toml to cargo.toml.bak
+        let status = Command::new("cp")
+            .args(["Cargo.toml", "Cargo.toml.bak"])
+            .status()
+            .expect("failed to execute process");
+        assert!(status.success());
+
+        let mut cargo_toml = std::fs::File::open("Cargo.toml").unwrap();
+        let mut cargo_toml_contents = String::new();
+        cargo_toml.read_to_string(&mut cargo_toml_contents).unwrap();
+        let mut cargo_toml_contents = cargo_toml_contents.split('\n').collect::<Vec<_>>();
+
+        // draw a random version number from 0.0.0 to 0.100.100
+        let mut rng = rand::thread_rng();
+        let version = &format!(
+            "version = \"0.{}.{}-test\"",
+            rng.gen_range(0..100),
+            rng.gen_range(0..100)
+        );
+        let cargo_toml_contents = cargo_toml_contents
+            .iter_mut()
+            .map(|line| {
+                if line.starts_with("version") {
+                    *line = version;
+                }
+                *line
+            })
+            .collect::<Vec<_>>();
+        let mut cargo_toml = std::fs::File::create("Cargo.toml").unwrap();
+        cargo_toml
+            .write_all(cargo_toml_contents.join("\n").as_bytes())
+            .unwrap();
+
+        let status = Command::new("cargo")
+            .args([
+                "run",
+                "--bin",
+                "ezkl",
+                "--",
+                "gen-witness",
+                "-D",
+                &format!("{}/{}/input.json", test_dir, example_name),
+                "-M",
+                &format!("{}/{}/network.compiled", test_dir, example_name),
+                "-O",
+                &format!("{}/{}/witness.json", test_dir, example_name),
+            ])
+            .status()
+            .expect("failed to execute process");
+        assert!(status.success());
+
+        // now delete cargo.toml and move cargo.toml.bak to cargo.toml
+        let status = Command::new("rm")
+            .args(["Cargo.toml"])
+            .status()
+            .expect("failed to execute process");
+        assert!(status.success());
+
+        let status = Command::new("mv")
+            .args(["Cargo.toml.bak", "Cargo.toml"])
+            .status()
+            .expect("failed to execute process");
+        assert!(status.success());
+    }
+
+    // Mock prove (fast, but does not cover some potential issues)
+    fn run_example(example_name: String) {
+        let status = Command::new("cargo")
+            .args(["run", "--release", "--example", example_name.as_str()])
+            .status()
+            .expect("failed to execute process");
+        assert!(status.success());
+    }
+
+    // Mock prove (fast, but does not cover some potential issues)
+    #[allow(clippy::too_many_arguments)]
+    fn mock(
+        test_dir: &str,
+        example_name: String,
+        input_visibility: &str,
+        param_visibility: &str,
+        output_visibility: &str,
+        batch_size: usize,
+        cal_target: &str,
+        scales_to_use: Option<Vec<u32>>,
+        tolerance: f32,
+    ) {
+        let mut tolerance = tolerance;
+        gen_circuit_settings_and_witness(
+            test_dir,
+            example_name.clone(),
+            input_visibility,
+            param_visibility,
+            output_visibility,
+            batch_size,
+            cal_target,
+            scales_to_use,
+            2,
+            false,
+            &mut tolerance,
+            Commitments::KZG,
+            2,
+        );
+
+        if tolerance > 0.0 {
+            // load witness and shift the output by a small amount that is less than tolerance percent
+            let witness = GraphWitness::from_path(
+                format!("{}/{}/witness.json", test_dir, example_name).into(),
+            )
+            .unwrap();
+            let witness = witness.clone();
+            let outputs = witness.outputs.clone();
+
+            // get values as i64
+            let output_perturbed_safe: Vec<Vec<halo2curves::bn256::Fr>> = outputs
+                .iter()
+                .map(|sv| {
+                    sv.iter()
+                        .map(|v| {
+                            // randomly perturb by a small amount less than tolerance
+                            let perturbation = if v == &halo2curves::bn256::Fr::zero() {
+                                halo2curves::bn256::Fr::zero()
+                            } else {
+                                integer_rep_to_felt(
+                                    (felt_to_integer_rep(*v) as f32
+                                        * (rand::thread_rng().gen_range(-0.01..0.01) * tolerance))
+                                        as IntegerRep,
+                                )
+                            };
+
+                            *v + perturbation
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+
+            // get values as i64
+            let output_perturbed_bad: Vec<Vec<halo2curves::bn256::Fr>> = outputs
+                .iter()
+                .map(|sv| {
+                    sv.iter()
+                        .map(|v| {
+                            // randomly perturb by a small amount less than tolerance
+                            let perturbation = if v == &halo2curves::bn256::Fr::zero() {
+                                halo2curves::bn256::Fr::from(2)
+                            } else {
+                                integer_rep_to_felt(
+                                    (felt_to_integer_rep(*v) as f32
+                                        * (rand::thread_rng().gen_range(0.02..0.1) * tolerance))
+                                        as IntegerRep,
+                                )
+                            };
+                            *v + perturbation
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+
+            let good_witness = GraphWitness {
+                outputs: output_perturbed_safe,
+                ..witness.clone()
+            };
+
+            // save
+            good_witness
+                .save(format!("{}/{}/witness_ok.json", test_dir, example_name).into())
+                .unwrap();
+
+            let bad_witness = GraphWitness {
+                outputs: output_perturbed_bad,
+                ..witness.clone()
+            };
+
+            // save
+            bad_witness
+                .save(format!("{}/{}/witness_bad.json", test_dir, example_name).into())
+                .unwrap();
+
+            let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args([
+                    "mock",
+                    "-W",
+                    format!("{}/{}/witness.json", test_dir, example_name).as_str(),
+                    "-M",
+                    format!("{}/{}/network.compiled", test_dir, example_name).as_str(),
+                ])
+                .status()
+                .expect("failed to execute process");
+            assert!(status.success());
+
+            let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args([
+                    "mock",
+                    "-W",
+                    format!("{}/{}/witness_ok.json", test_dir, example_name).as_str(),
+                    "-M",
+                    format!("{}/{}/network.compiled", test_dir, example_name).as_str(),
+                ])
+                .status()
+                .expect("failed to execute process");
+            assert!(status.success());
+
+            let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args([
+                    "mock",
+                    "-W",
+                    format!("{}/{}/witness_bad.json", test_dir, example_name).as_str(),
+                    "-M",
+                    format!("{}/{}/network.compiled", test_dir, example_name).as_str(),
+                ])
+                .status()
+                .expect("failed to execute process");
+            assert!(!status.success());
+        } else {
+            let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args([
+                    "mock",
+                    "-W",
+                    format!("{}/{}/witness.json", test_dir, example_name).as_str(),
+                    "-M",
+                    format!("{}/{}/network.compiled", test_dir, example_name).as_str(),
+                ])
+                .status()
+                .expect("failed to execute process");
+            assert!(status.success());
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn gen_circuit_settings_and_witness(
+        test_dir: &str,
+        example_name: String,
+        input_visibility: &str,
+        param_visibility: &str,
+        output_visibility: &str,
+        batch_size: usize,
+        cal_target: &str,
+        scales_to_use: Option<Vec<u32>>,
+        num_inner_columns: usize,
+        div_rebasing: bool,
+        tolerance: &mut f32,
+        commitment: Commitments,
+        lookup_safety_margin: usize,
+    ) {
+        let mut args = vec![
+            "gen-settings".to_string(),
+            "-M".to_string(),
+            format!("{}/{}/network.onnx", test_dir, example_name),
+            format!(
+                "--settings-path={}/{}/settings.json",
+                test_dir, example_name
+            ),
+            format!("--variables=batch_size->{}", batch_size),
+            format!("--input-visibility={}", input_visibility),
+            format!("--param-visibility={}", param_visibility),
+            format!("--output-visibility={}", output_visibility),
+            format!("--num-inner-cols={}", num_inner_columns),
+            format!("--tolerance={}", tolerance),
+            format!("--commitment={}", commitment),
+        ];
+
+        if div_rebasing {
+            args.push("--div-rebasing".to_string());
+        };
+
+        let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+            .args(args)
+            .stdout(std::process::Stdio::null())
+            .status()
+            .expect("failed to execute process");
+        assert!(status.success());
+
+        let mut calibrate_args = vec![
+            "calibrate-settings".to_string(),
+            "--data".to_string(),
+            format!("{}/{}/input.json", test_dir, example_name),
+            "-M".to_string(),
+            format!("{}/{}/network.onnx", test_dir, example_name),
+            format!(
+                "--settings-path={}/{}/settings.json",
+                test_dir, example_name
+            ),
+            format!("--target={}", cal_target),
+            format!("--lookup-safety-margin={}", lookup_safety_margin),
+        ];
+
+        if let Some(scales) = scales_to_use {
+            let scales = scales
+                .iter()
+                .map(|s| s.to_string())
+                .collect::<Vec<_>>()
+                .join(",");
+            calibrate_args.push("--scales".to_string());
+            calibrate_args.push(scales);
+        }
+
+        let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+            .args(calibrate_args)
+            .status()
+            .expect("failed to execute process");
+        assert!(status.success());
+
+        let mut settings =
+            GraphSettings::load(&format!("{}/{}/settings.json", test_dir, example_name).into())
+                .unwrap();
+
+        let any_output_scales_smol = settings.model_output_scales.iter().any(|s| *s <= 0);
+
+        if any_output_scales_smol {
+            // set the tolerance to 0.0
+            settings.run_args.tolerance = Tolerance {
+                val: 0.0,
+                scale: 0.0.into(),
+            };
+            settings
+                .save(&format!("{}/{}/settings.json", test_dir, example_name).into())
+                .unwrap();
+            *tolerance = 0.0;
+        }
+
+        let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+            .args([
+                "compile-circuit",
+                "-M",
+                format!("{}/{}/network.onnx", test_dir, example_name).as_str(),
+                "--compiled-circuit",
+                format!("{}/{}/network.compiled", test_dir, example_name).as_str(),
+                &format!(
+                    "--settings-path={}/{}/settings.json",
+                    test_dir, example_name
+                ),
+            ])
+            .stdout(std::process::Stdio::null())
+            .status()
+            .expect("failed to execute process");
+        assert!(status.success());
+
+        let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+            .args([
+                "gen-witness",
+                "-D",
+                &format!("{}/{}/input.json", test_dir, example_name),
+                "-M",
+                &format!("{}/{}/network.compiled", test_dir, example_name),
+                "-O",
+                &format!("{}/{}/witness.json", test_dir, example_name),
+            ])
+            .stdout(std::process::Stdio::null())
+            .status()
+            .expect("failed to execute process");
+        assert!(status.success());
+    }
+
+    // Mock prove (fast, but does not cover some potential issues)
+    #[allow(clippy::too_many_arguments)]
+    fn accuracy_measurement(
+        test_dir: &str,
+        example_name: String,
+        input_visibility: &str,
+        param_visibility: &str,
+        output_visibility: &str,
+        batch_size: usize,
+        cal_target: &str,
+        target_perc: f32,
+        div_rebasing: bool,
+    ) {
+        gen_circuit_settings_and_witness(
+            test_dir,
+            example_name.clone(),
+            input_visibility,
+            param_visibility,
+            output_visibility,
+            batch_size,
+            cal_target,
+            None,
+            2,
+            div_rebasing,
+            &mut 0.0,
+            Commitments::KZG,
+            2,
+        );
+
+        println!(
+            " ------------ running accuracy measurement for {}",
+            example_name
+        );
+        // run python ./output_comparison.py in the test dir
+        let status = Command::new("python")
+            .args([
+                "tests/output_comparison.py",
+                &format!("{}/{}/network.onnx", test_dir, example_name),
+                &format!("{}/{}/input.json", test_dir, example_name),
+                &format!("{}/{}/witness.json", test_dir, example_name),
+                &format!("{}/{}/settings.json", test_dir, example_name),
+                &format!("{}", target_perc),
+            ])
+            .status()
+            .expect("failed to execute process");
+
+        assert!(status.success());
+    }
+
+    // Mock prove (fast, but does not cover some potential issues)
+    fn render_circuit(test_dir: &str, example_name: String) {
+        let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+            .args([
+                "render-circuit",
+                "-M",
+                format!("{}/{}/network.onnx", test_dir, example_name).as_str(),
+                "-O",
+                format!("{}/{}/render.png", test_dir, example_name).as_str(),
+                "--lookup-range=-32768->32768",
+                "-K=17",
+            ])
+            .status()
+            .expect("failed to execute process");
+        assert!(status.success());
+    }
+
+    // prove-serialize-verify, the usual full path
+    fn kzg_aggr_mock_prove_and_verify(test_dir: &str, example_name: String) {
+        prove_and_verify(
+            test_dir,
+            example_name.clone(),
+            "safe",
+            "private",
+            "private",
+            "public",
+            2,
+            None,
+            false,
+            "for-aggr",
+            Commitments::KZG,
+            2,
+        );
+        let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+            .args([
+                "mock-aggregate",
+                "--logrows=23",
+                "--aggregation-snarks",
+                &format!("{}/{}/proof.pf", test_dir, example_name),
+            ])
+            .status()
+            .expect("failed to execute process");
+        assert!(status.success());
     }
-    };
-}
 
-    macro_rules! test_func_examples {
-    () => {
-        #[cfg(test)]
-        mod tests_examples {
-            use seq_macro::seq;
-            use crate::native_tests::EXAMPLES;
-            use test_case::test_case;
-            use crate::native_tests::run_example as run;
-            seq!(N in 0..=1 {
-            #(#[test_case(EXAMPLES[N])])*
-            fn example_(test: &str) {
-                run(test.to_string());
-            }
-            });
-    }
-    };
-}
+    // leaves proven with a poseidon transcript aggregate natively, others are rejected
+    fn kzg_aggr_transcripts(test_dir: &str, example_name: String) {
+        prove_and_verify(
+            test_dir,
+            example_name.clone(),
+            "safe",
+            "private",
+            "private",
+            "public",
+            2,
+            None,
+            false,
+            "for-aggr",
+            Commitments::KZG,
+            2,
+        );
 
-    test_func!();
-    test_func_aggr!();
-    test_func_evm!();
-    test_func_examples!();
+        let settings_path = format!("{}/{}/settings.json", test_dir, example_name);
 
-    fn model_serialization_different_binaries(test_dir: &str, example_name: String) {
-        let status = Command::new("cargo")
+        // verify must enforce the transcript recorded in the proof
+        let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
             .args([
-                "run",
-                "--bin",
-                "ezkl",
-                "--",
-                "gen-settings",
-                "-M",
-                format!("{}/{}/network.onnx", test_dir, example_name).as_str(),
-                &format!(
-                    "--settings-path={}/{}/settings.json",
-                    test_dir, example_name
-                ),
+                "verify",
+                format!("--settings-path={}", settings_path).as_str(),
+                "--proof-path",
+                &format!("{}/{}/proof.pf", test_dir, example_name),
+                "--vk-path",
+                &format!("{}/{}/key.vk", test_dir, example_name),
+                "--transcript=evm",
+            ])
+            .status()
+            .expect("failed to execute process");
+        assert!(!status.success());
+
+        let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+            .args([
+                "mock-aggregate",
+                "--logrows=23",
+                "--aggregation-snarks",
+                &format!("{}/{}/proof.pf", test_dir, example_name),
             ])
             .status()
             .expect("failed to execute process");
         assert!(status.success());
 
-        let status = Command::new("cargo")
+        // the fewest rows a poseidon leaf is aggregated in, as the smallest k the mock prover lays the circuit out at
+        let leaf = Snark::load::<KZGCommitmentScheme<Bn256>>(
+            &format!("{}/{}/proof.pf", test_dir, example_name).into(),
+        )
+        .unwrap();
+        let circuit =
+            AggregationCircuit::new(&G1Affine::generator().into(), [leaf], false).unwrap();
+        let poseidon_logrows = (1..=23)
+            .find(|k| MockProver::run(*k, &circuit, vec![circuit.instances()]).is_ok())
+            .expect("the aggregation of a poseidon leaf does not fit in 2^23 rows");
+        let prover =
+            MockProver::run(poseidon_logrows, &circuit, vec![circuit.instances()]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+        println!("a poseidon leaf aggregates at k = {}", poseidon_logrows);
+
+        // a blake leaf verifies natively but cannot be verified inside the aggregation circuit
+        let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
             .args([
-                "run",
-                "--bin",
-                "ezkl",
-                "--",
-                "compile-circuit",
+                "prove",
+                "-W",
+                format!("{}/{}/witness.json", test_dir, example_name).as_str(),
                 "-M",
-                format!("{}/{}/network.onnx", test_dir, example_name).as_str(),
-                "--compiled-circuit",
                 format!("{}/{}/network.compiled", test_dir, example_name).as_str(),
-                &format!(
-                    "--settings-path={}/{}/settings.json",
-                    test_dir, example_name
-                ),
+                "--proof-path",
+                &format!("{}/{}/proof_blake.pf", test_dir, example_name),
+                "--pk-path",
+                &format!("{}/{}/key.pk", test_dir, example_name),
+                "--transcript=blake",
             ])
             .status()
             .expect("failed to execute process");
         assert!(status.success());
 
-        // now alter binary slightly
-        // create new temp cargo.toml with a different version
-        // cpy old cargo.toml to cargo.toml.bak
-        let status = Command::new("cp")
-            .args(["Cargo.toml", "Cargo.toml.bak"])
+        let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+            .args([
+                "verify",
+                format!("--settings-path={}", settings_path).as_str(),
+                "--proof-path",
+                &format!("{}/{}/proof_blake.pf", test_dir, example_name),
+                "--vk-path",
+                &format!("{}/{}/key.vk", test_dir, example_name),
+                "--transcript=blake",
+            ])
             .status()
             .expect("failed to execute process");
         assert!(status.success());
 
-        let mut cargo_toml = std::fs::File::open("Cargo.toml").unwrap();
-        let mut cargo_toml_contents = String::new();
-        cargo_toml.read_to_string(&mut cargo_toml_contents).unwrap();
-        let mut cargo_toml_contents = cargo_toml_contents.split('\n').collect::<Vec<_>>();
+        let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+            .args([
+                "mock-aggregate",
+                "--logrows=23",
+                "--aggregation-snarks",
+                &format!("{}/{}/proof_blake.pf", test_dir, example_name),
+            ])
+            .status()
+            .expect("failed to execute process");
+        assert!(!status.success());
 
-        // draw a random version number from 0.0.0 to 0.100.100
-        let mut rng = rand::thread_rng();
-        let version = &format!(
-            "version = \"0.{}.{}-test\"",
-            rng.gen_range(0..100),
-            rng.gen_range(0..100)
-        );
-        let cargo_toml_contents = cargo_toml_contents
-            .iter_mut()
-            .map(|line| {
-                if line.starts_with("version") {
-                    *line = version;
-                }
-                *line
-            })
-            .collect::<Vec<_>>();
-        let mut cargo_toml = std::fs::File::create("Cargo.toml").unwrap();
-        cargo_toml
-            .write_all(cargo_toml_contents.join("\n").as_bytes())
+        // evm and blake leaves can't be laid out at any k, the aggregation circuit only verifies poseidon transcripts
+        let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+            .args([
+                "prove",
+                "-W",
+                format!("{}/{}/witness.json", test_dir, example_name).as_str(),
+                "-M",
+                format!("{}/{}/network.compiled", test_dir, example_name).as_str(),
+                "--proof-path",
+                &format!("{}/{}/proof_evm_single.pf", test_dir, example_name),
+                "--pk-path",
+                &format!("{}/{}/key.pk", test_dir, example_name),
+                "--transcript=evm",
+            ])
+            .status()
+            .expect("failed to execute process");
+        assert!(status.success());
+        for (proof, transcript) in [
+            ("proof_evm_single.pf", TranscriptType::EVM),
+            ("proof_blake.pf", TranscriptType::Blake),
+        ] {
+            let leaf = Snark::load::<KZGCommitmentScheme<Bn256>>(
+                &format!("{}/{}/{}", test_dir, example_name, proof).into(),
+            )
             .unwrap();
+            assert!(matches!(
+                AggregationCircuit::new(&G1Affine::generator().into(), [leaf], false),
+                Err(AggregationError::UnsupportedTranscript(t)) if t == transcript
+            ));
+        }
 
-        let status = Command::new("cargo")
+        // for-aggr proofs are pinned to the poseidon transcript
+        let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
             .args([
-                "run",
-                "--bin",
-                "ezkl",
-                "--",
-                "gen-witness",
-                "-D",
-                &format!("{}/{}/input.json", test_dir, example_name),
+                "prove",
+                "-W",
+                format!("{}/{}/witness.json", test_dir, example_name).as_str(),
                 "-M",
-                &format!("{}/{}/network.compiled", test_dir, example_name),
-                "-O",
-                &format!("{}/{}/witness.json", test_dir, example_name),
+                format!("{}/{}/network.compiled", test_dir, example_name).as_str(),
+                "--proof-path",
+                &format!("{}/{}/proof_evm.pf", test_dir, example_name),
+                "--pk-path",
+                &format!("{}/{}/key.pk", test_dir, example_name),
+                "--proof-type=for-aggr",
+                "--transcript=evm",
             ])
             .status()
             .expect("failed to execute process");
-        assert!(status.success());
+        assert!(!status.success());
+    }
 
-        // now delete cargo.toml and move cargo.toml.bak to cargo.toml
-        let status = Command::new("rm")
-            .args(["Cargo.toml"])
+    // prove-serialize-verify, the usual full path
+    fn aggr_prove_and_verify(
+        test_dir: &str,
+        example_name: String,
+        input_visibility: &str,
+        param_visibility: &str,
+        output_visibility: &str,
+        commitment: Commitments,
+    ) {
+        prove_and_verify(
+            test_dir,
+            example_name.clone(),
+            "safe",
+            input_visibility,
+            param_visibility,
+            output_visibility,
+            2,
+            None,
+            false,
+            "for-aggr",
+            Commitments::KZG,
+            2,
+        );
+
+        download_srs(23, commitment);
+        // now setup-aggregate
+        let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+            .args([
+                "setup-aggregate",
+                "--sample-snarks",
+                &format!("{}/{}/proof.pf", test_dir, example_name),
+                "--logrows=23",
+                "--vk-path",
+                &format!("{}/{}/aggr.vk", test_dir, example_name),
+                "--pk-path",
+                &format!("{}/{}/aggr.pk", test_dir, example_name),
+                &format!("--commitment={}", commitment),
+            ])
             .status()
             .expect("failed to execute process");
         assert!(status.success());
 
-        let status = Command::new("mv")
-            .args(["Cargo.toml.bak", "Cargo.toml"])
+        let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+            .args([
+                "aggregate",
+                "--logrows=23",
+                "--aggregation-snarks",
+                &format!("{}/{}/proof.pf", test_dir, example_name),
+                "--proof-path",
+                &format!("{}/{}/aggr.pf", test_dir, example_name),
+                "--pk-path",
+                &format!("{}/{}/aggr.pk", test_dir, example_name),
+                &format!("--commitment={}", commitment),
+            ])
             .status()
             .expect("failed to execute process");
         assert!(status.success());
-    }
-
-    // Mock prove (fast, but does not cover some potential issues)
-    fn run_example(example_name: String) {
-        let status = Command::new("cargo")
-            .args(["run", "--release", "--example", example_name.as_str()])
+        let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+            .args([
+                "verify-aggr",
+                "--logrows=23",
+                "--proof-path",
+                &format!("{}/{}/aggr.pf", test_dir, example_name),
+                "--vk-path",
+                &format!("{}/{}/aggr.vk", test_dir, example_name),
+            ])
             .status()
             .expect("failed to execute process");
         assert!(status.success());
     }
 
-    // Mock prove (fast, but does not cover some potential issues)
-    #[allow(clippy::too_many_arguments)]
-    fn mock(
+    // prove-serialize-verify, the usual full path
+    fn kzg_evm_aggr_prove_and_verify(
         test_dir: &str,
         example_name: String,
         input_visibility: &str,
         param_visibility: &str,
         output_visibility: &str,
-        batch_size: usize,
-        cal_target: &str,
-        scales_to_use: Option<Vec<u32>>,
-        tolerance: f32,
     ) {
-        let mut tolerance = tolerance;
-        gen_circuit_settings_and_witness(
+        aggr_prove_and_verify(
             test_dir,
             example_name.clone(),
             input_visibility,
             param_visibility,
             output_visibility,
-            batch_size,
-            cal_target,
-            scales_to_use,
-            2,
-            false,
-            &mut tolerance,
             Commitments::KZG,
-            2,
         );
 
-        if tolerance > 0.0 {
-            // load witness and shift the output by a small amount that is less than tolerance percent
-            let witness = GraphWitness::from_path(
-                format!("{}/{}/witness.json", test_dir, example_name).into(),
-            )
-            .unwrap();
-            let witness = witness.clone();
-            let outputs = witness.outputs.clone();
+        download_srs(23, Commitments::KZG);
 
-            // get values as i64
-            let output_perturbed_safe: Vec<Vec<halo2curves::bn256::Fr>> = outputs
-                .iter()
-                .map(|sv| {
-                    sv.iter()
-                        .map(|v| {
-                            // randomly perturb by a small amount less than tolerance
-                            let perturbation = if v == &halo2curves::bn256::Fr::zero() {
-                                halo2curves::bn256::Fr::zero()
-                            } else {
-                                integer_rep_to_felt(
-                                    (felt_to_integer_rep(*v) as f32
-                                        * (rand::thread_rng().gen_range(-0.01..0.01) * tolerance))
-                                        as IntegerRep,
-                                )
-                            };
+        let vk_arg = &format!("{}/{}/aggr.vk", test_dir, example_name);
 
-                            *v + perturbation
-                        })
-                        .collect::<Vec<_>>()
-                })
-                .collect::<Vec<_>>();
+        fn build_args<'a>(base_args: Vec<&'a str>, sol_arg: &'a str) -> Vec<&'a str> {
+            let mut args = base_args;
 
-            // get values as i64
-            let output_perturbed_bad: Vec<Vec<halo2curves::bn256::Fr>> = outputs
-                .iter()
-                .map(|sv| {
-                    sv.iter()
-                        .map(|v| {
-                            // randomly perturb by a small amount less than tolerance
-                            let perturbation = if v == &halo2curves::bn256::Fr::zero() {
-                                halo2curves::bn256::Fr::from(2)
-                            } else {
-                                integer_rep_to_felt(
-                                    (felt_to_integer_rep(*v) as f32
-                                        * (rand::thread_rng().gen_range(0.02..0.1) * tolerance))
-                                        as IntegerRep,
-                                )
-                            };
-                            *v + perturbation
-                        })
-                        .collect::<Vec<_>>()
-                })
-                .collect::<Vec<_>>();
+            args.push("--sol-code-path");
+            args.push(sol_arg);
+            args
+        }
 
-            let good_witness = GraphWitness {
-                outputs: output_perturbed_safe,
-                ..witness.clone()
-            };
+        let sol_arg = format!("{}/{}/kzg_aggr.sol", test_dir, example_name);
+        let addr_path_arg = format!("--addr-path={}/{}/addr.txt", test_dir, example_name);
+        let rpc_arg = format!("--rpc-url={}", *ANVIL_URL);
+        let settings_arg = format!("{}/{}/settings.json", test_dir, example_name);
+        let private_key = format!("--private-key={}", *ANVIL_DEFAULT_PRIVATE_KEY);
 
-            // save
-            good_witness
-                .save(format!("{}/{}/witness_ok.json", test_dir, example_name).into())
-                .unwrap();
+        // create encoded calldata
+        let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+            .args([
+                "encode-evm-calldata",
+                "--proof-path",
+                &format!("{}/{}/aggr.pf", test_dir, example_name),
+            ])
+            .status()
+            .expect("failed to execute process");
 
-            let bad_witness = GraphWitness {
-                outputs: output_perturbed_bad,
-                ..witness.clone()
-            };
+        assert!(status.success());
 
-            // save
-            bad_witness
-                .save(format!("{}/{}/witness_bad.json", test_dir, example_name).into())
-                .unwrap();
+        let base_args = vec![
+            "create-evm-verifier-aggr",
+            "--vk-path",
+            vk_arg.as_str(),
+            "--aggregation-settings",
+            settings_arg.as_str(),
+            "--logrows=23",
+        ];
 
-            let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
-                .args([
-                    "mock",
-                    "-W",
-                    format!("{}/{}/witness.json", test_dir, example_name).as_str(),
-                    "-M",
-                    format!("{}/{}/network.compiled", test_dir, example_name).as_str(),
-                ])
-                .status()
-                .expect("failed to execute process");
-            assert!(status.success());
+        let args = build_args(base_args, &sol_arg);
 
-            let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
-                .args([
-                    "mock",
-                    "-W",
-                    format!("{}/{}/witness_ok.json", test_dir, example_name).as_str(),
-                    "-M",
-                    format!("{}/{}/network.compiled", test_dir, example_name).as_str(),
-                ])
-                .status()
-                .expect("failed to execute process");
-            assert!(status.success());
+        let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+            .args(args)
+            .status()
+            .expect("failed to execute process");
+        assert!(status.success());
 
-            let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
-                .args([
-                    "mock",
-                    "-W",
-                    format!("{}/{}/witness_bad.json", test_dir, example_name).as_str(),
-                    "-M",
-                    format!("{}/{}/network.compiled", test_dir, example_name).as_str(),
-                ])
-                .status()
-                .expect("failed to execute process");
-            assert!(!status.success());
-        } else {
-            let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
-                .args([
-                    "mock",
-                    "-W",
-                    format!("{}/{}/witness.json", test_dir, example_name).as_str(),
-                    "-M",
-                    format!("{}/{}/network.compiled", test_dir, example_name).as_str(),
-                ])
-                .status()
-                .expect("failed to execute process");
-            assert!(status.success());
-        }
+        // deploy the verifier
+        let args = vec![
+            "deploy-evm",
+            rpc_arg.as_str(),
+            addr_path_arg.as_str(),
+            "--sol-code-path",
+            sol_arg.as_str(),
+            private_key.as_str(),
+        ];
+
+        let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+            .args(&args)
+            .status()
+            .expect("failed to execute process");
+        assert!(status.success());
+
+        // read in the address
+        let addr = std::fs::read_to_string(format!("{}/{}/addr.txt", test_dir, example_name))
+            .expect("failed to read address file");
+
+        let deployed_addr_arg = format!("--addr-verifier={}", addr);
+
+        let pf_arg = format!("{}/{}/aggr.pf", test_dir, example_name);
+
+        let mut base_args = vec![
+            "verify-evm",
+            "--proof-path",
+            pf_arg.as_str(),
+            deployed_addr_arg.as_str(),
+            rpc_arg.as_str(),
+        ];
+
+        let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+            .args(&base_args)
+            .status()
+            .expect("failed to execute process");
+        assert!(status.success());
+        // As sanity check, add example that should fail.
+        base_args[2] = PF_FAILURE_AGGR;
+        let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+            .args(base_args)
+            .status()
+            .expect("failed to execute process");
+        assert!(!status.success());
     }
 
-    #[allow(clippy::too_many_arguments)]
-    fn gen_circuit_settings_and_witness(
+    // aggregate proofs of two differently shaped circuits and check each child's vk hash is bound
+    fn kzg_evm_aggr_heterogeneous_prove_and_verify(
         test_dir: &str,
-        example_name: String,
-        input_visibility: &str,
-        param_visibility: &str,
-        output_visibility: &str,
-        batch_size: usize,
-        cal_target: &str,
-        scales_to_use: Option<Vec<u32>>,
-        num_inner_columns: usize,
-        div_rebasing: bool,
-        tolerance: &mut f32,
-        commitment: Commitments,
-        lookup_safety_margin: usize,
+        example_a: &str,
+        example_b: &str,
     ) {
-        let mut args = vec![
-            "gen-settings".to_string(),
-            "-M".to_string(),
-            format!("{}/{}/network.onnx", test_dir, example_name),
-            format!(
-                "--settings-path={}/{}/settings.json",
-                test_dir, example_name
-            ),
-            format!("--variables=batch_size->{}", batch_size),
-            format!("--input-visibility={}", input_visibility),
-            format!("--param-visibility={}", param_visibility),
-            format!("--output-visibility={}", output_visibility),
-            format!("--num-inner-cols={}", num_inner_columns),
-            format!("--tolerance={}", tolerance),
-            format!("--commitment={}", commitment),
-        ];
+        for example in [example_a, example_b] {
+            prove_and_verify(
+                test_dir,
+                example.to_string(),
+                "safe",
+                "private",
+                "private",
+                "public",
+                2,
+                None,
+                false,
+                "for-aggr",
+                Commitments::KZG,
+                2,
+            );
+        }
 
-        if div_rebasing {
-            args.push("--div-rebasing".to_string());
-        };
+        download_srs(23, Commitments::KZG);
+
+        let snarks_arg = format!(
+            "{}/{}/proof.pf,{}/{}/proof.pf",
+            test_dir, example_a, test_dir, example_b
+        );
+        let vk_arg = format!("{}/aggr.vk", test_dir);
+        let pk_arg = format!("{}/aggr.pk", test_dir);
+        let pf_arg = format!("{}/aggr.pf", test_dir);
+
+        let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+            .args([
+                "setup-aggregate",
+                "--sample-snarks",
+                &snarks_arg,
+                "--logrows=23",
+                "--vk-path",
+                &vk_arg,
+                "--pk-path",
+                &pk_arg,
+            ])
+            .status()
+            .expect("failed to execute process");
+        assert!(status.success());
+
+        let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+            .args([
+                "aggregate",
+                "--logrows=23",
+                "--aggregation-snarks",
+                &snarks_arg,
+                "--proof-path",
+                &pf_arg,
+                "--pk-path",
+                &pk_arg,
+            ])
+            .status()
+            .expect("failed to execute process");
+        assert!(status.success());
 
         let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
-            .args(args)
-            .stdout(std::process::Stdio::null())
+            .args([
+                "verify-aggr",
+                "--logrows=23",
+                "--proof-path",
+                &pf_arg,
+                "--vk-path",
+                &vk_arg,
+            ])
             .status()
             .expect("failed to execute process");
         assert!(status.success());
 
-        let mut calibrate_args = vec![
-            "calibrate-settings".to_string(),
-            "--data".to_string(),
-            format!("{}/{}/input.json", test_dir, example_name),
-            "-M".to_string(),
-            format!("{}/{}/network.onnx", test_dir, example_name),
-            format!(
-                "--settings-path={}/{}/settings.json",
-                test_dir, example_name
-            ),
-            format!("--target={}", cal_target),
-            format!("--lookup-safety-margin={}", lookup_safety_margin),
-        ];
-
-        if let Some(scales) = scales_to_use {
-            let scales = scales
-                .iter()
-                .map(|s| s.to_string())
-                .collect::<Vec<_>>()
-                .join(",");
-            calibrate_args.push("--scales".to_string());
-            calibrate_args.push(scales);
+        // the aggregate instances carry the children's vk hashes right after the accumulator limbs
+        let aggr = Snark::load::<KZGCommitmentScheme<Bn256>>(&PathBuf::from(pf_arg.clone()))
+            .expect("failed to read aggregate proof");
+        let mut num_child_instances = 0;
+        for (i, example) in [example_a, example_b].iter().enumerate() {
+            let settings =
+                GraphSettings::load(&format!("{}/{}/settings.json", test_dir, example).into())
+                    .expect("failed to load settings");
+            num_child_instances += settings.total_instances().iter().sum::<usize>();
+            let vk = ezkl::pfsys::load_vk::<KZGCommitmentScheme<Bn256>, GraphCircuit>(
+                format!("{}/{}/key.vk", test_dir, example).into(),
+                settings,
+            )
+            .expect("failed to load vk");
+            assert_eq!(aggr.instances[0][16 + i], vk.transcript_repr());
         }
+        assert_eq!(aggr.instances[0].len(), 16 + 2 + num_child_instances);
+
+        let sol_arg = format!("{}/kzg_aggr.sol", test_dir);
+        let addr_path_arg = format!("--addr-path={}/addr.txt", test_dir);
+        let rpc_arg = format!("--rpc-url={}", *ANVIL_URL);
+        let settings_arg = format!(
+            "{}/{}/settings.json,{}/{}/settings.json",
+            test_dir, example_a, test_dir, example_b
+        );
+        let private_key = format!("--private-key={}", *ANVIL_DEFAULT_PRIVATE_KEY);
 
         let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
-            .args(calibrate_args)
+            .args([
+                "create-evm-verifier-aggr",
+                "--vk-path",
+                &vk_arg,
+                "--aggregation-settings",
+                &settings_arg,
+                "--logrows=23",
+                "--sol-code-path",
+                &sol_arg,
+            ])
             .status()
             .expect("failed to execute process");
         assert!(status.success());
 
-        let mut settings =
-            GraphSettings::load(&format!("{}/{}/settings.json", test_dir, example_name).into())
-                .unwrap();
-
-        let any_output_scales_smol = settings.model_output_scales.iter().any(|s| *s <= 0);
-
-        if any_output_scales_smol {
-            // set the tolerance to 0.0
-            settings.run_args.tolerance = Tolerance {
-                val: 0.0,
-                scale: 0.0.into(),
-            };
-            settings
-                .save(&format!("{}/{}/settings.json", test_dir, example_name).into())
-                .unwrap();
-            *tolerance = 0.0;
-        }
-
         let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
             .args([
-                "compile-circuit",
-                "-M",
-                format!("{}/{}/network.onnx", test_dir, example_name).as_str(),
-                "--compiled-circuit",
-                format!("{}/{}/network.compiled", test_dir, example_name).as_str(),
-                &format!(
-                    "--settings-path={}/{}/settings.json",
-                    test_dir, example_name
-                ),
+                "deploy-evm",
+                rpc_arg.as_str(),
+                addr_path_arg.as_str(),
+                "--sol-code-path",
+                sol_arg.as_str(),
+                private_key.as_str(),
             ])
-            .stdout(std::process::Stdio::null())
             .status()
             .expect("failed to execute process");
         assert!(status.success());
 
+        let addr = std::fs::read_to_string(format!("{}/addr.txt", test_dir))
+            .expect("failed to read address file");
+        let deployed_addr_arg = format!("--addr-verifier={}", addr);
+
         let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
             .args([
-                "gen-witness",
-                "-D",
-                &format!("{}/{}/input.json", test_dir, example_name),
-                "-M",
-                &format!("{}/{}/network.compiled", test_dir, example_name),
-                "-O",
-                &format!("{}/{}/witness.json", test_dir, example_name),
+                "verify-evm",
+                "--proof-path",
+                pf_arg.as_str(),
+                deployed_addr_arg.as_str(),
+                rpc_arg.as_str(),
             ])
-            .stdout(std::process::Stdio::null())
             .status()
             .expect("failed to execute process");
         assert!(status.success());
     }
 
-    // Mock prove (fast, but does not cover some potential issues)
+    // prove-serialize-verify, the usual full path
     #[allow(clippy::too_many_arguments)]
-    fn accuracy_measurement(
+    fn prove_and_verify(
         test_dir: &str,
         example_name: String,
+        checkmode: &str,
         input_visibility: &str,
         param_visibility: &str,
         output_visibility: &str,
-        batch_size: usize,
-        cal_target: &str,
-        target_perc: f32,
-        div_rebasing: bool,
+        num_inner_columns: usize,
+        scales_to_use: Option<Vec<u32>>,
+        overflow: bool,
+        proof_type: &str,
+        commitment: Commitments,
+        lookup_safety_margin: usize,
     ) {
+        let target_str = if overflow {
+            "resources/col-overflow"
+        } else {
+            "resources"
+        };
+
         gen_circuit_settings_and_witness(
             test_dir,
             example_name.clone(),
             input_visibility,
             param_visibility,
             output_visibility,
-            batch_size,
-            cal_target,
-            None,
-            2,
-            div_rebasing,
+            1,
+            target_str,
+            scales_to_use,
+            num_inner_columns,
+            false,
             &mut 0.0,
-            Commitments::KZG,
-            2,
+            commitment,
+            lookup_safety_margin,
         );
 
-        println!(
-            " ------------ running accuracy measurement for {}",
-            example_name
-        );
-        // run python ./output_comparison.py in the test dir
-        let status = Command::new("python")
+        let settings_path = format!("{}/{}/settings.json", test_dir, example_name);
+
+        init_params(settings_path.clone().into());
+
+        let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
             .args([
-                "tests/output_comparison.py",
-                &format!("{}/{}/network.onnx", test_dir, example_name),
-                &format!("{}/{}/input.json", test_dir, example_name),
-                &format!("{}/{}/witness.json", test_dir, example_name),
-                &format!("{}/{}/settings.json", test_dir, example_name),
-                &format!("{}", target_perc),
+                "setup",
+                "-M",
+                &format!("{}/{}/network.compiled", test_dir, example_name),
+                "--pk-path",
+                &format!("{}/{}/key.pk", test_dir, example_name),
+                "--vk-path",
+                &format!("{}/{}/key.vk", test_dir, example_name),
+                "--disable-selector-compression",
             ])
             .status()
             .expect("failed to execute process");
-
         assert!(status.success());
-    }
 
-    // Mock prove (fast, but does not cover some potential issues)
-    fn render_circuit(test_dir: &str, example_name: String) {
         let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
             .args([
-                "render-circuit",
+                "prove",
+                "-W",
+                format!("{}/{}/witness.json", test_dir, example_name).as_str(),
                 "-M",
-                format!("{}/{}/network.onnx", test_dir, example_name).as_str(),
-                "-O",
-                format!("{}/{}/render.png", test_dir, example_name).as_str(),
-                "--lookup-range=-32768->32768",
-                "-K=17",
+                format!("{}/{}/network.compiled", test_dir, example_name).as_str(),
+                "--proof-path",
+                &format!("{}/{}/proof.pf", test_dir, example_name),
+                "--pk-path",
+                &format!("{}/{}/key.pk", test_dir, example_name),
+                &format!("--check-mode={}", checkmode),
+                &format!("--proof-type={}", proof_type),
+            ])
+            .status()
+            .expect("failed to execute process");
+        assert!(status.success());
+
+        let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+            .args([
+                "swap-proof-commitments",
+                "--proof-path",
+                &format!("{}/{}/proof.pf", test_dir, example_name),
+                "--witness-path",
+                format!("{}/{}/witness.json", test_dir, example_name).as_str(),
+            ])
+            .status()
+            .expect("failed to execute process");
+        assert!(status.success());
+
+        let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+            .args([
+                "verify",
+                format!("--settings-path={}", settings_path).as_str(),
+                "--proof-path",
+                &format!("{}/{}/proof.pf", test_dir, example_name),
+                "--vk-path",
+                &format!("{}/{}/key.vk", test_dir, example_name),
+            ])
+            .status()
+            .expect("failed to execute process");
+        assert!(status.success());
+
+        // load settings file
+        let settings =
+            std::fs::read_to_string(settings_path.clone()).expect("failed to read settings file");
+
+        let graph_settings = serde_json::from_str::<GraphSettings>(&settings)
+            .expect("failed to parse settings file");
+
+        // get_srs for the graph_settings_num_instances
+        download_srs(1, graph_settings.run_args.commitment.into());
+
+        let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+            .args([
+                "verify",
+                format!("--settings-path={}", settings_path).as_str(),
+                "--proof-path",
+                &format!("{}/{}/proof.pf", test_dir, example_name),
+                "--vk-path",
+                &format!("{}/{}/key.vk", test_dir, example_name),
+                "--reduced-srs",
             ])
             .status()
             .expect("failed to execute process");
         assert!(status.success());
     }
 
-    // prove-serialize-verify, the usual full path
-    fn kzg_aggr_mock_prove_and_verify(test_dir: &str, example_name: String) {
-        prove_and_verify(
+    // estimate the proof size, proving time and verifier gas without setup, then compare against a proof created in
+    // process and the gas of its verifier run in revm
+    fn estimate_accuracy(test_dir: &str, example_name: String) {
+        use ezkl::circuit::CheckMode;
+        use ezkl::pfsys::srs::load_srs_prover;
+        use ezkl::pfsys::{create_keys, create_proof_circuit, save_vk};
+        use halo2_proofs::poly::kzg::multiopen::{ProverSHPLONK, VerifierSHPLONK};
+        use halo2_proofs::poly::kzg::strategy::SingleStrategy;
+        use snark_verifier::system::halo2::transcript::evm::EvmTranscript;
+
+        gen_circuit_settings_and_witness(
             test_dir,
             example_name.clone(),
-            "safe",
             "private",
             "private",
             "public",
-            2,
+            1,
+            "resources",
             None,
+            1,
             false,
-            "for-aggr",
+            &mut 0.0,
             Commitments::KZG,
             2,
         );
+
+        let dir = format!("{}/{}", test_dir, example_name);
+        let settings_path = format!("{}/settings.json", dir);
+        let estimate_path = format!("{}/estimate.json", dir);
+
+        let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+            .args([
+                "estimate",
+                "-M",
+                &format!("{}/network.onnx", dir),
+                "-S",
+                &settings_path,
+                "-O",
+                &estimate_path,
+            ])
+            .status()
+            .expect("failed to execute process");
+        assert!(status.success());
+        let estimate: serde_json::Value = serde_json::from_str(
+            &std::fs::read_to_string(estimate_path).expect("failed to read estimate file"),
+        )
+        .expect("failed to parse estimate file");
+
+        init_params(settings_path.clone().into());
+
+        let mut circuit = GraphCircuit::load(format!("{}/network.compiled", dir).into()).unwrap();
+        let witness = GraphWitness::from_path(format!("{}/witness.json", dir).into()).unwrap();
+        circuit.load_graph_witness(&witness).unwrap();
+        let instances = circuit.prepare_public_inputs(&witness).unwrap();
+        let logrows = circuit.settings().run_args.logrows;
+        let params = load_srs_prover::<KZGCommitmentScheme<Bn256>>(ezkl::execute::get_srs_path(
+            logrows,
+            None,
+            Commitments::KZG,
+        ))
+        .unwrap();
+        let pk = create_keys::<KZGCommitmentScheme<Bn256>, GraphCircuit>(&circuit, &params, true)
+            .unwrap();
+
+        // only the proving itself is timed, which is what the estimate models
+        let proving_start = std::time::Instant::now();
+        let snark = create_proof_circuit::<
+            KZGCommitmentScheme<Bn256>,
+            _,
+            ProverSHPLONK<_>,
+            VerifierSHPLONK<_>,
+            SingleStrategy<_>,
+            _,
+            EvmTranscript<_, _, _, _>,
+            EvmTranscript<_, _, _, _>,
+        >(
+            circuit,
+            vec![instances],
+            &params,
+            &pk,
+            CheckMode::UNSAFE,
+            Commitments::KZG,
+            TranscriptType::EVM,
+            None,
+            None,
+        )
+        .unwrap();
+        let proving_time = proving_start.elapsed().as_secs_f64();
+
+        // the proof size is derived exactly from the shape of the circuit
+        assert_eq!(
+            estimate["proof_size_bytes"].as_u64().unwrap() as usize,
+            snark.proof.len()
+        );
+
+        // the proving time is calibrated on this machine, but the load of the test runner makes it noisy
+        let estimated_time = estimate["proving_time_secs"].as_f64().unwrap();
+        assert!(
+            estimated_time > proving_time / 3.0 && estimated_time < proving_time * 3.0,
+            "estimated proving time {}s is not within a factor of 3 of {}s",
+            estimated_time,
+            proving_time
+        );
+
+        let proof_path = format!("{}/proof.pf", dir);
+        let vk_path = format!("{}/key.vk", dir);
+        snark.save(&proof_path.clone().into()).unwrap();
+        save_vk::<halo2curves::bn256::G1Affine>(&vk_path.clone().into(), pk.get_vk()).unwrap();
+
+        let sol_code_path = format!("{}/kzg.sol", dir);
         let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
             .args([
-                "mock-aggregate",
-                "--logrows=23",
-                "--aggregation-snarks",
-                &format!("{}/{}/proof.pf", test_dir, example_name),
+                "create-evm-verifier",
+                "--vk-path",
+                &vk_path,
+                "--settings-path",
+                &settings_path,
+                "--sol-code-path",
+                &sol_code_path,
+            ])
+            .status()
+            .expect("failed to execute process");
+        assert!(status.success());
+
+        let gas_path = format!("{}/gas.json", dir);
+        let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+            .args([
+                "estimate-gas",
+                "--proof-path",
+                &proof_path,
+                "--sol-code-path",
+                &sol_code_path,
+                "--backend=revm",
+                "-O",
+                &gas_path,
             ])
             .status()
             .expect("failed to execute process");
         assert!(status.success());
+        let gas: serde_json::Value = serde_json::from_str(
+            &std::fs::read_to_string(gas_path).expect("failed to read gas report"),
+        )
+        .expect("failed to parse gas report");
+
+        let estimated_gas = estimate["verifier_gas"].as_u64().unwrap() as f64;
+        let gas_used = gas["gas_used"].as_u64().unwrap() as f64;
+        assert!(
+            (estimated_gas - gas_used).abs() / gas_used < 0.2,
+            "estimated verifier gas {} is not within 20% of {}",
+            estimated_gas,
+            gas_used
+        );
     }
 
     // prove-serialize-verify, the usual full path
-    fn aggr_prove_and_verify(
+    fn kzg_evm_prove_and_verify(
+        num_inner_columns: usize,
         test_dir: &str,
         example_name: String,
         input_visibility: &str,
         param_visibility: &str,
         output_visibility: &str,
-        commitment: Commitments,
     ) {
+        let anvil_url = ANVIL_URL.as_str();
+
         prove_and_verify(
             test_dir,
             example_name.clone(),
@@ -1827,315 +8140,899 @@ mod native_tests {
             input_visibility,
             param_visibility,
             output_visibility,
-            2,
+            num_inner_columns,
             None,
             false,
-            "for-aggr",
+            "single",
             Commitments::KZG,
             2,
         );
 
-        download_srs(23, commitment);
-        // now setup-aggregate
+        let settings_path = format!("{}/{}/settings.json", test_dir, example_name);
+        init_params(settings_path.clone().into());
+
+        let vk_arg = format!("{}/{}/key.vk", test_dir, example_name);
+        let rpc_arg = format!("--rpc-url={}", anvil_url);
+        let addr_path_arg = format!("--addr-path={}/{}/addr.txt", test_dir, example_name);
+        let settings_arg = format!("--settings-path={}", settings_path);
+
+        // create encoded calldata
         let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
             .args([
-                "setup-aggregate",
-                "--sample-snarks",
+                "encode-evm-calldata",
+                "--proof-path",
                 &format!("{}/{}/proof.pf", test_dir, example_name),
-                "--logrows=23",
-                "--vk-path",
-                &format!("{}/{}/aggr.vk", test_dir, example_name),
-                "--pk-path",
-                &format!("{}/{}/aggr.pk", test_dir, example_name),
-                &format!("--commitment={}", commitment),
             ])
             .status()
             .expect("failed to execute process");
+
+        assert!(status.success());
+
+        // create the verifier
+        let mut args = vec!["create-evm-verifier", "--vk-path", &vk_arg, &settings_arg];
+
+        let sol_arg = format!("{}/{}/kzg.sol", test_dir, example_name);
+
+        // create everything to test the pipeline
+        args.push("--sol-code-path");
+        args.push(sol_arg.as_str());
+
+        let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+            .args(&args)
+            .status()
+            .expect("failed to execute process");
         assert!(status.success());
 
+        // the map of the instances is written next to the verifier
+        let instance_map: Vec<ezkl::graph::instances::InstanceLabel> = serde_json::from_reader(
+            std::fs::File::open(ezkl::execute::instance_map_path(sol_arg.as_ref())).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            instance_map,
+            GraphSettings::load(&settings_path.clone().into())
+                .unwrap()
+                .instance_map()
+        );
+
+        // deploy the verifier
+        let mut args = vec!["deploy-evm", rpc_arg.as_str(), addr_path_arg.as_str()];
+
+        args.push("--sol-code-path");
+        args.push(sol_arg.as_str());
+
         let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
-            .args([
-                "aggregate",
-                "--logrows=23",
-                "--aggregation-snarks",
-                &format!("{}/{}/proof.pf", test_dir, example_name),
-                "--proof-path",
-                &format!("{}/{}/aggr.pf", test_dir, example_name),
-                "--pk-path",
-                &format!("{}/{}/aggr.pk", test_dir, example_name),
-                &format!("--commitment={}", commitment),
-            ])
+            .args(&args)
             .status()
             .expect("failed to execute process");
         assert!(status.success());
+
+        // read in the address
+        let addr = std::fs::read_to_string(format!("{}/{}/addr.txt", test_dir, example_name))
+            .expect("failed to read address file");
+
+        let deployed_addr_arg = format!("--addr-verifier={}", addr);
+
+        // now verify the proof
+        let pf_arg = format!("{}/{}/proof.pf", test_dir, example_name);
+        let mut args = vec![
+            "verify-evm",
+            "--proof-path",
+            pf_arg.as_str(),
+            rpc_arg.as_str(),
+            deployed_addr_arg.as_str(),
+        ];
+
         let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
-            .args([
-                "verify-aggr",
-                "--logrows=23",
-                "--proof-path",
-                &format!("{}/{}/aggr.pf", test_dir, example_name),
-                "--vk-path",
-                &format!("{}/{}/aggr.vk", test_dir, example_name),
-            ])
+            .args(&args)
             .status()
             .expect("failed to execute process");
         assert!(status.success());
+        // As sanity check, add example that should fail.
+        args[2] = PF_FAILURE;
+        let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+            .args(args)
+            .status()
+            .expect("failed to execute process");
+        assert!(!status.success());
     }
 
     // prove-serialize-verify, the usual full path
-    fn kzg_evm_aggr_prove_and_verify(
+    fn kzg_evm_prove_and_verify_reusable_verifier(
+        num_inner_columns: usize,
         test_dir: &str,
         example_name: String,
         input_visibility: &str,
         param_visibility: &str,
         output_visibility: &str,
-    ) {
-        aggr_prove_and_verify(
+        reusable_verifier_address: &mut Option<String>,
+        overflow: bool,
+    ) -> String {
+        let anvil_url = ANVIL_URL.as_str();
+
+        prove_and_verify(
             test_dir,
             example_name.clone(),
+            "safe",
             input_visibility,
             param_visibility,
             output_visibility,
+            num_inner_columns,
+            None,
+            overflow,
+            "single",
             Commitments::KZG,
+            2,
         );
 
-        download_srs(23, Commitments::KZG);
+        let settings_path = format!("{}/{}/settings.json", test_dir, example_name);
+        init_params(settings_path.clone().into());
 
-        let vk_arg = &format!("{}/{}/aggr.vk", test_dir, example_name);
+        let vk_arg = format!("{}/{}/key.vk", test_dir, example_name);
+        let rpc_arg = format!("--rpc-url={}", anvil_url);
+        let addr_path_arg = format!("--addr-path={}/{}/addr.txt", test_dir, example_name);
+        let settings_arg = format!("--settings-path={}", settings_path);
+        let sol_arg = format!("--sol-code-path={}/{}/kzg.sol", test_dir, example_name);
 
-        fn build_args<'a>(base_args: Vec<&'a str>, sol_arg: &'a str) -> Vec<&'a str> {
-            let mut args = base_args;
+        // if the reusable verifier address is not set, create the verifier
+        let deployed_addr_arg = match reusable_verifier_address {
+            Some(addr) => addr.clone(),
+            None => {
+                // create the reusable verifier
+                let args = vec![
+                    "create-evm-verifier",
+                    "--vk-path",
+                    &vk_arg,
+                    &settings_arg,
+                    &sol_arg,
+                    "--reusable",
+                ];
 
-            args.push("--sol-code-path");
-            args.push(sol_arg);
-            args
-        }
+                let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                    .args(&args)
+                    .status()
+                    .expect("failed to execute process");
+                assert!(status.success());
 
-        let sol_arg = format!("{}/{}/kzg_aggr.sol", test_dir, example_name);
-        let addr_path_arg = format!("--addr-path={}/{}/addr.txt", test_dir, example_name);
-        let rpc_arg = format!("--rpc-url={}", *ANVIL_URL);
-        let settings_arg = format!("{}/{}/settings.json", test_dir, example_name);
-        let private_key = format!("--private-key={}", *ANVIL_DEFAULT_PRIVATE_KEY);
+                // deploy the verifier
+                let args = vec![
+                    "deploy-evm",
+                    rpc_arg.as_str(),
+                    addr_path_arg.as_str(),
+                    sol_arg.as_str(),
+                    "-C=verifier/reusable",
+                ];
 
-        // create encoded calldata
-        let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
-            .args([
-                "encode-evm-calldata",
-                "--proof-path",
-                &format!("{}/{}/aggr.pf", test_dir, example_name),
-            ])
-            .status()
-            .expect("failed to execute process");
+                let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                    .args(&args)
+                    .status()
+                    .expect("failed to execute process");
+                assert!(status.success());
 
-        assert!(status.success());
+                // read in the address
+                let addr =
+                    std::fs::read_to_string(format!("{}/{}/addr.txt", test_dir, example_name))
+                        .expect("failed to read address file");
 
-        let base_args = vec![
-            "create-evm-verifier-aggr",
+                let deployed_addr_arg = format!("--addr-verifier={}", addr);
+                // set the reusable verifier address
+                *reusable_verifier_address = Some(addr);
+                deployed_addr_arg
+            }
+        };
+
+        let addr_path_arg_vk = format!("--addr-path={}/{}/addr_vk.txt", test_dir, example_name);
+        let sol_arg_vk: String = format!("--sol-code-path={}/{}/vk.sol", test_dir, example_name);
+        // create the verifier
+        let args = vec![
+            "create-evm-vka",
             "--vk-path",
-            vk_arg.as_str(),
-            "--aggregation-settings",
-            settings_arg.as_str(),
-            "--logrows=23",
+            &vk_arg,
+            &settings_arg,
+            &sol_arg_vk,
         ];
 
-        let args = build_args(base_args, &sol_arg);
-
         let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
-            .args(args)
+            .args(&args)
             .status()
             .expect("failed to execute process");
         assert!(status.success());
 
-        // deploy the verifier
+        // deploy the vka
         let args = vec![
             "deploy-evm",
             rpc_arg.as_str(),
-            addr_path_arg.as_str(),
-            "--sol-code-path",
-            sol_arg.as_str(),
-            private_key.as_str(),
+            addr_path_arg_vk.as_str(),
+            sol_arg_vk.as_str(),
+            "-C=vka",
         ];
 
         let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
             .args(&args)
             .status()
             .expect("failed to execute process");
-        assert!(status.success());
-
-        // read in the address
-        let addr = std::fs::read_to_string(format!("{}/{}/addr.txt", test_dir, example_name))
-            .expect("failed to read address file");
-
-        let deployed_addr_arg = format!("--addr-verifier={}", addr);
+        assert!(status.success());
+
+        // read in the address
+        let addr_vk = std::fs::read_to_string(format!("{}/{}/addr_vk.txt", test_dir, example_name))
+            .expect("failed to read address file");
+
+        let deployed_addr_arg_vk = format!("--addr-vk={}", addr_vk);
+
+        // create encoded calldata
+        let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+            .args([
+                "encode-evm-calldata",
+                "--proof-path",
+                &format!("{}/{}/proof.pf", test_dir, example_name),
+                &deployed_addr_arg_vk,
+            ])
+            .status()
+            .expect("failed to execute process");
 
-        let pf_arg = format!("{}/{}/aggr.pf", test_dir, example_name);
+        assert!(status.success());
 
-        let mut base_args = vec![
+        // now verify the proof
+        let pf_arg = format!("{}/{}/proof.pf", test_dir, example_name);
+        let args = vec![
             "verify-evm",
             "--proof-path",
             pf_arg.as_str(),
-            deployed_addr_arg.as_str(),
             rpc_arg.as_str(),
+            deployed_addr_arg.as_str(),
+            deployed_addr_arg_vk.as_str(),
         ];
 
         let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
-            .args(&base_args)
+            .args(&args)
             .status()
             .expect("failed to execute process");
         assert!(status.success());
-        // As sanity check, add example that should fail.
-        base_args[2] = PF_FAILURE_AGGR;
-        let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
-            .args(base_args)
-            .status()
-            .expect("failed to execute process");
-        assert!(!status.success());
-    }
+        // Read the original proof file
+        let original_proof_data: ezkl::pfsys::Snark<
+            halo2curves::bn256::Fr,
+            halo2curves::bn256::G1Affine,
+        > = Snark::load::<KZGCommitmentScheme<Bn256>>(&PathBuf::from(format!(
+            "{}/{}/proof.pf",
+            test_dir, example_name
+        )))
+        .expect("Failed to read proof file");
 
-    // prove-serialize-verify, the usual full path
-    #[allow(clippy::too_many_arguments)]
-    fn prove_and_verify(
-        test_dir: &str,
-        example_name: String,
-        checkmode: &str,
-        input_visibility: &str,
-        param_visibility: &str,
-        output_visibility: &str,
-        num_inner_columns: usize,
-        scales_to_use: Option<Vec<u32>>,
-        overflow: bool,
-        proof_type: &str,
-        commitment: Commitments,
-        lookup_safety_margin: usize,
-    ) {
-        let target_str = if overflow {
-            "resources/col-overflow"
-        } else {
-            "resources"
-        };
+        for i in 0..1 {
+            // Create a copy of the original proof data
+            let mut modified_proof_data = original_proof_data.clone();
 
-        gen_circuit_settings_and_witness(
+            // Flip a random bit
+            let random_byte = rand::thread_rng().gen_range(0..modified_proof_data.proof.len());
+            let random_bit = rand::thread_rng().gen_range(0..8);
+            modified_proof_data.proof[random_byte] ^= 1 << random_bit;
+
+            // Write the modified proof to a new file
+            let modified_pf_arg = format!("{}/{}/modified_proof_{}.pf", test_dir, example_name, i);
+            modified_proof_data
+                .save(&PathBuf::from(modified_pf_arg.clone()))
+                .expect("Failed to save modified proof file");
+
+            // Verify the modified proof (should fail)
+            let mut args_mod = args.clone();
+            args_mod[2] = &modified_pf_arg;
+            let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args(&args_mod)
+                .status()
+                .expect("failed to execute process");
+
+            if status.success() {
+                log::error!("Verification unexpectedly succeeded for modified proof {}. Flipped bit {} in byte {}", i, random_bit, random_byte);
+            }
+
+            assert!(
+                !status.success(),
+                "Modified proof {} should have failed verification",
+                i
+            );
+        }
+
+        // Returned deploy_addr_arg for reusable verifier
+        deployed_addr_arg
+    }
+
+    fn kzg_evm_verify_batch(test_dir: &str, example_name: &str, batch_size: usize) {
+        prove_and_verify(
             test_dir,
-            example_name.clone(),
-            input_visibility,
-            param_visibility,
-            output_visibility,
+            example_name.to_string(),
+            "safe",
+            "private",
+            "fixed",
+            "public",
             1,
-            target_str,
-            scales_to_use,
-            num_inner_columns,
+            None,
             false,
-            &mut 0.0,
-            commitment,
-            lookup_safety_margin,
+            "single",
+            Commitments::KZG,
+            2,
         );
 
         let settings_path = format!("{}/{}/settings.json", test_dir, example_name);
-
         init_params(settings_path.clone().into());
 
+        // prove the same circuit on perturbed inputs
+        let input: serde_json::Value = serde_json::from_str(
+            &std::fs::read_to_string(format!("{}/{}/input.json", test_dir, example_name)).unwrap(),
+        )
+        .unwrap();
+        let mut proof_paths = vec![format!("{}/{}/proof.pf", test_dir, example_name)];
+        for i in 1..batch_size {
+            let mut input = input.clone();
+            input.as_object_mut().unwrap().remove("output_data");
+            for x in input["input_data"][0].as_array_mut().unwrap() {
+                *x = serde_json::json!(x.as_f64().unwrap() / (i + 1) as f64);
+            }
+            let input_path = format!("{}/{}/input_{}.json", test_dir, example_name, i);
+            let witness_path = format!("{}/{}/witness_{}.json", test_dir, example_name, i);
+            let proof_path = format!("{}/{}/proof_{}.pf", test_dir, example_name, i);
+            std::fs::write(&input_path, input.to_string()).unwrap();
+
+            let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args([
+                    "gen-witness",
+                    "-D",
+                    &input_path,
+                    "-M",
+                    &format!("{}/{}/network.compiled", test_dir, example_name),
+                    "-O",
+                    &witness_path,
+                ])
+                .status()
+                .expect("failed to execute process");
+            assert!(status.success());
+
+            let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args([
+                    "prove",
+                    "-W",
+                    &witness_path,
+                    "-M",
+                    &format!("{}/{}/network.compiled", test_dir, example_name),
+                    "--proof-path",
+                    &proof_path,
+                    "--pk-path",
+                    &format!("{}/{}/key.pk", test_dir, example_name),
+                ])
+                .status()
+                .expect("failed to execute process");
+            assert!(status.success());
+            proof_paths.push(proof_path);
+        }
+
+        let sol_arg = format!("{}/{}/kzg.sol", test_dir, example_name);
         let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
             .args([
-                "setup",
-                "-M",
-                &format!("{}/{}/network.compiled", test_dir, example_name),
-                "--pk-path",
-                &format!("{}/{}/key.pk", test_dir, example_name),
+                "create-evm-verifier",
                 "--vk-path",
                 &format!("{}/{}/key.vk", test_dir, example_name),
-                "--disable-selector-compression",
+                "--settings-path",
+                &settings_path,
+                "--sol-code-path",
+                &sol_arg,
+                "--batch",
             ])
             .status()
             .expect("failed to execute process");
         assert!(status.success());
 
+        let addr_path = format!("{}/{}/addr.txt", test_dir, example_name);
         let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
             .args([
-                "prove",
-                "-W",
-                format!("{}/{}/witness.json", test_dir, example_name).as_str(),
-                "-M",
-                format!("{}/{}/network.compiled", test_dir, example_name).as_str(),
-                "--proof-path",
-                &format!("{}/{}/proof.pf", test_dir, example_name),
-                "--pk-path",
-                &format!("{}/{}/key.pk", test_dir, example_name),
-                &format!("--check-mode={}", checkmode),
-                &format!("--proof-type={}", proof_type),
+                "deploy-evm",
+                &format!("--rpc-url={}", ANVIL_URL.as_str()),
+                &format!("--addr-path={}", addr_path),
+                "--sol-code-path",
+                &sol_arg,
             ])
             .status()
             .expect("failed to execute process");
         assert!(status.success());
+        let addr = std::fs::read_to_string(&addr_path).expect("failed to read address file");
 
-        let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
-            .args([
-                "swap-proof-commitments",
-                "--proof-path",
-                &format!("{}/{}/proof.pf", test_dir, example_name),
-                "--witness-path",
-                format!("{}/{}/witness.json", test_dir, example_name).as_str(),
-            ])
-            .status()
-            .expect("failed to execute process");
-        assert!(status.success());
+        let verify_batch = |proof_paths: &[String]| {
+            Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args([
+                    "verify-evm-batch",
+                    &format!("--rpc-url={}", ANVIL_URL.as_str()),
+                    &format!("--addr-verifier={}", addr),
+                    "--proof-paths",
+                    &proof_paths.join(","),
+                ])
+                .status()
+                .expect("failed to execute process")
+        };
+
+        // all proofs verified in one call
+        assert!(verify_batch(&proof_paths).success());
+
+        // a batch fails as soon as one of its proofs is invalid
+        let mut tampered: Snark<halo2curves::bn256::Fr, halo2curves::bn256::G1Affine> =
+            Snark::load::<KZGCommitmentScheme<Bn256>>(&PathBuf::from(&proof_paths[1])).unwrap();
+        tampered.instances[0][0] += halo2curves::bn256::Fr::from(1);
+        let tampered_path = format!("{}/{}/proof_tampered.pf", test_dir, example_name);
+        tampered.save(&PathBuf::from(&tampered_path)).unwrap();
+        let mut bad_batch = proof_paths.clone();
+        bad_batch[1] = tampered_path;
+        assert!(!verify_batch(&bad_batch).success());
+
+        // gas grows linearly so a large enough batch no longer fits in a block,
+        // 1l_mlp costs a few hundred thousand gas per proof so 256 proofs is well past 30M
+        let oversized = proof_paths
+            .iter()
+            .cycle()
+            .take(256)
+            .cloned()
+            .collect::<Vec<_>>();
+        assert!(!verify_batch(&oversized).success());
+    }
+
+    fn kzg_evm_estimate_gas(test_dir: &str, example_name: &str) {
+        prove_and_verify(
+            test_dir,
+            example_name.to_string(),
+            "safe",
+            "private",
+            "fixed",
+            "public",
+            1,
+            None,
+            false,
+            "single",
+            Commitments::KZG,
+            2,
+        );
+
+        let settings_path = format!("{}/{}/settings.json", test_dir, example_name);
+        init_params(settings_path.clone().into());
 
+        let sol_arg = format!("{}/{}/kzg.sol", test_dir, example_name);
         let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
             .args([
-                "verify",
-                format!("--settings-path={}", settings_path).as_str(),
-                "--proof-path",
-                &format!("{}/{}/proof.pf", test_dir, example_name),
+                "create-evm-verifier",
                 "--vk-path",
                 &format!("{}/{}/key.vk", test_dir, example_name),
+                "--settings-path",
+                &settings_path,
+                "--sol-code-path",
+                &sol_arg,
             ])
             .status()
             .expect("failed to execute process");
         assert!(status.success());
 
-        // load settings file
-        let settings =
-            std::fs::read_to_string(settings_path.clone()).expect("failed to read settings file");
+        let estimate = |backend: &str| -> serde_json::Value {
+            let output = format!("{}/{}/gas_{}.json", test_dir, example_name, backend);
+            let mut args = vec![
+                "estimate-gas".to_string(),
+                "--proof-path".to_string(),
+                format!("{}/{}/proof.pf", test_dir, example_name),
+                "--sol-code-path".to_string(),
+                sol_arg.clone(),
+                "--backend".to_string(),
+                backend.to_string(),
+                "-O".to_string(),
+                output.clone(),
+            ];
+            if backend == "anvil" {
+                args.push(format!("--rpc-url={}", ANVIL_URL.as_str()));
+            }
+            let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args(&args)
+                .status()
+                .expect("failed to execute process");
+            assert!(status.success());
+            serde_json::from_str(&std::fs::read_to_string(output).unwrap()).unwrap()
+        };
 
-        let graph_settings = serde_json::from_str::<GraphSettings>(&settings)
-            .expect("failed to parse settings file");
+        let revm = estimate("revm");
+        let anvil = estimate("anvil");
+
+        let revm_gas = revm["gas_used"].as_u64().unwrap();
+        let anvil_gas = anvil["gas_used"].as_u64().unwrap();
+        // a small model should verify well within a block
+        assert!(revm_gas > 21_000 && revm_gas < 15_000_000, "{}", revm_gas);
+        assert!(revm["fits_block_target"].as_bool().unwrap());
+        // node estimates pad for the 63/64 rule, the two backends should still agree within 10%
+        let diff = (revm_gas as f64 - anvil_gas as f64).abs() / anvil_gas as f64;
+        assert!(diff < 0.1, "revm: {} anvil: {}", revm_gas, anvil_gas);
+
+        // both backends encode the same calldata
+        assert_eq!(revm["calldata_bytes"], anvil["calldata_bytes"]);
+        let calldata_bytes = revm["calldata_bytes"].as_u64().unwrap();
+        let proof_bytes = revm["proof_bytes"].as_u64().unwrap();
+        let num_instances = revm["num_instances"].as_u64().unwrap();
+        assert!(num_instances > 0);
+        assert!(calldata_bytes >= proof_bytes + 32 * num_instances);
+    }
 
-        // get_srs for the graph_settings_num_instances
-        download_srs(1, graph_settings.run_args.commitment.into());
+    // the public input and output are packed into digests, re-derived on-chain from the values in calldata
+    fn kzg_evm_packed_instances(test_dir: &str, example_name: &str) {
+        use halo2curves::ff::Field;
 
-        let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
-            .args([
-                "verify",
-                format!("--settings-path={}", settings_path).as_str(),
+        let dir = format!("{}/{}", test_dir, example_name);
+        let settings_path = format!("{}/settings.json", dir);
+        let ezkl = |args: &[&str]| {
+            Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args(args)
+                .stdout(std::process::Stdio::null())
+                .status()
+                .expect("failed to execute process")
+        };
+        let estimate = |name: &str, extra: &[&str]| -> Option<serde_json::Value> {
+            let output = format!("{}/gas_{}.json", dir, name);
+            let mut args = vec![
+                "estimate-gas".to_string(),
+                "--proof-path".to_string(),
+                format!("{}/proof.pf", dir),
+                "--sol-code-path".to_string(),
+                format!("{}/kzg.sol", dir),
+                "--backend=anvil".to_string(),
+                format!("--rpc-url={}", ANVIL_URL.as_str()),
+                "-O".to_string(),
+                output.clone(),
+            ];
+            args.extend(extra.iter().map(|a| a.to_string()));
+            let args = args.iter().map(|a| a.as_str()).collect::<Vec<_>>();
+            ezkl(&args)
+                .success()
+                .then(|| serde_json::from_str(&std::fs::read_to_string(output).unwrap()).unwrap())
+        };
+        let create_verifier = || {
+            assert!(ezkl(&[
+                "create-evm-verifier",
+                "--vk-path",
+                &format!("{}/key.vk", dir),
+                "--settings-path",
+                &settings_path,
+                "--sol-code-path",
+                &format!("{}/kzg.sol", dir),
+            ])
+            .success());
+        };
+
+        // the baseline passes every public value as an instance
+        prove_and_verify(
+            test_dir,
+            example_name.to_string(),
+            "safe",
+            "public",
+            "fixed",
+            "public",
+            1,
+            None,
+            false,
+            "single",
+            Commitments::KZG,
+            2,
+        );
+        let baseline_settings = GraphSettings::load(&settings_path.clone().into()).unwrap();
+        assert!(baseline_settings.packed_tensors.is_empty());
+        init_params(settings_path.clone().into());
+        create_verifier();
+        let baseline = estimate("baseline", &[]).unwrap();
+
+        // the same model, with the tensors of more than 2 values packed
+        assert!(ezkl(&[
+            "gen-settings",
+            "-M",
+            &format!("{}/network.onnx", dir),
+            &format!("--settings-path={}", settings_path),
+            "--input-visibility=public",
+            "--param-visibility=fixed",
+            "--output-visibility=public",
+            "--pack-public-threshold=2",
+            "--poseidon-params=circomlib",
+        ])
+        .success());
+        assert!(ezkl(&[
+            "calibrate-settings",
+            "--data",
+            &format!("{}/input.json", dir),
+            "-M",
+            &format!("{}/network.onnx", dir),
+            &format!("--settings-path={}", settings_path),
+            "--target=resources",
+        ])
+        .success());
+        assert!(ezkl(&[
+            "compile-circuit",
+            "-M",
+            &format!("{}/network.onnx", dir),
+            "--compiled-circuit",
+            &format!("{}/network.compiled", dir),
+            &format!("--settings-path={}", settings_path),
+        ])
+        .success());
+        assert!(ezkl(&[
+            "gen-witness",
+            "-D",
+            &format!("{}/input.json", dir),
+            "-M",
+            &format!("{}/network.compiled", dir),
+            "-O",
+            &format!("{}/witness.json", dir),
+        ])
+        .success());
+        init_params(settings_path.clone().into());
+        assert!(ezkl(&[
+            "setup",
+            "-M",
+            &format!("{}/network.compiled", dir),
+            "--pk-path",
+            &format!("{}/key.pk", dir),
+            "--vk-path",
+            &format!("{}/key.vk", dir),
+            "--disable-selector-compression",
+        ])
+        .success());
+        assert!(ezkl(&[
+            "prove",
+            "-W",
+            &format!("{}/witness.json", dir),
+            "-M",
+            &format!("{}/network.compiled", dir),
+            "--proof-path",
+            &format!("{}/proof.pf", dir),
+            "--pk-path",
+            &format!("{}/key.pk", dir),
+        ])
+        .success());
+        assert!(ezkl(&[
+            "verify",
+            &format!("--settings-path={}", settings_path),
+            "--proof-path",
+            &format!("{}/proof.pf", dir),
+            "--vk-path",
+            &format!("{}/key.vk", dir),
+        ])
+        .success());
+
+        let settings = GraphSettings::load(&settings_path.clone().into()).unwrap();
+        assert_eq!(settings.packed_tensors.len(), 2);
+        let baseline_instances = baseline_settings.total_instances().iter().sum::<usize>();
+        let packed_instances = settings.total_instances().iter().sum::<usize>();
+        assert_eq!(packed_instances, baseline_instances - 3 - 4 + 2);
+
+        create_verifier();
+        let packed_sol = format!("{}/packed.sol", dir);
+        assert!(ezkl(&[
+            "create-evm-packed",
+            "--settings-path",
+            &settings_path,
+            "--sol-code-path",
+            &packed_sol,
+            "--abi-path",
+            &format!("{}/packed_abi.json", dir),
+        ])
+        .success());
+
+        let witness_path = format!("{}/witness.json", dir);
+        let packed_args = |witness_path: &str| {
+            vec![
+                format!("--packed-sol-code-path={}", packed_sol),
+                format!("--witness-path={}", witness_path),
+                format!("--settings-path={}", settings_path),
+            ]
+        };
+        let args = packed_args(&witness_path);
+        let args = args.iter().map(|a| a.as_str()).collect::<Vec<_>>();
+        let packed = estimate("packed", &args).unwrap();
+        // the digests are hashed on-chain, whether that is cheaper than the instances depends on the model
+        println!(
+            "baseline: {} gas for {} calldata bytes, packed: {} gas for {} calldata bytes",
+            baseline["gas_used"],
+            baseline["calldata_bytes"],
+            packed["gas_used"],
+            packed["calldata_bytes"]
+        );
+        assert!(packed["gas_used"].as_u64().unwrap() > 21_000);
+
+        // values that don't hash to the digests of the proof are rejected
+        let mut witness = GraphWitness::from_path(witness_path.clone().into()).unwrap();
+        let value = witness.inputs[0][0];
+        witness.inputs[0][0] = value + halo2curves::bn256::Fr::ONE;
+        let tampered_path = format!("{}/witness_tampered.json", dir);
+        witness.save(tampered_path.clone().into()).unwrap();
+        let args = packed_args(&tampered_path);
+        let args = args.iter().map(|a| a.as_str()).collect::<Vec<_>>();
+        assert!(estimate("tampered", &args).is_none());
+    }
+
+    // the public output is packed two values to an instance, which the verifier is called with as they are
+    fn kzg_evm_limb_packed_instances(test_dir: &str, example_name: &str) {
+        use ezkl::graph::limbs::unpack_limbs;
+
+        let dir = format!("{}/{}", test_dir, example_name);
+        let settings_path = format!("{}/settings.json", dir);
+        let ezkl = |args: &[&str]| {
+            Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args(args)
+                .stdout(std::process::Stdio::null())
+                .status()
+                .expect("failed to execute process")
+        };
+        let estimate = |name: &str| -> serde_json::Value {
+            let output = format!("{}/gas_{}.json", dir, name);
+            assert!(ezkl(&[
+                "estimate-gas",
                 "--proof-path",
-                &format!("{}/{}/proof.pf", test_dir, example_name),
+                &format!("{}/proof.pf", dir),
+                "--sol-code-path",
+                &format!("{}/kzg.sol", dir),
+                "--backend=anvil",
+                &format!("--rpc-url={}", ANVIL_URL.as_str()),
+                "-O",
+                &output,
+            ])
+            .success());
+            serde_json::from_str(&std::fs::read_to_string(output).unwrap()).unwrap()
+        };
+        let create_verifier = || {
+            assert!(ezkl(&[
+                "create-evm-verifier",
                 "--vk-path",
-                &format!("{}/{}/key.vk", test_dir, example_name),
-                "--reduced-srs",
+                &format!("{}/key.vk", dir),
+                "--settings-path",
+                &settings_path,
+                "--sol-code-path",
+                &format!("{}/kzg.sol", dir),
             ])
-            .status()
-            .expect("failed to execute process");
-        assert!(status.success());
-    }
+            .success());
+        };
 
-    // prove-serialize-verify, the usual full path
-    fn kzg_evm_prove_and_verify(
-        num_inner_columns: usize,
-        test_dir: &str,
-        example_name: String,
-        input_visibility: &str,
-        param_visibility: &str,
-        output_visibility: &str,
-    ) {
-        let anvil_url = ANVIL_URL.as_str();
+        // the baseline passes every value of the output as an instance
+        prove_and_verify(
+            test_dir,
+            example_name.to_string(),
+            "safe",
+            "private",
+            "fixed",
+            "public",
+            1,
+            None,
+            false,
+            "single",
+            Commitments::KZG,
+            2,
+        );
+        let baseline_settings = GraphSettings::load(&settings_path.clone().into()).unwrap();
+        init_params(settings_path.clone().into());
+        create_verifier();
+        let baseline = estimate("baseline");
+
+        assert!(ezkl(&[
+            "gen-settings",
+            "-M",
+            &format!("{}/network.onnx", dir),
+            &format!("--settings-path={}", settings_path),
+            "--param-visibility=fixed",
+            "--instance-limbs=2",
+        ])
+        .success());
+        assert!(ezkl(&[
+            "calibrate-settings",
+            "--data",
+            &format!("{}/input.json", dir),
+            "-M",
+            &format!("{}/network.onnx", dir),
+            &format!("--settings-path={}", settings_path),
+            "--target=resources",
+        ])
+        .success());
+        assert!(ezkl(&[
+            "compile-circuit",
+            "-M",
+            &format!("{}/network.onnx", dir),
+            "--compiled-circuit",
+            &format!("{}/network.compiled", dir),
+            &format!("--settings-path={}", settings_path),
+        ])
+        .success());
+        let witness_path = format!("{}/witness.json", dir);
+        assert!(ezkl(&[
+            "gen-witness",
+            "-D",
+            &format!("{}/input.json", dir),
+            "-M",
+            &format!("{}/network.compiled", dir),
+            "-O",
+            &witness_path,
+        ])
+        .success());
+        init_params(settings_path.clone().into());
+        assert!(ezkl(&[
+            "setup",
+            "-M",
+            &format!("{}/network.compiled", dir),
+            "--pk-path",
+            &format!("{}/key.pk", dir),
+            "--vk-path",
+            &format!("{}/key.vk", dir),
+            "--disable-selector-compression",
+        ])
+        .success());
+        let proof_path = format!("{}/proof.pf", dir);
+        assert!(ezkl(&[
+            "prove",
+            "-W",
+            &witness_path,
+            "-M",
+            &format!("{}/network.compiled", dir),
+            "--proof-path",
+            &proof_path,
+            "--pk-path",
+            &format!("{}/key.pk", dir),
+        ])
+        .success());
+        assert!(ezkl(&[
+            "verify",
+            &format!("--settings-path={}", settings_path),
+            "--proof-path",
+            &proof_path,
+            "--vk-path",
+            &format!("{}/key.vk", dir),
+        ])
+        .success());
+
+        // the output takes half its instances, which unpack to its values
+        let settings = GraphSettings::load(&settings_path.clone().into()).unwrap();
+        let packed = settings.limb_packed_output(0).unwrap().clone();
+        let baseline_instances = baseline_settings.total_instances().iter().sum::<usize>();
+        let packed_instances = settings.total_instances().iter().sum::<usize>();
+        assert_eq!(
+            packed_instances,
+            baseline_instances - packed.len + packed.num_instances()
+        );
+        let proof = Snark::load::<KZGCommitmentScheme<Bn256>>(&proof_path.clone().into()).unwrap();
+        let start = settings.limb_packed_instance_starts()[0];
+        let instances = &proof.instances[0][start..start + packed.num_instances()];
+        let witness = GraphWitness::from_path(witness_path.into()).unwrap();
+        assert_eq!(
+            unpack_limbs(&packed, instances).unwrap(),
+            witness.outputs[0]
+        );
+
+        create_verifier();
+        let limb_packed = estimate("limb_packed");
+        println!(
+            "baseline: {} gas for {} instances, limb packed: {} gas for {} instances",
+            baseline["gas_used"],
+            baseline["num_instances"],
+            limb_packed["gas_used"],
+            limb_packed["num_instances"]
+        );
+        assert!(limb_packed["gas_used"].as_u64().unwrap() < baseline["gas_used"].as_u64().unwrap());
+
+        // the contract unpacking the output compiles against the layout of the settings
+        assert!(ezkl(&[
+            "create-evm-packed",
+            "--settings-path",
+            &settings_path,
+            "--sol-code-path",
+            &format!("{}/limb_packed.sol", dir),
+            "--abi-path",
+            &format!("{}/limb_packed_abi.json", dir),
+        ])
+        .success());
+        let source = std::fs::read_to_string(format!("{}/limb_packed.sol", dir)).unwrap();
+        assert!(source.contains("uint256 constant LIMBS = 2;"));
+    }
 
+    // the hashed output is bound to a commitment a data provider stored on-chain
+    fn kzg_evm_instance_bindings(test_dir: &str, example_name: &str) {
         prove_and_verify(
             test_dir,
-            example_name.clone(),
+            example_name.to_string(),
             "safe",
-            input_visibility,
-            param_visibility,
-            output_visibility,
-            num_inner_columns,
+            "private",
+            "fixed",
+            "hashed",
+            1,
             None,
             false,
             "single",
@@ -2145,278 +9042,395 @@ mod native_tests {
 
         let settings_path = format!("{}/{}/settings.json", test_dir, example_name);
         init_params(settings_path.clone().into());
-
-        let vk_arg = format!("{}/{}/key.vk", test_dir, example_name);
-        let rpc_arg = format!("--rpc-url={}", anvil_url);
-        let addr_path_arg = format!("--addr-path={}/{}/addr.txt", test_dir, example_name);
         let settings_arg = format!("--settings-path={}", settings_path);
+        let rpc_arg = format!("--rpc-url={}", ANVIL_URL.as_str());
 
-        // create encoded calldata
-        let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+        let witness_path = format!("{}/{}/witness.json", test_dir, example_name);
+        let witness = GraphWitness::from_path(witness_path.clone().into()).unwrap();
+        let commitment = witness.processed_outputs.unwrap().poseidon_hash.unwrap()[0];
+
+        // a provider contract returning the storage slot named by its first argument, which serves
+        // both `extsload(bytes32)` and a `commitments(uint256)` getter
+        let provider = "0x000000000000000000000000000000000000b1d5";
+        let slot = format!("0x{:064x}", 1);
+        let set_commitment = |value: &str| {
+            let status = Command::new("cast")
+                .args([
+                    "rpc",
+                    "anvil_setStorageAt",
+                    provider,
+                    &slot,
+                    value,
+                    "--rpc-url",
+                    ANVIL_URL.as_str(),
+                ])
+                .status()
+                .expect("failed to execute process");
+            assert!(status.success());
+        };
+        let status = Command::new("cast")
             .args([
-                "encode-evm-calldata",
-                "--proof-path",
-                &format!("{}/{}/proof.pf", test_dir, example_name),
+                "rpc",
+                "anvil_setCode",
+                provider,
+                "0x6004355460005260206000f3",
+                "--rpc-url",
+                ANVIL_URL.as_str(),
             ])
             .status()
             .expect("failed to execute process");
-
         assert!(status.success());
-
-        // create the verifier
-        let mut args = vec!["create-evm-verifier", "--vk-path", &vk_arg, &settings_arg];
+        set_commitment(&format!("{:?}", commitment));
+
+        let bindings_path = format!("{}/{}/instance_bindings.json", test_dir, example_name);
+        let bindings = serde_json::json!([
+            { "instance": 0, "source": { "storage": { "address": provider, "slot": slot } } },
+            {
+                "instance": 0,
+                "source": {
+                    "call": { "address": provider, "call_data": format!("00000000{}", &slot[2..]) }
+                }
+            }
+        ]);
+        std::fs::write(&bindings_path, bindings.to_string()).unwrap();
 
         let sol_arg = format!("{}/{}/kzg.sol", test_dir, example_name);
-
-        // create everything to test the pipeline
-        args.push("--sol-code-path");
-        args.push(sol_arg.as_str());
-
         let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
-            .args(&args)
+            .args([
+                "create-evm-verifier",
+                "--vk-path",
+                &format!("{}/{}/key.vk", test_dir, example_name),
+                &settings_arg,
+                "--sol-code-path",
+                &sol_arg,
+            ])
             .status()
             .expect("failed to execute process");
         assert!(status.success());
 
-        // deploy the verifier
-        let mut args = vec!["deploy-evm", rpc_arg.as_str(), addr_path_arg.as_str()];
-
-        args.push("--sol-code-path");
-        args.push(sol_arg.as_str());
-
+        let addr_path_verifier = format!("{}/{}/addr_verifier.txt", test_dir, example_name);
         let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
-            .args(&args)
+            .args([
+                "deploy-evm",
+                &rpc_arg,
+                "--addr-path",
+                &addr_path_verifier,
+                "--sol-code-path",
+                &sol_arg,
+            ])
             .status()
             .expect("failed to execute process");
         assert!(status.success());
 
-        // read in the address
-        let addr = std::fs::read_to_string(format!("{}/{}/addr.txt", test_dir, example_name))
-            .expect("failed to read address file");
-
-        let deployed_addr_arg = format!("--addr-verifier={}", addr);
-
-        // now verify the proof
-        let pf_arg = format!("{}/{}/proof.pf", test_dir, example_name);
-        let mut args = vec![
-            "verify-evm",
-            "--proof-path",
-            pf_arg.as_str(),
-            rpc_arg.as_str(),
-            deployed_addr_arg.as_str(),
-        ];
-
+        let data_path = format!("{}/{}/input.json", test_dir, example_name);
+        let sol_da_arg = format!("{}/{}/kzg_da.sol", test_dir, example_name);
         let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
-            .args(&args)
+            .args([
+                "create-evm-da",
+                &settings_arg,
+                "--sol-code-path",
+                &sol_da_arg,
+                "-D",
+                &data_path,
+                "-W",
+                &witness_path,
+                "--instance-bindings",
+                &bindings_path,
+            ])
             .status()
             .expect("failed to execute process");
         assert!(status.success());
-        // As sanity check, add example that should fail.
-        args[2] = PF_FAILURE;
+
+        let addr_path_da = format!("{}/{}/addr_da.txt", test_dir, example_name);
         let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
-            .args(args)
+            .args([
+                "deploy-evm-da",
+                &settings_arg,
+                "-D",
+                &data_path,
+                "--sol-code-path",
+                &sol_da_arg,
+                &rpc_arg,
+                "--addr-path",
+                &addr_path_da,
+            ])
             .status()
             .expect("failed to execute process");
-        assert!(!status.success());
-    }
+        assert!(status.success());
 
-    // prove-serialize-verify, the usual full path
-    fn kzg_evm_prove_and_verify_reusable_verifier(
-        num_inner_columns: usize,
-        test_dir: &str,
-        example_name: String,
-        input_visibility: &str,
-        param_visibility: &str,
-        output_visibility: &str,
-        reusable_verifier_address: &mut Option<String>,
-        overflow: bool,
-    ) -> String {
-        let anvil_url = ANVIL_URL.as_str();
+        let addr_verifier =
+            std::fs::read_to_string(addr_path_verifier).expect("failed to read address file");
+        let addr_da = std::fs::read_to_string(addr_path_da).expect("failed to read address file");
+        let verify = || {
+            Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args([
+                    "verify-evm",
+                    "--proof-path",
+                    &format!("{}/{}/proof.pf", test_dir, example_name),
+                    &rpc_arg,
+                    &format!("--addr-verifier={}", addr_verifier),
+                    &format!("--addr-da={}", addr_da),
+                ])
+                .status()
+                .expect("failed to execute process")
+        };
+        assert!(verify().success());
 
-        prove_and_verify(
-            test_dir,
-            example_name.clone(),
-            "safe",
-            input_visibility,
-            param_visibility,
-            output_visibility,
-            num_inner_columns,
-            None,
-            overflow,
-            "single",
-            Commitments::KZG,
-            2,
-        );
+        // once the stored commitment changes the proof no longer attests to it
+        set_commitment(&format!("0x{:064x}", 12345));
+        assert!(!verify().success());
+    }
 
-        let settings_path = format!("{}/{}/settings.json", test_dir, example_name);
-        init_params(settings_path.clone().into());
+    // each model version gets its own verifier, registered under the fingerprint of its settings
+    fn kzg_evm_registry(test_dir: &str, example_names: &[&str]) {
+        let rpc_arg = format!("--rpc-url={}", ANVIL_URL.as_str());
+        let registry_path = format!("{}/registry.address", test_dir);
 
-        let vk_arg = format!("{}/{}/key.vk", test_dir, example_name);
-        let rpc_arg = format!("--rpc-url={}", anvil_url);
-        let addr_path_arg = format!("--addr-path={}/{}/addr.txt", test_dir, example_name);
-        let settings_arg = format!("--settings-path={}", settings_path);
-        let sol_arg = format!("--sol-code-path={}/{}/kzg.sol", test_dir, example_name);
+        let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+            .args([
+                "deploy-evm-registry",
+                rpc_arg.as_str(),
+                "--addr-path",
+                &registry_path,
+            ])
+            .status()
+            .expect("failed to execute process");
+        assert!(status.success());
+        let registry =
+            std::fs::read_to_string(&registry_path).expect("failed to read address file");
+        let registry_arg = format!("--registry={}", registry);
 
-        // if the reusable verifier address is not set, create the verifier
-        let deployed_addr_arg = match reusable_verifier_address {
-            Some(addr) => addr.clone(),
-            None => {
-                // create the reusable verifier
-                let args = vec![
-                    "create-evm-verifier",
-                    "--vk-path",
-                    &vk_arg,
-                    &settings_arg,
-                    &sol_arg,
-                    "--reusable",
-                ];
+        let deploy_and_register = |example_name: &str| -> String {
+            let addr_path = format!("{}/{}/addr_verifier.txt", test_dir, example_name);
+            let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args([
+                    "deploy-evm-verifier",
+                    rpc_arg.as_str(),
+                    "--addr-path",
+                    &addr_path,
+                    "--sol-code-path",
+                    &format!("{}/{}/kzg.sol", test_dir, example_name),
+                    registry_arg.as_str(),
+                    "--settings-path",
+                    &format!("{}/{}/settings.json", test_dir, example_name),
+                ])
+                .status()
+                .expect("failed to execute process");
+            assert!(status.success());
+            std::fs::read_to_string(addr_path).expect("failed to read address file")
+        };
 
-                let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
-                    .args(&args)
-                    .status()
-                    .expect("failed to execute process");
-                assert!(status.success());
+        let resolve_and_verify = |example_name: &str| -> String {
+            let addr_path = format!("{}/{}/addr_resolved.txt", test_dir, example_name);
+            let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args([
+                    "resolve-evm-verifier",
+                    registry_arg.as_str(),
+                    "--settings-path",
+                    &format!("{}/{}/settings.json", test_dir, example_name),
+                    rpc_arg.as_str(),
+                    "--addr-path",
+                    &addr_path,
+                ])
+                .status()
+                .expect("failed to execute process");
+            assert!(status.success());
+            let resolved = std::fs::read_to_string(addr_path).expect("failed to read address file");
 
-                // deploy the verifier
-                let args = vec![
-                    "deploy-evm",
+            let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args([
+                    "verify-evm",
+                    "--proof-path",
+                    &format!("{}/{}/proof.pf", test_dir, example_name),
                     rpc_arg.as_str(),
-                    addr_path_arg.as_str(),
-                    sol_arg.as_str(),
-                    "-C=verifier/reusable",
-                ];
+                    &format!("--addr-verifier={}", resolved),
+                ])
+                .status()
+                .expect("failed to execute process");
+            assert!(status.success());
+            resolved
+        };
 
-                let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
-                    .args(&args)
-                    .status()
-                    .expect("failed to execute process");
-                assert!(status.success());
+        for example_name in example_names {
+            prove_and_verify(
+                test_dir,
+                example_name.to_string(),
+                "safe",
+                "private",
+                "fixed",
+                "public",
+                1,
+                None,
+                false,
+                "single",
+                Commitments::KZG,
+                2,
+            );
 
-                // read in the address
-                let addr =
-                    std::fs::read_to_string(format!("{}/{}/addr.txt", test_dir, example_name))
-                        .expect("failed to read address file");
+            let settings_path = format!("{}/{}/settings.json", test_dir, example_name);
+            init_params(settings_path.clone().into());
 
-                let deployed_addr_arg = format!("--addr-verifier={}", addr);
-                // set the reusable verifier address
-                *reusable_verifier_address = Some(addr);
-                deployed_addr_arg
-            }
-        };
+            let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args([
+                    "create-evm-verifier",
+                    "--vk-path",
+                    &format!("{}/{}/key.vk", test_dir, example_name),
+                    "--settings-path",
+                    &settings_path,
+                    "--sol-code-path",
+                    &format!("{}/{}/kzg.sol", test_dir, example_name),
+                ])
+                .status()
+                .expect("failed to execute process");
+            assert!(status.success());
+
+            deploy_and_register(example_name);
+        }
 
-        let addr_path_arg_vk = format!("--addr-path={}/{}/addr_vk.txt", test_dir, example_name);
-        let sol_arg_vk: String = format!("--sol-code-path={}/{}/vk.sol", test_dir, example_name);
-        // create the verifier
-        let args = vec![
-            "create-evm-vka",
-            "--vk-path",
-            &vk_arg,
-            &settings_arg,
-            &sol_arg_vk,
-        ];
+        let mut resolved = vec![];
+        for example_name in example_names {
+            let deployed =
+                std::fs::read_to_string(format!("{}/{}/addr_verifier.txt", test_dir, example_name))
+                    .expect("failed to read address file");
+            let verifier = resolve_and_verify(example_name);
+            assert_eq!(verifier, deployed);
+            resolved.push(verifier);
+        }
+        assert_ne!(resolved[0], resolved[1]);
 
-        let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
-            .args(&args)
-            .status()
-            .expect("failed to execute process");
-        assert!(status.success());
+        // redeploying a model's verifier moves its registration, the other model is untouched
+        let redeployed = deploy_and_register(example_names[0]);
+        assert_ne!(redeployed, resolved[0]);
+        assert_eq!(resolve_and_verify(example_names[0]), redeployed);
+        assert_eq!(resolve_and_verify(example_names[1]), resolved[1]);
+    }
 
-        // deploy the vka
-        let args = vec![
-            "deploy-evm",
-            rpc_arg.as_str(),
-            addr_path_arg_vk.as_str(),
-            sol_arg_vk.as_str(),
-            "-C=vka",
-        ];
+    fn kzg_evm_foundry_harness(test_dir: &str, example_name: &str) {
+        prove_and_verify(
+            test_dir,
+            example_name.to_string(),
+            "safe",
+            "private",
+            "fixed",
+            "public",
+            1,
+            None,
+            false,
+            "single",
+            Commitments::KZG,
+            2,
+        );
+
+        let settings_path = format!("{}/{}/settings.json", test_dir, example_name);
+        init_params(settings_path.clone().into());
 
+        let proof_path = format!("{}/{}/proof.pf", test_dir, example_name);
+        let foundry_dir = format!("{}/{}/foundry", test_dir, example_name);
         let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
-            .args(&args)
+            .args([
+                "create-evm-verifier",
+                "--vk-path",
+                &format!("{}/{}/key.vk", test_dir, example_name),
+                "--settings-path",
+                &settings_path,
+                "--sol-code-path",
+                &format!("{}/{}/kzg.sol", test_dir, example_name),
+                "--foundry-dir",
+                &foundry_dir,
+                "--fixture-proof-path",
+                &proof_path,
+            ])
             .status()
             .expect("failed to execute process");
         assert!(status.success());
 
-        // read in the address
-        let addr_vk = std::fs::read_to_string(format!("{}/{}/addr_vk.txt", test_dir, example_name))
-            .expect("failed to read address file");
-
-        let deployed_addr_arg_vk = format!("--addr-vk={}", addr_vk);
+        for file in [
+            "foundry.toml",
+            "src/Halo2Verifier.sol",
+            "test/Halo2Verifier.t.sol",
+            "test/Vm.sol",
+            "test/fixtures/proof.json",
+            "script/Deploy.s.sol",
+        ] {
+            assert!(PathBuf::from(format!("{}/{}", foundry_dir, file)).exists());
+        }
 
-        // create encoded calldata
+        // the fixture calldata must be byte for byte what encode-evm-calldata produces
+        let calldata_path = format!("{}/{}/calldata.bytes", test_dir, example_name);
         let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
             .args([
                 "encode-evm-calldata",
                 "--proof-path",
-                &format!("{}/{}/proof.pf", test_dir, example_name),
-                &deployed_addr_arg_vk,
+                &proof_path,
+                "--calldata-path",
+                &calldata_path,
             ])
             .status()
             .expect("failed to execute process");
-
         assert!(status.success());
+        let fixture: serde_json::Value = serde_json::from_str(
+            &std::fs::read_to_string(format!("{}/test/fixtures/proof.json", foundry_dir)).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            fixture["calldata"].as_str().unwrap(),
+            format!("0x{}", hex::encode(std::fs::read(&calldata_path).unwrap()))
+        );
 
-        // now verify the proof
-        let pf_arg = format!("{}/{}/proof.pf", test_dir, example_name);
-        let args = vec![
-            "verify-evm",
-            "--proof-path",
-            pf_arg.as_str(),
-            rpc_arg.as_str(),
-            deployed_addr_arg.as_str(),
-            deployed_addr_arg_vk.as_str(),
-        ];
-
-        let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
-            .args(&args)
+        // only run forge where foundry is installed
+        if Command::new("forge").arg("--version").status().is_err() {
+            println!("forge not found, skipping forge test");
+            return;
+        }
+        let status = Command::new("forge")
+            .args(["test", "--root", &foundry_dir])
             .status()
             .expect("failed to execute process");
         assert!(status.success());
-        // Read the original proof file
-        let original_proof_data: ezkl::pfsys::Snark<
-            halo2curves::bn256::Fr,
-            halo2curves::bn256::G1Affine,
-        > = Snark::load::<KZGCommitmentScheme<Bn256>>(&PathBuf::from(format!(
-            "{}/{}/proof.pf",
-            test_dir, example_name
-        )))
-        .expect("Failed to read proof file");
-
-        for i in 0..1 {
-            // Create a copy of the original proof data
-            let mut modified_proof_data = original_proof_data.clone();
-
-            // Flip a random bit
-            let random_byte = rand::thread_rng().gen_range(0..modified_proof_data.proof.len());
-            let random_bit = rand::thread_rng().gen_range(0..8);
-            modified_proof_data.proof[random_byte] ^= 1 << random_bit;
-
-            // Write the modified proof to a new file
-            let modified_pf_arg = format!("{}/{}/modified_proof_{}.pf", test_dir, example_name, i);
-            modified_proof_data
-                .save(&PathBuf::from(modified_pf_arg.clone()))
-                .expect("Failed to save modified proof file");
-
-            // Verify the modified proof (should fail)
-            let mut args_mod = args.clone();
-            args_mod[2] = &modified_pf_arg;
-            let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
-                .args(&args_mod)
-                .status()
-                .expect("failed to execute process");
-
-            if status.success() {
-                log::error!("Verification unexpectedly succeeded for modified proof {}. Flipped bit {} in byte {}", i, random_bit, random_byte);
-            }
+    }
 
-            assert!(
-                !status.success(),
-                "Modified proof {} should have failed verification",
-                i
-            );
-        }
+    // two models share one deployed reusable verifier, each with its own vka
+    fn kzg_evm_reusable_verifier_two_models(test_dir: &str, example_a: &str, example_b: &str) {
+        let mut reusable_verifier_address = None;
+        let deployed_addr_arg = kzg_evm_prove_and_verify_reusable_verifier(
+            2,
+            test_dir,
+            example_a.to_string(),
+            "private",
+            "private",
+            "public",
+            &mut reusable_verifier_address,
+            false,
+        );
+        // the second model is verified against the verifier deployed for the first
+        let mut reusable_verifier_address = Some(deployed_addr_arg.clone());
+        let deployed_addr_arg_b = kzg_evm_prove_and_verify_reusable_verifier(
+            2,
+            test_dir,
+            example_b.to_string(),
+            "private",
+            "private",
+            "public",
+            &mut reusable_verifier_address,
+            false,
+        );
+        assert_eq!(deployed_addr_arg, deployed_addr_arg_b);
 
-        // Returned deploy_addr_arg for reusable verifier
-        deployed_addr_arg
+        // a proof must not verify against another model's vka
+        let addr_vk_a = std::fs::read_to_string(format!("{}/{}/addr_vk.txt", test_dir, example_a))
+            .expect("failed to read address file");
+        let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+            .args([
+                "verify-evm",
+                "--proof-path",
+                &format!("{}/{}/proof.pf", test_dir, example_b),
+                &format!("--rpc-url={}", *ANVIL_URL),
+                deployed_addr_arg.as_str(),
+                &format!("--addr-vk={}", addr_vk_a),
+            ])
+            .status()
+            .expect("failed to execute process");
+        assert!(!status.success());
     }
 
     // run js browser evm verify tests for a given example
@@ -2563,6 +9577,17 @@ mod native_tests {
                 .status()
                 .expect("failed to execute process");
             assert!(status.success());
+
+            if input_source == "nested-on-chain" {
+                // every call reads its value out of the oracle's nested return type
+                let data = GraphData::from_path(test_on_chain_data_path.clone().into()).unwrap();
+                match data.input_data {
+                    DataSource::OnChain(source) => {
+                        assert!(source.calls[0].returns.iter().all(|r| r.is_some()))
+                    }
+                    _ => panic!("expected an on-chain source"),
+                }
+            }
         }
 
         let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
@@ -2692,7 +9717,11 @@ mod native_tests {
 
         let deployed_addr_da_arg = format!("--addr-da={}", addr_da);
 
-        let args = vec![
+        let storage_proof = input_source == "storage-proof" || output_source == "storage-proof";
+        // storage attestation needs the slots to fetch the storage proofs for
+        let data_path_arg = format!("--data-path={}", test_on_chain_data_path);
+
+        let mut args = vec![
             "verify-evm",
             "--proof-path",
             pf_arg.as_str(),
@@ -2700,13 +9729,46 @@ mod native_tests {
             deployed_addr_da_arg.as_str(),
             rpc_arg.as_str(),
         ];
+        if storage_proof {
+            args.push(data_path_arg.as_str());
+        }
         let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
             .args(&args)
             .status()
             .expect("failed to execute process");
         assert!(status.success());
+        if storage_proof {
+            // overwrite the attested storage, the proofs are against the snapshotted block so it must still verify
+            let data = GraphData::from_path(test_on_chain_data_path.clone().into()).unwrap();
+            let source = match data.input_data {
+                DataSource::StorageProof(source) => source,
+                _ => match data.output_data {
+                    Some(DataSource::StorageProof(source)) => source,
+                    _ => panic!("expected a storage proof source"),
+                },
+            };
+            let status = Command::new("cast")
+                .args([
+                    "rpc",
+                    "anvil_setStorageAt",
+                    &format!("0x{}", source.accounts[0].address),
+                    &source.accounts[0].slots[0].0,
+                    &format!("0x{:064x}", 12345),
+                    "--rpc-url",
+                    LIMITLESS_ANVIL_URL.as_str(),
+                ])
+                .status()
+                .expect("failed to execute process");
+            assert!(status.success());
+
+            let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args(&args)
+                .status()
+                .expect("failed to execute process");
+            assert!(status.success());
+        }
         // Create a new set of test on chain data only for the on-chain input source
-        if input_source != "file" || output_source != "file" {
+        if (input_source != "file" || output_source != "file") && !storage_proof {
             let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
                 .args([
                     "setup-test-evm-data",
@@ -2742,7 +9804,7 @@ mod native_tests {
             assert!(status.success());
         }
         // As sanity check, add example that should fail.
-        let args = vec![
+        let mut args = vec![
             "verify-evm",
             "--proof-path",
             PF_FAILURE,
@@ -2750,6 +9812,9 @@ mod native_tests {
             deployed_addr_da_arg.as_str(),
             rpc_arg.as_str(),
         ];
+        if storage_proof {
+            args.push(data_path_arg.as_str());
+        }
         let status = Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
             .args(args)
             .status()
@@ -2757,6 +9822,268 @@ mod native_tests {
         assert!(!status.success());
     }
 
+    // the on-chain input changes after the pinned block, the pinned witness and the snapshot must not see the change
+    fn kzg_evm_pinned_on_chain_input_prove_and_verify(test_dir: &str, example_name: String) {
+        gen_circuit_settings_and_witness(
+            test_dir,
+            example_name.clone(),
+            "public",
+            "private",
+            "private",
+            1,
+            "resources",
+            // we need the accuracy
+            Some(vec![4]),
+            1,
+            false,
+            &mut 0.0,
+            Commitments::KZG,
+            2,
+        );
+
+        let model_path = format!("{}/{}/network.compiled", test_dir, example_name);
+        let settings_path = format!("{}/{}/settings.json", test_dir, example_name);
+        init_params(settings_path.clone().into());
+
+        let data_path = format!("{}/{}/input.json", test_dir, example_name);
+        let witness_path = format!("{}/{}/witness.json", test_dir, example_name);
+        let pinned_witness_path = format!("{}/{}/witness_pinned.json", test_dir, example_name);
+        let test_on_chain_data_path = format!("{}/{}/on_chain_input.json", test_dir, example_name);
+        let rpc_arg = format!("--rpc-url={}", LIMITLESS_ANVIL_URL.as_str());
+        let private_key = format!("--private-key={}", *ANVIL_DEFAULT_PRIVATE_KEY);
+        let vk_path = format!("{}/{}/key.vk", test_dir, example_name);
+        let pk_path = format!("{}/{}/key.pk", test_dir, example_name);
+        let settings_arg = format!("--settings-path={}", settings_path);
+
+        let ezkl = |args: &[&str]| {
+            Command::new(format!("{}/release/ezkl", *CARGO_TARGET_DIR))
+                .args(args)
+                .status()
+                .expect("failed to execute process")
+        };
+
+        assert!(ezkl(&[
+            "setup",
+            "-M",
+            &model_path,
+            "--pk-path",
+            &pk_path,
+            "--vk-path",
+            &vk_path
+        ])
+        .success());
+        assert!(ezkl(&[
+            "gen-witness",
+            "-D",
+            &data_path,
+            "-M",
+            &model_path,
+            "-O",
+            &witness_path
+        ])
+        .success());
+
+        let witness: GraphWitness = GraphWitness::from_path(witness_path.clone().into()).unwrap();
+        let mut input: GraphData = GraphData::from_path(data_path.clone().into()).unwrap();
+        input.output_data = Some(DataSource::File(
+            witness
+                .pretty_elements
+                .unwrap()
+                .rescaled_outputs
+                .iter()
+                .map(|o| {
+                    o.iter()
+                        .map(|f| FileSourceInner::Float(f.parse().unwrap()))
+                        .collect()
+                })
+                .collect(),
+        ));
+        input.save(data_path.clone().into()).unwrap();
+
+        assert!(ezkl(&[
+            "setup-test-evm-data",
+            "-D",
+            &data_path,
+            "-M",
+            &model_path,
+            "--test-data",
+            &test_on_chain_data_path,
+            &rpc_arg,
+            "--input-source=on-chain",
+            "--output-source=file",
+        ])
+        .success());
+
+        let sol_arg = format!("{}/{}/kzg.sol", test_dir, example_name);
+        let addr_path_verifier_arg = format!(
+            "--addr-path={}/{}/addr_verifier.txt",
+            test_dir, example_name
+        );
+        assert!(ezkl(&[
+            "create-evm-verifier",
+            "--vk-path",
+            &vk_path,
+            &settings_arg,
+            "--sol-code-path",
+            &sol_arg
+        ])
+        .success());
+        assert!(ezkl(&[
+            "deploy-evm",
+            &rpc_arg,
+            &addr_path_verifier_arg,
+            "--sol-code-path",
+            &sol_arg
+        ])
+        .success());
+
+        assert!(ezkl(&[
+            "create-evm-da",
+            &settings_arg,
+            "--sol-code-path",
+            &sol_arg,
+            "-W",
+            &witness_path,
+            "-D",
+            &test_on_chain_data_path,
+        ])
+        .success());
+        let addr_path_da_arg = format!("--addr-path={}/{}/addr_da.txt", test_dir, example_name);
+        assert!(ezkl(&[
+            "deploy-evm-da",
+            &settings_arg,
+            "-D",
+            &test_on_chain_data_path,
+            "--sol-code-path",
+            &sol_arg,
+            &rpc_arg,
+            &addr_path_da_arg,
+            &private_key,
+        ])
+        .success());
+
+        let addr_verifier =
+            std::fs::read_to_string(format!("{}/{}/addr_verifier.txt", test_dir, example_name))
+                .expect("failed to read address file");
+        let addr_da = std::fs::read_to_string(format!("{}/{}/addr_da.txt", test_dir, example_name))
+            .expect("failed to read address file");
+        let addr_da_arg = format!("--addr-da={}", addr_da);
+
+        // snapshot the calls and pin the on-chain source to the snapshot block
+        assert!(ezkl(&[
+            "pin-evm-da",
+            &addr_da_arg,
+            "-D",
+            &test_on_chain_data_path,
+            &rpc_arg,
+            &private_key,
+        ])
+        .success());
+        let pinned_data = GraphData::from_path(test_on_chain_data_path.clone().into()).unwrap();
+        let source = match &pinned_data.input_data {
+            DataSource::OnChain(source) => source.clone(),
+            _ => panic!("expected an on-chain source"),
+        };
+        let pinned_block = source.block.expect("the source should be pinned");
+
+        // change the first input after the pinned block, `arr` in TestReads stores its elements from keccak256(0)
+        let status = Command::new("cast")
+            .args([
+                "rpc",
+                "anvil_mine",
+                "--rpc-url",
+                LIMITLESS_ANVIL_URL.as_str(),
+            ])
+            .status()
+            .expect("failed to execute process");
+        assert!(status.success());
+        let status = Command::new("cast")
+            .args([
+                "rpc",
+                "anvil_setStorageAt",
+                &format!("0x{}", source.calls[0].address),
+                "0x290decd9548b62a8d60345a988386fc84ba6bc95484008f6362f93160ef3e563",
+                &format!("0x{:064x}", 12345),
+                "--rpc-url",
+                LIMITLESS_ANVIL_URL.as_str(),
+            ])
+            .status()
+            .expect("failed to execute process");
+        assert!(status.success());
+
+        // the pinned witness reads the state at the pinned block
+        assert!(ezkl(&[
+            "gen-witness",
+            "-D",
+            &test_on_chain_data_path,
+            "-M",
+            &model_path,
+            "-O",
+            &pinned_witness_path,
+        ])
+        .success());
+        let pinned_witness: GraphWitness =
+            GraphWitness::from_path(pinned_witness_path.clone().into()).unwrap();
+        assert_eq!(pinned_witness.inputs, witness.inputs);
+        let recorded = pinned_witness.pinned_block.clone().unwrap();
+        assert_eq!(recorded.number, pinned_block);
+        assert_eq!(Some(recorded.hash), source.block_hash);
+
+        // unpinned, the witness reads the changed state
+        let mut unpinned_data = pinned_data.clone();
+        if let DataSource::OnChain(source) = &mut unpinned_data.input_data {
+            source.block = None;
+            source.block_hash = None;
+        }
+        let unpinned_data_path = format!("{}/{}/unpinned_input.json", test_dir, example_name);
+        let unpinned_witness_path = format!("{}/{}/witness_unpinned.json", test_dir, example_name);
+        unpinned_data
+            .save(unpinned_data_path.clone().into())
+            .unwrap();
+        assert!(ezkl(&[
+            "gen-witness",
+            "-D",
+            &unpinned_data_path,
+            "-M",
+            &model_path,
+            "-O",
+            &unpinned_witness_path,
+        ])
+        .success());
+        let unpinned_witness: GraphWitness =
+            GraphWitness::from_path(unpinned_witness_path.into()).unwrap();
+        assert_ne!(unpinned_witness.inputs, witness.inputs);
+        assert!(unpinned_witness.pinned_block.is_none());
+
+        // the pinned proof verifies against the snapshot even though the live state changed
+        let pf_path = format!("{}/{}/proof.pf", test_dir, example_name);
+        assert!(ezkl(&[
+            "prove",
+            "-W",
+            &pinned_witness_path,
+            "-M",
+            &model_path,
+            "--proof-path",
+            &pf_path,
+            "--pk-path",
+            &pk_path,
+        ])
+        .success());
+        let proof = Snark::load::<KZGCommitmentScheme<Bn256>>(&PathBuf::from(&pf_path)).unwrap();
+        assert_eq!(proof.pinned_block.unwrap().number, pinned_block);
+
+        let addr_verifier_arg = format!("--addr-verifier={}", addr_verifier);
+        assert!(ezkl(&[
+            "verify-evm",
+            "--proof-path",
+            &pf_path,
+            &addr_verifier_arg,
+            &addr_da_arg,
+            &rpc_arg,
+        ])
+        .success());
+    }
+
     fn build_ezkl() {
         #[cfg(feature = "icicle")]
         let args = [
@@ -2767,8 +10094,17 @@ mod native_tests {
             "--features",
             "icicle",
         ];
-        #[cfg(not(feature = "icicle"))]
+        #[cfg(all(not(feature = "icicle"), not(feature = "server")))]
         let args = ["build", "--release", "--bin", "ezkl"];
+        #[cfg(all(not(feature = "icicle"), feature = "server"))]
+        let args = [
+            "build",
+            "--release",
+            "--bin",
+            "ezkl",
+            "--features",
+            "server",
+        ];
         #[cfg(not(feature = "mv-lookup"))]
         let args = [
             "build",
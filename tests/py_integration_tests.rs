@@ -254,6 +254,15 @@ mod py_tests {
                 test_dir.close().unwrap();
             }
 
+            #[test]
+            fn image_preprocessing_matches_torchvision_() {
+                crate::py_tests::init_binary();
+                let test_dir: TempDir = TempDir::new("image_preprocessing").unwrap();
+                let path = test_dir.path().to_str().unwrap();
+                image_preprocessing_matches_torchvision(path);
+                test_dir.close().unwrap();
+            }
+
 
     }
     };
@@ -282,5 +291,46 @@ mod py_tests {
         assert!(status.success());
     }
 
+    // Preprocess images with torchvision and check the equivalent specs land within a few pixel levels of it,
+    // the decoders and resamplers round differently
+    fn image_preprocessing_matches_torchvision(test_dir: &str) {
+        use ezkl::graph::input::{ImagePreprocessing, PixelRange};
+
+        let status = Command::new("python")
+            .args(["tests/python/torchvision_preprocessing.py", test_dir])
+            .status()
+            .expect("failed to execute process");
+        assert!(status.success());
+
+        let cases: Vec<serde_json::Value> = serde_json::from_str(
+            &std::fs::read_to_string(format!("{}/cases.json", test_dir)).unwrap(),
+        )
+        .unwrap();
+        for case in cases {
+            let spec: ImagePreprocessing = serde_json::from_value(case["spec"].clone()).unwrap();
+            let expected: Vec<f64> = serde_json::from_value(case["expected"].clone()).unwrap();
+            let image = std::fs::read(case["image"].as_str().unwrap()).unwrap();
+            let found = spec.apply(&image).unwrap();
+            assert_eq!(found.len(), expected.len());
+
+            let range = match spec.range {
+                PixelRange::ZeroOne => 1.0,
+                PixelRange::MinusOneOne => 2.0,
+            };
+            let min_std = spec.std.iter().copied().fold(1.0, f64::min);
+            let tolerance = 4.0 / 255.0 * range / min_std;
+            for (found, expected) in found.iter().zip(expected.iter()) {
+                let diff = (found.to_float() - expected).abs();
+                assert!(
+                    diff <= tolerance,
+                    "{:?}: {} vs {}",
+                    spec,
+                    found.to_float(),
+                    expected
+                );
+            }
+        }
+    }
+
     test_func!();
 }
@@ -1,9 +1,16 @@
+import asyncio
 import ezkl
 import os
 import pytest
 import json
+import gc
+import numpy as np
+import shutil
 import subprocess
+import sys
+import threading
 import time
+import weakref
 
 folder_path = os.path.abspath(
     os.path.join(
@@ -62,6 +69,17 @@ def test_poseidon_hash():
         res[0]) == "0x0da7e5e5c8877242fa699f586baf770d731defd54f952d4adeb85047a0e32f45"
 
 
+def test_elgamal_gen_keys():
+    """
+    Test for elgamal_gen_keys
+    """
+    secret_key, public_key = ezkl.elgamal_gen_keys()
+    assert secret_key.startswith("0x")
+    assert len(public_key) == 2
+    # keys are drawn at random
+    assert ezkl.elgamal_gen_keys()[0] != secret_key
+
+
 
 def test_field_serialization():
     """
@@ -198,6 +216,38 @@ def test_model_compile():
     assert res == True
 
 
+def test_inspect():
+    """
+    Test for the inspection of a compiled circuit
+    """
+    compiled_model_path = os.path.join(
+        folder_path,
+        'model.compiled'
+    )
+    settings_path = os.path.join(
+        folder_path,
+        'settings.json'
+    )
+    with open(settings_path, "r") as f:
+        settings = json.load(f)
+
+    inspection = ezkl.inspect(compiled_model_path)
+    assert isinstance(inspection, dict)
+    nodes = inspection["nodes"]
+    assert len(nodes) > 0
+    assert all(node["name"] for node in nodes)
+    # the input node has the visibility of the inputs calibrated above, and shapes as lists
+    inputs = [node for node in nodes if node["op"] == "Input"]
+    assert len(inputs) == len(settings["model_input_scales"])
+    assert inputs[0]["visibility"] == "hashed/public"
+    assert isinstance(inputs[0]["out_dims"][0], list)
+    assert len(inspection["instance_layout"]) > 0
+
+    # a single node is inspected on its own
+    assert ezkl.inspect(compiled_model_path, node=nodes[0]["idx"]) == nodes[0]
+    assert ezkl.inspect(compiled_model_path, node=10_000) is None
+
+
 async def test_forward():
     """
     Test for vanilla forward pass
@@ -229,6 +279,188 @@ async def test_forward():
     assert data["processed_outputs"]["poseidon_hash"] == res["processed_outputs"]["poseidon_hash"]
 
 
+def relu_input_arrays():
+    with open(os.path.join(examples_path, 'onnx', '1l_relu', 'input.json'), "r") as f:
+        data = json.load(f)
+    return [np.array(x, dtype=np.float64) for x in data["input_data"]]
+
+
+async def test_forward_arrays():
+    """
+    Test that the forward pass on numpy arrays matches the one on the json data file
+    """
+    data_path = os.path.join(
+        examples_path,
+        'onnx',
+        '1l_relu',
+        'input.json'
+    )
+    model_path = os.path.join(
+        folder_path,
+        'model.compiled'
+    )
+    output_path = os.path.join(
+        folder_path,
+        'witness_from_json.json'
+    )
+    dequantized_path = os.path.join(
+        folder_path,
+        'dequantized_outputs.npy'
+    )
+
+    expected = await ezkl.gen_witness(
+        data_path, model_path, output_path, dequantized_output=dequantized_path)
+    res, outputs = await ezkl.gen_witness_arrays(relu_input_arrays(), model_path)
+
+    assert res["inputs"] == expected["inputs"]
+    assert res["outputs"] == expected["outputs"]
+    assert res["processed_inputs"]["poseidon_hash"] == expected["processed_inputs"]["poseidon_hash"]
+    assert res["processed_outputs"]["poseidon_hash"] == expected["processed_outputs"]["poseidon_hash"]
+
+    dequantized = np.load(dequantized_path)
+    assert len(outputs) == 1
+    assert outputs[0].dtype == np.float64
+    assert outputs[0].shape == dequantized.shape
+    assert np.array_equal(outputs[0], dequantized)
+
+    # float32 arrays are widened as the json floats are parsed
+    widened = [x.astype(np.float32) for x in relu_input_arrays()]
+    res_f32, _ = await ezkl.gen_witness_arrays(widened, model_path)
+    with open(data_path, "r") as f:
+        data = json.load(f)
+    data["input_data"] = [x.astype(np.float64).tolist() for x in widened]
+    widened_path = os.path.join(folder_path, 'input_f32.json')
+    with open(widened_path, "w") as f:
+        json.dump(data, f)
+    expected_f32 = await ezkl.gen_witness(widened_path, model_path, None)
+    assert res_f32["inputs"] == expected_f32["inputs"]
+    assert res_f32["outputs"] == expected_f32["outputs"]
+
+    float_outputs = ezkl.forward_arrays(
+        relu_input_arrays(),
+        os.path.join(examples_path, 'onnx', '1l_relu', 'network.onnx'),
+        os.path.join(folder_path, 'settings.json'),
+    )
+    assert float_outputs[0].dtype == np.float32
+    assert np.allclose(float_outputs[0].reshape(-1), dequantized.reshape(-1), atol=1e-2)
+
+
+async def test_forward_arrays_validation():
+    """
+    Test that arrays which don't fit the model raise ValueError
+    """
+    model_path = os.path.join(
+        folder_path,
+        'model.compiled'
+    )
+
+    with pytest.raises(ValueError):
+        await ezkl.gen_witness_arrays([np.zeros(4)], model_path)
+
+    with pytest.raises(ValueError):
+        await ezkl.gen_witness_arrays(relu_input_arrays() * 2, model_path)
+
+    with pytest.raises(ValueError):
+        await ezkl.gen_witness_arrays([np.array(["a", "b", "c"])], model_path)
+
+
+async def test_calibrate_arrays():
+    """
+    Test that calibrating on numpy arrays matches calibrating on the json data file
+    """
+    data_path = os.path.join(
+        examples_path,
+        'onnx',
+        '1l_relu',
+        'input.json'
+    )
+    model_path = os.path.join(
+        examples_path,
+        'onnx',
+        '1l_relu',
+        'network.onnx'
+    )
+    json_settings_path = os.path.join(folder_path, 'settings_calibrated_json.json')
+    arrays_settings_path = os.path.join(folder_path, 'settings_calibrated_arrays.json')
+
+    for path in [json_settings_path, arrays_settings_path]:
+        assert ezkl.gen_settings(model_path, path) == True
+
+    res = await ezkl.calibrate_settings(
+        data_path, model_path, json_settings_path, "resources", 1, [0, 1, 2])
+    assert res == True
+    res = await ezkl.calibrate_settings_arrays(
+        relu_input_arrays(), model_path, arrays_settings_path, "resources", 1, [0, 1, 2])
+    assert res == True
+
+    with open(json_settings_path, "r") as f:
+        expected = json.load(f)
+    with open(arrays_settings_path, "r") as f:
+        assert json.load(f) == expected
+
+    with pytest.raises(ValueError):
+        await ezkl.calibrate_settings_arrays(
+            [np.zeros(4)], model_path, arrays_settings_path, "resources")
+
+
+async def test_torch_tensors():
+    """
+    Test that torch tensors are read through DLPack as the numpy arrays they hold, and that calibration iterates
+    batches of tensors as it stacks numpy arrays
+    """
+    torch = pytest.importorskip("torch")
+    onnx_path = os.path.join(examples_path, 'onnx', '1l_relu', 'network.onnx')
+    model_path = os.path.join(folder_path, 'model.compiled')
+    arrays = relu_input_arrays()
+
+    expected, expected_outputs = await ezkl.gen_witness_arrays(arrays, model_path)
+    tensors = [torch.from_numpy(a) for a in arrays]
+    witness, outputs = await ezkl.gen_witness_arrays(tensors, model_path)
+    assert witness["inputs"] == expected["inputs"]
+    assert witness["outputs"] == expected["outputs"]
+    assert np.array_equal(outputs[0], expected_outputs[0])
+
+    # tensors that require gradients are detached, float32 ones read as numpy float32 arrays
+    grad_tensors = [t.float().requires_grad_() for t in tensors]
+    witness_f32, _ = await ezkl.gen_witness_arrays(grad_tensors, model_path)
+    expected_f32, _ = await ezkl.gen_witness_arrays([a.astype(np.float32) for a in arrays], model_path)
+    assert witness_f32["inputs"] == expected_f32["inputs"]
+
+    assert np.array_equal(
+        ezkl.forward_arrays(tensors, onnx_path, os.path.join(folder_path, 'settings.json'))[0],
+        ezkl.forward_arrays(arrays, onnx_path, os.path.join(folder_path, 'settings.json'))[0],
+    )
+
+    # calibrating on batches of tensors matches calibrating on the numpy arrays of the stacked batches
+    batches = [arrays[0].reshape(1, -1), -arrays[0].reshape(1, -1), 2 * arrays[0].reshape(1, -1)]
+    numpy_settings_path = os.path.join(folder_path, 'settings_calibrated_numpy.json')
+    torch_settings_path = os.path.join(folder_path, 'settings_calibrated_torch.json')
+    loader_settings_path = os.path.join(folder_path, 'settings_calibrated_loader.json')
+    for path in [numpy_settings_path, torch_settings_path, loader_settings_path]:
+        assert ezkl.gen_settings(onnx_path, path) == True
+
+    res = await ezkl.calibrate_settings_arrays(
+        [np.concatenate(batches)], onnx_path, numpy_settings_path, "resources", 1, [0, 1, 2])
+    assert res == True
+    res = await ezkl.calibrate_settings_arrays(
+        ([torch.from_numpy(b)] for b in batches), onnx_path, torch_settings_path, "resources", 1, [0, 1, 2])
+    assert res == True
+    loader = torch.utils.data.DataLoader(torch.from_numpy(np.concatenate(batches)), batch_size=1)
+    res = await ezkl.calibrate_settings_arrays(
+        loader, onnx_path, loader_settings_path, "resources", 1, [0, 1, 2])
+    assert res == True
+
+    with open(numpy_settings_path, "r") as f:
+        expected = json.load(f)
+    for path in [torch_settings_path, loader_settings_path]:
+        with open(path, "r") as f:
+            assert json.load(f) == expected
+
+    with pytest.raises(ezkl.InputError):
+        await ezkl.calibrate_settings_arrays(
+            iter([torch.zeros(1, 3), torch.zeros(1, 4)]), onnx_path, loader_settings_path, "resources")
+
+
 async def test_get_srs():
     """
     Test for get_srs
@@ -268,6 +500,36 @@ def test_mock():
     assert res == True
 
 
+def test_redact_witness():
+    """
+    Test that a redacted witness keeps the hashes but none of the private values, and can't be proven
+    """
+    witness_path = os.path.join(folder_path, 'witness.json')
+    settings_path = os.path.join(folder_path, 'settings.json')
+    model_path = os.path.join(folder_path, 'model.compiled')
+    redacted_path = os.path.join(folder_path, 'witness_redacted.json')
+
+    res = ezkl.redact_witness(witness_path, settings_path, redacted_path)
+
+    with open(witness_path, "r") as f:
+        full = json.load(f)
+    with open(redacted_path, "r") as f:
+        contents = f.read()
+    redacted = json.loads(contents)
+
+    for felt in [x for t in full["inputs"] + full["outputs"] for x in t]:
+        assert felt not in contents
+    assert all(len(t) == 0 for t in redacted["inputs"] + redacted["outputs"])
+    assert redacted["processed_inputs"]["poseidon_hash"] == full["processed_inputs"]["poseidon_hash"]
+    assert "inputs[0]" in res["redaction"]["redacted"]
+    assert res["redaction"]["encrypted_witness"] is None
+
+    with pytest.raises(ezkl.InputError):
+        ezkl.mock(redacted_path, model_path)
+    with pytest.raises(ezkl.InputError):
+        ezkl.unredact_witness(redacted_path, "AGE-SECRET-KEY-1")
+
+
 def test_setup():
     """
     Test for setup
@@ -364,6 +626,369 @@ def test_prove_and_verify():
     assert os.path.isfile(vk_path)
 
 
+def prover_session_paths():
+    return (
+        os.path.join(folder_path, 'model.compiled'),
+        os.path.join(folder_path, 'test.pk'),
+        os.path.join(folder_path, 'witness.json'),
+    )
+
+
+async def test_prover_session():
+    """
+    Test that a prover session proves and verifies from the artifacts it loaded once
+    """
+    model_path, pk_path, witness_path = prover_session_paths()
+
+    # the session keeps its own copy of the artifacts, they may go away once it is loaded
+    session_dir = os.path.join(folder_path, 'session')
+    os.makedirs(session_dir, exist_ok=True)
+    session_model_path = shutil.copy(model_path, session_dir)
+    session_pk_path = shutil.copy(pk_path, session_dir)
+    session = ezkl.ProverSession(session_model_path, session_pk_path, srs_path)
+    shutil.rmtree(session_dir)
+
+    with open(witness_path, "r") as f:
+        expected = json.load(f)
+    data_path = os.path.join(examples_path, 'onnx', '1l_relu', 'input.json')
+    witness = await session.gen_witness(data_path)
+    assert witness["inputs"] == expected["inputs"]
+    assert witness["outputs"] == expected["outputs"]
+
+    arrays_witness = await session.gen_witness(relu_input_arrays())
+    assert arrays_witness["outputs"] == expected["outputs"]
+
+    proof = session.prove(witness)
+    assert proof["transcript_type"] == 'EVM'
+    assert session.verify(proof) == True
+
+    proof_path = os.path.join(folder_path, 'session.pf')
+    res = session.prove(witness_path, proof_path, "for-aggr")
+    assert res['transcript_type'] == 'Poseidon'
+    assert session.verify(proof_path) == True
+    # the proof file verifies as the proofs of ezkl.prove do
+    res = ezkl.verify(
+        proof_path,
+        os.path.join(folder_path, 'settings.json'),
+        os.path.join(folder_path, 'test.vk'),
+        srs_path,
+    )
+    assert res == True
+
+    tampered = dict(proof)
+    tampered["proof"] = proof["proof"][:-1] + [(proof["proof"][-1] + 1) % 256]
+    with pytest.raises(RuntimeError):
+        session.verify(tampered)
+
+
+def test_prover_session_lifetime():
+    """
+    Test that the witnesses and proofs of a session don't hold on to it, and that it is freed once dropped
+    """
+    model_path, pk_path, witness_path = prover_session_paths()
+    session = ezkl.ProverSession(model_path, pk_path, srs_path)
+    ref = weakref.ref(session)
+    base = sys.getrefcount(session)
+
+    proof = session.prove(witness_path)
+    assert session.verify(proof) == True
+    assert session.logrows > 0
+    assert sys.getrefcount(session) == base
+
+    del session
+    gc.collect()
+    assert ref() is None
+    assert len(proof["proof"]) > 0
+
+
+def test_prover_session_threads():
+    """
+    Test that python threads share a session, proving concurrently as the GIL is released while they do
+    """
+    model_path, pk_path, witness_path = prover_session_paths()
+    session = ezkl.ProverSession(model_path, pk_path, srs_path)
+
+    results = []
+    errors = []
+    # a thread that keeps taking the GIL, which proofs holding it would stall
+    ticks = []
+    done = threading.Event()
+
+    def tick():
+        while not done.is_set():
+            ticks.append(time.perf_counter())
+            time.sleep(0.001)
+
+    def work():
+        try:
+            proof = session.prove(witness_path)
+            results.append(session.verify(proof))
+        except Exception as e:
+            errors.append(e)
+
+    ticker = threading.Thread(target=tick)
+    ticker.start()
+    workers = [threading.Thread(target=work) for _ in range(4)]
+    start = time.perf_counter()
+    for w in workers:
+        w.start()
+    for w in workers:
+        w.join()
+    done.set()
+    ticker.join()
+
+    assert errors == []
+    assert results == [True] * 4
+    assert len([t for t in ticks if t > start]) > 1
+
+
+def test_prover_session_benchmark():
+    """
+    Benchmark the proofs of a session against as many calls to ezkl.prove, which reload the artifacts each time
+    """
+    model_path, pk_path, witness_path = prover_session_paths()
+    proofs = 3
+
+    start = time.perf_counter()
+    for _ in range(proofs):
+        ezkl.prove(witness_path, model_path, pk_path, None, srs_path=srs_path)
+    unamortized = time.perf_counter() - start
+
+    start = time.perf_counter()
+    session = ezkl.ProverSession(model_path, pk_path, srs_path)
+    loaded = time.perf_counter() - start
+    for _ in range(proofs):
+        session.prove(witness_path)
+    amortized = time.perf_counter() - start
+
+    print(
+        "{} proofs: {:.3f}s with ezkl.prove, {:.3f}s with a session ({:.3f}s loading)".format(
+            proofs, unamortized, amortized, loaded)
+    )
+    # the session loads the artifacts once rather than once per proof
+    assert amortized - loaded < unamortized
+
+
+def test_exception_hierarchy():
+    """
+    Test that the exceptions of ezkl derive from EzklError, itself a RuntimeError
+    """
+    for name in ["InputError", "SettingsError", "UnsupportedModelError", "CircuitOverflowError",
+                 "ProofVerificationError", "ArtifactError", "CancellationError"]:
+        assert issubclass(getattr(ezkl, name), ezkl.EzklError)
+    assert issubclass(ezkl.EzklError, RuntimeError)
+    assert issubclass(ezkl.InputError, ValueError)
+    assert issubclass(ezkl.ArtifactError, OSError)
+
+
+async def test_input_error_fields():
+    """
+    Test that inputs which don't fit the model raise InputError with the offending input and shapes
+    """
+    model_path = os.path.join(folder_path, 'model.compiled')
+
+    with pytest.raises(ezkl.InputError) as excinfo:
+        await ezkl.gen_witness_arrays([np.zeros(4)], model_path)
+    err = excinfo.value
+    assert err.kind == "graph"
+    assert err.input == 0
+    assert err.actual_shape == [4]
+    assert err.expected_shape != [4]
+    assert err.details["actual_shape"] == [4]
+
+    with pytest.raises(ezkl.InputError) as excinfo:
+        await ezkl.gen_witness_arrays(relu_input_arrays() * 2, model_path)
+    assert excinfo.value.expected == 1
+    assert excinfo.value.actual == 2
+
+    with pytest.raises(ezkl.InputError) as excinfo:
+        await ezkl.gen_witness_arrays([np.array(["a", "b", "c"])], model_path)
+    assert excinfo.value.input == 0
+
+
+def test_artifact_error_fields():
+    """
+    Test that missing artifacts raise ArtifactError
+    """
+    missing_path = os.path.join(folder_path, 'missing.pk')
+    with pytest.raises(ezkl.ArtifactError) as excinfo:
+        ezkl.ProverSession(os.path.join(folder_path, 'model.compiled'), missing_path, srs_path)
+    assert isinstance(excinfo.value, OSError)
+
+
+def test_proof_verification_error():
+    """
+    Test that a tampered proof raises ProofVerificationError
+    """
+    model_path, pk_path, witness_path = prover_session_paths()
+    session = ezkl.ProverSession(model_path, pk_path, srs_path)
+    proof = session.prove(witness_path)
+
+    tampered = dict(proof)
+    tampered["proof"] = proof["proof"][:-1] + [(proof["proof"][-1] + 1) % 256]
+    with pytest.raises(ezkl.ProofVerificationError):
+        session.verify(tampered)
+
+
+def test_circuit_overflow_error():
+    """
+    Test that a circuit with too few rows for the model raises CircuitOverflowError
+    """
+    with open(os.path.join(folder_path, 'settings.json'), "r") as f:
+        settings = json.load(f)
+    settings["run_args"]["logrows"] = 4
+    small_settings_path = os.path.join(folder_path, 'small_settings.json')
+    with open(small_settings_path, "w") as f:
+        json.dump(settings, f)
+
+    small_model_path = os.path.join(folder_path, 'small_model.compiled')
+    res = ezkl.compile_circuit(
+        os.path.join(examples_path, 'onnx', '1l_relu', 'network.onnx'),
+        small_model_path,
+        small_settings_path,
+    )
+    assert res == True
+
+    with pytest.raises(ezkl.CircuitOverflowError) as excinfo:
+        ezkl.setup(
+            small_model_path,
+            os.path.join(folder_path, 'small.vk'),
+            os.path.join(folder_path, 'small.pk'),
+            srs_path=srs_path,
+        )
+    err = excinfo.value
+    if "max_rows" in err.details:
+        assert err.row > err.max_rows
+        assert err.node
+
+
+async def test_async_prove_and_verify():
+    """
+    Test that two proofs run concurrently on the rust thread pool without blocking the event loop, and that a
+    cancelled proof is abandoned
+    """
+    model_path, pk_path, witness_path = prover_session_paths()
+    settings_path = os.path.join(folder_path, 'settings.json')
+    vk_path = os.path.join(folder_path, 'test.vk')
+
+    witness = await ezkl.gen_witness_async(
+        os.path.join(examples_path, 'onnx', '1l_relu', 'input.json'), model_path)
+    with open(witness_path, "r") as f:
+        assert witness["outputs"] == json.load(f)["outputs"]
+    arrays_witness = await ezkl.gen_witness_async(relu_input_arrays(), model_path)
+    assert arrays_witness["outputs"] == witness["outputs"]
+
+    ticks = 0
+    done = asyncio.Event()
+
+    async def tick():
+        nonlocal ticks
+        while not done.is_set():
+            ticks += 1
+            await asyncio.sleep(0.01)
+
+    ticker = asyncio.ensure_future(tick())
+    proof_paths = [os.path.join(folder_path, 'async_{}.pf'.format(i)) for i in range(2)]
+    proofs = await asyncio.gather(*[
+        ezkl.prove_async(witness_path, model_path, pk_path, proof_path, srs_path=srs_path)
+        for proof_path in proof_paths
+    ])
+    done.set()
+    await ticker
+    # the event loop kept running while the proofs did
+    assert ticks > 1
+    for proof, proof_path in zip(proofs, proof_paths):
+        assert proof["transcript_type"] == 'EVM'
+        assert await ezkl.verify_async(proof_path, settings_path, vk_path, srs_path) == True
+
+    cancelled_path = os.path.join(folder_path, 'async_cancelled.pf')
+    cancelled = asyncio.ensure_future(
+        ezkl.prove_async(witness_path, model_path, pk_path, cancelled_path, srs_path=srs_path))
+    kept_path = os.path.join(folder_path, 'async_kept.pf')
+    kept = asyncio.ensure_future(
+        ezkl.prove_async(witness_path, model_path, pk_path, kept_path, srs_path=srs_path))
+    await asyncio.sleep(0)
+    cancelled.cancel()
+    with pytest.raises(asyncio.CancelledError):
+        await cancelled
+    # the other proof is unaffected
+    await kept
+    assert await ezkl.verify_async(kept_path, settings_path, vk_path, srs_path) == True
+    # the cancelled proof stopped before it could be saved
+    await asyncio.sleep(1)
+    assert not os.path.exists(cancelled_path)
+
+
+async def test_prove_timeout():
+    """
+    Test that a proof running out of its timeout raises a CancellationError promptly, without saving the proof
+    """
+    model_path, pk_path, witness_path = prover_session_paths()
+    timed_out_path = os.path.join(folder_path, 'timed_out.pf')
+
+    start = time.time()
+    with pytest.raises(ezkl.CancellationError):
+        ezkl.prove(witness_path, model_path, pk_path, timed_out_path, srs_path=srs_path, timeout=0)
+    with pytest.raises(ezkl.CancellationError):
+        await ezkl.prove_async(
+            witness_path, model_path, pk_path, timed_out_path, srs_path=srs_path, timeout=0)
+    assert time.time() - start < 10
+    assert not os.path.exists(timed_out_path)
+
+    with pytest.raises(ValueError):
+        ezkl.prove(witness_path, model_path, pk_path, timed_out_path, srs_path=srs_path, timeout=-1)
+
+
+async def test_run_pipeline():
+    """
+    Test for the pipeline from the onnx model to the keys, then proving from its artifacts
+    """
+    data_path = os.path.join(
+        examples_path,
+        'onnx',
+        '1l_relu',
+        'input.json'
+    )
+    model_path = os.path.join(
+        examples_path,
+        'onnx',
+        '1l_relu',
+        'network.onnx'
+    )
+    output_dir = os.path.join(folder_path, 'pipeline')
+    pipeline_srs_path = os.path.join(folder_path, 'kzg_test_pipeline.params')
+
+    res = await ezkl.run_pipeline(
+        model_path, data_path, output_dir, srs_path=pipeline_srs_path)
+    assert res == True
+    for artifact in ['settings.json', 'model.compiled', 'vk.key', 'pk.key', 'pipeline.json']:
+        assert os.path.isfile(os.path.join(output_dir, artifact))
+
+    # the second run is up to date
+    res = await ezkl.run_pipeline(
+        model_path, data_path, output_dir, srs_path=pipeline_srs_path)
+    assert res == True
+
+    witness_path = os.path.join(output_dir, 'witness.json')
+    proof_path = os.path.join(output_dir, 'proof.pf')
+    compiled_path = os.path.join(output_dir, 'model.compiled')
+    await ezkl.gen_witness(data_path, compiled_path, witness_path)
+    ezkl.prove(
+        witness_path,
+        compiled_path,
+        os.path.join(output_dir, 'pk.key'),
+        proof_path,
+        srs_path=pipeline_srs_path,
+    )
+    res = ezkl.verify(
+        proof_path,
+        os.path.join(output_dir, 'settings.json'),
+        os.path.join(output_dir, 'vk.key'),
+        pipeline_srs_path,
+    )
+    assert res == True
+
+
 def test_prove_evm():
     """
     Test for prove using evm transcript
@@ -0,0 +1,88 @@
+import json
+import os
+import sys
+
+import numpy as np
+import torch
+from PIL import Image
+from torchvision import transforms
+
+# Writes images and their torchvision preprocessing next to the equivalent ezkl
+# image preprocessing specs, for the rust side to compare against.
+
+
+def main(out_dir):
+    rng = np.random.default_rng(0)
+    # smooth gradients with noise on top, so resampling has something to average
+    y, x = np.mgrid[0:37, 0:53]
+    pixels = np.stack([x * 4, y * 6, (x + y) * 2], axis=-1)
+    pixels = pixels + rng.integers(0, 32, size=pixels.shape)
+    image = Image.fromarray(np.clip(pixels, 0, 255).astype(np.uint8), "RGB")
+
+    png = os.path.join(out_dir, "image.png")
+    jpeg = os.path.join(out_dir, "image.jpg")
+    image.save(png)
+    image.save(jpeg, quality=95)
+
+    mean, std = [0.485, 0.456, 0.406], [0.229, 0.224, 0.225]
+    cases = [
+        (
+            png,
+            transforms.Compose([
+                transforms.Resize(24),
+                transforms.CenterCrop(20),
+                transforms.ToTensor(),
+                transforms.Normalize(mean, std),
+            ]),
+            {
+                "resize": {"shorter": 24},
+                "center_crop": [20, 20],
+                "mean": mean,
+                "std": std,
+            },
+        ),
+        (
+            png,
+            transforms.Compose([
+                transforms.Resize((16, 30)),
+                transforms.ToTensor(),
+                transforms.Normalize([0.5] * 3, [0.5] * 3),
+            ]),
+            {"resize": {"exact": [16, 30]}, "range": "minus_one_one"},
+        ),
+        (
+            png,
+            transforms.Compose([
+                transforms.Grayscale(),
+                transforms.CenterCrop((31, 17)),
+                transforms.ToTensor(),
+            ]),
+            {"center_crop": [31, 17], "channel_order": "gray"},
+        ),
+        (
+            jpeg,
+            transforms.Compose([
+                transforms.Resize(28),
+                transforms.CenterCrop(28),
+                transforms.ToTensor(),
+            ]),
+            {"resize": {"shorter": 28}, "center_crop": [28, 28]},
+        ),
+    ]
+
+    out = []
+    for path, transform, spec in cases:
+        with Image.open(path) as img:
+            tensor = transform(img.convert("RGB"))
+        out.append({
+            "image": path,
+            "spec": spec,
+            "expected": tensor.to(torch.float64).flatten().tolist(),
+        })
+
+    with open(os.path.join(out_dir, "cases.json"), "w") as f:
+        json.dump(out, f)
+
+
+if __name__ == "__main__":
+    main(sys.argv[1])
@@ -0,0 +1,32 @@
+#[cfg(all(feature = "verifier-only", not(feature = "ezkl")))]
+#[cfg(test)]
+mod verifier_only_tests {
+    use ezkl::pfsys::verifier::{verify_proof_bytes, VerifierParams};
+    use ezkl::pfsys::{PfsysError, Snark};
+    use halo2curves::bn256::{Fr, G1Affine};
+
+    // the artifacts are those of a proof created by the full crate, see `verifier_only_` in the integration tests
+    #[test]
+    #[ignore = "needs the artifacts of a proof of the full crate in EZKL_VERIFIER_ONLY_DIR"]
+    fn verifies_proof_of_full_crate() {
+        let dir =
+            std::env::var("EZKL_VERIFIER_ONLY_DIR").expect("EZKL_VERIFIER_ONLY_DIR is not set");
+        let read = |name: &str| std::fs::read(format!("{}/{}", dir, name)).unwrap();
+
+        let snark: Snark<Fr, G1Affine> = serde_json::from_slice(&read("proof.pf")).unwrap();
+        let params = VerifierParams::from_bytes(&read("settings.json"), &read("kzg.srs")).unwrap();
+        let vk = read("key.vk");
+        let instances = snark.instances.concat();
+
+        verify_proof_bytes(&vk, &snark.proof, &instances, &params).unwrap();
+
+        // tampered instances don't verify
+        let mut tampered = instances.clone();
+        tampered[0] += Fr::from(1u64);
+        assert!(verify_proof_bytes(&vk, &snark.proof, &tampered, &params).is_err());
+        assert!(matches!(
+            verify_proof_bytes(&vk, &snark.proof, &instances[1..], &params),
+            Err(PfsysError::InstanceCount { .. })
+        ));
+    }
+}
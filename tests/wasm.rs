@@ -1,19 +1,24 @@
 #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
 #[cfg(test)]
 mod wasm32 {
+    #[cfg(feature = "web")]
+    use ezkl::bindings::wasm::initProverThreads;
     use ezkl::bindings::wasm::{
-        bufferToVecOfFelt, compiledCircuitValidation, encodeVerifierCalldata, feltToBigEndian,
-        feltToFloat, feltToInt, feltToLittleEndian, genPk, genVk, genWitness, inputValidation,
-        kzgCommit, pkValidation, poseidonHash, proofValidation, prove, settingsValidation,
-        srsValidation, u8_array_to_u128_le, verify, verifyAggr, vkValidation, witnessValidation,
+        bufferToVecOfFelt, compiledCircuitValidation, decodeInstances, encodeVerifierCalldata,
+        feltToBigEndian, feltToFloat, feltToInt, feltToLittleEndian, finalizePk, finalizeSrs,
+        genPk, genVk, genWitness, genWitnessFromArrays, inputValidation, kzgCommit, loadPkChunk,
+        loadSrsChunk, pkValidation, poseidonHash, proofValidation, prove, proveLoaded,
+        settingsValidation, srsValidation, u8_array_to_u128_le, validateWitness, verify,
+        verifyAggr, vkValidation, witnessFloatOutputs, witnessValidation,
     };
     use ezkl::circuit::modules::polycommit::PolyCommitChip;
+    use ezkl::circuit::modules::poseidon::circomlib::CircomlibChip;
     use ezkl::circuit::modules::poseidon::spec::{PoseidonSpec, POSEIDON_RATE, POSEIDON_WIDTH};
     use ezkl::circuit::modules::poseidon::PoseidonChip;
     use ezkl::circuit::modules::Module;
-    use ezkl::graph::modules::POSEIDON_LEN_GRAPH;
+    use ezkl::graph::modules::{ModuleForwardResult, POSEIDON_LEN_GRAPH};
     use ezkl::graph::GraphCircuit;
-    use ezkl::graph::{GraphSettings, GraphWitness};
+    use ezkl::graph::{GraphSettings, GraphWitness, Visibility};
     use ezkl::pfsys;
     use halo2_proofs::plonk::VerifyingKey;
     use halo2_proofs::poly::kzg::commitment::KZGCommitmentScheme;
@@ -22,6 +27,9 @@ mod wasm32 {
     use halo2curves::bn256::Bn256;
     use halo2curves::bn256::{Fr, G1Affine};
     use snark_verifier::util::arithmetic::PrimeField;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use wasm_bindgen::prelude::*;
     #[cfg(feature = "web")]
     pub use wasm_bindgen_rayon::init_thread_pool;
     use wasm_bindgen_test::*;
@@ -117,8 +125,8 @@ mod wasm32 {
         )
         .unwrap();
         let commitment_ser = kzgCommit(
-            wasm_bindgen::Clamped(message_ser),
-            wasm_bindgen::Clamped(VK.to_vec()),
+            wasm_bindgen::Clamped(message_ser.clone()),
+            Some(VK.to_vec()),
             wasm_bindgen::Clamped(SETTINGS.to_vec()),
             wasm_bindgen::Clamped(SRS.to_vec()),
         )
@@ -133,6 +141,24 @@ mod wasm32 {
         );
 
         assert_eq!(commitment, reference_commitment);
+
+        // without a vk the blinding factors are read from the settings
+        let no_vk = |settings: &GraphSettings| {
+            kzgCommit(
+                wasm_bindgen::Clamped(message_ser.clone()),
+                None,
+                wasm_bindgen::Clamped(serde_json::to_vec(settings).unwrap()),
+                wasm_bindgen::Clamped(SRS.to_vec()),
+            )
+        };
+        assert!(no_vk(&settings).is_err());
+        let settings = GraphSettings {
+            num_blinding_factors: Some(vk.cs().blinding_factors()),
+            ..settings
+        };
+        let commitment: Vec<halo2curves::bn256::G1Affine> =
+            serde_json::from_slice(&no_vk(&settings).map_err(|_| "failed").unwrap()[..]).unwrap();
+        assert_eq!(commitment, reference_commitment);
     }
 
     #[wasm_bindgen_test]
@@ -221,7 +247,7 @@ mod wasm32 {
 
         let message_ser = serde_json::to_vec(&message).unwrap();
 
-        let hash = poseidonHash(wasm_bindgen::Clamped(message_ser))
+        let hash = poseidonHash(wasm_bindgen::Clamped(message_ser.clone()), None)
             .map_err(|_| "failed")
             .unwrap();
         let hash: Vec<Vec<Fr>> = serde_json::from_slice(&hash[..]).unwrap();
@@ -233,7 +259,57 @@ mod wasm32 {
             .map_err(|_| "failed")
             .unwrap();
 
-        assert_eq!(hash, reference_hash)
+        assert_eq!(hash, reference_hash);
+
+        let hash = poseidonHash(
+            wasm_bindgen::Clamped(message_ser),
+            Some("circomlib".to_string()),
+        )
+        .map_err(|_| "failed")
+        .unwrap();
+        let hash: Vec<Vec<Fr>> = serde_json::from_slice(&hash[..]).unwrap();
+        let reference_hash = CircomlibChip::run(message).map_err(|_| "failed").unwrap();
+        assert_eq!(hash, reference_hash);
+    }
+
+    #[wasm_bindgen_test]
+    async fn verify_validate_witness() {
+        let valid = |witness: &GraphWitness, settings: &GraphSettings| {
+            validateWitness(
+                wasm_bindgen::Clamped(serde_json::to_vec(witness).unwrap()),
+                wasm_bindgen::Clamped(serde_json::to_vec(settings).unwrap()),
+            )
+            .is_ok()
+        };
+        let witness: GraphWitness = serde_json::from_slice(&WITNESS).unwrap();
+        let mut settings: GraphSettings = serde_json::from_slice(&SETTINGS).unwrap();
+        assert!(valid(&witness, &settings));
+
+        // hashed inputs are checked against the native hash of the inputs of the witness
+        settings.run_args.input_visibility = Visibility::Hashed {
+            hash_is_public: true,
+            outlets: vec![],
+        };
+        assert!(!valid(&witness, &settings));
+        let hash =
+            PoseidonChip::<PoseidonSpec, POSEIDON_WIDTH, POSEIDON_RATE, POSEIDON_LEN_GRAPH>::run(
+                witness.inputs[0].clone(),
+            )
+            .map_err(|_| "failed")
+            .unwrap();
+        let mut hashed = witness.clone();
+        hashed.processed_inputs = Some(ModuleForwardResult {
+            poseidon_hash: Some(hash[0].clone()),
+            ..Default::default()
+        });
+        assert!(valid(&hashed, &settings));
+        hashed.inputs[0][0] += Fr::from(1);
+        assert!(!valid(&hashed, &settings));
+
+        let mut missing_output = witness.clone();
+        missing_output.outputs.clear();
+        let settings: GraphSettings = serde_json::from_slice(&SETTINGS).unwrap();
+        assert!(!valid(&missing_output, &settings));
     }
 
     #[wasm_bindgen_test]
@@ -252,6 +328,110 @@ mod wasm32 {
         assert_eq!(witness, reference_witness);
     }
 
+    fn witness_from_json(input: &[u8]) -> GraphWitness {
+        let witness = genWitness(
+            wasm_bindgen::Clamped(NETWORK_COMPILED.to_vec()),
+            wasm_bindgen::Clamped(input.to_vec()),
+        )
+        .map_err(|_| "failed")
+        .unwrap();
+        serde_json::from_slice(&witness).unwrap()
+    }
+
+    fn witness_from_arrays(arrays: js_sys::Array, shapes: Option<js_sys::Array>) -> GraphWitness {
+        let witness = genWitnessFromArrays(
+            wasm_bindgen::Clamped(NETWORK_COMPILED.to_vec()),
+            arrays,
+            shapes,
+        )
+        .map_err(|_| "failed")
+        .unwrap();
+        serde_json::from_slice(&witness).unwrap()
+    }
+
+    #[wasm_bindgen_test]
+    async fn gen_witness_from_arrays_test() {
+        let input: serde_json::Value = serde_json::from_slice(INPUT).unwrap();
+        let data: Vec<Vec<f64>> = serde_json::from_value(input["input_data"].clone()).unwrap();
+        let shapes: Vec<Vec<usize>> =
+            serde_json::from_value(input["input_shapes"].clone()).unwrap();
+        let shapes: js_sys::Array = serde_wasm_bindgen::to_value(&shapes).unwrap().into();
+
+        // a Float64Array holds the floats of the json exactly
+        let arrays: js_sys::Array = data
+            .iter()
+            .map(|d| JsValue::from(js_sys::Float64Array::from(&d[..])))
+            .collect();
+        let witness = witness_from_arrays(arrays, Some(shapes.clone()));
+        assert_eq!(witness, witness_from_json(INPUT));
+
+        // a Float32Array matches the json of the same f32 values
+        let data32: Vec<Vec<f32>> = data
+            .iter()
+            .map(|d| d.iter().map(|x| *x as f32).collect())
+            .collect();
+        let arrays: js_sys::Array = data32
+            .iter()
+            .map(|d| JsValue::from(js_sys::Float32Array::from(&d[..])))
+            .collect();
+        // widened as the wasm side widens them, as the json of an f32 is its shortest decimal
+        let widened: Vec<Vec<f64>> = data32
+            .iter()
+            .map(|d| d.iter().map(|x| *x as f64).collect())
+            .collect();
+        let input32 = serde_json::to_vec(&serde_json::json!({ "input_data": widened })).unwrap();
+        assert_eq!(
+            witness_from_arrays(arrays, None),
+            witness_from_json(&input32)
+        );
+
+        // the dequantized outputs
+        let settings: GraphSettings = serde_json::from_slice(SETTINGS).unwrap();
+        let outputs = witnessFloatOutputs(
+            wasm_bindgen::Clamped(serde_json::to_vec(&witness).unwrap()),
+            wasm_bindgen::Clamped(SETTINGS.to_vec()),
+        )
+        .map_err(|_| "failed")
+        .unwrap();
+        let expected = witness.get_float_outputs(&settings.model_output_scales);
+        assert_eq!(outputs.length() as usize, expected.len());
+        for (output, expected) in outputs.iter().zip(expected) {
+            let output: js_sys::Float32Array = output.dyn_into().unwrap();
+            assert_eq!(output.to_vec(), expected.to_vec());
+        }
+
+        // the outputs decoded from the instances of a proof, named as the settings don't record the names
+        let proof: pfsys::Snark<Fr, G1Affine> = serde_json::from_slice(PROOF).unwrap();
+        let decoded: serde_json::Value = serde_json::from_slice(
+            &decodeInstances(
+                wasm_bindgen::Clamped(serde_json::to_vec(&proof.instances).unwrap()),
+                wasm_bindgen::Clamped(SETTINGS.to_vec()),
+            )
+            .map_err(|_| "failed")
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(decoded["outputs"][0]["name"], "output 0");
+        assert_eq!(decoded["outputs"][0]["shape"], serde_json::json!([1, 4]));
+        let values: Vec<f64> =
+            serde_json::from_value(decoded["outputs"][0]["values"].clone()).unwrap();
+        let expected = proof.instances[0]
+            .iter()
+            .map(|x| ezkl::fieldutils::felt_to_f64(*x) / ezkl::graph::scale_to_multiplier(0))
+            .collect::<Vec<_>>();
+        assert_eq!(values, expected);
+
+        // an input of the wrong length is rejected
+        let arrays: js_sys::Array =
+            std::iter::once(JsValue::from(js_sys::Float32Array::new_with_length(1))).collect();
+        assert!(genWitnessFromArrays(
+            wasm_bindgen::Clamped(NETWORK_COMPILED.to_vec()),
+            arrays,
+            None
+        )
+        .is_err());
+    }
+
     #[wasm_bindgen_test]
     async fn gen_pk_test() {
         let vk = genVk(
@@ -310,6 +490,8 @@ mod wasm32 {
             wasm_bindgen::Clamped(pk.clone()),
             wasm_bindgen::Clamped(NETWORK_COMPILED.to_vec()),
             wasm_bindgen::Clamped(SRS.to_vec()),
+            None,
+            None,
         )
         .map_err(|_| "failed")
         .unwrap();
@@ -329,6 +511,217 @@ mod wasm32 {
         assert!(value);
     }
 
+    /// Proves the test network, recording the phases reported to the progress callback. The `aborted` property of the
+    /// signal is set once `abort_at` is reported.
+    fn prove_with_progress(
+        abort_at: Option<&'static str>,
+    ) -> (Result<Vec<u8>, JsValue>, Rc<RefCell<Vec<(String, f64)>>>) {
+        let vk = genVk(
+            wasm_bindgen::Clamped(NETWORK_COMPILED.to_vec()),
+            wasm_bindgen::Clamped(SRS.to_vec()),
+            true,
+        )
+        .map_err(|_| "failed")
+        .unwrap();
+
+        let pk = genPk(
+            wasm_bindgen::Clamped(vk),
+            wasm_bindgen::Clamped(NETWORK_COMPILED.to_vec()),
+            wasm_bindgen::Clamped(SRS.to_vec()),
+        )
+        .map_err(|_| "failed")
+        .unwrap();
+
+        let events = Rc::new(RefCell::new(vec![]));
+        let signal = js_sys::Object::new();
+        let callback = {
+            let events = events.clone();
+            let signal = signal.clone();
+            Closure::wrap(Box::new(move |phase: String, fraction: f64| {
+                if abort_at == Some(phase.as_str()) {
+                    js_sys::Reflect::set(&signal, &"aborted".into(), &true.into()).unwrap();
+                }
+                events.borrow_mut().push((phase, fraction));
+            }) as Box<dyn FnMut(String, f64)>)
+        };
+
+        let proof = prove(
+            wasm_bindgen::Clamped(WITNESS.to_vec()),
+            wasm_bindgen::Clamped(pk),
+            wasm_bindgen::Clamped(NETWORK_COMPILED.to_vec()),
+            wasm_bindgen::Clamped(SRS.to_vec()),
+            Some(
+                callback
+                    .as_ref()
+                    .unchecked_ref::<js_sys::Function>()
+                    .clone(),
+            ),
+            Some(signal.into()),
+        );
+        (proof, events)
+    }
+
+    #[wasm_bindgen_test]
+    async fn prove_reports_progress() {
+        let (proof, events) = prove_with_progress(None);
+        assert!(proof.is_ok());
+
+        let events = events.borrow();
+        let phases: Vec<&str> = events.iter().map(|(phase, _)| phase.as_str()).collect();
+        for phase in ["load", "synthesis", "commit", "done"] {
+            assert!(phases.contains(&phase), "no {} progress", phase);
+        }
+        assert_eq!(events.last().unwrap(), &("done".to_string(), 1.0));
+        // the fractions of a phase only grow
+        for pair in events.windows(2) {
+            if pair[0].0 == pair[1].0 {
+                assert!(pair[0].1 <= pair[1].1);
+            }
+        }
+    }
+
+    #[wasm_bindgen_test]
+    async fn prove_is_cancelled() {
+        let (proof, events) = prove_with_progress(Some("synthesis"));
+        let error: js_sys::Error = proof.unwrap_err().dyn_into().unwrap();
+        assert_eq!(String::from(error.name()), "AbortError");
+
+        // proving stops at the first report after the abort
+        let phases: Vec<String> = events.borrow().iter().map(|(p, _)| p.clone()).collect();
+        assert_eq!(phases.last().map(|p| p.as_str()), Some("synthesis"));
+        assert!(!phases.iter().any(|p| p == "commit" || p == "done"));
+    }
+
+    #[wasm_bindgen_test]
+    async fn prove_with_chunked_srs_and_pk() {
+        let vk = genVk(
+            wasm_bindgen::Clamped(NETWORK_COMPILED.to_vec()),
+            wasm_bindgen::Clamped(SRS.to_vec()),
+            true,
+        )
+        .map_err(|_| "failed")
+        .unwrap();
+
+        let pk = genPk(
+            wasm_bindgen::Clamped(vk.clone()),
+            wasm_bindgen::Clamped(NETWORK_COMPILED.to_vec()),
+            wasm_bindgen::Clamped(SRS.to_vec()),
+        )
+        .map_err(|_| "failed")
+        .unwrap();
+
+        // the srs in 4 chunks, fed out of order as parallel range requests may complete
+        let chunk_len = SRS.len().div_ceil(4);
+        let mut chunks: Vec<(usize, &[u8])> = SRS
+            .chunks(chunk_len)
+            .enumerate()
+            .map(|(i, chunk)| (i * chunk_len, chunk))
+            .collect();
+        chunks.swap(0, 2);
+        // a missing chunk is reported, and the chunks are discarded
+        for (offset, chunk) in &chunks[..3] {
+            loadSrsChunk(js_sys::Uint8Array::from(*chunk), *offset, Some(SRS.len()));
+        }
+        assert!(finalizeSrs(None).is_err());
+        for (offset, chunk) in &chunks {
+            loadSrsChunk(js_sys::Uint8Array::from(*chunk), *offset, None);
+        }
+        assert!(finalizeSrs(Some("kzg".to_string())).is_ok());
+
+        let chunk_len = pk.len().div_ceil(4);
+        for (i, chunk) in pk.chunks(chunk_len).enumerate() {
+            loadPkChunk(js_sys::Uint8Array::from(chunk), i * chunk_len, None);
+        }
+        finalizePk(wasm_bindgen::Clamped(SETTINGS.to_vec()))
+            .map_err(|_| "failed")
+            .unwrap();
+
+        let proof = proveLoaded(
+            wasm_bindgen::Clamped(WITNESS.to_vec()),
+            wasm_bindgen::Clamped(NETWORK_COMPILED.to_vec()),
+            None,
+            None,
+        )
+        .map_err(|_| "failed")
+        .unwrap();
+
+        let value = verify(
+            wasm_bindgen::Clamped(proof),
+            wasm_bindgen::Clamped(vk),
+            wasm_bindgen::Clamped(SETTINGS.to_vec()),
+            wasm_bindgen::Clamped(SRS.to_vec()),
+        )
+        .map_err(|_| "failed")
+        .unwrap();
+        assert!(value);
+    }
+
+    #[cfg(feature = "web")]
+    #[wasm_bindgen_test]
+    async fn multithreaded_proving_test() {
+        let isolated = js_sys::Reflect::get(&js_sys::global(), &"crossOriginIsolated".into())
+            .map_or(false, |isolated| isolated.is_truthy());
+        if !isolated {
+            // without SharedArrayBuffer the pool isn't started, and proving stays single-threaded
+            assert_eq!(initProverThreads(Some(2)).await.unwrap(), 1);
+            return;
+        }
+
+        let single_threaded = genWitness(
+            wasm_bindgen::Clamped(NETWORK_COMPILED.to_vec()),
+            wasm_bindgen::Clamped(INPUT.to_vec()),
+        )
+        .map_err(|_| "failed")
+        .unwrap();
+
+        assert_eq!(initProverThreads(Some(2)).await.unwrap(), 2);
+        // the pool is started once
+        assert_eq!(initProverThreads(Some(4)).await.unwrap(), 2);
+
+        let witness = genWitness(
+            wasm_bindgen::Clamped(NETWORK_COMPILED.to_vec()),
+            wasm_bindgen::Clamped(INPUT.to_vec()),
+        )
+        .map_err(|_| "failed")
+        .unwrap();
+        assert_eq!(witness, single_threaded);
+
+        let vk = genVk(
+            wasm_bindgen::Clamped(NETWORK_COMPILED.to_vec()),
+            wasm_bindgen::Clamped(SRS.to_vec()),
+            true,
+        )
+        .map_err(|_| "failed")
+        .unwrap();
+        let pk = genPk(
+            wasm_bindgen::Clamped(vk.clone()),
+            wasm_bindgen::Clamped(NETWORK_COMPILED.to_vec()),
+            wasm_bindgen::Clamped(SRS.to_vec()),
+        )
+        .map_err(|_| "failed")
+        .unwrap();
+
+        let proof = prove(
+            wasm_bindgen::Clamped(witness),
+            wasm_bindgen::Clamped(pk),
+            wasm_bindgen::Clamped(NETWORK_COMPILED.to_vec()),
+            wasm_bindgen::Clamped(SRS.to_vec()),
+            None,
+            None,
+        )
+        .map_err(|_| "failed")
+        .unwrap();
+        let value = verify(
+            wasm_bindgen::Clamped(proof),
+            wasm_bindgen::Clamped(vk),
+            wasm_bindgen::Clamped(SETTINGS.to_vec()),
+            wasm_bindgen::Clamped(SRS.to_vec()),
+        )
+        .map_err(|_| "failed")
+        .unwrap();
+        assert!(value);
+    }
+
     #[wasm_bindgen_test]
     async fn verify_validations() {
         // Run witness validation on network (should fail)